@@ -318,7 +318,7 @@ impl ResponseSession {
         let mut response =
             Response::with_version(head.version(), head.status_code(), head.reason_phrase())?;
         for (name, value) in head.headers() {
-            response.add_header(name, value)?;
+            response.add_header(name.as_ref(), value)?;
         }
         if let Some(b) = body_field {
             response = response.body(b);
@@ -1,38 +1,31 @@
 //! URL のパース処理
 //!
-//! `http://` / `https://` スキームのみをサポートする最小実装。
-//! 本サンプル / integration test 用途であり、汎用 URL parser を目指していない。
+//! `http://` / `https://` スキームのみをサポートする。
+//! URL の構文解析は `shiguredo_http11::uri::Uri` (RFC 3986) に任せる。
+
+use shiguredo_http11::uri::Uri;
 
 /// URL を (scheme, host, port, path) に分解する
 ///
 /// scheme 省略不可。port 省略時は `https` → 443 / `http` → 80 を補う。
-/// path 省略時は `/` を返す。
+/// host は IPv6 リテラルの角括弧を除いた形で返す。
+/// path は request-target (path + query) で、path 省略時は `/` を返す。
 pub fn parse_url(
     url: &str,
 ) -> Result<(String, String, u16, String), Box<dyn std::error::Error + Send + Sync>> {
-    let (scheme, rest) = if let Some(rest) = url.strip_prefix("https://") {
-        ("https".to_string(), rest)
-    } else if let Some(rest) = url.strip_prefix("http://") {
-        ("http".to_string(), rest)
-    } else {
-        return Err("URL must start with http:// or https://".into());
-    };
-
-    let (host_port, path) = match rest.find('/') {
-        Some(i) => (&rest[..i], &rest[i..]),
-        None => (rest, "/"),
+    let uri = Uri::parse(url)?;
+    let scheme = match uri.scheme() {
+        Some(scheme) if scheme.eq_ignore_ascii_case("https") => "https".to_string(),
+        Some(scheme) if scheme.eq_ignore_ascii_case("http") => "http".to_string(),
+        _ => return Err("URL must start with http:// or https://".into()),
     };
-
-    let (host, port) = match host_port.find(':') {
-        Some(i) => {
-            let port: u16 = host_port[i + 1..].parse()?;
-            (&host_port[..i], port)
-        }
-        None => {
-            let port = if scheme == "https" { 443 } else { 80 };
-            (host_port, port)
-        }
+    let host = match uri.hostname() {
+        Some(host) if !host.is_empty() => host.to_string(),
+        _ => return Err(format!("URL has empty host: {url}").into()),
     };
+    let port = uri
+        .port_or_default()
+        .ok_or("URL must start with http:// or https://")?;
 
-    Ok((scheme, host.to_string(), port, path.to_string()))
+    Ok((scheme, host, port, uri.origin_form()))
 }
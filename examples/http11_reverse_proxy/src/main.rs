@@ -13,9 +13,13 @@ use std::time::{Duration, Instant};
 use rustls::ClientConfig;
 use rustls::pki_types::ServerName;
 use rustls_platform_verifier::ConfigVerifierExt;
+use shiguredo_http11::forwarded::{ForwardedElement, Node};
+use shiguredo_http11::host::Host;
+use shiguredo_http11::proxy::ProxyRewriter;
+use shiguredo_http11::uri::Uri;
+use shiguredo_http11::via::ViaEntry;
 use shiguredo_http11::{
-    BodyKind, BodyProgress, DecoderLimits, HttpHead, Request, RequestDecoder, Response,
-    ResponseDecoder, StatusCode, encode_chunk, encode_response_headers,
+    BodyKind, BodyProgress, DecoderLimits, HttpHead, RequestDecoder, Response, ResponseDecoder,
 };
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufWriter, ReadBuf};
 use tokio::net::{TcpListener, TcpStream};
@@ -67,65 +71,30 @@ type UpstreamKey = (Scheme, String, u16);
 /// - `https://host[:port][/path][?query]`
 /// - IPv6 リテラルは `[host]` 形式 (例: `https://[::1]:8443/`)
 ///
+/// URL の構文解析は `shiguredo_http11::uri::Uri` (RFC 3986) に任せる。
 /// path / query 部は無視する (本サンプルではクライアントの URL をそのまま転送する)。
 fn parse_upstream_url(url: &str) -> Result<UpstreamUrl, Box<dyn std::error::Error>> {
-    let (scheme, rest) = if let Some(rest) = url.strip_prefix("https://") {
-        (Scheme::Https, rest)
-    } else if let Some(rest) = url.strip_prefix("http://") {
-        (Scheme::Http, rest)
-    } else {
-        return Err(format!("upstream URL must start with http:// or https://: {}", url).into());
-    };
-
-    // authority 部 (host[:port]) を path / query 区切りより前で取り出す
-    let authority_end = rest.find(['/', '?']).unwrap_or(rest.len());
-    let authority = &rest[..authority_end];
-    if authority.is_empty() {
-        return Err(format!("upstream URL has empty host: {}", url).into());
-    }
-
-    // IPv6 リテラルの判定
-    let (host, port_opt) = if let Some(rest) = authority.strip_prefix('[') {
-        // IPv6 リテラル: ']' まで host、その後 ':port' があれば port
-        let close = rest
-            .find(']')
-            .ok_or_else(|| format!("unterminated IPv6 literal in upstream URL: {}", url))?;
-        let host = &rest[..close];
-        let after = &rest[close + 1..];
-        if after.is_empty() {
-            (host, None)
-        } else if let Some(port_str) = after.strip_prefix(':') {
-            (host, Some(port_str))
-        } else {
-            return Err(format!(
-                "unexpected characters after IPv6 literal in upstream URL: {}",
-                url
-            )
-            .into());
+    let uri = Uri::parse(url).map_err(|e| format!("invalid upstream URL: {} ({})", url, e))?;
+    let scheme = match uri.scheme() {
+        Some(scheme) if scheme.eq_ignore_ascii_case("https") => Scheme::Https,
+        Some(scheme) if scheme.eq_ignore_ascii_case("http") => Scheme::Http,
+        _ => {
+            return Err(
+                format!("upstream URL must start with http:// or https://: {}", url).into(),
+            );
         }
-    } else if let Some(colon) = authority.rfind(':') {
-        // IPv4 / reg-name: 末尾の ':' で port を切り出す
-        (&authority[..colon], Some(&authority[colon + 1..]))
-    } else {
-        (authority, None)
     };
 
-    if host.is_empty() {
-        return Err(format!("upstream URL has empty host: {}", url).into());
-    }
-
-    let port = match port_opt {
-        Some("") => scheme.default_port(),
-        Some(s) => s
-            .parse::<u16>()
-            .map_err(|e| format!("invalid port in upstream URL: {} ({})", url, e))?,
-        None => scheme.default_port(),
+    // IPv6 リテラルは brackets を除いた host を使う
+    let host = match uri.hostname() {
+        Some(host) if !host.is_empty() => host,
+        _ => return Err(format!("upstream URL has empty host: {}", url).into()),
     };
 
     Ok(UpstreamUrl {
         scheme,
         host: host.to_string(),
-        port,
+        port: uri.port().unwrap_or(scheme.default_port()),
     })
 }
 
@@ -501,10 +470,10 @@ async fn handle_client(
     // ハンドラがハングする経路を持つ (issue 0051)。
     if matches!(req_body_kind, BodyKind::Tunnel) {
         info!(method = %req_head.method(), "CONNECT rejected with 405 Method Not Allowed");
-        let response = Response::with_status(StatusCode::METHOD_NOT_ALLOWED)
-            .header("Allow", "GET, HEAD, POST, PUT, DELETE, OPTIONS, PATCH")?
-            .header("Content-Length", "0")?
-            .header("Connection", "close")?;
+        let response = Response::method_not_allowed(&[
+            "GET", "HEAD", "POST", "PUT", "DELETE", "OPTIONS", "PATCH",
+        ])?
+        .header("Connection", "close")?;
         socket.write_all(&response.encode()?).await?;
         return Ok(());
     }
@@ -552,59 +521,54 @@ async fn handle_client(
         }
     }
 
-    // アップストリームへプロキシリクエストを作成
-    let mut upstream_request = Request::new(req_head.method(), req_head.uri())?;
-
-    // Connection ヘッダーに列挙されたヘッダー名を収集
-    let connection_headers: Vec<String> = req_head
-        .headers()
-        .iter()
-        .filter(|(name, _)| name.eq_ignore_ascii_case("Connection"))
-        .flat_map(|(_, value)| {
-            value
-                .split(',')
-                .map(|s| s.trim().to_ascii_lowercase())
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<_>>()
-        })
-        .collect();
-
-    // ヘッダーをコピー (hop-by-hop ヘッダー、Host、Content-Length は除外)
-    // Content-Length は Transfer-Encoding 除外後に不整合が生じる可能性があるため除外し、
-    // encoder の自動設定に任せる (RFC 9112 Section 6.3 対応)
-    for (name, value) in req_head.headers() {
-        if name.eq_ignore_ascii_case("host") {
-            continue;
-        }
-        if name.eq_ignore_ascii_case("content-length") {
-            continue;
-        }
-        if is_hop_by_hop_header(name, &connection_headers) {
-            continue;
-        }
-        upstream_request.add_header(name, value)?;
+    // アップストリームへ転送するリクエストを組み立てる
+    // hop-by-hop ヘッダーの除去、Host の書き換え、Via / Forwarded / X-Forwarded-* の追加、
+    // フレーミングヘッダーの設定は ProxyRewriter に任せる。
+    // ボディは収集済みのため Content-Length で送る (RFC 9112 Section 6.3 対応)。
+    let rewriter = ProxyRewriter::new()
+        .with_upstream_host(Host::parse(upstream_host_header)?)
+        .with_via(ViaEntry::new("1.1", "http11_reverse_proxy")?)
+        .with_x_forwarded();
+
+    // RFC 7239: クライアントのアドレスと元の Host を Forwarded / X-Forwarded-* で伝える
+    let mut forwarded = ForwardedElement::new()
+        .with_for(Node::from(socket.peer_addr()?))
+        .with_proto("http")?;
+    if let Some(host) = req_head.get_header("Host")
+        && let Ok(element) = forwarded.clone().with_host(host)
+    {
+        forwarded = element;
     }
 
-    upstream_request.add_header("Host", upstream_host_header)?;
-    // Keep-Alive を使用して接続を再利用
-    upstream_request.add_header("Connection", "keep-alive")?;
     // 元リクエストにフレーミングがあった場合のみボディを引き継ぐ。
-    // BodyKind::None なら upstream にもボディなしで送る (Content-Length 自動付与もしない)。
-    let upstream_request = if matches!(req_body_kind, BodyKind::None) {
-        upstream_request
+    // BodyKind::None なら upstream にもボディなしで送る (Content-Length も付与しない)。
+    let upstream_body_kind = if matches!(req_body_kind, BodyKind::None) {
+        BodyKind::None
     } else {
-        upstream_request.body(request_body)
+        BodyKind::ContentLength(request_body.len() as u64)
     };
+    let mut upstream_request =
+        rewriter.rewrite_request(&req_head, upstream_body_kind, Some(&forwarded))?;
+    // Keep-Alive を使用して接続を再利用
+    upstream_request
+        .head_mut()
+        .add_header("Connection", "keep-alive")?;
 
-    debug!(
-        body_size = upstream_request.body_bytes().map(<[u8]>::len).unwrap_or(0),
-        "Upstream request body"
-    );
+    let mut request_bytes = upstream_request.encode_head();
+    request_bytes.extend_from_slice(&request_body);
+
+    debug!(body_size = request_body.len(), "Upstream request body");
 
     // 接続プールから接続を取得してリクエストを送信
-    let result =
-        stream_upstream_response_pooled(&mut socket, &upstream_request, upstream, pool.clone())
-            .await;
+    let result = stream_upstream_response_pooled(
+        &mut socket,
+        &request_bytes,
+        req_head.method(),
+        &rewriter,
+        upstream,
+        pool.clone(),
+    )
+    .await;
 
     // エラーの場合はログに出力
     if let Err(ref e) = result {
@@ -616,7 +580,9 @@ async fn handle_client(
 
 async fn stream_upstream_response_pooled(
     downstream: &mut TcpStream,
-    request: &Request,
+    request_bytes: &[u8],
+    method: &str,
+    rewriter: &ProxyRewriter,
     upstream: &UpstreamUrl,
     pool: SharedPool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -656,9 +622,14 @@ async fn stream_upstream_response_pooled(
     );
 
     // リクエスト送信とレスポンス受信
-    let request_method = request.method().to_string();
-    let result =
-        stream_response_on_connection(downstream, request, &request_method, &mut conn.stream).await;
+    let result = stream_response_on_connection(
+        downstream,
+        request_bytes,
+        method,
+        rewriter,
+        &mut conn.stream,
+    )
+    .await;
 
     // 接続を再利用するかどうかを判定
     let should_reuse = match &result {
@@ -681,17 +652,17 @@ async fn stream_upstream_response_pooled(
 /// 戻り値: 接続を再利用可能かどうか
 async fn stream_response_on_connection(
     downstream: &mut TcpStream,
-    request: &Request,
+    request_bytes: &[u8],
     method: &str,
+    rewriter: &ProxyRewriter,
     upstream: &mut UpstreamStream,
 ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
     // ダウンストリームをバッファリング（64KB バッファ）
     let mut downstream = BufWriter::with_capacity(65536, downstream);
 
     // リクエスト送信
-    let request_bytes = request.encode()?;
     debug!(bytes = request_bytes.len(), "Upstream request bytes");
-    upstream.write_all(&request_bytes).await?;
+    upstream.write_all(request_bytes).await?;
     // UpstreamStream は内部で BufWriter を保持しているため明示的に flush する必要がある。
     upstream.flush().await?;
 
@@ -707,6 +678,9 @@ async fn stream_response_on_connection(
     // RFC 9110 Section 9.1: メソッドトークンは case-sensitive のため、
     // 小文字化等の変換をせずそのまま渡す。
     decoder.set_request_method(method);
+    // chunked ボディは upstream のチャンク境界 / chunk-ext / trailer を保ったまま
+    // バイト単位で同一に転送する (再チャンク化しない)
+    decoder.set_preserve_chunk_framing(true);
 
     const READ_CHUNK: usize = 8192;
 
@@ -750,65 +724,15 @@ async fn stream_response_on_connection(
     }
 
     // クライアントへレスポンスヘッダーを送信
-    // 注: upstream の reason_phrase が空文字列の場合 (RFC 9112 Section 4 の reason-phrase absent)、
-    // Response::new は Err を返すため from_raw_parts 経路に切り替える必要があるが、
-    // 本サンプルでは upstream が常に reason_phrase を送る前提で `Response::new` を使う。
-    // 任意の upstream を受け入れる本格的な proxy では、decoder 経由で得た raw_parts を
-    // そのまま再構築する経路 (本 issue では公開されていない) を将来検討する。
-    let mut response_for_headers =
-        Response::new(resp_head.status_code(), resp_head.reason_phrase())?;
-
-    let connection_headers: Vec<String> = resp_head
-        .headers()
-        .iter()
-        .filter(|(name, _)| name.eq_ignore_ascii_case("Connection"))
-        .flat_map(|(_, value)| {
-            value
-                .split(',')
-                .map(|s| s.trim().to_ascii_lowercase())
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<_>>()
-        })
-        .collect();
-
+    // hop-by-hop ヘッダーを除去し、body_kind に合わせて Content-Length /
+    // Transfer-Encoding / Connection: close を設定する。
+    // HEAD (RFC 9110 Section 9.3.2) / 304 への応答では upstream の Content-Length を引き継ぐ。
+    // upstream の reason-phrase が空 (RFC 9112 Section 4 の absent) でもそのまま転送できる。
     let use_chunked = matches!(body_kind, BodyKind::Chunked);
-    let is_head = method.eq_ignore_ascii_case("HEAD");
-    // HEAD の場合は元のヘッダーから Content-Length を取得 (RFC 9110 Section 9.3.2)
-    // resp_head.content_length() は smuggling 検知 (mismatched 複数行 CL 等) で
-    // Err を返すため、ここで伝播する。
-    let content_length = match body_kind {
-        BodyKind::ContentLength(len) => Some(len),
-        BodyKind::None if is_head => resp_head.content_length()?,
-        _ => None,
-    };
-
-    for (name, value) in resp_head.headers() {
-        if is_hop_by_hop_header(name, &connection_headers) {
-            continue;
-        }
-        // Content-Length と Transfer-Encoding は body_kind に基づいて後で設定する
-        if name.eq_ignore_ascii_case("content-length")
-            || name.eq_ignore_ascii_case("transfer-encoding")
-        {
-            continue;
-        }
-        response_for_headers.add_header(name, value)?;
-    }
-
-    if let Some(len) = content_length {
-        response_for_headers.add_header("Content-Length", len.to_string())?;
-        debug!(content_length = len, "Using Content-Length");
-    } else if use_chunked {
-        response_for_headers.add_header("Transfer-Encoding", "chunked")?;
-        debug!("using Transfer-Encoding: chunked");
-    } else if is_close_delimited {
-        // close-delimited body: 接続が閉じるまでがボディ
-        response_for_headers.add_header("Connection", "close")?;
-        debug!("using Connection: close (close-delimited body)");
-    }
+    let response = rewriter.rewrite_response(&resp_head, body_kind)?;
+    debug!(body_kind = ?response.body_kind(), "Downstream response framing");
 
-    let header_bytes = encode_response_headers(&response_for_headers)?;
-    downstream.write_all(&header_bytes).await?;
+    downstream.write_all(&response.encode_head()).await?;
     downstream.flush().await?;
 
     // close-delimited body の場合の処理 (issue 0052):
@@ -856,27 +780,27 @@ async fn stream_response_on_connection(
     }
 
     // ボディをストリーミング転送
+    // chunked の場合は take_chunk_framing() で取り出したフレーミング
+    // (chunk-size 行 / CRLF / last-chunk / trailer-section) とデータを受信順に書き出す
     let mut total_body_bytes = 0usize;
     if !matches!(body_kind, BodyKind::None) {
         'outer: loop {
             loop {
+                if use_chunked {
+                    let framing = decoder.take_chunk_framing();
+                    if !framing.is_empty() {
+                        downstream.write_all(&framing).await?;
+                    }
+                }
                 match decoder.peek_body() {
                     Some(data) => {
                         let len = data.len();
-                        if use_chunked {
-                            // 単一チャンクのフレーミングはライブラリの encode_chunk に委譲する
-                            // (`size\r\n<data>\r\n` 形式、RFC 9112 Section 7.1)
-                            downstream.write_all(&encode_chunk(data)).await?;
-                        } else {
-                            downstream.write_all(data).await?;
-                        }
+                        downstream.write_all(data).await?;
                         total_body_bytes += len;
 
                         match decoder.consume_body(len)? {
-                            BodyProgress::Complete { trailers } => {
-                                if use_chunked {
-                                    write_last_chunk(&mut downstream, &trailers).await?;
-                                }
+                            BodyProgress::Complete { .. } => {
+                                write_remaining_framing(&mut downstream, &mut decoder).await?;
                                 debug!(total_bytes = total_body_bytes, "Body complete");
                                 break 'outer;
                             }
@@ -887,10 +811,8 @@ async fn stream_response_on_connection(
                     }
                     None => {
                         match decoder.progress()? {
-                            BodyProgress::Complete { trailers } => {
-                                if use_chunked {
-                                    write_last_chunk(&mut downstream, &trailers).await?;
-                                }
+                            BodyProgress::Complete { .. } => {
+                                write_remaining_framing(&mut downstream, &mut decoder).await?;
                                 debug!(total_bytes = total_body_bytes, "Body complete");
                                 break 'outer;
                             }
@@ -929,44 +851,16 @@ async fn stream_response_on_connection(
     Ok(can_reuse)
 }
 
-/// chunked 転送の終端 (`0\r\n<trailers>\r\n`) を downstream へ書き出す
+/// ボディ完了時点で未送信のフレーミング (last-chunk 行 / trailer-section) を downstream へ書き出す
 ///
-/// trailers が空なら `encode_chunk(b"")` (= `0\r\n\r\n`) を流用する。
-/// trailers がある場合は RFC 9112 Section 7.1.2 の trailer-section を手書きする
-/// (ライブラリ側は encode_chunk に trailer を載せる API を提供していない)。
-async fn write_last_chunk(
+/// chunked 以外のボディではフレーミングを記録しないため何も書き出さない。
+async fn write_remaining_framing(
     downstream: &mut BufWriter<&mut TcpStream>,
-    trailers: &[(String, String)],
+    decoder: &mut ResponseDecoder,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    if trailers.is_empty() {
-        downstream.write_all(&encode_chunk(b"")).await?;
-        return Ok(());
+    let framing = decoder.take_chunk_framing();
+    if !framing.is_empty() {
+        downstream.write_all(&framing).await?;
     }
-    let mut end_chunk = b"0\r\n".to_vec();
-    for (name, value) in trailers {
-        end_chunk.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
-    }
-    end_chunk.extend_from_slice(b"\r\n");
-    downstream.write_all(&end_chunk).await?;
     Ok(())
 }
-
-fn is_hop_by_hop_header(name: &str, connection_headers: &[String]) -> bool {
-    const HOP_BY_HOP_HEADERS: &[&str] = &[
-        "connection",
-        "keep-alive",
-        "proxy-authenticate",
-        "proxy-authorization",
-        "proxy-connection",
-        "te",
-        "transfer-encoding",
-        "upgrade",
-    ];
-
-    let name_lower = name.to_ascii_lowercase();
-    if HOP_BY_HOP_HEADERS.contains(&name_lower.as_str()) {
-        return true;
-    }
-
-    connection_headers.contains(&name_lower)
-}
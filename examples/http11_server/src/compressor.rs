@@ -2,58 +2,17 @@
 //!
 //! Accept-Encoding ヘッダーからの圧縮方式選択と一括圧縮を提供する。
 
-use shiguredo_http11::compression::CompressionError;
+use shiguredo_http11::accept::{AcceptEncoding, negotiate_encoding};
+use shiguredo_http11::compression::{CompressionError, Compressor, GzipCompressor};
 
 /// Accept-Encoding ヘッダーから最適な圧縮方式を選択
 ///
 /// 優先順位: zstd > br > gzip > identity
 pub fn select_encoding(accept_encoding: &str) -> Option<&'static str> {
-    let encodings: Vec<(&str, f32)> = accept_encoding
-        .split(',')
-        .filter_map(|part| {
-            let part = part.trim();
-            let (encoding, quality) = if let Some(pos) = part.find(";q=") {
-                let enc = part[..pos].trim();
-                // RFC 9110 Section 12.4.2: 無効な quality 値のデフォルトは 0
-                let q: f32 = part[pos + 3..].trim().parse().unwrap_or(0.0);
-                (enc, q)
-            } else {
-                (part, 1.0)
-            };
-            if quality > 0.0 {
-                Some((encoding, quality))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    // 優先順位でソート（quality が高い順、同じなら zstd > br > gzip の順）
-    let mut best: Option<(&str, f32, u8)> = None;
-
-    for (enc, q) in encodings {
-        let priority = match enc {
-            "zstd" => 3,
-            "br" => 2,
-            "gzip" | "x-gzip" | "*" => 1,
-            _ => continue,
-        };
-
-        match best {
-            None => best = Some((enc, q, priority)),
-            Some((_, best_q, best_p)) => {
-                if q > best_q || (q == best_q && priority > best_p) {
-                    best = Some((enc, q, priority));
-                }
-            }
-        }
-    }
-
-    best.map(|(enc, _, _)| match enc {
-        "zstd" => "zstd",
-        "br" => "br",
-        _ => "gzip",
-    })
+    let accept_encoding = AcceptEncoding::parse(accept_encoding).ok()?;
+    // identity を返す場合と identity も受理されない場合は圧縮しない
+    negotiate_encoding(&accept_encoding, &["zstd", "br", "gzip"])
+        .filter(|encoding| *encoding != "identity")
 }
 
 /// 圧縮方式に対応する Content-Encoding 値
@@ -69,9 +28,7 @@ pub fn encoding_header(encoding: &str) -> &'static str {
 /// ボディを一括圧縮
 pub fn compress_body(data: &[u8], encoding: &str) -> Result<Vec<u8>, CompressionError> {
     match encoding {
-        "gzip" => {
-            noflate::gzip::compress(data).map_err(|e| CompressionError::Internal(e.to_string()))
-        }
+        "gzip" => compress_all(&mut GzipCompressor::new(), data),
         "br" => {
             use std::io::Write;
             let mut compressed = Vec::new();
@@ -87,3 +44,28 @@ pub fn compress_body(data: &[u8], encoding: &str) -> Result<Vec<u8>, Compression
         _ => Ok(data.to_vec()),
     }
 }
+
+/// `Compressor` で入力をすべて圧縮
+fn compress_all(
+    compressor: &mut impl Compressor,
+    data: &[u8],
+) -> Result<Vec<u8>, CompressionError> {
+    let mut compressed = Vec::new();
+    let mut output = [0u8; 8192];
+    let mut input = data;
+    loop {
+        let status = compressor.compress(input, &mut output)?;
+        compressed.extend_from_slice(&output[..status.produced()]);
+        input = &input[status.consumed()..];
+        if input.is_empty() && !status.is_output_full() {
+            break;
+        }
+    }
+    loop {
+        let status = compressor.finish(&mut output)?;
+        compressed.extend_from_slice(&output[..status.produced()]);
+        if status.is_complete() {
+            return Ok(compressed);
+        }
+    }
+}
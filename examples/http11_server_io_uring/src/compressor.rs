@@ -2,58 +2,17 @@
 //!
 //! Accept-Encoding ヘッダーからの圧縮方式選択と一括圧縮を提供する。
 
+use shiguredo_http11::accept::{AcceptEncoding, negotiate_encoding};
 use shiguredo_http11::compression::CompressionError;
 
 /// Accept-Encoding ヘッダーから最適な圧縮方式を選択
 ///
 /// 優先順位: zstd > br > gzip > identity
 pub fn select_encoding(accept_encoding: &str) -> Option<&'static str> {
-    let encodings: Vec<(&str, f32)> = accept_encoding
-        .split(',')
-        .filter_map(|part| {
-            let part = part.trim();
-            let (encoding, quality) = if let Some(pos) = part.find(";q=") {
-                let enc = part[..pos].trim();
-                // RFC 9110 Section 12.4.2: 無効な quality 値のデフォルトは 0
-                let q: f32 = part[pos + 3..].trim().parse().unwrap_or(0.0);
-                (enc, q)
-            } else {
-                (part, 1.0)
-            };
-            if quality > 0.0 {
-                Some((encoding, quality))
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    // 優先順位でソート（quality が高い順、同じなら zstd > br > gzip の順）
-    let mut best: Option<(&str, f32, u8)> = None;
-
-    for (enc, q) in encodings {
-        let priority = match enc {
-            "zstd" => 3,
-            "br" => 2,
-            "gzip" | "x-gzip" | "*" => 1,
-            _ => continue,
-        };
-
-        match best {
-            None => best = Some((enc, q, priority)),
-            Some((_, best_q, best_p)) => {
-                if q > best_q || (q == best_q && priority > best_p) {
-                    best = Some((enc, q, priority));
-                }
-            }
-        }
-    }
-
-    best.map(|(enc, _, _)| match enc {
-        "zstd" => "zstd",
-        "br" => "br",
-        _ => "gzip",
-    })
+    let accept_encoding = AcceptEncoding::parse(accept_encoding).ok()?;
+    // identity を返す場合と identity も受理されない場合は圧縮しない
+    negotiate_encoding(&accept_encoding, &["zstd", "br", "gzip"])
+        .filter(|encoding| *encoding != "identity")
 }
 
 /// 圧縮方式に対応する Content-Encoding 値
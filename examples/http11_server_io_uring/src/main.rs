@@ -31,6 +31,7 @@ use io_uring::{IoUring, Probe};
 use rustls::pki_types::pem::PemObject;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use rustls::{ServerConfig, ServerConnection, SupportedCipherSuite};
+use shiguredo_http11::date::HttpDate;
 use shiguredo_http11::{EncodeError, HttpHead, RequestDecoder, Response, StatusCode};
 use slab::Slab;
 use tracing::{error, info};
@@ -1000,7 +1001,9 @@ fn build_response(
         .unwrap_or(0);
 
     // RFC 9110 準拠の Date ヘッダー (IMF-fixdate 形式)
-    let date = format_http_date(now);
+    let date = HttpDate::from_unix_seconds(now)
+        .map(|date| date.to_string())
+        .unwrap_or_default();
 
     // RFC 9110 Section 9.3.2: HEAD レスポンスは GET と同じヘッダーを返すがボディは送信しない
     let is_head = request.method().eq_ignore_ascii_case("HEAD");
@@ -1166,60 +1169,3 @@ fn add_connection_headers(
     }
 }
 
-/// RFC 9110 準拠の IMF-fixdate 形式で日付を生成
-fn format_http_date(timestamp: u64) -> String {
-    // 日曜日始まりの曜日配列
-    const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
-    const MONTHS: [&str; 12] = [
-        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
-    ];
-
-    // Unix epoch (1970-01-01) は木曜日 (= 4)
-    let days_since_epoch = timestamp / 86400;
-    let day_of_week = ((days_since_epoch + 4) % 7) as usize;
-
-    // 年月日を計算
-    let mut remaining_days = days_since_epoch as i64;
-    let mut year = 1970i32;
-
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
-        }
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-
-    let mut month = 0usize;
-    let days_in_months = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    for (i, &days) in days_in_months.iter().enumerate() {
-        if remaining_days < days as i64 {
-            month = i;
-            break;
-        }
-        remaining_days -= days as i64;
-    }
-
-    let day = remaining_days + 1;
-
-    // 時分秒を計算
-    let time_of_day = timestamp % 86400;
-    let hour = time_of_day / 3600;
-    let minute = (time_of_day % 3600) / 60;
-    let second = time_of_day % 60;
-
-    format!(
-        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
-        DAYS[day_of_week], day, MONTHS[month], year, hour, minute, second
-    )
-}
-
-fn is_leap_year(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
@@ -0,0 +1,20 @@
+//! Allow ヘッダーと 405 レスポンス生成のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::Response;
+use shiguredo_http11::allow::Allow;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(allow) = Allow::parse(s) {
+        assert_eq!(Allow::parse(&allow.to_string()), Ok(allow));
+    }
+    let methods: Vec<&str> = s.split(',').collect();
+    let _ = Response::method_not_allowed(&methods);
+    let _ = Response::method_not_allowed_problem(&["GET"], s);
+});
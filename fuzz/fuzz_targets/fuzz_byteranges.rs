@@ -0,0 +1,30 @@
+//! ByteRangesParser の任意バイト列に対するパニック安全性を検証する
+//!
+//! - 複数の boundary 文字列 ("boundary", "THIS_STRING_SEPARATES", "---") を用いて、
+//!   同じ任意データに対するパースを試行する
+//! - 各パートの content_type, content_range, start, end, body アクセサを呼び出し、
+//!   パニックしないことと、Content-Range の長さとボディの長さが一致することを確認する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::range::ByteRangesParser;
+
+fuzz_target!(|data: &[u8]| {
+    let boundaries = ["boundary", "THIS_STRING_SEPARATES", "---"];
+
+    for boundary in boundaries {
+        let mut parser = ByteRangesParser::new(boundary);
+        let _ = parser.feed(data);
+
+        while let Ok(Some(part)) = parser.next_part() {
+            let _ = part.content_type();
+            let _ = part.start();
+            let _ = part.end();
+            assert_eq!(
+                part.content_range().length(),
+                Some(part.body().len() as u64)
+            );
+        }
+    }
+});
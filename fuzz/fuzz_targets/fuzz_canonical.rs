@@ -0,0 +1,44 @@
+//! メッセージの正規化と差分を検証する
+//!
+//! - 任意のバイト列をレスポンスとしてデコードし、正規化と差分がパニックしないことを確認する
+//! - 同じメッセージ同士の差分がないことを確認する
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::ResponseDecoder;
+use shiguredo_http11::canonical::{Canonicalizer, HeaderOrder};
+
+#[derive(Arbitrary, Debug)]
+struct FuzzCanonical {
+    left: Vec<u8>,
+    right: Vec<u8>,
+    preserve_order: bool,
+    lowercase_names: bool,
+}
+
+fn decode(input: &[u8]) -> Option<shiguredo_http11::Response> {
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(input).ok()?;
+    decoder.decode().ok()?
+}
+
+fuzz_target!(|data: FuzzCanonical| {
+    let (Some(left), Some(right)) = (decode(&data.left), decode(&data.right)) else {
+        return;
+    };
+    let order = if data.preserve_order {
+        HeaderOrder::Preserve
+    } else {
+        HeaderOrder::SortByName
+    };
+    let canonicalizer = Canonicalizer::new()
+        .with_header_order(order)
+        .with_lowercase_names(data.lowercase_names);
+
+    assert!(canonicalizer.diff_responses(&left, &left).is_none());
+    if let Some(diff) = canonicalizer.diff_responses(&left, &right) {
+        let _ = diff.to_string();
+    }
+});
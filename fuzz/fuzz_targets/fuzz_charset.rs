@@ -0,0 +1,31 @@
+//! charset を考慮したテキストデコードのパニック安全性を検証する
+//!
+//! - 先頭バイトで charset を選び、残りのバイト列を decode / decode_lossy に渡す
+//! - decode が成功する場合は decode_lossy と結果が一致することを確認する
+//! - 任意の UTF-8 文字列を charset ラベルとして Charset::from_label() に渡す
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::charset::Charset;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, body)) = data.split_first() else {
+        return;
+    };
+    let charset = match selector % 3 {
+        0 => Charset::Utf8,
+        1 => Charset::UsAscii,
+        _ => Charset::Iso8859_1,
+    };
+    let lossy = charset.decode_lossy(body);
+    if let Ok(decoded) = charset.decode(body) {
+        assert_eq!(decoded, lossy);
+    }
+
+    if let Ok(label) = std::str::from_utf8(body)
+        && let Some(charset) = Charset::from_label(label)
+    {
+        assert_eq!(Charset::from_label(charset.name()), Some(charset));
+    }
+});
@@ -0,0 +1,68 @@
+//! chunked ボディのフレーミング保持を検証する
+//!
+//! - 任意のバイト列を chunked ボディとして分割フィードする
+//! - 正常に完了した場合、take_chunk_framing() と peek_body() のデータを
+//!   受信順に連結した結果が、消費した入力と一致することを確認する
+//! - chunk_ext() が返す値は encode_chunk_with_ext() で再エンコードできることを確認する
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::{BodyProgress, ResponseDecoder, encode_chunk_with_ext};
+
+const HEAD: &[u8] = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+
+#[derive(Arbitrary, Debug)]
+struct FuzzChunkFraming {
+    body: Vec<u8>,
+    split_hint: u8,
+}
+
+fuzz_target!(|input: FuzzChunkFraming| {
+    let split = (input.split_hint as usize % 16) + 1;
+
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_preserve_chunk_framing(true);
+    if decoder.feed(HEAD).is_err() || !matches!(decoder.decode_headers(), Ok(Some(_))) {
+        return;
+    }
+
+    let mut output = Vec::new();
+    let mut fed = 0;
+    let mut parts = input.body.chunks(split);
+    loop {
+        output.extend_from_slice(&decoder.take_chunk_framing());
+        if let Some(ext) = decoder.chunk_ext() {
+            assert!(encode_chunk_with_ext(b"x", ext).is_ok());
+        }
+        if let Some(data) = decoder.peek_body() {
+            let len = data.len();
+            output.extend_from_slice(data);
+            match decoder.consume_body(len) {
+                Ok(BodyProgress::Complete { .. }) => break,
+                Ok(_) => continue,
+                Err(_) => return,
+            }
+        }
+        match decoder.progress() {
+            Ok(BodyProgress::Complete { .. }) => break,
+            Ok(BodyProgress::Advanced) => {}
+            Ok(BodyProgress::NeedData) => match parts.next() {
+                Some(part) => {
+                    if decoder.feed(part).is_err() {
+                        return;
+                    }
+                    fed += part.len();
+                }
+                None => return,
+            },
+            Err(_) => return,
+        }
+    }
+    output.extend_from_slice(&decoder.take_chunk_framing());
+
+    // 未消費のバイト列はボディに含まない
+    let consumed = fed - decoder.remaining().len();
+    assert_eq!(output, &input.body[..consumed]);
+});
@@ -0,0 +1,84 @@
+//! 組み込みの gzip / deflate 実装のパニック安全性を検証する
+//!
+//! - 先頭バイトで形式と分割サイズを選び、残りのバイト列を展開器に渡す
+//! - 同じバイト列を圧縮して展開し、元に戻ることを確認する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::compression::{
+    Compressor, Decompressor, DeflateCompressor, DeflateDecompressor, GzipCompressor,
+    GzipDecompressor,
+};
+
+fn decompress(decompressor: &mut dyn Decompressor, data: &[u8], chunk: usize) -> Option<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    let mut output = vec![0u8; chunk];
+    let mut input = data;
+    loop {
+        let piece = &input[..input.len().min(chunk)];
+        let status = decompressor.decompress(piece, &mut output).ok()?;
+        decompressed.extend_from_slice(&output[..status.produced()]);
+        input = &input[status.consumed()..];
+        if status.is_complete() {
+            return Some(decompressed);
+        }
+        // 空の入力 (入力の終端) で完了しなければ途切れている
+        if piece.is_empty() && status.produced() == 0 && !status.is_output_full() {
+            return None;
+        }
+        // 展開爆弾で時間がかかりすぎないようにする
+        if decompressed.len() > 1 << 20 {
+            return None;
+        }
+    }
+}
+
+fn compress(compressor: &mut dyn Compressor, data: &[u8], chunk: usize) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    let mut output = vec![0u8; chunk];
+    let mut input = data;
+    loop {
+        let status = compressor.compress(input, &mut output).unwrap();
+        compressed.extend_from_slice(&output[..status.produced()]);
+        input = &input[status.consumed()..];
+        if input.is_empty() && !status.is_output_full() {
+            break;
+        }
+    }
+    loop {
+        let status = compressor.finish(&mut output).unwrap();
+        compressed.extend_from_slice(&output[..status.produced()]);
+        if status.is_complete() {
+            break;
+        }
+    }
+    compressed
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, body)) = data.split_first() else {
+        return;
+    };
+    let chunk = (selector >> 1) as usize + 1;
+    let gzip = selector & 1 == 0;
+
+    let (mut compressor, mut decompressor): (Box<dyn Compressor>, Box<dyn Decompressor>) = if gzip
+    {
+        (Box::new(GzipCompressor::new()), Box::new(GzipDecompressor::new()))
+    } else {
+        (
+            Box::new(DeflateCompressor::new()),
+            Box::new(DeflateDecompressor::new()),
+        )
+    };
+
+    let _ = decompress(decompressor.as_mut(), body, chunk);
+
+    decompressor.reset();
+    let compressed = compress(compressor.as_mut(), body, chunk);
+    assert_eq!(
+        decompress(decompressor.as_mut(), &compressed, chunk).as_deref(),
+        Some(body)
+    );
+});
@@ -0,0 +1,38 @@
+//! 適合性テスト用ハーネスで分割位置によらないデコードを検証する
+//!
+//! - 任意のバイト列をリクエストまたはレスポンスとしてデコードする
+//! - 全ての分割位置で一括投入と同じデコード結果になることを確認する
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::conformance::Harness;
+
+/// 分割位置の全探索は入力長の 2 乗に比例するため、入力を制限する
+const MAX_INPUT: usize = 512;
+
+#[derive(Arbitrary, Debug)]
+struct FuzzConformance {
+    input: Vec<u8>,
+    response: bool,
+    head: bool,
+    eof: bool,
+}
+
+fuzz_target!(|data: FuzzConformance| {
+    let input = &data.input[..data.input.len().min(MAX_INPUT)];
+    let harness = if data.response {
+        let harness = Harness::response().with_eof(data.eof);
+        if data.head {
+            harness.with_request_method("HEAD")
+        } else {
+            harness
+        }
+    } else {
+        Harness::request()
+    };
+    if let Err(mismatch) = harness.replay_all_splits(input) {
+        panic!("{mismatch}");
+    }
+});
@@ -0,0 +1,28 @@
+//! Connection ヘッダーと hop-by-hop ヘッダー除去のパニック安全性を検証する
+
+#![no_main]
+
+use std::borrow::Cow;
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::connection::{ConnectionOptions, is_hop_by_hop, strip_hop_by_hop};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(options) = ConnectionOptions::parse(s) {
+        assert_eq!(ConnectionOptions::parse(&options.to_string()), Ok(options));
+    }
+
+    // 行ごとに "name: value" としてヘッダーリストを作り、除去後の不変条件を確認する
+    let mut headers: Vec<(Cow<'static, str>, String)> = s
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (Cow::Owned(name.to_string()), value.to_string()))
+        .collect();
+    let connection = ConnectionOptions::collect(&headers);
+    strip_hop_by_hop(&mut headers);
+    assert!(headers.iter().all(|(name, _)| !is_hop_by_hop(name, &connection)));
+});
@@ -3,11 +3,12 @@
 //! - 任意の UTF-8 文字列で ContentDisposition::parse() を呼び出す
 //! - パース成功時は disposition_type, filename, filename_ext, name 等の
 //!   アクセサを呼び出し、Display 出力の再パースで一致を確認する
+//! - 任意の文字列を with_filename_utf8() に渡し、Display -> parse でファイル名が復元されることを確認する
 
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use shiguredo_http11::content_disposition::ContentDisposition;
+use shiguredo_http11::content_disposition::{ContentDisposition, DispositionType};
 
 fuzz_target!(|data: &[u8]| {
     // UTF-8 文字列として解釈できる場合のみテスト
@@ -19,6 +20,7 @@ fuzz_target!(|data: &[u8]| {
             let _ = cd.filename();
             let _ = cd.filename_ascii();
             let _ = cd.filename_ext();
+            let _ = cd.filename_ext_language();
             let _ = cd.name();
             let _ = cd.is_inline();
             let _ = cd.is_attachment();
@@ -28,5 +30,12 @@ fuzz_target!(|data: &[u8]| {
             let displayed = cd.to_string();
             let _ = ContentDisposition::parse(&displayed);
         }
+
+        // with_filename_utf8 のラウンドトリップ
+        let cd = ContentDisposition::new(DispositionType::Attachment).with_filename_utf8(s);
+        let parsed = ContentDisposition::parse(&cd.to_string());
+        if !s.is_empty() {
+            assert_eq!(parsed.ok().as_ref().and_then(|cd| cd.filename()), Some(s));
+        }
     }
 });
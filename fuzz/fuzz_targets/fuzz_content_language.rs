@@ -3,14 +3,20 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use shiguredo_http11::content_language::ContentLanguage;
+use shiguredo_http11::content_language::{ContentLanguage, LanguageTag};
 
 fuzz_target!(|data: &[u8]| {
-    if let Ok(s) = std::str::from_utf8(data)
-        && let Ok(value) = ContentLanguage::parse(s)
-    {
-        let _ = value.tags();
-        let displayed = value.to_string();
-        let _ = ContentLanguage::parse(&displayed);
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Ok(value) = ContentLanguage::parse(s) {
+            let _ = value.tags();
+            assert_eq!(value.language_tags().len(), value.tags().len());
+            let displayed = value.to_string();
+            let _ = ContentLanguage::parse(&displayed);
+        }
+        if let Ok(tag) = LanguageTag::parse(s) {
+            assert_eq!(tag.to_string(), s);
+            assert!(tag.matches_basic_range("*"));
+            assert!(tag.matches_extended_range("*"));
+        }
     }
 });
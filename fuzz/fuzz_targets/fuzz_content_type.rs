@@ -21,6 +21,11 @@ fuzz_target!(|data: &[u8]| {
             let _ = ct.mime_type();
             let _ = ct.charset();
             let _ = ct.boundary();
+            let _ = ct.suffix();
+            let _ = ct.is_json_compatible();
+            let _ = ct.is_xml_compatible();
+            assert!(ct.essence_eq(&ct));
+            assert!(ct.is_essence(&ct.mime_type()));
             let _ = ct.parameters();
             let _ = ct.is_text();
             let _ = ct.is_json();
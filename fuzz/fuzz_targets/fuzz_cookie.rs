@@ -3,7 +3,7 @@
 //! - Cookie: 任意入力でパースし、各 cookie の name/value アクセサを呼び出す。
 //!   Display 出力を再パースして name と value の一致を確認する
 //! - Set-Cookie: パース成功時は expires, max_age, domain, path, secure,
-//!   http_only, same_site の全属性アクセサを呼び出す。
+//!   http_only, same_site, partitioned の全属性アクセサと validate を呼び出す。
 //!   Display 出力の再パースで全属性の一致を確認する
 
 #![no_main]
@@ -36,6 +36,8 @@ fuzz_target!(|data: &[u8]| {
             let _ = set_cookie.secure();
             let _ = set_cookie.http_only();
             let _ = set_cookie.same_site();
+            let _ = set_cookie.partitioned();
+            let _ = set_cookie.validate();
 
             // Display 実装のテスト
             let displayed = set_cookie.to_string();
@@ -0,0 +1,35 @@
+//! Origin のパニック安全性と CORS ポリシー判定のパニック安全性を検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::Request;
+use shiguredo_http11::cors::{CorsPolicy, Origin};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(origin) = Origin::parse(s) {
+        let reparsed = Origin::parse(&origin.to_string()).unwrap();
+        assert_eq!(reparsed, origin);
+    }
+
+    let mut parts = s.splitn(3, '\n');
+    let origin = parts.next().unwrap_or_default();
+    let method = parts.next().unwrap_or_default();
+    let headers = parts.next().unwrap_or_default();
+    let Ok(request) = Request::new("OPTIONS", "/")
+        .and_then(|r| r.header("Origin", origin))
+        .and_then(|r| r.header("Access-Control-Request-Method", method))
+        .and_then(|r| r.header("Access-Control-Request-Headers", headers))
+    else {
+        return;
+    };
+    let policy = CorsPolicy::new()
+        .with_any_origin()
+        .with_credentials(true)
+        .with_any_header();
+    let _ = policy.evaluate("OPTIONS", &request);
+});
@@ -1,7 +1,7 @@
 //! DecoderLimits の任意パラメータに対するパニック安全性を検証する
 //!
 //! - 任意の制限値 (max_buffer_size, max_headers_count, max_header_line_size,
-//!   max_body_size) でデコーダーを初期化する
+//!   max_body_size, max_uri_length, max_method_length) でデコーダーを初期化する
 //! - 任意のバイト列を RequestDecoder / ResponseDecoder 双方に投入し、
 //!   制限値の組み合わせでパニックしないことを確認する
 
@@ -17,6 +17,8 @@ struct FuzzLimits {
     max_headers_count: u16,
     max_header_line_size: u16,
     max_body_size: u32,
+    max_uri_length: u16,
+    max_method_length: u8,
     data: Vec<u8>,
 }
 
@@ -27,6 +29,8 @@ fn build_limits(input: &FuzzLimits) -> DecoderLimits {
         max_header_line_size: input.max_header_line_size as usize,
         max_body_size: input.max_body_size as usize,
         max_chunk_line_size: 64,
+        max_uri_length: input.max_uri_length as usize,
+        max_method_length: input.max_method_length as usize,
     }
 }
 
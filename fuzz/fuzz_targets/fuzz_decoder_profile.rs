@@ -0,0 +1,25 @@
+//! DecoderProfile のプリセットを検証する
+//!
+//! - 任意のバイト列を各プリセットでリクエストとしてデコードし、パニックしないことを確認する
+//! - strict() で受理したメッセージは他のプリセットでも同じ結果で受理することを確認する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::{DecoderProfile, Request, RequestDecoder};
+
+fn decode(profile: DecoderProfile, data: &[u8]) -> Option<Request> {
+    let mut decoder = RequestDecoder::with_profile(profile);
+    decoder.feed(data).ok()?;
+    decoder.decode().ok()?
+}
+
+fuzz_target!(|data: &[u8]| {
+    let strict = decode(DecoderProfile::strict(), data);
+    let browser = decode(DecoderProfile::browser_compatible(), data);
+    let internal = decode(DecoderProfile::internal(), data);
+    if let Some(strict) = strict {
+        assert_eq!(browser.as_ref(), Some(&strict));
+        assert_eq!(internal.as_ref(), Some(&strict));
+    }
+});
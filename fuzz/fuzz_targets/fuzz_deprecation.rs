@@ -0,0 +1,26 @@
+//! Deprecation / Sunset / Warning のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::deprecation::{Deprecation, Sunset, Warning};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(deprecation) = Deprecation::parse(s, 2026) {
+        let _ = deprecation.date();
+        assert_eq!(
+            Deprecation::parse(&deprecation.to_string(), 2026),
+            Ok(deprecation)
+        );
+    }
+    if let Ok(sunset) = Sunset::parse(s, 2026) {
+        let _ = sunset.unix_seconds();
+    }
+    if let Ok(warning) = Warning::parse(s, 2026) {
+        assert_eq!(Warning::parse(&warning.to_string(), 2026), Ok(warning));
+    }
+});
@@ -4,15 +4,21 @@
 //! - Repr-Digest: ダイジェスト値のパースとアクセサ、Display ラウンドトリップを検証する
 //! - Want-Content-Digest: 要求ダイジェストのパースとアクセサ、Display ラウンドトリップを検証する
 //! - Want-Repr-Digest: 要求ダイジェストのパースとアクセサ、Display ラウンドトリップを検証する
+//! - Want-*-Digest の choose() が優先度 0 のアルゴリズムを選ばないことを確認する
+//! - 任意のバイト列の SHA-256 Digest を計算し、同じバイト列で検証に成功することを確認する
 
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
 use shiguredo_http11::digest_fields::{
-    ContentDigest, ReprDigest, WantContentDigest, WantReprDigest,
+    ContentDigest, ReprDigest, SUPPORTED_ALGORITHMS, WantContentDigest, WantReprDigest,
 };
 
 fuzz_target!(|data: &[u8]| {
+    let digest = ContentDigest::sha256(data);
+    assert!(digest.verify(data).is_ok());
+    let _ = ReprDigest::parse(&digest.to_string()).map(|d| d.verify(data));
+
     if let Ok(s) = std::str::from_utf8(data) {
         if let Ok(value) = ContentDigest::parse(s) {
             for item in value.items() {
@@ -21,6 +27,7 @@ fuzz_target!(|data: &[u8]| {
                 let _ = item.value().bytes();
                 let _ = value.get(item.algorithm());
             }
+            let _ = value.verify(data);
             let displayed = value.to_string();
             let _ = ContentDigest::parse(&displayed);
         }
@@ -42,6 +49,9 @@ fuzz_target!(|data: &[u8]| {
                 let _ = item.weight();
                 let _ = value.get(item.algorithm());
             }
+            if let Some(algorithm) = value.choose(SUPPORTED_ALGORITHMS) {
+                assert!(value.get(algorithm).is_some_and(|w| w > 0));
+            }
             let displayed = value.to_string();
             let _ = WantContentDigest::parse(&displayed);
         }
@@ -52,6 +62,7 @@ fuzz_target!(|data: &[u8]| {
                 let _ = item.weight();
                 let _ = value.get(item.algorithm());
             }
+            let _ = value.choose(SUPPORTED_ALGORITHMS);
             let displayed = value.to_string();
             let _ = WantReprDigest::parse(&displayed);
         }
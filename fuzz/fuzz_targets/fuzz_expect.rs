@@ -1,8 +1,8 @@
 //! Expect ヘッダーのパニック安全性と Display ラウンドトリップを検証する
 //!
 //! - 任意の UTF-8 文字列で Expect::parse() を呼び出す
-//! - パース成功時は has_100_continue() と各 item の token/value/is_100_continue
-//!   アクセサを呼び出す
+//! - パース成功時は has_100_continue() / has_unknown() と各 item の
+//!   token/value/is_100_continue/is_known アクセサを呼び出す
 //! - Display 出力を再パースしてパニック安全性を確認する
 
 #![no_main]
@@ -15,10 +15,15 @@ fuzz_target!(|data: &[u8]| {
         && let Ok(value) = Expect::parse(s)
     {
         let _ = value.has_100_continue();
+        assert_eq!(
+            value.has_unknown(),
+            value.items().iter().any(|item| !item.is_known())
+        );
         for item in value.items() {
             let _ = item.token();
             let _ = item.value();
             let _ = item.is_100_continue();
+            let _ = item.is_known();
         }
         let displayed = value.to_string();
         let _ = Expect::parse(&displayed);
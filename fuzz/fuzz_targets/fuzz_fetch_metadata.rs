@@ -0,0 +1,25 @@
+//! Fetch Metadata リクエストヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::fetch_metadata::{FetchDest, FetchMode, FetchSite, FetchUser};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(site) = FetchSite::parse(s) {
+        assert_eq!(FetchSite::parse(&site.to_string()), Ok(site));
+    }
+    if let Ok(mode) = FetchMode::parse(s) {
+        assert_eq!(FetchMode::parse(&mode.to_string()), Ok(mode));
+    }
+    if let Ok(dest) = FetchDest::parse(s) {
+        assert_eq!(FetchDest::parse(&dest.to_string()), Ok(dest));
+    }
+    if let Ok(user) = FetchUser::parse(s) {
+        assert_eq!(FetchUser::parse(&user.to_string()), Ok(user));
+    }
+});
@@ -0,0 +1,17 @@
+//! Forwarded ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::forwarded::{Forwarded, Node};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = Node::parse(s);
+        if let Ok(forwarded) = Forwarded::parse(s) {
+            let _ = forwarded.to_x_forwarded();
+            let reparsed = Forwarded::parse(&forwarded.to_string()).unwrap();
+            assert_eq!(reparsed, forwarded);
+        }
+    }
+});
@@ -0,0 +1,19 @@
+//! Idempotency-Key ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::idempotency_key::{IdempotencyKey, RequestIdempotency};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(key) = IdempotencyKey::parse(s) {
+        assert!(!key.as_str().is_empty());
+        assert_eq!(IdempotencyKey::parse(&key.to_string()), Ok(key));
+    }
+    let _ = IdempotencyKey::new(s);
+    let _ = RequestIdempotency::classify(s, None).is_retryable();
+});
@@ -0,0 +1,19 @@
+//! Link ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::link::Link;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data)
+        && let Ok(link) = Link::parse(s)
+    {
+        for value in link.values() {
+            let _ = value.title();
+            let _ = value.media_type();
+        }
+        let reparsed = Link::parse(&link.to_string()).unwrap();
+        assert_eq!(reparsed, link);
+    }
+});
@@ -0,0 +1,36 @@
+//! HTTP メッセージ検査のパニック安全性と、違反がなければエンコードできることを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::lint::{validate_request, validate_response};
+use shiguredo_http11::{Request, Response};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let mut lines = s.split('\n');
+    let target = lines.next().unwrap_or("/");
+
+    let Ok(mut request) = Request::new("POST", target) else {
+        return;
+    };
+    let mut response = Response::new(200, "OK").unwrap();
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let _ = request.add_header(name, value);
+        let _ = response.add_header(name, value);
+    }
+    request.set_body(data.to_vec());
+    response.set_body(data.to_vec());
+
+    if !validate_request(&request).iter().any(|v| v.is_error()) {
+        assert!(request.encode().is_ok());
+    }
+    if !validate_response(&response).iter().any(|v| v.is_error()) {
+        assert!(response.encode().is_ok());
+    }
+});
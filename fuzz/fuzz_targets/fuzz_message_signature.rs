@@ -0,0 +1,32 @@
+//! HTTP Message Signatures のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use std::borrow::Cow;
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::message_signature::{Signature, SignatureInput, SignedMessage};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(input) = SignatureInput::parse(s) {
+        assert_eq!(SignatureInput::parse(&input.to_string()), Ok(input.clone()));
+
+        // 入力自体をヘッダー値と request-target に使って署名ベースを構築する
+        let headers: Vec<(Cow<'static, str>, String)> = vec![
+            ("Host".into(), "example.com".to_string()),
+            ("Signature-Input".into(), s.to_string()),
+        ];
+        let target = s.split_whitespace().next().unwrap_or("/");
+        let message = SignedMessage::request("GET", target, &headers).with_scheme("https");
+        for (_, params) in input.iter() {
+            let _ = params.signature_base(&message);
+        }
+    }
+    if let Ok(signature) = Signature::parse(s) {
+        assert_eq!(Signature::parse(&signature.to_string()), Ok(signature));
+    }
+});
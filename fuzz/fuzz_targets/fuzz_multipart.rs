@@ -4,11 +4,25 @@
 //!   "---") を用いて、同じ任意データに対するパースを試行する
 //! - 各パートの name, filename, content_type, body, body_str, is_file
 //!   アクセサを呼び出し、パニックしないことを確認する
+//! - `with_max_depth(4)` で入れ子の multipart を展開し、入れ子のパートも再帰的に辿る
 
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use shiguredo_http11::multipart::MultipartParser;
+use shiguredo_http11::multipart::{MultipartParser, Part};
+
+fn visit(part: &Part) {
+    let _ = part.name();
+    let _ = part.filename();
+    let _ = part.content_type();
+    let _ = part.body();
+    let _ = part.body_str();
+    let _ = part.is_file();
+    let _ = part.is_multipart();
+    for nested in part.parts() {
+        visit(nested);
+    }
+}
 
 fuzz_target!(|data: &[u8]| {
     // 様々な境界でパースを試行
@@ -27,5 +41,11 @@ fuzz_target!(|data: &[u8]| {
             let _ = part.body_str();
             let _ = part.is_file();
         }
+
+        let mut parser = MultipartParser::new(boundary).with_max_depth(4);
+        let _ = parser.feed(data);
+        while let Ok(Some(part)) = parser.next_part() {
+            visit(&part);
+        }
     }
 });
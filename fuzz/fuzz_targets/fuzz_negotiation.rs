@@ -0,0 +1,40 @@
+//! プロアクティブネゴシエーションのパニック安全性と、候補から選ぶことを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::Request;
+use shiguredo_http11::negotiation::Negotiator;
+
+const MEDIA_TYPES: &[&str] = &["application/json", "text/html"];
+const ENCODINGS: &[&str] = &["br", "gzip"];
+const LANGUAGES: &[&str] = &["en", "ja"];
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    let mut request = Request::new("GET", "/").unwrap();
+    for (name, value) in ["Accept", "Accept-Encoding", "Accept-Language"]
+        .into_iter()
+        .zip(s.split('\n'))
+    {
+        let _ = request.add_header(name, value);
+    }
+
+    let negotiator = Negotiator::new()
+        .with_media_types(MEDIA_TYPES)
+        .with_encodings(ENCODINGS)
+        .with_languages(LANGUAGES);
+    if let Ok(negotiation) = negotiator.negotiate(&request) {
+        assert!(MEDIA_TYPES.contains(&negotiation.media_type().unwrap()));
+        assert!(LANGUAGES.contains(&negotiation.language().unwrap()));
+        if let Some(encoding) = negotiation.content_encoding() {
+            assert!(ENCODINGS.contains(&encoding));
+        }
+        assert_eq!(
+            negotiation.vary_header().as_deref(),
+            Some("Accept, Accept-Encoding, Accept-Language")
+        );
+    }
+});
@@ -0,0 +1,22 @@
+//! Prefer / Preference-Applied のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::prefer::{Prefer, PreferenceApplied};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(prefer) = Prefer::parse(s) {
+        let _ = prefer.return_preference();
+        let _ = prefer.wait();
+        let _ = prefer.handling();
+        assert_eq!(Prefer::parse(&prefer.to_string()), Ok(prefer));
+    }
+    if let Ok(applied) = PreferenceApplied::parse(s) {
+        assert_eq!(PreferenceApplied::parse(&applied.to_string()), Ok(applied));
+    }
+});
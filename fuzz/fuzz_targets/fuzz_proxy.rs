@@ -0,0 +1,36 @@
+//! プロキシのメッセージ書き換えのパニック安全性と、書き換え結果が再デコードできることを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::proxy::ProxyRewriter;
+use shiguredo_http11::via::ViaEntry;
+use shiguredo_http11::{BodyKind, HttpHead, RequestDecoder, ResponseDecoder};
+
+fuzz_target!(|data: &[u8]| {
+    let rewriter = ProxyRewriter::new().with_via(ViaEntry::new("1.1", "fuzz").unwrap());
+
+    let mut decoder = RequestDecoder::new();
+    if decoder.feed(data).is_ok()
+        && let Ok(Some((head, body_kind))) = decoder.decode_headers()
+        && let Ok(request) = rewriter.rewrite_request(&head, body_kind, None)
+    {
+        assert!(request.head().get_header("Connection").is_none());
+        let mut decoder = RequestDecoder::new();
+        decoder.feed(&request.encode_head()).unwrap();
+        let _ = decoder.decode_headers();
+    }
+
+    let mut decoder = ResponseDecoder::new();
+    if decoder.feed(data).is_ok()
+        && let Ok(Some((head, body_kind))) = decoder.decode_headers()
+        && let Ok(response) = rewriter.rewrite_response(&head, body_kind)
+    {
+        if body_kind != BodyKind::CloseDelimited {
+            assert!(response.head().get_header("Connection").is_none());
+        }
+        let mut decoder = ResponseDecoder::new();
+        decoder.feed(&response.encode_head()).unwrap();
+        let _ = decoder.decode_headers();
+    }
+});
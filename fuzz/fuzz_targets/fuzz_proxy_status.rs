@@ -0,0 +1,17 @@
+//! Proxy-Status のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::proxy_status::ProxyStatus;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(status) = ProxyStatus::parse(s) {
+        let _ = status.first_error().map(|entry| entry.error());
+        assert_eq!(ProxyStatus::parse(&status.to_string()), Ok(status));
+    }
+});
@@ -1,16 +1,20 @@
 //! Range 関連ヘッダーのパニック安全性と Display ラウンドトリップを検証する
 //!
 //! - Range: パースと unit, ranges, first アクセサ、to_bounds(1000) / to_bounds(0)
-//!   による境界計算、Display ラウンドトリップを検証する
+//!   による境界計算、evaluate(1000) / evaluate(0) による評価と Content-Range / 416 レスポンスの生成、
+//!   Display ラウンドトリップを検証する
 //! - Content-Range: パースと start, end, complete_length, is_unsatisfied
-//!   アクセサ、Display ラウンドトリップを検証する
-//! - Accept-Ranges: パースと accepts_bytes, is_none アクセサを検証する
+//!   アクセサ、validate_upload、Display ラウンドトリップを検証する
+//! - Accept-Ranges: パースと accepts, accepts_bytes, is_none アクセサを検証する
+//! - if_range_allows(): 任意の If-Range 値に対してパニックしないことを検証する
 //! - ContentRange::new_bytes(): バイナリデータから直接構築してアクセサと Display を検証する
 
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use shiguredo_http11::range::{AcceptRanges, ContentRange, Range};
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::range::{AcceptRanges, ContentRange, Range, if_range_allows};
 
 fuzz_target!(|data: &[u8]| {
     // ContentRange::new_bytes() の直接構築経路
@@ -56,6 +60,17 @@ fuzz_target!(|data: &[u8]| {
                 let _ = spec.to_bounds(1000);
                 let _ = spec.to_bounds(0);
             }
+
+            // evaluate テスト
+            for complete_length in [1000, 0] {
+                let satisfaction = range.evaluate(complete_length);
+                let _ = satisfaction.ranges();
+                let _ = satisfaction.content_range();
+                let _ = satisfaction.content_ranges();
+                if let Some(response) = satisfaction.to_unsatisfiable_response() {
+                    assert_eq!(response.status_code(), 416);
+                }
+            }
         }
 
         // Content-Range パース
@@ -66,6 +81,10 @@ fuzz_target!(|data: &[u8]| {
             let _ = cr.complete_length();
             let _ = cr.length();
             let _ = cr.is_unsatisfied();
+            if let Some(length) = cr.length() {
+                let _ = cr.validate_upload(length);
+            }
+            let _ = cr.validate_upload(0);
 
             // Display ラウンドトリップ
             let displayed = cr.to_string();
@@ -77,6 +96,15 @@ fuzz_target!(|data: &[u8]| {
             let _ = ar.units();
             let _ = ar.accepts_bytes();
             let _ = ar.is_none();
+            for unit in ar.units() {
+                assert_eq!(ar.accepts(unit), !ar.is_none());
+            }
         }
+
+        // If-Range 評価
+        let etag = EntityTag::strong("fuzz").unwrap();
+        let modified = HttpDate::from_unix_seconds(784111777).unwrap();
+        let _ = if_range_allows(s, Some(&etag), Some(&modified));
+        let _ = if_range_allows(s, None, None);
     }
 });
@@ -0,0 +1,20 @@
+//! RateLimit / RateLimit-Policy のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::rate_limit::{RateLimit, RateLimitPolicy};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(policy) = RateLimitPolicy::parse(s) {
+        assert_eq!(RateLimitPolicy::parse(&policy.to_string()), Ok(policy));
+    }
+    if let Ok(limit) = RateLimit::parse(s) {
+        let _ = limit.retry_after();
+        assert_eq!(RateLimit::parse(&limit.to_string()), Ok(limit));
+    }
+});
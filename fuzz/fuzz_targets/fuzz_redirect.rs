@@ -0,0 +1,25 @@
+//! リダイレクトの追従のパニック安全性と、追従したリクエストがエンコードできることを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::redirect::RedirectPolicy;
+use shiguredo_http11::{Request, ResponseDecoder};
+
+fuzz_target!(|data: &[u8]| {
+    let url = "https://example.com/a/b?x=1#top";
+    let (request, _) = Request::from_url("POST", url).unwrap();
+    let request = request.body(b"body".to_vec());
+
+    let mut decoder = ResponseDecoder::new();
+    if decoder.feed(data).is_err() {
+        return;
+    }
+    let Ok(Some((head, _))) = decoder.decode_headers() else {
+        return;
+    };
+    if let Ok(Some(redirect)) = RedirectPolicy::new().follow(&request, url, &head, 0) {
+        assert!(redirect.request().encode().is_ok());
+        let _ = RedirectPolicy::new().follow(redirect.request(), redirect.url(), &head, 1);
+    }
+});
@@ -0,0 +1,25 @@
+//! request-target のパニック安全性と Display ラウンドトリップ、path / query アクセサを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::Request;
+use shiguredo_http11::request_target::{RequestTarget, classify};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(target) = RequestTarget::parse(s) {
+        assert_eq!(Some(target.form()), classify(s));
+        let displayed = target.to_string();
+        assert_eq!(RequestTarget::parse(&displayed), Ok(target));
+    }
+    if let Ok(request) = Request::new("GET", s) {
+        let _ = request.path();
+        let _ = request.query();
+        let _ = request.decoded_path();
+        let _ = request.query_pairs();
+    }
+});
@@ -0,0 +1,47 @@
+//! 再送ポリシーのパニック安全性と、待ち時間が上限を超えないことを検証する
+
+#![no_main]
+
+use core::time::Duration;
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::retry::{Failure, Jitter, RetryPolicy};
+use shiguredo_http11::{Request, ResponseDecoder};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 16 {
+        return;
+    }
+    let (params, input) = data.split_at(16);
+    let attempt = u32::from_le_bytes(params[0..4].try_into().unwrap());
+    let now = u32::from_le_bytes(params[4..8].try_into().unwrap()) as u64;
+    let random_value = u64::from_le_bytes(params[8..16].try_into().unwrap());
+
+    let jitter = match params[0] % 3 {
+        0 => Jitter::None,
+        1 => Jitter::Full,
+        _ => Jitter::Equal,
+    };
+    let policy = RetryPolicy::new().with_jitter(jitter);
+    let delay = policy.backoff(attempt, random_value);
+    assert!(delay <= Duration::from_secs(10));
+
+    let mut decoder = ResponseDecoder::new();
+    if decoder.feed(input).is_err() {
+        return;
+    }
+    let Ok(Some((head, _))) = decoder.decode_headers() else {
+        return;
+    };
+    let request = Request::new("GET", "/").unwrap();
+    let decision = policy.decide(
+        &request,
+        &Failure::Response(&head),
+        attempt,
+        now,
+        random_value,
+    );
+    if let Some(delay) = decision.delay() {
+        assert!(delay <= Duration::from_secs(60));
+    }
+});
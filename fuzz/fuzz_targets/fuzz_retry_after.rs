@@ -0,0 +1,26 @@
+//! Retry-After ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+//!
+//! - 任意の UTF-8 文字列で RetryAfter::parse() を呼び出す
+//! - パース成功時は retry_at() と delay_from() を呼び出し、
+//!   Display 出力の再パースで一致を確認する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::retry_after::RetryAfter;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data)
+        && let Ok(retry_after) = RetryAfter::parse(s, 2026)
+    {
+        let _ = retry_after.is_delay();
+        let _ = retry_after.is_date();
+        let _ = retry_after.retry_at(0);
+        let _ = retry_after.retry_at(u64::MAX);
+        let _ = retry_after.delay_from(1_700_000_000);
+
+        let displayed = retry_after.to_string();
+        let reparsed = RetryAfter::parse(&displayed, 2026).expect("Display output must reparse");
+        assert_eq!(reparsed.retry_at(0), retry_after.retry_at(0));
+    }
+});
@@ -0,0 +1,34 @@
+//! セキュリティヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::security_headers::{
+    ContentTypeOptions, CrossOriginEmbedderPolicy, CrossOriginOpenerPolicy, FrameOptions,
+    PermissionsPolicy, ReferrerPolicy, StrictTransportSecurity,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(hsts) = StrictTransportSecurity::parse(s) {
+        assert_eq!(StrictTransportSecurity::parse(&hsts.to_string()), Ok(hsts));
+    }
+    if let Ok(coop) = CrossOriginOpenerPolicy::parse(s) {
+        assert_eq!(CrossOriginOpenerPolicy::parse(&coop.to_string()), Ok(coop));
+    }
+    if let Ok(coep) = CrossOriginEmbedderPolicy::parse(s) {
+        assert_eq!(
+            CrossOriginEmbedderPolicy::parse(&coep.to_string()),
+            Ok(coep)
+        );
+    }
+    if let Ok(policy) = PermissionsPolicy::parse(s) {
+        assert_eq!(PermissionsPolicy::parse(&policy.to_string()), Ok(policy));
+    }
+    let _ = ContentTypeOptions::parse(s);
+    let _ = FrameOptions::parse(s);
+    let _ = ReferrerPolicy::parse(s);
+});
@@ -0,0 +1,21 @@
+//! Server-Timing のパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::server_timing::ServerTiming;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(timing) = ServerTiming::parse(s) {
+        for metric in timing.metrics() {
+            let _ = metric.duration();
+            let _ = metric.duration_ms();
+            let _ = metric.description();
+        }
+        assert_eq!(ServerTiming::parse(&timing.to_string()), Ok(timing));
+    }
+});
@@ -0,0 +1,22 @@
+//! Structured Field Values のパニック安全性とシリアライズのラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::sfv::{Dictionary, Item, List};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(item) = Item::parse(s) {
+        assert_eq!(Item::parse(&item.to_string()), Ok(item));
+    }
+    if let Ok(list) = List::parse(s) {
+        assert_eq!(List::parse(&list.to_string()), Ok(list));
+    }
+    if let Ok(dictionary) = Dictionary::parse(s) {
+        assert_eq!(Dictionary::parse(&dictionary.to_string()), Ok(dictionary));
+    }
+});
@@ -0,0 +1,33 @@
+//! Server-Sent Events のデコーダーのパニック安全性と、再エンコードしたイベントが同じ内容で
+//! デコードされることを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::sse::{Event, SseDecoder};
+
+fuzz_target!(|data: &[u8]| {
+    let mut decoder = SseDecoder::new().with_max_buffer_size(4096);
+    for chunk in data.chunks(7) {
+        if decoder.feed(chunk).is_err() {
+            return;
+        }
+        loop {
+            match decoder.next_event() {
+                Ok(Some(event)) => check_roundtrip(&event),
+                Ok(None) => break,
+                Err(_) => return,
+            }
+        }
+    }
+});
+
+fn check_roundtrip(event: &Event) {
+    let mut decoder = SseDecoder::new();
+    decoder.feed(&event.encode()).unwrap();
+    let decoded = decoder.next_event().unwrap().unwrap();
+    assert_eq!(decoded.data(), event.data());
+    assert_eq!(decoded.event(), event.event());
+    assert_eq!(decoded.id(), event.id());
+    assert_eq!(decoded.retry(), event.retry());
+}
@@ -0,0 +1,29 @@
+//! 静的コンテンツのレスポンス決定のパニック安全性と、範囲が表現に収まることを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::RequestDecoder;
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::static_content::StaticResource;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&length, data)) = data.split_first() else {
+        return;
+    };
+    let mut decoder = RequestDecoder::new();
+    if decoder.feed(data).is_err() {
+        return;
+    }
+    let Ok(Some((head, _))) = decoder.decode_headers() else {
+        return;
+    };
+
+    let length = u64::from(length);
+    let resource = StaticResource::new(length).with_etag(EntityTag::strong("v1").unwrap());
+    let plan = resource.plan(&head, 0);
+    for &(start, end) in plan.ranges() {
+        assert!(start <= end && end < length);
+    }
+    let _ = plan.to_response();
+});
@@ -0,0 +1,19 @@
+//! TE ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::te::Te;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(te) = Te::parse(s) {
+        let _ = te.accepts_trailers();
+        let _ = te.negotiate(&["gzip", "deflate", "chunked"]);
+        assert!(te.accepts("chunked"));
+        assert_eq!(Te::parse(&te.to_string()), Ok(te));
+    }
+});
@@ -0,0 +1,50 @@
+//! ワイヤーレベルのトレースを検証する
+//!
+//! - 任意のバイト列をリクエストとして分割フィードし、observer でイベントを受け取る
+//! - 各イベントの raw が入力の offset 位置のバイト列と一致し、直後に CRLF が続くことを確認する
+
+#![no_main]
+
+use std::sync::{Arc, Mutex};
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::RequestDecoder;
+use shiguredo_http11::trace::WireEvent;
+
+#[derive(Arbitrary, Debug)]
+struct FuzzTrace {
+    input: Vec<u8>,
+    split_hint: u8,
+}
+
+fuzz_target!(|data: FuzzTrace| {
+    let split = (data.split_hint as usize % 32) + 1;
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let captured = log.clone();
+
+    let mut decoder = RequestDecoder::new();
+    decoder.set_wire_observer(Box::new(move |event: WireEvent<'_>| {
+        let entry = match event {
+            WireEvent::StartLine { offset, raw }
+            | WireEvent::Header { offset, raw, .. }
+            | WireEvent::Chunk { offset, raw, .. }
+            | WireEvent::Trailer { offset, raw, .. } => (offset, raw.to_vec()),
+            _ => return,
+        };
+        captured.lock().unwrap().push(entry);
+    }));
+
+    for part in data.input.chunks(split) {
+        if decoder.feed(part).is_err() || decoder.decode().is_err() {
+            break;
+        }
+    }
+
+    for (offset, raw) in log.lock().unwrap().iter() {
+        let start = *offset as usize;
+        let end = start + raw.len();
+        assert_eq!(&data.input[start..end], raw.as_slice());
+        assert_eq!(&data.input[end..end + 2], b"\r\n");
+    }
+});
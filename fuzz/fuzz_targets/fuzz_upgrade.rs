@@ -1,9 +1,9 @@
-//! Upgrade ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+//! Upgrade / HTTP2-Settings ヘッダーのパニック安全性と Display ラウンドトリップを検証する
 
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use shiguredo_http11::upgrade::Upgrade;
+use shiguredo_http11::upgrade::{Http2Settings, Upgrade};
 
 fuzz_target!(|data: &[u8]| {
     if let Ok(s) = std::str::from_utf8(data)
@@ -17,4 +17,10 @@ fuzz_target!(|data: &[u8]| {
         let displayed = value.to_string();
         let _ = Upgrade::parse(&displayed);
     }
+    if let Ok(s) = std::str::from_utf8(data)
+        && let Ok(settings) = Http2Settings::parse(s)
+    {
+        let reparsed = Http2Settings::parse(&settings.to_string()).unwrap();
+        assert_eq!(reparsed, settings);
+    }
 });
@@ -5,11 +5,14 @@
 //! - normalize() による URI 正規化のパニック安全性を確認する
 //! - 絶対 URI をベースとした resolve() による相対 URI 解決を検証する
 //! - percent_encode → percent_decode のラウンドトリップを確認する
+//! - parse_query → serialize_query → parse_query のラウンドトリップを確認する
 
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use shiguredo_http11::uri::{Uri, normalize, percent_decode, percent_encode, resolve};
+use shiguredo_http11::uri::{
+    Uri, normalize, parse_query, percent_decode, percent_encode, resolve, serialize_query,
+};
 
 fuzz_target!(|data: &[u8]| {
     // UTF-8 文字列として解釈できる場合のみテスト
@@ -43,5 +46,11 @@ fuzz_target!(|data: &[u8]| {
         // パーセントエンコード/デコード
         let _ = percent_encode(s);
         let _ = percent_decode(s);
+
+        // クエリ文字列
+        if let Ok(pairs) = parse_query(s) {
+            let query = serialize_query(pairs.clone());
+            assert_eq!(parse_query(&query).unwrap(), pairs);
+        }
     }
 });
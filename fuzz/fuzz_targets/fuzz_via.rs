@@ -0,0 +1,16 @@
+//! Via ヘッダーのパニック安全性と Display ラウンドトリップを検証する
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use shiguredo_http11::via::Via;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data)
+        && let Ok(via) = Via::parse(s)
+    {
+        let _ = via.has_pseudonym("proxy");
+        let reparsed = Via::parse(&via.to_string()).unwrap();
+        assert_eq!(reparsed, via);
+    }
+});
@@ -33,6 +33,31 @@ pub fn language_tag() -> impl Strategy<Value = String> {
         })
 }
 
+/// well-formed な言語タグ (RFC 5646 Section 2.1 の langtag)
+///
+/// language ["-" script] ["-" region] *("-" variant) *("-" extension) ["-" privateuse]
+pub fn well_formed_language_tag() -> impl Strategy<Value = String> {
+    (
+        "[A-Za-z]{2,3}|[A-Za-z]{5,8}",
+        proptest::option::of("[A-Z][a-z]{3}"),
+        proptest::option::of("[A-Z]{2}|[0-9]{3}"),
+        proptest::collection::vec("[A-Za-z0-9]{5,8}|[0-9][A-Za-z0-9]{3}", 0..=2),
+        proptest::collection::vec("[a-wyz0-9](-[A-Za-z0-9]{2,8}){1,2}", 0..=2),
+        proptest::option::of("x(-[A-Za-z0-9]{1,8}){1,2}"),
+    )
+        .prop_map(
+            |(language, script, region, variants, extensions, private_use)| {
+                let mut subtags = vec![language];
+                subtags.extend(script);
+                subtags.extend(region);
+                subtags.extend(variants);
+                subtags.extend(extensions);
+                subtags.extend(private_use);
+                subtags.join("-")
+            },
+        )
+}
+
 // ========================================
 // quoted-string 用 char / String strategy (RFC 9110 Section 5.6.4)
 // ========================================
@@ -1,7 +1,10 @@
 //! Accept 系ヘッダーのプロパティテスト
 
 use proptest::prelude::*;
-use shiguredo_http11::accept::{Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, QValue};
+use shiguredo_http11::accept::{
+    Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, QValue, filter_languages,
+    lookup_language, negotiate, negotiate_encoding,
+};
 
 // ========================================
 // Strategy 定義
@@ -378,3 +381,166 @@ proptest! {
         prop_assert_eq!(&reparsed_item.parameters()[0].1, &value);
     }
 }
+
+// ========================================
+// メディアタイプのネゴシエーションの PBT
+// ========================================
+
+// 選ばれた候補は候補一覧に含まれ、q 値が 0 より大きく、他のどの候補よりも q 値が低くない
+proptest! {
+    #[test]
+    fn prop_negotiate_selects_best(
+        ranges in proptest::collection::vec((accept_media_range(), 0u16..=1000), 1..5),
+        available in proptest::collection::vec(
+            (accept_media_type_token(), accept_media_subtype_token())
+                .prop_map(|(t, s)| format!("{}/{}", t, s)),
+            0..5,
+        ),
+    ) {
+        let header = ranges
+            .iter()
+            .map(|(range, q)| format!("{};q={}", range, accept_qvalue_string(*q)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let accept = Accept::parse(&header).unwrap();
+        let candidates: Vec<&str> = available.iter().map(|s| s.as_str()).collect();
+
+        match negotiate(&accept, &candidates) {
+            Some(selected) => {
+                prop_assert!(candidates.contains(&selected));
+                let q = accept.quality(selected).unwrap();
+                prop_assert!(q.value() > 0);
+                for candidate in &candidates {
+                    if let Some(other) = accept.quality(candidate) {
+                        prop_assert!(other <= q);
+                    }
+                }
+            }
+            None => {
+                for candidate in &candidates {
+                    prop_assert!(accept.quality(candidate).is_none_or(|q| q.value() == 0));
+                }
+            }
+        }
+    }
+}
+
+// ========================================
+// 言語のネゴシエーションの PBT
+// ========================================
+
+// 基本フィルタリングの結果は q 値の降順で、q=0 と不一致の言語タグを含まない
+proptest! {
+    #[test]
+    fn prop_filter_languages_sorted(
+        ranges in proptest::collection::vec((accept_language_tag(), 0u16..=1000), 1..5),
+        available in proptest::collection::vec(accept_language_tag(), 0..6),
+    ) {
+        let header = ranges
+            .iter()
+            .map(|(range, q)| format!("{};q={}", range, accept_qvalue_string(*q)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let accept = AcceptLanguage::parse(&header).unwrap();
+        let candidates: Vec<&str> = available.iter().map(|s| s.as_str()).collect();
+
+        let filtered = filter_languages(&accept, &candidates);
+        let qualities: Vec<QValue> = filtered
+            .iter()
+            .map(|tag| accept.quality(tag).unwrap())
+            .collect();
+        prop_assert!(qualities.iter().all(|q| q.value() > 0));
+        prop_assert!(qualities.windows(2).all(|w| w[0] >= w[1]));
+        for candidate in &candidates {
+            let included = filtered.contains(candidate);
+            let acceptable = accept.quality(candidate).is_some_and(|q| q.value() > 0);
+            prop_assert_eq!(included, acceptable);
+        }
+    }
+}
+
+// ルックアップの結果は候補一覧に含まれ、q=0 で除外されていない
+proptest! {
+    #[test]
+    fn prop_lookup_language_selects_available(
+        ranges in proptest::collection::vec((accept_language_tag(), 0u16..=1000), 1..5),
+        available in proptest::collection::vec(accept_language_tag(), 0..6),
+    ) {
+        let header = ranges
+            .iter()
+            .map(|(range, q)| format!("{};q={}", range, accept_qvalue_string(*q)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let accept = AcceptLanguage::parse(&header).unwrap();
+        let candidates: Vec<&str> = available.iter().map(|s| s.as_str()).collect();
+
+        if let Some(selected) = lookup_language(&accept, &candidates) {
+            prop_assert!(candidates.contains(&selected));
+            // 切り詰めで一致した言語タグは基本フィルタリングでは一致しないことがある
+            prop_assert!(accept.quality(selected).is_none_or(|q| q.value() > 0));
+        }
+    }
+}
+
+// ========================================
+// コンテンツコーディングのネゴシエーションの PBT
+// ========================================
+
+// 選ばれたコーディングは q 値が 0 より大きい候補か identity で、他の候補より q 値が低くない
+proptest! {
+    #[test]
+    fn prop_negotiate_encoding_selects_best(
+        ranges in proptest::collection::vec(
+            (
+                prop_oneof![
+                    Just("*".to_string()),
+                    Just("identity".to_string()),
+                    Just("gzip".to_string()),
+                    Just("br".to_string()),
+                    Just("zstd".to_string()),
+                    Just("deflate".to_string()),
+                ],
+                0u16..=1000,
+            ),
+            0..5,
+        ),
+    ) {
+        let header = ranges
+            .iter()
+            .map(|(coding, q)| format!("{};q={}", coding, accept_qvalue_string(*q)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let accept = AcceptEncoding::parse(&header).unwrap();
+        let available = ["zstd", "br", "gzip"];
+
+        match negotiate_encoding(&accept, &available) {
+            Some("identity") => {
+                let identity = accept.quality("identity");
+                prop_assert!(identity.is_none_or(|q| q.value() > 0));
+                for candidate in available {
+                    // identity の q 値より小さい候補しかない
+                    let below_identity = accept.quality(candidate).is_none_or(|q| {
+                        q.value() == 0 || identity.is_some_and(|identity_q| q < identity_q)
+                    });
+                    prop_assert!(below_identity);
+                }
+            }
+            Some(selected) => {
+                let q = accept.quality(selected).unwrap();
+                prop_assert!(q.value() > 0);
+                for candidate in available {
+                    if let Some(other) = accept.quality(candidate) {
+                        prop_assert!(other <= q);
+                    }
+                }
+                prop_assert!(accept.quality("identity").is_none_or(|identity_q| identity_q <= q));
+            }
+            None => {
+                prop_assert_eq!(accept.quality("identity").map(|q| q.value()), Some(0));
+                for candidate in available {
+                    prop_assert!(accept.quality(candidate).is_none_or(|q| q.value() == 0));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Allow ヘッダーのプロパティテスト (allow.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::Response;
+use shiguredo_http11::allow::Allow;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn method() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("GET".to_string()),
+        Just("HEAD".to_string()),
+        Just("POST".to_string()),
+        Just("OPTIONS".to_string()),
+        "[A-Z][A-Z-]{0,10}",
+    ]
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_allow_roundtrip(methods in proptest::collection::vec(method(), 0..8)) {
+        let methods: Vec<&str> = methods.iter().map(String::as_str).collect();
+        let allow = Allow::from_methods(&methods).unwrap();
+        // 重複は除かれる
+        for method in &methods {
+            prop_assert!(allow.contains(method));
+        }
+        prop_assert!(allow.methods().len() <= methods.len());
+        prop_assert_eq!(Allow::parse(&allow.to_string()).unwrap(), allow);
+    }
+
+    #[test]
+    fn prop_allow_parse_ows(methods in proptest::collection::vec(method(), 1..8)) {
+        let input = methods.join(" ,\t");
+        let allow = Allow::parse(&input).unwrap();
+        let methods: Vec<&str> = methods.iter().map(String::as_str).collect();
+        prop_assert_eq!(allow, Allow::from_methods(&methods).unwrap());
+    }
+
+    #[test]
+    fn prop_method_not_allowed(methods in proptest::collection::vec(method(), 0..8)) {
+        let methods: Vec<&str> = methods.iter().map(String::as_str).collect();
+        let response = Response::method_not_allowed(&methods).unwrap();
+        prop_assert_eq!(response.status_code(), 405);
+        let allow = Allow::from_message(&response).unwrap().unwrap();
+        prop_assert_eq!(allow, Allow::from_methods(&methods).unwrap());
+        prop_assert_eq!(response.body_bytes(), Some(&b""[..]));
+    }
+}
+
+// ========================================
+// パニック安全性
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_allow_parse_no_panic(input in "[ -~\t]{0,64}") {
+        let _ = Allow::parse(&input);
+    }
+}
@@ -3,7 +3,7 @@
 use proptest::prelude::*;
 use shiguredo_http11::auth::{
     AuthChallenge, Authorization, BasicAuth, BearerChallenge, BearerToken, DigestAuth,
-    DigestChallenge, ProxyAuthenticate, ProxyAuthorization, WwwAuthenticate,
+    DigestChallenge, ProxyAuthenticate, ProxyAuthorization, WwwAuthenticate, secure_eq,
 };
 
 // ========================================
@@ -110,6 +110,33 @@ proptest! {
     }
 }
 
+// secure_eq は == と同じ結果を返す
+proptest! {
+    #[test]
+    fn prop_secure_eq_matches_eq(
+        a in proptest::collection::vec(any::<u8>(), 0..16),
+        b in proptest::collection::vec(any::<u8>(), 0..16),
+    ) {
+        prop_assert_eq!(secure_eq(&a, &b), a == b);
+        prop_assert!(secure_eq(&a, &a));
+    }
+}
+
+// BasicAuth::verify はユーザー名とパスワードの両方が一致する場合のみ true
+proptest! {
+    #[test]
+    fn prop_basic_auth_verify(
+        username in "[a-zA-Z][a-zA-Z0-9]{0,7}",
+        password in password_with_colon(),
+        other in "[a-zA-Z0-9:]{0,8}",
+    ) {
+        let auth = BasicAuth::parse(&BasicAuth::new(&username, &password).unwrap().to_header_value()).unwrap();
+        prop_assert!(auth.verify(&username, &password));
+        prop_assert_eq!(auth.verify(&username, &other), other == password);
+        prop_assert_eq!(auth.verify(&other, &password), other == username);
+    }
+}
+
 // BasicAuth スキーム名の大文字小文字を区別しない
 proptest! {
     #[test]
@@ -0,0 +1,74 @@
+//! 鮮度計算のプロパティテスト
+
+use std::borrow::Cow;
+
+use proptest::prelude::*;
+use shiguredo_http11::cache::freshness::{CacheKind, Freshness, LifetimeSource, ResponseTiming};
+use shiguredo_http11::date::HttpDate;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+// 2000-01-01 から 2100-01-01 までの UNIX 時間
+fn unix_time() -> impl Strategy<Value = u64> {
+    946684800u64..4102444800
+}
+
+fn cache_kind() -> impl Strategy<Value = CacheKind> {
+    prop_oneof![Just(CacheKind::Private), Just(CacheKind::Shared)]
+}
+
+// ========================================
+// 鮮度計算の PBT
+// ========================================
+
+// max-age による鮮度は time_to_live と staleness が一貫する
+proptest! {
+    #[test]
+    fn prop_freshness_max_age_consistent(
+        date in unix_time(),
+        max_age in 0u64..86400,
+        age in 0u64..86400,
+        delay in 0u64..60,
+        resident in 0u64..86400,
+        kind in cache_kind(),
+    ) {
+        let headers: Vec<(Cow<'static, str>, String)> = vec![
+            ("Date".into(), HttpDate::from_unix_seconds(date).unwrap().to_string()),
+            ("Age".into(), age.to_string()),
+            ("Cache-Control".into(), format!("max-age={}", max_age)),
+        ];
+        let timing = ResponseTiming::new(date, date + delay, date + delay + resident);
+        let f = Freshness::calculate(200, &headers, timing, kind);
+
+        prop_assert_eq!(f.lifetime(), max_age);
+        prop_assert_eq!(f.lifetime_source(), LifetimeSource::MaxAge);
+        // apparent_age と corrected_age_value の大きい方に滞在時間を加える
+        prop_assert_eq!(f.current_age(), (age + delay).max(delay) + resident);
+        prop_assert_eq!(f.is_fresh(), f.time_to_live() > 0);
+        prop_assert_eq!(f.is_fresh(), !f.requires_revalidation());
+        prop_assert!(f.time_to_live() == 0 || f.staleness() == 0);
+    }
+}
+
+// 現在の Age は判定時刻に対して単調増加する
+proptest! {
+    #[test]
+    fn prop_freshness_age_monotonic(
+        date in unix_time(),
+        now1 in 0u64..86400,
+        now2 in 0u64..86400,
+        kind in cache_kind(),
+    ) {
+        let headers: Vec<(Cow<'static, str>, String)> = vec![
+            ("Date".into(), HttpDate::from_unix_seconds(date).unwrap().to_string()),
+            ("Cache-Control".into(), "max-age=3600".to_string()),
+        ];
+        let (early, late) = if now1 <= now2 { (now1, now2) } else { (now2, now1) };
+        let f1 = Freshness::calculate(200, &headers, ResponseTiming::new(date, date, date + early), kind);
+        let f2 = Freshness::calculate(200, &headers, ResponseTiming::new(date, date, date + late), kind);
+        prop_assert!(f1.current_age() <= f2.current_age());
+        prop_assert!(f1.time_to_live() >= f2.time_to_live());
+    }
+}
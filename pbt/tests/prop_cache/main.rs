@@ -1,4 +1,12 @@
-//! キャッシュヘッダーのプロパティテスト
+//! キャッシュのプロパティテスト
+//!
+//! キャッシュヘッダー (Cache-Control / Expires / Age) のテストはここに置き、
+//! サブモジュールのテストは `src/cache/` のモジュールごとに分割する。
+//! - `freshness`: RFC 9111 の鮮度計算
+//! - `store`: インメモリ HTTP キャッシュ `HttpCache`
+
+mod freshness;
+mod store;
 
 use proptest::prelude::*;
 use shiguredo_http11::cache::{Age, CacheControl, Expires};
@@ -64,6 +72,44 @@ proptest! {
     }
 }
 
+// 修飾形式と拡張ディレクティブを含めたラウンドトリップ
+proptest! {
+    #[test]
+    fn prop_cache_control_fields_and_extensions_roundtrip(
+        no_cache_fields in proptest::collection::vec("[A-Za-z][A-Za-z0-9-]{0,15}", 0..3),
+        private_fields in proptest::collection::vec("[A-Za-z][A-Za-z0-9-]{0,15}", 0..3),
+        extensions in proptest::collection::vec(
+            ("x-[a-z0-9-]{1,8}", prop::option::of("[ -~]{0,16}")),
+            0..3,
+        ),
+        max_stale in prop::option::of(seconds()),
+        min_fresh in prop::option::of(seconds()),
+        stale_if_error in prop::option::of(seconds()),
+    ) {
+        let mut cc = CacheControl::new()
+            .with_no_cache_fields(no_cache_fields.clone())
+            .with_private_fields(private_fields.clone());
+        for (name, value) in &extensions {
+            cc = cc.with_extension(name, value.as_deref());
+        }
+        if let Some(seconds) = max_stale {
+            cc = cc.with_max_stale(seconds);
+        }
+        if let Some(seconds) = min_fresh {
+            cc = cc.with_min_fresh(seconds);
+        }
+        if let Some(seconds) = stale_if_error {
+            cc = cc.with_stale_if_error(seconds);
+        }
+
+        let reparsed = CacheControl::parse(&cc.to_string()).unwrap();
+        prop_assert_eq!(reparsed.no_cache_fields(), no_cache_fields.as_slice());
+        prop_assert_eq!(reparsed.private_fields(), private_fields.as_slice());
+        prop_assert_eq!(reparsed.extensions(), cc.extensions());
+        prop_assert_eq!(reparsed, cc);
+    }
+}
+
 // is_cacheable の正確性
 proptest! {
     #[test]
@@ -0,0 +1,108 @@
+//! インメモリ HTTP キャッシュのプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::cache::freshness::CacheKind;
+use shiguredo_http11::cache::{HttpCache, update_stored_response};
+use shiguredo_http11::{Request, Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Store { path: u8, body_len: usize },
+    Lookup { path: u8 },
+    Invalidate { path: u8 },
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        (0u8..8, 0usize..200).prop_map(|(path, body_len)| Operation::Store { path, body_len }),
+        (0u8..8).prop_map(|path| Operation::Lookup { path }),
+        (0u8..8).prop_map(|path| Operation::Invalidate { path }),
+    ]
+}
+
+fn request(method: &str, path: u8) -> Request {
+    Request::new(method, format!("/{}", path))
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+}
+
+// ========================================
+// サイズ上限の PBT
+// ========================================
+
+// どの操作列でも合計サイズは上限を超えず、保存直後のエントリは参照できる
+proptest! {
+    #[test]
+    fn prop_http_cache_size_bounded(
+        max_size in 0usize..1000,
+        operations in proptest::collection::vec(operation(), 0..50),
+    ) {
+        let mut cache = HttpCache::new(CacheKind::Private, max_size);
+        for operation in operations {
+            match operation {
+                Operation::Store { path, body_len } => {
+                    let response = Response::with_status(StatusCode::OK)
+                        .header("Cache-Control", "max-age=60")
+                        .unwrap()
+                        .body(vec![0u8; body_len]);
+                    let stored = cache.store(&request("GET", path), &response, 0, 0);
+                    if stored {
+                        let hit = cache.lookup(&request("GET", path), 0).unwrap();
+                        prop_assert_eq!(hit.response().body_bytes().unwrap().len(), body_len);
+                    }
+                }
+                Operation::Lookup { path } => {
+                    let _ = cache.lookup(&request("GET", path), 0);
+                }
+                Operation::Invalidate { path } => {
+                    let response = Response::with_status(StatusCode::NO_CONTENT);
+                    cache.invalidate(&request("PUT", path), &response);
+                    prop_assert!(cache.lookup(&request("GET", path), 0).is_none());
+                }
+            }
+            prop_assert!(cache.size() <= max_size);
+            if cache.is_empty() {
+                prop_assert_eq!(cache.size(), 0);
+            }
+        }
+    }
+}
+
+// ========================================
+// 304 Not Modified による更新の PBT
+// ========================================
+
+// 更新後は 304 のフィールドの値を持ち、除外するフィールドとボディは変わらない
+proptest! {
+    #[test]
+    fn prop_update_stored_response_overrides(
+        stored_value in "[a-z0-9]{1,8}",
+        new_value in "[a-z0-9]{1,8}",
+        body in proptest::collection::vec(any::<u8>(), 0..32),
+    ) {
+        let mut stored = Response::with_status(StatusCode::OK)
+            .header("X-Value", &stored_value)
+            .unwrap()
+            .header("Content-Length", body.len().to_string())
+            .unwrap()
+            .body(body.clone());
+        let raw = format!(
+            "HTTP/1.1 304 Not Modified\r\nX-Value: {}\r\nContent-Length: 999\r\n\r\n",
+            new_value
+        );
+        let mut decoder = ResponseDecoder::new();
+        decoder.feed(raw.as_bytes()).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+        update_stored_response(&mut stored, &head).unwrap();
+        prop_assert_eq!(stored.get_headers("X-Value"), vec![new_value.as_str()]);
+        let expected_length = body.len().to_string();
+        prop_assert_eq!(stored.get_header("Content-Length"), Some(expected_length.as_str()));
+        prop_assert_eq!(stored.body_bytes(), Some(body.as_slice()));
+    }
+}
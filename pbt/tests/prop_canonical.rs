@@ -0,0 +1,64 @@
+//! メッセージの正規化と差分のプロパティテスト (canonical.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::canonical::{Canonicalizer, DiffLine};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn headers() -> impl Strategy<Value = Vec<(String, String)>> {
+    proptest::collection::vec(("X-[A-Za-z]{1,3}", "[!-~]{0,8}"), 0..8)
+}
+
+fn request(headers: &[(String, String)]) -> Request {
+    let mut request = Request::new("GET", "/").unwrap();
+    for (name, value) in headers {
+        request.add_header(name, value).unwrap();
+    }
+    request
+}
+
+// ========================================
+// 正規化と差分
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_diff_self_is_none(headers in headers()) {
+        let request = request(&headers);
+        prop_assert!(Canonicalizer::new().diff_requests(&request, &request).is_none());
+    }
+
+    #[test]
+    fn prop_diff_lines_reconstruct(left in headers(), right in headers()) {
+        let canonicalizer = Canonicalizer::new();
+        let a = canonicalizer.request(&request(&left));
+        let b = canonicalizer.request(&request(&right));
+        let Some(diff) = a.diff(&b) else {
+            prop_assert_eq!(a, b);
+            return Ok(());
+        };
+        // Same + Removed は左側、Same + Added は右側の行を順に再構成する
+        let mut left_lines = Vec::new();
+        let mut right_lines = Vec::new();
+        for line in diff.lines() {
+            match line {
+                DiffLine::Same(line) => {
+                    left_lines.push(line.clone());
+                    right_lines.push(line.clone());
+                }
+                DiffLine::Removed(line) => left_lines.push(line.clone()),
+                DiffLine::Added(line) => right_lines.push(line.clone()),
+            }
+        }
+        let expected = |message: &shiguredo_http11::canonical::CanonicalMessage| {
+            let mut lines = vec![message.start_line().to_string()];
+            lines.extend(message.headers().iter().map(|(n, v)| format!("{n}: {v}")));
+            lines
+        };
+        prop_assert_eq!(left_lines, expected(&a));
+        prop_assert_eq!(right_lines, expected(&b));
+    }
+}
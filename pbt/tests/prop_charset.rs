@@ -0,0 +1,64 @@
+//! charset を考慮したボディのテキストデコードのプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::charset::{Charset, decode_text_lossy};
+use shiguredo_http11::content_type::ContentType;
+
+// ========================================
+// デコードのテスト
+// ========================================
+
+proptest! {
+    /// UTF-8 文字列は UTF-8 としてそのままデコードされる
+    #[test]
+    fn prop_charset_utf8_roundtrip(s in "\\PC{0,64}") {
+        let decoded = Charset::Utf8.decode(s.as_bytes()).unwrap();
+        prop_assert_eq!(decoded.trim_start_matches('\u{FEFF}'), s.trim_start_matches('\u{FEFF}'));
+    }
+}
+
+proptest! {
+    /// ISO-8859-1 は任意のバイト列をデコードでき、文字数はバイト数と一致する
+    #[test]
+    fn prop_charset_iso_8859_1_total(bytes in proptest::collection::vec(any::<u8>(), 0..128)) {
+        let decoded = Charset::Iso8859_1.decode(&bytes).unwrap();
+        prop_assert_eq!(decoded.chars().count(), bytes.len());
+        let encoded: Vec<u8> = decoded.chars().map(|c| c as u32 as u8).collect();
+        prop_assert_eq!(encoded, bytes);
+    }
+}
+
+proptest! {
+    /// US-ASCII のデコードは ASCII のバイト列の場合のみ成功する
+    #[test]
+    fn prop_charset_us_ascii(bytes in proptest::collection::vec(any::<u8>(), 0..128)) {
+        let result = Charset::UsAscii.decode(&bytes);
+        prop_assert_eq!(result.is_ok(), bytes.is_ascii());
+        prop_assert_eq!(Charset::UsAscii.decode_lossy(&bytes).chars().count(), bytes.len());
+    }
+}
+
+proptest! {
+    /// decode が成功する場合は decode_lossy と結果が一致する
+    #[test]
+    fn prop_charset_lossy_matches_strict(
+        bytes in proptest::collection::vec(any::<u8>(), 0..128),
+        charset in prop_oneof![Just(Charset::Utf8), Just(Charset::UsAscii), Just(Charset::Iso8859_1)]
+    ) {
+        if let Ok(decoded) = charset.decode(&bytes) {
+            prop_assert_eq!(decoded, charset.decode_lossy(&bytes));
+        }
+    }
+}
+
+proptest! {
+    /// decode_text_lossy は任意の charset ラベルでパニックしない
+    #[test]
+    fn prop_charset_decode_text_lossy_any_label(
+        label in "[a-zA-Z0-9_-]{1,16}",
+        bytes in proptest::collection::vec(any::<u8>(), 0..64)
+    ) {
+        let ct = ContentType::new("text", "plain").with_parameter("charset", &label);
+        let _ = decode_text_lossy(Some(&ct), &bytes);
+    }
+}
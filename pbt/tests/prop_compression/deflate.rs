@@ -0,0 +1,41 @@
+//! deflate 実装のプロパティテスト (compression/deflate.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::compression::{DeflateCompressor, DeflateDecompressor};
+
+use super::{compress_with, data_strategy, decompress_with};
+
+// ========================================
+// 往復のテスト
+// ========================================
+
+proptest! {
+    /// deflate で圧縮したデータは分割の仕方によらず元に戻る
+    #[test]
+    fn prop_deflate_roundtrip(
+        data in data_strategy(),
+        input_chunk in 1usize..512,
+        output_chunk in 1usize..512,
+    ) {
+        let compressed = compress_with(&mut DeflateCompressor::new(), &data, input_chunk, output_chunk);
+        let output = decompress_with(&mut DeflateDecompressor::new(), &compressed, input_chunk, output_chunk);
+        prop_assert_eq!(output, Some(data));
+    }
+}
+
+// ========================================
+// 不正なデータのテスト
+// ========================================
+
+proptest! {
+    /// 任意のバイト列を展開してもパニックせず、分割の仕方で結果が変わらない
+    #[test]
+    fn prop_decompress_arbitrary_input(
+        data in proptest::collection::vec(any::<u8>(), 0..256),
+        input_chunk in 1usize..64,
+    ) {
+        let whole = decompress_with(&mut DeflateDecompressor::new(), &data, data.len().max(1), 8192);
+        let split = decompress_with(&mut DeflateDecompressor::new(), &data, input_chunk, 7);
+        prop_assert_eq!(whole, split);
+    }
+}
@@ -0,0 +1,58 @@
+//! gzip 実装のプロパティテスト (compression/gzip.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::compression::{GzipCompressor, GzipDecompressor};
+
+use super::{compress_with, data_strategy, decompress_with};
+
+// ========================================
+// 往復のテスト
+// ========================================
+
+proptest! {
+    /// gzip で圧縮したデータは分割の仕方によらず元に戻る
+    #[test]
+    fn prop_gzip_roundtrip(
+        data in data_strategy(),
+        input_chunk in 1usize..512,
+        output_chunk in 1usize..512,
+    ) {
+        let compressed = compress_with(&mut GzipCompressor::new(), &data, input_chunk, output_chunk);
+        let output = decompress_with(&mut GzipDecompressor::new(), &compressed, input_chunk, output_chunk);
+        prop_assert_eq!(output, Some(data));
+    }
+}
+
+proptest! {
+    /// 圧縮結果は入力や出力の分割の仕方に依存しない
+    #[test]
+    fn prop_compress_deterministic(
+        data in data_strategy(),
+        input_chunk in 1usize..512,
+        output_chunk in 1usize..512,
+    ) {
+        let expected = compress_with(&mut GzipCompressor::new(), &data, data.len().max(1), 8192);
+        let actual = compress_with(&mut GzipCompressor::new(), &data, input_chunk, output_chunk);
+        prop_assert_eq!(actual, expected);
+    }
+}
+
+// ========================================
+// 不正なデータのテスト
+// ========================================
+
+proptest! {
+    /// トレイラー (CRC-32 / ISIZE) のビットを反転すると展開はエラーになる
+    #[test]
+    fn prop_gzip_detects_trailer_corruption(
+        data in proptest::collection::vec(any::<u8>(), 0..256),
+        index in 0usize..8,
+        bit in 0u8..8,
+    ) {
+        let mut compressed = compress_with(&mut GzipCompressor::new(), &data, data.len().max(1), 8192);
+        let i = compressed.len() - 8 + index;
+        compressed[i] ^= 1 << bit;
+        let output = decompress_with(&mut GzipDecompressor::new(), &compressed, compressed.len(), 8192);
+        prop_assert_eq!(output, None);
+    }
+}
@@ -0,0 +1,79 @@
+//! 組み込みの gzip / deflate 実装のプロパティテスト (compression/)
+//!
+//! `compression-impl` feature の組み込み実装のテストを `src/compression/` のモジュールごとに分割する。
+//! ここには各サブモジュールで共通利用するヘルパーを置く。
+//! - `deflate`: deflate (zlib / raw DEFLATE) の圧縮・展開
+//! - `gzip`: gzip の圧縮・展開
+
+mod deflate;
+mod gzip;
+
+use proptest::prelude::*;
+use shiguredo_http11::compression::{Compressor, Decompressor};
+
+/// 入力を `input_chunk` バイトずつ、出力を `output_chunk` バイトずつ処理して圧縮する
+pub(crate) fn compress_with<C: Compressor>(
+    compressor: &mut C,
+    data: &[u8],
+    input_chunk: usize,
+    output_chunk: usize,
+) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    let mut output = vec![0u8; output_chunk];
+    for chunk in data.chunks(input_chunk) {
+        let mut input = chunk;
+        loop {
+            let status = compressor.compress(input, &mut output).unwrap();
+            compressed.extend_from_slice(&output[..status.produced()]);
+            input = &input[status.consumed()..];
+            if input.is_empty() && !status.is_output_full() {
+                break;
+            }
+        }
+    }
+    loop {
+        let status = compressor.finish(&mut output).unwrap();
+        compressed.extend_from_slice(&output[..status.produced()]);
+        if status.is_complete() {
+            break;
+        }
+    }
+    compressed
+}
+
+/// 入力を `input_chunk` バイトずつ、出力を `output_chunk` バイトずつ処理して展開する
+///
+/// 展開が完了しない場合やエラーの場合は None を返す。
+pub(crate) fn decompress_with<D: Decompressor>(
+    decompressor: &mut D,
+    data: &[u8],
+    input_chunk: usize,
+    output_chunk: usize,
+) -> Option<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    let mut output = vec![0u8; output_chunk];
+    let mut input = data;
+    loop {
+        let chunk = &input[..input.len().min(input_chunk)];
+        let status = decompressor.decompress(chunk, &mut output).ok()?;
+        decompressed.extend_from_slice(&output[..status.produced()]);
+        input = &input[status.consumed()..];
+        if status.is_complete() {
+            return Some(decompressed);
+        }
+        // 空の入力 (入力の終端) で完了しなければ途切れている
+        if chunk.is_empty() && status.produced() == 0 && !status.is_output_full() {
+            return None;
+        }
+    }
+}
+
+/// 繰り返しを含みやすいバイト列
+pub(crate) fn data_strategy() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        proptest::collection::vec(any::<u8>(), 0..2048),
+        proptest::collection::vec(prop::sample::select(b"abc \r\n".to_vec()), 0..4096),
+        (proptest::collection::vec(any::<u8>(), 1..64), 1usize..200)
+            .prop_map(|(unit, n)| unit.repeat(n)),
+    ]
+}
@@ -0,0 +1,68 @@
+//! 適合性テスト用ハーネスのプロパティテスト (conformance.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::conformance::{Harness, Outcome};
+use shiguredo_http11::encode_chunk;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn header() -> impl Strategy<Value = (String, String)> {
+    ("X-[A-Za-z0-9]{1,10}", "[ -~]{0,20}")
+}
+
+fn body() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    proptest::collection::vec(proptest::collection::vec(any::<u8>(), 1..16), 0..4)
+}
+
+// ========================================
+// 分割位置によらないデコード
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_conformance_request_all_splits(
+        headers in proptest::collection::vec(header(), 0..4),
+        chunks in body(),
+        chunked in any::<bool>(),
+    ) {
+        let data: Vec<u8> = chunks.concat();
+        let mut input = b"POST / HTTP/1.1\r\nHost: example.com\r\n".to_vec();
+        for (name, value) in &headers {
+            input.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        if chunked {
+            input.extend_from_slice(b"Transfer-Encoding: chunked\r\n\r\n");
+            for chunk in &chunks {
+                input.extend_from_slice(&encode_chunk(chunk));
+            }
+            input.extend_from_slice(b"0\r\n\r\n");
+        } else {
+            input.extend_from_slice(format!("Content-Length: {}\r\n\r\n", data.len()).as_bytes());
+            input.extend_from_slice(&data);
+        }
+
+        let outcomes = Harness::request().replay_all_splits(&input);
+        prop_assert!(outcomes.is_ok());
+        let outcomes = outcomes.unwrap();
+        let mut expected_len = 2;
+        if !data.is_empty() {
+            prop_assert_eq!(&outcomes[1], &Outcome::Body(data));
+            expected_len += 1;
+        }
+        prop_assert_eq!(outcomes.len(), expected_len);
+        let is_complete = matches!(outcomes.last(), Some(Outcome::Complete { .. }));
+        prop_assert!(is_complete);
+    }
+
+    #[test]
+    fn prop_conformance_response_arbitrary(
+        input in proptest::collection::vec(any::<u8>(), 0..64),
+        eof in any::<bool>(),
+    ) {
+        // 任意のバイト列でも分割位置によらず同じ結果になる
+        let result = Harness::response().with_eof(eof).replay_all_splits(&input);
+        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! 接続管理のプロパティテスト
+
+use std::borrow::Cow;
+
+use proptest::prelude::*;
+use shiguredo_http11::connection::{
+    ConnectionOptions, ResponsePersistence, is_hop_by_hop, strip_hop_by_hop,
+};
+use shiguredo_http11::{BodyKind, Request, RequestDecoder, Response};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn version() -> impl Strategy<Value = &'static str> {
+    prop_oneof![Just("HTTP/1.0"), Just("HTTP/1.1")]
+}
+
+fn connection() -> impl Strategy<Value = Option<&'static str>> {
+    prop_oneof![
+        Just(None),
+        Just(Some("close")),
+        Just(Some("keep-alive")),
+        Just(Some("Keep-Alive")),
+        Just(Some("keep-alive, close")),
+    ]
+}
+
+fn framing() -> impl Strategy<Value = BodyKind> {
+    prop_oneof![
+        (0u64..1024).prop_map(BodyKind::ContentLength),
+        Just(BodyKind::Chunked),
+        Just(BodyKind::CloseDelimited),
+        Just(BodyKind::None),
+    ]
+}
+
+// ========================================
+// プロパティテスト
+// ========================================
+
+proptest! {
+    // HTTP/1.0 のクライアントには Transfer-Encoding を送らず、持続する場合は必ず
+    // Connection: keep-alive と Content-Length (またはボディなし) になる
+    #[test]
+    fn prop_http10_never_chunked(conn in connection(), framing in framing()) {
+        let mut request = Request::with_version("GET", "/", "HTTP/1.0").unwrap();
+        if let Some(value) = conn {
+            request.add_header("Connection", value).unwrap();
+        }
+        let plan = ResponsePersistence::for_request(&request, framing);
+        prop_assert_eq!(plan.transfer_encoding_header(), None);
+        if plan.is_keep_alive() {
+            prop_assert_eq!(plan.connection_header(), Some("keep-alive"));
+            prop_assert!(matches!(plan.framing(), BodyKind::ContentLength(_) | BodyKind::None));
+        } else {
+            prop_assert_eq!(plan.connection_header(), Some("close"));
+        }
+    }
+
+    // 適用したレスポンスをデコードすると、クライアント側の持続判定が計画と一致する
+    #[test]
+    fn prop_apply_matches_client_view(
+        version in version(),
+        conn in connection(),
+        len in 0usize..64,
+    ) {
+        let mut input = format!("GET / {version}\r\nHost: a\r\n");
+        if let Some(value) = conn {
+            input.push_str(&format!("Connection: {value}\r\n"));
+        }
+        input.push_str("\r\n");
+        let mut decoder = RequestDecoder::new();
+        decoder.feed(input.as_bytes()).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+        let plan = ResponsePersistence::for_request(&head, BodyKind::ContentLength(len as u64));
+        let mut response = Response::with_version(version, 200, "OK").unwrap().body(vec![b'x'; len]);
+        plan.apply(&mut response).unwrap();
+        prop_assert_eq!(response.is_keep_alive(), plan.is_keep_alive());
+        prop_assert!(response.encode().is_ok());
+    }
+}
+
+// ========================================
+// hop-by-hop ヘッダー
+// ========================================
+
+fn header_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("Connection".to_string()),
+        Just("Keep-Alive".to_string()),
+        Just("TE".to_string()),
+        Just("Transfer-Encoding".to_string()),
+        "[A-Za-z][A-Za-z0-9-]{0,8}",
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_connection_options_roundtrip(options in proptest::collection::vec("[a-z0-9-]{1,10}", 0..5)) {
+        let mut connection = ConnectionOptions::new();
+        for option in &options {
+            connection = connection.with_option(option).unwrap();
+        }
+        prop_assert_eq!(ConnectionOptions::parse(&connection.to_string()), Ok(connection));
+    }
+
+    // 除去後に hop-by-hop ヘッダーは残らず、それ以外は順序を保って残る
+    #[test]
+    fn prop_strip_hop_by_hop(
+        headers in proptest::collection::vec((header_name(), "[a-zA-Z0-9, -]{0,16}"), 0..10)
+    ) {
+        let headers: Vec<(Cow<'static, str>, String)> = headers
+            .into_iter()
+            .map(|(name, value)| (Cow::Owned(name), value))
+            .collect();
+        let connection = ConnectionOptions::collect(&headers);
+        let expected: Vec<(Cow<'static, str>, String)> = headers
+            .iter()
+            .filter(|(name, _)| !is_hop_by_hop(name, &connection))
+            .cloned()
+            .collect();
+        let mut stripped = headers.clone();
+        strip_hop_by_hop(&mut stripped);
+        prop_assert!(stripped.iter().all(|(name, _)| !name.eq_ignore_ascii_case("Connection")));
+        prop_assert_eq!(stripped, expected);
+    }
+}
@@ -0,0 +1,89 @@
+//! Content-Encoding を展開するストリーミングリーダーのプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::ResponseDecoder;
+use shiguredo_http11::compression::{Compressor, GzipCompressor};
+use shiguredo_http11::content_decoder::{ContentDecoder, DefaultDecompressors};
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut compressor = GzipCompressor::new();
+    let mut output = vec![0u8; data.len() + 1024];
+    let status = compressor.compress(data, &mut output).unwrap();
+    let mut len = status.produced();
+    let status = compressor.finish(&mut output[len..]).unwrap();
+    len += status.produced();
+    output.truncate(len);
+    output
+}
+
+fn chunked(data: &[u8], chunk_size: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    for chunk in data.chunks(chunk_size) {
+        out.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b"0\r\n\r\n");
+    out
+}
+
+// ========================================
+// ストリーミング展開のテスト
+// ========================================
+
+proptest! {
+    /// 受信の分割、chunk サイズ、バッファサイズ、コーディングの数によらず元のボディが得られる
+    #[test]
+    fn prop_content_decoder_streaming(
+        data in proptest::collection::vec(prop::sample::select(b"abc xyz\n".to_vec()), 0..2048),
+        use_chunked in any::<bool>(),
+        chunk_size in 1usize..64,
+        feed_size in 1usize..64,
+        buffer_size in 1usize..64,
+        layers in 1usize..4,
+    ) {
+        let mut compressed = data.clone();
+        for _ in 0..layers {
+            compressed = gzip(&compressed);
+        }
+        let content_encoding = vec!["gzip"; layers].join(", ");
+        let (framing, body) = if use_chunked {
+            ("Transfer-Encoding: chunked".to_string(), chunked(&compressed, chunk_size))
+        } else {
+            (format!("Content-Length: {}", compressed.len()), compressed)
+        };
+        let message = [
+            format!("HTTP/1.1 200 OK\r\nContent-Encoding: {content_encoding}\r\n{framing}\r\n\r\n").into_bytes(),
+            body,
+        ]
+        .concat();
+
+        let mut decoder = ResponseDecoder::new();
+        let mut reader = None;
+        let mut output = Vec::new();
+        for piece in message.chunks(feed_size) {
+            decoder.feed(piece).unwrap();
+            if reader.is_none() {
+                let Some((head, _)) = decoder.decode_headers().unwrap() else {
+                    continue;
+                };
+                reader = Some(
+                    ContentDecoder::from_head(&head, &DefaultDecompressors)
+                        .unwrap()
+                        .with_buffer_size(buffer_size),
+                );
+            }
+            let reader = reader.as_mut().unwrap();
+            while let Some(data) = reader.peek_body(&mut decoder).unwrap() {
+                output.extend_from_slice(data);
+                let len = data.len();
+                reader.consume_body(len);
+            }
+        }
+
+        let reader = reader.unwrap();
+        prop_assert!(reader.is_complete());
+        prop_assert_eq!(reader.decoded_len(), data.len());
+        prop_assert_eq!(output, data);
+    }
+}
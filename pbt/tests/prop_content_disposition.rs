@@ -398,3 +398,21 @@ proptest! {
         prop_assert!(result.is_ok(), "count={}: {:?}", count, result);
     }
 }
+
+// ========================================
+// with_filename_utf8 のテスト
+// ========================================
+
+proptest! {
+    /// with_filename_utf8 で設定したファイル名は Display -> parse で復元される
+    #[test]
+    fn prop_content_disposition_filename_utf8_roundtrip(filename in "\\PC{1,32}") {
+        let cd = ContentDisposition::new(DispositionType::Attachment).with_filename_utf8(&filename);
+        let header = cd.to_string();
+        let parsed = ContentDisposition::parse(&header).unwrap();
+        prop_assert_eq!(parsed.filename(), Some(filename.as_str()));
+        // フォールバックの filename は常に ASCII
+        let ascii = parsed.filename_ascii().unwrap();
+        prop_assert!(ascii.is_ascii());
+    }
+}
@@ -1,7 +1,7 @@
 //! Content-Encoding ヘッダーのプロパティテスト
 
 use proptest::prelude::*;
-use shiguredo_http11::content_encoding::ContentEncoding;
+use shiguredo_http11::content_encoding::{ContentCoding, ContentEncoding};
 
 // ========================================
 // Strategy 定義
@@ -54,3 +54,31 @@ proptest! {
         prop_assert_eq!(ce.encodings().len(), encodings.len());
     }
 }
+
+// push したコーディングは pop で逆順に取り出せ、展開順は適用順の逆になる
+proptest! {
+    #[test]
+    fn prop_content_encoding_push_pop(encodings in proptest::collection::vec(custom_encoding(), 0..5)) {
+        let mut ce = ContentEncoding::new();
+        for enc in &encodings {
+            ce.push(ContentCoding::parse(enc).unwrap());
+        }
+
+        let reparsed = ContentEncoding::parse(&ce.to_string()).unwrap();
+        prop_assert_eq!(&reparsed, &ce);
+
+        let order: Vec<String> = ce.decoding_order().map(|c| c.as_str().to_string()).collect();
+        let mut popped = Vec::new();
+        while let Some(coding) = ce.pop() {
+            popped.push(coding.as_str().to_string());
+        }
+        let mut expected: Vec<String> = encodings
+            .iter()
+            .map(|enc| enc.to_lowercase())
+            .filter(|enc| enc != "identity")
+            .collect();
+        expected.reverse();
+        prop_assert_eq!(&popped, &expected);
+        prop_assert_eq!(&order, &expected);
+    }
+}
@@ -1,15 +1,15 @@
 //! Content-Language ヘッダーのプロパティテスト (content_language.rs)
 
 use proptest::prelude::*;
-use shiguredo_http11::content_language::ContentLanguage;
+use shiguredo_http11::content_language::{ContentLanguage, LanguageTag};
 
 // 言語タグ生成は pbt クレートを使用
-use pbt::language_tag;
+use pbt::{language_tag, well_formed_language_tag};
 
 // Content-Language のラウンドトリップ
 proptest! {
     #[test]
-    fn prop_content_language_roundtrip(tags in proptest::collection::vec(language_tag(), 1..4)) {
+    fn prop_content_language_roundtrip(tags in proptest::collection::vec(well_formed_language_tag(), 1..4)) {
         let header = tags.join(", ");
         let parsed = ContentLanguage::parse(&header).unwrap();
         let displayed = parsed.to_string();
@@ -17,3 +17,40 @@ proptest! {
         prop_assert_eq!(parsed, reparsed);
     }
 }
+
+// LanguageTag のパースとサブタグ分解
+proptest! {
+    #[test]
+    fn prop_language_tag_well_formed(tag in well_formed_language_tag()) {
+        let parsed = LanguageTag::parse(&tag).unwrap();
+        prop_assert_eq!(parsed.as_str(), tag.as_str());
+        prop_assert!(parsed.language().is_some());
+        // 自分自身と、先頭サブタグの言語レンジに一致する
+        prop_assert!(parsed.matches_basic_range(&tag));
+        prop_assert!(parsed.matches_extended_range(&tag));
+        let language = parsed.language().unwrap();
+        prop_assert!(parsed.matches_basic_range(language));
+        let wildcard = format!("{}-*", language);
+        prop_assert!(parsed.matches_extended_range(&wildcard));
+    }
+
+    #[test]
+    fn prop_language_tag_parse_no_panic(tag in language_tag()) {
+        if let Ok(parsed) = LanguageTag::parse(&tag) {
+            prop_assert_eq!(parsed.to_string(), tag.clone());
+            prop_assert!(ContentLanguage::parse(&tag).is_ok());
+        } else {
+            prop_assert!(ContentLanguage::parse(&tag).is_err());
+        }
+    }
+
+    #[test]
+    fn prop_basic_match_implies_extended_match(tag in well_formed_language_tag(), n in 1usize..6) {
+        // Basic Filtering で一致するレンジは Extended Filtering でも一致する
+        let parsed = LanguageTag::parse(&tag).unwrap();
+        let range: Vec<&str> = tag.split('-').take(n).collect();
+        let range = range.join("-");
+        prop_assert!(parsed.matches_basic_range(&range));
+        prop_assert!(parsed.matches_extended_range(&range));
+    }
+}
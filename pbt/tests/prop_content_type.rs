@@ -477,3 +477,37 @@ proptest! {
         prop_assert_eq!(reparsed.parameter("ext"), Some(value.as_str()));
     }
 }
+
+// ========================================
+// essence / サフィックスのテスト
+// ========================================
+
+proptest! {
+    /// パラメータの有無や大文字小文字に関わらず essence は一致する
+    #[test]
+    fn prop_content_type_essence_ignores_parameters(
+        media_type in "[a-z]{1,8}",
+        subtype in "[a-z]{1,8}",
+        charset in "[a-zA-Z0-9-]{1,16}"
+    ) {
+        let upper = format!("{}/{}; charset={}", media_type.to_ascii_uppercase(), subtype, charset);
+        let ct = ContentType::parse(&upper).unwrap();
+        let essence = format!("{}/{}", media_type, subtype);
+        prop_assert!(ct.essence_eq(&ContentType::new(&media_type, &subtype)));
+        prop_assert!(ct.is_essence(&essence));
+    }
+}
+
+proptest! {
+    /// "+suffix" 付きのサブタイプからサフィックスを取り出せる
+    #[test]
+    fn prop_content_type_suffix(
+        base in "[a-z][a-z0-9.-]{0,15}",
+        suffix in prop_oneof![Just("json"), Just("xml"), Just("cbor"), Just("zip")]
+    ) {
+        let ct = ContentType::parse(&format!("application/{}+{}", base, suffix)).unwrap();
+        prop_assert_eq!(ct.suffix(), Some(suffix));
+        prop_assert_eq!(ct.is_json_compatible(), suffix == "json");
+        prop_assert_eq!(ct.is_xml_compatible(), suffix == "xml");
+    }
+}
@@ -0,0 +1,99 @@
+//! CookieJar の PBT
+
+use proptest::prelude::*;
+use shiguredo_http11::cookie::{CookieJar, SetCookie};
+
+const NOW: u64 = 1_700_000_000;
+
+// ========================================
+// 保存と送信の PBT
+// ========================================
+
+// 保存した host-only Cookie は同じホストとパス配下にだけ送信される
+proptest! {
+    #[test]
+    fn prop_cookie_jar_host_only_scope(
+        name in "[a-zA-Z][a-zA-Z0-9]{0,7}",
+        value in "[a-zA-Z0-9]{0,16}",
+        host in "[a-z]{1,8}\\.[a-z]{2,4}",
+        sub in "[a-z]{1,8}",
+        path in "/[a-z]{1,8}",
+        rest in "[a-z]{0,8}",
+    ) {
+        let mut jar = CookieJar::new();
+        let set_cookie = SetCookie::new(&name, &value).unwrap().with_path(&path);
+        let origin = format!("http://{}/", host);
+        prop_assert!(jar.store(&origin, &set_cookie, NOW));
+
+        let expected = Some(format!("{}={}", name, value));
+        prop_assert_eq!(jar.cookie_header(&format!("http://{}{}", host, path), NOW), expected.clone());
+        prop_assert_eq!(jar.cookie_header(&format!("http://{}{}/{}", host, path, rest), NOW), expected);
+        prop_assert_eq!(jar.cookie_header(&format!("http://{}.{}{}", sub, host, path), NOW), None);
+        prop_assert_eq!(jar.cookie_header(&format!("http://{}/", host), NOW), None);
+    }
+}
+
+// Domain 属性付き Cookie はサブドメインにも送信される
+proptest! {
+    #[test]
+    fn prop_cookie_jar_domain_scope(
+        host in "[a-z]{1,8}\\.[a-z]{2,4}",
+        sub in "[a-z]{1,8}",
+        other in "[a-z]{1,8}\\.[a-z]{2,4}",
+    ) {
+        prop_assume!(host != other && !other.ends_with(&format!(".{}", host)));
+        let mut jar = CookieJar::new();
+        let set_cookie = SetCookie::new("a", "1").unwrap().with_domain(&host);
+        let subdomain_url = format!("https://{}.{}/x", sub, host);
+        let host_url = format!("https://{}/", host);
+        let other_url = format!("https://{}/", other);
+        prop_assert!(jar.store(&subdomain_url, &set_cookie, NOW));
+        prop_assert!(jar.cookie_header(&host_url, NOW).is_some());
+        prop_assert!(jar.cookie_header(&subdomain_url, NOW).is_some());
+        prop_assert!(jar.cookie_header(&other_url, NOW).is_none());
+    }
+}
+
+// Max-Age の期限前は送信され、期限後は送信されない
+proptest! {
+    #[test]
+    fn prop_cookie_jar_max_age(max_age in 1i64..=86400 * 365, elapsed in 0u64..=86400 * 366) {
+        let mut jar = CookieJar::new();
+        let set_cookie = SetCookie::new("a", "1").unwrap().with_max_age(max_age);
+        prop_assert!(jar.store("https://example.com/", &set_cookie, NOW));
+        let sent = jar.cookie_header("https://example.com/", NOW + elapsed).is_some();
+        prop_assert_eq!(sent, elapsed < max_age as u64);
+
+        jar.remove_expired(NOW + elapsed);
+        prop_assert_eq!(jar.is_empty(), !sent);
+    }
+}
+
+// 同じ name / domain / path の Cookie を繰り返し保存しても 1 つだけ残り、最後の値が送信される
+proptest! {
+    #[test]
+    fn prop_cookie_jar_replace(values in proptest::collection::vec("[a-zA-Z0-9]{0,8}", 1..8)) {
+        let mut jar = CookieJar::new();
+        for value in &values {
+            let set_cookie = SetCookie::new("a", value).unwrap().with_path("/");
+            prop_assert!(jar.store("https://example.com/", &set_cookie, NOW));
+        }
+        prop_assert_eq!(jar.len(), 1);
+        prop_assert_eq!(
+            jar.cookie_header("https://example.com/", NOW),
+            Some(format!("a={}", values.last().unwrap()))
+        );
+    }
+}
+
+// Secure Cookie は安全な URL でのみ送信される
+proptest! {
+    #[test]
+    fn prop_cookie_jar_secure(secure in any::<bool>()) {
+        let mut jar = CookieJar::new();
+        let set_cookie = SetCookie::new("a", "1").unwrap().with_secure(secure);
+        prop_assert!(jar.store("https://example.com/", &set_cookie, NOW));
+        prop_assert!(jar.cookie_header("https://example.com/", NOW).is_some());
+        prop_assert_eq!(jar.cookie_header("http://example.com/", NOW).is_some(), !secure);
+    }
+}
@@ -0,0 +1,234 @@
+//! Cookie のプロパティテスト (cookie/)
+//!
+//! Cookie / Set-Cookie ヘッダーのテストはここに置き、
+//! サブモジュールのテストは `src/cookie/` のモジュールごとに分割する。
+//! - `jar`: クライアント向け Cookie ストア `CookieJar`
+
+mod jar;
+
+use proptest::prelude::*;
+use shiguredo_http11::cookie::{Cookie, CookieError, CookieHeader, SameSite, SetCookie};
+use shiguredo_http11::date::HttpDate;
+
+// ========================================
+// Cookie パースのテスト
+// ========================================
+
+// Cookie のラウンドトリップ
+proptest! {
+    #[test]
+    fn prop_cookie_roundtrip(name in "[a-zA-Z][a-zA-Z0-9_-]{0,15}", value in "[a-zA-Z0-9_-]{0,32}") {
+        let cookie = Cookie::new(&name, &value).unwrap();
+        let displayed = cookie.to_string();
+        let cookies = Cookie::parse(&displayed).unwrap();
+        prop_assert_eq!(cookies.len(), 1);
+        prop_assert_eq!(cookies[0].name(), name.as_str());
+        prop_assert_eq!(cookies[0].value(), value.as_str());
+    }
+}
+
+// 複数 Cookie のパース
+proptest! {
+    #[test]
+    fn prop_cookie_parse_multiple(name1 in "[a-zA-Z][a-zA-Z0-9]{0,7}", value1 in "[a-zA-Z0-9]{0,16}", name2 in "[a-zA-Z][a-zA-Z0-9]{0,7}", value2 in "[a-zA-Z0-9]{0,16}") {
+        let cookie_str = format!("{}={}; {}={}", name1, value1, name2, value2);
+        let cookies = Cookie::parse(&cookie_str).unwrap();
+        prop_assert_eq!(cookies.len(), 2);
+        prop_assert_eq!(cookies[0].name(), name1.as_str());
+        prop_assert_eq!(cookies[0].value(), value1.as_str());
+        prop_assert_eq!(cookies[1].name(), name2.as_str());
+        prop_assert_eq!(cookies[1].value(), value2.as_str());
+    }
+}
+
+// SetCookie のラウンドトリップ
+proptest! {
+    #[test]
+    fn prop_set_cookie_roundtrip(name in "[a-zA-Z][a-zA-Z0-9_-]{0,15}", value in "[a-zA-Z0-9_-]{0,32}") {
+        let cookie = SetCookie::new(&name, &value).unwrap();
+        let displayed = cookie.to_string();
+        let reparsed = SetCookie::parse(&displayed, 2026).unwrap();
+        prop_assert_eq!(reparsed.name(), name.as_str());
+        prop_assert_eq!(reparsed.value(), value.as_str());
+    }
+}
+
+// SetCookie 属性付きラウンドトリップ
+proptest! {
+    #[test]
+    fn prop_set_cookie_with_attributes(name in "[a-zA-Z][a-zA-Z0-9]{0,7}", value in "[a-zA-Z0-9]{0,16}", path in "/[a-zA-Z0-9_-]{0,16}", max_age in 0i64..=86400) {
+        let cookie = SetCookie::new(&name, &value)
+            .unwrap()
+            .with_path(&path)
+            .with_max_age(max_age)
+            .with_secure(true)
+            .with_http_only(true);
+
+        let displayed = cookie.to_string();
+        let reparsed = SetCookie::parse(&displayed, 2026).unwrap();
+
+        prop_assert_eq!(reparsed.name(), name.as_str());
+        prop_assert_eq!(reparsed.value(), value.as_str());
+        prop_assert_eq!(reparsed.path(), Some(path.as_str()));
+        prop_assert_eq!(reparsed.max_age(), Some(max_age));
+        prop_assert!(reparsed.secure());
+        prop_assert!(reparsed.http_only());
+    }
+}
+
+// SameSite 属性ラウンドトリップ
+proptest! {
+    #[test]
+    fn prop_set_cookie_same_site(name in "[a-zA-Z][a-zA-Z0-9]{0,7}", value in "[a-zA-Z0-9]{0,16}", same_site in prop_oneof![Just(SameSite::Strict), Just(SameSite::Lax), Just(SameSite::None)]) {
+        let cookie = SetCookie::new(&name, &value)
+            .unwrap()
+            .with_same_site(same_site);
+
+        let displayed = cookie.to_string();
+        let reparsed = SetCookie::parse(&displayed, 2026).unwrap();
+
+        prop_assert_eq!(reparsed.same_site(), Some(same_site));
+    }
+}
+
+// Domain 属性付き SetCookie
+proptest! {
+    #[test]
+    fn prop_set_cookie_with_domain(name in "[a-zA-Z][a-zA-Z0-9]{0,7}", value in "[a-zA-Z0-9]{0,16}", domain in "[a-z]{1,8}\\.[a-z]{2,4}") {
+        let cookie = SetCookie::new(&name, &value).unwrap().with_domain(&domain);
+
+        let displayed = cookie.to_string();
+        let reparsed = SetCookie::parse(&displayed, 2026).unwrap();
+
+        prop_assert_eq!(reparsed.domain(), Some(domain.as_str()));
+    }
+}
+
+// ========================================
+// 全属性のラウンドトリップと検証の PBT
+// ========================================
+
+// 全属性を設定した SetCookie は Display -> parse で一致する
+proptest! {
+    #[test]
+    fn prop_set_cookie_full_roundtrip(
+        name in "[a-zA-Z][a-zA-Z0-9_-]{0,15}",
+        value in "[a-zA-Z0-9_-]{0,32}",
+        expires in proptest::option::of(0u64..253_402_300_799),
+        max_age in proptest::option::of(0i64..=86400 * 365),
+        domain in proptest::option::of("[a-z]{1,8}\\.[a-z]{2,4}"),
+        path in proptest::option::of("/[a-zA-Z0-9_-]{0,16}"),
+        secure in any::<bool>(),
+        http_only in any::<bool>(),
+        same_site in proptest::option::of(prop_oneof![Just(SameSite::Strict), Just(SameSite::Lax), Just(SameSite::None)]),
+        partitioned in any::<bool>(),
+    ) {
+        let mut cookie = SetCookie::new(&name, &value)
+            .unwrap()
+            .with_secure(secure)
+            .with_http_only(http_only)
+            .with_partitioned(partitioned);
+        if let Some(expires) = expires {
+            cookie = cookie.with_expires(HttpDate::from_unix_seconds(expires).unwrap());
+        }
+        if let Some(max_age) = max_age {
+            cookie = cookie.with_max_age(max_age);
+        }
+        if let Some(domain) = &domain {
+            cookie = cookie.with_domain(domain);
+        }
+        if let Some(path) = &path {
+            cookie = cookie.with_path(path);
+        }
+        if let Some(same_site) = same_site {
+            cookie = cookie.with_same_site(same_site);
+        }
+
+        let reparsed = SetCookie::parse(&cookie.to_string(), 2026).unwrap();
+        prop_assert_eq!(&reparsed, &cookie);
+        prop_assert_eq!(reparsed.validate(), cookie.validate());
+    }
+}
+
+// __Host- プレフィックスは Secure / Path=/ / Domain なしのときだけ妥当
+proptest! {
+    #[test]
+    fn prop_set_cookie_host_prefix(
+        suffix in "[a-zA-Z0-9]{1,8}",
+        secure in any::<bool>(),
+        root_path in any::<bool>(),
+        with_domain in any::<bool>(),
+    ) {
+        let mut cookie = SetCookie::new(&format!("__Host-{}", suffix), "v")
+            .unwrap()
+            .with_secure(secure)
+            .with_path(if root_path { "/" } else { "/app" });
+        if with_domain {
+            cookie = cookie.with_domain("example.com");
+        }
+        let ok = secure && root_path && !with_domain;
+        if ok {
+            prop_assert_eq!(cookie.validate(), Ok(()));
+        } else {
+            prop_assert_eq!(cookie.validate(), Err(CookieError::InvalidPrefix));
+        }
+    }
+}
+
+// cookie-date 形式 (ハイフン区切り 4 桁年) の Expires は IMF-fixdate と同じ日時になる
+proptest! {
+    #[test]
+    fn prop_set_cookie_expires_cookie_date(seconds in 0u64..253_402_300_799) {
+        let date = HttpDate::from_unix_seconds(seconds).unwrap();
+        let imf = date.to_string();
+        // "Sun, 06 Nov 1994 08:49:37 GMT" -> "Sun, 06-Nov-1994 08:49:37 GMT"
+        let cookie_date = format!("{}-{}-{}", &imf[..7], &imf[8..11], &imf[12..]);
+        let cookie = SetCookie::parse(&format!("id=a; Expires={}", cookie_date), 2026).unwrap();
+        prop_assert_eq!(cookie.expires(), Some(&date));
+    }
+}
+
+// ========================================
+// CookieHeader の PBT
+// ========================================
+
+// 組み立てた Cookie ヘッダーは寛容なパースで同じペアに戻る
+proptest! {
+    #[test]
+    fn prop_cookie_header_roundtrip(
+        pairs in proptest::collection::vec(("[a-zA-Z][a-zA-Z0-9_-]{0,7}", "[a-zA-Z0-9_-]{0,16}"), 0..8),
+    ) {
+        let mut header = CookieHeader::new();
+        for (name, value) in &pairs {
+            header = header.with_cookie(name, value).unwrap();
+        }
+        let reparsed = CookieHeader::parse(&header.to_string());
+        prop_assert_eq!(&reparsed, &header);
+        for (name, value) in &pairs {
+            let first = pairs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+            prop_assert_eq!(reparsed.get(name), first);
+            prop_assert!(reparsed.get_all(name).contains(&value.as_str()));
+        }
+    }
+}
+
+// 寛容なパースは不正なペアを読み飛ばし、正しいペアの順序を保つ
+proptest! {
+    #[test]
+    fn prop_cookie_header_lenient_skips_invalid(
+        valid in proptest::collection::vec(("[a-z]{1,8}", "[a-z0-9]{0,8}"), 1..6),
+        noise in proptest::collection::vec(prop_oneof![Just(""), Just(" "), Just("novalue"), Just("=x"), Just("a b=1"), Just("c=d e")], 0..6),
+    ) {
+        let mut parts: Vec<String> = Vec::new();
+        for (i, (name, value)) in valid.iter().enumerate() {
+            parts.push(format!("{}={}", name, value));
+            if let Some(n) = noise.get(i) {
+                parts.push(n.to_string());
+            }
+        }
+        let header = CookieHeader::parse(&parts.join(";"));
+        let names: Vec<&str> = header.cookies().iter().map(|c| c.name()).collect();
+        let expected: Vec<&str> = valid.iter().map(|(n, _)| n.as_str()).collect();
+        prop_assert_eq!(names, expected);
+    }
+}
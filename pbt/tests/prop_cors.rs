@@ -0,0 +1,66 @@
+//! CORS のプロパティテスト (cors.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::cors::{CorsOutcome, CorsPolicy, Origin};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn origin_string() -> impl Strategy<Value = String> {
+    (
+        prop_oneof![Just("http"), Just("https"), Just("HTTPS")],
+        "[a-zA-Z][a-zA-Z0-9-]{0,10}(\\.[a-z]{2,5}){0,2}",
+        proptest::option::of(1u16..),
+    )
+        .prop_map(|(scheme, host, port)| match port {
+            Some(port) => format!("{scheme}://{host}:{port}"),
+            None => format!("{scheme}://{host}"),
+        })
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// Origin の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_origin_roundtrip(input in origin_string()) {
+        let origin = Origin::parse(&input).unwrap();
+        let displayed = origin.to_string();
+        prop_assert_eq!(&displayed, &displayed.to_ascii_lowercase());
+        prop_assert_eq!(Origin::parse(&displayed).unwrap(), origin);
+    }
+
+    /// 許可したオリジンからのリクエストには正規化したオリジンを返す
+    #[test]
+    fn prop_cors_allowed_origin_echoed(input in origin_string(), credentials in any::<bool>()) {
+        let policy = CorsPolicy::new()
+            .with_allowed_origin(&input)
+            .unwrap()
+            .with_credentials(credentials);
+        let request = Request::new("GET", "/").unwrap().header("Origin", &input).unwrap();
+        let outcome = policy.evaluate("GET", &request);
+        let is_actual = matches!(outcome, CorsOutcome::Actual { .. });
+        prop_assert!(is_actual);
+
+        let origin = Origin::parse(&input).unwrap().to_string();
+        let headers = outcome.headers();
+        prop_assert!(headers.contains(&("Access-Control-Allow-Origin".to_string(), origin)));
+        prop_assert!(headers.contains(&("Vary".to_string(), "Origin".to_string())));
+    }
+
+    /// 許可していないオリジンには Access-Control-Allow-Origin を返さない
+    #[test]
+    fn prop_cors_other_origin_rejected(input in origin_string()) {
+        let policy = CorsPolicy::new()
+            .with_allowed_origin("https://allowed.invalid")
+            .unwrap();
+        let request = Request::new("GET", "/").unwrap().header("Origin", &input).unwrap();
+        let outcome = policy.evaluate("GET", &request);
+        prop_assert!(!outcome.is_allowed());
+        prop_assert!(outcome.headers().iter().all(|(name, _)| name == "Vary"));
+    }
+}
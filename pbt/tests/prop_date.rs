@@ -381,3 +381,63 @@ proptest! {
         prop_assert_eq!(date.second(), second);
     }
 }
+
+// ========================================
+// UNIX 時間との変換の PBT
+// ========================================
+
+// UNIX 時間 -> HttpDate -> UNIX 時間 で元に戻り、IMF-fixdate でも往復できる
+proptest! {
+    #[test]
+    fn prop_unix_seconds_roundtrip(seconds in 0u64..253402300800) {
+        let date = HttpDate::from_unix_seconds(seconds).unwrap();
+        prop_assert_eq!(date.unix_seconds(), seconds);
+        let reparsed = HttpDate::parse(&date.to_string()).unwrap();
+        prop_assert_eq!(reparsed, date);
+    }
+}
+
+// ========================================
+// 3 形式のパースと SystemTime の PBT
+// ========================================
+
+// 同じ日時を IMF-fixdate / rfc850-date / asctime で表したものは同じ HttpDate になる
+proptest! {
+    #[test]
+    fn prop_parse_any_all_formats_agree(seconds in 0u64..253402300800) {
+        let date = HttpDate::from_unix_seconds(seconds).unwrap();
+        // 2 桁年が曖昧にならないよう基準年を日付の年に合わせる
+        let reference_year = date.year();
+        let long_day = match date.day_of_week() {
+            DayOfWeek::Sunday => "Sunday",
+            DayOfWeek::Monday => "Monday",
+            DayOfWeek::Tuesday => "Tuesday",
+            DayOfWeek::Wednesday => "Wednesday",
+            DayOfWeek::Thursday => "Thursday",
+            DayOfWeek::Friday => "Friday",
+            DayOfWeek::Saturday => "Saturday",
+        };
+        let imf = date.to_string();
+        let month = &imf[8..11];
+        let time = format!("{:02}:{:02}:{:02}", date.hour(), date.minute(), date.second());
+        let rfc850 = format!("{}, {:02}-{}-{:02} {} GMT", long_day, date.day(), month, date.year() % 100, time);
+        let asctime = format!("{} {} {:>2} {} {}", date.day_of_week().short_name(), month, date.day(), time, date.year());
+
+        prop_assert_eq!(HttpDate::parse_any(&imf, reference_year).unwrap(), date.clone());
+        prop_assert_eq!(HttpDate::parse_any(&rfc850, reference_year).unwrap(), date.clone());
+        prop_assert_eq!(HttpDate::parse_any(&asctime, reference_year).unwrap(), date);
+    }
+}
+
+// SystemTime -> HttpDate -> SystemTime で秒単位に切り捨てた時刻に戻る
+proptest! {
+    #[test]
+    fn prop_system_time_roundtrip(seconds in 0u64..253402300800, nanos in 0u32..1_000_000_000) {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let time = UNIX_EPOCH + Duration::new(seconds, nanos);
+        let date = HttpDate::from_system_time(time).unwrap();
+        prop_assert_eq!(date.unix_seconds(), seconds);
+        prop_assert_eq!(date.to_system_time(), UNIX_EPOCH + Duration::from_secs(seconds));
+    }
+}
@@ -2,8 +2,8 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::{
-    BodyKind, BodyProgress, DecoderLimits, Error, RequestDecoder, ResponseDecoder, encode_chunk,
-    encode_chunks,
+    BodyKind, BodyProgress, BodyRead, DecoderLimits, Error, ParsePhase, RequestDecoder,
+    ResponseDecoder, encode_chunk, encode_chunks,
 };
 
 use super::body;
@@ -219,6 +219,44 @@ proptest! {
     }
 }
 
+proptest! {
+    /// read_body() は dst サイズや feed の分割位置によらず同じボディを返す
+    #[test]
+    fn prop_chunked_read_body_roundtrip(
+        chunks in proptest::collection::vec(body(), 1..5),
+        dst_size in 1usize..32,
+        feed_size in 1usize..32,
+    ) {
+        let non_empty_chunks: Vec<Vec<u8>> = chunks.into_iter().filter(|c| !c.is_empty()).collect();
+        let chunk_refs: Vec<&[u8]> = non_empty_chunks.iter().map(|c| c.as_slice()).collect();
+        let encoded = encode_chunks(&chunk_refs);
+
+        let mut decoder = ResponseDecoder::new();
+        decoder.feed(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n").unwrap();
+        decoder.decode_headers().unwrap().unwrap();
+
+        let mut body = Vec::new();
+        let mut dst = vec![0u8; dst_size];
+        let mut input = encoded.chunks(feed_size);
+        loop {
+            match decoder.read_body(&mut dst).unwrap() {
+                BodyRead::Data(n) => body.extend_from_slice(&dst[..n]),
+                BodyRead::NeedData => {
+                    let data = input.next().expect("body must complete before input ends");
+                    decoder.feed(data).unwrap();
+                }
+                BodyRead::Complete { len, .. } => {
+                    body.extend_from_slice(&dst[..len]);
+                    break;
+                }
+                _ => unreachable!(),
+            }
+        }
+        let expected: Vec<u8> = non_empty_chunks.iter().flatten().copied().collect();
+        prop_assert_eq!(&body, &expected);
+    }
+}
+
 proptest! {
     #[test]
     fn prop_encode_chunk_valid(data in body()) {
@@ -807,8 +845,8 @@ proptest! {
         prop_assert!(result.is_err(), "Unicode 空白を含む TE は reject されるべき");
         let err = result.unwrap_err();
         prop_assert!(
-            matches!(&err, Error::InvalidData(_)),
-            "InvalidData を期待: {:?}", err
+            matches!(&err, Error::Syntax { phase: ParsePhase::Header, .. }),
+            "Header 段階の Syntax を期待: {:?}", err
         );
     }
 }
@@ -845,8 +883,8 @@ proptest! {
         prop_assert!(result.is_err(), "Unicode 空白を含む TE は reject されるべき");
         let err = result.unwrap_err();
         prop_assert!(
-            matches!(&err, Error::InvalidData(_)),
-            "InvalidData を期待: {:?}", err
+            matches!(&err, Error::Syntax { phase: ParsePhase::Header, .. }),
+            "Header 段階の Syntax を期待: {:?}", err
         );
     }
 }
@@ -0,0 +1,74 @@
+//! chunked ボディのフレーミング保持の PBT
+
+use proptest::prelude::*;
+use shiguredo_http11::{BodyProgress, ResponseDecoder, encode_chunk_with_ext};
+
+fn chunk_ext() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(String::new()),
+        "[a-z]{1,8}".prop_map(|name| format!(";{name}")),
+        ("[a-z]{1,8}", "[a-z0-9]{1,8}").prop_map(|(name, value)| format!(";{name}={value}")),
+        ("[a-z]{1,8}", "[a-z ]{0,8}").prop_map(|(name, value)| format!(" ; {name}=\"{value}\"")),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn prop_chunk_framing_roundtrip(
+        chunks in proptest::collection::vec(
+            (proptest::collection::vec(any::<u8>(), 1..64), chunk_ext()),
+            0..8,
+        ),
+        last_ext in chunk_ext(),
+        split in 1usize..32,
+    ) {
+        let mut body = Vec::new();
+        for (data, ext) in &chunks {
+            body.extend_from_slice(&encode_chunk_with_ext(data, ext.as_bytes()).unwrap());
+        }
+        body.extend_from_slice(&encode_chunk_with_ext(b"", last_ext.as_bytes()).unwrap());
+
+        let mut decoder = ResponseDecoder::new();
+        decoder.set_preserve_chunk_framing(true);
+        decoder
+            .feed(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n")
+            .unwrap();
+        decoder.decode_headers().unwrap().unwrap();
+
+        let mut output = Vec::new();
+        let mut data_exts = Vec::new();
+        let mut parts = body.chunks(split);
+        loop {
+            output.extend_from_slice(&decoder.take_chunk_framing());
+            if let Some(data) = decoder.peek_body() {
+                let len = data.len();
+                output.extend_from_slice(data);
+                data_exts.push((len, decoder.chunk_ext().unwrap_or_default().to_vec()));
+                if let BodyProgress::Complete { .. } = decoder.consume_body(len).unwrap() {
+                    break;
+                }
+                continue;
+            }
+            match decoder.progress().unwrap() {
+                BodyProgress::Complete { .. } => break,
+                BodyProgress::Advanced => {}
+                BodyProgress::NeedData => decoder.feed(parts.next().unwrap()).unwrap(),
+            }
+        }
+        output.extend_from_slice(&decoder.take_chunk_framing());
+
+        // フレーミングとデータを受信順に連結すると元のボディと一致する
+        prop_assert_eq!(&output, &body);
+        prop_assert_eq!(decoder.chunk_ext().unwrap_or_default(), last_ext.as_bytes());
+        // データの chunk-ext は属するチャンクの chunk-ext と一致する
+        let mut expected = Vec::new();
+        for (data, ext) in &chunks {
+            expected.extend(core::iter::repeat_n(ext.as_bytes(), data.len()));
+        }
+        let mut actual = Vec::new();
+        for (len, ext) in &data_exts {
+            actual.extend(core::iter::repeat_n(ext.as_slice(), *len));
+        }
+        prop_assert_eq!(actual, expected);
+    }
+}
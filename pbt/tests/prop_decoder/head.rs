@@ -2,7 +2,8 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::{
-    BodyKind, HttpHead, RequestDecoder, ResponseDecoder, ResponseHead, StatusClass,
+    BodyKind, Error, HttpHead, NonUtf8Policy, ParsePhase, RequestDecoder, ResponseDecoder,
+    ResponseHead, StatusClass,
 };
 
 use super::{
@@ -106,6 +107,48 @@ proptest! {
     }
 }
 
+proptest! {
+    /// 不正なヘッダー行の Syntax エラーは、その行の先頭オフセットと行内容を保持する
+    #[test]
+    fn prop_header_syntax_error_offset(
+        headers in proptest::collection::vec(("[A-Za-z]{1,16}", "[A-Za-z0-9]{0,16}"), 0..8),
+        bad_line in "[A-Za-z]{1,16} [A-Za-z0-9]{1,16}",
+        chunked_feed in any::<bool>(),
+    ) {
+        let mut prefix = String::from("GET / HTTP/1.1\r\nHost: localhost\r\n");
+        for (name, value) in &headers {
+            prefix.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        let data = format!("{}{}\r\n\r\n", prefix, bad_line);
+
+        let mut decoder = RequestDecoder::new();
+        let result = if chunked_feed {
+            // 1 バイトずつ投入しても絶対オフセットは変わらない
+            let mut result = Ok(None);
+            for b in data.as_bytes() {
+                decoder.feed(std::slice::from_ref(b)).unwrap();
+                result = decoder.decode_headers();
+                if !matches!(result, Ok(None)) {
+                    break;
+                }
+            }
+            result
+        } else {
+            decoder.feed(data.as_bytes()).unwrap();
+            decoder.decode_headers()
+        };
+
+        match result {
+            Err(Error::Syntax { phase, offset, line, .. }) => {
+                prop_assert_eq!(phase, ParsePhase::Header);
+                prop_assert_eq!(offset, prefix.len() as u64);
+                prop_assert_eq!(line, bad_line);
+            }
+            other => prop_assert!(false, "Syntax エラーを期待: {:?}", other),
+        }
+    }
+}
+
 proptest! {
     #[test]
     fn prop_header_empty_name_error(
@@ -856,3 +899,153 @@ proptest! {
         prop_assert_eq!(head.version(), version);
     }
 }
+
+// ========================================
+// NonUtf8Policy の PBT
+// ========================================
+
+proptest! {
+    /// Latin-1 として解釈した値は各文字を 1 バイトに戻すと受信バイト列に一致する
+    #[test]
+    fn prop_header_value_latin1_roundtrip(
+        value in proptest::collection::vec(
+            prop_oneof![0x21u8..=0x7E, 0x80u8..=0xFF],
+            1..32,
+        ),
+    ) {
+        prop_assume!(core::str::from_utf8(&value).is_err());
+        let mut data = b"HTTP/1.1 200 OK\r\nX-Value: ".to_vec();
+        data.extend_from_slice(&value);
+        data.extend_from_slice(b"\r\nContent-Length: 0\r\n\r\n");
+
+        let mut decoder = ResponseDecoder::new();
+        decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+        decoder.feed(&data).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        let decoded: Vec<u8> = head
+            .get_header("X-Value")
+            .unwrap()
+            .chars()
+            .map(|c| u8::try_from(u32::from(c)).unwrap())
+            .collect();
+        prop_assert_eq!(decoded, value);
+    }
+}
+
+proptest! {
+    /// get_header_bytes は NonUtf8Policy によらず受信した field-value のバイト列を返す
+    #[test]
+    fn prop_header_value_raw_bytes(
+        value in proptest::collection::vec(
+            prop_oneof![0x21u8..=0x7E, 0x80u8..=0xFF],
+            1..32,
+        ),
+        policy in prop_oneof![Just(NonUtf8Policy::Latin1), Just(NonUtf8Policy::Replace)],
+    ) {
+        let mut data = b"GET / HTTP/1.1\r\nHost: a\r\nX-Value:  ".to_vec();
+        data.extend_from_slice(&value);
+        data.extend_from_slice(b" \r\n\r\n");
+
+        let mut decoder = RequestDecoder::new();
+        decoder.set_non_utf8_policy(policy);
+        decoder.feed(&data).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        prop_assert_eq!(head.get_header_bytes("X-Value"), Some(value.as_slice()));
+    }
+}
+
+// ========================================
+// デコード済みヘッドのヘッダー操作の PBT
+// ========================================
+
+proptest! {
+    // remove_header 後も残りのヘッダーの受信バイト列を取得できる
+    #[test]
+    fn prop_remove_header_keeps_raw_values(
+        entries in proptest::collection::vec((0usize..3, proptest::collection::vec(0x80u8..=0xFF, 1..4)), 1..8),
+        removed in 0usize..3,
+    ) {
+        let names = ["X-A", "X-B", "X-C"];
+        let mut raw = b"GET / HTTP/1.1\r\nHost: a\r\n".to_vec();
+        for (name, value) in &entries {
+            raw.extend_from_slice(names[*name].as_bytes());
+            raw.extend_from_slice(b": ");
+            raw.extend_from_slice(value);
+            raw.extend_from_slice(b"\r\n");
+        }
+        raw.extend_from_slice(b"\r\n");
+
+        let mut decoder = RequestDecoder::new();
+        decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+        decoder.feed(&raw).unwrap();
+        let (mut head, _) = decoder.decode_headers().unwrap().unwrap();
+        head.remove_header(names[removed]);
+
+        let mut expected: Vec<(&str, &[u8])> = vec![("Host", b"a")];
+        expected.extend(
+            entries
+                .iter()
+                .filter(|(name, _)| *name != removed)
+                .map(|(name, value)| (names[*name], value.as_slice())),
+        );
+        let actual: Vec<(&str, &[u8])> = head.headers_raw().collect();
+        prop_assert_eq!(actual, expected);
+    }
+}
+
+// ========================================
+// デコード済みヘッドとメッセージの Connection 判定一致の PBT
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_decoded_head_is_keep_alive_matches_message(
+        tokens in proptest::collection::vec(
+            (
+                prop_oneof![
+                    Just("close"),
+                    Just("Close"),
+                    Just("keep-alive"),
+                    Just("Keep-Alive"),
+                    Just("upgrade"),
+                    Just("foo"),
+                    Just(""),
+                ],
+                prop_oneof![Just(""), Just(" "), Just("\t")],
+            ),
+            1..5,
+        ),
+        version in prop_oneof![Just("HTTP/1.0"), Just("HTTP/1.1")]
+    ) {
+        let value = tokens
+            .iter()
+            .map(|(token, ows)| format!("{ows}{token}{ows}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let names: Vec<&str> = tokens.iter().map(|(token, _)| *token).collect();
+        let expected = if names.iter().any(|t| t.eq_ignore_ascii_case("close")) {
+            false
+        } else {
+            names.iter().any(|t| t.eq_ignore_ascii_case("keep-alive")) || version == "HTTP/1.1"
+        };
+
+        let mut decoder = RequestDecoder::new();
+        decoder
+            .feed(format!("GET / {version}\r\nHost: a\r\nConnection: {value}\r\n\r\n").as_bytes())
+            .unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        prop_assert_eq!(head.is_keep_alive(), expected);
+        let request = shiguredo_http11::Request::with_version("GET", "/", version)
+            .unwrap()
+            .header("Connection", value.as_str())
+            .unwrap();
+        prop_assert_eq!(request.is_keep_alive(), expected);
+
+        let mut decoder = ResponseDecoder::new();
+        decoder
+            .feed(format!("{version} 200 OK\r\nConnection: {value}\r\n\r\n").as_bytes())
+            .unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        prop_assert_eq!(head.is_keep_alive(), expected);
+    }
+}
@@ -1,10 +1,13 @@
 //! Decoder のプロパティテスト (decoder/)
 
 mod body;
+mod chunk_framing;
 mod head;
+mod profile;
 mod request;
 mod request_target;
 mod response;
+mod stats;
 
 use proptest::prelude::*;
 
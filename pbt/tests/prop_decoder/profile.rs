@@ -0,0 +1,39 @@
+//! DecoderProfile の PBT
+
+use proptest::prelude::*;
+use shiguredo_http11::{DecoderProfile, ResponseDecoder};
+
+/// プロファイルを適用したデコーダーでレスポンスを一括デコードする
+fn decode(profile: DecoderProfile, input: &[u8]) -> Option<shiguredo_http11::Response> {
+    let mut decoder = ResponseDecoder::with_profile(profile);
+    decoder.feed(input).ok()?;
+    decoder.decode().ok()?
+}
+
+proptest! {
+    /// strict() で受理したメッセージは、他のプリセットでも同じ結果で受理する
+    #[test]
+    fn prop_profile_strict_is_most_restrictive(
+        version in prop_oneof![Just("HTTP/1.1"), Just("HTTP/1.0"), Just("RTSP/1.0")],
+        value in proptest::collection::vec(0x20u8..=0xFF, 0..32),
+        body in proptest::collection::vec(any::<u8>(), 0..64),
+    ) {
+        let mut input = format!("{version} 200 OK\r\nX-Value: ").into_bytes();
+        input.extend_from_slice(&value);
+        input.extend_from_slice(format!("\r\nContent-Length: {}\r\n\r\n", body.len()).as_bytes());
+        input.extend_from_slice(&body);
+
+        if let Some(strict) = decode(DecoderProfile::strict(), &input) {
+            prop_assert_eq!(
+                decode(DecoderProfile::browser_compatible(), &input),
+                Some(strict.clone())
+            );
+            prop_assert_eq!(decode(DecoderProfile::internal(), &input), Some(strict));
+        }
+        // internal() はプロトコルバージョンと非 UTF-8 の field-value を受理する
+        // (DEL は field-value に使えない)
+        if !value.contains(&0x7F) {
+            prop_assert!(decode(DecoderProfile::internal(), &input).is_some());
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::RequestDecoder;
+use shiguredo_http11::request_target::RequestTargetForm;
 
 // ========================================
 // Strategy 定義
@@ -269,3 +270,52 @@ proptest! {
         prop_assert_eq!(head.uri(), uri.as_str());
     }
 }
+
+// ========================================
+// RequestHead の request-target アクセサテスト
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_absolute_form_accessors(
+        host in hostname(),
+        port in proptest::option::of(1..=65535u16),
+        path in origin_form_uri(),
+        query in proptest::option::of(path_segment()),
+    ) {
+        let authority = match port {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.clone(),
+        };
+        let uri = match &query {
+            Some(q) => format!("http://{}{}?{}", authority, path, q),
+            None => format!("http://{}{}", authority, path),
+        };
+        let request_line = format!("GET {} HTTP/1.1\r\nHost: other.example\r\n\r\n", uri);
+        let mut decoder = RequestDecoder::new();
+        decoder.feed(request_line.as_bytes()).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+        prop_assert_eq!(head.target_form(), Some(RequestTargetForm::Absolute));
+        prop_assert_eq!(head.target_scheme(), Some("http"));
+        prop_assert_eq!(head.target_authority(), Some(authority.as_str()));
+        prop_assert_eq!(head.target_path(), Some(path.as_str()));
+        // absolute-form では Host ヘッダーより request-target の authority を優先する
+        prop_assert_eq!(head.effective_authority(), Some(authority.as_str()));
+    }
+
+    #[test]
+    fn prop_origin_form_accessors(path in origin_form_uri(), query in path_segment()) {
+        let uri = format!("{}?{}", path, query);
+        let request_line = format!("GET {} HTTP/1.1\r\nHost: example.com\r\n\r\n", uri);
+        let mut decoder = RequestDecoder::new();
+        decoder.feed(request_line.as_bytes()).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+        prop_assert_eq!(head.target_form(), Some(RequestTargetForm::Origin));
+        prop_assert_eq!(head.target_scheme(), None);
+        prop_assert_eq!(head.target_authority(), None);
+        prop_assert_eq!(head.target_path(), Some(path.as_str()));
+        prop_assert_eq!(head.effective_authority(), Some("example.com"));
+    }
+}
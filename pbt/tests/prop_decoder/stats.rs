@@ -0,0 +1,45 @@
+//! DecoderStats の PBT
+
+use proptest::prelude::*;
+use shiguredo_http11::RequestDecoder;
+
+use super::http_uri;
+
+proptest! {
+    /// Content-Length ボディの場合、投入バイト数はヘッダーとボディのバイト数の和に等しい
+    /// (分割投入しても累計は変わらない)
+    #[test]
+    fn prop_stats_content_length_accounting(
+        uri in http_uri(),
+        bodies in proptest::collection::vec(proptest::collection::vec(any::<u8>(), 0..64), 1..4),
+        chunk_size in 1usize..32,
+    ) {
+        let mut data = Vec::new();
+        let mut expected_body = 0u64;
+        for body in &bodies {
+            data.extend_from_slice(
+                format!("POST {uri} HTTP/1.1\r\nHost: a\r\nContent-Length: {}\r\n\r\n", body.len())
+                    .as_bytes(),
+            );
+            data.extend_from_slice(body);
+            expected_body += body.len() as u64;
+        }
+
+        let mut decoder = RequestDecoder::new();
+        let mut decoded = 0;
+        for chunk in data.chunks(chunk_size) {
+            decoder.feed(chunk).unwrap();
+            while decoder.decode().unwrap().is_some() {
+                decoded += 1;
+            }
+        }
+
+        let stats = decoder.stats();
+        prop_assert_eq!(decoded, bodies.len());
+        prop_assert_eq!(stats.messages, bodies.len() as u64);
+        prop_assert_eq!(stats.bytes_fed, data.len() as u64);
+        prop_assert_eq!(stats.body_bytes, expected_body);
+        prop_assert_eq!(stats.header_bytes + stats.body_bytes, stats.bytes_fed);
+        prop_assert_eq!(stats.chunks, 0);
+    }
+}
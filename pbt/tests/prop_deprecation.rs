@@ -0,0 +1,80 @@
+//! Deprecation / Sunset / Warning ヘッダーのプロパティテスト (deprecation.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::deprecation::{Deprecation, Sunset, Warning, WarningValue};
+
+// sf-integer の範囲
+const MAX_INTEGER: u64 = 999_999_999_999_999;
+
+// 9999-12-31T23:59:59Z
+const MAX_HTTP_DATE: u64 = 253_402_300_799;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn http_date() -> impl Strategy<Value = HttpDate> {
+    (0..=MAX_HTTP_DATE).prop_map(|seconds| HttpDate::from_unix_seconds(seconds).unwrap())
+}
+
+fn warning_value() -> impl Strategy<Value = WarningValue> {
+    (
+        100u16..=999,
+        "[!#-+\\--~]{1,16}",
+        "[ -~]{0,32}",
+        proptest::option::of(http_date()),
+    )
+        .prop_map(|(code, agent, text, date)| {
+            let value = WarningValue::new(code, &agent, &text).unwrap();
+            match date {
+                Some(date) => value.with_date(date),
+                None => value,
+            }
+        })
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_deprecation_roundtrip(timestamp in 0..=MAX_INTEGER) {
+        let deprecation = Deprecation::new(timestamp).unwrap();
+        let parsed = Deprecation::parse(&deprecation.to_string(), 2026).unwrap();
+        prop_assert_eq!(parsed, deprecation);
+        prop_assert_eq!(parsed.timestamp(), timestamp);
+    }
+
+    #[test]
+    fn prop_deprecation_http_date(date in http_date()) {
+        let parsed = Deprecation::parse(&date.to_string(), 2026).unwrap();
+        prop_assert_eq!(parsed, Deprecation::from_date(&date));
+        prop_assert_eq!(parsed.date(), Some(date));
+    }
+
+    #[test]
+    fn prop_sunset_roundtrip(date in http_date(), now in 0..=MAX_HTTP_DATE) {
+        let sunset = Sunset::new(date);
+        let parsed = Sunset::parse(&sunset.to_string(), 2026).unwrap();
+        prop_assert_eq!(parsed.is_past(now), now >= sunset.unix_seconds());
+        prop_assert_eq!(parsed, sunset);
+    }
+
+    #[test]
+    fn prop_warning_roundtrip(values in proptest::collection::vec(warning_value(), 1..5)) {
+        let warning = values.into_iter().fold(Warning::new(), Warning::with_value);
+        let parsed = Warning::parse(&warning.to_string(), 2026).unwrap();
+        prop_assert_eq!(parsed, warning);
+    }
+
+    #[test]
+    fn prop_warning_parse_no_panic(input in "[ -~]{0,64}") {
+        if let Ok(warning) = Warning::parse(&input, 2026) {
+            prop_assert_eq!(Warning::parse(&warning.to_string(), 2026), Ok(warning));
+        }
+        let _ = Deprecation::parse(&input, 2026);
+        let _ = Sunset::parse(&input, 2026);
+    }
+}
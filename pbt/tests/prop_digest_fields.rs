@@ -2,7 +2,7 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::digest_fields::{
-    ContentDigest, ReprDigest, WantContentDigest, WantReprDigest,
+    ContentDigest, DigestFieldsError, DigestHasher, ReprDigest, WantContentDigest, WantReprDigest,
 };
 
 // ========================================
@@ -359,3 +359,84 @@ proptest! {
         prop_assert!(reparsed.is_ok());
     }
 }
+
+// ========================================
+// Digest の計算と検証のテスト
+// ========================================
+
+proptest! {
+    /// 計算した Digest は同じデータで検証に成功し、Display -> parse 後も検証できる
+    #[test]
+    fn prop_digest_sha256_verify_roundtrip(data in proptest::collection::vec(any::<u8>(), 0..512)) {
+        let digest = ContentDigest::sha256(&data);
+        prop_assert_eq!(digest.verify(&data), Ok(()));
+        let parsed = ReprDigest::parse(&digest.to_string()).unwrap();
+        prop_assert_eq!(parsed.verify(&data), Ok(()));
+    }
+}
+
+proptest! {
+    /// 1 バイトでも異なるデータは検証に失敗する
+    #[test]
+    fn prop_digest_sha256_detects_modification(
+        data in proptest::collection::vec(any::<u8>(), 1..256),
+        index in any::<prop::sample::Index>(),
+        flip in 1u8..=255
+    ) {
+        let digest = ContentDigest::sha256(&data);
+        let mut modified = data.clone();
+        let i = index.index(modified.len());
+        modified[i] ^= flip;
+        prop_assert_eq!(digest.verify(&modified), Err(DigestFieldsError::Mismatch));
+    }
+}
+
+proptest! {
+    /// 任意の分割で投入しても一括計算と一致する
+    #[test]
+    fn prop_digest_streaming_matches_one_shot(
+        data in proptest::collection::vec(any::<u8>(), 0..512),
+        chunk_size in 1usize..100
+    ) {
+        let mut hasher = DigestHasher::new();
+        let digest = ContentDigest::sha256(&data);
+        let mut verifier = digest.verifier().unwrap();
+        for chunk in data.chunks(chunk_size) {
+            hasher.update(chunk);
+            verifier.update(chunk);
+        }
+        prop_assert_eq!(ContentDigest::new(hasher.finish()), digest);
+        prop_assert_eq!(verifier.finish(), Ok(()));
+    }
+}
+
+// ========================================
+// Want-*-Digest によるアルゴリズム選択のテスト
+// ========================================
+
+proptest! {
+    /// choose() は supported に含まれ、優先度が最大かつ 0 でないアルゴリズムを返す
+    #[test]
+    fn prop_want_digest_choose_is_maximal(
+        weights in proptest::collection::vec(0u8..=10, 1..6)
+    ) {
+        let algorithms = ["sha-256", "sha-512", "unixsum", "unixcksum", "adler", "crc32c"];
+        let header = weights
+            .iter()
+            .zip(algorithms)
+            .map(|(w, a)| format!("{}={}", a, w))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let want = WantContentDigest::parse(&header).unwrap();
+        let supported = &algorithms[..weights.len()];
+        let max = weights.iter().copied().max().unwrap();
+        match want.choose(supported) {
+            Some(chosen) => {
+                let weight = want.get(chosen).unwrap();
+                prop_assert!(weight > 0);
+                prop_assert_eq!(weight, max);
+            }
+            None => prop_assert_eq!(max, 0),
+        }
+    }
+}
@@ -2,9 +2,10 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::{
-    EncodeError, Request, RequestEncoder, Response, ResponseEncoder, StatusCode, encode_chunk,
-    encode_chunks, encode_request, encode_request_headers, encode_response,
-    encode_response_headers,
+    EncodeError, HeaderCase, HttpHead, Request, RequestEncoder, Response, ResponseEncoder,
+    StatusCode, encode_chunk, encode_chunks, encode_request, encode_request_headers,
+    encode_request_vectored, encode_response, encode_response_headers, encode_response_vectored,
+    encode_response_with_interim,
 };
 
 // ========================================
@@ -212,6 +213,61 @@ proptest! {
     }
 }
 
+proptest! {
+    // vectored 出力を結合すると encode_request と一致し、body はコピーされない
+    #[test]
+    fn prop_encode_request_vectored_matches_encode(method in http_method(), uri in uri(), data in body()) {
+        let req = Request::new(method, &uri)
+            .unwrap()
+            .header("Host", "example.com")
+            .unwrap()
+            .body(data.clone());
+        let parts = encode_request_vectored(&req).unwrap();
+        prop_assert_eq!(parts.to_vec(), encode_request(&req).unwrap());
+        prop_assert_eq!(parts.body().as_ptr(), req.body_bytes().unwrap().as_ptr());
+        prop_assert_eq!(parts.len(), parts.head().len() + data.len());
+    }
+}
+
+proptest! {
+    // encode_into / encode_to_writer は encode と同じバイト列を書き出す
+    #[test]
+    fn prop_encode_into_and_writer_match_encode(
+        status in status_code(),
+        phrase in reason_phrase(),
+        data in body(),
+        prefix in proptest::collection::vec(any::<u8>(), 0..16),
+    ) {
+        let res = Response::new(status, phrase).unwrap().body(data);
+        let expected = encode_response(&res).unwrap();
+
+        let mut buf = prefix.clone();
+        res.encode_into(&mut buf).unwrap();
+        prop_assert_eq!(&buf[..prefix.len()], &prefix[..]);
+        prop_assert_eq!(&buf[prefix.len()..], &expected[..]);
+
+        let mut out = Vec::new();
+        res.encode_to_writer(&mut out).unwrap();
+        prop_assert_eq!(out, expected);
+    }
+}
+
+proptest! {
+    // vectored 出力を結合すると encode_response と一致する (omit_body を含む)
+    #[test]
+    fn prop_encode_response_vectored_matches_encode(
+        status in status_code(),
+        phrase in reason_phrase(),
+        data in body(),
+        omit in any::<bool>(),
+    ) {
+        let res = Response::new(status, phrase).unwrap().body(data).omit_body(omit);
+        let parts = encode_response_vectored(&res).unwrap();
+        prop_assert_eq!(parts.to_vec(), encode_response(&res).unwrap());
+        prop_assert!(parts.head().ends_with(b"\r\n\r\n"));
+    }
+}
+
 proptest! {
     #[test]
     fn prop_encode_response_omit_body_with_content_length(
@@ -910,3 +966,112 @@ proptest! {
         prop_assert_eq!(&output[..data.len()], &data[..]);
     }
 }
+
+proptest! {
+    /// ヘッダー名の大文字小文字の指定はヘッダー名のみを変え、出力長とデコード結果の値を変えない
+    #[test]
+    fn prop_header_case_only_changes_names(
+        headers in proptest::collection::vec((header_name(), header_value()), 0..5),
+        data in body(),
+        case in prop_oneof![
+            Just(HeaderCase::Preserve),
+            Just(HeaderCase::TitleCase),
+            Just(HeaderCase::Lowercase),
+        ],
+    ) {
+        let mut res = Response::with_status(StatusCode::OK).body(data);
+        for (name, value) in &headers {
+            res.add_header(name.as_str(), value.as_str()).unwrap();
+        }
+        let preserved = encode_response(&res).unwrap();
+        let res = res.header_case(case);
+        let encoded = encode_response(&res).unwrap();
+        prop_assert_eq!(encoded.len(), preserved.len());
+
+        let mut decoder = shiguredo_http11::ResponseDecoder::new();
+        decoder.feed(&encoded).unwrap();
+        let decoded = decoder.decode().unwrap().unwrap();
+        // 自動付与される Content-Length を含む
+        prop_assert_eq!(decoded.headers().len(), headers.len() + 1);
+        for ((name, value), (orig_name, orig_value)) in decoded.headers().iter().zip(&headers) {
+            prop_assert!(name.eq_ignore_ascii_case(orig_name));
+            prop_assert_eq!(value.as_str(), orig_value.trim_matches(' '));
+            match case {
+                HeaderCase::Lowercase => prop_assert_eq!(name.clone(), orig_name.to_ascii_lowercase()),
+                HeaderCase::Preserve => prop_assert_eq!(name, orig_name),
+                _ => {}
+            }
+        }
+    }
+}
+
+proptest! {
+    /// 中間レスポンス群と最終レスポンスはデコーダーで順に同じ数だけ取り出せる
+    #[test]
+    fn prop_interim_responses_decode_in_order(
+        interim_codes in proptest::collection::vec(prop_oneof![Just(100u16), Just(102u16), Just(103u16)], 0..4),
+        links in proptest::collection::vec("<[a-z/]{1,16}>; rel=preload", 0..3),
+        data in body(),
+    ) {
+        let interim: Vec<Response> = interim_codes
+            .iter()
+            .map(|&code| {
+                if code == 103 {
+                    Response::early_hints(links.clone()).unwrap()
+                } else {
+                    Response::from_status(code).unwrap()
+                }
+            })
+            .collect();
+        let res = Response::with_status(StatusCode::OK).body(data.clone());
+        let encoded = encode_response_with_interim(&interim, &res).unwrap();
+
+        let mut decoder = shiguredo_http11::ResponseDecoder::new();
+        decoder.feed(&encoded).unwrap();
+        for (code, expected) in interim_codes.iter().zip(&interim) {
+            let decoded = decoder.decode().unwrap().unwrap();
+            prop_assert_eq!(decoded.status_code(), *code);
+            prop_assert_eq!(decoded.get_headers("Link"), expected.get_headers("Link"));
+        }
+        let decoded = decoder.decode().unwrap().unwrap();
+        prop_assert_eq!(decoded.status_code(), 200);
+        prop_assert_eq!(decoded.body_bytes(), Some(&data[..]));
+    }
+}
+
+proptest! {
+    /// encoded_len_hint は実際の出力長以上で、超過は自動付与の Content-Length 分 (19) 以下
+    #[test]
+    fn prop_encoded_len_hint_bounds(
+        method in http_method(),
+        uri in uri(),
+        headers in proptest::collection::vec((header_name(), header_value()), 0..8),
+        status in status_code(),
+        data in proptest::option::of(body()),
+    ) {
+        let mut req = Request::new(method, &uri)
+            .unwrap()
+            .header("Host", "example.com")
+            .unwrap();
+        let mut res = Response::new(status, "Reason").unwrap();
+        for (name, value) in &headers {
+            req.add_header(name.as_str(), value.as_str()).unwrap();
+            res.add_header(name.as_str(), value.as_str()).unwrap();
+        }
+        if let Some(data) = data {
+            req.set_body(data.clone());
+            res.set_body(data);
+        }
+
+        if let Ok(encoded) = encode_request(&req) {
+            let hint = req.encoded_len_hint();
+            prop_assert!(hint >= encoded.len());
+            prop_assert!(hint - encoded.len() <= 19);
+        }
+        if let Ok(encoded) = encode_response(&res) {
+            let hint = res.encoded_len_hint();
+            prop_assert!(hint >= encoded.len());
+            prop_assert!(hint - encoded.len() <= 19);
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! ETag のプロパティテスト
 
 use proptest::prelude::*;
-use shiguredo_http11::etag::{EntityTag, parse_etag_list};
+use shiguredo_http11::etag::{
+    ETagHasher, EntityTag, if_match_matches, if_none_match_matches, parse_etag_list,
+};
 
 // ========================================
 // ETag パースのテスト
@@ -94,3 +96,109 @@ proptest! {
         prop_assert_eq!(list, reparsed);
     }
 }
+
+// ========================================
+// If-None-Match / If-Match の判定の PBT
+// ========================================
+
+// リスト中のいずれかの ETag との Weak / Strong 比較の結果と一致する
+proptest! {
+    #[test]
+    fn prop_if_match_helpers_agree_with_compare(
+        list in proptest::collection::vec(("[a-z0-9]{1,8}", any::<bool>()), 1..5),
+        current_tag in "[a-z0-9]{1,8}",
+        current_weak in any::<bool>(),
+    ) {
+        let etags: Vec<EntityTag> = list
+            .iter()
+            .map(|(tag, weak)| {
+                if *weak {
+                    EntityTag::weak(tag).unwrap()
+                } else {
+                    EntityTag::strong(tag).unwrap()
+                }
+            })
+            .collect();
+        let field_value = etags
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let current = if current_weak {
+            EntityTag::weak(&current_tag).unwrap()
+        } else {
+            EntityTag::strong(&current_tag).unwrap()
+        };
+
+        prop_assert_eq!(
+            if_none_match_matches(&field_value, Some(&current)),
+            etags.iter().any(|e| e.weak_compare(&current))
+        );
+        prop_assert_eq!(
+            if_match_matches(&field_value, Some(&current)),
+            etags.iter().any(|e| e.strong_compare(&current))
+        );
+        prop_assert!(!if_none_match_matches(&field_value, None));
+        prop_assert!(!if_match_matches(&field_value, None));
+    }
+}
+
+// ========================================
+// ETag 生成の PBT
+// ========================================
+
+// 任意の分割でストリーミング計算しても from_content と一致する
+proptest! {
+    #[test]
+    fn prop_etag_hasher_matches_from_content(
+        data in proptest::collection::vec(any::<u8>(), 0..512),
+        splits in proptest::collection::vec(0usize..512, 0..8),
+    ) {
+        let mut points: Vec<usize> = splits.into_iter().map(|s| s.min(data.len())).collect();
+        points.sort_unstable();
+
+        let mut hasher = ETagHasher::new();
+        let mut start = 0;
+        for point in points {
+            hasher.update(&data[start..point]);
+            start = point;
+        }
+        hasher.update(&data[start..]);
+
+        prop_assert_eq!(hasher.finish(), EntityTag::from_content(&data));
+    }
+}
+
+// 生成された ETag は常にパース可能でラウンドトリップする
+proptest! {
+    #[test]
+    fn prop_generated_etag_roundtrip(
+        data in proptest::collection::vec(any::<u8>(), 0..128),
+        size in any::<u64>(),
+        modified in any::<u64>(),
+    ) {
+        for etag in [
+            EntityTag::from_content(&data),
+            EntityTag::weak_from_content(&data),
+            EntityTag::from_metadata(size, modified),
+            EntityTag::strong_from_metadata(size, modified),
+        ] {
+            let parsed = EntityTag::parse(&etag.to_string()).unwrap();
+            prop_assert_eq!(parsed, etag);
+        }
+    }
+}
+
+// メタデータが異なれば ETag も異なる
+proptest! {
+    #[test]
+    fn prop_from_metadata_injective(
+        a in (any::<u64>(), any::<u64>()),
+        b in (any::<u64>(), any::<u64>()),
+    ) {
+        prop_assert_eq!(
+            EntityTag::from_metadata(a.0, a.1) == EntityTag::from_metadata(b.0, b.1),
+            a == b
+        );
+    }
+}
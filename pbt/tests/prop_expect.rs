@@ -147,6 +147,11 @@ proptest! {
         prop_assert!(!expect.items()[0].is_100_continue());
         prop_assert!(expect.items()[1].is_100_continue());
 
+        // 100-continue 以外は未知の expectation
+        prop_assert!(expect.has_unknown());
+        let unknown: Vec<_> = expect.unknown().collect();
+        prop_assert_eq!(unknown, vec![&expect.items()[0]]);
+
         // ラウンドトリップ
         let displayed = expect.to_string();
         let reparsed = Expect::parse(&displayed).unwrap();
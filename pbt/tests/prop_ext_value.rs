@@ -0,0 +1,37 @@
+//! ExtValue (RFC 8187) のプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::ext_value::ExtValue;
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+// 任意の文字列は生成してパースすると元に戻る
+proptest! {
+    #[test]
+    fn prop_ext_value_roundtrip(value in any::<String>(), language in proptest::option::of("[a-z]{2,3}(-[A-Z]{2})?")) {
+        let mut ext = ExtValue::new(&value);
+        if let Some(language) = &language {
+            ext = ext.with_language(language).unwrap();
+        }
+        let encoded = ext.to_string();
+
+        // 生成結果は ASCII のみ
+        prop_assert!(encoded.is_ascii());
+        let parsed = ExtValue::parse(&encoded).unwrap();
+        prop_assert_eq!(parsed.value(), value.as_str());
+        prop_assert_eq!(parsed.language(), language.as_deref());
+    }
+}
+
+// 任意の入力でパニックしない
+proptest! {
+    #[test]
+    fn prop_ext_value_parse_no_panic(input in any::<String>()) {
+        if let Ok(ext) = ExtValue::parse(&input) {
+            let reparsed = ExtValue::parse(&ext.to_string()).unwrap();
+            prop_assert_eq!(reparsed, ext);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Fetch Metadata リクエストヘッダーのプロパティテスト (fetch_metadata.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::fetch_metadata::{
+    FetchDest, FetchMetadata, FetchMode, FetchSite, FetchUser, ResourceIsolationPolicy,
+};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn fetch_site() -> impl Strategy<Value = FetchSite> {
+    prop_oneof![
+        Just(FetchSite::CrossSite),
+        Just(FetchSite::SameOrigin),
+        Just(FetchSite::SameSite),
+        Just(FetchSite::None),
+    ]
+}
+
+fn fetch_mode() -> impl Strategy<Value = FetchMode> {
+    prop_oneof![
+        Just(FetchMode::Cors),
+        Just(FetchMode::Navigate),
+        Just(FetchMode::NoCors),
+        Just(FetchMode::SameOrigin),
+        Just(FetchMode::WebSocket),
+    ]
+}
+
+fn fetch_dest() -> impl Strategy<Value = FetchDest> {
+    prop_oneof![
+        Just("audio"),
+        Just("document"),
+        Just("embed"),
+        Just("empty"),
+        Just("font"),
+        Just("iframe"),
+        Just("image"),
+        Just("object"),
+        Just("script"),
+        Just("serviceworker"),
+        Just("style"),
+        Just("worker"),
+    ]
+    .prop_map(|s| FetchDest::parse(s).unwrap())
+}
+
+fn policy() -> impl Strategy<Value = ResourceIsolationPolicy> {
+    (any::<bool>(), any::<bool>(), any::<bool>()).prop_map(
+        |(same_origin_only, no_navigation, safe_requests)| {
+            let mut policy = ResourceIsolationPolicy::new();
+            if same_origin_only {
+                policy = policy.with_same_origin_only();
+            }
+            if no_navigation {
+                policy = policy.without_cross_site_navigation();
+            }
+            if safe_requests {
+                policy = policy.with_cross_site_safe_requests();
+            }
+            policy
+        },
+    )
+}
+
+fn method() -> impl Strategy<Value = &'static str> {
+    prop_oneof![
+        Just("GET"),
+        Just("HEAD"),
+        Just("OPTIONS"),
+        Just("POST"),
+        Just("PUT"),
+        Just("DELETE"),
+        Just("PATCH"),
+    ]
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_fetch_metadata_roundtrip(
+        site in fetch_site(),
+        mode in fetch_mode(),
+        dest in fetch_dest(),
+        user in any::<bool>(),
+    ) {
+        prop_assert_eq!(FetchSite::parse(&site.to_string()), Ok(site));
+        prop_assert_eq!(FetchMode::parse(&mode.to_string()), Ok(mode));
+        prop_assert_eq!(FetchDest::parse(&dest.to_string()), Ok(dest));
+        prop_assert_eq!(FetchUser::parse(&FetchUser(user).to_string()), Ok(FetchUser(user)));
+    }
+
+    #[test]
+    fn prop_fetch_metadata_parse_no_panic(input in "[ -~]{0,32}") {
+        if let Ok(site) = FetchSite::parse(&input) {
+            prop_assert_eq!(FetchSite::parse(site.as_str()), Ok(site));
+        }
+        if let Ok(dest) = FetchDest::parse(&input) {
+            prop_assert_eq!(FetchDest::parse(dest.as_str()), Ok(dest));
+        }
+        let _ = FetchMode::parse(&input);
+        let _ = FetchUser::parse(&input);
+    }
+}
+
+// ========================================
+// ポリシー判定
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_policy_allows_same_origin(
+        policy in policy(),
+        method in method(),
+        mode in fetch_mode(),
+        dest in fetch_dest(),
+    ) {
+        let metadata = FetchMetadata::new(FetchSite::SameOrigin, mode, dest);
+        prop_assert!(policy.evaluate(method, &metadata).is_allowed());
+        prop_assert!(policy.evaluate(method, &FetchMetadata::default()).is_allowed());
+    }
+
+    #[test]
+    fn prop_policy_rejects_cross_site_unsafe_methods(
+        policy in policy(),
+        method in prop_oneof![Just("POST"), Just("PUT"), Just("DELETE"), Just("PATCH")],
+        mode in fetch_mode(),
+        dest in fetch_dest(),
+    ) {
+        let metadata = FetchMetadata::new(FetchSite::CrossSite, mode, dest);
+        prop_assert!(!policy.evaluate(method, &metadata).is_allowed());
+    }
+}
@@ -0,0 +1,26 @@
+//! application/x-www-form-urlencoded のプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::form;
+
+proptest! {
+    // 任意の文字列の組はシリアライズ後にパースすると元に戻る
+    #[test]
+    fn prop_form_roundtrip(pairs in proptest::collection::vec((any::<String>(), any::<String>()), 0..8)) {
+        let serialized = form::serialize(&pairs);
+        prop_assert_eq!(form::parse(&serialized), pairs);
+    }
+
+    // シリアライズ結果は英数字と *-._+%= & のみで構成される
+    #[test]
+    fn prop_form_serialize_charset(pairs in proptest::collection::vec((any::<String>(), any::<String>()), 0..8)) {
+        let serialized = form::serialize(&pairs);
+        prop_assert!(serialized.bytes().all(|b| b.is_ascii_alphanumeric() || b"*-._+%=&".contains(&b)));
+    }
+
+    // 任意の入力のパースはパニックしない
+    #[test]
+    fn prop_form_parse_no_panic(input in any::<String>()) {
+        let _ = form::parse(&input);
+    }
+}
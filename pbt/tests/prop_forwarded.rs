@@ -0,0 +1,137 @@
+//! Forwarded / X-Forwarded-* ヘッダーのプロパティテスト (forwarded.rs)
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use pbt::qdtext_value;
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::forwarded::{
+    Forwarded, ForwardedElement, Node, append_forwarded, append_x_forwarded,
+};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn ip_addr() -> impl Strategy<Value = IpAddr> {
+    prop_oneof![
+        any::<[u8; 4]>().prop_map(|octets| IpAddr::V4(Ipv4Addr::from(octets))),
+        any::<[u16; 8]>().prop_map(|segments| IpAddr::V6(Ipv6Addr::from(segments))),
+    ]
+}
+
+fn obfuscated() -> impl Strategy<Value = String> {
+    "_[A-Za-z0-9._-]{1,12}"
+}
+
+fn node() -> impl Strategy<Value = Node> {
+    prop_oneof![
+        ip_addr().prop_map(Node::from),
+        (ip_addr(), any::<u16>()).prop_map(|(ip, port)| Node::from(SocketAddr::new(ip, port))),
+        Just(Node::unknown()),
+        (obfuscated(), proptest::option::of(obfuscated())).prop_map(|(name, port)| {
+            let node = Node::obfuscated(&name).unwrap();
+            match port {
+                Some(port) => node.with_obfuscated_port(&port).unwrap(),
+                None => node,
+            }
+        }),
+    ]
+}
+
+fn element() -> impl Strategy<Value = ForwardedElement> {
+    (
+        proptest::option::of(node()),
+        proptest::option::of(node()),
+        proptest::option::of((
+            "[a-z][a-z0-9-]{0,10}(\\.[a-z]{1,5})?",
+            proptest::option::of(any::<u16>()),
+        )),
+        proptest::option::of("[A-Za-z][A-Za-z0-9+.-]{0,8}"),
+    )
+        .prop_map(|(for_node, by, host, proto)| {
+            let mut element = ForwardedElement::new();
+            if let Some(node) = for_node {
+                element = element.with_for(node);
+            }
+            if let Some(node) = by {
+                element = element.with_by(node);
+            }
+            if let Some((name, port)) = host {
+                let host = match port {
+                    Some(port) => format!("{name}:{port}"),
+                    None => name,
+                };
+                element = element.with_host(&host).unwrap();
+            }
+            if let Some(proto) = proto {
+                element = element.with_proto(&proto).unwrap();
+            }
+            element
+        })
+        .prop_filter("empty element", |element| !element.is_empty())
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// Node の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_node_roundtrip(node in node()) {
+        prop_assert_eq!(Node::parse(&node.to_string()).unwrap(), node);
+    }
+
+    /// Forwarded の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_forwarded_roundtrip(
+        elements in proptest::collection::vec(element(), 1..5),
+        extension in proptest::option::of(qdtext_value(0..=16)),
+    ) {
+        let mut forwarded = Forwarded::new();
+        for element in elements {
+            forwarded.push(element);
+        }
+        let mut encoded = forwarded.to_string();
+        if let Some(value) = &extension {
+            // 拡張パラメータは quoted-string のまま受理する
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            encoded = format!("ext=\"{escaped}\", {encoded}");
+            let parsed = Forwarded::parse(&encoded).unwrap();
+            prop_assert_eq!(parsed.elements()[0].extensions()[0].1.as_str(), value.as_str());
+            prop_assert_eq!(&parsed.elements()[1..], forwarded.elements());
+        } else {
+            prop_assert_eq!(Forwarded::parse(&encoded).unwrap(), forwarded);
+        }
+    }
+
+    /// append_forwarded / append_x_forwarded は既存のホップの後ろに追加する
+    #[test]
+    fn prop_append_hops(elements in proptest::collection::vec(element(), 1..5)) {
+        let mut request = Request::new("GET", "/").unwrap();
+        for element in &elements {
+            request = append_forwarded(request, element).unwrap();
+            request = append_x_forwarded(request, element).unwrap();
+        }
+
+        let forwarded = Forwarded::from_request(&request).unwrap().unwrap();
+        prop_assert_eq!(forwarded.elements(), elements.as_slice());
+
+        let x_forwarded = Forwarded::from_x_forwarded(&request).unwrap().unwrap();
+        prop_assert_eq!(x_forwarded.elements().len(), elements.len());
+        prop_assert_eq!(
+            x_forwarded.elements()[0].proto(),
+            elements.iter().find_map(|e| e.proto())
+        );
+    }
+}
+
+proptest! {
+    /// 任意の入力でパニックしない
+    #[test]
+    fn prop_forwarded_parse_no_panic(input in "[ -~]{0,64}") {
+        let _ = Forwarded::parse(&input);
+        let _ = Node::parse(&input);
+    }
+}
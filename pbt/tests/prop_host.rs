@@ -2,6 +2,7 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::host::Host;
+use shiguredo_http11::uri::Uri;
 
 // ========================================
 // Strategy 定義
@@ -157,3 +158,50 @@ proptest! {
         prop_assert_eq!(host.host(), input.as_str());
     }
 }
+
+// ========================================
+// 比較のテスト
+// ========================================
+
+// 大文字小文字とデフォルトポートの有無によらず一致する
+proptest! {
+    #[test]
+    fn prop_host_matches_case_and_default_port(
+        name in hostname(),
+        https in any::<bool>(),
+        explicit in any::<bool>(),
+    ) {
+        let (scheme, port) = if https { ("https", 443) } else { ("http", 80) };
+        let host = Host::parse(&name.to_ascii_uppercase()).unwrap();
+        let other = Host::new(&name.to_ascii_lowercase(), explicit.then_some(port)).unwrap();
+        prop_assert!(host.matches(&other, scheme));
+        prop_assert!(other.matches(&host, scheme));
+        prop_assert_eq!(other.without_default_port(scheme).port(), None);
+
+        let uri = Uri::parse(&format!("{scheme}://{other}/")).unwrap();
+        prop_assert!(host.matches_uri(&uri));
+        prop_assert!(Host::from_uri(&uri).unwrap().matches(&host, scheme));
+    }
+}
+
+// 異なるポートは一致しない
+proptest! {
+    #[test]
+    fn prop_host_matches_port(name in hostname(), a in valid_port(), b in valid_port()) {
+        let host_a = Host::new(&name, Some(a)).unwrap();
+        let host_b = Host::new(&name, Some(b)).unwrap();
+        prop_assert_eq!(host_a.matches(&host_b, "http"), a == b);
+    }
+}
+
+// IPv6 の表記によらずアドレスとして比較する
+proptest! {
+    #[test]
+    fn prop_host_ipv6_matches(addr in ipv6_addr()) {
+        let parsed: std::net::Ipv6Addr = addr.parse().unwrap();
+        let host = Host::new(&addr, None).unwrap();
+        let other = Host::new(&parsed.to_string(), None).unwrap();
+        prop_assert!(host.matches(&other, "http"));
+        prop_assert_eq!(host.hostname(), addr.as_str());
+    }
+}
@@ -0,0 +1,67 @@
+//! Idempotency-Key ヘッダーのプロパティテスト (idempotency_key.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::idempotency_key::{IdempotencyKey, RequestIdempotency};
+use shiguredo_http11::method::Method;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn key() -> impl Strategy<Value = String> {
+    "[ -~]{1,64}"
+}
+
+fn method() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("GET".to_string()),
+        Just("HEAD".to_string()),
+        Just("PUT".to_string()),
+        Just("DELETE".to_string()),
+        Just("POST".to_string()),
+        Just("PATCH".to_string()),
+        "[A-Z]{1,10}",
+    ]
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_idempotency_key_roundtrip(key in key()) {
+        let key = IdempotencyKey::new(&key).unwrap();
+        prop_assert_eq!(IdempotencyKey::parse(&key.to_string()), Ok(key));
+    }
+
+    #[test]
+    fn prop_idempotency_key_max_length(key in key(), max_length in 0usize..80) {
+        let result = IdempotencyKey::with_max_length(&key, max_length);
+        prop_assert_eq!(result.is_ok(), key.len() <= max_length);
+    }
+
+    #[test]
+    fn prop_idempotency_key_parse_no_panic(input in "[ -~]{0,64}") {
+        if let Ok(key) = IdempotencyKey::parse(&input) {
+            prop_assert!(!key.as_str().is_empty());
+            prop_assert_eq!(IdempotencyKey::parse(&key.to_string()), Ok(key));
+        }
+    }
+}
+
+// ========================================
+// メソッドの分類
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_classify_matches_method(method in method(), has_key in any::<bool>()) {
+        let key = IdempotencyKey::new("key").unwrap();
+        let key = has_key.then_some(&key);
+        let parsed = Method::parse(&method).unwrap();
+        let idempotency = RequestIdempotency::classify(&method, key);
+        prop_assert_eq!(idempotency.is_safe(), parsed.is_safe());
+        prop_assert_eq!(idempotency.is_retryable(), parsed.is_idempotent() || has_key);
+    }
+}
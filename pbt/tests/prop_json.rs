@@ -0,0 +1,19 @@
+//! JSON ボディ変換のプロパティテスト
+
+use std::collections::BTreeMap;
+
+use proptest::prelude::*;
+use shiguredo_http11::{Response, ResponseDecoder, StatusCode};
+
+proptest! {
+    // json_body で設定した値はエンコード・デコード後に parse_json で元に戻る
+    #[test]
+    fn prop_json_body_roundtrip(value in proptest::collection::btree_map(any::<String>(), any::<i64>(), 0..8)) {
+        let response = Response::with_status(StatusCode::OK).json_body(&value).unwrap();
+        let mut decoder = ResponseDecoder::new();
+        decoder.feed(&response.encode().unwrap()).unwrap();
+        let decoded = decoder.decode().unwrap().unwrap();
+        let parsed: BTreeMap<String, i64> = decoded.parse_json().unwrap();
+        prop_assert_eq!(parsed, value);
+    }
+}
@@ -0,0 +1,91 @@
+//! Link ヘッダーのプロパティテスト (link.rs)
+
+use pbt::qdtext_value;
+use proptest::prelude::*;
+use shiguredo_http11::ext_value::ExtValue;
+use shiguredo_http11::link::{Link, LinkValue};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn target() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "/[a-z0-9/._,;=?&-]{0,16}",
+        "https://[a-z]{1,8}\\.example/[a-z0-9/?=&]{0,12}",
+    ]
+}
+
+fn link_value() -> impl Strategy<Value = LinkValue> {
+    (
+        target(),
+        proptest::collection::vec("[a-z][a-z0-9.-]{0,10}", 0..3),
+        proptest::option::of("[#/a-z0-9]{1,8}"),
+        proptest::option::of(qdtext_value(0..=12)),
+        proptest::option::of("[ -~\u{80}-\u{3000}]{0,12}"),
+        proptest::collection::vec(
+            (
+                "[a-z][a-z0-9-]{0,6}",
+                proptest::option::of(qdtext_value(0..=8)),
+            ),
+            0..3,
+        ),
+    )
+        .prop_map(|(target, rel, anchor, title, title_ext, params)| {
+            let mut value = LinkValue::new(&target).unwrap();
+            if !rel.is_empty() {
+                value = value.with_rel(&rel.join(" ")).unwrap();
+            }
+            if let Some(anchor) = anchor {
+                value = value.with_anchor(&anchor).unwrap();
+            }
+            if let Some(title) = title {
+                value = value.with_title(&title).unwrap();
+            }
+            if let Some(title_ext) = title_ext {
+                value = value.with_title_ext(ExtValue::new(&title_ext));
+            }
+            for (name, param) in params {
+                if matches!(name.as_str(), "rel" | "anchor" | "title") {
+                    continue;
+                }
+                value = value.with_param(&name, param.as_deref()).unwrap();
+            }
+            value
+        })
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// Link の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_link_roundtrip(values in proptest::collection::vec(link_value(), 0..4)) {
+        let mut link = Link::new();
+        for value in values {
+            link.push(value);
+        }
+        prop_assert_eq!(Link::parse(&link.to_string()).unwrap(), link);
+    }
+
+    /// 複数行に分けても 1 行と同じ結果になる
+    #[test]
+    fn prop_link_lines_joined(values in proptest::collection::vec(link_value(), 1..4)) {
+        let hints = shiguredo_http11::Response::early_hints(
+            values.iter().map(ToString::to_string),
+        )
+        .unwrap();
+        let parsed = Link::from_message(&hints).unwrap().unwrap();
+        prop_assert_eq!(parsed.values(), values.as_slice());
+    }
+}
+
+proptest! {
+    /// 任意の入力でパニックしない
+    #[test]
+    fn prop_link_parse_no_panic(input in "[ -~\t]{0,64}") {
+        let _ = Link::parse(&input);
+    }
+}
@@ -0,0 +1,80 @@
+//! HTTP メッセージ検査のプロパティテスト (lint.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::lint::{validate_request, validate_response};
+use shiguredo_http11::{Request, Response};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn header() -> impl Strategy<Value = (String, String)> {
+    (
+        prop_oneof![
+            Just("Host".to_string()),
+            Just("Content-Length".to_string()),
+            Just("Transfer-Encoding".to_string()),
+            Just("Content-Type".to_string()),
+            "[A-Za-z][A-Za-z-]{0,10}",
+        ],
+        prop_oneof![
+            Just("example.com".to_string()),
+            Just("chunked".to_string()),
+            Just("gzip, chunked".to_string()),
+            "[0-9]{1,3}",
+            "[ -~]{0,16}",
+        ],
+    )
+}
+
+fn body() -> impl Strategy<Value = Option<Vec<u8>>> {
+    proptest::option::of(proptest::collection::vec(any::<u8>(), 0..16))
+}
+
+// ========================================
+// エンコードとの整合性
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_lint_request_without_errors_encodes(
+        method in prop_oneof![Just("GET"), Just("POST"), Just("TRACE")],
+        uri in prop_oneof![Just("/"), Just("*"), Just("http://example.com/")],
+        headers in proptest::collection::vec(header(), 0..6),
+        body in body(),
+    ) {
+        let mut request = Request::new(method, uri).unwrap();
+        for (name, value) in &headers {
+            request.add_header(name.as_str(), value.as_str()).unwrap();
+        }
+        if let Some(body) = body {
+            request.set_body(body);
+        }
+        let violations = validate_request(&request);
+        if !violations.iter().any(|v| v.is_error()) {
+            let result = request.encode();
+            prop_assert!(result.is_ok(), "{:?}", result);
+        }
+    }
+
+    #[test]
+    fn prop_lint_response_without_errors_encodes(
+        status_code in prop_oneof![Just(200u16), Just(204), Just(205), Just(304), 100u16..600],
+        headers in proptest::collection::vec(header(), 0..6),
+        body in body(),
+        omit_body in any::<bool>(),
+    ) {
+        let mut response = Response::new(status_code, "X").unwrap().omit_body(omit_body);
+        for (name, value) in &headers {
+            response.add_header(name.as_str(), value.as_str()).unwrap();
+        }
+        if let Some(body) = body {
+            response.set_body(body);
+        }
+        let violations = validate_response(&response);
+        if !violations.iter().any(|v| v.is_error()) {
+            let result = response.encode();
+            prop_assert!(result.is_ok(), "{:?}", result);
+        }
+    }
+}
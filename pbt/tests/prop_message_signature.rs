@@ -0,0 +1,142 @@
+//! HTTP Message Signatures のプロパティテスト (message_signature.rs)
+
+use std::borrow::Cow;
+
+use proptest::prelude::*;
+use shiguredo_http11::message_signature::{
+    self, ComponentId, Signature, SignatureInput, SignatureParams, SignedMessage,
+};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn field_name() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9-]{0,10}"
+}
+
+fn label() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9_-]{0,8}"
+}
+
+fn component() -> impl Strategy<Value = ComponentId> {
+    prop_oneof![
+        prop::sample::select(vec![
+            "@method",
+            "@path",
+            "@query",
+            "@authority",
+            "@request-target"
+        ])
+        .prop_map(|name| ComponentId::new(name).unwrap()),
+        field_name().prop_map(|name| ComponentId::new(&name).unwrap()),
+        field_name().prop_map(|name| ComponentId::new(&name).unwrap().with_bs().unwrap()),
+        "[ -~]{1,8}".prop_map(|name| ComponentId::query_param(&name)),
+    ]
+}
+
+fn signature_params() -> impl Strategy<Value = SignatureParams> {
+    (
+        proptest::collection::vec(component(), 0..6),
+        proptest::option::of(0u64..=4_000_000_000),
+        proptest::option::of("[ -~]{0,16}"),
+    )
+        .prop_map(|(components, created, keyid)| {
+            let mut params = SignatureParams::new();
+            for component in components {
+                if let Ok(next) = params.clone().with_component(component) {
+                    params = next;
+                }
+            }
+            if let Some(created) = created {
+                params = params.with_created(created);
+            }
+            if let Some(keyid) = keyid {
+                params = params.with_keyid(&keyid).unwrap();
+            }
+            params
+        })
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_signature_input_roundtrip(
+        entries in proptest::collection::vec((label(), signature_params()), 0..4)
+    ) {
+        let mut input = SignatureInput::new();
+        for (label, params) in entries {
+            input = input.with_signature(&label, params).unwrap();
+        }
+        let parsed = SignatureInput::parse(&input.to_string()).unwrap();
+        prop_assert_eq!(parsed, input);
+    }
+
+    #[test]
+    fn prop_signature_roundtrip(
+        entries in proptest::collection::vec(
+            (label(), proptest::collection::vec(any::<u8>(), 0..64)),
+            0..4
+        )
+    ) {
+        let mut signature = Signature::new();
+        for (label, value) in entries {
+            signature = signature.with_signature(&label, value).unwrap();
+        }
+        let parsed = Signature::parse(&signature.to_string()).unwrap();
+        prop_assert_eq!(parsed, signature);
+    }
+}
+
+// ========================================
+// 署名ベース
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_signature_base_ends_with_params(params in signature_params()) {
+        let headers: Vec<(Cow<'static, str>, String)> = params
+            .components()
+            .iter()
+            .filter(|component| !component.is_derived())
+            .map(|component| (Cow::Owned(component.name().to_string()), "value".to_string()))
+            .collect();
+        let message = SignedMessage::request("GET", "/", &headers);
+        if let Ok(base) = params.signature_base(&message) {
+            let last = base.lines().last().unwrap();
+            prop_assert_eq!(last, format!("\"@signature-params\": {}", params));
+            prop_assert!(!base.ends_with('\n'));
+        }
+    }
+
+    #[test]
+    fn prop_sign_then_verify(
+        params in signature_params(),
+        value in "[!-~]{1,16}",
+        now in 0u64..4_000_000_000,
+    ) {
+        let mut headers: Vec<(Cow<'static, str>, String)> = params
+            .components()
+            .iter()
+            .filter(|component| !component.is_derived())
+            .map(|component| (Cow::Owned(component.name().to_string()), value.clone()))
+            .collect();
+        headers.push(("Host".into(), "example.com".to_string()));
+        let message = SignedMessage::request("POST", "/a/b?x=1", &headers);
+        let Ok((input, signature)) =
+            message_signature::sign(&message, "sig", &params, |base| Ok(base.to_vec()))
+        else {
+            return Ok(());
+        };
+        headers.push(("Signature-Input".into(), input.to_string()));
+        headers.push(("Signature".into(), signature.to_string()));
+        let message = SignedMessage::request("POST", "/a/b?x=1", &headers);
+        let verified = message_signature::verify(&message, "sig", now, |_, base, signature| {
+            base == signature
+        });
+        prop_assert_eq!(verified, Ok(params));
+    }
+}
@@ -0,0 +1,54 @@
+//! HTTP メソッド型のプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::method::Method;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn token() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9!#$%&'*+.^_`|~-]{1,16}"
+}
+
+// ========================================
+// プロパティテスト
+// ========================================
+
+proptest! {
+    // token は常にパースでき、as_str / Display / String 変換が入力と一致する
+    #[test]
+    fn prop_method_roundtrip(s in token()) {
+        let method = Method::parse(&s).unwrap();
+        prop_assert_eq!(method.as_str(), s.as_str());
+        prop_assert_eq!(method.to_string(), s.clone());
+        prop_assert!(method == s.as_str());
+        prop_assert_eq!(String::from(method), s);
+    }
+
+    // Method から構築した Request のメソッドは Method と等しい
+    #[test]
+    fn prop_method_into_request(s in token()) {
+        let method = Method::parse(&s).unwrap();
+        let request = Request::new(method.clone(), "/").unwrap();
+        prop_assert!(request.method() == method);
+    }
+
+    // idempotent は safe を含む (RFC 9110 Section 9.2.2)
+    #[test]
+    fn prop_safe_implies_idempotent(s in prop_oneof![
+        token(),
+        Just("GET".to_string()),
+        Just("HEAD".to_string()),
+        Just("PUT".to_string()),
+    ]) {
+        let method = Method::parse(&s).unwrap();
+        if method.is_safe() {
+            prop_assert!(method.is_idempotent());
+        }
+        if method.is_cacheable() {
+            prop_assert!(method.is_safe());
+        }
+    }
+}
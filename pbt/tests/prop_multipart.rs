@@ -2,7 +2,9 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::content_type::ContentType;
-use shiguredo_http11::multipart::{MultipartBuilder, MultipartParser, Part};
+use shiguredo_http11::multipart::{
+    MultipartBuilder, MultipartEncoder, MultipartError, MultipartParser, Part,
+};
 
 // ========================================
 // Strategy 定義
@@ -418,3 +420,117 @@ proptest! {
         );
     }
 }
+
+// ========================================
+// MultipartEncoder / with_safe_boundary のテスト
+// ========================================
+
+// ストリーミングエンコーダーの出力は MultipartBuilder と一致する
+proptest! {
+    #[test]
+    fn prop_multipart_encoder_matches_builder(
+        boundary in valid_boundary(),
+        fields in proptest::collection::vec(
+            (valid_field_name(), proptest::collection::vec(any::<u8>(), 0..128)),
+            0..5
+        ),
+        chunk_size in 1usize..32,
+    ) {
+        let mut builder = MultipartBuilder::with_boundary(&boundary);
+        let mut encoder = MultipartEncoder::with_boundary(&boundary);
+        let mut streamed = Vec::new();
+        for (name, data) in &fields {
+            builder = builder.part(Part::new(name).with_body(data));
+            streamed.extend_from_slice(&encoder.begin_part(&Part::new(name)));
+            for chunk in data.chunks(chunk_size) {
+                streamed.extend_from_slice(chunk);
+            }
+        }
+        streamed.extend_from_slice(&encoder.finish());
+
+        prop_assert_eq!(streamed, builder.build());
+    }
+}
+
+// boundary を含むボディでも with_safe_boundary 後はラウンドトリップする
+proptest! {
+    #[test]
+    fn prop_multipart_safe_boundary_roundtrip(
+        boundary in "[a-z]{1,4}",
+        values in proptest::collection::vec((0u8..4, "[a-z-]{0,8}"), 1..4),
+    ) {
+        // boundary と衝突しやすいボディを作る
+        let bodies: Vec<String> = values
+            .iter()
+            .map(|(n, extra)| match n {
+                0 => format!("--{}", boundary),
+                1 => format!("x\r\n--{}-1{}", boundary, extra),
+                2 => format!("--{}-2\r\n", boundary),
+                _ => extra.clone(),
+            })
+            .collect();
+
+        let mut builder = MultipartBuilder::with_boundary(&boundary);
+        for (i, body) in bodies.iter().enumerate() {
+            builder = builder.text_field(&format!("f{}", i), body);
+        }
+        let builder = builder.with_safe_boundary();
+        let encoded = builder.build();
+
+        let mut parser = MultipartParser::try_new(builder.boundary()).unwrap();
+        parser.feed(&encoded).unwrap();
+        for body in &bodies {
+            let part = parser.next_part().unwrap().unwrap();
+            prop_assert_eq!(part.body(), body.as_bytes());
+        }
+        prop_assert!(parser.next_part().unwrap().is_none());
+    }
+}
+
+// ========================================
+// 入れ子の multipart のテスト
+// ========================================
+
+// 入れ子の multipart は max_depth 以内ならラウンドトリップし、超えるとエラーになる
+proptest! {
+    #[test]
+    fn prop_multipart_nested_roundtrip(
+        depth in 1usize..5,
+        max_depth in 0usize..6,
+        files in proptest::collection::vec((valid_filename(), proptest::collection::vec(any::<u8>(), 0..64)), 1..4),
+    ) {
+        let leaves: Vec<Part> = files
+            .iter()
+            .map(|(filename, data)| Part::attachment(filename, "application/octet-stream").with_body(data))
+            .collect();
+        let mut nested = leaves.clone();
+        for level in (1..depth).rev() {
+            nested = vec![Part::attachment("nested", "text/plain").with_nested_parts(
+                "mixed",
+                &format!("level{}", level),
+                nested,
+            )];
+        }
+        let field = Part::new("files").with_nested_parts("mixed", "level0", nested);
+        let body = MultipartBuilder::with_boundary("outer").part(field.clone()).build();
+
+        let mut parser = MultipartParser::new("outer").with_max_depth(max_depth);
+        parser.feed(&body).unwrap();
+        let result = parser.next_part();
+        if max_depth == 0 {
+            let part = result.unwrap().unwrap();
+            prop_assert!(part.parts().is_empty());
+            prop_assert_eq!(part.body(), field.body());
+        } else if max_depth < depth {
+            prop_assert_eq!(result.unwrap_err(), MultipartError::NestingTooDeep);
+        } else {
+            let part = result.unwrap().unwrap();
+            prop_assert_eq!(&part, &field);
+            let mut current = &part;
+            for _ in 1..depth {
+                current = &current.parts()[0];
+            }
+            prop_assert_eq!(current.parts(), leaves.as_slice());
+        }
+    }
+}
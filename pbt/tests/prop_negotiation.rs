@@ -0,0 +1,106 @@
+//! プロアクティブネゴシエーションのプロパティテスト (negotiation.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::accept::{AcceptEncoding, negotiate_encoding};
+use shiguredo_http11::negotiation::Negotiator;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+const MEDIA_TYPES: &[&str] = &["application/json", "text/html", "text/plain", "image/png"];
+const ENCODINGS: &[&str] = &["br", "gzip", "zstd", "deflate"];
+const LANGUAGES: &[&str] = &["en", "en-US", "ja", "fr"];
+
+fn subset(candidates: &'static [&'static str]) -> impl Strategy<Value = Vec<&'static str>> {
+    proptest::sample::subsequence(candidates, 0..=candidates.len())
+}
+
+fn header_value() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[a-z*]{1,5}(/[a-z*]{1,5})?(;q=[01](\\.[0-9]{1,3})?)?(, [a-z*]{1,5}(/[a-z*]{1,5})?(;q=[01](\\.[0-9]{1,3})?)?){0,3}",
+        "[ -~]{0,20}",
+    ]
+}
+
+fn request() -> impl Strategy<Value = Request> {
+    (
+        proptest::option::of(header_value()),
+        proptest::option::of(header_value()),
+        proptest::option::of(header_value()),
+    )
+        .prop_map(|(accept, encoding, language)| {
+            let mut request = Request::new("GET", "/").unwrap();
+            for (name, value) in [
+                ("Accept", accept),
+                ("Accept-Encoding", encoding),
+                ("Accept-Language", language),
+            ] {
+                if let Some(value) = value {
+                    let _ = request.add_header(name, value);
+                }
+            }
+            request
+        })
+}
+
+// ========================================
+// 選択と Vary の性質
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_negotiation_selects_candidates(
+        media_types in subset(MEDIA_TYPES),
+        encodings in subset(ENCODINGS),
+        languages in subset(LANGUAGES),
+        request in request(),
+    ) {
+        let negotiator = Negotiator::new()
+            .with_media_types(&media_types)
+            .with_encodings(&encodings)
+            .with_languages(&languages);
+        let Ok(negotiation) = negotiator.negotiate(&request) else {
+            return Ok(());
+        };
+        prop_assert_eq!(negotiation.media_type().is_some(), !media_types.is_empty());
+        prop_assert_eq!(negotiation.language().is_some(), !languages.is_empty());
+        if let Some(media_type) = negotiation.media_type() {
+            prop_assert!(media_types.contains(&media_type));
+        }
+        if let Some(encoding) = negotiation.content_encoding() {
+            prop_assert!(encodings.contains(&encoding));
+        }
+        if let Some(language) = negotiation.language() {
+            prop_assert!(languages.contains(&language));
+        }
+        let vary_fields = negotiator.vary_fields();
+        prop_assert_eq!(negotiation.vary_fields(), vary_fields.as_slice());
+    }
+
+    #[test]
+    fn prop_negotiation_encoding_matches_negotiate_encoding(
+        encodings in subset(ENCODINGS),
+        value in header_value(),
+    ) {
+        let Ok(accept) = AcceptEncoding::parse(&value) else {
+            return Ok(());
+        };
+        let mut request = Request::new("GET", "/").unwrap();
+        if request.add_header("Accept-Encoding", value.as_str()).is_err() || encodings.is_empty() {
+            return Ok(());
+        }
+        let negotiator = Negotiator::new().with_encodings(&encodings);
+        let expected = negotiate_encoding(&accept, &encodings);
+        match negotiator.negotiate(&request) {
+            Ok(negotiation) => {
+                prop_assert_eq!(
+                    negotiation.content_encoding(),
+                    expected.filter(|e| *e != "identity")
+                );
+            }
+            Err(_) => prop_assert_eq!(expected, None),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Prefer / Preference-Applied ヘッダーのプロパティテスト (prefer.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::prefer::{Prefer, Preference, PreferenceApplied};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn token() -> impl Strategy<Value = String> {
+    "[a-z0-9!#$%&'*+.^_`|~-]{1,12}"
+}
+
+fn value() -> impl Strategy<Value = Option<String>> {
+    proptest::option::of("[ -~]{1,16}")
+}
+
+fn preference() -> impl Strategy<Value = Preference> {
+    (
+        token(),
+        value(),
+        proptest::collection::vec((token(), value()), 0..4),
+    )
+        .prop_map(|(name, value, params)| {
+            let mut preference = Preference::new(&name).unwrap();
+            if let Some(value) = value {
+                preference = preference.with_value(&value).unwrap();
+            }
+            for (name, value) in params {
+                preference = preference.with_param(&name, value.as_deref()).unwrap();
+            }
+            preference
+        })
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_prefer_roundtrip(preferences in proptest::collection::vec(preference(), 0..5)) {
+        let prefer = preferences
+            .into_iter()
+            .fold(Prefer::new(), Prefer::with_preference);
+        let parsed = Prefer::parse(&prefer.to_string()).unwrap();
+        prop_assert_eq!(parsed, prefer);
+    }
+
+    #[test]
+    fn prop_preference_applied_roundtrip(
+        applied in proptest::collection::vec((token(), value()), 0..5)
+    ) {
+        let mut header = PreferenceApplied::new();
+        for (name, value) in &applied {
+            header = header.with(name, value.as_deref()).unwrap();
+        }
+        let parsed = PreferenceApplied::parse(&header.to_string()).unwrap();
+        prop_assert_eq!(parsed, header);
+    }
+
+    #[test]
+    fn prop_prefer_first_wins(wait1 in 0u64..1_000_000, wait2 in 0u64..1_000_000) {
+        let prefer = Prefer::parse(&format!("wait={wait1}, respond-async, wait={wait2}")).unwrap();
+        prop_assert_eq!(prefer.wait(), Some(wait1));
+        prop_assert!(prefer.respond_async());
+    }
+
+    #[test]
+    fn prop_prefer_parse_no_panic(input in "[ -~]{0,64}") {
+        if let Ok(prefer) = Prefer::parse(&input) {
+            prop_assert_eq!(Prefer::parse(&prefer.to_string()), Ok(prefer));
+        }
+        let _ = PreferenceApplied::parse(&input);
+    }
+}
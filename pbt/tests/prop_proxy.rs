@@ -0,0 +1,122 @@
+//! プロキシのメッセージ書き換えのプロパティテスト (proxy.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::connection::HOP_BY_HOP_HEADERS;
+use shiguredo_http11::proxy::ProxyRewriter;
+use shiguredo_http11::{BodyKind, HttpHead, RequestDecoder, RequestHead, ResponseHead};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn header_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "X-[A-Za-z]{1,8}",
+        Just("Accept".to_string()),
+        Just("Connection".to_string()),
+        Just("Keep-Alive".to_string()),
+        Just("Upgrade".to_string()),
+        Just("TE".to_string()),
+        Just("Content-Length".to_string()),
+        Just("Transfer-Encoding".to_string()),
+        Just("Proxy-Authorization".to_string()),
+    ]
+}
+
+fn header_value() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9-]{1,10}( ?, ?[A-Za-z0-9-]{1,10}){0,2}"
+}
+
+fn headers() -> impl Strategy<Value = Vec<(String, String)>> {
+    proptest::collection::vec((header_name(), header_value()), 0..8)
+}
+
+fn body_kind() -> impl Strategy<Value = BodyKind> {
+    prop_oneof![
+        Just(BodyKind::None),
+        Just(BodyKind::Chunked),
+        (0u64..100_000).prop_map(BodyKind::ContentLength),
+    ]
+}
+
+fn request_head(headers: &[(String, String)]) -> RequestHead {
+    let mut head = RequestHead::new("POST", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    for (name, value) in headers {
+        head.add_header(name, value).unwrap();
+    }
+    head
+}
+
+// ========================================
+// hop-by-hop ヘッダーとフレーミング
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_proxy_request_has_no_hop_by_hop(headers in headers(), kind in body_kind()) {
+        let head = request_head(&headers);
+        let request = ProxyRewriter::new().rewrite_request(&head, kind, None).unwrap();
+        let forwarded = request.head();
+        for (name, _) in forwarded.headers() {
+            let is_framing = name.eq_ignore_ascii_case("transfer-encoding");
+            prop_assert!(
+                is_framing || !HOP_BY_HOP_HEADERS.iter().any(|h| h.eq_ignore_ascii_case(name))
+            );
+        }
+        // Content-Length と Transfer-Encoding は同時に付かない
+        prop_assert!(
+            forwarded.get_header("Content-Length").is_none()
+                || forwarded.get_header("Transfer-Encoding").is_none()
+        );
+        prop_assert_eq!(forwarded.get_headers("Host"), vec!["example.com"]);
+        match kind {
+            BodyKind::ContentLength(len) => {
+                let expected = len.to_string();
+                prop_assert_eq!(forwarded.get_headers("Content-Length"), vec![expected.as_str()]);
+            }
+            BodyKind::Chunked => {
+                prop_assert_eq!(forwarded.get_headers("Transfer-Encoding").len(), 1);
+            }
+            _ => {
+                prop_assert!(forwarded.get_header("Content-Length").is_none());
+                prop_assert!(forwarded.get_header("Transfer-Encoding").is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn prop_proxy_request_encode_decodes(headers in headers(), kind in body_kind()) {
+        // 受信した Transfer-Encoding は chunked で転送するときに引き継がれるため含めない
+        let headers: Vec<_> = headers
+            .into_iter()
+            .filter(|(name, _)| name != "Transfer-Encoding")
+            .collect();
+        let head = request_head(&headers);
+        let request = ProxyRewriter::new().rewrite_request(&head, kind, None).unwrap();
+        let mut decoder = RequestDecoder::new();
+        decoder.feed(&request.encode_head()).unwrap();
+        let (decoded, decoded_kind) = decoder.decode_headers().unwrap().unwrap();
+        prop_assert_eq!(decoded.headers(), request.head().headers());
+        prop_assert_eq!(decoded_kind, kind);
+    }
+
+    #[test]
+    fn prop_proxy_response_keeps_end_to_end(headers in headers(), kind in body_kind()) {
+        let mut head = ResponseHead::new(200, "OK").unwrap();
+        for (name, value) in &headers {
+            head.add_header(name, value).unwrap();
+        }
+        let response = ProxyRewriter::new().rewrite_response(&head, kind).unwrap();
+        // X- ヘッダーは Connection に列挙されない限り残る
+        for (name, _) in headers.iter().filter(|(name, _)| name.starts_with("X-")) {
+            let listed = head
+                .get_headers("Connection")
+                .iter()
+                .any(|v| v.split(',').any(|o| o.trim().eq_ignore_ascii_case(name)));
+            prop_assert_eq!(response.head().get_header(name).is_some(), !listed);
+        }
+    }
+}
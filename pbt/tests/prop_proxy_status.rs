@@ -0,0 +1,81 @@
+//! Proxy-Status ヘッダーのプロパティテスト (proxy_status.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::proxy_status::{ProxyErrorType, ProxyStatus, ProxyStatusEntry};
+use shiguredo_http11::sfv::BareItem;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn error_type() -> impl Strategy<Value = ProxyErrorType> {
+    prop_oneof![
+        Just(ProxyErrorType::DnsTimeout),
+        Just(ProxyErrorType::DnsError),
+        Just(ProxyErrorType::ConnectionRefused),
+        Just(ProxyErrorType::TlsAlertReceived),
+        Just(ProxyErrorType::HttpResponseTimeout),
+        Just(ProxyErrorType::ProxyLoopDetected),
+        "x_[a-z_]{1,10}".prop_map(ProxyErrorType::Other),
+    ]
+}
+
+fn entry() -> impl Strategy<Value = ProxyStatusEntry> {
+    (
+        "[!-~][ -~]{0,16}",
+        proptest::option::of(error_type()),
+        proptest::option::of("[ -~]{0,16}"),
+        proptest::option::of(proptest::collection::vec(any::<u8>(), 0..8)),
+        proptest::option::of(100u16..1000),
+        proptest::option::of("[ -~]{0,16}"),
+        proptest::option::of(0i64..1000),
+    )
+        .prop_map(
+            |(intermediary, error, next_hop, protocol, received, details, alert_id)| {
+                let mut entry = ProxyStatusEntry::new(&intermediary).unwrap();
+                if let Some(error) = error {
+                    entry = entry.with_error(error);
+                }
+                if let Some(next_hop) = next_hop {
+                    entry = entry.with_next_hop(&next_hop).unwrap();
+                }
+                if let Some(protocol) = protocol {
+                    entry = entry.with_next_protocol(protocol);
+                }
+                if let Some(received) = received {
+                    entry = entry.with_received_status(received);
+                }
+                if let Some(details) = details {
+                    entry = entry.with_details(&details).unwrap();
+                }
+                if let Some(alert_id) = alert_id {
+                    entry = entry
+                        .with_param("alert-id", BareItem::Integer(alert_id))
+                        .unwrap();
+                }
+                entry
+            },
+        )
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// Proxy-Status の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_proxy_status_roundtrip(entries in proptest::collection::vec(entry(), 0..4)) {
+        let header = entries.into_iter().fold(ProxyStatus::new(), ProxyStatus::with_entry);
+        prop_assert_eq!(ProxyStatus::parse(&header.to_string()), Ok(header));
+    }
+
+    /// エラーの種類は名前から復元でき、推奨ステータスコードは 4xx / 5xx
+    #[test]
+    fn prop_proxy_error_type_name(error in error_type()) {
+        prop_assert_eq!(ProxyErrorType::from_name(error.as_str()), Ok(error.clone()));
+        if let Some(status) = error.recommended_status() {
+            prop_assert!(status.is_client_error() || status.is_server_error());
+        }
+    }
+}
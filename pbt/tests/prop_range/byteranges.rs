@@ -0,0 +1,82 @@
+//! multipart/byteranges のプロパティテスト (range/byteranges.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::multipart::MultipartError;
+use shiguredo_http11::range::{ByteRangesBuilder, ByteRangesParser, Range};
+
+// 生成したボディを任意の分割でパースすると元の範囲とデータに戻る
+proptest! {
+    #[test]
+    fn prop_byteranges_roundtrip(
+        content in proptest::collection::vec(any::<u8>(), 1..256),
+        bounds in proptest::collection::vec((any::<u16>(), any::<u16>()), 1..5),
+        chunk_size in 1usize..64,
+    ) {
+        let total = content.len();
+        let mut builder = ByteRangesBuilder::with_boundary("byteranges-boundary", total as u64);
+        let mut expected = Vec::new();
+        for (a, b) in bounds {
+            let (a, b) = (a as usize % total, b as usize % total);
+            let (start, end) = if a <= b { (a, b) } else { (b, a) };
+            builder = builder.part(start as u64, end as u64, &content[start..=end]);
+            expected.push((start as u64, end as u64, content[start..=end].to_vec()));
+        }
+        let body = builder.build();
+
+        let mut parser = ByteRangesParser::new("byteranges-boundary");
+        let mut parts = Vec::new();
+        for chunk in body.chunks(chunk_size) {
+            parser.feed(chunk).unwrap();
+            loop {
+                match parser.next_part() {
+                    Ok(Some(part)) => parts.push(part),
+                    Ok(None) | Err(MultipartError::Incomplete) => break,
+                    Err(e) => {
+                        let message = e.to_string();
+                        prop_assert!(false, "unexpected error: {}", message);
+                    }
+                }
+            }
+        }
+        prop_assert!(parser.is_finished());
+        let actual: Vec<(u64, u64, Vec<u8>)> = parts
+            .iter()
+            .map(|part| (part.start(), part.end(), part.body().to_vec()))
+            .collect();
+        prop_assert_eq!(actual, expected);
+    }
+}
+
+// Range ヘッダーから生成したパートは to_bounds の結果と一致する
+proptest! {
+    #[test]
+    fn prop_byteranges_parts_from_range_matches_to_bounds(
+        content in proptest::collection::vec(any::<u8>(), 1..128),
+        specs in proptest::collection::vec((0u64..200, 0u64..200), 1..4),
+    ) {
+        let header = specs
+            .iter()
+            .map(|(a, b)| format!("{}-{}", a.min(b), a.max(b)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let range = Range::parse(&format!("bytes={}", header)).unwrap();
+        let total = content.len() as u64;
+        let builder = ByteRangesBuilder::with_boundary("b", total).parts_from_range(&range, &content);
+
+        let expected: Vec<(u64, u64)> = range
+            .ranges()
+            .iter()
+            .filter_map(|spec| spec.to_bounds(total))
+            .collect();
+        prop_assert_eq!(builder.len(), expected.len());
+
+        let mut parser = ByteRangesParser::new("b");
+        parser.feed(&builder.build()).unwrap();
+        for (start, end) in expected {
+            let part = parser.next_part().unwrap().unwrap();
+            prop_assert_eq!((part.start(), part.end()), (start, end));
+            prop_assert_eq!(part.body(), &content[start as usize..=end as usize]);
+        }
+        prop_assert!(parser.next_part().unwrap().is_none());
+    }
+}
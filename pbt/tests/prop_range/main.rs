@@ -1,7 +1,19 @@
 //! Range 関連のプロパティテスト
+//!
+//! Range / Content-Range / Accept-Ranges のテストはここに置き、
+//! サブモジュールのテストは `src/range/` のモジュールごとに分割する。
+//! - `byteranges`: multipart/byteranges の生成とパース
+
+mod byteranges;
 
 use proptest::prelude::*;
-use shiguredo_http11::range::{ContentRange, Range, RangeSpec};
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::range::{
+    AcceptRanges, ContentRange, Range, RangeError, RangeSatisfaction, RangeSpec, coalesce_ranges,
+    if_range_allows,
+};
+use shiguredo_http11::{Response, StatusCode};
 
 // ========================================
 // RangeSpec のテスト
@@ -300,6 +312,67 @@ proptest! {
     }
 }
 
+// AcceptRanges の範囲単位の判定
+proptest! {
+    #[test]
+    fn prop_accept_ranges_accepts(units in proptest::collection::vec("[a-z]{1,8}", 1..5)) {
+        prop_assume!(units.iter().all(|unit| unit != "none"));
+        let ar = AcceptRanges::parse(&units.join(", ")).unwrap();
+        for unit in &units {
+            prop_assert!(ar.accepts(unit));
+            prop_assert!(ar.accepts(&unit.to_ascii_uppercase()));
+        }
+        prop_assert!(!ar.accepts("none"));
+        prop_assert_eq!(ar.accepts_bytes(), units.iter().any(|unit| unit == "bytes"));
+
+        let response = Response::with_status(StatusCode::OK).accept_ranges(&ar);
+        prop_assert_eq!(AcceptRanges::from_message(&response), Ok(Some(ar)));
+    }
+}
+
+// Response::range_not_satisfiable のテスト
+proptest! {
+    #[test]
+    fn prop_range_not_satisfiable_response(total in any::<u64>()) {
+        let response = Response::range_not_satisfiable(total);
+        prop_assert_eq!(response.status_code(), 416);
+
+        let cr = ContentRange::parse(response.get_header("Content-Range").unwrap()).unwrap();
+        prop_assert!(cr.is_unsatisfied());
+        prop_assert_eq!(cr.complete_length(), Some(total));
+        prop_assert_eq!(response.body_bytes(), Some(&b""[..]));
+    }
+}
+
+// ContentRange::validate_upload のテスト
+proptest! {
+    #[test]
+    fn prop_content_range_validate_upload(
+        start in 0u64..10000,
+        length in 1u64..10000,
+        extra in 0u64..10000,
+        body_length in 0u64..20000,
+    ) {
+        let end = start + length - 1;
+        let cr = ContentRange::parse(&format!("bytes {}-{}/{}", start, end, end + 1 + extra)).unwrap();
+        prop_assert_eq!(cr.validate_upload(length), Ok(()));
+        if body_length != length {
+            prop_assert_eq!(
+                cr.validate_upload(body_length),
+                Err(RangeError::LengthMismatch { expected: length, actual: body_length })
+            );
+        }
+
+        // 終了位置 < 開始位置はパース時に拒否する
+        if length > 1 {
+            prop_assert_eq!(
+                ContentRange::parse(&format!("bytes {}-{}/*", end, start)),
+                Err(RangeError::InvalidBounds)
+            );
+        }
+    }
+}
+
 // ContentRange Display ラウンドトリップ (unsatisfied)
 proptest! {
     #[test]
@@ -327,3 +400,109 @@ proptest! {
         prop_assert!(cr.complete_length().is_none());
     }
 }
+
+// ========================================
+// Range の評価のテスト
+// ========================================
+
+// 結合後の範囲は昇順で重ならず隣接せず、元の範囲と同じバイト集合を覆う
+proptest! {
+    #[test]
+    fn prop_coalesce_ranges_covers_same_bytes(
+        ranges in proptest::collection::vec((0u64..200, 0u64..200), 0..8),
+    ) {
+        let ranges: Vec<(u64, u64)> = ranges
+            .into_iter()
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        let coalesced = coalesce_ranges(&ranges);
+
+        for pair in coalesced.windows(2) {
+            prop_assert!(pair[0].1 + 1 < pair[1].0);
+        }
+        for byte in 0u64..200 {
+            let covered = ranges.iter().any(|&(s, e)| s <= byte && byte <= e);
+            let covered_after = coalesced.iter().any(|&(s, e)| s <= byte && byte <= e);
+            prop_assert_eq!(covered, covered_after);
+        }
+    }
+}
+
+// evaluate の結果は satisfiable_bounds を結合したものと一致し、Content-Range は範囲内に収まる
+proptest! {
+    #[test]
+    fn prop_evaluate_consistent_with_bounds(
+        specs in proptest::collection::vec((0u64..300, 0u64..300, 0u8..3), 1..5),
+        total in 1u64..256,
+    ) {
+        let header = specs
+            .iter()
+            .map(|&(a, b, kind)| match kind {
+                0 => format!("{}-{}", a.min(b), a.max(b)),
+                1 => format!("{}-", a),
+                _ => format!("-{}", b),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let range = Range::parse(&format!("bytes={}", header)).unwrap();
+        let bounds = range.satisfiable_bounds(total);
+        let satisfaction = range.evaluate(total);
+
+        if bounds.is_empty() {
+            prop_assert_eq!(
+                satisfaction,
+                RangeSatisfaction::Unsatisfiable { complete_length: total }
+            );
+        } else {
+            let coalesced = coalesce_ranges(&bounds);
+            prop_assert_eq!(satisfaction.ranges(), coalesced.as_slice());
+            for content_range in satisfaction.content_ranges() {
+                prop_assert_eq!(content_range.complete_length(), Some(total));
+                prop_assert!(content_range.end().unwrap() < total);
+                let reparsed = ContentRange::parse(&content_range.to_string()).unwrap();
+                prop_assert_eq!(reparsed, content_range);
+            }
+        }
+    }
+}
+
+// ========================================
+// if_range_allows のテスト
+// ========================================
+
+// entity-tag は Strong 比較と一致する
+proptest! {
+    #[test]
+    fn prop_if_range_allows_etag_strong_compare(
+        tag1 in "[a-z0-9]{1,8}",
+        tag2 in "[a-z0-9]{1,8}",
+        weak1 in any::<bool>(),
+        weak2 in any::<bool>(),
+    ) {
+        let if_range = if weak1 { EntityTag::weak(&tag1) } else { EntityTag::strong(&tag1) }.unwrap();
+        let current = if weak2 { EntityTag::weak(&tag2) } else { EntityTag::strong(&tag2) }.unwrap();
+        let value = if_range.to_string();
+        prop_assert_eq!(
+            if_range_allows(&value, Some(&current), None),
+            if_range.strong_compare(&current)
+        );
+    }
+}
+
+// HTTP-date は Last-Modified と同じ秒の場合のみ一致する
+proptest! {
+    #[test]
+    fn prop_if_range_allows_date_exact(
+        modified in 0u64..253_402_300_799,
+        requested in 0u64..253_402_300_799,
+    ) {
+        let modified_date = HttpDate::from_unix_seconds(modified).unwrap();
+        let requested_date = HttpDate::from_unix_seconds(requested).unwrap();
+        let value = requested_date.to_string();
+        prop_assert_eq!(
+            if_range_allows(&value, None, Some(&modified_date)),
+            modified == requested
+        );
+        prop_assert!(!if_range_allows(&value, None, None));
+    }
+}
@@ -0,0 +1,93 @@
+//! RateLimit ヘッダーのプロパティテスト (rate_limit.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::rate_limit::{
+    QuotaPolicy, QuotaUnit, RateLimit, RateLimitBuilder, RateLimitPolicy, ServiceLimit,
+};
+
+// sf-integer の範囲
+const MAX_INTEGER: u64 = 999_999_999_999_999;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn quota_unit() -> impl Strategy<Value = QuotaUnit> {
+    prop_oneof![
+        Just(QuotaUnit::Requests),
+        Just(QuotaUnit::ContentBytes),
+        Just(QuotaUnit::ConcurrentRequests),
+        "[a-z][a-z-]{0,10}".prop_map(QuotaUnit::Other),
+    ]
+}
+
+fn quota_policy() -> impl Strategy<Value = QuotaPolicy> {
+    (
+        "[ -~]{0,16}",
+        0..=MAX_INTEGER,
+        quota_unit(),
+        proptest::option::of(0..=MAX_INTEGER),
+        proptest::option::of(proptest::collection::vec(any::<u8>(), 0..16)),
+    )
+        .prop_map(|(name, quota, unit, window, key)| {
+            let mut policy = QuotaPolicy::new(&name, quota)
+                .unwrap()
+                .with_unit(unit)
+                .unwrap();
+            if let Some(window) = window {
+                policy = policy.with_window(window);
+            }
+            if let Some(key) = key {
+                policy = policy.with_partition_key(key);
+            }
+            policy
+        })
+}
+
+fn service_limit() -> impl Strategy<Value = ServiceLimit> {
+    (
+        "[ -~]{0,16}",
+        0..=MAX_INTEGER,
+        proptest::option::of(0..=MAX_INTEGER),
+    )
+        .prop_map(|(name, remaining, reset)| {
+            let limit = ServiceLimit::new(&name, remaining).unwrap();
+            match reset {
+                Some(reset) => limit.with_reset(reset),
+                None => limit,
+            }
+        })
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// RateLimit-Policy の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_rate_limit_policy_roundtrip(policies in proptest::collection::vec(quota_policy(), 0..4)) {
+        let header = policies
+            .into_iter()
+            .fold(RateLimitPolicy::new(), RateLimitPolicy::with_policy);
+        prop_assert_eq!(RateLimitPolicy::parse(&header.to_string()), Ok(header));
+    }
+
+    /// RateLimit の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_rate_limit_roundtrip(limits in proptest::collection::vec(service_limit(), 0..4)) {
+        let header = limits.into_iter().fold(RateLimit::new(), RateLimit::with_limit);
+        prop_assert_eq!(RateLimit::parse(&header.to_string()), Ok(header));
+    }
+
+    /// ビルダーの残りクォータはクォータを超えず、枯渇時は Retry-After を返す
+    #[test]
+    fn prop_rate_limit_builder(quota in 0u64..1000, remaining in 0u64..2000, reset in 0u64..100000) {
+        let builder = RateLimitBuilder::new()
+            .with_quota(QuotaPolicy::new("default", quota).unwrap(), remaining, reset);
+        let limit = &builder.limit().limits()[0];
+        prop_assert_eq!(limit.remaining(), remaining.min(quota));
+        prop_assert_eq!(builder.is_exhausted(), remaining.min(quota) == 0);
+        prop_assert_eq!(builder.limit().retry_after().is_some(), builder.is_exhausted());
+    }
+}
@@ -0,0 +1,84 @@
+//! リダイレクトの追従のプロパティテスト (redirect.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::redirect::RedirectPolicy;
+use shiguredo_http11::{Request, ResponseDecoder, ResponseHead};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn status_code() -> impl Strategy<Value = u16> {
+    prop_oneof![Just(301u16), Just(302), Just(303), Just(307), Just(308)]
+}
+
+fn method() -> impl Strategy<Value = &'static str> {
+    prop_oneof![
+        Just("GET"),
+        Just("HEAD"),
+        Just("POST"),
+        Just("PUT"),
+        Just("DELETE")
+    ]
+}
+
+fn location() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "/([a-z0-9][a-z0-9/]{0,11})?",
+        "[a-z0-9]{1,8}",
+        "\\?[a-z0-9=&]{0,8}",
+        "(http|https)://(example\\.com|other\\.test)(:[0-9]{2,4})?/[a-z0-9]{0,8}",
+    ]
+}
+
+fn redirect_to(status_code: u16, location: &str) -> ResponseHead {
+    let input =
+        format!("HTTP/1.1 {status_code} X\r\nLocation: {location}\r\nContent-Length: 0\r\n\r\n");
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(input.as_bytes()).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+// ========================================
+// 追従したリクエストの性質
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_redirect_request_is_encodable(
+        method in method(),
+        status_code in status_code(),
+        location in location(),
+        body in proptest::collection::vec(any::<u8>(), 0..8),
+    ) {
+        let url = "https://example.com/a/b";
+        let request = Request::from_url(method, url)
+            .unwrap()
+            .0
+            .header("Authorization", "Bearer token")
+            .unwrap()
+            .body(body);
+        let head = redirect_to(status_code, &location);
+        let redirect = RedirectPolicy::new()
+            .follow(&request, url, &head, 0)
+            .unwrap()
+            .unwrap();
+        let next = redirect.request();
+
+        prop_assert!(next.encode().is_ok());
+        prop_assert_eq!(next.get_headers("Host").len(), 1);
+        prop_assert_eq!(
+            next.get_header("Authorization").is_none(),
+            redirect.is_cross_origin()
+        );
+        if next.method() == method {
+            prop_assert_eq!(next.body_bytes(), request.body_bytes());
+        } else {
+            prop_assert_eq!(next.method(), "GET");
+            prop_assert_eq!(next.body_bytes(), None);
+        }
+        if matches!(status_code, 307 | 308) {
+            prop_assert_eq!(next.method(), method);
+        }
+    }
+}
@@ -476,3 +476,84 @@ proptest! {
         prop_assert_eq!(req.get_header("X-Test"), Some(old_value.as_str()));
     }
 }
+
+// remove_header / insert_header_if_absent: 他の名前のヘッダーの順序は保たれる
+proptest! {
+    #[test]
+    fn prop_request_remove_and_insert_header(
+        headers in headers(),
+        name in header_name(),
+        value in header_value(),
+    ) {
+        let mut req = Request::new("GET", "/").unwrap();
+        for (n, v) in &headers {
+            req.add_header(n.as_str(), v.as_str()).unwrap();
+        }
+        let others: Vec<(String, String)> = headers
+            .iter()
+            .filter(|(n, _)| !n.eq_ignore_ascii_case(&name))
+            .cloned()
+            .collect();
+
+        req.remove_header(&name);
+        prop_assert!(!req.has_header(&name));
+        let remaining: Vec<(String, String)> = HttpHead::headers(&req)
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.clone()))
+            .collect();
+        prop_assert_eq!(&remaining, &others);
+
+        req.insert_header_if_absent(name.as_str(), value.as_str()).unwrap();
+        req.insert_header_if_absent(name.as_str(), "other").unwrap();
+        prop_assert_eq!(req.get_headers(&name), vec![value.as_str()]);
+    }
+}
+
+// ========================================
+// Request::from_url
+// ========================================
+
+proptest! {
+    // from_url の Host / request-target / Endpoint は URL の構成要素と一致する
+    #[test]
+    fn prop_from_url_components(
+        secure in any::<bool>(),
+        host in "[a-z][a-z0-9-]{0,10}(\\.[a-z]{2,5}){0,2}",
+        port in proptest::option::of(1u16..=65535),
+        path in "(/[a-zA-Z0-9._~-]{0,8}){0,3}",
+        query in proptest::option::of("[a-zA-Z0-9=&]{0,12}"),
+    ) {
+        let scheme = if secure { "https" } else { "http" };
+        let default_port = if secure { 443 } else { 80 };
+        let mut url = format!("{scheme}://{host}");
+        if let Some(p) = port {
+            url.push_str(&format!(":{p}"));
+        }
+        url.push_str(&path);
+        if let Some(q) = &query {
+            url.push('?');
+            url.push_str(q);
+        }
+
+        let (req, endpoint) = Request::from_url("GET", &url).unwrap();
+        let effective_port = port.unwrap_or(default_port);
+        prop_assert_eq!(endpoint.port(), effective_port);
+        prop_assert_eq!(endpoint.host(), host.as_str());
+        prop_assert_eq!(endpoint.is_secure(), secure);
+
+        let expected_host = if effective_port == default_port {
+            host.clone()
+        } else {
+            format!("{host}:{effective_port}")
+        };
+        prop_assert_eq!(req.get_header("Host"), Some(expected_host.as_str()));
+
+        let mut expected_target = if path.is_empty() { "/".to_string() } else { path.clone() };
+        if let Some(q) = &query {
+            expected_target.push('?');
+            expected_target.push_str(q);
+        }
+        prop_assert_eq!(req.uri(), expected_target.as_str());
+        prop_assert!(req.encode().is_ok());
+    }
+}
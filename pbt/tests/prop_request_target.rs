@@ -0,0 +1,112 @@
+//! request-target のプロパティテスト (request_target.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::request_target::{RequestTarget, RequestTargetForm, classify};
+use shiguredo_http11::uri::{percent_encode_path, serialize_query};
+use shiguredo_http11::{Request, RequestDecoder};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn path() -> impl Strategy<Value = String> {
+    proptest::collection::vec("[a-zA-Z0-9._~-]{1,8}", 0..4)
+        .prop_map(|segments| format!("/{}", segments.join("/")))
+}
+
+fn hostname() -> impl Strategy<Value = String> {
+    proptest::collection::vec("[a-z0-9]{1,8}", 1..4).prop_map(|labels| labels.join("."))
+}
+
+// ========================================
+// 各形式
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_origin_form_roundtrip(path in path(), query in proptest::option::of("[a-z0-9=&%]{0,16}")) {
+        let target = match &query {
+            Some(query) => format!("{}?{}", path, query),
+            None => path.clone(),
+        };
+        let parsed = RequestTarget::parse(&target).unwrap();
+        prop_assert_eq!(parsed.to_string(), target);
+        prop_assert_eq!(parsed, RequestTarget::OriginForm { path, query });
+    }
+
+    #[test]
+    fn prop_authority_form_roundtrip(host in hostname(), port in any::<u16>()) {
+        let target = format!("{}:{}", host, port);
+        let parsed = RequestTarget::parse(&target).unwrap();
+        prop_assert_eq!(parsed.to_string(), target);
+        prop_assert_eq!(parsed, RequestTarget::AuthorityForm { host, port });
+    }
+
+    #[test]
+    fn prop_absolute_form_roundtrip(host in hostname(), path in path()) {
+        let target = format!("http://{}{}", host, path);
+        let parsed = RequestTarget::parse(&target).unwrap();
+        prop_assert_eq!(parsed.form(), RequestTargetForm::Absolute);
+        prop_assert_eq!(parsed.to_string(), target);
+    }
+}
+
+// ========================================
+// デコーダーとの整合性
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_decoded_target_matches_classify(path in path(), host in hostname(), port in any::<u16>()) {
+        for (method, target) in [
+            ("GET", path.clone()),
+            ("GET", format!("http://{}{}", host, path)),
+            ("CONNECT", format!("{}:{}", host, port)),
+            ("OPTIONS", "*".to_string()),
+        ] {
+            let raw = format!("{} {} HTTP/1.1\r\nHost: {}\r\n\r\n", method, target, host);
+            let mut decoder = RequestDecoder::new();
+            decoder.feed(raw.as_bytes()).unwrap();
+            let (head, _) = decoder.decode_headers().unwrap().unwrap();
+            let parsed = head.target().unwrap();
+            prop_assert_eq!(Some(parsed.form()), classify(head.uri()));
+            prop_assert_eq!(parsed.to_string(), target);
+        }
+    }
+}
+
+// ========================================
+// path / query アクセサ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_path_query_accessors(
+        segments in proptest::collection::vec("\\PC{0,8}", 0..4),
+        pairs in proptest::collection::vec(("[a-z]{1,4}", "\\PC{0,8}"), 0..4),
+    ) {
+        let path = format!(
+            "/{}",
+            segments.iter().map(|s| percent_encode_path(s)).collect::<Vec<_>>().join("/")
+        );
+        let query = serialize_query(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        let request = Request::new("GET", format!("{}?{}", path, query)).unwrap();
+        prop_assert_eq!(request.path(), path.as_str());
+        prop_assert_eq!(request.query(), Some(query.as_str()));
+        prop_assert_eq!(request.decoded_path().unwrap(), format!("/{}", segments.join("/")));
+        prop_assert_eq!(request.query_pairs().unwrap(), pairs);
+    }
+}
+
+// ========================================
+// パニック安全性
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_request_target_parse_no_panic(input in "[ -~]{0,64}") {
+        if let Ok(parsed) = RequestTarget::parse(&input) {
+            prop_assert_eq!(Some(parsed.form()), classify(&input));
+        }
+    }
+}
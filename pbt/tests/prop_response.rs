@@ -1,6 +1,7 @@
 //! Response 構造体のプロパティテスト
 
 use proptest::prelude::*;
+use shiguredo_http11::date::{DayOfWeek, HttpDate};
 use shiguredo_http11::{EncodeError, HttpHead, Response, ResponseDecoder, StatusClass, StatusCode};
 
 // ========================================
@@ -584,3 +585,31 @@ proptest! {
         prop_assert_eq!(response.body_bytes(), Some(body_data.as_slice()));
     }
 }
+
+// ========================================
+// Date ヘッダー自動付与
+// ========================================
+
+proptest! {
+    // auto_date で付与した Date ヘッダーはデコード後に同じ HttpDate にパースできる
+    #[test]
+    fn prop_auto_date_roundtrip(
+        status in status_code(),
+        year in 1000u16..=9999,
+        month in 1u8..=12,
+        day in 1u8..=28,
+        hour in 0u8..=23,
+        minute in 0u8..=59,
+        second in 0u8..=59,
+    ) {
+        let date = HttpDate::new(DayOfWeek::Monday, day, month, year, hour, minute, second).unwrap();
+        let res = Response::new(status, "Reason").unwrap().auto_date(date.clone());
+        let encoded = res.encode().unwrap();
+
+        let mut decoder = ResponseDecoder::new();
+        decoder.feed(&encoded).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        prop_assert_eq!(head.get_headers("Date").len(), 1);
+        prop_assert_eq!(HttpDate::parse(head.get_header("Date").unwrap()).unwrap(), date);
+    }
+}
@@ -0,0 +1,101 @@
+//! リクエストの再送ポリシーのプロパティテスト (retry.rs)
+
+use core::time::Duration;
+
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::retry::{Failure, Jitter, RetryPolicy};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn jitter() -> impl Strategy<Value = Jitter> {
+    prop_oneof![Just(Jitter::None), Just(Jitter::Full), Just(Jitter::Equal)]
+}
+
+fn policy() -> impl Strategy<Value = RetryPolicy> {
+    (
+        0u64..1_000_000_000,
+        0u64..100_000_000_000,
+        jitter(),
+        0u32..10,
+    )
+        .prop_map(|(base, max, jitter, max_retries)| {
+            RetryPolicy::new()
+                .with_base_delay(Duration::from_nanos(base))
+                .with_max_delay(Duration::from_nanos(max))
+                .with_jitter(jitter)
+                .with_max_retries(max_retries)
+        })
+}
+
+// ========================================
+// バックオフ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_backoff_bounded(
+        base in 0u64..1_000_000_000,
+        max in 0u64..100_000_000_000,
+        jitter in jitter(),
+        attempt in any::<u32>(),
+        random_value in any::<u64>(),
+    ) {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_nanos(base))
+            .with_max_delay(Duration::from_nanos(max))
+            .with_jitter(jitter);
+        let delay = policy.backoff(attempt, random_value);
+        let full = RetryPolicy::new()
+            .with_base_delay(Duration::from_nanos(base))
+            .with_max_delay(Duration::from_nanos(max))
+            .with_jitter(Jitter::None)
+            .backoff(attempt, random_value);
+        prop_assert!(full <= Duration::from_nanos(max));
+        prop_assert!(delay <= full);
+        if jitter == Jitter::Equal {
+            prop_assert!(delay >= full / 2);
+        }
+    }
+
+    #[test]
+    fn prop_backoff_monotonic_without_jitter(
+        base in 0u64..1_000_000_000,
+        max in 0u64..100_000_000_000,
+        attempt in 0u32..64,
+    ) {
+        let policy = RetryPolicy::new()
+            .with_base_delay(Duration::from_nanos(base))
+            .with_max_delay(Duration::from_nanos(max))
+            .with_jitter(Jitter::None);
+        prop_assert!(policy.backoff(attempt, 0) <= policy.backoff(attempt + 1, 0));
+    }
+}
+
+// ========================================
+// 再送の判定
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_decide_respects_max_retries(
+        policy in policy(),
+        method in prop_oneof![Just("GET"), Just("PUT"), Just("POST")],
+        attempt in 0u32..20,
+        random_value in any::<u64>(),
+    ) {
+        let request = Request::new(method, "/").unwrap();
+        for failure in [Failure::NotSent, Failure::NoResponse] {
+            let decision = policy.decide(&request, &failure, attempt, 0, random_value);
+            if decision.is_retry() {
+                prop_assert!(attempt < policy.max_retries());
+                prop_assert_eq!(decision.delay(), Some(policy.backoff(attempt, random_value)));
+            }
+            if method == "POST" && matches!(failure, Failure::NoResponse) {
+                prop_assert!(!decision.is_retry());
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Retry-After ヘッダーの PBT
+
+use core::time::Duration;
+use proptest::prelude::*;
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::retry_after::RetryAfter;
+
+// ========================================
+// ラウンドトリップの PBT
+// ========================================
+
+// delay-seconds は Display -> parse で元に戻る
+proptest! {
+    #[test]
+    fn prop_retry_after_delay_roundtrip(seconds in any::<u64>()) {
+        let retry_after = RetryAfter::Delay(seconds);
+        prop_assert_eq!(RetryAfter::parse(&retry_after.to_string(), 2026).unwrap(), retry_after);
+    }
+}
+
+// HTTP-date は Display -> parse で元に戻る
+proptest! {
+    #[test]
+    fn prop_retry_after_date_roundtrip(seconds in 0u64..253402300800) {
+        let retry_after = RetryAfter::Date(HttpDate::from_unix_seconds(seconds).unwrap());
+        prop_assert_eq!(RetryAfter::parse(&retry_after.to_string(), 2026).unwrap(), retry_after);
+    }
+}
+
+// ========================================
+// 待ち時間の PBT
+// ========================================
+
+// delay-seconds の待ち時間は now によらず一定
+proptest! {
+    #[test]
+    fn prop_retry_after_delay_independent_of_now(seconds in 0u64..=u32::MAX as u64, now in 0u64..=u32::MAX as u64) {
+        let retry_after = RetryAfter::Delay(seconds);
+        prop_assert_eq!(retry_after.delay_from(now), Duration::from_secs(seconds));
+        prop_assert_eq!(retry_after.retry_at(now), now + seconds);
+    }
+}
+
+// HTTP-date の待ち時間は日時までの差 (過去なら 0)
+proptest! {
+    #[test]
+    fn prop_retry_after_date_delay(target in 0u64..253402300800, now in 0u64..253402300800) {
+        let retry_after = RetryAfter::Date(HttpDate::from_unix_seconds(target).unwrap());
+        prop_assert_eq!(retry_after.retry_at(now), target);
+        prop_assert_eq!(retry_after.delay_from(now), Duration::from_secs(target.saturating_sub(now)));
+    }
+}
+
+// 任意の文字列でパニックしない
+proptest! {
+    #[test]
+    fn prop_retry_after_parse_no_panic(input in "\\PC{0,64}") {
+        let _ = RetryAfter::parse(&input, 2026);
+    }
+}
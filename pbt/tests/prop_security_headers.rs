@@ -0,0 +1,106 @@
+//! セキュリティヘッダーのプロパティテスト (security_headers.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::cors::Origin;
+use shiguredo_http11::security_headers::{
+    AllowlistEntry, CrossOriginOpenerPolicy, OpenerPolicy, PermissionsPolicy,
+    StrictTransportSecurity,
+};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn hsts() -> impl Strategy<Value = StrictTransportSecurity> {
+    (any::<u64>(), any::<bool>(), any::<bool>()).prop_map(|(max_age, subdomains, preload)| {
+        let mut hsts = StrictTransportSecurity::new(max_age);
+        if subdomains {
+            hsts = hsts.with_include_subdomains();
+        }
+        if preload {
+            hsts = hsts.with_preload();
+        }
+        hsts
+    })
+}
+
+fn allowlist_entry() -> impl Strategy<Value = AllowlistEntry> {
+    prop_oneof![
+        Just(AllowlistEntry::Any),
+        Just(AllowlistEntry::SelfOrigin),
+        Just(AllowlistEntry::Src),
+        (
+            "https?",
+            "[a-z][a-z0-9-]{0,10}\\.example",
+            proptest::option::of(1u16..)
+        )
+            .prop_map(|(scheme, host, port)| {
+                let origin = match port {
+                    Some(port) => format!("{scheme}://{host}:{port}"),
+                    None => format!("{scheme}://{host}"),
+                };
+                AllowlistEntry::Origin(Origin::parse(&origin).unwrap())
+            }),
+    ]
+}
+
+fn permissions_policy() -> impl Strategy<Value = PermissionsPolicy> {
+    proptest::collection::vec(
+        (
+            "[a-z][a-z0-9-]{0,15}",
+            proptest::collection::vec(allowlist_entry(), 0..4),
+        ),
+        0..5,
+    )
+    .prop_map(|features| {
+        features
+            .into_iter()
+            .fold(PermissionsPolicy::new(), |policy, (feature, allowlist)| {
+                policy.with_feature(&feature, allowlist).unwrap()
+            })
+    })
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// Strict-Transport-Security の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_hsts_roundtrip(hsts in hsts()) {
+        prop_assert_eq!(StrictTransportSecurity::parse(&hsts.to_string()), Ok(hsts));
+    }
+
+    /// Cross-Origin-Opener-Policy の report-to は任意の印字可能 ASCII でラウンドトリップする
+    #[test]
+    fn prop_opener_policy_report_to_roundtrip(endpoint in "[ -~]{0,20}") {
+        let coop = CrossOriginOpenerPolicy::new(OpenerPolicy::SameOrigin)
+            .with_report_to(&endpoint)
+            .unwrap();
+        let parsed = CrossOriginOpenerPolicy::parse(&coop.to_string()).unwrap();
+        prop_assert_eq!(parsed.report_to(), Some(endpoint.as_str()));
+    }
+
+    /// Permissions-Policy の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_permissions_policy_roundtrip(policy in permissions_policy()) {
+        prop_assert_eq!(PermissionsPolicy::parse(&policy.to_string()), Ok(policy));
+    }
+}
+
+// ========================================
+// パニック安全性のテスト
+// ========================================
+
+proptest! {
+    /// 任意の文字列をパースしてもパニックしない
+    #[test]
+    fn prop_security_headers_parse_no_panic(input in "[ -~]{0,64}") {
+        let _ = StrictTransportSecurity::parse(&input);
+        let _ = CrossOriginOpenerPolicy::parse(&input);
+        if let Ok(policy) = PermissionsPolicy::parse(&input) {
+            prop_assert_eq!(PermissionsPolicy::parse(&policy.to_string()), Ok(policy));
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Server-Timing ヘッダーのプロパティテスト (server_timing.rs)
+
+use core::time::Duration;
+
+use proptest::prelude::*;
+use shiguredo_http11::server_timing::{Metric, ServerTiming};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn token() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9!#$%&'*+.^_`|~-]{1,12}"
+}
+
+fn metric() -> impl Strategy<Value = Metric> {
+    (
+        token(),
+        proptest::option::of(0u64..=1_000_000_000_000),
+        proptest::option::of("[ -~]{0,16}"),
+        proptest::collection::vec(("[a-z][a-z0-9]{0,6}", "[ -~]{0,8}"), 0..3),
+    )
+        .prop_map(|(name, micros, description, params)| {
+            let mut metric = Metric::new(&name).unwrap();
+            if let Some(micros) = micros {
+                metric = metric.with_duration(Duration::from_micros(micros));
+            }
+            if let Some(description) = description {
+                metric = metric.with_description(&description).unwrap();
+            }
+            for (name, value) in params {
+                if let Ok(next) = metric.clone().with_param(&name, &value) {
+                    metric = next;
+                }
+            }
+            metric
+        })
+}
+
+fn server_timing() -> impl Strategy<Value = ServerTiming> {
+    proptest::collection::vec(metric(), 0..5).prop_map(|metrics| {
+        metrics
+            .into_iter()
+            .fold(ServerTiming::new(), ServerTiming::with_metric)
+    })
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_server_timing_roundtrip(timing in server_timing()) {
+        let parsed = ServerTiming::parse(&timing.to_string()).unwrap();
+        prop_assert_eq!(parsed, timing);
+    }
+
+    // 複数行を連結してパースした結果は merge と一致する
+    #[test]
+    fn prop_server_timing_merge(a in server_timing(), b in server_timing()) {
+        let joined = [a.to_string(), b.to_string()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        prop_assert_eq!(ServerTiming::parse(&joined).unwrap(), a.merge(b));
+    }
+
+    #[test]
+    fn prop_duration_precision(micros in 0u64..=1_000_000_000_000) {
+        let metric = Metric::new("m").unwrap().with_duration(Duration::from_micros(micros));
+        let parsed = ServerTiming::parse(&metric.to_string()).unwrap();
+        prop_assert_eq!(parsed.get("m").unwrap().duration(), Some(Duration::from_micros(micros)));
+    }
+
+    #[test]
+    fn prop_server_timing_parse_no_panic(input in "[ -~]{0,64}") {
+        if let Ok(timing) = ServerTiming::parse(&input) {
+            prop_assert_eq!(ServerTiming::parse(&timing.to_string()), Ok(timing));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Structured Field Values のプロパティテスト (sfv.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::sfv::{
+    BareItem, Decimal, Dictionary, InnerList, Item, List, ListMember, MAX_INTEGER, MIN_INTEGER,
+};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn key() -> impl Strategy<Value = String> {
+    "[a-z*][a-z0-9_.*-]{0,8}"
+}
+
+fn bare_item() -> impl Strategy<Value = BareItem> {
+    prop_oneof![
+        (MIN_INTEGER..=MAX_INTEGER).prop_map(BareItem::Integer),
+        (MIN_INTEGER..=MAX_INTEGER)
+            .prop_map(|v| BareItem::Decimal(Decimal::from_thousandths(v).unwrap())),
+        "[ -~]{0,16}".prop_map(BareItem::String),
+        "[A-Za-z*][A-Za-z0-9!#$%&'*+.^_`|~:/-]{0,12}".prop_map(BareItem::Token),
+        proptest::collection::vec(any::<u8>(), 0..16).prop_map(BareItem::ByteSequence),
+        any::<bool>().prop_map(BareItem::Boolean),
+        (MIN_INTEGER..=MAX_INTEGER).prop_map(BareItem::Date),
+        ".{0,8}".prop_map(BareItem::DisplayString),
+    ]
+}
+
+fn item() -> impl Strategy<Value = Item> {
+    (
+        bare_item(),
+        proptest::collection::vec((key(), bare_item()), 0..3),
+    )
+        .prop_map(|(bare_item, params)| {
+            params
+                .into_iter()
+                .fold(Item::new(bare_item).unwrap(), |item, (key, value)| {
+                    item.with_param(&key, value).unwrap()
+                })
+        })
+}
+
+fn list_member() -> impl Strategy<Value = ListMember> {
+    prop_oneof![
+        item().prop_map(ListMember::Item),
+        (
+            proptest::collection::vec(item(), 0..3),
+            proptest::collection::vec((key(), bare_item()), 0..2),
+        )
+            .prop_map(|(items, params)| {
+                let inner_list = items
+                    .into_iter()
+                    .fold(InnerList::new(), InnerList::with_item);
+                let inner_list = params
+                    .into_iter()
+                    .fold(inner_list, |inner_list, (key, value)| {
+                        inner_list.with_param(&key, value).unwrap()
+                    });
+                ListMember::InnerList(inner_list)
+            }),
+    ]
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_item_roundtrip(item in item()) {
+        prop_assert_eq!(Item::parse(&item.to_string()), Ok(item));
+    }
+
+    #[test]
+    fn prop_list_roundtrip(members in proptest::collection::vec(list_member(), 0..4)) {
+        let list = members.into_iter().fold(List::new(), List::with_member);
+        prop_assert_eq!(List::parse(&list.to_string()), Ok(list));
+    }
+
+    #[test]
+    fn prop_dictionary_roundtrip(
+        members in proptest::collection::vec((key(), list_member()), 0..4)
+    ) {
+        let dictionary = members
+            .into_iter()
+            .fold(Dictionary::new(), |dictionary, (key, member)| {
+                dictionary.with_member(&key, member).unwrap()
+            });
+        prop_assert_eq!(Dictionary::parse(&dictionary.to_string()), Ok(dictionary));
+    }
+
+    #[test]
+    fn prop_decimal_display(thousandths in MIN_INTEGER..=MAX_INTEGER) {
+        let decimal = Decimal::from_thousandths(thousandths).unwrap();
+        let text = decimal.to_string();
+        let (integer, fraction) = text.trim_start_matches('-').split_once('.').unwrap();
+        prop_assert!(integer.len() <= 12);
+        prop_assert!((1..=3).contains(&fraction.len()));
+        prop_assert_eq!(
+            Item::parse(&text).unwrap().bare_item().as_decimal(),
+            Some(decimal)
+        );
+    }
+
+    #[test]
+    fn prop_parse_no_panic(input in "[ -~\t]{0,64}") {
+        if let Ok(item) = Item::parse(&input) {
+            prop_assert_eq!(Item::parse(&item.to_string()), Ok(item));
+        }
+        if let Ok(list) = List::parse(&input) {
+            prop_assert_eq!(List::parse(&list.to_string()), Ok(list));
+        }
+        if let Ok(dictionary) = Dictionary::parse(&input) {
+            prop_assert_eq!(Dictionary::parse(&dictionary.to_string()), Ok(dictionary));
+        }
+    }
+}
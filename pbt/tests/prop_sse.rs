@@ -0,0 +1,95 @@
+//! Server-Sent Events のプロパティテスト (sse.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::sse::{Event, SseDecoder, encode_comment};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn event() -> impl Strategy<Value = Event> {
+    (
+        "[ -~\\n]{0,40}",
+        proptest::option::of("[ -~]{0,10}"),
+        proptest::option::of("[!-~][ -~]{0,10}"),
+        proptest::option::of(any::<u64>()),
+    )
+        .prop_map(|(data, event, id, retry)| {
+            let mut e = Event::new(&data);
+            if let Some(event) = event {
+                e = e.with_event(&event).unwrap();
+            }
+            if let Some(id) = id {
+                e = e.with_id(&id).unwrap();
+            }
+            if let Some(retry) = retry {
+                e = e.with_retry(retry);
+            }
+            e
+        })
+}
+
+fn decode(chunks: &[&[u8]]) -> Vec<Event> {
+    let mut decoder = SseDecoder::new();
+    let mut events = Vec::new();
+    for chunk in chunks {
+        decoder.feed(chunk).unwrap();
+        while let Some(event) = decoder.next_event().unwrap() {
+            events.push(event);
+        }
+    }
+    events
+}
+
+// ========================================
+// エンコードとデコードの性質
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_sse_roundtrip(events in proptest::collection::vec(event(), 0..5), comment in "[ -~\\n]{0,20}") {
+        let mut stream = Vec::new();
+        for event in &events {
+            stream.extend_from_slice(&encode_comment(&comment));
+            stream.extend_from_slice(&event.encode());
+        }
+        let decoded = decode(&[&stream]);
+        prop_assert_eq!(decoded.len(), events.len());
+
+        let mut last_event_id = None;
+        for (decoded, event) in decoded.iter().zip(&events) {
+            if let Some(id) = event.id() {
+                last_event_id = (!id.is_empty()).then(|| id.to_string());
+            }
+            prop_assert_eq!(decoded.data(), event.data());
+            prop_assert_eq!(decoded.event(), event.event().filter(|e| !e.is_empty()));
+            prop_assert_eq!(decoded.id(), last_event_id.as_deref());
+            prop_assert_eq!(decoded.retry(), event.retry());
+        }
+    }
+
+    #[test]
+    fn prop_sse_chunking_independent(
+        input in proptest::collection::vec(
+            prop_oneof![
+                Just(b'\r'),
+                Just(b'\n'),
+                Just(b':'),
+                Just(b' '),
+                Just(0xEF),
+                proptest::sample::select(b"datevnirya0".to_vec()),
+                any::<u8>(),
+            ],
+            0..200,
+        ),
+        split in any::<prop::sample::Index>(),
+    ) {
+        let at = split.index(input.len() + 1);
+        let (first, second) = input.split_at(at);
+        let whole = decode(&[&input]);
+        let chunked = decode(&[first, second]);
+        prop_assert_eq!(&whole, &chunked);
+        let bytewise: Vec<&[u8]> = input.chunks(1).collect();
+        prop_assert_eq!(whole, decode(&bytewise));
+    }
+}
@@ -0,0 +1,115 @@
+//! 静的コンテンツのレスポンス決定のプロパティテスト (static_content.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::content_type::ContentType;
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::static_content::{PlanStatus, StaticResource};
+use shiguredo_http11::{RequestDecoder, RequestHead};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn range_value() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "bytes=([0-9]{1,3}-[0-9]{0,3}|-[0-9]{1,3})(, ?([0-9]{1,3}-[0-9]{0,3}|-[0-9]{1,3})){0,4}",
+        "[ -~]{0,20}",
+    ]
+}
+
+fn request(method: &'static str, headers: &[(&str, &str)]) -> Option<RequestHead> {
+    let mut input = format!("{method} / HTTP/1.1\r\nHost: example.com\r\n");
+    for (name, value) in headers {
+        input.push_str(&format!("{name}: {value}\r\n"));
+    }
+    input.push_str("\r\n");
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(input.as_bytes()).ok()?;
+    decoder.decode_headers().ok()?.map(|(head, _)| head)
+}
+
+// ========================================
+// Range の性質
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_static_content_range_plan(
+        length in 0u64..2000,
+        range in range_value(),
+        with_content_type in any::<bool>(),
+        random_value in any::<u64>(),
+    ) {
+        let Some(head) = request("GET", &[("Range", &range)]) else {
+            return Ok(());
+        };
+        let mut resource = StaticResource::new(length).with_etag(EntityTag::strong("v1").unwrap());
+        if with_content_type {
+            resource = resource.with_content_type(ContentType::new("text", "plain"));
+        }
+        let plan = resource.plan(&head, random_value);
+        if !plan.has_body() {
+            prop_assert!(plan.to_response().unwrap().encode().is_ok());
+        }
+
+        match plan.status() {
+            PlanStatus::Ok => {
+                let expected = length.to_string();
+                prop_assert_eq!(plan.get_header("Content-Length"), Some(expected.as_str()));
+                prop_assert!(plan.ranges().is_empty());
+            }
+            PlanStatus::PartialContent => {
+                prop_assert!(!plan.ranges().is_empty());
+                for window in plan.ranges().windows(2) {
+                    // 昇順かつ重複・隣接しない
+                    prop_assert!(window[0].1 + 1 < window[1].0);
+                }
+                for &(start, end) in plan.ranges() {
+                    prop_assert!(start <= end && end < length);
+                }
+                let content: Vec<u8> = (0..length).map(|i| i as u8).collect();
+                let body = match plan.byte_ranges_builder() {
+                    Some(mut builder) => {
+                        prop_assert!(plan.ranges().len() > 1);
+                        for &(start, end) in plan.ranges() {
+                            builder = builder.part(start, end, &content[start as usize..=end as usize]);
+                        }
+                        builder.build()
+                    }
+                    None => {
+                        let (start, end) = plan.ranges()[0];
+                        content[start as usize..=end as usize].to_vec()
+                    }
+                };
+                let expected = body.len().to_string();
+                prop_assert_eq!(plan.get_header("Content-Length"), Some(expected.as_str()));
+                prop_assert!(plan.to_response().unwrap().body(body).encode().is_ok());
+            }
+            PlanStatus::RangeNotSatisfiable => {
+                let expected = format!("bytes */{length}");
+                prop_assert_eq!(plan.get_header("Content-Range"), Some(expected.as_str()));
+            }
+            status => prop_assert!(false, "unexpected status: {:?}", status),
+        }
+    }
+
+    #[test]
+    fn prop_static_content_head_matches_get(
+        length in 0u64..2000,
+        range in range_value(),
+    ) {
+        let (Some(get), Some(head)) = (
+            request("GET", &[]),
+            request("HEAD", &[("Range", &range)]),
+        ) else {
+            return Ok(());
+        };
+        let resource = StaticResource::new(length);
+        let get_plan = resource.plan(&get, 0);
+        let head_plan = resource.plan(&head, 0);
+        prop_assert_eq!(head_plan.status(), PlanStatus::Ok);
+        prop_assert_eq!(head_plan.headers(), get_plan.headers());
+        prop_assert!(!head_plan.has_body());
+        prop_assert!(head_plan.to_response().unwrap().encode().is_ok());
+    }
+}
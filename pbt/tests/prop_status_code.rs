@@ -4,7 +4,8 @@
 //! `StatusCode::class` が `from_status_code` と整合することを検証する。
 
 use proptest::prelude::*;
-use shiguredo_http11::{StatusClass, StatusCode};
+use shiguredo_http11::status_code::reason_phrase_for;
+use shiguredo_http11::{EncodeError, Response, StatusClass, StatusCode};
 
 proptest! {
     /// 任意の u16 に対する from_status_code のパーティション性
@@ -32,4 +33,43 @@ proptest! {
             prop_assert_eq!(sc.class(), expected);
         }
     }
+
+    /// reason_phrase_for は登録済みコードで canonical reason を、それ以外で空文字列を返す
+    #[test]
+    fn prop_reason_phrase_for_matches_from_code(code: u16) {
+        let expected = StatusCode::from_code(code).map(|s| s.canonical_reason()).unwrap_or("");
+        prop_assert_eq!(reason_phrase_for(code), expected);
+    }
+
+    /// Response::from_status は 100..=599 で成功し、reason_phrase_for と一致する
+    #[test]
+    fn prop_response_from_status(code: u16) {
+        match Response::from_status(code) {
+            Ok(res) => {
+                prop_assert!((100..=599).contains(&code));
+                prop_assert_eq!(res.status_code(), code);
+                prop_assert_eq!(res.reason_phrase(), reason_phrase_for(code));
+                prop_assert!(res.encode().is_ok());
+            }
+            Err(e) => {
+                prop_assert!(!(100..=599).contains(&code));
+                prop_assert_eq!(e, EncodeError::InvalidStatusCode { code });
+            }
+        }
+    }
+
+    /// 3 桁の文字列の FromStr は from_code と一致し、分類ヘルパーは class() と一致する
+    #[test]
+    fn prop_status_code_from_str(code in 100u16..=999) {
+        let parsed = code.to_string().parse::<StatusCode>();
+        prop_assert_eq!(parsed.clone().ok(), StatusCode::from_code(code));
+        if let Ok(sc) = parsed {
+            prop_assert!(sc == code);
+            prop_assert_eq!(sc.is_informational(), sc.class() == StatusClass::Informational);
+            prop_assert_eq!(sc.is_success(), sc.class() == StatusClass::Successful);
+            prop_assert_eq!(sc.is_redirection(), sc.class() == StatusClass::Redirection);
+            prop_assert_eq!(sc.is_client_error(), sc.class() == StatusClass::ClientError);
+            prop_assert_eq!(sc.is_server_error(), sc.class() == StatusClass::ServerError);
+        }
+    }
 }
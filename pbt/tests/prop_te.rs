@@ -0,0 +1,91 @@
+//! TE ヘッダーのプロパティテスト (te.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::accept::QValue;
+use shiguredo_http11::te::Te;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn coding() -> impl Strategy<Value = String> {
+    "[a-z0-9!#$%&'*+.^_`|~-]{1,10}".prop_filter("reserved", |s| s != "chunked" && s != "trailers")
+}
+
+fn qvalue() -> impl Strategy<Value = QValue> {
+    (0u16..=1000).prop_map(|v| {
+        let s = if v == 1000 {
+            "1".to_string()
+        } else {
+            format!("0.{:03}", v)
+        };
+        QValue::parse(&s).unwrap()
+    })
+}
+
+fn te() -> impl Strategy<Value = Te> {
+    (
+        any::<bool>(),
+        proptest::collection::vec((coding(), qvalue()), 0..5),
+    )
+        .prop_map(|(trailers, codings)| {
+            let mut te = Te::new();
+            if trailers {
+                te = te.with_trailers();
+            }
+            for (coding, q) in codings {
+                te = te.with_coding(&coding, q).unwrap();
+            }
+            te
+        })
+}
+
+// ========================================
+// ラウンドトリップ
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_te_roundtrip(te in te()) {
+        let parsed = Te::parse(&te.to_string()).unwrap();
+        prop_assert_eq!(parsed, te);
+    }
+
+    #[test]
+    fn prop_te_chunked_always_accepted(te in te()) {
+        prop_assert!(te.accepts("chunked"));
+    }
+}
+
+// ========================================
+// ネゴシエーション
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_te_negotiate_is_acceptable(
+        te in te(),
+        available in proptest::collection::vec(coding(), 0..5),
+    ) {
+        let available: Vec<&str> = available.iter().map(String::as_str).collect();
+        match te.negotiate(&available) {
+            Some(selected) => {
+                prop_assert!(te.accepts(selected));
+                let best = te.quality(selected).unwrap();
+                for candidate in &available {
+                    if let Some(q) = te.quality(candidate) {
+                        prop_assert!(q <= best);
+                    }
+                }
+            }
+            None => prop_assert!(available.iter().all(|c| !te.accepts(c))),
+        }
+    }
+
+    #[test]
+    fn prop_te_parse_no_panic(input in "[ -~]{0,64}") {
+        if let Ok(te) = Te::parse(&input) {
+            prop_assert_eq!(Te::parse(&te.to_string()), Ok(te));
+        }
+    }
+}
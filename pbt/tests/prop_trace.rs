@@ -0,0 +1,85 @@
+//! ワイヤーレベルのトレースのプロパティテスト (trace.rs)
+
+use std::sync::{Arc, Mutex};
+
+use proptest::prelude::*;
+use shiguredo_http11::ResponseDecoder;
+use shiguredo_http11::trace::WireEvent;
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn header() -> impl Strategy<Value = (String, String)> {
+    ("X-[A-Za-z0-9]{1,10}", "[ -~]{0,20}")
+}
+
+fn chunk() -> impl Strategy<Value = (Vec<u8>, String)> {
+    (
+        proptest::collection::vec(any::<u8>(), 1..32),
+        prop_oneof![
+            Just(String::new()),
+            "[a-z]{1,5}".prop_map(|n| format!(";{n}"))
+        ],
+    )
+}
+
+/// レスポンスをデコードし、(offset, raw) と種別の一覧を返す
+fn trace(input: &[u8], split: usize) -> Vec<(&'static str, u64, Vec<u8>)> {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let captured = log.clone();
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_wire_observer(Box::new(move |event: WireEvent<'_>| {
+        let entry = match event {
+            WireEvent::StartLine { offset, raw } => ("start-line", offset, raw.to_vec()),
+            WireEvent::Header { offset, raw, .. } => ("header", offset, raw.to_vec()),
+            WireEvent::Chunk { offset, raw, .. } => ("chunk", offset, raw.to_vec()),
+            WireEvent::Trailer { offset, raw, .. } => ("trailer", offset, raw.to_vec()),
+            _ => return,
+        };
+        captured.lock().unwrap().push(entry);
+    }));
+    for part in input.chunks(split) {
+        decoder.feed(part).unwrap();
+        decoder.decode().unwrap();
+    }
+    log.lock().unwrap().clone()
+}
+
+// ========================================
+// デコーダーのイベント
+// ========================================
+
+proptest! {
+    #[test]
+    fn prop_trace_raw_matches_input(
+        headers in proptest::collection::vec(header(), 0..8),
+        chunks in proptest::collection::vec(chunk(), 0..4),
+        split in 1usize..64,
+    ) {
+        let mut input = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n".to_vec();
+        for (name, value) in &headers {
+            input.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        input.extend_from_slice(b"\r\n");
+        for (data, ext) in &chunks {
+            input.extend_from_slice(
+                &shiguredo_http11::encode_chunk_with_ext(data, ext.as_bytes()).unwrap(),
+            );
+        }
+        input.extend_from_slice(b"0\r\n\r\n");
+
+        let entries = trace(&input, split);
+        // start-line + ヘッダー行 + チャンク行 (last-chunk を含む)
+        prop_assert_eq!(entries.len(), 2 + headers.len() + chunks.len() + 1);
+        for (kind, offset, raw) in &entries {
+            // raw は入力の offset 位置のバイト列で、直後に CRLF が続く
+            let start = *offset as usize;
+            let end = start + raw.len();
+            prop_assert_eq!(&input[start..end], raw.as_slice(), "{}", kind);
+            prop_assert_eq!(&input[end..end + 2], b"\r\n");
+        }
+        // 分割位置によらず同じイベントを通知する
+        prop_assert_eq!(entries, trace(&input, input.len()));
+    }
+}
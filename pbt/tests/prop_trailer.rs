@@ -1,5 +1,7 @@
 //! Trailer ヘッダーのプロパティテスト (trailer.rs)
 
+use std::borrow::Cow;
+
 use proptest::prelude::*;
 use shiguredo_http11::trailer::{Trailer, is_prohibited_trailer_field};
 
@@ -45,3 +47,24 @@ proptest! {
         prop_assert_eq!(parsed, reparsed);
     }
 }
+
+// ビルダーで追加したフィールドはパースした結果と一致する
+proptest! {
+    #[test]
+    fn prop_trailer_builder_matches_parse(
+        tokens in proptest::collection::vec(allowed_trailer_token(8), 1..5)
+    ) {
+        let mut trailer = Trailer::new();
+        for token in &tokens {
+            trailer = trailer.with_field(token).unwrap();
+        }
+        let parsed = Trailer::parse(&trailer.to_string()).unwrap();
+        prop_assert_eq!(&parsed, &trailer);
+
+        let trailers: Vec<(Cow<'static, str>, String)> = tokens
+            .iter()
+            .map(|token| (Cow::Owned(token.to_ascii_uppercase()), String::new()))
+            .collect();
+        prop_assert!(trailer.validate_fields(&trailers).is_ok());
+    }
+}
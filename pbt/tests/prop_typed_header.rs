@@ -0,0 +1,69 @@
+//! 型付きヘッダーのプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::cache::CacheControl;
+use shiguredo_http11::vary::Vary;
+use shiguredo_http11::{HttpHead, Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn cache_control() -> impl Strategy<Value = CacheControl> {
+    (
+        proptest::option::of(0u64..1_000_000),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(|(max_age, no_cache, no_store, public)| {
+            let mut cc = CacheControl::new();
+            if let Some(seconds) = max_age {
+                cc = cc.with_max_age(seconds);
+            }
+            if no_cache {
+                cc = cc.with_no_cache();
+            }
+            if no_store {
+                cc = cc.with_no_store();
+            }
+            if public {
+                cc = cc.with_public();
+            }
+            cc
+        })
+}
+
+fn field_name() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9-]{0,15}"
+}
+
+// ========================================
+// プロパティテスト
+// ========================================
+
+proptest! {
+    // set_typed した値はエンコード・デコードを経ても get_typed で同じ値になる
+    #[test]
+    fn prop_set_typed_get_typed_roundtrip(cc in cache_control()) {
+        let mut response = Response::with_status(StatusCode::OK);
+        response.set_typed(&cc).unwrap();
+        prop_assert_eq!(response.get_typed::<CacheControl>().unwrap().unwrap(), cc.clone());
+
+        let mut decoder = ResponseDecoder::new();
+        decoder.feed(&response.body(Vec::new()).encode().unwrap()).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        prop_assert_eq!(head.get_typed::<CacheControl>().unwrap().unwrap(), cc);
+    }
+
+    // リスト形式のヘッダーは複数行に分けても 1 行と同じ値になる
+    #[test]
+    fn prop_get_typed_combines_lines(names in proptest::collection::vec(field_name(), 1..5)) {
+        let mut response = Response::with_status(StatusCode::OK);
+        for name in &names {
+            response.add_header("Vary", name.as_str()).unwrap();
+        }
+        let vary = response.get_typed::<Vary>().unwrap().unwrap();
+        prop_assert_eq!(vary, Vary::parse(&names.join(", ")).unwrap());
+    }
+}
@@ -1,7 +1,7 @@
 //! Upgrade ヘッダーのプロパティテスト (upgrade.rs)
 
 use proptest::prelude::*;
-use shiguredo_http11::upgrade::Upgrade;
+use shiguredo_http11::upgrade::{Http2Settings, Upgrade};
 
 // HTTP トークン文字
 fn token_char() -> impl Strategy<Value = char> {
@@ -43,3 +43,16 @@ proptest! {
         prop_assert!(parsed.has_protocol(&first_protocol));
     }
 }
+
+// HTTP2-Settings のラウンドトリップ
+proptest! {
+    #[test]
+    fn prop_http2_settings_roundtrip(entries in proptest::collection::vec(any::<[u8; 6]>(), 0..8)) {
+        let payload = entries.concat();
+        let settings = Http2Settings::new(payload.clone()).unwrap();
+        let encoded = settings.to_string();
+        prop_assert!(!encoded.contains('='));
+        let parsed = Http2Settings::parse(&encoded).unwrap();
+        prop_assert_eq!(parsed.payload(), payload.as_slice());
+    }
+}
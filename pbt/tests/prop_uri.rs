@@ -2,8 +2,8 @@
 
 use proptest::prelude::*;
 use shiguredo_http11::uri::{
-    Uri, normalize, percent_decode, percent_decode_bytes, percent_encode, percent_encode_path,
-    percent_encode_query, resolve,
+    Uri, UriBuilder, normalize, parse_query, percent_decode, percent_decode_bytes, percent_encode,
+    percent_encode_path, percent_encode_query, remove_dot_segments, resolve, serialize_query,
 };
 
 // ========================================
@@ -248,6 +248,7 @@ proptest! {
         let uri = Uri::parse(&uri_str).unwrap();
 
         prop_assert_eq!(uri.host(), Some(addr.as_str()));
+        prop_assert_eq!(uri.hostname(), Some(&addr[1..addr.len() - 1]));
         prop_assert_eq!(uri.port(), Some(pt));
     }
 }
@@ -265,6 +266,7 @@ proptest! {
         // authority() は userinfo を含む
         let expected_auth = format!("{}@{}", user, h);
         prop_assert_eq!(uri.authority(), Some(expected_auth.as_str()));
+        prop_assert_eq!(uri.userinfo(), Some(user.as_str()));
     }
 }
 
@@ -348,8 +350,10 @@ proptest! {
         let uri = Uri::parse(&uri_str).unwrap();
 
         prop_assert_eq!(uri.scheme(), Some(s.as_str()));
+        prop_assert_eq!(uri.userinfo(), Some(user.as_str()));
         prop_assert_eq!(uri.host(), Some(h.as_str()));
         prop_assert_eq!(uri.port(), Some(pt));
+        prop_assert_eq!(uri.port_or_default(), Some(pt));
         prop_assert_eq!(uri.path(), p.as_str());
         prop_assert_eq!(uri.query(), Some(q.as_str()));
         prop_assert_eq!(uri.fragment(), Some(f.as_str()));
@@ -595,3 +599,127 @@ proptest! {
         prop_assert!(n1.scheme().is_none(), "scheme が新規に注入されないこと");
     }
 }
+
+// dot-segment を含むパス
+fn dotted_path() -> impl Strategy<Value = String> {
+    proptest::collection::vec(
+        prop_oneof![
+            Just(".".to_string()),
+            Just("..".to_string()),
+            Just("".to_string()),
+            "[a-z]{1,4}",
+        ],
+        0..8,
+    )
+    .prop_map(|segments| format!("/{}", segments.join("/")))
+}
+
+// remove_dot_segments は冪等で、結果に dot-segment を残さない
+proptest! {
+    #[test]
+    fn prop_remove_dot_segments(p in dotted_path()) {
+        let removed = remove_dot_segments(&p);
+        prop_assert!(removed.starts_with('/'));
+        prop_assert_eq!(remove_dot_segments(&removed), removed.clone());
+        for segment in removed.split('/') {
+            prop_assert!(segment != "." && segment != "..");
+        }
+    }
+}
+
+// join は resolve と同じ結果になり、解決結果に dot-segment を残さない
+proptest! {
+    #[test]
+    fn prop_uri_join(p in dotted_path(), relative in any::<bool>()) {
+        let base = Uri::parse("http://example.com/a/b/c?q").unwrap();
+        let reference = if relative { p.trim_start_matches('/').to_string() } else { p };
+        // "//" で始まる参照は network-path reference になるため対象外
+        if !reference.starts_with("//")
+            && let Ok(parsed) = Uri::parse(&reference)
+        {
+            let joined = base.join(&reference).unwrap();
+            prop_assert_eq!(&joined, &resolve(&base, &parsed).unwrap());
+            prop_assert_eq!(joined.host(), Some("example.com"));
+            for segment in joined.path().split('/') {
+                prop_assert!(segment != "." && segment != "..");
+            }
+        }
+    }
+}
+
+// ========================================
+// クエリ文字列のテスト
+// ========================================
+
+proptest! {
+    /// serialize_query() の結果を parse_query() すると元の組に戻る
+    #[test]
+    fn prop_query_roundtrip(
+        pairs in proptest::collection::vec(("\\PC{0,8}", "\\PC{0,8}"), 0..8)
+    ) {
+        let query = serialize_query(pairs.clone());
+        prop_assert_eq!(parse_query(&query).unwrap(), pairs);
+        let uri = Uri::parse(&format!("/?{query}")).unwrap();
+        prop_assert_eq!(uri.query(), Some(query.as_str()));
+    }
+}
+
+proptest! {
+    /// parse_query() は任意の入力でパニックしない
+    #[test]
+    fn prop_parse_query_no_panic(s in "\\PC{0,64}") {
+        let _ = parse_query(&s);
+    }
+}
+
+// ========================================
+// UriBuilder のテスト
+// ========================================
+
+proptest! {
+    /// 任意の値から組み立てた URI はパースでき、各コンポーネントをデコードすると元の値に戻る
+    #[test]
+    fn prop_uri_builder_components(
+        s in scheme(),
+        h in hostname(),
+        pt in proptest::option::of(port()),
+        segments in proptest::collection::vec("\\PC{0,8}", 0..4),
+        pairs in proptest::collection::vec(("\\PC{0,8}", "\\PC{0,8}"), 0..4),
+        fragment in proptest::option::of("\\PC{0,16}"),
+    ) {
+        let mut builder = UriBuilder::new().with_scheme(&s).unwrap().with_host(&h).unwrap();
+        if let Some(pt) = pt {
+            builder = builder.with_port(pt);
+        }
+        for segment in &segments {
+            builder = builder.with_path_segment(segment);
+        }
+        for (key, value) in &pairs {
+            builder = builder.with_query_pair(key, value);
+        }
+        if let Some(fragment) = &fragment {
+            builder = builder.with_fragment(fragment);
+        }
+        let uri = builder.build().unwrap();
+
+        prop_assert_eq!(Uri::parse(uri.as_str()).unwrap(), uri.clone());
+        let lower_scheme = s.to_ascii_lowercase();
+        prop_assert_eq!(uri.scheme(), Some(lower_scheme.as_str()));
+        prop_assert_eq!(uri.host(), Some(h.as_str()));
+        prop_assert_eq!(uri.port(), pt);
+
+        let decoded: Vec<String> = uri
+            .path()
+            .split('/')
+            .skip(1)
+            .map(|segment| percent_decode(segment).unwrap())
+            .collect();
+        prop_assert_eq!(decoded, segments);
+
+        let decoded_pairs = uri.query().map(|q| parse_query(q).unwrap()).unwrap_or_default();
+        prop_assert_eq!(decoded_pairs, pairs);
+
+        let decoded_fragment = uri.fragment().map(|f| percent_decode(f).unwrap());
+        prop_assert_eq!(decoded_fragment, fragment);
+    }
+}
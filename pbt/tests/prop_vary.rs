@@ -1,7 +1,8 @@
 //! Vary ヘッダーのプロパティテスト (vary.rs)
 
 use proptest::prelude::*;
-use shiguredo_http11::vary::Vary;
+use shiguredo_http11::Request;
+use shiguredo_http11::vary::{Vary, cache_key};
 
 // HTTP トークン文字
 fn token_char() -> impl Strategy<Value = char> {
@@ -30,3 +31,27 @@ proptest! {
         prop_assert_eq!(parsed, reparsed);
     }
 }
+
+// Accept-Encoding の要素の並び順と空白によらず二次キーが一致する
+proptest! {
+    #[test]
+    fn prop_vary_cache_key_list_order(
+        codings in proptest::collection::vec(token_string(8), 1..5),
+        seed in any::<u64>(),
+    ) {
+        let vary = Vary::parse("Accept-Encoding").unwrap();
+        let mut shuffled = codings.clone();
+        let len = shuffled.len();
+        shuffled.rotate_left((seed as usize) % len);
+
+        let a = Request::new("GET", "/")
+            .unwrap()
+            .header("Accept-Encoding", codings.join(", "))
+            .unwrap();
+        let b = Request::new("GET", "/")
+            .unwrap()
+            .header("Accept-Encoding", shuffled.join(" ,").to_uppercase())
+            .unwrap();
+        prop_assert_eq!(cache_key(&vary, &a), cache_key(&vary, &b));
+    }
+}
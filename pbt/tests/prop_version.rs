@@ -0,0 +1,68 @@
+//! プロトコルバージョン型のプロパティテスト
+
+use proptest::prelude::*;
+use shiguredo_http11::version::{HttpVersion, HttpVersionError};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn protocol_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("HTTP".to_string()),
+        Just("RTSP".to_string()),
+        "[A-Za-z][A-Za-z0-9!#$%&'*+.^_`|~-]{0,7}",
+    ]
+}
+
+fn protocol_version() -> impl Strategy<Value = String> {
+    (protocol_name(), 0u32..20, 0u32..20)
+        .prop_map(|(name, major, minor)| format!("{name}/{major}.{minor}"))
+}
+
+// ========================================
+// プロパティテスト
+// ========================================
+
+proptest! {
+    // parse した値の as_str / Display は入力と一致する
+    #[test]
+    fn prop_parse_roundtrip(s in protocol_version()) {
+        let version = HttpVersion::parse(&s).unwrap();
+        prop_assert_eq!(version.as_str(), s.as_str());
+        prop_assert_eq!(version.to_string(), s.clone());
+        prop_assert_eq!(String::from(version), s);
+    }
+
+    // parse_strict は "HTTP/" DIGIT "." DIGIT のときのみ成功する
+    #[test]
+    fn prop_parse_strict_grammar(s in protocol_version()) {
+        let bytes = s.as_bytes();
+        let expected = bytes.len() == 8
+            && s.starts_with("HTTP/")
+            && bytes[5].is_ascii_digit()
+            && bytes[6] == b'.'
+            && bytes[7].is_ascii_digit();
+        match HttpVersion::parse_strict(&s) {
+            Ok(version) => {
+                prop_assert!(expected);
+                prop_assert!(version.is_http());
+            }
+            Err(e) => {
+                prop_assert!(!expected);
+                prop_assert_eq!(e, HttpVersionError::NotHttpVersion);
+            }
+        }
+    }
+
+    // HTTP/1.0 / HTTP/1.1 は専用バリアント、それ以外は Other になる
+    #[test]
+    fn prop_known_variants(s in protocol_version()) {
+        let version = HttpVersion::parse(&s).unwrap();
+        match s.as_str() {
+            "HTTP/1.0" => prop_assert_eq!(version, HttpVersion::Http10),
+            "HTTP/1.1" => prop_assert_eq!(version, HttpVersion::Http11),
+            _ => prop_assert_eq!(version, HttpVersion::Other(s.clone())),
+        }
+    }
+}
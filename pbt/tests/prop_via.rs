@@ -0,0 +1,93 @@
+//! Via ヘッダーのプロパティテスト (via.rs)
+
+use proptest::prelude::*;
+use shiguredo_http11::Request;
+use shiguredo_http11::via::{Via, ViaEntry, append_via, detect_loop};
+
+// ========================================
+// Strategy 定義
+// ========================================
+
+fn received_protocol() -> impl Strategy<Value = String> {
+    prop_oneof!["[0-9]\\.[0-9]", "[A-Za-z][A-Za-z0-9-]{0,8}/[0-9]{1,2}",]
+}
+
+fn received_by() -> impl Strategy<Value = String> {
+    (
+        prop_oneof!["[a-z][a-z0-9.-]{0,15}", "\\[[0-9a-f:]{2,16}\\]"],
+        proptest::option::of(any::<u16>()),
+    )
+        .prop_map(|(name, port)| match port {
+            Some(port) => format!("{name}:{port}"),
+            None => name,
+        })
+}
+
+// 入れ子と quoted-pair を含む comment の中身
+fn comment() -> impl Strategy<Value = String> {
+    let leaf = "[ -'*-\\[\\]-~]{0,8}".prop_map(|s| s);
+    leaf.prop_recursive(3, 16, 3, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|s| format!("({s})")),
+            (inner.clone(), inner).prop_map(|(a, b)| format!("{a}\\({b}")),
+        ]
+    })
+}
+
+fn entry() -> impl Strategy<Value = ViaEntry> {
+    (
+        received_protocol(),
+        received_by(),
+        proptest::option::of(comment()),
+    )
+        .prop_map(|(protocol, by, comment)| {
+            let entry = ViaEntry::new(&protocol, &by).unwrap();
+            match comment {
+                Some(comment) => entry.with_comment(&comment).unwrap(),
+                None => entry,
+            }
+        })
+}
+
+// ========================================
+// ラウンドトリップのテスト
+// ========================================
+
+proptest! {
+    /// Via の Display 結果をパースすると元に戻る
+    #[test]
+    fn prop_via_roundtrip(entries in proptest::collection::vec(entry(), 0..5)) {
+        let mut via = Via::new();
+        for entry in entries {
+            via.push(entry);
+        }
+        prop_assert_eq!(Via::parse(&via.to_string()).unwrap(), via);
+    }
+
+    /// append_via で追加した pseudonym はループとして検出される
+    #[test]
+    fn prop_append_via_detects_loop(
+        entries in proptest::collection::vec(entry(), 1..5),
+        own in "[a-z]{1,8}-own",
+    ) {
+        let mut request = Request::new("GET", "/").unwrap();
+        for entry in &entries {
+            request = append_via(request, entry).unwrap();
+        }
+        prop_assert_eq!(detect_loop(&request, &own), Ok(false));
+
+        let request = append_via(request, &ViaEntry::new("1.1", &own).unwrap()).unwrap();
+        prop_assert_eq!(detect_loop(&request, &own.to_ascii_uppercase()), Ok(true));
+
+        let via = Via::from_message(&request).unwrap().unwrap();
+        prop_assert_eq!(&via.entries()[..entries.len()], entries.as_slice());
+    }
+}
+
+proptest! {
+    /// 任意の入力でパニックしない
+    #[test]
+    fn prop_via_parse_no_panic(input in "[ -~\t]{0,64}") {
+        let _ = Via::parse(&input);
+    }
+}
@@ -4,6 +4,12 @@
 //!
 //! RFC 9110 に基づいた Accept / Accept-Charset / Accept-Encoding / Accept-Language のパースを提供します。
 //!
+//! 提供可能な表現の選択 (プロアクティブネゴシエーション) も提供します。
+//!
+//! - `negotiate()`: Accept によるメディアタイプの選択 (RFC 9110 Section 12.5.1)
+//! - `negotiate_encoding()`: Accept-Encoding によるコンテンツコーディングの選択 (RFC 9110 Section 12.5.3)
+//! - `filter_languages()` / `lookup_language()`: Accept-Language による言語の選択 (RFC 4647)
+//!
 //! ## 使い方
 //!
 //! ```rust
@@ -20,6 +26,8 @@ use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::content_type::ContentType;
+use crate::typed_header::TypedHeader;
 use crate::validate::{
     QuotedStringError, escape_quotes, is_token_char, is_valid_language_tag, is_valid_token,
     parse_quoted_string, split_with_quotes, trim_ows,
@@ -185,6 +193,25 @@ impl Accept {
     pub fn items(&self) -> &[MediaRange] {
         &self.items
     }
+
+    /// メディアタイプに対する q 値を取得 (RFC 9110 Section 12.5.1)
+    ///
+    /// 一致するメディアレンジのうち最も具体的なものの q 値を返す。
+    /// 具体性は `type/subtype;param` > `type/subtype` > `type/*` > `*/*` の順で、
+    /// パラメータ付きのレンジは指定したパラメータをすべて含むメディアタイプにのみ一致する。
+    /// 一致するレンジがない場合、または `media_type` がメディアタイプとして不正な場合は `None`。
+    pub fn quality(&self, media_type: &str) -> Option<QValue> {
+        let content_type = ContentType::parse(media_type).ok()?;
+        self.items
+            .iter()
+            .filter_map(|range| range.specificity(&content_type).map(|s| (s, range.q)))
+            // 同じ具体性のレンジが複数ある場合は先に現れたものを使う
+            .fold(None, |best: Option<(usize, QValue)>, (s, q)| match best {
+                Some((best_s, _)) if best_s >= s => best,
+                _ => Some((s, q)),
+            })
+            .map(|(_, q)| q)
+    }
 }
 
 impl fmt::Display for Accept {
@@ -194,6 +221,57 @@ impl fmt::Display for Accept {
     }
 }
 
+impl TypedHeader for Accept {
+    const NAME: &'static str = "Accept";
+    type Error = AcceptError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// 提供可能なメディアタイプから Accept に最も適合するものを選ぶ (RFC 9110 Section 12.5.1)
+///
+/// 各候補の q 値を `Accept::quality()` で求め、q 値が最大の候補を返す。
+/// q 値が同じ場合は `available` で先に現れた候補 (サーバーの優先順) を選ぶ。
+/// q=0 の候補と、どのメディアレンジにも一致しない候補は選ばない。
+/// Accept が空リストの場合は制約がないものとして先頭の候補を返す。
+///
+/// 適合する候補がない場合は `None` を返す。406 Not Acceptable を返すか、
+/// Accept を無視してデフォルトの表現を返すかは呼び出し側が決める (RFC 9110 Section 12.5.1)。
+///
+/// ```rust
+/// use shiguredo_http11::accept::{Accept, negotiate};
+///
+/// let accept = Accept::parse("text/*;q=0.5, application/json").unwrap();
+/// assert_eq!(negotiate(&accept, &["text/html", "application/json"]), Some("application/json"));
+///
+/// let accept = Accept::parse("image/*").unwrap();
+/// assert_eq!(negotiate(&accept, &["text/html", "application/json"]), None);
+/// ```
+pub fn negotiate<'a>(accept: &Accept, available: &[&'a str]) -> Option<&'a str> {
+    if accept.items.is_empty() {
+        return available.first().copied();
+    }
+    let mut best: Option<(&'a str, QValue)> = None;
+    for &candidate in available {
+        let Some(q) = accept.quality(candidate) else {
+            continue;
+        };
+        if q.value() == 0 {
+            continue;
+        }
+        if best.is_none_or(|(_, best_q)| q > best_q) {
+            best = Some((candidate, q));
+        }
+    }
+    best.map(|(candidate, _)| candidate)
+}
+
 /// Accept メディアレンジ
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MediaRange {
@@ -223,6 +301,33 @@ impl MediaRange {
     pub fn qvalue(&self) -> QValue {
         self.q
     }
+
+    /// メディアタイプに一致する場合に具体性を返す
+    ///
+    /// `*/*` は 0、`type/*` は 1、`type/subtype` は 2 にパラメータ数を加えた値。
+    fn specificity(&self, content_type: &ContentType) -> Option<usize> {
+        if self.media_type == "*" {
+            return Some(0);
+        }
+        if !self
+            .media_type
+            .eq_ignore_ascii_case(content_type.media_type())
+        {
+            return None;
+        }
+        if self.subtype == "*" {
+            return Some(1);
+        }
+        if !self.subtype.eq_ignore_ascii_case(content_type.subtype()) {
+            return None;
+        }
+        let params_match = self.parameters.iter().all(|(name, value)| {
+            content_type
+                .parameter(name)
+                .is_some_and(|v| v.eq_ignore_ascii_case(value))
+        });
+        params_match.then_some(2 + self.parameters.len())
+    }
 }
 
 impl fmt::Display for MediaRange {
@@ -274,6 +379,19 @@ impl fmt::Display for AcceptCharset {
     }
 }
 
+impl TypedHeader for AcceptCharset {
+    const NAME: &'static str = "Accept-Charset";
+    type Error = AcceptError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Accept-Charset レンジ
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CharsetRange {
@@ -323,6 +441,85 @@ impl AcceptEncoding {
     pub fn items(&self) -> &[EncodingRange] {
         &self.items
     }
+
+    /// コンテンツコーディングに対する q 値を取得 (RFC 9110 Section 12.5.3)
+    ///
+    /// 同じコーディングのレンジがあればその q 値を、なければ `*` の q 値を返す。
+    /// `x-gzip` / `x-compress` は `gzip` / `compress` と同じものとして扱う (RFC 9110 Section 8.4.1)。
+    /// 一致するレンジがない場合は `None`。
+    pub fn quality(&self, coding: &str) -> Option<QValue> {
+        let coding = normalize_coding(coding);
+        self.items
+            .iter()
+            .find(|range| normalize_coding(&range.coding).eq_ignore_ascii_case(coding))
+            .or_else(|| self.items.iter().find(|range| range.coding == "*"))
+            .map(|range| range.q)
+    }
+}
+
+/// 提供可能なコンテンツコーディングから Accept-Encoding に最も適合するものを選ぶ (RFC 9110 Section 12.5.3)
+///
+/// `available` はサーバーが対応するコーディングを優先順に並べたもの。
+/// 各候補の q 値を `AcceptEncoding::quality()` で求め、q 値が最大の候補を返す。
+/// q 値が同じ場合は `available` で先に現れた候補を選ぶ。
+///
+/// `identity` (コーディングなし) のレンジ、または `*` がある場合は、その q 値で候補と比較し、
+/// どの候補よりも大きければ `"identity"` を返す。
+/// `identity` が記載されていない場合は、どの候補も受理されないときにだけ `"identity"` を返す。
+/// `identity;q=0` または、`identity` のレンジがない状態での `*;q=0` で
+/// identity が除外されていて、どの候補も受理されない場合は `None` を返す。この場合は
+/// 406 Not Acceptable を返すか、Accept-Encoding を無視して identity で送るかを呼び出し側が決める。
+///
+/// Accept-Encoding ヘッダーがない場合はどのコーディングも受理されるため、この関数は使わず
+/// サーバーの優先順で選ぶこと。空の Accept-Encoding は identity のみを受理する。
+///
+/// ```rust
+/// use shiguredo_http11::accept::{AcceptEncoding, negotiate_encoding};
+///
+/// let accept = AcceptEncoding::parse("gzip;q=0.8, br").unwrap();
+/// assert_eq!(negotiate_encoding(&accept, &["zstd", "br", "gzip"]), Some("br"));
+///
+/// let accept = AcceptEncoding::parse("deflate").unwrap();
+/// assert_eq!(negotiate_encoding(&accept, &["br", "gzip"]), Some("identity"));
+///
+/// let accept = AcceptEncoding::parse("identity, gzip;q=0.5").unwrap();
+/// assert_eq!(negotiate_encoding(&accept, &["br", "gzip"]), Some("identity"));
+///
+/// let accept = AcceptEncoding::parse("deflate, identity;q=0").unwrap();
+/// assert_eq!(negotiate_encoding(&accept, &["br", "gzip"]), None);
+/// ```
+pub fn negotiate_encoding<'a>(accept: &AcceptEncoding, available: &[&'a str]) -> Option<&'a str> {
+    let mut best: Option<(&'a str, QValue)> = None;
+    for &candidate in available {
+        let Some(q) = accept.quality(candidate) else {
+            continue;
+        };
+        if q.value() == 0 {
+            continue;
+        }
+        if best.is_none_or(|(_, best_q)| q > best_q) {
+            best = Some((candidate, q));
+        }
+    }
+    match (best, accept.quality("identity")) {
+        // identity のレンジ (または `*`) があれば、その q 値で候補と比較する
+        (Some((_, q)), Some(identity_q)) if identity_q > q => Some("identity"),
+        (Some((candidate, _)), _) => Some(candidate),
+        // RFC 9110 Section 12.5.3: identity は明示的に除外されない限り受理される
+        (None, Some(identity_q)) if identity_q.value() == 0 => None,
+        (None, _) => Some("identity"),
+    }
+}
+
+/// 互換のための別名を正規の名前にする (RFC 9110 Section 8.4.1.1 / 8.4.1.3)
+fn normalize_coding(coding: &str) -> &str {
+    if coding.eq_ignore_ascii_case("x-gzip") {
+        "gzip"
+    } else if coding.eq_ignore_ascii_case("x-compress") {
+        "compress"
+    } else {
+        coding
+    }
 }
 
 impl fmt::Display for AcceptEncoding {
@@ -332,6 +529,19 @@ impl fmt::Display for AcceptEncoding {
     }
 }
 
+impl TypedHeader for AcceptEncoding {
+    const NAME: &'static str = "Accept-Encoding";
+    type Error = AcceptError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Accept-Encoding レンジ
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EncodingRange {
@@ -381,6 +591,29 @@ impl AcceptLanguage {
     pub fn items(&self) -> &[LanguageRange] {
         &self.items
     }
+
+    /// 言語タグに対する q 値を取得 (RFC 4647 Section 3.3.1)
+    ///
+    /// 基本フィルタリングで一致する言語レンジのうち、最も長い (具体的な) ものの q 値を返す。
+    /// `*` はすべての言語タグに一致するが、最も具体性が低い。
+    /// 一致するレンジがない場合は `None`。
+    pub fn quality(&self, tag: &str) -> Option<QValue> {
+        self.items
+            .iter()
+            .filter(|range| basic_filter_matches(&range.language, tag))
+            .fold(None, |best: Option<(usize, QValue)>, range| {
+                let specificity = if range.language == "*" {
+                    0
+                } else {
+                    range.language.len()
+                };
+                match best {
+                    Some((best_s, _)) if best_s >= specificity => best,
+                    _ => Some((specificity, range.q)),
+                }
+            })
+            .map(|(_, q)| q)
+    }
 }
 
 impl fmt::Display for AcceptLanguage {
@@ -390,6 +623,113 @@ impl fmt::Display for AcceptLanguage {
     }
 }
 
+impl TypedHeader for AcceptLanguage {
+    const NAME: &'static str = "Accept-Language";
+    type Error = AcceptError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// 基本フィルタリングで提供可能な言語タグを絞り込む (RFC 4647 Section 3.3.1)
+///
+/// 言語レンジと一致する (レンジと等しいか、レンジの後に `-` が続く) 言語タグを
+/// `AcceptLanguage::quality()` の q 値の高い順に返す。q 値が同じ場合は `available` の順を保つ。
+/// q=0 の言語タグと、どのレンジにも一致しない言語タグは含めない。
+/// Accept-Language が空リストの場合は `available` をそのまま返す。
+///
+/// ```rust
+/// use shiguredo_http11::accept::{AcceptLanguage, filter_languages};
+///
+/// let accept = AcceptLanguage::parse("de, en;q=0.5").unwrap();
+/// assert_eq!(
+///     filter_languages(&accept, &["en-US", "fr", "de-CH", "de"]),
+///     vec!["de-CH", "de", "en-US"]
+/// );
+/// ```
+pub fn filter_languages<'a>(accept: &AcceptLanguage, available: &[&'a str]) -> Vec<&'a str> {
+    if accept.items.is_empty() {
+        return available.to_vec();
+    }
+    let mut matched: Vec<(&'a str, QValue)> = available
+        .iter()
+        .filter_map(|&tag| accept.quality(tag).map(|q| (tag, q)))
+        .filter(|(_, q)| q.value() > 0)
+        .collect();
+    // 安定ソートのため q 値が同じ場合は available の順になる
+    matched.sort_by(|(_, a), (_, b)| b.cmp(a));
+    matched.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// ルックアップで提供可能な言語タグを 1 つ選ぶ (RFC 4647 Section 3.4)
+///
+/// 言語レンジを q 値の高い順 (同じ場合はヘッダーでの順) に調べ、レンジと等しい言語タグを探す。
+/// 見つからない場合はレンジの末尾のサブタグを削って再試行する。削った結果、末尾が
+/// 1 文字のサブタグ (`x` 等) になる場合はそれも削る。
+/// `*` と q=0 のレンジは検索に使わず、q=0 で除外された言語タグは選ばない。
+///
+/// 選ばれた言語タグは `Content-Language` に使える。見つからない場合は `None` を返すため、
+/// 呼び出し側でデフォルトの言語を使うこと。
+///
+/// ```rust
+/// use shiguredo_http11::accept::{AcceptLanguage, lookup_language};
+///
+/// let accept = AcceptLanguage::parse("zh-Hant-CN-x-private1, en;q=0.5").unwrap();
+/// assert_eq!(lookup_language(&accept, &["en", "zh-Hant", "zh"]), Some("zh-Hant"));
+///
+/// let accept = AcceptLanguage::parse("fr").unwrap();
+/// assert_eq!(lookup_language(&accept, &["en", "ja"]), None);
+/// ```
+pub fn lookup_language<'a>(accept: &AcceptLanguage, available: &[&'a str]) -> Option<&'a str> {
+    let mut ranges: Vec<&LanguageRange> = accept
+        .items
+        .iter()
+        .filter(|range| range.language != "*" && range.q.value() > 0)
+        .collect();
+    ranges.sort_by_key(|range| core::cmp::Reverse(range.q));
+
+    for range in ranges {
+        let mut current = range.language.as_str();
+        loop {
+            let found = available.iter().copied().find(|tag| {
+                tag.eq_ignore_ascii_case(current)
+                    && accept.quality(tag).is_none_or(|q| q.value() > 0)
+            });
+            if found.is_some() {
+                return found;
+            }
+            let Some(pos) = current.rfind('-') else {
+                break;
+            };
+            current = &current[..pos];
+            // 末尾に残った 1 文字のサブタグ (拡張や私用のシングルトン) も削る
+            if let Some(pos) = current.rfind('-')
+                && current.len() - pos == 2
+            {
+                current = &current[..pos];
+            }
+        }
+    }
+    None
+}
+
+/// 言語レンジが言語タグに一致するか (RFC 4647 Section 3.3.1)
+///
+/// `LanguageTag::matches_basic_range()` と共通の実装。レンジの構文は検証しない。
+pub(crate) fn basic_filter_matches(range: &str, tag: &str) -> bool {
+    if range == "*" {
+        return true;
+    }
+    tag.len() >= range.len()
+        && tag.as_bytes()[..range.len()].eq_ignore_ascii_case(range.as_bytes())
+        && (tag.len() == range.len() || tag.as_bytes()[range.len()] == b'-')
+}
+
 /// Accept-Language レンジ
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LanguageRange {
@@ -0,0 +1,216 @@
+//! Allow ヘッダー (RFC 9110 Section 10.2.1)
+//!
+//! ## 概要
+//!
+//! 対象リソースがサポートするメソッドを伝える Allow ヘッダーのパースと生成、
+//! 405 (Method Not Allowed) レスポンスの生成を提供します。
+//!
+//! - `Allow`: メソッドの一覧 (重複は除き、追加した順序を保つ)
+//! - `Response::method_not_allowed()`: Allow 付きの空ボディの 405 レスポンス
+//! - `Response::method_not_allowed_problem()`: Allow 付きで
+//!   Problem Details (RFC 9457) の JSON をボディに持つ 405 レスポンス
+//!
+//! RFC 9110 Section 15.5.6: 405 レスポンスには Allow ヘッダーを含めなければならない。
+//! 空の Allow は、どのメソッドも許可しないことを表す。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Allow = #method
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::Response;
+//! use shiguredo_http11::allow::Allow;
+//!
+//! let response = Response::method_not_allowed(&["GET", "HEAD"]).unwrap();
+//! assert_eq!(response.status_code(), 405);
+//! assert_eq!(response.get_header("Allow"), Some("GET, HEAD"));
+//! assert_eq!(response.body_bytes(), Some(&b""[..]));
+//!
+//! let allow = Allow::from_message(&response).unwrap().unwrap();
+//! assert!(allow.contains("GET"));
+//! assert!(!allow.contains("POST"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::method::Method;
+use crate::response::Response;
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
+use crate::validate::trim_ows;
+
+/// Problem Details のメディアタイプ (RFC 9457 Section 3)
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Allow パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AllowError {
+    /// 不正なメソッド
+    InvalidMethod(String),
+}
+
+impl fmt::Display for AllowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllowError::InvalidMethod(method) => write!(f, "invalid Allow method: {:?}", method),
+        }
+    }
+}
+
+impl core::error::Error for AllowError {}
+
+/// Allow ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Allow {
+    methods: Vec<Method>,
+}
+
+impl Allow {
+    /// 空の Allow を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// メソッド名の一覧から作成
+    pub fn from_methods(methods: &[&str]) -> Result<Self, AllowError> {
+        methods.iter().try_fold(Allow::new(), |allow, method| {
+            let method =
+                Method::parse(method).map_err(|_| AllowError::InvalidMethod(method.to_string()))?;
+            Ok(allow.with_method(method))
+        })
+    }
+
+    /// Allow ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 空フィールド値・空要素は受理する。
+    pub fn parse(input: &str) -> Result<Self, AllowError> {
+        let methods: Vec<&str> = input
+            .split(',')
+            .map(trim_ows)
+            .filter(|method| !method.is_empty())
+            .collect();
+        Self::from_methods(&methods)
+    }
+
+    /// メッセージの Allow ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, AllowError> {
+        let values = message.get_headers("Allow");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// メソッドを追加 (ビルダーパターン)
+    ///
+    /// すでに含まれる場合は追加しない。
+    pub fn with_method(mut self, method: Method) -> Self {
+        if !self.methods.contains(&method) {
+            self.methods.push(method);
+        }
+        self
+    }
+
+    /// メソッドの一覧
+    pub fn methods(&self) -> &[Method] {
+        &self.methods
+    }
+
+    /// メソッドを含むか (大文字小文字を区別する)
+    pub fn contains(&self, method: &str) -> bool {
+        self.methods.iter().any(|m| m == method)
+    }
+
+    /// どのメソッドも許可しないか
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+}
+
+impl fmt::Display for Allow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, method) in self.methods.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", method)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for Allow {
+    const NAME: &'static str = "Allow";
+    type Error = AllowError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Response {
+    /// Allow 付きの 405 (Method Not Allowed) レスポンスを作成
+    ///
+    /// ボディは空 (`Content-Length: 0`)。不正なメソッド名を含む場合はエラーを返す。
+    pub fn method_not_allowed(methods: &[&str]) -> Result<Self, EncodeError> {
+        let allow = allow_from_methods(methods)?;
+        Response::with_status(StatusCode::METHOD_NOT_ALLOWED)
+            .header(Allow::NAME, allow.to_string())
+            .map(|response| response.body(Vec::new()))
+    }
+
+    /// Allow 付きで Problem Details (RFC 9457) をボディに持つ 405 レスポンスを作成
+    ///
+    /// `method` はリクエストのメソッドで、`detail` メンバーに含める。
+    /// Content-Type は `application/problem+json`。
+    pub fn method_not_allowed_problem(methods: &[&str], method: &str) -> Result<Self, EncodeError> {
+        let allow = allow_from_methods(methods)?;
+        let status = StatusCode::METHOD_NOT_ALLOWED;
+        let mut body = String::new();
+        body.push_str("{\"type\":\"about:blank\",\"title\":");
+        push_json_string(&mut body, status.canonical_reason());
+        body.push_str(",\"status\":");
+        body.push_str(&status.code().to_string());
+        body.push_str(",\"detail\":");
+        push_json_string(&mut body, &alloc::format!("method {method} is not allowed"));
+        body.push('}');
+        Ok(Response::with_status(status)
+            .header(Allow::NAME, allow.to_string())?
+            .header("Content-Type", PROBLEM_CONTENT_TYPE)?
+            .body(body.into_bytes()))
+    }
+}
+
+fn allow_from_methods(methods: &[&str]) -> Result<Allow, EncodeError> {
+    Allow::from_methods(methods)
+        .map_err(|AllowError::InvalidMethod(method)| EncodeError::InvalidMethod { method })
+}
+
+/// JSON の文字列 (RFC 8259 Section 7) を追加する
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&alloc::format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -18,6 +18,8 @@
 //! let auth = BasicAuth::parse("Basic dXNlcjpwYXNzd29yZA==").unwrap();
 //! assert_eq!(auth.username(), "user");
 //! assert_eq!(auth.password(), "password");
+//! // 資格情報の照合は `==` ではなく定数時間比較を使う
+//! assert!(auth.verify("user", "password"));
 //!
 //! // サーバー: WWW-Authenticate ヘッダーの作成 (Basic)
 //! let challenge = WwwAuthenticate::basic("example.com");
@@ -33,6 +35,8 @@ use alloc::vec::Vec;
 use core::fmt;
 
 use crate::base64;
+use crate::ext_value::ExtValue;
+use crate::typed_header::TypedHeader;
 use crate::validate::{
     escape_quotes, is_qdtext_char, is_quoted_pair_char, is_token_char, is_valid_token,
 };
@@ -214,6 +218,27 @@ impl BasicAuth {
         &self.password
     }
 
+    /// 期待するユーザー名とパスワードに一致するか検証
+    ///
+    /// [`secure_eq()`] で比較するため、一致した桁数によって処理時間が変わらない。
+    /// ユーザー名が一致しなくてもパスワードの比較は行う。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::auth::BasicAuth;
+    ///
+    /// let auth = BasicAuth::parse("Basic dXNlcjpwYXNzd29yZA==").unwrap();
+    /// assert!(auth.verify("user", "password"));
+    /// assert!(!auth.verify("user", "passw0rd"));
+    /// assert!(!auth.verify("admin", "password"));
+    /// ```
+    pub fn verify(&self, expected_username: &str, expected_password: &str) -> bool {
+        let username_eq = secure_eq(&self.username, expected_username);
+        let password_eq = secure_eq(&self.password, expected_password);
+        username_eq & password_eq
+    }
+
     /// Authorization ヘッダー値を生成
     ///
     /// # 例
@@ -230,6 +255,35 @@ impl BasicAuth {
     }
 }
 
+/// 定数時間でバイト列を比較
+///
+/// パスワードやトークンなどの秘密の値を `==` で比較すると、最初に異なるバイトの位置で
+/// 処理が終わるため、応答時間から一致した桁数を推測されるおそれがある。
+/// この関数は内容によらず `a` の全バイトを比較する。
+///
+/// 処理時間は `a` の長さに依存するため、`a` には受信した値、`b` には期待する値を渡す。
+/// 長さが異なる場合は false を返す。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::auth::secure_eq;
+///
+/// assert!(secure_eq("secret-token", "secret-token"));
+/// assert!(!secure_eq("secret-token", "secret-tokex"));
+/// assert!(!secure_eq("secret", "secret-token"));
+/// ```
+pub fn secure_eq(a: impl AsRef<[u8]>, b: impl AsRef<[u8]>) -> bool {
+    let a = a.as_ref();
+    let b = b.as_ref();
+    let mut diff = a.len() ^ b.len();
+    for (i, &x) in a.iter().enumerate() {
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= usize::from(x ^ y);
+    }
+    core::hint::black_box(diff) == 0
+}
+
 impl fmt::Display for BasicAuth {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.to_header_value())
@@ -337,6 +391,19 @@ impl fmt::Display for WwwAuthenticate {
     }
 }
 
+impl TypedHeader for WwwAuthenticate {
+    const NAME: &'static str = "WWW-Authenticate";
+    type Error = AuthError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Digest 認証 (Authorization / Proxy-Authorization)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigestAuth {
@@ -969,70 +1036,9 @@ fn has_control_chars(s: &str) -> bool {
 /// charset は UTF-8 のみサポート (RFC 7616 §3.4 で UTF-8 を要求するため、
 /// ISO-8859-1 等は本実装では `InvalidUsernameExtValue` で reject)。
 fn decode_username_ext_value(input: &str) -> Result<String, AuthError> {
-    let first_quote = input.find('\'').ok_or(AuthError::InvalidUsernameExtValue)?;
-    let charset = &input[..first_quote];
-    if !charset.eq_ignore_ascii_case("UTF-8") {
-        // RFC 7616 §3.4 は UTF-8 charset を要求する。
-        return Err(AuthError::InvalidUsernameExtValue);
-    }
-
-    let rest = &input[first_quote + 1..];
-    let second_quote = rest.find('\'').ok_or(AuthError::InvalidUsernameExtValue)?;
-    // language タグは無視する (RFC 8187 §3.2.1: 受信側は無視してよい)
-    let value_chars = &rest[second_quote + 1..];
-
-    // percent-decode する。RFC 8187 §3.2.1 の attr-char 範囲外は reject。
-    let bytes = value_chars.as_bytes();
-    let mut result = alloc::vec::Vec::with_capacity(bytes.len());
-    let mut i = 0;
-    while i < bytes.len() {
-        let b = bytes[i];
-        if b == b'%' {
-            if i + 2 >= bytes.len() {
-                return Err(AuthError::InvalidUsernameExtValue);
-            }
-            let hi = (bytes[i + 1] as char)
-                .to_digit(16)
-                .ok_or(AuthError::InvalidUsernameExtValue)? as u8;
-            let lo = (bytes[i + 2] as char)
-                .to_digit(16)
-                .ok_or(AuthError::InvalidUsernameExtValue)? as u8;
-            result.push((hi << 4) | lo);
-            i += 3;
-        } else if is_attr_char(b) {
-            result.push(b);
-            i += 1;
-        } else {
-            return Err(AuthError::InvalidUsernameExtValue);
-        }
-    }
-
-    String::from_utf8(result).map_err(|_| AuthError::InvalidUsernameExtValue)
-}
-
-/// RFC 8187 Section 3.2.1: attr-char
-///
-/// attr-char = ALPHA / DIGIT / "!" / "#" / "$" / "&" / "+" / "-" / "." /
-///             "^" / "_" / "`" / "|" / "~"
-fn is_attr_char(b: u8) -> bool {
-    matches!(
-        b,
-        b'A'..=b'Z'
-            | b'a'..=b'z'
-            | b'0'..=b'9'
-            | b'!'
-            | b'#'
-            | b'$'
-            | b'&'
-            | b'+'
-            | b'-'
-            | b'.'
-            | b'^'
-            | b'_'
-            | b'`'
-            | b'|'
-            | b'~'
-    )
+    ExtValue::parse(input)
+        .map(ExtValue::into_value)
+        .map_err(|_| AuthError::InvalidUsernameExtValue)
 }
 
 #[cfg(test)]
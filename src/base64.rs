@@ -12,6 +12,8 @@
 //!   - 末尾の余剰 bit (RFC 4648 §3.3「MUST be zero」) が 0 でない場合は reject
 //!   - 本ライブラリは Basic / Digest 認証等で credential canonicalization を担保するため
 //!     non-canonical base64 表現を受理しない方針とする
+//! - `encode_url` / `decode_url` は RFC 4648 Section 5 の URL 安全なアルファベット
+//!   (`-`, `_`) をパディングなしで扱う
 
 use alloc::string::String;
 use alloc::vec::Vec;
@@ -151,6 +153,42 @@ pub(crate) fn decode(input: &str) -> Result<Vec<u8>, Base64Error> {
     Ok(result)
 }
 
+/// Base64url エンコード (RFC 4648 Section 5、パディングなし)
+pub(crate) fn encode_url(input: &[u8]) -> String {
+    encode(input)
+        .chars()
+        .filter(|&c| c != '=')
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Base64url デコード (RFC 4648 Section 5、パディングなし)
+///
+/// パディング `=`、空白、標準アルファベットの `+` / `/` は受け付けない。
+/// 末尾の余剰 bit は `decode` と同じく 0 でなければならない。
+pub(crate) fn decode_url(input: &str) -> Result<Vec<u8>, Base64Error> {
+    let mut standard = String::with_capacity(input.len() + 2);
+    for c in input.chars() {
+        match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' => standard.push(c),
+            '-' => standard.push('+'),
+            '_' => standard.push('/'),
+            _ => return Err(Base64Error::InvalidCharacter),
+        }
+    }
+    match standard.len() % 4 {
+        0 => {}
+        2 => standard.push_str("=="),
+        3 => standard.push('='),
+        _ => return Err(Base64Error::InvalidPadding),
+    }
+    decode(&standard)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,4 +274,31 @@ mod tests {
         let result = decode("Zh==");
         assert_eq!(result, Err(Base64Error::InvalidPadding));
     }
+
+    #[test]
+    fn encode_url_basic() {
+        assert_eq!(encode_url(b""), "");
+        assert_eq!(encode_url(b"f"), "Zg");
+        assert_eq!(encode_url(b"fo"), "Zm8");
+        assert_eq!(encode_url(b"foo"), "Zm9v");
+        assert_eq!(encode_url(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn decode_url_basic() {
+        assert_eq!(decode_url("").unwrap(), b"");
+        assert_eq!(decode_url("Zg").unwrap(), b"f");
+        assert_eq!(decode_url("Zm8").unwrap(), b"fo");
+        assert_eq!(decode_url("-_8").unwrap(), [0xfb, 0xff]);
+    }
+
+    #[test]
+    fn decode_url_rejects_invalid_input() {
+        assert_eq!(decode_url("Zg=="), Err(Base64Error::InvalidCharacter));
+        assert_eq!(decode_url("+/8"), Err(Base64Error::InvalidCharacter));
+        assert_eq!(decode_url("Zm 8"), Err(Base64Error::InvalidCharacter));
+        assert_eq!(decode_url("Z"), Err(Base64Error::InvalidPadding));
+        // 末尾の余剰 bit が 0 でない
+        assert_eq!(decode_url("Zh"), Err(Base64Error::InvalidPadding));
+    }
 }
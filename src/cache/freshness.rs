@@ -0,0 +1,315 @@
+//! 鮮度計算 (RFC 9111 Section 4.2)
+//!
+//! ## 概要
+//!
+//! 保存されたレスポンスのヘッダー (`Date` / `Age` / `Cache-Control` / `Expires` /
+//! `Last-Modified`) と時刻から、鮮度の有効期間 (freshness lifetime)、現在の Age、
+//! 再検証の要否を計算する。
+//!
+//! Sans I/O のため時刻は呼び出し側が UNIX 時間 (秒) で渡す。
+//!
+//! - `request_time`: リクエストを送信した時刻
+//! - `response_time`: レスポンスを受信した時刻
+//! - `now`: 判定する時刻
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::cache::freshness::{CacheKind, Freshness, LifetimeSource, ResponseTiming};
+//! use shiguredo_http11::date::HttpDate;
+//! use shiguredo_http11::ResponseDecoder;
+//!
+//! let mut decoder = ResponseDecoder::new();
+//! decoder
+//!     .feed(
+//!         b"HTTP/1.1 200 OK\r\n\
+//!           Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+//!           Cache-Control: max-age=60\r\n\
+//!           Age: 10\r\n\
+//!           Content-Length: 0\r\n\r\n",
+//!     )
+//!     .unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//!
+//! let date = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap().unix_seconds();
+//! let timing = ResponseTiming::new(date, date, date + 30);
+//! let freshness = Freshness::calculate(
+//!     head.status_code(),
+//!     head.headers(),
+//!     timing,
+//!     CacheKind::Private,
+//! );
+//! assert_eq!(freshness.lifetime(), 60);
+//! assert_eq!(freshness.lifetime_source(), LifetimeSource::MaxAge);
+//! assert_eq!(freshness.current_age(), 40);
+//! assert!(freshness.is_fresh());
+//! assert_eq!(freshness.time_to_live(), 20);
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use super::{Age, CacheControl};
+use crate::date::HttpDate;
+use crate::typed_header::decode_typed;
+
+/// キャッシュの種類 (RFC 9111 Section 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    /// 単一ユーザー向けのキャッシュ (ブラウザ等)
+    Private,
+    /// 複数ユーザーで共有するキャッシュ (プロキシ、CDN 等)
+    ///
+    /// `s-maxage` と `proxy-revalidate` を考慮する。
+    Shared,
+}
+
+/// 鮮度の有効期間の根拠
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifetimeSource {
+    /// `Cache-Control: s-maxage` (共有キャッシュのみ)
+    SMaxAge,
+    /// `Cache-Control: max-age`
+    MaxAge,
+    /// `Expires` と `Date` の差
+    Expires,
+    /// `Last-Modified` からのヒューリスティック (RFC 9111 Section 4.2.2)
+    Heuristic,
+    /// 明示的な有効期間がなく、ヒューリスティックも適用できない (有効期間 0)
+    None,
+}
+
+/// リクエストとレスポンスの時刻 (UNIX 時間、秒)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseTiming {
+    request_time: u64,
+    response_time: u64,
+    now: u64,
+}
+
+impl ResponseTiming {
+    /// リクエスト送信時刻、レスポンス受信時刻、判定時刻から作成
+    pub fn new(request_time: u64, response_time: u64, now: u64) -> Self {
+        Self {
+            request_time,
+            response_time,
+            now,
+        }
+    }
+
+    /// リクエストを送信した時刻
+    pub fn request_time(&self) -> u64 {
+        self.request_time
+    }
+
+    /// レスポンスを受信した時刻
+    pub fn response_time(&self) -> u64 {
+        self.response_time
+    }
+
+    /// 判定する時刻
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+}
+
+/// 鮮度の計算結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Freshness {
+    lifetime: u64,
+    lifetime_source: LifetimeSource,
+    current_age: u64,
+    no_cache: bool,
+    must_revalidate: bool,
+    stale_while_revalidate: Option<u64>,
+    stale_if_error: Option<u64>,
+}
+
+impl Freshness {
+    /// レスポンスのステータスコードとヘッダーから鮮度を計算する
+    ///
+    /// - 有効期間 (RFC 9111 Section 4.2.1): 共有キャッシュの `s-maxage`、`max-age`、
+    ///   `Expires` - `Date` の順に使う。不正な `Expires` は有効期限切れとして扱う (Section 5.3)
+    /// - ヒューリスティック (Section 4.2.2): 明示的な有効期間がなく、ステータスコードが
+    ///   ヒューリスティックにキャッシュ可能 (RFC 9110 Section 15.1) または `public` があり、
+    ///   `Last-Modified` がある場合、`Date` - `Last-Modified` の 10% を有効期間とする
+    /// - 現在の Age (Section 4.2.3): `Age` / `Date` と時刻から計算する。
+    ///   `Date` がない、または不正な場合は `response_time` を使う
+    ///
+    /// `Cache-Control` が不正な場合は `no-cache` と同様に常に再検証が必要として扱う。
+    pub fn calculate(
+        status_code: u16,
+        headers: &[(Cow<'static, str>, String)],
+        timing: ResponseTiming,
+        kind: CacheKind,
+    ) -> Self {
+        // rfc850-date の 2 桁年の解決に使う
+        let reference_year = HttpDate::from_unix_seconds(timing.now)
+            .map(|date| date.year())
+            .unwrap_or(9999);
+        let date_value =
+            header_date(headers, "Date", reference_year).unwrap_or(timing.response_time);
+
+        let (cache_control, invalid_cache_control) = match decode_typed::<CacheControl>(headers) {
+            Some(Ok(cache_control)) => (cache_control, false),
+            Some(Err(_)) => (CacheControl::new(), true),
+            None => (CacheControl::new(), false),
+        };
+
+        let shared = kind == CacheKind::Shared;
+        let (lifetime, lifetime_source) = if let Some(s_maxage) =
+            cache_control.s_maxage().filter(|_| shared)
+        {
+            (s_maxage, LifetimeSource::SMaxAge)
+        } else if let Some(max_age) = cache_control.max_age() {
+            (max_age, LifetimeSource::MaxAge)
+        } else if let Some((_, expires)) = find_header(headers, "Expires") {
+            // RFC 9111 Section 5.3: 不正な日付 ("0" 等) は過去の日時として扱う
+            let lifetime = HttpDate::parse_any(expires, reference_year)
+                .map(|expires| expires.unix_seconds().saturating_sub(date_value))
+                .unwrap_or(0);
+            (lifetime, LifetimeSource::Expires)
+        } else if let Some(last_modified) = header_date(headers, "Last-Modified", reference_year)
+            .filter(|_| is_heuristically_cacheable(status_code) || cache_control.is_public())
+        {
+            (
+                date_value.saturating_sub(last_modified) / 10,
+                LifetimeSource::Heuristic,
+            )
+        } else {
+            (0, LifetimeSource::None)
+        };
+
+        // RFC 9111 Section 4.2.3: 現在の Age の計算
+        let age_value = find_header(headers, "Age")
+            .and_then(|(_, value)| Age::parse(value).ok())
+            .map(|age| age.seconds())
+            .unwrap_or(0);
+        let apparent_age = timing.response_time.saturating_sub(date_value);
+        let response_delay = timing.response_time.saturating_sub(timing.request_time);
+        let corrected_age_value = age_value.saturating_add(response_delay);
+        let corrected_initial_age = apparent_age.max(corrected_age_value);
+        let resident_time = timing.now.saturating_sub(timing.response_time);
+        let current_age = corrected_initial_age.saturating_add(resident_time);
+
+        // RFC 9111 Section 5.2.2.10: 共有キャッシュでは s-maxage は proxy-revalidate を含意する
+        let must_revalidate = cache_control.is_must_revalidate()
+            || (shared
+                && (cache_control.is_proxy_revalidate() || cache_control.s_maxage().is_some()));
+
+        Self {
+            lifetime,
+            lifetime_source,
+            current_age,
+            no_cache: cache_control.is_no_cache() || invalid_cache_control,
+            must_revalidate,
+            stale_while_revalidate: cache_control.stale_while_revalidate(),
+            stale_if_error: cache_control.stale_if_error(),
+        }
+    }
+
+    /// 鮮度の有効期間 (秒)
+    pub fn lifetime(&self) -> u64 {
+        self.lifetime
+    }
+
+    /// 鮮度の有効期間の根拠
+    pub fn lifetime_source(&self) -> LifetimeSource {
+        self.lifetime_source
+    }
+
+    /// ヒューリスティックな有効期間か
+    pub fn is_heuristic(&self) -> bool {
+        self.lifetime_source == LifetimeSource::Heuristic
+    }
+
+    /// 現在の Age (秒)
+    ///
+    /// 保存したレスポンスを送る場合は `Age` ヘッダーにこの値を設定する (RFC 9111 Section 5.1)。
+    pub fn current_age(&self) -> u64 {
+        self.current_age
+    }
+
+    /// 新鮮か (有効期間が現在の Age を超えている)
+    pub fn is_fresh(&self) -> bool {
+        self.lifetime > self.current_age
+    }
+
+    /// 新鮮でなくなるまでの秒数
+    ///
+    /// すでに古い場合は 0。
+    pub fn time_to_live(&self) -> u64 {
+        self.lifetime.saturating_sub(self.current_age)
+    }
+
+    /// 有効期間を過ぎてからの秒数
+    ///
+    /// 新鮮な場合は 0。
+    pub fn staleness(&self) -> u64 {
+        self.current_age.saturating_sub(self.lifetime)
+    }
+
+    /// 保存したレスポンスを送る前に再検証が必要か
+    ///
+    /// `no-cache` がある場合 (RFC 9111 Section 5.2.2.4) と、古い場合に true。
+    pub fn requires_revalidation(&self) -> bool {
+        self.no_cache || !self.is_fresh()
+    }
+
+    /// 古いレスポンスを再検証せずに送ることが許されているか (RFC 9111 Section 4.2.4)
+    ///
+    /// `no-cache`、`must-revalidate`、共有キャッシュでの `proxy-revalidate` / `s-maxage`
+    /// がある場合は false。オリジンサーバーに接続できない場合などに使う。
+    pub fn allows_stale(&self) -> bool {
+        !self.no_cache && !self.must_revalidate
+    }
+
+    /// 古いレスポンスを送りつつバックグラウンドで再検証できるか (RFC 5861 Section 3)
+    ///
+    /// 古く、`stale-while-revalidate` の期間内で、`allows_stale()` が true の場合に true。
+    pub fn is_within_stale_while_revalidate(&self) -> bool {
+        self.within_stale_window(self.stale_while_revalidate)
+    }
+
+    /// 再検証でエラーが発生した場合に古いレスポンスを送れるか (RFC 5861 Section 4)
+    ///
+    /// 古く、`stale-if-error` の期間内で、`allows_stale()` が true の場合に true。
+    pub fn is_within_stale_if_error(&self) -> bool {
+        self.within_stale_window(self.stale_if_error)
+    }
+
+    fn within_stale_window(&self, window: Option<u64>) -> bool {
+        !self.is_fresh()
+            && self.allows_stale()
+            && window.is_some_and(|window| self.staleness() <= window)
+    }
+}
+
+/// ヒューリスティックにキャッシュ可能なステータスコードか (RFC 9110 Section 15.1)
+pub(super) fn is_heuristically_cacheable(status_code: u16) -> bool {
+    matches!(
+        status_code,
+        200 | 203 | 204 | 206 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
+
+fn find_header<'a>(
+    headers: &'a [(Cow<'static, str>, String)],
+    name: &str,
+) -> Option<&'a (Cow<'static, str>, String)> {
+    headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name))
+}
+
+/// 日付ヘッダーを HTTP-date の 3 形式 (RFC 9110 Section 5.6.7) で UNIX 時間にする。
+/// ヘッダーがない、または不正な場合は `None`
+fn header_date(
+    headers: &[(Cow<'static, str>, String)],
+    name: &str,
+    reference_year: u16,
+) -> Option<u64> {
+    find_header(headers, name).and_then(|(_, value)| {
+        HttpDate::parse_any(value, reference_year)
+            .ok()
+            .map(|date| date.unix_seconds())
+    })
+}
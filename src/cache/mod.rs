@@ -21,8 +21,17 @@
 //! // Expires ヘッダー
 //! let expires = Expires::parse("Sun, 06 Nov 1994 08:49:37 GMT", 2026).unwrap();
 //! ```
+//!
+//! 鮮度の計算は [`freshness`] モジュール、レスポンスの保存と参照は [`HttpCache`] を参照。
+
+pub mod freshness;
+mod store;
+
+pub use store::{CacheDecision, CacheHit, HttpCache, update_stored_response};
 
-use crate::date::{DateError, HttpDate};
+use crate::date::HttpDate;
+use crate::typed_header::TypedHeader;
+use crate::validate::{escape_quotes, is_valid_token, parse_quoted_string, split_with_quotes};
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
@@ -91,6 +100,12 @@ pub struct CacheControl {
     private: bool,
     /// immutable ディレクティブ
     immutable: bool,
+    /// no-cache の修飾形式で指定されたフィールド名
+    no_cache_fields: Vec<String>,
+    /// private の修飾形式で指定されたフィールド名
+    private_fields: Vec<String>,
+    /// 未知のディレクティブ (名前, 値)
+    extensions: Vec<(String, Option<String>)>,
 }
 
 impl CacheControl {
@@ -119,7 +134,8 @@ impl CacheControl {
 
         let mut cc = CacheControl::new();
 
-        for directive in input.split(',') {
+        // quoted-string 内のカンマ (`private="a, b"`) で分割しないようにする
+        for directive in split_with_quotes(input, ',') {
             let directive = directive.trim();
             if directive.is_empty() {
                 continue;
@@ -131,43 +147,53 @@ impl CacheControl {
                 // RFC 9110 Section 5.6.4 quoted-string は両端を DQUOTE で囲む。
                 // 片端のみ DQUOTE がある partial quote (`max-age="3600`) は ABNF 違反のため reject する。
                 let value = if let Some(stripped) = raw_value.strip_prefix('"') {
-                    stripped
-                        .strip_suffix('"')
-                        .ok_or(CacheError::InvalidFormat)?
+                    match parse_quoted_string(stripped) {
+                        Ok((value, "")) => value,
+                        _ => return Err(CacheError::InvalidFormat),
+                    }
                 } else if raw_value.ends_with('"') {
                     return Err(CacheError::InvalidFormat);
                 } else {
-                    raw_value
+                    raw_value.to_string()
                 };
 
                 match name.as_str() {
                     "max-age" => {
-                        cc.max_age = Some(parse_seconds(value)?);
+                        cc.max_age = Some(parse_seconds(&value)?);
                     }
                     "s-maxage" => {
-                        cc.s_maxage = Some(parse_seconds(value)?);
+                        cc.s_maxage = Some(parse_seconds(&value)?);
                     }
                     "max-stale" => {
-                        cc.max_stale = Some(parse_seconds(value)?);
+                        cc.max_stale = Some(parse_seconds(&value)?);
                     }
                     "min-fresh" => {
-                        cc.min_fresh = Some(parse_seconds(value)?);
+                        cc.min_fresh = Some(parse_seconds(&value)?);
                     }
                     "stale-while-revalidate" => {
-                        cc.stale_while_revalidate = Some(parse_seconds(value)?);
+                        cc.stale_while_revalidate = Some(parse_seconds(&value)?);
                     }
                     "stale-if-error" => {
-                        cc.stale_if_error = Some(parse_seconds(value)?);
+                        cc.stale_if_error = Some(parse_seconds(&value)?);
                     }
                     // RFC 9111 Section 5.2.2.4: no-cache の修飾形式
                     // no-cache="field-name" は特定のフィールドのみキャッシュを防ぐ
                     // 注: 修飾形式の特別な処理は広く実装されていないため、
-                    // 非修飾形式と同様に扱う (RFC 9111 の NOTE 参照)
-                    "no-cache" => cc.no_cache = true,
+                    // is_no_cache() は非修飾形式と同様に true を返す (RFC 9111 の NOTE 参照)
+                    "no-cache" => {
+                        cc.no_cache = true;
+                        cc.no_cache_fields = parse_field_names(&value);
+                    }
                     // RFC 9111 Section 5.2.2.7: private の修飾形式
                     // private="field-name" は共有キャッシュで特定のフィールドの保存を防ぐ
-                    "private" => cc.private = true,
-                    _ => {} // 未知のディレクティブは無視
+                    "private" => {
+                        cc.private = true;
+                        cc.private_fields = parse_field_names(&value);
+                    }
+                    // RFC 9111 Section 5.2.3: 未知のディレクティブは無視するが、再生成のために保持する。
+                    // token でない名前は保持できないため捨てる
+                    _ if is_valid_token(&name) => cc.extensions.push((name, Some(value))),
+                    _ => {}
                 }
             } else {
                 let name = directive.to_lowercase();
@@ -183,7 +209,8 @@ impl CacheControl {
                     "private" => cc.private = true,
                     "immutable" => cc.immutable = true,
                     "max-stale" => cc.max_stale = Some(u64::MAX), // 値なしは無制限
-                    _ => {}                                       // 未知のディレクティブは無視
+                    _ if is_valid_token(&name) => cc.extensions.push((name, None)),
+                    _ => {}
                 }
             }
         }
@@ -251,6 +278,82 @@ impl CacheControl {
         self
     }
 
+    /// max-stale を設定
+    ///
+    /// `u64::MAX` は値なしの `max-stale` (任意の古さを許容) になる。
+    pub fn with_max_stale(mut self, seconds: u64) -> Self {
+        self.max_stale = Some(seconds);
+        self
+    }
+
+    /// min-fresh を設定
+    pub fn with_min_fresh(mut self, seconds: u64) -> Self {
+        self.min_fresh = Some(seconds);
+        self
+    }
+
+    /// stale-while-revalidate を設定 (RFC 5861 Section 3)
+    pub fn with_stale_while_revalidate(mut self, seconds: u64) -> Self {
+        self.stale_while_revalidate = Some(seconds);
+        self
+    }
+
+    /// stale-if-error を設定 (RFC 5861 Section 4)
+    pub fn with_stale_if_error(mut self, seconds: u64) -> Self {
+        self.stale_if_error = Some(seconds);
+        self
+    }
+
+    /// only-if-cached を設定
+    pub fn with_only_if_cached(mut self) -> Self {
+        self.only_if_cached = true;
+        self
+    }
+
+    /// must-understand を設定
+    pub fn with_must_understand(mut self) -> Self {
+        self.must_understand = true;
+        self
+    }
+
+    /// フィールド名付きの no-cache を設定 (RFC 9111 Section 5.2.2.4)
+    ///
+    /// フィールド名が空の場合は非修飾形式の `no-cache` になる。
+    pub fn with_no_cache_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.no_cache = true;
+        self.no_cache_fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// フィールド名付きの private を設定 (RFC 9111 Section 5.2.2.7)
+    ///
+    /// フィールド名が空の場合は非修飾形式の `private` になる。
+    pub fn with_private_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.private = true;
+        self.private_fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 拡張ディレクティブを追加 (RFC 9111 Section 5.2.3)
+    ///
+    /// 名前は小文字で保持する。値が token でない場合は quoted-string で出力する。
+    /// 名前が token でない場合は追加しない。
+    pub fn with_extension(mut self, name: &str, value: Option<&str>) -> Self {
+        if is_valid_token(name) {
+            self.extensions
+                .push((name.to_lowercase(), value.map(ToString::to_string)));
+        }
+        self
+    }
+
     /// max-age を取得
     pub fn max_age(&self) -> Option<u64> {
         self.max_age
@@ -331,6 +434,35 @@ impl CacheControl {
         self.immutable
     }
 
+    /// no-cache の修飾形式で指定されたフィールド名
+    ///
+    /// 非修飾形式の場合や no-cache がない場合は空。
+    pub fn no_cache_fields(&self) -> &[String] {
+        &self.no_cache_fields
+    }
+
+    /// private の修飾形式で指定されたフィールド名
+    ///
+    /// 非修飾形式の場合や private がない場合は空。
+    pub fn private_fields(&self) -> &[String] {
+        &self.private_fields
+    }
+
+    /// 未知のディレクティブ一覧 (名前, 値)
+    pub fn extensions(&self) -> &[(String, Option<String>)] {
+        &self.extensions
+    }
+
+    /// 未知のディレクティブを名前で取得
+    ///
+    /// ディレクティブがない場合は `None`、値なしの場合は `Some(None)`。
+    pub fn extension(&self, name: &str) -> Option<Option<&str>> {
+        self.extensions
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_deref())
+    }
+
     /// キャッシュ可能かどうか (簡易判定)
     pub fn is_cacheable(&self) -> bool {
         !self.no_store && (self.public || self.max_age.is_some() || self.s_maxage.is_some())
@@ -369,7 +501,7 @@ impl fmt::Display for CacheControl {
             parts.push(alloc::format!("stale-if-error={}", sie));
         }
         if self.no_cache {
-            parts.push("no-cache".to_string());
+            parts.push(directive_with_fields("no-cache", &self.no_cache_fields));
         }
         if self.no_store {
             parts.push("no-store".to_string());
@@ -393,16 +525,40 @@ impl fmt::Display for CacheControl {
             parts.push("public".to_string());
         }
         if self.private {
-            parts.push("private".to_string());
+            parts.push(directive_with_fields("private", &self.private_fields));
         }
         if self.immutable {
             parts.push("immutable".to_string());
         }
+        for (name, value) in &self.extensions {
+            match value {
+                Some(value) if is_valid_token(value) => {
+                    parts.push(alloc::format!("{}={}", name, value));
+                }
+                Some(value) => {
+                    parts.push(alloc::format!("{}=\"{}\"", name, escape_quotes(value)));
+                }
+                None => parts.push(name.clone()),
+            }
+        }
 
         write!(f, "{}", parts.join(", "))
     }
 }
 
+impl TypedHeader for CacheControl {
+    const NAME: &'static str = "Cache-Control";
+    type Error = CacheError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Age ヘッダー
 ///
 /// RFC 9111 Section 5.1
@@ -455,6 +611,19 @@ impl fmt::Display for Age {
     }
 }
 
+impl TypedHeader for Age {
+    const NAME: &'static str = "Age";
+    type Error = CacheError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Expires ヘッダー
 ///
 /// RFC 9111 Section 5.3
@@ -484,12 +653,8 @@ impl Expires {
     /// assert_eq!(expires.date().year(), 1994);
     /// ```
     pub fn parse(input: &str, reference_year: u16) -> Result<Self, CacheError> {
-        let date = HttpDate::parse(input)
-            .or_else(|e| match e {
-                DateError::Rfc850Date => HttpDate::parse_rfc850(input, reference_year),
-                other => Err(other),
-            })
-            .map_err(|_| CacheError::InvalidDate)?;
+        let date =
+            HttpDate::parse_any(input, reference_year).map_err(|_| CacheError::InvalidDate)?;
         Ok(Expires { date })
     }
 
@@ -513,6 +678,27 @@ impl fmt::Display for Expires {
 /// RFC 9111 Section 1.2.2 で定義されたオーバーフロー時の飽和値
 const DELTA_SECONDS_OVERFLOW: u64 = 2_147_483_648; // 2^31
 
+/// no-cache / private の修飾形式の値をフィールド名一覧にする
+fn parse_field_names(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// フィールド名付きのディレクティブを生成する
+///
+/// RFC 9111 Section 5.2.2.4 / 5.2.2.7: 修飾形式は quoted-string で出力する。
+fn directive_with_fields(name: &str, fields: &[String]) -> String {
+    if fields.is_empty() {
+        name.to_string()
+    } else {
+        alloc::format!("{}=\"{}\"", name, fields.join(", "))
+    }
+}
+
 /// delta-seconds をパース (RFC 9111 Section 1.2.2)
 ///
 /// delta-seconds = 1*DIGIT
@@ -0,0 +1,615 @@
+//! インメモリ HTTP キャッシュ (RFC 9111)
+//!
+//! ## 概要
+//!
+//! クライアント向けの Sans I/O なレスポンスキャッシュ `HttpCache` を提供する。
+//!
+//! - 保存可否の判定 (RFC 9111 Section 3)
+//! - メソッドと URI による一次キー、`Vary` による二次キー (RFC 9111 Section 4.1)。
+//!   二次キーは `vary::cache_key()` で正規化した値で比較する
+//! - 鮮度による Fresh / Stale / MustRevalidate の判定 (RFC 9111 Section 4.2)
+//! - 再検証用の条件付きリクエストの生成 (RFC 9111 Section 4.3.1)
+//! - 安全でないメソッドによる無効化 (RFC 9111 Section 4.4)
+//! - 合計サイズの上限と LRU による追い出し
+//!
+//! 保存と参照の対象は GET のレスポンスのみ。時刻は UNIX 時間 (秒) で呼び出し側が渡す。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::cache::freshness::CacheKind;
+//! use shiguredo_http11::cache::{CacheDecision, HttpCache};
+//! use shiguredo_http11::{Request, Response, StatusCode};
+//!
+//! let mut cache = HttpCache::new(CacheKind::Private, 1024 * 1024);
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("Host", "example.com")
+//!     .unwrap();
+//! let response = Response::with_status(StatusCode::OK)
+//!     .header("Cache-Control", "max-age=60")
+//!     .unwrap()
+//!     .header("ETag", "\"v1\"")
+//!     .unwrap()
+//!     .body(b"hello".to_vec());
+//! assert!(cache.store(&request, &response, 1000, 1000));
+//!
+//! let hit = cache.lookup(&request, 1030).unwrap();
+//! assert_eq!(hit.decision(), CacheDecision::Fresh);
+//! assert_eq!(hit.response().body_bytes(), Some(&b"hello"[..]));
+//!
+//! let hit = cache.lookup(&request, 1100).unwrap();
+//! assert_eq!(hit.decision(), CacheDecision::MustRevalidate);
+//! let conditional = hit.revalidation_request(&request).unwrap();
+//! assert_eq!(conditional.get_header("If-None-Match"), Some("\"v1\""));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::CacheControl;
+use super::freshness::{CacheKind, Freshness, ResponseTiming, is_heuristically_cacheable};
+use crate::decoder::{HttpHead, ResponseHead};
+use crate::error::EncodeError;
+use crate::etag::EntityTag;
+use crate::method::Method;
+use crate::request::Request;
+use crate::response::Response;
+use crate::typed_header::decode_typed;
+use crate::vary::{Vary, cache_key};
+
+/// 保存済みレスポンスの使い方の判定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheDecision {
+    /// 新鮮なため、そのまま使える
+    Fresh,
+    /// 古いが、再検証せずに使える
+    ///
+    /// リクエストの `max-stale` で許容されている場合と、`stale-while-revalidate`
+    /// の期間内の場合。後者はバックグラウンドで再検証すること (RFC 5861 Section 3)。
+    Stale,
+    /// 使う前に `revalidation_request()` で再検証する必要がある
+    MustRevalidate,
+}
+
+/// `HttpCache::lookup()` の結果
+#[derive(Debug)]
+pub struct CacheHit<'a> {
+    response: &'a Response,
+    freshness: Freshness,
+    decision: CacheDecision,
+}
+
+impl<'a> CacheHit<'a> {
+    /// 保存済みレスポンス
+    ///
+    /// `Age` ヘッダーは保存時のまま。送信するレスポンスには `to_response()` を使う。
+    pub fn response(&self) -> &'a Response {
+        self.response
+    }
+
+    /// 鮮度の計算結果
+    pub fn freshness(&self) -> &Freshness {
+        &self.freshness
+    }
+
+    /// 使い方の判定
+    pub fn decision(&self) -> CacheDecision {
+        self.decision
+    }
+
+    /// `Age` ヘッダーを現在の Age に更新したレスポンスを返す (RFC 9111 Section 5.1)
+    pub fn to_response(&self) -> Response {
+        let mut response = self.response.clone();
+        // 現在の Age は数字のみのため set_header は失敗しない
+        let _ = response.set_header("Age", self.freshness.current_age().to_string());
+        response
+    }
+
+    /// 再検証用の条件付きリクエストを作成する (RFC 9111 Section 4.3.1)
+    ///
+    /// 保存済みレスポンスの `ETag` を `If-None-Match` に、`Last-Modified` を
+    /// `If-Modified-Since` に設定する。どちらもない場合は `request` をそのまま複製する。
+    pub fn revalidation_request(&self, request: &Request) -> Result<Request, EncodeError> {
+        let mut conditional = request.clone();
+        if let Some(etag) = self.response.get_header("ETag") {
+            conditional.set_header("If-None-Match", etag)?;
+        }
+        if let Some(last_modified) = self.response.get_header("Last-Modified") {
+            conditional.set_header("If-Modified-Since", last_modified)?;
+        }
+        Ok(conditional)
+    }
+}
+
+/// 保存済みエントリ
+#[derive(Debug)]
+struct Entry {
+    response: Response,
+    request_time: u64,
+    response_time: u64,
+    // Vary ヘッダーと、保存時のリクエストから作った二次キー
+    vary: Option<(Vary, String)>,
+    size: usize,
+    last_used: u64,
+}
+
+/// インメモリ HTTP キャッシュ
+#[derive(Debug)]
+pub struct HttpCache {
+    kind: CacheKind,
+    max_size: usize,
+    size: usize,
+    // LRU 用の論理時計
+    clock: u64,
+    entries: BTreeMap<String, Vec<Entry>>,
+}
+
+impl HttpCache {
+    /// キャッシュの種類と合計サイズの上限 (バイト) を指定して作成
+    ///
+    /// サイズはボディとヘッダーの名前と値の長さの合計で見積もる。
+    pub fn new(kind: CacheKind, max_size: usize) -> Self {
+        Self {
+            kind,
+            max_size,
+            size: 0,
+            clock: 0,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// キャッシュの種類
+    pub fn kind(&self) -> CacheKind {
+        self.kind
+    }
+
+    /// 合計サイズの上限 (バイト)
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// 保存済みエントリの合計サイズ (バイト)
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 保存済みエントリ数
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    /// 空か
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// すべてのエントリを削除する
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.size = 0;
+    }
+
+    /// レスポンスを保存する
+    ///
+    /// `request_time` / `response_time` はリクエストを送信した時刻とレスポンスを受信した時刻。
+    /// RFC 9111 Section 3 の条件を満たさない場合は保存せず false を返す。
+    /// 同じ URI で `Vary` の値が一致する既存エントリは置き換える。
+    pub fn store(
+        &mut self,
+        request: &Request,
+        response: &Response,
+        request_time: u64,
+        response_time: u64,
+    ) -> bool {
+        if !self.is_storable(request, response) {
+            return false;
+        }
+        let vary = match decode_typed::<Vary>(response.headers()) {
+            Some(Ok(vary)) => match cache_key(&vary, request) {
+                Some(key) => Some((vary, key)),
+                // RFC 9111 Section 4.1: Vary: * は常に一致しないため保存しない
+                None => return false,
+            },
+            Some(Err(_)) => return false,
+            None => None,
+        };
+
+        let size = response_size(response);
+        if size > self.max_size {
+            return false;
+        }
+
+        let key = primary_key(request);
+        // RFC 9111 Section 4.1: このリクエストで選ばれる既存のエントリを置き換える
+        self.remove_matching(&key, |entry| vary_matches(&entry.vary, request));
+        self.clock += 1;
+        self.entries.entry(key).or_default().push(Entry {
+            response: response.clone(),
+            request_time,
+            response_time,
+            vary,
+            size,
+            last_used: self.clock,
+        });
+        self.size += size;
+        self.evict();
+        true
+    }
+
+    /// リクエストに使える保存済みレスポンスを探す
+    ///
+    /// GET 以外のリクエストと、一致するエントリがない場合は `None`。
+    /// `Vary` が一致するエントリが複数ある場合は最も新しく受信したものを使う (RFC 9111 Section 4.1)。
+    /// リクエストに `only-if-cached` があり `None` の場合は 504 を返すこと (RFC 9111 Section 5.2.1.7)。
+    pub fn lookup(&mut self, request: &Request, now: u64) -> Option<CacheHit<'_>> {
+        if request.method() != Method::GET {
+            return None;
+        }
+        let kind = self.kind;
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self
+            .entries
+            .get_mut(&primary_key(request))?
+            .iter_mut()
+            .filter(|entry| vary_matches(&entry.vary, request))
+            .max_by_key(|entry| entry.response_time)?;
+        entry.last_used = clock;
+
+        let timing = ResponseTiming::new(entry.request_time, entry.response_time, now);
+        let freshness = Freshness::calculate(
+            entry.response.status_code(),
+            entry.response.headers(),
+            timing,
+            kind,
+        );
+        let decision = decide(request, &freshness);
+        Some(CacheHit {
+            response: &entry.response,
+            freshness,
+            decision,
+        })
+    }
+
+    /// 304 Not Modified を受けて保存済みエントリを更新する (RFC 9111 Section 4.3.4)
+    ///
+    /// `Vary` がリクエストと一致するエントリのうち、304 の `ETag` (なければ `Last-Modified`)
+    /// と一致するものを `update_stored_response()` で更新し、受信時刻を更新する。
+    /// 304 が検証子を持たない場合は最も新しく受信したエントリを更新する。
+    /// 更新したエントリがある場合は true を返す。
+    pub fn update_from_not_modified(
+        &mut self,
+        request: &Request,
+        not_modified: &ResponseHead,
+        request_time: u64,
+        response_time: u64,
+    ) -> bool {
+        if request.method() != Method::GET || not_modified.status_code() != 304 {
+            return false;
+        }
+        let Some(entries) = self.entries.get_mut(&primary_key(request)) else {
+            return false;
+        };
+        let etag = not_modified
+            .get_header("ETag")
+            .and_then(|v| EntityTag::parse(v).ok());
+        let last_modified = not_modified.get_header("Last-Modified");
+
+        let mut selected: Vec<usize> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| vary_matches(&entry.vary, request))
+            .filter(|(_, entry)| {
+                let stored_etag = entry
+                    .response
+                    .get_header("ETag")
+                    .and_then(|v| EntityTag::parse(v).ok());
+                match (&etag, last_modified) {
+                    // 強い検証子は強い比較、弱い検証子は弱い比較で一致を判定する
+                    (Some(etag), _) if etag.is_strong() => {
+                        stored_etag.is_some_and(|stored| stored.strong_compare(etag))
+                    }
+                    (Some(etag), _) => stored_etag.is_some_and(|stored| stored.weak_compare(etag)),
+                    (None, Some(last_modified)) => {
+                        entry.response.get_header("Last-Modified") == Some(last_modified)
+                    }
+                    (None, None) => true,
+                }
+            })
+            .map(|(index, _)| index)
+            .collect();
+        // 弱い検証子や検証子なしの場合は最も新しく受信したエントリのみを更新する
+        if !etag.as_ref().is_some_and(EntityTag::is_strong)
+            && let Some(&latest) = selected
+                .iter()
+                .max_by_key(|&&index| entries[index].response_time)
+        {
+            selected = alloc::vec![latest];
+        }
+
+        let mut delta: isize = 0;
+        let mut updated = false;
+        for index in selected {
+            let entry = &mut entries[index];
+            if update_stored_response(&mut entry.response, not_modified).is_err() {
+                continue;
+            }
+            let size = response_size(&entry.response);
+            delta += size as isize - entry.size as isize;
+            entry.size = size;
+            entry.request_time = request_time;
+            entry.response_time = response_time;
+            updated = true;
+        }
+        self.size = self.size.saturating_add_signed(delta);
+        self.evict();
+        updated
+    }
+
+    /// 安全でないメソッドのレスポンスを受けて保存済みエントリを無効化する (RFC 9111 Section 4.4)
+    ///
+    /// メソッドが安全でなく、ステータスコードが 2xx / 3xx の場合に、リクエストの URI と
+    /// `Location` / `Content-Location` が同じオリジンで指す URI のエントリを削除する。
+    /// 削除した場合は true を返す。
+    pub fn invalidate(&mut self, request: &Request, response: &Response) -> bool {
+        let method_is_safe = Method::parse(request.method()).is_ok_and(|m| m.is_safe());
+        if method_is_safe || !(200..400).contains(&response.status_code()) {
+            return false;
+        }
+        let host = request_host(request);
+        let mut targets = alloc::vec![primary_key(request)];
+        for name in ["Location", "Content-Location"] {
+            // 同じオリジンの origin-form の参照のみを対象にする
+            if let Some(value) = response.get_header(name).filter(|v| v.starts_with('/')) {
+                targets.push(alloc::format!("{}{}", host, value));
+            }
+        }
+        let mut removed = false;
+        for key in targets {
+            if let Some(entries) = self.entries.remove(&key) {
+                self.size -= entries.iter().map(|e| e.size).sum::<usize>();
+                removed = true;
+            }
+        }
+        removed
+    }
+
+    /// RFC 9111 Section 3 の保存条件を満たすか
+    fn is_storable(&self, request: &Request, response: &Response) -> bool {
+        if request.method() != Method::GET {
+            return false;
+        }
+        // 206 は部分レスポンスの結合に、304 は保存済みレスポンスの更新に使うため保存しない
+        let status_code = response.status_code();
+        if !(200..600).contains(&status_code) || status_code == 206 || status_code == 304 {
+            return false;
+        }
+        let request_cc = cache_control(request.headers());
+        let Some(response_cc) = cache_control(response.headers()) else {
+            return false;
+        };
+        if request_cc.as_ref().is_some_and(CacheControl::is_no_store) || response_cc.is_no_store() {
+            return false;
+        }
+        let shared = self.kind == CacheKind::Shared;
+        if shared && response_cc.is_private() && response_cc.private_fields().is_empty() {
+            return false;
+        }
+        // RFC 9111 Section 3.5: 共有キャッシュは Authorization 付きのリクエストへの
+        // レスポンスを明示的に許可された場合のみ保存できる
+        if shared
+            && request.has_header("Authorization")
+            && !(response_cc.is_must_revalidate()
+                || response_cc.is_public()
+                || response_cc.s_maxage().is_some())
+        {
+            return false;
+        }
+        response.has_header("Expires")
+            || response_cc.max_age().is_some()
+            || (shared && response_cc.s_maxage().is_some())
+            || response_cc.is_public()
+            || (!shared && response_cc.is_private())
+            || is_heuristically_cacheable(status_code)
+    }
+
+    /// 条件に一致するエントリを削除する
+    fn remove_matching(&mut self, key: &str, mut predicate: impl FnMut(&Entry) -> bool) {
+        let Some(entries) = self.entries.get_mut(key) else {
+            return;
+        };
+        let mut removed = 0;
+        entries.retain(|entry| {
+            let matched = predicate(entry);
+            if matched {
+                removed += entry.size;
+            }
+            !matched
+        });
+        if entries.is_empty() {
+            self.entries.remove(key);
+        }
+        self.size -= removed;
+    }
+
+    /// 合計サイズが上限を超えている間、最も長く使われていないエントリを削除する
+    fn evict(&mut self) {
+        while self.size > self.max_size {
+            let Some((key, index)) = self
+                .entries
+                .iter()
+                .flat_map(|(key, entries)| {
+                    entries
+                        .iter()
+                        .enumerate()
+                        .map(move |(i, e)| (e.last_used, key, i))
+                })
+                .min_by_key(|(last_used, _, _)| *last_used)
+                .map(|(_, key, index)| (key.clone(), index))
+            else {
+                return;
+            };
+            if let Some(entries) = self.entries.get_mut(&key) {
+                let entry = entries.remove(index);
+                self.size -= entry.size;
+                if entries.is_empty() {
+                    self.entries.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+/// 304 Not Modified のヘッダーで保存済みレスポンスのヘッダーを更新する (RFC 9111 Section 3.2)
+///
+/// 304 に含まれる各フィールドで、保存済みレスポンスの同名のフィールドを置き換える
+/// (同名のフィールドが複数行ある場合はすべての行で置き換える)。
+/// 次のフィールドは更新しない:
+///
+/// - `Content-Length` (304 の値は保存済みのボディの長さと一致しない)
+/// - hop-by-hop フィールド (`Connection` とそこで指定されたフィールド、`Keep-Alive`、
+///   `Proxy-Connection`、`TE`、`Transfer-Encoding`、`Upgrade`) (RFC 9111 Section 3.1)
+/// - 304 の `Cache-Control` の `no-cache` / `private` の修飾形式で指定されたフィールド
+///
+/// いずれかの値が不正な場合はエラーを返し、`stored` は変更しない。
+pub fn update_stored_response(
+    stored: &mut Response,
+    not_modified: &ResponseHead,
+) -> Result<(), EncodeError> {
+    let headers = not_modified.headers();
+    let connection_options: Vec<&str> = not_modified
+        .get_headers("Connection")
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    let (no_cache_fields, private_fields) = match decode_typed::<CacheControl>(headers) {
+        Some(Ok(cc)) => (cc.no_cache_fields().to_vec(), cc.private_fields().to_vec()),
+        _ => (Vec::new(), Vec::new()),
+    };
+    let is_excluded = |name: &str| {
+        [
+            "Content-Length",
+            "Connection",
+            "Keep-Alive",
+            "Proxy-Connection",
+            "TE",
+            "Transfer-Encoding",
+            "Upgrade",
+        ]
+        .iter()
+        .chain(connection_options.iter())
+        .any(|n| n.eq_ignore_ascii_case(name))
+            || no_cache_fields
+                .iter()
+                .chain(private_fields.iter())
+                .any(|n| n.eq_ignore_ascii_case(name))
+    };
+
+    // アトミック性のため、複製に適用してから置き換える
+    let mut updated = stored.clone();
+    let mut replaced: Vec<&str> = Vec::new();
+    for (name, value) in headers {
+        if is_excluded(name) {
+            continue;
+        }
+        if replaced.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            updated.add_header(name.as_ref(), value.as_str())?;
+        } else {
+            updated.set_header(name.as_ref(), value.as_str())?;
+            replaced.push(name);
+        }
+    }
+    *stored = updated;
+    Ok(())
+}
+
+/// リクエストと鮮度から保存済みレスポンスの使い方を決める
+fn decide(request: &Request, freshness: &Freshness) -> CacheDecision {
+    // 不正な Cache-Control は指定がないものとして扱う
+    let request_cc = cache_control(request.headers()).unwrap_or_default();
+    // RFC 9111 Section 5.4: Cache-Control がない場合は Pragma: no-cache を no-cache とみなす
+    let pragma_no_cache = !request.has_header("Cache-Control")
+        && request.get_headers("Pragma").iter().any(|value| {
+            value
+                .split(',')
+                .any(|d| d.trim().eq_ignore_ascii_case("no-cache"))
+        });
+    if request_cc.is_no_cache() || pragma_no_cache {
+        return CacheDecision::MustRevalidate;
+    }
+
+    // RFC 9111 Section 5.2.1.1 / 5.2.1.3: max-age / min-fresh を満たさない場合は使えない
+    let fresh_enough = !freshness.requires_revalidation()
+        && request_cc
+            .max_age()
+            .is_none_or(|max_age| freshness.current_age() <= max_age)
+        && request_cc
+            .min_fresh()
+            .is_none_or(|min_fresh| freshness.time_to_live() >= min_fresh);
+    if fresh_enough {
+        return CacheDecision::Fresh;
+    }
+
+    // RFC 9111 Section 5.2.1.2: max-stale で許容された古さまで使える
+    let within_max_stale = request_cc
+        .max_stale()
+        .is_some_and(|max_stale| freshness.staleness() <= max_stale);
+    if freshness.allows_stale()
+        && (within_max_stale || freshness.is_within_stale_while_revalidate())
+    {
+        CacheDecision::Stale
+    } else {
+        CacheDecision::MustRevalidate
+    }
+}
+
+/// Cache-Control を取得する。ヘッダーがない場合は空、不正な場合は `None`
+fn cache_control(headers: &[(Cow<'static, str>, String)]) -> Option<CacheControl> {
+    match decode_typed::<CacheControl>(headers) {
+        Some(result) => result.ok(),
+        None => Some(CacheControl::new()),
+    }
+}
+
+/// 一次キー
+///
+/// origin-form の場合は `Host` (小文字) とリクエストターゲットを連結する。
+/// absolute-form の場合はリクエストターゲットをそのまま使う。
+fn primary_key(request: &Request) -> String {
+    let uri = request.uri();
+    if uri.starts_with('/') {
+        alloc::format!("{}{}", request_host(request), uri)
+    } else {
+        uri.to_string()
+    }
+}
+
+fn request_host(request: &Request) -> String {
+    request
+        .get_header("Host")
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default()
+}
+
+/// 保存時の Vary の二次キーがリクエストと一致するか
+fn vary_matches(vary: &Option<(Vary, String)>, request: &Request) -> bool {
+    match vary {
+        Some((vary, key)) => cache_key(vary, request).as_ref() == Some(key),
+        None => true,
+    }
+}
+
+/// エントリのサイズの見積もり
+fn response_size(response: &Response) -> usize {
+    let headers: usize = response
+        .headers()
+        .iter()
+        .map(|(name, value)| name.len() + value.len())
+        .sum();
+    headers + response.body_bytes().map_or(0, <[u8]>::len)
+}
@@ -0,0 +1,373 @@
+//! HTTP メッセージの正規化と差分
+//!
+//! ## 概要
+//!
+//! `Request` / `Response` をポリシーに従って正規化し、2 つのメッセージの差分を
+//! 人が読める形式で出力します。プロキシ経由のトラフィックと元のトラフィックを
+//! 比較する結合テストで、失敗時に原因を特定しやすくするために使う。
+//!
+//! 正規化は以下を行う。
+//!
+//! - field-value 前後の OWS を除去する
+//! - field-name を小文字にする (`with_lowercase_names(false)` で無効化)
+//! - ヘッダーを field-name で安定ソートする (`HeaderOrder::Preserve` で無効化)
+//!   - 同名ヘッダー間の順序は意味を持つため維持する (RFC 9110 Section 5.3)
+//! - `with_ignored_header()` で指定したヘッダーを除外する
+//!   - Date や Via のように経路で変わるヘッダーを比較対象から外す
+//!
+//! ボディがない場合と空のボディは区別しない。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::Request;
+//! use shiguredo_http11::canonical::Canonicalizer;
+//!
+//! let original = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("Host", "example.com")
+//!     .unwrap()
+//!     .header("Accept", "*/*")
+//!     .unwrap();
+//! let proxied = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("accept", "*/*")
+//!     .unwrap()
+//!     .header("Host", "example.com")
+//!     .unwrap()
+//!     .header("Via", "1.1 proxy")
+//!     .unwrap();
+//!
+//! let canonicalizer = Canonicalizer::new();
+//! let diff = canonicalizer.diff_requests(&original, &proxied).unwrap();
+//! assert_eq!(
+//!     diff.to_string(),
+//!     "--- left\n+++ right\n \
+//!      GET / HTTP/1.1\n \
+//!      accept: */*\n \
+//!      host: example.com\n\
+//!      +via: 1.1 proxy\n"
+//! );
+//!
+//! let canonicalizer = canonicalizer.with_ignored_header("Via");
+//! assert!(canonicalizer.diff_requests(&original, &proxied).is_none());
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::request::Request;
+use crate::response::Response;
+use crate::validate::trim_ows;
+
+/// ヘッダーの並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderOrder {
+    /// field-name で安定ソートする
+    #[default]
+    SortByName,
+    /// 元の順序を維持する
+    Preserve,
+}
+
+/// メッセージの正規化ポリシー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canonicalizer {
+    order: HeaderOrder,
+    lowercase_names: bool,
+    ignored_headers: Vec<String>,
+}
+
+impl Default for Canonicalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Canonicalizer {
+    /// デフォルトのポリシーを作成
+    ///
+    /// field-name を小文字にし、field-name でソートする。除外するヘッダーはない。
+    pub fn new() -> Self {
+        Self {
+            order: HeaderOrder::default(),
+            lowercase_names: true,
+            ignored_headers: Vec::new(),
+        }
+    }
+
+    /// ヘッダーの並び順を設定
+    pub fn with_header_order(mut self, order: HeaderOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// field-name を小文字にするかを設定
+    pub fn with_lowercase_names(mut self, lowercase: bool) -> Self {
+        self.lowercase_names = lowercase;
+        self
+    }
+
+    /// 比較から除外するヘッダーを追加 (大文字小文字を区別しない)
+    pub fn with_ignored_header(mut self, name: &str) -> Self {
+        self.ignored_headers.push(name.to_ascii_lowercase());
+        self
+    }
+
+    /// リクエストを正規化
+    pub fn request(&self, request: &Request) -> CanonicalMessage {
+        let start_line = format!(
+            "{} {} {}",
+            request.method(),
+            request.uri(),
+            HttpHead::version(request)
+        );
+        self.canonicalize(
+            start_line,
+            request.headers(),
+            request.body_bytes().unwrap_or_default(),
+        )
+    }
+
+    /// レスポンスを正規化
+    pub fn response(&self, response: &Response) -> CanonicalMessage {
+        let start_line = format!(
+            "{} {} {}",
+            response.version(),
+            response.status_code(),
+            response.reason_phrase()
+        );
+        self.canonicalize(
+            start_line,
+            response.headers(),
+            response.body_bytes().unwrap_or_default(),
+        )
+    }
+
+    /// 2 つのリクエストを正規化して差分を取る
+    ///
+    /// 一致する場合は `None` を返す。
+    pub fn diff_requests(&self, left: &Request, right: &Request) -> Option<MessageDiff> {
+        self.request(left).diff(&self.request(right))
+    }
+
+    /// 2 つのレスポンスを正規化して差分を取る
+    ///
+    /// 一致する場合は `None` を返す。
+    pub fn diff_responses(&self, left: &Response, right: &Response) -> Option<MessageDiff> {
+        self.response(left).diff(&self.response(right))
+    }
+
+    fn canonicalize(
+        &self,
+        start_line: String,
+        headers: &[(Cow<'static, str>, String)],
+        body: &[u8],
+    ) -> CanonicalMessage {
+        let mut headers: Vec<(String, String)> = headers
+            .iter()
+            .filter(|(name, _)| {
+                !self
+                    .ignored_headers
+                    .iter()
+                    .any(|ignored| name.eq_ignore_ascii_case(ignored))
+            })
+            .map(|(name, value)| {
+                let name = if self.lowercase_names {
+                    name.to_ascii_lowercase()
+                } else {
+                    name.to_string()
+                };
+                (name, trim_ows(value).to_string())
+            })
+            .collect();
+        if self.order == HeaderOrder::SortByName {
+            // 同名ヘッダー間の順序を維持するため安定ソートを使う
+            headers.sort_by_key(|(name, _)| name.to_ascii_lowercase());
+        }
+        CanonicalMessage {
+            start_line,
+            headers,
+            body: body.to_vec(),
+        }
+    }
+}
+
+/// 正規化したメッセージ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalMessage {
+    start_line: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl CanonicalMessage {
+    /// start-line (CRLF を除く)
+    pub fn start_line(&self) -> &str {
+        &self.start_line
+    }
+
+    /// 正規化したヘッダー
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// ボディ (ボディがない場合は空)
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// 差分を取る
+    ///
+    /// 一致する場合は `None` を返す。
+    pub fn diff(&self, other: &CanonicalMessage) -> Option<MessageDiff> {
+        if self == other {
+            return None;
+        }
+        let mut lines = Vec::new();
+        if self.start_line == other.start_line {
+            lines.push(DiffLine::Same(self.start_line.clone()));
+        } else {
+            lines.push(DiffLine::Removed(self.start_line.clone()));
+            lines.push(DiffLine::Added(other.start_line.clone()));
+        }
+        lines.extend(diff_lines(&self.header_lines(), &other.header_lines()));
+
+        let body = (self.body != other.body).then(|| BodyDiff {
+            left_len: self.body.len(),
+            right_len: other.body.len(),
+            offset: self
+                .body
+                .iter()
+                .zip(&other.body)
+                .position(|(a, b)| a != b)
+                .unwrap_or(self.body.len().min(other.body.len())),
+        });
+        Some(MessageDiff { lines, body })
+    }
+
+    fn header_lines(&self) -> Vec<String> {
+        self.headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}"))
+            .collect()
+    }
+}
+
+impl fmt::Display for CanonicalMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.start_line)?;
+        for (name, value) in &self.headers {
+            writeln!(f, "{name}: {value}")?;
+        }
+        writeln!(f)?;
+        write!(f, "{}", String::from_utf8_lossy(&self.body))
+    }
+}
+
+/// 差分の行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// 両方にある行
+    Same(String),
+    /// 左側にのみある行
+    Removed(String),
+    /// 右側にのみある行
+    Added(String),
+}
+
+/// ボディの差分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyDiff {
+    /// 左側のボディの長さ
+    pub left_len: usize,
+    /// 右側のボディの長さ
+    pub right_len: usize,
+    /// 最初に異なるバイトの位置
+    ///
+    /// 一方が他方の先頭部分の場合は短い方の長さ。
+    pub offset: usize,
+}
+
+/// 2 つのメッセージの差分
+///
+/// `Display` は unified diff に似た形式で start-line とヘッダーの差分を出力し、
+/// ボディが異なる場合は長さと最初に異なる位置を出力する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageDiff {
+    lines: Vec<DiffLine>,
+    body: Option<BodyDiff>,
+}
+
+impl MessageDiff {
+    /// start-line とヘッダーの差分の行
+    pub fn lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+
+    /// ボディの差分 (ボディが一致する場合は `None`)
+    pub fn body(&self) -> Option<&BodyDiff> {
+        self.body.as_ref()
+    }
+}
+
+impl fmt::Display for MessageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- left")?;
+        writeln!(f, "+++ right")?;
+        for line in &self.lines {
+            match line {
+                DiffLine::Same(line) => writeln!(f, " {line}")?,
+                DiffLine::Removed(line) => writeln!(f, "-{line}")?,
+                DiffLine::Added(line) => writeln!(f, "+{line}")?,
+            }
+        }
+        if let Some(body) = &self.body {
+            writeln!(
+                f,
+                "body differs: length {} != {}, first difference at byte {}",
+                body.left_len, body.right_len, body.offset
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 最長共通部分列で行の差分を取る
+fn diff_lines(left: &[String], right: &[String]) -> Vec<DiffLine> {
+    // lcs[i][j] は left[i..] と right[j..] の最長共通部分列の長さ
+    let mut lcs = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            lcs[i][j] = if left[i] == right[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            lines.push(DiffLine::Same(left[i].clone()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(left[i].clone()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(right[j].clone()));
+            j += 1;
+        }
+    }
+    lines.extend(left[i..].iter().cloned().map(DiffLine::Removed));
+    lines.extend(right[j..].iter().cloned().map(DiffLine::Added));
+    lines
+}
@@ -0,0 +1,250 @@
+//! charset を考慮したボディのテキストデコード
+//!
+//! ## 概要
+//!
+//! `Content-Type` の charset パラメータ (RFC 9110 Section 8.3.2) に従って
+//! ボディを `String` にデコードする。
+//!
+//! 外部依存なしで扱える以下の charset のみサポートする。
+//!
+//! - UTF-8 (先頭の BOM は取り除く)
+//! - US-ASCII
+//! - ISO-8859-1 (各バイトを U+0000 から U+00FF にそのまま対応させる)
+//!
+//! charset パラメータがない場合は UTF-8 として扱う。
+//! US-ASCII は UTF-8 の部分集合のため、RFC 6657 の text/plain の既定値
+//! (US-ASCII) とも矛盾しない。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::charset::{Charset, decode_text, decode_text_lossy};
+//! use shiguredo_http11::content_type::ContentType;
+//!
+//! let ct = ContentType::parse("text/plain; charset=ISO-8859-1").unwrap();
+//! assert_eq!(Charset::from_content_type(&ct), Ok(Charset::Iso8859_1));
+//! assert_eq!(decode_text(Some(&ct), b"caf\xe9").unwrap(), "café");
+//!
+//! // charset がない場合は UTF-8
+//! assert_eq!(decode_text(None, "日本語".as_bytes()).unwrap(), "日本語");
+//!
+//! // 不正なバイト列やサポートしていない charset は置換文字で読み飛ばせる
+//! let ct = ContentType::parse("text/plain; charset=shift_jis").unwrap();
+//! assert!(decode_text(Some(&ct), b"abc").is_err());
+//! assert_eq!(decode_text_lossy(Some(&ct), b"abc\xff"), "abc\u{FFFD}");
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::content_type::ContentType;
+use crate::request::Request;
+use crate::response::Response;
+
+/// テキストデコードエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CharsetError {
+    /// サポートしていない charset
+    UnsupportedCharset { charset: String },
+    /// charset として不正なバイト列
+    InvalidEncoding { charset: Charset },
+}
+
+impl fmt::Display for CharsetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CharsetError::UnsupportedCharset { charset } => {
+                write!(f, "unsupported charset: {:?}", charset)
+            }
+            CharsetError::InvalidEncoding { charset } => {
+                write!(f, "invalid {} byte sequence", charset)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CharsetError {}
+
+/// サポートしている charset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// UTF-8 (RFC 3629)
+    Utf8,
+    /// US-ASCII
+    UsAscii,
+    /// ISO-8859-1 (Latin-1)
+    Iso8859_1,
+}
+
+impl Charset {
+    /// charset 名からパース
+    ///
+    /// 大文字小文字を区別せず、IANA Character Sets レジストリの別名も受け付ける。
+    pub fn from_label(label: &str) -> Option<Self> {
+        const UTF8: &[&str] = &["utf-8", "utf8", "csutf8", "unicode-1-1-utf-8"];
+        const US_ASCII: &[&str] = &[
+            "us-ascii",
+            "ascii",
+            "us",
+            "iso646-us",
+            "iso-ir-6",
+            "ansi_x3.4-1968",
+            "ansi_x3.4-1986",
+            "iso_646.irv:1991",
+            "ibm367",
+            "cp367",
+            "csascii",
+        ];
+        const ISO_8859_1: &[&str] = &[
+            "iso-8859-1",
+            "iso_8859-1",
+            "iso_8859-1:1987",
+            "iso-ir-100",
+            "latin1",
+            "l1",
+            "ibm819",
+            "cp819",
+            "csisolatin1",
+        ];
+
+        let label = label.trim();
+        let matches = |aliases: &[&str]| aliases.iter().any(|a| a.eq_ignore_ascii_case(label));
+        if matches(UTF8) {
+            Some(Charset::Utf8)
+        } else if matches(US_ASCII) {
+            Some(Charset::UsAscii)
+        } else if matches(ISO_8859_1) {
+            Some(Charset::Iso8859_1)
+        } else {
+            None
+        }
+    }
+
+    /// Content-Type の charset パラメータから取得
+    ///
+    /// charset パラメータがない場合は `Charset::Utf8` を返す。
+    pub fn from_content_type(content_type: &ContentType) -> Result<Self, CharsetError> {
+        match content_type.charset() {
+            Some(label) => {
+                Self::from_label(label).ok_or_else(|| CharsetError::UnsupportedCharset {
+                    charset: label.to_string(),
+                })
+            }
+            None => Ok(Charset::Utf8),
+        }
+    }
+
+    /// 正規の charset 名を取得
+    pub fn name(&self) -> &'static str {
+        match self {
+            Charset::Utf8 => "UTF-8",
+            Charset::UsAscii => "US-ASCII",
+            Charset::Iso8859_1 => "ISO-8859-1",
+        }
+    }
+
+    /// バイト列をデコード
+    ///
+    /// charset として不正なバイト列を含む場合は `CharsetError::InvalidEncoding` を返す。
+    pub fn decode(&self, bytes: &[u8]) -> Result<String, CharsetError> {
+        let invalid = || CharsetError::InvalidEncoding { charset: *self };
+        match self {
+            Charset::Utf8 => core::str::from_utf8(strip_utf8_bom(bytes))
+                .map(ToString::to_string)
+                .map_err(|_| invalid()),
+            Charset::UsAscii => {
+                if !bytes.is_ascii() {
+                    return Err(invalid());
+                }
+                Ok(bytes.iter().map(|&b| b as char).collect())
+            }
+            Charset::Iso8859_1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+        }
+    }
+
+    /// バイト列をデコード (不正なバイトは U+FFFD に置き換える)
+    pub fn decode_lossy(&self, bytes: &[u8]) -> String {
+        match self {
+            Charset::Utf8 => String::from_utf8_lossy(strip_utf8_bom(bytes)).into_owned(),
+            Charset::UsAscii => bytes
+                .iter()
+                .map(|&b| if b.is_ascii() { b as char } else { '\u{FFFD}' })
+                .collect(),
+            Charset::Iso8859_1 => bytes.iter().map(|&b| b as char).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Content-Type の charset に従ってボディをデコード
+///
+/// `content_type` が None または charset パラメータがない場合は UTF-8 として扱う。
+pub fn decode_text(
+    content_type: Option<&ContentType>,
+    body: &[u8],
+) -> Result<String, CharsetError> {
+    let charset = match content_type {
+        Some(ct) => Charset::from_content_type(ct)?,
+        None => Charset::Utf8,
+    };
+    charset.decode(body)
+}
+
+/// Content-Type の charset に従ってボディをデコード (失敗しない)
+///
+/// サポートしていない charset の場合は UTF-8 として扱い、
+/// 不正なバイトは U+FFFD に置き換える。
+pub fn decode_text_lossy(content_type: Option<&ContentType>, body: &[u8]) -> String {
+    let charset = content_type
+        .and_then(|ct| Charset::from_content_type(ct).ok())
+        .unwrap_or(Charset::Utf8);
+    charset.decode_lossy(body)
+}
+
+/// UTF-8 の BOM (U+FEFF) を取り除く
+fn strip_utf8_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes)
+}
+
+/// `Content-Type` ヘッダー値をパースする (不正な値は None として扱う)
+fn parse_content_type(value: Option<&str>) -> Option<ContentType> {
+    value.and_then(|v| ContentType::parse(v).ok())
+}
+
+impl Request {
+    /// ボディを `Content-Type` の charset に従ってテキストとしてデコードする
+    ///
+    /// `Content-Type` がない、またはパースできない場合は UTF-8 として扱う。
+    pub fn body_text(&self) -> Result<String, CharsetError> {
+        let content_type = parse_content_type(self.get_header("Content-Type"));
+        decode_text(content_type.as_ref(), self.body_bytes().unwrap_or(&[]))
+    }
+
+    /// ボディをテキストとしてデコードする (不正なバイトは U+FFFD に置き換える)
+    pub fn body_text_lossy(&self) -> String {
+        let content_type = parse_content_type(self.get_header("Content-Type"));
+        decode_text_lossy(content_type.as_ref(), self.body_bytes().unwrap_or(&[]))
+    }
+}
+
+impl Response {
+    /// ボディを `Content-Type` の charset に従ってテキストとしてデコードする
+    ///
+    /// `Content-Type` がない、またはパースできない場合は UTF-8 として扱う。
+    pub fn body_text(&self) -> Result<String, CharsetError> {
+        let content_type = parse_content_type(self.get_header("Content-Type"));
+        decode_text(content_type.as_ref(), self.body_bytes().unwrap_or(&[]))
+    }
+
+    /// ボディをテキストとしてデコードする (不正なバイトは U+FFFD に置き換える)
+    pub fn body_text_lossy(&self) -> String {
+        let content_type = parse_content_type(self.get_header("Content-Type"));
+        decode_text_lossy(content_type.as_ref(), self.body_bytes().unwrap_or(&[]))
+    }
+}
@@ -0,0 +1,216 @@
+//! 複数の展開器の連結
+//!
+//! `Content-Encoding: gzip, br` のように複数のコーディングが適用されたボディを
+//! 1 つの `Decompressor` として展開する (RFC 9110 Section 8.4)。
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{CompressionError, CompressionStatus, Decompressor};
+
+/// 中間バッファのサイズ
+const BUFFER_SIZE: usize = 8192;
+
+/// 展開器の間の中間バッファ (`data[start..end]` が未処理)
+struct Buffer {
+    data: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl Buffer {
+    fn unread(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+}
+
+/// 展開器を連結した展開器
+///
+/// 展開器は展開する順 (Content-Encoding の並びの逆順) に渡す。
+/// 前段の展開器の出力を次段の展開器の入力にする。
+/// すべての展開器が完了したときに `Complete` を返す。
+///
+/// ```rust
+/// use shiguredo_http11::compression::{Decompressor, DecompressorChain, NoCompression};
+///
+/// let mut chain = DecompressorChain::new(vec![
+///     Box::new(NoCompression::new()),
+///     Box::new(NoCompression::new()),
+/// ]);
+/// let mut output = [0u8; 16];
+/// let status = chain.decompress(b"hello", &mut output).unwrap();
+/// assert_eq!(&output[..status.produced()], b"hello");
+/// ```
+pub struct DecompressorChain {
+    decompressors: Vec<Box<dyn Decompressor>>,
+    /// `buffers[i]` は `decompressors[i]` の出力 (最後の展開器の分はない)
+    buffers: Vec<Buffer>,
+    /// 展開器が完了したかどうか
+    complete: Vec<bool>,
+    /// 展開器が出力バッファ不足で止まっているかどうか
+    pending: Vec<bool>,
+}
+
+impl fmt::Debug for DecompressorChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecompressorChain")
+            .field("len", &self.decompressors.len())
+            .field("complete", &self.complete)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+impl DecompressorChain {
+    /// 展開する順に並べた展開器から作成
+    pub fn new(decompressors: Vec<Box<dyn Decompressor>>) -> Self {
+        let n = decompressors.len();
+        let buffers = (0..n.saturating_sub(1))
+            .map(|_| Buffer {
+                data: vec![0; BUFFER_SIZE],
+                start: 0,
+                end: 0,
+            })
+            .collect();
+        Self {
+            decompressors,
+            buffers,
+            complete: vec![false; n],
+            pending: vec![false; n],
+        }
+    }
+
+    /// 連結している展開器の数
+    pub fn len(&self) -> usize {
+        self.decompressors.len()
+    }
+
+    /// 展開器が 1 つもないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.decompressors.is_empty()
+    }
+}
+
+impl Decompressor for DecompressorChain {
+    fn decompress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        let n = self.decompressors.len();
+        if n == 0 {
+            // 展開器がない場合はそのままコピーする
+            let len = input.len().min(output.len());
+            output[..len].copy_from_slice(&input[..len]);
+            return Ok(if len < input.len() {
+                CompressionStatus::OutputFull {
+                    consumed: len,
+                    produced: len,
+                }
+            } else {
+                CompressionStatus::Continue {
+                    consumed: len,
+                    produced: len,
+                }
+            });
+        }
+
+        // 空の入力は終端の通知 (内部データの drain) として扱う
+        let draining = input.is_empty();
+        let mut consumed = 0;
+        let mut produced = 0;
+        loop {
+            let mut progressed = false;
+
+            // 中間の展開器: 出力先の中間バッファが空になったら次を展開する
+            for i in 0..n - 1 {
+                let (before, after) = self.buffers.split_at_mut(i);
+                let out = &mut after[0];
+                if out.start < out.end {
+                    if self.complete[i + 1] {
+                        // 後段が完了した後にデータが残っている
+                        return Err(CompressionError::InvalidData(
+                            "trailing data after inner content coding".into(),
+                        ));
+                    }
+                    continue;
+                }
+                if self.complete[i] {
+                    continue;
+                }
+                let src = match before.last() {
+                    Some(prev) => prev.unread(),
+                    None => &input[consumed..],
+                };
+                // 前段が終わっていないのに空の入力を渡すと終端と誤認されるため呼ばない
+                let upstream_finished = if i == 0 {
+                    draining
+                } else {
+                    self.complete[i - 1]
+                };
+                if src.is_empty() && !upstream_finished && !self.pending[i] {
+                    continue;
+                }
+                let status = self.decompressors[i].decompress(src, &mut out.data)?;
+                out.start = 0;
+                out.end = status.produced();
+                match before.last_mut() {
+                    Some(prev) => prev.start += status.consumed(),
+                    None => consumed += status.consumed(),
+                }
+                self.complete[i] = status.is_complete();
+                self.pending[i] = status.is_output_full();
+                progressed |= status.consumed() > 0 || status.produced() > 0;
+            }
+
+            // 最後の展開器: 呼び出し元の出力バッファに書き込む
+            let last = n - 1;
+            let src = match self.buffers.last() {
+                Some(prev) => prev.unread(),
+                None => &input[consumed..],
+            };
+            let upstream_finished = if last == 0 {
+                draining
+            } else {
+                self.complete[last - 1]
+            };
+            if !self.complete[last] && (!src.is_empty() || upstream_finished || self.pending[last])
+            {
+                let status = self.decompressors[last].decompress(src, &mut output[produced..])?;
+                match self.buffers.last_mut() {
+                    Some(prev) => prev.start += status.consumed(),
+                    None => consumed += status.consumed(),
+                }
+                produced += status.produced();
+                self.pending[last] = status.is_output_full();
+                progressed |= status.consumed() > 0 || status.produced() > 0;
+
+                self.complete[last] = status.is_complete();
+            }
+            // 前段のトレーラー (gzip の CRC-32 など) まで読み終えたら完了
+            if self.complete.iter().all(|complete| *complete) {
+                return Ok(CompressionStatus::Complete { consumed, produced });
+            }
+            if produced == output.len() {
+                return Ok(CompressionStatus::OutputFull { consumed, produced });
+            }
+            if !progressed {
+                return Ok(CompressionStatus::Continue { consumed, produced });
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        for decompressor in &mut self.decompressors {
+            decompressor.reset();
+        }
+        for buffer in &mut self.buffers {
+            buffer.start = 0;
+            buffer.end = 0;
+        }
+        self.complete.fill(false);
+        self.pending.fill(false);
+    }
+}
@@ -0,0 +1,80 @@
+//! CRC-32 (RFC 1952 Section 8) / Adler-32 (RFC 1950 Section 9)
+
+/// CRC-32 のテーブル (多項式 0xEDB88320)
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32 の逐次計算 (gzip)
+#[derive(Debug, Clone)]
+pub(crate) struct Crc32 {
+    value: u32,
+}
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self { value: 0xFFFF_FFFF }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.value = CRC32_TABLE[((self.value ^ b as u32) & 0xFF) as usize] ^ (self.value >> 8);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        !self.value
+    }
+}
+
+/// Adler-32 の法
+const ADLER_MOD: u32 = 65521;
+
+/// 剰余を取らずに加算できる最大バイト数 (zlib の NMAX)
+const ADLER_NMAX: usize = 5552;
+
+/// Adler-32 の逐次計算 (zlib)
+#[derive(Debug, Clone)]
+pub(crate) struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    pub(crate) fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(ADLER_NMAX) {
+            for &x in chunk {
+                self.a += x as u32;
+                self.b += self.a;
+            }
+            self.a %= ADLER_MOD;
+            self.b %= ADLER_MOD;
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
@@ -0,0 +1,1239 @@
+//! DEFLATE (RFC 1951) / zlib (RFC 1950) の依存なし実装
+//!
+//! ## 仕様
+//!
+//! - 圧縮は LZ77 (ハッシュチェーン) と固定ハフマン符号のみを使う。
+//!   固定ハフマン符号で大きくなるブロックは stored ブロックで出力する
+//! - 展開は stored / 固定ハフマン / 動的ハフマンのすべてのブロックに対応する
+//! - HTTP の `deflate` は zlib 形式で包んだ DEFLATE を指す (RFC 9110 Section 8.4.1.2)。
+//!   zlib ヘッダーのない raw DEFLATE を送る実装があるため、展開時は先頭 2 バイトで判別する
+
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::checksum::{Adler32, Crc32};
+use super::{CompressionError, CompressionStatus, Compressor, Decompressor};
+
+/// スライディングウィンドウのサイズ
+const WINDOW_SIZE: usize = 32 * 1024;
+const WINDOW_MASK: usize = WINDOW_SIZE - 1;
+/// 1 ブロックで圧縮する入力の最大バイト数 (stored ブロックの上限 65535 以下)
+const BLOCK_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+const HASH_SIZE: usize = 1 << HASH_BITS;
+/// 一致を探索するハッシュチェーンの最大長
+const MAX_CHAIN: usize = 64;
+/// 展開時に内部に保持する未処理入力の上限
+const MAX_INPUT_BUFFER: usize = 64 * 1024;
+
+/// 長さ符号 257..=285 の基準値 (RFC 1951 Section 3.2.5)
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+/// 長さ符号の拡張ビット数
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// 距離符号 0..=29 の基準値
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+/// 距離符号の拡張ビット数
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// 符号長符号の並び順 (RFC 1951 Section 3.2.7)
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// gzip ヘッダーのフラグ (RFC 1952 Section 2.3.1)
+const GZIP_FHCRC: u8 = 0x02;
+const GZIP_FEXTRA: u8 = 0x04;
+const GZIP_FNAME: u8 = 0x08;
+const GZIP_FCOMMENT: u8 = 0x10;
+/// 予約ビット
+const GZIP_RESERVED: u8 = 0xE0;
+
+/// 圧縮データの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// ヘッダーなしの DEFLATE
+    Raw,
+    /// zlib (RFC 1950)
+    Zlib,
+    /// gzip (RFC 1952)
+    Gzip,
+    /// zlib または raw DEFLATE (展開のみ、先頭 2 バイトで判別する)
+    ZlibOrRaw,
+}
+
+fn invalid(message: &str) -> CompressionError {
+    CompressionError::InvalidData(message.to_string())
+}
+
+/// 形式に対応するチェックサム
+#[derive(Debug, Clone)]
+enum Checksum {
+    None,
+    Adler32(Adler32),
+    Crc32(Crc32),
+}
+
+impl Checksum {
+    fn for_format(format: Format) -> Self {
+        match format {
+            Format::Raw => Checksum::None,
+            Format::Zlib | Format::ZlibOrRaw => Checksum::Adler32(Adler32::new()),
+            Format::Gzip => Checksum::Crc32(Crc32::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Checksum::None => {}
+            Checksum::Adler32(adler) => adler.update(data),
+            Checksum::Crc32(crc) => crc.update(data),
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        match self {
+            Checksum::None => 0,
+            Checksum::Adler32(adler) => adler.finish(),
+            Checksum::Crc32(crc) => crc.finish(),
+        }
+    }
+}
+
+// ========================================
+// 圧縮
+// ========================================
+
+/// LSB ファーストのビット書き込み
+#[derive(Debug, Clone, Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    out_pos: usize,
+    bit_buf: u64,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.bit_buf |= (value as u64) << self.bit_count;
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.out.push(self.bit_buf as u8);
+            self.bit_buf >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// ハフマン符号を書き込む (符号は MSB から詰めるためビットを反転する)
+    fn write_code(&mut self, code: u32, len: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - len), len);
+    }
+
+    fn align(&mut self) {
+        if self.bit_count > 0 {
+            self.write_bits(0, 8 - self.bit_count);
+        }
+    }
+
+    fn write_bytes(&mut self, data: &[u8]) {
+        debug_assert_eq!(self.bit_count, 0);
+        self.out.extend_from_slice(data);
+    }
+
+    fn has_pending(&self) -> bool {
+        self.out_pos < self.out.len()
+    }
+
+    fn drain(&mut self, output: &mut [u8]) -> usize {
+        let n = (self.out.len() - self.out_pos).min(output.len());
+        output[..n].copy_from_slice(&self.out[self.out_pos..self.out_pos + n]);
+        self.out_pos += n;
+        if self.out_pos == self.out.len() {
+            self.out.clear();
+            self.out_pos = 0;
+        }
+        n
+    }
+}
+
+/// LZ77 のトークン
+#[derive(Debug, Clone, Copy)]
+enum Token {
+    Literal(u8),
+    Match { length: usize, distance: usize },
+}
+
+fn length_index(length: usize) -> usize {
+    LENGTH_BASE
+        .iter()
+        .rposition(|&base| base as usize <= length)
+        .unwrap_or(0)
+}
+
+fn distance_index(distance: usize) -> usize {
+    DIST_BASE
+        .iter()
+        .rposition(|&base| base as usize <= distance)
+        .unwrap_or(0)
+}
+
+/// 固定ハフマン符号のリテラル/長さ符号 (RFC 1951 Section 3.2.6)
+fn fixed_literal_code(symbol: usize) -> (u32, u32) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xC0 + symbol - 280, 8),
+    }
+}
+
+/// 固定ハフマン符号で書き込んだ場合のビット数
+fn fixed_token_bits(token: &Token) -> usize {
+    match *token {
+        Token::Literal(b) => fixed_literal_code(b as usize).1 as usize,
+        Token::Match { length, distance } => {
+            let li = length_index(length);
+            let di = distance_index(distance);
+            fixed_literal_code(257 + li).1 as usize
+                + LENGTH_EXTRA[li] as usize
+                + 5
+                + DIST_EXTRA[di] as usize
+        }
+    }
+}
+
+fn hash(data: &[u8], i: usize) -> usize {
+    let v = ((data[i] as u32) << 16) | ((data[i + 1] as u32) << 8) | data[i + 2] as u32;
+    (v.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+/// raw DEFLATE の圧縮器
+#[derive(Debug, Clone)]
+struct DeflateEncoder {
+    /// 直前のウィンドウ (最大 32KB) と未圧縮の入力
+    data: Vec<u8>,
+    /// `data` のうち未圧縮の入力の開始位置
+    start: usize,
+    writer: BitWriter,
+}
+
+impl DeflateEncoder {
+    fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            start: 0,
+            writer: BitWriter::default(),
+        }
+    }
+
+    fn write(&mut self, input: &[u8]) {
+        self.data.extend_from_slice(input);
+        while self.data.len() - self.start >= BLOCK_SIZE {
+            self.encode_block(self.start + BLOCK_SIZE, false);
+        }
+    }
+
+    fn finish(&mut self) {
+        self.encode_block(self.data.len(), true);
+        self.writer.align();
+    }
+
+    fn encode_block(&mut self, end: usize, last: bool) {
+        let tokens = self.find_matches(end);
+        let len = end - self.start;
+
+        // ヘッダー 3 ビット + EOB 7 ビット
+        let fixed_bits = 3 + 7 + tokens.iter().map(fixed_token_bits).sum::<usize>();
+        // ヘッダー 3 ビット + 最大 7 ビットのパディング + LEN / NLEN
+        let stored_bits = 3 + 7 + 32 + len * 8;
+
+        if stored_bits < fixed_bits {
+            self.writer.write_bits(last as u32, 1);
+            self.writer.write_bits(0, 2);
+            self.writer.align();
+            self.writer.write_bits(len as u32, 16);
+            self.writer.write_bits(!(len as u32) & 0xFFFF, 16);
+            self.writer.write_bytes(&self.data[self.start..end]);
+        } else {
+            self.writer.write_bits(last as u32, 1);
+            self.writer.write_bits(1, 2);
+            for token in &tokens {
+                match *token {
+                    Token::Literal(b) => {
+                        let (code, len) = fixed_literal_code(b as usize);
+                        self.writer.write_code(code, len);
+                    }
+                    Token::Match { length, distance } => {
+                        let li = length_index(length);
+                        let (code, len) = fixed_literal_code(257 + li);
+                        self.writer.write_code(code, len);
+                        self.writer.write_bits(
+                            (length - LENGTH_BASE[li] as usize) as u32,
+                            LENGTH_EXTRA[li] as u32,
+                        );
+                        let di = distance_index(distance);
+                        self.writer.write_code(di as u32, 5);
+                        self.writer.write_bits(
+                            (distance - DIST_BASE[di] as usize) as u32,
+                            DIST_EXTRA[di] as u32,
+                        );
+                    }
+                }
+            }
+            let (code, len) = fixed_literal_code(256);
+            self.writer.write_code(code, len);
+        }
+
+        // 次のブロックのためにウィンドウ分だけ残す
+        self.start = end;
+        if self.start > WINDOW_SIZE {
+            let drop = self.start - WINDOW_SIZE;
+            self.data.drain(..drop);
+            self.start -= drop;
+        }
+    }
+
+    /// `data[start..end]` を LZ77 でトークンに分解する
+    fn find_matches(&self, end: usize) -> Vec<Token> {
+        let data = &self.data[..end];
+        let mut head = vec![usize::MAX; HASH_SIZE];
+        let mut prev = vec![usize::MAX; end];
+        let mut tokens = Vec::new();
+
+        let insert = |head: &mut [usize], prev: &mut [usize], i: usize| {
+            if i + MIN_MATCH <= end {
+                let h = hash(data, i);
+                prev[i] = head[h];
+                head[h] = i;
+            }
+        };
+
+        // ウィンドウ内の過去のデータを登録する
+        for i in 0..self.start {
+            insert(&mut head, &mut prev, i);
+        }
+
+        let mut i = self.start;
+        while i < end {
+            let mut best_len = 0;
+            let mut best_dist = 0;
+            if i + MIN_MATCH <= end {
+                let max_len = MAX_MATCH.min(end - i);
+                let mut candidate = head[hash(data, i)];
+                let mut chain = 0;
+                while candidate != usize::MAX && chain < MAX_CHAIN {
+                    let distance = i - candidate;
+                    if distance > WINDOW_SIZE {
+                        break;
+                    }
+                    if data[candidate + best_len] == data[i + best_len] {
+                        let len = data[candidate..candidate + max_len]
+                            .iter()
+                            .zip(&data[i..i + max_len])
+                            .take_while(|(a, b)| a == b)
+                            .count();
+                        if len > best_len {
+                            best_len = len;
+                            best_dist = distance;
+                            if len == max_len {
+                                break;
+                            }
+                        }
+                    }
+                    candidate = prev[candidate];
+                    chain += 1;
+                }
+                insert(&mut head, &mut prev, i);
+            }
+
+            if best_len >= MIN_MATCH {
+                tokens.push(Token::Match {
+                    length: best_len,
+                    distance: best_dist,
+                });
+                for j in i + 1..i + best_len {
+                    insert(&mut head, &mut prev, j);
+                }
+                i += best_len;
+            } else {
+                tokens.push(Token::Literal(data[i]));
+                i += 1;
+            }
+        }
+        tokens
+    }
+}
+
+/// 形式のヘッダーとトレイラーを含めた圧縮器
+#[derive(Debug, Clone)]
+pub(crate) struct StreamCompressor {
+    format: Format,
+    encoder: DeflateEncoder,
+    checksum: Checksum,
+    total_in: u64,
+    /// トレイラーまで書き込み済み
+    trailer_written: bool,
+    /// すべての出力を返し終えた
+    finished: bool,
+}
+
+impl StreamCompressor {
+    pub(crate) fn new(format: Format) -> Self {
+        let mut encoder = DeflateEncoder::new();
+        match format {
+            // CMF: CM=8 (deflate), CINFO=7 (32KB ウィンドウ) / FLG: FLEVEL=0, FCHECK
+            Format::Zlib | Format::ZlibOrRaw => encoder.writer.write_bytes(&[0x78, 0x01]),
+            // ID1 ID2 CM FLG MTIME(4) XFL OS(255: unknown)
+            Format::Gzip => encoder
+                .writer
+                .write_bytes(&[0x1F, 0x8B, 0x08, 0x00, 0, 0, 0, 0, 0x00, 0xFF]),
+            Format::Raw => {}
+        }
+        Self {
+            format,
+            encoder,
+            checksum: Checksum::for_format(format),
+            total_in: 0,
+            trailer_written: false,
+            finished: false,
+        }
+    }
+}
+
+impl Compressor for StreamCompressor {
+    fn compress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        if self.trailer_written {
+            return Err(CompressionError::AlreadyFinished);
+        }
+
+        let mut produced = self.encoder.writer.drain(output);
+        let mut consumed = 0;
+        // 内部バッファが膨らまないよう、出力を返し切ってから次の入力を受け取る
+        while !self.encoder.writer.has_pending() && consumed < input.len() {
+            let take = BLOCK_SIZE.min(input.len() - consumed);
+            let chunk = &input[consumed..consumed + take];
+            self.checksum.update(chunk);
+            self.total_in = self.total_in.wrapping_add(take as u64);
+            self.encoder.write(chunk);
+            consumed += take;
+            produced += self.encoder.writer.drain(&mut output[produced..]);
+        }
+
+        if self.encoder.writer.has_pending() {
+            Ok(CompressionStatus::OutputFull { consumed, produced })
+        } else {
+            Ok(CompressionStatus::Continue { consumed, produced })
+        }
+    }
+
+    fn finish(&mut self, output: &mut [u8]) -> Result<CompressionStatus, CompressionError> {
+        if self.finished {
+            return Err(CompressionError::AlreadyFinished);
+        }
+        if !self.trailer_written {
+            self.encoder.finish();
+            let check = self.checksum.finish();
+            match self.format {
+                Format::Zlib | Format::ZlibOrRaw => {
+                    self.encoder.writer.write_bytes(&check.to_be_bytes());
+                }
+                Format::Gzip => {
+                    self.encoder.writer.write_bytes(&check.to_le_bytes());
+                    self.encoder
+                        .writer
+                        .write_bytes(&(self.total_in as u32).to_le_bytes());
+                }
+                Format::Raw => {}
+            }
+            self.trailer_written = true;
+        }
+
+        let produced = self.encoder.writer.drain(output);
+        if self.encoder.writer.has_pending() {
+            Ok(CompressionStatus::OutputFull {
+                consumed: 0,
+                produced,
+            })
+        } else {
+            self.finished = true;
+            Ok(CompressionStatus::Complete {
+                consumed: 0,
+                produced,
+            })
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.format);
+    }
+}
+
+// ========================================
+// 展開
+// ========================================
+
+/// LSB ファーストのビット読み込み
+///
+/// 入力は内部に保持し、読み込み位置を巻き戻せるようにする。
+#[derive(Debug, Clone, Default)]
+struct BitReader {
+    input: Vec<u8>,
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+/// 読み込み位置のスナップショット
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl BitReader {
+    /// 未処理のバイト数
+    fn available(&self) -> usize {
+        self.input.len() - self.pos
+    }
+
+    /// 処理済みの入力を捨てる
+    fn compact(&mut self) {
+        if self.pos > 0 {
+            self.input.drain(..self.pos);
+            self.pos = 0;
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            pos: self.pos,
+            bit_buf: self.bit_buf,
+            bit_count: self.bit_count,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.pos = snapshot.pos;
+        self.bit_buf = snapshot.bit_buf;
+        self.bit_count = snapshot.bit_count;
+    }
+
+    /// `count` ビット (最大 16) を読み込む。入力が足りない場合は None
+    fn read_bits(&mut self, count: u32) -> Option<u32> {
+        while self.bit_count < count {
+            let b = *self.input.get(self.pos)?;
+            self.bit_buf |= (b as u32) << self.bit_count;
+            self.pos += 1;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buf & ((1u32 << count) - 1);
+        self.bit_buf = self.bit_buf.checked_shr(count).unwrap_or(0);
+        self.bit_count -= count;
+        Some(value)
+    }
+
+    /// バイト境界まで読み飛ばす
+    fn align(&mut self) {
+        self.bit_buf = 0;
+        self.bit_count = 0;
+    }
+
+    /// バイト境界から `n` バイト読み込む。入力が足りない場合は None
+    fn read_bytes(&mut self, n: usize) -> Option<&[u8]> {
+        debug_assert_eq!(self.bit_count, 0);
+        if self.available() < n {
+            return None;
+        }
+        let bytes = &self.input[self.pos..self.pos + n];
+        self.pos += n;
+        Some(bytes)
+    }
+}
+
+/// 正準ハフマン符号の復号表
+#[derive(Debug, Clone, Default)]
+struct Huffman {
+    /// 符号長ごとの符号数
+    counts: [u16; 16],
+    /// 符号順に並べたシンボル
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, CompressionError> {
+        let mut counts = [0u16; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // 符号長の組み合わせが過剰でないことを確認する
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(invalid("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offsets = [0u16; 16];
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn fixed_literal() -> Self {
+        let mut lengths = [0u8; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        Self::new(&lengths).expect("fixed literal code is valid")
+    }
+
+    fn fixed_distance() -> Self {
+        Self::new(&[5u8; 30]).expect("fixed distance code is valid")
+    }
+
+    /// シンボルを 1 つ復号する。入力が足りない場合は Ok(None)
+    fn decode(&self, reader: &mut BitReader) -> Result<Option<u16>, CompressionError> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+        for &count in &self.counts[1..] {
+            let Some(bit) = reader.read_bits(1) else {
+                return Ok(None);
+            };
+            code |= bit as i32;
+            let count = count as i32;
+            if code - count < first {
+                return Ok(Some(self.symbols[(index + code - first) as usize]));
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("invalid Huffman code"))
+    }
+}
+
+/// 展開の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InflateState {
+    /// zlib / gzip ヘッダー
+    Header,
+    /// ブロックヘッダー
+    BlockHeader,
+    /// stored ブロックのデータ
+    Stored { remaining: usize },
+    /// ハフマン符号のデータ
+    Codes,
+    /// 一致のコピー中
+    Copy { length: usize, distance: usize },
+    /// zlib / gzip トレイラー
+    Trailer,
+    /// gzip メンバーの後ろ (次のメンバーが続くかを判定する)
+    MemberEnd,
+    /// 完了
+    Done,
+}
+
+/// 形式のヘッダーとトレイラーを含めた展開器
+#[derive(Debug, Clone)]
+pub(crate) struct StreamDecompressor {
+    /// 初期化時の形式 (`reset()` で使う)
+    initial_format: Format,
+    format: Format,
+    state: InflateState,
+    reader: BitReader,
+    last_block: bool,
+    window: Vec<u8>,
+    total_out: u64,
+    literal: Huffman,
+    distance: Huffman,
+    checksum: Checksum,
+}
+
+impl StreamDecompressor {
+    pub(crate) fn new(format: Format) -> Self {
+        Self {
+            initial_format: format,
+            format,
+            state: InflateState::Header,
+            reader: BitReader::default(),
+            last_block: false,
+            window: vec![0; WINDOW_SIZE],
+            total_out: 0,
+            literal: Huffman::default(),
+            distance: Huffman::default(),
+            checksum: Checksum::for_format(format),
+        }
+    }
+
+    /// 状態機械を進める
+    ///
+    /// 出力バッファが満杯になったか、入力が足りなくなったか、完了したら戻る。
+    /// 戻り値は (生成したバイト数, 出力バッファが満杯で止まったか)。
+    fn run(&mut self, output: &mut [u8]) -> Result<(usize, bool), CompressionError> {
+        let mut produced = 0;
+        // チェックサムに反映済みの出力位置
+        let mut checked = 0;
+
+        let output_full = loop {
+            match self.state {
+                InflateState::Header => {
+                    if self.read_header()?.is_none() {
+                        break false;
+                    }
+                    self.state = InflateState::BlockHeader;
+                }
+                InflateState::BlockHeader => {
+                    let snapshot = self.reader.snapshot();
+                    if self.read_block_header()?.is_none() {
+                        self.reader.restore(snapshot);
+                        break false;
+                    }
+                }
+                InflateState::Stored { remaining } => {
+                    if remaining == 0 {
+                        self.end_block();
+                        continue;
+                    }
+                    if produced == output.len() {
+                        break true;
+                    }
+                    let n = remaining
+                        .min(output.len() - produced)
+                        .min(self.reader.available());
+                    if n == 0 {
+                        break false;
+                    }
+                    let pos = self.reader.pos;
+                    for i in 0..n {
+                        let b = self.reader.input[pos + i];
+                        self.emit(output, &mut produced, b);
+                    }
+                    self.reader.pos += n;
+                    self.state = InflateState::Stored {
+                        remaining: remaining - n,
+                    };
+                }
+                InflateState::Codes => {
+                    if produced == output.len() {
+                        break true;
+                    }
+                    let snapshot = self.reader.snapshot();
+                    match self.read_symbol(output, &mut produced)? {
+                        Some(()) => {}
+                        None => {
+                            self.reader.restore(snapshot);
+                            break false;
+                        }
+                    }
+                }
+                InflateState::Copy {
+                    mut length,
+                    distance,
+                } => {
+                    while length > 0 && produced < output.len() {
+                        let index = (self.total_out - distance as u64) as usize & WINDOW_MASK;
+                        let b = self.window[index];
+                        self.emit(output, &mut produced, b);
+                        length -= 1;
+                    }
+                    if length > 0 {
+                        self.state = InflateState::Copy { length, distance };
+                        break true;
+                    }
+                    self.state = InflateState::Codes;
+                }
+                InflateState::Trailer => {
+                    self.checksum.update(&output[checked..produced]);
+                    checked = produced;
+                    if self.read_trailer()?.is_none() {
+                        break false;
+                    }
+                    self.state = if self.format == Format::Gzip {
+                        InflateState::MemberEnd
+                    } else {
+                        InflateState::Done
+                    };
+                }
+                InflateState::MemberEnd => {
+                    // RFC 1952 Section 2.2: gzip ファイルは複数のメンバーを連結できる
+                    match &self.reader.input[self.reader.pos..] {
+                        [] | [0x1F] => break false,
+                        [0x1F, 0x8B, ..] => self.start_member(),
+                        _ => self.state = InflateState::Done,
+                    }
+                }
+                InflateState::Done => break false,
+            }
+        };
+
+        self.checksum.update(&output[checked..produced]);
+        Ok((produced, output_full))
+    }
+
+    fn emit(&mut self, output: &mut [u8], produced: &mut usize, b: u8) {
+        output[*produced] = b;
+        *produced += 1;
+        self.window[self.total_out as usize & WINDOW_MASK] = b;
+        self.total_out += 1;
+    }
+
+    /// 次の gzip メンバーの展開を始める
+    fn start_member(&mut self) {
+        self.state = InflateState::Header;
+        self.last_block = false;
+        // 一致の距離と ISIZE はメンバーごとに数える
+        self.total_out = 0;
+        self.checksum = Checksum::for_format(self.format);
+    }
+
+    fn end_block(&mut self) {
+        if self.last_block {
+            self.reader.align();
+            self.state = InflateState::Trailer;
+        } else {
+            self.state = InflateState::BlockHeader;
+        }
+    }
+
+    fn read_header(&mut self) -> Result<Option<()>, CompressionError> {
+        match self.format {
+            Format::Raw => Ok(Some(())),
+            Format::ZlibOrRaw => {
+                let Some(header) = self.reader.input.get(self.reader.pos..self.reader.pos + 2)
+                else {
+                    return Ok(None);
+                };
+                if is_zlib_header(header[0], header[1]) {
+                    self.format = Format::Zlib;
+                } else {
+                    self.format = Format::Raw;
+                    self.checksum = Checksum::None;
+                }
+                self.read_header()
+            }
+            Format::Zlib => {
+                let Some(header) = self.reader.read_bytes(2) else {
+                    return Ok(None);
+                };
+                let (cmf, flg) = (header[0], header[1]);
+                if !is_zlib_header(cmf, flg) {
+                    return Err(invalid("invalid zlib header"));
+                }
+                if flg & 0x20 != 0 {
+                    return Err(invalid("zlib preset dictionary is not supported"));
+                }
+                Ok(Some(()))
+            }
+            Format::Gzip => self.read_gzip_header(),
+        }
+    }
+
+    fn read_gzip_header(&mut self) -> Result<Option<()>, CompressionError> {
+        let buf = &self.reader.input[self.reader.pos..];
+        if buf.len() < 10 {
+            return Ok(None);
+        }
+        if buf[0] != 0x1F || buf[1] != 0x8B {
+            return Err(invalid("invalid gzip magic"));
+        }
+        if buf[2] != 8 {
+            return Err(invalid("unsupported gzip compression method"));
+        }
+        let flags = buf[3];
+        if flags & GZIP_RESERVED != 0 {
+            return Err(invalid("reserved gzip flags set"));
+        }
+
+        let mut i = 10;
+        if flags & GZIP_FEXTRA != 0 {
+            let Some(xlen) = buf.get(i..i + 2) else {
+                return Ok(None);
+            };
+            i += 2 + u16::from_le_bytes([xlen[0], xlen[1]]) as usize;
+            if buf.len() < i {
+                return Ok(None);
+            }
+        }
+        for flag in [GZIP_FNAME, GZIP_FCOMMENT] {
+            if flags & flag != 0 {
+                let Some(nul) = buf[i..].iter().position(|&b| b == 0) else {
+                    return Ok(None);
+                };
+                i += nul + 1;
+            }
+        }
+        if flags & GZIP_FHCRC != 0 {
+            let Some(hcrc) = buf.get(i..i + 2) else {
+                return Ok(None);
+            };
+            let mut crc = Crc32::new();
+            crc.update(&buf[..i]);
+            if (crc.finish() & 0xFFFF) as u16 != u16::from_le_bytes([hcrc[0], hcrc[1]]) {
+                return Err(invalid("gzip header CRC mismatch"));
+            }
+            i += 2;
+        }
+
+        self.reader.pos += i;
+        Ok(Some(()))
+    }
+
+    fn read_block_header(&mut self) -> Result<Option<()>, CompressionError> {
+        let Some(header) = self.reader.read_bits(3) else {
+            return Ok(None);
+        };
+        self.last_block = header & 1 == 1;
+        match header >> 1 {
+            0 => {
+                self.reader.align();
+                let Some(len) = self.reader.read_bits(16) else {
+                    return Ok(None);
+                };
+                let Some(nlen) = self.reader.read_bits(16) else {
+                    return Ok(None);
+                };
+                if len != !nlen & 0xFFFF {
+                    return Err(invalid("stored block length mismatch"));
+                }
+                self.state = InflateState::Stored {
+                    remaining: len as usize,
+                };
+            }
+            1 => {
+                self.literal = Huffman::fixed_literal();
+                self.distance = Huffman::fixed_distance();
+                self.state = InflateState::Codes;
+            }
+            2 => {
+                if self.read_dynamic_tables()?.is_none() {
+                    return Ok(None);
+                }
+                self.state = InflateState::Codes;
+            }
+            _ => return Err(invalid("invalid block type")),
+        }
+        Ok(Some(()))
+    }
+
+    /// 動的ハフマン符号の符号表を読み込む (RFC 1951 Section 3.2.7)
+    fn read_dynamic_tables(&mut self) -> Result<Option<()>, CompressionError> {
+        let reader = &mut self.reader;
+        let (Some(hlit), Some(hdist), Some(hclen)) = (
+            reader.read_bits(5),
+            reader.read_bits(5),
+            reader.read_bits(4),
+        ) else {
+            return Ok(None);
+        };
+        let hlit = hlit as usize + 257;
+        let hdist = hdist as usize + 1;
+        let hclen = hclen as usize + 4;
+        if hlit > 286 || hdist > 30 {
+            return Err(invalid("too many length or distance codes"));
+        }
+
+        let mut code_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..hclen] {
+            let Some(len) = reader.read_bits(3) else {
+                return Ok(None);
+            };
+            code_lengths[index] = len as u8;
+        }
+        let code_length_code = Huffman::new(&code_lengths)?;
+
+        let mut lengths = vec![0u8; hlit + hdist];
+        let mut i = 0;
+        while i < lengths.len() {
+            let Some(symbol) = code_length_code.decode(reader)? else {
+                return Ok(None);
+            };
+            let (value, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => {
+                    if i == 0 {
+                        return Err(invalid("repeat with no previous code length"));
+                    }
+                    let Some(extra) = reader.read_bits(2) else {
+                        return Ok(None);
+                    };
+                    (lengths[i - 1], 3 + extra as usize)
+                }
+                17 => {
+                    let Some(extra) = reader.read_bits(3) else {
+                        return Ok(None);
+                    };
+                    (0, 3 + extra as usize)
+                }
+                _ => {
+                    let Some(extra) = reader.read_bits(7) else {
+                        return Ok(None);
+                    };
+                    (0, 11 + extra as usize)
+                }
+            };
+            if i + repeat > lengths.len() {
+                return Err(invalid("too many code lengths"));
+            }
+            lengths[i..i + repeat].fill(value);
+            i += repeat;
+        }
+
+        if lengths[256] == 0 {
+            return Err(invalid("missing end-of-block code"));
+        }
+        self.literal = Huffman::new(&lengths[..hlit])?;
+        self.distance = Huffman::new(&lengths[hlit..])?;
+        Ok(Some(()))
+    }
+
+    /// リテラル、ブロック終端、または長さと距離の組を 1 つ処理する
+    fn read_symbol(
+        &mut self,
+        output: &mut [u8],
+        produced: &mut usize,
+    ) -> Result<Option<()>, CompressionError> {
+        let Some(symbol) = self.literal.decode(&mut self.reader)? else {
+            return Ok(None);
+        };
+        let symbol = symbol as usize;
+        if symbol < 256 {
+            self.emit(output, produced, symbol as u8);
+            return Ok(Some(()));
+        }
+        if symbol == 256 {
+            self.end_block();
+            return Ok(Some(()));
+        }
+
+        let li = symbol - 257;
+        if li >= LENGTH_BASE.len() {
+            return Err(invalid("invalid length code"));
+        }
+        let Some(extra) = self.reader.read_bits(LENGTH_EXTRA[li] as u32) else {
+            return Ok(None);
+        };
+        let length = LENGTH_BASE[li] as usize + extra as usize;
+
+        let Some(di) = self.distance.decode(&mut self.reader)? else {
+            return Ok(None);
+        };
+        let di = di as usize;
+        if di >= DIST_BASE.len() {
+            return Err(invalid("invalid distance code"));
+        }
+        let Some(extra) = self.reader.read_bits(DIST_EXTRA[di] as u32) else {
+            return Ok(None);
+        };
+        let distance = DIST_BASE[di] as usize + extra as usize;
+        if distance as u64 > self.total_out {
+            return Err(invalid("distance too far back"));
+        }
+
+        self.state = InflateState::Copy { length, distance };
+        Ok(Some(()))
+    }
+
+    fn read_trailer(&mut self) -> Result<Option<()>, CompressionError> {
+        let check = self.checksum.finish();
+        match self.format {
+            Format::Zlib | Format::ZlibOrRaw => {
+                let Some(trailer) = self.reader.read_bytes(4) else {
+                    return Ok(None);
+                };
+                if u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) != check {
+                    return Err(invalid("zlib Adler-32 mismatch"));
+                }
+            }
+            Format::Gzip => {
+                let total_out = self.total_out as u32;
+                let Some(trailer) = self.reader.read_bytes(8) else {
+                    return Ok(None);
+                };
+                if u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) != check {
+                    return Err(invalid("gzip CRC-32 mismatch"));
+                }
+                if u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]) != total_out
+                {
+                    return Err(invalid("gzip ISIZE mismatch"));
+                }
+            }
+            Format::Raw => {}
+        }
+        Ok(Some(()))
+    }
+}
+
+impl Decompressor for StreamDecompressor {
+    fn decompress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        if self.state == InflateState::Done {
+            return Ok(CompressionStatus::Complete {
+                consumed: 0,
+                produced: 0,
+            });
+        }
+
+        self.reader.compact();
+        let take = input
+            .len()
+            .min(MAX_INPUT_BUFFER.saturating_sub(self.reader.available()));
+        self.reader.input.extend_from_slice(&input[..take]);
+
+        let (produced, output_full) = self.run(output)?;
+
+        // gzip メンバーの後ろで空の入力 (入力の終端) を受け取った場合はストリームの終端とする
+        if self.state == InflateState::MemberEnd && input.is_empty() && self.reader.available() == 0
+        {
+            self.state = InflateState::Done;
+        }
+
+        if self.state == InflateState::Done {
+            // 圧縮データの後ろの余分なデータは消費しない
+            let unused = self.reader.available().min(take);
+            let len = self.reader.input.len();
+            self.reader.input.truncate(len - unused);
+            return Ok(CompressionStatus::Complete {
+                consumed: take - unused,
+                produced,
+            });
+        }
+        if output_full {
+            return Ok(CompressionStatus::OutputFull {
+                consumed: take,
+                produced,
+            });
+        }
+        if self.reader.available() >= MAX_INPUT_BUFFER {
+            return Err(invalid("input buffer limit exceeded"));
+        }
+        Ok(CompressionStatus::Continue {
+            consumed: take,
+            produced,
+        })
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.initial_format);
+    }
+}
+
+/// zlib ヘッダー (CMF, FLG) として妥当かどうか (RFC 1950 Section 2.2)
+fn is_zlib_header(cmf: u8, flg: u8) -> bool {
+    cmf & 0x0F == 8 && cmf >> 4 <= 7 && (((cmf as u16) << 8) | flg as u16).is_multiple_of(31)
+}
+
+// ========================================
+// 公開型
+// ========================================
+
+/// DEFLATE 圧縮器 (`Content-Encoding: deflate`)
+///
+/// zlib 形式 (RFC 1950) で出力する。
+#[derive(Debug, Clone)]
+pub struct DeflateCompressor {
+    inner: StreamCompressor,
+}
+
+impl DeflateCompressor {
+    /// 新しい圧縮器を作成
+    pub fn new() -> Self {
+        Self {
+            inner: StreamCompressor::new(Format::Zlib),
+        }
+    }
+}
+
+impl Default for DeflateCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor for DeflateCompressor {
+    fn compress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        self.inner.compress(input, output)
+    }
+
+    fn finish(&mut self, output: &mut [u8]) -> Result<CompressionStatus, CompressionError> {
+        self.inner.finish(output)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// DEFLATE 展開器 (`Content-Encoding: deflate`)
+///
+/// zlib 形式 (RFC 1950) を展開する。zlib ヘッダーがない場合は raw DEFLATE として展開する。
+#[derive(Debug, Clone)]
+pub struct DeflateDecompressor {
+    inner: StreamDecompressor,
+}
+
+impl DeflateDecompressor {
+    /// 新しい展開器を作成
+    pub fn new() -> Self {
+        Self {
+            inner: StreamDecompressor::new(Format::ZlibOrRaw),
+        }
+    }
+}
+
+impl Default for DeflateDecompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decompressor for DeflateDecompressor {
+    fn decompress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        self.inner.decompress(input, output)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
@@ -0,0 +1,86 @@
+//! gzip (RFC 1952) の依存なし実装
+//!
+//! 圧縮と展開は DEFLATE の実装 (`deflate` モジュール) を共有し、
+//! gzip のヘッダーとトレイラー (CRC-32 / ISIZE) を付け外しする。
+//!
+//! 展開は連結された複数のメンバー (RFC 1952 Section 2.2) に対応する。
+//! メンバーの後ろに gzip のマジックナンバーで始まらないデータが続く場合、そのデータは消費しない。
+//! メンバーの境界で入力が途切れた場合は次のメンバーが続く可能性があるため、
+//! 空の入力で呼び出されるまで `Complete` を返さない。
+
+use super::deflate::{Format, StreamCompressor, StreamDecompressor};
+use super::{CompressionError, CompressionStatus, Compressor, Decompressor};
+
+/// gzip 圧縮器 (`Content-Encoding: gzip`)
+#[derive(Debug, Clone)]
+pub struct GzipCompressor {
+    inner: StreamCompressor,
+}
+
+impl GzipCompressor {
+    /// 新しい圧縮器を作成
+    pub fn new() -> Self {
+        Self {
+            inner: StreamCompressor::new(Format::Gzip),
+        }
+    }
+}
+
+impl Default for GzipCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor for GzipCompressor {
+    fn compress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        self.inner.compress(input, output)
+    }
+
+    fn finish(&mut self, output: &mut [u8]) -> Result<CompressionStatus, CompressionError> {
+        self.inner.finish(output)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// gzip 展開器 (`Content-Encoding: gzip`)
+#[derive(Debug, Clone)]
+pub struct GzipDecompressor {
+    inner: StreamDecompressor,
+}
+
+impl GzipDecompressor {
+    /// 新しい展開器を作成
+    pub fn new() -> Self {
+        Self {
+            inner: StreamDecompressor::new(Format::Gzip),
+        }
+    }
+}
+
+impl Default for GzipDecompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decompressor for GzipDecompressor {
+    fn decompress(
+        &mut self,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> Result<CompressionStatus, CompressionError> {
+        self.inner.decompress(input, output)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
@@ -2,10 +2,57 @@
 //!
 //! RFC 9110 Section 8.4 (Content-Encoding) 準拠の圧縮/展開インターフェース。
 //! gzip, deflate, br 等の圧縮アルゴリズムを実装する際のトレイト定義を提供する。
+//!
+//! ## 組み込みの実装
+//!
+//! `compression-impl` feature を有効にすると、依存なしの gzip / deflate 実装
+//! (`GzipCompressor` / `GzipDecompressor` / `DeflateCompressor` / `DeflateDecompressor`)
+//! が使える。圧縮は固定ハフマン符号のみで、圧縮率よりも実装の小ささを優先している。
+//! 展開はすべてのブロック形式に対応する。
+//!
+//! ```rust
+//! # #[cfg(feature = "compression-impl")]
+//! # {
+//! use shiguredo_http11::compression::{
+//!     Compressor, Decompressor, GzipCompressor, GzipDecompressor,
+//! };
+//!
+//! let data = b"hello hello hello hello";
+//! let mut compressed = [0u8; 128];
+//! let mut compressor = GzipCompressor::new();
+//! let status = compressor.compress(data, &mut compressed).unwrap();
+//! let mut len = status.produced();
+//! let status = compressor.finish(&mut compressed[len..]).unwrap();
+//! assert!(status.is_complete());
+//! len += status.produced();
+//!
+//! let mut output = [0u8; 128];
+//! let mut decompressor = GzipDecompressor::new();
+//! let status = decompressor.decompress(&compressed[..len], &mut output).unwrap();
+//! assert_eq!(&output[..status.produced()], data);
+//! // gzip は複数のメンバーを連結できるため、空の入力で入力の終端を通知すると完了する
+//! let status = decompressor.decompress(&[], &mut output).unwrap();
+//! assert!(status.is_complete());
+//! # }
+//! ```
 
 use alloc::string::String;
 use core::fmt;
 
+mod chain;
+#[cfg(feature = "compression-impl")]
+mod checksum;
+#[cfg(feature = "compression-impl")]
+mod deflate;
+#[cfg(feature = "compression-impl")]
+mod gzip;
+
+pub use chain::DecompressorChain;
+#[cfg(feature = "compression-impl")]
+pub use deflate::{DeflateCompressor, DeflateDecompressor};
+#[cfg(feature = "compression-impl")]
+pub use gzip::{GzipCompressor, GzipDecompressor};
+
 /// 圧縮/展開エラー
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -25,8 +25,10 @@
 //! let _ = if_mod.date();
 //! ```
 
-use crate::date::{DateError, HttpDate};
+use crate::date::HttpDate;
 use crate::etag::{ETagList, EntityTag, parse_etag_list};
+use crate::typed_header::TypedHeader;
+use alloc::string::{String, ToString};
 use core::fmt;
 
 /// 条件付きリクエストエラー
@@ -95,6 +97,19 @@ impl fmt::Display for IfMatch {
     }
 }
 
+impl TypedHeader for IfMatch {
+    const NAME: &'static str = "If-Match";
+    type Error = ConditionalError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// If-None-Match ヘッダー (RFC 9110 Section 13.1.2)
 ///
 /// リソースの現在の表現が指定された ETag のいずれとも一致しない場合のみ
@@ -136,6 +151,19 @@ impl fmt::Display for IfNoneMatch {
     }
 }
 
+impl TypedHeader for IfNoneMatch {
+    const NAME: &'static str = "If-None-Match";
+    type Error = ConditionalError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// If-Modified-Since ヘッダー (RFC 9110 Section 13.1.3)
 ///
 /// 指定した日時以降にリソースが変更された場合のみリクエストを処理します。
@@ -148,11 +176,7 @@ impl IfModifiedSince {
     /// `reference_year` は RFC 850 形式の 2 桁年解決に使う現在年
     /// (RFC 9110 §5.6.7)。
     pub fn parse(input: &str, reference_year: u16) -> Result<Self, ConditionalError> {
-        HttpDate::parse(input)
-            .or_else(|e| match e {
-                DateError::Rfc850Date => HttpDate::parse_rfc850(input, reference_year),
-                other => Err(other),
-            })
+        HttpDate::parse_any(input, reference_year)
             .map(IfModifiedSince)
             .map_err(|_| ConditionalError::DateError)
     }
@@ -191,11 +215,7 @@ impl IfUnmodifiedSince {
     /// `reference_year` は RFC 850 形式の 2 桁年解決に使う現在年
     /// (RFC 9110 §5.6.7)。
     pub fn parse(input: &str, reference_year: u16) -> Result<Self, ConditionalError> {
-        HttpDate::parse(input)
-            .or_else(|e| match e {
-                DateError::Rfc850Date => HttpDate::parse_rfc850(input, reference_year),
-                other => Err(other),
-            })
+        HttpDate::parse_any(input, reference_year)
             .map(IfUnmodifiedSince)
             .map_err(|_| ConditionalError::DateError)
     }
@@ -243,11 +263,7 @@ impl IfRange {
                 .map(IfRange::ETag)
                 .map_err(|_| ConditionalError::ETagError)
         } else {
-            HttpDate::parse(input)
-                .or_else(|e| match e {
-                    DateError::Rfc850Date => HttpDate::parse_rfc850(input, reference_year),
-                    other => Err(other),
-                })
+            HttpDate::parse_any(input, reference_year)
                 .map(IfRange::Date)
                 .map_err(|_| ConditionalError::DateError)
         }
@@ -278,6 +294,28 @@ impl IfRange {
             IfRange::Date(d) => Some(d),
         }
     }
+
+    /// 現在の表現が条件を満たすか (RFC 9110 Section 13.1.5)
+    ///
+    /// ETag は Strong 比較で判定するため、Weak ETag はどちら側にあっても一致しない。
+    /// 日時は `last_modified` と完全に一致する場合のみ条件を満たす。
+    /// `last_modified` は強いバリデーターとして扱える Last-Modified のみを渡すこと
+    /// (RFC 9110 Section 8.8.2.2)。
+    ///
+    /// 戻り値が true = Range を処理する (206)
+    /// 戻り値が false = Range を無視して表現全体を返す (200)
+    pub fn matches(
+        &self,
+        current_etag: Option<&EntityTag>,
+        last_modified: Option<&HttpDate>,
+    ) -> bool {
+        match self {
+            IfRange::ETag(etag) => current_etag.is_some_and(|current| etag.strong_compare(current)),
+            IfRange::Date(date) => {
+                last_modified.is_some_and(|modified| date.unix_seconds() == modified.unix_seconds())
+            }
+        }
+    }
 }
 
 impl fmt::Display for IfRange {
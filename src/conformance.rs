@@ -0,0 +1,425 @@
+//! Sans-I/O デコーダーの適合性テスト用ハーネス
+//!
+//! ## 概要
+//!
+//! `RequestDecoder` / `ResponseDecoder` に任意の位置で分割したバイト列を投入し、
+//! デコード結果 (ヘッダー、ボディ、完了、エラー) を `Outcome` の列として記録する。
+//! ストリーミング API (`decode_headers()` / `peek_body()` / `consume_body()` /
+//! `progress()`) を使って駆動し、パイプライン化された後続メッセージもデコードする。
+//!
+//! ボディの区切りは投入したバイト列の分割位置に依存するため、比較時は連続する
+//! `Outcome::Body` を 1 つに結合する (`normalize()`)。
+//!
+//! `Harness::replay_all_splits()` は、一括で投入した結果を基準として、
+//! 全ての 1 箇所分割と 1 バイトずつの投入で同じ結果になることを検証する。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::BodyKind;
+//! use shiguredo_http11::conformance::{Harness, Outcome};
+//!
+//! let input = b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\n\r\nhello";
+//! let outcomes = Harness::request().replay_all_splits(input).unwrap();
+//!
+//! assert!(matches!(
+//!     &outcomes[0],
+//!     Outcome::RequestHead { body_kind: BodyKind::ContentLength(5), .. }
+//! ));
+//! assert_eq!(outcomes[1], Outcome::Body(b"hello".to_vec()));
+//! assert_eq!(outcomes[2], Outcome::Complete { trailers: vec![] });
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::{
+    BodyKind, BodyProgress, DecoderProfile, RequestDecoder, RequestHead, ResponseDecoder,
+    ResponseHead,
+};
+use crate::error::Error;
+use crate::limits::DecoderLimits;
+
+/// デコード結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Outcome {
+    /// リクエストのヘッダーをデコードした
+    RequestHead {
+        /// リクエストヘッダー
+        head: RequestHead,
+        /// ボディの種類
+        body_kind: BodyKind,
+    },
+    /// レスポンスのヘッダーをデコードした
+    ///
+    /// 1xx レスポンスも 1 つのメッセージとして記録する。
+    ResponseHead {
+        /// レスポンスヘッダー
+        head: ResponseHead,
+        /// ボディの種類
+        body_kind: BodyKind,
+    },
+    /// ボディデータ
+    Body(Vec<u8>),
+    /// メッセージが完了した
+    Complete {
+        /// トレーラー
+        trailers: Vec<(Cow<'static, str>, String)>,
+    },
+    /// エラーが発生した
+    ///
+    /// エラー以降はデコードしない。
+    Error(Error),
+}
+
+/// 連続する `Outcome::Body` を 1 つに結合する
+///
+/// 空の `Outcome::Body` は取り除く。
+pub fn normalize(outcomes: Vec<Outcome>) -> Vec<Outcome> {
+    let mut normalized: Vec<Outcome> = Vec::with_capacity(outcomes.len());
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Body(data) if data.is_empty() => {}
+            Outcome::Body(data) => {
+                if let Some(Outcome::Body(last)) = normalized.last_mut() {
+                    last.extend_from_slice(&data);
+                } else {
+                    normalized.push(Outcome::Body(data));
+                }
+            }
+            outcome => normalized.push(outcome),
+        }
+    }
+    normalized
+}
+
+/// デコード対象のメッセージの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Request,
+    Response,
+}
+
+/// 分割したバイト列をデコーダーに投入するハーネス
+#[derive(Debug, Clone)]
+pub struct Harness {
+    kind: Kind,
+    profile: DecoderProfile,
+    request_method: Option<String>,
+    eof: bool,
+}
+
+impl Harness {
+    /// `RequestDecoder` を駆動するハーネスを作成
+    pub fn request() -> Self {
+        Self::new(Kind::Request)
+    }
+
+    /// `ResponseDecoder` を駆動するハーネスを作成
+    pub fn response() -> Self {
+        Self::new(Kind::Response)
+    }
+
+    fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            profile: DecoderProfile::default(),
+            request_method: None,
+            eof: false,
+        }
+    }
+
+    /// デコーダーのプロファイルを設定
+    pub fn with_profile(mut self, profile: DecoderProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// デコーダーの制限を設定
+    pub fn with_limits(mut self, limits: DecoderLimits) -> Self {
+        self.profile.limits = limits;
+        self
+    }
+
+    /// レスポンスの元となったリクエストのメソッドを設定 (HEAD/CONNECT 判定用)
+    ///
+    /// `ResponseDecoder::set_request_method()` を呼ぶ。リクエストのハーネスでは無視する。
+    pub fn with_request_method(mut self, method: &str) -> Self {
+        self.request_method = Some(method.into());
+        self
+    }
+
+    /// 全てのバイト列を投入した後に接続終了を通知するかを設定
+    ///
+    /// `true` の場合は `ResponseDecoder::mark_eof()` を呼び、close-delimited ボディを
+    /// 完了させる。リクエストのハーネスでは無視する。
+    pub fn with_eof(mut self, eof: bool) -> Self {
+        self.eof = eof;
+        self
+    }
+
+    /// バイト列を順に投入してデコード結果を返す
+    ///
+    /// `parts` の要素ごとに `feed()` を呼び、デコードできるところまで進める。
+    /// 返り値は正規化しない。
+    pub fn run(&self, parts: &[&[u8]]) -> Vec<Outcome> {
+        match self.kind {
+            Kind::Request => {
+                let decoder = RequestDecoder::with_profile(self.profile.clone());
+                Runner::new(decoder).run(parts, false)
+            }
+            Kind::Response => {
+                let mut decoder = ResponseDecoder::with_profile(self.profile.clone());
+                if let Some(method) = &self.request_method {
+                    decoder.set_request_method(method);
+                }
+                Runner::new(decoder).run(parts, self.eof)
+            }
+        }
+    }
+
+    /// バイト列を指定した位置で分割して投入し、デコード結果を返す
+    ///
+    /// `split_points` は昇順で `input.len()` 以下であること。返り値は正規化しない。
+    pub fn run_split(&self, input: &[u8], split_points: &[usize]) -> Vec<Outcome> {
+        let mut parts = Vec::with_capacity(split_points.len() + 1);
+        let mut start = 0;
+        for &point in split_points {
+            parts.push(&input[start..point]);
+            start = point;
+        }
+        parts.push(&input[start..]);
+        self.run(&parts)
+    }
+
+    /// 全ての分割位置で同じデコード結果になることを検証する
+    ///
+    /// 一括で投入した結果を基準として、全ての 1 箇所分割と 1 バイトずつの投入を比較する。
+    /// 一致した場合は正規化した基準の結果を返す。
+    pub fn replay_all_splits(&self, input: &[u8]) -> Result<Vec<Outcome>, Mismatch> {
+        let expected = normalize(self.run(&[input]));
+        self.check_all_splits(input, &expected)?;
+        Ok(expected)
+    }
+
+    /// 全ての分割位置で期待するデコード結果になることを検証する
+    ///
+    /// 一括での投入、全ての 1 箇所分割、1 バイトずつの投入を `expected` と比較する。
+    /// `expected` は正規化した列であること。
+    pub fn check_all_splits(&self, input: &[u8], expected: &[Outcome]) -> Result<(), Mismatch> {
+        let check = |split_points: Vec<usize>| {
+            let actual = normalize(self.run_split(input, &split_points));
+            if actual == expected {
+                Ok(())
+            } else {
+                Err(Mismatch {
+                    split_points,
+                    expected: expected.to_vec(),
+                    actual,
+                })
+            }
+        };
+        check(Vec::new())?;
+        for point in 1..input.len() {
+            check(alloc::vec![point])?;
+        }
+        check((1..input.len()).collect())
+    }
+}
+
+/// 分割位置によってデコード結果が異なった
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    split_points: Vec<usize>,
+    expected: Vec<Outcome>,
+    actual: Vec<Outcome>,
+}
+
+impl Mismatch {
+    /// 入力を分割した位置 (空の場合は一括で投入)
+    pub fn split_points(&self) -> &[usize] {
+        &self.split_points
+    }
+
+    /// 期待したデコード結果 (正規化済み)
+    pub fn expected(&self) -> &[Outcome] {
+        &self.expected
+    }
+
+    /// 実際のデコード結果 (正規化済み)
+    pub fn actual(&self) -> &[Outcome] {
+        &self.actual
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "outcome mismatch at split points {:?}: expected {:?}, actual {:?}",
+            self.split_points, self.expected, self.actual
+        )
+    }
+}
+
+impl core::error::Error for Mismatch {}
+
+/// ハーネスから駆動するデコーダー
+trait Driver {
+    fn feed(&mut self, data: &[u8]) -> Result<(), Error>;
+    fn decode_headers(&mut self) -> Result<Option<(Outcome, BodyKind)>, Error>;
+    fn peek_body(&self) -> Option<&[u8]>;
+    fn consume_body(&mut self, len: usize) -> Result<BodyProgress, Error>;
+    fn progress(&mut self) -> Result<BodyProgress, Error>;
+    fn mark_eof(&mut self) {}
+}
+
+impl Driver for RequestDecoder {
+    fn feed(&mut self, data: &[u8]) -> Result<(), Error> {
+        RequestDecoder::feed(self, data)
+    }
+
+    fn decode_headers(&mut self) -> Result<Option<(Outcome, BodyKind)>, Error> {
+        Ok(RequestDecoder::decode_headers(self)?
+            .map(|(head, body_kind)| (Outcome::RequestHead { head, body_kind }, body_kind)))
+    }
+
+    fn peek_body(&self) -> Option<&[u8]> {
+        RequestDecoder::peek_body(self)
+    }
+
+    fn consume_body(&mut self, len: usize) -> Result<BodyProgress, Error> {
+        RequestDecoder::consume_body(self, len)
+    }
+
+    fn progress(&mut self) -> Result<BodyProgress, Error> {
+        RequestDecoder::progress(self)
+    }
+}
+
+impl Driver for ResponseDecoder {
+    fn feed(&mut self, data: &[u8]) -> Result<(), Error> {
+        ResponseDecoder::feed(self, data)
+    }
+
+    fn decode_headers(&mut self) -> Result<Option<(Outcome, BodyKind)>, Error> {
+        Ok(ResponseDecoder::decode_headers(self)?
+            .map(|(head, body_kind)| (Outcome::ResponseHead { head, body_kind }, body_kind)))
+    }
+
+    fn peek_body(&self) -> Option<&[u8]> {
+        ResponseDecoder::peek_body(self)
+    }
+
+    fn consume_body(&mut self, len: usize) -> Result<BodyProgress, Error> {
+        ResponseDecoder::consume_body(self, len)
+    }
+
+    fn progress(&mut self) -> Result<BodyProgress, Error> {
+        ResponseDecoder::progress(self)
+    }
+
+    fn mark_eof(&mut self) {
+        ResponseDecoder::mark_eof(self)
+    }
+}
+
+/// デコーダーを駆動して結果を記録する
+struct Runner<D> {
+    decoder: D,
+    outcomes: Vec<Outcome>,
+    in_body: bool,
+    stopped: bool,
+}
+
+impl<D: Driver> Runner<D> {
+    fn new(decoder: D) -> Self {
+        Self {
+            decoder,
+            outcomes: Vec::new(),
+            in_body: false,
+            stopped: false,
+        }
+    }
+
+    fn run(mut self, parts: &[&[u8]], eof: bool) -> Vec<Outcome> {
+        for part in parts {
+            if self.stopped {
+                break;
+            }
+            if let Err(e) = self.decoder.feed(part) {
+                self.stop(e);
+                break;
+            }
+            self.pump();
+        }
+        if eof && !self.stopped {
+            self.decoder.mark_eof();
+            self.pump();
+        }
+        self.outcomes
+    }
+
+    /// バッファのデータでデコードできるところまで進める
+    fn pump(&mut self) {
+        while !self.stopped {
+            if !self.in_body {
+                match self.decoder.decode_headers() {
+                    Ok(Some((outcome, body_kind))) => {
+                        self.outcomes.push(outcome);
+                        // トンネルモード以降は HTTP として解釈しない
+                        if body_kind == BodyKind::Tunnel {
+                            self.stopped = true;
+                        }
+                        self.in_body = true;
+                    }
+                    Ok(None) => return,
+                    Err(e) => self.stop(e),
+                }
+                continue;
+            }
+
+            if let Some(data) = self.decoder.peek_body() {
+                let data = data.to_vec();
+                let len = data.len();
+                self.outcomes.push(Outcome::Body(data));
+                let result = self.decoder.consume_body(len);
+                self.handle_progress(result);
+                continue;
+            }
+
+            let result = self.decoder.progress();
+            if !self.handle_progress(result) {
+                return;
+            }
+        }
+    }
+
+    /// `consume_body()` / `progress()` の結果を記録する
+    ///
+    /// 追加のデータが必要な場合は `false` を返す。
+    /// 多段遷移で `Advanced` を返した場合も、完了は次の `progress()` で検出する。
+    fn handle_progress(&mut self, result: Result<BodyProgress, Error>) -> bool {
+        match result {
+            Ok(BodyProgress::Complete { trailers }) => {
+                self.outcomes.push(Outcome::Complete { trailers });
+                self.in_body = false;
+                true
+            }
+            Ok(BodyProgress::Advanced) => true,
+            Ok(BodyProgress::NeedData) => false,
+            Err(e) => {
+                self.stop(e);
+                true
+            }
+        }
+    }
+
+    fn stop(&mut self, error: Error) {
+        self.outcomes.push(Outcome::Error(error));
+        self.stopped = true;
+    }
+}
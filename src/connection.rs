@@ -0,0 +1,351 @@
+//! 接続管理 (RFC 9112 Section 9)
+//!
+//! ## 概要
+//!
+//! レスポンス送信後に接続を維持できるかを判定し、そのために設定すべき
+//! `Connection` ヘッダーとボディのフレーミングを決める。
+//!
+//! HTTP/1.0 のクライアントとの持続接続 (RFC 9112 Section 9.3 / Appendix C.2.2) は
+//! 次の条件をすべて満たす場合にのみ成立する:
+//!
+//! - リクエストに `Connection: keep-alive` がある
+//! - レスポンスに `Connection: keep-alive` を明示する
+//! - ボディ長が `Content-Length` で決まる (またはボディがない)
+//!
+//! HTTP/1.0 のクライアントは chunked を解釈できないため、chunked を選んだ場合は
+//! close-delimited に切り替え、接続を閉じる (RFC 9112 Section 6.1)。
+//!
+//! プロキシが転送時に取り除く hop-by-hop ヘッダーの判定も提供する
+//! (RFC 9110 Section 7.6.1)。組み込みの hop-by-hop ヘッダーに加えて、
+//! `Connection` ヘッダーに列挙されたフィールドも hop-by-hop として扱う。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Connection        = #connection-option
+//! connection-option = token
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::connection::ResponsePersistence;
+//! use shiguredo_http11::{BodyKind, RequestDecoder, Response, StatusCode};
+//!
+//! let mut decoder = RequestDecoder::new();
+//! decoder
+//!     .feed(b"GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n")
+//!     .unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//!
+//! let body = b"hello".to_vec();
+//! let plan = ResponsePersistence::for_request(&head, BodyKind::ContentLength(body.len() as u64));
+//! assert!(plan.is_keep_alive());
+//! assert_eq!(plan.connection_header(), Some("keep-alive"));
+//!
+//! let mut response = Response::with_status(StatusCode::OK).body(body);
+//! plan.apply(&mut response).unwrap();
+//! assert_eq!(response.get_header("Connection"), Some("keep-alive"));
+//! ```
+//!
+//! ## hop-by-hop ヘッダーの除去
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//!
+//! use shiguredo_http11::connection::strip_hop_by_hop;
+//!
+//! let mut headers: Vec<(Cow<'static, str>, String)> = vec![
+//!     ("Host".into(), "example.com".to_string()),
+//!     ("Connection".into(), "close, X-Trace".to_string()),
+//!     ("X-Trace".into(), "1".to_string()),
+//!     ("Keep-Alive".into(), "timeout=5".to_string()),
+//! ];
+//! strip_hop_by_hop(&mut headers);
+//! assert_eq!(headers, [(Cow::Borrowed("Host"), "example.com".to_string())]);
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::{BodyKind, HttpHead};
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::typed_header::TypedHeader;
+use crate::validate::{is_valid_token, trim_ows};
+use crate::version::HttpVersion;
+
+/// Connection パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionError {
+    /// 不正な connection-option
+    InvalidOption,
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::InvalidOption => write!(f, "invalid Connection option"),
+        }
+    }
+}
+
+impl core::error::Error for ConnectionError {}
+
+/// 常に hop-by-hop として扱うヘッダー (小文字)
+///
+/// RFC 9110 Section 7.6.1 で挙げられている `Connection` / `Proxy-Connection` /
+/// `Keep-Alive` / `TE` / `Transfer-Encoding` / `Upgrade` に加えて、
+/// 接続ごとの認証である `Proxy-Authenticate` / `Proxy-Authorization` を含む。
+pub const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "proxy-connection",
+    "te",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// レスポンスの接続維持とフレーミングの判定結果
+///
+/// `ResponsePersistence::for_request()` で作成する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponsePersistence {
+    keep_alive: bool,
+    // HTTP/1.1 以外のピアには Connection: keep-alive の明示が必要
+    explicit_keep_alive: bool,
+    framing: BodyKind,
+}
+
+impl ResponsePersistence {
+    /// リクエストとサーバーが選んだボディのフレーミングから判定する
+    ///
+    /// - HTTP/1.1: `Connection: close` がなければ持続する。close-delimited を選んだ場合は閉じる
+    /// - HTTP/1.0 (およびその他のバージョン): `Connection: keep-alive` があり、
+    ///   フレーミングが `ContentLength` / `None` の場合のみ持続する。
+    ///   `Chunked` は `CloseDelimited` に切り替える (RFC 9112 Section 6.1)
+    /// - `Tunnel`: トンネルに切り替わるため持続接続として扱わず、`Connection` も設定しない
+    pub fn for_request<H: HttpHead + ?Sized>(request: &H, framing: BodyKind) -> Self {
+        let wants_keep_alive = request.is_keep_alive();
+        let is_http11 = request.http_version() == HttpVersion::Http11;
+
+        let framing = match framing {
+            // RFC 9112 Section 6.1: HTTP/1.1 を示さないリクエストへの応答に
+            // Transfer-Encoding を含めてはならない (MUST NOT)
+            BodyKind::Chunked if !is_http11 => BodyKind::CloseDelimited,
+            framing => framing,
+        };
+        let keep_alive = wants_keep_alive
+            && match framing {
+                BodyKind::ContentLength(_) | BodyKind::None => true,
+                BodyKind::Chunked => is_http11,
+                BodyKind::CloseDelimited | BodyKind::Tunnel => false,
+            };
+
+        Self {
+            keep_alive,
+            explicit_keep_alive: !is_http11,
+            framing,
+        }
+    }
+
+    /// レスポンス送信後に接続を維持できるか
+    pub fn is_keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+
+    /// 実際に使うボディのフレーミング
+    ///
+    /// HTTP/1.0 のクライアントに `Chunked` を指定した場合は `CloseDelimited` になる。
+    pub fn framing(&self) -> BodyKind {
+        self.framing
+    }
+
+    /// 設定すべき `Connection` ヘッダーの値
+    ///
+    /// HTTP/1.1 で持続する場合はデフォルトのため `None`。
+    /// HTTP/1.0 で持続する場合は `"keep-alive"`、閉じる場合は `"close"`。
+    /// `Tunnel` の場合は `None`。
+    pub fn connection_header(&self) -> Option<&'static str> {
+        if self.framing == BodyKind::Tunnel {
+            None
+        } else if !self.keep_alive {
+            Some("close")
+        } else if self.explicit_keep_alive {
+            Some("keep-alive")
+        } else {
+            None
+        }
+    }
+
+    /// 設定すべき `Transfer-Encoding` ヘッダーの値
+    pub fn transfer_encoding_header(&self) -> Option<&'static str> {
+        (self.framing == BodyKind::Chunked).then_some("chunked")
+    }
+
+    /// 設定すべき `Content-Length` ヘッダーの値
+    pub fn content_length_header(&self) -> Option<u64> {
+        match self.framing {
+            BodyKind::ContentLength(length) => Some(length),
+            _ => None,
+        }
+    }
+
+    /// `Connection` とフレーミングのヘッダーをレスポンスに設定する
+    ///
+    /// 同名の既存ヘッダーは置き換える。close-delimited の場合は `Transfer-Encoding`
+    /// を設定しないため、呼び出し側で chunked を設定していないことを確認すること。
+    pub fn apply<'a>(&self, response: &'a mut Response) -> Result<&'a mut Response, EncodeError> {
+        if let Some(value) = self.connection_header() {
+            response.set_header("Connection", value)?;
+        }
+        if let Some(value) = self.transfer_encoding_header() {
+            response.set_header("Transfer-Encoding", value)?;
+        }
+        if let Some(length) = self.content_length_header() {
+            response.set_header("Content-Length", length.to_string())?;
+        }
+        Ok(response)
+    }
+}
+
+/// Connection ヘッダーの connection-option の一覧 (RFC 9110 Section 7.6.1)
+///
+/// option は小文字に正規化し、重複は取り除く。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionOptions {
+    options: Vec<String>,
+}
+
+impl ConnectionOptions {
+    /// 空の一覧を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connection ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 受信者は空のリスト要素を無視しなければならない (MUST)。
+    pub fn parse(input: &str) -> Result<Self, ConnectionError> {
+        let mut options = ConnectionOptions::new();
+        for token in input.split(',') {
+            let token = trim_ows(token);
+            if token.is_empty() {
+                continue;
+            }
+            options = options.with_option(token)?;
+        }
+        Ok(options)
+    }
+
+    /// メッセージの Connection ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead + ?Sized>(
+        message: &H,
+    ) -> Result<Option<Self>, ConnectionError> {
+        let values = message.get_headers("Connection");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// ヘッダーリストの Connection ヘッダーから option を集める
+    ///
+    /// hop-by-hop ヘッダーの除去に使うため、token として不正な要素もエラーにせず
+    /// そのまま option として扱う。Connection ヘッダーがない場合は空になる。
+    pub fn collect(headers: &[(Cow<'static, str>, String)]) -> Self {
+        let mut options = ConnectionOptions::new();
+        for (_, value) in headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("Connection"))
+        {
+            for token in value.split(',') {
+                let token = trim_ows(token);
+                if !token.is_empty() {
+                    options.push(token);
+                }
+            }
+        }
+        options
+    }
+
+    /// option を追加 (ビルダーパターン)
+    pub fn with_option(mut self, option: &str) -> Result<Self, ConnectionError> {
+        if !is_valid_token(option) {
+            return Err(ConnectionError::InvalidOption);
+        }
+        self.push(option);
+        Ok(self)
+    }
+
+    fn push(&mut self, option: &str) {
+        if !self.contains(option) {
+            self.options.push(option.to_ascii_lowercase());
+        }
+    }
+
+    /// option の一覧 (小文字)
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    /// option を含むか (大文字小文字を区別しない)
+    pub fn contains(&self, option: &str) -> bool {
+        self.options.iter().any(|o| o.eq_ignore_ascii_case(option))
+    }
+
+    /// `close` を含むか
+    pub fn is_close(&self) -> bool {
+        self.contains("close")
+    }
+
+    /// option がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+}
+
+impl fmt::Display for ConnectionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.options.join(", "))
+    }
+}
+
+impl TypedHeader for ConnectionOptions {
+    const NAME: &'static str = "Connection";
+    type Error = ConnectionError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// ヘッダー名が hop-by-hop かどうか (RFC 9110 Section 7.6.1)
+///
+/// `HOP_BY_HOP_HEADERS` のいずれか、または `connection` に列挙されている場合に true を返す。
+pub fn is_hop_by_hop(name: &str, connection: &ConnectionOptions) -> bool {
+    HOP_BY_HOP_HEADERS
+        .iter()
+        .any(|header| header.eq_ignore_ascii_case(name))
+        || connection.contains(name)
+}
+
+/// ヘッダーリストから hop-by-hop ヘッダーを取り除く (RFC 9110 Section 7.6.1)
+///
+/// プロキシが転送する前に呼び出す。`Connection` ヘッダー自体と、そこに列挙された
+/// フィールド、`HOP_BY_HOP_HEADERS` のフィールドを削除する。残りの順序は保持する。
+pub fn strip_hop_by_hop(headers: &mut Vec<(Cow<'static, str>, String)>) {
+    let connection = ConnectionOptions::collect(headers);
+    headers.retain(|(name, _)| !is_hop_by_hop(name, &connection));
+}
@@ -0,0 +1,437 @@
+//! Content-Encoding を展開しながらボディを読むストリーミングリーダー
+//!
+//! ## 概要
+//!
+//! `RequestDecoder` / `ResponseDecoder` の `peek_body()` / `consume_body()` /
+//! `progress()` の上に載り、`Content-Encoding` (RFC 9110 Section 8.4) に従って
+//! ボディを展開しながら同じ peek / consume 形式で読み出す。
+//!
+//! - 展開器は [`DecompressorFactory`] でコーディング名から選ぶ。
+//!   [`DefaultDecompressors`] は `compression-impl` feature が有効な場合に
+//!   gzip / x-gzip / deflate を扱う。br や zstd はクロージャで追加できる
+//! - `Content-Encoding: deflate, gzip` のような複数のコーディングは
+//!   [`DecompressorChain`] で逆順に展開する
+//! - 展開後のサイズが上限 (デフォルト 10MB) を超えるとエラーにする (展開爆弾対策)
+//! - 圧縮データが途中で終わっている場合や、圧縮データの後ろに余分なデータがある
+//!   場合はエラーにする
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! # #[cfg(feature = "compression-impl")]
+//! # {
+//! use shiguredo_http11::ResponseDecoder;
+//! use shiguredo_http11::content_decoder::{ContentDecoder, DefaultDecompressors};
+//!
+//! // "hello world\n" を gzip で圧縮したボディ
+//! let body = [
+//!     0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xcb, 0x48, 0xcd, 0xc9,
+//!     0xc9, 0x57, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0xe1, 0x02, 0x00, 0x2d, 0x3b, 0x08, 0xaf,
+//!     0x0c, 0x00, 0x00, 0x00,
+//! ];
+//! let mut decoder = ResponseDecoder::new();
+//! decoder
+//!     .feed(b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: 32\r\n\r\n")
+//!     .unwrap();
+//! decoder.feed(&body).unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//!
+//! let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+//! let mut text = Vec::new();
+//! while let Some(data) = reader.peek_body(&mut decoder).unwrap() {
+//!     text.extend_from_slice(data);
+//!     let len = data.len();
+//!     reader.consume_body(len);
+//! }
+//! assert!(reader.is_complete());
+//! assert_eq!(text, b"hello world\n");
+//! # }
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::compression::{CompressionError, Decompressor, DecompressorChain, NoCompression};
+use crate::content_encoding::{ContentCoding, ContentEncoding, ContentEncodingError};
+use crate::decoder::{BodyProgress, HttpHead, RequestDecoder, ResponseDecoder};
+use crate::error::Error;
+
+/// 展開後のボディサイズの上限のデフォルト値 (10MB)
+pub const DEFAULT_MAX_DECODED_SIZE: usize = 10 * 1024 * 1024;
+
+/// 展開用バッファサイズのデフォルト値
+const DEFAULT_BUFFER_SIZE: usize = 8192;
+
+/// Content-Encoding の展開エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentDecoderError {
+    /// Content-Encoding ヘッダーが不正
+    InvalidContentEncoding(ContentEncodingError),
+    /// 展開器のないコーディング
+    UnsupportedCoding { coding: String },
+    /// ボディのデコードエラー
+    Decode(Error),
+    /// 展開エラー
+    Decompression(CompressionError),
+    /// 展開後のサイズが上限を超えた
+    DecodedTooLarge { size: usize, limit: usize },
+    /// ボディが圧縮データの途中で終わった
+    Truncated,
+    /// 圧縮データの後ろに余分なデータがある
+    TrailingData,
+}
+
+impl fmt::Display for ContentDecoderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentDecoderError::InvalidContentEncoding(e) => {
+                write!(f, "invalid Content-Encoding: {}", e)
+            }
+            ContentDecoderError::UnsupportedCoding { coding } => {
+                write!(f, "unsupported content coding: {}", coding)
+            }
+            ContentDecoderError::Decode(e) => write!(f, "{}", e),
+            ContentDecoderError::Decompression(e) => write!(f, "{}", e),
+            ContentDecoderError::DecodedTooLarge { size, limit } => {
+                write!(f, "decoded body too large: {} > {}", size, limit)
+            }
+            ContentDecoderError::Truncated => write!(f, "truncated compressed body"),
+            ContentDecoderError::TrailingData => {
+                write!(f, "trailing data after compressed body")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ContentDecoderError {}
+
+impl From<Error> for ContentDecoderError {
+    fn from(e: Error) -> Self {
+        ContentDecoderError::Decode(e)
+    }
+}
+
+impl From<CompressionError> for ContentDecoderError {
+    fn from(e: CompressionError) -> Self {
+        ContentDecoderError::Decompression(e)
+    }
+}
+
+/// コーディング名から展開器を作るファクトリ
+///
+/// `Fn(&ContentCoding) -> Option<Box<dyn Decompressor>>` のクロージャも使える。
+pub trait DecompressorFactory {
+    /// 展開器を作成する。対応していないコーディングの場合は None
+    fn create(&self, coding: &ContentCoding) -> Option<Box<dyn Decompressor>>;
+}
+
+impl<F> DecompressorFactory for F
+where
+    F: Fn(&ContentCoding) -> Option<Box<dyn Decompressor>>,
+{
+    fn create(&self, coding: &ContentCoding) -> Option<Box<dyn Decompressor>> {
+        self(coding)
+    }
+}
+
+/// 組み込みの展開器
+///
+/// `compression-impl` feature が有効な場合、gzip / x-gzip (RFC 9110 Section 8.4.1.3) と
+/// deflate を扱う。無効な場合はどのコーディングにも対応しない。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultDecompressors;
+
+impl DecompressorFactory for DefaultDecompressors {
+    #[cfg(feature = "compression-impl")]
+    fn create(&self, coding: &ContentCoding) -> Option<Box<dyn Decompressor>> {
+        use crate::compression::{DeflateDecompressor, GzipDecompressor};
+
+        match coding {
+            ContentCoding::Gzip => Some(Box::new(GzipDecompressor::new())),
+            ContentCoding::Deflate => Some(Box::new(DeflateDecompressor::new())),
+            ContentCoding::Other(name) if name == "x-gzip" => {
+                Some(Box::new(GzipDecompressor::new()))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(not(feature = "compression-impl"))]
+    fn create(&self, _coding: &ContentCoding) -> Option<Box<dyn Decompressor>> {
+        None
+    }
+}
+
+/// ボディの読み出し元
+///
+/// `RequestDecoder` / `ResponseDecoder` が実装する。
+pub trait BodySource {
+    /// 利用可能なボディデータを覗く
+    fn peek_body(&self) -> Option<&[u8]>;
+
+    /// ボディデータを消費する
+    fn consume_body(&mut self, len: usize) -> Result<BodyProgress, Error>;
+
+    /// 状態機械を進める
+    fn progress(&mut self) -> Result<BodyProgress, Error>;
+}
+
+impl<D: Decompressor> BodySource for RequestDecoder<D> {
+    fn peek_body(&self) -> Option<&[u8]> {
+        RequestDecoder::peek_body(self)
+    }
+
+    fn consume_body(&mut self, len: usize) -> Result<BodyProgress, Error> {
+        RequestDecoder::consume_body(self, len)
+    }
+
+    fn progress(&mut self) -> Result<BodyProgress, Error> {
+        RequestDecoder::progress(self)
+    }
+}
+
+impl<D: Decompressor> BodySource for ResponseDecoder<D> {
+    fn peek_body(&self) -> Option<&[u8]> {
+        ResponseDecoder::peek_body(self)
+    }
+
+    fn consume_body(&mut self, len: usize) -> Result<BodyProgress, Error> {
+        ResponseDecoder::consume_body(self, len)
+    }
+
+    fn progress(&mut self) -> Result<BodyProgress, Error> {
+        ResponseDecoder::progress(self)
+    }
+}
+
+/// Content-Encoding を展開しながらボディを読むリーダー
+///
+/// 1 メッセージごとに作成する。
+pub struct ContentDecoder {
+    decompressor: Box<dyn Decompressor>,
+    /// 展開済みデータのバッファ (`buf[start..end]` が未読)
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+    max_decoded_size: usize,
+    /// 展開済みの合計バイト数
+    decoded: usize,
+    /// 読み出し元からボディデータを受け取った
+    received: bool,
+    /// 読み出し元のボディが完了した
+    body_complete: bool,
+    /// 展開器が圧縮データの終端に達した
+    stream_complete: bool,
+    /// 展開済みデータをすべて取り出した
+    complete: bool,
+    trailers: Vec<(Cow<'static, str>, String)>,
+}
+
+impl fmt::Debug for ContentDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContentDecoder")
+            .field("buffered", &(self.end - self.start))
+            .field("max_decoded_size", &self.max_decoded_size)
+            .field("decoded", &self.decoded)
+            .field("body_complete", &self.body_complete)
+            .field("stream_complete", &self.stream_complete)
+            .field("complete", &self.complete)
+            .finish()
+    }
+}
+
+impl ContentDecoder {
+    fn with_decompressor(decompressor: Box<dyn Decompressor>) -> Self {
+        Self {
+            decompressor,
+            buf: vec![0; DEFAULT_BUFFER_SIZE],
+            start: 0,
+            end: 0,
+            max_decoded_size: DEFAULT_MAX_DECODED_SIZE,
+            decoded: 0,
+            received: false,
+            body_complete: false,
+            stream_complete: false,
+            complete: false,
+            trailers: Vec::new(),
+        }
+    }
+
+    /// 展開しないリーダーを作成
+    pub fn identity() -> Self {
+        Self::with_decompressor(Box::new(NoCompression::new()))
+    }
+
+    /// Content-Encoding からリーダーを作成
+    ///
+    /// `identity` は展開しない。複数のコーディングは適用した順の逆に展開する。
+    pub fn new(
+        encoding: &ContentEncoding,
+        factory: &dyn DecompressorFactory,
+    ) -> Result<Self, ContentDecoderError> {
+        let mut decompressors = Vec::new();
+        for coding in encoding.decoding_order() {
+            let decompressor =
+                factory
+                    .create(coding)
+                    .ok_or_else(|| ContentDecoderError::UnsupportedCoding {
+                        coding: coding.as_str().to_string(),
+                    })?;
+            decompressors.push(decompressor);
+        }
+        Ok(match decompressors.len() {
+            0 => Self::identity(),
+            1 => Self::with_decompressor(decompressors.remove(0)),
+            _ => Self::with_decompressor(Box::new(DecompressorChain::new(decompressors))),
+        })
+    }
+
+    /// ヘッダーの Content-Encoding からリーダーを作成
+    ///
+    /// Content-Encoding ヘッダーがない場合は展開しない。
+    pub fn from_head<H: HttpHead>(
+        head: &H,
+        factory: &dyn DecompressorFactory,
+    ) -> Result<Self, ContentDecoderError> {
+        match head.get_typed::<ContentEncoding>() {
+            Some(Ok(encoding)) => Self::new(&encoding, factory),
+            Some(Err(e)) => Err(ContentDecoderError::InvalidContentEncoding(e)),
+            None => Ok(Self::identity()),
+        }
+    }
+
+    /// 展開後のボディサイズの上限を設定
+    pub fn with_max_decoded_size(mut self, limit: usize) -> Self {
+        self.max_decoded_size = limit;
+        self
+    }
+
+    /// 展開用バッファのサイズを設定 (1 以上)
+    ///
+    /// `peek_body()` が一度に返すデータの最大バイト数になる。
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.buf = vec![0; size.max(1)];
+        self
+    }
+
+    /// 展開済みのボディデータを覗く
+    ///
+    /// 未読の展開済みデータがなければ `source` からボディを読んで展開する。
+    ///
+    /// # 戻り値
+    /// - `Ok(Some(data))`: 展開済みデータ。処理した分を `consume_body()` で消費する
+    /// - `Ok(None)`: 追加の `feed()` が必要、またはボディが完了した (`is_complete()` で判別)
+    pub fn peek_body<S: BodySource + ?Sized>(
+        &mut self,
+        source: &mut S,
+    ) -> Result<Option<&[u8]>, ContentDecoderError> {
+        while self.start == self.end && !self.complete {
+            if !self.fill(source)? {
+                break;
+            }
+        }
+        if self.start < self.end {
+            Ok(Some(&self.buf[self.start..self.end]))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// `peek_body()` で取得したデータを消費する
+    pub fn consume_body(&mut self, len: usize) {
+        self.start = (self.start + len).min(self.end);
+    }
+
+    /// ボディの展開済みデータをすべて取り出したかどうか
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// 展開済みの合計バイト数
+    pub fn decoded_len(&self) -> usize {
+        self.decoded
+    }
+
+    /// chunked ボディのトレーラー
+    pub fn trailers(&self) -> &[(Cow<'static, str>, String)] {
+        &self.trailers
+    }
+
+    /// 展開してバッファを埋める。追加の入力が必要な場合は false を返す
+    fn fill<S: BodySource + ?Sized>(
+        &mut self,
+        source: &mut S,
+    ) -> Result<bool, ContentDecoderError> {
+        self.start = 0;
+        self.end = 0;
+
+        if self.body_complete {
+            // 空のボディ (HEAD へのレスポンスや 204 等) は展開しない
+            if !self.received {
+                self.complete = true;
+                return Ok(true);
+            }
+            // 展開器の内部に残ったデータを取り出す
+            let status = self.decompressor.decompress(&[], &mut self.buf)?;
+            self.produced(status.produced())?;
+            if status.is_complete() {
+                self.complete = true;
+            } else if status.produced() == 0 {
+                return Err(ContentDecoderError::Truncated);
+            }
+            return Ok(true);
+        }
+
+        if let Some(input) = source.peek_body() {
+            if self.stream_complete {
+                return Err(ContentDecoderError::TrailingData);
+            }
+            self.received = true;
+            let status = self.decompressor.decompress(input, &mut self.buf)?;
+            self.produced(status.produced())?;
+            if status.is_complete() {
+                self.stream_complete = true;
+            }
+            if status.consumed() > 0 {
+                if let BodyProgress::Complete { trailers } =
+                    source.consume_body(status.consumed())?
+                {
+                    self.body_complete = true;
+                    self.trailers = trailers;
+                }
+            } else if status.produced() == 0 && !self.stream_complete {
+                return Err(ContentDecoderError::Decompression(
+                    CompressionError::Internal("decompressor made no progress".to_string()),
+                ));
+            }
+            return Ok(true);
+        }
+
+        match source.progress()? {
+            BodyProgress::Complete { trailers } => {
+                self.body_complete = true;
+                self.trailers = trailers;
+                Ok(true)
+            }
+            BodyProgress::Advanced => Ok(true),
+            BodyProgress::NeedData => Ok(false),
+        }
+    }
+
+    fn produced(&mut self, len: usize) -> Result<(), ContentDecoderError> {
+        self.end = len;
+        self.decoded = self.decoded.saturating_add(len);
+        if self.decoded > self.max_decoded_size {
+            return Err(ContentDecoderError::DecodedTooLarge {
+                size: self.decoded,
+                limit: self.max_decoded_size,
+            });
+        }
+        Ok(())
+    }
+}
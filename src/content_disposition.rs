@@ -23,6 +23,8 @@ use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::ext_value::ExtValue;
+use crate::typed_header::TypedHeader;
 use crate::validate::{escape_quotes, is_qdtext_char, is_quoted_pair_char, is_valid_token};
 
 /// Content-Disposition パースエラー
@@ -135,8 +137,8 @@ pub struct ContentDisposition {
     disposition_type: DispositionType,
     /// filename パラメータ (ASCII)
     filename: Option<String>,
-    /// filename* パラメータ (RFC 8187 エンコード済み、デコード後の値)
-    filename_ext: Option<String>,
+    /// filename* パラメータ (RFC 8187 ext-value)
+    filename_ext: Option<ExtValue>,
     /// name パラメータ (form-data 用)
     name: Option<String>,
     /// その他のパラメータ
@@ -208,7 +210,10 @@ impl ContentDisposition {
                         cd.filename = Some(parse_param_value(param_value)?);
                     }
                     "filename*" => {
-                        cd.filename_ext = Some(parse_ext_value(param_value)?);
+                        cd.filename_ext = Some(
+                            ExtValue::parse(param_value)
+                                .map_err(|_| ContentDispositionError::InvalidExtValue)?,
+                        );
                     }
                     "name" => {
                         cd.name = Some(parse_param_value(param_value)?);
@@ -244,7 +249,7 @@ impl ContentDisposition {
     ///
     /// RFC 6266 Section 4.3 に従い、filename* が存在する場合はそちらを優先します。
     pub fn filename(&self) -> Option<&str> {
-        self.filename_ext.as_deref().or(self.filename.as_deref())
+        self.filename_ext().or(self.filename.as_deref())
     }
 
     /// filename パラメータを取得 (ASCII のみ)
@@ -254,7 +259,12 @@ impl ContentDisposition {
 
     /// filename* パラメータを取得 (デコード済み)
     pub fn filename_ext(&self) -> Option<&str> {
-        self.filename_ext.as_deref()
+        self.filename_ext.as_ref().map(ExtValue::value)
+    }
+
+    /// filename* パラメータの言語タグを取得
+    pub fn filename_ext_language(&self) -> Option<&str> {
+        self.filename_ext.as_ref()?.language()
     }
 
     /// name パラメータを取得 (form-data 用)
@@ -296,7 +306,43 @@ impl ContentDisposition {
 
     /// filename* を設定 (UTF-8 でエンコード)
     pub fn with_filename_ext(mut self, filename: &str) -> Self {
-        self.filename_ext = Some(filename.to_string());
+        self.filename_ext = Some(ExtValue::new(filename));
+        self
+    }
+
+    /// 任意の文字を含むファイル名を設定
+    ///
+    /// RFC 6266 Appendix D に従い、ファイル名が ASCII の表示可能文字のみであれば
+    /// filename だけを設定する。それ以外の場合は filename* (RFC 8187, UTF-8) を設定し、
+    /// filename* に対応しない受信者向けに ASCII 以外の文字、制御文字、`%`、`\` を
+    /// `_` に置き換えた filename も設定する。
+    ///
+    /// ```rust
+    /// use shiguredo_http11::content_disposition::{ContentDisposition, DispositionType};
+    ///
+    /// let cd = ContentDisposition::new(DispositionType::Attachment).with_filename_utf8("報告書.pdf");
+    /// assert_eq!(
+    ///     cd.to_string(),
+    ///     "attachment; filename=\"___.pdf\"; filename*=UTF-8''%E5%A0%B1%E5%91%8A%E6%9B%B8.pdf"
+    /// );
+    ///
+    /// let cd = ContentDisposition::parse(&cd.to_string()).unwrap();
+    /// assert_eq!(cd.filename(), Some("報告書.pdf"));
+    /// assert_eq!(cd.filename_ascii(), Some("___.pdf"));
+    /// ```
+    pub fn with_filename_utf8(mut self, filename: &str) -> Self {
+        let is_safe = |c: char| c == ' ' || (c.is_ascii_graphic() && c != '%' && c != '\\');
+        if filename.chars().all(is_safe) {
+            self.filename = Some(filename.to_string());
+            self.filename_ext = None;
+        } else {
+            let fallback: String = filename
+                .chars()
+                .map(|c| if is_safe(c) { c } else { '_' })
+                .collect();
+            self.filename = Some(fallback);
+            self.filename_ext = Some(ExtValue::new(filename));
+        }
         self
     }
 
@@ -320,7 +366,7 @@ impl fmt::Display for ContentDisposition {
         }
 
         if let Some(filename_ext) = &self.filename_ext {
-            write!(f, "; filename*=UTF-8''{}", encode_ext_value(filename_ext))?;
+            write!(f, "; filename*={}", filename_ext)?;
         }
 
         for (name, value) in &self.parameters {
@@ -331,6 +377,19 @@ impl fmt::Display for ContentDisposition {
     }
 }
 
+impl TypedHeader for ContentDisposition {
+    const NAME: &'static str = "Content-Disposition";
+    type Error = ContentDispositionError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// 引用符を考慮してセミコロンで分割
 fn split_params(input: &str) -> Vec<String> {
     let mut parts = Vec::new();
@@ -428,84 +487,6 @@ fn parse_quoted_string(s: &str) -> Result<String, ContentDispositionError> {
     Ok(result)
 }
 
-/// RFC 8187 ext-value をパース
-///
-/// 形式: charset'language'value
-/// 例: UTF-8''%E6%97%A5%E6%9C%AC%E8%AA%9E.txt
-fn parse_ext_value(value: &str) -> Result<String, ContentDispositionError> {
-    let value = value.trim();
-
-    // charset'language'value の形式
-    let first_quote = value
-        .find('\'')
-        .ok_or(ContentDispositionError::InvalidExtValue)?;
-    let charset = &value[..first_quote];
-
-    let rest = &value[first_quote + 1..];
-    let second_quote = rest
-        .find('\'')
-        .ok_or(ContentDispositionError::InvalidExtValue)?;
-    // language は無視 (オプション)
-    let encoded_value = &rest[second_quote + 1..];
-
-    // charset は UTF-8 のみサポート (RFC 6266 推奨)
-    if !charset.eq_ignore_ascii_case("UTF-8") {
-        return Err(ContentDispositionError::InvalidExtValue);
-    }
-
-    // パーセントデコード
-    percent_decode(encoded_value)
-}
-
-/// パーセントデコード
-fn percent_decode(s: &str) -> Result<String, ContentDispositionError> {
-    let mut bytes = Vec::with_capacity(s.len());
-    let mut chars = s.chars();
-
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            let hex: String = chars.by_ref().take(2).collect();
-            if hex.len() != 2 {
-                return Err(ContentDispositionError::InvalidExtValue);
-            }
-            let byte = u8::from_str_radix(&hex, 16)
-                .map_err(|_| ContentDispositionError::InvalidExtValue)?;
-            bytes.push(byte);
-        } else {
-            // RFC 8187 Section 3.2: パーセントエンコード以外は attr-char のみ許可
-            if !c.is_ascii() || !is_attr_char(c as u8) {
-                return Err(ContentDispositionError::InvalidExtValue);
-            }
-            bytes.push(c as u8);
-        }
-    }
-
-    String::from_utf8(bytes).map_err(|_| ContentDispositionError::InvalidExtValue)
-}
-
-/// RFC 8187 ext-value 用にエンコード
-fn encode_ext_value(s: &str) -> String {
-    let mut result = String::new();
-    for byte in s.bytes() {
-        if is_attr_char(byte) {
-            result.push(byte as char);
-        } else {
-            result.push('%');
-            result.push_str(&alloc::format!("{:02X}", byte));
-        }
-    }
-    result
-}
-
-/// RFC 8187 attr-char
-fn is_attr_char(b: u8) -> bool {
-    matches!(b,
-        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' |
-        b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' |
-        b'^' | b'_' | b'`' | b'|' | b'~'
-    )
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
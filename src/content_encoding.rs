@@ -13,11 +13,36 @@
 //! assert!(ce.has_gzip());
 //! assert!(ce.has_deflate());
 //! ```
+//!
+//! ## 複数のコーディング
+//!
+//! RFC 9110 Section 8.4: 複数のコーディングは適用した順に並ぶ。
+//! 展開は末尾のコーディングから行う。
+//!
+//! ```rust
+//! use shiguredo_http11::content_encoding::{ContentCoding, ContentEncoding};
+//!
+//! let mut ce = ContentEncoding::parse("gzip, br").unwrap();
+//! let order: Vec<&str> = ce.decoding_order().map(ContentCoding::as_str).collect();
+//! assert_eq!(order, ["br", "gzip"]);
+//!
+//! // 最後に適用したコーディングを取り除く (br を展開した後)
+//! assert_eq!(ce.pop(), Some(ContentCoding::Other("br".to_string())));
+//! assert_eq!(ce.to_string(), "gzip");
+//!
+//! // コーディングを追加で適用する
+//! ce.push(ContentCoding::parse("zstd").unwrap());
+//! assert_eq!(ce.to_string(), "gzip, zstd");
+//!
+//! // identity は単独でのみ使える
+//! assert!(ContentEncoding::parse("gzip, identity").unwrap().validate().is_err());
+//! ```
 
-use alloc::string::String;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::typed_header::TypedHeader;
 use crate::validate::is_valid_token;
 
 /// Content-Encoding パースエラー
@@ -30,6 +55,8 @@ pub enum ContentEncodingError {
     InvalidFormat,
     /// 不正なエンコーディングトークン
     InvalidEncoding,
+    /// identity が他のコーディングと一緒に指定されている
+    IdentityNotAlone,
 }
 
 impl fmt::Display for ContentEncodingError {
@@ -42,6 +69,12 @@ impl fmt::Display for ContentEncodingError {
             ContentEncodingError::InvalidEncoding => {
                 write!(f, "invalid Content-Encoding token")
             }
+            ContentEncodingError::IdentityNotAlone => {
+                write!(
+                    f,
+                    "identity must not be combined with other content codings"
+                )
+            }
         }
     }
 }
@@ -59,6 +92,11 @@ pub enum ContentCoding {
 }
 
 impl ContentCoding {
+    /// トークンからパース (大文字小文字を区別しない)
+    pub fn parse(token: &str) -> Result<Self, ContentEncodingError> {
+        parse_coding(token.trim())
+    }
+
     /// 正規化したトークン値
     pub fn as_str(&self) -> &str {
         match self {
@@ -77,7 +115,20 @@ pub struct ContentEncoding {
     encodings: Vec<ContentCoding>,
 }
 
+impl Default for ContentEncoding {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ContentEncoding {
+    /// 空の Content-Encoding を作成
+    pub fn new() -> Self {
+        ContentEncoding {
+            encodings: Vec::new(),
+        }
+    }
+
     /// Content-Encoding ヘッダーをパース
     ///
     /// RFC 9110 Section 5.6.1.2: 受信者は空のリスト要素を無視しなければならない (MUST)。
@@ -100,11 +151,65 @@ impl ContentEncoding {
         Ok(ContentEncoding { encodings })
     }
 
-    /// エンコーディング一覧
+    /// エンコーディング一覧 (適用した順)
     pub fn encodings(&self) -> &[ContentCoding] {
         &self.encodings
     }
 
+    /// 展開する順 (適用した順の逆) にコーディングを返す
+    ///
+    /// identity は含まない。
+    pub fn decoding_order(&self) -> impl Iterator<Item = &ContentCoding> {
+        self.encodings
+            .iter()
+            .rev()
+            .filter(|coding| !matches!(coding, ContentCoding::Identity))
+    }
+
+    /// 最後に適用したコーディングを取得
+    pub fn last(&self) -> Option<&ContentCoding> {
+        self.encodings.last()
+    }
+
+    /// 新たに適用したコーディングを末尾に追加
+    ///
+    /// identity は追加しない。既存の identity は取り除く。
+    pub fn push(&mut self, coding: ContentCoding) {
+        if matches!(coding, ContentCoding::Identity) {
+            return;
+        }
+        self.encodings
+            .retain(|coding| !matches!(coding, ContentCoding::Identity));
+        self.encodings.push(coding);
+    }
+
+    /// 最後に適用したコーディングを取り除く
+    ///
+    /// 展開した後のヘッダーを作る場合に使う。
+    pub fn pop(&mut self) -> Option<ContentCoding> {
+        self.encodings.pop()
+    }
+
+    /// コーディングが適用されていないかどうか
+    ///
+    /// 空または identity のみの場合に true を返す。
+    pub fn is_identity(&self) -> bool {
+        self.encodings
+            .iter()
+            .all(|coding| matches!(coding, ContentCoding::Identity))
+    }
+
+    /// コーディングの並びを検証
+    ///
+    /// identity は「コーディングなし」を表すため、他のコーディングと
+    /// 一緒に指定されている場合は `IdentityNotAlone` を返す。
+    pub fn validate(&self) -> Result<(), ContentEncodingError> {
+        if self.has_identity() && self.encodings.len() > 1 {
+            return Err(ContentEncodingError::IdentityNotAlone);
+        }
+        Ok(())
+    }
+
     /// gzip を含むかどうか
     pub fn has_gzip(&self) -> bool {
         self.encodings
@@ -141,6 +246,19 @@ impl fmt::Display for ContentEncoding {
     }
 }
 
+impl TypedHeader for ContentEncoding {
+    const NAME: &'static str = "Content-Encoding";
+    type Error = ContentEncodingError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 fn parse_coding(token: &str) -> Result<ContentCoding, ContentEncodingError> {
     if token.is_empty() {
         return Err(ContentEncodingError::InvalidFormat);
@@ -4,20 +4,50 @@
 //!
 //! RFC 9110 に基づいた Content-Language ヘッダーのパースを提供します。
 //!
+//! 言語タグは `LanguageTag` で BCP 47 (RFC 5646) の well-formed を検証する。
+//! privateuse (`x-...`)、拡張サブタグ (`u-ca-gregory` など)、grandfathered
+//! (`i-klingon` など) にも対応する。レジストリに基づく妥当性 (valid) は検証しない。
+//!
+//! `LanguageTag::matches_basic_range()` / `LanguageTag::matches_extended_range()` は
+//! RFC 4647 の Basic Filtering / Extended Filtering による言語レンジとの照合を行う。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Language-Tag  = langtag / privateuse / grandfathered
+//! langtag       = language ["-" script] ["-" region] *("-" variant)
+//!                 *("-" extension) ["-" privateuse]
+//! language      = 2*3ALPHA ["-" extlang] / 4ALPHA / 5*8ALPHA
+//! extlang       = 3ALPHA *2("-" 3ALPHA)
+//! script        = 4ALPHA
+//! region        = 2ALPHA / 3DIGIT
+//! variant       = 5*8alphanum / (DIGIT 3alphanum)
+//! extension     = singleton 1*("-" (2*8alphanum))
+//! privateuse    = "x" 1*("-" (1*8alphanum))
+//! ```
+//!
 //! ## 使い方
 //!
 //! ```rust
-//! use shiguredo_http11::content_language::ContentLanguage;
+//! use shiguredo_http11::content_language::{ContentLanguage, LanguageTag};
 //!
 //! let cl = ContentLanguage::parse("en-US, ja").unwrap();
 //! assert_eq!(cl.tags().len(), 2);
+//!
+//! let tag = LanguageTag::parse("zh-Hant-TW-u-nu-hanidec").unwrap();
+//! assert_eq!(tag.language(), Some("zh"));
+//! assert_eq!(tag.script(), Some("Hant"));
+//! assert_eq!(tag.region(), Some("TW"));
+//! assert!(tag.matches_basic_range("zh-Hant"));
+//! assert!(tag.matches_extended_range("zh-*-TW"));
 //! ```
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
-use crate::validate::is_valid_language_tag;
+use crate::accept::basic_filter_matches;
+use crate::typed_header::TypedHeader;
 
 /// Content-Language パースエラー
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -56,7 +86,8 @@ pub struct ContentLanguage {
 impl ContentLanguage {
     /// Content-Language ヘッダーをパース
     ///
-    /// RFC 9110 Section 5.6.1.2: 空フィールド値・空要素は受理する
+    /// RFC 9110 Section 5.6.1.2: 空フィールド値・空要素は受理する。
+    /// 各言語タグは `LanguageTag::parse()` で well-formed かを検証する。
     pub fn parse(input: &str) -> Result<Self, ContentLanguageError> {
         let input = input.trim();
 
@@ -67,9 +98,7 @@ impl ContentLanguage {
             if tag.is_empty() {
                 continue;
             }
-            if !is_valid_language_tag(tag) {
-                return Err(ContentLanguageError::InvalidLanguageTag);
-            }
+            LanguageTag::parse(tag)?;
             tags.push(tag.to_string());
         }
 
@@ -80,6 +109,14 @@ impl ContentLanguage {
     pub fn tags(&self) -> &[String] {
         &self.tags
     }
+
+    /// 言語タグ一覧 (`LanguageTag`)
+    pub fn language_tags(&self) -> Vec<LanguageTag> {
+        self.tags
+            .iter()
+            .filter_map(|tag| LanguageTag::parse(tag).ok())
+            .collect()
+    }
 }
 
 impl fmt::Display for ContentLanguage {
@@ -87,3 +124,324 @@ impl fmt::Display for ContentLanguage {
         write!(f, "{}", self.tags.join(", "))
     }
 }
+
+impl TypedHeader for ContentLanguage {
+    const NAME: &'static str = "Content-Language";
+    type Error = ContentLanguageError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// RFC 5646 Section 2.2.8: irregular な grandfathered タグ
+const IRREGULAR_GRANDFATHERED: &[&str] = &[
+    "en-GB-oed",
+    "i-ami",
+    "i-bnn",
+    "i-default",
+    "i-enochian",
+    "i-hak",
+    "i-klingon",
+    "i-lux",
+    "i-mingo",
+    "i-navajo",
+    "i-pwn",
+    "i-tao",
+    "i-tay",
+    "i-tsu",
+    "sgn-BE-FR",
+    "sgn-BE-NL",
+    "sgn-CH-DE",
+];
+
+/// RFC 5646 Section 2.2.8: regular な grandfathered タグ
+///
+/// langtag の構文にも一致するため、サブタグも分解する。
+const REGULAR_GRANDFATHERED: &[&str] = &[
+    "art-lojban",
+    "cel-gaulish",
+    "no-bok",
+    "no-nyn",
+    "zh-guoyu",
+    "zh-hakka",
+    "zh-min",
+    "zh-min-nan",
+    "zh-xiang",
+];
+
+/// BCP 47 (RFC 5646) 言語タグ
+///
+/// 大文字小文字は入力のまま保持する。RFC 5646 Section 2.1.1 により
+/// 言語タグの比較は大文字小文字を区別しない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageTag {
+    tag: String,
+    language: Option<String>,
+    extlangs: Vec<String>,
+    script: Option<String>,
+    region: Option<String>,
+    variants: Vec<String>,
+    extensions: Vec<String>,
+    private_use: Option<String>,
+    grandfathered: bool,
+}
+
+impl LanguageTag {
+    /// 言語タグをパース
+    ///
+    /// RFC 5646 Section 2.1 の ABNF に一致する (well-formed) かを検証する。
+    pub fn parse(input: &str) -> Result<Self, ContentLanguageError> {
+        let mut tag = LanguageTag {
+            tag: input.to_string(),
+            language: None,
+            extlangs: Vec::new(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+            extensions: Vec::new(),
+            private_use: None,
+            grandfathered: false,
+        };
+
+        if IRREGULAR_GRANDFATHERED
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case(input))
+        {
+            tag.grandfathered = true;
+            return Ok(tag);
+        }
+        tag.grandfathered = REGULAR_GRANDFATHERED
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case(input));
+
+        let subtags: Vec<&str> = input.split('-').collect();
+        if subtags
+            .iter()
+            .any(|s| s.is_empty() || s.len() > 8 || !s.bytes().all(|b| b.is_ascii_alphanumeric()))
+        {
+            return Err(ContentLanguageError::InvalidLanguageTag);
+        }
+
+        let mut i = 0;
+
+        // privateuse のみのタグ
+        if !subtags[0].eq_ignore_ascii_case("x") {
+            // language
+            let language = subtags[0];
+            if language.len() < 2 || !is_alpha(language) {
+                return Err(ContentLanguageError::InvalidLanguageTag);
+            }
+            tag.language = Some(language.to_string());
+            i += 1;
+
+            // extlang (2*3ALPHA の language のみ)
+            if language.len() <= 3 {
+                while i < subtags.len()
+                    && tag.extlangs.len() < 3
+                    && subtags[i].len() == 3
+                    && is_alpha(subtags[i])
+                {
+                    tag.extlangs.push(subtags[i].to_string());
+                    i += 1;
+                }
+            }
+
+            // script
+            if i < subtags.len() && subtags[i].len() == 4 && is_alpha(subtags[i]) {
+                tag.script = Some(subtags[i].to_string());
+                i += 1;
+            }
+
+            // region
+            if i < subtags.len() && is_region(subtags[i]) {
+                tag.region = Some(subtags[i].to_string());
+                i += 1;
+            }
+
+            // variant
+            while i < subtags.len() && is_variant(subtags[i]) {
+                tag.variants.push(subtags[i].to_string());
+                i += 1;
+            }
+
+            // extension
+            while i < subtags.len()
+                && subtags[i].len() == 1
+                && !subtags[i].eq_ignore_ascii_case("x")
+            {
+                let start = i;
+                i += 1;
+                while i < subtags.len() && subtags[i].len() >= 2 {
+                    i += 1;
+                }
+                if i == start + 1 {
+                    return Err(ContentLanguageError::InvalidLanguageTag);
+                }
+                tag.extensions.push(subtags[start..i].join("-"));
+            }
+        }
+
+        // privateuse
+        if i < subtags.len() && subtags[i].eq_ignore_ascii_case("x") {
+            if i + 1 == subtags.len() {
+                return Err(ContentLanguageError::InvalidLanguageTag);
+            }
+            tag.private_use = Some(subtags[i + 1..].join("-"));
+            i = subtags.len();
+        }
+
+        if i != subtags.len() {
+            return Err(ContentLanguageError::InvalidLanguageTag);
+        }
+        Ok(tag)
+    }
+
+    /// 言語タグ (入力のまま)
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// 主言語サブタグ
+    ///
+    /// privateuse のみのタグと irregular な grandfathered タグは `None`。
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// 拡張言語サブタグ (extlang)
+    pub fn extlangs(&self) -> &[String] {
+        &self.extlangs
+    }
+
+    /// 文字体系サブタグ (script)
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    /// 地域サブタグ (region)
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// 変種サブタグ (variant)
+    pub fn variants(&self) -> &[String] {
+        &self.variants
+    }
+
+    /// 拡張 (singleton から始まる `u-ca-gregory` などのサブタグ列)
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// `x-` に続く privateuse サブタグ列
+    pub fn private_use(&self) -> Option<&str> {
+        self.private_use.as_deref()
+    }
+
+    /// privateuse のみのタグ (`x-...`) かどうか
+    pub fn is_private_use(&self) -> bool {
+        self.language.is_none() && self.private_use.is_some()
+    }
+
+    /// grandfathered タグかどうか
+    pub fn is_grandfathered(&self) -> bool {
+        self.grandfathered
+    }
+
+    /// Basic Filtering で言語レンジに一致するか (RFC 4647 Section 3.3.1)
+    ///
+    /// `*` はすべてのタグに一致する。それ以外はレンジがタグと等しいか、
+    /// タグの先頭からサブタグ単位で一致する場合に一致する。
+    /// 不正なレンジは一致しない。
+    pub fn matches_basic_range(&self, range: &str) -> bool {
+        (range == "*" || is_valid_basic_range(range)) && basic_filter_matches(range, &self.tag)
+    }
+
+    /// Extended Filtering で言語レンジに一致するか (RFC 4647 Section 3.3.2)
+    ///
+    /// `*` のサブタグは任意個のサブタグに一致する。
+    /// レンジのサブタグはタグの singleton を越えて一致させない。
+    /// 不正なレンジは一致しない。
+    pub fn matches_extended_range(&self, range: &str) -> bool {
+        if !is_valid_extended_range(range) {
+            return false;
+        }
+        let mut range = range.split('-');
+        let mut tag = self.tag.split('-');
+
+        // 1. 先頭サブタグを比較する
+        let (Some(first_range), Some(first_tag)) = (range.next(), tag.next()) else {
+            return false;
+        };
+        if first_range != "*" && !first_range.eq_ignore_ascii_case(first_tag) {
+            return false;
+        }
+
+        // 2. 残りのサブタグを比較する
+        let mut current_tag = tag.next();
+        for subtag in range {
+            if subtag == "*" {
+                continue;
+            }
+            loop {
+                let Some(t) = current_tag else {
+                    return false;
+                };
+                if t.eq_ignore_ascii_case(subtag) {
+                    current_tag = tag.next();
+                    break;
+                }
+                if t.len() == 1 {
+                    return false;
+                }
+                current_tag = tag.next();
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag)
+    }
+}
+
+fn is_alpha(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_region(s: &str) -> bool {
+    (s.len() == 2 && is_alpha(s)) || (s.len() == 3 && s.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_variant(s: &str) -> bool {
+    s.len() >= 5 || (s.len() == 4 && s.as_bytes()[0].is_ascii_digit())
+}
+
+/// language-range = (1*8ALPHA *("-" 1*8alphanum)) / "*"
+fn is_valid_basic_range(range: &str) -> bool {
+    let mut subtags = range.split('-');
+    subtags
+        .next()
+        .is_some_and(|s| !s.is_empty() && s.len() <= 8 && is_alpha(s))
+        && subtags
+            .all(|s| !s.is_empty() && s.len() <= 8 && s.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+/// extended-language-range = (1*8ALPHA / "*") *("-" (1*8alphanum / "*"))
+fn is_valid_extended_range(range: &str) -> bool {
+    let mut subtags = range.split('-');
+    subtags
+        .next()
+        .is_some_and(|s| s == "*" || (!s.is_empty() && s.len() <= 8 && is_alpha(s)))
+        && subtags.all(|s| {
+            s == "*"
+                || (!s.is_empty() && s.len() <= 8 && s.bytes().all(|b| b.is_ascii_alphanumeric()))
+        })
+}
@@ -13,7 +13,9 @@
 //! assert_eq!(cl.uri().path(), "/assets/logo.png");
 //! ```
 
+use crate::typed_header::TypedHeader;
 use crate::uri::Uri;
+use alloc::string::{String, ToString};
 use core::fmt;
 
 /// Content-Location パースエラー
@@ -88,6 +90,19 @@ impl fmt::Display for ContentLocation {
     }
 }
 
+impl TypedHeader for ContentLocation {
+    const NAME: &'static str = "Content-Location";
+    type Error = ContentLocationError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
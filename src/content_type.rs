@@ -21,12 +21,23 @@
 //! // multipart/form-data
 //! let ct = ContentType::parse("multipart/form-data; boundary=----WebKitFormBoundary").unwrap();
 //! assert_eq!(ct.boundary(), Some("----WebKitFormBoundary"));
+//!
+//! // essence (パラメータを除いた type/subtype) での比較
+//! let ct = ContentType::parse("Application/JSON; charset=utf-8").unwrap();
+//! assert!(ct.essence_eq(&ContentType::new("application", "json")));
+//! assert!(ct.is_essence("application/json"));
+//!
+//! // 構造化構文サフィックス (+json / +xml)
+//! let ct = ContentType::parse("application/problem+json").unwrap();
+//! assert_eq!(ct.suffix(), Some("json"));
+//! assert!(ct.is_json_compatible());
 //! ```
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::typed_header::TypedHeader;
 use crate::validate::{
     QuotedStringError, escape_quotes, is_token_char, is_valid_token, parse_quoted_string,
 };
@@ -152,12 +163,53 @@ impl ContentType {
         alloc::format!("{}/{}", self.media_type, self.subtype)
     }
 
+    /// essence (パラメータを除いた type/subtype) が一致するかどうか
+    ///
+    /// RFC 9110 Section 8.3.1: type / subtype は大文字小文字を区別しない。
+    pub fn essence_eq(&self, other: &ContentType) -> bool {
+        self.media_type.eq_ignore_ascii_case(&other.media_type)
+            && self.subtype.eq_ignore_ascii_case(&other.subtype)
+    }
+
+    /// essence が `type/subtype` 形式の文字列と一致するかどうか
+    ///
+    /// 大文字小文字を区別しない。`essence` にパラメータを含めた場合は一致しない。
+    pub fn is_essence(&self, essence: &str) -> bool {
+        match essence.split_once('/') {
+            Some((media_type, subtype)) => {
+                self.media_type.eq_ignore_ascii_case(media_type.trim())
+                    && self.subtype.eq_ignore_ascii_case(subtype.trim())
+            }
+            None => false,
+        }
+    }
+
+    /// 構造化構文サフィックスを取得 (例: "application/ld+json" の "json")
+    ///
+    /// RFC 6838 Section 4.2.8: サブタイプの最後の "+" 以降をサフィックスとする。
+    /// "+" がない場合、または "+" が先頭か末尾にある場合は None を返す。
+    pub fn suffix(&self) -> Option<&str> {
+        let (base, suffix) = self.subtype.rsplit_once('+')?;
+        if base.is_empty() || suffix.is_empty() {
+            return None;
+        }
+        Some(suffix)
+    }
+
+    /// 構造化構文サフィックスが一致するかどうか (大文字小文字を区別しない)
+    pub fn has_suffix(&self, suffix: &str) -> bool {
+        self.suffix()
+            .is_some_and(|s| s.eq_ignore_ascii_case(suffix))
+    }
+
     /// パラメータを取得
+    ///
+    /// パラメータ名は大文字小文字を区別しない (RFC 9110 Section 5.6.6)。
+    /// 同名のパラメータが複数ある場合は最初のものを返す。
     pub fn parameter(&self, name: &str) -> Option<&str> {
-        let name_lower = name.to_ascii_lowercase();
         self.parameters
             .iter()
-            .find(|(n, _)| n == &name_lower)
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
             .map(|(_, v)| v.as_str())
     }
 
@@ -186,6 +238,23 @@ impl ContentType {
         self.media_type == "application" && self.subtype == "json"
     }
 
+    /// JSON として扱えるかどうか
+    ///
+    /// application/json、または "+json" サフィックスを持つメディアタイプ
+    /// (application/problem+json など) の場合に true を返す。
+    pub fn is_json_compatible(&self) -> bool {
+        self.is_json() || self.has_suffix("json")
+    }
+
+    /// XML として扱えるかどうか
+    ///
+    /// application/xml、text/xml (RFC 7303)、または "+xml" サフィックスを持つ
+    /// メディアタイプ (image/svg+xml など) の場合に true を返す。
+    pub fn is_xml_compatible(&self) -> bool {
+        (self.subtype == "xml" && (self.media_type == "application" || self.media_type == "text"))
+            || self.has_suffix("xml")
+    }
+
     /// multipart/* かどうか
     pub fn is_multipart(&self) -> bool {
         self.media_type == "multipart"
@@ -217,6 +286,19 @@ impl fmt::Display for ContentType {
     }
 }
 
+impl TypedHeader for ContentType {
+    const NAME: &'static str = "Content-Type";
+    type Error = ContentTypeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// セミコロンで分割 (最初のセミコロンのみ)
 fn split_at_semicolon(input: &str) -> (&str, &str) {
     if let Some(pos) = input.find(';') {
@@ -0,0 +1,321 @@
+//! クライアント向け Cookie ストア (RFC 6265)
+//!
+//! ## 概要
+//!
+//! レスポンスの Set-Cookie を保存し、リクエストに付与する Cookie ヘッダーを生成する
+//! Sans I/O な `CookieJar` を提供する。
+//!
+//! - Domain / Path 属性の処理と host-only Cookie (RFC 6265 Section 5.3)
+//! - domain-match / path-match / Secure による送信対象の選択 (RFC 6265 Section 5.4)
+//! - Max-Age / Expires による有効期限 (Max-Age を優先する)
+//! - `__Secure-` / `__Host-` プレフィックスと Secure Cookie の上書き保護 (RFC 6265bis)
+//!
+//! 時刻は UNIX 時間 (秒) で呼び出し側が渡す。
+//!
+//! Public Suffix List による Domain 属性の制限と SameSite によるクロスサイト判定は
+//! 行わない。必要な場合は呼び出し側で `store()` の前に判定すること。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::cookie::{CookieJar, SetCookie};
+//!
+//! let mut jar = CookieJar::new();
+//! let set_cookie = SetCookie::parse("session=abc; Path=/; Secure; HttpOnly", 2026).unwrap();
+//! assert!(jar.store("https://example.com/login", &set_cookie, 1_700_000_000));
+//!
+//! assert_eq!(
+//!     jar.cookie_header("https://example.com/app", 1_700_000_000),
+//!     Some("session=abc".to_string())
+//! );
+//! // Secure Cookie は http では送信しない
+//! assert_eq!(jar.cookie_header("http://example.com/app", 1_700_000_000), None);
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::{Cookie, CookieHeader, SetCookie};
+use crate::date::HttpDate;
+use crate::decoder::HttpHead;
+use crate::uri::Uri;
+
+/// 保存された Cookie (RFC 6265 Section 5.3)
+#[derive(Debug, Clone)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    /// 有効期限 (UNIX 時間)。None はセッション Cookie
+    expiry: Option<u64>,
+    /// 作成順 (同じ Cookie の置き換えでは引き継ぐ)
+    creation: u64,
+    host_only: bool,
+    secure: bool,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: u64) -> bool {
+        self.expiry.is_some_and(|expiry| expiry <= now)
+    }
+}
+
+/// リクエスト URL から取り出した Cookie の判定に使う要素
+struct RequestUrl {
+    host: String,
+    path: String,
+    secure: bool,
+}
+
+impl RequestUrl {
+    fn parse(url: &str) -> Option<Self> {
+        let uri = Uri::parse(url).ok()?;
+        let secure = match uri.scheme()? {
+            s if s.eq_ignore_ascii_case("https") || s.eq_ignore_ascii_case("wss") => true,
+            s if s.eq_ignore_ascii_case("http") || s.eq_ignore_ascii_case("ws") => false,
+            _ => return None,
+        };
+        let host = uri.host()?;
+        if host.is_empty() {
+            return None;
+        }
+        Some(RequestUrl {
+            host: host.to_ascii_lowercase(),
+            path: uri.path().to_string(),
+            secure,
+        })
+    }
+}
+
+/// クライアント向け Cookie ストア
+#[derive(Debug, Clone, Default)]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+    /// 作成順を決める論理時計
+    clock: u64,
+}
+
+impl CookieJar {
+    /// 空の CookieJar を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 保存している Cookie の数 (期限切れを含む)
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Cookie を保存していないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// すべての Cookie を削除
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+
+    /// 期限切れの Cookie を削除
+    pub fn remove_expired(&mut self, now: u64) {
+        self.cookies.retain(|cookie| !cookie.is_expired(now));
+    }
+
+    /// セッション Cookie (有効期限のない Cookie) を削除
+    pub fn clear_session_cookies(&mut self) {
+        self.cookies.retain(|cookie| cookie.expiry.is_some());
+    }
+
+    /// `request_url` へのレスポンスで受信した Set-Cookie を保存する
+    ///
+    /// RFC 6265 Section 5.3 に従い Cookie を保存し、保存した場合に true を返す。
+    /// 以下の場合は保存しない (false を返す)。
+    ///
+    /// - `request_url` が http / https / ws / wss の絶対 URL でない
+    /// - Domain 属性がリクエストのホストに domain-match しない
+    ///   (IP アドレスのホストではホストと完全一致する場合のみ許可する)
+    /// - Secure 属性を持つ Cookie、または `__Secure-` / `__Host-` で始まる Cookie を
+    ///   安全でない (http / ws) URL から受信した
+    /// - [`SetCookie::validate`] が失敗した (プレフィックスの条件や SameSite=None の Secure 必須)
+    /// - 安全でない URL から同名の Secure Cookie を上書きしようとした
+    /// - 既に期限切れ (同じ Cookie を保存していれば削除する)
+    pub fn store(&mut self, request_url: &str, set_cookie: &SetCookie, now: u64) -> bool {
+        let Some(url) = RequestUrl::parse(request_url) else {
+            return false;
+        };
+
+        let (domain, host_only) = match set_cookie.domain() {
+            Some(domain) => {
+                let domain = domain.to_ascii_lowercase();
+                if !domain_match(&url.host, &domain) {
+                    return false;
+                }
+                (domain, false)
+            }
+            None => (url.host.clone(), true),
+        };
+
+        let path = match set_cookie.path() {
+            Some(path) => path.to_string(),
+            None => default_path(&url.path),
+        };
+
+        // RFC 6265bis Section 5.7: 安全でない URL からは Secure Cookie を保存しない。
+        // `__Secure-` / `__Host-` は validate() で Secure 必須のためここで除外される
+        if (!url.secure && set_cookie.secure()) || set_cookie.validate().is_err() {
+            return false;
+        }
+
+        // RFC 6265bis Section 5.7: 安全でない URL からは既存の Secure Cookie を上書きしない
+        if !url.secure
+            && self.cookies.iter().any(|existing| {
+                existing.secure
+                    && existing.name == set_cookie.name()
+                    && (domain_match(&domain, &existing.domain)
+                        || domain_match(&existing.domain, &domain))
+                    && path_match(&path, &existing.path)
+            })
+        {
+            return false;
+        }
+
+        // RFC 6265 Section 5.3: Max-Age は Expires より優先する。0 以下は即時期限切れ
+        let expiry = match (set_cookie.max_age(), set_cookie.expires()) {
+            (Some(max_age), _) if max_age <= 0 => Some(0),
+            (Some(max_age), _) => Some(now.saturating_add(max_age as u64)),
+            (None, Some(expires)) => Some(expires.unix_seconds()),
+            (None, None) => None,
+        };
+
+        let existing = self.cookies.iter().position(|cookie| {
+            cookie.name == set_cookie.name() && cookie.domain == domain && cookie.path == path
+        });
+        let creation = match existing {
+            Some(index) => self.cookies.remove(index).creation,
+            None => {
+                self.clock += 1;
+                self.clock
+            }
+        };
+
+        let cookie = StoredCookie {
+            name: set_cookie.name().to_string(),
+            value: set_cookie.value().to_string(),
+            domain,
+            path,
+            expiry,
+            creation,
+            host_only,
+            secure: set_cookie.secure(),
+        };
+        if cookie.is_expired(now) {
+            return false;
+        }
+        self.cookies.push(cookie);
+        true
+    }
+
+    /// レスポンスのすべての Set-Cookie ヘッダーを保存し、保存した数を返す
+    ///
+    /// パースできない Set-Cookie ヘッダーは無視する。
+    pub fn store_response<H: HttpHead>(
+        &mut self,
+        request_url: &str,
+        response: &H,
+        now: u64,
+    ) -> usize {
+        // RFC 850 形式の 2 桁年の解決に使う現在年
+        let reference_year = HttpDate::from_unix_seconds(now)
+            .map(|date| date.year())
+            .unwrap_or(9999);
+        response
+            .get_headers("Set-Cookie")
+            .into_iter()
+            .filter_map(|value| SetCookie::parse(value, reference_year).ok())
+            .filter(|set_cookie| self.store(request_url, set_cookie, now))
+            .count()
+    }
+
+    /// `request_url` へのリクエストに付与する Cookie ヘッダーの値を生成する
+    ///
+    /// RFC 6265 Section 5.4 に従い、domain-match / path-match し、Secure Cookie は
+    /// 安全な URL の場合のみ選択する。Path が長い順、同じ長さでは作成が古い順に並べる。
+    /// 送信する Cookie がない場合は None を返す。
+    pub fn cookie_header(&self, request_url: &str, now: u64) -> Option<String> {
+        let url = RequestUrl::parse(request_url)?;
+        let mut matched: Vec<&StoredCookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| {
+                !cookie.is_expired(now)
+                    && if cookie.host_only {
+                        url.host == cookie.domain
+                    } else {
+                        domain_match(&url.host, &cookie.domain)
+                    }
+                    && path_match(&url.path, &cookie.path)
+                    && (!cookie.secure || url.secure)
+            })
+            .collect();
+        if matched.is_empty() {
+            return None;
+        }
+        matched.sort_by(|a, b| {
+            b.path
+                .len()
+                .cmp(&a.path.len())
+                .then(a.creation.cmp(&b.creation))
+        });
+
+        let mut header = CookieHeader::new();
+        for cookie in matched {
+            header.push(Cookie {
+                name: cookie.name.clone(),
+                value: cookie.value.clone(),
+            });
+        }
+        Some(header.to_string())
+    }
+}
+
+/// domain-match (RFC 6265 Section 5.1.3)
+fn domain_match(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+    host.len() > domain.len()
+        && host.ends_with(domain)
+        && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+        && !is_ip_address(host)
+}
+
+/// ホストが IP アドレスかどうか
+fn is_ip_address(host: &str) -> bool {
+    host.starts_with('[') || host.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+}
+
+/// default-path (RFC 6265 Section 5.1.4)
+fn default_path(uri_path: &str) -> String {
+    if !uri_path.starts_with('/') {
+        return "/".to_string();
+    }
+    match uri_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(pos) => uri_path[..pos].to_string(),
+    }
+}
+
+/// path-match (RFC 6265 Section 5.1.4)
+fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    let request_path = if request_path.is_empty() {
+        "/"
+    } else {
+        request_path
+    };
+    if request_path == cookie_path {
+        return true;
+    }
+    request_path.starts_with(cookie_path)
+        && (cookie_path.ends_with('/') || request_path.as_bytes()[cookie_path.len()] == b'/')
+}
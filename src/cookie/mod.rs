@@ -7,13 +7,17 @@
 //! ## 使い方
 //!
 //! ```rust
-//! use shiguredo_http11::cookie::{Cookie, SetCookie, SameSite};
+//! use shiguredo_http11::cookie::{Cookie, CookieHeader, SetCookie, SameSite};
 //!
 //! // Cookie ヘッダーパース
 //! let cookies = Cookie::parse("session=abc123; user=john").unwrap();
 //! assert_eq!(cookies[0].name(), "session");
 //! assert_eq!(cookies[0].value(), "abc123");
 //!
+//! // 寛容なパースと名前による取得
+//! let header = CookieHeader::parse("session=abc123; ; user=john;");
+//! assert_eq!(header.get("user"), Some("john"));
+//!
 //! // Set-Cookie ヘッダーパース
 //! let set_cookie = SetCookie::parse("session=abc123; Path=/; HttpOnly; Secure", 2026).unwrap();
 //! assert_eq!(set_cookie.name(), "session");
@@ -21,9 +25,24 @@
 //! assert_eq!(set_cookie.path(), Some("/"));
 //! assert!(set_cookie.http_only());
 //! assert!(set_cookie.secure());
+//!
+//! // Set-Cookie ヘッダー生成 (__Host- プレフィックスの条件を検証)
+//! let set_cookie = SetCookie::new("__Host-id", "1")
+//!     .unwrap()
+//!     .with_path("/")
+//!     .with_secure(true)
+//!     .with_same_site(SameSite::Lax);
+//! assert!(set_cookie.validate().is_ok());
+//! assert_eq!(set_cookie.to_string(), "__Host-id=1; Path=/; Secure; SameSite=Lax");
 //! ```
+//!
+//! クライアント側での Cookie の保存と送信は [`CookieJar`] を参照。
+
+mod jar;
 
-use crate::date::{DateError, HttpDate};
+pub use jar::CookieJar;
+
+use crate::date::HttpDate;
 use crate::validate::{is_token_char, trim_ows};
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
@@ -45,6 +64,10 @@ pub enum CookieError {
     InvalidAttribute,
     /// 不正な SameSite
     InvalidSameSite,
+    /// `__Secure-` / `__Host-` プレフィックスの条件を満たしていない
+    InvalidPrefix,
+    /// Secure 属性が必要な属性 (SameSite=None / Partitioned) に Secure がない
+    SecureRequired,
 }
 
 impl fmt::Display for CookieError {
@@ -56,6 +79,8 @@ impl fmt::Display for CookieError {
             CookieError::InvalidValue => write!(f, "invalid cookie value"),
             CookieError::InvalidAttribute => write!(f, "invalid cookie attribute"),
             CookieError::InvalidSameSite => write!(f, "invalid SameSite attribute"),
+            CookieError::InvalidPrefix => write!(f, "cookie name prefix requirements not met"),
+            CookieError::SecureRequired => write!(f, "cookie attribute requires Secure"),
         }
     }
 }
@@ -146,6 +171,122 @@ impl fmt::Display for Cookie {
     }
 }
 
+/// Cookie ヘッダー (リクエスト)
+///
+/// サーバーが受信した Cookie ヘッダーを name=value ペアに分割し、
+/// クライアントが送信する Cookie ヘッダーを組み立てる。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::cookie::CookieHeader;
+///
+/// // 不正なペアは読み飛ばす
+/// let header = CookieHeader::parse("session=abc; ; broken; theme=\"dark\"");
+/// assert_eq!(header.len(), 2);
+/// assert_eq!(header.get("session"), Some("abc"));
+/// assert_eq!(header.get("theme"), Some("dark"));
+///
+/// let header = CookieHeader::new()
+///     .with_cookie("a", "1")
+///     .unwrap()
+///     .with_cookie("b", "2")
+///     .unwrap();
+/// assert_eq!(header.to_string(), "a=1; b=2");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieHeader {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieHeader {
+    /// 空の Cookie ヘッダーを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cookie ヘッダー文字列を寛容にパース
+    ///
+    /// [`Cookie::parse`] と異なりエラーにせず、以下を読み飛ばす。
+    ///
+    /// - 空のペア (`a=1;; b=2` や末尾の `;`)
+    /// - `=` を含まないペア
+    /// - 名前が token でない、または値が cookie-octet 以外を含むペア
+    ///
+    /// 値を囲む引用符は除去する。同名の Cookie はすべて受信順に保持する。
+    pub fn parse(input: &str) -> Self {
+        let cookies = input
+            .split(';')
+            .map(trim_ows)
+            .filter(|pair| !pair.is_empty())
+            .filter_map(|pair| parse_cookie_pair(pair).ok())
+            .map(|(name, value)| Cookie {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        CookieHeader { cookies }
+    }
+
+    /// Cookie を追加
+    pub fn with_cookie(mut self, name: &str, value: &str) -> Result<Self, CookieError> {
+        self.cookies.push(Cookie::new(name, value)?);
+        Ok(self)
+    }
+
+    /// Cookie を追加
+    pub fn push(&mut self, cookie: Cookie) {
+        self.cookies.push(cookie);
+    }
+
+    /// Cookie のリストを取得
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    /// 指定した名前の最初の Cookie の値を取得 (大文字小文字を区別する)
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|cookie| cookie.name == name)
+            .map(|cookie| cookie.value.as_str())
+    }
+
+    /// 指定した名前の Cookie の値をすべて取得 (大文字小文字を区別する)
+    ///
+    /// Path の異なる同名 Cookie は Path が長い順に送信される (RFC 6265 Section 5.4)。
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.cookies
+            .iter()
+            .filter(|cookie| cookie.name == name)
+            .map(|cookie| cookie.value.as_str())
+            .collect()
+    }
+
+    /// Cookie の数
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Cookie がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+}
+
+impl fmt::Display for CookieHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // RFC 6265 Section 4.2.1: cookie-string = cookie-pair *( ";" SP cookie-pair )
+        for (i, cookie) in self.cookies.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", cookie)?;
+        }
+        Ok(())
+    }
+}
+
 /// SameSite 属性
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SameSite {
@@ -200,6 +341,8 @@ pub struct SetCookie {
     http_only: bool,
     /// SameSite 属性
     same_site: Option<SameSite>,
+    /// Partitioned 属性 (CHIPS)
+    partitioned: bool,
 }
 
 impl SetCookie {
@@ -208,6 +351,12 @@ impl SetCookie {
     /// `reference_year` は Expires 属性の RFC 850 形式 2 桁年解決に使う
     /// 現在年 (RFC 9110 §5.6.7)。
     ///
+    /// Expires は HTTP-date として解釈できない場合、RFC 6265 Section 5.1.1 の
+    /// cookie-date アルゴリズムで解釈する (`Wed, 21-Oct-2015 07:28:00 GMT` など)。
+    ///
+    /// ユーザーエージェントと同様に属性を寛容に解釈するため、`__Secure-` / `__Host-`
+    /// プレフィックスの条件は検証しない。検証が必要な場合は [`SetCookie::validate`] を使う。
+    ///
     /// # 例
     ///
     /// ```rust
@@ -242,6 +391,7 @@ impl SetCookie {
             secure: false,
             http_only: false,
             same_site: None,
+            partitioned: false,
         };
 
         // 属性をパース
@@ -258,13 +408,10 @@ impl SetCookie {
                 match attr_name.to_ascii_lowercase().as_str() {
                     "expires" => {
                         // RFC 6265 Section 5.2.1: 不正な Expires は無視
-                        let parsed = HttpDate::parse(attr_value).or_else(|e| match e {
-                            DateError::Rfc850Date => {
-                                HttpDate::parse_rfc850(attr_value, reference_year)
-                            }
-                            other => Err(other),
-                        });
-                        if let Ok(date) = parsed {
+                        let parsed = HttpDate::parse_any(attr_value, reference_year)
+                            .ok()
+                            .or_else(|| parse_cookie_date(attr_value));
+                        if let Some(date) = parsed {
                             set_cookie.expires = Some(date);
                         }
                     }
@@ -319,6 +466,7 @@ impl SetCookie {
                 match part.to_ascii_lowercase().as_str() {
                     "secure" => set_cookie.secure = true,
                     "httponly" => set_cookie.http_only = true,
+                    "partitioned" => set_cookie.partitioned = true,
                     _ => {
                         // 未知の属性は無視
                     }
@@ -348,6 +496,7 @@ impl SetCookie {
             secure: false,
             http_only: false,
             same_site: None,
+            partitioned: false,
         })
     }
 
@@ -396,6 +545,60 @@ impl SetCookie {
         self.same_site
     }
 
+    /// Partitioned 属性を取得
+    pub fn partitioned(&self) -> bool {
+        self.partitioned
+    }
+
+    /// サーバーが送信する Set-Cookie として妥当かどうかを検証
+    ///
+    /// - RFC 6265bis Section 4.1.3.1: `__Secure-` で始まる名前は Secure が必要
+    /// - RFC 6265bis Section 4.1.3.2: `__Host-` で始まる名前は Secure が必要で、
+    ///   Domain を持たず、Path は `/` でなければならない
+    /// - RFC 6265bis Section 4.1.2.7: SameSite=None は Secure が必要
+    /// - CHIPS: Partitioned は Secure が必要
+    /// - Domain は LDH と `.` のみ、Path は制御文字と `;` を含まない
+    ///
+    /// プレフィックスはユーザーエージェントと同じく大文字小文字を区別せずに判定する。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::cookie::{CookieError, SetCookie};
+    ///
+    /// let cookie = SetCookie::new("__Host-id", "1").unwrap().with_secure(true);
+    /// // Path=/ がない
+    /// assert_eq!(cookie.validate(), Err(CookieError::InvalidPrefix));
+    /// assert!(cookie.with_path("/").validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), CookieError> {
+        if let Some(domain) = &self.domain
+            && (domain.is_empty() || domain.starts_with('.') || !is_valid_domain_value(domain))
+        {
+            return Err(CookieError::InvalidAttribute);
+        }
+        if let Some(path) = &self.path
+            && !path.bytes().all(is_path_octet)
+        {
+            return Err(CookieError::InvalidAttribute);
+        }
+
+        if has_prefix_ignore_case(&self.name, "__Secure-") && !self.secure {
+            return Err(CookieError::InvalidPrefix);
+        }
+        if has_prefix_ignore_case(&self.name, "__Host-")
+            && (!self.secure || self.domain.is_some() || self.path.as_deref() != Some("/"))
+        {
+            return Err(CookieError::InvalidPrefix);
+        }
+
+        if (self.same_site == Some(SameSite::None) || self.partitioned) && !self.secure {
+            return Err(CookieError::SecureRequired);
+        }
+
+        Ok(())
+    }
+
     /// Expires を設定
     pub fn with_expires(mut self, expires: HttpDate) -> Self {
         self.expires = Some(expires);
@@ -437,6 +640,12 @@ impl SetCookie {
         self.same_site = Some(same_site);
         self
     }
+
+    /// Partitioned を設定
+    pub fn with_partitioned(mut self, partitioned: bool) -> Self {
+        self.partitioned = partitioned;
+        self
+    }
 }
 
 impl fmt::Display for SetCookie {
@@ -471,6 +680,10 @@ impl fmt::Display for SetCookie {
             write!(f, "; SameSite={}", same_site)?;
         }
 
+        if self.partitioned {
+            write!(f, "; Partitioned")?;
+        }
+
         Ok(())
     }
 }
@@ -532,6 +745,112 @@ fn is_valid_domain_value(s: &str) -> bool {
         .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.')
 }
 
+/// Path 属性値に使える文字
+///
+/// RFC 6265 Section 4.1.1: path-value = <any CHAR except CTLs or ";">
+fn is_path_octet(b: u8) -> bool {
+    (0x20..0x7F).contains(&b) && b != b';'
+}
+
+/// 大文字小文字を区別せずにプレフィックスを判定
+fn has_prefix_ignore_case(s: &str, prefix: &str) -> bool {
+    s.len() >= prefix.len() && s.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+/// cookie-date をパース (RFC 6265 Section 5.1.1)
+///
+/// ブラウザ互換のため区切り文字で分割したトークンから時刻 / 日 / 月 / 年を順に探す。
+/// 2 桁年は 70-99 を 1900 年代、0-69 を 2000 年代として解釈する。
+fn parse_cookie_date(input: &str) -> Option<HttpDate> {
+    let mut time = None;
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+
+    for token in input.split(is_cookie_date_delimiter) {
+        if token.is_empty() {
+            continue;
+        }
+        if time.is_none()
+            && let Some(t) = parse_cookie_date_time(token)
+        {
+            time = Some(t);
+        } else if day.is_none()
+            && let Some(d) = parse_cookie_date_digits(token, 1, 2)
+        {
+            day = Some(d);
+        } else if month.is_none()
+            && let Some(m) = parse_cookie_date_month(token)
+        {
+            month = Some(m);
+        } else if year.is_none()
+            && let Some(y) = parse_cookie_date_digits(token, 2, 4)
+        {
+            year = Some(y);
+        }
+    }
+
+    let (hour, minute, second) = time?;
+    let day = day?;
+    let month = month?;
+    let year = match year? {
+        y @ 70..=99 => y + 1900,
+        y @ 0..=69 => y + 2000,
+        y => y,
+    };
+
+    if !(1..=31).contains(&day) || year < 1601 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    HttpDate::from_civil(year as u16, month, day as u8, hour, minute, second).ok()
+}
+
+/// cookie-date の区切り文字
+///
+/// delimiter = %x09 / %x20-2F / %x3B-40 / %x5B-60 / %x7B-7E
+fn is_cookie_date_delimiter(c: char) -> bool {
+    matches!(c, '\x09' | '\x20'..='\x2F' | '\x3B'..='\x40' | '\x5B'..='\x60' | '\x7B'..='\x7E')
+}
+
+/// `min`-`max` 桁の数字 (後ろに数字以外が続いてもよい) をパース
+fn parse_cookie_date_digits(token: &str, min: usize, max: usize) -> Option<u32> {
+    let digits = token.bytes().take_while(u8::is_ascii_digit).count();
+    if digits < min || digits > max {
+        return None;
+    }
+    token[..digits].parse().ok()
+}
+
+/// hms-time = time-field ":" time-field ":" time-field (後ろに数字以外が続いてもよい)
+fn parse_cookie_date_time(token: &str) -> Option<(u8, u8, u8)> {
+    let mut fields = token.splitn(3, ':');
+    let hour = fields.next()?;
+    let minute = fields.next()?;
+    let second = fields.next()?;
+    if !(1..=2).contains(&hour.len())
+        || !hour.bytes().all(|b| b.is_ascii_digit())
+        || !(1..=2).contains(&minute.len())
+        || !minute.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let second = parse_cookie_date_digits(second, 1, 2)?;
+    Some((hour.parse().ok()?, minute.parse().ok()?, second as u8))
+}
+
+/// month = 月名の先頭 3 文字 (大文字小文字を区別しない)
+fn parse_cookie_date_month(token: &str) -> Option<u8> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let prefix = token.get(..3)?;
+    MONTHS
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(prefix))
+        .map(|i| i as u8 + 1)
+}
+
 /// Cookie 値に使える文字
 fn is_cookie_octet(b: u8) -> bool {
     b == 0x21
@@ -0,0 +1,590 @@
+//! CORS (Fetch Standard)
+//!
+//! ## 概要
+//!
+//! Fetch Standard の CORS プロトコルのサーバー側の処理を提供します。
+//!
+//! - Origin / Access-Control-Request-Method / Access-Control-Request-Headers のパース
+//! - 許可するオリジン・メソッド・ヘッダー、credentials、max-age を指定するポリシー
+//! - プリフライト (OPTIONS) と実際のリクエストに返す Access-Control-* ヘッダーの生成
+//! - レスポンスがオリジンによって変わる場合の `Vary: Origin` の付与
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::cors::{CorsOutcome, CorsPolicy, apply_cors};
+//! use shiguredo_http11::{Request, Response, StatusCode};
+//!
+//! let policy = CorsPolicy::new()
+//!     .with_allowed_origin("https://app.example")
+//!     .unwrap()
+//!     .with_allowed_method("PUT")
+//!     .unwrap()
+//!     .with_allowed_header("Content-Type")
+//!     .unwrap()
+//!     .with_max_age(600);
+//!
+//! // プリフライト
+//! let preflight = Request::new("OPTIONS", "/items/1")
+//!     .unwrap()
+//!     .header("Origin", "https://app.example")
+//!     .unwrap()
+//!     .header("Access-Control-Request-Method", "PUT")
+//!     .unwrap()
+//!     .header("Access-Control-Request-Headers", "content-type")
+//!     .unwrap();
+//! let outcome = policy.evaluate(preflight.method(), &preflight);
+//! assert!(matches!(outcome, CorsOutcome::Preflight { .. }));
+//! let response = Response::cors_preflight(&outcome).unwrap();
+//! assert_eq!(response.status_code(), 204);
+//! assert_eq!(response.get_header("Access-Control-Allow-Origin"), Some("https://app.example"));
+//! assert_eq!(response.get_header("Access-Control-Allow-Methods"), Some("PUT"));
+//! assert_eq!(response.get_header("Access-Control-Max-Age"), Some("600"));
+//! assert_eq!(response.get_header("Vary"), Some("Origin"));
+//!
+//! // 実際のリクエスト
+//! let request = Request::new("PUT", "/items/1")
+//!     .unwrap()
+//!     .header("Origin", "https://app.example")
+//!     .unwrap();
+//! let outcome = policy.evaluate(request.method(), &request);
+//! let response = Response::with_status(StatusCode::OK)
+//!     .header("Vary", "Accept-Encoding")
+//!     .unwrap();
+//! let response = apply_cors(response, &outcome).unwrap();
+//! assert_eq!(response.get_header("Access-Control-Allow-Origin"), Some("https://app.example"));
+//! assert_eq!(response.get_header("Vary"), Some("Accept-Encoding, Origin"));
+//!
+//! // 許可していないオリジン
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("Origin", "https://evil.example")
+//!     .unwrap();
+//! assert!(matches!(policy.evaluate(request.method(), &request), CorsOutcome::Rejected { .. }));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::host::Host;
+use crate::method::Method;
+use crate::response::Response;
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
+use crate::uri::default_port;
+use crate::validate::{is_valid_token, trim_ows};
+
+/// CORS のパースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorsError {
+    /// 不正な Origin
+    InvalidOrigin,
+    /// 不正なメソッド
+    InvalidMethod,
+    /// 不正なヘッダー名
+    InvalidHeaderName,
+}
+
+impl fmt::Display for CorsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorsError::InvalidOrigin => write!(f, "invalid Origin"),
+            CorsError::InvalidMethod => write!(f, "invalid CORS method"),
+            CorsError::InvalidHeaderName => write!(f, "invalid CORS header name"),
+        }
+    }
+}
+
+impl core::error::Error for CorsError {}
+
+/// Origin ヘッダー
+///
+/// `null` または `scheme "://" host [ ":" port ]` の形式。
+/// スキームとホストは小文字に、スキームのデフォルトポートは省略した形に正規化する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    /// `None` は `null` オリジン
+    tuple: Option<(String, Host)>,
+}
+
+impl Origin {
+    /// `null` オリジンを作成
+    pub fn null() -> Self {
+        Origin { tuple: None }
+    }
+
+    /// Origin をパース
+    pub fn parse(input: &str) -> Result<Self, CorsError> {
+        let input = trim_ows(input);
+        if input == "null" {
+            return Ok(Origin::null());
+        }
+
+        let (scheme, authority) = input.split_once("://").ok_or(CorsError::InvalidOrigin)?;
+        let bytes = scheme.as_bytes();
+        if bytes.is_empty()
+            || !bytes[0].is_ascii_alphabetic()
+            || !bytes
+                .iter()
+                .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+        {
+            return Err(CorsError::InvalidOrigin);
+        }
+        // serialized-origin は path / query / userinfo を含まない
+        if authority.is_empty() || authority.contains(['/', '?', '#', '@']) {
+            return Err(CorsError::InvalidOrigin);
+        }
+
+        let scheme = scheme.to_ascii_lowercase();
+        let host = Host::parse(&authority.to_ascii_lowercase())
+            .map_err(|_| CorsError::InvalidOrigin)?
+            .without_default_port(&scheme);
+        Ok(Origin {
+            tuple: Some((scheme, host)),
+        })
+    }
+
+    /// リクエストの Origin ヘッダーを取得
+    ///
+    /// Origin ヘッダーがない場合は `None` を返す。
+    /// 複数行ある場合や、複数のオリジンを含む場合は `InvalidOrigin` を返す。
+    pub fn from_request<H: HttpHead>(request: &H) -> Result<Option<Self>, CorsError> {
+        let values = request.get_headers("Origin");
+        match values.as_slice() {
+            [] => Ok(None),
+            [value] => Origin::parse(value).map(Some),
+            _ => Err(CorsError::InvalidOrigin),
+        }
+    }
+
+    /// `null` オリジンかどうか
+    pub fn is_null(&self) -> bool {
+        self.tuple.is_none()
+    }
+
+    /// スキーム (小文字)
+    pub fn scheme(&self) -> Option<&str> {
+        self.tuple.as_ref().map(|(scheme, _)| scheme.as_str())
+    }
+
+    /// ホスト (IPv6 は角括弧付き)
+    pub fn host(&self) -> Option<&str> {
+        self.tuple.as_ref().map(|(_, host)| host.host())
+    }
+
+    /// ポート (デフォルトポートの場合は `None`)
+    pub fn port(&self) -> Option<u16> {
+        self.tuple.as_ref().and_then(|(_, host)| host.port())
+    }
+
+    /// ポート (省略時はスキームのデフォルトポート)
+    pub fn port_or_default(&self) -> Option<u16> {
+        self.tuple
+            .as_ref()
+            .and_then(|(scheme, host)| host.port().or_else(|| default_port(scheme)))
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tuple {
+            Some((scheme, host)) => write!(f, "{}://{}", scheme, host),
+            None => write!(f, "null"),
+        }
+    }
+}
+
+impl TypedHeader for Origin {
+    const NAME: &'static str = "Origin";
+    type Error = CorsError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Access-Control-Request-Method を取得
+///
+/// ヘッダーがない場合は `None` を返す。
+pub fn requested_method<H: HttpHead>(request: &H) -> Result<Option<Method>, CorsError> {
+    match request.get_header("Access-Control-Request-Method") {
+        Some(value) => Method::parse(trim_ows(value))
+            .map(Some)
+            .map_err(|_| CorsError::InvalidMethod),
+        None => Ok(None),
+    }
+}
+
+/// Access-Control-Request-Headers を取得
+///
+/// ヘッダー名は小文字にする。複数行ある場合は結合し、空の要素は無視する。
+pub fn requested_headers<H: HttpHead>(request: &H) -> Result<Vec<String>, CorsError> {
+    let mut names = Vec::new();
+    for value in request.get_headers("Access-Control-Request-Headers") {
+        for name in value.split(',') {
+            let name = trim_ows(name);
+            if name.is_empty() {
+                continue;
+            }
+            if !is_valid_token(name) {
+                return Err(CorsError::InvalidHeaderName);
+            }
+            names.push(name.to_ascii_lowercase());
+        }
+    }
+    Ok(names)
+}
+
+/// CORS リクエストを拒否した理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorsRejection {
+    /// Origin / Access-Control-Request-* ヘッダーが不正
+    InvalidRequest(CorsError),
+    /// 許可していないオリジン
+    OriginNotAllowed,
+    /// 許可していないメソッド
+    MethodNotAllowed(String),
+    /// 許可していないヘッダー
+    HeaderNotAllowed(String),
+}
+
+impl fmt::Display for CorsRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorsRejection::InvalidRequest(e) => write!(f, "invalid CORS request: {}", e),
+            CorsRejection::OriginNotAllowed => write!(f, "origin not allowed"),
+            CorsRejection::MethodNotAllowed(method) => {
+                write!(f, "method not allowed: {}", method)
+            }
+            CorsRejection::HeaderNotAllowed(name) => write!(f, "header not allowed: {}", name),
+        }
+    }
+}
+
+/// CORS ポリシーの判定結果
+///
+/// 各バリアントの `headers` はレスポンスに付けるヘッダー (Access-Control-* と Vary)。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsOutcome {
+    /// Origin ヘッダーがない (CORS リクエストではない)
+    NotCors,
+    /// 許可したプリフライトリクエスト
+    Preflight { headers: Vec<(String, String)> },
+    /// 許可した実際のリクエスト
+    Actual { headers: Vec<(String, String)> },
+    /// 拒否したリクエスト
+    ///
+    /// `headers` にはキャッシュのための Vary だけが入る。
+    Rejected {
+        reason: CorsRejection,
+        headers: Vec<(String, String)>,
+    },
+}
+
+impl CorsOutcome {
+    /// レスポンスに付けるヘッダー
+    pub fn headers(&self) -> &[(String, String)] {
+        match self {
+            CorsOutcome::NotCors => &[],
+            CorsOutcome::Preflight { headers }
+            | CorsOutcome::Actual { headers }
+            | CorsOutcome::Rejected { headers, .. } => headers,
+        }
+    }
+
+    /// 許可したかどうか (CORS リクエストでない場合も true)
+    pub fn is_allowed(&self) -> bool {
+        !matches!(self, CorsOutcome::Rejected { .. })
+    }
+}
+
+/// CORS ポリシー
+///
+/// デフォルトではどのオリジンも許可しない。
+/// CORS-safelisted method (GET / HEAD / POST) は常に許可する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorsPolicy {
+    any_origin: bool,
+    origins: Vec<Origin>,
+    any_method: bool,
+    methods: Vec<Method>,
+    any_header: bool,
+    headers: Vec<String>,
+    exposed_headers: Vec<String>,
+    credentials: bool,
+    max_age: Option<u64>,
+}
+
+impl CorsPolicy {
+    /// どのオリジンも許可しないポリシーを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// すべてのオリジンを許可
+    ///
+    /// credentials を許可しない場合は `Access-Control-Allow-Origin: *` を返す。
+    /// credentials を許可する場合は `*` を使えないため、リクエストのオリジンを返す。
+    pub fn with_any_origin(mut self) -> Self {
+        self.any_origin = true;
+        self
+    }
+
+    /// 許可するオリジンを追加 (`null` も指定できる)
+    pub fn with_allowed_origin(mut self, origin: &str) -> Result<Self, CorsError> {
+        self.origins.push(Origin::parse(origin)?);
+        Ok(self)
+    }
+
+    /// すべてのメソッドを許可
+    ///
+    /// プリフライトにはリクエストされたメソッドをそのまま返す。
+    pub fn with_any_method(mut self) -> Self {
+        self.any_method = true;
+        self
+    }
+
+    /// 許可するメソッドを追加 (大文字小文字を区別する)
+    pub fn with_allowed_method(mut self, method: &str) -> Result<Self, CorsError> {
+        self.methods
+            .push(Method::parse(method).map_err(|_| CorsError::InvalidMethod)?);
+        Ok(self)
+    }
+
+    /// すべてのリクエストヘッダーを許可
+    ///
+    /// プリフライトにはリクエストされたヘッダーをそのまま返す
+    /// (`*` は Authorization を含まず、credentials と併用できないため使わない)。
+    pub fn with_any_header(mut self) -> Self {
+        self.any_header = true;
+        self
+    }
+
+    /// 許可するリクエストヘッダーを追加 (大文字小文字を区別しない)
+    pub fn with_allowed_header(mut self, name: &str) -> Result<Self, CorsError> {
+        self.headers.push(parse_header_name(name)?);
+        Ok(self)
+    }
+
+    /// スクリプトに公開するレスポンスヘッダーを追加 (Access-Control-Expose-Headers)
+    pub fn with_exposed_header(mut self, name: &str) -> Result<Self, CorsError> {
+        self.exposed_headers.push(parse_header_name(name)?);
+        Ok(self)
+    }
+
+    /// credentials (Cookie / Authorization 等) を許可するかどうか
+    pub fn with_credentials(mut self, credentials: bool) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// プリフライト結果をキャッシュしてよい秒数 (Access-Control-Max-Age)
+    pub fn with_max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// オリジンを許可しているかどうか
+    pub fn is_origin_allowed(&self, origin: &Origin) -> bool {
+        self.any_origin || self.origins.contains(origin)
+    }
+
+    /// メソッドを許可しているかどうか
+    pub fn is_method_allowed(&self, method: &Method) -> bool {
+        self.any_method
+            || matches!(method.as_str(), "GET" | "HEAD" | "POST")
+            || self.methods.contains(method)
+    }
+
+    /// リクエストヘッダーを許可しているかどうか
+    pub fn is_header_allowed(&self, name: &str) -> bool {
+        self.any_header || self.headers.iter().any(|h| h.eq_ignore_ascii_case(name))
+    }
+
+    /// リクエストをポリシーで判定
+    ///
+    /// `method` はリクエストのメソッド (`Request::method()` / `RequestHead::method()`)。
+    /// OPTIONS で Origin と Access-Control-Request-Method を持つリクエストをプリフライトとして扱う。
+    pub fn evaluate<H: HttpHead>(&self, method: &str, request: &H) -> CorsOutcome {
+        // オリジンを列挙して許可する場合、レスポンスはオリジンによって変わる
+        let mut vary = Vec::new();
+        if !self.any_origin || self.credentials {
+            vary.push("Origin");
+        }
+
+        let origin = match Origin::from_request(request) {
+            Ok(Some(origin)) => origin,
+            Ok(None) => return CorsOutcome::NotCors,
+            Err(e) => return rejected(CorsRejection::InvalidRequest(e), &vary),
+        };
+        if !self.is_origin_allowed(&origin) {
+            return rejected(CorsRejection::OriginNotAllowed, &vary);
+        }
+
+        let is_preflight =
+            method == "OPTIONS" && request.has_header("Access-Control-Request-Method");
+        if !is_preflight {
+            let mut headers = self.origin_headers(&origin);
+            if !self.exposed_headers.is_empty() {
+                headers.push((
+                    "Access-Control-Expose-Headers".to_string(),
+                    self.exposed_headers.join(", "),
+                ));
+            }
+            push_vary(&mut headers, &vary);
+            return CorsOutcome::Actual { headers };
+        }
+
+        if self.any_method {
+            vary.push("Access-Control-Request-Method");
+        }
+        if self.any_header {
+            vary.push("Access-Control-Request-Headers");
+        }
+
+        let requested = match requested_method(request) {
+            Ok(Some(method)) => method,
+            Ok(None) => {
+                return rejected(
+                    CorsRejection::InvalidRequest(CorsError::InvalidMethod),
+                    &vary,
+                );
+            }
+            Err(e) => return rejected(CorsRejection::InvalidRequest(e), &vary),
+        };
+        if !self.is_method_allowed(&requested) {
+            return rejected(
+                CorsRejection::MethodNotAllowed(requested.as_str().to_string()),
+                &vary,
+            );
+        }
+        let requested_headers = match requested_headers(request) {
+            Ok(names) => names,
+            Err(e) => return rejected(CorsRejection::InvalidRequest(e), &vary),
+        };
+        if let Some(name) = requested_headers
+            .iter()
+            .find(|name| !self.is_header_allowed(name))
+        {
+            return rejected(CorsRejection::HeaderNotAllowed(name.clone()), &vary);
+        }
+
+        let mut headers = self.origin_headers(&origin);
+        let methods = if self.any_method {
+            requested.as_str().to_string()
+        } else {
+            let methods: Vec<&str> = self.methods.iter().map(Method::as_str).collect();
+            methods.join(", ")
+        };
+        if !methods.is_empty() {
+            headers.push(("Access-Control-Allow-Methods".to_string(), methods));
+        }
+        let allowed_headers = if self.any_header {
+            requested_headers.join(", ")
+        } else {
+            self.headers.join(", ")
+        };
+        if !allowed_headers.is_empty() {
+            headers.push(("Access-Control-Allow-Headers".to_string(), allowed_headers));
+        }
+        if let Some(max_age) = self.max_age {
+            headers.push(("Access-Control-Max-Age".to_string(), max_age.to_string()));
+        }
+        push_vary(&mut headers, &vary);
+        CorsOutcome::Preflight { headers }
+    }
+
+    fn origin_headers(&self, origin: &Origin) -> Vec<(String, String)> {
+        let allow_origin = if self.any_origin && !self.credentials {
+            "*".to_string()
+        } else {
+            origin.to_string()
+        };
+        let mut headers = alloc::vec![("Access-Control-Allow-Origin".to_string(), allow_origin)];
+        if self.credentials {
+            headers.push((
+                "Access-Control-Allow-Credentials".to_string(),
+                "true".to_string(),
+            ));
+        }
+        headers
+    }
+}
+
+impl Response {
+    /// プリフライトリクエストへのレスポンスを作成
+    ///
+    /// 許可した場合は 204 No Content に Access-Control-* ヘッダーを付ける。
+    /// それ以外の場合は 403 Forbidden を返す (Access-Control-* ヘッダーは付けない)。
+    pub fn cors_preflight(outcome: &CorsOutcome) -> Result<Self, EncodeError> {
+        let status = match outcome {
+            CorsOutcome::Preflight { .. } => StatusCode::NO_CONTENT,
+            _ => StatusCode::FORBIDDEN,
+        };
+        apply_cors(Response::with_status(status), outcome)
+    }
+}
+
+/// レスポンスに CORS の判定結果のヘッダーを付ける
+///
+/// Access-Control-* は既存の値を置き換える。
+/// Vary は既存の値に追加し、すでに含まれるフィールドや `Vary: *` の場合は追加しない。
+pub fn apply_cors(mut response: Response, outcome: &CorsOutcome) -> Result<Response, EncodeError> {
+    for (name, value) in outcome.headers() {
+        if name.eq_ignore_ascii_case("Vary") {
+            merge_vary(&mut response, value)?;
+        } else {
+            response.set_header(name.as_str(), value.as_str())?;
+        }
+    }
+    Ok(response)
+}
+
+fn merge_vary(response: &mut Response, fields: &str) -> Result<(), EncodeError> {
+    let existing = response.get_headers("Vary");
+    let mut values: Vec<&str> = existing
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(trim_ows)
+        .filter(|value| !value.is_empty())
+        .collect();
+    if values.contains(&"*") {
+        return Ok(());
+    }
+    for field in fields.split(',').map(trim_ows) {
+        if !values.iter().any(|value| value.eq_ignore_ascii_case(field)) {
+            values.push(field);
+        }
+    }
+    let value = values.join(", ");
+    response.set_header("Vary", value)?;
+    Ok(())
+}
+
+fn rejected(reason: CorsRejection, vary: &[&str]) -> CorsOutcome {
+    let mut headers = Vec::new();
+    push_vary(&mut headers, vary);
+    CorsOutcome::Rejected { reason, headers }
+}
+
+fn push_vary(headers: &mut Vec<(String, String)>, vary: &[&str]) {
+    if !vary.is_empty() {
+        headers.push(("Vary".to_string(), vary.join(", ")));
+    }
+}
+
+fn parse_header_name(name: &str) -> Result<String, CorsError> {
+    if !is_valid_token(name) {
+        return Err(CorsError::InvalidHeaderName);
+    }
+    Ok(name.to_string())
+}
@@ -4,6 +4,10 @@
 //!
 //! RFC 9110 に基づいた HTTP-date のパースと生成を提供します。
 //!
+//! - IMF-fixdate / rfc850-date / asctime-date の 3 形式のパース
+//! - IMF-fixdate での出力
+//! - UNIX 時間 (秒) との相互変換、`std` feature 有効時は `SystemTime` との相互変換
+//!
 //! ## 使い方
 //!
 //! ```rust
@@ -17,6 +21,16 @@
 //!
 //! // HTTP-date 形式で出力
 //! assert_eq!(date.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+//!
+//! // 3 形式すべてを受理する (rfc850-date の 2 桁年は基準年で解決する)
+//! let rfc850 = HttpDate::parse_any("Sunday, 06-Nov-94 08:49:37 GMT", 2026).unwrap();
+//! let asctime = HttpDate::parse_any("Sun Nov  6 08:49:37 1994", 2026).unwrap();
+//! assert_eq!(rfc850, date);
+//! assert_eq!(asctime, date);
+//!
+//! // UNIX 時間との相互変換
+//! assert_eq!(date.unix_seconds(), 784111777);
+//! assert_eq!(HttpDate::from_unix_seconds(784111777).unwrap(), date);
 //! ```
 
 use alloc::vec::Vec;
@@ -253,6 +267,17 @@ impl HttpDate {
         parse_rfc850_inner(day_name, rest, reference_year)
     }
 
+    /// 3 形式すべての HTTP-date をパース (RFC 9110 §5.6.7)
+    ///
+    /// [`HttpDate::parse`] を試し、rfc850-date の場合は [`HttpDate::parse_rfc850`] に
+    /// フォールバックする。`reference_year` は rfc850-date の 2 桁年解決に使う現在年。
+    pub fn parse_any(input: &str, reference_year: u16) -> Result<Self, DateError> {
+        Self::parse(input).or_else(|e| match e {
+            DateError::Rfc850Date => Self::parse_rfc850(input, reference_year),
+            other => Err(other),
+        })
+    }
+
     /// 新しい HttpDate を作成
     pub fn new(
         day_of_week: DayOfWeek,
@@ -328,6 +353,119 @@ impl HttpDate {
     pub fn second(&self) -> u8 {
         self.second
     }
+
+    /// UNIX 時間 (1970-01-01T00:00:00Z からの秒数) から作成
+    ///
+    /// IMF-fixdate の年は 4 桁のため、9999 年を超える場合は `DateError::InvalidYear` を返す。
+    pub fn from_unix_seconds(seconds: u64) -> Result<Self, DateError> {
+        let days = seconds / 86400;
+        let secs_of_day = seconds % 86400;
+        let (year, month, day) = civil_from_days(days);
+        if year > 9999 {
+            return Err(DateError::InvalidYear);
+        }
+        // 1970-01-01 は木曜日
+        let day_of_week = match (days + 4) % 7 {
+            0 => DayOfWeek::Sunday,
+            1 => DayOfWeek::Monday,
+            2 => DayOfWeek::Tuesday,
+            3 => DayOfWeek::Wednesday,
+            4 => DayOfWeek::Thursday,
+            5 => DayOfWeek::Friday,
+            _ => DayOfWeek::Saturday,
+        };
+        Ok(HttpDate {
+            day_of_week,
+            day,
+            month,
+            year: year as u16,
+            hour: (secs_of_day / 3600) as u8,
+            minute: (secs_of_day % 3600 / 60) as u8,
+            second: (secs_of_day % 60) as u8,
+        })
+    }
+
+    /// `SystemTime` から作成
+    ///
+    /// 秒未満は切り捨てる。1970 年より前の時刻と 9999 年を超える時刻は
+    /// `DateError::InvalidYear` を返す。
+    #[cfg(feature = "std")]
+    pub fn from_system_time(time: std::time::SystemTime) -> Result<Self, DateError> {
+        let seconds = time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|_| DateError::InvalidYear)?
+            .as_secs();
+        Self::from_unix_seconds(seconds)
+    }
+
+    /// `SystemTime` に変換
+    ///
+    /// 1970 年より前の日時は `UNIX_EPOCH` になる。
+    #[cfg(feature = "std")]
+    pub fn to_system_time(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(self.unix_seconds())
+    }
+
+    /// 年月日と時刻から作成し、曜日は日付から計算する
+    pub(crate) fn from_civil(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<Self, DateError> {
+        if !(1..=12).contains(&month) {
+            return Err(DateError::InvalidMonth);
+        }
+        // 1970-01-01 (木曜日) からの日数で曜日を決める
+        let day_of_week = match (days_from_civil(year, month, day) + 4).rem_euclid(7) {
+            0 => DayOfWeek::Sunday,
+            1 => DayOfWeek::Monday,
+            2 => DayOfWeek::Tuesday,
+            3 => DayOfWeek::Wednesday,
+            4 => DayOfWeek::Thursday,
+            5 => DayOfWeek::Friday,
+            _ => DayOfWeek::Saturday,
+        };
+        Self::new(day_of_week, day, month, year, hour, minute, second)
+    }
+
+    /// UNIX 時間 (1970-01-01T00:00:00Z からの秒数) に変換
+    ///
+    /// 1970 年より前の日時は 0 になる。うるう秒 (60 秒) は次の分の 0 秒として扱う。
+    pub fn unix_seconds(&self) -> u64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds =
+            days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        seconds.max(0) as u64
+    }
+}
+
+/// 1970-01-01 からの日数を年月日に変換する (先発グレゴリオ暦)
+fn civil_from_days(days: u64) -> (u64, u8, u8) {
+    // 0000-03-01 起点の日数にして 400 年周期 (146097 日) で計算する
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// 年月日を 1970-01-01 からの日数に変換する (先発グレゴリオ暦)
+fn days_from_civil(year: u16, month: u8, day: u8) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
 }
 
 impl fmt::Display for HttpDate {
@@ -6,18 +6,23 @@
 //!   本実装ではチャンクサイズ行やトレーラーを UTF-8 として強制的に解析している。
 //!   非 UTF-8 バイト列を含む場合はエラーとして拒否される。
 
-use crate::error::Error;
+use crate::error::{Error, ParsePhase};
+use crate::header_name;
 use crate::limits::DecoderLimits;
 use crate::request_target::{RequestTargetForm, detect_scheme};
+use crate::trace::{WireEvent, WireTracer};
 use crate::trailer::is_prohibited_trailer_field;
 use crate::validate::{
     is_pchar_or_slash, is_query_char, is_sub_delim_byte, is_token_char, is_unreserved_byte,
     is_valid_field_value, is_valid_header_name, is_valid_token, trim_ows,
 };
+use alloc::borrow::Cow;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
+use super::options::NonUtf8Policy;
 use super::phase::DecodePhase;
+use super::stats::DecoderStats;
 
 /// ボディの種類
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,7 +65,28 @@ pub enum BodyProgress {
     /// 呼び出し側はループを抜けてネットワーク I/O に戻る。
     NeedData,
     /// 完了（トレーラーがある場合は含む）
-    Complete { trailers: Vec<(String, String)> },
+    Complete {
+        trailers: Vec<(Cow<'static, str>, String)>,
+    },
+}
+
+/// `read_body()` の結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BodyRead {
+    /// `dst` の先頭に指定バイト数のボディデータを書き込んだ (1 以上)。
+    /// ボディは継続中なので、続けて `read_body()` を呼ぶこと。
+    Data(usize),
+    /// 書き込めるボディデータがなく、追加の feed() が必要。
+    /// close-delimited ボディでは `mark_eof()` で完了する。
+    NeedData,
+    /// ボディ完了（トレーラーがある場合は含む）
+    ///
+    /// `len` は完了までに `dst` の先頭に書き込んだバイト数 (0 の場合もある)。
+    Complete {
+        len: usize,
+        trailers: Vec<(Cow<'static, str>, String)>,
+    },
 }
 
 /// ボディデコーダー (内部用)
@@ -69,7 +95,7 @@ pub enum BodyProgress {
 #[derive(Debug)]
 pub(crate) struct BodyDecoder {
     /// トレーラーヘッダー
-    trailers: Vec<(String, String)>,
+    trailers: Vec<(Cow<'static, str>, String)>,
     /// ボディ内での消費済みバイト数
     body_consumed: usize,
     /// トレーラー数
@@ -80,6 +106,18 @@ pub(crate) struct BodyDecoder {
     /// 名前の trailer フィールドは reject される。decode_headers の完了時に
     /// `set_declared_trailers` で設定される (`Trailer:` ヘッダーがない場合は空)。
     declared_trailers: Vec<String>,
+    /// トレーラーの field-value に含まれる非 UTF-8 バイトの扱い
+    ///
+    /// デコーダーの設定であり、`reset()` ではクリアしない。
+    non_utf8_policy: NonUtf8Policy,
+    /// chunked ボディのフレーミングを記録するか
+    ///
+    /// デコーダーの設定であり、`reset()` ではクリアしない。
+    preserve_chunk_framing: bool,
+    /// 記録したフレーミング (チャンクデータ以外の受信バイト列)
+    chunk_framing: Vec<u8>,
+    /// 現在のチャンクの chunk-ext (chunk-size 直後からのバイト列)
+    chunk_ext: Vec<u8>,
 }
 
 impl Default for BodyDecoder {
@@ -96,6 +134,10 @@ impl BodyDecoder {
             body_consumed: 0,
             trailer_count: 0,
             declared_trailers: Vec::new(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            preserve_chunk_framing: false,
+            chunk_framing: Vec::new(),
+            chunk_ext: Vec::new(),
         }
     }
 
@@ -105,6 +147,44 @@ impl BodyDecoder {
         self.body_consumed = 0;
         self.trailer_count = 0;
         self.declared_trailers.clear();
+        self.chunk_framing.clear();
+        self.chunk_ext.clear();
+    }
+
+    /// トレーラーの field-value に含まれる非 UTF-8 バイトの扱いを設定する
+    pub fn set_non_utf8_policy(&mut self, policy: NonUtf8Policy) {
+        self.non_utf8_policy = policy;
+    }
+
+    /// chunked ボディのフレーミングを記録するかを設定する
+    pub fn set_preserve_chunk_framing(&mut self, preserve: bool) {
+        self.preserve_chunk_framing = preserve;
+        if !preserve {
+            self.chunk_framing.clear();
+            self.chunk_ext.clear();
+        }
+    }
+
+    /// chunked ボディのフレーミングを記録するかを取得
+    pub fn preserve_chunk_framing(&self) -> bool {
+        self.preserve_chunk_framing
+    }
+
+    /// 記録したフレーミングを取り出す
+    pub fn take_chunk_framing(&mut self) -> Vec<u8> {
+        core::mem::take(&mut self.chunk_framing)
+    }
+
+    /// 現在のチャンクの chunk-ext を取得
+    pub fn chunk_ext(&self) -> Option<&[u8]> {
+        (!self.chunk_ext.is_empty()).then_some(self.chunk_ext.as_slice())
+    }
+
+    /// フレーミングのバイト列を記録する
+    fn record_framing(&mut self, bytes: &[u8]) {
+        if self.preserve_chunk_framing {
+            self.chunk_framing.extend_from_slice(bytes);
+        }
     }
 
     /// `Trailer:` ヘッダーで申告された trailer フィールド名リストを設定する
@@ -161,13 +241,20 @@ impl BodyDecoder {
     }
 
     /// ボディデータを消費
+    ///
+    /// `stats` にボディバイト数 / チャンク数 / トレーラー数を計上する。
+    /// `stats.bytes_fed - buf.len()` を `buf` 先頭の絶対バイトオフセットとして
+    /// `Error::Syntax` の位置情報に使う。
     pub fn consume_body(
         &mut self,
         buf: &mut Vec<u8>,
         phase: &mut DecodePhase,
         len: usize,
         limits: &DecoderLimits,
+        stats: &mut DecoderStats,
+        tracer: &mut WireTracer,
     ) -> Result<BodyProgress, Error> {
+        let offset = stats.bytes_fed.saturating_sub(buf.len() as u64);
         match phase {
             DecodePhase::BodyContentLength { remaining } => {
                 if (len as u64) > *remaining {
@@ -183,6 +270,7 @@ impl BodyDecoder {
 
                 buf.drain(..len);
                 *remaining -= len as u64;
+                stats.body_bytes += len as u64;
                 self.body_consumed =
                     self.body_consumed
                         .checked_add(len)
@@ -212,7 +300,7 @@ impl BodyDecoder {
                     "BodyChunkedSize では consume_body ではなく progress を使うこと"
                 );
                 let initial_phase = phase.clone();
-                self.process_chunked_size(buf, phase, limits)?;
+                self.process_chunked_size(buf, phase, limits, stats, tracer, offset)?;
 
                 match phase {
                     DecodePhase::Complete => Ok(BodyProgress::Complete {
@@ -242,6 +330,7 @@ impl BodyDecoder {
 
                 buf.drain(..len);
                 *remaining -= len;
+                stats.body_bytes += len as u64;
                 self.body_consumed =
                     self.body_consumed
                         .checked_add(len)
@@ -256,12 +345,15 @@ impl BodyDecoder {
                     // CRLF が既にバッファにあれば即座に処理
                     if buf.len() >= 2 {
                         if buf[..2] != *b"\r\n" {
-                            return Err(Error::InvalidData(
-                                "invalid chunked encoding: expected CRLF after chunk data"
-                                    .to_string(),
+                            return Err(Error::syntax(
+                                ParsePhase::ChunkData,
+                                offset + len as u64,
+                                &buf[..2],
+                                "invalid chunked encoding: expected CRLF after chunk data",
                             ));
                         }
                         buf.drain(..2);
+                        self.record_framing(b"\r\n");
                         *phase = DecodePhase::BodyChunkedSize;
                     }
                 }
@@ -283,11 +375,15 @@ impl BodyDecoder {
                 // CRLF 待ち状態: バッファに CRLF があれば処理
                 if buf.len() >= 2 {
                     if buf[..2] != *b"\r\n" {
-                        return Err(Error::InvalidData(
-                            "invalid chunked encoding: expected CRLF after chunk data".to_string(),
+                        return Err(Error::syntax(
+                            ParsePhase::ChunkData,
+                            offset,
+                            &buf[..2],
+                            "invalid chunked encoding: expected CRLF after chunk data",
                         ));
                     }
                     buf.drain(..2);
+                    self.record_framing(b"\r\n");
                     *phase = DecodePhase::BodyChunkedSize;
                     Ok(BodyProgress::Advanced)
                 } else {
@@ -299,7 +395,7 @@ impl BodyDecoder {
                     len, 0,
                     "ChunkedTrailer では consume_body ではなく progress を使うこと"
                 );
-                let advanced = self.process_trailers(buf, phase, limits)?;
+                let advanced = self.process_trailers(buf, phase, limits, stats, tracer, offset)?;
 
                 match phase {
                     DecodePhase::Complete => Ok(BodyProgress::Complete {
@@ -341,6 +437,7 @@ impl BodyDecoder {
 
                 buf.drain(..len);
                 self.body_consumed = new_size;
+                stats.body_bytes += len as u64;
 
                 // close-delimited は mark_eof() が呼ばれるまで Advanced/NeedData を返す
                 Ok(BodyProgress::Advanced)
@@ -358,12 +455,74 @@ impl BodyDecoder {
         }
     }
 
+    /// ボディデータを `dst` に直接コピーして消費する
+    ///
+    /// `peek_body` / `consume_body` / `progress` 相当の処理を、`dst` が埋まるか
+    /// 追加データが必要になるか完了するまで繰り返す。
+    pub fn read_body(
+        &mut self,
+        buf: &mut Vec<u8>,
+        phase: &mut DecodePhase,
+        dst: &mut [u8],
+        limits: &DecoderLimits,
+        stats: &mut DecoderStats,
+        tracer: &mut WireTracer,
+    ) -> Result<BodyRead, Error> {
+        if dst.is_empty() {
+            return Err(Error::InvalidData(
+                "read_body: dst must not be empty".to_string(),
+            ));
+        }
+        let mut written = 0;
+        loop {
+            if let Some(data) = self.peek_body(buf, phase) {
+                if written == dst.len() {
+                    return Ok(BodyRead::Data(written));
+                }
+                let len = data.len().min(dst.len() - written);
+                dst[written..written + len].copy_from_slice(&data[..len]);
+                written += len;
+                if let BodyProgress::Complete { trailers } =
+                    self.consume_body(buf, phase, len, limits, stats, tracer)?
+                {
+                    return Ok(BodyRead::Complete {
+                        len: written,
+                        trailers,
+                    });
+                }
+                // NeedData (chunked CRLF 不足) でも peek_body から再試行する。
+                // peek_body が None なら progress 相当の処理に fall through する。
+                continue;
+            }
+            match self.consume_body(buf, phase, 0, limits, stats, tracer)? {
+                BodyProgress::Complete { trailers } => {
+                    return Ok(BodyRead::Complete {
+                        len: written,
+                        trailers,
+                    });
+                }
+                BodyProgress::Advanced => continue,
+                BodyProgress::NeedData => break,
+            }
+        }
+        if written == 0 {
+            Ok(BodyRead::NeedData)
+        } else {
+            Ok(BodyRead::Data(written))
+        }
+    }
+
     /// chunked のチャンクサイズ行を処理
+    ///
+    /// `offset` は `buf` 先頭の絶対バイトオフセット。
     fn process_chunked_size(
         &mut self,
         buf: &mut Vec<u8>,
         phase: &mut DecodePhase,
         limits: &DecoderLimits,
+        stats: &mut DecoderStats,
+        tracer: &mut WireTracer,
+        offset: u64,
     ) -> Result<(), Error> {
         if !matches!(phase, DecodePhase::BodyChunkedSize) {
             return Ok(());
@@ -378,75 +537,38 @@ impl BodyDecoder {
                 });
             }
 
-            // RFC 9112 Section 7.1.1: chunk extension の処理
-            // 注: chunk extension は一般的に使われていない (RFC 9112 が "specialized service" 向けと明記)。
-            // RFC 準拠のために処理しているが、内容は破棄する。
-            // chunk-ext の quoted-string は obs-text を含む可能性があるため
-            // UTF-8 変換せずバイト列として処理する。セミコロンまでを chunk-size として解釈。
-            let line_bytes = &buf[..pos];
-
-            // セミコロンの位置を探す (chunk-ext の開始)
-            let semi_pos = line_bytes.iter().position(|&b| b == b';');
-            let size_end = semi_pos.unwrap_or(pos);
-            let size_bytes = &line_bytes[..size_end];
-
-            // chunk-size = 1*HEXDIG (RFC 9112 Section 7.1)
-            // HEXDIG の末尾位置を探す
-            let hex_end = size_bytes
-                .iter()
-                .position(|b| !b.is_ascii_hexdigit())
-                .unwrap_or(size_bytes.len());
-
-            // chunk-size は 1 文字以上の HEXDIG で始まらなければならない
-            if hex_end == 0 {
-                let display = String::from_utf8_lossy(size_bytes);
-                return Err(Error::InvalidData(alloc::format!(
-                    "invalid chunk size: {}",
-                    display
-                )));
-            }
-
-            // HEXDIG の後にバイトがある場合の検証
-            let trailing = &size_bytes[hex_end..];
-            if !trailing.is_empty() {
-                if semi_pos.is_some() {
-                    // chunk-ext がある場合: HEXDIG と ";" の間は BWS (SP / HTAB) のみ許容
-                    // RFC 9112 Section 7.1.1: chunk-ext = *( BWS ";" ... )
-                    if !trailing.iter().all(|&b| b == b' ' || b == b'\t') {
-                        let display = String::from_utf8_lossy(size_bytes);
-                        return Err(Error::InvalidData(alloc::format!(
-                            "invalid chunk size: {}",
-                            display
-                        )));
-                    }
-                } else {
-                    // chunk-ext がない場合: chunk-size の後は CRLF のみ (BWS は不可)
-                    let display = String::from_utf8_lossy(size_bytes);
-                    return Err(Error::InvalidData(alloc::format!(
-                        "invalid chunk size: {}",
-                        display
-                    )));
+            let chunk_size = parse_chunk_size_line(&buf[..pos])
+                .map_err(|e| e.with_context(ParsePhase::ChunkSize, offset, &buf[..pos]))?;
+
+            if self.preserve_chunk_framing || tracer.is_enabled() {
+                let hex_end = buf[..pos]
+                    .iter()
+                    .position(|b| !b.is_ascii_hexdigit())
+                    .unwrap_or(pos);
+                tracer.emit(WireEvent::Chunk {
+                    offset,
+                    size: chunk_size,
+                    ext: &buf[hex_end..pos],
+                    raw: &buf[..pos],
+                });
+                if self.preserve_chunk_framing {
+                    self.chunk_ext.clear();
+                    self.chunk_ext.extend_from_slice(&buf[hex_end..pos]);
+                    self.chunk_framing.extend_from_slice(&buf[..pos + 2]);
                 }
             }
-
-            // HEXDIG 部分のみを chunk-size として解釈
-            let hex_bytes = &size_bytes[..hex_end];
-            let size_str = core::str::from_utf8(hex_bytes)
-                .map_err(|_| Error::InvalidData("invalid chunk size: not ASCII".to_string()))?;
-            let chunk_size = usize::from_str_radix(size_str, 16).map_err(|_| {
-                Error::InvalidData(alloc::format!("invalid chunk size: {}", size_str))
-            })?;
-
-            // chunk-ext の ABNF 検証 (RFC 9112 Section 7.1.1)
-            if let Some(sp) = semi_pos {
-                validate_chunk_ext(&line_bytes[sp..])?;
-            }
-
             buf.drain(..pos + 2);
 
             if chunk_size == 0 {
                 *phase = DecodePhase::ChunkedTrailer;
-                let _ = self.process_trailers(buf, phase, limits)?;
+                let _ = self.process_trailers(
+                    buf,
+                    phase,
+                    limits,
+                    stats,
+                    tracer,
+                    offset + pos as u64 + 2,
+                )?;
                 return Ok(());
             } else {
                 let new_size =
@@ -465,6 +587,7 @@ impl BodyDecoder {
                 *phase = DecodePhase::BodyChunkedData {
                     remaining: chunk_size,
                 };
+                stats.chunks += 1;
             }
         }
         Ok(())
@@ -474,17 +597,23 @@ impl BodyDecoder {
     ///
     /// 戻り値の `bool` は「1 行以上のトレーラ行を処理したか (終端の空行を含む)」。
     /// `consume_body()` の `ChunkedTrailer` 分岐で `Advanced` / `NeedData` を判定するために使う。
+    ///
+    /// `offset` は `buf` 先頭の絶対バイトオフセット。
     fn process_trailers(
         &mut self,
         buf: &mut Vec<u8>,
         phase: &mut DecodePhase,
         limits: &DecoderLimits,
+        stats: &mut DecoderStats,
+        tracer: &mut WireTracer,
+        mut offset: u64,
     ) -> Result<bool, Error> {
         let mut advanced = false;
         while matches!(phase, DecodePhase::ChunkedTrailer) {
             if let Some(pos) = find_line(buf) {
                 if pos == 0 {
                     buf.drain(..2);
+                    self.record_framing(b"\r\n");
                     *phase = DecodePhase::Complete;
                     return Ok(true);
                 } else {
@@ -504,40 +633,35 @@ impl BodyDecoder {
                         });
                     }
 
-                    let line = String::from_utf8(buf[..pos].to_vec())
-                        .map_err(|e| Error::InvalidData(alloc::format!("invalid UTF-8: {e}")))?;
-                    buf.drain(..pos + 2);
-
-                    // 不正なトレーラー行はエラーにする
-                    let (name, value) = parse_header_line(&line)?;
-
-                    // RFC 9110 Section 6.5.1: framing / routing / authentication /
-                    // request modifiers / response controls / content format /
-                    // connection management のカテゴリに該当するフィールドは
-                    // trailer に置けない (`Trailer:` ヘッダーで申告されていても拒否)。
-                    if is_prohibited_trailer_field(&name) {
-                        return Err(Error::InvalidData(alloc::format!(
-                            "prohibited trailer field: {}",
-                            name
-                        )));
-                    }
-
-                    // RFC 9110 Section 6.5.1 ホワイトリスト方式:
-                    // sender は `Trailer:` ヘッダーで事前申告したフィールド名のみ
-                    // trailer-section に置ける (MUST NOT generate a trailer field
-                    // unless ... the sender knows the recipient will accept it)。
-                    // 受信側は申告外のフィールドを reject し、認証ヘッダー等の
-                    // 後付け注入による smuggling 経路を遮断する。
-                    let name_lower = name.to_ascii_lowercase();
-                    if !self.declared_trailers.iter().any(|d| d == &name_lower) {
-                        return Err(Error::InvalidData(alloc::format!(
-                            "undeclared trailer field: {}",
-                            name
-                        )));
+                    let line_bytes = &buf[..pos];
+                    let (line, _) =
+                        decode_line(line_bytes, field_name_len(line_bytes), self.non_utf8_policy)
+                            .map_err(|e| {
+                            Error::syntax(
+                                ParsePhase::Trailer,
+                                offset,
+                                &buf[..pos],
+                                alloc::format!("invalid UTF-8: {e}"),
+                            )
+                        })?;
+                    let (name, value) = self.validate_trailer_line(&line).map_err(|e| {
+                        e.with_context(ParsePhase::Trailer, offset, line.as_bytes())
+                    })?;
+                    tracer.emit(WireEvent::Trailer {
+                        offset,
+                        name: &name,
+                        value: &value,
+                        raw: &buf[..pos],
+                    });
+                    if self.preserve_chunk_framing {
+                        self.chunk_framing.extend_from_slice(&buf[..pos + 2]);
                     }
+                    buf.drain(..pos + 2);
+                    offset += pos as u64 + 2;
 
                     self.trailers.push((name, value));
                     self.trailer_count += 1;
+                    stats.trailers += 1;
                     advanced = true;
                 }
             } else {
@@ -546,6 +670,108 @@ impl BodyDecoder {
         }
         Ok(advanced)
     }
+
+    /// トレーラー行をパースし、trailer に置けるフィールドか検証する
+    fn validate_trailer_line(&self, line: &str) -> Result<(Cow<'static, str>, String), Error> {
+        // 不正なトレーラー行はエラーにする
+        let (name, value) = parse_header_line(line)?;
+
+        // RFC 9110 Section 6.5.1: framing / routing / authentication /
+        // request modifiers / response controls / content format /
+        // connection management のカテゴリに該当するフィールドは
+        // trailer に置けない (`Trailer:` ヘッダーで申告されていても拒否)。
+        if is_prohibited_trailer_field(name) {
+            return Err(Error::InvalidData(alloc::format!(
+                "prohibited trailer field: {}",
+                name
+            )));
+        }
+
+        // RFC 9110 Section 6.5.1 ホワイトリスト方式:
+        // sender は `Trailer:` ヘッダーで事前申告したフィールド名のみ
+        // trailer-section に置ける (MUST NOT generate a trailer field
+        // unless ... the sender knows the recipient will accept it)。
+        // 受信側は申告外のフィールドを reject し、認証ヘッダー等の
+        // 後付け注入による smuggling 経路を遮断する。
+        let name_lower = name.to_ascii_lowercase();
+        if !self.declared_trailers.iter().any(|d| d == &name_lower) {
+            return Err(Error::InvalidData(alloc::format!(
+                "undeclared trailer field: {}",
+                name
+            )));
+        }
+
+        Ok((header_name::intern(name), value.to_string()))
+    }
+}
+
+/// チャンクサイズ行 (CRLF を除く) をパースしてチャンクサイズを返す
+///
+/// chunk = chunk-size [ chunk-ext ] CRLF (RFC 9112 Section 7.1)
+fn parse_chunk_size_line(line_bytes: &[u8]) -> Result<usize, Error> {
+    // RFC 9112 Section 7.1.1: chunk extension の処理
+    // 注: chunk extension は一般的に使われていない (RFC 9112 が "specialized service" 向けと明記)。
+    // RFC 準拠のために処理しているが、内容は破棄する。
+    // chunk-ext の quoted-string は obs-text を含む可能性があるため
+    // UTF-8 変換せずバイト列として処理する。セミコロンまでを chunk-size として解釈。
+
+    // セミコロンの位置を探す (chunk-ext の開始)
+    let semi_pos = line_bytes.iter().position(|&b| b == b';');
+    let size_end = semi_pos.unwrap_or(line_bytes.len());
+    let size_bytes = &line_bytes[..size_end];
+
+    // chunk-size = 1*HEXDIG (RFC 9112 Section 7.1)
+    // HEXDIG の末尾位置を探す
+    let hex_end = size_bytes
+        .iter()
+        .position(|b| !b.is_ascii_hexdigit())
+        .unwrap_or(size_bytes.len());
+
+    // chunk-size は 1 文字以上の HEXDIG で始まらなければならない
+    if hex_end == 0 {
+        let display = String::from_utf8_lossy(size_bytes);
+        return Err(Error::InvalidData(alloc::format!(
+            "invalid chunk size: {}",
+            display
+        )));
+    }
+
+    // HEXDIG の後にバイトがある場合の検証
+    let trailing = &size_bytes[hex_end..];
+    if !trailing.is_empty() {
+        if semi_pos.is_some() {
+            // chunk-ext がある場合: HEXDIG と ";" の間は BWS (SP / HTAB) のみ許容
+            // RFC 9112 Section 7.1.1: chunk-ext = *( BWS ";" ... )
+            if !trailing.iter().all(|&b| b == b' ' || b == b'\t') {
+                let display = String::from_utf8_lossy(size_bytes);
+                return Err(Error::InvalidData(alloc::format!(
+                    "invalid chunk size: {}",
+                    display
+                )));
+            }
+        } else {
+            // chunk-ext がない場合: chunk-size の後は CRLF のみ (BWS は不可)
+            let display = String::from_utf8_lossy(size_bytes);
+            return Err(Error::InvalidData(alloc::format!(
+                "invalid chunk size: {}",
+                display
+            )));
+        }
+    }
+
+    // HEXDIG 部分のみを chunk-size として解釈
+    let hex_bytes = &size_bytes[..hex_end];
+    let size_str = core::str::from_utf8(hex_bytes)
+        .map_err(|_| Error::InvalidData("invalid chunk size: not ASCII".to_string()))?;
+    let chunk_size = usize::from_str_radix(size_str, 16)
+        .map_err(|_| Error::InvalidData(alloc::format!("invalid chunk size: {}", size_str)))?;
+
+    // chunk-ext の ABNF 検証 (RFC 9112 Section 7.1.1)
+    if let Some(sp) = semi_pos {
+        validate_chunk_ext(&line_bytes[sp..])?;
+    }
+
+    Ok(chunk_size)
 }
 
 /// ヘッダーから `Trailer:` フィールドで申告された名前リストを抽出する
@@ -556,7 +782,7 @@ impl BodyDecoder {
 ///
 /// `Trailer:` ヘッダーは複数行あり得る。各行はカンマ区切りトークンリスト。
 /// 空要素は RFC 9110 Section 5.6.1.2 に従い無視する。
-pub(crate) fn collect_declared_trailers(headers: &[(String, String)]) -> Vec<String> {
+pub(crate) fn collect_declared_trailers(headers: &[(Cow<'static, str>, String)]) -> Vec<String> {
     let mut declared = Vec::new();
     for (name, value) in headers {
         if !name.eq_ignore_ascii_case("Trailer") {
@@ -587,7 +813,7 @@ pub(crate) fn collect_declared_trailers(headers: &[(String, String)]) -> Vec<Str
 /// obs-text       = %x80-FF
 ///
 /// 入力は ";" で始まるバイト列 (セミコロン以降のチャンク行)
-fn validate_chunk_ext(ext: &[u8]) -> Result<(), Error> {
+pub(crate) fn validate_chunk_ext(ext: &[u8]) -> Result<(), Error> {
     let mut i = 0;
 
     while i < ext.len() {
@@ -732,6 +958,40 @@ pub(crate) fn find_line(buf: &[u8]) -> Option<usize> {
     buf.windows(2).position(|w| w == b"\r\n")
 }
 
+/// 行のバイト列を `NonUtf8Policy` に従って文字列に変換する
+///
+/// `strict_len` までの部分 (field-name や status-code までの start-line) は常に
+/// UTF-8 として検証する。それ以降の部分は UTF-8 として不正な場合のみ `policy` に
+/// 従って変換する。変換した場合は戻り値の `bool` が `true` になる。
+///
+/// UTF-8 として正しい行は受信バッファを借用し、メッセージごとに確保しない。
+pub(crate) fn decode_line(
+    line: &[u8],
+    strict_len: usize,
+    policy: NonUtf8Policy,
+) -> Result<(Cow<'_, str>, bool), core::str::Utf8Error> {
+    let err = match core::str::from_utf8(line) {
+        Ok(s) => return Ok((Cow::Borrowed(s), false)),
+        Err(e) => e,
+    };
+    let strict_len = strict_len.min(line.len());
+    let prefix = core::str::from_utf8(&line[..strict_len])?;
+    let rest = &line[strict_len..];
+    let mut out = String::with_capacity(line.len() + rest.len());
+    out.push_str(prefix);
+    match policy {
+        NonUtf8Policy::Reject => return Err(err),
+        NonUtf8Policy::Latin1 => out.extend(rest.iter().map(|&b| char::from(b))),
+        NonUtf8Policy::Replace => out.push_str(&String::from_utf8_lossy(rest)),
+    }
+    Ok((Cow::Owned(out), true))
+}
+
+/// フィールド行のうち field-name 部分 (最初の ":" まで) の長さ
+pub(crate) fn field_name_len(line: &[u8]) -> usize {
+    line.iter().position(|&b| b == b':').unwrap_or(line.len())
+}
+
 /// ヘッダー行をパース
 ///
 /// # RFC 非準拠
@@ -739,8 +999,12 @@ pub(crate) fn find_line(buf: &[u8]) -> Option<usize> {
 /// 現在の実装ではヘッダー行を UTF-8 として解釈しており、obs-text (0x80-0xFF) を
 /// バイト列として扱っていない。RFC 9110 Section 5.5 では obs-text は任意のバイト列
 /// として定義されているが、本実装では UTF-8 として解釈するため、不正な UTF-8
-/// シーケンスを含むヘッダー行は拒否される。現時点ではこの制限を維持する。
-pub(crate) fn parse_header_line(line: &str) -> Result<(String, String), Error> {
+/// シーケンスを含むヘッダー行は拒否される。
+/// デコーダーでは `NonUtf8Policy` を設定すると、UTF-8 として不正なバイト列を
+/// Latin-1 として解釈するか U+FFFD に置換した文字列が渡される。
+///
+/// 戻り値は `line` を借用する。`String` への変換は呼び出し側で行う。
+pub(crate) fn parse_header_line(line: &str) -> Result<(&str, &str), Error> {
     if line.starts_with(' ') || line.starts_with('\t') {
         return Err(Error::InvalidData(
             "invalid header line: obs-fold".to_string(),
@@ -779,7 +1043,7 @@ pub(crate) fn parse_header_line(line: &str) -> Result<(String, String), Error> {
         ));
     }
 
-    Ok((name.to_string(), trimmed_value.to_string()))
+    Ok((name, trimmed_value))
 }
 
 /// request-target の形式を判定
@@ -1142,7 +1406,7 @@ fn validate_authority_form(target: &str) -> Result<(), Error> {
         .map_err(|_| Error::InvalidData("invalid authority-form: port out of range".to_string()))?;
 
     // Host パーサで host を検証
-    crate::host::Host::parse(host)
+    crate::host::validate(host)
         .map_err(|_| Error::InvalidData("invalid authority-form: invalid host".to_string()))?;
 
     Ok(())
@@ -1216,7 +1480,7 @@ pub(crate) enum TransferEncodingResult {
 /// - chunked 以外がある → Err (RFC: 400 Bad Request)
 /// - Transfer-Encoding なし → Ok(false)
 pub(crate) fn parse_transfer_encoding_for_request(
-    headers: &[(String, String)],
+    headers: &[(Cow<'static, str>, String)],
 ) -> Result<bool, Error> {
     let mut chunked_count = 0;
 
@@ -1272,7 +1536,7 @@ pub(crate) fn parse_transfer_encoding_for_request(
 /// - chunked がないか最後でない → Other (close-delimited)
 /// - Transfer-Encoding なし → None
 pub(crate) fn parse_transfer_encoding_for_response(
-    headers: &[(String, String)],
+    headers: &[(Cow<'static, str>, String)],
 ) -> Result<TransferEncodingResult, Error> {
     // すべての Transfer-Encoding ヘッダーを連結してトークンリストを作成
     let mut all_tokens: Vec<String> = Vec::new();
@@ -1330,7 +1594,9 @@ pub(crate) fn parse_transfer_encoding_for_response(
 }
 
 /// Content-Length ヘッダーを解析
-pub(crate) fn parse_content_length(headers: &[(String, String)]) -> Result<Option<u64>, Error> {
+pub(crate) fn parse_content_length(
+    headers: &[(Cow<'static, str>, String)],
+) -> Result<Option<u64>, Error> {
     let mut value: Option<u64> = None;
     for (name, raw_value) in headers {
         if name.eq_ignore_ascii_case("Content-Length") {
@@ -1391,7 +1657,7 @@ fn parse_content_length_value(input: &str) -> Result<u64, Error> {
 /// - Transfer-Encoding と Content-Length の両方がある場合はエラー
 /// - リクエストでは chunked 以外の Transfer-Encoding は拒否
 pub(crate) fn resolve_body_headers_for_request(
-    headers: &[(String, String)],
+    headers: &[(Cow<'static, str>, String)],
 ) -> Result<(bool, Option<u64>), Error> {
     let transfer_encoding_chunked = parse_transfer_encoding_for_request(headers)?;
     let content_length = parse_content_length(headers)?;
@@ -1425,7 +1691,7 @@ pub(crate) fn resolve_body_headers_for_request(
 ///
 /// chunked が最後でない場合は close-delimited として扱う (TE のみ存在時)。
 pub(crate) fn resolve_body_headers_for_response(
-    headers: &[(String, String)],
+    headers: &[(Cow<'static, str>, String)],
 ) -> Result<(TransferEncodingResult, Option<u64>), Error> {
     let te_result = parse_transfer_encoding_for_response(headers)?;
     let content_length = parse_content_length(headers)?;
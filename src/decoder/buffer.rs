@@ -4,8 +4,13 @@
 //! (`feed` / `feed_unchecked` / `mut_buf` / `advance_buf` / `available_buf`
 //! / `remaining`) の実装を集約し、将来のバグ修正時に片側だけ修正される
 //! リスクを排除する。
+//!
+//! Keep-Alive 接続でメッセージ間に記憶領域を再利用する `StringPool` もここに置く。
 
 use crate::error::Error;
+use crate::header_name;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
 /// `&[u8]` 由来のバイト列を内部バッファ末尾に追加する
@@ -150,3 +155,85 @@ pub(super) fn remaining(buf: &[u8], pending: usize) -> &[u8] {
     debug_assert!(pending == 0, "remaining called with pending mut_buf");
     buf
 }
+
+/// メッセージ間で再利用する `String` の記憶領域
+///
+/// 返却されたメッセージの start-line の要素とヘッダーの `String` を保持し、
+/// 次のメッセージのデコードで消費する順に取り出す。同じ構成のメッセージが続く
+/// 場合は、取り出した `String` の容量が足りるため新たに確保しない。
+#[derive(Debug, Default)]
+pub(super) struct StringPool {
+    strings: Vec<String>,
+}
+
+impl StringPool {
+    /// `value` を設定した `String` を取り出す
+    ///
+    /// 保持している `String` がない場合は新たに確保する。
+    pub(super) fn take(&mut self, value: &str) -> String {
+        match self.strings.pop() {
+            Some(mut s) => {
+                s.clear();
+                s.push_str(value);
+                s
+            }
+            None => value.to_string(),
+        }
+    }
+
+    /// ヘッダー名を取り出す
+    ///
+    /// よく使われるヘッダー名は `'static` な文字列を借用し、それ以外は `take` で取り出す。
+    pub(super) fn take_name(&mut self, name: &str) -> Cow<'static, str> {
+        match header_name::lookup(name) {
+            Some(name) => Cow::Borrowed(name),
+            None => Cow::Owned(self.take(name)),
+        }
+    }
+
+    /// 返却されたメッセージの記憶領域に入れ替える
+    ///
+    /// 保持できるのは 1 メッセージ分までで、以前の内容は破棄する。
+    /// ヘッダーを受信順に取り出した後、`start_line` の要素を並び順に取り出せるよう積む。
+    /// `headers` は空にして容量だけを残す。
+    pub(super) fn refill<const N: usize>(
+        &mut self,
+        start_line: [String; N],
+        headers: &mut Vec<(Cow<'static, str>, String)>,
+    ) {
+        self.strings.clear();
+        self.strings.extend(start_line.into_iter().rev());
+        for (name, value) in headers.drain(..).rev() {
+            self.strings.push(value);
+            // インターンしたヘッダー名は記憶領域を持たない
+            if let Cow::Owned(name) = name {
+                self.strings.push(name);
+            }
+        }
+    }
+}
+
+/// 返却されたヘッダーの `Vec` の容量をデコーダーに戻す
+///
+/// デコーダー側のヘッダーが使用中の場合や、すでに十分な容量がある場合は何もしない。
+pub(super) fn recycle_headers(
+    current: &mut Vec<(Cow<'static, str>, String)>,
+    returned: Vec<(Cow<'static, str>, String)>,
+) {
+    if current.is_empty() && returned.capacity() > current.capacity() {
+        *current = returned;
+    }
+}
+
+/// 返却されたボディの `Vec` の容量をデコーダーに戻す
+///
+/// デコーダー側のボディが使用中の場合や、すでに十分な容量がある場合は何もしない。
+pub(super) fn recycle_body(current: &mut Vec<u8>, returned: Option<Vec<u8>>) {
+    if let Some(mut returned) = returned
+        && current.is_empty()
+        && returned.capacity() > current.capacity()
+    {
+        returned.clear();
+        *current = returned;
+    }
+}
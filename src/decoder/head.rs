@@ -1,11 +1,20 @@
 //! HTTP ヘッダー型の定義
 
 use crate::error::{EncodeError, Error};
+use crate::header_name;
+use crate::request_target::{
+    RequestTarget, RequestTargetError, RequestTargetForm, classify, split_absolute_form,
+    target_path, target_query,
+};
 use crate::status_code::StatusClass;
+use crate::typed_header::{TypedHeader, decode_typed};
+use crate::uri::{UriError, parse_query, percent_decode};
 use crate::validate::{
     is_valid_field_value, is_valid_header_name, is_valid_method, is_valid_protocol_version,
     is_valid_reason_phrase, is_valid_request_target, is_valid_status_code, trim_ows,
 };
+use crate::version::HttpVersion;
+use alloc::borrow::Cow;
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -14,8 +23,15 @@ pub trait HttpHead {
     /// HTTP バージョンを取得
     fn version(&self) -> &str;
 
+    /// HTTP バージョンを `HttpVersion` として取得
+    ///
+    /// `HTTP/1.0` / `HTTP/1.1` 以外は `HttpVersion::Other` になる。
+    fn http_version(&self) -> HttpVersion {
+        HttpVersion::from_valid(self.version())
+    }
+
     /// ヘッダーリストを取得
-    fn headers(&self) -> &[(String, String)];
+    fn headers(&self) -> &[(Cow<'static, str>, String)];
 
     /// ヘッダーを取得 (大文字小文字を区別しない)
     fn get_header(&self, name: &str) -> Option<&str> {
@@ -41,6 +57,14 @@ pub trait HttpHead {
             .any(|(n, _)| n.eq_ignore_ascii_case(name))
     }
 
+    /// 型付きヘッダーを取得
+    ///
+    /// ヘッダーが存在しない場合は `None`。同名のヘッダーが複数行ある場合は
+    /// RFC 9110 Section 5.3 に従い `", "` で結合した値をパースする。
+    fn get_typed<T: TypedHeader>(&self) -> Option<Result<T, T::Error>> {
+        decode_typed(self.headers())
+    }
+
     /// Connection ヘッダーの値を取得 (RFC 9110 Section 7.6.1)
     ///
     /// 最初の `Connection` ヘッダー値をそのままの `&str` で返す。
@@ -108,7 +132,7 @@ pub trait HttpHead {
         // HTTP/1.1 完全一致のみ persistent をデフォルトとする。
         // `ends_with("/1.1")` だと `RTSP/1.1` / `FOO/1.1` のような他プロトコルで
         // 誤って persistent 判定する経路が生じるため厳格化する。
-        self.version() == "HTTP/1.1"
+        self.version() == HttpVersion::Http11.as_str()
     }
 
     /// Content-Length ヘッダーの値を取得
@@ -175,7 +199,9 @@ pub struct RequestHead {
     /// HTTP バージョン (HTTP/1.1 等)
     pub(crate) version: String,
     /// ヘッダー
-    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) headers: Vec<(Cow<'static, str>, String)>,
+    /// `NonUtf8Policy` により変換したヘッダー値の (インデックス, 受信バイト列)
+    pub(crate) raw_values: Vec<(usize, Vec<u8>)>,
 }
 
 impl RequestHead {
@@ -208,6 +234,7 @@ impl RequestHead {
             uri: uri.into(),
             version: version.into(),
             headers: Vec::new(),
+            raw_values: Vec::new(),
         })
     }
 
@@ -219,16 +246,43 @@ impl RequestHead {
 
     /// ヘッダーを追加する (バリデート付き、可変借用)
     pub fn add_header(&mut self, name: &str, value: &str) -> Result<&mut Self, EncodeError> {
-        if !is_valid_header_name(name) {
-            return Err(EncodeError::InvalidHeaderName { name: name.into() });
-        }
-        if !is_valid_field_value(value) {
-            return Err(EncodeError::InvalidHeaderValue {
-                name: name.into(),
-                value: value.into(),
-            });
+        validate_header(name, value)?;
+        self.headers.push((header_name::intern(name), value.into()));
+        Ok(self)
+    }
+
+    /// 指定した名前の既存ヘッダーを全削除し、新規に追加する (バリデート付き、可変借用)
+    ///
+    /// 追加したヘッダーは末尾に位置する。バリデーションが失敗した場合は
+    /// 既存ヘッダーは変更されない。
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<&mut Self, EncodeError> {
+        validate_header(name, value)?;
+        remove_headers(&mut self.headers, &mut self.raw_values, name);
+        self.headers.push((header_name::intern(name), value.into()));
+        Ok(self)
+    }
+
+    /// 指定した名前のヘッダーをすべて削除する (可変借用)
+    ///
+    /// 大文字小文字を区別せずに一致するヘッダーを削除する。存在しない場合は何もしない。
+    pub fn remove_header(&mut self, name: &str) -> &mut Self {
+        remove_headers(&mut self.headers, &mut self.raw_values, name);
+        self
+    }
+
+    /// 指定した名前のヘッダーが存在しない場合のみ追加する (バリデート付き、可変借用)
+    ///
+    /// 既存のヘッダーがある場合は変更しない。既存のヘッダーの有無にかかわらず
+    /// バリデーションを行い、失敗した場合はエラーを返す。
+    pub fn insert_header_if_absent(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<&mut Self, EncodeError> {
+        validate_header(name, value)?;
+        if !HttpHead::has_header(self, name) {
+            self.headers.push((header_name::intern(name), value.into()));
         }
-        self.headers.push((name.into(), value.into()));
         Ok(self)
     }
 
@@ -256,10 +310,159 @@ impl RequestHead {
     ///
     /// RFC 9110 Section 5。順序は受信順を保持する。
     #[must_use]
-    pub fn headers(&self) -> &[(String, String)] {
+    pub fn headers(&self) -> &[(Cow<'static, str>, String)] {
         &self.headers
     }
 
+    /// ヘッダーリストを受信バイト列の値で取得する
+    ///
+    /// `NonUtf8Policy::Latin1` / `NonUtf8Policy::Replace` で変換したヘッダー値は
+    /// 変換前の受信バイト列 (前後の OWS を除く) を、それ以外は `headers()` の値の
+    /// バイト列を返す。署名検証や転送など、受信したオクテットそのものが必要な
+    /// 場合に使う。
+    pub fn headers_raw(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.as_ref(), raw_value(&self.headers, &self.raw_values, i)))
+    }
+
+    /// ヘッダー値を受信バイト列で取得する (大文字小文字を区別しない)
+    ///
+    /// 同名のヘッダーが複数ある場合は最初の値を返す。
+    #[must_use]
+    pub fn get_header_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.headers_raw()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// request-target の形式を取得する (RFC 9112 Section 3.2)
+    ///
+    /// `RequestDecoder` 経由で得た `RequestHead` では常に `Some` を返す。
+    /// `new` で構築した場合、どの形式にも該当しない URI では `None` を返す。
+    #[must_use]
+    pub fn target_form(&self) -> Option<RequestTargetForm> {
+        classify(&self.uri)
+    }
+
+    /// request-target を形式ごとの構成要素にパースして取得する (RFC 9112 Section 3.2)
+    ///
+    /// `RequestDecoder` は request-target の形式とメソッドの組み合わせを検証済みのため、
+    /// authority-form は CONNECT、asterisk-form は OPTIONS のリクエストに限られる。
+    pub fn target(&self) -> Result<RequestTarget, RequestTargetError> {
+        RequestTarget::parse(&self.uri)
+    }
+
+    /// absolute-form の request-target のスキームを取得する
+    ///
+    /// absolute-form 以外では `None` を返す。大文字小文字は受信したまま返す。
+    #[must_use]
+    pub fn target_scheme(&self) -> Option<&str> {
+        match self.target_form()? {
+            RequestTargetForm::Absolute => split_absolute_form(&self.uri).map(|(s, _, _)| s),
+            _ => None,
+        }
+    }
+
+    /// request-target の authority を取得する
+    ///
+    /// - absolute-form: "//" に続く authority (例: `http://example.com:8080/` なら `example.com:8080`)
+    /// - authority-form: request-target 全体 (例: `example.com:443`)
+    /// - origin-form / asterisk-form / "//" を含まない absolute-form: `None`
+    #[must_use]
+    pub fn target_authority(&self) -> Option<&str> {
+        match self.target_form()? {
+            RequestTargetForm::Absolute => split_absolute_form(&self.uri)?.1,
+            RequestTargetForm::Authority => Some(&self.uri),
+            RequestTargetForm::Origin | RequestTargetForm::Asterisk => None,
+        }
+    }
+
+    /// request-target の path を取得する (query は含まない)
+    ///
+    /// - origin-form: "?" より前の部分
+    /// - absolute-form: authority に続く path。空の場合は空文字列を返すため、
+    ///   origin-form に変換する場合は "/" を補うこと (RFC 9112 Section 3.2.1)
+    /// - authority-form / asterisk-form: `None`
+    #[must_use]
+    pub fn target_path(&self) -> Option<&str> {
+        target_path(&self.uri)
+    }
+
+    /// request-target の path を取得する (ルーティング用、パーセントデコードしない)
+    ///
+    /// `target_path()` と同じ値を返す。authority-form / asterisk-form では空文字列を返す。
+    #[must_use]
+    pub fn path(&self) -> &str {
+        target_path(&self.uri).unwrap_or("")
+    }
+
+    /// request-target の query を取得する ("?" を含まない、パーセントデコードしない)
+    ///
+    /// "?" がない場合は `None`、"?" のみの場合は空文字列を返す。
+    #[must_use]
+    pub fn query(&self) -> Option<&str> {
+        target_query(&self.uri)
+    }
+
+    /// path をパーセントデコードして取得する
+    ///
+    /// `%2F` もデコードするため、デコード後の "/" でセグメントを分割してはならない。
+    pub fn decoded_path(&self) -> Result<String, UriError> {
+        percent_decode(self.path())
+    }
+
+    /// query をキーと値の組にパースして取得する (`uri::parse_query`)
+    ///
+    /// query がない場合は空を返す。
+    pub fn query_pairs(&self) -> Result<Vec<(String, String)>, UriError> {
+        parse_query(self.query().unwrap_or(""))
+    }
+
+    /// リクエストの実効的な authority を取得する
+    ///
+    /// RFC 9112 Section 3.2.2: absolute-form の場合は Host ヘッダーではなく
+    /// request-target の authority を使う (MUST)。authority-form の場合は
+    /// request-target 全体、それ以外は Host ヘッダーの値を返す。
+    #[must_use]
+    pub fn effective_authority(&self) -> Option<&str> {
+        self.target_authority()
+            .or_else(|| HttpHead::get_header(self, "Host"))
+    }
+
+    /// キープアライブ接続かどうかを判定 (RFC 9112 Section 9.3)
+    ///
+    /// `Connection` ヘッダーはカンマ区切りトークンリストとして扱う
+    /// (例: `Connection: keep-alive, upgrade` → `true`、`Connection: close, foo` → `false`)。
+    ///
+    /// 詳細は委譲先 `HttpHead::is_keep_alive` を参照。
+    #[must_use]
+    pub fn is_keep_alive(&self) -> bool {
+        HttpHead::is_keep_alive(self)
+    }
+
+    /// `Content-Length` ヘッダーの値を取得 (RFC 9110 Section 8.6)
+    ///
+    /// デコーダーと同じ検証を行い、数字以外を含む値や一致しない重複は
+    /// `Err(Error::InvalidData(..))` を返す。ヘッダーがない場合は `Ok(None)`。
+    ///
+    /// 詳細は委譲先 `HttpHead::content_length` を参照。
+    pub fn content_length(&self) -> Result<Option<u64>, Error> {
+        HttpHead::content_length(self)
+    }
+
+    /// Transfer-Encoding の最後が chunked かどうかを判定 (RFC 9112 Section 6.3)
+    ///
+    /// `Transfer-Encoding` ヘッダーはカンマ区切りトークンリストとして扱い、
+    /// OWS・空要素・大文字小文字の違いはデコーダーと同様に受理する。
+    ///
+    /// 詳細は委譲先 `HttpHead::is_chunked` を参照。
+    #[must_use]
+    pub fn is_chunked(&self) -> bool {
+        HttpHead::is_chunked(self)
+    }
+
     /// `RequestDecoder` 内部からの構築用 (バリデーションスキップ)
     ///
     /// `RequestDecoder::decode_headers` は start-line / ヘッダーをデコード時に
@@ -274,7 +477,8 @@ impl RequestHead {
         method: String,
         uri: String,
         version: String,
-        headers: Vec<(String, String)>,
+        headers: Vec<(Cow<'static, str>, String)>,
+        raw_values: Vec<(usize, Vec<u8>)>,
     ) -> Self {
         debug_assert!(is_valid_method(&method), "method must be valid token");
         debug_assert!(
@@ -297,6 +501,7 @@ impl RequestHead {
             uri,
             version,
             headers,
+            raw_values,
         }
     }
 }
@@ -306,7 +511,7 @@ impl HttpHead for RequestHead {
         &self.version
     }
 
-    fn headers(&self) -> &[(String, String)] {
+    fn headers(&self) -> &[(Cow<'static, str>, String)] {
         &self.headers
     }
 }
@@ -330,7 +535,9 @@ pub struct ResponseHead {
     /// ステータスフレーズ (OK, Not Found, etc.)
     pub(crate) reason_phrase: String,
     /// ヘッダー
-    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) headers: Vec<(Cow<'static, str>, String)>,
+    /// `NonUtf8Policy` により変換したヘッダー値の (インデックス, 受信バイト列)
+    pub(crate) raw_values: Vec<(usize, Vec<u8>)>,
 }
 
 impl ResponseHead {
@@ -370,6 +577,7 @@ impl ResponseHead {
             status_code,
             reason_phrase: reason_phrase.into(),
             headers: Vec::new(),
+            raw_values: Vec::new(),
         })
     }
 
@@ -381,16 +589,43 @@ impl ResponseHead {
 
     /// ヘッダーを追加する (バリデート付き、可変借用)
     pub fn add_header(&mut self, name: &str, value: &str) -> Result<&mut Self, EncodeError> {
-        if !is_valid_header_name(name) {
-            return Err(EncodeError::InvalidHeaderName { name: name.into() });
-        }
-        if !is_valid_field_value(value) {
-            return Err(EncodeError::InvalidHeaderValue {
-                name: name.into(),
-                value: value.into(),
-            });
+        validate_header(name, value)?;
+        self.headers.push((header_name::intern(name), value.into()));
+        Ok(self)
+    }
+
+    /// 指定した名前の既存ヘッダーを全削除し、新規に追加する (バリデート付き、可変借用)
+    ///
+    /// 追加したヘッダーは末尾に位置する。バリデーションが失敗した場合は
+    /// 既存ヘッダーは変更されない。
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<&mut Self, EncodeError> {
+        validate_header(name, value)?;
+        remove_headers(&mut self.headers, &mut self.raw_values, name);
+        self.headers.push((header_name::intern(name), value.into()));
+        Ok(self)
+    }
+
+    /// 指定した名前のヘッダーをすべて削除する (可変借用)
+    ///
+    /// 大文字小文字を区別せずに一致するヘッダーを削除する。存在しない場合は何もしない。
+    pub fn remove_header(&mut self, name: &str) -> &mut Self {
+        remove_headers(&mut self.headers, &mut self.raw_values, name);
+        self
+    }
+
+    /// 指定した名前のヘッダーが存在しない場合のみ追加する (バリデート付き、可変借用)
+    ///
+    /// 既存のヘッダーがある場合は変更しない。既存のヘッダーの有無にかかわらず
+    /// バリデーションを行い、失敗した場合はエラーを返す。
+    pub fn insert_header_if_absent(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<&mut Self, EncodeError> {
+        validate_header(name, value)?;
+        if !HttpHead::has_header(self, name) {
+            self.headers.push((header_name::intern(name), value.into()));
         }
-        self.headers.push((name.into(), value.into()));
         Ok(self)
     }
 
@@ -431,10 +666,65 @@ impl ResponseHead {
     ///
     /// RFC 9110 Section 5。順序は受信順を保持する。
     #[must_use]
-    pub fn headers(&self) -> &[(String, String)] {
+    pub fn headers(&self) -> &[(Cow<'static, str>, String)] {
         &self.headers
     }
 
+    /// ヘッダーリストを受信バイト列の値で取得する
+    ///
+    /// `NonUtf8Policy::Latin1` / `NonUtf8Policy::Replace` で変換したヘッダー値は
+    /// 変換前の受信バイト列 (前後の OWS を除く) を、それ以外は `headers()` の値の
+    /// バイト列を返す。署名検証や転送など、受信したオクテットそのものが必要な
+    /// 場合に使う。
+    pub fn headers_raw(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.as_ref(), raw_value(&self.headers, &self.raw_values, i)))
+    }
+
+    /// ヘッダー値を受信バイト列で取得する (大文字小文字を区別しない)
+    ///
+    /// 同名のヘッダーが複数ある場合は最初の値を返す。
+    #[must_use]
+    pub fn get_header_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.headers_raw()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// キープアライブ接続かどうかを判定 (RFC 9112 Section 9.3)
+    ///
+    /// `Connection` ヘッダーはカンマ区切りトークンリストとして扱う
+    /// (例: `Connection: keep-alive, upgrade` → `true`、`Connection: close, foo` → `false`)。
+    ///
+    /// 詳細は委譲先 `HttpHead::is_keep_alive` を参照。
+    #[must_use]
+    pub fn is_keep_alive(&self) -> bool {
+        HttpHead::is_keep_alive(self)
+    }
+
+    /// `Content-Length` ヘッダーの値を取得 (RFC 9110 Section 8.6)
+    ///
+    /// デコーダーと同じ検証を行い、数字以外を含む値や一致しない重複は
+    /// `Err(Error::InvalidData(..))` を返す。ヘッダーがない場合は `Ok(None)`。
+    ///
+    /// 詳細は委譲先 `HttpHead::content_length` を参照。
+    pub fn content_length(&self) -> Result<Option<u64>, Error> {
+        HttpHead::content_length(self)
+    }
+
+    /// Transfer-Encoding の最後が chunked かどうかを判定 (RFC 9112 Section 6.3)
+    ///
+    /// `Transfer-Encoding` ヘッダーはカンマ区切りトークンリストとして扱い、
+    /// OWS・空要素・大文字小文字の違いはデコーダーと同様に受理する。
+    ///
+    /// 詳細は委譲先 `HttpHead::is_chunked` を参照。
+    #[must_use]
+    pub fn is_chunked(&self) -> bool {
+        HttpHead::is_chunked(self)
+    }
+
     /// `ResponseDecoder` 内部からの構築用 (バリデーションスキップ)
     ///
     /// `ResponseDecoder::decode_headers` は status-line / ヘッダーをデコード時に
@@ -447,7 +737,8 @@ impl ResponseHead {
         version: String,
         status_code: u16,
         reason_phrase: String,
-        headers: Vec<(String, String)>,
+        headers: Vec<(Cow<'static, str>, String)>,
+        raw_values: Vec<(usize, Vec<u8>)>,
     ) -> Self {
         debug_assert!(
             is_valid_protocol_version(&version),
@@ -473,6 +764,7 @@ impl ResponseHead {
             status_code,
             reason_phrase,
             headers,
+            raw_values,
         }
     }
 }
@@ -482,7 +774,59 @@ impl HttpHead for ResponseHead {
         &self.version
     }
 
-    fn headers(&self) -> &[(String, String)] {
+    fn headers(&self) -> &[(Cow<'static, str>, String)] {
         &self.headers
     }
 }
+
+/// ヘッダー名と値をバリデートする
+fn validate_header(name: &str, value: &str) -> Result<(), EncodeError> {
+    if !is_valid_header_name(name) {
+        return Err(EncodeError::InvalidHeaderName { name: name.into() });
+    }
+    if !is_valid_field_value(value) {
+        return Err(EncodeError::InvalidHeaderValue {
+            name: name.into(),
+            value: value.into(),
+        });
+    }
+    Ok(())
+}
+
+/// 指定した名前のヘッダーを削除する (大文字小文字を区別しない)
+///
+/// `raw_values` は削除したヘッダーの分を除き、残りのインデックスを詰める。
+fn remove_headers(
+    headers: &mut Vec<(Cow<'static, str>, String)>,
+    raw_values: &mut Vec<(usize, Vec<u8>)>,
+    name: &str,
+) {
+    let removed: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .filter(|(_, (n, _))| n.eq_ignore_ascii_case(name))
+        .map(|(i, _)| i)
+        .collect();
+    if removed.is_empty() {
+        return;
+    }
+    raw_values.retain(|(i, _)| !removed.contains(i));
+    for (i, _) in raw_values.iter_mut() {
+        *i -= removed.iter().filter(|r| **r < *i).count();
+    }
+    headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+}
+
+/// ヘッダー値の受信バイト列を取得する
+///
+/// `raw_values` に変換前のバイト列があればそれを、なければ `headers` の値を返す。
+fn raw_value<'a>(
+    headers: &'a [(Cow<'static, str>, String)],
+    raw_values: &'a [(usize, Vec<u8>)],
+    index: usize,
+) -> &'a [u8] {
+    raw_values
+        .iter()
+        .find(|(i, _)| *i == index)
+        .map_or(headers[index].1.as_bytes(), |(_, v)| v.as_slice())
+}
@@ -87,12 +87,19 @@
 mod body;
 mod buffer;
 mod head;
+mod options;
 mod phase;
 mod request;
 mod response;
+mod stats;
 
 // 公開 API
-pub use body::{BodyKind, BodyProgress};
+pub use body::{BodyKind, BodyProgress, BodyRead};
 pub use head::{HttpHead, RequestHead, ResponseHead};
+pub use options::{DecoderProfile, NonUtf8Policy};
 pub use request::RequestDecoder;
 pub use response::ResponseDecoder;
+pub use stats::DecoderStats;
+
+// クレート内部 API
+pub(crate) use body::validate_chunk_ext;
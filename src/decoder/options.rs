@@ -0,0 +1,124 @@
+//! デコーダーのオプション
+
+use crate::limits::DecoderLimits;
+
+/// 非 UTF-8 バイトの扱い
+///
+/// RFC 9110 Section 5.5 / RFC 9112 Section 4 では field-value と reason-phrase に
+/// obs-text (0x80-0xFF) を許容しているが、本実装はこれらを `String` として保持するため
+/// UTF-8 として解釈できないバイト列の扱いを選択する必要がある。
+///
+/// 適用されるのはレスポンスの reason-phrase とヘッダー / トレーラーの field-value のみ。
+/// request-line と field-name は常に UTF-8 (実質 ASCII) として検証し、不正な場合は
+/// エラーにする。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NonUtf8Policy {
+    /// UTF-8 として不正なバイト列を含む場合はエラーにする (デフォルト)
+    #[default]
+    Reject,
+    /// UTF-8 として不正な場合、その値全体を ISO-8859-1 (Latin-1) として解釈する
+    ///
+    /// 各バイトを同じ値の Unicode スカラー値 (U+0000-U+00FF) に変換するため、
+    /// 元のバイト列を復元できる。値の中に正しい UTF-8 シーケンスが混在していても
+    /// それらもバイト単位で変換される。
+    Latin1,
+    /// UTF-8 として不正なバイト列を U+FFFD に置換する
+    Replace,
+}
+
+/// デコーダーの設定をまとめたプロファイル
+///
+/// 用途に応じたプリセットを `strict()` / `browser_compatible()` / `internal()` で作成し、
+/// フィールドや `with_*` で個別に調整してから
+/// `RequestDecoder::with_profile()` / `ResponseDecoder::with_profile()` に渡す。
+///
+/// 以下はプロファイルに関わらず常に受理しない。
+///
+/// - LF のみの行の終端 (bare LF)。行の終端は CRLF のみとして扱う
+/// - obs-fold による行の継続 (RFC 9112 Section 5.2)。エラーにする
+/// - Transfer-Encoding と Content-Length の併存、不正な Content-Length 等の
+///   HTTP Request Smuggling (CWE-444) の足場となるフレーミング。エラーにする
+///
+/// `Default` はデコーダーのデフォルト設定と同じ。
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct DecoderProfile {
+    /// 制限設定
+    pub limits: DecoderLimits,
+    /// 非 UTF-8 バイトの扱い
+    pub non_utf8_policy: NonUtf8Policy,
+    /// プロトコルバージョンを HTTP-version の文法 (RFC 9112 Section 2.3) に限定するか
+    pub strict_version: bool,
+}
+
+impl DecoderProfile {
+    /// 未信頼の入力を RFC に厳密に従って扱うプロファイル
+    ///
+    /// - 制限はデフォルト
+    /// - UTF-8 として不正な field-value はエラーにする (`NonUtf8Policy::Reject`)
+    /// - `HTTP/1.1` 等の HTTP-version 以外のプロトコルバージョンはエラーにする
+    pub fn strict() -> Self {
+        Self {
+            limits: DecoderLimits::default(),
+            non_utf8_policy: NonUtf8Policy::Reject,
+            strict_version: true,
+        }
+    }
+
+    /// ブラウザーと同程度の入力を受理するプロファイル
+    ///
+    /// - ヘッダーの総量と行長の上限をブラウザー並みに緩和する
+    /// - UTF-8 として不正な field-value は Latin-1 として解釈する (`NonUtf8Policy::Latin1`)
+    /// - HTTP-version 以外のプロトコルバージョンはエラーにする
+    pub fn browser_compatible() -> Self {
+        Self {
+            limits: DecoderLimits {
+                max_buffer_size: 256 * 1024, // 256KB
+                max_headers_count: 256,
+                max_header_line_size: 64 * 1024, // 64KB
+                ..DecoderLimits::default()
+            },
+            non_utf8_policy: NonUtf8Policy::Latin1,
+            strict_version: true,
+        }
+    }
+
+    /// 信頼できる内部サービス間の通信向けのプロファイル
+    ///
+    /// - バッファ、ヘッダー、ボディの上限を大きくする (上限はなくさない)
+    /// - UTF-8 として不正な field-value は Latin-1 として解釈する (`NonUtf8Policy::Latin1`)
+    /// - RTSP 等のために `protocol "/" DIGIT+ "." DIGIT+` のプロトコルバージョンを受理する
+    pub fn internal() -> Self {
+        Self {
+            limits: DecoderLimits {
+                max_buffer_size: 1024 * 1024, // 1MB
+                max_headers_count: 1000,
+                max_header_line_size: 64 * 1024,   // 64KB
+                max_body_size: 1024 * 1024 * 1024, // 1GB
+                max_uri_length: 64 * 1024,         // 64KB
+                ..DecoderLimits::default()
+            },
+            non_utf8_policy: NonUtf8Policy::Latin1,
+            strict_version: false,
+        }
+    }
+
+    /// 制限設定を変更
+    pub fn with_limits(mut self, limits: DecoderLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 非 UTF-8 バイトの扱いを変更
+    pub fn with_non_utf8_policy(mut self, policy: NonUtf8Policy) -> Self {
+        self.non_utf8_policy = policy;
+        self
+    }
+
+    /// プロトコルバージョンを HTTP-version の文法に限定するかを変更
+    pub fn with_strict_version(mut self, strict: bool) -> Self {
+        self.strict_version = strict;
+        self
+    }
+}
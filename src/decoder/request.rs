@@ -3,30 +3,44 @@
 //! # RFC 非準拠
 //!
 //! - RFC 9112 Section 2.2: HTTP/1.1 メッセージはオクテット列として解析しなければならない (MUST) だが、
-//!   本実装では UTF-8 として強制的に解析している。非 UTF-8 バイト列を含むリクエストは
-//!   エラーとして拒否される。
+//!   本実装では `String` として保持するため UTF-8 として解析している。ヘッダー / トレーラーの
+//!   field-value に含まれる非 UTF-8 バイト列の扱いは [`NonUtf8Policy`] で選択できる。
+//!   - `Reject` (デフォルト): エラーとして拒否する
+//!   - `Latin1`: 値全体を ISO-8859-1 として解釈する
+//!   - `Replace`: 不正なバイト列を U+FFFD に置換する
+//!
+//!   request-line と field-name は [`NonUtf8Policy`] の対象外で、非 UTF-8 バイト列を含む場合は
+//!   常にエラーとして拒否する。
 //!
 //! - RFC 9112 Section 2.2: request-line の前に受信した空行 (CRLF) を少なくとも 1 行は
 //!   無視すべき (SHOULD) だが、本実装では厳格にパースし、先頭の空行を不正なリクエスト行
 //!   として拒否する。アプリケーション層で必要に応じて先頭の空行を除去すること。
 
 use crate::compression::{CompressionStatus, Decompressor, NoCompression};
-use crate::error::Error;
+use crate::error::{Error, ParsePhase};
 use crate::limits::DecoderLimits;
 use crate::request::Request;
+use crate::trace::{WireEvent, WireObserver, WireTracer};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
-use crate::validate::{is_valid_method, is_valid_protocol_version, is_valid_request_target};
+use crate::validate::{
+    is_token_char, is_valid_http_version, is_valid_method, is_valid_protocol_version,
+    request_target_violation, trim_ows_bytes,
+};
 
 use super::body::{
-    BodyDecoder, BodyKind, BodyProgress, collect_declared_trailers, find_line, parse_header_line,
-    parse_request_target_form, resolve_body_headers_for_request,
-    validate_request_target_for_method,
+    BodyDecoder, BodyKind, BodyProgress, BodyRead, collect_declared_trailers, decode_line,
+    field_name_len, find_line, parse_header_line, parse_request_target_form,
+    resolve_body_headers_for_request, validate_request_target_for_method,
 };
-use super::buffer;
+use super::buffer::{self, StringPool};
 use super::head::RequestHead;
+use super::options::{DecoderProfile, NonUtf8Policy};
 use super::phase::DecodePhase;
+use super::stats::DecoderStats;
 
 /// HTTP リクエストデコーダー (Sans I/O)
 ///
@@ -57,8 +71,18 @@ use super::phase::DecodePhase;
 pub struct RequestDecoder<D: Decompressor = NoCompression> {
     buf: Vec<u8>,
     phase: DecodePhase,
-    start_line: Option<String>,
-    headers: Vec<(String, String)>,
+    /// 受信済みの request-line (未受信の場合は空)
+    ///
+    /// メッセージごとに確保し直さないよう、クリアして再利用する。
+    start_line: String,
+    headers: Vec<(Cow<'static, str>, String)>,
+    /// 直前のメッセージのヘッダー数
+    ///
+    /// ヘッダーの `Vec` は `RequestHead` に移すため、次のメッセージでは
+    /// この数を事前に確保して再確保の回数を抑える。
+    header_capacity: usize,
+    /// 返却されたメッセージから再利用する `String` の記憶領域
+    string_pool: StringPool,
     body_decoder: BodyDecoder,
     limits: DecoderLimits,
     /// decode() 用: デコード済みヘッダー
@@ -71,6 +95,19 @@ pub struct RequestDecoder<D: Decompressor = NoCompression> {
     decompressor: D,
     /// `mut_buf` で確保した未確定領域のバイト数
     pending: usize,
+    /// 統計情報
+    ///
+    /// `stats.bytes_fed - buf.len()` が `buf` 先頭の絶対バイトオフセットになる
+    /// (`Error::Syntax` の `offset` 算出に使う)。
+    stats: DecoderStats,
+    /// 非 UTF-8 バイトの扱い
+    non_utf8_policy: NonUtf8Policy,
+    /// `non_utf8_policy` により変換したヘッダーの (インデックス, 受信バイト列)
+    raw_values: Vec<(usize, Vec<u8>)>,
+    /// HTTP-version の文法 (RFC 9112 Section 2.3) に限定するか
+    strict_version: bool,
+    /// ワイヤーレベルのトレース
+    tracer: WireTracer,
 }
 
 impl Default for RequestDecoder<NoCompression> {
@@ -85,8 +122,10 @@ impl RequestDecoder<NoCompression> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits: DecoderLimits::default(),
             decoded_head: None,
@@ -94,6 +133,11 @@ impl RequestDecoder<NoCompression> {
             decoded_body: Vec::new(),
             decompressor: NoCompression::new(),
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
 
@@ -102,8 +146,10 @@ impl RequestDecoder<NoCompression> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits,
             decoded_head: None,
@@ -111,8 +157,27 @@ impl RequestDecoder<NoCompression> {
             decoded_body: Vec::new(),
             decompressor: NoCompression::new(),
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
+
+    /// プロファイルを適用してデコーダーを作成
+    ///
+    /// ```rust
+    /// use shiguredo_http11::{DecoderProfile, RequestDecoder};
+    ///
+    /// let decoder = RequestDecoder::with_profile(DecoderProfile::strict());
+    /// assert!(decoder.strict_version());
+    /// ```
+    pub fn with_profile(profile: DecoderProfile) -> Self {
+        let mut decoder = Self::new();
+        decoder.set_profile(&profile);
+        decoder
+    }
 }
 
 impl<D: Decompressor> RequestDecoder<D> {
@@ -121,8 +186,10 @@ impl<D: Decompressor> RequestDecoder<D> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits: DecoderLimits::default(),
             decoded_head: None,
@@ -130,6 +197,11 @@ impl<D: Decompressor> RequestDecoder<D> {
             decoded_body: Vec::new(),
             decompressor,
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
 
@@ -138,8 +210,10 @@ impl<D: Decompressor> RequestDecoder<D> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits,
             decoded_head: None,
@@ -147,6 +221,11 @@ impl<D: Decompressor> RequestDecoder<D> {
             decoded_body: Vec::new(),
             decompressor,
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
 
@@ -155,6 +234,117 @@ impl<D: Decompressor> RequestDecoder<D> {
         &self.limits
     }
 
+    /// 統計情報を取得
+    ///
+    /// デコーダー作成または `reset()` 以降の累計を返す。
+    pub fn stats(&self) -> &DecoderStats {
+        &self.stats
+    }
+
+    /// 非 UTF-8 バイトの扱いを設定する
+    ///
+    /// ヘッダー / トレーラーの field-valueに UTF-8 として不正なバイト列が含まれる場合の扱いを
+    /// 指定する。デフォルトは `NonUtf8Policy::Reject` でエラーにする。
+    /// request-line と field-name は設定に関わらず常に検証する。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_non_utf8_policy(&mut self, policy: NonUtf8Policy) {
+        self.non_utf8_policy = policy;
+        self.body_decoder.set_non_utf8_policy(policy);
+    }
+
+    /// 非 UTF-8 バイトの扱いを取得
+    pub fn non_utf8_policy(&self) -> NonUtf8Policy {
+        self.non_utf8_policy
+    }
+
+    /// プロトコルバージョンを HTTP-version の文法に限定するか設定する
+    ///
+    /// `true` の場合、request-line の version が `"HTTP/" DIGIT "." DIGIT`
+    /// (RFC 9112 Section 2.3) に一致しなければエラーにする。
+    /// デフォルトは `false` で、RTSP 等のために `protocol "/" DIGIT+ "." DIGIT+` を受理する。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_strict_version(&mut self, strict: bool) {
+        self.strict_version = strict;
+    }
+
+    /// プロトコルバージョンを HTTP-version の文法に限定するかを取得
+    pub fn strict_version(&self) -> bool {
+        self.strict_version
+    }
+
+    /// プロファイルを適用する
+    ///
+    /// 制限設定、非 UTF-8 バイトの扱い、プロトコルバージョンの文法をまとめて設定する。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_profile(&mut self, profile: &DecoderProfile) {
+        self.limits = profile.limits.clone();
+        self.set_non_utf8_policy(profile.non_utf8_policy);
+        self.set_strict_version(profile.strict_version);
+    }
+
+    /// 現在の設定をプロファイルとして取得
+    pub fn profile(&self) -> DecoderProfile {
+        DecoderProfile {
+            limits: self.limits.clone(),
+            non_utf8_policy: self.non_utf8_policy,
+            strict_version: self.strict_version,
+        }
+    }
+
+    /// chunked ボディのフレーミングを記録するか設定する
+    ///
+    /// `true` の場合、chunk-size 行 (chunk-ext を含む)、チャンクデータ直後の CRLF、
+    /// last-chunk 行、trailer 行、終端の CRLF を受信したバイト列のまま記録する。
+    /// [`take_chunk_framing()`](Self::take_chunk_framing) で取り出したバイト列と
+    /// [`peek_body()`](Self::peek_body) のデータを受信順に書き出すと、
+    /// 元の chunked ボディとバイト単位で一致する。
+    /// プロキシがチャンク境界や chunk-ext を保ったまま転送するために使う。
+    /// デフォルトは `false`。`reset()` 後も設定は維持される。
+    ///
+    /// 記録はボディの状態機械が進んだ時点で行うため、`peek_body()` /
+    /// `consume_body()` / `progress()` と組み合わせて使う。
+    /// `read_body()` は複数のチャンクを 1 回でコピーするため、境界の復元には使えない。
+    /// `decode()` はメッセージの完了時に記録を破棄する。
+    pub fn set_preserve_chunk_framing(&mut self, preserve: bool) {
+        self.body_decoder.set_preserve_chunk_framing(preserve);
+    }
+
+    /// chunked ボディのフレーミングを記録するかを取得
+    pub fn preserve_chunk_framing(&self) -> bool {
+        self.body_decoder.preserve_chunk_framing()
+    }
+
+    /// 記録した chunked ボディのフレーミングを取り出す
+    ///
+    /// 前回の呼び出し以降に記録したバイト列を返し、内部の記録を空にする。
+    /// [`set_preserve_chunk_framing()`](Self::set_preserve_chunk_framing) が
+    /// `false` の場合は常に空を返す。
+    pub fn take_chunk_framing(&mut self) -> Vec<u8> {
+        self.body_decoder.take_chunk_framing()
+    }
+
+    /// 現在のチャンクの chunk-ext を取得
+    ///
+    /// 直近にパースした chunk-size 行の chunk-size 直後から CRLF の手前まで (BWS を含む)
+    /// を返す。chunk-ext がない場合や、フレーミングを記録していない場合は `None`。
+    pub fn chunk_ext(&self) -> Option<&[u8]> {
+        self.body_decoder.chunk_ext()
+    }
+
+    /// ワイヤーレベルのイベントを受け取る observer を設定する
+    ///
+    /// start-line / ヘッダー行 / chunk-size 行 / トレーラー行をパースするたびに
+    /// [`WireEvent`] を通知する。設定済みの observer は置き換える。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_wire_observer(&mut self, observer: Box<dyn WireObserver>) {
+        self.tracer.set(observer);
+    }
+
+    /// 設定した observer を取り外して返す
+    pub fn take_wire_observer(&mut self) -> Option<Box<dyn WireObserver>> {
+        self.tracer.take()
+    }
+
     /// 既にメモリ上にあるバイト列を内部バッファに投入する
     ///
     /// `data` を `extend_from_slice` でコピーする (1 回の memcpy)。
@@ -179,7 +369,9 @@ impl<D: Decompressor> RequestDecoder<D> {
             self.pending,
             self.limits.max_buffer_size,
             data,
-        )
+        )?;
+        self.stats.bytes_fed = self.stats.bytes_fed.saturating_add(data.len() as u64);
+        Ok(())
     }
 
     /// バッファにデータを追加 (制限チェックなし)
@@ -194,6 +386,7 @@ impl<D: Decompressor> RequestDecoder<D> {
     /// 信頼済み入力またはテスト用途にのみ使用すること。
     pub fn feed_unchecked(&mut self, data: &[u8]) {
         buffer::feed_unchecked(&mut self.buf, self.pending, data);
+        self.stats.bytes_fed = self.stats.bytes_fed.saturating_add(data.len() as u64);
     }
 
     /// 内部バッファ末尾に `len` バイトの書き込み枠を確保し、その可変スライスを返す
@@ -227,7 +420,9 @@ impl<D: Decompressor> RequestDecoder<D> {
     /// `len > pending` の場合、debug ビルドでは panic、release ビルドでは
     /// `pending` で飽和する。
     pub fn advance_buf(&mut self, len: usize) {
+        let len = len.min(self.pending);
         buffer::advance_buf(&mut self.buf, &mut self.pending, len);
+        self.stats.bytes_fed = self.stats.bytes_fed.saturating_add(len as u64);
     }
 
     /// 書き込み可能な残り容量を返す
@@ -244,18 +439,66 @@ impl<D: Decompressor> RequestDecoder<D> {
         buffer::remaining(&self.buf, self.pending)
     }
 
+    /// 内部バッファ先頭の絶対バイトオフセット
+    fn buf_offset(&self) -> u64 {
+        self.stats.bytes_fed.saturating_sub(self.buf.len() as u64)
+    }
+
     /// デコーダーをリセット
+    ///
+    /// 受信済みの未処理データと統計情報も破棄する。確保済みの容量は解放しない。
     pub fn reset(&mut self) {
+        self.clear_for_next_message();
         self.buf.clear();
+        self.pending = 0;
+        self.stats = DecoderStats::default();
+    }
+
+    /// 次のメッセージのデコードに備えてメッセージ単位の状態をクリアする
+    ///
+    /// `reset()` と異なり、受信済みの未処理データ (パイプライン化された次の
+    /// リクエスト等) と統計情報は保持する。確保済みのバッファ容量も解放しない。
+    /// CONNECT に 4xx/5xx で応答してトンネルモードから通常モードに戻す場合などに使う。
+    ///
+    /// ボディの途中で呼び出した場合、残りのボディは次のリクエストの先頭として扱われる。
+    pub fn clear_for_next_message(&mut self) {
         self.phase = DecodePhase::StartLine;
-        self.start_line = None;
+        self.start_line.clear();
         self.headers.clear();
+        self.raw_values.clear();
         self.body_decoder.reset();
         self.decoded_head = None;
         self.decoded_body_kind = None;
         self.decoded_body.clear();
         self.decompressor.reset();
-        self.pending = 0;
+    }
+
+    /// デコード済みの `RequestHead` を返却し、記憶領域を次のリクエストで再利用する
+    ///
+    /// Keep-Alive 接続で同じデコーダーを使い続ける場合、処理を終えたヘッダーを
+    /// 返却すると、request-line の要素とヘッダーの `String` および `Vec` を
+    /// 次のリクエストのデコードで使い回す。返却は任意で、返却しなくても動作は変わらない。
+    /// 保持するのは直近に返却した 1 メッセージ分のみ。
+    pub fn recycle_head(&mut self, head: RequestHead) {
+        let RequestHead {
+            method,
+            uri,
+            version,
+            mut headers,
+            ..
+        } = head;
+        self.string_pool
+            .refill([method, uri, version], &mut headers);
+        buffer::recycle_headers(&mut self.headers, headers);
+    }
+
+    /// `decode()` で取得した `Request` を返却し、記憶領域を次のリクエストで再利用する
+    ///
+    /// `recycle_head()` に加えて、ボディの `Vec` も次の `decode()` で使い回す。
+    pub fn recycle_request(&mut self, request: Request) {
+        let (head, body) = request.into_head_and_body();
+        self.recycle_head(head);
+        buffer::recycle_body(&mut self.decoded_body, body);
     }
 
     /// バッファの残りデータを取り出す (トンネルモード用)
@@ -326,6 +569,115 @@ impl<D: Decompressor> RequestDecoder<D> {
         Ok(BodyKind::None)
     }
 
+    /// ヘッダーセクション終端の空行を受信した後の検証と `RequestHead` の構築
+    ///
+    /// Host ヘッダー検証 (RFC 9112 Section 3.2) とボディ種別の決定を行い、
+    /// ボディフェーズへ遷移する。
+    fn finish_headers(&mut self) -> Result<(RequestHead, BodyKind), Error> {
+        // RFC 9112 Section 3.2: HTTP/1.1 リクエストでは Host ヘッダーが必須
+        if self.start_line.is_empty() {
+            return Err(Error::InvalidData("missing request line".to_string()));
+        }
+        let start_line_ref = &self.start_line;
+        let version = start_line_ref.split(' ').nth(2).unwrap_or("");
+        if version == "HTTP/1.1" {
+            let mut host_headers = self
+                .headers
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case("Host"));
+            let Some((_, host_value)) = host_headers.next() else {
+                return Err(Error::InvalidData(
+                    "HTTP/1.1 request missing Host header".to_string(),
+                ));
+            };
+            if host_headers.next().is_some() {
+                return Err(Error::InvalidData(
+                    "HTTP/1.1 request contains multiple Host headers".to_string(),
+                ));
+            }
+            // Host ヘッダー値検証
+            // 空の Host ヘッダーは許可 (RFC 9112 Section 3.2)
+            if !host_value.is_empty() && crate::host::validate(host_value).is_err() {
+                return Err(Error::InvalidData(
+                    "HTTP/1.1 request contains invalid Host header value".to_string(),
+                ));
+            }
+        }
+
+        // RFC 9110 Section 9.3.6:
+        // "A CONNECT request message does not have content."
+        // "When a server responds with a 2xx (Successful) status code to a
+        //  CONNECT request, the connection becomes a tunnel immediately
+        //  after the header section, with the connection used as-is to
+        //  convey the data of the tunnel."
+        //
+        // CONNECT 受信時はヘッダー終端直後の任意バイト列をトンネルデータと
+        // して扱う必要がある。`BodyKind::None` で Complete 遷移してしまうと
+        // 後続バイトが「次の HTTP リクエスト」として decode_headers で
+        // parse されはじめ、HTTP Request Smuggling 経路を生む。
+        // ResponseDecoder の 2xx 応答経路と対称に `BodyKind::Tunnel` に
+        // 遷移させ、`take_remaining()` で transparent に転送できるようにする。
+        //
+        // CONNECT 失敗時 (サーバが 4xx/5xx を返す等) は呼出側で `reset()`
+        // して通常のリクエスト処理に戻すか、接続をクローズする。
+        //
+        // RFC は CONNECT リクエスト側の Content-Length / Transfer-Encoding
+        // を MUST NOT としていない (MUST NOT は 2xx レスポンス側の制約)
+        // ため、それらヘッダーが付いていても即エラーにはしない。
+        let method = start_line_ref.split(' ').next().unwrap_or("");
+        let body_kind = if method == "CONNECT" {
+            BodyKind::Tunnel
+        } else {
+            self.determine_body_kind(version)?
+        };
+
+        // ヘッダー完了、ボディフェーズに遷移
+        // RFC 9112: リクエストは close-delimited を使わない
+        match body_kind {
+            BodyKind::ContentLength(len) => {
+                if len > 0 {
+                    self.phase = DecodePhase::BodyContentLength { remaining: len };
+                } else {
+                    self.phase = DecodePhase::Complete;
+                }
+            }
+            BodyKind::Chunked => {
+                self.phase = DecodePhase::BodyChunkedSize;
+            }
+            BodyKind::CloseDelimited | BodyKind::None => {
+                self.phase = DecodePhase::Complete;
+            }
+            BodyKind::Tunnel => {
+                // CONNECT リクエスト: ヘッダー終端後はトンネルモード。
+                // 後続バイトは `take_remaining()` で取り出す。
+                self.phase = DecodePhase::Tunnel;
+            }
+        }
+
+        // RFC 9110 Section 6.5.1 のホワイトリスト方式 trailer 受理に
+        // 必要な「申告された trailer フィールド名リスト」を、
+        // ヘッダーから `Trailer:` を抽出して BodyDecoder に渡す。
+        // chunked 以外の本 body kind では trailer は来ないが、
+        // BodyDecoder は body kind を問わず参照するため常に設定する。
+        let declared_trailers = collect_declared_trailers(&self.headers);
+        self.body_decoder.set_declared_trailers(declared_trailers);
+
+        // RequestHead を構築
+        // request-line はバリデート済みのため 3 要素に分割できる
+        let mut parts = self.start_line.splitn(3, ' ');
+        self.header_capacity = self.headers.len();
+        let head = RequestHead::from_validated_parts(
+            self.string_pool.take(parts.next().unwrap_or_default()),
+            self.string_pool.take(parts.next().unwrap_or_default()),
+            self.string_pool.take(parts.next().unwrap_or_default()),
+            core::mem::take(&mut self.headers),
+            core::mem::take(&mut self.raw_values),
+        );
+        self.start_line.clear();
+
+        Ok((head, body_kind))
+    }
+
     /// ヘッダーをデコード
     ///
     /// ヘッダーが完了したら `Some((RequestHead, BodyKind))` を返す
@@ -339,62 +691,47 @@ impl<D: Decompressor> RequestDecoder<D> {
         loop {
             match &self.phase {
                 DecodePhase::StartLine => {
-                    if let Some(pos) = find_line(&self.buf) {
-                        let line = String::from_utf8(self.buf[..pos].to_vec()).map_err(|e| {
-                            Error::InvalidData(alloc::format!("invalid UTF-8: {e}"))
+                    let line_end = find_line(&self.buf);
+                    check_request_line_lengths(
+                        &self.buf[..line_end.unwrap_or(self.buf.len())],
+                        &self.limits,
+                    )?;
+                    if let Some(pos) = line_end {
+                        let offset = self.buf_offset();
+                        let line = core::str::from_utf8(&self.buf[..pos]).map_err(|e| {
+                            Error::syntax(
+                                ParsePhase::StartLine,
+                                offset,
+                                &self.buf[..pos],
+                                alloc::format!("invalid UTF-8: {e}"),
+                            )
                         })?;
-                        self.buf.drain(..pos + 2);
-                        if line.contains('\r') || line.contains('\n') {
-                            return Err(Error::InvalidData(
-                                "invalid request line: contains CR/LF".to_string(),
-                            ));
-                        }
-
-                        // Parse: METHOD SP URI SP VERSION CRLF
-                        let parts: Vec<&str> = line.splitn(3, ' ').collect();
-                        if parts.len() != 3 {
-                            return Err(Error::InvalidData(alloc::format!(
-                                "invalid request line: {}",
-                                line
-                            )));
-                        }
-
-                        // メソッド名の検証 (RFC 9110 Section 9)
-                        if !is_valid_method(parts[0]) {
-                            return Err(Error::InvalidData(
-                                "invalid request line: invalid method".to_string(),
-                            ));
-                        }
-
-                        // リクエストターゲットの検証 (RFC 9112 Section 3)
-                        if !is_valid_request_target(parts[1]) {
-                            return Err(Error::InvalidData(
-                                "invalid request line: invalid request-target".to_string(),
-                            ));
-                        }
-
-                        // request-target は RFC 3986 Section 2 で US-ASCII 限定であり、
-                        // decoder 側でも obs-text (0x80-0xFF) を reject する。
-                        // is_valid_request_target は受信側互換性のため obs-text を許容するが、
-                        // 構築された Request は送信されることを前提とするため、ここで早期に拒否する。
-                        if parts[1].bytes().any(|b| b >= 0x80) {
+                        self.stats.header_bytes += pos as u64 + 2;
+                        validate_request_line(line).map_err(|e| {
+                            e.with_context(ParsePhase::StartLine, offset, line.as_bytes())
+                        })?;
+                        if self.strict_version
+                            && !is_valid_http_version(line.rsplit(' ').next().unwrap_or(""))
+                        {
                             return Err(Error::InvalidData(
-                                "invalid request-target: non-ASCII characters".to_string(),
+                                "invalid request line: invalid HTTP-version (RFC 9112 Section 2.3)"
+                                    .to_string(),
+                            )
+                            .with_context(
+                                ParsePhase::StartLine,
+                                offset,
+                                line.as_bytes(),
                             ));
                         }
 
-                        // request-target の形式判定と検証 (RFC 9112 Section 3.2)
-                        let request_target_form = parse_request_target_form(parts[1])?;
-                        validate_request_target_for_method(parts[0], &request_target_form)?;
-
-                        // プロトコルバージョンの検証
-                        if !is_valid_protocol_version(parts[2]) {
-                            return Err(Error::InvalidData(
-                                "invalid request line: invalid protocol version".to_string(),
-                            ));
-                        }
+                        self.tracer.emit(WireEvent::StartLine {
+                            offset,
+                            raw: &self.buf[..pos],
+                        });
+                        self.start_line.push_str(line);
+                        self.buf.drain(..pos + 2);
 
-                        self.start_line = Some(line);
+                        self.headers.reserve(self.header_capacity);
                         self.phase = DecodePhase::Headers;
                     } else {
                         return Ok(None);
@@ -402,118 +739,18 @@ impl<D: Decompressor> RequestDecoder<D> {
                 }
                 DecodePhase::Headers => {
                     if let Some(pos) = find_line(&self.buf) {
+                        let offset = self.buf_offset();
                         if pos == 0 {
                             // 空行 — ヘッダーセクション終端
                             self.buf.drain(..2);
-
-                            // RFC 9112 Section 3.2: HTTP/1.1 リクエストでは Host ヘッダーが必須
-                            let start_line_ref = self.start_line.as_ref().ok_or_else(|| {
-                                Error::InvalidData("missing request line".to_string())
-                            })?;
-                            let version = start_line_ref.split(' ').nth(2).unwrap_or("");
-                            if version == "HTTP/1.1" {
-                                let host_headers: Vec<_> = self
-                                    .headers
-                                    .iter()
-                                    .filter(|(name, _)| name.eq_ignore_ascii_case("Host"))
-                                    .collect();
-                                if host_headers.is_empty() {
-                                    return Err(Error::InvalidData(
-                                        "HTTP/1.1 request missing Host header".to_string(),
-                                    ));
-                                }
-                                if host_headers.len() > 1 {
-                                    return Err(Error::InvalidData(
-                                        "HTTP/1.1 request contains multiple Host headers"
-                                            .to_string(),
-                                    ));
-                                }
-                                // Host ヘッダー値検証
-                                // 空の Host ヘッダーは許可 (RFC 9112 Section 3.2)
-                                let (_, host_value) = host_headers[0];
-                                if !host_value.is_empty()
-                                    && crate::host::Host::parse(host_value).is_err()
-                                {
-                                    return Err(Error::InvalidData(
-                                        "HTTP/1.1 request contains invalid Host header value"
-                                            .to_string(),
-                                    ));
-                                }
-                            }
-
-                            // RFC 9110 Section 9.3.6:
-                            // "A CONNECT request message does not have content."
-                            // "When a server responds with a 2xx (Successful) status code to a
-                            //  CONNECT request, the connection becomes a tunnel immediately
-                            //  after the header section, with the connection used as-is to
-                            //  convey the data of the tunnel."
-                            //
-                            // CONNECT 受信時はヘッダー終端直後の任意バイト列をトンネルデータと
-                            // して扱う必要がある。`BodyKind::None` で Complete 遷移してしまうと
-                            // 後続バイトが「次の HTTP リクエスト」として decode_headers で
-                            // parse されはじめ、HTTP Request Smuggling 経路を生む。
-                            // ResponseDecoder の 2xx 応答経路と対称に `BodyKind::Tunnel` に
-                            // 遷移させ、`take_remaining()` で transparent に転送できるようにする。
-                            //
-                            // CONNECT 失敗時 (サーバが 4xx/5xx を返す等) は呼出側で `reset()`
-                            // して通常のリクエスト処理に戻すか、接続をクローズする。
-                            //
-                            // RFC は CONNECT リクエスト側の Content-Length / Transfer-Encoding
-                            // を MUST NOT としていない (MUST NOT は 2xx レスポンス側の制約)
-                            // ため、それらヘッダーが付いていても即エラーにはしない。
-                            let method = start_line_ref.split(' ').next().unwrap_or("");
-                            let body_kind = if method == "CONNECT" {
-                                BodyKind::Tunnel
-                            } else {
-                                self.determine_body_kind(version)?
-                            };
-
-                            // ヘッダー完了、ボディフェーズに遷移
-                            // RFC 9112: リクエストは close-delimited を使わない
-                            match body_kind {
-                                BodyKind::ContentLength(len) => {
-                                    if len > 0 {
-                                        self.phase =
-                                            DecodePhase::BodyContentLength { remaining: len };
-                                    } else {
-                                        self.phase = DecodePhase::Complete;
-                                    }
-                                }
-                                BodyKind::Chunked => {
-                                    self.phase = DecodePhase::BodyChunkedSize;
-                                }
-                                BodyKind::CloseDelimited | BodyKind::None => {
-                                    self.phase = DecodePhase::Complete;
-                                }
-                                BodyKind::Tunnel => {
-                                    // CONNECT リクエスト: ヘッダー終端後はトンネルモード。
-                                    // 後続バイトは `take_remaining()` で取り出す。
-                                    self.phase = DecodePhase::Tunnel;
-                                }
-                            }
-
-                            // RFC 9110 Section 6.5.1 のホワイトリスト方式 trailer 受理に
-                            // 必要な「申告された trailer フィールド名リスト」を、
-                            // ヘッダーから `Trailer:` を抽出して BodyDecoder に渡す。
-                            // chunked 以外の本 body kind では trailer は来ないが、
-                            // BodyDecoder は body kind を問わず参照するため常に設定する。
-                            let declared_trailers = collect_declared_trailers(&self.headers);
-                            self.body_decoder.set_declared_trailers(declared_trailers);
-
-                            // RequestHead を構築
-                            let start_line = self.start_line.take().ok_or_else(|| {
-                                Error::InvalidData("missing request line".to_string())
-                            })?;
-                            let parts: Vec<&str> = start_line.splitn(3, ' ').collect();
-
-                            let head = RequestHead::from_validated_parts(
-                                parts[0].to_string(),
-                                parts[1].to_string(),
-                                parts[2].to_string(),
-                                core::mem::take(&mut self.headers),
-                            );
-
-                            return Ok(Some((head, body_kind)));
+                            self.stats.header_bytes += 2;
+                            // ヘッダーセクション全体に対する検証エラーは特定の行に
+                            // 紐付かないため、終端の空行の位置で報告する
+                            let decoded = self
+                                .finish_headers()
+                                .map_err(|e| e.with_context(ParsePhase::Header, offset, &[]))?;
+                            self.stats.messages += 1;
+                            return Ok(Some(decoded));
                         } else {
                             // ヘッダー行サイズ上限の検査
                             if pos > self.limits.max_header_line_size {
@@ -531,14 +768,45 @@ impl<D: Decompressor> RequestDecoder<D> {
                                 });
                             }
 
-                            let line =
-                                String::from_utf8(self.buf[..pos].to_vec()).map_err(|e| {
-                                    Error::InvalidData(alloc::format!("invalid UTF-8: {e}"))
-                                })?;
-                            self.buf.drain(..pos + 2);
+                            let line_bytes = &self.buf[..pos];
+                            let (line, converted) = decode_line(
+                                line_bytes,
+                                field_name_len(line_bytes),
+                                self.non_utf8_policy,
+                            )
+                            .map_err(|e| {
+                                Error::syntax(
+                                    ParsePhase::Header,
+                                    offset,
+                                    line_bytes,
+                                    alloc::format!("invalid UTF-8: {e}"),
+                                )
+                            })?;
+                            // 変換した場合のみ field-value の受信バイト列を保持する
+                            let raw_value = converted.then(|| {
+                                let value_start = (field_name_len(line_bytes) + 1).min(pos);
+                                trim_ows_bytes(&line_bytes[value_start..]).to_vec()
+                            });
+                            self.stats.header_bytes += pos as u64 + 2;
 
-                            let (name, value) = parse_header_line(&line)?;
-                            self.headers.push((name, value));
+                            let (name, value) = parse_header_line(&line).map_err(|e| {
+                                e.with_context(ParsePhase::Header, offset, line.as_bytes())
+                            })?;
+                            self.tracer.emit(WireEvent::Header {
+                                offset,
+                                name,
+                                value,
+                                raw: &self.buf[..pos],
+                            });
+                            let header = (
+                                self.string_pool.take_name(name),
+                                self.string_pool.take(value),
+                            );
+                            self.buf.drain(..pos + 2);
+                            if let Some(raw_value) = raw_value {
+                                self.raw_values.push((self.headers.len(), raw_value));
+                            }
+                            self.headers.push(header);
                         }
                     } else {
                         return Ok(None);
@@ -546,11 +814,7 @@ impl<D: Decompressor> RequestDecoder<D> {
                 }
                 DecodePhase::Complete => {
                     // 完了状態から次のメッセージへ遷移
-                    self.phase = DecodePhase::StartLine;
-                    self.start_line = None;
-                    self.headers.clear();
-                    self.body_decoder.reset();
-                    self.decompressor.reset();
+                    self.clear_for_next_message();
                     continue;
                 }
                 DecodePhase::Tunnel => {
@@ -658,8 +922,52 @@ impl<D: Decompressor> RequestDecoder<D> {
                 "consume_body(0) is not allowed, use progress() instead".to_string(),
             ));
         }
-        self.body_decoder
-            .consume_body(&mut self.buf, &mut self.phase, len, &self.limits)
+        self.body_decoder.consume_body(
+            &mut self.buf,
+            &mut self.phase,
+            len,
+            &self.limits,
+            &mut self.stats,
+            &mut self.tracer,
+        )
+    }
+
+    /// ボディデータを `dst` に直接コピーして消費する
+    ///
+    /// `decode_headers()` 成功後に呼ぶ。
+    /// `peek_body()` + `consume_body()` と異なり、呼び出し側のバッファへ 1 回の
+    /// コピーで書き込む。`dst` が埋まるか、追加データが必要になるか、ボディが
+    /// 完了するまで状態機械 (チャンクサイズ行やトレーラーの処理を含む) を進める。
+    /// `dst` が空の場合はエラーを返す。
+    ///
+    /// 展開は行わない。Content-Encoding を展開する場合は
+    /// `peek_body_decompressed()` を使う。
+    ///
+    /// # 使い方
+    ///
+    /// ```ignore
+    /// let mut dst = [0u8; 8192];
+    /// loop {
+    ///     match decoder.read_body(&mut dst)? {
+    ///         BodyRead::Data(n) => process(&dst[..n]),
+    ///         BodyRead::NeedData => break, // ネットワーク I/O に戻る
+    ///         BodyRead::Complete { len, trailers } => {
+    ///             process(&dst[..len]);
+    ///             break;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn read_body(&mut self, dst: &mut [u8]) -> Result<BodyRead, Error> {
+        debug_assert!(self.pending == 0, "read_body called with pending mut_buf");
+        self.body_decoder.read_body(
+            &mut self.buf,
+            &mut self.phase,
+            dst,
+            &self.limits,
+            &mut self.stats,
+            &mut self.tracer,
+        )
     }
 
     /// 状態機械を進める (ボディデータは消費しない)
@@ -676,8 +984,14 @@ impl<D: Decompressor> RequestDecoder<D> {
     /// で直接 phase を確認している。
     pub fn progress(&mut self) -> Result<BodyProgress, Error> {
         debug_assert!(self.pending == 0, "progress called with pending mut_buf");
-        self.body_decoder
-            .consume_body(&mut self.buf, &mut self.phase, 0, &self.limits)
+        self.body_decoder.consume_body(
+            &mut self.buf,
+            &mut self.phase,
+            0,
+            &self.limits,
+            &mut self.stats,
+            &mut self.tracer,
+        )
     }
 
     /// リクエスト全体を一括でデコード
@@ -756,11 +1070,7 @@ impl<D: Decompressor> RequestDecoder<D> {
         };
 
         // Keep-Alive 対応: 次のリクエストのために状態をリセット
-        self.phase = DecodePhase::StartLine;
-        self.decoded_body_kind = None;
-        self.decoded_body.clear();
-        self.body_decoder.reset();
-        self.decompressor.reset();
+        self.clear_for_next_message();
 
         Ok(Some(Request::from_raw_parts(
             head.method,
@@ -771,3 +1081,92 @@ impl<D: Decompressor> RequestDecoder<D> {
         )))
     }
 }
+
+/// request-line のメソッドと request-target の長さを検証する
+///
+/// RFC 9112 Section 3: 長すぎるメソッドには 501、長すぎる request-target には
+/// 414 を返すべき (SHOULD / MUST)。`line` は CRLF を受信していない途中の
+/// request-line でもよく、受信済みの部分だけで上限超過が確定した時点でエラーを返す。
+fn check_request_line_lengths(line: &[u8], limits: &DecoderLimits) -> Result<(), Error> {
+    let method_len = line.iter().position(|&b| b == b' ').unwrap_or(line.len());
+    if method_len > limits.max_method_length {
+        return Err(Error::MethodTooLong {
+            size: method_len,
+            limit: limits.max_method_length,
+        });
+    }
+    if method_len == line.len() {
+        return Ok(());
+    }
+    let target = &line[method_len + 1..];
+    let target_len = target
+        .iter()
+        .position(|&b| b == b' ')
+        .unwrap_or(target.len());
+    if target_len > limits.max_uri_length {
+        return Err(Error::UriTooLong {
+            size: target_len,
+            limit: limits.max_uri_length,
+        });
+    }
+    Ok(())
+}
+
+/// リクエスト行を検証する (RFC 9112 Section 3)
+///
+/// request-line = method SP request-target SP HTTP-version
+fn validate_request_line(line: &str) -> Result<(), Error> {
+    if line.contains('\r') || line.contains('\n') {
+        return Err(Error::InvalidData(
+            "invalid request line: contains CR/LF".to_string(),
+        ));
+    }
+
+    // Parse: METHOD SP URI SP VERSION CRLF
+    let mut parts = line.splitn(3, ' ');
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(Error::InvalidData("invalid request line".to_string()));
+    };
+
+    // メソッド名の検証 (RFC 9110 Section 9.1: method = token)
+    if !is_valid_method(method) {
+        let reason = match method.bytes().find(|&b| !is_token_char(b)) {
+            Some(b) => alloc::format!(
+                "invalid request line: invalid method: non-token character 0x{b:02x}"
+            ),
+            None => "invalid request line: invalid method: empty".to_string(),
+        };
+        return Err(Error::InvalidData(reason));
+    }
+
+    // リクエストターゲットの検証 (RFC 9112 Section 3)
+    if let Some(violation) = request_target_violation(target) {
+        return Err(Error::InvalidData(alloc::format!(
+            "invalid request line: invalid request-target: {violation}"
+        )));
+    }
+
+    // request-target は RFC 3986 Section 2 で US-ASCII 限定であり、
+    // decoder 側でも obs-text (0x80-0xFF) を reject する。
+    // is_valid_request_target は受信側互換性のため obs-text を許容するが、
+    // 構築された Request は送信されることを前提とするため、ここで早期に拒否する。
+    if target.bytes().any(|b| b >= 0x80) {
+        return Err(Error::InvalidData(
+            "invalid request-target: non-ASCII characters".to_string(),
+        ));
+    }
+
+    // request-target の形式判定と検証 (RFC 9112 Section 3.2)
+    let request_target_form = parse_request_target_form(target)?;
+    validate_request_target_for_method(method, &request_target_form)?;
+
+    // プロトコルバージョンの検証
+    if !is_valid_protocol_version(version) {
+        return Err(Error::InvalidData(
+            "invalid request line: invalid protocol version".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -3,25 +3,41 @@
 //! # RFC 非準拠
 //!
 //! - RFC 9112 Section 2.2: HTTP/1.1 メッセージはオクテット列として解析しなければならない (MUST) だが、
-//!   本実装では UTF-8 として強制的に解析している。非 UTF-8 バイト列を含むレスポンスは
-//!   エラーとして拒否される。
+//!   本実装では `String` として保持するため UTF-8 として解析している。reason-phrase と
+//!   ヘッダー / トレーラーの field-value に含まれる非 UTF-8 バイト列の扱いは
+//!   [`NonUtf8Policy`] で選択できる。
+//!   - `Reject` (デフォルト): エラーとして拒否する
+//!   - `Latin1`: 値全体を ISO-8859-1 として解釈する
+//!   - `Replace`: 不正なバイト列を U+FFFD に置換する
+//!
+//!   status-line の reason-phrase 以外の部分と field-name は [`NonUtf8Policy`] の対象外で、
+//!   非 UTF-8 バイト列を含む場合は常にエラーとして拒否する。
 
 use crate::compression::{CompressionStatus, Decompressor, NoCompression};
-use crate::error::Error;
+use crate::error::{Error, ParsePhase};
 use crate::limits::DecoderLimits;
 use crate::response::Response;
+use crate::trace::{WireEvent, WireObserver, WireTracer};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
-use crate::validate::{is_valid_protocol_version, is_valid_reason_phrase, is_valid_status_code};
+use crate::validate::{
+    is_valid_http_version, is_valid_protocol_version, is_valid_reason_phrase, is_valid_status_code,
+    trim_ows_bytes,
+};
 
 use super::body::{
-    BodyDecoder, BodyKind, BodyProgress, TransferEncodingResult, collect_declared_trailers,
-    find_line, parse_header_line, resolve_body_headers_for_response,
+    BodyDecoder, BodyKind, BodyProgress, BodyRead, TransferEncodingResult,
+    collect_declared_trailers, decode_line, field_name_len, find_line, parse_header_line,
+    resolve_body_headers_for_response,
 };
-use super::buffer;
+use super::buffer::{self, StringPool};
 use super::head::ResponseHead;
+use super::options::{DecoderProfile, NonUtf8Policy};
 use super::phase::DecodePhase;
+use super::stats::DecoderStats;
 
 /// HTTP レスポンスデコーダー (Sans I/O)
 ///
@@ -52,8 +68,18 @@ use super::phase::DecodePhase;
 pub struct ResponseDecoder<D: Decompressor = NoCompression> {
     buf: Vec<u8>,
     phase: DecodePhase,
-    start_line: Option<String>,
-    headers: Vec<(String, String)>,
+    /// 受信済みの status-line (未受信の場合は空)
+    ///
+    /// メッセージごとに確保し直さないよう、クリアして再利用する。
+    start_line: String,
+    headers: Vec<(Cow<'static, str>, String)>,
+    /// 直前のメッセージのヘッダー数
+    ///
+    /// ヘッダーの `Vec` は `ResponseHead` に移すため、次のメッセージでは
+    /// この数を事前に確保して再確保の回数を抑える。
+    header_capacity: usize,
+    /// 返却されたメッセージから再利用する `String` の記憶領域
+    string_pool: StringPool,
     body_decoder: BodyDecoder,
     limits: DecoderLimits,
     /// decode() 用: デコード済みヘッダー
@@ -68,6 +94,19 @@ pub struct ResponseDecoder<D: Decompressor = NoCompression> {
     request_method: Option<String>,
     /// `mut_buf` で確保した未確定領域のバイト数
     pending: usize,
+    /// 統計情報
+    ///
+    /// `stats.bytes_fed - buf.len()` が `buf` 先頭の絶対バイトオフセットになる
+    /// (`Error::Syntax` の `offset` 算出に使う)。
+    stats: DecoderStats,
+    /// 非 UTF-8 バイトの扱い
+    non_utf8_policy: NonUtf8Policy,
+    /// `non_utf8_policy` により変換したヘッダーの (インデックス, 受信バイト列)
+    raw_values: Vec<(usize, Vec<u8>)>,
+    /// HTTP-version の文法 (RFC 9112 Section 2.3) に限定するか
+    strict_version: bool,
+    /// ワイヤーレベルのトレース
+    tracer: WireTracer,
 }
 
 impl Default for ResponseDecoder<NoCompression> {
@@ -82,8 +121,10 @@ impl ResponseDecoder<NoCompression> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits: DecoderLimits::default(),
             decoded_head: None,
@@ -92,6 +133,11 @@ impl ResponseDecoder<NoCompression> {
             decompressor: NoCompression::new(),
             request_method: None,
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
 
@@ -100,8 +146,10 @@ impl ResponseDecoder<NoCompression> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits,
             decoded_head: None,
@@ -110,8 +158,27 @@ impl ResponseDecoder<NoCompression> {
             decompressor: NoCompression::new(),
             request_method: None,
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
+
+    /// プロファイルを適用してデコーダーを作成
+    ///
+    /// ```rust
+    /// use shiguredo_http11::{DecoderProfile, ResponseDecoder};
+    ///
+    /// let decoder = ResponseDecoder::with_profile(DecoderProfile::strict());
+    /// assert!(decoder.strict_version());
+    /// ```
+    pub fn with_profile(profile: DecoderProfile) -> Self {
+        let mut decoder = Self::new();
+        decoder.set_profile(&profile);
+        decoder
+    }
 }
 
 impl<D: Decompressor> ResponseDecoder<D> {
@@ -120,8 +187,10 @@ impl<D: Decompressor> ResponseDecoder<D> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits: DecoderLimits::default(),
             decoded_head: None,
@@ -130,6 +199,11 @@ impl<D: Decompressor> ResponseDecoder<D> {
             decompressor,
             request_method: None,
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
 
@@ -138,8 +212,10 @@ impl<D: Decompressor> ResponseDecoder<D> {
         Self {
             buf: Vec::new(),
             phase: DecodePhase::StartLine,
-            start_line: None,
+            start_line: String::new(),
             headers: Vec::new(),
+            header_capacity: 0,
+            string_pool: StringPool::default(),
             body_decoder: BodyDecoder::new(),
             limits,
             decoded_head: None,
@@ -148,6 +224,11 @@ impl<D: Decompressor> ResponseDecoder<D> {
             decompressor,
             request_method: None,
             pending: 0,
+            stats: DecoderStats::default(),
+            non_utf8_policy: NonUtf8Policy::default(),
+            raw_values: Vec::new(),
+            strict_version: false,
+            tracer: WireTracer::default(),
         }
     }
 
@@ -196,6 +277,117 @@ impl<D: Decompressor> ResponseDecoder<D> {
         &self.limits
     }
 
+    /// 統計情報を取得
+    ///
+    /// デコーダー作成または `reset()` 以降の累計を返す。
+    pub fn stats(&self) -> &DecoderStats {
+        &self.stats
+    }
+
+    /// 非 UTF-8 バイトの扱いを設定する
+    ///
+    /// reason-phrase とヘッダー / トレーラーの field-valueに UTF-8 として不正なバイト列が含まれる場合の扱いを
+    /// 指定する。デフォルトは `NonUtf8Policy::Reject` でエラーにする。
+    /// status-code までの status-line と field-name は設定に関わらず常に検証する。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_non_utf8_policy(&mut self, policy: NonUtf8Policy) {
+        self.non_utf8_policy = policy;
+        self.body_decoder.set_non_utf8_policy(policy);
+    }
+
+    /// 非 UTF-8 バイトの扱いを取得
+    pub fn non_utf8_policy(&self) -> NonUtf8Policy {
+        self.non_utf8_policy
+    }
+
+    /// プロトコルバージョンを HTTP-version の文法に限定するか設定する
+    ///
+    /// `true` の場合、status-line の version が `"HTTP/" DIGIT "." DIGIT`
+    /// (RFC 9112 Section 2.3) に一致しなければエラーにする。
+    /// デフォルトは `false` で、RTSP 等のために `protocol "/" DIGIT+ "." DIGIT+` を受理する。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_strict_version(&mut self, strict: bool) {
+        self.strict_version = strict;
+    }
+
+    /// プロトコルバージョンを HTTP-version の文法に限定するかを取得
+    pub fn strict_version(&self) -> bool {
+        self.strict_version
+    }
+
+    /// プロファイルを適用する
+    ///
+    /// 制限設定、非 UTF-8 バイトの扱い、プロトコルバージョンの文法をまとめて設定する。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_profile(&mut self, profile: &DecoderProfile) {
+        self.limits = profile.limits.clone();
+        self.set_non_utf8_policy(profile.non_utf8_policy);
+        self.set_strict_version(profile.strict_version);
+    }
+
+    /// 現在の設定をプロファイルとして取得
+    pub fn profile(&self) -> DecoderProfile {
+        DecoderProfile {
+            limits: self.limits.clone(),
+            non_utf8_policy: self.non_utf8_policy,
+            strict_version: self.strict_version,
+        }
+    }
+
+    /// chunked ボディのフレーミングを記録するか設定する
+    ///
+    /// `true` の場合、chunk-size 行 (chunk-ext を含む)、チャンクデータ直後の CRLF、
+    /// last-chunk 行、trailer 行、終端の CRLF を受信したバイト列のまま記録する。
+    /// [`take_chunk_framing()`](Self::take_chunk_framing) で取り出したバイト列と
+    /// [`peek_body()`](Self::peek_body) のデータを受信順に書き出すと、
+    /// 元の chunked ボディとバイト単位で一致する。
+    /// プロキシがチャンク境界や chunk-ext を保ったまま転送するために使う。
+    /// デフォルトは `false`。`reset()` 後も設定は維持される。
+    ///
+    /// 記録はボディの状態機械が進んだ時点で行うため、`peek_body()` /
+    /// `consume_body()` / `progress()` と組み合わせて使う。
+    /// `read_body()` は複数のチャンクを 1 回でコピーするため、境界の復元には使えない。
+    /// `decode()` はメッセージの完了時に記録を破棄する。
+    pub fn set_preserve_chunk_framing(&mut self, preserve: bool) {
+        self.body_decoder.set_preserve_chunk_framing(preserve);
+    }
+
+    /// chunked ボディのフレーミングを記録するかを取得
+    pub fn preserve_chunk_framing(&self) -> bool {
+        self.body_decoder.preserve_chunk_framing()
+    }
+
+    /// 記録した chunked ボディのフレーミングを取り出す
+    ///
+    /// 前回の呼び出し以降に記録したバイト列を返し、内部の記録を空にする。
+    /// [`set_preserve_chunk_framing()`](Self::set_preserve_chunk_framing) が
+    /// `false` の場合は常に空を返す。
+    pub fn take_chunk_framing(&mut self) -> Vec<u8> {
+        self.body_decoder.take_chunk_framing()
+    }
+
+    /// 現在のチャンクの chunk-ext を取得
+    ///
+    /// 直近にパースした chunk-size 行の chunk-size 直後から CRLF の手前まで (BWS を含む)
+    /// を返す。chunk-ext がない場合や、フレーミングを記録していない場合は `None`。
+    pub fn chunk_ext(&self) -> Option<&[u8]> {
+        self.body_decoder.chunk_ext()
+    }
+
+    /// ワイヤーレベルのイベントを受け取る observer を設定する
+    ///
+    /// start-line / ヘッダー行 / chunk-size 行 / トレーラー行をパースするたびに
+    /// [`WireEvent`] を通知する。設定済みの observer は置き換える。
+    /// `reset()` 後も設定は維持される。
+    pub fn set_wire_observer(&mut self, observer: Box<dyn WireObserver>) {
+        self.tracer.set(observer);
+    }
+
+    /// 設定した observer を取り外して返す
+    pub fn take_wire_observer(&mut self) -> Option<Box<dyn WireObserver>> {
+        self.tracer.take()
+    }
+
     /// 既にメモリ上にあるバイト列を内部バッファに投入する
     ///
     /// `data` を `extend_from_slice` でコピーする (1 回の memcpy)。
@@ -220,7 +412,9 @@ impl<D: Decompressor> ResponseDecoder<D> {
             self.pending,
             self.limits.max_buffer_size,
             data,
-        )
+        )?;
+        self.stats.bytes_fed = self.stats.bytes_fed.saturating_add(data.len() as u64);
+        Ok(())
     }
 
     /// バッファにデータを追加 (制限チェックなし)
@@ -235,6 +429,7 @@ impl<D: Decompressor> ResponseDecoder<D> {
     /// 信頼済み入力またはテスト用途にのみ使用すること。
     pub fn feed_unchecked(&mut self, data: &[u8]) {
         buffer::feed_unchecked(&mut self.buf, self.pending, data);
+        self.stats.bytes_fed = self.stats.bytes_fed.saturating_add(data.len() as u64);
     }
 
     /// 内部バッファ末尾に `len` バイトの書き込み枠を確保し、その可変スライスを返す
@@ -268,7 +463,9 @@ impl<D: Decompressor> ResponseDecoder<D> {
     /// `len > pending` の場合、debug ビルドでは panic、release ビルドでは
     /// `pending` で飽和する。
     pub fn advance_buf(&mut self, len: usize) {
+        let len = len.min(self.pending);
         buffer::advance_buf(&mut self.buf, &mut self.pending, len);
+        self.stats.bytes_fed = self.stats.bytes_fed.saturating_add(len as u64);
     }
 
     /// 書き込み可能な残り容量を返す
@@ -285,19 +482,70 @@ impl<D: Decompressor> ResponseDecoder<D> {
         buffer::remaining(&self.buf, self.pending)
     }
 
+    /// 内部バッファ先頭の絶対バイトオフセット
+    fn buf_offset(&self) -> u64 {
+        self.stats.bytes_fed.saturating_sub(self.buf.len() as u64)
+    }
+
     /// デコーダーをリセット
+    ///
+    /// 受信済みの未処理データと統計情報も破棄する。確保済みの容量は解放しない。
     pub fn reset(&mut self) {
+        self.clear_for_next_message();
         self.buf.clear();
+        self.pending = 0;
+        self.stats = DecoderStats::default();
+    }
+
+    /// 次のメッセージのデコードに備えてメッセージ単位の状態をクリアする
+    ///
+    /// `reset()` と異なり、受信済みの未処理データと統計情報は保持する。
+    /// 確保済みのバッファ容量も解放しない。
+    ///
+    /// `set_request_method()` で設定したリクエストメソッドもクリアするため、
+    /// 次のレスポンスのメソッドは本メソッドの呼び出し後に設定すること。
+    /// ボディの途中で呼び出した場合、残りのボディは次のレスポンスの先頭として扱われる。
+    pub fn clear_for_next_message(&mut self) {
         self.phase = DecodePhase::StartLine;
-        self.start_line = None;
+        self.start_line.clear();
         self.headers.clear();
+        self.raw_values.clear();
         self.body_decoder.reset();
         self.decoded_head = None;
         self.decoded_body_kind = None;
         self.decoded_body.clear();
         self.decompressor.reset();
+        // request_method は元のリクエストごとに設定し直す前提でクリアする。
+        // クリアしないと Keep-Alive 接続で前回の CONNECT などが残り、次のレス
+        // ポンスを誤ってトンネル判定してしまう状態漏れバグになる。
         self.request_method = None;
-        self.pending = 0;
+    }
+
+    /// デコード済みの `ResponseHead` を返却し、記憶領域を次のレスポンスで再利用する
+    ///
+    /// Keep-Alive 接続で同じデコーダーを使い続ける場合、処理を終えたヘッダーを
+    /// 返却すると、status-line の要素とヘッダーの `String` および `Vec` を
+    /// 次のレスポンスのデコードで使い回す。返却は任意で、返却しなくても動作は変わらない。
+    /// 保持するのは直近に返却した 1 メッセージ分のみ。
+    pub fn recycle_head(&mut self, head: ResponseHead) {
+        let ResponseHead {
+            version,
+            reason_phrase,
+            mut headers,
+            ..
+        } = head;
+        self.string_pool
+            .refill([version, reason_phrase], &mut headers);
+        buffer::recycle_headers(&mut self.headers, headers);
+    }
+
+    /// `decode()` で取得した `Response` を返却し、記憶領域を次のレスポンスで再利用する
+    ///
+    /// `recycle_head()` に加えて、ボディの `Vec` も次の `decode()` で使い回す。
+    pub fn recycle_response(&mut self, response: Response) {
+        let (head, body) = response.into_head_and_body();
+        self.recycle_head(head);
+        buffer::recycle_body(&mut self.decoded_body, body);
     }
 
     /// 接続終了を通知 (close-delimited ボディ用)
@@ -383,11 +631,7 @@ impl<D: Decompressor> ResponseDecoder<D> {
         // HTTP/1.2 が将来定義された場合は別途検討する (将来変更される可能性がある)。
         // item 1 で HEAD/1xx/204/304 は既に返っているため、このチェックに到達
         // するのはボディが存在しうるレスポンスのみ。
-        let version = self
-            .start_line
-            .as_ref()
-            .and_then(|sl| sl.split(' ').next())
-            .unwrap_or("");
+        let version = self.start_line.split(' ').next().unwrap_or("");
         if version != "HTTP/1.1"
             && self
                 .headers
@@ -425,6 +669,69 @@ impl<D: Decompressor> ResponseDecoder<D> {
         Ok(BodyKind::CloseDelimited)
     }
 
+    /// ヘッダーセクション終端の空行を受信した後の検証と `ResponseHead` の構築
+    ///
+    /// ボディ種別を決定し (RFC 9112 Section 6.3)、ボディフェーズへ遷移する。
+    fn finish_headers(&mut self) -> Result<(ResponseHead, BodyKind), Error> {
+        // ステータスコードを取得
+        if self.start_line.is_empty() {
+            return Err(Error::InvalidData("missing status line".to_string()));
+        }
+        let status_code = self.start_line.split(' ').nth(1).unwrap_or_default();
+        let status_code: u16 = status_code.parse().map_err(|_| {
+            Error::InvalidData(alloc::format!("invalid status code: {status_code}"))
+        })?;
+
+        let body_kind = self.determine_body_kind(status_code)?;
+
+        // ヘッダー完了、ボディフェーズに遷移
+        match body_kind {
+            BodyKind::ContentLength(len) => {
+                if len > 0 {
+                    self.phase = DecodePhase::BodyContentLength { remaining: len };
+                } else {
+                    self.phase = DecodePhase::Complete;
+                }
+            }
+            BodyKind::Chunked => {
+                self.phase = DecodePhase::BodyChunkedSize;
+            }
+            BodyKind::CloseDelimited => {
+                self.phase = DecodePhase::BodyCloseDelimited;
+            }
+            BodyKind::None => {
+                self.phase = DecodePhase::Complete;
+            }
+            BodyKind::Tunnel => {
+                self.phase = DecodePhase::Tunnel;
+            }
+        }
+
+        // RFC 9110 Section 6.5.1 ホワイトリスト方式 trailer 受理用に、
+        // ヘッダーから `Trailer:` フィールドで申告された名前を抽出して
+        // BodyDecoder に渡す。chunked 以外の body kind では trailer は
+        // 来ないが、BodyDecoder は body kind を問わず参照する。
+        let declared_trailers = collect_declared_trailers(&self.headers);
+        self.body_decoder.set_declared_trailers(declared_trailers);
+
+        // ResponseHead を構築
+        // status-line はバリデート済みのため version と status-code は必ず存在する
+        let mut parts = self.start_line.splitn(3, ' ');
+        let version = self.string_pool.take(parts.next().unwrap_or_default());
+        let reason_phrase = self.string_pool.take(parts.nth(1).unwrap_or_default());
+        self.header_capacity = self.headers.len();
+        let head = ResponseHead::from_validated_parts(
+            version,
+            status_code,
+            reason_phrase,
+            core::mem::take(&mut self.headers),
+            core::mem::take(&mut self.raw_values),
+        );
+        self.start_line.clear();
+
+        Ok((head, body_kind))
+    }
+
     /// ヘッダーをデコード
     ///
     /// ヘッダーが完了したら `Some((ResponseHead, BodyKind))` を返す
@@ -439,61 +746,47 @@ impl<D: Decompressor> ResponseDecoder<D> {
             match &self.phase {
                 DecodePhase::StartLine => {
                     if let Some(pos) = find_line(&self.buf) {
-                        let line = String::from_utf8(self.buf[..pos].to_vec()).map_err(|e| {
-                            Error::InvalidData(alloc::format!("invalid UTF-8: {e}"))
+                        let offset = self.buf_offset();
+                        let line_bytes = &self.buf[..pos];
+                        let (line, _) = decode_line(
+                            line_bytes,
+                            reason_phrase_start(line_bytes),
+                            self.non_utf8_policy,
+                        )
+                        .map_err(|e| {
+                            Error::syntax(
+                                ParsePhase::StartLine,
+                                offset,
+                                &self.buf[..pos],
+                                alloc::format!("invalid UTF-8: {e}"),
+                            )
                         })?;
-                        self.buf.drain(..pos + 2);
-
-                        // CR/LF チェック (埋め込まれた改行を拒否)
-                        if line.contains('\r') || line.contains('\n') {
-                            return Err(Error::InvalidData(
-                                "invalid status line: contains CR/LF".to_string(),
-                            ));
-                        }
-
-                        // Parse: VERSION SP STATUS-CODE SP REASON-PHRASE CRLF
-                        let parts: Vec<&str> = line.splitn(3, ' ').collect();
-                        if parts.len() < 2 {
-                            return Err(Error::InvalidData(alloc::format!(
-                                "invalid status line: {}",
-                                line
-                            )));
-                        }
-
-                        // プロトコルバージョンの検証
-                        if !is_valid_protocol_version(parts[0]) {
-                            return Err(Error::InvalidData(
-                                "invalid status line: invalid protocol version".to_string(),
-                            ));
-                        }
-
-                        // ステータスコードの検証 (RFC 9110 Section 15)
-                        let status_code: u16 = parts[1].parse().map_err(|_| {
-                            Error::InvalidData(alloc::format!(
-                                "invalid status line: invalid status code: {}",
-                                parts[1]
-                            ))
+                        self.stats.header_bytes += pos as u64 + 2;
+                        validate_status_line(&line).map_err(|e| {
+                            e.with_context(ParsePhase::StartLine, offset, line.as_bytes())
                         })?;
-                        if !is_valid_status_code(status_code) {
-                            return Err(Error::InvalidData(alloc::format!(
-                                "invalid status line: status code out of range: {}",
-                                status_code
-                            )));
-                        }
-
-                        // reason-phrase の検証 (RFC 9112 Section 4)
-                        // 空文字列は status-line ABNF における reason-phrase absent
-                        // (`HTTP/1.1 200 \r\n`) として許容する。
-                        if let Some(reason) = parts.get(2)
-                            && !reason.is_empty()
-                            && !is_valid_reason_phrase(reason)
+                        if self.strict_version
+                            && !is_valid_http_version(line.split(' ').next().unwrap_or(""))
                         {
                             return Err(Error::InvalidData(
-                                "invalid status line: invalid reason-phrase".to_string(),
+                                "invalid status line: invalid HTTP-version (RFC 9112 Section 2.3)"
+                                    .to_string(),
+                            )
+                            .with_context(
+                                ParsePhase::StartLine,
+                                offset,
+                                line.as_bytes(),
                             ));
                         }
 
-                        self.start_line = Some(line);
+                        self.tracer.emit(WireEvent::StartLine {
+                            offset,
+                            raw: &self.buf[..pos],
+                        });
+                        self.start_line.push_str(&line);
+                        self.buf.drain(..pos + 2);
+
+                        self.headers.reserve(self.header_capacity);
                         self.phase = DecodePhase::Headers;
                     } else {
                         return Ok(None);
@@ -501,67 +794,18 @@ impl<D: Decompressor> ResponseDecoder<D> {
                 }
                 DecodePhase::Headers => {
                     if let Some(pos) = find_line(&self.buf) {
+                        let offset = self.buf_offset();
                         if pos == 0 {
                             // 空行 — ヘッダーセクション終端
                             self.buf.drain(..2);
-
-                            // ステータスコードを取得
-                            let start_line = self.start_line.as_ref().ok_or_else(|| {
-                                Error::InvalidData("missing status line".to_string())
-                            })?;
-                            let parts: Vec<&str> = start_line.splitn(3, ' ').collect();
-                            let status_code: u16 = parts[1].parse().map_err(|_| {
-                                Error::InvalidData(alloc::format!(
-                                    "invalid status code: {}",
-                                    parts[1]
-                                ))
-                            })?;
-
-                            let body_kind = self.determine_body_kind(status_code)?;
-
-                            // ヘッダー完了、ボディフェーズに遷移
-                            match body_kind {
-                                BodyKind::ContentLength(len) => {
-                                    if len > 0 {
-                                        self.phase =
-                                            DecodePhase::BodyContentLength { remaining: len };
-                                    } else {
-                                        self.phase = DecodePhase::Complete;
-                                    }
-                                }
-                                BodyKind::Chunked => {
-                                    self.phase = DecodePhase::BodyChunkedSize;
-                                }
-                                BodyKind::CloseDelimited => {
-                                    self.phase = DecodePhase::BodyCloseDelimited;
-                                }
-                                BodyKind::None => {
-                                    self.phase = DecodePhase::Complete;
-                                }
-                                BodyKind::Tunnel => {
-                                    self.phase = DecodePhase::Tunnel;
-                                }
-                            }
-
-                            // RFC 9110 Section 6.5.1 ホワイトリスト方式 trailer 受理用に、
-                            // ヘッダーから `Trailer:` フィールドで申告された名前を抽出して
-                            // BodyDecoder に渡す。chunked 以外の body kind では trailer は
-                            // 来ないが、BodyDecoder は body kind を問わず参照する。
-                            let declared_trailers = collect_declared_trailers(&self.headers);
-                            self.body_decoder.set_declared_trailers(declared_trailers);
-
-                            // ResponseHead を構築
-                            let start_line = self.start_line.take().unwrap();
-                            let parts: Vec<&str> = start_line.splitn(3, ' ').collect();
-
-                            let head = ResponseHead::from_validated_parts(
-                                parts[0].to_string(),
-                                status_code,
-                                parts.get(2).unwrap_or(&"").to_string(),
-                                core::mem::take(&mut self.headers),
-                            );
-
-                            return Ok(Some((head, body_kind)));
+                            self.stats.header_bytes += 2;
+                            // ヘッダーセクション全体に対する検証エラーは特定の行に
+                            // 紐付かないため、終端の空行の位置で報告する
+                            let decoded = self
+                                .finish_headers()
+                                .map_err(|e| e.with_context(ParsePhase::Header, offset, &[]))?;
+                            self.stats.messages += 1;
+                            return Ok(Some(decoded));
                         } else {
                             // ヘッダー行サイズ上限の検査
                             if pos > self.limits.max_header_line_size {
@@ -579,14 +823,45 @@ impl<D: Decompressor> ResponseDecoder<D> {
                                 });
                             }
 
-                            let line =
-                                String::from_utf8(self.buf[..pos].to_vec()).map_err(|e| {
-                                    Error::InvalidData(alloc::format!("invalid UTF-8: {e}"))
-                                })?;
-                            self.buf.drain(..pos + 2);
+                            let line_bytes = &self.buf[..pos];
+                            let (line, converted) = decode_line(
+                                line_bytes,
+                                field_name_len(line_bytes),
+                                self.non_utf8_policy,
+                            )
+                            .map_err(|e| {
+                                Error::syntax(
+                                    ParsePhase::Header,
+                                    offset,
+                                    line_bytes,
+                                    alloc::format!("invalid UTF-8: {e}"),
+                                )
+                            })?;
+                            // 変換した場合のみ field-value の受信バイト列を保持する
+                            let raw_value = converted.then(|| {
+                                let value_start = (field_name_len(line_bytes) + 1).min(pos);
+                                trim_ows_bytes(&line_bytes[value_start..]).to_vec()
+                            });
+                            self.stats.header_bytes += pos as u64 + 2;
 
-                            let (name, value) = parse_header_line(&line)?;
-                            self.headers.push((name, value));
+                            let (name, value) = parse_header_line(&line).map_err(|e| {
+                                e.with_context(ParsePhase::Header, offset, line.as_bytes())
+                            })?;
+                            self.tracer.emit(WireEvent::Header {
+                                offset,
+                                name,
+                                value,
+                                raw: &self.buf[..pos],
+                            });
+                            let header = (
+                                self.string_pool.take_name(name),
+                                self.string_pool.take(value),
+                            );
+                            self.buf.drain(..pos + 2);
+                            if let Some(raw_value) = raw_value {
+                                self.raw_values.push((self.headers.len(), raw_value));
+                            }
+                            self.headers.push(header);
                         }
                     } else {
                         return Ok(None);
@@ -594,16 +869,7 @@ impl<D: Decompressor> ResponseDecoder<D> {
                 }
                 DecodePhase::Complete => {
                     // 完了状態から次のメッセージへ遷移
-                    self.phase = DecodePhase::StartLine;
-                    self.start_line = None;
-                    self.headers.clear();
-                    self.body_decoder.reset();
-                    self.decompressor.reset();
-                    // request_method は元のリクエストごとに設定し直す前提で
-                    // ここでクリアする。クリアしないと Keep-Alive 接続で前回の
-                    // CONNECT などが残り、次のレスポンスを誤ってトンネル判定
-                    // してしまう状態漏れバグになる。
-                    self.request_method = None;
+                    self.clear_for_next_message();
                     continue;
                 }
                 DecodePhase::Tunnel => {
@@ -711,8 +977,52 @@ impl<D: Decompressor> ResponseDecoder<D> {
                 "consume_body(0) is not allowed, use progress() instead".to_string(),
             ));
         }
-        self.body_decoder
-            .consume_body(&mut self.buf, &mut self.phase, len, &self.limits)
+        self.body_decoder.consume_body(
+            &mut self.buf,
+            &mut self.phase,
+            len,
+            &self.limits,
+            &mut self.stats,
+            &mut self.tracer,
+        )
+    }
+
+    /// ボディデータを `dst` に直接コピーして消費する
+    ///
+    /// `decode_headers()` 成功後に呼ぶ。
+    /// `peek_body()` + `consume_body()` と異なり、呼び出し側のバッファへ 1 回の
+    /// コピーで書き込む。`dst` が埋まるか、追加データが必要になるか、ボディが
+    /// 完了するまで状態機械 (チャンクサイズ行やトレーラーの処理を含む) を進める。
+    /// `dst` が空の場合はエラーを返す。
+    ///
+    /// 展開は行わない。Content-Encoding を展開する場合は
+    /// `peek_body_decompressed()` を使う。
+    ///
+    /// # 使い方
+    ///
+    /// ```ignore
+    /// let mut dst = [0u8; 8192];
+    /// loop {
+    ///     match decoder.read_body(&mut dst)? {
+    ///         BodyRead::Data(n) => process(&dst[..n]),
+    ///         BodyRead::NeedData => break, // ネットワーク I/O に戻る
+    ///         BodyRead::Complete { len, trailers } => {
+    ///             process(&dst[..len]);
+    ///             break;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn read_body(&mut self, dst: &mut [u8]) -> Result<BodyRead, Error> {
+        debug_assert!(self.pending == 0, "read_body called with pending mut_buf");
+        self.body_decoder.read_body(
+            &mut self.buf,
+            &mut self.phase,
+            dst,
+            &self.limits,
+            &mut self.stats,
+            &mut self.tracer,
+        )
     }
 
     /// 状態機械を進める (ボディデータは消費しない)
@@ -729,8 +1039,14 @@ impl<D: Decompressor> ResponseDecoder<D> {
     /// で直接 phase を確認している。
     pub fn progress(&mut self) -> Result<BodyProgress, Error> {
         debug_assert!(self.pending == 0, "progress called with pending mut_buf");
-        self.body_decoder
-            .consume_body(&mut self.buf, &mut self.phase, 0, &self.limits)
+        self.body_decoder.consume_body(
+            &mut self.buf,
+            &mut self.phase,
+            0,
+            &self.limits,
+            &mut self.stats,
+            &mut self.tracer,
+        )
     }
 
     /// レスポンス全体を一括でデコード
@@ -844,15 +1160,7 @@ impl<D: Decompressor> ResponseDecoder<D> {
         };
 
         // Keep-Alive 対応: 次のレスポンスのために状態をリセット
-        self.phase = DecodePhase::StartLine;
-        self.decoded_body_kind = None;
-        self.decoded_body.clear();
-        self.body_decoder.reset();
-        self.decompressor.reset();
-        // request_method は元のリクエストごとに設定し直す前提でクリアする。
-        // クリアしないと Keep-Alive 接続で前回の CONNECT などが残り、次のレス
-        // ポンスを誤ってトンネル判定してしまう状態漏れバグになる。
-        self.request_method = None;
+        self.clear_for_next_message();
 
         Ok(Some(Response::from_raw_parts(
             head.version,
@@ -863,3 +1171,70 @@ impl<D: Decompressor> ResponseDecoder<D> {
         )))
     }
 }
+
+/// status-line のうち reason-phrase の開始位置
+///
+/// 2 つ目の SP の直後を返す。SP が 2 つ未満の場合は行の長さを返す。
+fn reason_phrase_start(line: &[u8]) -> usize {
+    line.iter()
+        .enumerate()
+        .filter(|&(_, &b)| b == b' ')
+        .nth(1)
+        .map_or(line.len(), |(i, _)| i + 1)
+}
+
+/// ステータス行を検証する (RFC 9112 Section 4)
+///
+/// status-line = HTTP-version SP status-code SP [ reason-phrase ]
+fn validate_status_line(line: &str) -> Result<(), Error> {
+    // CR/LF チェック (埋め込まれた改行を拒否)
+    if line.contains('\r') || line.contains('\n') {
+        return Err(Error::InvalidData(
+            "invalid status line: contains CR/LF".to_string(),
+        ));
+    }
+
+    // Parse: VERSION SP STATUS-CODE SP REASON-PHRASE CRLF
+    let mut parts = line.splitn(3, ' ');
+    let version = parts.next().unwrap_or_default();
+    let Some(status_code) = parts.next() else {
+        return Err(Error::InvalidData(
+            "invalid status line: missing status code".to_string(),
+        ));
+    };
+
+    // プロトコルバージョンの検証
+    if !is_valid_protocol_version(version) {
+        return Err(Error::InvalidData(
+            "invalid status line: invalid protocol version".to_string(),
+        ));
+    }
+
+    // ステータスコードの検証 (RFC 9110 Section 15)
+    let status_code: u16 = status_code.parse().map_err(|_| {
+        Error::InvalidData(alloc::format!(
+            "invalid status line: invalid status code: {}",
+            status_code
+        ))
+    })?;
+    if !is_valid_status_code(status_code) {
+        return Err(Error::InvalidData(alloc::format!(
+            "invalid status line: status code out of range: {}",
+            status_code
+        )));
+    }
+
+    // reason-phrase の検証 (RFC 9112 Section 4)
+    // 空文字列は status-line ABNF における reason-phrase absent
+    // (`HTTP/1.1 200 \r\n`) として許容する。
+    if let Some(reason) = parts.next()
+        && !reason.is_empty()
+        && !is_valid_reason_phrase(reason)
+    {
+        return Err(Error::InvalidData(
+            "invalid status line: invalid reason-phrase".to_string(),
+        ));
+    }
+
+    Ok(())
+}
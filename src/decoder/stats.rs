@@ -0,0 +1,29 @@
+//! デコーダーの統計情報
+
+/// デコーダーの統計情報
+///
+/// `RequestDecoder::stats` / `ResponseDecoder::stats` で取得する。
+/// 値はデコーダー作成または `reset()` 以降の累計で、Keep-Alive 接続で
+/// 複数メッセージをデコードしても引き継がれる。
+/// プロキシでの転送量の計上や、接続単位のクォータ制御に使う。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DecoderStats {
+    /// `feed` / `feed_unchecked` / `advance_buf` で投入された確定済みバイト数
+    pub bytes_fed: u64,
+    /// start-line とヘッダーセクション (終端の空行を含む) のバイト数
+    ///
+    /// chunked のトレーラーセクションは含まない。
+    pub header_bytes: u64,
+    /// ボディとして消費されたバイト数
+    ///
+    /// chunked の場合はチャンクデータのみを数え、チャンクサイズ行や CRLF などの
+    /// フレーミングは含まない。展開前のバイト数である。
+    pub body_bytes: u64,
+    /// ヘッダーのデコードが完了したメッセージ数
+    pub messages: u64,
+    /// 受信したチャンク数 (サイズ 0 の last-chunk は含まない)
+    pub chunks: u64,
+    /// 受信したトレーラーフィールド数
+    pub trailers: u64,
+}
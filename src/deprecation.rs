@@ -0,0 +1,538 @@
+//! Deprecation / Sunset / Warning ヘッダー (RFC 9745 / RFC 8594 / RFC 7234)
+//!
+//! ## 概要
+//!
+//! API のライフサイクルを通知する Deprecation / Sunset ヘッダーと、
+//! 従来の Warning ヘッダーのパースと生成を提供します。
+//!
+//! - Deprecation (RFC 9745): リソースが非推奨になった (なる) 日時
+//! - Sunset (RFC 8594): リソースが応答しなくなる予定の日時
+//! - Warning (RFC 7234 Section 5.5): メッセージの状態や変換に関する追加情報
+//!
+//! Warning は RFC 9111 で廃止されたが、既存のサーバーやキャッシュが
+//! 送信するため受信側で扱えるようにしている。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Deprecation = sf-date              ; @1688169599 (RFC 9745)
+//! Sunset      = HTTP-date
+//!
+//! Warning       = 1#warning-value
+//! warning-value = warn-code SP warn-agent SP warn-text [ SP warn-date ]
+//! warn-code     = 3DIGIT
+//! warn-agent    = ( uri-host [ ":" port ] ) / pseudonym
+//! warn-text     = quoted-string
+//! warn-date     = DQUOTE HTTP-date DQUOTE
+//! ```
+//!
+//! 時刻は UNIX 時間 (秒) で呼び出し側が渡す。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::deprecation::{Deprecation, Sunset, Warning, WarningValue};
+//! use shiguredo_http11::{Response, StatusCode};
+//!
+//! let deprecation = Deprecation::parse("@1688169599", 2026).unwrap();
+//! assert!(deprecation.is_deprecated_at(1_700_000_000));
+//!
+//! let sunset = Sunset::parse("Wed, 11 Nov 2026 11:11:11 GMT", 2026).unwrap();
+//! assert!(!sunset.is_past(1_700_000_000));
+//!
+//! let warning = Warning::parse("110 cache.example.com \"Response is Stale\"", 2026).unwrap();
+//! assert_eq!(warning.values()[0].code(), WarningValue::RESPONSE_IS_STALE);
+//! assert_eq!(warning.values()[0].text(), "Response is Stale");
+//!
+//! // 生成
+//! let response = Response::with_status(StatusCode::OK)
+//!     .deprecation(&deprecation)
+//!     .unwrap()
+//!     .sunset(&sunset)
+//!     .unwrap();
+//! assert_eq!(response.get_header("Deprecation"), Some("@1688169599"));
+//! assert_eq!(response.get_header("Sunset"), Some("Wed, 11 Nov 2026 11:11:11 GMT"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::date::HttpDate;
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::sfv::{self, Item, SfvError};
+use crate::validate::{QuotedStringError, escape_quotes, parse_quoted_string};
+
+/// Deprecation / Sunset / Warning パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeprecationError {
+    /// 空の入力
+    Empty,
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正な日時
+    InvalidDate,
+    /// 不正な warn-code
+    InvalidWarnCode,
+    /// 不正な warn-agent
+    InvalidWarnAgent,
+    /// 不正な warn-text
+    InvalidWarnText,
+    /// quoted-string の閉じ引用符がない
+    UnterminatedQuote,
+}
+
+impl fmt::Display for DeprecationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeprecationError::Empty => write!(f, "empty header value"),
+            DeprecationError::InvalidFormat => write!(f, "invalid header format"),
+            DeprecationError::InvalidDate => write!(f, "invalid date"),
+            DeprecationError::InvalidWarnCode => write!(f, "invalid warn-code"),
+            DeprecationError::InvalidWarnAgent => write!(f, "invalid warn-agent"),
+            DeprecationError::InvalidWarnText => write!(f, "invalid warn-text"),
+            DeprecationError::UnterminatedQuote => write!(f, "unterminated quoted-string"),
+        }
+    }
+}
+
+impl core::error::Error for DeprecationError {}
+
+impl From<QuotedStringError> for DeprecationError {
+    fn from(e: QuotedStringError) -> Self {
+        match e {
+            QuotedStringError::InvalidQdtext | QuotedStringError::InvalidQuotedPair => {
+                DeprecationError::InvalidWarnText
+            }
+            QuotedStringError::Unterminated => DeprecationError::UnterminatedQuote,
+        }
+    }
+}
+
+/// Deprecation ヘッダー (RFC 9745)
+///
+/// 非推奨になった (なる) 日時を UNIX 時間で保持する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deprecation {
+    timestamp: u64,
+}
+
+impl Deprecation {
+    /// UNIX 時間から作成
+    ///
+    /// sf-date は 15 桁までのため、それを超える場合は `DeprecationError::InvalidDate` を返す。
+    pub fn new(timestamp: u64) -> Result<Self, DeprecationError> {
+        if timestamp > sfv::MAX_INTEGER as u64 {
+            return Err(DeprecationError::InvalidDate);
+        }
+        Ok(Deprecation { timestamp })
+    }
+
+    /// HTTP-date から作成
+    pub fn from_date(date: &HttpDate) -> Self {
+        Deprecation {
+            timestamp: date.unix_seconds(),
+        }
+    }
+
+    /// Deprecation ヘッダーをパース
+    ///
+    /// RFC 9745 の sf-date (`@1688169599`) に加えて、
+    /// ドラフト段階で使われていた HTTP-date 形式も受け付ける。
+    /// sf-date のパラメーターは無視する。1970 年より前の日時はエラーにする。
+    ///
+    /// `reference_year` は RFC 850 形式の 2 桁年を解決するために使う現在年。
+    pub fn parse(input: &str, reference_year: u16) -> Result<Self, DeprecationError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(DeprecationError::Empty);
+        }
+
+        if input.starts_with('@') {
+            let item = Item::parse(input).map_err(|e| match e {
+                SfvError::InvalidDate => DeprecationError::InvalidDate,
+                _ => DeprecationError::InvalidFormat,
+            })?;
+            let timestamp = item
+                .bare_item()
+                .as_date()
+                .and_then(|timestamp| u64::try_from(timestamp).ok())
+                .ok_or(DeprecationError::InvalidDate)?;
+            return Ok(Deprecation { timestamp });
+        }
+
+        HttpDate::parse_any(input, reference_year)
+            .map(|date| Self::from_date(&date))
+            .map_err(|_| DeprecationError::InvalidFormat)
+    }
+
+    /// レスポンスの Deprecation ヘッダーを取得
+    pub fn from_response<H: HttpHead>(
+        response: &H,
+        reference_year: u16,
+    ) -> Result<Option<Self>, DeprecationError> {
+        response
+            .get_header("Deprecation")
+            .map(|value| Self::parse(value, reference_year))
+            .transpose()
+    }
+
+    /// 非推奨になる UNIX 時間
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// 非推奨になる日時を HTTP-date で取得
+    ///
+    /// 9999 年を超える場合は `None` を返す。
+    pub fn date(&self) -> Option<HttpDate> {
+        HttpDate::from_unix_seconds(self.timestamp).ok()
+    }
+
+    /// `now` (UNIX 時間) の時点で非推奨になっているか
+    pub fn is_deprecated_at(&self, now: u64) -> bool {
+        now >= self.timestamp
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}", self.timestamp)
+    }
+}
+
+/// Sunset ヘッダー (RFC 8594)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Sunset {
+    date: HttpDate,
+}
+
+impl Sunset {
+    /// HTTP-date から作成
+    pub fn new(date: HttpDate) -> Self {
+        Sunset { date }
+    }
+
+    /// Sunset ヘッダーをパース
+    ///
+    /// `reference_year` は RFC 850 形式の 2 桁年を解決するために使う現在年。
+    pub fn parse(input: &str, reference_year: u16) -> Result<Self, DeprecationError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(DeprecationError::Empty);
+        }
+        HttpDate::parse_any(input, reference_year)
+            .map(Sunset::new)
+            .map_err(|_| DeprecationError::InvalidDate)
+    }
+
+    /// レスポンスの Sunset ヘッダーを取得
+    pub fn from_response<H: HttpHead>(
+        response: &H,
+        reference_year: u16,
+    ) -> Result<Option<Self>, DeprecationError> {
+        response
+            .get_header("Sunset")
+            .map(|value| Self::parse(value, reference_year))
+            .transpose()
+    }
+
+    /// 応答しなくなる予定の日時
+    pub fn date(&self) -> &HttpDate {
+        &self.date
+    }
+
+    /// 応答しなくなる予定の UNIX 時間
+    pub fn unix_seconds(&self) -> u64 {
+        self.date.unix_seconds()
+    }
+
+    /// `now` (UNIX 時間) の時点で Sunset の日時を過ぎているか
+    pub fn is_past(&self, now: u64) -> bool {
+        now >= self.unix_seconds()
+    }
+}
+
+impl fmt::Display for Sunset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date)
+    }
+}
+
+/// Warning の warning-value (RFC 7234 Section 5.5)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningValue {
+    code: u16,
+    agent: String,
+    text: String,
+    date: Option<HttpDate>,
+}
+
+impl WarningValue {
+    /// 110 Response is Stale
+    pub const RESPONSE_IS_STALE: u16 = 110;
+    /// 111 Revalidation Failed
+    pub const REVALIDATION_FAILED: u16 = 111;
+    /// 112 Disconnected Operation
+    pub const DISCONNECTED_OPERATION: u16 = 112;
+    /// 113 Heuristic Expiration
+    pub const HEURISTIC_EXPIRATION: u16 = 113;
+    /// 199 Miscellaneous Warning
+    pub const MISCELLANEOUS_WARNING: u16 = 199;
+    /// 214 Transformation Applied
+    pub const TRANSFORMATION_APPLIED: u16 = 214;
+    /// 299 Miscellaneous Persistent Warning
+    pub const MISCELLANEOUS_PERSISTENT_WARNING: u16 = 299;
+
+    /// warn-code / warn-agent / warn-text から作成
+    ///
+    /// warn-agent が不明な場合は `-` を指定する。
+    pub fn new(code: u16, agent: &str, text: &str) -> Result<Self, DeprecationError> {
+        if !(100..=999).contains(&code) {
+            return Err(DeprecationError::InvalidWarnCode);
+        }
+        if !is_valid_agent(agent) {
+            return Err(DeprecationError::InvalidWarnAgent);
+        }
+        if text.chars().any(char::is_control) {
+            return Err(DeprecationError::InvalidWarnText);
+        }
+        Ok(WarningValue {
+            code,
+            agent: agent.to_string(),
+            text: text.to_string(),
+            date: None,
+        })
+    }
+
+    /// warn-date を設定 (ビルダーパターン)
+    pub fn with_date(mut self, date: HttpDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// warn-code
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    /// warn-agent
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+
+    /// warn-text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// warn-date
+    pub fn date(&self) -> Option<&HttpDate> {
+        self.date.as_ref()
+    }
+
+    /// 検証後も残す警告 (2xx) かどうか
+    ///
+    /// RFC 7234 Section 5.5: 1xx の警告は検証に成功したら削除する。
+    pub fn is_persistent(&self) -> bool {
+        (200..300).contains(&self.code)
+    }
+
+    /// warn-date がレスポンスの Date と一致するか
+    ///
+    /// warn-date がない場合は常に一致とみなす。
+    pub fn matches_date(&self, date: &HttpDate) -> bool {
+        self.date.as_ref().is_none_or(|d| d == date)
+    }
+}
+
+impl fmt::Display for WarningValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} \"{}\"",
+            self.code,
+            self.agent,
+            escape_quotes(&self.text)
+        )?;
+        if let Some(date) = &self.date {
+            write!(f, " \"{}\"", date)?;
+        }
+        Ok(())
+    }
+}
+
+/// Warning ヘッダー (RFC 7234 Section 5.5)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Warning {
+    values: Vec<WarningValue>,
+}
+
+impl Warning {
+    /// 空の Warning を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Warning ヘッダーをパース
+    ///
+    /// 空の要素は無視する。`reference_year` は warn-date の
+    /// RFC 850 形式の 2 桁年を解決するために使う現在年。
+    pub fn parse(input: &str, reference_year: u16) -> Result<Self, DeprecationError> {
+        let mut values = Vec::new();
+        let mut rest = input;
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ',']);
+            if rest.is_empty() {
+                break;
+            }
+            let (value, remaining) = parse_warning_value(rest, reference_year)?;
+            rest = remaining.trim_start_matches([' ', '\t']);
+            if !rest.is_empty() && !rest.starts_with(',') {
+                return Err(DeprecationError::InvalidFormat);
+            }
+            values.push(value);
+        }
+        if values.is_empty() {
+            return Err(DeprecationError::Empty);
+        }
+        Ok(Warning { values })
+    }
+
+    /// レスポンスの Warning ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_response<H: HttpHead>(
+        response: &H,
+        reference_year: u16,
+    ) -> Result<Option<Self>, DeprecationError> {
+        let values = response.get_headers("Warning");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", "), reference_year).map(Some)
+    }
+
+    /// warning-value を追加 (ビルダーパターン)
+    pub fn with_value(mut self, value: WarningValue) -> Self {
+        self.values.push(value);
+        self
+    }
+
+    /// warning-value の一覧
+    pub fn values(&self) -> &[WarningValue] {
+        &self.values
+    }
+
+    /// warning-value がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// warn-date がレスポンスの Date と異なる warning-value を取り除く
+    ///
+    /// RFC 7234 Section 5.5: warn-date が Date と異なる warning-value は
+    /// 保存や転送の前に削除しなければならない。
+    pub fn without_mismatched_dates(mut self, date: &HttpDate) -> Self {
+        self.values.retain(|value| value.matches_date(date));
+        self
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl Response {
+    /// Deprecation ヘッダーを設定 (ビルダーパターン)
+    ///
+    /// 既存の Deprecation は置き換える。
+    pub fn deprecation(mut self, deprecation: &Deprecation) -> Result<Self, EncodeError> {
+        self.set_header("Deprecation", deprecation.to_string())?;
+        Ok(self)
+    }
+
+    /// Sunset ヘッダーを設定 (ビルダーパターン)
+    ///
+    /// 既存の Sunset は置き換える。
+    pub fn sunset(mut self, sunset: &Sunset) -> Result<Self, EncodeError> {
+        self.set_header("Sunset", sunset.to_string())?;
+        Ok(self)
+    }
+
+    /// Warning ヘッダーを追加 (ビルダーパターン)
+    pub fn warning(mut self, value: &WarningValue) -> Result<Self, EncodeError> {
+        self.add_header("Warning", value.to_string())?;
+        Ok(self)
+    }
+}
+
+/// warning-value をパースし、残りの入力を返す
+fn parse_warning_value(
+    input: &str,
+    reference_year: u16,
+) -> Result<(WarningValue, &str), DeprecationError> {
+    let code = input
+        .get(..3)
+        .filter(|code| code.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|code| code.parse::<u16>().ok())
+        .filter(|code| *code >= 100)
+        .ok_or(DeprecationError::InvalidWarnCode)?;
+    let rest = input[3..]
+        .strip_prefix(' ')
+        .ok_or(DeprecationError::InvalidFormat)?;
+
+    let agent_end = rest.find(' ').ok_or(DeprecationError::InvalidFormat)?;
+    let agent = &rest[..agent_end];
+    if !is_valid_agent(agent) {
+        return Err(DeprecationError::InvalidWarnAgent);
+    }
+
+    let rest = rest[agent_end + 1..]
+        .strip_prefix('"')
+        .ok_or(DeprecationError::InvalidWarnText)?;
+    let (text, mut rest) = parse_quoted_string(rest)?;
+
+    let mut date = None;
+    if let Some(after) = rest.strip_prefix(' ')
+        && let Some(after) = after.strip_prefix('"')
+    {
+        let end = after.find('"').ok_or(DeprecationError::UnterminatedQuote)?;
+        let parsed = HttpDate::parse_any(&after[..end], reference_year)
+            .map_err(|_| DeprecationError::InvalidDate)?;
+        date = Some(parsed);
+        rest = &after[end + 1..];
+    }
+
+    Ok((
+        WarningValue {
+            code,
+            agent: agent.to_string(),
+            text,
+            date,
+        },
+        rest,
+    ))
+}
+
+/// warn-agent として有効か
+///
+/// uri-host [ ":" port ] / pseudonym を厳密には区別せず、
+/// 区切り文字を含まない可視 ASCII の並びを受け付ける。
+fn is_valid_agent(agent: &str) -> bool {
+    !agent.is_empty()
+        && agent
+            .bytes()
+            .all(|b| b.is_ascii_graphic() && b != b'"' && b != b',')
+}
@@ -5,6 +5,13 @@
 //! RFC 9530 に基づいた Content-Digest / Repr-Digest / Want-Content-Digest /
 //! Want-Repr-Digest のパースを提供します。
 //!
+//! また、依存なしの SHA-256 実装で `sha-256` の Digest を計算・検証できます。
+//!
+//! - Content-Digest: メッセージのコンテンツ (Content-Encoding 適用後、
+//!   Transfer-Encoding 適用前のボディ) に対する Digest
+//! - Repr-Digest: 選択された表現データ全体に対する Digest
+//!   (Range リクエストの 206 レスポンスでも部分ではなく全体に対して計算する)
+//!
 //! ## 使い方
 //!
 //! ```rust
@@ -15,6 +22,55 @@
 //!
 //! let want = WantContentDigest::parse("sha-256=1, sha-512=3").unwrap();
 //! assert_eq!(want.items().len(), 2);
+//!
+//! // Digest の計算と検証
+//! let digest = ContentDigest::sha256(b"{\"hello\": \"world\"}\n");
+//! assert_eq!(
+//!     digest.to_string(),
+//!     "sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:"
+//! );
+//! assert!(digest.verify(b"{\"hello\": \"world\"}\n").is_ok());
+//! ```
+//!
+//! ## Want-Content-Digest によるアルゴリズムの選択
+//!
+//! ```rust
+//! use shiguredo_http11::digest_fields::{
+//!     ContentDigest, SHA_256, SUPPORTED_ALGORITHMS, WantContentDigest,
+//! };
+//!
+//! let want = WantContentDigest::parse("sha-512=3, sha-256=1").unwrap();
+//! if want.choose(SUPPORTED_ALGORITHMS) == Some(SHA_256) {
+//!     let digest = ContentDigest::sha256(b"body");
+//!     assert_eq!(digest.items()[0].algorithm(), SHA_256);
+//! }
+//! ```
+//!
+//! ## ストリーミング検証
+//!
+//! `peek_body()` で取得したデータを `DigestVerifier` に投入してから
+//! `consume_body()` で消費することで、ボディ全体をメモリに載せずに検証できます。
+//!
+//! ```rust
+//! use shiguredo_http11::digest_fields::ContentDigest;
+//! use shiguredo_http11::{BodyProgress, HttpHead, ResponseDecoder};
+//!
+//! let mut decoder = ResponseDecoder::new();
+//! decoder
+//!     .feed(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nContent-Digest: sha-256=:ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=:\r\n\r\nabc")
+//!     .unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//! let digest = head.get_typed::<ContentDigest>().unwrap().unwrap();
+//!
+//! let mut verifier = digest.verifier().unwrap();
+//! while let Some(data) = decoder.peek_body() {
+//!     verifier.update(data);
+//!     let len = data.len();
+//!     if let BodyProgress::Complete { .. } = decoder.consume_body(len).unwrap() {
+//!         break;
+//!     }
+//! }
+//! assert!(verifier.finish().is_ok());
 //! ```
 
 use alloc::string::{String, ToString};
@@ -22,6 +78,8 @@ use alloc::vec::Vec;
 use core::fmt;
 
 use crate::base64;
+use crate::sha256::{self, Sha256};
+use crate::typed_header::TypedHeader;
 use crate::validate::is_valid_token;
 
 /// Digest Fields パースエラー
@@ -40,6 +98,10 @@ pub enum DigestFieldsError {
     InvalidBase64,
     /// 不正な優先度
     InvalidPreference,
+    /// 検証に使えるアルゴリズムがない
+    UnsupportedAlgorithm,
+    /// Digest が一致しない
+    Mismatch,
 }
 
 impl fmt::Display for DigestFieldsError {
@@ -51,12 +113,24 @@ impl fmt::Display for DigestFieldsError {
             DigestFieldsError::InvalidByteSequence => write!(f, "invalid digest byte sequence"),
             DigestFieldsError::InvalidBase64 => write!(f, "invalid digest base64"),
             DigestFieldsError::InvalidPreference => write!(f, "invalid digest preference"),
+            DigestFieldsError::UnsupportedAlgorithm => {
+                write!(f, "no supported digest algorithm")
+            }
+            DigestFieldsError::Mismatch => write!(f, "digest mismatch"),
         }
     }
 }
 
 impl core::error::Error for DigestFieldsError {}
 
+/// SHA-256 のアルゴリズム名 (RFC 9530 Section 7.2)
+pub const SHA_256: &str = "sha-256";
+
+/// このクレートで計算・検証できるアルゴリズム
+///
+/// `WantContentDigest::choose()` / `WantReprDigest::choose()` にそのまま渡せる。
+pub const SUPPORTED_ALGORITHMS: &[&str] = &[SHA_256];
+
 /// Digest 値
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigestValue {
@@ -84,6 +158,36 @@ pub struct DigestEntry {
 }
 
 impl DigestEntry {
+    /// アルゴリズム名と Digest 値のバイト列から作成
+    ///
+    /// アルゴリズム名が token でない場合は `DigestFieldsError::InvalidAlgorithm` を返す。
+    /// アルゴリズム名は小文字に正規化する。
+    pub fn new(algorithm: &str, bytes: &[u8]) -> Result<Self, DigestFieldsError> {
+        if !is_valid_token(algorithm) {
+            return Err(DigestFieldsError::InvalidAlgorithm);
+        }
+        Ok(DigestEntry {
+            algorithm: algorithm.to_ascii_lowercase(),
+            value: DigestValue {
+                bytes: bytes.to_vec(),
+            },
+        })
+    }
+
+    /// SHA-256 の Digest エントリを計算
+    pub fn sha256(data: &[u8]) -> Self {
+        Self::sha256_from_digest(sha256::digest(data))
+    }
+
+    fn sha256_from_digest(digest: [u8; 32]) -> Self {
+        DigestEntry {
+            algorithm: SHA_256.to_string(),
+            value: DigestValue {
+                bytes: digest.to_vec(),
+            },
+        }
+    }
+
     /// アルゴリズム名
     pub fn algorithm(&self) -> &str {
         &self.algorithm
@@ -114,6 +218,40 @@ impl ContentDigest {
         Ok(ContentDigest { items })
     }
 
+    /// Digest エントリから作成
+    pub fn new(entry: DigestEntry) -> Self {
+        ContentDigest {
+            items: alloc::vec![entry],
+        }
+    }
+
+    /// Digest エントリを追加
+    pub fn with_entry(mut self, entry: DigestEntry) -> Self {
+        self.items.push(entry);
+        self
+    }
+
+    /// コンテンツの SHA-256 Digest から作成
+    pub fn sha256(data: &[u8]) -> Self {
+        Self::new(DigestEntry::sha256(data))
+    }
+
+    /// コンテンツを検証
+    ///
+    /// サポートしているアルゴリズム (`sha-256`) の Digest がない場合は
+    /// `DigestFieldsError::UnsupportedAlgorithm`、一致しない場合は
+    /// `DigestFieldsError::Mismatch` を返す。
+    pub fn verify(&self, data: &[u8]) -> Result<(), DigestFieldsError> {
+        let mut verifier = self.verifier()?;
+        verifier.update(data);
+        verifier.finish()
+    }
+
+    /// ストリーミング検証用の [`DigestVerifier`] を作成
+    pub fn verifier(&self) -> Result<DigestVerifier, DigestFieldsError> {
+        DigestVerifier::new(&self.items)
+    }
+
     /// Digest 一覧
     pub fn items(&self) -> &[DigestEntry] {
         &self.items
@@ -136,6 +274,19 @@ impl fmt::Display for ContentDigest {
     }
 }
 
+impl TypedHeader for ContentDigest {
+    const NAME: &'static str = "Content-Digest";
+    type Error = DigestFieldsError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Repr-Digest ヘッダー
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ReprDigest {
@@ -149,6 +300,40 @@ impl ReprDigest {
         Ok(ReprDigest { items })
     }
 
+    /// Digest エントリから作成
+    pub fn new(entry: DigestEntry) -> Self {
+        ReprDigest {
+            items: alloc::vec![entry],
+        }
+    }
+
+    /// Digest エントリを追加
+    pub fn with_entry(mut self, entry: DigestEntry) -> Self {
+        self.items.push(entry);
+        self
+    }
+
+    /// 表現データの SHA-256 Digest から作成
+    pub fn sha256(data: &[u8]) -> Self {
+        Self::new(DigestEntry::sha256(data))
+    }
+
+    /// 表現データを検証
+    ///
+    /// サポートしているアルゴリズム (`sha-256`) の Digest がない場合は
+    /// `DigestFieldsError::UnsupportedAlgorithm`、一致しない場合は
+    /// `DigestFieldsError::Mismatch` を返す。
+    pub fn verify(&self, data: &[u8]) -> Result<(), DigestFieldsError> {
+        let mut verifier = self.verifier()?;
+        verifier.update(data);
+        verifier.finish()
+    }
+
+    /// ストリーミング検証用の [`DigestVerifier`] を作成
+    pub fn verifier(&self) -> Result<DigestVerifier, DigestFieldsError> {
+        DigestVerifier::new(&self.items)
+    }
+
     /// Digest 一覧
     pub fn items(&self) -> &[DigestEntry] {
         &self.items
@@ -171,6 +356,19 @@ impl fmt::Display for ReprDigest {
     }
 }
 
+impl TypedHeader for ReprDigest {
+    const NAME: &'static str = "Repr-Digest";
+    type Error = DigestFieldsError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Digest 優先度
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DigestPreference {
@@ -179,6 +377,23 @@ pub struct DigestPreference {
 }
 
 impl DigestPreference {
+    /// アルゴリズム名と優先度から作成
+    ///
+    /// アルゴリズム名が token でない場合は `DigestFieldsError::InvalidAlgorithm`、
+    /// 優先度が 10 を超える場合は `DigestFieldsError::InvalidPreference` を返す。
+    pub fn new(algorithm: &str, weight: u8) -> Result<Self, DigestFieldsError> {
+        if !is_valid_token(algorithm) {
+            return Err(DigestFieldsError::InvalidAlgorithm);
+        }
+        if weight > 10 {
+            return Err(DigestFieldsError::InvalidPreference);
+        }
+        Ok(DigestPreference {
+            algorithm: algorithm.to_ascii_lowercase(),
+            weight,
+        })
+    }
+
     /// アルゴリズム名
     pub fn algorithm(&self) -> &str {
         &self.algorithm
@@ -209,6 +424,38 @@ impl WantContentDigest {
         Ok(WantContentDigest { items })
     }
 
+    /// 優先度から作成
+    pub fn new(preference: DigestPreference) -> Self {
+        WantContentDigest {
+            items: alloc::vec![preference],
+        }
+    }
+
+    /// 優先度を追加
+    pub fn with_preference(mut self, preference: DigestPreference) -> Self {
+        self.items.push(preference);
+        self
+    }
+
+    /// サポートしているアルゴリズムの中から最も優先度の高いものを選ぶ
+    ///
+    /// 優先度 0 (not acceptable) のアルゴリズムは選ばない (RFC 9530 Section 4)。
+    /// 優先度が同じ場合は `supported` の順序を優先する。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::digest_fields::{WantContentDigest, SUPPORTED_ALGORITHMS, SHA_256};
+    ///
+    /// let want = WantContentDigest::parse("sha-512=10, sha-256=3, unixsum=0").unwrap();
+    /// assert_eq!(want.choose(&["unixsum", "sha-256", "sha-512"]), Some("sha-512"));
+    /// assert_eq!(want.choose(SUPPORTED_ALGORITHMS), Some(SHA_256));
+    /// assert_eq!(want.choose(&["unixsum"]), None);
+    /// ```
+    pub fn choose<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        choose_algorithm(&self.items, supported)
+    }
+
     /// 優先度一覧
     pub fn items(&self) -> &[DigestPreference] {
         &self.items
@@ -231,6 +478,19 @@ impl fmt::Display for WantContentDigest {
     }
 }
 
+impl TypedHeader for WantContentDigest {
+    const NAME: &'static str = "Want-Content-Digest";
+    type Error = DigestFieldsError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Want-Repr-Digest ヘッダー
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WantReprDigest {
@@ -244,6 +504,38 @@ impl WantReprDigest {
         Ok(WantReprDigest { items })
     }
 
+    /// 優先度から作成
+    pub fn new(preference: DigestPreference) -> Self {
+        WantReprDigest {
+            items: alloc::vec![preference],
+        }
+    }
+
+    /// 優先度を追加
+    pub fn with_preference(mut self, preference: DigestPreference) -> Self {
+        self.items.push(preference);
+        self
+    }
+
+    /// サポートしているアルゴリズムの中から最も優先度の高いものを選ぶ
+    ///
+    /// 優先度 0 (not acceptable) のアルゴリズムは選ばない (RFC 9530 Section 4)。
+    /// 優先度が同じ場合は `supported` の順序を優先する。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::digest_fields::{WantReprDigest, SUPPORTED_ALGORITHMS, SHA_256};
+    ///
+    /// let want = WantReprDigest::parse("sha-512=10, sha-256=3, unixsum=0").unwrap();
+    /// assert_eq!(want.choose(&["unixsum", "sha-256", "sha-512"]), Some("sha-512"));
+    /// assert_eq!(want.choose(SUPPORTED_ALGORITHMS), Some(SHA_256));
+    /// assert_eq!(want.choose(&["unixsum"]), None);
+    /// ```
+    pub fn choose<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        choose_algorithm(&self.items, supported)
+    }
+
     /// 優先度一覧
     pub fn items(&self) -> &[DigestPreference] {
         &self.items
@@ -266,6 +558,122 @@ impl fmt::Display for WantReprDigest {
     }
 }
 
+impl TypedHeader for WantReprDigest {
+    const NAME: &'static str = "Want-Repr-Digest";
+    type Error = DigestFieldsError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// ストリーミングで SHA-256 の Digest を計算するハッシャー
+///
+/// 結果は同じバイト列に対する [`DigestEntry::sha256`] と一致する。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::digest_fields::{ContentDigest, DigestHasher};
+///
+/// let mut hasher = DigestHasher::new();
+/// hasher.update(b"hel").update(b"lo");
+/// assert_eq!(ContentDigest::new(hasher.finish()), ContentDigest::sha256(b"hello"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DigestHasher {
+    sha: Sha256,
+}
+
+impl DigestHasher {
+    /// 新しいハッシャーを作成
+    pub fn new() -> Self {
+        DigestHasher { sha: Sha256::new() }
+    }
+
+    /// データを追加
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.sha.update(data);
+        self
+    }
+
+    /// `sha-256` の Digest エントリを生成
+    pub fn finish(self) -> DigestEntry {
+        DigestEntry::sha256_from_digest(self.sha.finalize())
+    }
+}
+
+impl Default for DigestHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ストリーミングで Digest を検証する
+///
+/// `ContentDigest::verifier()` / `ReprDigest::verifier()` で作成する。
+#[derive(Debug, Clone)]
+pub struct DigestVerifier {
+    hasher: DigestHasher,
+    expected: Vec<u8>,
+}
+
+impl DigestVerifier {
+    fn new(items: &[DigestEntry]) -> Result<Self, DigestFieldsError> {
+        let expected = items
+            .iter()
+            .find(|item| item.algorithm == SHA_256)
+            .ok_or(DigestFieldsError::UnsupportedAlgorithm)?;
+        Ok(DigestVerifier {
+            hasher: DigestHasher::new(),
+            expected: expected.value.bytes.clone(),
+        })
+    }
+
+    /// データを追加
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.hasher.update(data);
+        self
+    }
+
+    /// 検証する
+    ///
+    /// 一致しない場合は `DigestFieldsError::Mismatch` を返す。
+    pub fn finish(self) -> Result<(), DigestFieldsError> {
+        if self.hasher.finish().value.bytes == self.expected {
+            Ok(())
+        } else {
+            Err(DigestFieldsError::Mismatch)
+        }
+    }
+}
+
+/// 優先度一覧とサポートしているアルゴリズムから最も優先度の高いものを選ぶ
+fn choose_algorithm<'a>(items: &[DigestPreference], supported: &[&'a str]) -> Option<&'a str> {
+    let mut best: Option<(&'a str, u8)> = None;
+    for &algorithm in supported {
+        // 同じアルゴリズムが複数ある場合は最初のものを使う (get() と同じ)
+        let Some(weight) = items
+            .iter()
+            .find(|item| item.algorithm.eq_ignore_ascii_case(algorithm))
+            .map(|item| item.weight)
+        else {
+            continue;
+        };
+        if weight == 0 {
+            continue;
+        }
+        if best.is_none_or(|(_, best_weight)| weight > best_weight) {
+            best = Some((algorithm, weight));
+        }
+    }
+    best.map(|(algorithm, _)| algorithm)
+}
+
 fn parse_digest_dictionary(input: &str) -> Result<Vec<DigestEntry>, DigestFieldsError> {
     let entries = parse_dictionary(input, parse_byte_sequence)?;
     Ok(entries
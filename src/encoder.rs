@@ -1,17 +1,66 @@
 use crate::compression::{CompressionError, CompressionStatus, Compressor, NoCompression};
-use crate::decoder::HttpHead;
+use crate::decoder::{HttpHead, validate_chunk_ext};
 use crate::error::EncodeError;
 use crate::host::Host;
 use crate::request::Request;
-use crate::request_target::{RequestTargetForm, detect_scheme};
+use crate::request_target::{RequestTargetForm, classify};
 use crate::response::Response;
+use crate::trace::{WireEvent, WireObserver, WireTracer};
 use crate::validate::{
     is_valid_field_value, is_valid_header_name, is_valid_method, is_valid_reason_phrase,
     is_valid_request_target, is_valid_status_code, trim_ows,
 };
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
+/// エンコード時のヘッダー名の大文字小文字
+///
+/// RFC 9110 Section 5.1: フィールド名は大文字小文字を区別しないが、古い RTSP 実装や
+/// 一部の中継装置は特定の表記を期待する。`Request::header_case()` /
+/// `Response::header_case()` で指定し、`encode_*` / `encode_*_headers` 系の
+/// すべてのエンコード関数と、自動付与する `Content-Length` / `Date` に適用される。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HeaderCase {
+    /// 呼び出し側が指定した名前をそのまま送信する
+    #[default]
+    Preserve,
+    /// 先頭と `-` の直後を大文字、それ以外を小文字にする (`content-type` → `Content-Type`)
+    TitleCase,
+    /// すべて小文字にする (`Content-Type` → `content-type`)
+    Lowercase,
+}
+
+/// ヘッダー名を `case` に従って書き込む
+///
+/// 大文字小文字の変換はバイト長を変えないため、容量見積もりには影響しない。
+fn write_header_name(buf: &mut Vec<u8>, name: &str, case: HeaderCase) {
+    match case {
+        HeaderCase::Preserve => buf.extend_from_slice(name.as_bytes()),
+        HeaderCase::Lowercase => buf.extend(name.bytes().map(|b| b.to_ascii_lowercase())),
+        HeaderCase::TitleCase => {
+            let mut upper = true;
+            for b in name.bytes() {
+                buf.push(if upper {
+                    b.to_ascii_uppercase()
+                } else {
+                    b.to_ascii_lowercase()
+                });
+                upper = b == b'-';
+            }
+        }
+    }
+}
+
+/// ヘッダー行 (`name: value CRLF`) を書き込む
+fn write_header_line(buf: &mut Vec<u8>, name: &str, value: &[u8], case: HeaderCase) {
+    write_header_name(buf, name, case);
+    buf.extend_from_slice(b": ");
+    buf.extend_from_slice(value);
+    buf.extend_from_slice(b"\r\n");
+}
+
 /// エンコード用のバージョン文字列バリデーション
 ///
 /// VCHAR のみ (SP/CTL 禁止)。RTSP 等の非 HTTP プロトコルにも対応。
@@ -74,6 +123,11 @@ const ENCODE_CAPACITY_LIMIT: usize = 64 * 1024 * 1024;
 /// 桁数の厳密計算は二度走査回避のために行わず、最悪ケースで過剰確保する。
 const AUTO_CONTENT_LENGTH_CAPACITY: usize = 38;
 
+/// 自動付与される Date 行の容量 (固定値)
+///
+/// `"Date: " (6) + IMF-fixdate (29) + CRLF (2) = 37`
+const AUTO_DATE_CAPACITY: usize = 37;
+
 /// `encode_request` で Content-Length を自動付与するか判定
 fn should_auto_emit_content_length_for_request(request: &Request) -> bool {
     request.body_bytes().is_some()
@@ -84,7 +138,7 @@ fn should_auto_emit_content_length_for_request(request: &Request) -> bool {
 /// ステータスコードがボディを持ちうるか判定
 ///
 /// RFC 9110 Section 6.4.1: 1xx / 204 / 304 はボディを含めてはならない
-fn response_status_has_body(status_code: u16) -> bool {
+pub(crate) fn response_status_has_body(status_code: u16) -> bool {
     !((100..200).contains(&status_code) || status_code == 204 || status_code == 304)
 }
 
@@ -106,6 +160,15 @@ fn should_auto_emit_content_length_for_response(response: &Response) -> bool {
 ///
 /// オーバーフロー時は `None` を返し、呼び出し側は `Vec::new()` にフォールバックする
 fn estimate_request_capacity(request: &Request) -> Option<usize> {
+    let total = estimate_request_head_capacity(request)?;
+    match request.body_bytes() {
+        Some(body) => total.checked_add(body.len()),
+        None => Some(total),
+    }
+}
+
+/// `encode_request` の出力のうちボディを除いた部分の容量を見積もる
+fn estimate_request_head_capacity(request: &Request) -> Option<usize> {
     let mut total: usize = 0;
     // Request line: METHOD SP URI SP VERSION CRLF (固定 4: SP + SP + CRLF)
     total = total.checked_add(request.method().len())?;
@@ -122,17 +185,19 @@ fn estimate_request_capacity(request: &Request) -> Option<usize> {
         total = total.checked_add(AUTO_CONTENT_LENGTH_CAPACITY)?;
     }
     // End-of-headers CRLF
-    total = total.checked_add(2)?;
-    if let Some(body) = request.body_bytes() {
-        total = total.checked_add(body.len())?;
-    }
-    Some(total)
+    total.checked_add(2)
 }
 
 /// `encode_response` の出力容量を `checked_add` で見積もる
 ///
 /// オーバーフロー時は `None` を返し、呼び出し側は `Vec::new()` にフォールバックする
 fn estimate_response_capacity(response: &Response) -> Option<usize> {
+    let total = estimate_response_head_capacity(response)?;
+    total.checked_add(response_body_to_encode(response).len())
+}
+
+/// `encode_response` の出力のうちボディを除いた部分の容量を見積もる
+fn estimate_response_head_capacity(response: &Response) -> Option<usize> {
     let mut total: usize = 0;
     // ステータス行: VERSION SP STATUS-CODE SP REASON CRLF
     // (固定 4: SP + SP + CRLF, 加えて status code は 3 桁固定で見積もる)
@@ -145,16 +210,26 @@ fn estimate_response_capacity(response: &Response) -> Option<usize> {
         total = total.checked_add(value.len())?;
         total = total.checked_add(4)?;
     }
+    if response.pending_auto_date().is_some() {
+        total = total.checked_add(AUTO_DATE_CAPACITY)?;
+    }
     if should_auto_emit_content_length_for_response(response) {
         total = total.checked_add(AUTO_CONTENT_LENGTH_CAPACITY)?;
     }
-    total = total.checked_add(2)?;
+    total.checked_add(2)
+}
+
+/// `encode_response` で送信されるボディを返す
+///
+/// RFC 9110 Section 6.4.1: 1xx/204/304 はボディを含めてはならない
+/// HEAD レスポンスでは omit_body: true としてボディ送信を抑止する
+fn response_body_to_encode(response: &Response) -> &[u8] {
     let body_will_be_encoded =
         response_status_has_body(response.status_code()) && !response.is_body_omitted();
-    if body_will_be_encoded && let Some(body) = response.body_bytes() {
-        total = total.checked_add(body.len())?;
+    match response.body_bytes() {
+        Some(body) if body_will_be_encoded => body,
+        _ => &[],
     }
-    Some(total)
 }
 
 /// 容量見積もりを `ENCODE_CAPACITY_LIMIT` で頭打ちにし、`Vec` を確保する
@@ -169,6 +244,17 @@ fn allocate_encode_buffer(estimated: Option<usize>) -> Vec<u8> {
     }
 }
 
+/// 既存バッファに容量見積もり分を追加確保する
+///
+/// `allocate_encode_buffer` と同じく `ENCODE_CAPACITY_LIMIT` を超える見積もりでは確保しない。
+fn reserve_encode_buffer(buf: &mut Vec<u8>, estimated: Option<usize>) {
+    if let Some(c) = estimated
+        && c <= ENCODE_CAPACITY_LIMIT
+    {
+        buf.reserve(c);
+    }
+}
+
 /// リクエストフィールドのバリデーション
 fn validate_request_fields(request: &Request) -> Result<(), EncodeError> {
     // メソッドの検証
@@ -211,57 +297,11 @@ fn validate_request_fields(request: &Request) -> Result<(), EncodeError> {
 
 /// request-target の形式を判定
 ///
-/// RFC 9112 Section 3.2:
-/// - origin-form: "/" で始まる (例: /path?query)
-/// - absolute-form: absolute-URI (例: http://host/path, urn:isbn:0451450523)
-/// - authority-form: uri-host ":" port (例: host:port)
-/// - asterisk-form: "*" のみ
-///
-/// authority-form と "://" なし absolute-form は文法的に曖昧なため、
-/// デコーダー (decoder/body.rs) と同じ順序で判定する:
-/// 1. port が数値の host:port → authority-form
-/// 2. 有効なスキームが検出 → absolute-form
+/// 判定規則は `request_target::classify` を参照。判定できない場合はエラーを返す。
 fn detect_request_target_form(uri: &str) -> Result<RequestTargetForm, EncodeError> {
-    if uri == "*" {
-        Ok(RequestTargetForm::Asterisk)
-    } else if uri.contains("://") {
-        Ok(RequestTargetForm::Absolute)
-    } else if uri.starts_with('/') {
-        Ok(RequestTargetForm::Origin)
-    } else if looks_like_authority_form(uri) {
-        Ok(RequestTargetForm::Authority)
-    } else if detect_scheme(uri).is_some() {
-        // "://" を含まない absolute-URI (例: urn:isbn:0451450523)
-        Ok(RequestTargetForm::Absolute)
-    } else {
-        Err(EncodeError::InvalidRequestTarget {
-            uri: uri.to_string(),
-        })
-    }
-}
-
-/// authority-form かどうかの簡易判定
-///
-/// authority-form = uri-host ":" port (RFC 9112 Section 3.2.3)
-/// uri-host = host (RFC 3986 Section 3.2.2)
-///
-/// ホスト部分に userinfo 区切りの "@" が含まれる場合は authority-form ではない。
-/// RFC 9110 Section 9.3.6: "consisting of only the host and port number"
-fn looks_like_authority_form(uri: &str) -> bool {
-    // userinfo は authority-form に含まれない
-    if uri.contains('@') {
-        return false;
-    }
-    if let Some(colon_pos) = uri.rfind(':') {
-        let port_str = &uri[colon_pos + 1..];
-        let host = &uri[..colon_pos];
-        !port_str.is_empty()
-            && port_str.bytes().all(|b| b.is_ascii_digit())
-            && port_str.parse::<u16>().is_ok()
-            && !host.is_empty()
-    } else {
-        false
-    }
+    classify(uri).ok_or_else(|| EncodeError::InvalidRequestTarget {
+        uri: uri.to_string(),
+    })
 }
 
 /// authority-form の host を検証 (エンコーダー用)
@@ -283,7 +323,7 @@ fn validate_encoder_authority_form(uri: &str) -> Result<(), EncodeError> {
 /// - CONNECT は authority-form のみ (Section 3.2.3)
 /// - asterisk-form ("*") は OPTIONS のみ (Section 3.2.4)
 /// - その他のメソッドは origin-form または absolute-form (Section 3.2.1, 3.2.2)
-fn validate_request_target_form(method: &str, uri: &str) -> Result<(), EncodeError> {
+pub(crate) fn validate_request_target_form(method: &str, uri: &str) -> Result<(), EncodeError> {
     let form = detect_request_target_form(uri)?;
 
     // RFC 9110 Section 9.1: メソッドトークンは case-sensitive
@@ -367,14 +407,16 @@ fn validate_response_fields(response: &Response) -> Result<(), EncodeError> {
 }
 
 /// ヘッダー名と値のバリデーション
-fn validate_headers(headers: &[(String, String)]) -> Result<(), EncodeError> {
+fn validate_headers(headers: &[(Cow<'static, str>, String)]) -> Result<(), EncodeError> {
     for (name, value) in headers {
         if !is_valid_header_name(name) {
-            return Err(EncodeError::InvalidHeaderName { name: name.clone() });
+            return Err(EncodeError::InvalidHeaderName {
+                name: name.to_string(),
+            });
         }
         if !is_valid_field_value(value) {
             return Err(EncodeError::InvalidHeaderValue {
-                name: name.clone(),
+                name: name.to_string(),
                 value: value.clone(),
             });
         }
@@ -388,7 +430,7 @@ fn validate_headers(headers: &[(String, String)]) -> Result<(), EncodeError> {
 /// - HTTP/1.1 リクエストには Host ヘッダーが必須
 /// - Host ヘッダーは重複してはならない
 /// - Host ヘッダーの値は有効な authority でなければならない
-fn validate_host_header(request: &Request) -> Result<(), EncodeError> {
+pub(crate) fn validate_host_header(request: &Request) -> Result<(), EncodeError> {
     if request.version() != "HTTP/1.1" {
         return Ok(());
     }
@@ -489,7 +531,7 @@ fn validate_host_header(request: &Request) -> Result<(), EncodeError> {
 ///
 /// 送信者は http/https URI に userinfo を生成してはならない (MUST NOT)
 /// 他のスキームには適用しない
-fn reject_http_userinfo(uri: &str) -> Result<(), EncodeError> {
+pub(crate) fn reject_http_userinfo(uri: &str) -> Result<(), EncodeError> {
     let lower = uri.to_ascii_lowercase();
     if !lower.starts_with("http://") && !lower.starts_with("https://") {
         return Ok(());
@@ -511,7 +553,7 @@ fn reject_http_userinfo(uri: &str) -> Result<(), EncodeError> {
 /// RFC 9110 Section 4.2.1/4.2.2: http/https URI の空 host を検出して拒否する
 ///
 /// 送信者は空 host 識別子を持つ http/https URI を生成してはならない (MUST NOT)
-fn reject_http_empty_host(uri: &str) -> Result<(), EncodeError> {
+pub(crate) fn reject_http_empty_host(uri: &str) -> Result<(), EncodeError> {
     let lower = uri.to_ascii_lowercase();
     if !lower.starts_with("http://") && !lower.starts_with("https://") {
         return Ok(());
@@ -562,7 +604,7 @@ fn reject_http_without_authority_prefix(uri: &str) -> Result<(), EncodeError> {
 /// 2. 複数ヘッダーの値が一致することを検証 → 不一致なら DuplicateContentLength
 /// 3. 検証済みの値を Option<u64> で返す
 fn validate_content_length_headers(
-    headers: &[(String, String)],
+    headers: &[(Cow<'static, str>, String)],
 ) -> Result<Option<u64>, EncodeError> {
     let mut result: Option<u64> = None;
 
@@ -621,6 +663,35 @@ fn extract_authority_from_uri(uri: &str) -> Option<String> {
 /// RFC 9112 Section 3.2: HTTP/1.1 リクエストには Host ヘッダーが必須
 /// RFC 9112 Section 6.2: Transfer-Encoding と Content-Length は同時に送信してはならない
 pub fn encode_request(request: &Request) -> Result<Vec<u8>, EncodeError> {
+    validate_request_for_encode(request)?;
+
+    let mut buf = allocate_encode_buffer(Some(request.encoded_len_hint()));
+    write_request_head(&mut buf, request);
+    if let Some(body) = request.body_bytes() {
+        buf.extend_from_slice(body);
+    }
+
+    Ok(buf)
+}
+
+/// リクエストを `buf` の末尾にエンコード
+///
+/// `encode_request` と同じ検証を行う。エラー時は `buf` を変更しない。
+/// 呼び出し側でバッファを使い回すことで、エンコードごとのアロケーションを避けられる。
+pub fn encode_request_into(request: &Request, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+    validate_request_for_encode(request)?;
+
+    reserve_encode_buffer(buf, Some(request.encoded_len_hint()));
+    write_request_head(buf, request);
+    if let Some(body) = request.body_bytes() {
+        buf.extend_from_slice(body);
+    }
+
+    Ok(())
+}
+
+/// `encode_request` / `encode_request_vectored` 共通のバリデーション
+fn validate_request_for_encode(request: &Request) -> Result<(), EncodeError> {
     // フィールドバリデーション
     validate_request_fields(request)?;
 
@@ -656,8 +727,11 @@ pub fn encode_request(request: &Request) -> Result<Vec<u8>, EncodeError> {
         }
     }
 
-    let mut buf = allocate_encode_buffer(estimate_request_capacity(request));
+    Ok(())
+}
 
+/// リクエスト行、ヘッダー、自動付与の Content-Length、ヘッダー終端の空行を書き込む
+fn write_request_head(buf: &mut Vec<u8>, request: &Request) {
     // リクエスト行: METHOD SP URI SP VERSION CRLF
     buf.extend_from_slice(request.method().as_bytes());
     buf.push(b' ');
@@ -666,11 +740,9 @@ pub fn encode_request(request: &Request) -> Result<Vec<u8>, EncodeError> {
     buf.extend_from_slice(request.version().as_bytes());
     buf.extend_from_slice(b"\r\n");
 
+    let case = request.header_case_policy();
     for (name, value) in HttpHead::headers(request) {
-        buf.extend_from_slice(name.as_bytes());
-        buf.extend_from_slice(b": ");
-        buf.extend_from_slice(value.as_bytes());
-        buf.extend_from_slice(b"\r\n");
+        write_header_line(buf, name, value.as_bytes(), case);
     }
 
     // Content-Length (body == Some の場合、Content-Length / Transfer-Encoding 未指定なら自動付与)
@@ -680,19 +752,14 @@ pub fn encode_request(request: &Request) -> Result<Vec<u8>, EncodeError> {
         && !request.has_header("Content-Length")
         && !request.has_header("Transfer-Encoding")
     {
-        buf.extend_from_slice(b"Content-Length: ");
-        write_usize_decimal(&mut buf, body.len());
+        write_header_name(buf, "Content-Length", case);
+        buf.extend_from_slice(b": ");
+        write_usize_decimal(buf, body.len());
         buf.extend_from_slice(b"\r\n");
     }
 
     // ヘッダー終端の空行
     buf.extend_from_slice(b"\r\n");
-
-    if let Some(body) = request.body_bytes() {
-        buf.extend_from_slice(body);
-    }
-
-    Ok(buf)
 }
 
 /// レスポンスをエンコード
@@ -707,6 +774,31 @@ pub fn encode_request(request: &Request) -> Result<Vec<u8>, EncodeError> {
 /// しかし、エンコーダーはリクエストメソッドの情報を持たないため、この制約は
 /// 呼び出し側アプリケーションの責務とする。
 pub fn encode_response(response: &Response) -> Result<Vec<u8>, EncodeError> {
+    validate_response_for_encode(response)?;
+
+    let mut buf = allocate_encode_buffer(Some(response.encoded_len_hint()));
+    write_response_head(&mut buf, response);
+    buf.extend_from_slice(response_body_to_encode(response));
+
+    Ok(buf)
+}
+
+/// レスポンスを `buf` の末尾にエンコード
+///
+/// `encode_response` と同じ検証を行う。エラー時は `buf` を変更しない。
+/// 呼び出し側でバッファを使い回すことで、エンコードごとのアロケーションを避けられる。
+pub fn encode_response_into(response: &Response, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+    validate_response_for_encode(response)?;
+
+    reserve_encode_buffer(buf, Some(response.encoded_len_hint()));
+    write_response_head(buf, response);
+    buf.extend_from_slice(response_body_to_encode(response));
+
+    Ok(())
+}
+
+/// `encode_response` / `encode_response_vectored` 共通のバリデーション
+fn validate_response_for_encode(response: &Response) -> Result<(), EncodeError> {
     // フィールドバリデーション
     validate_response_fields(response)?;
 
@@ -774,23 +866,40 @@ pub fn encode_response(response: &Response) -> Result<Vec<u8>, EncodeError> {
         }
     }
 
-    let mut buf = allocate_encode_buffer(estimate_response_capacity(response));
+    Ok(())
+}
 
+/// `Response::auto_date` で指定された Date ヘッダーを書き込む
+///
+/// `Date` ヘッダーが既に存在する場合は書き込まない。
+fn write_auto_date(buf: &mut Vec<u8>, response: &Response) {
+    if let Some(date) = response.pending_auto_date() {
+        write_header_line(
+            buf,
+            "Date",
+            date.to_string().as_bytes(),
+            response.header_case_policy(),
+        );
+    }
+}
+
+/// ステータス行、ヘッダー、自動付与の Date / Content-Length、ヘッダー終端の空行を書き込む
+fn write_response_head(buf: &mut Vec<u8>, response: &Response) {
     // ステータス行: VERSION SP STATUS-CODE SP REASON-PHRASE CRLF
     buf.extend_from_slice(HttpHead::version(response).as_bytes());
     buf.push(b' ');
-    write_usize_decimal(&mut buf, response.status_code() as usize);
+    write_usize_decimal(buf, response.status_code() as usize);
     buf.push(b' ');
     buf.extend_from_slice(response.reason_phrase().as_bytes());
     buf.extend_from_slice(b"\r\n");
 
+    let case = response.header_case_policy();
     for (name, value) in HttpHead::headers(response) {
-        buf.extend_from_slice(name.as_bytes());
-        buf.extend_from_slice(b": ");
-        buf.extend_from_slice(value.as_bytes());
-        buf.extend_from_slice(b"\r\n");
+        write_header_line(buf, name, value.as_bytes(), case);
     }
 
+    write_auto_date(buf, response);
+
     // Content-Length 自動付与
     // RFC 9112: keep-alive を維持するために Content-Length または Transfer-Encoding が必要
     // 1xx/204/304 はボディがないため Content-Length を追加しない
@@ -803,24 +912,102 @@ pub fn encode_response(response: &Response) -> Result<Vec<u8>, EncodeError> {
     // を介して判定する (条件がずれると過小確保で再確保が発生する)
     if should_auto_emit_content_length_for_response(response) {
         let len = response.body_bytes().map(<[u8]>::len).unwrap_or(0);
-        buf.extend_from_slice(b"Content-Length: ");
-        write_usize_decimal(&mut buf, len);
+        write_header_name(buf, "Content-Length", case);
+        buf.extend_from_slice(b": ");
+        write_usize_decimal(buf, len);
         buf.extend_from_slice(b"\r\n");
     }
 
     // ヘッダー終端の空行
     buf.extend_from_slice(b"\r\n");
+}
 
-    // RFC 9110 Section 6.4.1: 1xx/204/304 はボディを含めてはならない
-    // HEAD レスポンスでは omit_body: true としてボディ送信を抑止する
-    if body_will_be_encoded && let Some(body) = response.body_bytes() {
-        buf.extend_from_slice(body);
+/// 中間レスポンスとして送信できるか検証する
+///
+/// RFC 9110 Section 15.2: 1xx は最終レスポンスの前に送信される中間レスポンス。
+/// 101 Switching Protocols はその後の接続が別プロトコルになるため、最終レスポンスとして扱う。
+fn validate_interim_response(response: &Response) -> Result<(), EncodeError> {
+    let status_code = response.status_code();
+    if !(100..200).contains(&status_code) || status_code == 101 {
+        return Err(EncodeError::InvalidInterimResponse { status_code });
+    }
+    if response.body_bytes().is_some_and(|b| !b.is_empty()) {
+        return Err(EncodeError::ForbiddenBodyForInformational { status_code });
+    }
+    validate_response_for_encode(response)
+}
+
+/// 中間レスポンス (1xx) をエンコード
+///
+/// 100 Continue や 103 Early Hints (RFC 8297) のように、最終レスポンスの前に
+/// 同じ交換で送信するレスポンスをエンコードする。
+///
+/// - 101 を含む 1xx 以外のステータスは `EncodeError::InvalidInterimResponse`
+/// - 空でないボディを持つ場合は `EncodeError::ForbiddenBodyForInformational`
+///
+/// `encode_response` は 1xx のボディを黙って抑止するが、本関数はエラーにする。
+pub fn encode_interim_response(response: &Response) -> Result<Vec<u8>, EncodeError> {
+    validate_interim_response(response)?;
+
+    let mut buf = allocate_encode_buffer(estimate_response_head_capacity(response));
+    write_response_head(&mut buf, response);
+    Ok(buf)
+}
+
+/// 中間レスポンス (1xx) 群と最終レスポンスを続けてエンコード
+///
+/// `interim` を順に `encode_interim_response` と同じ規則で、最後に `response` を
+/// `encode_response` と同じ規則でエンコードして連結する。
+/// 最終レスポンスが 1xx (101 を除く) の場合は `EncodeError::InvalidFinalResponse` を返す。
+/// いずれかの検証に失敗した場合は何も出力しない。
+///
+/// ```rust
+/// use shiguredo_http11::{Response, StatusCode, encode_response_with_interim};
+///
+/// let hints = Response::early_hints(["</style.css>; rel=preload; as=style"]).unwrap();
+/// let response = Response::with_status(StatusCode::OK).body(b"ok".to_vec());
+/// let bytes = encode_response_with_interim(&[hints], &response).unwrap();
+/// assert!(bytes.starts_with(b"HTTP/1.1 103 Early Hints\r\nLink: </style.css>"));
+/// ```
+pub fn encode_response_with_interim(
+    interim: &[Response],
+    response: &Response,
+) -> Result<Vec<u8>, EncodeError> {
+    for r in interim {
+        validate_interim_response(r)?;
     }
+    let status_code = response.status_code();
+    if (100..200).contains(&status_code) && status_code != 101 {
+        return Err(EncodeError::InvalidFinalResponse { status_code });
+    }
+    validate_response_for_encode(response)?;
 
+    let estimated = interim
+        .iter()
+        .try_fold(0usize, |total, r| {
+            total.checked_add(estimate_response_head_capacity(r)?)
+        })
+        .and_then(|total| total.checked_add(estimate_response_capacity(response)?));
+    let mut buf = allocate_encode_buffer(estimated);
+    for r in interim {
+        write_response_head(&mut buf, r);
+    }
+    write_response_head(&mut buf, response);
+    buf.extend_from_slice(response_body_to_encode(response));
     Ok(buf)
 }
 
 impl Request {
+    /// `encode()` の出力長の見積もり
+    ///
+    /// 実際の出力長以上の値を返す。自動付与する `Content-Length` の値を 20 桁で
+    /// 見積もるため、実際の出力長より最大 19 バイト大きくなる。`encode()` / `encode_into()` は内部でこの値を使って
+    /// 1 回だけ確保する。独自のバッファを使う場合は `Vec::reserve` などに渡して事前確保できる。
+    /// 見積もりがオーバーフローする場合は `usize::MAX` を返す。
+    pub fn encoded_len_hint(&self) -> usize {
+        estimate_request_capacity(self).unwrap_or(usize::MAX)
+    }
+
     /// リクエストをバイト列にエンコード
     ///
     /// 構築時バリデーションで弾かれる構文上の不正値を含まない Request でも、
@@ -834,6 +1021,16 @@ impl Request {
 }
 
 impl Response {
+    /// `encode()` の出力長の見積もり
+    ///
+    /// 実際の出力長以上の値を返す。自動付与する `Content-Length` の値を 20 桁で
+    /// 見積もるため、実際の出力長より最大 19 バイト大きくなる。`encode()` / `encode_into()` は内部でこの値を使って
+    /// 1 回だけ確保する。独自のバッファを使う場合は `Vec::reserve` などに渡して事前確保できる。
+    /// 見積もりがオーバーフローする場合は `usize::MAX` を返す。
+    pub fn encoded_len_hint(&self) -> usize {
+        estimate_response_capacity(self).unwrap_or(usize::MAX)
+    }
+
     /// レスポンスをバイト列にエンコード
     ///
     /// 構築時バリデーションで弾かれる構文上の不正値を含まない Response でも、
@@ -847,6 +1044,143 @@ impl Response {
     }
 }
 
+/// ヘッダー部とボディを分けたエンコード結果
+///
+/// `head` はスタートライン、ヘッダー、ヘッダー終端の空行を含むバイト列、
+/// `body` は `Request` / `Response` が保持するボディの借用である。
+/// `head` の後に `body` を続けて送信すると `encode()` と同じバイト列になる。
+///
+/// ボディをコピーしないため、大きなボディでも `write_vectored` 等の
+/// scatter-gather I/O にそのまま渡せる。
+///
+/// ```rust
+/// use shiguredo_http11::{Response, StatusCode};
+///
+/// let response = Response::with_status(StatusCode::OK).body(b"hello".to_vec());
+/// let parts = response.encode_vectored().unwrap();
+/// assert_eq!(parts.head(), b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n");
+/// assert_eq!(parts.body(), b"hello");
+/// assert_eq!(parts.to_vec(), response.encode().unwrap());
+///
+/// // std::io::IoSlice に変換して write_vectored に渡す
+/// let slices = parts.as_slices().map(std::io::IoSlice::new);
+/// assert_eq!(slices.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedParts<'a> {
+    head: Vec<u8>,
+    body: &'a [u8],
+}
+
+impl<'a> EncodedParts<'a> {
+    /// スタートライン、ヘッダー、ヘッダー終端の空行を取得
+    pub fn head(&self) -> &[u8] {
+        &self.head
+    }
+
+    /// ボディを取得 (ボディを送信しない場合は空)
+    pub fn body(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// `[head, body]` の順に並べたスライスを取得
+    pub fn as_slices(&self) -> [&[u8]; 2] {
+        [&self.head, self.body]
+    }
+
+    /// 合計バイト数を取得
+    pub fn len(&self) -> usize {
+        self.head.len() + self.body.len()
+    }
+
+    /// 合計バイト数が 0 かどうか
+    ///
+    /// `head` は常にスタートラインを含むため、実際には `false` のみを返す。
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// ヘッダー部を取り出す
+    pub fn into_head(self) -> Vec<u8> {
+        self.head
+    }
+
+    /// 連続したバイト列に結合する
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut buf = allocate_encode_buffer(self.head.len().checked_add(self.body.len()));
+        buf.extend_from_slice(&self.head);
+        buf.extend_from_slice(self.body);
+        buf
+    }
+}
+
+/// リクエストをヘッダー部とボディの借用に分けてエンコード
+///
+/// `encode_request` と同じ検証を行い、ボディをコピーせずに返す。
+pub fn encode_request_vectored(request: &Request) -> Result<EncodedParts<'_>, EncodeError> {
+    validate_request_for_encode(request)?;
+
+    let mut head = allocate_encode_buffer(estimate_request_head_capacity(request));
+    write_request_head(&mut head, request);
+
+    Ok(EncodedParts {
+        head,
+        body: request.body_bytes().unwrap_or(&[]),
+    })
+}
+
+/// レスポンスをヘッダー部とボディの借用に分けてエンコード
+///
+/// `encode_response` と同じ検証を行い、ボディをコピーせずに返す。
+/// 1xx / 204 / 304 と `omit_body(true)` の場合、ボディは空になる。
+pub fn encode_response_vectored(response: &Response) -> Result<EncodedParts<'_>, EncodeError> {
+    validate_response_for_encode(response)?;
+
+    let mut head = allocate_encode_buffer(estimate_response_head_capacity(response));
+    write_response_head(&mut head, response);
+
+    Ok(EncodedParts {
+        head,
+        body: response_body_to_encode(response),
+    })
+}
+
+impl Request {
+    /// リクエストをヘッダー部とボディの借用に分けてエンコード
+    ///
+    /// 詳細は `encode_request_vectored` を参照。
+    pub fn encode_vectored(&self) -> Result<EncodedParts<'_>, EncodeError> {
+        encode_request_vectored(self)
+    }
+}
+
+impl Response {
+    /// レスポンスをヘッダー部とボディの借用に分けてエンコード
+    ///
+    /// 詳細は `encode_response_vectored` を参照。
+    pub fn encode_vectored(&self) -> Result<EncodedParts<'_>, EncodeError> {
+        encode_response_vectored(self)
+    }
+}
+
+impl Request {
+    /// リクエストを `buf` の末尾にエンコード
+    ///
+    /// 詳細は `encode_request_into` を参照。
+    pub fn encode_into(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        encode_request_into(self, buf)
+    }
+}
+
+impl Response {
+    /// レスポンスを `buf` の末尾にエンコード
+    ///
+    /// 詳細は `encode_response_into` を参照。
+    pub fn encode_into(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        encode_response_into(self, buf)
+    }
+}
+
 /// Chunked Transfer Encoding 用のチャンクをエンコード
 ///
 /// データを HTTP chunked 形式にエンコードします。
@@ -878,6 +1212,48 @@ pub fn encode_chunk(data: &[u8]) -> Vec<u8> {
     buf
 }
 
+/// chunk-ext 付きのチャンクをエンコード
+///
+/// `ext` は chunk-size の直後に置くバイト列で、`;name=value` の形式を取る
+/// (RFC 9112 Section 7.1.1)。`ext` が空の場合は `encode_chunk` と同じ出力になる。
+/// 空のデータを渡すと chunk-ext 付きの終端チャンク (`0;ext\r\n\r\n`) を生成します。
+///
+/// `ResponseDecoder::chunk_ext()` 等で取得した値をそのまま渡して、
+/// チャンクの chunk-ext を保ったまま転送するために使う。
+///
+/// `ext` が chunk-ext の ABNF に違反する場合は `EncodeError::InvalidChunkExtension` を返す。
+pub fn encode_chunk_with_ext(data: &[u8], ext: &[u8]) -> Result<Vec<u8>, EncodeError> {
+    // 空でない chunk-ext は少なくとも 1 つの ";" を含む
+    // (BWS のみの場合、デコーダーは chunk-size 直後の空白を拒否する)
+    if (!ext.is_empty() && !ext.contains(&b';')) || validate_chunk_ext(ext).is_err() {
+        return Err(EncodeError::InvalidChunkExtension {
+            ext: String::from_utf8_lossy(ext).into_owned(),
+        });
+    }
+
+    let cap = data
+        .len()
+        .checked_add(20)
+        .and_then(|c| c.checked_add(ext.len()));
+    let mut buf = match cap {
+        Some(c) => Vec::with_capacity(c),
+        None => Vec::new(),
+    };
+
+    write_hex_usize(&mut buf, data.len());
+    buf.extend_from_slice(ext);
+    buf.extend_from_slice(b"\r\n");
+    if data.is_empty() {
+        // 終端チャンク (trailer-section なし)
+        buf.extend_from_slice(b"\r\n");
+        return Ok(buf);
+    }
+    buf.extend_from_slice(data);
+    buf.extend_from_slice(b"\r\n");
+
+    Ok(buf)
+}
+
 /// 複数のデータを chunked 形式でエンコード
 ///
 /// すべてのチャンクを結合し、終端チャンクも追加します。
@@ -922,6 +1298,18 @@ fn encode_chunks_capacity(chunks: &[&[u8]]) -> Option<usize> {
 /// RFC 9112 Section 3.2: HTTP/1.1 リクエストには Host ヘッダーが必須
 /// RFC 9112 Section 6.2: Transfer-Encoding と Content-Length は同時に送信してはならない
 pub fn encode_request_headers(request: &Request) -> Result<Vec<u8>, EncodeError> {
+    let mut buf = Vec::new();
+    encode_request_headers_into(request, &mut buf)?;
+    Ok(buf)
+}
+
+/// リクエストヘッダーのみを `buf` の末尾にエンコード (ボディなし)
+///
+/// `encode_request_headers` と同じ検証を行う。エラー時は `buf` を変更しない。
+pub fn encode_request_headers_into(
+    request: &Request,
+    buf: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
     // フィールドバリデーション
     validate_request_fields(request)?;
 
@@ -960,8 +1348,6 @@ pub fn encode_request_headers(request: &Request) -> Result<Vec<u8>, EncodeError>
     // encode_request_headers はボディを扱わないため、CONNECT 専用チェックは不要。
     // ヘッダーの有無による制約はアプリケーション層の責務とする。
 
-    let mut buf = Vec::new();
-
     // リクエスト行: METHOD SP URI SP VERSION CRLF
     buf.extend_from_slice(request.method().as_bytes());
     buf.push(b' ');
@@ -970,17 +1356,15 @@ pub fn encode_request_headers(request: &Request) -> Result<Vec<u8>, EncodeError>
     buf.extend_from_slice(request.version().as_bytes());
     buf.extend_from_slice(b"\r\n");
 
+    let case = request.header_case_policy();
     for (name, value) in HttpHead::headers(request) {
-        buf.extend_from_slice(name.as_bytes());
-        buf.extend_from_slice(b": ");
-        buf.extend_from_slice(value.as_bytes());
-        buf.extend_from_slice(b"\r\n");
+        write_header_line(buf, name, value.as_bytes(), case);
     }
 
     // ヘッダー終端の空行
     buf.extend_from_slice(b"\r\n");
 
-    Ok(buf)
+    Ok(())
 }
 
 /// レスポンスヘッダーのみをエンコード (ボディなし)
@@ -998,6 +1382,18 @@ pub fn encode_request_headers(request: &Request) -> Result<Vec<u8>, EncodeError>
 /// しかし、エンコーダーはリクエストメソッドの情報を持たないため、この制約は
 /// 呼び出し側アプリケーションの責務とする。
 pub fn encode_response_headers(response: &Response) -> Result<Vec<u8>, EncodeError> {
+    let mut buf = Vec::new();
+    encode_response_headers_into(response, &mut buf)?;
+    Ok(buf)
+}
+
+/// レスポンスヘッダーのみを `buf` の末尾にエンコード (ボディなし)
+///
+/// `encode_response_headers` と同じ検証を行う。エラー時は `buf` を変更しない。
+pub fn encode_response_headers_into(
+    response: &Response,
+    buf: &mut Vec<u8>,
+) -> Result<(), EncodeError> {
     // フィールドバリデーション
     validate_response_fields(response)?;
 
@@ -1053,27 +1449,25 @@ pub fn encode_response_headers(response: &Response) -> Result<Vec<u8>, EncodeErr
         }
     }
 
-    let mut buf = Vec::new();
-
     // ステータス行: VERSION SP STATUS-CODE SP REASON-PHRASE CRLF
     buf.extend_from_slice(HttpHead::version(response).as_bytes());
     buf.push(b' ');
-    write_usize_decimal(&mut buf, response.status_code() as usize);
+    write_usize_decimal(buf, response.status_code() as usize);
     buf.push(b' ');
     buf.extend_from_slice(response.reason_phrase().as_bytes());
     buf.extend_from_slice(b"\r\n");
 
+    let case = response.header_case_policy();
     for (name, value) in HttpHead::headers(response) {
-        buf.extend_from_slice(name.as_bytes());
-        buf.extend_from_slice(b": ");
-        buf.extend_from_slice(value.as_bytes());
-        buf.extend_from_slice(b"\r\n");
+        write_header_line(buf, name, value.as_bytes(), case);
     }
 
+    write_auto_date(buf, response);
+
     // ヘッダー終端の空行
     buf.extend_from_slice(b"\r\n");
 
-    Ok(buf)
+    Ok(())
 }
 
 impl Request {
@@ -1087,6 +1481,13 @@ impl Request {
     pub fn encode_headers(&self) -> Result<Vec<u8>, EncodeError> {
         encode_request_headers(self)
     }
+
+    /// ヘッダーのみを `buf` の末尾にエンコード
+    ///
+    /// 詳細は `encode_request_headers_into` を参照。
+    pub fn encode_headers_into(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        encode_request_headers_into(self, buf)
+    }
 }
 
 impl Response {
@@ -1099,8 +1500,109 @@ impl Response {
     pub fn encode_headers(&self) -> Result<Vec<u8>, EncodeError> {
         encode_response_headers(self)
     }
+
+    /// ヘッダーのみを `buf` の末尾にエンコード
+    ///
+    /// 詳細は `encode_response_headers_into` を参照。
+    pub fn encode_headers_into(&self, buf: &mut Vec<u8>) -> Result<(), EncodeError> {
+        encode_response_headers_into(self, buf)
+    }
+}
+
+/// `std::io::Write` への書き出し (`std` feature)
+///
+/// ボディは `EncodedParts` と同じくコピーせずに書き出す。
+/// エンコード時の検証エラーは `std::io::ErrorKind::InvalidInput` の `std::io::Error` に
+/// `EncodeError` を包んで返す (`get_ref()` / `into_inner()` で取り出せる)。
+/// 検証エラーの場合は何も書き込まない。
+#[cfg(feature = "std")]
+mod write {
+    use super::{
+        encode_request_headers, encode_request_vectored, encode_response_headers,
+        encode_response_vectored,
+    };
+    use crate::error::EncodeError;
+    use crate::request::Request;
+    use crate::response::Response;
+    use std::io;
+
+    fn invalid_input(e: EncodeError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+
+    /// リクエストをエンコードして `w` に書き出す
+    pub fn encode_request_to_writer<W: io::Write + ?Sized>(
+        request: &Request,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let parts = encode_request_vectored(request).map_err(invalid_input)?;
+        w.write_all(parts.head())?;
+        w.write_all(parts.body())
+    }
+
+    /// レスポンスをエンコードして `w` に書き出す
+    pub fn encode_response_to_writer<W: io::Write + ?Sized>(
+        response: &Response,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let parts = encode_response_vectored(response).map_err(invalid_input)?;
+        w.write_all(parts.head())?;
+        w.write_all(parts.body())
+    }
+
+    /// リクエストヘッダーのみをエンコードして `w` に書き出す
+    pub fn encode_request_headers_to_writer<W: io::Write + ?Sized>(
+        request: &Request,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let head = encode_request_headers(request).map_err(invalid_input)?;
+        w.write_all(&head)
+    }
+
+    /// レスポンスヘッダーのみをエンコードして `w` に書き出す
+    pub fn encode_response_headers_to_writer<W: io::Write + ?Sized>(
+        response: &Response,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let head = encode_response_headers(response).map_err(invalid_input)?;
+        w.write_all(&head)
+    }
+
+    impl Request {
+        /// リクエストをエンコードして `w` に書き出す
+        ///
+        /// 詳細は `encode_request_to_writer` を参照。
+        pub fn encode_to_writer<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+            encode_request_to_writer(self, w)
+        }
+
+        /// ヘッダーのみをエンコードして `w` に書き出す
+        pub fn encode_headers_to_writer<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+            encode_request_headers_to_writer(self, w)
+        }
+    }
+
+    impl Response {
+        /// レスポンスをエンコードして `w` に書き出す
+        ///
+        /// 詳細は `encode_response_to_writer` を参照。
+        pub fn encode_to_writer<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+            encode_response_to_writer(self, w)
+        }
+
+        /// ヘッダーのみをエンコードして `w` に書き出す
+        pub fn encode_headers_to_writer<W: io::Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+            encode_response_headers_to_writer(self, w)
+        }
+    }
 }
 
+#[cfg(feature = "std")]
+pub use write::{
+    encode_request_headers_to_writer, encode_request_to_writer, encode_response_headers_to_writer,
+    encode_response_to_writer,
+};
+
 /// レスポンスエンコーダー (圧縮対応)
 ///
 /// # 型パラメータ
@@ -1130,6 +1632,8 @@ impl Response {
 #[derive(Debug)]
 pub struct ResponseEncoder<C: Compressor = NoCompression> {
     compressor: C,
+    /// ワイヤーレベルのトレース
+    tracer: WireTracer,
 }
 
 impl Default for ResponseEncoder<NoCompression> {
@@ -1143,6 +1647,7 @@ impl ResponseEncoder<NoCompression> {
     pub fn new() -> Self {
         Self {
             compressor: NoCompression::new(),
+            tracer: WireTracer::default(),
         }
     }
 }
@@ -1150,7 +1655,42 @@ impl ResponseEncoder<NoCompression> {
 impl<C: Compressor> ResponseEncoder<C> {
     /// 圧縮器付きでエンコーダーを作成
     pub fn with_compressor(compressor: C) -> Self {
-        Self { compressor }
+        Self {
+            compressor,
+            tracer: WireTracer::default(),
+        }
+    }
+
+    /// ワイヤーレベルのイベントを受け取る observer を設定する
+    ///
+    /// [`encode()`](Self::encode) / [`encode_headers()`](Self::encode_headers) で
+    /// エンコードするたびに [`WireEvent::Encoded`] を通知する。
+    /// 設定済みの observer は置き換える。
+    pub fn set_wire_observer(&mut self, observer: Box<dyn WireObserver>) {
+        self.tracer.set(observer);
+    }
+
+    /// 設定した observer を取り外して返す
+    pub fn take_wire_observer(&mut self) -> Option<Box<dyn WireObserver>> {
+        self.tracer.take()
+    }
+
+    /// レスポンス全体をエンコード
+    ///
+    /// `encode_response` と同じ出力を返し、observer にエンコード結果を通知する。
+    pub fn encode(&mut self, response: &Response) -> Result<Vec<u8>, EncodeError> {
+        let buf = encode_response(response)?;
+        self.tracer.emit(WireEvent::Encoded { raw: &buf });
+        Ok(buf)
+    }
+
+    /// レスポンスヘッダーのみをエンコード (ボディなし)
+    ///
+    /// `encode_response_headers` と同じ出力を返し、observer にエンコード結果を通知する。
+    pub fn encode_headers(&mut self, response: &Response) -> Result<Vec<u8>, EncodeError> {
+        let buf = encode_response_headers(response)?;
+        self.tracer.emit(WireEvent::Encoded { raw: &buf });
+        Ok(buf)
     }
 
     /// ボディを圧縮（ストリーミング）
@@ -1219,6 +1759,8 @@ impl<C: Compressor> ResponseEncoder<C> {
 #[derive(Debug)]
 pub struct RequestEncoder<C: Compressor = NoCompression> {
     compressor: C,
+    /// ワイヤーレベルのトレース
+    tracer: WireTracer,
 }
 
 impl Default for RequestEncoder<NoCompression> {
@@ -1232,6 +1774,7 @@ impl RequestEncoder<NoCompression> {
     pub fn new() -> Self {
         Self {
             compressor: NoCompression::new(),
+            tracer: WireTracer::default(),
         }
     }
 }
@@ -1239,7 +1782,42 @@ impl RequestEncoder<NoCompression> {
 impl<C: Compressor> RequestEncoder<C> {
     /// 圧縮器付きでエンコーダーを作成
     pub fn with_compressor(compressor: C) -> Self {
-        Self { compressor }
+        Self {
+            compressor,
+            tracer: WireTracer::default(),
+        }
+    }
+
+    /// ワイヤーレベルのイベントを受け取る observer を設定する
+    ///
+    /// [`encode()`](Self::encode) / [`encode_headers()`](Self::encode_headers) で
+    /// エンコードするたびに [`WireEvent::Encoded`] を通知する。
+    /// 設定済みの observer は置き換える。
+    pub fn set_wire_observer(&mut self, observer: Box<dyn WireObserver>) {
+        self.tracer.set(observer);
+    }
+
+    /// 設定した observer を取り外して返す
+    pub fn take_wire_observer(&mut self) -> Option<Box<dyn WireObserver>> {
+        self.tracer.take()
+    }
+
+    /// リクエスト全体をエンコード
+    ///
+    /// `encode_request` と同じ出力を返し、observer にエンコード結果を通知する。
+    pub fn encode(&mut self, request: &Request) -> Result<Vec<u8>, EncodeError> {
+        let buf = encode_request(request)?;
+        self.tracer.emit(WireEvent::Encoded { raw: &buf });
+        Ok(buf)
+    }
+
+    /// リクエストヘッダーのみをエンコード (ボディなし)
+    ///
+    /// `encode_request_headers` と同じ出力を返し、observer にエンコード結果を通知する。
+    pub fn encode_headers(&mut self, request: &Request) -> Result<Vec<u8>, EncodeError> {
+        let buf = encode_request_headers(request)?;
+        self.tracer.emit(WireEvent::Encoded { raw: &buf });
+        Ok(buf)
     }
 
     /// ボディを圧縮（ストリーミング）
@@ -2,13 +2,68 @@ use alloc::string::String;
 use core::fmt;
 
 use crate::compression::CompressionError;
+use crate::status_code::StatusCode;
+
+/// `Error::Syntax` に保持する行の最大バイト数
+///
+/// 巨大な不正行をそのままエラーに複製してメモリを浪費しないよう、
+/// 先頭からこのバイト数までに切り詰める。
+const MAX_ERROR_LINE_LEN: usize = 64;
+
+/// デコードエラーが発生したパース段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParsePhase {
+    /// リクエスト行 / ステータス行 (RFC 9112 Section 3 / Section 4)
+    StartLine,
+    /// ヘッダーセクション (RFC 9112 Section 5)
+    Header,
+    /// chunked のチャンクサイズ行 (RFC 9112 Section 7.1)
+    ChunkSize,
+    /// chunked のチャンクデータ直後の CRLF (RFC 9112 Section 7.1)
+    ChunkData,
+    /// chunked のトレーラーセクション (RFC 9112 Section 7.1.2)
+    Trailer,
+}
+
+impl fmt::Display for ParsePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ParsePhase::StartLine => "start-line",
+            ParsePhase::Header => "header",
+            ParsePhase::ChunkSize => "chunk-size",
+            ParsePhase::ChunkData => "chunk-data",
+            ParsePhase::Trailer => "trailer",
+        };
+        f.write_str(s)
+    }
+}
 
 /// HTTP パースエラー
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Error {
     /// 不正なデータ
+    ///
+    /// API の誤用 (ストリーミング API と `decode()` の混在等) や、受信バイト列の
+    /// 位置に紐付かない検証エラーに使う。受信バイト列の構文エラーは
+    /// [`Error::Syntax`] で報告する。
     InvalidData(String),
+    /// 受信バイト列の構文エラー
+    ///
+    /// - `phase`: エラーが発生したパース段階
+    /// - `offset`: 問題の行の先頭の絶対バイトオフセット
+    ///   (デコーダー作成または `reset()` 以降に投入された先頭バイトを 0 とする)
+    /// - `line`: 問題の行 (CRLF を除く、先頭 64 バイトまでに切り詰め、非 UTF-8 は置換文字)。
+    ///   ヘッダーセクション終端での検証エラー (Host 欠落等) のように特定の行に
+    ///   紐付かない場合は空文字列
+    /// - `reason`: エラー内容
+    Syntax {
+        phase: ParsePhase,
+        offset: u64,
+        line: String,
+        reason: String,
+    },
     /// バッファサイズ超過
     BufferOverflow { size: usize, limit: usize },
     /// ヘッダー数超過
@@ -19,6 +74,16 @@ pub enum Error {
     BodyTooLarge { size: usize, limit: usize },
     /// チャンクサイズ行が長すぎる
     ChunkLineTooLong { size: usize, limit: usize },
+    /// request-target が長すぎる
+    ///
+    /// request-line の受信途中で検出した場合、`size` は検出時点までに受信した
+    /// request-target のバイト数 (上限 + 1 以上) になる。
+    UriTooLong { size: usize, limit: usize },
+    /// メソッドが長すぎる
+    ///
+    /// request-line の受信途中で検出した場合、`size` は検出時点までに受信した
+    /// メソッドのバイト数 (上限 + 1 以上) になる。
+    MethodTooLong { size: usize, limit: usize },
     /// 圧縮/展開エラー
     Compression(CompressionError),
 }
@@ -27,6 +92,22 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::InvalidData(msg) => write!(f, "invalid data: {}", msg),
+            Error::Syntax {
+                phase,
+                offset,
+                line,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "syntax error in {} at offset {}: {}",
+                    phase, offset, reason
+                )?;
+                if !line.is_empty() {
+                    write!(f, " (line: {:?})", line)?;
+                }
+                Ok(())
+            }
             Error::BufferOverflow { size, limit } => {
                 write!(f, "buffer overflow: {} > {}", size, limit)
             }
@@ -42,6 +123,12 @@ impl fmt::Display for Error {
             Error::ChunkLineTooLong { size, limit } => {
                 write!(f, "chunk line too long: {} > {}", size, limit)
             }
+            Error::UriTooLong { size, limit } => {
+                write!(f, "request-target too long: {} > {}", size, limit)
+            }
+            Error::MethodTooLong { size, limit } => {
+                write!(f, "method too long: {} > {}", size, limit)
+            }
             Error::Compression(e) => write!(f, "compression error: {}", e),
         }
     }
@@ -49,6 +136,72 @@ impl fmt::Display for Error {
 
 impl core::error::Error for Error {}
 
+impl Error {
+    /// 受信バイト列の構文エラーを作成する
+    pub(crate) fn syntax(
+        phase: ParsePhase,
+        offset: u64,
+        line: &[u8],
+        reason: impl Into<String>,
+    ) -> Self {
+        Error::Syntax {
+            phase,
+            offset,
+            line: truncate_line(line),
+            reason: reason.into(),
+        }
+    }
+
+    /// `InvalidData` をパース段階と位置情報付きの `Syntax` に変換する
+    ///
+    /// `InvalidData` 以外 (上限超過等) はそのまま返す。
+    pub(crate) fn with_context(self, phase: ParsePhase, offset: u64, line: &[u8]) -> Self {
+        match self {
+            Error::InvalidData(reason) => Error::syntax(phase, offset, line, reason),
+            other => other,
+        }
+    }
+
+    /// サーバーがこのエラーに対して返すべきステータスコードの目安を返す
+    ///
+    /// `RequestDecoder` のエラーを 4xx レスポンスに変換する用途を想定している。
+    ///
+    /// - `BodyTooLarge`: 413 Content Too Large (RFC 9110 Section 15.5.14)
+    /// - `UriTooLong`: 414 URI Too Long (RFC 9110 Section 15.5.15)
+    /// - `MethodTooLong`: 501 Not Implemented (RFC 9112 Section 3)
+    /// - `BufferOverflow` / `TooManyHeaders` / `HeaderLineTooLong`:
+    ///   431 Request Header Fields Too Large (RFC 6585 Section 5)
+    /// - それ以外: 400 Bad Request (RFC 9110 Section 15.5.1)
+    ///
+    /// `BufferOverflow` は内部バッファに収まらない入力で発生する。ボディは
+    /// ストリーミングで消費される前提のため、ヘッダーセクションが巨大な場合として 431 を返す。
+    #[must_use]
+    pub fn suggested_status(&self) -> StatusCode {
+        match self {
+            Error::BodyTooLarge { .. } => StatusCode::CONTENT_TOO_LARGE,
+            Error::UriTooLong { .. } => StatusCode::URI_TOO_LONG,
+            Error::MethodTooLong { .. } => StatusCode::NOT_IMPLEMENTED,
+            Error::BufferOverflow { .. }
+            | Error::TooManyHeaders { .. }
+            | Error::HeaderLineTooLong { .. } => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+            Error::InvalidData(_)
+            | Error::Syntax { .. }
+            | Error::ChunkLineTooLong { .. }
+            | Error::Compression(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// エラーに保持する行を `MAX_ERROR_LINE_LEN` バイトまでに切り詰めて文字列化する
+fn truncate_line(line: &[u8]) -> String {
+    let end = line.len().min(MAX_ERROR_LINE_LEN);
+    let mut s = String::from_utf8_lossy(&line[..end]).into_owned();
+    if line.len() > MAX_ERROR_LINE_LEN {
+        s.push_str("...");
+    }
+    s
+}
+
 impl From<CompressionError> for Error {
     fn from(e: CompressionError) -> Self {
         Error::Compression(e)
@@ -115,6 +268,16 @@ pub enum EncodeError {
     /// Content-Length ヘッダーが複数存在し、値が不一致
     /// RFC 9110 Section 8.6 / RFC 9112 Section 6.3
     DuplicateContentLength,
+    /// 中間レスポンスとして 1xx (101 を除く) 以外のレスポンスが指定された
+    /// RFC 9110 Section 15.2: 中間レスポンスは最終レスポンスの前に送信される 1xx
+    InvalidInterimResponse { status_code: u16 },
+    /// 1xx レスポンスにボディが含まれている
+    /// RFC 9110 Section 15.2: 1xx レスポンスはヘッダー部の後の空行で終了し、content を持たない
+    ForbiddenBodyForInformational { status_code: u16 },
+    /// 最終レスポンスとして 1xx (101 を除く) が指定された
+    InvalidFinalResponse { status_code: u16 },
+    /// chunk-ext が RFC 9112 Section 7.1.1 の ABNF に違反
+    InvalidChunkExtension { ext: String },
 }
 
 impl fmt::Display for EncodeError {
@@ -234,6 +397,30 @@ impl fmt::Display for EncodeError {
                     "duplicate Content-Length headers with mismatched values (RFC 9110 Section 8.6)"
                 )
             }
+            EncodeError::InvalidInterimResponse { status_code } => {
+                write!(
+                    f,
+                    "{} response cannot be sent as an interim response (RFC 9110 Section 15.2)",
+                    status_code
+                )
+            }
+            EncodeError::ForbiddenBodyForInformational { status_code } => {
+                write!(
+                    f,
+                    "{} response must not contain a body (RFC 9110 Section 15.2)",
+                    status_code
+                )
+            }
+            EncodeError::InvalidFinalResponse { status_code } => {
+                write!(
+                    f,
+                    "{} response cannot be sent as a final response (RFC 9110 Section 15.2)",
+                    status_code
+                )
+            }
+            EncodeError::InvalidChunkExtension { ext } => {
+                write!(f, "invalid chunk-ext {:?} (RFC 9112 Section 7.1.1)", ext)
+            }
         }
     }
 }
@@ -18,12 +18,23 @@
 //! let etag = EntityTag::parse("W/\"abc123\"").unwrap();
 //! assert!(etag.is_weak());
 //! assert_eq!(etag.tag(), "abc123");
+//!
+//! // If-None-Match の判定 (Weak 比較)
+//! use shiguredo_http11::etag::if_none_match_matches;
+//! assert!(if_none_match_matches("\"abc123\", \"def\"", Some(&etag)));
+//!
+//! // コンテンツから ETag を生成
+//! let etag = EntityTag::from_content(b"<html></html>");
+//! assert!(etag.is_strong());
 //! ```
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::sha256::Sha256;
+use crate::typed_header::TypedHeader;
+
 /// ETag パースエラー
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -173,6 +184,140 @@ impl EntityTag {
     pub fn weak_compare(&self, other: &EntityTag) -> bool {
         self.tag == other.tag
     }
+
+    /// コンテンツから Strong ETag を生成
+    ///
+    /// タグ値は SHA-256 の先頭 16 バイトを小文字 16 進数で表現した 32 文字。
+    /// 同じバイト列からは常に同じ ETag が得られる。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::etag::EntityTag;
+    ///
+    /// let etag = EntityTag::from_content(b"hello");
+    /// assert!(etag.is_strong());
+    /// assert_eq!(etag.tag().len(), 32);
+    /// assert_eq!(etag, EntityTag::from_content(b"hello"));
+    /// ```
+    pub fn from_content(content: &[u8]) -> Self {
+        let mut hasher = ETagHasher::new();
+        hasher.update(content);
+        hasher.finish()
+    }
+
+    /// コンテンツから Weak ETag を生成
+    ///
+    /// タグ値は [`EntityTag::from_content`] と同じ
+    pub fn weak_from_content(content: &[u8]) -> Self {
+        let mut hasher = ETagHasher::new();
+        hasher.update(content);
+        hasher.finish_weak()
+    }
+
+    /// サイズと更新日時から Weak ETag を生成
+    ///
+    /// タグ値は `{size:x}-{modified:x}` (小文字 16 進数) 形式。
+    /// `modified` は UNIX 時刻 (秒) などの単調な値を想定する。
+    ///
+    /// 同じ秒内の更新や同サイズでの書き換えを区別できないため、
+    /// RFC 9110 Section 8.8.1 に従い既定では Weak とする。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::etag::EntityTag;
+    ///
+    /// let etag = EntityTag::from_metadata(1024, 1_700_000_000);
+    /// assert!(etag.is_weak());
+    /// assert_eq!(etag.to_string(), "W/\"400-6553f100\"");
+    /// ```
+    pub fn from_metadata(size: u64, modified: u64) -> Self {
+        EntityTag {
+            weak: true,
+            tag: metadata_tag(size, modified),
+        }
+    }
+
+    /// サイズと更新日時から Strong ETag を生成
+    ///
+    /// 更新日時の分解能内で内容が変わらないことをアプリケーションが保証できる場合のみ使う
+    pub fn strong_from_metadata(size: u64, modified: u64) -> Self {
+        EntityTag {
+            weak: false,
+            tag: metadata_tag(size, modified),
+        }
+    }
+}
+
+/// メタデータ ETag のタグ値を生成
+fn metadata_tag(size: u64, modified: u64) -> String {
+    alloc::format!("{:x}-{:x}", size, modified)
+}
+
+/// ストリーミングで ETag を計算するハッシャー
+///
+/// 大きなファイルをメモリに載せずに、チャンクごとに投入して ETag を計算する。
+/// 結果は同じバイト列に対する [`EntityTag::from_content`] と一致する。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::etag::{ETagHasher, EntityTag};
+///
+/// let mut hasher = ETagHasher::new();
+/// hasher.update(b"hel").update(b"lo");
+/// assert_eq!(hasher.finish(), EntityTag::from_content(b"hello"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ETagHasher {
+    sha: Sha256,
+}
+
+impl ETagHasher {
+    /// 新しいハッシャーを作成
+    pub fn new() -> Self {
+        ETagHasher { sha: Sha256::new() }
+    }
+
+    /// データを追加
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.sha.update(data);
+        self
+    }
+
+    /// Strong ETag を生成
+    pub fn finish(self) -> EntityTag {
+        EntityTag {
+            weak: false,
+            tag: self.hex_tag(),
+        }
+    }
+
+    /// Weak ETag を生成
+    pub fn finish_weak(self) -> EntityTag {
+        EntityTag {
+            weak: true,
+            tag: self.hex_tag(),
+        }
+    }
+
+    fn hex_tag(self) -> String {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let digest = self.sha.finalize();
+        let mut tag = String::with_capacity(32);
+        for b in &digest[..16] {
+            tag.push(HEX[(b >> 4) as usize] as char);
+            tag.push(HEX[(b & 0x0f) as usize] as char);
+        }
+        tag
+    }
+}
+
+impl Default for ETagHasher {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl fmt::Display for EntityTag {
@@ -185,6 +330,19 @@ impl fmt::Display for EntityTag {
     }
 }
 
+impl TypedHeader for EntityTag {
+    const NAME: &'static str = "ETag";
+    type Error = ETagError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// etagc 文字 (RFC 9110)
 /// %x21 / %x23-7E / obs-text
 fn is_etagc(b: u8) -> bool {
@@ -244,6 +402,58 @@ pub fn parse_etag_list(input: &str) -> Result<ETagList, ETagError> {
     Ok(ETagList::Tags(etags))
 }
 
+/// If-None-Match のフィールド値が現在の表現の ETag と一致するか (RFC 9110 Section 13.1.2)
+///
+/// Weak 比較で判定し、`*` は現在の表現がある場合に一致する。
+/// `current_etag` は現在の表現の ETag で、表現が存在しない場合は `None` を渡す。
+///
+/// 一致する場合 (true)、GET / HEAD には 304 Not Modified を、それ以外のメソッドには
+/// 412 Precondition Failed を返す。フィールド値が不正な場合は条件を無視して false を返す。
+/// 複数行ある場合は `", "` で結合した値を渡すこと。
+///
+/// ```rust
+/// use shiguredo_http11::etag::{EntityTag, if_none_match_matches};
+///
+/// let current = EntityTag::strong("v1").unwrap();
+/// assert!(if_none_match_matches("W/\"v1\", \"v2\"", Some(&current)));
+/// assert!(!if_none_match_matches("\"v2\"", Some(&current)));
+/// assert!(if_none_match_matches("*", Some(&current)));
+/// assert!(!if_none_match_matches("*", None));
+/// ```
+pub fn if_none_match_matches(field_value: &str, current_etag: Option<&EntityTag>) -> bool {
+    match (parse_etag_list(field_value), current_etag) {
+        (Ok(ETagList::Any), current) => current.is_some(),
+        (Ok(list), Some(current)) => list.contains_weak(current),
+        _ => false,
+    }
+}
+
+/// If-Match のフィールド値が現在の表現の ETag と一致するか (RFC 9110 Section 13.1.1)
+///
+/// Strong 比較で判定するため、Weak ETag はどちら側にあっても一致しない。
+/// `*` は現在の表現がある場合に一致する。
+/// `current_etag` は現在の表現の ETag で、表現が存在しない場合は `None` を渡す。
+///
+/// 一致しない場合 (false) は 412 Precondition Failed を返す。
+/// フィールド値が不正な場合も一致しないものとして false を返す。
+/// 複数行ある場合は `", "` で結合した値を渡すこと。
+///
+/// ```rust
+/// use shiguredo_http11::etag::{EntityTag, if_match_matches};
+///
+/// let current = EntityTag::strong("v1").unwrap();
+/// assert!(if_match_matches("\"v0\", \"v1\"", Some(&current)));
+/// assert!(!if_match_matches("W/\"v1\"", Some(&current)));
+/// assert!(!if_match_matches("*", None));
+/// ```
+pub fn if_match_matches(field_value: &str, current_etag: Option<&EntityTag>) -> bool {
+    match (parse_etag_list(field_value), current_etag) {
+        (Ok(ETagList::Any), current) => current.is_some(),
+        (Ok(list), Some(current)) => list.contains_strong(current),
+        _ => false,
+    }
+}
+
 /// ETag リスト
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ETagList {
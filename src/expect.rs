@@ -26,15 +26,45 @@
 //! let expect = Expect::parse("100-continue").unwrap();
 //! assert!(expect.has_100_continue());
 //! ```
+//!
+//! ## サーバーでの判定
+//!
+//! RFC 9110 Section 10.1.1: 100-continue 以外の expectation を受け取ったサーバーは
+//! 417 (Expectation Failed) を返してよい (MAY)。
+//!
+//! ```rust
+//! use shiguredo_http11::Request;
+//! use shiguredo_http11::expect::{ExpectOutcome, evaluate};
+//!
+//! let request = Request::new("PUT", "/upload")
+//!     .unwrap()
+//!     .header("Expect", "100-continue")
+//!     .unwrap();
+//! assert_eq!(evaluate(&request), ExpectOutcome::Continue);
+//!
+//! let request = Request::new("PUT", "/upload")
+//!     .unwrap()
+//!     .header("Expect", "x-unknown")
+//!     .unwrap();
+//! assert_eq!(evaluate(&request), ExpectOutcome::Reject);
+//! // 417 Expectation Failed を返す
+//! let response = shiguredo_http11::Response::expectation_failed();
+//! assert_eq!(response.status_code(), 417);
+//! ```
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::decoder::HttpHead;
+use crate::response::Response;
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
 use crate::validate::{
     QuotedStringError, escape_quotes, is_token_char, is_valid_token, parse_quoted_string,
     split_with_quotes,
 };
+use crate::version::HttpVersion;
 
 /// Expect パースエラー
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -133,6 +163,66 @@ impl Expect {
             .iter()
             .any(|item| item.token.eq_ignore_ascii_case("100-continue"))
     }
+
+    /// 本実装が理解しない expectation 一覧
+    ///
+    /// RFC 9110 で定義されている expectation は値を持たない 100-continue のみのため、
+    /// それ以外 (値付きの `100-continue=...` を含む) をすべて返す。
+    pub fn unknown(&self) -> impl Iterator<Item = &Expectation> {
+        self.items.iter().filter(|item| !item.is_known())
+    }
+
+    /// 本実装が理解しない expectation を含むかどうか
+    pub fn has_unknown(&self) -> bool {
+        self.unknown().next().is_some()
+    }
+}
+
+/// Expect ヘッダーの判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectOutcome {
+    /// expectation がない (または無視する)
+    None,
+    /// 100-continue を受け取った。ボディを読む前に 100 (Continue) を返す
+    Continue,
+    /// 理解しない expectation を受け取った。417 (Expectation Failed) を返す
+    Reject,
+}
+
+/// リクエストの Expect ヘッダーを判定
+///
+/// - Expect ヘッダーがない、または空の場合は `None`
+/// - パースできない、または 100-continue 以外の expectation を含む場合は `Reject`
+/// - 100-continue のみの場合は `Continue`
+///
+/// RFC 9110 Section 10.1.1: HTTP/1.0 のリクエストの 100-continue は
+/// 無視しなければならない (MUST) ため `None` を返す。
+pub fn evaluate<H: HttpHead>(request: &H) -> ExpectOutcome {
+    let values = request.get_headers("Expect");
+    if values.is_empty() {
+        return ExpectOutcome::None;
+    }
+    let Ok(expect) = Expect::parse(&values.join(", ")) else {
+        return ExpectOutcome::Reject;
+    };
+    if expect.has_unknown() {
+        ExpectOutcome::Reject
+    } else if expect.has_100_continue() && request.http_version() != HttpVersion::Http10 {
+        ExpectOutcome::Continue
+    } else {
+        ExpectOutcome::None
+    }
+}
+
+impl Response {
+    /// 417 (Expectation Failed) レスポンスを作成
+    ///
+    /// RFC 9110 Section 15.5.18: Expect ヘッダーの expectation を満たせない場合に返す。
+    /// ボディを読まずに返す場合、接続を閉じるか、ボディを読み捨てて再利用するかは
+    /// 呼び出し側が決める (RFC 9110 Section 10.1.1)。
+    pub fn expectation_failed() -> Self {
+        Response::with_status(StatusCode::EXPECTATION_FAILED)
+    }
 }
 
 impl fmt::Display for Expect {
@@ -142,6 +232,19 @@ impl fmt::Display for Expect {
     }
 }
 
+impl TypedHeader for Expect {
+    const NAME: &'static str = "Expect";
+    type Error = ExpectError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Expectation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Expectation {
@@ -164,6 +267,11 @@ impl Expectation {
     pub fn is_100_continue(&self) -> bool {
         self.token.eq_ignore_ascii_case("100-continue")
     }
+
+    /// 本実装が理解する expectation (値のない 100-continue) かどうか
+    pub fn is_known(&self) -> bool {
+        self.is_100_continue() && self.value.is_none()
+    }
 }
 
 impl fmt::Display for Expectation {
@@ -0,0 +1,197 @@
+//! 拡張パラメータ値 (RFC 8187)
+//!
+//! ## 概要
+//!
+//! `filename*` / `title*` / `username*` のような拡張パラメータの値 (ext-value) の
+//! パースと生成を提供します。
+//!
+//! ```text
+//! ext-value   = charset  "'" [ language ] "'" value-chars
+//! charset     = "UTF-8" / mime-charset
+//! value-chars = *( pct-encoded / attr-char )
+//! ```
+//!
+//! charset は UTF-8 のみサポートする (RFC 8187 Section 3.2.1: 生成側は UTF-8 を
+//! 使わなければならない)。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::ext_value::ExtValue;
+//!
+//! let ext = ExtValue::parse("UTF-8'ja'%E6%97%A5%E6%9C%AC%E8%AA%9E.txt").unwrap();
+//! assert_eq!(ext.value(), "日本語.txt");
+//! assert_eq!(ext.language(), Some("ja"));
+//!
+//! let ext = ExtValue::new("€ rates");
+//! assert_eq!(ext.to_string(), "UTF-8''%E2%82%AC%20rates");
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::validate::is_valid_language_tag;
+
+/// ext-value パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExtValueError {
+    /// `charset'language'value-chars` の形式ではない
+    InvalidFormat,
+    /// UTF-8 以外の charset
+    UnsupportedCharset,
+    /// 不正な言語タグ
+    InvalidLanguage,
+    /// 不正なパーセントエンコーディング、または attr-char 以外の文字
+    InvalidEncoding,
+    /// デコード結果が UTF-8 として不正
+    InvalidUtf8,
+}
+
+impl fmt::Display for ExtValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtValueError::InvalidFormat => write!(f, "invalid ext-value format"),
+            ExtValueError::UnsupportedCharset => write!(f, "unsupported ext-value charset"),
+            ExtValueError::InvalidLanguage => write!(f, "invalid ext-value language tag"),
+            ExtValueError::InvalidEncoding => write!(f, "invalid ext-value percent-encoding"),
+            ExtValueError::InvalidUtf8 => write!(f, "invalid UTF-8 in ext-value"),
+        }
+    }
+}
+
+impl core::error::Error for ExtValueError {}
+
+/// 拡張パラメータ値 (RFC 8187 Section 3.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtValue {
+    /// 言語タグ
+    language: Option<String>,
+    /// デコード済みの値
+    value: String,
+}
+
+impl ExtValue {
+    /// 値から ExtValue を作成
+    pub fn new(value: &str) -> Self {
+        ExtValue {
+            language: None,
+            value: value.to_string(),
+        }
+    }
+
+    /// 言語タグを設定
+    ///
+    /// 言語タグが不正な場合は `ExtValueError::InvalidLanguage` を返す。
+    pub fn with_language(mut self, language: &str) -> Result<Self, ExtValueError> {
+        if !is_valid_language_tag(language) {
+            return Err(ExtValueError::InvalidLanguage);
+        }
+        self.language = Some(language.to_string());
+        Ok(self)
+    }
+
+    /// ext-value をパース
+    ///
+    /// charset は大文字小文字を区別せず UTF-8 のみ受け付ける。
+    /// 言語タグは省略できる。
+    pub fn parse(input: &str) -> Result<Self, ExtValueError> {
+        let input = input.trim();
+
+        let (charset, rest) = input.split_once('\'').ok_or(ExtValueError::InvalidFormat)?;
+        let (language, value_chars) = rest.split_once('\'').ok_or(ExtValueError::InvalidFormat)?;
+
+        if !charset.eq_ignore_ascii_case("UTF-8") {
+            return Err(ExtValueError::UnsupportedCharset);
+        }
+        let language = if language.is_empty() {
+            None
+        } else if is_valid_language_tag(language) {
+            Some(language.to_string())
+        } else {
+            return Err(ExtValueError::InvalidLanguage);
+        };
+
+        Ok(ExtValue {
+            language,
+            value: percent_decode(value_chars)?,
+        })
+    }
+
+    /// デコード済みの値を取得
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// 言語タグを取得
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /// デコード済みの値を取り出す
+    pub fn into_value(self) -> String {
+        self.value
+    }
+}
+
+impl fmt::Display for ExtValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "UTF-8'{}'", self.language.as_deref().unwrap_or(""))?;
+        for byte in self.value.bytes() {
+            if is_attr_char(byte) {
+                write!(f, "{}", byte as char)?;
+            } else {
+                write!(f, "%{:02X}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// value-chars をパーセントデコード
+fn percent_decode(input: &str) -> Result<String, ExtValueError> {
+    let bytes = input.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .ok_or(ExtValueError::InvalidEncoding)?;
+            let hi = hex_value(hex[0]).ok_or(ExtValueError::InvalidEncoding)?;
+            let lo = hex_value(hex[1]).ok_or(ExtValueError::InvalidEncoding)?;
+            result.push((hi << 4) | lo);
+            i += 3;
+        } else if is_attr_char(b) {
+            result.push(b);
+            i += 1;
+        } else {
+            return Err(ExtValueError::InvalidEncoding);
+        }
+    }
+    String::from_utf8(result).map_err(|_| ExtValueError::InvalidUtf8)
+}
+
+/// HEXDIG の値
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        _ => None,
+    }
+}
+
+/// RFC 8187 Section 3.2.1: attr-char
+///
+/// attr-char = ALPHA / DIGIT / "!" / "#" / "$" / "&" / "+" / "-" / "." /
+///             "^" / "_" / "`" / "|" / "~"
+fn is_attr_char(b: u8) -> bool {
+    matches!(b,
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' |
+        b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' |
+        b'^' | b'_' | b'`' | b'|' | b'~'
+    )
+}
@@ -0,0 +1,574 @@
+//! Fetch Metadata リクエストヘッダー (W3C Fetch Metadata Request Headers)
+//!
+//! ## 概要
+//!
+//! ブラウザが付与する Sec-Fetch-Site / Sec-Fetch-Mode / Sec-Fetch-Dest / Sec-Fetch-User の
+//! パースと生成、およびそれらを使ったリソース分離ポリシーの判定を提供します。
+//!
+//! - Sec-Fetch-Site: リクエストの発生元とリクエスト先の関係 (`same-origin` / `cross-site` など)
+//! - Sec-Fetch-Mode: リクエストのモード (`navigate` / `no-cors` / `cors` など)
+//! - Sec-Fetch-Dest: リクエストの用途 (`document` / `image` / `script` など)
+//! - Sec-Fetch-User: ユーザー操作によるナビゲーションかどうか
+//!
+//! いずれも Structured Field の Item (Sec-Fetch-User は Boolean、それ以外は Token) として送られる。
+//! `FetchMetadata::from_request()` は不正な値や未知の値を無視する。
+//!
+//! `ResourceIsolationPolicy` はクロスサイトからのリクエストを拒否する
+//! リソース分離ポリシーを判定する。Sec-Fetch-Site を送らないブラウザからの
+//! リクエストは常に許可する。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Sec-Fetch-Site = sf-token   ; cross-site / same-origin / same-site / none
+//! Sec-Fetch-Mode = sf-token   ; cors / navigate / no-cors / same-origin / websocket
+//! Sec-Fetch-Dest = sf-token   ; audio / document / empty / image / script など
+//! Sec-Fetch-User = sf-boolean ; ?1 のみ送られる
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::fetch_metadata::{FetchMetadata, FetchSite, ResourceIsolationPolicy};
+//! use shiguredo_http11::Request;
+//!
+//! let request = Request::new("POST", "/transfer")
+//!     .unwrap()
+//!     .header("Sec-Fetch-Site", "cross-site")
+//!     .unwrap()
+//!     .header("Sec-Fetch-Mode", "no-cors")
+//!     .unwrap()
+//!     .header("Sec-Fetch-Dest", "image")
+//!     .unwrap();
+//!
+//! let metadata = FetchMetadata::from_request(&request);
+//! assert_eq!(metadata.site(), Some(FetchSite::CrossSite));
+//!
+//! let policy = ResourceIsolationPolicy::new();
+//! assert!(!policy.evaluate(request.method(), &metadata).is_allowed());
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::method::Method;
+use crate::sfv::Item;
+use crate::typed_header::TypedHeader;
+
+/// Fetch Metadata パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FetchMetadataError {
+    /// Structured Field として不正な形式
+    InvalidFormat,
+    /// 未知の値
+    UnknownValue,
+}
+
+impl fmt::Display for FetchMetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchMetadataError::InvalidFormat => write!(f, "invalid fetch metadata header format"),
+            FetchMetadataError::UnknownValue => write!(f, "unknown fetch metadata value"),
+        }
+    }
+}
+
+impl core::error::Error for FetchMetadataError {}
+
+/// sf-token の値を取り出す (パラメーターは無視する)
+fn parse_token(input: &str) -> Result<String, FetchMetadataError> {
+    let item = Item::parse(input).map_err(|_| FetchMetadataError::InvalidFormat)?;
+    item.bare_item()
+        .as_token()
+        .map(ToString::to_string)
+        .ok_or(FetchMetadataError::InvalidFormat)
+}
+
+// ========================================
+// Sec-Fetch-Site
+// ========================================
+
+/// Sec-Fetch-Site ヘッダー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchSite {
+    /// `cross-site`
+    CrossSite,
+    /// `same-origin`
+    SameOrigin,
+    /// `same-site`
+    SameSite,
+    /// `none` (アドレスバーへの入力などユーザーが直接起こしたリクエスト)
+    None,
+}
+
+impl FetchSite {
+    /// Sec-Fetch-Site をパース
+    pub fn parse(input: &str) -> Result<Self, FetchMetadataError> {
+        match parse_token(input)?.as_str() {
+            "cross-site" => Ok(FetchSite::CrossSite),
+            "same-origin" => Ok(FetchSite::SameOrigin),
+            "same-site" => Ok(FetchSite::SameSite),
+            "none" => Ok(FetchSite::None),
+            _ => Err(FetchMetadataError::UnknownValue),
+        }
+    }
+
+    /// 値の文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FetchSite::CrossSite => "cross-site",
+            FetchSite::SameOrigin => "same-origin",
+            FetchSite::SameSite => "same-site",
+            FetchSite::None => "none",
+        }
+    }
+}
+
+impl fmt::Display for FetchSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TypedHeader for FetchSite {
+    const NAME: &'static str = "Sec-Fetch-Site";
+    type Error = FetchMetadataError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// Sec-Fetch-Mode
+// ========================================
+
+/// Sec-Fetch-Mode ヘッダー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchMode {
+    /// `cors`
+    Cors,
+    /// `navigate`
+    Navigate,
+    /// `no-cors`
+    NoCors,
+    /// `same-origin`
+    SameOrigin,
+    /// `websocket`
+    WebSocket,
+}
+
+impl FetchMode {
+    /// Sec-Fetch-Mode をパース
+    pub fn parse(input: &str) -> Result<Self, FetchMetadataError> {
+        match parse_token(input)?.as_str() {
+            "cors" => Ok(FetchMode::Cors),
+            "navigate" => Ok(FetchMode::Navigate),
+            "no-cors" => Ok(FetchMode::NoCors),
+            "same-origin" => Ok(FetchMode::SameOrigin),
+            "websocket" => Ok(FetchMode::WebSocket),
+            _ => Err(FetchMetadataError::UnknownValue),
+        }
+    }
+
+    /// 値の文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FetchMode::Cors => "cors",
+            FetchMode::Navigate => "navigate",
+            FetchMode::NoCors => "no-cors",
+            FetchMode::SameOrigin => "same-origin",
+            FetchMode::WebSocket => "websocket",
+        }
+    }
+}
+
+impl fmt::Display for FetchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TypedHeader for FetchMode {
+    const NAME: &'static str = "Sec-Fetch-Mode";
+    type Error = FetchMetadataError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// Sec-Fetch-Dest
+// ========================================
+
+/// Sec-Fetch-Dest ヘッダー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FetchDest {
+    /// `audio`
+    Audio,
+    /// `audioworklet`
+    AudioWorklet,
+    /// `document`
+    Document,
+    /// `embed`
+    Embed,
+    /// `empty` (fetch() / XMLHttpRequest など)
+    Empty,
+    /// `fencedframe`
+    FencedFrame,
+    /// `font`
+    Font,
+    /// `frame`
+    Frame,
+    /// `iframe`
+    Iframe,
+    /// `image`
+    Image,
+    /// `json`
+    Json,
+    /// `manifest`
+    Manifest,
+    /// `object`
+    Object,
+    /// `paintworklet`
+    PaintWorklet,
+    /// `report`
+    Report,
+    /// `script`
+    Script,
+    /// `serviceworker`
+    ServiceWorker,
+    /// `sharedworker`
+    SharedWorker,
+    /// `style`
+    Style,
+    /// `track`
+    Track,
+    /// `video`
+    Video,
+    /// `webidentity`
+    WebIdentity,
+    /// `worker`
+    Worker,
+    /// `xslt`
+    Xslt,
+}
+
+impl FetchDest {
+    const ALL: &'static [FetchDest] = &[
+        FetchDest::Audio,
+        FetchDest::AudioWorklet,
+        FetchDest::Document,
+        FetchDest::Embed,
+        FetchDest::Empty,
+        FetchDest::FencedFrame,
+        FetchDest::Font,
+        FetchDest::Frame,
+        FetchDest::Iframe,
+        FetchDest::Image,
+        FetchDest::Json,
+        FetchDest::Manifest,
+        FetchDest::Object,
+        FetchDest::PaintWorklet,
+        FetchDest::Report,
+        FetchDest::Script,
+        FetchDest::ServiceWorker,
+        FetchDest::SharedWorker,
+        FetchDest::Style,
+        FetchDest::Track,
+        FetchDest::Video,
+        FetchDest::WebIdentity,
+        FetchDest::Worker,
+        FetchDest::Xslt,
+    ];
+
+    /// Sec-Fetch-Dest をパース
+    pub fn parse(input: &str) -> Result<Self, FetchMetadataError> {
+        let token = parse_token(input)?;
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|dest| dest.as_str() == token)
+            .ok_or(FetchMetadataError::UnknownValue)
+    }
+
+    /// 値の文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FetchDest::Audio => "audio",
+            FetchDest::AudioWorklet => "audioworklet",
+            FetchDest::Document => "document",
+            FetchDest::Embed => "embed",
+            FetchDest::Empty => "empty",
+            FetchDest::FencedFrame => "fencedframe",
+            FetchDest::Font => "font",
+            FetchDest::Frame => "frame",
+            FetchDest::Iframe => "iframe",
+            FetchDest::Image => "image",
+            FetchDest::Json => "json",
+            FetchDest::Manifest => "manifest",
+            FetchDest::Object => "object",
+            FetchDest::PaintWorklet => "paintworklet",
+            FetchDest::Report => "report",
+            FetchDest::Script => "script",
+            FetchDest::ServiceWorker => "serviceworker",
+            FetchDest::SharedWorker => "sharedworker",
+            FetchDest::Style => "style",
+            FetchDest::Track => "track",
+            FetchDest::Video => "video",
+            FetchDest::WebIdentity => "webidentity",
+            FetchDest::Worker => "worker",
+            FetchDest::Xslt => "xslt",
+        }
+    }
+}
+
+impl fmt::Display for FetchDest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TypedHeader for FetchDest {
+    const NAME: &'static str = "Sec-Fetch-Dest";
+    type Error = FetchMetadataError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// Sec-Fetch-User
+// ========================================
+
+/// Sec-Fetch-User ヘッダー
+///
+/// ユーザー操作によるナビゲーションの場合のみ `?1` が送られる。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchUser(pub bool);
+
+impl FetchUser {
+    /// Sec-Fetch-User をパース
+    pub fn parse(input: &str) -> Result<Self, FetchMetadataError> {
+        let item = Item::parse(input).map_err(|_| FetchMetadataError::InvalidFormat)?;
+        item.bare_item()
+            .as_boolean()
+            .map(FetchUser)
+            .ok_or(FetchMetadataError::InvalidFormat)
+    }
+}
+
+impl fmt::Display for FetchUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if self.0 { "?1" } else { "?0" })
+    }
+}
+
+impl TypedHeader for FetchUser {
+    const NAME: &'static str = "Sec-Fetch-User";
+    type Error = FetchMetadataError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// FetchMetadata
+// ========================================
+
+/// リクエストの Fetch Metadata
+///
+/// ヘッダーがない場合、または値が不正か未知の場合は `None` になる。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchMetadata {
+    site: Option<FetchSite>,
+    mode: Option<FetchMode>,
+    dest: Option<FetchDest>,
+    user: bool,
+}
+
+impl FetchMetadata {
+    /// Fetch Metadata を指定して作成
+    pub fn new(site: FetchSite, mode: FetchMode, dest: FetchDest) -> Self {
+        FetchMetadata {
+            site: Some(site),
+            mode: Some(mode),
+            dest: Some(dest),
+            user: false,
+        }
+    }
+
+    /// ユーザー操作によるリクエストとする (ビルダーパターン)
+    pub fn with_user(mut self) -> Self {
+        self.user = true;
+        self
+    }
+
+    /// リクエストの Sec-Fetch-* ヘッダーを取得
+    ///
+    /// 複数行ある場合や値が不正な場合は、そのヘッダーを無視する。
+    pub fn from_request<H: HttpHead>(request: &H) -> Self {
+        fn single<H: HttpHead>(request: &H, name: &str) -> Option<String> {
+            let values = request.get_headers(name);
+            match values.as_slice() {
+                [value] => Some(value.to_string()),
+                _ => None,
+            }
+        }
+        FetchMetadata {
+            site: single(request, FetchSite::NAME).and_then(|v| FetchSite::parse(&v).ok()),
+            mode: single(request, FetchMode::NAME).and_then(|v| FetchMode::parse(&v).ok()),
+            dest: single(request, FetchDest::NAME).and_then(|v| FetchDest::parse(&v).ok()),
+            user: single(request, FetchUser::NAME)
+                .and_then(|v| FetchUser::parse(&v).ok())
+                .is_some_and(|user| user.0),
+        }
+    }
+
+    /// Sec-Fetch-Site
+    pub fn site(&self) -> Option<FetchSite> {
+        self.site
+    }
+
+    /// Sec-Fetch-Mode
+    pub fn mode(&self) -> Option<FetchMode> {
+        self.mode
+    }
+
+    /// Sec-Fetch-Dest
+    pub fn dest(&self) -> Option<FetchDest> {
+        self.dest
+    }
+
+    /// Sec-Fetch-User が `?1` かどうか
+    pub fn is_user_activated(&self) -> bool {
+        self.user
+    }
+
+    /// ブラウザが Fetch Metadata を送っているかどうか (Sec-Fetch-Site があるか)
+    pub fn is_present(&self) -> bool {
+        self.site.is_some()
+    }
+}
+
+// ========================================
+// ResourceIsolationPolicy
+// ========================================
+
+/// リソース分離ポリシーの判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyDecision {
+    /// 許可
+    Allow,
+    /// 拒否 (403 Forbidden などで応答する)
+    Reject,
+}
+
+impl PolicyDecision {
+    /// 許可されたかどうか
+    pub fn is_allowed(&self) -> bool {
+        *self == PolicyDecision::Allow
+    }
+}
+
+/// Fetch Metadata によるリソース分離ポリシー
+///
+/// デフォルトでは次の順に判定する:
+///
+/// 1. Sec-Fetch-Site がない (Fetch Metadata に対応していないブラウザ) → 許可
+/// 2. `same-origin` / `same-site` / `none` → 許可
+/// 3. GET の `navigate` で、Sec-Fetch-Dest が `object` / `embed` 以外 → 許可
+/// 4. それ以外のクロスサイトリクエスト → 拒否
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceIsolationPolicy {
+    allow_same_site: bool,
+    allow_cross_site_navigation: bool,
+    allow_cross_site_safe_requests: bool,
+}
+
+impl Default for ResourceIsolationPolicy {
+    fn default() -> Self {
+        ResourceIsolationPolicy {
+            allow_same_site: true,
+            allow_cross_site_navigation: true,
+            allow_cross_site_safe_requests: false,
+        }
+    }
+}
+
+impl ResourceIsolationPolicy {
+    /// デフォルトのポリシーを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `same-site` のリクエストも拒否する (ビルダーパターン)
+    ///
+    /// 同じサイトの別オリジン (サブドメインなど) を信頼しない場合に使う。
+    pub fn with_same_origin_only(mut self) -> Self {
+        self.allow_same_site = false;
+        self
+    }
+
+    /// クロスサイトからのナビゲーションも拒否する (ビルダーパターン)
+    ///
+    /// 外部からリンクされることのない管理画面などに使う。
+    pub fn without_cross_site_navigation(mut self) -> Self {
+        self.allow_cross_site_navigation = false;
+        self
+    }
+
+    /// クロスサイトからの safe メソッドのリクエストを許可する (ビルダーパターン)
+    ///
+    /// 画像の埋め込みなどを許しつつ、状態を変更するエンドポイントへの
+    /// クロスサイトリクエスト (POST の `no-cors` など) だけを拒否する場合に使う。
+    pub fn with_cross_site_safe_requests(mut self) -> Self {
+        self.allow_cross_site_safe_requests = true;
+        self
+    }
+
+    /// リクエストのメソッドと Fetch Metadata からポリシーを判定する
+    pub fn evaluate(&self, method: &str, metadata: &FetchMetadata) -> PolicyDecision {
+        let allowed = match metadata.site {
+            None | Some(FetchSite::SameOrigin) | Some(FetchSite::None) => true,
+            Some(FetchSite::SameSite) if self.allow_same_site => true,
+            Some(_) => {
+                let is_get = method == "GET";
+                let is_navigation = self.allow_cross_site_navigation
+                    && is_get
+                    && metadata.mode == Some(FetchMode::Navigate)
+                    && !matches!(metadata.dest, Some(FetchDest::Object | FetchDest::Embed));
+                let is_safe = self.allow_cross_site_safe_requests
+                    && Method::parse(method).is_ok_and(|method| method.is_safe());
+                is_navigation || is_safe
+            }
+        };
+        if allowed {
+            PolicyDecision::Allow
+        } else {
+            PolicyDecision::Reject
+        }
+    }
+}
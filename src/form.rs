@@ -0,0 +1,113 @@
+//! application/x-www-form-urlencoded (WHATWG URL Standard Section 5)
+//!
+//! ## 概要
+//!
+//! HTML フォームの送信で使われる `application/x-www-form-urlencoded` 形式の
+//! シリアライズとパースを提供する。
+//!
+//! - シリアライズ: ASCII 英数字と `*` `-` `.` `_` 以外をパーセントエンコードし、空白は `+` にする
+//! - パース: `&` で分割し、`+` を空白に戻してからパーセントデコードする。
+//!   不正なパーセントエンコーディングはそのまま残し、不正な UTF-8 は U+FFFD に置き換える
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::form;
+//!
+//! let body = form::serialize(&[("name", "山田 太郎"), ("lang", "ja")]);
+//! assert_eq!(body, "name=%E5%B1%B1%E7%94%B0+%E5%A4%AA%E9%83%8E&lang=ja");
+//!
+//! let pairs = form::parse(&body);
+//! assert_eq!(pairs[0], ("name".to_string(), "山田 太郎".to_string()));
+//! assert_eq!(pairs[1], ("lang".to_string(), "ja".to_string()));
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// `application/x-www-form-urlencoded` のメディアタイプ
+pub const CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// 名前と値の組をシリアライズする
+///
+/// 組は `&` で、名前と値は `=` で連結する。
+pub fn serialize<K: AsRef<str>, V: AsRef<str>>(pairs: &[(K, V)]) -> String {
+    let mut output = String::new();
+    for (i, (name, value)) in pairs.iter().enumerate() {
+        if i > 0 {
+            output.push('&');
+        }
+        encode_into(&mut output, name.as_ref());
+        output.push('=');
+        encode_into(&mut output, value.as_ref());
+    }
+    output
+}
+
+/// 名前と値の組のリストにパースする
+///
+/// 空の要素 (`a=1&&b=2` の中間など) は無視する。`=` を含まない要素は値を空文字列とする。
+pub fn parse(input: &str) -> Vec<(String, String)> {
+    input
+        .split('&')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let (name, value) = s.split_once('=').unwrap_or((s, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+/// 1 つの名前または値をエンコードして `output` に追加する
+fn encode_into(output: &mut String, input: &str) {
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                output.push(byte as char)
+            }
+            b' ' => output.push('+'),
+            _ => {
+                output.push('%');
+                output.push(HEX[(byte >> 4) as usize] as char);
+                output.push(HEX[(byte & 0x0F) as usize] as char);
+            }
+        }
+    }
+}
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// 1 つの名前または値をデコードする
+fn decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => output.push(b' '),
+            b'%' => match (
+                bytes.get(i + 1).and_then(|&b| hex_value(b)),
+                bytes.get(i + 2).and_then(|&b| hex_value(b)),
+            ) {
+                (Some(high), Some(low)) => {
+                    output.push((high << 4) | low);
+                    i += 2;
+                }
+                // 不正なパーセントエンコーディングはそのまま残す
+                _ => output.push(b'%'),
+            },
+            b => output.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        _ => None,
+    }
+}
@@ -0,0 +1,762 @@
+//! Forwarded ヘッダー (RFC 7239) と X-Forwarded-* ヘッダー
+//!
+//! ## 概要
+//!
+//! RFC 7239 に基づいた Forwarded ヘッダーのパースと生成を提供します。
+//! 広く使われている X-Forwarded-For / X-Forwarded-Proto / X-Forwarded-Host との
+//! 相互変換と、プロキシが自身のホップを追加するためのヘルパーも提供します。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Forwarded         = 1#forwarded-element
+//! forwarded-element = [ forwarded-pair ] *( ";" [ forwarded-pair ] )
+//! forwarded-pair    = token "=" value
+//! value             = token / quoted-string
+//!
+//! node     = nodename [ ":" node-port ]
+//! nodename = IPv4address / "[" IPv6address "]" / "unknown" / obfnode
+//! obfnode  = "_" 1*( ALPHA / DIGIT / "." / "_" / "-")
+//! node-port = port / obfport
+//! port     = 1*5DIGIT
+//! obfport  = "_" 1*(ALPHA / DIGIT / "." / "_" / "-")
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::forwarded::{Forwarded, ForwardedElement, Node, NodeName};
+//!
+//! let forwarded = Forwarded::parse(r#"for="[2001:db8::1]:4711";proto=https, for=_hidden"#).unwrap();
+//! let first = &forwarded.elements()[0];
+//! assert_eq!(first.for_node().unwrap().to_string(), "[2001:db8::1]:4711");
+//! assert_eq!(first.proto(), Some("https"));
+//! assert_eq!(
+//!     forwarded.elements()[1].for_node().unwrap().name(),
+//!     &NodeName::Obfuscated("_hidden".to_string())
+//! );
+//!
+//! // IPv6 と port を含む node は quoted-string で出力する
+//! let element = ForwardedElement::new()
+//!     .with_for(Node::parse("[::1]:8080").unwrap())
+//!     .with_proto("http")
+//!     .unwrap();
+//! assert_eq!(element.to_string(), r#"for="[::1]:8080";proto=http"#);
+//! ```
+//!
+//! ## X-Forwarded-* との変換
+//!
+//! X-Forwarded-For の各アドレスが 1 つの forwarded-element になり、
+//! X-Forwarded-Proto / X-Forwarded-Host は最初の (クライアントに最も近い) 要素の
+//! proto / host になる。
+//!
+//! ```rust
+//! use shiguredo_http11::Request;
+//! use shiguredo_http11::forwarded::Forwarded;
+//!
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("X-Forwarded-For", "192.0.2.43, 2001:db8::1")
+//!     .unwrap()
+//!     .header("X-Forwarded-Proto", "https")
+//!     .unwrap();
+//! let forwarded = Forwarded::from_x_forwarded(&request).unwrap().unwrap();
+//! assert_eq!(
+//!     forwarded.to_string(),
+//!     r#"for=192.0.2.43;proto=https, for="[2001:db8::1]""#
+//! );
+//! assert_eq!(
+//!     forwarded.to_x_forwarded(),
+//!     [
+//!         ("X-Forwarded-For".to_string(), "192.0.2.43, 2001:db8::1".to_string()),
+//!         ("X-Forwarded-Proto".to_string(), "https".to_string()),
+//!     ]
+//! );
+//! ```
+//!
+//! ## ホップの追加
+//!
+//! ```rust
+//! use shiguredo_http11::Request;
+//! use shiguredo_http11::forwarded::{ForwardedElement, Node, append_forwarded, append_x_forwarded};
+//!
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("Forwarded", "for=192.0.2.43")
+//!     .unwrap();
+//! let element = ForwardedElement::new()
+//!     .with_for(Node::parse("198.51.100.17").unwrap())
+//!     .with_proto("http")
+//!     .unwrap();
+//! let request = append_forwarded(request, &element).unwrap();
+//! assert_eq!(
+//!     request.get_header("Forwarded"),
+//!     Some("for=192.0.2.43, for=198.51.100.17;proto=http")
+//! );
+//!
+//! let request = append_x_forwarded(request, &element).unwrap();
+//! assert_eq!(request.get_header("X-Forwarded-For"), Some("198.51.100.17"));
+//! assert_eq!(request.get_header("X-Forwarded-Proto"), Some("http"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::host::Host;
+use crate::request::Request;
+use crate::typed_header::TypedHeader;
+use crate::validate::{
+    QuotedStringError, escape_quotes, is_token_char, is_valid_token, parse_quoted_string,
+    split_with_quotes, trim_ows,
+};
+
+/// Forwarded パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ForwardedError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正なパラメータ名
+    InvalidParameterName,
+    /// 同じ要素内で重複したパラメータ (RFC 7239 Section 4)
+    DuplicateParameter(String),
+    /// 不正な値 (qdtext / quoted-pair の文字種違反を含む)
+    InvalidValue,
+    /// quoted-string の閉じ DQUOTE が見つからない (RFC 9110 Section 5.6.4)
+    UnterminatedQuote,
+    /// 不正な node (RFC 7239 Section 6)
+    InvalidNode,
+    /// 不正な host (RFC 7239 Section 5.3)
+    InvalidHost,
+    /// 不正な proto (RFC 7239 Section 5.4)
+    InvalidProto,
+}
+
+impl fmt::Display for ForwardedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForwardedError::InvalidFormat => write!(f, "invalid Forwarded header format"),
+            ForwardedError::InvalidParameterName => {
+                write!(f, "invalid Forwarded parameter name")
+            }
+            ForwardedError::DuplicateParameter(name) => {
+                write!(f, "duplicate Forwarded parameter: {}", name)
+            }
+            ForwardedError::InvalidValue => write!(f, "invalid Forwarded value"),
+            ForwardedError::UnterminatedQuote => write!(f, "unterminated quoted-string"),
+            ForwardedError::InvalidNode => write!(f, "invalid Forwarded node"),
+            ForwardedError::InvalidHost => write!(f, "invalid Forwarded host"),
+            ForwardedError::InvalidProto => write!(f, "invalid Forwarded proto"),
+        }
+    }
+}
+
+impl core::error::Error for ForwardedError {}
+
+impl From<QuotedStringError> for ForwardedError {
+    fn from(e: QuotedStringError) -> Self {
+        match e {
+            QuotedStringError::InvalidQdtext | QuotedStringError::InvalidQuotedPair => {
+                ForwardedError::InvalidValue
+            }
+            QuotedStringError::Unterminated => ForwardedError::UnterminatedQuote,
+        }
+    }
+}
+
+/// node の名前 (RFC 7239 Section 6)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeName {
+    /// IPv4 / IPv6 アドレス
+    Ip(IpAddr),
+    /// `unknown` (RFC 7239 Section 6.2)
+    Unknown,
+    /// 難読化した識別子 (RFC 7239 Section 6.3、`_` で始まる)
+    Obfuscated(String),
+}
+
+/// node のポート (RFC 7239 Section 6)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodePort {
+    /// ポート番号
+    Port(u16),
+    /// 難読化したポート (`_` で始まる)
+    Obfuscated(String),
+}
+
+/// Forwarded の for / by に入る node (RFC 7239 Section 6)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    name: NodeName,
+    port: Option<NodePort>,
+}
+
+impl Node {
+    /// 名前から node を作成
+    pub fn new(name: NodeName) -> Self {
+        Node { name, port: None }
+    }
+
+    /// `unknown` の node を作成
+    ///
+    /// RFC 7239 Section 6.2: 前のホップが不明な場合に使う。
+    pub fn unknown() -> Self {
+        Node::new(NodeName::Unknown)
+    }
+
+    /// 難読化した識別子の node を作成
+    ///
+    /// RFC 7239 Section 6.3: `_` で始まり、ALPHA / DIGIT / `.` / `_` / `-` のみを含む。
+    pub fn obfuscated(identifier: &str) -> Result<Self, ForwardedError> {
+        if !is_obfuscated(identifier) {
+            return Err(ForwardedError::InvalidNode);
+        }
+        Ok(Node::new(NodeName::Obfuscated(identifier.to_string())))
+    }
+
+    /// node をパース
+    ///
+    /// IPv6 アドレスは `[` `]` で囲む。
+    pub fn parse(input: &str) -> Result<Self, ForwardedError> {
+        let (name, port) = if let Some(rest) = input.strip_prefix('[') {
+            let (address, rest) = rest.split_once(']').ok_or(ForwardedError::InvalidNode)?;
+            let address = address
+                .parse::<Ipv6Addr>()
+                .map_err(|_| ForwardedError::InvalidNode)?;
+            let port = match rest {
+                "" => None,
+                _ => Some(rest.strip_prefix(':').ok_or(ForwardedError::InvalidNode)?),
+            };
+            (NodeName::Ip(IpAddr::V6(address)), port)
+        } else {
+            let (name, port) = match input.split_once(':') {
+                Some((name, port)) => (name, Some(port)),
+                None => (input, None),
+            };
+            let name = if name.eq_ignore_ascii_case("unknown") {
+                NodeName::Unknown
+            } else if is_obfuscated(name) {
+                NodeName::Obfuscated(name.to_string())
+            } else {
+                let address = name
+                    .parse::<Ipv4Addr>()
+                    .map_err(|_| ForwardedError::InvalidNode)?;
+                NodeName::Ip(IpAddr::V4(address))
+            };
+            (name, port)
+        };
+
+        let port = port.map(parse_node_port).transpose()?;
+        Ok(Node { name, port })
+    }
+
+    /// ポートを設定
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(NodePort::Port(port));
+        self
+    }
+
+    /// 難読化したポートを設定
+    pub fn with_obfuscated_port(mut self, port: &str) -> Result<Self, ForwardedError> {
+        if !is_obfuscated(port) {
+            return Err(ForwardedError::InvalidNode);
+        }
+        self.port = Some(NodePort::Obfuscated(port.to_string()));
+        Ok(self)
+    }
+
+    /// 名前
+    pub fn name(&self) -> &NodeName {
+        &self.name
+    }
+
+    /// ポート
+    pub fn port(&self) -> Option<&NodePort> {
+        self.port.as_ref()
+    }
+
+    /// IP アドレス (名前が IP アドレスの場合)
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.name {
+            NodeName::Ip(ip) => Some(ip),
+            _ => None,
+        }
+    }
+}
+
+impl From<IpAddr> for Node {
+    fn from(ip: IpAddr) -> Self {
+        Node::new(NodeName::Ip(ip))
+    }
+}
+
+impl From<SocketAddr> for Node {
+    fn from(addr: SocketAddr) -> Self {
+        Node::from(addr.ip()).with_port(addr.port())
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            NodeName::Ip(IpAddr::V4(ip)) => write!(f, "{}", ip)?,
+            NodeName::Ip(IpAddr::V6(ip)) => write!(f, "[{}]", ip)?,
+            NodeName::Unknown => write!(f, "unknown")?,
+            NodeName::Obfuscated(name) => write!(f, "{}", name)?,
+        }
+        match &self.port {
+            Some(NodePort::Port(port)) => write!(f, ":{}", port),
+            Some(NodePort::Obfuscated(port)) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+/// forwarded-element (1 ホップ分の情報)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForwardedElement {
+    for_node: Option<Node>,
+    by: Option<Node>,
+    host: Option<Host>,
+    proto: Option<String>,
+    extensions: Vec<(String, String)>,
+}
+
+impl ForwardedElement {
+    /// 空の要素を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// for (リクエストを送ってきたクライアント) を設定
+    pub fn with_for(mut self, node: Node) -> Self {
+        self.for_node = Some(node);
+        self
+    }
+
+    /// by (リクエストを受け取ったプロキシのインターフェース) を設定
+    pub fn with_by(mut self, node: Node) -> Self {
+        self.by = Some(node);
+        self
+    }
+
+    /// host (プロキシが受け取った Host ヘッダーの値) を設定
+    pub fn with_host(mut self, host: &str) -> Result<Self, ForwardedError> {
+        self.host = Some(Host::parse(host).map_err(|_| ForwardedError::InvalidHost)?);
+        Ok(self)
+    }
+
+    /// proto (プロキシが受け取ったリクエストのスキーム) を設定
+    ///
+    /// RFC 7239 Section 5.4: URI スキーム名。小文字に正規化する。
+    pub fn with_proto(mut self, proto: &str) -> Result<Self, ForwardedError> {
+        self.proto = Some(parse_proto(proto)?);
+        Ok(self)
+    }
+
+    /// for
+    pub fn for_node(&self) -> Option<&Node> {
+        self.for_node.as_ref()
+    }
+
+    /// by
+    pub fn by(&self) -> Option<&Node> {
+        self.by.as_ref()
+    }
+
+    /// host
+    pub fn host(&self) -> Option<&Host> {
+        self.host.as_ref()
+    }
+
+    /// proto
+    pub fn proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+
+    /// 拡張パラメータ (名前は小文字)
+    pub fn extensions(&self) -> &[(String, String)] {
+        &self.extensions
+    }
+
+    /// パラメータが 1 つもないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.for_node.is_none()
+            && self.by.is_none()
+            && self.host.is_none()
+            && self.proto.is_none()
+            && self.extensions.is_empty()
+    }
+
+    fn parse(input: &str) -> Result<Self, ForwardedError> {
+        let mut element = ForwardedElement::new();
+        let mut seen: Vec<String> = Vec::new();
+        for pair in split_with_quotes(input, ';') {
+            let pair = trim_ows(&pair);
+            if pair.is_empty() {
+                continue;
+            }
+            let (name, value) = pair.split_once('=').ok_or(ForwardedError::InvalidFormat)?;
+            let name = trim_ows(name);
+            if !is_valid_token(name) {
+                return Err(ForwardedError::InvalidParameterName);
+            }
+            let name = name.to_ascii_lowercase();
+            if seen.contains(&name) {
+                return Err(ForwardedError::DuplicateParameter(name));
+            }
+            let value = parse_value(value)?;
+            match name.as_str() {
+                "for" => element.for_node = Some(Node::parse(&value)?),
+                "by" => element.by = Some(Node::parse(&value)?),
+                "host" => element = element.with_host(&value)?,
+                "proto" => element = element.with_proto(&value)?,
+                _ => element.extensions.push((name.clone(), value)),
+            }
+            seen.push(name);
+        }
+        Ok(element)
+    }
+}
+
+impl fmt::Display for ForwardedElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pairs: Vec<(&str, String)> = Vec::new();
+        if let Some(node) = &self.for_node {
+            pairs.push(("for", node.to_string()));
+        }
+        if let Some(node) = &self.by {
+            pairs.push(("by", node.to_string()));
+        }
+        if let Some(host) = &self.host {
+            pairs.push(("host", host.to_string()));
+        }
+        if let Some(proto) = &self.proto {
+            pairs.push(("proto", proto.clone()));
+        }
+        for (name, value) in &self.extensions {
+            pairs.push((name, value.clone()));
+        }
+
+        for (i, (name, value)) in pairs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            if needs_quoting(value) {
+                write!(f, "{}=\"{}\"", name, escape_quotes(value))?;
+            } else {
+                write!(f, "{}={}", name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Forwarded ヘッダー
+///
+/// 要素はクライアントに近いホップから順に並ぶ。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Forwarded {
+    elements: Vec<ForwardedElement>,
+}
+
+impl Forwarded {
+    /// 空の Forwarded を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forwarded ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 空の値と空の要素は無視する。
+    pub fn parse(input: &str) -> Result<Self, ForwardedError> {
+        let mut elements = Vec::new();
+        for part in split_with_quotes(trim_ows(input), ',') {
+            let part = trim_ows(&part);
+            if part.is_empty() {
+                continue;
+            }
+            elements.push(ForwardedElement::parse(part)?);
+        }
+        Ok(Forwarded { elements })
+    }
+
+    /// リクエストの Forwarded ヘッダーを取得
+    ///
+    /// 複数行の Forwarded は結合する。Forwarded ヘッダーがない場合は `None` を返す。
+    pub fn from_request<H: HttpHead>(request: &H) -> Result<Option<Self>, ForwardedError> {
+        let values = request.get_headers("Forwarded");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Forwarded::parse(&values.join(", ")).map(Some)
+    }
+
+    /// リクエストの X-Forwarded-For / X-Forwarded-Proto / X-Forwarded-Host を変換
+    ///
+    /// X-Forwarded-For の各アドレスを for とする要素を作り、X-Forwarded-Proto と
+    /// X-Forwarded-Host は最初の要素の proto / host にする。
+    /// X-Forwarded-For がない場合は proto / host だけを持つ要素を 1 つ作る。
+    /// X-Forwarded-* ヘッダーが 1 つもない場合は `None` を返す。
+    ///
+    /// X-Forwarded-For のアドレスは IPv6 の角括弧を省略してもよい。
+    pub fn from_x_forwarded<H: HttpHead>(request: &H) -> Result<Option<Self>, ForwardedError> {
+        let for_values = request.get_headers("X-Forwarded-For");
+        let proto = request.get_header("X-Forwarded-Proto");
+        let host = request.get_header("X-Forwarded-Host");
+        if for_values.is_empty() && proto.is_none() && host.is_none() {
+            return Ok(None);
+        }
+
+        let mut elements = Vec::new();
+        for value in for_values {
+            for address in value.split(',') {
+                let address = trim_ows(address);
+                if address.is_empty() {
+                    continue;
+                }
+                let node = match address.parse::<Ipv6Addr>() {
+                    Ok(ip) => Node::from(IpAddr::V6(ip)),
+                    Err(_) => Node::parse(address)?,
+                };
+                elements.push(ForwardedElement::new().with_for(node));
+            }
+        }
+        if elements.is_empty() {
+            elements.push(ForwardedElement::new());
+        }
+
+        // 複数のプロキシが付けた場合は最初の値 (クライアントに最も近い) を使う
+        let first = &mut elements[0];
+        if let Some(proto) = proto.and_then(|value| value.split(',').next()) {
+            first.proto = Some(parse_proto(trim_ows(proto))?);
+        }
+        if let Some(host) = host.and_then(|value| value.split(',').next()) {
+            first.host =
+                Some(Host::parse(trim_ows(host)).map_err(|_| ForwardedError::InvalidHost)?);
+        }
+
+        Ok(Some(Forwarded { elements }))
+    }
+
+    /// X-Forwarded-For / X-Forwarded-Proto / X-Forwarded-Host に変換
+    ///
+    /// X-Forwarded-For には各要素の for の名前を並べる (ポートは含めない)。
+    /// for のない要素は `unknown` にする。
+    /// X-Forwarded-Proto / X-Forwarded-Host は最初の要素の proto / host から作る。
+    pub fn to_x_forwarded(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if self
+            .elements
+            .iter()
+            .any(|element| element.for_node.is_some())
+        {
+            let addresses: Vec<String> = self
+                .elements
+                .iter()
+                .map(|element| x_forwarded_for_address(element.for_node.as_ref()))
+                .collect();
+            headers.push(("X-Forwarded-For".to_string(), addresses.join(", ")));
+        }
+        if let Some(first) = self.elements.first() {
+            if let Some(proto) = &first.proto {
+                headers.push(("X-Forwarded-Proto".to_string(), proto.clone()));
+            }
+            if let Some(host) = &first.host {
+                headers.push(("X-Forwarded-Host".to_string(), host.to_string()));
+            }
+        }
+        headers
+    }
+
+    /// 要素一覧 (クライアントに近い順)
+    pub fn elements(&self) -> &[ForwardedElement] {
+        &self.elements
+    }
+
+    /// 末尾に要素を追加
+    pub fn push(&mut self, element: ForwardedElement) {
+        self.elements.push(element);
+    }
+
+    /// 末尾に要素を追加 (ビルダー)
+    pub fn with_element(mut self, element: ForwardedElement) -> Self {
+        self.elements.push(element);
+        self
+    }
+}
+
+impl fmt::Display for Forwarded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = self.elements.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+impl TypedHeader for Forwarded {
+    const NAME: &'static str = "Forwarded";
+    type Error = ForwardedError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// リクエストの Forwarded ヘッダーの末尾に要素を追加
+///
+/// RFC 7239 Section 4: プロキシは既存の Forwarded ヘッダーに自身の要素を追加する。
+/// 複数行の Forwarded は 1 行にまとめる。既存の値は検証せずにそのまま残す。
+/// 空の要素は追加しない。
+pub fn append_forwarded(
+    mut request: Request,
+    element: &ForwardedElement,
+) -> Result<Request, EncodeError> {
+    if let Some(value) = forwarded_value(&request, element) {
+        request.set_header("Forwarded", value)?;
+    }
+    Ok(request)
+}
+
+/// リクエストの X-Forwarded-For の末尾に要素の for を追加
+///
+/// X-Forwarded-Proto / X-Forwarded-Host は既存の値がない場合のみ、要素の proto / host から設定する
+/// (最初のプロキシが受け取ったリクエストの情報を残すため)。
+/// 要素に for がない場合は `unknown` を追加する。
+pub fn append_x_forwarded(
+    mut request: Request,
+    element: &ForwardedElement,
+) -> Result<Request, EncodeError> {
+    for (name, value) in x_forwarded_values(&request, element) {
+        request.set_header(name, value)?;
+    }
+    Ok(request)
+}
+
+/// 要素を末尾に追加した Forwarded ヘッダーの値を返す
+///
+/// 空の要素の場合は `None` を返す。
+pub(crate) fn forwarded_value<H: HttpHead>(
+    message: &H,
+    element: &ForwardedElement,
+) -> Option<String> {
+    if element.is_empty() {
+        return None;
+    }
+    Some(append_list_value(message, "Forwarded", element.to_string()))
+}
+
+/// 要素から設定する X-Forwarded-* ヘッダーの名前と値を返す
+///
+/// X-Forwarded-For は既存の値の末尾に追加した値、X-Forwarded-Proto / X-Forwarded-Host は
+/// 既存の値がない場合のみ返す。
+pub(crate) fn x_forwarded_values<H: HttpHead>(
+    message: &H,
+    element: &ForwardedElement,
+) -> Vec<(&'static str, String)> {
+    let mut values = vec![(
+        "X-Forwarded-For",
+        append_list_value(
+            message,
+            "X-Forwarded-For",
+            x_forwarded_for_address(element.for_node.as_ref()),
+        ),
+    )];
+    if let Some(proto) = &element.proto
+        && message.get_header("X-Forwarded-Proto").is_none()
+    {
+        values.push(("X-Forwarded-Proto", proto.clone()));
+    }
+    if let Some(host) = &element.host
+        && message.get_header("X-Forwarded-Host").is_none()
+    {
+        values.push(("X-Forwarded-Host", host.to_string()));
+    }
+    values
+}
+
+/// 既存のヘッダー行を 1 行にまとめて末尾に値を追加した値を返す
+///
+/// 前後の OWS と余分なカンマは取り除く。
+fn append_list_value<H: HttpHead>(message: &H, name: &str, value: String) -> String {
+    let mut values: Vec<&str> = message
+        .get_headers(name)
+        .into_iter()
+        .map(|value| value.trim_matches(|c| c == ',' || c == ' ' || c == '\t'))
+        .filter(|value| !value.is_empty())
+        .collect();
+    values.push(&value);
+    values.join(", ")
+}
+
+/// X-Forwarded-For に入れるアドレス (ポートと IPv6 の角括弧は含めない)
+fn x_forwarded_for_address(node: Option<&Node>) -> String {
+    match node.map(Node::name) {
+        Some(NodeName::Ip(ip)) => ip.to_string(),
+        Some(NodeName::Obfuscated(name)) => name.clone(),
+        Some(NodeName::Unknown) | None => "unknown".to_string(),
+    }
+}
+
+fn parse_value(input: &str) -> Result<String, ForwardedError> {
+    let input = trim_ows(input);
+    if let Some(rest) = input.strip_prefix('"') {
+        let (value, remaining) = parse_quoted_string(rest)?;
+        if !trim_ows(remaining).is_empty() {
+            return Err(ForwardedError::InvalidValue);
+        }
+        Ok(value)
+    } else if !is_valid_token(input) {
+        Err(ForwardedError::InvalidValue)
+    } else {
+        Ok(input.to_string())
+    }
+}
+
+fn parse_node_port(port: &str) -> Result<NodePort, ForwardedError> {
+    if is_obfuscated(port) {
+        return Ok(NodePort::Obfuscated(port.to_string()));
+    }
+    // RFC 7239 Section 6: port = 1*5DIGIT
+    if port.is_empty() || port.len() > 5 || !port.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ForwardedError::InvalidNode);
+    }
+    port.parse::<u16>()
+        .map(NodePort::Port)
+        .map_err(|_| ForwardedError::InvalidNode)
+}
+
+fn parse_proto(proto: &str) -> Result<String, ForwardedError> {
+    // RFC 3986 Section 3.1: scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+    let bytes = proto.as_bytes();
+    if bytes.is_empty()
+        || !bytes[0].is_ascii_alphabetic()
+        || !bytes
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+    {
+        return Err(ForwardedError::InvalidProto);
+    }
+    Ok(proto.to_ascii_lowercase())
+}
+
+/// obfnode / obfport (RFC 7239 Section 6.3)
+fn is_obfuscated(value: &str) -> bool {
+    value.len() > 1
+        && value.starts_with('_')
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || b == b'-')
+}
+
+fn needs_quoting(value: &str) -> bool {
+    value.is_empty() || value.bytes().any(|b| !is_token_char(b))
+}
@@ -0,0 +1,104 @@
+//! よく使われるヘッダー名のインターン
+//!
+//! デコードしたヘッダー名やビルダーで追加したヘッダー名が下表のいずれかと完全に一致する場合は
+//! `'static` な文字列を借用し、メッセージごとの `String` の確保をなくす。
+//! ヘッダー名は大文字小文字を区別しないが (RFC 9110 Section 5.1)、受信した表記を保持するため
+//! 一致とみなすのは表の表記 (先頭大文字) とすべて小文字の表記のみとする。
+//! それ以外の表記や表にない名前は `Cow::Owned` として保持する。
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+
+/// インターンするヘッダー名 (表記, 小文字表記)
+///
+/// RFC 9110 / RFC 9111 / RFC 9112 / RFC 6265 等で定義され、リクエストまたはレスポンスで
+/// 頻繁に使われるものを選んでいる。
+const WELL_KNOWN: &[(&str, &str)] = &[
+    ("Accept", "accept"),
+    ("Accept-Charset", "accept-charset"),
+    ("Accept-Encoding", "accept-encoding"),
+    ("Accept-Language", "accept-language"),
+    ("Accept-Ranges", "accept-ranges"),
+    ("Access-Control-Allow-Origin", "access-control-allow-origin"),
+    ("Age", "age"),
+    ("Allow", "allow"),
+    ("Authorization", "authorization"),
+    ("Cache-Control", "cache-control"),
+    ("Connection", "connection"),
+    ("Content-Disposition", "content-disposition"),
+    ("Content-Encoding", "content-encoding"),
+    ("Content-Language", "content-language"),
+    ("Content-Length", "content-length"),
+    ("Content-Location", "content-location"),
+    ("Content-Range", "content-range"),
+    ("Content-Type", "content-type"),
+    ("Cookie", "cookie"),
+    ("Date", "date"),
+    ("ETag", "etag"),
+    ("Expect", "expect"),
+    ("Expires", "expires"),
+    ("Forwarded", "forwarded"),
+    ("Host", "host"),
+    ("If-Match", "if-match"),
+    ("If-Modified-Since", "if-modified-since"),
+    ("If-None-Match", "if-none-match"),
+    ("If-Range", "if-range"),
+    ("If-Unmodified-Since", "if-unmodified-since"),
+    ("Keep-Alive", "keep-alive"),
+    ("Last-Modified", "last-modified"),
+    ("Link", "link"),
+    ("Location", "location"),
+    ("Origin", "origin"),
+    ("Pragma", "pragma"),
+    ("Proxy-Authenticate", "proxy-authenticate"),
+    ("Proxy-Authorization", "proxy-authorization"),
+    ("Range", "range"),
+    ("Referer", "referer"),
+    ("Retry-After", "retry-after"),
+    ("Server", "server"),
+    ("Set-Cookie", "set-cookie"),
+    ("Strict-Transport-Security", "strict-transport-security"),
+    ("TE", "te"),
+    ("Trailer", "trailer"),
+    ("Transfer-Encoding", "transfer-encoding"),
+    ("Upgrade", "upgrade"),
+    ("User-Agent", "user-agent"),
+    ("Vary", "vary"),
+    ("Via", "via"),
+    ("WWW-Authenticate", "www-authenticate"),
+    ("X-Forwarded-For", "x-forwarded-for"),
+    ("X-Forwarded-Proto", "x-forwarded-proto"),
+];
+
+/// 表にあるヘッダー名と完全に一致する場合は `'static` な文字列を返す
+pub(crate) fn lookup(name: &str) -> Option<&'static str> {
+    WELL_KNOWN.iter().find_map(|&(canonical, lower)| {
+        if name == canonical {
+            Some(canonical)
+        } else if name == lower {
+            Some(lower)
+        } else {
+            None
+        }
+    })
+}
+
+/// ヘッダー名をインターンする
+///
+/// 表にある名前は `Cow::Borrowed`、それ以外は `Cow::Owned` を返す。
+pub(crate) fn intern(name: &str) -> Cow<'static, str> {
+    match lookup(name) {
+        Some(name) => Cow::Borrowed(name),
+        None => Cow::Owned(name.to_string()),
+    }
+}
+
+/// 所有済みのヘッダー名をインターンする
+///
+/// 表にある名前は `name` を破棄して `Cow::Borrowed` を、それ以外は `name` をそのまま返す。
+pub(crate) fn intern_owned(name: String) -> Cow<'static, str> {
+    match lookup(&name) {
+        Some(name) => Cow::Borrowed(name),
+        None => Cow::Owned(name),
+    }
+}
@@ -13,11 +13,36 @@
 //! assert_eq!(host.host(), "example.com");
 //! assert_eq!(host.port(), Some(8080));
 //! ```
+//!
+//! ## 比較
+//!
+//! ホスト名は大文字小文字を区別せず、ポートはスキームのデフォルトポートを補って比較する。
+//!
+//! ```rust
+//! use shiguredo_http11::host::Host;
+//! use shiguredo_http11::uri::Uri;
+//!
+//! let host = Host::parse("Example.COM").unwrap();
+//! assert!(host.matches(&Host::parse("example.com:443").unwrap(), "https"));
+//! assert!(!host.matches(&Host::parse("example.com:443").unwrap(), "http"));
+//!
+//! // absolute-form の request-target と Host ヘッダーの突き合わせ
+//! let target = Uri::parse("http://example.com:80/index.html").unwrap();
+//! assert!(host.matches_uri(&target));
+//! assert_eq!(Host::from_uri(&target).unwrap().without_default_port("http").to_string(), "example.com");
+//!
+//! // IPv6 リテラル
+//! let host = Host::parse("[2001:DB8::1]:8080").unwrap();
+//! assert_eq!(host.hostname(), "2001:DB8::1");
+//! assert!(host.matches(&Host::new("2001:db8:0::1", Some(8080)).unwrap(), "http"));
+//! ```
 
 use alloc::string::{String, ToString};
 use core::fmt;
 use core::net::{Ipv4Addr, Ipv6Addr};
 
+use crate::typed_header::TypedHeader;
+use crate::uri::{Uri, default_port};
 use crate::validate::trim_ows;
 
 /// Host パースエラー
@@ -57,55 +82,111 @@ pub struct Host {
 impl Host {
     /// Host ヘッダーをパース
     pub fn parse(input: &str) -> Result<Self, HostError> {
-        let input = trim_ows(input);
-        if input.is_empty() {
-            return Err(HostError::Empty);
-        }
-
-        if input.chars().any(|c| c.is_whitespace()) {
-            return Err(HostError::InvalidFormat);
-        }
-
-        if input.starts_with('[') {
-            return parse_ipv6_host(input);
-        }
-
-        let (host_part, port) = split_host_port(input)?;
-        if host_part.is_empty() || host_part.contains('@') {
-            return Err(HostError::InvalidHost);
-        }
-
-        if host_part.parse::<Ipv4Addr>().is_ok() {
-            return Ok(Host {
-                host: host_part.to_string(),
-                port,
-            });
-        }
+        let (host, port) = split_host(input)?;
+        Ok(Host {
+            host: host.to_string(),
+            port,
+        })
+    }
 
-        if !is_valid_reg_name(host_part) {
+    /// ホストとポートから作成
+    ///
+    /// host は reg-name / IPv4 アドレス / IP-literal を受け付ける。
+    /// IPv6 アドレスは角括弧なしでもよく、角括弧で囲んで保持する。
+    /// host にポートを含めることはできない。
+    pub fn new(host: &str, port: Option<u16>) -> Result<Self, HostError> {
+        let literal;
+        let host = if host.parse::<Ipv6Addr>().is_ok() {
+            literal = alloc::format!("[{}]", host);
+            literal.as_str()
+        } else {
+            host
+        };
+        let parsed = Host::parse(host)?;
+        if parsed.port.is_some() || parsed.host != host {
             return Err(HostError::InvalidHost);
         }
-
         Ok(Host {
-            host: host_part.to_string(),
+            host: parsed.host,
             port,
         })
     }
 
+    /// URI の authority から作成
+    ///
+    /// userinfo は含めない。authority がない、またはホストが空の場合は `Empty` を返す。
+    pub fn from_uri(uri: &Uri) -> Result<Self, HostError> {
+        let host = uri.host().filter(|host| !host.is_empty());
+        let host = host.ok_or(HostError::Empty)?;
+        Host::new(host, uri.port())
+    }
+
     /// Host 名 (IPv6 は角括弧付き)
     pub fn host(&self) -> &str {
         &self.host
     }
 
+    /// Host 名 (IPv6 の角括弧を除く)
+    pub fn hostname(&self) -> &str {
+        self.host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(&self.host)
+    }
+
     /// ポート番号 (任意)
     pub fn port(&self) -> Option<u16> {
         self.port
     }
 
+    /// ポート番号 (省略時はスキームのデフォルトポート)
+    pub fn port_or_default(&self, scheme: &str) -> Option<u16> {
+        self.port.or_else(|| default_port(scheme))
+    }
+
+    /// スキームのデフォルトポートと同じポートを取り除く
+    ///
+    /// RFC 3986 Section 6.2.3: デフォルトポートと同じポートは省略するのが正規形。
+    pub fn without_default_port(&self, scheme: &str) -> Host {
+        let port = self.port.filter(|&port| Some(port) != default_port(scheme));
+        Host {
+            host: self.host.clone(),
+            port,
+        }
+    }
+
     /// IPv6 リテラルかどうか
     pub fn is_ipv6(&self) -> bool {
         self.host.starts_with('[')
     }
+
+    /// 同じホストとポートを指しているか
+    ///
+    /// ホスト名は大文字小文字を区別せず、IPv6 アドレスはアドレスとして比較する。
+    /// ポートは `scheme` のデフォルトポートを補って比較する。
+    pub fn matches(&self, other: &Host, scheme: &str) -> bool {
+        self.port_or_default(scheme) == other.port_or_default(scheme)
+            && same_host(self.hostname(), other.hostname())
+    }
+
+    /// URI の authority と同じホストとポートを指しているか
+    ///
+    /// absolute-form の request-target と Host ヘッダーを突き合わせる場合に使う。
+    /// ポートは URI のスキームのデフォルトポートを補って比較する。
+    /// URI にホストがない場合は false を返す。
+    pub fn matches_uri(&self, uri: &Uri) -> bool {
+        let Ok(authority) = Host::from_uri(uri) else {
+            return false;
+        };
+        self.matches(&authority, uri.scheme().unwrap_or(""))
+    }
+}
+
+fn same_host(a: &str, b: &str) -> bool {
+    match (a.parse::<Ipv6Addr>(), b.parse::<Ipv6Addr>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a.eq_ignore_ascii_case(b),
+    }
 }
 
 impl fmt::Display for Host {
@@ -118,7 +199,20 @@ impl fmt::Display for Host {
     }
 }
 
-fn parse_ipv6_host(input: &str) -> Result<Host, HostError> {
+impl TypedHeader for Host {
+    const NAME: &'static str = "Host";
+    type Error = HostError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn parse_ipv6_host(input: &str) -> Result<(&str, Option<u16>), HostError> {
     let end = input.find(']').ok_or(HostError::InvalidHost)?;
     let host_inner = &input[1..end];
     let rest = &input[end + 1..];
@@ -139,10 +233,41 @@ fn parse_ipv6_host(input: &str) -> Result<Host, HostError> {
         return Err(HostError::InvalidHost);
     }
 
-    Ok(Host {
-        host: input[..end + 1].to_string(),
-        port,
-    })
+    Ok((&input[..end + 1], port))
+}
+
+/// Host ヘッダーを検証し、メモリを確保せずに host とポートに分割する
+fn split_host(input: &str) -> Result<(&str, Option<u16>), HostError> {
+    let input = trim_ows(input);
+    if input.is_empty() {
+        return Err(HostError::Empty);
+    }
+
+    if input.chars().any(|c| c.is_whitespace()) {
+        return Err(HostError::InvalidFormat);
+    }
+
+    if input.starts_with('[') {
+        return parse_ipv6_host(input);
+    }
+
+    let (host_part, port) = split_host_port(input)?;
+    if host_part.is_empty() || host_part.contains('@') {
+        return Err(HostError::InvalidHost);
+    }
+
+    if host_part.parse::<Ipv4Addr>().is_err() && !is_valid_reg_name(host_part) {
+        return Err(HostError::InvalidHost);
+    }
+
+    Ok((host_part, port))
+}
+
+/// Host ヘッダーを検証する
+///
+/// `Host::parse()` と同じ検証を `Host` を作らずに行う。
+pub(crate) fn validate(input: &str) -> Result<(), HostError> {
+    split_host(input).map(|_| ())
 }
 
 fn split_host_port(input: &str) -> Result<(&str, Option<u16>), HostError> {
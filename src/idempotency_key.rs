@@ -0,0 +1,214 @@
+//! Idempotency-Key ヘッダー (draft-ietf-httpapi-idempotency-key-header)
+//!
+//! ## 概要
+//!
+//! POST / PATCH など idempotent ではないメソッドのリクエストを安全に再送するための
+//! Idempotency-Key リクエストヘッダーのパースと生成を提供します。
+//!
+//! - 値は Structured Field の String (`"8e03978e-40d5-43e8-bc93-6894a57f9324"` など)
+//! - キーはクライアントが生成する不透明な文字列で、UUID などの推測困難な値を推奨する
+//! - 空のキーと、上限 (デフォルト 255 バイト) を超えるキーはエラーにする
+//!
+//! `RequestIdempotency` は RFC 9110 Section 9.2 のメソッドの性質と Idempotency-Key の
+//! 有無から、リクエストを自動的に再送してよいかを分類する。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Idempotency-Key = sf-string
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::idempotency_key::{IdempotencyKey, RequestIdempotency};
+//! use shiguredo_http11::Request;
+//!
+//! let request = Request::new("POST", "/payments")
+//!     .unwrap()
+//!     .header("Idempotency-Key", "\"8e03978e-40d5-43e8-bc93-6894a57f9324\"")
+//!     .unwrap();
+//!
+//! let key = IdempotencyKey::from_request(&request).unwrap().unwrap();
+//! assert_eq!(key.as_str(), "8e03978e-40d5-43e8-bc93-6894a57f9324");
+//!
+//! let idempotency = RequestIdempotency::classify(request.method(), Some(&key));
+//! assert_eq!(idempotency, RequestIdempotency::KeyProtected);
+//! assert!(idempotency.is_retryable());
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::method::Method;
+use crate::sfv::{Item, is_valid_string, write_string};
+use crate::typed_header::TypedHeader;
+
+/// キーのデフォルトの最大長 (バイト)
+pub const DEFAULT_MAX_KEY_LENGTH: usize = 255;
+
+/// Idempotency-Key パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdempotencyKeyError {
+    /// sf-string として不正な形式
+    InvalidFormat,
+    /// 空のキー
+    Empty,
+    /// キーが最大長を超えている
+    TooLong,
+    /// sf-string に使えない文字を含む
+    InvalidCharacter,
+    /// 複数の Idempotency-Key ヘッダー
+    Multiple,
+}
+
+impl fmt::Display for IdempotencyKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdempotencyKeyError::InvalidFormat => {
+                write!(f, "invalid Idempotency-Key header format")
+            }
+            IdempotencyKeyError::Empty => write!(f, "empty idempotency key"),
+            IdempotencyKeyError::TooLong => write!(f, "idempotency key too long"),
+            IdempotencyKeyError::InvalidCharacter => {
+                write!(f, "invalid character in idempotency key")
+            }
+            IdempotencyKeyError::Multiple => write!(f, "multiple Idempotency-Key headers"),
+        }
+    }
+}
+
+impl core::error::Error for IdempotencyKeyError {}
+
+/// Idempotency-Key ヘッダー
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey {
+    key: String,
+}
+
+impl IdempotencyKey {
+    /// キーを指定して作成
+    ///
+    /// キーは 1 バイト以上 `DEFAULT_MAX_KEY_LENGTH` バイト以下の印字可能な ASCII 文字列。
+    pub fn new(key: &str) -> Result<Self, IdempotencyKeyError> {
+        Self::with_max_length(key, DEFAULT_MAX_KEY_LENGTH)
+    }
+
+    /// 最大長を指定してキーを作成
+    pub fn with_max_length(key: &str, max_length: usize) -> Result<Self, IdempotencyKeyError> {
+        if key.is_empty() {
+            return Err(IdempotencyKeyError::Empty);
+        }
+        if !is_valid_string(key) {
+            return Err(IdempotencyKeyError::InvalidCharacter);
+        }
+        if key.len() > max_length {
+            return Err(IdempotencyKeyError::TooLong);
+        }
+        Ok(IdempotencyKey {
+            key: key.to_string(),
+        })
+    }
+
+    /// Idempotency-Key ヘッダーをパース
+    ///
+    /// sf-string のパラメーターは無視する。
+    pub fn parse(input: &str) -> Result<Self, IdempotencyKeyError> {
+        Self::parse_with_max_length(input, DEFAULT_MAX_KEY_LENGTH)
+    }
+
+    /// 最大長を指定して Idempotency-Key ヘッダーをパース
+    pub fn parse_with_max_length(
+        input: &str,
+        max_length: usize,
+    ) -> Result<Self, IdempotencyKeyError> {
+        let item = Item::parse(input).map_err(|_| IdempotencyKeyError::InvalidFormat)?;
+        let key = item
+            .bare_item()
+            .as_string()
+            .ok_or(IdempotencyKeyError::InvalidFormat)?;
+        Self::with_max_length(key, max_length)
+    }
+
+    /// リクエストの Idempotency-Key ヘッダーを取得
+    ///
+    /// ヘッダーがない場合は `None` を返す。複数行ある場合は `Multiple` を返す。
+    pub fn from_request<H: HttpHead>(request: &H) -> Result<Option<Self>, IdempotencyKeyError> {
+        let values = request.get_headers("Idempotency-Key");
+        match values.as_slice() {
+            [] => Ok(None),
+            [value] => Self::parse(value).map(Some),
+            _ => Err(IdempotencyKeyError::Multiple),
+        }
+    }
+
+    /// キー
+    pub fn as_str(&self) -> &str {
+        &self.key
+    }
+}
+
+impl fmt::Display for IdempotencyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_string(f, &self.key)
+    }
+}
+
+impl TypedHeader for IdempotencyKey {
+    const NAME: &'static str = "Idempotency-Key";
+    type Error = IdempotencyKeyError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// リクエストの冪等性の分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestIdempotency {
+    /// safe メソッド (GET / HEAD / OPTIONS / TRACE、RFC 9110 Section 9.2.1)
+    Safe,
+    /// safe ではないが idempotent なメソッド (PUT / DELETE、RFC 9110 Section 9.2.2)
+    Idempotent,
+    /// idempotent ではないメソッドだが Idempotency-Key を持つ
+    KeyProtected,
+    /// idempotent ではないメソッド (POST / PATCH / CONNECT / 拡張メソッド)
+    NonIdempotent,
+}
+
+impl RequestIdempotency {
+    /// メソッドと Idempotency-Key の有無からリクエストを分類する
+    ///
+    /// メソッドが token として不正な場合は `NonIdempotent` とする。
+    pub fn classify(method: &str, key: Option<&IdempotencyKey>) -> Self {
+        let method = Method::parse(method).ok();
+        if method.as_ref().is_some_and(Method::is_safe) {
+            RequestIdempotency::Safe
+        } else if method.as_ref().is_some_and(Method::is_idempotent) {
+            RequestIdempotency::Idempotent
+        } else if key.is_some() {
+            RequestIdempotency::KeyProtected
+        } else {
+            RequestIdempotency::NonIdempotent
+        }
+    }
+
+    /// リクエストの状態を変更しないか (safe メソッドか)
+    pub fn is_safe(&self) -> bool {
+        *self == RequestIdempotency::Safe
+    }
+
+    /// レスポンスを受け取れなかった場合に自動的に再送してよいか
+    ///
+    /// RFC 9110 Section 9.2.2: idempotent なメソッドのリクエストは再送してよい。
+    /// Idempotency-Key を持つリクエストも、サーバーが重複を検出するため再送してよい。
+    pub fn is_retryable(&self) -> bool {
+        *self != RequestIdempotency::NonIdempotent
+    }
+}
@@ -0,0 +1,134 @@
+//! JSON ボディの変換 (`serde_json` feature)
+//!
+//! ## 概要
+//!
+//! `serde` でシリアライズ可能な値を `application/json` のボディとして設定し、
+//! 受信したボディを `serde::de::DeserializeOwned` な型に変換する API を
+//! `Request` / `Response` に追加する。
+//!
+//! - `json_body()`: 値を JSON にシリアライズしてボディに設定し、`Content-Type` を
+//!   `application/json` に置き換える
+//! - `parse_json()`: `Content-Type` が JSON (`application/json` または `+json` サフィックス、
+//!   RFC 6839 Section 3.1) であることを確認してからボディをデシリアライズする
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::{Response, StatusCode};
+//!
+//! let response = Response::with_status(StatusCode::OK)
+//!     .json_body(&["a", "b"])
+//!     .unwrap();
+//! assert_eq!(response.get_header("Content-Type"), Some("application/json"));
+//! assert_eq!(response.body_bytes(), Some(&br#"["a","b"]"#[..]));
+//!
+//! let values: Vec<String> = response.parse_json().unwrap();
+//! assert_eq!(values, ["a", "b"]);
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::content_type::ContentType;
+use crate::request::Request;
+use crate::response::Response;
+
+/// JSON のメディアタイプ (RFC 8259 Section 11)
+pub const CONTENT_TYPE: &str = "application/json";
+
+/// JSON ボディの変換エラー
+///
+/// `serde_json::Error` を保持するため `Clone` / `PartialEq` は実装しない。
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JsonError {
+    /// `Content-Type` がない、または JSON ではない
+    UnexpectedContentType { content_type: Option<String> },
+    /// シリアライズ / デシリアライズのエラー
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::UnexpectedContentType {
+                content_type: Some(content_type),
+            } => write!(f, "unexpected Content-Type for JSON: {:?}", content_type),
+            JsonError::UnexpectedContentType { content_type: None } => {
+                write!(f, "missing Content-Type for JSON")
+            }
+            JsonError::Json(e) => write!(f, "JSON error: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for JsonError {}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(e: serde_json::Error) -> Self {
+        JsonError::Json(e)
+    }
+}
+
+/// `Content-Type` の値が JSON を表すか
+///
+/// `application/json` と、構造化構文サフィックス `+json` (RFC 6839 Section 3.1) を受理する。
+fn is_json_content_type(value: &str) -> bool {
+    ContentType::parse(value).is_ok_and(|ct| {
+        ct.media_type().eq_ignore_ascii_case("application")
+            && (ct.subtype().eq_ignore_ascii_case("json")
+                || ct.subtype().to_ascii_lowercase().ends_with("+json"))
+    })
+}
+
+/// `Content-Type` を確認してボディをデシリアライズする
+fn parse_json_body<T: DeserializeOwned>(
+    content_type: Option<&str>,
+    body: Option<&[u8]>,
+) -> Result<T, JsonError> {
+    if !content_type.is_some_and(is_json_content_type) {
+        return Err(JsonError::UnexpectedContentType {
+            content_type: content_type.map(String::from),
+        });
+    }
+    Ok(serde_json::from_slice(body.unwrap_or(&[]))?)
+}
+
+impl Request {
+    /// 値を JSON にシリアライズしてボディに設定 (ビルダーパターン)
+    ///
+    /// `Content-Type` は `application/json` に置き換える。
+    pub fn json_body<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, JsonError> {
+        let body = serde_json::to_vec(value)?;
+        self.replace_valid_header("Content-Type", CONTENT_TYPE);
+        Ok(self.body(body))
+    }
+
+    /// ボディを JSON としてデシリアライズする
+    ///
+    /// `Content-Type` が JSON でない場合は `JsonError::UnexpectedContentType` を返す。
+    pub fn parse_json<T: DeserializeOwned>(&self) -> Result<T, JsonError> {
+        parse_json_body(self.get_header("Content-Type"), self.body_bytes())
+    }
+}
+
+impl Response {
+    /// 値を JSON にシリアライズしてボディに設定 (ビルダーパターン)
+    ///
+    /// `Content-Type` は `application/json` に置き換える。
+    pub fn json_body<T: Serialize + ?Sized>(mut self, value: &T) -> Result<Self, JsonError> {
+        let body = serde_json::to_vec(value)?;
+        self.replace_valid_header("Content-Type", CONTENT_TYPE);
+        Ok(self.body(body))
+    }
+
+    /// ボディを JSON としてデシリアライズする
+    ///
+    /// `Content-Type` が JSON でない場合は `JsonError::UnexpectedContentType` を返す。
+    pub fn parse_json<T: DeserializeOwned>(&self) -> Result<T, JsonError> {
+        parse_json_body(self.get_header("Content-Type"), self.body_bytes())
+    }
+}
@@ -8,6 +8,13 @@
 //! - **Sans I/O**: I/O を完全に分離した設計
 //! - **柔軟性**: HTTP/1.1, RTSP/1.0, RTSP/2.0 等に対応
 //!
+//! ## feature
+//!
+//! - `std`: `std::io::Write` へ書き出すエンコード API (`Request::encode_to_writer()` 等) と
+//!   `HttpDate` と `SystemTime` の相互変換 (`HttpDate::from_system_time()` 等) を有効にする
+//! - `serde_json`: JSON ボディの変換 API (`Request::json_body()` / `Response::parse_json()` 等) を有効にする。
+//!   `serde` / `serde_json` に依存する
+//!
 //! ## 使い方
 //!
 //! ### クライアント (リクエスト送信、レスポンス受信)
@@ -59,49 +66,102 @@
 
 #![cfg_attr(not(test), no_std)]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod accept;
+pub mod allow;
 pub mod auth;
 mod base64;
 pub mod cache;
+pub mod canonical;
+pub mod charset;
 pub mod compression;
 pub mod conditional;
+pub mod conformance;
+pub mod connection;
+pub mod content_decoder;
 pub mod content_disposition;
 pub mod content_encoding;
 pub mod content_language;
 pub mod content_location;
 pub mod content_type;
 pub mod cookie;
+pub mod cors;
 pub mod date;
 mod decoder;
+pub mod deprecation;
 pub mod digest_fields;
 mod encoder;
 mod error;
 pub mod etag;
 pub mod expect;
+pub mod ext_value;
+pub mod fetch_metadata;
+pub mod form;
+pub mod forwarded;
+mod header_name;
 pub mod host;
+pub mod idempotency_key;
+#[cfg(feature = "serde_json")]
+pub mod json;
 mod limits;
+pub mod link;
+pub mod lint;
+pub mod message_signature;
+pub mod method;
 pub mod multipart;
+pub mod negotiation;
+pub mod prefer;
+pub mod proxy;
+pub mod proxy_status;
 pub mod range;
+pub mod rate_limit;
+pub mod redirect;
 mod request;
 pub mod request_target;
 mod response;
+pub mod retry;
+pub mod retry_after;
+pub mod security_headers;
+pub mod server_timing;
+pub mod sfv;
+mod sha256;
+pub mod sse;
+pub mod static_content;
 pub mod status_code;
+pub mod te;
+pub mod trace;
 pub mod trailer;
+pub mod typed_header;
 pub mod upgrade;
 pub mod uri;
 mod validate;
 pub mod vary;
+pub mod version;
+pub mod via;
 
 pub use decoder::{
-    BodyKind, BodyProgress, HttpHead, RequestDecoder, RequestHead, ResponseDecoder, ResponseHead,
+    BodyKind, BodyProgress, BodyRead, DecoderProfile, DecoderStats, HttpHead, NonUtf8Policy,
+    RequestDecoder, RequestHead, ResponseDecoder, ResponseHead,
+};
+pub use encoder::{
+    EncodedParts, HeaderCase, RequestEncoder, ResponseEncoder, encode_chunk, encode_chunk_with_ext,
+    encode_chunks, encode_interim_response, encode_request, encode_request_headers,
+    encode_request_headers_into, encode_request_into, encode_request_vectored, encode_response,
+    encode_response_headers, encode_response_headers_into, encode_response_into,
+    encode_response_vectored, encode_response_with_interim,
 };
+#[cfg(feature = "std")]
 pub use encoder::{
-    RequestEncoder, ResponseEncoder, encode_chunk, encode_chunks, encode_request,
-    encode_request_headers, encode_response, encode_response_headers,
+    encode_request_headers_to_writer, encode_request_to_writer, encode_response_headers_to_writer,
+    encode_response_to_writer,
 };
-pub use error::{EncodeError, Error};
+pub use error::{EncodeError, Error, ParsePhase};
 pub use limits::DecoderLimits;
-pub use request::Request;
+pub use method::Method;
+pub use request::{Endpoint, Request};
 pub use response::Response;
 pub use status_code::{StatusClass, StatusCode};
+pub use typed_header::TypedHeader;
+pub use version::HttpVersion;
@@ -15,6 +15,17 @@ pub struct DecoderLimits {
     /// チャンクサイズは 16 進数で表現されるため、通常は非常に短い。
     /// 例: "FFFFFFFF\r\n" (4GB) でも 10 バイト程度。
     pub max_chunk_line_size: usize,
+    /// 最大 request-target 長 (デフォルト: 8KB)
+    ///
+    /// 超過した場合は `Error::UriTooLong` を返す (414 URI Too Long に対応)。
+    /// request-line の CRLF を待たずに、受信済みのバイト列で判定する。
+    /// `RequestDecoder` のみで使用する。
+    pub max_uri_length: usize,
+    /// 最大メソッド長 (デフォルト: 32バイト)
+    ///
+    /// 超過した場合は `Error::MethodTooLong` を返す (501 Not Implemented に対応)。
+    /// `RequestDecoder` のみで使用する。
+    pub max_method_length: usize,
 }
 
 impl Default for DecoderLimits {
@@ -25,6 +36,8 @@ impl Default for DecoderLimits {
             max_header_line_size: 8 * 1024,  // 8KB
             max_body_size: 10 * 1024 * 1024, // 10MB
             max_chunk_line_size: 64,         // 64 bytes
+            max_uri_length: 8 * 1024,        // 8KB
+            max_method_length: 32,           // 32 bytes
         }
     }
 }
@@ -44,6 +57,8 @@ impl DecoderLimits {
             max_header_line_size: usize::MAX,
             max_body_size: usize::MAX,
             max_chunk_line_size: usize::MAX,
+            max_uri_length: usize::MAX,
+            max_method_length: usize::MAX,
         }
     }
 }
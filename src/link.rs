@@ -0,0 +1,515 @@
+//! Link ヘッダー (RFC 8288)
+//!
+//! ## 概要
+//!
+//! RFC 8288 に基づいた Link ヘッダーのパースと生成を提供します。
+//! ページネーションのリンクをたどるクライアントや、103 Early Hints (RFC 8297) で
+//! preload ヒントを送るサーバーで使います。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Link       = #link-value
+//! link-value = "<" URI-Reference ">" *( OWS ";" OWS link-param )
+//! link-param = token BWS [ "=" BWS ( token / quoted-string ) ]
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::link::Link;
+//! use shiguredo_http11::uri::Uri;
+//!
+//! let link = Link::parse(
+//!     r#"<https://api.example.com/items?page=2>; rel="next", </items?page=9>; rel=last; title*=UTF-8'en'last%20page"#,
+//! )
+//! .unwrap();
+//! let next = link.find_rel("next").unwrap();
+//! assert_eq!(next.target(), "https://api.example.com/items?page=2");
+//!
+//! // 相対参照はリクエスト URI を基準に解決する
+//! let last = link.find_rel("last").unwrap();
+//! assert_eq!(last.title(), Some("last page"));
+//! let base = Uri::parse("https://api.example.com/items?page=1").unwrap();
+//! assert_eq!(
+//!     last.resolve_target(&base).unwrap().as_str(),
+//!     "https://api.example.com/items?page=9"
+//! );
+//! ```
+//!
+//! ## 103 Early Hints
+//!
+//! ```rust
+//! use shiguredo_http11::Response;
+//! use shiguredo_http11::link::LinkValue;
+//!
+//! let style = LinkValue::new("/style.css")
+//!     .unwrap()
+//!     .with_rel("preload")
+//!     .unwrap()
+//!     .with_param("as", Some("style"))
+//!     .unwrap();
+//! assert_eq!(style.to_string(), "</style.css>; rel=preload; as=style");
+//!
+//! let hints = Response::early_hints([style.to_string()]).unwrap();
+//! assert_eq!(
+//!     hints.get_header("Link"),
+//!     Some("</style.css>; rel=preload; as=style")
+//! );
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::ext_value::ExtValue;
+use crate::typed_header::TypedHeader;
+use crate::uri::{Uri, UriError};
+use crate::validate::{
+    QuotedStringError, escape_quotes, is_token_char, is_valid_token, parse_quoted_string,
+};
+
+/// Link パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LinkError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正なリンク先 (`<` `>` で囲まれた URI-Reference)
+    InvalidTarget,
+    /// 不正なパラメータ名
+    InvalidParameterName,
+    /// 不正なパラメータ値 (qdtext / quoted-pair の文字種違反を含む)
+    InvalidParameterValue,
+    /// quoted-string の閉じ DQUOTE が見つからない (RFC 9110 Section 5.6.4)
+    UnterminatedQuote,
+    /// title* の ext-value が不正 (RFC 8187)
+    InvalidExtValue,
+    /// 不正なリレーションタイプ
+    InvalidRelation,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::InvalidFormat => write!(f, "invalid Link header format"),
+            LinkError::InvalidTarget => write!(f, "invalid Link target"),
+            LinkError::InvalidParameterName => write!(f, "invalid Link parameter name"),
+            LinkError::InvalidParameterValue => write!(f, "invalid Link parameter value"),
+            LinkError::UnterminatedQuote => write!(f, "unterminated quoted-string"),
+            LinkError::InvalidExtValue => write!(f, "invalid ext-value encoding"),
+            LinkError::InvalidRelation => write!(f, "invalid Link relation type"),
+        }
+    }
+}
+
+impl core::error::Error for LinkError {}
+
+impl From<QuotedStringError> for LinkError {
+    fn from(e: QuotedStringError) -> Self {
+        match e {
+            QuotedStringError::InvalidQdtext | QuotedStringError::InvalidQuotedPair => {
+                LinkError::InvalidParameterValue
+            }
+            QuotedStringError::Unterminated => LinkError::UnterminatedQuote,
+        }
+    }
+}
+
+/// link-value (1 つのリンク)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkValue {
+    target: String,
+    rel: Vec<String>,
+    anchor: Option<String>,
+    title: Option<String>,
+    title_ext: Option<ExtValue>,
+    /// rel / anchor / title / title* 以外のパラメータ (名前は小文字、値のないパラメータは `None`)
+    parameters: Vec<(String, Option<String>)>,
+}
+
+impl LinkValue {
+    /// リンク先から LinkValue を作成
+    ///
+    /// リンク先は URI-Reference で、空白・制御文字・`<` / `>` を含めることはできない。
+    pub fn new(target: &str) -> Result<Self, LinkError> {
+        if !is_valid_target(target) {
+            return Err(LinkError::InvalidTarget);
+        }
+        Ok(LinkValue {
+            target: target.to_string(),
+            rel: Vec::new(),
+            anchor: None,
+            title: None,
+            title_ext: None,
+            parameters: Vec::new(),
+        })
+    }
+
+    /// リレーションタイプを追加
+    ///
+    /// 空白区切りで複数指定できる (例: `"preload prefetch"`)。
+    pub fn with_rel(mut self, rel: &str) -> Result<Self, LinkError> {
+        let relations = parse_relations(rel)?;
+        if relations.is_empty() {
+            return Err(LinkError::InvalidRelation);
+        }
+        self.rel.extend(relations);
+        Ok(self)
+    }
+
+    /// anchor (リンクのコンテキスト) を設定
+    pub fn with_anchor(mut self, anchor: &str) -> Result<Self, LinkError> {
+        if !is_valid_target(anchor) {
+            return Err(LinkError::InvalidParameterValue);
+        }
+        self.anchor = Some(anchor.to_string());
+        Ok(self)
+    }
+
+    /// title を設定
+    pub fn with_title(mut self, title: &str) -> Result<Self, LinkError> {
+        if !is_valid_text(title) {
+            return Err(LinkError::InvalidParameterValue);
+        }
+        self.title = Some(title.to_string());
+        Ok(self)
+    }
+
+    /// title* (RFC 8187 ext-value) を設定
+    ///
+    /// ASCII 以外の文字を含むタイトルに使う。
+    pub fn with_title_ext(mut self, title: ExtValue) -> Self {
+        self.title_ext = Some(title);
+        self
+    }
+
+    /// パラメータを追加
+    ///
+    /// rel / anchor / title / title* は専用のメソッドで設定する。
+    /// 値のないパラメータ (例: `crossorigin`) は `None` を指定する。
+    pub fn with_param(mut self, name: &str, value: Option<&str>) -> Result<Self, LinkError> {
+        if !is_valid_token(name) {
+            return Err(LinkError::InvalidParameterName);
+        }
+        let name = name.to_ascii_lowercase();
+        if matches!(name.as_str(), "rel" | "anchor" | "title" | "title*") {
+            return Err(LinkError::InvalidParameterName);
+        }
+        if let Some(value) = value
+            && !is_valid_text(value)
+        {
+            return Err(LinkError::InvalidParameterValue);
+        }
+        self.parameters.push((name, value.map(ToString::to_string)));
+        Ok(self)
+    }
+
+    /// リンク先 (URI-Reference)
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// リンク先をベース URI で解決する
+    ///
+    /// RFC 8288 Section 3.1: リンク先が相対参照の場合はリクエスト URI を基準に解決する。
+    pub fn resolve_target(&self, base: &Uri) -> Result<Uri, UriError> {
+        base.join(&self.target)
+    }
+
+    /// リレーションタイプ一覧
+    pub fn rel(&self) -> &[String] {
+        &self.rel
+    }
+
+    /// 指定したリレーションタイプを含むかどうか (大文字小文字を区別しない)
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel.iter().any(|r| r.eq_ignore_ascii_case(rel))
+    }
+
+    /// anchor
+    pub fn anchor(&self) -> Option<&str> {
+        self.anchor.as_deref()
+    }
+
+    /// title を取得 (title* があればそちらを優先)
+    ///
+    /// RFC 8288 Section 3.4.1: title* と title の両方がある場合、title* を優先する。
+    pub fn title(&self) -> Option<&str> {
+        self.title_ext
+            .as_ref()
+            .map(ExtValue::value)
+            .or(self.title.as_deref())
+    }
+
+    /// title* パラメータ
+    pub fn title_ext(&self) -> Option<&ExtValue> {
+        self.title_ext.as_ref()
+    }
+
+    /// type パラメータ (リンク先のメディアタイプのヒント)
+    pub fn media_type(&self) -> Option<&str> {
+        self.param("type")
+    }
+
+    /// パラメータの値を取得 (名前は大文字小文字を区別しない)
+    ///
+    /// 同名のパラメータが複数ある場合は最初の値を返す。
+    /// 値のないパラメータは `None` を返すため、有無は `has_param()` で確認する。
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    /// パラメータがあるかどうか (名前は大文字小文字を区別しない)
+    pub fn has_param(&self, name: &str) -> bool {
+        self.parameters
+            .iter()
+            .any(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+
+    /// rel / anchor / title / title* 以外のパラメータ一覧
+    pub fn parameters(&self) -> &[(String, Option<String>)] {
+        &self.parameters
+    }
+}
+
+impl fmt::Display for LinkValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}>", self.target)?;
+        if !self.rel.is_empty() {
+            write_param(f, "rel", &self.rel.join(" "))?;
+        }
+        if let Some(anchor) = &self.anchor {
+            write_param(f, "anchor", anchor)?;
+        }
+        if let Some(title) = &self.title {
+            write_param(f, "title", title)?;
+        }
+        if let Some(title_ext) = &self.title_ext {
+            write!(f, "; title*={}", title_ext)?;
+        }
+        for (name, value) in &self.parameters {
+            match value {
+                Some(value) => write_param(f, name, value)?,
+                None => write!(f, "; {}", name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Link ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Link {
+    values: Vec<LinkValue>,
+}
+
+impl Link {
+    /// 空の Link を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Link ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 空のリスト要素は無視する。
+    /// RFC 8288 Section 3.3 / 3.4.1: rel / anchor / title / title* が
+    /// 複数回現れた場合は最初のものを使い、以降は無視する。
+    pub fn parse(input: &str) -> Result<Self, LinkError> {
+        let mut values = Vec::new();
+        let mut rest = input;
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ',']);
+            if rest.is_empty() {
+                break;
+            }
+            let (value, remaining) = parse_link_value(rest)?;
+            values.push(value);
+            rest = remaining;
+        }
+        Ok(Link { values })
+    }
+
+    /// メッセージの Link ヘッダーを取得
+    ///
+    /// 複数行の Link は結合する。Link ヘッダーがない場合は `None` を返す。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, LinkError> {
+        let values = message.get_headers("Link");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Link::parse(&values.join(", ")).map(Some)
+    }
+
+    /// リンク一覧
+    pub fn values(&self) -> &[LinkValue] {
+        &self.values
+    }
+
+    /// 指定したリレーションタイプを持つ最初のリンクを取得
+    pub fn find_rel(&self, rel: &str) -> Option<&LinkValue> {
+        self.values.iter().find(|value| value.has_rel(rel))
+    }
+
+    /// 指定したリレーションタイプを持つリンクをすべて取得
+    pub fn filter_rel<'a>(&'a self, rel: &'a str) -> impl Iterator<Item = &'a LinkValue> {
+        self.values.iter().filter(move |value| value.has_rel(rel))
+    }
+
+    /// 末尾にリンクを追加
+    pub fn push(&mut self, value: LinkValue) {
+        self.values.push(value);
+    }
+
+    /// 末尾にリンクを追加 (ビルダー)
+    pub fn with_value(mut self, value: LinkValue) -> Self {
+        self.values.push(value);
+        self
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = self.values.iter().map(|v| v.to_string()).collect();
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+impl TypedHeader for Link {
+    const NAME: &'static str = "Link";
+    type Error = LinkError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// link-value を 1 つパースし、残りの入力を返す
+fn parse_link_value(input: &str) -> Result<(LinkValue, &str), LinkError> {
+    let rest = input.strip_prefix('<').ok_or(LinkError::InvalidFormat)?;
+    let end = rest.find('>').ok_or(LinkError::InvalidTarget)?;
+    let mut value = LinkValue::new(&rest[..end])?;
+    let mut rest = &rest[end + 1..];
+
+    let mut seen_rel = false;
+    loop {
+        rest = rest.trim_start_matches([' ', '\t']);
+        let Some(after) = rest.strip_prefix(';') else {
+            break;
+        };
+        rest = after.trim_start_matches([' ', '\t']);
+        if rest.is_empty() || rest.starts_with([';', ',']) {
+            continue;
+        }
+
+        let name_end = rest
+            .bytes()
+            .position(|b| !is_token_char(b))
+            .unwrap_or(rest.len());
+        if name_end == 0 {
+            return Err(LinkError::InvalidParameterName);
+        }
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start_matches([' ', '\t']);
+
+        let param_value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start_matches([' ', '\t']);
+            if let Some(quoted) = after.strip_prefix('"') {
+                let (param_value, remaining) = parse_quoted_string(quoted)?;
+                rest = remaining;
+                Some(param_value)
+            } else {
+                let value_end = after
+                    .bytes()
+                    .position(|b| !is_token_char(b))
+                    .unwrap_or(after.len());
+                if value_end == 0 {
+                    return Err(LinkError::InvalidParameterValue);
+                }
+                rest = &after[value_end..];
+                Some(after[..value_end].to_string())
+            }
+        } else {
+            None
+        };
+
+        match name.as_str() {
+            "rel" => {
+                if !seen_rel {
+                    seen_rel = true;
+                    value.rel = parse_relations(param_value.as_deref().unwrap_or(""))?;
+                }
+            }
+            "anchor" => {
+                if value.anchor.is_none() {
+                    let anchor = param_value.ok_or(LinkError::InvalidParameterValue)?;
+                    value = value.with_anchor(&anchor)?;
+                }
+            }
+            "title" => {
+                if value.title.is_none() {
+                    value.title = Some(param_value.ok_or(LinkError::InvalidParameterValue)?);
+                }
+            }
+            "title*" => {
+                if value.title_ext.is_none() {
+                    let ext = param_value.ok_or(LinkError::InvalidExtValue)?;
+                    value.title_ext =
+                        Some(ExtValue::parse(&ext).map_err(|_| LinkError::InvalidExtValue)?);
+                }
+            }
+            _ => value.parameters.push((name, param_value)),
+        }
+    }
+
+    if !rest.is_empty() && !rest.starts_with(',') {
+        return Err(LinkError::InvalidFormat);
+    }
+    Ok((value, rest))
+}
+
+fn parse_relations(input: &str) -> Result<Vec<String>, LinkError> {
+    input
+        .split_ascii_whitespace()
+        .map(|rel| {
+            if rel
+                .bytes()
+                .all(|b| b.is_ascii_graphic() && b != b'"' && b != b'\\')
+            {
+                Ok(rel.to_string())
+            } else {
+                Err(LinkError::InvalidRelation)
+            }
+        })
+        .collect()
+}
+
+/// URI-Reference として送れる文字のみで構成されているか
+fn is_valid_target(target: &str) -> bool {
+    target
+        .bytes()
+        .all(|b| b.is_ascii_graphic() && b != b'<' && b != b'>' && b != b'"' && b != b'\\')
+}
+
+/// quoted-string として送れる文字のみで構成されているか
+fn is_valid_text(value: &str) -> bool {
+    value
+        .chars()
+        .all(|c| c == '\t' || (c >= ' ' && c != '\u{7f}'))
+}
+
+fn write_param(f: &mut fmt::Formatter<'_>, name: &str, value: &str) -> fmt::Result {
+    if is_valid_token(value) {
+        write!(f, "; {}={}", name, value)
+    } else {
+        write!(f, "; {}=\"{}\"", name, escape_quotes(value))
+    }
+}
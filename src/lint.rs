@@ -0,0 +1,460 @@
+//! HTTP メッセージの検査 (RFC 9110 / RFC 9112)
+//!
+//! ## 概要
+//!
+//! `Request` / `Response` を検査し、仕様違反や相互運用上の問題を構造化された
+//! `Violation` のリストとして返します。
+//!
+//! `encode()` は最初に見つかった違反でエラーを返すが、本モジュールはすべての違反を
+//! 列挙する。エンコード時には検出しない問題 (304 レスポンスのボディ、重複した単一値
+//! ヘッダー、ヘッダー値前後の空白など) も報告するため、テストスイートや
+//! ゲートウェイでのメッセージ監査に使える。
+//!
+//! - `Severity::Error`: RFC の MUST / MUST NOT に違反している
+//! - `Severity::Warning`: 受信側によって解釈が分かれる、または推奨されない
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::Response;
+//! use shiguredo_http11::lint::{Severity, Violation, validate_response};
+//!
+//! let response = Response::new(204, "No Content")
+//!     .unwrap()
+//!     .header("Content-Length", "5")
+//!     .unwrap()
+//!     .body(b"hello".to_vec());
+//!
+//! let violations = validate_response(&response);
+//! assert_eq!(
+//!     violations,
+//!     vec![
+//!         Violation::ForbiddenFramingHeader {
+//!             status_code: 204,
+//!             name: "Content-Length".to_string(),
+//!         },
+//!         Violation::BodyNotAllowed { status_code: 204 },
+//!     ]
+//! );
+//! assert!(violations.iter().all(|v| v.severity() == Severity::Error));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::encoder::{
+    reject_http_empty_host, reject_http_userinfo, response_status_has_body, validate_host_header,
+    validate_request_target_form,
+};
+use crate::error::EncodeError;
+use crate::request::Request;
+use crate::response::Response;
+use crate::validate::{is_valid_field_value, is_valid_header_name, is_valid_token, trim_ows};
+
+/// 重複してはならない単一値のヘッダー
+///
+/// RFC 9110 Section 5.3: リスト形式ではないフィールドは同名の行を複数持てない
+const SINGLETON_FIELDS: &[&str] = &[
+    "Age",
+    "Authorization",
+    "Content-Location",
+    "Content-Type",
+    "Date",
+    "ETag",
+    "Expires",
+    "If-Modified-Since",
+    "If-Range",
+    "If-Unmodified-Since",
+    "Last-Modified",
+    "Location",
+    "Max-Forwards",
+    "Proxy-Authorization",
+    "Referer",
+    "Retry-After",
+];
+
+/// 違反の重大度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// 推奨されない、または受信側によって解釈が分かれる
+    Warning,
+    /// RFC の要件 (MUST / MUST NOT) に違反している
+    Error,
+}
+
+/// 検出された違反
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Violation {
+    /// ヘッダー名が token ではない (RFC 9110 Section 5.1)
+    InvalidHeaderName { name: String },
+    /// ヘッダー値に使えない文字を含む (RFC 9110 Section 5.5)
+    InvalidHeaderValue { name: String },
+    /// ヘッダー値の前後に空白がある (RFC 9110 Section 5.5)
+    SurroundingWhitespace { name: String },
+    /// 単一値のヘッダーが複数行ある (RFC 9110 Section 5.3)
+    DuplicateSingletonField { name: String },
+    /// request-target がメソッドに対して不正な形式 (RFC 9112 Section 3.2)
+    InvalidRequestTarget { uri: String },
+    /// HTTP/1.1 リクエストに Host ヘッダーがない (RFC 9112 Section 3.2)
+    MissingHost,
+    /// Host ヘッダーが複数行ある (RFC 9112 Section 3.2)
+    DuplicateHost,
+    /// Host ヘッダーの値が不正 (RFC 9110 Section 7.2)
+    InvalidHost { value: String },
+    /// Host ヘッダーが request-target の authority と一致しない (RFC 9112 Section 3.2)
+    HostAuthorityMismatch { host: String, authority: String },
+    /// authority を持たない request-target に空ではない Host ヘッダーがある (RFC 9112 Section 3.2)
+    NonEmptyHostWithoutAuthority { host: String },
+    /// Transfer-Encoding と Content-Length の両方がある (RFC 9112 Section 6.2)
+    ConflictingFraming,
+    /// Transfer-Encoding の値が不正、または chunked が複数回適用されている (RFC 9112 Section 6.1)
+    InvalidTransferEncoding { value: String },
+    /// リクエストの Transfer-Encoding の最後が chunked ではない (RFC 9112 Section 6.1)
+    ChunkedNotFinal,
+    /// HTTP/1.0 のメッセージに Transfer-Encoding がある (RFC 9112 Section 6.1)
+    TransferEncodingInHttp10,
+    /// Content-Length の値が `1*DIGIT` ではない (RFC 9110 Section 8.6)
+    InvalidContentLength { value: String },
+    /// Content-Length が異なる値で複数ある (RFC 9110 Section 8.6)
+    DuplicateContentLength,
+    /// Content-Length とボディの長さが一致しない (RFC 9110 Section 8.6)
+    ContentLengthMismatch { header_value: u64, body_length: u64 },
+    /// ステータスコードに対して送信できないフレーミングヘッダーがある (RFC 9110 Section 8.6, RFC 9112 Section 6.1)
+    ForbiddenFramingHeader { status_code: u16, name: String },
+    /// ボディを持てないステータスコードのレスポンスにボディがある (RFC 9110 Section 6.4.1, 15.3.6)
+    BodyNotAllowed { status_code: u16 },
+    /// ボディを送信してはならないメソッドのリクエストにボディがある (RFC 9110 Section 9.3.8)
+    BodyNotAllowedForMethod { method: String },
+}
+
+impl Violation {
+    /// 違反の重大度
+    pub fn severity(&self) -> Severity {
+        match self {
+            Violation::SurroundingWhitespace { .. }
+            | Violation::DuplicateSingletonField { .. }
+            | Violation::TransferEncodingInHttp10 => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// RFC の要件に違反しているか
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::InvalidHeaderName { name } => write!(f, "invalid header name: {name}"),
+            Violation::InvalidHeaderValue { name } => {
+                write!(f, "invalid character in header value: {name}")
+            }
+            Violation::SurroundingWhitespace { name } => {
+                write!(f, "leading or trailing whitespace in header value: {name}")
+            }
+            Violation::DuplicateSingletonField { name } => {
+                write!(f, "duplicate singleton header: {name}")
+            }
+            Violation::InvalidRequestTarget { uri } => write!(f, "invalid request target: {uri}"),
+            Violation::MissingHost => write!(f, "missing Host header"),
+            Violation::DuplicateHost => write!(f, "duplicate Host header"),
+            Violation::InvalidHost { value } => write!(f, "invalid Host header: {value}"),
+            Violation::HostAuthorityMismatch { host, authority } => write!(
+                f,
+                "Host header does not match request target authority: {host} != {authority}"
+            ),
+            Violation::NonEmptyHostWithoutAuthority { host } => write!(
+                f,
+                "non-empty Host header for request target without authority: {host}"
+            ),
+            Violation::ConflictingFraming => {
+                write!(f, "both Transfer-Encoding and Content-Length are present")
+            }
+            Violation::InvalidTransferEncoding { value } => {
+                write!(f, "invalid Transfer-Encoding: {value}")
+            }
+            Violation::ChunkedNotFinal => {
+                write!(f, "chunked is not the final transfer coding of request")
+            }
+            Violation::TransferEncodingInHttp10 => {
+                write!(f, "Transfer-Encoding in HTTP/1.0 message")
+            }
+            Violation::InvalidContentLength { value } => {
+                write!(f, "invalid Content-Length: {value}")
+            }
+            Violation::DuplicateContentLength => {
+                write!(f, "multiple Content-Length headers with different values")
+            }
+            Violation::ContentLengthMismatch {
+                header_value,
+                body_length,
+            } => write!(
+                f,
+                "Content-Length mismatch: header={header_value}, body={body_length}"
+            ),
+            Violation::ForbiddenFramingHeader { status_code, name } => {
+                write!(f, "{name} not allowed for status {status_code}")
+            }
+            Violation::BodyNotAllowed { status_code } => {
+                write!(f, "body not allowed for status {status_code}")
+            }
+            Violation::BodyNotAllowedForMethod { method } => {
+                write!(f, "body not allowed for method {method}")
+            }
+        }
+    }
+}
+
+/// リクエストを検査する
+///
+/// 違反がない場合は空の Vec を返す。違反はヘッダー、request-target、Host、
+/// フレーミング、ボディの順に並ぶ。
+pub fn validate_request(request: &Request) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    check_headers(HttpHead::headers(request), &mut violations);
+
+    let uri = request.uri();
+    if validate_request_target_form(request.method(), uri)
+        .and_then(|()| reject_http_userinfo(uri))
+        .and_then(|()| reject_http_empty_host(uri))
+        .is_err()
+    {
+        violations.push(Violation::InvalidRequestTarget {
+            uri: uri.to_string(),
+        });
+    }
+    if let Err(e) = validate_host_header(request) {
+        violations.extend(host_violation(e));
+    }
+
+    let is_http10 = request.version() == "HTTP/1.0";
+    check_transfer_encoding(request, is_http10, true, &mut violations);
+    let has_te = request.has_header("Transfer-Encoding");
+    let content_length = check_content_length(request, &mut violations);
+    if has_te && content_length.is_some() {
+        violations.push(Violation::ConflictingFraming);
+    }
+
+    let body_length = request.body_bytes().map(<[u8]>::len).unwrap_or(0) as u64;
+    if !has_te
+        && let Some(Some(header_value)) = content_length
+        && header_value != body_length
+    {
+        violations.push(Violation::ContentLengthMismatch {
+            header_value,
+            body_length,
+        });
+    }
+    // RFC 9110 Section 9.3.8: TRACE リクエストはボディを送信してはならない
+    if request.method() == "TRACE" && body_length != 0 {
+        violations.push(Violation::BodyNotAllowedForMethod {
+            method: request.method().to_string(),
+        });
+    }
+
+    violations
+}
+
+/// レスポンスを検査する
+///
+/// 違反がない場合は空の Vec を返す。違反はヘッダー、フレーミング、ボディの順に並ぶ。
+///
+/// `Response::omit_body(true)` のレスポンス (HEAD への応答など) は、ボディが空の場合
+/// Content-Length とボディの長さを比較しない。
+pub fn validate_response(response: &Response) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    check_headers(HttpHead::headers(response), &mut violations);
+
+    let status_code = response.status_code();
+    let is_http10 = response.version() == "HTTP/1.0";
+    check_transfer_encoding(response, is_http10, false, &mut violations);
+    let has_te = response.has_header("Transfer-Encoding");
+    let content_length = check_content_length(response, &mut violations);
+    if has_te && content_length.is_some() {
+        violations.push(Violation::ConflictingFraming);
+    }
+
+    // RFC 9110 Section 8.6 / RFC 9112 Section 6.1: 1xx / 204 はフレーミングヘッダーを持たない
+    // RFC 9110 Section 15.3.6: 205 は Content-Length: 0 のみ許可
+    let is_1xx_or_204 = (100..200).contains(&status_code) || status_code == 204;
+    if (is_1xx_or_204 || status_code == 205) && has_te {
+        violations.push(Violation::ForbiddenFramingHeader {
+            status_code,
+            name: "Transfer-Encoding".to_string(),
+        });
+    }
+    if content_length.is_some()
+        && (is_1xx_or_204 || (status_code == 205 && content_length != Some(Some(0))))
+    {
+        violations.push(Violation::ForbiddenFramingHeader {
+            status_code,
+            name: "Content-Length".to_string(),
+        });
+    }
+
+    let body_length = response.body_bytes().map(<[u8]>::len).unwrap_or(0) as u64;
+    let has_body = response_status_has_body(status_code) && status_code != 205;
+    if !has_body && body_length != 0 {
+        violations.push(Violation::BodyNotAllowed { status_code });
+    }
+    if has_body
+        && !has_te
+        && let Some(Some(header_value)) = content_length
+        && (!response.is_body_omitted() || body_length != 0)
+        && header_value != body_length
+    {
+        violations.push(Violation::ContentLengthMismatch {
+            header_value,
+            body_length,
+        });
+    }
+
+    violations
+}
+
+/// ヘッダー名と値、単一値ヘッダーの重複を検査する
+fn check_headers(headers: &[(Cow<'static, str>, String)], violations: &mut Vec<Violation>) {
+    for (name, value) in headers {
+        if !is_valid_header_name(name) {
+            violations.push(Violation::InvalidHeaderName {
+                name: name.to_string(),
+            });
+        }
+        if !is_valid_field_value(value) {
+            violations.push(Violation::InvalidHeaderValue {
+                name: name.to_string(),
+            });
+        } else if trim_ows(value).len() != value.len() {
+            violations.push(Violation::SurroundingWhitespace {
+                name: name.to_string(),
+            });
+        }
+    }
+    for field in SINGLETON_FIELDS {
+        let count = headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(field))
+            .count();
+        if count > 1 {
+            violations.push(Violation::DuplicateSingletonField {
+                name: field.to_string(),
+            });
+        }
+    }
+}
+
+/// Transfer-Encoding を検査する
+///
+/// `is_request` の場合は chunked が最後の transfer coding であることも確認する。
+fn check_transfer_encoding<H: HttpHead>(
+    message: &H,
+    is_http10: bool,
+    is_request: bool,
+    violations: &mut Vec<Violation>,
+) {
+    let values = message.get_headers("Transfer-Encoding");
+    if values.is_empty() {
+        return;
+    }
+    if is_http10 {
+        violations.push(Violation::TransferEncodingInHttp10);
+    }
+
+    let codings: Vec<&str> = values
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(trim_ows)
+        .filter(|coding| !coding.is_empty())
+        .collect();
+    let mut chunked_count = 0;
+    for coding in &codings {
+        // transfer-coding = token *( OWS ";" OWS transfer-parameter )
+        let name = trim_ows(coding.split(';').next().unwrap_or(""));
+        if !is_valid_token(name) {
+            violations.push(Violation::InvalidTransferEncoding {
+                value: values.join(", "),
+            });
+            return;
+        }
+        if name.eq_ignore_ascii_case("chunked") {
+            chunked_count += 1;
+        }
+    }
+    if codings.is_empty() || chunked_count > 1 {
+        violations.push(Violation::InvalidTransferEncoding {
+            value: values.join(", "),
+        });
+        return;
+    }
+    if is_request
+        && !codings
+            .last()
+            .is_some_and(|coding| coding.eq_ignore_ascii_case("chunked"))
+    {
+        violations.push(Violation::ChunkedNotFinal);
+    }
+}
+
+/// Content-Length を検査する
+///
+/// ヘッダーがない場合は `None`、値が不正または不一致の場合は `Some(None)`、
+/// 有効な場合は `Some(Some(値))` を返す。
+fn check_content_length<H: HttpHead>(
+    message: &H,
+    violations: &mut Vec<Violation>,
+) -> Option<Option<u64>> {
+    let values = message.get_headers("Content-Length");
+    if values.is_empty() {
+        return None;
+    }
+    let mut result = None;
+    let mut valid = true;
+    for value in values {
+        let trimmed = trim_ows(value);
+        // RFC 9110 Section 8.6: Content-Length = 1*DIGIT
+        let parsed = if !trimmed.is_empty() && trimmed.bytes().all(|b| b.is_ascii_digit()) {
+            trimmed.parse::<u64>().ok()
+        } else {
+            None
+        };
+        match (parsed, result) {
+            (None, _) => {
+                violations.push(Violation::InvalidContentLength {
+                    value: value.to_string(),
+                });
+                valid = false;
+            }
+            (Some(n), None) => result = Some(n),
+            (Some(n), Some(prev)) if n != prev => {
+                if valid {
+                    violations.push(Violation::DuplicateContentLength);
+                }
+                valid = false;
+            }
+            (Some(_), Some(_)) => {}
+        }
+    }
+    Some(if valid { result } else { None })
+}
+
+/// Host ヘッダー検証のエラーを違反に変換する
+fn host_violation(error: EncodeError) -> Option<Violation> {
+    match error {
+        EncodeError::MissingHostHeader => Some(Violation::MissingHost),
+        EncodeError::DuplicateHostHeader => Some(Violation::DuplicateHost),
+        EncodeError::InvalidHostHeader { value } => Some(Violation::InvalidHost { value }),
+        EncodeError::HostAuthorityMismatch { host, authority } => {
+            Some(Violation::HostAuthorityMismatch { host, authority })
+        }
+        EncodeError::NonEmptyHostWithoutAuthority { host, .. } => {
+            Some(Violation::NonEmptyHostWithoutAuthority { host })
+        }
+        _ => None,
+    }
+}
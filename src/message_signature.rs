@@ -0,0 +1,1133 @@
+//! HTTP Message Signatures (RFC 9421)
+//!
+//! ## 概要
+//!
+//! RFC 9421 に基づいた署名ベース (signature base) の構築と、
+//! Signature-Input / Signature ヘッダーのパースと生成を提供します。
+//! 署名アルゴリズムは扱わず、署名と検証は呼び出し側が渡すコールバックで行います。
+//!
+//! - `ComponentId`: 署名対象のコンポーネント識別子
+//!   (`@method` / `@target-uri` などの派生コンポーネントとヘッダーフィールド)
+//! - `SignatureParams`: コンポーネントの一覧と created / expires / keyid などのパラメーター
+//! - `SignatureInput` / `Signature`: ラベルごとの署名パラメーターと署名値
+//! - `SignedMessage`: 署名ベースの材料となるメッセージ
+//! - `sign()` / `verify()`: 署名ベースを構築してコールバックを呼び出す
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Signature-Input = sf-dictionary  ; 各メンバーは inner-list (コンポーネント識別子) とパラメーター
+//! Signature       = sf-dictionary  ; 各メンバーは sf-binary (署名値)
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::message_signature::{
+//!     self, ComponentId, SignatureParams, SignedMessage,
+//! };
+//! use shiguredo_http11::Request;
+//!
+//! let request = Request::new("POST", "/foo?param=Value&Pet=dog")
+//!     .unwrap()
+//!     .header("Host", "example.com")
+//!     .unwrap()
+//!     .header("Content-Type", "application/json")
+//!     .unwrap();
+//!
+//! let params = SignatureParams::new()
+//!     .with_component(ComponentId::new("@method").unwrap())
+//!     .unwrap()
+//!     .with_component(ComponentId::new("@authority").unwrap())
+//!     .unwrap()
+//!     .with_component(ComponentId::new("content-type").unwrap())
+//!     .unwrap()
+//!     .with_created(1618884473)
+//!     .with_keyid("test-key")
+//!     .unwrap();
+//!
+//! let message = SignedMessage::from(&request);
+//! assert_eq!(
+//!     params.signature_base(&message).unwrap(),
+//!     "\"@method\": POST\n\
+//!      \"@authority\": example.com\n\
+//!      \"content-type\": application/json\n\
+//!      \"@signature-params\": (\"@method\" \"@authority\" \"content-type\")\
+//!      ;created=1618884473;keyid=\"test-key\""
+//! );
+//!
+//! // 署名はコールバックで行う
+//! let (input, signature) =
+//!     message_signature::sign(&message, "sig1", &params, |base| Ok(base.to_vec())).unwrap();
+//! let request = request
+//!     .header("Signature-Input", input.to_string())
+//!     .unwrap()
+//!     .header("Signature", signature.to_string())
+//!     .unwrap();
+//!
+//! // 検証もコールバックで行う
+//! let verified = message_signature::verify(
+//!     &SignedMessage::from(&request),
+//!     "sig1",
+//!     1618884480,
+//!     |_params, base, signature| base == signature,
+//! )
+//! .unwrap();
+//! assert_eq!(verified.keyid(), Some("test-key"));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::base64;
+use crate::decoder::{HttpHead, RequestHead, ResponseHead};
+use crate::form;
+use crate::request::Request;
+use crate::request_target::{RequestTargetForm, classify, split_absolute_form};
+use crate::response::Response;
+use crate::sfv::{self, BareItem, Dictionary, InnerList, Item, List, MAX_INTEGER, Parameters};
+use crate::typed_header::TypedHeader;
+use crate::uri;
+use crate::validate::{is_valid_header_name, trim_ows};
+
+/// HTTP Message Signatures のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SignatureError {
+    /// Signature-Input ヘッダーの形式が不正
+    InvalidSignatureInput,
+    /// Signature ヘッダーの形式が不正
+    InvalidSignature,
+    /// コンポーネント識別子が不正
+    InvalidComponent,
+    /// 署名パラメーターが不正
+    InvalidParameter,
+    /// 署名ラベルが不正
+    InvalidLabel,
+    /// 署名対象のコンポーネントがメッセージにない
+    MissingComponent(String),
+    /// 同じコンポーネントが複数回指定された
+    DuplicateComponent(String),
+    /// 指定したラベルの署名がない
+    UnknownLabel(String),
+    /// 署名の有効期限が切れている
+    Expired,
+    /// 署名コールバックが失敗した
+    SigningFailed,
+    /// 署名の検証に失敗した
+    VerificationFailed,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::InvalidSignatureInput => write!(f, "invalid Signature-Input header"),
+            SignatureError::InvalidSignature => write!(f, "invalid Signature header"),
+            SignatureError::InvalidComponent => write!(f, "invalid component identifier"),
+            SignatureError::InvalidParameter => write!(f, "invalid signature parameter"),
+            SignatureError::InvalidLabel => write!(f, "invalid signature label"),
+            SignatureError::MissingComponent(name) => write!(f, "missing component: {}", name),
+            SignatureError::DuplicateComponent(name) => {
+                write!(f, "duplicate component: {}", name)
+            }
+            SignatureError::UnknownLabel(label) => write!(f, "unknown signature label: {}", label),
+            SignatureError::Expired => write!(f, "signature expired"),
+            SignatureError::SigningFailed => write!(f, "signing failed"),
+            SignatureError::VerificationFailed => write!(f, "signature verification failed"),
+        }
+    }
+}
+
+impl core::error::Error for SignatureError {}
+
+/// 派生コンポーネント名 (RFC 9421 Section 2.2)
+const DERIVED_COMPONENTS: &[&str] = &[
+    "@method",
+    "@target-uri",
+    "@authority",
+    "@scheme",
+    "@request-target",
+    "@path",
+    "@query",
+    "@query-param",
+    "@status",
+];
+
+// ========================================
+// ComponentId
+// ========================================
+
+/// コンポーネント識別子 (RFC 9421 Section 2)
+///
+/// 名前とパラメーターを sf-string の Item として表す。
+/// パラメーターの順序は署名ベースに影響するため、追加した順 (パースした順) に保持する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentId {
+    name: String,
+    params: Parameters,
+}
+
+impl ComponentId {
+    /// コンポーネント名を指定して作成
+    ///
+    /// `@` で始まる名前は派生コンポーネント、それ以外はヘッダーフィールド名として扱う。
+    /// フィールド名は小文字に正規化する。`@query-param` は `query_param()` で作成する。
+    pub fn new(name: &str) -> Result<Self, SignatureError> {
+        let name = name.to_ascii_lowercase();
+        let valid = if name.starts_with('@') {
+            DERIVED_COMPONENTS.contains(&name.as_str()) && name != "@query-param"
+        } else {
+            is_valid_header_name(&name)
+        };
+        if !valid {
+            return Err(SignatureError::InvalidComponent);
+        }
+        Ok(ComponentId {
+            name,
+            params: Parameters::new(),
+        })
+    }
+
+    /// クエリパラメーターのコンポーネント (`@query-param;name="..."`) を作成
+    ///
+    /// `name` にはデコード済みのパラメーター名を渡す。識別子にはパーセントエンコードして格納する。
+    pub fn query_param(name: &str) -> Self {
+        let mut params = Parameters::new();
+        params.insert_unchecked(
+            "name".to_string(),
+            BareItem::String(uri::percent_encode(name)),
+        );
+        ComponentId {
+            name: "@query-param".to_string(),
+            params,
+        }
+    }
+
+    /// `sf` パラメーターを付ける (ビルダーパターン)
+    ///
+    /// 既知の Structured Field として値を再シリアライズする。
+    pub fn with_sf(self) -> Result<Self, SignatureError> {
+        self.with_flag("sf")
+    }
+
+    /// `bs` パラメーターを付ける (ビルダーパターン)
+    ///
+    /// 各フィールド行を Byte Sequence としてエンコードする。
+    pub fn with_bs(self) -> Result<Self, SignatureError> {
+        self.with_flag("bs")
+    }
+
+    /// `tr` パラメーターを付ける (ビルダーパターン)
+    ///
+    /// ヘッダーではなくトレーラーの値を使う。
+    pub fn with_tr(self) -> Result<Self, SignatureError> {
+        self.with_flag("tr")
+    }
+
+    /// `req` パラメーターを付ける (ビルダーパターン)
+    ///
+    /// レスポンスの署名で、対応するリクエストのコンポーネントを使う。
+    pub fn with_req(mut self) -> Self {
+        self.params
+            .insert_unchecked("req".to_string(), BareItem::Boolean(true));
+        self
+    }
+
+    /// `key` パラメーターを付ける (ビルダーパターン)
+    ///
+    /// Dictionary フィールドの指定したメンバーだけを対象にする。
+    pub fn with_key(mut self, key: &str) -> Result<Self, SignatureError> {
+        if !sfv::is_valid_key(key) {
+            return Err(SignatureError::InvalidComponent);
+        }
+        self.params
+            .insert_unchecked("key".to_string(), BareItem::String(key.to_string()));
+        self.validate()?;
+        Ok(self)
+    }
+
+    fn with_flag(mut self, key: &str) -> Result<Self, SignatureError> {
+        self.params
+            .insert_unchecked(key.to_string(), BareItem::Boolean(true));
+        self.validate()?;
+        Ok(self)
+    }
+
+    fn from_item(item: &Item) -> Result<Self, SignatureError> {
+        let name = item
+            .bare_item()
+            .as_string()
+            .ok_or(SignatureError::InvalidComponent)?;
+        // 識別子は小文字で送られる (MUST)
+        if name.bytes().any(|b| b.is_ascii_uppercase()) {
+            return Err(SignatureError::InvalidComponent);
+        }
+        let valid = if name.starts_with('@') {
+            DERIVED_COMPONENTS.contains(&name)
+        } else {
+            is_valid_header_name(name)
+        };
+        if !valid {
+            return Err(SignatureError::InvalidComponent);
+        }
+        let id = ComponentId {
+            name: name.to_string(),
+            params: item.params().clone(),
+        };
+        id.validate()?;
+        Ok(id)
+    }
+
+    fn validate(&self) -> Result<(), SignatureError> {
+        for (key, value) in self.params.iter() {
+            let valid = match key {
+                "sf" | "bs" | "tr" | "req" => value.as_boolean() == Some(true),
+                "key" => value.as_string().is_some_and(sfv::is_valid_key),
+                "name" => value.as_string().is_some(),
+                _ => false,
+            };
+            if !valid {
+                return Err(SignatureError::InvalidComponent);
+            }
+        }
+        let has = |key| self.params.contains_key(key);
+        if self.is_derived() {
+            let allowed = |key: &str| key == "req" || (key == "name" && self.is_query_param());
+            if self.params.iter().any(|(key, _)| !allowed(key))
+                || (self.is_query_param() && !has("name"))
+            {
+                return Err(SignatureError::InvalidComponent);
+            }
+        } else if has("name") || (has("bs") && (has("sf") || has("key"))) {
+            return Err(SignatureError::InvalidComponent);
+        }
+        Ok(())
+    }
+
+    fn is_query_param(&self) -> bool {
+        self.name == "@query-param"
+    }
+
+    /// コンポーネント名 (派生コンポーネントは `@` を含む)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// 派生コンポーネントかどうか
+    pub fn is_derived(&self) -> bool {
+        self.name.starts_with('@')
+    }
+
+    /// `req` パラメーターが付いているかどうか
+    pub fn is_req(&self) -> bool {
+        self.params.contains_key("req")
+    }
+}
+
+impl fmt::Display for ComponentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        sfv::write_string(f, &self.name)?;
+        write!(f, "{}", self.params)
+    }
+}
+
+// ========================================
+// SignatureParams
+// ========================================
+
+/// 署名パラメーター (RFC 9421 Section 2.3)
+///
+/// 署名対象のコンポーネントの一覧と、created / expires / nonce / alg / keyid / tag の
+/// パラメーターを持つ。未知のパラメーターはパース時に保持する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureParams {
+    components: Vec<ComponentId>,
+    params: Parameters,
+}
+
+impl SignatureParams {
+    /// 空の署名パラメーターを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// コンポーネントを追加 (ビルダーパターン)
+    ///
+    /// 同じ識別子がすでにある場合はエラー。
+    pub fn with_component(mut self, component: ComponentId) -> Result<Self, SignatureError> {
+        if self.components.contains(&component) {
+            return Err(SignatureError::DuplicateComponent(component.to_string()));
+        }
+        self.components.push(component);
+        Ok(self)
+    }
+
+    /// 作成時刻 (UNIX 秒) を設定 (ビルダーパターン)
+    ///
+    /// sf-integer の上限を超える値は上限に丸める。
+    pub fn with_created(self, created: u64) -> Self {
+        self.with_integer("created", created)
+    }
+
+    /// 有効期限 (UNIX 秒) を設定 (ビルダーパターン)
+    ///
+    /// sf-integer の上限を超える値は上限に丸める。
+    pub fn with_expires(self, expires: u64) -> Self {
+        self.with_integer("expires", expires)
+    }
+
+    /// nonce を設定 (ビルダーパターン)
+    pub fn with_nonce(self, nonce: &str) -> Result<Self, SignatureError> {
+        self.with_string("nonce", nonce)
+    }
+
+    /// アルゴリズム名を設定 (ビルダーパターン)
+    pub fn with_alg(self, alg: &str) -> Result<Self, SignatureError> {
+        self.with_string("alg", alg)
+    }
+
+    /// 鍵 ID を設定 (ビルダーパターン)
+    pub fn with_keyid(self, keyid: &str) -> Result<Self, SignatureError> {
+        self.with_string("keyid", keyid)
+    }
+
+    /// アプリケーション固有のタグを設定 (ビルダーパターン)
+    pub fn with_tag(self, tag: &str) -> Result<Self, SignatureError> {
+        self.with_string("tag", tag)
+    }
+
+    fn with_integer(mut self, key: &str, value: u64) -> Self {
+        let value = i64::try_from(value).unwrap_or(MAX_INTEGER).min(MAX_INTEGER);
+        self.params
+            .insert_unchecked(key.to_string(), BareItem::Integer(value));
+        self
+    }
+
+    fn with_string(mut self, key: &str, value: &str) -> Result<Self, SignatureError> {
+        if !sfv::is_valid_string(value) {
+            return Err(SignatureError::InvalidParameter);
+        }
+        self.params
+            .insert_unchecked(key.to_string(), BareItem::String(value.to_string()));
+        Ok(self)
+    }
+
+    fn from_inner_list(inner_list: &InnerList) -> Result<Self, SignatureError> {
+        let mut signature_params = SignatureParams::new();
+        for item in inner_list.items() {
+            signature_params = signature_params.with_component(ComponentId::from_item(item)?)?;
+        }
+        for (key, value) in inner_list.params().iter() {
+            let valid = match key {
+                "created" | "expires" => value.as_integer().is_some_and(|v| v >= 0),
+                "nonce" | "alg" | "keyid" | "tag" => value.as_string().is_some(),
+                _ => true,
+            };
+            if !valid {
+                return Err(SignatureError::InvalidParameter);
+            }
+        }
+        signature_params.params = inner_list.params().clone();
+        Ok(signature_params)
+    }
+
+    /// 署名対象のコンポーネント
+    pub fn components(&self) -> &[ComponentId] {
+        &self.components
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// 作成時刻 (UNIX 秒)
+    pub fn created(&self) -> Option<u64> {
+        self.integer("created")
+    }
+
+    /// 有効期限 (UNIX 秒)
+    pub fn expires(&self) -> Option<u64> {
+        self.integer("expires")
+    }
+
+    /// nonce
+    pub fn nonce(&self) -> Option<&str> {
+        self.string("nonce")
+    }
+
+    /// アルゴリズム名
+    pub fn alg(&self) -> Option<&str> {
+        self.string("alg")
+    }
+
+    /// 鍵 ID
+    pub fn keyid(&self) -> Option<&str> {
+        self.string("keyid")
+    }
+
+    /// タグ
+    pub fn tag(&self) -> Option<&str> {
+        self.string("tag")
+    }
+
+    fn integer(&self, key: &str) -> Option<u64> {
+        self.params
+            .get(key)
+            .and_then(BareItem::as_integer)
+            .and_then(|v| u64::try_from(v).ok())
+    }
+
+    fn string(&self, key: &str) -> Option<&str> {
+        self.params.get(key).and_then(BareItem::as_string)
+    }
+
+    /// 指定時刻 (UNIX 秒) に有効期限を過ぎているかどうか
+    ///
+    /// expires がない場合は常に `false`。
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires().is_some_and(|expires| now > expires)
+    }
+
+    /// 署名ベースを構築 (RFC 9421 Section 2.5)
+    ///
+    /// コンポーネントごとに `"<識別子>": <値>` の行を並べ、
+    /// 最後に改行なしで `"@signature-params"` の行を付ける。
+    pub fn signature_base(&self, message: &SignedMessage<'_>) -> Result<String, SignatureError> {
+        let mut base = String::new();
+        for component in &self.components {
+            let value = message.component_value(component)?;
+            base.push_str(&component.to_string());
+            base.push_str(": ");
+            base.push_str(&value);
+            base.push('\n');
+        }
+        base.push_str("\"@signature-params\": ");
+        base.push_str(&self.to_string());
+        Ok(base)
+    }
+}
+
+impl fmt::Display for SignatureParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, component) in self.components.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", component)?;
+        }
+        write!(f, "){}", self.params)
+    }
+}
+
+// ========================================
+// Signature-Input / Signature
+// ========================================
+
+/// Signature-Input ヘッダー (RFC 9421 Section 4.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureInput {
+    signatures: Vec<(String, SignatureParams)>,
+}
+
+impl SignatureInput {
+    /// 空の Signature-Input を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signature-Input をパース
+    pub fn parse(input: &str) -> Result<Self, SignatureError> {
+        let dictionary =
+            Dictionary::parse(input).map_err(|_| SignatureError::InvalidSignatureInput)?;
+        let signatures = dictionary
+            .iter()
+            .map(|(label, member)| {
+                let inner_list = member
+                    .as_inner_list()
+                    .ok_or(SignatureError::InvalidSignatureInput)?;
+                Ok((
+                    label.to_string(),
+                    SignatureParams::from_inner_list(inner_list)?,
+                ))
+            })
+            .collect::<Result<_, SignatureError>>()?;
+        Ok(SignatureInput { signatures })
+    }
+
+    /// メッセージの Signature-Input ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, SignatureError> {
+        let values = message.get_headers("Signature-Input");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// 署名を追加 (ビルダーパターン)
+    ///
+    /// 同じラベルがすでにある場合は置き換える。
+    pub fn with_signature(
+        mut self,
+        label: &str,
+        params: SignatureParams,
+    ) -> Result<Self, SignatureError> {
+        if !sfv::is_valid_key(label) {
+            return Err(SignatureError::InvalidLabel);
+        }
+        match self.signatures.iter_mut().find(|(l, _)| l == label) {
+            Some(entry) => entry.1 = params,
+            None => self.signatures.push((label.to_string(), params)),
+        }
+        Ok(self)
+    }
+
+    /// ラベルの署名パラメーターを取得
+    pub fn get(&self, label: &str) -> Option<&SignatureParams> {
+        self.signatures
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, params)| params)
+    }
+
+    /// 署名を順に取得
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SignatureParams)> {
+        self.signatures
+            .iter()
+            .map(|(label, params)| (label.as_str(), params))
+    }
+
+    /// 署名がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+}
+
+impl fmt::Display for SignatureInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (label, params)) in self.signatures.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", label, params)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for SignatureInput {
+    const NAME: &'static str = "Signature-Input";
+    type Error = SignatureError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Signature ヘッダー (RFC 9421 Section 4.2)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Signature {
+    signatures: Vec<(String, Vec<u8>)>,
+}
+
+impl Signature {
+    /// 空の Signature を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signature をパース
+    pub fn parse(input: &str) -> Result<Self, SignatureError> {
+        let dictionary = Dictionary::parse(input).map_err(|_| SignatureError::InvalidSignature)?;
+        let signatures = dictionary
+            .iter()
+            .map(|(label, member)| {
+                let value = member
+                    .as_item()
+                    .and_then(|item| item.bare_item().as_byte_sequence())
+                    .ok_or(SignatureError::InvalidSignature)?;
+                Ok((label.to_string(), value.to_vec()))
+            })
+            .collect::<Result<_, SignatureError>>()?;
+        Ok(Signature { signatures })
+    }
+
+    /// メッセージの Signature ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, SignatureError> {
+        let values = message.get_headers("Signature");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// 署名値を追加 (ビルダーパターン)
+    ///
+    /// 同じラベルがすでにある場合は置き換える。
+    pub fn with_signature(
+        mut self,
+        label: &str,
+        signature: impl Into<Vec<u8>>,
+    ) -> Result<Self, SignatureError> {
+        if !sfv::is_valid_key(label) {
+            return Err(SignatureError::InvalidLabel);
+        }
+        let signature = signature.into();
+        match self.signatures.iter_mut().find(|(l, _)| l == label) {
+            Some(entry) => entry.1 = signature,
+            None => self.signatures.push((label.to_string(), signature)),
+        }
+        Ok(self)
+    }
+
+    /// ラベルの署名値を取得
+    pub fn get(&self, label: &str) -> Option<&[u8]> {
+        self.signatures
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, signature)| signature.as_slice())
+    }
+
+    /// 署名値を順に取得
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.signatures
+            .iter()
+            .map(|(label, signature)| (label.as_str(), signature.as_slice()))
+    }
+
+    /// 署名値がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (label, signature)) in self.signatures.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}=", label)?;
+            sfv::write_byte_sequence(f, signature)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for Signature {
+    const NAME: &'static str = "Signature";
+    type Error = SignatureError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// SignedMessage
+// ========================================
+
+#[derive(Debug, Clone, Copy)]
+enum MessageKind<'a> {
+    Request { method: &'a str, target: &'a str },
+    Response { status: u16 },
+}
+
+/// 署名ベースの材料となるメッセージ
+///
+/// デコード済みのリクエストは origin-form の request-target からスキームが分からないため、
+/// `@target-uri` / `@scheme` を使う場合は `with_scheme()` で指定する。
+#[derive(Debug, Clone, Copy)]
+pub struct SignedMessage<'a> {
+    kind: MessageKind<'a>,
+    headers: &'a [(Cow<'static, str>, String)],
+    trailers: &'a [(Cow<'static, str>, String)],
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    request: Option<&'a SignedMessage<'a>>,
+}
+
+impl<'a> SignedMessage<'a> {
+    /// リクエストのメソッド、request-target、ヘッダーから作成
+    pub fn request(
+        method: &'a str,
+        target: &'a str,
+        headers: &'a [(Cow<'static, str>, String)],
+    ) -> Self {
+        SignedMessage {
+            kind: MessageKind::Request { method, target },
+            headers,
+            trailers: &[],
+            scheme: None,
+            authority: None,
+            request: None,
+        }
+    }
+
+    /// レスポンスのステータスコードとヘッダーから作成
+    pub fn response(status: u16, headers: &'a [(Cow<'static, str>, String)]) -> Self {
+        SignedMessage {
+            kind: MessageKind::Response { status },
+            headers,
+            trailers: &[],
+            scheme: None,
+            authority: None,
+            request: None,
+        }
+    }
+
+    /// スキームを指定 (ビルダーパターン)
+    pub fn with_scheme(mut self, scheme: &'a str) -> Self {
+        self.scheme = Some(scheme);
+        self
+    }
+
+    /// authority を指定 (ビルダーパターン)
+    ///
+    /// 指定しない場合は request-target の authority、Host ヘッダーの順に使う。
+    pub fn with_authority(mut self, authority: &'a str) -> Self {
+        self.authority = Some(authority);
+        self
+    }
+
+    /// トレーラーを指定 (ビルダーパターン)
+    pub fn with_trailers(mut self, trailers: &'a [(Cow<'static, str>, String)]) -> Self {
+        self.trailers = trailers;
+        self
+    }
+
+    /// レスポンスに対応するリクエストを指定 (ビルダーパターン)
+    ///
+    /// `req` パラメーター付きのコンポーネントはこのリクエストから値を取る。
+    pub fn with_request(mut self, request: &'a SignedMessage<'a>) -> Self {
+        self.request = Some(request);
+        self
+    }
+
+    fn component_value(&self, component: &ComponentId) -> Result<String, SignatureError> {
+        let message = if component.is_req() {
+            self.request
+                .ok_or_else(|| SignatureError::MissingComponent(component.to_string()))?
+        } else {
+            self
+        };
+        let value = if component.is_derived() {
+            message.derived_value(component)
+        } else {
+            message.field_value(component)
+        };
+        value.ok_or_else(|| SignatureError::MissingComponent(component.to_string()))?
+    }
+
+    fn derived_value(&self, component: &ComponentId) -> Option<Result<String, SignatureError>> {
+        if component.name() == "@status" {
+            return match self.kind {
+                MessageKind::Response { status } => Some(Ok(status.to_string())),
+                MessageKind::Request { .. } => None,
+            };
+        }
+        let MessageKind::Request { method, target } = self.kind else {
+            return None;
+        };
+        let form = classify(target)?;
+        let value = match component.name() {
+            "@method" => method.to_string(),
+            "@request-target" => target.to_string(),
+            "@scheme" => self.scheme()?,
+            "@authority" => self.authority()?,
+            "@target-uri" => match form {
+                RequestTargetForm::Absolute => target.to_string(),
+                RequestTargetForm::Origin => {
+                    alloc::format!("{}://{}{}", self.scheme()?, self.authority()?, target)
+                }
+                RequestTargetForm::Authority | RequestTargetForm::Asterisk => {
+                    alloc::format!("{}://{}", self.scheme()?, self.authority()?)
+                }
+            },
+            "@path" => {
+                let path = target_path(target, form)?;
+                if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path.to_string()
+                }
+            }
+            "@query" => alloc::format!("?{}", target_query(target, form)?.unwrap_or("")),
+            "@query-param" => {
+                let name = component
+                    .params()
+                    .get("name")
+                    .and_then(BareItem::as_string)?;
+                let query = target_query(target, form)?.unwrap_or("");
+                let mut values = form::parse(query)
+                    .into_iter()
+                    .filter(|(key, _)| uri::percent_encode(key) == name);
+                let (_, value) = values.next()?;
+                // 同じ名前のパラメーターが複数ある場合は署名できない
+                if values.next().is_some() {
+                    return Some(Err(SignatureError::InvalidComponent));
+                }
+                uri::percent_encode(&value)
+            }
+            _ => return None,
+        };
+        Some(Ok(value))
+    }
+
+    fn scheme(&self) -> Option<String> {
+        let scheme = match (self.scheme, self.kind) {
+            (Some(scheme), _) => scheme,
+            (None, MessageKind::Request { target, .. }) => split_absolute_form(target)?.0,
+            (None, MessageKind::Response { .. }) => return None,
+        };
+        Some(scheme.to_ascii_lowercase())
+    }
+
+    fn authority(&self) -> Option<String> {
+        let MessageKind::Request { target, .. } = self.kind else {
+            return None;
+        };
+        let authority = match self.authority {
+            Some(authority) => authority,
+            None => match classify(target)? {
+                RequestTargetForm::Absolute => split_absolute_form(target)?.1?,
+                RequestTargetForm::Authority => target,
+                _ => header_value(self.headers, "Host")?,
+            },
+        };
+        let authority = authority.to_ascii_lowercase();
+        // デフォルトポートは省略する (RFC 9421 Section 2.2.3)
+        if let Some(port) = self.scheme().as_deref().and_then(uri::default_port)
+            && let Some(host) = authority.strip_suffix(&alloc::format!(":{}", port))
+        {
+            return Some(host.to_string());
+        }
+        Some(authority)
+    }
+
+    fn field_value(&self, component: &ComponentId) -> Option<Result<String, SignatureError>> {
+        let fields = if component.params().contains_key("tr") {
+            self.trailers
+        } else {
+            self.headers
+        };
+        let lines: Vec<&str> = fields
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(component.name()))
+            .map(|(_, value)| trim_ows(value))
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        if component.params().contains_key("bs") {
+            let encoded: Vec<String> = lines
+                .iter()
+                .map(|line| alloc::format!(":{}:", base64::encode(line.as_bytes())))
+                .collect();
+            return Some(Ok(encoded.join(", ")));
+        }
+        let value = lines.join(", ");
+        if let Some(key) = component.params().get("key").and_then(BareItem::as_string) {
+            let dictionary = match Dictionary::parse(&value) {
+                Ok(dictionary) => dictionary,
+                Err(_) => return Some(Err(SignatureError::InvalidComponent)),
+            };
+            return dictionary.get(key).map(|member| Ok(member.to_string()));
+        }
+        if component.params().contains_key("sf") {
+            return Some(reserialize(component.name(), &value));
+        }
+        Some(Ok(value))
+    }
+}
+
+impl<'a> From<&'a Request> for SignedMessage<'a> {
+    fn from(request: &'a Request) -> Self {
+        SignedMessage::request(request.method(), request.uri(), HttpHead::headers(request))
+    }
+}
+
+impl<'a> From<&'a RequestHead> for SignedMessage<'a> {
+    fn from(head: &'a RequestHead) -> Self {
+        SignedMessage::request(head.method(), head.uri(), head.headers())
+    }
+}
+
+impl<'a> From<&'a Response> for SignedMessage<'a> {
+    fn from(response: &'a Response) -> Self {
+        SignedMessage::response(response.status_code(), HttpHead::headers(response))
+    }
+}
+
+impl<'a> From<&'a ResponseHead> for SignedMessage<'a> {
+    fn from(head: &'a ResponseHead) -> Self {
+        SignedMessage::response(head.status_code(), head.headers())
+    }
+}
+
+fn header_value<'h>(headers: &'h [(Cow<'static, str>, String)], name: &str) -> Option<&'h str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| trim_ows(v))
+}
+
+fn target_path(target: &str, form: RequestTargetForm) -> Option<&str> {
+    let path = match form {
+        RequestTargetForm::Origin => target,
+        RequestTargetForm::Absolute => split_absolute_form(target)?.2,
+        RequestTargetForm::Authority | RequestTargetForm::Asterisk => return None,
+    };
+    Some(&path[..path.find('?').unwrap_or(path.len())])
+}
+
+fn target_query(target: &str, form: RequestTargetForm) -> Option<Option<&str>> {
+    match form {
+        RequestTargetForm::Origin | RequestTargetForm::Absolute => {
+            let without_fragment = &target[..target.find('#').unwrap_or(target.len())];
+            Some(without_fragment.split_once('?').map(|(_, query)| query))
+        }
+        RequestTargetForm::Authority | RequestTargetForm::Asterisk => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StructuredType {
+    Item,
+    List,
+    Dictionary,
+}
+
+/// Structured Field として定義されている既知のフィールド
+fn structured_type(name: &str) -> Option<StructuredType> {
+    let kind = match name {
+        "accept-ch" | "cache-status" | "client-cert-chain" | "proxy-status" | "ratelimit"
+        | "ratelimit-policy" => StructuredType::List,
+        "accept-signature"
+        | "cdn-cache-control"
+        | "content-digest"
+        | "permissions-policy"
+        | "priority"
+        | "repr-digest"
+        | "reporting-endpoints"
+        | "signature"
+        | "signature-input"
+        | "want-content-digest"
+        | "want-repr-digest" => StructuredType::Dictionary,
+        "client-cert"
+        | "cross-origin-embedder-policy"
+        | "cross-origin-embedder-policy-report-only"
+        | "cross-origin-opener-policy"
+        | "cross-origin-opener-policy-report-only"
+        | "deprecation"
+        | "origin-agent-cluster" => StructuredType::Item,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// `sf` パラメーター付きのフィールド値を再シリアライズ (RFC 9421 Section 2.1.1)
+fn reserialize(name: &str, value: &str) -> Result<String, SignatureError> {
+    let kind = structured_type(name).ok_or(SignatureError::InvalidComponent)?;
+    let serialized = match kind {
+        StructuredType::Item => Item::parse(value).map(|item| item.to_string()),
+        StructuredType::List => List::parse(value).map(|list| list.to_string()),
+        StructuredType::Dictionary => {
+            Dictionary::parse(value).map(|dictionary| dictionary.to_string())
+        }
+    };
+    serialized.map_err(|_| SignatureError::InvalidComponent)
+}
+
+// ========================================
+// 署名と検証
+// ========================================
+
+/// メッセージに署名する (RFC 9421 Section 3.1)
+///
+/// 署名ベースを構築して `signer` に渡し、返された署名値から
+/// Signature-Input / Signature ヘッダーの値を作る。
+/// `signer` は署名に失敗した場合 `SignatureError::SigningFailed` を返す。
+pub fn sign<F>(
+    message: &SignedMessage<'_>,
+    label: &str,
+    params: &SignatureParams,
+    signer: F,
+) -> Result<(SignatureInput, Signature), SignatureError>
+where
+    F: FnOnce(&[u8]) -> Result<Vec<u8>, SignatureError>,
+{
+    let input = SignatureInput::new().with_signature(label, params.clone())?;
+    let base = params.signature_base(message)?;
+    let signature = Signature::new().with_signature(label, signer(base.as_bytes())?)?;
+    Ok((input, signature))
+}
+
+/// メッセージの署名を検証する (RFC 9421 Section 3.2)
+///
+/// メッセージの Signature-Input / Signature ヘッダーから `label` の署名を取り出し、
+/// 有効期限を確認したうえで、署名パラメーター、署名ベース、署名値を `verifier` に渡す。
+/// 鍵やアルゴリズムの選択は `verifier` が署名パラメーターの keyid / alg を見て行う。
+/// 検証に成功した場合は署名パラメーターを返す。
+pub fn verify<F>(
+    message: &SignedMessage<'_>,
+    label: &str,
+    now: u64,
+    verifier: F,
+) -> Result<SignatureParams, SignatureError>
+where
+    F: FnOnce(&SignatureParams, &[u8], &[u8]) -> bool,
+{
+    let unknown = || SignatureError::UnknownLabel(label.to_string());
+    let input = match header_values(message.headers, "Signature-Input") {
+        Some(value) => SignatureInput::parse(&value)?,
+        None => return Err(unknown()),
+    };
+    let signatures = match header_values(message.headers, "Signature") {
+        Some(value) => Signature::parse(&value)?,
+        None => return Err(unknown()),
+    };
+    let params = input.get(label).ok_or_else(unknown)?;
+    let signature = signatures.get(label).ok_or_else(unknown)?;
+    if params.is_expired(now) {
+        return Err(SignatureError::Expired);
+    }
+    let base = params.signature_base(message)?;
+    if !verifier(params, base.as_bytes(), signature) {
+        return Err(SignatureError::VerificationFailed);
+    }
+    Ok(params.clone())
+}
+
+fn header_values(headers: &[(Cow<'static, str>, String)], name: &str) -> Option<String> {
+    let values: Vec<&str> = headers
+        .iter()
+        .filter(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(", "))
+    }
+}
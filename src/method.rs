@@ -0,0 +1,186 @@
+//! HTTP メソッド型 (RFC 9110 Section 9)
+//!
+//! ## 概要
+//!
+//! RFC 9110 Section 9.3 と RFC 5789 で定義されたメソッドを const 値として提供する。
+//! 拡張メソッド (WebDAV、RTSP 等) も `Method::parse` で token として受理する。
+//!
+//! `Request` / `RequestHead` はメソッドを文字列で保持するため、`Method` は
+//! 文字列との比較と `String` への変換をサポートする。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::method::Method;
+//! use shiguredo_http11::Request;
+//!
+//! let request = Request::new(Method::POST, "/").unwrap();
+//! assert!(request.method() == Method::POST);
+//!
+//! let method: Method = "GET".parse().unwrap();
+//! assert!(method.is_safe());
+//! assert!(method.is_idempotent());
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::validate::is_valid_method;
+
+/// メソッドのパースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MethodError {
+    /// 空の入力
+    Empty,
+    /// token (RFC 9110 Section 5.6.2) でない文字を含む
+    InvalidToken,
+}
+
+impl fmt::Display for MethodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MethodError::Empty => write!(f, "empty method"),
+            MethodError::InvalidToken => write!(f, "invalid method: not a token"),
+        }
+    }
+}
+
+impl core::error::Error for MethodError {}
+
+/// HTTP メソッド
+///
+/// RFC 9110 Section 9.1: メソッド名は大文字小文字を区別する。
+/// `"get"` は `Method::GET` と等しくない。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Method(Cow<'static, str>);
+
+impl Method {
+    /// `GET` (RFC 9110 Section 9.3.1)
+    pub const GET: Self = Self(Cow::Borrowed("GET"));
+    /// `HEAD` (RFC 9110 Section 9.3.2)
+    pub const HEAD: Self = Self(Cow::Borrowed("HEAD"));
+    /// `POST` (RFC 9110 Section 9.3.3)
+    pub const POST: Self = Self(Cow::Borrowed("POST"));
+    /// `PUT` (RFC 9110 Section 9.3.4)
+    pub const PUT: Self = Self(Cow::Borrowed("PUT"));
+    /// `DELETE` (RFC 9110 Section 9.3.5)
+    pub const DELETE: Self = Self(Cow::Borrowed("DELETE"));
+    /// `CONNECT` (RFC 9110 Section 9.3.6)
+    pub const CONNECT: Self = Self(Cow::Borrowed("CONNECT"));
+    /// `OPTIONS` (RFC 9110 Section 9.3.7)
+    pub const OPTIONS: Self = Self(Cow::Borrowed("OPTIONS"));
+    /// `TRACE` (RFC 9110 Section 9.3.8)
+    pub const TRACE: Self = Self(Cow::Borrowed("TRACE"));
+    /// `PATCH` (RFC 5789 Section 2)
+    pub const PATCH: Self = Self(Cow::Borrowed("PATCH"));
+
+    /// メソッド名をパースする
+    ///
+    /// RFC 9110 Section 9.1: `method = token`。登録済みメソッドは const 値と同じ
+    /// 借用表現になり、それ以外は所有した文字列を保持する。
+    pub fn parse(s: &str) -> Result<Self, MethodError> {
+        if s.is_empty() {
+            return Err(MethodError::Empty);
+        }
+        if !is_valid_method(s) {
+            return Err(MethodError::InvalidToken);
+        }
+        Ok(Self::from_token(s))
+    }
+
+    /// token として検証済みのメソッド名から構築する
+    fn from_token(s: &str) -> Self {
+        match s {
+            "GET" => Self::GET,
+            "HEAD" => Self::HEAD,
+            "POST" => Self::POST,
+            "PUT" => Self::PUT,
+            "DELETE" => Self::DELETE,
+            "CONNECT" => Self::CONNECT,
+            "OPTIONS" => Self::OPTIONS,
+            "TRACE" => Self::TRACE,
+            "PATCH" => Self::PATCH,
+            _ => Self(Cow::Owned(String::from(s))),
+        }
+    }
+
+    /// メソッド名を取得
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// safe メソッドか (RFC 9110 Section 9.2.1)
+    ///
+    /// GET / HEAD / OPTIONS / TRACE が該当する。
+    pub fn is_safe(&self) -> bool {
+        matches!(self.as_str(), "GET" | "HEAD" | "OPTIONS" | "TRACE")
+    }
+
+    /// idempotent メソッドか (RFC 9110 Section 9.2.2)
+    ///
+    /// safe メソッドに加えて PUT / DELETE が該当する。
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self.as_str(), "PUT" | "DELETE")
+    }
+
+    /// レスポンスがキャッシュ可能と定義されたメソッドか (RFC 9110 Section 9.2.3)
+    ///
+    /// GET / HEAD が該当する。POST は明示的な鮮度情報がある場合のみキャッシュ可能な
+    /// ため含めない。
+    pub fn is_cacheable(&self) -> bool {
+        matches!(self.as_str(), "GET" | "HEAD")
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Method {
+    type Err = MethodError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl AsRef<str> for Method {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<Method> for String {
+    fn from(method: Method) -> Self {
+        method.0.into_owned()
+    }
+}
+
+impl PartialEq<str> for Method {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Method {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<Method> for str {
+    fn eq(&self, other: &Method) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Method> for &str {
+    fn eq(&self, other: &Method) -> bool {
+        *self == other.as_str()
+    }
+}
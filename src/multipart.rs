@@ -4,6 +4,9 @@
 //!
 //! RFC 7578 に基づいた multipart/form-data のパース/生成を提供します。
 //!
+//! 生成は一括で行う `MultipartBuilder` と、大きなファイルをチャンク単位で送信するための
+//! ストリーミングエンコーダー `MultipartEncoder` がある。
+//!
 //! ## 使い方
 //!
 //! ```rust
@@ -27,7 +30,7 @@
 
 use crate::content_disposition::ContentDisposition;
 use crate::content_type::ContentType;
-use crate::validate::is_token_char;
+use crate::validate::{is_token_char, is_valid_field_value, is_valid_header_name};
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
@@ -55,6 +58,14 @@ pub enum MultipartError {
     /// Content-Disposition に name パラメータが欠落している
     /// RFC 7578 Section 4.2: "name" パラメータを含まなければならない
     MissingName,
+    /// multipart/byteranges のパートに Content-Range が欠落している
+    /// RFC 9110 Section 14.6: 各パートは Content-Range ヘッダーを含まなければならない
+    MissingContentRange,
+    /// multipart/byteranges のパートの Content-Range が不正
+    /// (満たせない範囲、または範囲の長さとボディの長さが一致しない)
+    InvalidContentRange,
+    /// 入れ子の multipart が `MultipartParser::with_max_depth()` で指定した深さを超えた
+    NestingTooDeep,
     /// バッファサイズが上限を超えた
     BufferOverflow {
         /// 超過後のサイズ
@@ -90,6 +101,16 @@ impl fmt::Display for MultipartError {
                     "Content-Disposition must contain name parameter (RFC 7578 Section 4.2)"
                 )
             }
+            MultipartError::MissingContentRange => {
+                write!(
+                    f,
+                    "missing Content-Range header in multipart/byteranges part (RFC 9110 Section 14.6)"
+                )
+            }
+            MultipartError::InvalidContentRange => {
+                write!(f, "invalid Content-Range in multipart/byteranges part")
+            }
+            MultipartError::NestingTooDeep => write!(f, "multipart nesting too deep"),
             MultipartError::BufferOverflow { size, limit } => {
                 write!(f, "buffer overflow: size={size}, limit={limit}")
             }
@@ -110,6 +131,8 @@ pub struct Part {
     headers: Vec<(String, String)>,
     /// ボディ
     body: Vec<u8>,
+    /// 入れ子の multipart のパート
+    parts: Vec<Part>,
 }
 
 impl Part {
@@ -123,6 +146,7 @@ impl Part {
             content_type: None,
             headers: Vec::new(),
             body: Vec::new(),
+            parts: Vec::new(),
         }
     }
 
@@ -138,6 +162,24 @@ impl Part {
             content_type: ct,
             headers: Vec::new(),
             body: Vec::new(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// 添付ファイルのパートを作成
+    ///
+    /// 入れ子の multipart/mixed の中で使う `Content-Disposition: attachment` のパートを作成する
+    /// (RFC 2388 Section 5.1)。
+    pub fn attachment(filename: &str, content_type: &str) -> Self {
+        Part {
+            content_disposition: Some(
+                ContentDisposition::new(crate::content_disposition::DispositionType::Attachment)
+                    .with_filename(filename),
+            ),
+            content_type: ContentType::parse(content_type).ok(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            parts: Vec::new(),
         }
     }
 
@@ -153,6 +195,60 @@ impl Part {
         self
     }
 
+    /// 入れ子の multipart をボディに設定
+    ///
+    /// Content-Type を `multipart/<subtype>; boundary=<boundary>` にし、`parts` から
+    /// 生成した multipart ボディを設定する。`parts()` で入れ子のパートを取得できる。
+    ///
+    /// ```rust
+    /// use shiguredo_http11::multipart::{MultipartBuilder, Part};
+    ///
+    /// let files = Part::new("files").with_nested_parts(
+    ///     "mixed",
+    ///     "inner",
+    ///     vec![
+    ///         Part::attachment("file1.txt", "text/plain").with_body(b"one"),
+    ///         Part::attachment("file2.txt", "text/plain").with_body(b"two"),
+    ///     ],
+    /// );
+    /// assert_eq!(files.content_type().unwrap().mime_type(), "multipart/mixed");
+    /// let body = MultipartBuilder::with_boundary("outer").part(files).build();
+    /// ```
+    pub fn with_nested_parts(mut self, subtype: &str, boundary: &str, parts: Vec<Part>) -> Self {
+        let mut encoder = MultipartEncoder::with_boundary(boundary);
+        let mut body = Vec::new();
+        for part in &parts {
+            body.extend_from_slice(&encoder.begin_part(part));
+            body.extend_from_slice(&part.body);
+        }
+        body.extend_from_slice(&encoder.finish());
+
+        self.content_type =
+            Some(ContentType::new("multipart", subtype).with_parameter("boundary", boundary));
+        self.body = body;
+        self.parts = parts;
+        self
+    }
+
+    /// ヘッダーを追加
+    ///
+    /// Content-Disposition / Content-Type 以外のヘッダー (例: Content-Transfer-Encoding,
+    /// Content-ID) を追加する。Content-Disposition / Content-Type は
+    /// 専用の設定を使うこと。
+    /// 名前が token でない場合、値に CR / LF / NUL 等の不正な文字を含む場合は
+    /// `MultipartError::InvalidHeader` を返す。
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self, MultipartError> {
+        if !is_valid_header_name(name)
+            || !is_valid_field_value(value)
+            || name.eq_ignore_ascii_case("Content-Disposition")
+            || name.eq_ignore_ascii_case("Content-Type")
+        {
+            return Err(MultipartError::InvalidHeader);
+        }
+        self.headers.push((name.to_string(), value.to_string()));
+        Ok(self)
+    }
+
     /// パートの名前を取得
     pub fn name(&self) -> Option<&str> {
         self.content_disposition.as_ref()?.name()
@@ -183,6 +279,20 @@ impl Part {
         &self.body
     }
 
+    /// 入れ子の multipart のパートを取得
+    ///
+    /// パース時は `MultipartParser::with_max_depth()` で入れ子の展開を有効にした場合のみ設定される。
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    /// ボディが multipart (Content-Type が multipart/* で boundary を持つ) かどうか
+    pub fn is_multipart(&self) -> bool {
+        self.content_type
+            .as_ref()
+            .is_some_and(|ct| ct.is_multipart() && ct.boundary().is_some())
+    }
+
     /// ボディを文字列として取得
     pub fn body_str(&self) -> Option<&str> {
         core::str::from_utf8(&self.body).ok()
@@ -228,6 +338,16 @@ pub struct MultipartParser {
     /// (pos が前進した場合は scan_offset が古いオフセットを指していても無害)。
     /// パートを切り出して状態遷移するときに `pos` 以上にリセットする。
     boundary_scan_offset: usize,
+    /// RFC 7578 の form-data の制約 (Content-Disposition と name パラメータ) を検証するか
+    ///
+    /// multipart/byteranges のパースでは検証しない。
+    form_data: bool,
+    /// 入れ子の multipart を展開する最大の深さ (0 は展開しない)
+    max_depth: usize,
+    /// 入れ子の multipart のパーサーか
+    ///
+    /// 入れ子のパーサーで `max_depth` を超える入れ子を検出した場合はエラーにする。
+    nested: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -256,7 +376,7 @@ fn is_valid_boundary_char(b: u8) -> bool {
 }
 
 /// RFC 2046 Section 5.1.1: boundary の検証
-fn is_valid_boundary(boundary: &str) -> bool {
+pub(crate) fn is_valid_boundary(boundary: &str) -> bool {
     let bytes = boundary.as_bytes();
     // 1-70 文字
     if bytes.is_empty() || bytes.len() > 70 {
@@ -293,15 +413,38 @@ impl MultipartParser {
             finished: false,
             max_buffer_size: 10 * 1024 * 1024,
             boundary_scan_offset: 0,
+            form_data: true,
+            max_depth: 0,
+            nested: false,
         }
     }
 
+    /// form-data の制約を検証しないパーサーにする
+    ///
+    /// multipart/byteranges のように Content-Disposition を持たないパートを扱うために使う。
+    pub(crate) fn without_form_data_checks(mut self) -> Self {
+        self.form_data = false;
+        self
+    }
+
     /// バッファ最大サイズを設定
     pub fn with_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
         self.max_buffer_size = max_buffer_size;
         self
     }
 
+    /// 入れ子の multipart を展開する最大の深さを設定
+    ///
+    /// デフォルトは 0 で、入れ子の multipart は展開せずボディのまま返す。
+    /// 1 以上を指定すると、Content-Type が multipart/* のパートのボディをパースし、
+    /// `Part::parts()` で入れ子のパートを返す。入れ子のパートには RFC 7578 の
+    /// form-data の制約 (Content-Disposition と name パラメータ) を適用しない。
+    /// 指定した深さを超える入れ子がある場合は `MultipartError::NestingTooDeep` を返す。
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
     /// boundary を検証して新しいパーサーを作成
     ///
     /// RFC 2046 Section 5.1.1 に従い、boundary 文字列を検証します。
@@ -447,19 +590,22 @@ impl MultipartParser {
                             }
                         }
 
-                        // RFC 7578 Section 4.2: 各パートは Content-Disposition ヘッダーを
-                        // 含まなければならない (MUST)
-                        let content_disposition =
-                            content_disposition.ok_or(MultipartError::MissingContentDisposition)?;
+                        if self.form_data {
+                            // RFC 7578 Section 4.2: 各パートは Content-Disposition ヘッダーを
+                            // 含まなければならない (MUST)
+                            let content_disposition = content_disposition
+                                .as_ref()
+                                .ok_or(MultipartError::MissingContentDisposition)?;
 
-                        // RFC 7578 Section 4.2: disposition type は "form-data" でなければならない (MUST)
-                        if !content_disposition.is_form_data() {
-                            return Err(MultipartError::InvalidContentDisposition);
-                        }
+                            // RFC 7578 Section 4.2: disposition type は "form-data" でなければならない (MUST)
+                            if !content_disposition.is_form_data() {
+                                return Err(MultipartError::InvalidContentDisposition);
+                            }
 
-                        // RFC 7578 Section 4.2: "name" パラメータを含まなければならない (MUST)
-                        if content_disposition.name().is_none() {
-                            return Err(MultipartError::MissingName);
+                            // RFC 7578 Section 4.2: "name" パラメータを含まなければならない (MUST)
+                            if content_disposition.name().is_none() {
+                                return Err(MultipartError::MissingName);
+                            }
                         }
 
                         // 次の境界を探す。body_start は絶対オフセット、相対位置で検索。
@@ -471,6 +617,7 @@ impl MultipartParser {
                             let body_end = search_start + body_end_rel;
                             // パートのボディは所有権移転で 1 回だけコピーする
                             let body = self.buffer[body_start..body_end].to_vec();
+                            let parts = self.parse_nested(content_type.as_ref(), &body)?;
 
                             // 終了境界または次パート区切りを判定。
                             // 内部デリミタ直後に transport-padding (SP/HTAB) をスキップする。
@@ -512,10 +659,11 @@ impl MultipartParser {
                             }
 
                             return Ok(Some(Part {
-                                content_disposition: Some(content_disposition),
+                                content_disposition,
                                 content_type,
                                 headers,
                                 body,
+                                parts,
                             }));
                         } else {
                             // boundary が見つからなかった。haystack 末尾近くで
@@ -570,6 +718,46 @@ impl MultipartParser {
     }
 }
 
+impl MultipartParser {
+    /// Content-Type が multipart/* のパートのボディを入れ子の multipart としてパースする
+    fn parse_nested(
+        &self,
+        content_type: Option<&ContentType>,
+        body: &[u8],
+    ) -> Result<Vec<Part>, MultipartError> {
+        let Some(boundary) = content_type
+            .filter(|ct| ct.is_multipart())
+            .and_then(|ct| ct.boundary())
+        else {
+            return Ok(Vec::new());
+        };
+        if self.max_depth == 0 {
+            if self.nested {
+                return Err(MultipartError::NestingTooDeep);
+            }
+            return Ok(Vec::new());
+        }
+
+        let mut parser = MultipartParser::new(boundary)
+            .with_max_buffer_size(body.len())
+            .with_max_depth(self.max_depth - 1)
+            .without_form_data_checks();
+        parser.nested = true;
+        parser.feed(body)?;
+
+        let mut parts = Vec::new();
+        loop {
+            match parser.next_part() {
+                Ok(Some(part)) => parts.push(part),
+                Ok(None) => return Ok(parts),
+                // 入れ子のボディは完全に受信済みなので、終了境界がない場合は不正なパート
+                Err(MultipartError::Incomplete) => return Err(MultipartError::InvalidPart),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
 /// multipart ボディビルダー
 #[derive(Debug, Clone)]
 pub struct MultipartBuilder {
@@ -661,55 +849,199 @@ impl MultipartBuilder {
         self
     }
 
+    /// boundary がパートの内容と衝突しないようにする
+    ///
+    /// いずれかのパートのヘッダーまたはボディに `--<boundary>` が含まれる場合、
+    /// boundary の末尾に `-1`, `-2`, ... を付けて衝突しない boundary に置き換える
+    /// (RFC 2046 Section 5.1.1: boundary はカプセル化したデータ内に現れてはならない)。
+    /// すべてのパートを追加した後、`content_type()` を取得する前に呼ぶこと。
+    pub fn with_safe_boundary(mut self) -> Self {
+        if !self.boundary_collides(&self.boundary) {
+            return self;
+        }
+        let mut suffix = 1u64;
+        loop {
+            let suffix_str = alloc::format!("-{}", suffix);
+            // RFC 2046 Section 5.1.1: boundary は 70 文字以内
+            let mut base_len = self.boundary.len().min(70 - suffix_str.len());
+            while !self.boundary.is_char_boundary(base_len) {
+                base_len -= 1;
+            }
+            let candidate = alloc::format!("{}{}", &self.boundary[..base_len], suffix_str);
+            if !self.boundary_collides(&candidate) {
+                self.boundary = candidate;
+                return self;
+            }
+            suffix += 1;
+        }
+    }
+
+    /// boundary がいずれかのパートのヘッダーまたはボディに現れるか
+    fn boundary_collides(&self, boundary: &str) -> bool {
+        let mut delimiter = Vec::with_capacity(2 + boundary.len());
+        delimiter.extend_from_slice(b"--");
+        delimiter.extend_from_slice(boundary.as_bytes());
+        self.parts.iter().any(|part| {
+            let mut head = Vec::new();
+            write_part_headers(part, &mut head);
+            find_bytes(&head, &delimiter).is_some() || find_bytes(&part.body, &delimiter).is_some()
+        })
+    }
+
     /// ボディをビルド
     pub fn build(&self) -> Vec<u8> {
+        let mut encoder = MultipartEncoder::with_boundary(&self.boundary);
         let mut result = Vec::new();
 
         for part in &self.parts {
-            // 境界
-            result.extend_from_slice(b"--");
-            result.extend_from_slice(self.boundary.as_bytes());
-            result.extend_from_slice(b"\r\n");
+            result.extend_from_slice(&encoder.begin_part(part));
+            result.extend_from_slice(&part.body);
+        }
 
-            // Content-Disposition
-            if let Some(cd) = &part.content_disposition {
-                result.extend_from_slice(b"Content-Disposition: ");
-                result.extend_from_slice(cd.to_string().as_bytes());
-                result.extend_from_slice(b"\r\n");
-            }
+        result.extend_from_slice(&encoder.finish());
+        result
+    }
+}
 
-            // Content-Type
-            if let Some(ct) = &part.content_type {
-                result.extend_from_slice(b"Content-Type: ");
-                result.extend_from_slice(ct.to_string().as_bytes());
-                result.extend_from_slice(b"\r\n");
-            }
+/// multipart ボディのストリーミングエンコーダー
+///
+/// 大きなファイルをメモリに載せずに送信するため、パートのヘッダーと境界だけを生成する。
+/// パートのボディは `begin_part()` の出力に続けて呼び出し側がそのまま送信する。
+///
+/// ボディに boundary が現れないことは検査できないため、十分な長さの乱数から
+/// 生成した boundary を使うこと。
+///
+/// ```rust
+/// use shiguredo_http11::multipart::{MultipartEncoder, MultipartParser, Part};
+///
+/// let mut encoder = MultipartEncoder::new(12345);
+/// let boundary = encoder.boundary().to_string();
+/// let mut body = Vec::new();
+///
+/// body.extend_from_slice(&encoder.begin_part(&Part::new("field")));
+/// body.extend_from_slice(b"value");
+///
+/// body.extend_from_slice(&encoder.begin_part(&Part::file("file", "a.bin", "application/octet-stream")));
+/// for chunk in [b"chunk1", b"chunk2"] {
+///     body.extend_from_slice(chunk);
+/// }
+/// body.extend_from_slice(&encoder.finish());
+///
+/// let mut parser = MultipartParser::new(&boundary);
+/// parser.feed(&body).unwrap();
+/// assert_eq!(parser.next_part().unwrap().unwrap().body(), b"value");
+/// assert_eq!(parser.next_part().unwrap().unwrap().body(), b"chunk1chunk2");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultipartEncoder {
+    /// 境界文字列
+    boundary: String,
+    /// パートを開始済みか
+    started: bool,
+}
 
-            // その他のヘッダー
-            for (name, value) in &part.headers {
-                result.extend_from_slice(name.as_bytes());
-                result.extend_from_slice(b": ");
-                result.extend_from_slice(value.as_bytes());
-                result.extend_from_slice(b"\r\n");
-            }
+impl MultipartEncoder {
+    /// 乱数値を受け取って境界を生成する
+    ///
+    /// 境界は `MultipartBuilder::new()` と同じ形式で生成する。
+    pub fn new(random_value: u64) -> Self {
+        Self::with_boundary(&alloc::format!("----FormBoundary{}", random_value))
+    }
 
-            // ヘッダーとボディの区切り
-            result.extend_from_slice(b"\r\n");
+    /// 境界を指定して作成
+    pub fn with_boundary(boundary: &str) -> Self {
+        MultipartEncoder {
+            boundary: boundary.to_string(),
+            started: false,
+        }
+    }
 
-            // ボディ
-            result.extend_from_slice(&part.body);
+    /// boundary を検証して作成
+    ///
+    /// RFC 2046 Section 5.1.1 に従い、boundary 文字列を検証します。
+    pub fn try_with_boundary(boundary: &str) -> Result<Self, MultipartError> {
+        if !is_valid_boundary(boundary) {
+            return Err(MultipartError::InvalidBoundary);
+        }
+        Ok(Self::with_boundary(boundary))
+    }
+
+    /// 境界文字列を取得
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Content-Type ヘッダー値を取得
+    ///
+    /// RFC 9110 Section 5.6.6: boundary が token に該当しない場合は quoted-string で囲む
+    pub fn content_type(&self) -> String {
+        if self.boundary.bytes().all(is_token_char) {
+            alloc::format!("multipart/form-data; boundary={}", self.boundary)
+        } else {
+            alloc::format!("multipart/form-data; boundary=\"{}\"", self.boundary)
+        }
+    }
+
+    /// パートを開始し、境界とパートのヘッダーを生成
+    ///
+    /// `part` のボディは使わない。前のパートがある場合は、そのボディを終える CRLF を含む。
+    pub fn begin_part(&mut self, part: &Part) -> Vec<u8> {
+        let mut result = Vec::new();
+        if self.started {
             result.extend_from_slice(b"\r\n");
         }
+        self.started = true;
 
-        // 終了境界
+        // 境界
         result.extend_from_slice(b"--");
         result.extend_from_slice(self.boundary.as_bytes());
-        result.extend_from_slice(b"--\r\n");
+        result.extend_from_slice(b"\r\n");
+
+        write_part_headers(part, &mut result);
+
+        // ヘッダーとボディの区切り
+        result.extend_from_slice(b"\r\n");
+        result
+    }
 
+    /// 終了境界を生成
+    pub fn finish(self) -> Vec<u8> {
+        let mut result = Vec::new();
+        if self.started {
+            result.extend_from_slice(b"\r\n");
+        }
+        result.extend_from_slice(b"--");
+        result.extend_from_slice(self.boundary.as_bytes());
+        result.extend_from_slice(b"--\r\n");
         result
     }
 }
 
+/// パートのヘッダーを書き込む
+fn write_part_headers(part: &Part, result: &mut Vec<u8>) {
+    // Content-Disposition
+    if let Some(cd) = &part.content_disposition {
+        result.extend_from_slice(b"Content-Disposition: ");
+        result.extend_from_slice(cd.to_string().as_bytes());
+        result.extend_from_slice(b"\r\n");
+    }
+
+    // Content-Type
+    if let Some(ct) = &part.content_type {
+        result.extend_from_slice(b"Content-Type: ");
+        result.extend_from_slice(ct.to_string().as_bytes());
+        result.extend_from_slice(b"\r\n");
+    }
+
+    // その他のヘッダー
+    for (name, value) in &part.headers {
+        result.extend_from_slice(name.as_bytes());
+        result.extend_from_slice(b": ");
+        result.extend_from_slice(value.as_bytes());
+        result.extend_from_slice(b"\r\n");
+    }
+}
+
 /// バイト列から部分列を検索
 ///
 /// 実装は「needle の先頭バイト一致点を `iter().position()` で skip し、
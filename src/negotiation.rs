@@ -0,0 +1,236 @@
+//! プロアクティブネゴシエーション (RFC 9110 Section 12.1)
+//!
+//! ## 概要
+//!
+//! リクエストの Accept / Accept-Encoding / Accept-Language とサーバーが提供できる
+//! 表現から、送信する表現と Vary ヘッダーをまとめて決めます。
+//!
+//! - メディアタイプは `accept::negotiate()` で選ぶ
+//! - コンテンツコーディングは `accept::negotiate_encoding()` で選ぶ
+//! - 言語は `accept::lookup_language()` で選び、見つからない場合は先頭の言語を使う
+//!
+//! Vary にはリクエストによって選択が変わりうるフィールドを入れる。
+//! メディアタイプと言語は候補が 2 つ以上の場合、コンテンツコーディングは
+//! 候補が 1 つ以上の場合 (identity との選択になるため) に含める。
+//! Vary はリクエストにヘッダーがあるかどうかによらず同じになるため、
+//! キャッシュは常に正しい二次キーを使える (RFC 9110 Section 12.5.5)。
+//!
+//! ヘッダーがない場合と不正な場合は制約がないものとして扱い、サーバーの優先順
+//! (候補の先頭) で選ぶ。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::negotiation::Negotiator;
+//! use shiguredo_http11::Request;
+//!
+//! let negotiator = Negotiator::new()
+//!     .with_media_types(&["application/json", "text/html"])
+//!     .with_encodings(&["br", "gzip"])
+//!     .with_languages(&["en", "ja"]);
+//!
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("Accept", "text/html, application/json;q=0.9")
+//!     .unwrap()
+//!     .header("Accept-Encoding", "gzip")
+//!     .unwrap()
+//!     .header("Accept-Language", "ja-JP, en;q=0.5")
+//!     .unwrap();
+//!
+//! let negotiation = negotiator.negotiate(&request).unwrap();
+//! assert_eq!(negotiation.media_type(), Some("text/html"));
+//! assert_eq!(negotiation.content_encoding(), Some("gzip"));
+//! assert_eq!(negotiation.language(), Some("ja"));
+//! assert_eq!(
+//!     negotiation.vary_header().as_deref(),
+//!     Some("Accept, Accept-Encoding, Accept-Language")
+//! );
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::accept::{
+    Accept, AcceptEncoding, AcceptLanguage, lookup_language, negotiate, negotiate_encoding,
+};
+use crate::decoder::HttpHead;
+
+/// ネゴシエーションエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NegotiationError {
+    /// Accept に適合するメディアタイプがない
+    NoAcceptableMediaType,
+    /// Accept-Encoding が identity を含むすべての候補を除外している
+    NoAcceptableEncoding,
+}
+
+impl fmt::Display for NegotiationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NegotiationError::NoAcceptableMediaType => write!(f, "no acceptable media type"),
+            NegotiationError::NoAcceptableEncoding => write!(f, "no acceptable content coding"),
+        }
+    }
+}
+
+impl core::error::Error for NegotiationError {}
+
+/// サーバーが提供できる表現
+///
+/// 候補はいずれもサーバーの優先順に並べる。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Negotiator<'a> {
+    media_types: Vec<&'a str>,
+    encodings: Vec<&'a str>,
+    languages: Vec<&'a str>,
+}
+
+impl<'a> Negotiator<'a> {
+    /// 候補のない Negotiator を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 提供できるメディアタイプを指定
+    pub fn with_media_types(mut self, media_types: &[&'a str]) -> Self {
+        self.media_types = media_types.to_vec();
+        self
+    }
+
+    /// 提供できるコンテンツコーディングを指定
+    ///
+    /// identity (コーディングなし) は常に提供できるものとして扱うため、含めなくてよい。
+    pub fn with_encodings(mut self, encodings: &[&'a str]) -> Self {
+        self.encodings = encodings.to_vec();
+        self
+    }
+
+    /// 提供できる言語タグを指定
+    ///
+    /// 先頭の言語タグは、Accept-Language に一致するものがない場合のデフォルトになる。
+    pub fn with_languages(mut self, languages: &[&'a str]) -> Self {
+        self.languages = languages.to_vec();
+        self
+    }
+
+    /// Vary に含めるフィールド名
+    ///
+    /// 候補の数だけで決まり、リクエストには依存しない。
+    /// 406 Not Acceptable のレスポンスにも同じ Vary を付与すること。
+    pub fn vary_fields(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.media_types.len() > 1 {
+            fields.push("Accept");
+        }
+        if !self.encodings.is_empty() {
+            fields.push("Accept-Encoding");
+        }
+        if self.languages.len() > 1 {
+            fields.push("Accept-Language");
+        }
+        fields
+    }
+
+    /// リクエストに対して送信する表現を選ぶ
+    ///
+    /// 同名のヘッダーが複数行ある場合は結合してパースする。
+    pub fn negotiate<H: HttpHead>(&self, request: &H) -> Result<Negotiation<'a>, NegotiationError> {
+        let media_type = match (
+            self.media_types.as_slice(),
+            header_value(request, "Accept").and_then(|v| Accept::parse(&v).ok()),
+        ) {
+            ([], _) => None,
+            (media_types, None) => media_types.first().copied(),
+            (media_types, Some(accept)) => Some(
+                negotiate(&accept, media_types).ok_or(NegotiationError::NoAcceptableMediaType)?,
+            ),
+        };
+
+        let encoding = match (
+            self.encodings.as_slice(),
+            header_value(request, "Accept-Encoding").and_then(|v| AcceptEncoding::parse(&v).ok()),
+        ) {
+            ([], _) => None,
+            // RFC 9110 Section 12.5.3: Accept-Encoding がない場合はどのコーディングも受理される
+            (encodings, None) => encodings.first().copied(),
+            (encodings, Some(accept)) => Some(
+                negotiate_encoding(&accept, encodings)
+                    .ok_or(NegotiationError::NoAcceptableEncoding)?,
+            ),
+        };
+
+        let language = match (
+            self.languages.as_slice(),
+            header_value(request, "Accept-Language").and_then(|v| AcceptLanguage::parse(&v).ok()),
+        ) {
+            ([], _) => None,
+            // RFC 9110 Section 12.5.4: 一致しない場合は 406 ではなくデフォルトの言語で応答してよい
+            (languages, Some(accept)) => {
+                lookup_language(&accept, languages).or_else(|| languages.first().copied())
+            }
+            (languages, None) => languages.first().copied(),
+        };
+
+        Ok(Negotiation {
+            media_type,
+            encoding,
+            language,
+            vary: self.vary_fields(),
+        })
+    }
+}
+
+/// ネゴシエーションの結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Negotiation<'a> {
+    media_type: Option<&'a str>,
+    encoding: Option<&'a str>,
+    language: Option<&'a str>,
+    vary: Vec<&'static str>,
+}
+
+impl<'a> Negotiation<'a> {
+    /// 選ばれたメディアタイプ
+    ///
+    /// メディアタイプの候補を指定していない場合は `None`。
+    pub fn media_type(&self) -> Option<&'a str> {
+        self.media_type
+    }
+
+    /// 選ばれたコンテンツコーディング
+    ///
+    /// コーディングの候補を指定していない場合と identity が選ばれた場合は `None`。
+    /// `Some` の場合はボディをそのコーディングで符号化し、Content-Encoding を付与する。
+    pub fn content_encoding(&self) -> Option<&'a str> {
+        self.encoding
+            .filter(|encoding| !encoding.eq_ignore_ascii_case("identity"))
+    }
+
+    /// 選ばれた言語タグ
+    ///
+    /// 言語の候補を指定していない場合は `None`。Content-Language に使える。
+    pub fn language(&self) -> Option<&'a str> {
+        self.language
+    }
+
+    /// Vary に含めるフィールド名
+    pub fn vary_fields(&self) -> &[&'static str] {
+        &self.vary
+    }
+
+    /// Vary ヘッダーの値
+    ///
+    /// 選択がリクエストに依存しない場合は `None`。
+    pub fn vary_header(&self) -> Option<String> {
+        (!self.vary.is_empty()).then(|| self.vary.join(", "))
+    }
+}
+
+/// 同名のヘッダーを `", "` で結合して取得
+fn header_value<H: HttpHead>(request: &H, name: &str) -> Option<String> {
+    let values = request.get_headers(name);
+    (!values.is_empty()).then(|| values.join(", "))
+}
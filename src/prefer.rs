@@ -0,0 +1,541 @@
+//! Prefer / Preference-Applied ヘッダー (RFC 7240)
+//!
+//! ## 概要
+//!
+//! クライアントがサーバーに処理の希望を伝える Prefer ヘッダーと、
+//! サーバーが採用した希望を返す Preference-Applied ヘッダーのパースと生成を提供します。
+//!
+//! - RFC 7240 で定義された respond-async / return / wait / handling の取得
+//! - 拡張の preference とパラメーターの取得
+//! - 同じ preference が複数回現れる場合は最初のものを採用する (RFC 7240 Section 2)
+//!
+//! サーバーが Prefer によってレスポンスを変える場合、キャッシュ可能なレスポンスには
+//! `Vary: Prefer` を付ける (RFC 7240 Section 2)。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Prefer             = 1#preference
+//! preference         = token [ BWS "=" BWS word ]
+//!                      *( OWS ";" [ OWS parameter ] )
+//! parameter          = token [ BWS "=" BWS word ]
+//! Preference-Applied = 1#applied-pref
+//! applied-pref       = token [ BWS "=" BWS word ]
+//! word               = token / quoted-string
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::prefer::{Prefer, PreferenceApplied, ReturnPreference};
+//! use shiguredo_http11::{Request, Response, StatusCode};
+//!
+//! let request = Request::new("POST", "/items")
+//!     .unwrap()
+//!     .header("Prefer", "return=minimal, wait=10, respond-async")
+//!     .unwrap();
+//! let prefer = Prefer::from_request(&request).unwrap().unwrap();
+//! assert_eq!(prefer.return_preference(), Some(ReturnPreference::Minimal));
+//! assert_eq!(prefer.wait(), Some(10));
+//! assert!(prefer.respond_async());
+//!
+//! // return=minimal を採用したことを返す
+//! let applied = PreferenceApplied::new()
+//!     .with_preference(prefer.get("return").unwrap());
+//! let response = Response::with_status(StatusCode::NO_CONTENT)
+//!     .preference_applied(&applied)
+//!     .unwrap();
+//! assert_eq!(response.get_header("Preference-Applied"), Some("return=minimal"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::typed_header::TypedHeader;
+use crate::validate::{
+    QuotedStringError, escape_quotes, is_token_char, is_valid_field_value, is_valid_token,
+    parse_quoted_string,
+};
+
+/// Prefer パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PreferError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正な preference 名またはパラメーター名
+    InvalidName,
+    /// 不正な値
+    InvalidValue,
+    /// 閉じられていない quoted-string
+    UnterminatedQuote,
+}
+
+impl fmt::Display for PreferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreferError::InvalidFormat => write!(f, "invalid Prefer header format"),
+            PreferError::InvalidName => write!(f, "invalid preference name"),
+            PreferError::InvalidValue => write!(f, "invalid preference value"),
+            PreferError::UnterminatedQuote => write!(f, "unterminated quoted-string"),
+        }
+    }
+}
+
+impl core::error::Error for PreferError {}
+
+impl From<QuotedStringError> for PreferError {
+    fn from(e: QuotedStringError) -> Self {
+        match e {
+            QuotedStringError::InvalidQdtext | QuotedStringError::InvalidQuotedPair => {
+                PreferError::InvalidValue
+            }
+            QuotedStringError::Unterminated => PreferError::UnterminatedQuote,
+        }
+    }
+}
+
+/// return preference の値 (RFC 7240 Section 4.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnPreference {
+    /// `return=representation`
+    Representation,
+    /// `return=minimal`
+    Minimal,
+}
+
+/// handling preference の値 (RFC 7240 Section 4.4)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handling {
+    /// `handling=strict`
+    Strict,
+    /// `handling=lenient`
+    Lenient,
+}
+
+/// 1 つの preference
+///
+/// 名前とパラメーター名は小文字に正規化する。
+/// 空の値 (`foo=""`) は値なしとして扱う (RFC 7240 Section 2)。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preference {
+    name: String,
+    value: Option<String>,
+    params: Vec<(String, Option<String>)>,
+}
+
+impl Preference {
+    /// 名前を指定して作成
+    pub fn new(name: &str) -> Result<Self, PreferError> {
+        if !is_valid_token(name) {
+            return Err(PreferError::InvalidName);
+        }
+        Ok(Preference {
+            name: name.to_ascii_lowercase(),
+            value: None,
+            params: Vec::new(),
+        })
+    }
+
+    /// 値を設定 (ビルダーパターン)
+    pub fn with_value(mut self, value: &str) -> Result<Self, PreferError> {
+        self.value = validate_value(value)?;
+        Ok(self)
+    }
+
+    /// パラメーターを追加 (ビルダーパターン)
+    pub fn with_param(mut self, name: &str, value: Option<&str>) -> Result<Self, PreferError> {
+        if !is_valid_token(name) {
+            return Err(PreferError::InvalidName);
+        }
+        let value = match value {
+            Some(value) => validate_value(value)?,
+            None => None,
+        };
+        self.params.push((name.to_ascii_lowercase(), value));
+        Ok(self)
+    }
+
+    /// 名前 (小文字)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 値
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// パラメーターの一覧
+    pub fn params(&self) -> &[(String, Option<String>)] {
+        &self.params
+    }
+
+    /// パラメーターの値を取得 (名前は大文字小文字を区別しない)
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// パラメーターがあるかどうか (名前は大文字小文字を区別しない)
+    pub fn has_param(&self, name: &str) -> bool {
+        self.params
+            .iter()
+            .any(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Preference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_pair(f, &self.name, self.value.as_deref())?;
+        for (name, value) in &self.params {
+            write!(f, "; ")?;
+            write_pair(f, name, value.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+/// Prefer ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Prefer {
+    preferences: Vec<Preference>,
+}
+
+impl Prefer {
+    /// 空の Prefer を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prefer ヘッダーをパース
+    ///
+    /// 空の要素は無視する。
+    pub fn parse(input: &str) -> Result<Self, PreferError> {
+        let mut preferences = Vec::new();
+        let mut rest = input;
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ',']);
+            if rest.is_empty() {
+                break;
+            }
+            let (name, value, remaining) = parse_pair(rest)?;
+            let mut preference = Preference {
+                name,
+                value,
+                params: Vec::new(),
+            };
+            rest = remaining;
+
+            loop {
+                rest = rest.trim_start_matches([' ', '\t']);
+                let Some(after) = rest.strip_prefix(';') else {
+                    break;
+                };
+                rest = after.trim_start_matches([' ', '\t']);
+                if rest.is_empty() || rest.starts_with([';', ',']) {
+                    continue;
+                }
+                let (name, value, remaining) = parse_pair(rest)?;
+                preference.params.push((name, value));
+                rest = remaining;
+            }
+
+            if !rest.is_empty() && !rest.starts_with(',') {
+                return Err(PreferError::InvalidFormat);
+            }
+            preferences.push(preference);
+        }
+        Ok(Prefer { preferences })
+    }
+
+    /// リクエストの Prefer ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_request<H: HttpHead>(request: &H) -> Result<Option<Self>, PreferError> {
+        let values = request.get_headers("Prefer");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// preference を追加 (ビルダーパターン)
+    pub fn with_preference(mut self, preference: Preference) -> Self {
+        self.preferences.push(preference);
+        self
+    }
+
+    /// preference の一覧
+    pub fn preferences(&self) -> &[Preference] {
+        &self.preferences
+    }
+
+    /// preference を取得 (名前は大文字小文字を区別しない)
+    ///
+    /// 同じ preference が複数回現れる場合は最初のものを返す。
+    pub fn get(&self, name: &str) -> Option<&Preference> {
+        self.preferences
+            .iter()
+            .find(|preference| preference.name.eq_ignore_ascii_case(name))
+    }
+
+    /// preference があるかどうか (名前は大文字小文字を区別しない)
+    pub fn has(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// respond-async があるかどうか (RFC 7240 Section 4.1)
+    pub fn respond_async(&self) -> bool {
+        self.has("respond-async")
+    }
+
+    /// return preference (RFC 7240 Section 4.2)
+    ///
+    /// 値が representation / minimal 以外の場合は `None` を返す。
+    pub fn return_preference(&self) -> Option<ReturnPreference> {
+        match self.get("return")?.value()? {
+            value if value.eq_ignore_ascii_case("representation") => {
+                Some(ReturnPreference::Representation)
+            }
+            value if value.eq_ignore_ascii_case("minimal") => Some(ReturnPreference::Minimal),
+            _ => None,
+        }
+    }
+
+    /// wait preference の秒数 (RFC 7240 Section 4.3)
+    ///
+    /// 値が delta-seconds でない場合は `None` を返す。
+    /// u64 に収まらない場合は u64::MAX に飽和させる。
+    pub fn wait(&self) -> Option<u64> {
+        let value = self.get("wait")?.value()?;
+        if !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some(value.bytes().fold(0u64, |acc, b| {
+            acc.saturating_mul(10).saturating_add(u64::from(b - b'0'))
+        }))
+    }
+
+    /// handling preference (RFC 7240 Section 4.4)
+    ///
+    /// 値が strict / lenient 以外の場合は `None` を返す。
+    pub fn handling(&self) -> Option<Handling> {
+        match self.get("handling")?.value()? {
+            value if value.eq_ignore_ascii_case("strict") => Some(Handling::Strict),
+            value if value.eq_ignore_ascii_case("lenient") => Some(Handling::Lenient),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Prefer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, preference) in self.preferences.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", preference)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for Prefer {
+    const NAME: &'static str = "Prefer";
+    type Error = PreferError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Preference-Applied ヘッダー
+///
+/// applied-pref はパラメーターを持たない (RFC 7240 Section 3)。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreferenceApplied {
+    applied: Vec<(String, Option<String>)>,
+}
+
+impl PreferenceApplied {
+    /// 空の Preference-Applied を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 採用した preference を追加 (ビルダーパターン)
+    ///
+    /// preference の名前と値を使い、パラメーターは含めない。
+    pub fn with_preference(mut self, preference: &Preference) -> Self {
+        self.applied
+            .push((preference.name.clone(), preference.value.clone()));
+        self
+    }
+
+    /// 名前と値を指定して追加 (ビルダーパターン)
+    pub fn with(mut self, name: &str, value: Option<&str>) -> Result<Self, PreferError> {
+        let preference = Preference::new(name)?;
+        let value = match value {
+            Some(value) => validate_value(value)?,
+            None => None,
+        };
+        self.applied.push((preference.name, value));
+        Ok(self)
+    }
+
+    /// Preference-Applied ヘッダーをパース
+    pub fn parse(input: &str) -> Result<Self, PreferError> {
+        let mut applied = Vec::new();
+        let mut rest = input;
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ',']);
+            if rest.is_empty() {
+                break;
+            }
+            let (name, value, remaining) = parse_pair(rest)?;
+            rest = remaining.trim_start_matches([' ', '\t']);
+            if !rest.is_empty() && !rest.starts_with(',') {
+                return Err(PreferError::InvalidFormat);
+            }
+            applied.push((name, value));
+        }
+        Ok(PreferenceApplied { applied })
+    }
+
+    /// レスポンスの Preference-Applied ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_response<H: HttpHead>(response: &H) -> Result<Option<Self>, PreferError> {
+        let values = response.get_headers("Preference-Applied");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// 採用された preference の名前と値の一覧
+    pub fn applied(&self) -> &[(String, Option<String>)] {
+        &self.applied
+    }
+
+    /// preference が採用されたかどうか (名前は大文字小文字を区別しない)
+    pub fn contains(&self, name: &str) -> bool {
+        self.applied
+            .iter()
+            .any(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+
+    /// 採用された preference の値を取得 (名前は大文字小文字を区別しない)
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.applied
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// 採用された preference がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty()
+    }
+}
+
+impl fmt::Display for PreferenceApplied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.applied.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write_pair(f, name, value.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for PreferenceApplied {
+    const NAME: &'static str = "Preference-Applied";
+    type Error = PreferError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Response {
+    /// Preference-Applied ヘッダーを設定 (ビルダーパターン)
+    ///
+    /// 既存の Preference-Applied は置き換える。採用した preference がない場合は何もしない。
+    pub fn preference_applied(mut self, applied: &PreferenceApplied) -> Result<Self, EncodeError> {
+        if !applied.is_empty() {
+            self.set_header(PreferenceApplied::NAME, applied.to_string())?;
+        }
+        Ok(self)
+    }
+}
+
+/// `token [ BWS "=" BWS word ]` をパース
+///
+/// 名前は小文字に正規化し、空の値は `None` にする。
+fn parse_pair(input: &str) -> Result<(String, Option<String>, &str), PreferError> {
+    let name_end = input
+        .bytes()
+        .position(|b| !is_token_char(b))
+        .unwrap_or(input.len());
+    if name_end == 0 {
+        return Err(PreferError::InvalidName);
+    }
+    let name = input[..name_end].to_ascii_lowercase();
+    let rest = input[name_end..].trim_start_matches([' ', '\t']);
+
+    let Some(after) = rest.strip_prefix('=') else {
+        return Ok((name, None, rest));
+    };
+    let after = after.trim_start_matches([' ', '\t']);
+    let (value, rest) = if let Some(quoted) = after.strip_prefix('"') {
+        parse_quoted_string(quoted)?
+    } else {
+        let value_end = after
+            .bytes()
+            .position(|b| !is_token_char(b))
+            .unwrap_or(after.len());
+        if value_end == 0 {
+            return Err(PreferError::InvalidValue);
+        }
+        (after[..value_end].to_string(), &after[value_end..])
+    };
+    let value = if value.is_empty() { None } else { Some(value) };
+    Ok((name, value, rest))
+}
+
+fn validate_value(value: &str) -> Result<Option<String>, PreferError> {
+    if !is_valid_field_value(value) {
+        return Err(PreferError::InvalidValue);
+    }
+    if value.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(value.to_string()))
+}
+
+fn write_pair(f: &mut fmt::Formatter<'_>, name: &str, value: Option<&str>) -> fmt::Result {
+    f.write_str(name)?;
+    match value {
+        Some(value) if is_valid_token(value) => write!(f, "={}", value),
+        Some(value) => write!(f, "=\"{}\"", escape_quotes(value)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,425 @@
+//! プロキシのメッセージ書き換え (RFC 9110 Section 7.6 / RFC 9112 Section 3.2.2)
+//!
+//! ## 概要
+//!
+//! デコード済みの `RequestHead` / `ResponseHead` から、プロキシが次のホップに
+//! 転送するヘッダーを組み立てる Sans I/O の書き換え処理を提供します。
+//!
+//! - hop-by-hop ヘッダー (Connection とそこに列挙されたヘッダーを含む) を取り除く
+//! - Host を上流のホストに書き換える (absolute-form では request-target の authority を使う)
+//! - Via / Forwarded / X-Forwarded-* に自身の情報を追加する
+//! - 転送するボディの `BodyKind` に合わせて Content-Length / Transfer-Encoding を設定する
+//! - HTTP-version は自身のバージョン (HTTP/1.1) にする (RFC 9110 Section 6.2)
+//!
+//! ボディは書き換えない。呼び出し側は `ProxyRequest::body_kind()` /
+//! `ProxyResponse::body_kind()` に従ってボディを転送する
+//! (`BodyKind::Chunked` なら `encode_chunk()` でチャンク化する)。
+//!
+//! 受信したヘッダー値の受信バイト列 (`headers_raw()`) は引き継がない。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::forwarded::{ForwardedElement, Node};
+//! use shiguredo_http11::host::Host;
+//! use shiguredo_http11::proxy::ProxyRewriter;
+//! use shiguredo_http11::via::ViaEntry;
+//! use shiguredo_http11::{BodyKind, HttpHead, RequestDecoder};
+//!
+//! let mut decoder = RequestDecoder::new();
+//! decoder
+//!     .feed(b"POST /api HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\nContent-Length: 5\r\n\r\nhello")
+//!     .unwrap();
+//! let (head, body_kind) = decoder.decode_headers().unwrap().unwrap();
+//!
+//! let rewriter = ProxyRewriter::new()
+//!     .with_upstream_host(Host::parse("backend:8080").unwrap())
+//!     .with_via(ViaEntry::new("1.1", "proxy").unwrap());
+//! let forwarded = ForwardedElement::new().with_for(Node::parse("192.0.2.1").unwrap());
+//! let request = rewriter
+//!     .rewrite_request(&head, body_kind, Some(&forwarded))
+//!     .unwrap();
+//!
+//! assert_eq!(request.head().get_header("Host"), Some("backend:8080"));
+//! assert_eq!(request.head().get_header("Connection"), None);
+//! assert_eq!(request.head().get_header("Content-Length"), Some("5"));
+//! assert_eq!(request.head().get_header("Via"), Some("1.1 proxy"));
+//! assert_eq!(request.head().get_header("Forwarded"), Some("for=192.0.2.1"));
+//! assert_eq!(request.body_kind(), BodyKind::ContentLength(5));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::connection::{ConnectionOptions, is_hop_by_hop};
+use crate::decoder::{BodyKind, HttpHead, RequestHead, ResponseHead};
+use crate::forwarded::{ForwardedElement, forwarded_value, x_forwarded_values};
+use crate::host::Host;
+use crate::request_target::RequestTargetForm;
+use crate::version::HttpVersion;
+use crate::via::{self, ViaEntry, detect_loop};
+
+/// プロキシの書き換えエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProxyError {
+    /// Via に自身の pseudonym が含まれている (リクエストのループ)
+    LoopDetected,
+    /// 転送先の Host を決められない
+    MissingHost,
+    /// リクエストに使えない BodyKind (`CloseDelimited`)
+    InvalidFraming,
+    /// 転送するヘッダーが不正
+    InvalidHeader,
+}
+
+impl fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyError::LoopDetected => write!(f, "request loop detected"),
+            ProxyError::MissingHost => write!(f, "missing host for forwarded request"),
+            ProxyError::InvalidFraming => write!(f, "invalid message framing for proxy"),
+            ProxyError::InvalidHeader => write!(f, "invalid header for forwarded message"),
+        }
+    }
+}
+
+impl core::error::Error for ProxyError {}
+
+/// プロキシのメッセージ書き換え設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyRewriter {
+    upstream_host: Option<Host>,
+    via: Option<ViaEntry>,
+    x_forwarded: bool,
+    origin_form: bool,
+}
+
+impl ProxyRewriter {
+    /// デフォルトの設定で作成
+    ///
+    /// Host は受信したものを引き継ぎ、Via / X-Forwarded-* は追加しない。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 転送するリクエストの Host を指定したホストに書き換える (ビルダーパターン)
+    ///
+    /// リバースプロキシで上流サーバーのホストを Host に設定する場合に使う。
+    pub fn with_upstream_host(mut self, host: Host) -> Self {
+        self.upstream_host = Some(host);
+        self
+    }
+
+    /// Via に追加するエントリを設定 (ビルダーパターン)
+    ///
+    /// RFC 9110 Section 7.6.3: プロキシは転送するリクエストに Via を追加しなければならない (MUST)。
+    /// エントリの received-by を pseudonym として、リクエストのループも検出する。
+    pub fn with_via(mut self, entry: ViaEntry) -> Self {
+        self.via = Some(entry);
+        self
+    }
+
+    /// Forwarded に加えて X-Forwarded-For / X-Forwarded-Proto / X-Forwarded-Host も追加する (ビルダーパターン)
+    pub fn with_x_forwarded(mut self) -> Self {
+        self.x_forwarded = true;
+        self
+    }
+
+    /// absolute-form の request-target を origin-form に変換する (ビルダーパターン)
+    ///
+    /// RFC 9112 Section 3.2.1: オリジンサーバーへ送るリクエストは origin-form を使う。
+    /// 転送先がオリジンサーバーの場合に使う。
+    pub fn with_origin_form(mut self) -> Self {
+        self.origin_form = true;
+        self
+    }
+
+    /// 転送するリクエストのヘッダーを組み立てる
+    ///
+    /// `body_kind` は転送するリクエストボディのフレーミング。
+    /// `forwarded` を指定した場合は Forwarded (と X-Forwarded-*) に追加する。
+    ///
+    /// RFC 9112 Section 3.2.2: absolute-form の場合、受信した Host は使わず
+    /// request-target の authority から Host を生成する (MUST)。
+    pub fn rewrite_request(
+        &self,
+        head: &RequestHead,
+        body_kind: BodyKind,
+        forwarded: Option<&ForwardedElement>,
+    ) -> Result<ProxyRequest, ProxyError> {
+        if let Some(entry) = &self.via
+            && detect_loop(head, entry.received_by()).unwrap_or(false)
+        {
+            return Err(ProxyError::LoopDetected);
+        }
+        if body_kind == BodyKind::CloseDelimited {
+            return Err(ProxyError::InvalidFraming);
+        }
+
+        let form = head.target_form();
+        let target = match (form, head.target_path()) {
+            (Some(RequestTargetForm::Absolute), Some(path)) if self.origin_form => {
+                let path = if path.is_empty() { "/" } else { path };
+                match head.uri().split_once('?') {
+                    Some((_, query)) => format!("{}?{}", path, query),
+                    None => path.to_string(),
+                }
+            }
+            _ => head.uri().to_string(),
+        };
+        let host = match (&self.upstream_host, form) {
+            (Some(host), _) => host.to_string(),
+            (None, Some(RequestTargetForm::Absolute | RequestTargetForm::Authority)) => head
+                .target_authority()
+                .ok_or(ProxyError::MissingHost)?
+                .to_string(),
+            (None, _) => head
+                .get_header("Host")
+                .ok_or(ProxyError::MissingHost)?
+                .to_string(),
+        };
+
+        let mut forward =
+            RequestHead::new(head.method(), &target).map_err(|_| ProxyError::InvalidHeader)?;
+        let mut headers = vec![("Host", host)];
+        for (name, value) in end_to_end_headers(head) {
+            if name.eq_ignore_ascii_case("host")
+                || (self.via.is_some() && name.eq_ignore_ascii_case("via"))
+                || (forwarded.is_some() && name.eq_ignore_ascii_case("forwarded"))
+                || (forwarded.is_some()
+                    && self.x_forwarded
+                    && name.eq_ignore_ascii_case("x-forwarded-for"))
+            {
+                continue;
+            }
+            headers.push((name, value.clone()));
+        }
+        headers.extend(framing_headers(head, body_kind));
+        if let Some(entry) = &self.via {
+            headers.push((
+                "Via",
+                via::append_list_value(head.get_headers("Via"), entry),
+            ));
+        }
+        if let Some(element) = forwarded {
+            headers.extend(forwarded_value(head, element).map(|value| ("Forwarded", value)));
+            if self.x_forwarded {
+                headers.extend(x_forwarded_values(head, element));
+            }
+        }
+        for (name, value) in headers {
+            forward
+                .add_header(name, &value)
+                .map_err(|_| ProxyError::InvalidHeader)?;
+        }
+
+        Ok(ProxyRequest {
+            head: forward,
+            body_kind,
+        })
+    }
+
+    /// 転送するレスポンスのヘッダーを組み立てる
+    ///
+    /// `body_kind` は転送するレスポンスボディのフレーミング。
+    /// 上流と異なるフレーミングにする場合は `downstream_body_kind()` で決める。
+    ///
+    /// - `BodyKind::CloseDelimited` の場合は `Connection: close` を付与する
+    /// - `BodyKind::None` の場合は受信した Content-Length を引き継ぐ
+    ///   (HEAD / 304 のレスポンスは選択された表現の長さを示すため、RFC 9110 Section 8.6)
+    pub fn rewrite_response(
+        &self,
+        head: &ResponseHead,
+        body_kind: BodyKind,
+    ) -> Result<ProxyResponse, ProxyError> {
+        let mut forward = ResponseHead::new(head.status_code(), head.reason_phrase())
+            .map_err(|_| ProxyError::InvalidHeader)?;
+        let mut headers = Vec::new();
+        for (name, value) in end_to_end_headers(head) {
+            if self.via.is_some() && name.eq_ignore_ascii_case("via") {
+                continue;
+            }
+            headers.push((name, value.clone()));
+        }
+        match body_kind {
+            BodyKind::None => {
+                // 1xx / 204 は Content-Length を持たない (RFC 9110 Section 8.6)
+                let status = head.status_code();
+                if !(100..200).contains(&status)
+                    && status != 204
+                    && head.get_header("Transfer-Encoding").is_none()
+                    && let Some(len) = head.get_header("Content-Length")
+                {
+                    headers.push(("Content-Length", len.to_string()));
+                }
+            }
+            BodyKind::CloseDelimited => headers.push(("Connection", "close".to_string())),
+            _ => headers.extend(framing_headers(head, body_kind)),
+        }
+        if let Some(entry) = &self.via {
+            headers.push((
+                "Via",
+                via::append_list_value(head.get_headers("Via"), entry),
+            ));
+        }
+        for (name, value) in headers {
+            forward
+                .add_header(name, &value)
+                .map_err(|_| ProxyError::InvalidHeader)?;
+        }
+
+        Ok(ProxyResponse {
+            head: forward,
+            body_kind,
+        })
+    }
+}
+
+/// 上流のレスポンスボディをクライアントに転送するときのフレーミングを決める
+///
+/// - HTTP/1.1 のクライアントには close-delimited のボディを chunked にして送る
+///   (クライアントとの接続を維持できる)
+/// - HTTP/1.1 以外のクライアントには chunked のボディを close-delimited にして送る
+///   (RFC 9112 Section 6.1: HTTP/1.1 に対応しない受信者には chunked を送ってはならない)
+/// - それ以外は上流のフレーミングをそのまま使う
+pub fn downstream_body_kind(upstream: BodyKind, client_version: &HttpVersion) -> BodyKind {
+    let is_http11 = *client_version == HttpVersion::Http11;
+    match upstream {
+        BodyKind::CloseDelimited if is_http11 => BodyKind::Chunked,
+        BodyKind::Chunked if !is_http11 => BodyKind::CloseDelimited,
+        kind => kind,
+    }
+}
+
+/// 転送するリクエスト
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyRequest {
+    head: RequestHead,
+    body_kind: BodyKind,
+}
+
+impl ProxyRequest {
+    /// 転送するリクエストヘッダー
+    pub fn head(&self) -> &RequestHead {
+        &self.head
+    }
+
+    /// 転送するリクエストヘッダー (可変借用)
+    ///
+    /// 上流との接続を維持するための `Connection: keep-alive` などを追加する場合に使う。
+    pub fn head_mut(&mut self) -> &mut RequestHead {
+        &mut self.head
+    }
+
+    /// 転送するボディのフレーミング
+    pub fn body_kind(&self) -> BodyKind {
+        self.body_kind
+    }
+
+    /// リクエストヘッダーに分解
+    pub fn into_head(self) -> RequestHead {
+        self.head
+    }
+
+    /// リクエストラインとヘッダーをエンコード (ボディは含まない)
+    pub fn encode_head(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(self.head.method().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.head.uri().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.head.version().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        encode_headers(&mut buf, self.head.headers());
+        buf
+    }
+}
+
+/// 転送するレスポンス
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyResponse {
+    head: ResponseHead,
+    body_kind: BodyKind,
+}
+
+impl ProxyResponse {
+    /// 転送するレスポンスヘッダー
+    pub fn head(&self) -> &ResponseHead {
+        &self.head
+    }
+
+    /// 転送するレスポンスヘッダー (可変借用)
+    pub fn head_mut(&mut self) -> &mut ResponseHead {
+        &mut self.head
+    }
+
+    /// 転送するボディのフレーミング
+    pub fn body_kind(&self) -> BodyKind {
+        self.body_kind
+    }
+
+    /// レスポンスヘッダーに分解
+    pub fn into_head(self) -> ResponseHead {
+        self.head
+    }
+
+    /// ステータスラインとヘッダーをエンコード (ボディは含まない)
+    pub fn encode_head(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(self.head.version().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.head.status_code().to_string().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.head.reason_phrase().as_bytes());
+        buf.extend_from_slice(b"\r\n");
+        encode_headers(&mut buf, self.head.headers());
+        buf
+    }
+}
+
+/// hop-by-hop ヘッダーと Content-Length 以外のヘッダー
+///
+/// Transfer-Encoding は hop-by-hop ヘッダーとして取り除かれる。
+fn end_to_end_headers<H: HttpHead>(head: &H) -> impl Iterator<Item = (&str, &String)> {
+    let connection = ConnectionOptions::collect(head.headers());
+    head.headers()
+        .iter()
+        .filter(move |(name, _)| {
+            !is_hop_by_hop(name, &connection) && !name.eq_ignore_ascii_case("content-length")
+        })
+        .map(|(name, value)| (name.as_ref(), value))
+}
+
+/// `BodyKind` に合わせた Content-Length / Transfer-Encoding
+///
+/// chunked で転送する場合、受信したメッセージに Transfer-Encoding があれば
+/// その値 (chunked 以外の転送コーディングを含む) を引き継ぐ。
+fn framing_headers<H: HttpHead>(received: &H, body_kind: BodyKind) -> Vec<(&'static str, String)> {
+    match body_kind {
+        BodyKind::ContentLength(len) => vec![("Content-Length", len.to_string())],
+        BodyKind::Chunked => {
+            let codings = received.get_headers("Transfer-Encoding");
+            if codings.is_empty() {
+                vec![("Transfer-Encoding", "chunked".to_string())]
+            } else {
+                vec![("Transfer-Encoding", codings.join(", "))]
+            }
+        }
+        BodyKind::None | BodyKind::CloseDelimited | BodyKind::Tunnel => Vec::new(),
+    }
+}
+
+fn encode_headers(buf: &mut Vec<u8>, headers: &[(Cow<'static, str>, String)]) {
+    for (name, value) in headers {
+        buf.extend_from_slice(name.as_bytes());
+        buf.extend_from_slice(b": ");
+        buf.extend_from_slice(value.as_bytes());
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf.extend_from_slice(b"\r\n");
+}
@@ -0,0 +1,659 @@
+//! Proxy-Status ヘッダー (RFC 9209)
+//!
+//! ## 概要
+//!
+//! 中継者 (プロキシ、ゲートウェイ、CDN) がレスポンスの生成にどう関わったか、
+//! 上流との通信でどのエラーが発生したかを伝える Proxy-Status ヘッダーの
+//! パースと生成を提供します。
+//!
+//! - 中継者ごとに 1 つのエントリを持ち、上流に近い中継者から順に並ぶ
+//! - エラーの種類は RFC 9209 Section 2.3 の Proxy Error Types レジストリの値を使う
+//! - `Response::proxy_error()`: エラーの種類の推奨ステータスコードで
+//!   Proxy-Status 付きのエラーレスポンスを作成する
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Proxy-Status = sf-list
+//! ; 各要素は中継者の識別子 (sf-string / sf-token) とパラメーター
+//! ; error (sf-token) / next-hop (sf-string / sf-token) / next-protocol (sf-binary) /
+//! ; received-status (sf-integer) / details (sf-string)
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::Response;
+//! use shiguredo_http11::proxy_status::{ProxyErrorType, ProxyStatus, ProxyStatusEntry};
+//!
+//! // ゲートウェイ: 上流への接続がタイムアウトした
+//! let entry = ProxyStatusEntry::new("gateway")
+//!     .unwrap()
+//!     .with_error(ProxyErrorType::ConnectionTimeout)
+//!     .with_next_hop("backend.example.com:8080")
+//!     .unwrap();
+//! let response = Response::proxy_error(&entry).unwrap();
+//! assert_eq!(response.status_code(), 504);
+//! assert_eq!(
+//!     response.get_header("Proxy-Status"),
+//!     Some("gateway;error=connection_timeout;next-hop=\"backend.example.com:8080\"")
+//! );
+//!
+//! // クライアント: ヘッダーをパース
+//! let status = ProxyStatus::from_message(&response).unwrap().unwrap();
+//! let entry = &status.entries()[0];
+//! assert_eq!(entry.intermediary(), "gateway");
+//! assert_eq!(entry.error(), Some(&ProxyErrorType::ConnectionTimeout));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::sfv::{self, BareItem, List, ListMember, Parameters};
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
+
+/// Proxy-Status パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProxyStatusError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正な中継者の識別子
+    InvalidIdentifier,
+    /// パラメーターの値が不正
+    InvalidParameter(String),
+}
+
+impl fmt::Display for ProxyStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyStatusError::InvalidFormat => write!(f, "invalid Proxy-Status header format"),
+            ProxyStatusError::InvalidIdentifier => {
+                write!(f, "invalid Proxy-Status intermediary identifier")
+            }
+            ProxyStatusError::InvalidParameter(name) => {
+                write!(f, "invalid Proxy-Status parameter: {}", name)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ProxyStatusError {}
+
+/// Proxy Error Types (RFC 9209 Section 2.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyErrorType {
+    /// `dns_timeout`
+    DnsTimeout,
+    /// `dns_error` (パラメーター: rcode, info-code)
+    DnsError,
+    /// `destination_not_found`
+    DestinationNotFound,
+    /// `destination_unavailable`
+    DestinationUnavailable,
+    /// `destination_ip_prohibited`
+    DestinationIpProhibited,
+    /// `destination_ip_unroutable`
+    DestinationIpUnroutable,
+    /// `connection_refused`
+    ConnectionRefused,
+    /// `connection_terminated`
+    ConnectionTerminated,
+    /// `connection_timeout`
+    ConnectionTimeout,
+    /// `connection_read_timeout`
+    ConnectionReadTimeout,
+    /// `connection_write_timeout`
+    ConnectionWriteTimeout,
+    /// `connection_limit_reached`
+    ConnectionLimitReached,
+    /// `tls_protocol_error`
+    TlsProtocolError,
+    /// `tls_certificate_error`
+    TlsCertificateError,
+    /// `tls_alert_received` (パラメーター: alert-id, alert-message)
+    TlsAlertReceived,
+    /// `http_request_error` (パラメーター: status-code, status-phrase)
+    HttpRequestError,
+    /// `http_request_denied`
+    HttpRequestDenied,
+    /// `http_response_incomplete`
+    HttpResponseIncomplete,
+    /// `http_response_header_section_size` (パラメーター: header-section-size)
+    HttpResponseHeaderSectionSize,
+    /// `http_response_header_size` (パラメーター: header-name)
+    HttpResponseHeaderSize,
+    /// `http_response_body_size` (パラメーター: body-size)
+    HttpResponseBodySize,
+    /// `http_response_trailer_section_size` (パラメーター: trailer-section-size)
+    HttpResponseTrailerSectionSize,
+    /// `http_response_trailer_size` (パラメーター: trailer-name)
+    HttpResponseTrailerSize,
+    /// `http_response_transfer_coding` (パラメーター: coding)
+    HttpResponseTransferCoding,
+    /// `http_response_content_coding` (パラメーター: coding)
+    HttpResponseContentCoding,
+    /// `http_response_timeout`
+    HttpResponseTimeout,
+    /// `http_upgrade_failed`
+    HttpUpgradeFailed,
+    /// `http_protocol_error`
+    HttpProtocolError,
+    /// `proxy_internal_response`
+    ProxyInternalResponse,
+    /// `proxy_internal_error`
+    ProxyInternalError,
+    /// `proxy_configuration_error`
+    ProxyConfigurationError,
+    /// `proxy_loop_detected`
+    ProxyLoopDetected,
+    /// レジストリにないエラーの種類
+    Other(String),
+}
+
+/// (エラーの種類, 名前, 推奨ステータスコード)
+const ERROR_TYPES: &[(ProxyErrorType, &str, Option<StatusCode>)] = &[
+    (
+        ProxyErrorType::DnsTimeout,
+        "dns_timeout",
+        Some(StatusCode::GATEWAY_TIMEOUT),
+    ),
+    (
+        ProxyErrorType::DnsError,
+        "dns_error",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::DestinationNotFound,
+        "destination_not_found",
+        Some(StatusCode::INTERNAL_SERVER_ERROR),
+    ),
+    (
+        ProxyErrorType::DestinationUnavailable,
+        "destination_unavailable",
+        Some(StatusCode::SERVICE_UNAVAILABLE),
+    ),
+    (
+        ProxyErrorType::DestinationIpProhibited,
+        "destination_ip_prohibited",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::DestinationIpUnroutable,
+        "destination_ip_unroutable",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::ConnectionRefused,
+        "connection_refused",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::ConnectionTerminated,
+        "connection_terminated",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::ConnectionTimeout,
+        "connection_timeout",
+        Some(StatusCode::GATEWAY_TIMEOUT),
+    ),
+    (
+        ProxyErrorType::ConnectionReadTimeout,
+        "connection_read_timeout",
+        Some(StatusCode::GATEWAY_TIMEOUT),
+    ),
+    (
+        ProxyErrorType::ConnectionWriteTimeout,
+        "connection_write_timeout",
+        Some(StatusCode::GATEWAY_TIMEOUT),
+    ),
+    (
+        ProxyErrorType::ConnectionLimitReached,
+        "connection_limit_reached",
+        Some(StatusCode::SERVICE_UNAVAILABLE),
+    ),
+    (
+        ProxyErrorType::TlsProtocolError,
+        "tls_protocol_error",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::TlsCertificateError,
+        "tls_certificate_error",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::TlsAlertReceived,
+        "tls_alert_received",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpRequestError,
+        "http_request_error",
+        Some(StatusCode::BAD_REQUEST),
+    ),
+    (
+        ProxyErrorType::HttpRequestDenied,
+        "http_request_denied",
+        Some(StatusCode::FORBIDDEN),
+    ),
+    (
+        ProxyErrorType::HttpResponseIncomplete,
+        "http_response_incomplete",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseHeaderSectionSize,
+        "http_response_header_section_size",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseHeaderSize,
+        "http_response_header_size",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseBodySize,
+        "http_response_body_size",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseTrailerSectionSize,
+        "http_response_trailer_section_size",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseTrailerSize,
+        "http_response_trailer_size",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseTransferCoding,
+        "http_response_transfer_coding",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseContentCoding,
+        "http_response_content_coding",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::HttpResponseTimeout,
+        "http_response_timeout",
+        Some(StatusCode::GATEWAY_TIMEOUT),
+    ),
+    (
+        ProxyErrorType::HttpUpgradeFailed,
+        "http_upgrade_failed",
+        None,
+    ),
+    (
+        ProxyErrorType::HttpProtocolError,
+        "http_protocol_error",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+    (
+        ProxyErrorType::ProxyInternalResponse,
+        "proxy_internal_response",
+        None,
+    ),
+    (
+        ProxyErrorType::ProxyInternalError,
+        "proxy_internal_error",
+        Some(StatusCode::INTERNAL_SERVER_ERROR),
+    ),
+    (
+        ProxyErrorType::ProxyConfigurationError,
+        "proxy_configuration_error",
+        Some(StatusCode::INTERNAL_SERVER_ERROR),
+    ),
+    (
+        ProxyErrorType::ProxyLoopDetected,
+        "proxy_loop_detected",
+        Some(StatusCode::BAD_GATEWAY),
+    ),
+];
+
+impl ProxyErrorType {
+    /// 名前からエラーの種類を取得
+    ///
+    /// レジストリにない名前は `Other` になる。sf-token として不正な場合はエラーを返す。
+    pub fn from_name(name: &str) -> Result<Self, ProxyStatusError> {
+        if let Some((error, _, _)) = ERROR_TYPES.iter().find(|(_, n, _)| *n == name) {
+            return Ok(error.clone());
+        }
+        if !sfv::is_valid_token(name) {
+            return Err(ProxyStatusError::InvalidParameter("error".to_string()));
+        }
+        Ok(ProxyErrorType::Other(name.to_string()))
+    }
+
+    /// 名前
+    pub fn as_str(&self) -> &str {
+        match self {
+            ProxyErrorType::Other(name) => name,
+            error => ERROR_TYPES
+                .iter()
+                .find(|(e, _, _)| e == error)
+                .map(|(_, name, _)| *name)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// レジストリの推奨ステータスコード
+    ///
+    /// 推奨がない種類 (`http_upgrade_failed` / `proxy_internal_response`) と
+    /// `Other` は `None` を返す。
+    pub fn recommended_status(&self) -> Option<StatusCode> {
+        ERROR_TYPES
+            .iter()
+            .find(|(e, _, _)| e == self)
+            .and_then(|(_, _, status)| *status)
+    }
+}
+
+impl fmt::Display for ProxyErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 型を定めたパラメーターの key
+const KNOWN_PARAMS: &[&str] = &[
+    "error",
+    "next-hop",
+    "next-protocol",
+    "received-status",
+    "details",
+];
+
+/// Proxy-Status の中継者ごとのエントリ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyStatusEntry {
+    intermediary: String,
+    error: Option<ProxyErrorType>,
+    next_hop: Option<String>,
+    next_protocol: Option<Vec<u8>>,
+    received_status: Option<u16>,
+    details: Option<String>,
+    params: Parameters,
+}
+
+impl ProxyStatusEntry {
+    /// 中継者の識別子を指定して作成
+    ///
+    /// 識別子は sf-token として有効な場合は sf-token、それ以外は sf-string として出力するため、
+    /// 印字可能 ASCII でなければならない。
+    pub fn new(intermediary: &str) -> Result<Self, ProxyStatusError> {
+        if intermediary.is_empty() || !sfv::is_valid_string(intermediary) {
+            return Err(ProxyStatusError::InvalidIdentifier);
+        }
+        Ok(ProxyStatusEntry {
+            intermediary: intermediary.to_string(),
+            error: None,
+            next_hop: None,
+            next_protocol: None,
+            received_status: None,
+            details: None,
+            params: Parameters::new(),
+        })
+    }
+
+    /// エラーの種類を設定 (ビルダーパターン)
+    pub fn with_error(mut self, error: ProxyErrorType) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    /// 次のホップ (上流のホスト名、IP アドレス、エイリアス) を設定 (ビルダーパターン)
+    pub fn with_next_hop(mut self, next_hop: &str) -> Result<Self, ProxyStatusError> {
+        if !sfv::is_valid_string(next_hop) {
+            return Err(ProxyStatusError::InvalidParameter("next-hop".to_string()));
+        }
+        self.next_hop = Some(next_hop.to_string());
+        Ok(self)
+    }
+
+    /// 次のホップとの通信に使った ALPN プロトコル ID を設定 (ビルダーパターン)
+    pub fn with_next_protocol(mut self, protocol: impl Into<Vec<u8>>) -> Self {
+        self.next_protocol = Some(protocol.into());
+        self
+    }
+
+    /// 次のホップから受信したステータスコードを設定 (ビルダーパターン)
+    pub fn with_received_status(mut self, status: u16) -> Self {
+        self.received_status = Some(status);
+        self
+    }
+
+    /// 人が読むための詳細を設定 (ビルダーパターン)
+    pub fn with_details(mut self, details: &str) -> Result<Self, ProxyStatusError> {
+        if !sfv::is_valid_string(details) {
+            return Err(ProxyStatusError::InvalidParameter("details".to_string()));
+        }
+        self.details = Some(details.to_string());
+        Ok(self)
+    }
+
+    /// エラーの種類ごとのパラメーター (rcode, alert-id 等) や拡張パラメーターを追加 (ビルダーパターン)
+    ///
+    /// error / next-hop / next-protocol / received-status / details は専用のメソッドで設定する。
+    pub fn with_param(mut self, key: &str, value: BareItem) -> Result<Self, ProxyStatusError> {
+        if KNOWN_PARAMS.contains(&key) {
+            return Err(ProxyStatusError::InvalidParameter(key.to_string()));
+        }
+        self.params
+            .insert(key, value)
+            .map_err(|_| ProxyStatusError::InvalidParameter(key.to_string()))?;
+        Ok(self)
+    }
+
+    /// 中継者の識別子
+    pub fn intermediary(&self) -> &str {
+        &self.intermediary
+    }
+
+    /// エラーの種類
+    pub fn error(&self) -> Option<&ProxyErrorType> {
+        self.error.as_ref()
+    }
+
+    /// 次のホップ
+    pub fn next_hop(&self) -> Option<&str> {
+        self.next_hop.as_deref()
+    }
+
+    /// 次のホップとの通信に使った ALPN プロトコル ID
+    pub fn next_protocol(&self) -> Option<&[u8]> {
+        self.next_protocol.as_deref()
+    }
+
+    /// 次のホップから受信したステータスコード
+    pub fn received_status(&self) -> Option<u16> {
+        self.received_status
+    }
+
+    /// 詳細
+    pub fn details(&self) -> Option<&str> {
+        self.details.as_deref()
+    }
+
+    /// エラーの種類ごとのパラメーターと拡張パラメーター
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    fn from_member(member: &ListMember) -> Result<Self, ProxyStatusError> {
+        let item = member.as_item().ok_or(ProxyStatusError::InvalidFormat)?;
+        let intermediary = match item.bare_item() {
+            BareItem::String(value) | BareItem::Token(value) if !value.is_empty() => value,
+            _ => return Err(ProxyStatusError::InvalidIdentifier),
+        };
+        let mut entry = ProxyStatusEntry::new(intermediary)?;
+        for (key, value) in item.params().iter() {
+            let invalid = || ProxyStatusError::InvalidParameter(key.to_string());
+            match (key, value) {
+                ("error", BareItem::Token(value)) => {
+                    entry.error = Some(ProxyErrorType::from_name(value)?);
+                }
+                ("next-hop", BareItem::String(value) | BareItem::Token(value)) => {
+                    entry.next_hop = Some(value.clone());
+                }
+                ("next-protocol", BareItem::ByteSequence(value)) => {
+                    entry.next_protocol = Some(value.clone());
+                }
+                ("received-status", BareItem::Integer(value)) => {
+                    entry.received_status = Some(u16::try_from(*value).map_err(|_| invalid())?);
+                }
+                ("details", BareItem::String(value)) => entry.details = Some(value.clone()),
+                (key, _) if KNOWN_PARAMS.contains(&key) => return Err(invalid()),
+                (key, value) => entry
+                    .params
+                    .insert_unchecked(key.to_string(), value.clone()),
+            }
+        }
+        Ok(entry)
+    }
+}
+
+impl fmt::Display for ProxyStatusEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_token_or_string(f, &self.intermediary)?;
+        if let Some(error) = &self.error {
+            write!(f, ";error={}", error)?;
+        }
+        if let Some(next_hop) = &self.next_hop {
+            write!(f, ";next-hop=")?;
+            sfv::write_string(f, next_hop)?;
+        }
+        if let Some(protocol) = &self.next_protocol {
+            write!(f, ";next-protocol=")?;
+            sfv::write_byte_sequence(f, protocol)?;
+        }
+        if let Some(status) = self.received_status {
+            write!(f, ";received-status={}", status)?;
+        }
+        if let Some(details) = &self.details {
+            write!(f, ";details=")?;
+            sfv::write_string(f, details)?;
+        }
+        write!(f, "{}", self.params)
+    }
+}
+
+/// Proxy-Status ヘッダー
+///
+/// エントリは上流に近い中継者から順に並ぶ。中継者は自身のエントリを末尾に追加する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyStatus {
+    entries: Vec<ProxyStatusEntry>,
+}
+
+impl ProxyStatus {
+    /// 空の Proxy-Status を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Proxy-Status をパース
+    pub fn parse(input: &str) -> Result<Self, ProxyStatusError> {
+        let entries = List::parse(input)
+            .map_err(|_| ProxyStatusError::InvalidFormat)?
+            .members()
+            .iter()
+            .map(ProxyStatusEntry::from_member)
+            .collect::<Result<_, _>>()?;
+        Ok(ProxyStatus { entries })
+    }
+
+    /// メッセージの Proxy-Status ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, ProxyStatusError> {
+        let values = message.get_headers("Proxy-Status");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// エントリを末尾に追加 (ビルダーパターン)
+    pub fn with_entry(mut self, entry: ProxyStatusEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// エントリの一覧
+    pub fn entries(&self) -> &[ProxyStatusEntry] {
+        &self.entries
+    }
+
+    /// 中継者の識別子でエントリを検索
+    pub fn find(&self, intermediary: &str) -> Option<&ProxyStatusEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.intermediary == intermediary)
+    }
+
+    /// エラーを報告した最初のエントリ
+    ///
+    /// 上流に最も近い、エラーの原因となった中継者のエントリを返す。
+    pub fn first_error(&self) -> Option<&ProxyStatusEntry> {
+        self.entries.iter().find(|entry| entry.error.is_some())
+    }
+}
+
+impl fmt::Display for ProxyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for ProxyStatus {
+    const NAME: &'static str = "Proxy-Status";
+    type Error = ProxyStatusError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Response {
+    /// Proxy-Status 付きのエラーレスポンスを作成
+    ///
+    /// ステータスコードはエラーの種類の推奨ステータスコードを使い、推奨がない場合や
+    /// エラーの種類がない場合は 502 (Bad Gateway) を使う。
+    pub fn proxy_error(entry: &ProxyStatusEntry) -> Result<Self, EncodeError> {
+        let status = entry
+            .error
+            .as_ref()
+            .and_then(ProxyErrorType::recommended_status)
+            .unwrap_or(StatusCode::BAD_GATEWAY);
+        Response::with_status(status).header(ProxyStatus::NAME, entry.to_string())
+    }
+}
+
+/// sf-token として有効な場合は sf-token、それ以外は sf-string として出力する
+fn write_token_or_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    if sfv::is_valid_token(value) {
+        f.write_str(value)
+    } else {
+        sfv::write_string(f, value)
+    }
+}
@@ -0,0 +1,324 @@
+//! multipart/byteranges (RFC 9110 Section 14.6)
+//!
+//! ## 概要
+//!
+//! 複数の範囲を要求された Range リクエストに対する 206 (Partial Content) レスポンスの
+//! ボディ `multipart/byteranges` の生成とパースを提供する。
+//!
+//! 各パートは Content-Type (任意) と Content-Range (必須) のヘッダーを持つ。
+//!
+//! ```text
+//! --THIS_STRING_SEPARATES
+//! Content-Type: application/pdf
+//! Content-Range: bytes 500-999/8000
+//!
+//! ...the first range...
+//! --THIS_STRING_SEPARATES
+//! Content-Type: application/pdf
+//! Content-Range: bytes 7000-7999/8000
+//!
+//! ...the second range
+//! --THIS_STRING_SEPARATES--
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::content_type::ContentType;
+//! use shiguredo_http11::range::{ByteRangesBuilder, ByteRangesParser, Range};
+//!
+//! let content = b"0123456789abcdefghij";
+//! let range = Range::parse("bytes=0-3, -4").unwrap();
+//!
+//! // 生成
+//! let builder = ByteRangesBuilder::with_boundary("SEPARATOR", content.len() as u64)
+//!     .with_content_type(ContentType::new("text", "plain"))
+//!     .parts_from_range(&range, content);
+//! assert_eq!(builder.content_type(), "multipart/byteranges; boundary=SEPARATOR");
+//! let body = builder.build();
+//!
+//! // パース
+//! let mut parser = ByteRangesParser::new("SEPARATOR");
+//! parser.feed(&body).unwrap();
+//! let part = parser.next_part().unwrap().unwrap();
+//! assert_eq!(part.content_range().to_string(), "bytes 0-3/20");
+//! assert_eq!(part.body(), b"0123");
+//! let part = parser.next_part().unwrap().unwrap();
+//! assert_eq!(part.content_range().to_string(), "bytes 16-19/20");
+//! assert_eq!(part.body(), b"ghij");
+//! assert!(parser.next_part().unwrap().is_none());
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::{ContentRange, Range};
+use crate::content_type::ContentType;
+use crate::multipart::{MultipartError, MultipartParser, is_valid_boundary};
+use crate::validate::is_token_char;
+
+/// multipart/byteranges のパート
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteRangePart {
+    /// Content-Type
+    content_type: Option<ContentType>,
+    /// Content-Range
+    content_range: ContentRange,
+    /// ボディ (範囲のデータ)
+    body: Vec<u8>,
+}
+
+impl ByteRangePart {
+    /// Content-Type を取得
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+
+    /// Content-Range を取得
+    pub fn content_range(&self) -> &ContentRange {
+        &self.content_range
+    }
+
+    /// 範囲の開始位置を取得
+    pub fn start(&self) -> u64 {
+        self.content_range.start().unwrap_or(0)
+    }
+
+    /// 範囲の終了位置を取得 (両端含む)
+    pub fn end(&self) -> u64 {
+        self.content_range.end().unwrap_or(0)
+    }
+
+    /// ボディを取得
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+}
+
+/// multipart/byteranges パーサー
+///
+/// [`MultipartParser`] と同じく Sans I/O で、`feed()` したデータから
+/// `next_part()` でパートを取り出す。
+#[derive(Debug, Clone)]
+pub struct ByteRangesParser {
+    inner: MultipartParser,
+}
+
+impl ByteRangesParser {
+    /// 新しいパーサーを作成
+    ///
+    /// バッファ上限は 10MB。変更する場合は `with_max_buffer_size()` を使用する。
+    pub fn new(boundary: &str) -> Self {
+        ByteRangesParser {
+            inner: MultipartParser::new(boundary).without_form_data_checks(),
+        }
+    }
+
+    /// boundary を検証して新しいパーサーを作成
+    ///
+    /// RFC 2046 Section 5.1.1 に従い、boundary 文字列を検証します。
+    pub fn try_new(boundary: &str) -> Result<Self, MultipartError> {
+        if !is_valid_boundary(boundary) {
+            return Err(MultipartError::InvalidBoundary);
+        }
+        Ok(Self::new(boundary))
+    }
+
+    /// バッファ最大サイズを設定
+    pub fn with_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.inner = self.inner.with_max_buffer_size(max_buffer_size);
+        self
+    }
+
+    /// データを追加
+    pub fn feed(&mut self, data: &[u8]) -> Result<(), MultipartError> {
+        self.inner.feed(data)
+    }
+
+    /// パースが完了したかどうか
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    /// 次のパートを取得
+    ///
+    /// Content-Range が欠落している場合は `MissingContentRange`、
+    /// 満たせない範囲 (`bytes */1000`) や範囲の長さとボディの長さが一致しない場合は
+    /// `InvalidContentRange` を返す。
+    pub fn next_part(&mut self) -> Result<Option<ByteRangePart>, MultipartError> {
+        let Some(part) = self.inner.next_part()? else {
+            return Ok(None);
+        };
+
+        let value = part
+            .headers()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Content-Range"))
+            .map(|(_, value)| value)
+            .ok_or(MultipartError::MissingContentRange)?;
+        let content_range =
+            ContentRange::parse(value).map_err(|_| MultipartError::InvalidContentRange)?;
+        if content_range.length() != Some(part.body().len() as u64) {
+            return Err(MultipartError::InvalidContentRange);
+        }
+
+        Ok(Some(ByteRangePart {
+            content_type: part.content_type().cloned(),
+            content_range,
+            body: part.body().to_vec(),
+        }))
+    }
+}
+
+/// multipart/byteranges ボディビルダー
+#[derive(Debug, Clone)]
+pub struct ByteRangesBuilder {
+    /// 境界文字列
+    boundary: String,
+    /// 各パートの Content-Type
+    content_type: Option<ContentType>,
+    /// 表現全体の長さ
+    complete_length: u64,
+    /// パート
+    parts: Vec<ByteRangePart>,
+}
+
+impl ByteRangesBuilder {
+    /// 乱数値を受け取って境界を生成する
+    ///
+    /// Sans I/O の原則に従い、乱数生成は呼び出し側の責任となる。
+    /// `complete_length` は表現全体の長さで、各パートの Content-Range に使う。
+    pub fn new(random_value: u64, complete_length: u64) -> Self {
+        Self::with_boundary(
+            &alloc::format!("----RangeBoundary{}", random_value),
+            complete_length,
+        )
+    }
+
+    /// 境界を指定して作成
+    pub fn with_boundary(boundary: &str, complete_length: u64) -> Self {
+        ByteRangesBuilder {
+            boundary: boundary.to_string(),
+            content_type: None,
+            complete_length,
+            parts: Vec::new(),
+        }
+    }
+
+    /// boundary を検証して作成
+    ///
+    /// RFC 2046 Section 5.1.1 に従い、boundary 文字列を検証します。
+    pub fn try_with_boundary(boundary: &str, complete_length: u64) -> Result<Self, MultipartError> {
+        if !is_valid_boundary(boundary) {
+            return Err(MultipartError::InvalidBoundary);
+        }
+        Ok(Self::with_boundary(boundary, complete_length))
+    }
+
+    /// 各パートの Content-Type (表現の Content-Type) を設定
+    pub fn with_content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// 境界文字列を取得
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Content-Type ヘッダー値を取得
+    ///
+    /// RFC 9110 Section 5.6.6: boundary が token に該当しない場合は quoted-string で囲む
+    pub fn content_type(&self) -> String {
+        if self.boundary.bytes().all(is_token_char) {
+            alloc::format!("multipart/byteranges; boundary={}", self.boundary)
+        } else {
+            alloc::format!("multipart/byteranges; boundary=\"{}\"", self.boundary)
+        }
+    }
+
+    /// パートの数を取得
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// パートがないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// `start` から `end` (両端含む) の範囲のデータを追加
+    ///
+    /// # Panics
+    ///
+    /// - `start > end` または `end >= complete_length` の場合 panic する
+    /// - `data` の長さが範囲の長さと一致しない場合 panic する
+    pub fn part(mut self, start: u64, end: u64, data: &[u8]) -> Self {
+        let content_range = ContentRange::new_bytes(start, end, Some(self.complete_length));
+        assert!(
+            content_range.length() == Some(data.len() as u64),
+            "ByteRangesBuilder: data length must match the range length"
+        );
+        self.parts.push(ByteRangePart {
+            content_type: self.content_type.clone(),
+            content_range,
+            body: data.to_vec(),
+        });
+        self
+    }
+
+    /// Range ヘッダーの各範囲を `content` から切り出して追加
+    ///
+    /// `content` は表現全体で、長さは `complete_length` と一致している必要がある
+    /// (一致しない場合は `part()` と同じく panic することがある)。
+    /// bytes 以外の範囲単位の場合と、満たせない範囲は無視する。
+    pub fn parts_from_range(mut self, range: &Range, content: &[u8]) -> Self {
+        if !range.unit().eq_ignore_ascii_case("bytes") {
+            return self;
+        }
+        for spec in range.ranges() {
+            if let Some((start, end)) = spec.to_bounds(content.len() as u64) {
+                self = self.part(start, end, &content[start as usize..=end as usize]);
+            }
+        }
+        self
+    }
+
+    /// ボディをビルド
+    pub fn build(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+
+        for part in &self.parts {
+            // 境界
+            result.extend_from_slice(b"--");
+            result.extend_from_slice(self.boundary.as_bytes());
+            result.extend_from_slice(b"\r\n");
+
+            // Content-Type
+            if let Some(ct) = &part.content_type {
+                result.extend_from_slice(b"Content-Type: ");
+                result.extend_from_slice(ct.to_string().as_bytes());
+                result.extend_from_slice(b"\r\n");
+            }
+
+            // Content-Range
+            result.extend_from_slice(b"Content-Range: ");
+            result.extend_from_slice(part.content_range.to_string().as_bytes());
+            result.extend_from_slice(b"\r\n");
+
+            // ヘッダー終了
+            result.extend_from_slice(b"\r\n");
+
+            // ボディ
+            result.extend_from_slice(&part.body);
+            result.extend_from_slice(b"\r\n");
+        }
+
+        // 終了境界
+        result.extend_from_slice(b"--");
+        result.extend_from_slice(self.boundary.as_bytes());
+        result.extend_from_slice(b"--\r\n");
+
+        result
+    }
+}
@@ -23,11 +23,29 @@
 //! let ar = AcceptRanges::parse("bytes").unwrap();
 //! assert!(ar.accepts_bytes());
 //! ```
+//!
+//! 表現の長さに対する評価 (満たせる範囲の計算、416 の判定、範囲の結合、
+//! Content-Range の生成) は [`Range::evaluate`] で行う。
+//! If-Range による Range を処理するかの判定は [`if_range_allows`] で行う。
+//!
+//! 複数範囲のレスポンスボディ (multipart/byteranges) は
+//! [`ByteRangesBuilder`] / [`ByteRangesParser`] で生成・パースする。
+
+mod byteranges;
+
+pub use byteranges::{ByteRangePart, ByteRangesBuilder, ByteRangesParser};
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::conditional::IfRange;
+use crate::date::HttpDate;
+use crate::decoder::HttpHead;
+use crate::etag::EntityTag;
+use crate::response::Response;
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
 use crate::validate::is_valid_token;
 
 /// Range パースエラー
@@ -44,6 +62,13 @@ pub enum RangeError {
     InvalidRange,
     /// 範囲が不正 (開始 > 終了)
     InvalidBounds,
+    /// Content-Range の範囲の長さとボディの長さが一致しない
+    LengthMismatch {
+        /// Content-Range から求めた長さ
+        expected: u64,
+        /// ボディの長さ
+        actual: u64,
+    },
 }
 
 impl fmt::Display for RangeError {
@@ -54,6 +79,11 @@ impl fmt::Display for RangeError {
             RangeError::InvalidUnit => write!(f, "invalid range unit"),
             RangeError::InvalidRange => write!(f, "invalid range specification"),
             RangeError::InvalidBounds => write!(f, "invalid range bounds"),
+            RangeError::LengthMismatch { expected, actual } => write!(
+                f,
+                "content range length mismatch: expected {}, actual {}",
+                expected, actual
+            ),
         }
     }
 }
@@ -200,6 +230,76 @@ impl Range {
     pub fn first(&self) -> Option<&RangeSpec> {
         self.ranges.first()
     }
+
+    /// 表現の長さ `complete_length` に対して満たせる範囲を計算
+    ///
+    /// 各範囲は `RangeSpec::to_bounds()` で末尾に切り詰め、suffix-range は開始位置に
+    /// 解決する。満たせない範囲は除き、順序は Range ヘッダーの指定順のままにする。
+    /// bytes 以外の範囲単位の場合は空を返す。
+    pub fn satisfiable_bounds(&self, complete_length: u64) -> Vec<(u64, u64)> {
+        if !self.is_bytes() {
+            return Vec::new();
+        }
+        self.ranges
+            .iter()
+            .filter_map(|spec| spec.to_bounds(complete_length))
+            .collect()
+    }
+
+    /// 表現の長さ `complete_length` に対して Range リクエストを評価する
+    ///
+    /// RFC 9110 Section 14.2 に従い、満たせる範囲は昇順に並べて
+    /// 重複・隣接する範囲を結合する ([`coalesce_ranges`])。
+    ///
+    /// - bytes 以外の範囲単位の場合は `Ignored` (Range を無視して 200 で全体を返す)
+    /// - 長さ 0 の表現に対して suffix-length が 0 でない suffix-range を含む場合は
+    ///   `Ignored` (RFC 9110 Section 14.1.3: 満たせるが返す範囲がないため全体を返す)
+    /// - 満たせる範囲がない場合は `Unsatisfiable` (416)
+    /// - それ以外は `Satisfiable` (206)
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::range::{Range, RangeSatisfaction};
+    ///
+    /// let range = Range::parse("bytes=0-99, 50-199, -100").unwrap();
+    /// let satisfaction = range.evaluate(1000);
+    /// assert_eq!(
+    ///     satisfaction,
+    ///     RangeSatisfaction::Satisfiable {
+    ///         ranges: vec![(0, 199), (900, 999)],
+    ///         complete_length: 1000,
+    ///     }
+    /// );
+    ///
+    /// let range = Range::parse("bytes=2000-").unwrap();
+    /// let satisfaction = range.evaluate(1000);
+    /// assert!(satisfaction.is_unsatisfiable());
+    /// assert_eq!(satisfaction.content_range().unwrap().to_string(), "bytes */1000");
+    /// ```
+    pub fn evaluate(&self, complete_length: u64) -> RangeSatisfaction {
+        if !self.is_bytes() {
+            return RangeSatisfaction::Ignored;
+        }
+        if complete_length == 0
+            && self
+                .ranges
+                .iter()
+                .any(|spec| matches!(spec, RangeSpec::Suffix { length } if *length > 0))
+        {
+            return RangeSatisfaction::Ignored;
+        }
+
+        let ranges = coalesce_ranges(&self.satisfiable_bounds(complete_length));
+        if ranges.is_empty() {
+            RangeSatisfaction::Unsatisfiable { complete_length }
+        } else {
+            RangeSatisfaction::Satisfiable {
+                ranges,
+                complete_length,
+            }
+        }
+    }
 }
 
 impl fmt::Display for Range {
@@ -210,6 +310,219 @@ impl fmt::Display for Range {
     }
 }
 
+impl TypedHeader for Range {
+    const NAME: &'static str = "Range";
+    type Error = RangeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Range リクエストの評価結果 ([`Range::evaluate`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeSatisfaction {
+    /// Range を無視して表現全体を返す (200)
+    Ignored,
+    /// 満たせる範囲がある (206)
+    Satisfiable {
+        /// 昇順に並べて重複・隣接を結合した範囲 (両端含む)
+        ranges: Vec<(u64, u64)>,
+        /// 表現の長さ
+        complete_length: u64,
+    },
+    /// 満たせる範囲がない (416)
+    Unsatisfiable {
+        /// 表現の長さ
+        complete_length: u64,
+    },
+}
+
+impl RangeSatisfaction {
+    /// 満たせる範囲があるかどうか
+    pub fn is_satisfiable(&self) -> bool {
+        matches!(self, RangeSatisfaction::Satisfiable { .. })
+    }
+
+    /// 満たせる範囲がないかどうか (416 を返すべきか)
+    pub fn is_unsatisfiable(&self) -> bool {
+        matches!(self, RangeSatisfaction::Unsatisfiable { .. })
+    }
+
+    /// 満たせる範囲を取得
+    ///
+    /// `Satisfiable` 以外では空を返す。
+    pub fn ranges(&self) -> &[(u64, u64)] {
+        match self {
+            RangeSatisfaction::Satisfiable { ranges, .. } => ranges,
+            _ => &[],
+        }
+    }
+
+    /// 範囲ごとの Content-Range を生成
+    ///
+    /// `Satisfiable` では各範囲の `bytes first-last/complete-length`、
+    /// `Unsatisfiable` では `bytes */complete-length` を返す。`Ignored` では空を返す。
+    /// 複数範囲の場合は multipart/byteranges の各パートに付与する。
+    pub fn content_ranges(&self) -> Vec<ContentRange> {
+        match self {
+            RangeSatisfaction::Ignored => Vec::new(),
+            RangeSatisfaction::Satisfiable {
+                ranges,
+                complete_length,
+            } => ranges
+                .iter()
+                .map(|&(start, end)| ContentRange::new_bytes(start, end, Some(*complete_length)))
+                .collect(),
+            RangeSatisfaction::Unsatisfiable { complete_length } => {
+                alloc::vec![ContentRange::unsatisfied("bytes", *complete_length)]
+            }
+        }
+    }
+
+    /// レスポンスヘッダーに付与する Content-Range を生成
+    ///
+    /// 単一範囲の 206 と 416 で返す Content-Range を返す。
+    /// `Ignored` と複数範囲 (multipart/byteranges) の場合は None を返す。
+    pub fn content_range(&self) -> Option<ContentRange> {
+        match self {
+            RangeSatisfaction::Satisfiable { ranges, .. } if ranges.len() != 1 => None,
+            _ => self.content_ranges().into_iter().next(),
+        }
+    }
+
+    /// 満たせる範囲がない場合の 416 レスポンスを作成
+    ///
+    /// `Unsatisfiable` では [`Response::range_not_satisfiable`] を返し、
+    /// それ以外では None を返す。
+    pub fn to_unsatisfiable_response(&self) -> Option<Response> {
+        match self {
+            RangeSatisfaction::Unsatisfiable { complete_length } => {
+                Some(Response::range_not_satisfiable(*complete_length))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Response {
+    /// Accept-Ranges ヘッダーを設定 (ビルダーパターン)
+    ///
+    /// 既存の Accept-Ranges は置き換える。
+    /// [`StaticResource`](crate::static_content::StaticResource) は
+    /// `AcceptRanges::bytes()` を付与する。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::{Response, StatusCode};
+    /// use shiguredo_http11::range::AcceptRanges;
+    ///
+    /// let response = Response::with_status(StatusCode::OK).accept_ranges(&AcceptRanges::none());
+    /// assert_eq!(response.get_header("Accept-Ranges"), Some("none"));
+    ///
+    /// let accept_ranges = AcceptRanges::from_message(&response).unwrap().unwrap();
+    /// assert!(!accept_ranges.accepts_bytes());
+    /// ```
+    pub fn accept_ranges(mut self, accept_ranges: &AcceptRanges) -> Self {
+        self.replace_valid_header(AcceptRanges::NAME, &accept_ranges.to_string());
+        self
+    }
+
+    /// 416 (Range Not Satisfiable) レスポンスを作成
+    ///
+    /// RFC 9110 Section 15.5.17: 416 では `Content-Range: bytes */complete-length`
+    /// で表現の長さを伝えるべき (SHOULD)。ボディは空 (`Content-Length: 0`)。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::Response;
+    /// use shiguredo_http11::range::Range;
+    ///
+    /// let satisfaction = Range::parse("bytes=2000-").unwrap().evaluate(1000);
+    /// let response = satisfaction.to_unsatisfiable_response().unwrap();
+    /// assert_eq!(response, Response::range_not_satisfiable(1000));
+    /// assert_eq!(response.status_code(), 416);
+    /// assert_eq!(response.get_header("Content-Range"), Some("bytes */1000"));
+    /// ```
+    pub fn range_not_satisfiable(complete_length: u64) -> Self {
+        let mut response = Response::with_status(StatusCode::RANGE_NOT_SATISFIABLE);
+        response.replace_valid_header(
+            ContentRange::NAME,
+            &ContentRange::unsatisfied("bytes", complete_length).to_string(),
+        );
+        response.body(Vec::new())
+    }
+}
+
+/// 範囲を昇順に並べ、重複・隣接する範囲を結合する
+///
+/// RFC 9110 Section 14.2: サーバーは重複する範囲や小さな隙間で隔てられた範囲を
+/// 結合してよい。本関数は重複する範囲と隣接する範囲 (`0-9` と `10-19`) のみを結合する。
+/// `start > end` の範囲は除く。
+pub fn coalesce_ranges(ranges: &[(u64, u64)]) -> Vec<(u64, u64)> {
+    let mut sorted: Vec<(u64, u64)> = ranges
+        .iter()
+        .copied()
+        .filter(|(start, end)| start <= end)
+        .collect();
+    sorted.sort_unstable();
+
+    let mut result: Vec<(u64, u64)> = Vec::with_capacity(sorted.len());
+    for (start, end) in sorted {
+        if let Some(last) = result.last_mut()
+            && start <= last.1.saturating_add(1)
+        {
+            last.1 = last.1.max(end);
+            continue;
+        }
+        result.push((start, end));
+    }
+    result
+}
+
+/// If-Range のフィールド値に従って Range を処理するか (RFC 9110 Section 13.1.5)
+///
+/// `current_etag` は現在の表現の ETag、`last_modified` は現在の表現の Last-Modified で、
+/// ない場合は `None` を渡す。`last_modified` は強いバリデーターとして扱える場合
+/// (RFC 9110 Section 8.8.2.2) のみ渡すこと。
+///
+/// - entity-tag の場合は `current_etag` と Strong 比較で一致すると true
+/// - HTTP-date の場合は `last_modified` と完全に一致すると true
+/// - フィールド値が不正な場合は false
+///
+/// false の場合は Range を無視して 200 で表現全体を返す。
+/// Range ヘッダーのないリクエストの If-Range は呼び出し側で無視すること。
+///
+/// ```rust
+/// use shiguredo_http11::date::HttpDate;
+/// use shiguredo_http11::etag::EntityTag;
+/// use shiguredo_http11::range::if_range_allows;
+///
+/// let etag = EntityTag::strong("v1").unwrap();
+/// let modified = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+///
+/// assert!(if_range_allows("\"v1\"", Some(&etag), Some(&modified)));
+/// assert!(!if_range_allows("W/\"v1\"", Some(&etag), Some(&modified)));
+/// assert!(if_range_allows("Sun, 06 Nov 1994 08:49:37 GMT", Some(&etag), Some(&modified)));
+/// assert!(!if_range_allows("Sun, 06 Nov 1994 08:49:38 GMT", Some(&etag), Some(&modified)));
+/// ```
+pub fn if_range_allows(
+    if_range_value: &str,
+    current_etag: Option<&EntityTag>,
+    last_modified: Option<&HttpDate>,
+) -> bool {
+    // 完全一致のみを認めるため、RFC 850 形式の 2 桁年は Last-Modified の年を基準に解決する
+    let reference_year = last_modified.map(|date| date.year()).unwrap_or(9999);
+    IfRange::parse(if_range_value, reference_year)
+        .is_ok_and(|if_range| if_range.matches(current_etag, last_modified))
+}
+
 /// 範囲指定をパース
 fn parse_range_spec(s: &str) -> Result<RangeSpec, RangeError> {
     let dash_pos = s.find('-').ok_or(RangeError::InvalidRange)?;
@@ -420,6 +733,62 @@ impl ContentRange {
     pub fn is_unsatisfied(&self) -> bool {
         self.start.is_none() && self.end.is_none()
     }
+
+    /// メッセージの Content-Range ヘッダーを取得
+    ///
+    /// 部分アップロードを行う PUT / PATCH のリクエストから取得する場合に使う。
+    /// ヘッダーがない場合は None を返す。Content-Range はリストではないため、
+    /// 複数行ある場合は `RangeError::InvalidFormat` を返す。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, RangeError> {
+        match message.get_headers(Self::NAME).as_slice() {
+            [] => Ok(None),
+            [value] => Self::parse(value).map(Some),
+            _ => Err(RangeError::InvalidFormat),
+        }
+    }
+
+    /// 部分アップロードのリクエストの Content-Range をボディの長さに対して検証する
+    ///
+    /// - 範囲単位は bytes のみ受け付ける (`RangeError::InvalidUnit`)
+    /// - `bytes first-last/complete-length` では範囲の長さがボディの長さと一致すること
+    /// - `bytes */complete-length` では完全な長さのみを伝えるため、ボディが空であること
+    ///
+    /// 開始位置 ≦ 終了位置 < 完全な長さはパース時に検証済み。
+    /// 開始位置が受信済みの長さと一致するかは呼び出し側が確認する。
+    ///
+    /// RFC 9110 Section 14.5: 部分 PUT に対応しないサーバーは、Content-Range を含む
+    /// PUT に 400 (Bad Request) を返さなければならない。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::range::{ContentRange, RangeError};
+    ///
+    /// let content_range = ContentRange::parse("bytes 100-199/1000").unwrap();
+    /// assert_eq!(content_range.validate_upload(100), Ok(()));
+    /// assert_eq!(
+    ///     content_range.validate_upload(99),
+    ///     Err(RangeError::LengthMismatch { expected: 100, actual: 99 })
+    /// );
+    /// ```
+    pub fn validate_upload(&self, body_length: u64) -> Result<(), RangeError> {
+        if !self.unit.eq_ignore_ascii_case("bytes") {
+            return Err(RangeError::InvalidUnit);
+        }
+        let expected = if self.is_unsatisfied() {
+            0
+        } else {
+            // 範囲の長さが u64 に収まらない場合はボディで表現できない
+            self.length().ok_or(RangeError::InvalidBounds)?
+        };
+        if expected != body_length {
+            return Err(RangeError::LengthMismatch {
+                expected,
+                actual: body_length,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Display for ContentRange {
@@ -439,6 +808,19 @@ impl fmt::Display for ContentRange {
     }
 }
 
+impl TypedHeader for ContentRange {
+    const NAME: &'static str = "Content-Range";
+    type Error = RangeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Accept-Ranges ヘッダー (RFC 9110 Section 14.3)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AcceptRanges {
@@ -495,14 +877,35 @@ impl AcceptRanges {
         }
     }
 
+    /// メッセージの Accept-Ranges ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。ヘッダーがない場合は None を返す。
+    /// RFC 9110 Section 14.3: Accept-Ranges がなくてもクライアントは
+    /// Range リクエストを送ってよい。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, RangeError> {
+        let values = message.get_headers(Self::NAME);
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
     /// 単位のリストを取得
     pub fn units(&self) -> &[String] {
         &self.units
     }
 
+    /// 範囲単位を受け入れるかどうか (大文字小文字を区別しない)
+    ///
+    /// `none` は範囲単位ではないため常に false を返す。
+    pub fn accepts(&self, unit: &str) -> bool {
+        !unit.eq_ignore_ascii_case("none")
+            && self.units.iter().any(|u| u.eq_ignore_ascii_case(unit))
+    }
+
     /// bytes を受け入れるかどうか
     pub fn accepts_bytes(&self) -> bool {
-        self.units.iter().any(|u| u.eq_ignore_ascii_case("bytes"))
+        self.accepts("bytes")
     }
 
     /// 何も受け入れないかどうか
@@ -517,6 +920,19 @@ impl fmt::Display for AcceptRanges {
     }
 }
 
+impl TypedHeader for AcceptRanges {
+    const NAME: &'static str = "Accept-Ranges";
+    type Error = RangeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,754 @@
+//! RateLimit / RateLimit-Policy ヘッダー (draft-ietf-httpapi-ratelimit-headers)
+//!
+//! ## 概要
+//!
+//! サーバーがクライアントにクォータの状態を伝える RateLimit / RateLimit-Policy ヘッダーの
+//! パースと生成を提供します。広く使われている X-RateLimit-Limit / X-RateLimit-Remaining /
+//! X-RateLimit-Reset にも対応します。
+//!
+//! - RateLimit-Policy: クォータポリシー (名前、クォータ、単位、ウィンドウ、パーティションキー)
+//! - RateLimit: ポリシーごとの残りクォータとリセットまでの秒数
+//! - `RateLimitBuilder`: クォータエンジンの状態からヘッダーを生成し、
+//!   429 (Too Many Requests) に Retry-After を付ける
+//!
+//! ## ABNF
+//!
+//! ```text
+//! RateLimit-Policy = sf-list   ; 各要素は sf-string (ポリシー名) とパラメーター q / qu / w / pk
+//! RateLimit        = sf-list   ; 各要素は sf-string (ポリシー名) とパラメーター r / t / pk
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::rate_limit::{QuotaPolicy, RateLimit, RateLimitBuilder, RateLimitPolicy};
+//! use shiguredo_http11::Response;
+//!
+//! // サーバー: クォータエンジンの状態からヘッダーを生成
+//! let builder = RateLimitBuilder::new()
+//!     .with_quota(QuotaPolicy::new("default", 100).unwrap().with_window(60), 0, 42)
+//!     .with_legacy_headers();
+//! let response = Response::too_many_requests(&builder).unwrap();
+//! assert_eq!(response.status_code(), 429);
+//! assert_eq!(response.get_header("RateLimit-Policy"), Some("\"default\";q=100;w=60"));
+//! assert_eq!(response.get_header("RateLimit"), Some("\"default\";r=0;t=42"));
+//! assert_eq!(response.get_header("Retry-After"), Some("42"));
+//! assert_eq!(response.get_header("X-RateLimit-Remaining"), Some("0"));
+//!
+//! // クライアント: ヘッダーをパース
+//! let limit = RateLimit::from_message(&response).unwrap().unwrap();
+//! assert_eq!(limit.find("default").unwrap().remaining(), 0);
+//! let policy = RateLimitPolicy::from_message(&response).unwrap().unwrap();
+//! assert_eq!(policy.find("default").unwrap().quota(), 100);
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::retry_after::RetryAfter;
+use crate::sfv::{self, BareItem, List, ListMember};
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
+use crate::validate::trim_ows;
+
+/// RateLimit パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RateLimitError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正なポリシー名
+    InvalidName,
+    /// RateLimit-Policy に q パラメーターがない
+    MissingQuota,
+    /// RateLimit に r パラメーターがない
+    MissingRemaining,
+    /// パラメーターの値が不正
+    InvalidParameter(String),
+    /// 不正な X-RateLimit-* ヘッダー
+    InvalidLegacyHeader(&'static str),
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitError::InvalidFormat => write!(f, "invalid RateLimit header format"),
+            RateLimitError::InvalidName => write!(f, "invalid RateLimit policy name"),
+            RateLimitError::MissingQuota => write!(f, "missing quota in RateLimit-Policy"),
+            RateLimitError::MissingRemaining => write!(f, "missing remaining in RateLimit"),
+            RateLimitError::InvalidParameter(name) => {
+                write!(f, "invalid RateLimit parameter: {}", name)
+            }
+            RateLimitError::InvalidLegacyHeader(name) => write!(f, "invalid {} header", name),
+        }
+    }
+}
+
+impl core::error::Error for RateLimitError {}
+
+/// クォータの単位 (qu パラメーター)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotaUnit {
+    /// `requests` (デフォルト)
+    Requests,
+    /// `content-bytes`
+    ContentBytes,
+    /// `concurrent-requests`
+    ConcurrentRequests,
+    /// 拡張の単位
+    Other(String),
+}
+
+impl QuotaUnit {
+    fn from_value(value: String) -> Self {
+        match value.as_str() {
+            "requests" => QuotaUnit::Requests,
+            "content-bytes" => QuotaUnit::ContentBytes,
+            "concurrent-requests" => QuotaUnit::ConcurrentRequests,
+            _ => QuotaUnit::Other(value),
+        }
+    }
+
+    /// 単位名
+    pub fn as_str(&self) -> &str {
+        match self {
+            QuotaUnit::Requests => "requests",
+            QuotaUnit::ContentBytes => "content-bytes",
+            QuotaUnit::ConcurrentRequests => "concurrent-requests",
+            QuotaUnit::Other(value) => value,
+        }
+    }
+}
+
+/// RateLimit-Policy のクォータポリシー
+///
+/// sf-integer の上限 (999,999,999,999,999) を超える値は上限に丸めて出力する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaPolicy {
+    name: String,
+    quota: u64,
+    unit: QuotaUnit,
+    window: Option<u64>,
+    partition_key: Option<Vec<u8>>,
+}
+
+impl QuotaPolicy {
+    /// ポリシー名とクォータを指定して作成
+    ///
+    /// ポリシー名は sf-string に格納できる印字可能 ASCII でなければならない。
+    pub fn new(name: &str, quota: u64) -> Result<Self, RateLimitError> {
+        validate_name(name)?;
+        Ok(QuotaPolicy {
+            name: name.to_string(),
+            quota,
+            unit: QuotaUnit::Requests,
+            window: None,
+            partition_key: None,
+        })
+    }
+
+    /// クォータの単位を設定 (ビルダーパターン)
+    pub fn with_unit(mut self, unit: QuotaUnit) -> Result<Self, RateLimitError> {
+        if let QuotaUnit::Other(value) = &unit
+            && !sfv::is_valid_string(value)
+        {
+            return Err(RateLimitError::InvalidParameter("qu".to_string()));
+        }
+        self.unit = unit;
+        Ok(self)
+    }
+
+    /// クォータのウィンドウ (秒) を設定 (ビルダーパターン)
+    pub fn with_window(mut self, seconds: u64) -> Self {
+        self.window = Some(seconds);
+        self
+    }
+
+    /// パーティションキーを設定 (ビルダーパターン)
+    pub fn with_partition_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.partition_key = Some(key.into());
+        self
+    }
+
+    /// ポリシー名
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// クォータ
+    pub fn quota(&self) -> u64 {
+        self.quota
+    }
+
+    /// クォータの単位
+    pub fn unit(&self) -> &QuotaUnit {
+        &self.unit
+    }
+
+    /// クォータのウィンドウ (秒)
+    pub fn window(&self) -> Option<u64> {
+        self.window
+    }
+
+    /// パーティションキー
+    pub fn partition_key(&self) -> Option<&[u8]> {
+        self.partition_key.as_deref()
+    }
+
+    fn from_member(member: &ListMember) -> Result<Self, RateLimitError> {
+        let (name, params) = member_name(member)?;
+        let mut quota = None;
+        let mut unit = QuotaUnit::Requests;
+        let mut window = None;
+        let mut partition_key = None;
+        for (key, value) in params.iter() {
+            match (key, value) {
+                ("q", value) => quota = Some(non_negative(key, value)?),
+                ("qu", BareItem::String(value)) => unit = QuotaUnit::from_value(value.clone()),
+                ("w", value) => window = Some(non_negative(key, value)?),
+                ("pk", BareItem::ByteSequence(value)) => partition_key = Some(value.clone()),
+                ("qu" | "pk", _) => return Err(RateLimitError::InvalidParameter(key.to_string())),
+                // 未知のパラメーターは無視する
+                _ => {}
+            }
+        }
+        Ok(QuotaPolicy {
+            name,
+            quota: quota.ok_or(RateLimitError::MissingQuota)?,
+            unit,
+            window,
+            partition_key,
+        })
+    }
+}
+
+impl fmt::Display for QuotaPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        sfv::write_string(f, &self.name)?;
+        write!(f, ";q={}", self.quota.min(sfv::MAX_INTEGER as u64))?;
+        if self.unit != QuotaUnit::Requests {
+            write!(f, ";qu=")?;
+            sfv::write_string(f, self.unit.as_str())?;
+        }
+        if let Some(window) = self.window {
+            write!(f, ";w={}", window.min(sfv::MAX_INTEGER as u64))?;
+        }
+        if let Some(key) = &self.partition_key {
+            write!(f, ";pk=")?;
+            sfv::write_byte_sequence(f, key)?;
+        }
+        Ok(())
+    }
+}
+
+/// RateLimit-Policy ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+    policies: Vec<QuotaPolicy>,
+}
+
+impl RateLimitPolicy {
+    /// 空の RateLimit-Policy を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// RateLimit-Policy をパース
+    pub fn parse(input: &str) -> Result<Self, RateLimitError> {
+        let policies = parse_list(input)?
+            .members()
+            .iter()
+            .map(QuotaPolicy::from_member)
+            .collect::<Result<_, _>>()?;
+        Ok(RateLimitPolicy { policies })
+    }
+
+    /// メッセージの RateLimit-Policy ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, RateLimitError> {
+        let values = message.get_headers("RateLimit-Policy");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// ポリシーを追加 (ビルダーパターン)
+    pub fn with_policy(mut self, policy: QuotaPolicy) -> Self {
+        self.policies.push(policy);
+        self
+    }
+
+    /// ポリシーの一覧
+    pub fn policies(&self) -> &[QuotaPolicy] {
+        &self.policies
+    }
+
+    /// 名前でポリシーを検索
+    pub fn find(&self, name: &str) -> Option<&QuotaPolicy> {
+        self.policies.iter().find(|policy| policy.name == name)
+    }
+}
+
+impl fmt::Display for RateLimitPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, policy) in self.policies.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", policy)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for RateLimitPolicy {
+    const NAME: &'static str = "RateLimit-Policy";
+    type Error = RateLimitError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// RateLimit のポリシーごとの状態
+///
+/// sf-integer の上限 (999,999,999,999,999) を超える値は上限に丸めて出力する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceLimit {
+    name: String,
+    remaining: u64,
+    reset: Option<u64>,
+    partition_key: Option<Vec<u8>>,
+}
+
+impl ServiceLimit {
+    /// ポリシー名と残りクォータを指定して作成
+    pub fn new(name: &str, remaining: u64) -> Result<Self, RateLimitError> {
+        validate_name(name)?;
+        Ok(ServiceLimit {
+            name: name.to_string(),
+            remaining,
+            reset: None,
+            partition_key: None,
+        })
+    }
+
+    /// クォータがリセットされるまでの秒数を設定 (ビルダーパターン)
+    pub fn with_reset(mut self, seconds: u64) -> Self {
+        self.reset = Some(seconds);
+        self
+    }
+
+    /// パーティションキーを設定 (ビルダーパターン)
+    pub fn with_partition_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        self.partition_key = Some(key.into());
+        self
+    }
+
+    /// ポリシー名
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 残りクォータ
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// クォータがリセットされるまでの秒数
+    pub fn reset(&self) -> Option<u64> {
+        self.reset
+    }
+
+    /// パーティションキー
+    pub fn partition_key(&self) -> Option<&[u8]> {
+        self.partition_key.as_deref()
+    }
+
+    fn from_member(member: &ListMember) -> Result<Self, RateLimitError> {
+        let (name, params) = member_name(member)?;
+        let mut remaining = None;
+        let mut reset = None;
+        let mut partition_key = None;
+        for (key, value) in params.iter() {
+            match (key, value) {
+                ("r", value) => remaining = Some(non_negative(key, value)?),
+                ("t", value) => reset = Some(non_negative(key, value)?),
+                ("pk", BareItem::ByteSequence(value)) => partition_key = Some(value.clone()),
+                ("pk", _) => return Err(RateLimitError::InvalidParameter(key.to_string())),
+                // 未知のパラメーターは無視する
+                _ => {}
+            }
+        }
+        Ok(ServiceLimit {
+            name,
+            remaining: remaining.ok_or(RateLimitError::MissingRemaining)?,
+            reset,
+            partition_key,
+        })
+    }
+}
+
+impl fmt::Display for ServiceLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        sfv::write_string(f, &self.name)?;
+        write!(f, ";r={}", self.remaining.min(sfv::MAX_INTEGER as u64))?;
+        if let Some(reset) = self.reset {
+            write!(f, ";t={}", reset.min(sfv::MAX_INTEGER as u64))?;
+        }
+        if let Some(key) = &self.partition_key {
+            write!(f, ";pk=")?;
+            sfv::write_byte_sequence(f, key)?;
+        }
+        Ok(())
+    }
+}
+
+/// RateLimit ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    limits: Vec<ServiceLimit>,
+}
+
+impl RateLimit {
+    /// 空の RateLimit を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// RateLimit をパース
+    pub fn parse(input: &str) -> Result<Self, RateLimitError> {
+        let limits = parse_list(input)?
+            .members()
+            .iter()
+            .map(ServiceLimit::from_member)
+            .collect::<Result<_, _>>()?;
+        Ok(RateLimit { limits })
+    }
+
+    /// メッセージの RateLimit ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, RateLimitError> {
+        let values = message.get_headers("RateLimit");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// ポリシーの状態を追加 (ビルダーパターン)
+    pub fn with_limit(mut self, limit: ServiceLimit) -> Self {
+        self.limits.push(limit);
+        self
+    }
+
+    /// ポリシーの状態の一覧
+    pub fn limits(&self) -> &[ServiceLimit] {
+        &self.limits
+    }
+
+    /// 名前でポリシーの状態を検索
+    pub fn find(&self, name: &str) -> Option<&ServiceLimit> {
+        self.limits.iter().find(|limit| limit.name == name)
+    }
+
+    /// 残りクォータが 0 のポリシーがあるか
+    pub fn is_exhausted(&self) -> bool {
+        self.limits.iter().any(|limit| limit.remaining == 0)
+    }
+
+    /// 残りクォータが 0 のポリシーがすべてリセットされるまでの秒数
+    ///
+    /// 残りクォータが 0 のポリシーがない場合や、リセットまでの秒数がわからない場合は `None` を返す。
+    pub fn retry_after(&self) -> Option<RetryAfter> {
+        self.limits
+            .iter()
+            .filter(|limit| limit.remaining == 0)
+            .map(|limit| limit.reset)
+            .try_fold(None, |max: Option<u64>, reset| {
+                reset.map(|reset| Some(max.map_or(reset, |max| max.max(reset))))
+            })
+            .flatten()
+            .map(RetryAfter::Delay)
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, limit) in self.limits.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", limit)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for RateLimit {
+    const NAME: &'static str = "RateLimit";
+    type Error = RateLimitError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// X-RateLimit-Limit / X-RateLimit-Remaining / X-RateLimit-Reset
+///
+/// 標準化されていないヘッダーで、X-RateLimit-Reset はサービスによって
+/// リセットまでの秒数の場合と UNIX 時間の場合がある。本実装は値をそのまま保持し、
+/// 生成時はリセットまでの秒数を出力する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegacyRateLimit {
+    limit: Option<u64>,
+    remaining: Option<u64>,
+    reset: Option<u64>,
+}
+
+impl LegacyRateLimit {
+    /// 空の X-RateLimit-* を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// X-RateLimit-Limit を設定 (ビルダーパターン)
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// X-RateLimit-Remaining を設定 (ビルダーパターン)
+    pub fn with_remaining(mut self, remaining: u64) -> Self {
+        self.remaining = Some(remaining);
+        self
+    }
+
+    /// X-RateLimit-Reset を設定 (ビルダーパターン)
+    pub fn with_reset(mut self, reset: u64) -> Self {
+        self.reset = Some(reset);
+        self
+    }
+
+    /// メッセージの X-RateLimit-* ヘッダーを取得
+    ///
+    /// いずれのヘッダーもない場合は `None` を返す。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, RateLimitError> {
+        let legacy = LegacyRateLimit {
+            limit: legacy_value(message, "X-RateLimit-Limit")?,
+            remaining: legacy_value(message, "X-RateLimit-Remaining")?,
+            reset: legacy_value(message, "X-RateLimit-Reset")?,
+        };
+        if legacy == LegacyRateLimit::default() {
+            return Ok(None);
+        }
+        Ok(Some(legacy))
+    }
+
+    /// X-RateLimit-Limit
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// X-RateLimit-Remaining
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining
+    }
+
+    /// X-RateLimit-Reset
+    pub fn reset(&self) -> Option<u64> {
+        self.reset
+    }
+
+    /// ヘッダーの名前と値の一覧
+    pub fn headers(&self) -> Vec<(String, String)> {
+        [
+            ("X-RateLimit-Limit", self.limit),
+            ("X-RateLimit-Remaining", self.remaining),
+            ("X-RateLimit-Reset", self.reset),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name.to_string(), value.to_string())))
+        .collect()
+    }
+}
+
+fn legacy_value<H: HttpHead>(
+    message: &H,
+    name: &'static str,
+) -> Result<Option<u64>, RateLimitError> {
+    match message.get_headers(name).as_slice() {
+        [] => Ok(None),
+        [value] => {
+            let value = trim_ows(value);
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(RateLimitError::InvalidLegacyHeader(name));
+            }
+            Ok(Some(value.parse().unwrap_or(u64::MAX)))
+        }
+        _ => Err(RateLimitError::InvalidLegacyHeader(name)),
+    }
+}
+
+/// クォータエンジンの状態から RateLimit ヘッダーを生成するビルダー
+///
+/// ポリシーごとにクォータポリシーと残りクォータ、リセットまでの秒数を追加する。
+/// `Response::too_many_requests()` / `rate_limit::apply_rate_limit()` でレスポンスに反映する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimitBuilder {
+    policy: RateLimitPolicy,
+    limit: RateLimit,
+    legacy: bool,
+}
+
+impl RateLimitBuilder {
+    /// 空のビルダーを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// クォータポリシーと現在の状態を追加 (ビルダーパターン)
+    ///
+    /// 残りクォータはクォータを超えないように丸める。
+    pub fn with_quota(mut self, policy: QuotaPolicy, remaining: u64, reset: u64) -> Self {
+        self.limit.limits.push(ServiceLimit {
+            name: policy.name.clone(),
+            remaining: remaining.min(policy.quota),
+            reset: Some(reset),
+            partition_key: policy.partition_key.clone(),
+        });
+        self.policy.policies.push(policy);
+        self
+    }
+
+    /// X-RateLimit-* ヘッダーも生成する (ビルダーパターン)
+    ///
+    /// 残りクォータが最も少ないポリシーの値を使う。
+    pub fn with_legacy_headers(mut self) -> Self {
+        self.legacy = true;
+        self
+    }
+
+    /// RateLimit-Policy
+    pub fn policy(&self) -> &RateLimitPolicy {
+        &self.policy
+    }
+
+    /// RateLimit
+    pub fn limit(&self) -> &RateLimit {
+        &self.limit
+    }
+
+    /// X-RateLimit-* (残りクォータが最も少ないポリシーの値)
+    pub fn legacy(&self) -> Option<LegacyRateLimit> {
+        let (policy, limit) = self
+            .policy
+            .policies
+            .iter()
+            .zip(&self.limit.limits)
+            .min_by_key(|(_, limit)| limit.remaining)?;
+        let mut legacy = LegacyRateLimit::new()
+            .with_limit(policy.quota)
+            .with_remaining(limit.remaining);
+        if let Some(reset) = limit.reset {
+            legacy = legacy.with_reset(reset);
+        }
+        Some(legacy)
+    }
+
+    /// 残りクォータが 0 のポリシーがあるか
+    pub fn is_exhausted(&self) -> bool {
+        self.limit.is_exhausted()
+    }
+
+    /// ヘッダーの名前と値の一覧
+    pub fn headers(&self) -> Vec<(String, String)> {
+        if self.limit.limits.is_empty() {
+            return Vec::new();
+        }
+        let mut headers = Vec::from([
+            (RateLimitPolicy::NAME.to_string(), self.policy.to_string()),
+            (RateLimit::NAME.to_string(), self.limit.to_string()),
+        ]);
+        if self.legacy
+            && let Some(legacy) = self.legacy()
+        {
+            headers.extend(legacy.headers());
+        }
+        headers
+    }
+}
+
+/// レスポンスに RateLimit ヘッダーを付ける
+///
+/// 既存の RateLimit / RateLimit-Policy / X-RateLimit-* は置き換える。
+pub fn apply_rate_limit(
+    mut response: Response,
+    builder: &RateLimitBuilder,
+) -> Result<Response, EncodeError> {
+    for (name, value) in builder.headers() {
+        response.set_header(name, value)?;
+    }
+    Ok(response)
+}
+
+impl Response {
+    /// RateLimit ヘッダー付きの 429 (Too Many Requests) レスポンスを作成
+    ///
+    /// 残りクォータが 0 のポリシーのリセットまでの秒数がわかる場合は Retry-After を付ける。
+    pub fn too_many_requests(builder: &RateLimitBuilder) -> Result<Self, EncodeError> {
+        let mut response = apply_rate_limit(
+            Response::with_status(StatusCode::TOO_MANY_REQUESTS),
+            builder,
+        )?;
+        if let Some(retry_after) = builder.limit.retry_after() {
+            response.set_header("Retry-After", retry_after.to_string())?;
+        }
+        Ok(response)
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), RateLimitError> {
+    if sfv::is_valid_string(name) {
+        Ok(())
+    } else {
+        Err(RateLimitError::InvalidName)
+    }
+}
+
+/// sf-list をパースする
+fn parse_list(input: &str) -> Result<List, RateLimitError> {
+    List::parse(input).map_err(|_| RateLimitError::InvalidFormat)
+}
+
+/// sf-string のポリシー名とパラメーターを取得
+fn member_name(member: &ListMember) -> Result<(String, &sfv::Parameters), RateLimitError> {
+    let item = member.as_item().ok_or(RateLimitError::InvalidFormat)?;
+    let name = item
+        .bare_item()
+        .as_string()
+        .ok_or(RateLimitError::InvalidName)?;
+    Ok((name.to_string(), item.params()))
+}
+
+fn non_negative(key: &str, value: &BareItem) -> Result<u64, RateLimitError> {
+    match value {
+        BareItem::Integer(value) if *value >= 0 => Ok(*value as u64),
+        _ => Err(RateLimitError::InvalidParameter(key.to_string())),
+    }
+}
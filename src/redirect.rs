@@ -0,0 +1,326 @@
+//! リダイレクトの追従 (RFC 9110 Section 15.4)
+//!
+//! ## 概要
+//!
+//! 3xx レスポンスを受け取ったクライアントが送信する次のリクエストを組み立てる。
+//! I/O は行わないため、どの実行環境のクライアントからも同じ規則で使える。
+//!
+//! - Location ヘッダーをリクエスト URL に対して解決する (RFC 9110 Section 10.2.2)
+//! - 303 は HEAD 以外を GET に、301 / 302 は POST を GET に書き換える
+//!   (RFC 9110 Section 15.4.2 / 15.4.3 / 15.4.4)
+//! - 307 / 308 はメソッドとボディを維持する (RFC 9110 Section 15.4.8 / 15.4.9)
+//! - GET に書き換えた場合はボディと Content-* ヘッダーを取り除く
+//! - 異なるオリジンへのリダイレクトでは Authorization / Cookie を取り除く
+//! - リダイレクト回数の上限を超えた場合はエラーにする
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::redirect::RedirectPolicy;
+//! use shiguredo_http11::{Request, ResponseDecoder};
+//!
+//! let url = "https://example.com/login";
+//! let (request, _endpoint) = Request::from_url("POST", url).unwrap();
+//! let request = request
+//!     .header("Authorization", "Bearer token")
+//!     .unwrap()
+//!     .body(b"user=alice".to_vec());
+//!
+//! let mut decoder = ResponseDecoder::new();
+//! decoder
+//!     .feed(b"HTTP/1.1 303 See Other\r\nLocation: https://accounts.example.net/home\r\nContent-Length: 0\r\n\r\n")
+//!     .unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//!
+//! let policy = RedirectPolicy::new();
+//! let redirect = policy.follow(&request, url, &head, 0).unwrap().unwrap();
+//! assert_eq!(redirect.url(), "https://accounts.example.net/home");
+//! assert_eq!(redirect.endpoint().host(), "accounts.example.net");
+//! assert!(redirect.is_cross_origin());
+//!
+//! let next = redirect.request();
+//! assert_eq!(next.method(), "GET");
+//! assert_eq!(next.uri(), "/home");
+//! assert_eq!(next.get_header("Host"), Some("accounts.example.net"));
+//! assert_eq!(next.get_header("Authorization"), None);
+//! assert_eq!(next.body_bytes(), None);
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::decoder::{HttpHead, ResponseHead};
+use crate::request::{Endpoint, Request};
+use crate::uri::Uri;
+
+/// リダイレクト回数のデフォルトの上限
+///
+/// Fetch Standard の redirect count の上限と同じ値。
+pub const DEFAULT_MAX_REDIRECTS: usize = 20;
+
+/// リクエストメソッドを GET に書き換えた場合に取り除くヘッダー
+///
+/// ボディを送らなくなるため、ボディを説明するヘッダーも送らない。
+const BODY_HEADERS: &[&str] = &[
+    "Content-Encoding",
+    "Content-Language",
+    "Content-Length",
+    "Content-Location",
+    "Content-Type",
+    "Transfer-Encoding",
+];
+
+/// 異なるオリジンへのリダイレクトで取り除くヘッダー
+///
+/// 資格情報をリダイレクト先に漏らさないため。Cookie は Cookie ストアから
+/// リダイレクト先に合わせて付け直すこと。
+const CREDENTIAL_HEADERS: &[&str] = &["Authorization", "Cookie"];
+
+/// リダイレクトエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RedirectError {
+    /// リダイレクト回数が上限に達した
+    TooManyRedirects,
+    /// リダイレクトのステータスコードだが Location ヘッダーがない
+    MissingLocation,
+    /// Location ヘッダーが不正、または複数ある
+    InvalidLocation,
+    /// リクエスト URL が http / https の絶対 URL ではない
+    InvalidUrl,
+    /// リダイレクト先が http / https 以外のスキーム
+    UnsupportedScheme,
+    /// https から http へのリダイレクトを許可していない
+    InsecureRedirect,
+}
+
+impl fmt::Display for RedirectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RedirectError::TooManyRedirects => write!(f, "too many redirects"),
+            RedirectError::MissingLocation => write!(f, "missing Location header"),
+            RedirectError::InvalidLocation => write!(f, "invalid Location header"),
+            RedirectError::InvalidUrl => write!(f, "invalid request URL"),
+            RedirectError::UnsupportedScheme => write!(f, "unsupported redirect scheme"),
+            RedirectError::InsecureRedirect => {
+                write!(f, "redirect from https to http is not allowed")
+            }
+        }
+    }
+}
+
+impl core::error::Error for RedirectError {}
+
+/// リダイレクトの追従ポリシー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectPolicy {
+    max_redirects: usize,
+    allow_https_downgrade: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedirectPolicy {
+    /// デフォルトのポリシーを作成
+    ///
+    /// リダイレクト回数の上限は `DEFAULT_MAX_REDIRECTS`、https から http への
+    /// リダイレクトは許可する。
+    pub fn new() -> Self {
+        RedirectPolicy {
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            allow_https_downgrade: true,
+        }
+    }
+
+    /// リダイレクト回数の上限を指定
+    ///
+    /// 0 を指定するとリダイレクトを追従しない。
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// https から http へのリダイレクトを拒否する
+    pub fn without_https_downgrade(mut self) -> Self {
+        self.allow_https_downgrade = false;
+        self
+    }
+
+    /// リダイレクト回数の上限
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects
+    }
+
+    /// レスポンスに従って次のリクエストを組み立てる
+    ///
+    /// - `request`: 直前に送信したリクエスト
+    /// - `url`: `request` を送信した URL (http / https の絶対 URL)
+    /// - `response`: `request` に対するレスポンス
+    /// - `redirects`: これまでに追従したリダイレクトの回数
+    ///
+    /// 追従するリダイレクトではない場合は `None` を返す。
+    /// 対象は 301 / 302 / 303 / 307 / 308 と、Location ヘッダーを持つ 300。
+    pub fn follow(
+        &self,
+        request: &Request,
+        url: &str,
+        response: &ResponseHead,
+        redirects: usize,
+    ) -> Result<Option<Redirect>, RedirectError> {
+        let status_code = response.status_code();
+        let locations = response.get_headers("Location");
+        match status_code {
+            301 | 302 | 303 | 307 | 308 => {}
+            300 if !locations.is_empty() => {}
+            _ => return Ok(None),
+        }
+        let location = match locations.as_slice() {
+            [] => return Err(RedirectError::MissingLocation),
+            [location] => location.trim(),
+            _ => return Err(RedirectError::InvalidLocation),
+        };
+        if redirects >= self.max_redirects {
+            return Err(RedirectError::TooManyRedirects);
+        }
+
+        let base = Uri::parse(url).map_err(|_| RedirectError::InvalidUrl)?;
+        if !base.scheme().is_some_and(is_http_scheme) || base.host().is_none() {
+            return Err(RedirectError::InvalidUrl);
+        }
+        let target = base
+            .join(location)
+            .map_err(|_| RedirectError::InvalidLocation)?;
+        let scheme = target.scheme().ok_or(RedirectError::InvalidLocation)?;
+        if !is_http_scheme(scheme) {
+            return Err(RedirectError::UnsupportedScheme);
+        }
+        if !self.allow_https_downgrade
+            && base
+                .scheme()
+                .is_some_and(|s| s.eq_ignore_ascii_case("https"))
+            && scheme.eq_ignore_ascii_case("http")
+        {
+            return Err(RedirectError::InsecureRedirect);
+        }
+
+        // RFC 9110 Section 10.2.2: Location にフラグメントがない場合は元のフラグメントを引き継ぐ
+        let url = match (target.fragment(), base.fragment()) {
+            (None, Some(fragment)) => alloc::format!("{target}#{fragment}"),
+            _ => target.to_string(),
+        };
+
+        let method = redirect_method(status_code, request.method());
+        let method_changed = method != request.method();
+        let cross_origin = !same_origin(&base, &target);
+
+        let (mut next, endpoint) =
+            Request::from_url(method, &url).map_err(|_| RedirectError::InvalidLocation)?;
+        next.set_header_case(request.header_case_policy());
+        for (name, value) in HttpHead::headers(request) {
+            if name.eq_ignore_ascii_case("Host")
+                || (method_changed && contains_ignore_case(BODY_HEADERS, name))
+                || (cross_origin && contains_ignore_case(CREDENTIAL_HEADERS, name))
+            {
+                continue;
+            }
+            next.add_header(name.as_ref(), value.as_str())
+                .map_err(|_| RedirectError::InvalidLocation)?;
+        }
+        if !method_changed && let Some(body) = request.body_bytes() {
+            next.set_body(body);
+        }
+
+        Ok(Some(Redirect {
+            request: next,
+            endpoint,
+            url,
+            status_code,
+            cross_origin,
+        }))
+    }
+}
+
+/// 追従するリダイレクト
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    request: Request,
+    endpoint: Endpoint,
+    url: String,
+    status_code: u16,
+    cross_origin: bool,
+}
+
+impl Redirect {
+    /// 次に送信するリクエスト
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// 次に送信するリクエストを取り出す
+    pub fn into_request(self) -> Request {
+        self.request
+    }
+
+    /// 次のリクエストの接続先
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// リダイレクト先の URL
+    ///
+    /// 次の `RedirectPolicy::follow()` の `url` に渡す。
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// リダイレクトのステータスコード
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// リダイレクトが恒久的か (301 / 308)
+    ///
+    /// 恒久的なリダイレクトは、以降のリクエストで参照を書き換えてよい。
+    pub fn is_permanent(&self) -> bool {
+        matches!(self.status_code, 301 | 308)
+    }
+
+    /// 異なるオリジンへのリダイレクトか
+    pub fn is_cross_origin(&self) -> bool {
+        self.cross_origin
+    }
+}
+
+/// リダイレクト先に送るメソッド
+fn redirect_method(status_code: u16, method: &str) -> &str {
+    match status_code {
+        // RFC 9110 Section 15.4.4: 303 は GET (または HEAD) で取得する
+        303 if method != "HEAD" => "GET",
+        // RFC 9110 Section 15.4.2 / 15.4.3: 歴史的な理由により POST は GET に書き換えてよい
+        300..=302 if method == "POST" => "GET",
+        _ => method,
+    }
+}
+
+/// オリジン (スキーム、ホスト、ポート) が同じか
+fn same_origin(a: &Uri, b: &Uri) -> bool {
+    let scheme_a = a.scheme().unwrap_or("");
+    let scheme_b = b.scheme().unwrap_or("");
+    scheme_a.eq_ignore_ascii_case(scheme_b)
+        && a.hostname()
+            .unwrap_or("")
+            .eq_ignore_ascii_case(b.hostname().unwrap_or(""))
+        && a.port_or_default() == b.port_or_default()
+}
+
+fn is_http_scheme(scheme: &str) -> bool {
+    scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+fn contains_ignore_case(names: &[&str], name: &str) -> bool {
+    names.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
@@ -1,9 +1,17 @@
-use crate::decoder::HttpHead;
+use crate::decoder::{HttpHead, RequestHead};
+use crate::encoder::HeaderCase;
 use crate::error::EncodeError;
+use crate::form;
+use crate::header_name;
+use crate::request_target::{target_path, target_query};
+use crate::typed_header::TypedHeader;
+use crate::uri::{Uri, UriError, parse_query, percent_decode};
 use crate::validate::{
     is_valid_field_value, is_valid_header_name, is_valid_method, is_valid_protocol_version,
     is_valid_request_target,
 };
+use crate::version::HttpVersion;
+use alloc::borrow::Cow;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
@@ -37,8 +45,46 @@ pub struct Request {
     method: String,
     uri: String,
     version: String,
-    headers: Vec<(String, String)>,
+    headers: Vec<(Cow<'static, str>, String)>,
     body: Option<Vec<u8>>,
+    // エンコード時のヘッダー名の大文字小文字
+    header_case: HeaderCase,
+}
+
+/// `Request::from_url` が返す接続先
+///
+/// トランスポート層が接続先と TLS の要否を決めるために使う。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    scheme: String,
+    host: String,
+    port: u16,
+}
+
+impl Endpoint {
+    /// スキームを取得 (小文字の `http` / `https`)
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// 接続先ホストを取得
+    ///
+    /// IPv6 リテラルは角括弧を除いた形 (`::1` 等) で返す。
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// 接続先ポートを取得
+    ///
+    /// URL にポートがない場合はスキームのデフォルトポート (http: 80 / https: 443)。
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// TLS で接続すべきか (`https` かどうか)
+    pub fn is_secure(&self) -> bool {
+        self.scheme == "https"
+    }
 }
 
 impl HttpHead for Request {
@@ -46,7 +92,7 @@ impl HttpHead for Request {
         &self.version
     }
 
-    fn headers(&self) -> &[(String, String)] {
+    fn headers(&self) -> &[(Cow<'static, str>, String)] {
         &self.headers
     }
 }
@@ -90,9 +136,73 @@ impl Request {
             version: "HTTP/1.1".to_string(),
             headers: Vec::new(),
             body: None,
+            header_case: HeaderCase::Preserve,
         })
     }
 
+    /// URL からリクエストを作成
+    ///
+    /// `http` / `https` の絶対 URL を受け取り、origin-form の request-target
+    /// (path + query、path が空なら `/`) と Host ヘッダーを設定したリクエストと、
+    /// 接続先を表す `Endpoint` を返す。
+    ///
+    /// - Host ヘッダーはスキームのデフォルトポート (http: 80 / https: 443) の場合は
+    ///   ポートを省略し、それ以外は `host:port` とする (RFC 9110 Section 7.2)
+    /// - フラグメントは送信しない (RFC 9110 Section 7.1)
+    /// - userinfo を含む URL は拒否する (RFC 9110 Section 4.2.4)
+    /// - 空 host は拒否する (RFC 9110 Section 4.2.1 / 4.2.2)
+    ///
+    /// ```rust
+    /// use shiguredo_http11::{HttpHead, Request};
+    ///
+    /// let (request, endpoint) = Request::from_url("GET", "https://example.com:8443/path?q=1").unwrap();
+    /// assert_eq!(request.uri(), "/path?q=1");
+    /// assert_eq!(request.get_header("Host"), Some("example.com:8443"));
+    /// assert_eq!(endpoint.scheme(), "https");
+    /// assert_eq!(endpoint.host(), "example.com");
+    /// assert_eq!(endpoint.port(), 8443);
+    /// assert!(endpoint.is_secure());
+    /// ```
+    pub fn from_url(method: impl Into<String>, url: &str) -> Result<(Self, Endpoint), EncodeError> {
+        let invalid = || EncodeError::InvalidRequestTarget {
+            uri: url.to_string(),
+        };
+        let parsed = Uri::parse(url).map_err(|_| invalid())?;
+        let scheme = parsed.scheme().ok_or_else(invalid)?.to_ascii_lowercase();
+        let default_port = match scheme.as_str() {
+            "http" => 80,
+            "https" => 443,
+            _ => return Err(invalid()),
+        };
+        parsed.authority().ok_or_else(invalid)?;
+        if parsed.userinfo().is_some() {
+            return Err(EncodeError::UserinfoInHttpUri {
+                uri: url.to_string(),
+            });
+        }
+        let host = parsed.host().unwrap_or("");
+        if host.is_empty() {
+            return Err(EncodeError::EmptyHostInHttpUri {
+                uri: url.to_string(),
+            });
+        }
+        let port = parsed.port().unwrap_or(default_port);
+        let host_header = if port == default_port {
+            host.to_string()
+        } else {
+            alloc::format!("{host}:{port}")
+        };
+
+        let request = Self::new(method, parsed.origin_form())?.header("Host", host_header)?;
+        // IPv6 リテラルは接続時に角括弧を含めない
+        let endpoint = Endpoint {
+            scheme,
+            host: parsed.hostname().unwrap_or(host).to_string(),
+            port,
+        };
+        Ok((request, endpoint))
+    }
+
     /// カスタムバージョンでリクエストを作成
     ///
     /// バリデーション順序: method → uri (構文) → uri (obs-text 拒否) → version。
@@ -136,6 +246,7 @@ impl Request {
             version,
             headers: Vec::new(),
             body: None,
+            header_case: HeaderCase::Preserve,
         })
     }
 
@@ -165,7 +276,7 @@ impl Request {
         method: String,
         uri: String,
         version: String,
-        headers: Vec<(String, String)>,
+        headers: Vec<(Cow<'static, str>, String)>,
         body: Option<Vec<u8>>,
     ) -> Self {
         // debug ビルドのみで契約を検査する。release では検証スキップ (decoder 経路の最適化)。
@@ -201,9 +312,22 @@ impl Request {
             version,
             headers,
             body,
+            header_case: HeaderCase::Preserve,
         }
     }
 
+    /// `from_raw_parts` の逆変換 (デコーダーへの記憶領域の返却用)
+    pub(crate) fn into_head_and_body(self) -> (RequestHead, Option<Vec<u8>>) {
+        let head = RequestHead::from_validated_parts(
+            self.method,
+            self.uri,
+            self.version,
+            self.headers,
+            Vec::new(),
+        );
+        (head, self.body)
+    }
+
     /// ヘッダーを追加 (ビルダーパターン)
     ///
     /// 名前は RFC 9110 Section 5.1 の field-name = token (1*tchar、RFC 9110 Section 5.6.2)、
@@ -228,6 +352,40 @@ impl Request {
         self
     }
 
+    /// `application/x-www-form-urlencoded` のボディを設定 (ビルダーパターン)
+    ///
+    /// `form::serialize(pairs)` をボディに設定し、`Content-Type` を
+    /// `application/x-www-form-urlencoded` に置き換える。
+    ///
+    /// ```rust
+    /// use shiguredo_http11::Request;
+    ///
+    /// let request = Request::new("POST", "/login")
+    ///     .unwrap()
+    ///     .form_body(&[("user", "alice"), ("remember", "on")]);
+    /// assert_eq!(request.body_bytes(), Some(&b"user=alice&remember=on"[..]));
+    /// assert_eq!(
+    ///     request.get_header("Content-Type"),
+    ///     Some("application/x-www-form-urlencoded")
+    /// );
+    /// ```
+    pub fn form_body<K: AsRef<str>, V: AsRef<str>>(mut self, pairs: &[(K, V)]) -> Self {
+        self.replace_valid_header("Content-Type", form::CONTENT_TYPE);
+        self.body = Some(form::serialize(pairs).into_bytes());
+        self
+    }
+
+    /// 検証済みの名前と値で同名のヘッダーを置き換える (内部用)
+    ///
+    /// `set_header` と異なりバリデーションを行わないため、定数など
+    /// 妥当性が明らかな値にのみ使う。
+    pub(crate) fn replace_valid_header(&mut self, name: &str, value: &str) {
+        debug_assert!(is_valid_header_name(name) && is_valid_field_value(value));
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers
+            .push((header_name::intern(name), value.to_string()));
+    }
+
     /// ボディなしを明示 (ビルダーパターン)
     ///
     /// `body = None` に設定する。builder チェイン中に `body()` を呼んだ後で
@@ -275,7 +433,7 @@ impl Request {
         if !is_valid_field_value(&value) {
             return Err(EncodeError::InvalidHeaderValue { name, value });
         }
-        self.headers.push((name, value));
+        self.headers.push((header_name::intern_owned(name), value));
         Ok(self)
     }
 
@@ -309,10 +467,69 @@ impl Request {
             return Err(EncodeError::InvalidHeaderValue { name, value });
         }
         self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
-        self.headers.push((name, value));
+        self.headers.push((header_name::intern_owned(name), value));
         Ok(self)
     }
 
+    /// 指定した名前のヘッダーをすべて削除する (mutator)
+    ///
+    /// 大文字小文字を区別せずに一致するヘッダーを削除する。存在しない場合は何もしない。
+    pub fn remove_header(&mut self, name: &str) -> &mut Self {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self
+    }
+
+    /// 指定した名前のヘッダーが存在しない場合のみ追加する (mutator)
+    ///
+    /// 既存のヘッダーがある場合は変更しない。デフォルト値の付与に使う。
+    /// 既存のヘッダーの有無にかかわらずバリデーションを行い、失敗した場合はエラーを返す。
+    pub fn insert_header_if_absent(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<&mut Self, EncodeError> {
+        let name = name.into();
+        let value = value.into();
+        if !is_valid_header_name(&name) {
+            return Err(EncodeError::InvalidHeaderName { name });
+        }
+        if !is_valid_field_value(&value) {
+            return Err(EncodeError::InvalidHeaderValue { name, value });
+        }
+        if !self.has_header(&name) {
+            self.headers.push((header_name::intern_owned(name), value));
+        }
+        Ok(self)
+    }
+
+    /// 型付きヘッダーを設定する (mutator)
+    ///
+    /// `set_header(T::NAME, value.encode_value())` と同じく、同名の既存ヘッダーを
+    /// すべて削除してから追加する。
+    pub fn set_typed<T: TypedHeader>(&mut self, value: &T) -> Result<&mut Self, EncodeError> {
+        self.set_header(T::NAME, value.encode_value())
+    }
+
+    /// エンコード時のヘッダー名の大文字小文字を指定 (ビルダーパターン)
+    ///
+    /// デフォルトは `HeaderCase::Preserve` (指定した名前をそのまま送信)。
+    /// 自動付与される `Content-Length` / `Date` にも適用される。
+    pub fn header_case(mut self, case: HeaderCase) -> Self {
+        self.header_case = case;
+        self
+    }
+
+    /// エンコード時のヘッダー名の大文字小文字を指定 (mutator)
+    pub fn set_header_case(&mut self, case: HeaderCase) -> &mut Self {
+        self.header_case = case;
+        self
+    }
+
+    /// エンコード時のヘッダー名の大文字小文字を取得
+    pub fn header_case_policy(&self) -> HeaderCase {
+        self.header_case
+    }
+
     /// HTTP メソッドを取得
     pub fn method(&self) -> &str {
         &self.method
@@ -323,11 +540,45 @@ impl Request {
         &self.uri
     }
 
+    /// request-target の path を取得する (ルーティング用、パーセントデコードしない)
+    ///
+    /// origin-form は "?" より前、absolute-form は authority に続く path を返す。
+    /// authority-form / asterisk-form では空文字列を返す。
+    pub fn path(&self) -> &str {
+        target_path(&self.uri).unwrap_or("")
+    }
+
+    /// request-target の query を取得する ("?" を含まない、パーセントデコードしない)
+    ///
+    /// "?" がない場合は `None`、"?" のみの場合は空文字列を返す。
+    pub fn query(&self) -> Option<&str> {
+        target_query(&self.uri)
+    }
+
+    /// path をパーセントデコードして取得する
+    ///
+    /// `%2F` もデコードするため、デコード後の "/" でセグメントを分割してはならない。
+    pub fn decoded_path(&self) -> Result<String, UriError> {
+        percent_decode(self.path())
+    }
+
+    /// query をキーと値の組にパースして取得する (`uri::parse_query`)
+    ///
+    /// query がない場合は空を返す。
+    pub fn query_pairs(&self) -> Result<Vec<(String, String)>, UriError> {
+        parse_query(self.query().unwrap_or(""))
+    }
+
     /// HTTP バージョンを取得
     pub fn version(&self) -> &str {
         &self.version
     }
 
+    /// HTTP バージョンを `HttpVersion` として取得
+    pub fn http_version(&self) -> HttpVersion {
+        HttpHead::http_version(self)
+    }
+
     /// ボディを取得
     ///
     /// 注: builder メソッド `body(data)` と名前を区別するため `body_bytes` と命名している。
@@ -351,6 +602,14 @@ impl Request {
         HttpHead::has_header(self, name)
     }
 
+    /// 型付きヘッダーを取得
+    ///
+    /// ヘッダーが存在しない場合は `None`。同名のヘッダーが複数行ある場合は
+    /// `", "` で結合した値をパースする。
+    pub fn get_typed<T: TypedHeader>(&self) -> Option<Result<T, T::Error>> {
+        HttpHead::get_typed(self)
+    }
+
     /// Connection ヘッダーの値を取得 (RFC 9110 Section 7.6.1)
     ///
     /// 最初の `Connection` ヘッダー値をそのままの `&str` で返す。
@@ -397,12 +656,12 @@ impl Request {
     /// `Content-Length` ヘッダーの値を取得
     /// (RFC 9110 Section 8.6 / RFC 9112 Section 6.2)
     ///
-    /// 最初の `Content-Length` ヘッダー値を `u64` としてパースして返す。
-    /// 複数ヘッダーが存在しても最初の値のみ参照する
-    /// (RFC 9110 Section 5.3 により、`Content-Length` の複数フィールド行生成は
-    /// そもそも禁止されている)。
+    /// デコーダーと同じ検証を行い、不正な値は `None` にせずエラーとして返す:
     ///
-    /// 値がパース不能な場合は `None` を返す。
+    /// - `Ok(None)`: `Content-Length` ヘッダーが存在しない
+    /// - `Ok(Some(n))`: 単一値、または同値の重複 (複数行・カンマ区切り) を 1 つにまとめた値
+    /// - `Err(Error::InvalidData(..))`: 数字以外を含む、オーバーフロー、空、
+    ///   重複した値が一致しない (HTTP Request Smuggling の兆候)
     ///
     /// 注: `Content-Length` の型は `u64` で、RFC 9110 Section 8.6 の
     /// 「整数変換オーバーフロー防止 (Section 17.5)」要件に基づく。
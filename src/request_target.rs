@@ -1,23 +1,266 @@
 //! request-target の形式 (RFC 9112 Section 3.2)
 //!
 //! encoder と decoder で共有される概念。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::request_target::{RequestTarget, RequestTargetForm, classify};
+//!
+//! assert_eq!(classify("/index.html"), Some(RequestTargetForm::Origin));
+//! assert_eq!(classify("http://example.com/"), Some(RequestTargetForm::Absolute));
+//! assert_eq!(classify("example.com:443"), Some(RequestTargetForm::Authority));
+//! assert_eq!(classify("*"), Some(RequestTargetForm::Asterisk));
+//!
+//! // 形式ごとの構成要素にパースする
+//! let target = RequestTarget::parse("example.com:443").unwrap();
+//! assert_eq!(
+//!     target,
+//!     RequestTarget::AuthorityForm {
+//!         host: "example.com".to_string(),
+//!         port: 443,
+//!     }
+//! );
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::host::{Host, HostError};
+use crate::uri::{Uri, UriError};
 
 /// RFC 9112 Section 3.2 request-target の形式
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RequestTargetForm {
     /// origin-form: absolute-path [ "?" query ]
-    /// 例: /path/to/resource?query=value
+    /// 例: `/path/to/resource?query=value`
     Origin,
     /// absolute-form: absolute-URI
-    /// 例: http://example.com/path
+    /// 例: `http://example.com/path`
     Absolute,
     /// authority-form: uri-host ":" port (CONNECT のみ)
-    /// 例: example.com:443
+    /// 例: `example.com:443`
     Authority,
     /// asterisk-form: "*" (OPTIONS のみ)
     Asterisk,
 }
 
+/// request-target パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RequestTargetError {
+    /// どの形式にも該当しない
+    UnknownForm,
+    /// absolute-form の URI が不正
+    InvalidUri(UriError),
+    /// authority-form の authority が不正
+    InvalidAuthority(HostError),
+}
+
+impl fmt::Display for RequestTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestTargetError::UnknownForm => write!(f, "unknown request-target form"),
+            RequestTargetError::InvalidUri(e) => write!(f, "invalid request-target URI: {}", e),
+            RequestTargetError::InvalidAuthority(e) => {
+                write!(f, "invalid request-target authority: {}", e)
+            }
+        }
+    }
+}
+
+impl core::error::Error for RequestTargetError {}
+
+/// 形式ごとの構成要素にパースした request-target (RFC 9112 Section 3.2)
+///
+/// request-target の文字列を形式ごとに切り出し直す代わりに使う。
+/// CONNECT の authority-form と OPTIONS の asterisk-form は path を持たない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestTarget {
+    /// origin-form: absolute-path [ "?" query ]
+    OriginForm {
+        /// absolute-path (パーセントデコードしない)
+        path: String,
+        /// "?" に続く query (パーセントデコードしない)
+        query: Option<String>,
+    },
+    /// absolute-form: absolute-URI
+    AbsoluteForm(Uri),
+    /// authority-form: uri-host ":" port
+    AuthorityForm {
+        /// uri-host (IPv6 は角括弧付き)
+        host: String,
+        /// port
+        port: u16,
+    },
+    /// asterisk-form: "*"
+    Asterisk,
+}
+
+impl RequestTarget {
+    /// request-target をパースする
+    ///
+    /// 形式は [`classify`] で判定する。absolute-form は [`Uri::parse`]、
+    /// authority-form は [`Host::parse`] で検証し、authority-form では port を必須とする。
+    pub fn parse(target: &str) -> Result<Self, RequestTargetError> {
+        match classify(target).ok_or(RequestTargetError::UnknownForm)? {
+            RequestTargetForm::Origin => {
+                let (path, query) = match target.split_once('?') {
+                    Some((path, query)) => (path, Some(query.to_string())),
+                    None => (target, None),
+                };
+                Ok(RequestTarget::OriginForm {
+                    path: path.to_string(),
+                    query,
+                })
+            }
+            RequestTargetForm::Absolute => Uri::parse(target)
+                .map(RequestTarget::AbsoluteForm)
+                .map_err(RequestTargetError::InvalidUri),
+            RequestTargetForm::Authority => {
+                let host = Host::parse(target).map_err(RequestTargetError::InvalidAuthority)?;
+                let port = host
+                    .port()
+                    .ok_or(RequestTargetError::InvalidAuthority(HostError::InvalidPort))?;
+                Ok(RequestTarget::AuthorityForm {
+                    host: host.host().to_string(),
+                    port,
+                })
+            }
+            RequestTargetForm::Asterisk => Ok(RequestTarget::Asterisk),
+        }
+    }
+
+    /// request-target の形式を取得する
+    #[must_use]
+    pub fn form(&self) -> RequestTargetForm {
+        match self {
+            RequestTarget::OriginForm { .. } => RequestTargetForm::Origin,
+            RequestTarget::AbsoluteForm(_) => RequestTargetForm::Absolute,
+            RequestTarget::AuthorityForm { .. } => RequestTargetForm::Authority,
+            RequestTarget::Asterisk => RequestTargetForm::Asterisk,
+        }
+    }
+}
+
+impl fmt::Display for RequestTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestTarget::OriginForm { path, query } => {
+                write!(f, "{}", path)?;
+                if let Some(query) = query {
+                    write!(f, "?{}", query)?;
+                }
+                Ok(())
+            }
+            RequestTarget::AbsoluteForm(uri) => write!(f, "{}", uri.as_str()),
+            RequestTarget::AuthorityForm { host, port } => write!(f, "{}:{}", host, port),
+            RequestTarget::Asterisk => write!(f, "*"),
+        }
+    }
+}
+
+/// request-target の形式を判定する
+///
+/// 形式の判定のみを行い、各形式の文法 (host の妥当性やパス文字等) は検証しない。
+/// どの形式にも該当しない場合は `None` を返す。
+///
+/// - origin-form: "/" で始まる (例: /path?query)
+/// - absolute-form: absolute-URI (例: http://host/path, urn:isbn:0451450523)
+/// - authority-form: uri-host ":" port (例: host:port)
+/// - asterisk-form: "*" のみ
+///
+/// authority-form と "://" なし absolute-form は文法的に曖昧なため、
+/// デコーダー (decoder/body.rs) と同じ順序で判定する:
+/// 1. port が数値の host:port → authority-form
+/// 2. 有効なスキームが検出 → absolute-form
+#[must_use]
+pub fn classify(target: &str) -> Option<RequestTargetForm> {
+    if target == "*" {
+        Some(RequestTargetForm::Asterisk)
+    } else if target.starts_with('/') {
+        Some(RequestTargetForm::Origin)
+    } else if target.contains("://") {
+        Some(RequestTargetForm::Absolute)
+    } else if looks_like_authority_form(target) {
+        Some(RequestTargetForm::Authority)
+    } else if detect_scheme(target).is_some() {
+        // "://" を含まない absolute-URI (例: urn:isbn:0451450523)
+        Some(RequestTargetForm::Absolute)
+    } else {
+        None
+    }
+}
+
+/// authority-form (uri-host ":" port) らしい形かどうか
+///
+/// port が 0-65535 の数値で host が空でないことのみを確認する。
+fn looks_like_authority_form(target: &str) -> bool {
+    // userinfo は authority-form に含まれない
+    if target.contains('@') {
+        return false;
+    }
+    if let Some(colon_pos) = target.rfind(':') {
+        let port_str = &target[colon_pos + 1..];
+        let host = &target[..colon_pos];
+        !port_str.is_empty()
+            && port_str.bytes().all(|b| b.is_ascii_digit())
+            && port_str.parse::<u16>().is_ok()
+            && !host.is_empty()
+    } else {
+        false
+    }
+}
+
+/// request-target の path を取得する (query は含まない)
+///
+/// origin-form は "?" より前、absolute-form は authority に続く path を返す。
+/// authority-form / asterisk-form と形式を判定できない場合は `None` を返す。
+pub(crate) fn target_path(target: &str) -> Option<&str> {
+    match classify(target)? {
+        RequestTargetForm::Origin => Some(&target[..target.find('?').unwrap_or(target.len())]),
+        RequestTargetForm::Absolute => split_absolute_form(target).map(|(_, _, p)| p),
+        RequestTargetForm::Authority | RequestTargetForm::Asterisk => None,
+    }
+}
+
+/// request-target の query を取得する ("?" は含まない)
+///
+/// origin-form / absolute-form で "?" を含む場合のみ `Some` を返す。
+pub(crate) fn target_query(target: &str) -> Option<&str> {
+    match classify(target)? {
+        RequestTargetForm::Origin | RequestTargetForm::Absolute => {
+            target.split_once('?').map(|(_, query)| query)
+        }
+        RequestTargetForm::Authority | RequestTargetForm::Asterisk => None,
+    }
+}
+
+/// absolute-form の request-target を構成要素に分割する
+///
+/// absolute-URI = scheme ":" hier-part [ "?" query ]
+///
+/// `(scheme, authority, path)` を返す。authority は hier-part が "//" で始まる
+/// 場合のみ `Some` で、path には query を含まない。
+/// スキームが検出できない場合は `None` を返す。
+pub(crate) fn split_absolute_form(target: &str) -> Option<(&str, Option<&str>, &str)> {
+    let scheme_len = detect_scheme(target)?;
+    let scheme = &target[..scheme_len];
+    let rest = &target[scheme_len + 1..];
+    let rest = &rest[..rest.find('?').unwrap_or(rest.len())];
+    match rest.strip_prefix("//") {
+        Some(after_slashes) => {
+            let authority_end = after_slashes.find('/').unwrap_or(after_slashes.len());
+            Some((
+                scheme,
+                Some(&after_slashes[..authority_end]),
+                &after_slashes[authority_end..],
+            ))
+        }
+        None => Some((scheme, None, rest)),
+    }
+}
+
 /// スキームを検出する (RFC 3986 Section 3.1)
 ///
 /// scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
@@ -1,10 +1,16 @@
-use crate::decoder::HttpHead;
+use crate::date::HttpDate;
+use crate::decoder::{HttpHead, ResponseHead};
+use crate::encoder::HeaderCase;
 use crate::error::EncodeError;
-use crate::status_code::{StatusClass, StatusCode};
+use crate::header_name;
+use crate::status_code::{StatusClass, StatusCode, reason_phrase_for};
+use crate::typed_header::TypedHeader;
 use crate::validate::{
     is_valid_field_value, is_valid_header_name, is_valid_protocol_version, is_valid_reason_phrase,
     is_valid_status_code,
 };
+use crate::version::HttpVersion;
+use alloc::borrow::Cow;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
@@ -36,7 +42,7 @@ pub struct Response {
     version: String,
     status_code: u16,
     reason_phrase: String,
-    headers: Vec<(String, String)>,
+    headers: Vec<(Cow<'static, str>, String)>,
     body: Option<Vec<u8>>,
     // ボディ送信を抑止するフラグ (HEAD レスポンス用)
     //
@@ -48,6 +54,12 @@ pub struct Response {
     // 注: pending/0018 で encoder 側のフラグへの移譲が検討されており、
     // 本フィールドは将来撤去される可能性がある。
     omit_body: bool,
+    // Date ヘッダーが未設定のときにエンコーダーが付与する日時
+    //
+    // Sans I/O のため時計を持たず、現在時刻は呼び出し側が `auto_date(now)` で渡す。
+    auto_date: Option<HttpDate>,
+    // エンコード時のヘッダー名の大文字小文字
+    header_case: HeaderCase,
 }
 
 impl HttpHead for Response {
@@ -55,7 +67,7 @@ impl HttpHead for Response {
         &self.version
     }
 
-    fn headers(&self) -> &[(String, String)] {
+    fn headers(&self) -> &[(Cow<'static, str>, String)] {
         &self.headers
     }
 }
@@ -100,6 +112,74 @@ impl Response {
             headers: Vec::new(),
             body: None,
             omit_body: false,
+            auto_date: None,
+            header_case: HeaderCase::Preserve,
+        })
+    }
+
+    /// 103 Early Hints レスポンスを作成 (RFC 8297)
+    ///
+    /// `links` の各要素を `Link` ヘッダー (RFC 8288) として 1 行ずつ追加する。
+    /// 最終レスポンスの前に `encode_interim_response` / `encode_response_with_interim`
+    /// で送信する。
+    ///
+    /// ```rust
+    /// use shiguredo_http11::{Response, encode_interim_response};
+    ///
+    /// let hints = Response::early_hints([
+    ///     "</style.css>; rel=preload; as=style",
+    ///     "</script.js>; rel=preload; as=script",
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(
+    ///     encode_interim_response(&hints).unwrap(),
+    ///     b"HTTP/1.1 103 Early Hints\r\n\
+    /// Link: </style.css>; rel=preload; as=style\r\n\
+    /// Link: </script.js>; rel=preload; as=script\r\n\r\n"
+    /// );
+    /// ```
+    pub fn early_hints<I, S>(links: I) -> Result<Self, EncodeError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut response = Self::with_status(StatusCode::EARLY_HINTS);
+        for link in links {
+            response.add_header("Link", link)?;
+        }
+        Ok(response)
+    }
+
+    /// ステータスコード値から Response を作成 (HTTP/1.1)
+    ///
+    /// reason-phrase は `status_code::reason_phrase_for` で決める。IANA 未登録のコードは
+    /// 空の reason-phrase になる (RFC 9112 Section 4 で reason-phrase は省略可能)。`status_code` が 100..=599 の範囲外の場合は
+    /// `EncodeError::InvalidStatusCode` を返す。
+    ///
+    /// ```rust
+    /// use shiguredo_http11::Response;
+    ///
+    /// let response = Response::from_status(404).unwrap();
+    /// assert_eq!(response.reason_phrase(), "Not Found");
+    /// assert_eq!(Response::from_status(599).unwrap().reason_phrase(), "");
+    /// assert!(Response::from_status(600).is_err());
+    /// ```
+    pub fn from_status(status_code: u16) -> Result<Self, EncodeError> {
+        if !is_valid_status_code(status_code) {
+            return Err(EncodeError::InvalidStatusCode { code: status_code });
+        }
+        // `Response::new` は空の reason-phrase を拒否するため直接構築する。
+        // reason_phrase_for は IANA 登録の ASCII 文字列か空文字列のみを返し、
+        // どちらもエンコーダーの reason-phrase 検証を通過する。
+        Ok(Self {
+            version: "HTTP/1.1".to_string(),
+            status_code,
+            reason_phrase: reason_phrase_for(status_code).to_string(),
+            headers: Vec::new(),
+            body: None,
+            omit_body: false,
+            auto_date: None,
+            header_case: HeaderCase::Preserve,
         })
     }
 
@@ -166,6 +246,8 @@ impl Response {
             headers: Vec::new(),
             body: None,
             omit_body: false,
+            auto_date: None,
+            header_case: HeaderCase::Preserve,
         })
     }
 
@@ -198,7 +280,7 @@ impl Response {
         version: String,
         status_code: u16,
         reason_phrase: String,
-        headers: Vec<(String, String)>,
+        headers: Vec<(Cow<'static, str>, String)>,
         body: Option<Vec<u8>>,
     ) -> Self {
         // debug ビルドのみで契約を検査する。release では検証スキップ (decoder 経路の最適化)。
@@ -229,9 +311,23 @@ impl Response {
             headers,
             body,
             omit_body: false,
+            auto_date: None,
+            header_case: HeaderCase::Preserve,
         }
     }
 
+    /// `from_raw_parts` の逆変換 (デコーダーへの記憶領域の返却用)
+    pub(crate) fn into_head_and_body(self) -> (ResponseHead, Option<Vec<u8>>) {
+        let head = ResponseHead::from_validated_parts(
+            self.version,
+            self.status_code,
+            self.reason_phrase,
+            self.headers,
+            Vec::new(),
+        );
+        (head, self.body)
+    }
+
     /// ボディ送信を抑止する (ビルダーパターン)
     ///
     /// HEAD リクエストへのレスポンスでメッセージボディを送信しない場合に使用する
@@ -252,6 +348,29 @@ impl Response {
         self
     }
 
+    /// Date ヘッダーの自動付与を設定 (ビルダーパターン)
+    ///
+    /// RFC 9110 Section 6.6.1: 時計を持つオリジンサーバーは 2xx / 3xx / 4xx レスポンスに
+    /// Date ヘッダーを生成しなければならない (MUST)。
+    /// エンコード時に `Date` ヘッダーが存在しなければ `now` を IMF-fixdate 形式で付与する。
+    /// 既に `Date` ヘッダーがある場合は何もしない。
+    ///
+    /// ```rust
+    /// use shiguredo_http11::date::{DayOfWeek, HttpDate};
+    /// use shiguredo_http11::{Response, StatusCode};
+    ///
+    /// let now = HttpDate::new(DayOfWeek::Sunday, 6, 11, 1994, 8, 49, 37).unwrap();
+    /// let response = Response::with_status(StatusCode::NO_CONTENT).auto_date(now);
+    /// assert_eq!(
+    ///     response.encode().unwrap(),
+    ///     b"HTTP/1.1 204 No Content\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n"
+    /// );
+    /// ```
+    pub fn auto_date(mut self, now: HttpDate) -> Self {
+        self.auto_date = Some(now);
+        self
+    }
+
     /// ヘッダーを追加 (ビルダーパターン)
     ///
     /// 名前は RFC 9110 Section 5.1 の field-name = token (1*tchar、RFC 9110 Section 5.6.2)、
@@ -320,7 +439,7 @@ impl Response {
         if !is_valid_field_value(&value) {
             return Err(EncodeError::InvalidHeaderValue { name, value });
         }
-        self.headers.push((name, value));
+        self.headers.push((header_name::intern_owned(name), value));
         Ok(self)
     }
 
@@ -355,10 +474,80 @@ impl Response {
             return Err(EncodeError::InvalidHeaderValue { name, value });
         }
         self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
-        self.headers.push((name, value));
+        self.headers.push((header_name::intern_owned(name), value));
+        Ok(self)
+    }
+
+    /// 指定した名前のヘッダーをすべて削除する (mutator)
+    ///
+    /// 大文字小文字を区別せずに一致するヘッダーを削除する。存在しない場合は何もしない。
+    pub fn remove_header(&mut self, name: &str) -> &mut Self {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self
+    }
+
+    /// 指定した名前のヘッダーが存在しない場合のみ追加する (mutator)
+    ///
+    /// 既存のヘッダーがある場合は変更しない。デフォルト値の付与に使う。
+    /// 既存のヘッダーの有無にかかわらずバリデーションを行い、失敗した場合はエラーを返す。
+    pub fn insert_header_if_absent(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<&mut Self, EncodeError> {
+        let name = name.into();
+        let value = value.into();
+        if !is_valid_header_name(&name) {
+            return Err(EncodeError::InvalidHeaderName { name });
+        }
+        if !is_valid_field_value(&value) {
+            return Err(EncodeError::InvalidHeaderValue { name, value });
+        }
+        if !self.has_header(&name) {
+            self.headers.push((header_name::intern_owned(name), value));
+        }
         Ok(self)
     }
 
+    /// 型付きヘッダーを設定する (mutator)
+    ///
+    /// `set_header(T::NAME, value.encode_value())` と同じく、同名の既存ヘッダーを
+    /// すべて削除してから追加する。
+    pub fn set_typed<T: TypedHeader>(&mut self, value: &T) -> Result<&mut Self, EncodeError> {
+        self.set_header(T::NAME, value.encode_value())
+    }
+
+    /// エンコード時のヘッダー名の大文字小文字を指定 (ビルダーパターン)
+    ///
+    /// デフォルトは `HeaderCase::Preserve` (指定した名前をそのまま送信)。
+    /// 自動付与される `Content-Length` / `Date` にも適用される。
+    pub fn header_case(mut self, case: HeaderCase) -> Self {
+        self.header_case = case;
+        self
+    }
+
+    /// エンコード時のヘッダー名の大文字小文字を指定 (mutator)
+    pub fn set_header_case(&mut self, case: HeaderCase) -> &mut Self {
+        self.header_case = case;
+        self
+    }
+
+    /// エンコード時のヘッダー名の大文字小文字を取得
+    pub fn header_case_policy(&self) -> HeaderCase {
+        self.header_case
+    }
+
+    /// 検証済みの名前と値で同名のヘッダーを置き換える (内部用)
+    ///
+    /// `set_header` と異なりバリデーションを行わないため、定数など
+    /// 妥当性が明らかな値にのみ使う。
+    pub(crate) fn replace_valid_header(&mut self, name: &str, value: &str) {
+        debug_assert!(is_valid_header_name(name) && is_valid_field_value(value));
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers
+            .push((header_name::intern(name), value.to_string()));
+    }
+
     /// ボディを設定 (mutator)
     ///
     /// 空 `Vec` を渡した場合は「明示的な空ボディ」として扱われ、
@@ -390,11 +579,33 @@ impl Response {
         self
     }
 
+    /// Date ヘッダーの自動付与を設定 (mutator)
+    ///
+    /// `None` を渡すと自動付与を無効にする。詳細は `auto_date` を参照。
+    pub fn set_auto_date(&mut self, now: Option<HttpDate>) -> &mut Self {
+        self.auto_date = now;
+        self
+    }
+
+    /// エンコード時に付与される Date ヘッダーの日時を取得
+    ///
+    /// 自動付与が無効、または既に `Date` ヘッダーが存在する場合は `None` を返す。
+    pub fn pending_auto_date(&self) -> Option<&HttpDate> {
+        self.auto_date
+            .as_ref()
+            .filter(|_| !HttpHead::has_header(self, "Date"))
+    }
+
     /// HTTP バージョンを取得
     pub fn version(&self) -> &str {
         &self.version
     }
 
+    /// HTTP バージョンを `HttpVersion` として取得
+    pub fn http_version(&self) -> HttpVersion {
+        HttpHead::http_version(self)
+    }
+
     /// ステータスコードを取得
     pub fn status_code(&self) -> u16 {
         self.status_code
@@ -438,6 +649,14 @@ impl Response {
         HttpHead::has_header(self, name)
     }
 
+    /// 型付きヘッダーを取得
+    ///
+    /// ヘッダーが存在しない場合は `None`。同名のヘッダーが複数行ある場合は
+    /// `", "` で結合した値をパースする。
+    pub fn get_typed<T: TypedHeader>(&self) -> Option<Result<T, T::Error>> {
+        HttpHead::get_typed(self)
+    }
+
     /// ステータスコードのクラス分類を返す。
     ///
     /// RFC 9110 Section 15 に基づく分類。
@@ -494,12 +713,12 @@ impl Response {
     /// `Content-Length` ヘッダーの値を取得
     /// (RFC 9110 Section 8.6 / RFC 9112 Section 6.2)
     ///
-    /// 最初の `Content-Length` ヘッダー値を `u64` としてパースして返す。
-    /// 複数ヘッダーが存在しても最初の値のみ参照する
-    /// (RFC 9110 Section 5.3 により、`Content-Length` の複数フィールド行生成は
-    /// そもそも禁止されている)。
+    /// デコーダーと同じ検証を行い、不正な値は `None` にせずエラーとして返す:
     ///
-    /// 値がパース不能な場合は `None` を返す。
+    /// - `Ok(None)`: `Content-Length` ヘッダーが存在しない
+    /// - `Ok(Some(n))`: 単一値、または同値の重複 (複数行・カンマ区切り) を 1 つにまとめた値
+    /// - `Err(Error::InvalidData(..))`: 数字以外を含む、オーバーフロー、空、
+    ///   重複した値が一致しない (HTTP Request Smuggling の兆候)
     ///
     /// 注: `Content-Length` の型は `u64` で、RFC 9110 Section 8.6 の
     /// 「整数変換オーバーフロー防止 (Section 17.5)」要件に基づく。
@@ -0,0 +1,302 @@
+//! リクエストの再送ポリシー (RFC 9110 Section 9.2.2)
+//!
+//! ## 概要
+//!
+//! 失敗したリクエストを再送してよいかの判定と、再送までの待ち時間の計算を提供します。
+//! I/O と時計、乱数を持たないため、ブロッキング / 非同期どちらのクライアントからも
+//! 同じ規則で使える。
+//!
+//! - リクエストの送信前に失敗した場合は、メソッドによらず再送してよい
+//! - リクエストの送信後にレスポンスを受け取れなかった場合は、idempotent なメソッドか
+//!   Idempotency-Key を持つリクエストのみ再送する (RFC 9110 Section 9.2.2)
+//! - 429 (Too Many Requests) / 503 (Service Unavailable) のレスポンスは、
+//!   再送してよいリクエストのみ再送する
+//! - 待ち時間は指数バックオフにジッターを加えて計算し、Retry-After があればそれに従う
+//!   (RFC 9110 Section 10.2.3)
+//!
+//! 時刻は UNIX 時間 (秒)、乱数は呼び出し側が渡す。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use shiguredo_http11::retry::{Failure, RetryDecision, RetryPolicy, StopReason};
+//! use shiguredo_http11::{Request, ResponseDecoder};
+//!
+//! let policy = RetryPolicy::new();
+//! let request = Request::new("GET", "/").unwrap().header("Host", "example.com").unwrap();
+//!
+//! // レスポンスを受け取れなかった GET は再送する
+//! let decision = policy.decide(&request, &Failure::NoResponse, 0, 1_000, 42);
+//! assert!(decision.is_retry());
+//!
+//! // 503 の Retry-After に従う
+//! let mut decoder = ResponseDecoder::new();
+//! decoder
+//!     .feed(b"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 5\r\nContent-Length: 0\r\n\r\n")
+//!     .unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//! let decision = policy.decide(&request, &Failure::Response(&head), 0, 1_000, 42);
+//! assert_eq!(decision, RetryDecision::Retry(Duration::from_secs(5)));
+//!
+//! // POST は送信後の失敗では再送しない
+//! let request = Request::new("POST", "/").unwrap().header("Host", "example.com").unwrap();
+//! let decision = policy.decide(&request, &Failure::NoResponse, 0, 1_000, 42);
+//! assert_eq!(decision, RetryDecision::Stop(StopReason::NotRetryable));
+//! ```
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::date::HttpDate;
+use crate::decoder::{HttpHead, ResponseHead};
+use crate::idempotency_key::{IdempotencyKey, RequestIdempotency};
+use crate::request::Request;
+use crate::retry_after::RetryAfter;
+
+/// デフォルトの最大再送回数
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// デフォルトのバックオフの基準時間
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// デフォルトのバックオフの上限
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// デフォルトの Retry-After の上限
+pub const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// デフォルトで再送するステータスコード
+pub const DEFAULT_RETRY_STATUSES: &[u16] = &[429, 503];
+
+/// 失敗した送受信
+#[derive(Debug, Clone, Copy)]
+pub enum Failure<'a> {
+    /// リクエストの送信を始める前に失敗した
+    ///
+    /// 接続の確立に失敗した場合や、再利用しようとした接続がすでに閉じていた場合。
+    /// サーバーはリクエストを受け取っていない。
+    NotSent,
+    /// リクエストの送信後、レスポンスを受け取る前に接続が失敗した
+    ///
+    /// サーバーがリクエストを処理したかどうかはわからない。
+    NoResponse,
+    /// レスポンスを受け取った
+    Response(&'a ResponseHead),
+}
+
+/// バックオフに加えるジッター
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// ジッターを加えない
+    None,
+    /// 0 からバックオフまでの一様な値にする
+    #[default]
+    Full,
+    /// バックオフの半分から全体までの一様な値にする
+    Equal,
+}
+
+/// 再送しない理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// 送信後に失敗した、idempotent ではないリクエスト
+    NotRetryable,
+    /// 再送の対象ではないステータスコード
+    Status(u16),
+    /// 最大再送回数に達した
+    MaxRetries,
+    /// Retry-After の待ち時間が上限を超えている
+    RetryAfterTooLong,
+}
+
+/// 再送の判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// 待ち時間の後に再送する
+    Retry(Duration),
+    /// 再送しない
+    Stop(StopReason),
+}
+
+impl RetryDecision {
+    /// 再送するか
+    pub fn is_retry(&self) -> bool {
+        matches!(self, RetryDecision::Retry(_))
+    }
+
+    /// 再送までの待ち時間
+    pub fn delay(&self) -> Option<Duration> {
+        match self {
+            RetryDecision::Retry(delay) => Some(*delay),
+            RetryDecision::Stop(_) => None,
+        }
+    }
+}
+
+/// 再送ポリシー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: Jitter,
+    retry_statuses: Vec<u16>,
+    max_retry_after: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// デフォルトのポリシーを作成
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: Jitter::default(),
+            retry_statuses: DEFAULT_RETRY_STATUSES.to_vec(),
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+        }
+    }
+
+    /// 最大再送回数を指定
+    ///
+    /// 0 を指定すると再送しない。
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// バックオフの基準時間を指定
+    ///
+    /// n 回目の再送 (0 始まり) のバックオフは `base_delay * 2^n` になる。
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// バックオフの上限を指定
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// ジッターを指定
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// 再送するステータスコードを指定
+    pub fn with_retry_statuses(mut self, statuses: &[u16]) -> Self {
+        self.retry_statuses = statuses.to_vec();
+        self
+    }
+
+    /// Retry-After の待ち時間の上限を指定
+    ///
+    /// Retry-After がこれを超える場合は再送しない。
+    pub fn with_max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// 最大再送回数
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// ステータスコードが再送の対象か
+    pub fn is_retry_status(&self, status_code: u16) -> bool {
+        self.retry_statuses.contains(&status_code)
+    }
+
+    /// `attempt` 回目の再送 (0 始まり) のバックオフを計算
+    ///
+    /// `base_delay * 2^attempt` を `max_delay` で抑え、ジッターを加える。
+    /// `random_value` は一様な乱数。
+    pub fn backoff(&self, attempt: u32, random_value: u64) -> Duration {
+        let delay = 1u32
+            .checked_shl(attempt)
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        let nanos = u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX);
+        let jittered = match self.jitter {
+            Jitter::None => nanos,
+            Jitter::Full => uniform(random_value, nanos),
+            Jitter::Equal => nanos - nanos / 2 + uniform(random_value, nanos / 2),
+        };
+        Duration::from_nanos(jittered)
+    }
+
+    /// 失敗した送受信を再送するかを判定する
+    ///
+    /// - `request`: 送信しようとしたリクエスト
+    /// - `failure`: 失敗の内容
+    /// - `attempt`: これまでに再送した回数
+    /// - `now`: 現在時刻 (UNIX 時間)。Retry-After の HTTP-date の解決に使う
+    /// - `random_value`: ジッターに使う一様な乱数
+    ///
+    /// 不正な Retry-After は無視してバックオフを使う。
+    pub fn decide(
+        &self,
+        request: &Request,
+        failure: &Failure<'_>,
+        attempt: u32,
+        now: u64,
+        random_value: u64,
+    ) -> RetryDecision {
+        let key = IdempotencyKey::from_request(request).ok().flatten();
+        let retryable = RequestIdempotency::classify(request.method(), key.as_ref()).is_retryable();
+
+        let retry_after = match failure {
+            Failure::NotSent => None,
+            Failure::NoResponse if retryable => None,
+            Failure::NoResponse => return RetryDecision::Stop(StopReason::NotRetryable),
+            Failure::Response(response) => {
+                let status_code = response.status_code();
+                if !self.is_retry_status(status_code) {
+                    return RetryDecision::Stop(StopReason::Status(status_code));
+                }
+                if !retryable {
+                    return RetryDecision::Stop(StopReason::NotRetryable);
+                }
+                retry_after_delay(response, now)
+            }
+        };
+        if attempt >= self.max_retries {
+            return RetryDecision::Stop(StopReason::MaxRetries);
+        }
+
+        match retry_after {
+            Some(delay) if delay > self.max_retry_after => {
+                RetryDecision::Stop(StopReason::RetryAfterTooLong)
+            }
+            Some(delay) => RetryDecision::Retry(delay),
+            None => RetryDecision::Retry(self.backoff(attempt, random_value)),
+        }
+    }
+}
+
+/// `random_value` を 0 以上 `max` 以下の値に写す
+fn uniform(random_value: u64, max: u64) -> u64 {
+    match max.checked_add(1) {
+        Some(range) => random_value % range,
+        None => random_value,
+    }
+}
+
+/// レスポンスの Retry-After から待ち時間を取得
+fn retry_after_delay(response: &ResponseHead, now: u64) -> Option<Duration> {
+    let value = response.get_header("Retry-After")?;
+    // RFC 850 形式の 2 桁年の解決に現在年を使う
+    let reference_year = HttpDate::from_unix_seconds(now).map_or(9999, |date| date.year());
+    RetryAfter::parse(value, reference_year)
+        .ok()
+        .map(|retry_after| retry_after.delay_from(now))
+}
@@ -0,0 +1,135 @@
+//! Retry-After ヘッダー (RFC 9110 Section 10.2.3)
+//!
+//! ## 概要
+//!
+//! 503 (Service Unavailable) / 429 (Too Many Requests) / 3xx で使われる
+//! Retry-After ヘッダーのパースと生成を提供します。
+//!
+//! ```text
+//! Retry-After = HTTP-date / delay-seconds
+//! delay-seconds = 1*DIGIT
+//! ```
+//!
+//! 時刻は UNIX 時間 (秒) で呼び出し側が渡す。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use shiguredo_http11::retry_after::RetryAfter;
+//!
+//! // delay-seconds
+//! let retry_after = RetryAfter::parse("120", 2026).unwrap();
+//! assert_eq!(retry_after.delay_from(1_000), Duration::from_secs(120));
+//!
+//! // HTTP-date
+//! let retry_after = RetryAfter::parse("Fri, 31 Dec 1999 23:59:59 GMT", 2026).unwrap();
+//! assert_eq!(retry_after.retry_at(0), 946684799);
+//! assert_eq!(retry_after.delay_from(946684700), Duration::from_secs(99));
+//!
+//! // 生成
+//! assert_eq!(RetryAfter::Delay(30).to_string(), "30");
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::time::Duration;
+
+use crate::date::HttpDate;
+
+/// Retry-After パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RetryAfterError {
+    /// 空の入力
+    Empty,
+    /// delay-seconds でも HTTP-date でもない
+    InvalidFormat,
+}
+
+impl fmt::Display for RetryAfterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryAfterError::Empty => write!(f, "empty Retry-After header"),
+            RetryAfterError::InvalidFormat => write!(f, "invalid Retry-After format"),
+        }
+    }
+}
+
+impl core::error::Error for RetryAfterError {}
+
+/// Retry-After ヘッダー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetryAfter {
+    /// delay-seconds (レスポンス受信からの秒数)
+    Delay(u64),
+    /// HTTP-date (再試行してよい日時)
+    Date(HttpDate),
+}
+
+impl RetryAfter {
+    /// Retry-After ヘッダーをパース
+    ///
+    /// `reference_year` は RFC 850 形式の 2 桁年を解決するために使う
+    /// 現在年 (RFC 9110 §5.6.7)。
+    ///
+    /// delay-seconds が u64 に収まらない場合は u64::MAX に飽和させる。
+    pub fn parse(input: &str, reference_year: u16) -> Result<Self, RetryAfterError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(RetryAfterError::Empty);
+        }
+
+        if input.bytes().all(|b| b.is_ascii_digit()) {
+            let seconds = input.bytes().fold(0u64, |acc, b| {
+                acc.saturating_mul(10).saturating_add(u64::from(b - b'0'))
+            });
+            return Ok(RetryAfter::Delay(seconds));
+        }
+
+        HttpDate::parse_any(input, reference_year)
+            .map(RetryAfter::Date)
+            .map_err(|_| RetryAfterError::InvalidFormat)
+    }
+
+    /// `now` (UNIX 時間) から再試行までの UNIX 時間を取得
+    ///
+    /// delay-seconds は `now` に加算する。
+    pub fn retry_at(&self, now: u64) -> u64 {
+        match self {
+            RetryAfter::Delay(seconds) => now.saturating_add(*seconds),
+            RetryAfter::Date(date) => date.unix_seconds(),
+        }
+    }
+
+    /// `now` (UNIX 時間) から再試行までの待ち時間を取得
+    ///
+    /// HTTP-date が `now` 以前の場合は 0 になる。
+    pub fn delay_from(&self, now: u64) -> Duration {
+        Duration::from_secs(self.retry_at(now).saturating_sub(now))
+    }
+
+    /// delay-seconds 形式かどうか
+    pub fn is_delay(&self) -> bool {
+        matches!(self, RetryAfter::Delay(_))
+    }
+
+    /// HTTP-date 形式かどうか
+    pub fn is_date(&self) -> bool {
+        matches!(self, RetryAfter::Date(_))
+    }
+
+    /// ヘッダー値を生成
+    pub fn to_header_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryAfter::Delay(seconds) => write!(f, "{}", seconds),
+            RetryAfter::Date(date) => write!(f, "{}", date),
+        }
+    }
+}
@@ -0,0 +1,1066 @@
+//! セキュリティ関連のレスポンスヘッダー
+//!
+//! ## 概要
+//!
+//! ブラウザのセキュリティ機能を有効にするレスポンスヘッダーのパースと生成を提供します。
+//!
+//! - Strict-Transport-Security (RFC 6797)
+//! - X-Content-Type-Options (Fetch Standard)
+//! - X-Frame-Options (HTML Standard)
+//! - Referrer-Policy (Referrer Policy)
+//! - Cross-Origin-Opener-Policy / Cross-Origin-Embedder-Policy (HTML Standard)
+//! - Permissions-Policy (Permissions Policy)
+//!
+//! `SecurityHeaders` でまとめて設定し、`Response::security_headers()` でレスポンスに反映します。
+//! `Response::recommended_security_headers()` は推奨のデフォルト値を一度に設定します。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::security_headers::{
+//!     AllowlistEntry, FrameOptions, PermissionsPolicy, SecurityHeaders, StrictTransportSecurity,
+//! };
+//! use shiguredo_http11::{Response, StatusCode};
+//!
+//! // 推奨のデフォルト値を設定
+//! let response = Response::with_status(StatusCode::OK)
+//!     .recommended_security_headers()
+//!     .unwrap();
+//! assert_eq!(
+//!     response.get_header("Strict-Transport-Security"),
+//!     Some("max-age=31536000; includeSubDomains")
+//! );
+//! assert_eq!(response.get_header("X-Content-Type-Options"), Some("nosniff"));
+//! assert_eq!(response.get_header("X-Frame-Options"), Some("DENY"));
+//!
+//! // 個別に設定
+//! let headers = SecurityHeaders::recommended()
+//!     .with_strict_transport_security(
+//!         StrictTransportSecurity::new(63072000)
+//!             .with_include_subdomains()
+//!             .with_preload(),
+//!     )
+//!     .with_frame_options(FrameOptions::SameOrigin)
+//!     .with_permissions_policy(
+//!         PermissionsPolicy::new()
+//!             .with_feature("camera", [])
+//!             .unwrap()
+//!             .with_feature("geolocation", [AllowlistEntry::SelfOrigin])
+//!             .unwrap(),
+//!     );
+//! let response = Response::with_status(StatusCode::OK)
+//!     .security_headers(&headers)
+//!     .unwrap();
+//! assert_eq!(response.get_header("X-Frame-Options"), Some("SAMEORIGIN"));
+//! assert_eq!(
+//!     response.get_header("Permissions-Policy"),
+//!     Some("camera=(), geolocation=(self)")
+//! );
+//!
+//! // パース
+//! let hsts = StrictTransportSecurity::parse("max-age=\"600\"; includeSubDomains").unwrap();
+//! assert_eq!(hsts.max_age(), 600);
+//! assert!(hsts.include_subdomains());
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::cors::Origin;
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::sfv::{self, BareItem, Dictionary, Item, ListMember};
+use crate::typed_header::TypedHeader;
+use crate::validate::{is_valid_token, parse_quoted_string, split_with_quotes, trim_ows};
+
+/// セキュリティヘッダーのパースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SecurityHeaderError {
+    /// 不正な Strict-Transport-Security
+    InvalidStrictTransportSecurity,
+    /// Strict-Transport-Security に max-age がない
+    MissingMaxAge,
+    /// Strict-Transport-Security のディレクティブが重複している
+    DuplicateDirective(String),
+    /// 不正な X-Content-Type-Options
+    InvalidContentTypeOptions,
+    /// 不正な X-Frame-Options
+    InvalidFrameOptions,
+    /// 不正な Referrer-Policy
+    InvalidReferrerPolicy,
+    /// 不正な Cross-Origin-Opener-Policy
+    InvalidOpenerPolicy,
+    /// 不正な Cross-Origin-Embedder-Policy
+    InvalidEmbedderPolicy,
+    /// 不正な report-to エンドポイント名
+    InvalidReportTo,
+    /// 不正な Permissions-Policy
+    InvalidPermissionsPolicy,
+}
+
+impl fmt::Display for SecurityHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecurityHeaderError::InvalidStrictTransportSecurity => {
+                write!(f, "invalid Strict-Transport-Security header")
+            }
+            SecurityHeaderError::MissingMaxAge => {
+                write!(f, "missing max-age in Strict-Transport-Security")
+            }
+            SecurityHeaderError::DuplicateDirective(name) => {
+                write!(f, "duplicate Strict-Transport-Security directive: {}", name)
+            }
+            SecurityHeaderError::InvalidContentTypeOptions => {
+                write!(f, "invalid X-Content-Type-Options header")
+            }
+            SecurityHeaderError::InvalidFrameOptions => write!(f, "invalid X-Frame-Options header"),
+            SecurityHeaderError::InvalidReferrerPolicy => {
+                write!(f, "invalid Referrer-Policy header")
+            }
+            SecurityHeaderError::InvalidOpenerPolicy => {
+                write!(f, "invalid Cross-Origin-Opener-Policy header")
+            }
+            SecurityHeaderError::InvalidEmbedderPolicy => {
+                write!(f, "invalid Cross-Origin-Embedder-Policy header")
+            }
+            SecurityHeaderError::InvalidReportTo => write!(f, "invalid report-to endpoint"),
+            SecurityHeaderError::InvalidPermissionsPolicy => {
+                write!(f, "invalid Permissions-Policy header")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SecurityHeaderError {}
+
+// ========================================
+// Strict-Transport-Security
+// ========================================
+
+/// Strict-Transport-Security ヘッダー (RFC 6797)
+///
+/// 未知のディレクティブは無視する (RFC 6797 Section 6.1)。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrictTransportSecurity {
+    max_age: u64,
+    include_subdomains: bool,
+    preload: bool,
+}
+
+impl StrictTransportSecurity {
+    /// max-age (秒) を指定して作成
+    ///
+    /// max-age に 0 を指定すると、ブラウザは既存の HSTS ポリシーを削除する。
+    pub fn new(max_age: u64) -> Self {
+        StrictTransportSecurity {
+            max_age,
+            include_subdomains: false,
+            preload: false,
+        }
+    }
+
+    /// includeSubDomains を付ける (ビルダーパターン)
+    pub fn with_include_subdomains(mut self) -> Self {
+        self.include_subdomains = true;
+        self
+    }
+
+    /// preload を付ける (ビルダーパターン)
+    ///
+    /// preload は RFC 6797 では定義されていないが、ブラウザの HSTS preload list
+    /// への登録に使われる。
+    pub fn with_preload(mut self) -> Self {
+        self.preload = true;
+        self
+    }
+
+    /// Strict-Transport-Security をパース
+    ///
+    /// ディレクティブ名は大文字小文字を区別しない。
+    /// 同じディレクティブが複数回現れる場合はエラーを返す (RFC 6797 Section 6.1)。
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        let mut max_age = None;
+        let mut include_subdomains = false;
+        let mut preload = false;
+        let mut seen: Vec<String> = Vec::new();
+
+        for directive in split_with_quotes(input, ';') {
+            let directive = trim_ows(&directive);
+            if directive.is_empty() {
+                continue;
+            }
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (trim_ows(name), Some(trim_ows(value))),
+                None => (directive, None),
+            };
+            if !is_valid_token(name) {
+                return Err(SecurityHeaderError::InvalidStrictTransportSecurity);
+            }
+            let name = name.to_ascii_lowercase();
+            if seen.contains(&name) {
+                return Err(SecurityHeaderError::DuplicateDirective(name));
+            }
+            let value = value.map(parse_directive_value).transpose()?;
+
+            match name.as_str() {
+                "max-age" => {
+                    let value = value.ok_or(SecurityHeaderError::InvalidStrictTransportSecurity)?;
+                    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                        return Err(SecurityHeaderError::InvalidStrictTransportSecurity);
+                    }
+                    // 非常に大きな値は u64::MAX に丸める
+                    max_age = Some(value.parse::<u64>().unwrap_or(u64::MAX));
+                }
+                "includesubdomains" => {
+                    if value.is_some() {
+                        return Err(SecurityHeaderError::InvalidStrictTransportSecurity);
+                    }
+                    include_subdomains = true;
+                }
+                "preload" => {
+                    if value.is_some() {
+                        return Err(SecurityHeaderError::InvalidStrictTransportSecurity);
+                    }
+                    preload = true;
+                }
+                _ => {}
+            }
+            seen.push(name);
+        }
+
+        Ok(StrictTransportSecurity {
+            max_age: max_age.ok_or(SecurityHeaderError::MissingMaxAge)?,
+            include_subdomains,
+            preload,
+        })
+    }
+
+    /// max-age (秒)
+    pub fn max_age(&self) -> u64 {
+        self.max_age
+    }
+
+    /// includeSubDomains が付いているか
+    pub fn include_subdomains(&self) -> bool {
+        self.include_subdomains
+    }
+
+    /// preload が付いているか
+    pub fn preload(&self) -> bool {
+        self.preload
+    }
+}
+
+/// directive-value = token / quoted-string
+fn parse_directive_value(value: &str) -> Result<String, SecurityHeaderError> {
+    if let Some(rest) = value.strip_prefix('"') {
+        let (value, rest) = parse_quoted_string(rest)
+            .map_err(|_| SecurityHeaderError::InvalidStrictTransportSecurity)?;
+        if !rest.is_empty() {
+            return Err(SecurityHeaderError::InvalidStrictTransportSecurity);
+        }
+        Ok(value)
+    } else if is_valid_token(value) {
+        Ok(value.to_string())
+    } else {
+        Err(SecurityHeaderError::InvalidStrictTransportSecurity)
+    }
+}
+
+impl fmt::Display for StrictTransportSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "max-age={}", self.max_age)?;
+        if self.include_subdomains {
+            write!(f, "; includeSubDomains")?;
+        }
+        if self.preload {
+            write!(f, "; preload")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for StrictTransportSecurity {
+    const NAME: &'static str = "Strict-Transport-Security";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// X-Content-Type-Options
+// ========================================
+
+/// X-Content-Type-Options ヘッダー (Fetch Standard)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentTypeOptions {
+    /// `nosniff`
+    NoSniff,
+}
+
+impl ContentTypeOptions {
+    /// X-Content-Type-Options をパース
+    ///
+    /// Fetch Standard に従い、カンマ区切りの最初の値が `nosniff`
+    /// (大文字小文字を区別しない) の場合のみ受理する。
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        let first = input.split(',').next().map(trim_ows).unwrap_or_default();
+        if first.eq_ignore_ascii_case("nosniff") {
+            Ok(ContentTypeOptions::NoSniff)
+        } else {
+            Err(SecurityHeaderError::InvalidContentTypeOptions)
+        }
+    }
+}
+
+impl fmt::Display for ContentTypeOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentTypeOptions::NoSniff => write!(f, "nosniff"),
+        }
+    }
+}
+
+impl TypedHeader for ContentTypeOptions {
+    const NAME: &'static str = "X-Content-Type-Options";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// X-Frame-Options
+// ========================================
+
+/// X-Frame-Options ヘッダー (HTML Standard)
+///
+/// 廃止された `ALLOW-FROM` には対応しない。
+/// 埋め込みを許可するオリジンを指定する場合は CSP の frame-ancestors を使う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameOptions {
+    /// `DENY`
+    Deny,
+    /// `SAMEORIGIN`
+    SameOrigin,
+}
+
+impl FrameOptions {
+    /// X-Frame-Options をパース
+    ///
+    /// 大文字小文字を区別しない。カンマ区切りで複数の値がある場合は、
+    /// すべて同じ値の場合のみ受理する (HTML Standard)。
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        let mut result = None;
+        for value in input.split(',').map(trim_ows) {
+            let option = if value.eq_ignore_ascii_case("deny") {
+                FrameOptions::Deny
+            } else if value.eq_ignore_ascii_case("sameorigin") {
+                FrameOptions::SameOrigin
+            } else {
+                return Err(SecurityHeaderError::InvalidFrameOptions);
+            };
+            if result.is_some_and(|result| result != option) {
+                return Err(SecurityHeaderError::InvalidFrameOptions);
+            }
+            result = Some(option);
+        }
+        result.ok_or(SecurityHeaderError::InvalidFrameOptions)
+    }
+}
+
+impl fmt::Display for FrameOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameOptions::Deny => write!(f, "DENY"),
+            FrameOptions::SameOrigin => write!(f, "SAMEORIGIN"),
+        }
+    }
+}
+
+impl TypedHeader for FrameOptions {
+    const NAME: &'static str = "X-Frame-Options";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// Referrer-Policy
+// ========================================
+
+/// Referrer-Policy ヘッダー (Referrer Policy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferrerPolicy {
+    /// `no-referrer`
+    NoReferrer,
+    /// `no-referrer-when-downgrade`
+    NoReferrerWhenDowngrade,
+    /// `same-origin`
+    SameOrigin,
+    /// `origin`
+    Origin,
+    /// `strict-origin`
+    StrictOrigin,
+    /// `origin-when-cross-origin`
+    OriginWhenCrossOrigin,
+    /// `strict-origin-when-cross-origin` (ブラウザのデフォルト)
+    StrictOriginWhenCrossOrigin,
+    /// `unsafe-url`
+    UnsafeUrl,
+}
+
+impl ReferrerPolicy {
+    /// Referrer-Policy をパース
+    ///
+    /// カンマ区切りの値のうち、認識できる最後の値を採用する。
+    /// 認識できない値は無視する (Referrer Policy Section 8.1)。
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        input
+            .split(',')
+            .rev()
+            .map(trim_ows)
+            .find_map(ReferrerPolicy::from_token)
+            .ok_or(SecurityHeaderError::InvalidReferrerPolicy)
+    }
+
+    fn from_token(token: &str) -> Option<Self> {
+        let policy = match token {
+            "no-referrer" => ReferrerPolicy::NoReferrer,
+            "no-referrer-when-downgrade" => ReferrerPolicy::NoReferrerWhenDowngrade,
+            "same-origin" => ReferrerPolicy::SameOrigin,
+            "origin" => ReferrerPolicy::Origin,
+            "strict-origin" => ReferrerPolicy::StrictOrigin,
+            "origin-when-cross-origin" => ReferrerPolicy::OriginWhenCrossOrigin,
+            "strict-origin-when-cross-origin" => ReferrerPolicy::StrictOriginWhenCrossOrigin,
+            "unsafe-url" => ReferrerPolicy::UnsafeUrl,
+            _ => return None,
+        };
+        Some(policy)
+    }
+
+    /// ポリシー名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferrerPolicy::NoReferrer => "no-referrer",
+            ReferrerPolicy::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            ReferrerPolicy::SameOrigin => "same-origin",
+            ReferrerPolicy::Origin => "origin",
+            ReferrerPolicy::StrictOrigin => "strict-origin",
+            ReferrerPolicy::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            ReferrerPolicy::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            ReferrerPolicy::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+impl fmt::Display for ReferrerPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TypedHeader for ReferrerPolicy {
+    const NAME: &'static str = "Referrer-Policy";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// Cross-Origin-Opener-Policy / Cross-Origin-Embedder-Policy
+// ========================================
+
+/// Cross-Origin-Opener-Policy の値
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OpenerPolicy {
+    /// `unsafe-none`
+    UnsafeNone,
+    /// `same-origin-allow-popups`
+    SameOriginAllowPopups,
+    /// `same-origin`
+    SameOrigin,
+    /// `noopener-allow-popups`
+    NoopenerAllowPopups,
+}
+
+impl OpenerPolicy {
+    /// ポリシー名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpenerPolicy::UnsafeNone => "unsafe-none",
+            OpenerPolicy::SameOriginAllowPopups => "same-origin-allow-popups",
+            OpenerPolicy::SameOrigin => "same-origin",
+            OpenerPolicy::NoopenerAllowPopups => "noopener-allow-popups",
+        }
+    }
+}
+
+/// Cross-Origin-Opener-Policy ヘッダー (HTML Standard)
+///
+/// Structured Field の Item で、`report-to` パラメーター以外は無視する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossOriginOpenerPolicy {
+    policy: OpenerPolicy,
+    report_to: Option<String>,
+}
+
+impl CrossOriginOpenerPolicy {
+    /// ポリシーを指定して作成
+    pub fn new(policy: OpenerPolicy) -> Self {
+        CrossOriginOpenerPolicy {
+            policy,
+            report_to: None,
+        }
+    }
+
+    /// 違反レポートの送信先エンドポイント名を設定 (ビルダーパターン)
+    pub fn with_report_to(mut self, endpoint: &str) -> Result<Self, SecurityHeaderError> {
+        self.report_to = Some(validate_report_to(endpoint)?);
+        Ok(self)
+    }
+
+    /// Cross-Origin-Opener-Policy をパース
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        let (token, report_to) =
+            parse_policy_item(input).ok_or(SecurityHeaderError::InvalidOpenerPolicy)?;
+        let policy = match token.as_str() {
+            "unsafe-none" => OpenerPolicy::UnsafeNone,
+            "same-origin-allow-popups" => OpenerPolicy::SameOriginAllowPopups,
+            "same-origin" => OpenerPolicy::SameOrigin,
+            "noopener-allow-popups" => OpenerPolicy::NoopenerAllowPopups,
+            _ => return Err(SecurityHeaderError::InvalidOpenerPolicy),
+        };
+        Ok(CrossOriginOpenerPolicy { policy, report_to })
+    }
+
+    /// ポリシー
+    pub fn policy(&self) -> OpenerPolicy {
+        self.policy
+    }
+
+    /// 違反レポートの送信先エンドポイント名
+    pub fn report_to(&self) -> Option<&str> {
+        self.report_to.as_deref()
+    }
+}
+
+impl fmt::Display for CrossOriginOpenerPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_policy_item(f, self.policy.as_str(), self.report_to.as_deref())
+    }
+}
+
+impl TypedHeader for CrossOriginOpenerPolicy {
+    const NAME: &'static str = "Cross-Origin-Opener-Policy";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Cross-Origin-Embedder-Policy の値
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmbedderPolicy {
+    /// `unsafe-none`
+    UnsafeNone,
+    /// `require-corp`
+    RequireCorp,
+    /// `credentialless`
+    Credentialless,
+}
+
+impl EmbedderPolicy {
+    /// ポリシー名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbedderPolicy::UnsafeNone => "unsafe-none",
+            EmbedderPolicy::RequireCorp => "require-corp",
+            EmbedderPolicy::Credentialless => "credentialless",
+        }
+    }
+}
+
+/// Cross-Origin-Embedder-Policy ヘッダー (HTML Standard)
+///
+/// Structured Field の Item で、`report-to` パラメーター以外は無視する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossOriginEmbedderPolicy {
+    policy: EmbedderPolicy,
+    report_to: Option<String>,
+}
+
+impl CrossOriginEmbedderPolicy {
+    /// ポリシーを指定して作成
+    pub fn new(policy: EmbedderPolicy) -> Self {
+        CrossOriginEmbedderPolicy {
+            policy,
+            report_to: None,
+        }
+    }
+
+    /// 違反レポートの送信先エンドポイント名を設定 (ビルダーパターン)
+    pub fn with_report_to(mut self, endpoint: &str) -> Result<Self, SecurityHeaderError> {
+        self.report_to = Some(validate_report_to(endpoint)?);
+        Ok(self)
+    }
+
+    /// Cross-Origin-Embedder-Policy をパース
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        let (token, report_to) =
+            parse_policy_item(input).ok_or(SecurityHeaderError::InvalidEmbedderPolicy)?;
+        let policy = match token.as_str() {
+            "unsafe-none" => EmbedderPolicy::UnsafeNone,
+            "require-corp" => EmbedderPolicy::RequireCorp,
+            "credentialless" => EmbedderPolicy::Credentialless,
+            _ => return Err(SecurityHeaderError::InvalidEmbedderPolicy),
+        };
+        Ok(CrossOriginEmbedderPolicy { policy, report_to })
+    }
+
+    /// ポリシー
+    pub fn policy(&self) -> EmbedderPolicy {
+        self.policy
+    }
+
+    /// 違反レポートの送信先エンドポイント名
+    pub fn report_to(&self) -> Option<&str> {
+        self.report_to.as_deref()
+    }
+}
+
+impl fmt::Display for CrossOriginEmbedderPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_policy_item(f, self.policy.as_str(), self.report_to.as_deref())
+    }
+}
+
+impl TypedHeader for CrossOriginEmbedderPolicy {
+    const NAME: &'static str = "Cross-Origin-Embedder-Policy";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// report-to のエンドポイント名は sf-string に格納できる印字可能 ASCII のみ
+fn validate_report_to(endpoint: &str) -> Result<String, SecurityHeaderError> {
+    if sfv::is_valid_string(endpoint) {
+        Ok(endpoint.to_string())
+    } else {
+        Err(SecurityHeaderError::InvalidReportTo)
+    }
+}
+
+/// `token *( ";" key [ "=" bare-item ] )` をパースし、token と report-to を返す
+fn parse_policy_item(input: &str) -> Option<(String, Option<String>)> {
+    let item = Item::parse(trim_ows(input)).ok()?;
+    let token = item.bare_item().as_token()?.to_string();
+    let report_to = item
+        .params()
+        .get("report-to")
+        .and_then(BareItem::as_string)
+        .map(ToString::to_string);
+    Some((token, report_to))
+}
+
+fn write_policy_item(
+    f: &mut fmt::Formatter<'_>,
+    token: &str,
+    report_to: Option<&str>,
+) -> fmt::Result {
+    f.write_str(token)?;
+    if let Some(endpoint) = report_to {
+        write!(f, ";report-to=")?;
+        sfv::write_string(f, endpoint)?;
+    }
+    Ok(())
+}
+
+// ========================================
+// Permissions-Policy
+// ========================================
+
+/// Permissions-Policy の allowlist の要素
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowlistEntry {
+    /// `*` (すべてのオリジン)
+    Any,
+    /// `self`
+    SelfOrigin,
+    /// `src` (iframe の src 属性のオリジン)
+    Src,
+    /// オリジン (`"https://example.com"`)
+    Origin(Origin),
+}
+
+impl fmt::Display for AllowlistEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllowlistEntry::Any => write!(f, "*"),
+            AllowlistEntry::SelfOrigin => write!(f, "self"),
+            AllowlistEntry::Src => write!(f, "src"),
+            AllowlistEntry::Origin(origin) => write!(f, "\"{}\"", origin),
+        }
+    }
+}
+
+/// Permissions-Policy ヘッダー (Permissions Policy)
+///
+/// Structured Field の Dictionary で、キーが機能名、値が allowlist。
+/// 空の allowlist (`()`) はその機能を無効にする。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionsPolicy {
+    features: Vec<(String, Vec<AllowlistEntry>)>,
+}
+
+impl PermissionsPolicy {
+    /// 空の Permissions-Policy を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 機能と allowlist を追加 (ビルダーパターン)
+    ///
+    /// 同じ機能がすでにある場合は allowlist を置き換える。
+    /// 機能名は sf-key (小文字英字または `*` で始まる) でなければならない。
+    pub fn with_feature<I>(
+        mut self,
+        feature: &str,
+        allowlist: I,
+    ) -> Result<Self, SecurityHeaderError>
+    where
+        I: IntoIterator<Item = AllowlistEntry>,
+    {
+        if !sfv::is_valid_key(feature) {
+            return Err(SecurityHeaderError::InvalidPermissionsPolicy);
+        }
+        self.insert(feature.to_string(), allowlist.into_iter().collect());
+        Ok(self)
+    }
+
+    fn insert(&mut self, feature: String, allowlist: Vec<AllowlistEntry>) {
+        match self.features.iter_mut().find(|(name, _)| *name == feature) {
+            Some(entry) => entry.1 = allowlist,
+            None => self.features.push((feature, allowlist)),
+        }
+    }
+
+    /// Permissions-Policy をパース
+    ///
+    /// 同じ機能が複数回現れる場合は最後の値を採用する。
+    /// allowlist 内の未知のトークンやオリジンとして解釈できない文字列は無視する。
+    pub fn parse(input: &str) -> Result<Self, SecurityHeaderError> {
+        let dictionary =
+            Dictionary::parse(input).map_err(|_| SecurityHeaderError::InvalidPermissionsPolicy)?;
+        let mut policy = PermissionsPolicy::new();
+        for (feature, member) in dictionary.iter() {
+            let allowlist =
+                parse_allowlist(member).ok_or(SecurityHeaderError::InvalidPermissionsPolicy)?;
+            policy.insert(feature.to_string(), allowlist);
+        }
+        Ok(policy)
+    }
+
+    /// 機能の allowlist を取得
+    pub fn allowlist(&self, feature: &str) -> Option<&[AllowlistEntry]> {
+        self.features
+            .iter()
+            .find(|(name, _)| name == feature)
+            .map(|(_, allowlist)| allowlist.as_slice())
+    }
+
+    /// 機能と allowlist の一覧
+    pub fn features(&self) -> &[(String, Vec<AllowlistEntry>)] {
+        &self.features
+    }
+
+    /// 機能が空かどうか
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+}
+
+/// allowlist をパースする
+///
+/// 値のない機能 (`camera`) は allowlist として解釈できないため `None` を返す。
+fn parse_allowlist(member: &ListMember) -> Option<Vec<AllowlistEntry>> {
+    let items = match member {
+        ListMember::Item(item) if *item.bare_item() == BareItem::Boolean(true) => return None,
+        ListMember::Item(item) => core::slice::from_ref(item),
+        ListMember::InnerList(inner_list) => inner_list.items(),
+    };
+
+    Some(
+        items
+            .iter()
+            .filter_map(|item| match item.bare_item() {
+                BareItem::Token(token) => match token.as_str() {
+                    "*" => Some(AllowlistEntry::Any),
+                    "self" => Some(AllowlistEntry::SelfOrigin),
+                    "src" => Some(AllowlistEntry::Src),
+                    _ => None,
+                },
+                BareItem::String(value) => Origin::parse(value)
+                    .ok()
+                    .filter(|origin| !origin.is_null())
+                    .map(AllowlistEntry::Origin),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+impl fmt::Display for PermissionsPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (feature, allowlist)) in self.features.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}=", feature)?;
+            if let [AllowlistEntry::Any] = allowlist.as_slice() {
+                write!(f, "*")?;
+                continue;
+            }
+            write!(f, "(")?;
+            for (j, entry) in allowlist.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", entry)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for PermissionsPolicy {
+    const NAME: &'static str = "Permissions-Policy";
+    type Error = SecurityHeaderError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+// ========================================
+// まとめて設定
+// ========================================
+
+/// レスポンスに付けるセキュリティヘッダーの組み合わせ
+///
+/// `SecurityHeaders::new()` はすべて未設定、`SecurityHeaders::recommended()` は
+/// 多くのアプリケーションで安全に使える値を設定した状態で作成する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityHeaders {
+    strict_transport_security: Option<StrictTransportSecurity>,
+    content_type_options: Option<ContentTypeOptions>,
+    frame_options: Option<FrameOptions>,
+    referrer_policy: Option<ReferrerPolicy>,
+    opener_policy: Option<CrossOriginOpenerPolicy>,
+    embedder_policy: Option<CrossOriginEmbedderPolicy>,
+    permissions_policy: Option<PermissionsPolicy>,
+}
+
+impl SecurityHeaders {
+    /// すべて未設定の状態で作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 推奨のデフォルト値で作成
+    ///
+    /// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+    /// - `X-Content-Type-Options: nosniff`
+    /// - `X-Frame-Options: DENY`
+    /// - `Referrer-Policy: strict-origin-when-cross-origin`
+    /// - `Cross-Origin-Opener-Policy: same-origin`
+    ///
+    /// Cross-Origin-Embedder-Policy と Permissions-Policy はサブリソースや利用する機能に
+    /// 依存するため設定しない。
+    pub fn recommended() -> Self {
+        SecurityHeaders {
+            strict_transport_security: Some(
+                StrictTransportSecurity::new(31_536_000).with_include_subdomains(),
+            ),
+            content_type_options: Some(ContentTypeOptions::NoSniff),
+            frame_options: Some(FrameOptions::Deny),
+            referrer_policy: Some(ReferrerPolicy::StrictOriginWhenCrossOrigin),
+            opener_policy: Some(CrossOriginOpenerPolicy::new(OpenerPolicy::SameOrigin)),
+            embedder_policy: None,
+            permissions_policy: None,
+        }
+    }
+
+    /// Strict-Transport-Security を設定 (ビルダーパターン)
+    pub fn with_strict_transport_security(mut self, value: StrictTransportSecurity) -> Self {
+        self.strict_transport_security = Some(value);
+        self
+    }
+
+    /// Strict-Transport-Security を設定しない (ビルダーパターン)
+    pub fn without_strict_transport_security(mut self) -> Self {
+        self.strict_transport_security = None;
+        self
+    }
+
+    /// X-Content-Type-Options を設定 (ビルダーパターン)
+    pub fn with_content_type_options(mut self, value: ContentTypeOptions) -> Self {
+        self.content_type_options = Some(value);
+        self
+    }
+
+    /// X-Content-Type-Options を設定しない (ビルダーパターン)
+    pub fn without_content_type_options(mut self) -> Self {
+        self.content_type_options = None;
+        self
+    }
+
+    /// X-Frame-Options を設定 (ビルダーパターン)
+    pub fn with_frame_options(mut self, value: FrameOptions) -> Self {
+        self.frame_options = Some(value);
+        self
+    }
+
+    /// X-Frame-Options を設定しない (ビルダーパターン)
+    pub fn without_frame_options(mut self) -> Self {
+        self.frame_options = None;
+        self
+    }
+
+    /// Referrer-Policy を設定 (ビルダーパターン)
+    pub fn with_referrer_policy(mut self, value: ReferrerPolicy) -> Self {
+        self.referrer_policy = Some(value);
+        self
+    }
+
+    /// Referrer-Policy を設定しない (ビルダーパターン)
+    pub fn without_referrer_policy(mut self) -> Self {
+        self.referrer_policy = None;
+        self
+    }
+
+    /// Cross-Origin-Opener-Policy を設定 (ビルダーパターン)
+    pub fn with_opener_policy(mut self, value: CrossOriginOpenerPolicy) -> Self {
+        self.opener_policy = Some(value);
+        self
+    }
+
+    /// Cross-Origin-Opener-Policy を設定しない (ビルダーパターン)
+    pub fn without_opener_policy(mut self) -> Self {
+        self.opener_policy = None;
+        self
+    }
+
+    /// Cross-Origin-Embedder-Policy を設定 (ビルダーパターン)
+    pub fn with_embedder_policy(mut self, value: CrossOriginEmbedderPolicy) -> Self {
+        self.embedder_policy = Some(value);
+        self
+    }
+
+    /// Cross-Origin-Embedder-Policy を設定しない (ビルダーパターン)
+    pub fn without_embedder_policy(mut self) -> Self {
+        self.embedder_policy = None;
+        self
+    }
+
+    /// Permissions-Policy を設定 (ビルダーパターン)
+    pub fn with_permissions_policy(mut self, value: PermissionsPolicy) -> Self {
+        self.permissions_policy = Some(value);
+        self
+    }
+
+    /// Permissions-Policy を設定しない (ビルダーパターン)
+    pub fn without_permissions_policy(mut self) -> Self {
+        self.permissions_policy = None;
+        self
+    }
+
+    /// 設定されているヘッダーの名前と値の一覧
+    pub fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        push_typed(&mut headers, self.strict_transport_security.as_ref());
+        push_typed(&mut headers, self.content_type_options.as_ref());
+        push_typed(&mut headers, self.frame_options.as_ref());
+        push_typed(&mut headers, self.referrer_policy.as_ref());
+        push_typed(&mut headers, self.opener_policy.as_ref());
+        push_typed(&mut headers, self.embedder_policy.as_ref());
+        push_typed(
+            &mut headers,
+            self.permissions_policy
+                .as_ref()
+                .filter(|policy| !policy.is_empty()),
+        );
+        headers
+    }
+}
+
+fn push_typed<T: TypedHeader>(headers: &mut Vec<(String, String)>, value: Option<&T>) {
+    if let Some(value) = value {
+        headers.push((T::NAME.to_string(), value.encode_value()));
+    }
+}
+
+impl Response {
+    /// セキュリティヘッダーを付ける (ビルダーパターン)
+    ///
+    /// アプリケーションがすでに設定しているヘッダーは上書きしない。
+    pub fn security_headers(mut self, headers: &SecurityHeaders) -> Result<Self, EncodeError> {
+        for (name, value) in headers.headers() {
+            if !self.has_header(&name) {
+                self.add_header(name, value)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// 推奨のデフォルト値のセキュリティヘッダーを付ける (ビルダーパターン)
+    ///
+    /// `SecurityHeaders::recommended()` を `Response::security_headers()` で付ける。
+    pub fn recommended_security_headers(self) -> Result<Self, EncodeError> {
+        self.security_headers(&SecurityHeaders::recommended())
+    }
+}
@@ -0,0 +1,412 @@
+//! Server-Timing ヘッダー (W3C Server Timing)
+//!
+//! ## 概要
+//!
+//! W3C Server Timing に基づいた Server-Timing ヘッダーのパースと生成を提供します。
+//!
+//! サーバーはリクエスト処理の内訳 (DB 問い合わせ、キャッシュ参照など) を
+//! メトリクスとしてレスポンスに付け、クライアントやプロキシはそれを読み取る。
+//!
+//! - メトリクスは名前と、任意の `dur` (ミリ秒) / `desc` (説明) を持つ
+//! - `dur` はマイクロ秒の精度で保持する
+//! - 複数の Server-Timing ヘッダー (トレーラーを含む) は 1 つのリストとして連結する
+//! - 同じパラメーターが複数ある場合は最初のものを使う
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Server-Timing             = #server-timing-metric
+//! server-timing-metric      = metric-name *( OWS ";" OWS server-timing-param )
+//! metric-name               = token
+//! server-timing-param       = server-timing-param-name OWS "=" OWS server-timing-param-value
+//! server-timing-param-name  = token
+//! server-timing-param-value = token / quoted-string
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use core::time::Duration;
+//! use shiguredo_http11::server_timing::{Metric, ServerTiming};
+//! use shiguredo_http11::{Response, StatusCode};
+//!
+//! // サーバー: 処理時間の内訳を付ける
+//! let timing = ServerTiming::new()
+//!     .with_metric(
+//!         Metric::new("db")
+//!             .unwrap()
+//!             .with_duration(Duration::from_micros(53_250))
+//!             .with_description("Primary DB")
+//!             .unwrap(),
+//!     )
+//!     .with_metric(Metric::new("cache").unwrap().with_description("hit").unwrap());
+//! let response = Response::with_status(StatusCode::OK)
+//!     .server_timing(&timing)
+//!     .unwrap();
+//! assert_eq!(
+//!     response.get_header("Server-Timing"),
+//!     Some("db;dur=53.25;desc=\"Primary DB\", cache;desc=hit")
+//! );
+//!
+//! // クライアント: メトリクスを読む
+//! let timing = ServerTiming::from_message(&response).unwrap().unwrap();
+//! assert_eq!(timing.get("db").unwrap().duration(), Some(Duration::from_micros(53_250)));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::response::Response;
+use crate::typed_header::TypedHeader;
+use crate::validate::{
+    QuotedStringError, escape_quotes, is_valid_field_value, is_valid_token, parse_quoted_string,
+    split_with_quotes, trim_ows,
+};
+
+/// Server-Timing パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ServerTimingError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正なメトリクス名
+    InvalidName,
+    /// 不正なパラメーター
+    InvalidParameter,
+    /// 不正な dur の値
+    InvalidDuration,
+    /// quoted-string の閉じ DQUOTE が見つからない (RFC 9110 Section 5.6.4)
+    UnterminatedQuote,
+}
+
+impl fmt::Display for ServerTimingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerTimingError::InvalidFormat => write!(f, "invalid Server-Timing header format"),
+            ServerTimingError::InvalidName => write!(f, "invalid metric name"),
+            ServerTimingError::InvalidParameter => write!(f, "invalid metric parameter"),
+            ServerTimingError::InvalidDuration => write!(f, "invalid metric duration"),
+            ServerTimingError::UnterminatedQuote => write!(f, "unterminated quoted-string"),
+        }
+    }
+}
+
+impl core::error::Error for ServerTimingError {}
+
+impl From<QuotedStringError> for ServerTimingError {
+    fn from(e: QuotedStringError) -> Self {
+        match e {
+            QuotedStringError::InvalidQdtext | QuotedStringError::InvalidQuotedPair => {
+                ServerTimingError::InvalidParameter
+            }
+            QuotedStringError::Unterminated => ServerTimingError::UnterminatedQuote,
+        }
+    }
+}
+
+/// Server-Timing ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerTiming {
+    metrics: Vec<Metric>,
+}
+
+impl ServerTiming {
+    /// 空の Server-Timing を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Server-Timing ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 受信者は空のリスト要素を無視しなければならない (MUST)。
+    pub fn parse(input: &str) -> Result<Self, ServerTimingError> {
+        let mut metrics = Vec::new();
+        for part in split_with_quotes(trim_ows(input), ',') {
+            let part = trim_ows(&part);
+            if part.is_empty() {
+                continue;
+            }
+            metrics.push(Metric::parse(part)?);
+        }
+        Ok(ServerTiming { metrics })
+    }
+
+    /// メッセージの Server-Timing ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, ServerTimingError> {
+        let values = message.get_headers("Server-Timing");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// メトリクスを追加 (ビルダーパターン)
+    pub fn with_metric(mut self, metric: Metric) -> Self {
+        self.metrics.push(metric);
+        self
+    }
+
+    /// 別の Server-Timing のメトリクスを後ろに連結する (ビルダーパターン)
+    ///
+    /// ヘッダーとトレーラーの Server-Timing をまとめる場合や、
+    /// プロキシが上流のメトリクスに自身のメトリクスを加える場合に使う。
+    pub fn merge(mut self, other: ServerTiming) -> Self {
+        self.metrics.extend(other.metrics);
+        self
+    }
+
+    /// メトリクスの一覧
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// 名前でメトリクスを検索 (最初に一致したもの)
+    pub fn get(&self, name: &str) -> Option<&Metric> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+
+    /// メトリクスがないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+}
+
+impl fmt::Display for ServerTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, metric) in self.metrics.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", metric)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypedHeader for ServerTiming {
+    const NAME: &'static str = "Server-Timing";
+    type Error = ServerTimingError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Server-Timing のメトリクス
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metric {
+    name: String,
+    duration_micros: Option<u64>,
+    description: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl Metric {
+    /// メトリクス名を指定して作成
+    pub fn new(name: &str) -> Result<Self, ServerTimingError> {
+        if !is_valid_token(name) {
+            return Err(ServerTimingError::InvalidName);
+        }
+        Ok(Metric {
+            name: name.to_string(),
+            duration_micros: None,
+            description: None,
+            params: Vec::new(),
+        })
+    }
+
+    fn parse(input: &str) -> Result<Self, ServerTimingError> {
+        let mut parts = split_with_quotes(input, ';').into_iter();
+        let name_raw = parts.next().unwrap_or_default();
+        let name = trim_ows(&name_raw);
+        if name.is_empty() {
+            return Err(ServerTimingError::InvalidFormat);
+        }
+        let mut metric = Metric::new(name)?;
+        let mut dur_seen = false;
+        let mut desc_seen = false;
+        for param in parts {
+            let param = trim_ows(&param);
+            if param.is_empty() {
+                continue;
+            }
+            let (name, value) = param
+                .split_once('=')
+                .ok_or(ServerTimingError::InvalidParameter)?;
+            let name = trim_ows(name);
+            if !is_valid_token(name) {
+                return Err(ServerTimingError::InvalidParameter);
+            }
+            let value = parse_param_value(value)?;
+            // 同じパラメーターは最初のものを使う
+            if name.eq_ignore_ascii_case("dur") {
+                if !dur_seen {
+                    metric.duration_micros = Some(parse_duration(&value)?);
+                    dur_seen = true;
+                }
+            } else if name.eq_ignore_ascii_case("desc") {
+                if !desc_seen {
+                    metric.description = Some(value);
+                    desc_seen = true;
+                }
+            } else {
+                metric.params.push((name.to_ascii_lowercase(), value));
+            }
+        }
+        Ok(metric)
+    }
+
+    /// 処理時間を設定 (ビルダーパターン)
+    ///
+    /// マイクロ秒未満は切り捨てる。
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration_micros = Some(u64::try_from(duration.as_micros()).unwrap_or(u64::MAX));
+        self
+    }
+
+    /// 説明を設定 (ビルダーパターン)
+    pub fn with_description(mut self, description: &str) -> Result<Self, ServerTimingError> {
+        if !is_valid_field_value(description) {
+            return Err(ServerTimingError::InvalidParameter);
+        }
+        self.description = Some(description.to_string());
+        Ok(self)
+    }
+
+    /// dur / desc 以外のパラメーターを追加 (ビルダーパターン)
+    pub fn with_param(mut self, name: &str, value: &str) -> Result<Self, ServerTimingError> {
+        if !is_valid_token(name)
+            || name.eq_ignore_ascii_case("dur")
+            || name.eq_ignore_ascii_case("desc")
+            || !is_valid_field_value(value)
+        {
+            return Err(ServerTimingError::InvalidParameter);
+        }
+        self.params
+            .push((name.to_ascii_lowercase(), value.to_string()));
+        Ok(self)
+    }
+
+    /// メトリクス名
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 処理時間
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_micros.map(Duration::from_micros)
+    }
+
+    /// 処理時間 (ミリ秒)
+    pub fn duration_ms(&self) -> Option<f64> {
+        self.duration_micros.map(|micros| micros as f64 / 1000.0)
+    }
+
+    /// 説明
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// dur / desc 以外のパラメーター (名前は小文字)
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(micros) = self.duration_micros {
+            write!(f, ";dur={}", micros / 1000)?;
+            let frac = micros % 1000;
+            if frac != 0 {
+                let mut frac = alloc::format!("{:03}", frac);
+                while frac.ends_with('0') {
+                    frac.pop();
+                }
+                write!(f, ".{}", frac)?;
+            }
+        }
+        if let Some(description) = &self.description {
+            write!(f, ";desc=")?;
+            write_param_value(f, description)?;
+        }
+        for (name, value) in &self.params {
+            write!(f, ";{}=", name)?;
+            write_param_value(f, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl Response {
+    /// Server-Timing ヘッダーを追加 (ビルダーパターン)
+    ///
+    /// 既存の Server-Timing は残し、新しい行として追加する。メトリクスがない場合は何もしない。
+    pub fn server_timing(mut self, timing: &ServerTiming) -> Result<Self, EncodeError> {
+        if !timing.is_empty() {
+            self.add_header(ServerTiming::NAME, timing.to_string())?;
+        }
+        Ok(self)
+    }
+}
+
+fn parse_param_value(input: &str) -> Result<String, ServerTimingError> {
+    let input = trim_ows(input);
+    if let Some(rest) = input.strip_prefix('"') {
+        let (value, remaining) = parse_quoted_string(rest)?;
+        if !trim_ows(remaining).is_empty() {
+            return Err(ServerTimingError::InvalidParameter);
+        }
+        Ok(value)
+    } else if !is_valid_token(input) {
+        Err(ServerTimingError::InvalidParameter)
+    } else {
+        Ok(input.to_string())
+    }
+}
+
+/// dur の値 (ミリ秒の 10 進数) をマイクロ秒に変換する
+///
+/// 小数点以下 4 桁目以降は切り捨て、上限を超える値は上限に丸める。
+fn parse_duration(input: &str) -> Result<u64, ServerTimingError> {
+    let (integer, fraction) = input.split_once('.').unwrap_or((input, ""));
+    if integer.is_empty()
+        || !integer.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+        || (input.contains('.') && fraction.is_empty())
+    {
+        return Err(ServerTimingError::InvalidDuration);
+    }
+    let mut micros: u64 = 0;
+    for b in integer.bytes() {
+        micros = micros
+            .saturating_mul(10)
+            .saturating_add(u64::from(b - b'0'));
+    }
+    micros = micros.saturating_mul(1000);
+    let mut scale = 100;
+    for b in fraction.bytes().take(3) {
+        micros = micros.saturating_add(u64::from(b - b'0') * scale);
+        scale /= 10;
+    }
+    Ok(micros)
+}
+
+fn write_param_value(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    if is_valid_token(value) {
+        write!(f, "{}", value)
+    } else {
+        write!(f, "\"{}\"", escape_quotes(value))
+    }
+}
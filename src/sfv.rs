@@ -0,0 +1,1082 @@
+//! Structured Field Values (RFC 8941 / RFC 9651)
+//!
+//! ## 概要
+//!
+//! RFC 9651 (RFC 8941 の改訂版) に基づいた Structured Field Values の
+//! パースとシリアライズを提供します。
+//! Priority / Cache-Status / Proxy-Status / Signature-Input などの
+//! Structured Field で定義されたヘッダーを扱うモジュールの土台として使います。
+//!
+//! - Item / List / Dictionary の 3 種類のトップレベル型
+//! - Integer / Decimal / String / Token / Byte Sequence / Boolean の bare-item
+//! - RFC 9651 で追加された Date / Display String の bare-item
+//! - Item と Inner List の Parameters
+//!
+//! パースは RFC 9651 Section 4.2 のアルゴリズムに従い、
+//! 1 箇所でも不正な部分があればフィールド全体をエラーにする。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! sf-list       = list-member *( OWS "," OWS list-member )
+//! list-member   = sf-item / inner-list
+//! inner-list    = "(" *SP [ sf-item *( 1*SP sf-item ) *SP ] ")" parameters
+//! sf-dictionary = dict-member *( OWS "," OWS dict-member )
+//! dict-member   = member-key ( parameters / ( "=" member-value ))
+//! sf-item       = bare-item parameters
+//! bare-item     = sf-integer / sf-decimal / sf-string / sf-token
+//!                 / sf-binary / sf-boolean / sf-date / sf-displaystring
+//! parameters    = *( ";" *SP parameter )
+//! parameter     = param-key [ "=" param-value ]
+//! key           = ( lcalpha / "*" ) *( lcalpha / DIGIT / "_" / "-" / "." / "*" )
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::sfv::{BareItem, Decimal, Dictionary, InnerList, Item, List};
+//!
+//! // Item
+//! let item = Item::parse("text/html;q=0.5").unwrap();
+//! assert_eq!(item.bare_item().as_token(), Some("text/html"));
+//! assert_eq!(
+//!     item.params().get("q").and_then(BareItem::as_decimal),
+//!     Some(Decimal::from_thousandths(500).unwrap())
+//! );
+//!
+//! // Dictionary (Priority ヘッダー)
+//! let priority = Dictionary::parse("u=1, i").unwrap();
+//! let urgency = priority.get("u").and_then(|member| member.as_item()).unwrap();
+//! assert_eq!(urgency.bare_item().as_integer(), Some(1));
+//! let incremental = priority.get("i").and_then(|member| member.as_item()).unwrap();
+//! assert_eq!(incremental.bare_item().as_boolean(), Some(true));
+//! assert_eq!(priority.to_string(), "u=1, i");
+//!
+//! // List と Inner List
+//! let list = List::parse("(\"foo\" \"bar\");lvl=5, abc;a=?0").unwrap();
+//! assert_eq!(list.members().len(), 2);
+//! assert_eq!(list.members()[0].as_inner_list().unwrap().items().len(), 2);
+//!
+//! // シリアライズ
+//! let list = List::new()
+//!     .with_member(
+//!         InnerList::new()
+//!             .with_item(Item::new(BareItem::Integer(1)).unwrap())
+//!             .with_item(Item::new(BareItem::Boolean(true)).unwrap()),
+//!     )
+//!     .with_member(
+//!         Item::new(BareItem::Token("gzip".to_string()))
+//!             .unwrap()
+//!             .with_param("q", BareItem::Decimal(Decimal::from_thousandths(250).unwrap()))
+//!             .unwrap(),
+//!     );
+//! assert_eq!(list.to_string(), "(1 ?1), gzip;q=0.25");
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::base64;
+use crate::validate::is_token_char;
+
+/// sf-integer の最大値 (15 桁)
+pub const MAX_INTEGER: i64 = 999_999_999_999_999;
+
+/// sf-integer の最小値 (15 桁)
+pub const MIN_INTEGER: i64 = -MAX_INTEGER;
+
+/// Structured Field のパース / 生成エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SfvError {
+    /// ASCII 以外の文字を含む
+    NonAscii,
+    /// 空の Item
+    Empty,
+    /// 不正な bare-item の先頭文字
+    InvalidBareItem,
+    /// 不正な key
+    InvalidKey,
+    /// 不正な sf-integer (16 桁以上を含む)
+    InvalidInteger,
+    /// 不正な sf-decimal
+    InvalidDecimal,
+    /// 不正な sf-string
+    InvalidString,
+    /// 不正な sf-token
+    InvalidToken,
+    /// 不正な sf-binary
+    InvalidByteSequence,
+    /// 不正な sf-boolean
+    InvalidBoolean,
+    /// 不正な sf-date
+    InvalidDate,
+    /// 不正な sf-displaystring
+    InvalidDisplayString,
+    /// 不正な inner-list
+    InvalidInnerList,
+    /// List / Dictionary のメンバーの区切りが不正
+    InvalidSeparator,
+    /// 末尾に余分な文字がある
+    TrailingCharacters,
+}
+
+impl fmt::Display for SfvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SfvError::NonAscii => write!(f, "non-ASCII character in structured field"),
+            SfvError::Empty => write!(f, "empty structured field item"),
+            SfvError::InvalidBareItem => write!(f, "invalid bare-item"),
+            SfvError::InvalidKey => write!(f, "invalid structured field key"),
+            SfvError::InvalidInteger => write!(f, "invalid sf-integer"),
+            SfvError::InvalidDecimal => write!(f, "invalid sf-decimal"),
+            SfvError::InvalidString => write!(f, "invalid sf-string"),
+            SfvError::InvalidToken => write!(f, "invalid sf-token"),
+            SfvError::InvalidByteSequence => write!(f, "invalid sf-binary"),
+            SfvError::InvalidBoolean => write!(f, "invalid sf-boolean"),
+            SfvError::InvalidDate => write!(f, "invalid sf-date"),
+            SfvError::InvalidDisplayString => write!(f, "invalid sf-displaystring"),
+            SfvError::InvalidInnerList => write!(f, "invalid inner-list"),
+            SfvError::InvalidSeparator => write!(f, "invalid structured field member separator"),
+            SfvError::TrailingCharacters => {
+                write!(f, "trailing characters after structured field")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SfvError {}
+
+// ========================================
+// Decimal
+// ========================================
+
+/// sf-decimal (RFC 9651 Section 3.3.2)
+///
+/// 小数点以下 3 桁までの固定小数点数で、値を 1000 倍した整数で保持する。
+/// 整数部は 12 桁まで。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal {
+    thousandths: i64,
+}
+
+impl Decimal {
+    /// 1000 倍した値から作成
+    ///
+    /// 絶対値が 999,999,999,999.999 を超える場合は `SfvError::InvalidDecimal` を返す。
+    pub fn from_thousandths(thousandths: i64) -> Result<Self, SfvError> {
+        if !(MIN_INTEGER..=MAX_INTEGER).contains(&thousandths) {
+            return Err(SfvError::InvalidDecimal);
+        }
+        Ok(Decimal { thousandths })
+    }
+
+    /// 整数から作成
+    pub fn from_integer(value: i64) -> Result<Self, SfvError> {
+        value
+            .checked_mul(1000)
+            .ok_or(SfvError::InvalidDecimal)
+            .and_then(Self::from_thousandths)
+    }
+
+    /// 1000 倍した値
+    pub fn thousandths(&self) -> i64 {
+        self.thousandths
+    }
+
+    /// 整数部 (0 方向に切り捨て)
+    pub fn integer_part(&self) -> i64 {
+        self.thousandths / 1000
+    }
+
+    /// f64 に変換
+    pub fn to_f64(&self) -> f64 {
+        self.thousandths as f64 / 1000.0
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.thousandths < 0 {
+            write!(f, "-")?;
+        }
+        let abs = self.thousandths.unsigned_abs();
+        write!(f, "{}.", abs / 1000)?;
+        // 小数部は末尾の 0 を取り除き、少なくとも 1 桁は出力する
+        let mut fraction = abs % 1000;
+        let mut digits = 3;
+        while digits > 1 && fraction.is_multiple_of(10) {
+            fraction /= 10;
+            digits -= 1;
+        }
+        write!(f, "{:0width$}", fraction, width = digits)
+    }
+}
+
+// ========================================
+// BareItem
+// ========================================
+
+/// bare-item (RFC 9651 Section 3.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BareItem {
+    /// sf-integer
+    Integer(i64),
+    /// sf-decimal
+    Decimal(Decimal),
+    /// sf-string (印字可能 ASCII のみ)
+    String(String),
+    /// sf-token
+    Token(String),
+    /// sf-binary
+    ByteSequence(Vec<u8>),
+    /// sf-boolean
+    Boolean(bool),
+    /// sf-date (UNIX 時間)
+    Date(i64),
+    /// sf-displaystring (Unicode 文字列)
+    DisplayString(String),
+}
+
+impl BareItem {
+    /// sf-integer の値
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            BareItem::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// sf-decimal の値
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            BareItem::Decimal(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// sf-string の値
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            BareItem::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// sf-token の値
+    pub fn as_token(&self) -> Option<&str> {
+        match self {
+            BareItem::Token(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// sf-binary の値
+    pub fn as_byte_sequence(&self) -> Option<&[u8]> {
+        match self {
+            BareItem::ByteSequence(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// sf-boolean の値
+    pub fn as_boolean(&self) -> Option<bool> {
+        match self {
+            BareItem::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// sf-date の値 (UNIX 時間)
+    pub fn as_date(&self) -> Option<i64> {
+        match self {
+            BareItem::Date(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// sf-displaystring の値
+    pub fn as_display_string(&self) -> Option<&str> {
+        match self {
+            BareItem::DisplayString(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// シリアライズできる値かどうかを検証
+    ///
+    /// Integer / Date の範囲、String の文字種、Token の形式を確認する。
+    pub fn validate(&self) -> Result<(), SfvError> {
+        match self {
+            BareItem::Integer(value) if !(MIN_INTEGER..=MAX_INTEGER).contains(value) => {
+                Err(SfvError::InvalidInteger)
+            }
+            BareItem::Date(value) if !(MIN_INTEGER..=MAX_INTEGER).contains(value) => {
+                Err(SfvError::InvalidDate)
+            }
+            BareItem::String(value) if !is_valid_string(value) => Err(SfvError::InvalidString),
+            BareItem::Token(value) if !is_valid_token(value) => Err(SfvError::InvalidToken),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<i64> for BareItem {
+    fn from(value: i64) -> Self {
+        BareItem::Integer(value)
+    }
+}
+
+impl From<Decimal> for BareItem {
+    fn from(value: Decimal) -> Self {
+        BareItem::Decimal(value)
+    }
+}
+
+impl From<bool> for BareItem {
+    fn from(value: bool) -> Self {
+        BareItem::Boolean(value)
+    }
+}
+
+impl From<Vec<u8>> for BareItem {
+    fn from(value: Vec<u8>) -> Self {
+        BareItem::ByteSequence(value)
+    }
+}
+
+impl fmt::Display for BareItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BareItem::Integer(value) => write!(f, "{}", value),
+            BareItem::Decimal(value) => write!(f, "{}", value),
+            BareItem::String(value) => write_string(f, value),
+            BareItem::Token(value) => f.write_str(value),
+            BareItem::ByteSequence(value) => write_byte_sequence(f, value),
+            BareItem::Boolean(value) => write!(f, "?{}", u8::from(*value)),
+            BareItem::Date(value) => write!(f, "@{}", value),
+            BareItem::DisplayString(value) => {
+                write!(f, "%\"")?;
+                for b in value.bytes() {
+                    if b == b'%' || b == b'"' || !(0x20..=0x7E).contains(&b) {
+                        write!(f, "%{:02x}", b)?;
+                    } else {
+                        write!(f, "{}", b as char)?;
+                    }
+                }
+                write!(f, "\"")
+            }
+        }
+    }
+}
+
+// ========================================
+// Parameters
+// ========================================
+
+/// Parameters (RFC 9651 Section 3.1.2)
+///
+/// key の順序を保持する。同じ key を追加した場合は値を置き換える。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Parameters {
+    entries: Vec<(String, BareItem)>,
+}
+
+impl Parameters {
+    /// 空の Parameters を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// パラメーターを追加
+    ///
+    /// 同じ key がすでにある場合は値を置き換える。
+    pub fn insert(&mut self, key: &str, value: BareItem) -> Result<(), SfvError> {
+        if !is_valid_key(key) {
+            return Err(SfvError::InvalidKey);
+        }
+        value.validate()?;
+        self.insert_unchecked(key.to_string(), value);
+        Ok(())
+    }
+
+    pub(crate) fn insert_unchecked(&mut self, key: String, value: BareItem) {
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+
+    /// key の値を取得
+    pub fn get(&self, key: &str) -> Option<&BareItem> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// key が存在するか
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// パラメーターを順に取得
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BareItem)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    /// パラメーターの数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// パラメーターがないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.entries {
+            write!(f, ";{}", key)?;
+            if *value != BareItem::Boolean(true) {
+                write!(f, "={}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// ========================================
+// Item / InnerList
+// ========================================
+
+/// sf-item (RFC 9651 Section 3.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    bare_item: BareItem,
+    params: Parameters,
+}
+
+impl Item {
+    /// bare-item から作成
+    pub fn new(bare_item: BareItem) -> Result<Self, SfvError> {
+        bare_item.validate()?;
+        Ok(Item {
+            bare_item,
+            params: Parameters::new(),
+        })
+    }
+
+    /// パラメーターを追加 (ビルダーパターン)
+    pub fn with_param(mut self, key: &str, value: BareItem) -> Result<Self, SfvError> {
+        self.params.insert(key, value)?;
+        Ok(self)
+    }
+
+    /// Item をパース
+    pub fn parse(input: &str) -> Result<Self, SfvError> {
+        let input = prepare(input)?;
+        if input.is_empty() {
+            return Err(SfvError::Empty);
+        }
+        let (item, rest) = parse_item(input)?;
+        finish(rest)?;
+        Ok(item)
+    }
+
+    /// bare-item
+    pub fn bare_item(&self) -> &BareItem {
+        &self.bare_item
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.bare_item, self.params)
+    }
+}
+
+/// inner-list (RFC 9651 Section 3.1.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InnerList {
+    items: Vec<Item>,
+    params: Parameters,
+}
+
+impl InnerList {
+    /// 空の Inner List を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Item を追加 (ビルダーパターン)
+    pub fn with_item(mut self, item: Item) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// パラメーターを追加 (ビルダーパターン)
+    pub fn with_param(mut self, key: &str, value: BareItem) -> Result<Self, SfvError> {
+        self.params.insert(key, value)?;
+        Ok(self)
+    }
+
+    /// Item の一覧
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+}
+
+impl fmt::Display for InnerList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        write!(f, "){}", self.params)
+    }
+}
+
+/// List / Dictionary のメンバー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListMember {
+    /// sf-item
+    Item(Item),
+    /// inner-list
+    InnerList(InnerList),
+}
+
+impl ListMember {
+    /// sf-item の場合は Item を返す
+    pub fn as_item(&self) -> Option<&Item> {
+        match self {
+            ListMember::Item(item) => Some(item),
+            ListMember::InnerList(_) => None,
+        }
+    }
+
+    /// inner-list の場合は InnerList を返す
+    pub fn as_inner_list(&self) -> Option<&InnerList> {
+        match self {
+            ListMember::Item(_) => None,
+            ListMember::InnerList(inner_list) => Some(inner_list),
+        }
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &Parameters {
+        match self {
+            ListMember::Item(item) => item.params(),
+            ListMember::InnerList(inner_list) => inner_list.params(),
+        }
+    }
+}
+
+impl From<Item> for ListMember {
+    fn from(item: Item) -> Self {
+        ListMember::Item(item)
+    }
+}
+
+impl From<InnerList> for ListMember {
+    fn from(inner_list: InnerList) -> Self {
+        ListMember::InnerList(inner_list)
+    }
+}
+
+impl fmt::Display for ListMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListMember::Item(item) => write!(f, "{}", item),
+            ListMember::InnerList(inner_list) => write!(f, "{}", inner_list),
+        }
+    }
+}
+
+// ========================================
+// List / Dictionary
+// ========================================
+
+/// sf-list (RFC 9651 Section 3.1)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct List {
+    members: Vec<ListMember>,
+}
+
+impl List {
+    /// 空の List を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// メンバーを追加 (ビルダーパターン)
+    pub fn with_member(mut self, member: impl Into<ListMember>) -> Self {
+        self.members.push(member.into());
+        self
+    }
+
+    /// List をパース
+    ///
+    /// 空の入力は空の List になる。複数行のヘッダーは `, ` で連結してから渡す。
+    pub fn parse(input: &str) -> Result<Self, SfvError> {
+        let mut rest = prepare(input)?;
+        let mut members = Vec::new();
+        while !rest.is_empty() {
+            let (member, after) = parse_list_member(rest)?;
+            members.push(member);
+            rest = next_member(after)?;
+        }
+        Ok(List { members })
+    }
+
+    /// メンバーの一覧
+    pub fn members(&self) -> &[ListMember] {
+        &self.members
+    }
+
+    /// メンバーがないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, member) in self.members.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", member)?;
+        }
+        Ok(())
+    }
+}
+
+/// sf-dictionary (RFC 9651 Section 3.2)
+///
+/// key の順序を保持する。同じ key を追加した場合は値を置き換える。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    entries: Vec<(String, ListMember)>,
+}
+
+impl Dictionary {
+    /// 空の Dictionary を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// メンバーを追加 (ビルダーパターン)
+    ///
+    /// 同じ key がすでにある場合は値を置き換える。
+    pub fn with_member(
+        mut self,
+        key: &str,
+        member: impl Into<ListMember>,
+    ) -> Result<Self, SfvError> {
+        if !is_valid_key(key) {
+            return Err(SfvError::InvalidKey);
+        }
+        self.insert(key.to_string(), member.into());
+        Ok(self)
+    }
+
+    fn insert(&mut self, key: String, member: ListMember) {
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = member,
+            None => self.entries.push((key, member)),
+        }
+    }
+
+    /// Dictionary をパース
+    ///
+    /// 空の入力は空の Dictionary になる。同じ key が複数回現れる場合は最後の値を採用する。
+    pub fn parse(input: &str) -> Result<Self, SfvError> {
+        let mut rest = prepare(input)?;
+        let mut dictionary = Dictionary::new();
+        while !rest.is_empty() {
+            let (key, after) = parse_key(rest)?;
+            let (member, after) = match after.strip_prefix('=') {
+                Some(value) => parse_list_member(value)?,
+                None => {
+                    let (params, after) = parse_parameters(after)?;
+                    let item = Item {
+                        bare_item: BareItem::Boolean(true),
+                        params,
+                    };
+                    (ListMember::Item(item), after)
+                }
+            };
+            dictionary.insert(key.to_string(), member);
+            rest = next_member(after)?;
+        }
+        Ok(dictionary)
+    }
+
+    /// key のメンバーを取得
+    pub fn get(&self, key: &str) -> Option<&ListMember> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, member)| member)
+    }
+
+    /// メンバーを順に取得
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ListMember)> {
+        self.entries
+            .iter()
+            .map(|(key, member)| (key.as_str(), member))
+    }
+
+    /// メンバーの数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// メンバーがないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, member)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            f.write_str(key)?;
+            match member {
+                // 値が true の Item は値を省略する
+                ListMember::Item(item) if item.bare_item == BareItem::Boolean(true) => {
+                    write!(f, "{}", item.params)?;
+                }
+                _ => write!(f, "={}", member)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// ========================================
+// パーサー
+// ========================================
+
+/// ASCII であることを確認し、前後の SP を取り除く
+fn prepare(input: &str) -> Result<&str, SfvError> {
+    if !input.is_ascii() {
+        return Err(SfvError::NonAscii);
+    }
+    Ok(input.trim_matches(' '))
+}
+
+fn finish(rest: &str) -> Result<(), SfvError> {
+    if rest.is_empty() {
+        Ok(())
+    } else {
+        Err(SfvError::TrailingCharacters)
+    }
+}
+
+/// メンバーの後の `OWS "," OWS` を読み、次のメンバーの先頭を返す
+fn next_member(input: &str) -> Result<&str, SfvError> {
+    let rest = input.trim_start_matches([' ', '\t']);
+    if rest.is_empty() {
+        return Ok(rest);
+    }
+    let rest = rest
+        .strip_prefix(',')
+        .ok_or(SfvError::InvalidSeparator)?
+        .trim_start_matches([' ', '\t']);
+    if rest.is_empty() {
+        // 末尾のカンマ
+        return Err(SfvError::InvalidSeparator);
+    }
+    Ok(rest)
+}
+
+fn parse_list_member(input: &str) -> Result<(ListMember, &str), SfvError> {
+    if input.starts_with('(') {
+        let (inner_list, rest) = parse_inner_list(input)?;
+        Ok((ListMember::InnerList(inner_list), rest))
+    } else {
+        let (item, rest) = parse_item(input)?;
+        Ok((ListMember::Item(item), rest))
+    }
+}
+
+fn parse_inner_list(input: &str) -> Result<(InnerList, &str), SfvError> {
+    let mut rest = input.strip_prefix('(').ok_or(SfvError::InvalidInnerList)?;
+    let mut items = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(' ');
+        if let Some(after) = rest.strip_prefix(')') {
+            let (params, after) = parse_parameters(after)?;
+            return Ok((InnerList { items, params }, after));
+        }
+        if rest.is_empty() {
+            return Err(SfvError::InvalidInnerList);
+        }
+        let (item, after) = parse_item(rest)?;
+        if !after.starts_with([' ', ')']) {
+            return Err(SfvError::InvalidInnerList);
+        }
+        items.push(item);
+        rest = after;
+    }
+}
+
+fn parse_item(input: &str) -> Result<(Item, &str), SfvError> {
+    let (bare_item, rest) = parse_bare_item(input)?;
+    let (params, rest) = parse_parameters(rest)?;
+    Ok((Item { bare_item, params }, rest))
+}
+
+fn parse_parameters(mut input: &str) -> Result<(Parameters, &str), SfvError> {
+    let mut params = Parameters::new();
+    while let Some(rest) = input.strip_prefix(';') {
+        let (key, rest) = parse_key(rest.trim_start_matches(' '))?;
+        let (value, rest) = match rest.strip_prefix('=') {
+            Some(value) => parse_bare_item(value)?,
+            None => (BareItem::Boolean(true), rest),
+        };
+        params.insert_unchecked(key.to_string(), value);
+        input = rest;
+    }
+    Ok((params, input))
+}
+
+fn parse_key(input: &str) -> Result<(&str, &str), SfvError> {
+    let end = input
+        .bytes()
+        .position(|b| !is_key_char(b))
+        .unwrap_or(input.len());
+    let key = &input[..end];
+    if !is_valid_key(key) {
+        return Err(SfvError::InvalidKey);
+    }
+    Ok((key, &input[end..]))
+}
+
+fn parse_bare_item(input: &str) -> Result<(BareItem, &str), SfvError> {
+    let first = input.bytes().next().ok_or(SfvError::InvalidBareItem)?;
+    match first {
+        b'-' | b'0'..=b'9' => parse_number(input),
+        b'"' => parse_string(&input[1..]).map(|(value, rest)| (BareItem::String(value), rest)),
+        b'*' | b'A'..=b'Z' | b'a'..=b'z' => {
+            let end = input
+                .bytes()
+                .position(|b| !(is_token_char(b) || b == b':' || b == b'/'))
+                .unwrap_or(input.len());
+            Ok((BareItem::Token(input[..end].to_string()), &input[end..]))
+        }
+        b':' => parse_byte_sequence(&input[1..]),
+        b'?' => match input.as_bytes().get(1) {
+            Some(b'0') => Ok((BareItem::Boolean(false), &input[2..])),
+            Some(b'1') => Ok((BareItem::Boolean(true), &input[2..])),
+            _ => Err(SfvError::InvalidBoolean),
+        },
+        b'@' => match parse_number(&input[1..]) {
+            Ok((BareItem::Integer(value), rest)) => Ok((BareItem::Date(value), rest)),
+            _ => Err(SfvError::InvalidDate),
+        },
+        b'%' => parse_display_string(&input[1..]),
+        _ => Err(SfvError::InvalidBareItem),
+    }
+}
+
+/// sf-integer / sf-decimal をパース (RFC 9651 Section 4.2.4)
+fn parse_number(input: &str) -> Result<(BareItem, &str), SfvError> {
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let integer_len = digits
+        .bytes()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(digits.len());
+    if integer_len == 0 {
+        return Err(SfvError::InvalidInteger);
+    }
+
+    let Some(fraction) = digits[integer_len..].strip_prefix('.') else {
+        if integer_len > 15 {
+            return Err(SfvError::InvalidInteger);
+        }
+        let value: i64 = digits[..integer_len]
+            .parse()
+            .map_err(|_| SfvError::InvalidInteger)?;
+        let value = if negative { -value } else { value };
+        return Ok((BareItem::Integer(value), &digits[integer_len..]));
+    };
+
+    if integer_len > 12 {
+        return Err(SfvError::InvalidDecimal);
+    }
+    let fraction_len = fraction
+        .bytes()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(fraction.len());
+    if fraction_len == 0 || fraction_len > 3 {
+        return Err(SfvError::InvalidDecimal);
+    }
+    let integer: i64 = digits[..integer_len]
+        .parse()
+        .map_err(|_| SfvError::InvalidDecimal)?;
+    let mut thousandths: i64 = fraction[..fraction_len]
+        .parse()
+        .map_err(|_| SfvError::InvalidDecimal)?;
+    for _ in fraction_len..3 {
+        thousandths *= 10;
+    }
+    let value = integer * 1000 + thousandths;
+    let value = if negative { -value } else { value };
+    Ok((
+        BareItem::Decimal(Decimal { thousandths: value }),
+        &fraction[fraction_len..],
+    ))
+}
+
+/// sf-string を開始の `"` の後からパースする (RFC 9651 Section 4.2.5)
+///
+/// 印字可能 ASCII のみで、エスケープできるのは `"` と `\` のみ。
+fn parse_string(input: &str) -> Result<(String, &str), SfvError> {
+    let mut value = String::new();
+    let mut bytes = input.bytes().enumerate();
+    while let Some((i, b)) = bytes.next() {
+        match b {
+            b'\\' => match bytes.next() {
+                Some((_, c @ (b'"' | b'\\'))) => value.push(c as char),
+                _ => return Err(SfvError::InvalidString),
+            },
+            b'"' => return Ok((value, &input[i + 1..])),
+            0x20..=0x7E => value.push(b as char),
+            _ => return Err(SfvError::InvalidString),
+        }
+    }
+    Err(SfvError::InvalidString)
+}
+
+/// sf-binary を開始の `:` の後からパースする (RFC 9651 Section 4.2.7)
+fn parse_byte_sequence(input: &str) -> Result<(BareItem, &str), SfvError> {
+    let end = input.find(':').ok_or(SfvError::InvalidByteSequence)?;
+    let encoded = &input[..end];
+    if !encoded
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+    {
+        return Err(SfvError::InvalidByteSequence);
+    }
+    // RFC 9651 Section 4.2.7: "=" パディングがない場合も失敗させるべきではない (SHOULD NOT)
+    let bytes = if encoded.contains('=') || encoded.len().is_multiple_of(4) {
+        base64::decode(encoded)
+    } else {
+        let mut padded = String::from(encoded);
+        while !padded.len().is_multiple_of(4) {
+            padded.push('=');
+        }
+        base64::decode(&padded)
+    }
+    .map_err(|_| SfvError::InvalidByteSequence)?;
+    Ok((BareItem::ByteSequence(bytes), &input[end + 1..]))
+}
+
+/// sf-displaystring を開始の `%` の後からパースする (RFC 9651 Section 4.2.10)
+fn parse_display_string(input: &str) -> Result<(BareItem, &str), SfvError> {
+    let body = input
+        .strip_prefix('"')
+        .ok_or(SfvError::InvalidDisplayString)?;
+    let mut bytes = Vec::new();
+    let mut iter = body.bytes().enumerate();
+    while let Some((i, b)) = iter.next() {
+        match b {
+            b'%' => {
+                let mut hex = || {
+                    iter.next().and_then(|(_, c)| match c {
+                        b'0'..=b'9' => Some(c - b'0'),
+                        b'a'..=b'f' => Some(c - b'a' + 10),
+                        _ => None,
+                    })
+                };
+                let high = hex().ok_or(SfvError::InvalidDisplayString)?;
+                let low = hex().ok_or(SfvError::InvalidDisplayString)?;
+                bytes.push(high << 4 | low);
+            }
+            b'"' => {
+                let value = String::from_utf8(bytes).map_err(|_| SfvError::InvalidDisplayString)?;
+                return Ok((BareItem::DisplayString(value), &body[i + 1..]));
+            }
+            0x20..=0x7E => bytes.push(b),
+            _ => return Err(SfvError::InvalidDisplayString),
+        }
+    }
+    Err(SfvError::InvalidDisplayString)
+}
+
+// ========================================
+// 検証 / 生成のヘルパー
+// ========================================
+
+/// key = ( lcalpha / "*" ) *( lcalpha / DIGIT / "_" / "-" / "." / "*" )
+pub(crate) fn is_valid_key(key: &str) -> bool {
+    let bytes = key.as_bytes();
+    !bytes.is_empty()
+        && (bytes[0].is_ascii_lowercase() || bytes[0] == b'*')
+        && bytes.iter().all(|&b| is_key_char(b))
+}
+
+fn is_key_char(b: u8) -> bool {
+    b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-' | b'.' | b'*')
+}
+
+/// sf-token = ( ALPHA / "*" ) *( tchar / ":" / "/" )
+pub(crate) fn is_valid_token(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    !bytes.is_empty()
+        && (bytes[0].is_ascii_alphabetic() || bytes[0] == b'*')
+        && bytes
+            .iter()
+            .all(|&b| is_token_char(b) || b == b':' || b == b'/')
+}
+
+/// sf-string に格納できる文字列か (印字可能 ASCII のみ)
+pub(crate) fn is_valid_string(value: &str) -> bool {
+    value.bytes().all(|b| (0x20..=0x7E).contains(&b))
+}
+
+/// sf-string を生成
+///
+/// `value` は `is_valid_string` を満たしていること。
+pub(crate) fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "\"")
+}
+
+/// sf-binary を生成
+pub(crate) fn write_byte_sequence(f: &mut fmt::Formatter<'_>, value: &[u8]) -> fmt::Result {
+    write!(f, ":{}:", base64::encode(value))
+}
@@ -0,0 +1,447 @@
+//! Server-Sent Events (HTML Living Standard Section 9.2)
+//!
+//! ## 概要
+//!
+//! `text/event-stream` のイベントの生成とパースを提供します。
+//!
+//! - [`SseDecoder`] はレスポンスボディのバイト列を受け取り、イベント単位で返す
+//! - [`Event::encode`] はイベントを `text/event-stream` の形式に変換する
+//! - [`encode_comment`] / [`KEEP_ALIVE`] は接続を維持するためのコメントを生成する
+//!
+//! パースは HTML Living Standard の "event stream interpretation" に従う。
+//!
+//! - 行末は CRLF / LF / CR のいずれも受け付ける
+//! - 複数の `data` フィールドは LF で結合する
+//! - `:` で始まる行はコメントとして無視する
+//! - 不明なフィールドは無視する
+//! - 空行でイベントを確定する。`data` フィールドを持たないイベントは返さない
+//! - UTF-8 として不正なバイト列は U+FFFD に置き換える
+//!
+//! ## ABNF
+//!
+//! ```text
+//! stream        = [ bom ] *event
+//! event         = *( comment / field ) end-of-line
+//! comment       = colon *any-char end-of-line
+//! field         = 1*name-char [ colon [ space ] *any-char ] end-of-line
+//! end-of-line   = ( cr lf / cr / lf )
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::sse::{Event, SseDecoder};
+//!
+//! let event = Event::new("first line\nsecond line")
+//!     .with_event("update")
+//!     .unwrap()
+//!     .with_id("42")
+//!     .unwrap();
+//! let encoded = event.encode();
+//! assert_eq!(
+//!     encoded,
+//!     b"id: 42\nevent: update\ndata: first line\ndata: second line\n\n"
+//! );
+//!
+//! let mut decoder = SseDecoder::new();
+//! decoder.feed(&encoded).unwrap();
+//! assert_eq!(decoder.next_event().unwrap(), Some(event));
+//! assert_eq!(decoder.next_event().unwrap(), None);
+//! assert_eq!(decoder.last_event_id(), "42");
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// `text/event-stream` のメディアタイプ
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// 接続を維持するための空のコメント
+pub const KEEP_ALIVE: &[u8] = b":\n";
+
+/// デフォルトのバッファ最大サイズ (1MB)
+pub const DEFAULT_MAX_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// UTF-8 BOM
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Server-Sent Events エラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SseError {
+    /// イベントタイプに CR / LF が含まれる
+    InvalidEventType,
+    /// イベント ID に CR / LF / NUL が含まれる
+    InvalidId,
+    /// 未処理のデータまたはイベントのデータが上限を超えた
+    BufferOverflow {
+        /// 超過後のサイズ
+        size: usize,
+        /// 設定された上限
+        limit: usize,
+    },
+}
+
+impl fmt::Display for SseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SseError::InvalidEventType => write!(f, "invalid event type"),
+            SseError::InvalidId => write!(f, "invalid event id"),
+            SseError::BufferOverflow { size, limit } => {
+                write!(f, "buffer overflow: size={size}, limit={limit}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SseError {}
+
+/// イベント
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: String,
+    retry: Option<u64>,
+}
+
+impl Event {
+    /// データを指定して作成
+    pub fn new(data: &str) -> Self {
+        Event {
+            data: data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// イベントタイプを指定
+    ///
+    /// CR / LF を含む場合は `SseError::InvalidEventType` を返す。
+    pub fn with_event(mut self, event: &str) -> Result<Self, SseError> {
+        if event.contains(['\r', '\n']) {
+            return Err(SseError::InvalidEventType);
+        }
+        self.event = Some(event.to_string());
+        Ok(self)
+    }
+
+    /// イベント ID を指定
+    ///
+    /// 空文字列を指定すると、クライアントの最後のイベント ID をリセットする。
+    /// CR / LF / NUL を含む場合は `SseError::InvalidId` を返す。
+    pub fn with_id(mut self, id: &str) -> Result<Self, SseError> {
+        if id.contains(['\r', '\n', '\0']) {
+            return Err(SseError::InvalidId);
+        }
+        self.id = Some(id.to_string());
+        Ok(self)
+    }
+
+    /// 再接続までの時間 (ミリ秒) を指定
+    pub fn with_retry(mut self, retry: u64) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// イベント ID
+    ///
+    /// パースしたイベントでは、そのイベントを確定した時点の最後のイベント ID になる
+    /// (`id` フィールドは後続のイベントにも引き継がれる)。空の場合は `None`。
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// イベントタイプ
+    ///
+    /// 指定されていない場合は `None` で、クライアントは `message` として扱う。
+    pub fn event(&self) -> Option<&str> {
+        self.event.as_deref()
+    }
+
+    /// データ
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// 再接続までの時間 (ミリ秒)
+    pub fn retry(&self) -> Option<u64> {
+        self.retry
+    }
+
+    /// `text/event-stream` の形式に変換する
+    ///
+    /// データに含まれる改行 (CRLF / LF / CR) ごとに `data` フィールドを分け、
+    /// 最後に空行を付与する。
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.data.len() + 32);
+        if let Some(id) = &self.id {
+            push_field(&mut buf, "id", id);
+        }
+        if let Some(event) = &self.event {
+            push_field(&mut buf, "event", event);
+        }
+        if let Some(retry) = self.retry {
+            push_field(&mut buf, "retry", &retry.to_string());
+        }
+        for line in split_lines(&self.data) {
+            push_field(&mut buf, "data", line);
+        }
+        buf.push(b'\n');
+        buf
+    }
+}
+
+/// コメントを `text/event-stream` の形式に変換する
+///
+/// 改行ごとにコメント行を分ける。クライアントはコメントを無視するため、
+/// 接続の維持やデバッグ用の情報に使える。
+pub fn encode_comment(comment: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(comment.len() + 4);
+    for line in split_lines(comment) {
+        buf.push(b':');
+        if !line.is_empty() {
+            buf.push(b' ');
+            buf.extend_from_slice(line.as_bytes());
+        }
+        buf.push(b'\n');
+    }
+    buf
+}
+
+/// `text/event-stream` のデコーダー
+#[derive(Debug, Clone)]
+pub struct SseDecoder {
+    /// 未処理のデータ
+    buffer: Vec<u8>,
+    /// 先頭の BOM を確認したか
+    bom_checked: bool,
+    /// 直前の行が CR で終わったか (続く LF を読み飛ばす)
+    skip_lf: bool,
+    /// 確定前のイベントのデータ
+    data: String,
+    /// 確定前のイベントに `data` フィールドがあるか
+    has_data: bool,
+    /// 確定前のイベントタイプ
+    event: String,
+    /// 確定前のイベントの再接続時間
+    event_retry: Option<u64>,
+    /// 最後のイベント ID
+    last_event_id: String,
+    /// 最後に受信した再接続時間
+    retry: Option<u64>,
+    /// バッファ最大サイズ
+    max_buffer_size: usize,
+}
+
+impl Default for SseDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SseDecoder {
+    /// 新しいデコーダーを作成
+    ///
+    /// バッファ上限は [`DEFAULT_MAX_BUFFER_SIZE`]。変更する場合は `with_max_buffer_size()` を使用する。
+    pub fn new() -> Self {
+        SseDecoder {
+            buffer: Vec::new(),
+            bom_checked: false,
+            skip_lf: false,
+            data: String::new(),
+            has_data: false,
+            event: String::new(),
+            event_retry: None,
+            last_event_id: String::new(),
+            retry: None,
+            max_buffer_size: DEFAULT_MAX_BUFFER_SIZE,
+        }
+    }
+
+    /// バッファ最大サイズを設定
+    ///
+    /// 未処理のデータと、確定前のイベントのデータの両方に適用する。
+    pub fn with_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.max_buffer_size = max_buffer_size;
+        self
+    }
+
+    /// 最後のイベント ID を指定して作成
+    ///
+    /// 再接続時に Last-Event-ID ヘッダーで送った値を引き継ぐ場合に使う。
+    pub fn with_last_event_id(mut self, last_event_id: &str) -> Self {
+        self.last_event_id = last_event_id.to_string();
+        self
+    }
+
+    /// データを追加
+    ///
+    /// 未処理のデータが `max_buffer_size` を超える場合は `SseError::BufferOverflow` を返す。
+    pub fn feed(&mut self, data: &[u8]) -> Result<(), SseError> {
+        let size = self.buffer.len().saturating_add(data.len());
+        if size > self.max_buffer_size {
+            return Err(SseError::BufferOverflow {
+                size,
+                limit: self.max_buffer_size,
+            });
+        }
+        self.buffer.extend_from_slice(data);
+        Ok(())
+    }
+
+    /// 次のイベントを取得
+    ///
+    /// 確定したイベントがない場合は `None` を返す。
+    /// 確定前のイベントのデータが `max_buffer_size` を超える場合は `SseError::BufferOverflow` を返す。
+    pub fn next_event(&mut self) -> Result<Option<Event>, SseError> {
+        let mut pos = 0;
+        let result = self.process(&mut pos);
+        self.buffer.drain(..pos);
+        result
+    }
+
+    /// 最後のイベント ID
+    ///
+    /// 再接続時に Last-Event-ID ヘッダーで送る値。
+    pub fn last_event_id(&self) -> &str {
+        &self.last_event_id
+    }
+
+    /// 最後に受信した再接続時間 (ミリ秒)
+    pub fn retry(&self) -> Option<u64> {
+        self.retry
+    }
+
+    /// 未処理のデータ量
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn process(&mut self, pos: &mut usize) -> Result<Option<Event>, SseError> {
+        if !self.bom_checked {
+            let len = self.buffer.len().min(BOM.len());
+            if self.buffer[..len] != BOM[..len] {
+                self.bom_checked = true;
+            } else if len == BOM.len() {
+                self.bom_checked = true;
+                *pos = BOM.len();
+            } else {
+                // BOM の途中かどうか判断できない
+                return Ok(None);
+            }
+        }
+
+        loop {
+            if self.skip_lf && *pos < self.buffer.len() {
+                self.skip_lf = false;
+                if self.buffer[*pos] == b'\n' {
+                    *pos += 1;
+                }
+            }
+            let Some(offset) = self.buffer[*pos..]
+                .iter()
+                .position(|&b| b == b'\r' || b == b'\n')
+            else {
+                return Ok(None);
+            };
+            let start = *pos;
+            let end = start + offset;
+            self.skip_lf = self.buffer[end] == b'\r';
+            *pos = end + 1;
+
+            let line = String::from_utf8_lossy(&self.buffer[start..end]).into_owned();
+            if line.is_empty() {
+                if let Some(event) = self.dispatch() {
+                    return Ok(Some(event));
+                }
+            } else {
+                self.process_line(&line)?;
+            }
+        }
+    }
+
+    /// 1 行を処理する
+    fn process_line(&mut self, line: &str) -> Result<(), SseError> {
+        let (name, value) = match line.split_once(':') {
+            // コメント
+            Some(("", _)) => return Ok(()),
+            Some((name, value)) => (name, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match name {
+            "data" => {
+                let size = self.data.len() + usize::from(self.has_data) + value.len();
+                if size > self.max_buffer_size {
+                    return Err(SseError::BufferOverflow {
+                        size,
+                        limit: self.max_buffer_size,
+                    });
+                }
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+                self.has_data = true;
+            }
+            "event" => self.event = value.to_string(),
+            "id" if !value.contains('\0') => self.last_event_id = value.to_string(),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                // 桁あふれする値は無視する
+                if let Ok(retry) = value.parse() {
+                    self.event_retry = Some(retry);
+                    self.retry = Some(retry);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 空行でイベントを確定する
+    fn dispatch(&mut self) -> Option<Event> {
+        let event = core::mem::take(&mut self.event);
+        let retry = self.event_retry.take();
+        let data = core::mem::take(&mut self.data);
+        if !core::mem::take(&mut self.has_data) {
+            return None;
+        }
+        Some(Event {
+            id: (!self.last_event_id.is_empty()).then(|| self.last_event_id.clone()),
+            event: (!event.is_empty()).then_some(event),
+            data,
+            retry,
+        })
+    }
+}
+
+/// フィールドを 1 行追加
+fn push_field(buf: &mut Vec<u8>, name: &str, value: &str) {
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(b": ");
+    buf.extend_from_slice(value.as_bytes());
+    buf.push(b'\n');
+}
+
+/// CRLF / LF / CR で行に分割する
+fn split_lines(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(s);
+    core::iter::from_fn(move || {
+        let current = rest?;
+        match current.find(['\r', '\n']) {
+            Some(i) => {
+                let next = if current[i..].starts_with("\r\n") {
+                    i + 2
+                } else {
+                    i + 1
+                };
+                rest = Some(&current[next..]);
+                Some(&current[..i])
+            }
+            None => {
+                rest = None;
+                Some(current)
+            }
+        }
+    })
+}
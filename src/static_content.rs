@@ -0,0 +1,486 @@
+//! 静的コンテンツのレスポンス決定 (RFC 9110 Section 13 / Section 14)
+//!
+//! ## 概要
+//!
+//! 長さ・ETag・Last-Modified がわかっている表現 (ファイルなど) に対するリクエストから、
+//! 返すべきレスポンスのステータスとヘッダーを決めます。
+//! ボディの読み出しは行わないため、ブロッキング / 非同期どちらのサーバーからも使える。
+//!
+//! 条件付きリクエストは RFC 9110 Section 13.2.2 の順序で評価する。
+//!
+//! 1. If-Match (ない場合は If-Unmodified-Since) が偽なら 412
+//! 2. If-None-Match (ない場合は GET / HEAD の If-Modified-Since) が偽なら
+//!    GET / HEAD は 304、それ以外は 412
+//! 3. GET の Range を If-Range に従って評価し、満たせない場合は 416、
+//!    満たせる場合は 206 (複数範囲は multipart/byteranges)
+//! 4. それ以外は 200
+//!
+//! 不正な条件ヘッダーは無視する。ただし If-Match は不正な場合も一致しないものとして扱う。
+//! 日時のヘッダーが複数行ある場合と、Range が複数行ある場合も無視する。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::RequestDecoder;
+//! use shiguredo_http11::etag::EntityTag;
+//! use shiguredo_http11::static_content::{PlanStatus, StaticResource};
+//!
+//! let resource = StaticResource::new(1000).with_etag(EntityTag::strong("v1").unwrap());
+//!
+//! let mut decoder = RequestDecoder::new();
+//! decoder
+//!     .feed(b"GET /file HTTP/1.1\r\nHost: example.com\r\nRange: bytes=0-99\r\n\r\n")
+//!     .unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//!
+//! let plan = resource.plan(&head, 0);
+//! assert_eq!(plan.status(), PlanStatus::PartialContent);
+//! assert_eq!(plan.ranges(), &[(0, 99)]);
+//! assert_eq!(plan.get_header("Content-Range"), Some("bytes 0-99/1000"));
+//! assert_eq!(plan.get_header("Content-Length"), Some("100"));
+//! assert_eq!(plan.get_header("ETag"), Some("\"v1\""));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::conditional::{IfMatch, IfModifiedSince, IfNoneMatch, IfUnmodifiedSince};
+use crate::content_type::ContentType;
+use crate::date::HttpDate;
+use crate::decoder::{HttpHead, RequestHead};
+use crate::error::EncodeError;
+use crate::etag::EntityTag;
+use crate::range::{
+    AcceptRanges, ByteRangesBuilder, ContentRange, Range, RangeSatisfaction, if_range_allows,
+};
+use crate::response::Response;
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
+
+/// 静的コンテンツの表現のメタデータ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticResource {
+    length: u64,
+    etag: Option<EntityTag>,
+    last_modified: Option<HttpDate>,
+    content_type: Option<ContentType>,
+}
+
+impl StaticResource {
+    /// 表現の長さを指定して作成
+    pub fn new(length: u64) -> Self {
+        StaticResource {
+            length,
+            etag: None,
+            last_modified: None,
+            content_type: None,
+        }
+    }
+
+    /// ETag を指定
+    pub fn with_etag(mut self, etag: EntityTag) -> Self {
+        self.etag = Some(etag);
+        self
+    }
+
+    /// Last-Modified を指定
+    ///
+    /// If-Range の日時との比較にも使うため、強いバリデーターとして扱える
+    /// 日時 (RFC 9110 Section 8.8.2.2) を指定すること。
+    pub fn with_last_modified(mut self, last_modified: HttpDate) -> Self {
+        self.last_modified = Some(last_modified);
+        self
+    }
+
+    /// 表現の Content-Type を指定
+    ///
+    /// 200 と単一範囲の 206 の Content-Type、multipart/byteranges の各パートの
+    /// Content-Type に使う。
+    pub fn with_content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// 表現の長さ
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// ETag
+    pub fn etag(&self) -> Option<&EntityTag> {
+        self.etag.as_ref()
+    }
+
+    /// Last-Modified
+    pub fn last_modified(&self) -> Option<&HttpDate> {
+        self.last_modified.as_ref()
+    }
+
+    /// リクエストに対するレスポンスを決める
+    ///
+    /// `random_value` は multipart/byteranges の境界の生成に使う乱数。
+    pub fn plan(&self, request: &RequestHead, random_value: u64) -> ResponsePlan {
+        let method = request.method();
+        let is_get_or_head = method == "GET" || method == "HEAD";
+
+        if !self.evaluate_if_match(request) {
+            return self.plan_without_body(PlanStatus::PreconditionFailed);
+        }
+        if !self.evaluate_if_none_match(request, is_get_or_head) {
+            let status = if is_get_or_head {
+                PlanStatus::NotModified
+            } else {
+                PlanStatus::PreconditionFailed
+            };
+            return self.plan_without_body(status);
+        }
+
+        // RFC 9110 Section 14.2: Range は GET 以外では無視する
+        let satisfaction = if method == "GET" {
+            self.evaluate_range(request)
+        } else {
+            RangeSatisfaction::Ignored
+        };
+        match satisfaction {
+            RangeSatisfaction::Ignored => self.plan_full(method != "HEAD"),
+            RangeSatisfaction::Unsatisfiable { complete_length } => {
+                let headers = alloc::vec![
+                    (
+                        "Content-Range".to_string(),
+                        ContentRange::unsatisfied("bytes", complete_length).to_string(),
+                    ),
+                    (
+                        AcceptRanges::NAME.to_string(),
+                        AcceptRanges::bytes().to_string(),
+                    ),
+                ];
+                ResponsePlan {
+                    status: PlanStatus::RangeNotSatisfiable,
+                    headers,
+                    ranges: Vec::new(),
+                    boundary: None,
+                    complete_length,
+                    content_type: self.content_type.clone(),
+                    has_body: false,
+                }
+            }
+            RangeSatisfaction::Satisfiable { ranges, .. } => {
+                self.plan_partial(ranges, random_value)
+            }
+        }
+    }
+
+    /// If-Match / If-Unmodified-Since を評価する (RFC 9110 Section 13.2.2 の 1 と 2)
+    fn evaluate_if_match(&self, request: &RequestHead) -> bool {
+        if let Some(value) = joined_header(request, "If-Match") {
+            // 表現は存在するため `*` は常に一致する
+            return IfMatch::parse(&value).is_ok_and(|if_match| {
+                if_match.is_any()
+                    || self
+                        .etag
+                        .as_ref()
+                        .is_some_and(|etag| if_match.matches(etag))
+            });
+        }
+        match (
+            single_header(request, "If-Unmodified-Since"),
+            &self.last_modified,
+        ) {
+            (Some(value), Some(last_modified)) => {
+                IfUnmodifiedSince::parse(value, last_modified.year())
+                    .map_or(true, |since| last_modified <= since.date())
+            }
+            _ => true,
+        }
+    }
+
+    /// If-None-Match / If-Modified-Since を評価する (RFC 9110 Section 13.2.2 の 3 と 4)
+    fn evaluate_if_none_match(&self, request: &RequestHead, is_get_or_head: bool) -> bool {
+        if let Some(value) = joined_header(request, "If-None-Match") {
+            return IfNoneMatch::parse(&value).map_or(true, |if_none_match| {
+                !if_none_match.is_any()
+                    && self
+                        .etag
+                        .as_ref()
+                        .is_none_or(|etag| if_none_match.matches(etag))
+            });
+        }
+        if !is_get_or_head {
+            return true;
+        }
+        match (
+            single_header(request, "If-Modified-Since"),
+            &self.last_modified,
+        ) {
+            (Some(value), Some(last_modified)) => {
+                IfModifiedSince::parse(value, last_modified.year())
+                    .map_or(true, |since| since.is_modified(last_modified))
+            }
+            _ => true,
+        }
+    }
+
+    /// Range / If-Range を評価する (RFC 9110 Section 13.2.2 の 5)
+    fn evaluate_range(&self, request: &RequestHead) -> RangeSatisfaction {
+        let Some(range) = single_header(request, "Range").and_then(|v| Range::parse(v).ok()) else {
+            return RangeSatisfaction::Ignored;
+        };
+        if let Some(if_range) = joined_header(request, "If-Range")
+            && !if_range_allows(&if_range, self.etag.as_ref(), self.last_modified.as_ref())
+        {
+            return RangeSatisfaction::Ignored;
+        }
+        range.evaluate(self.length)
+    }
+
+    /// 304 / 412 のレスポンスを決める
+    fn plan_without_body(&self, status: PlanStatus) -> ResponsePlan {
+        let mut headers = Vec::new();
+        if status == PlanStatus::NotModified {
+            // RFC 9110 Section 15.4.5: Last-Modified は ETag がない場合のみ付与する
+            if let Some(etag) = &self.etag {
+                headers.push(("ETag".to_string(), etag.to_string()));
+            } else if let Some(last_modified) = &self.last_modified {
+                headers.push(("Last-Modified".to_string(), last_modified.to_string()));
+            }
+        }
+        ResponsePlan {
+            status,
+            headers,
+            ranges: Vec::new(),
+            boundary: None,
+            complete_length: self.length,
+            content_type: self.content_type.clone(),
+            has_body: false,
+        }
+    }
+
+    /// 200 のレスポンスを決める
+    fn plan_full(&self, has_body: bool) -> ResponsePlan {
+        let mut headers = Vec::new();
+        if let Some(content_type) = &self.content_type {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+        headers.push(("Content-Length".to_string(), self.length.to_string()));
+        self.push_validators(&mut headers);
+        ResponsePlan {
+            status: PlanStatus::Ok,
+            headers,
+            ranges: Vec::new(),
+            boundary: None,
+            complete_length: self.length,
+            content_type: self.content_type.clone(),
+            has_body,
+        }
+    }
+
+    /// 206 のレスポンスを決める
+    fn plan_partial(&self, ranges: Vec<(u64, u64)>, random_value: u64) -> ResponsePlan {
+        let mut headers = Vec::new();
+        let mut boundary = None;
+        if let [(start, end)] = ranges[..] {
+            if let Some(content_type) = &self.content_type {
+                headers.push(("Content-Type".to_string(), content_type.to_string()));
+            }
+            headers.push(("Content-Length".to_string(), (end - start + 1).to_string()));
+            headers.push((
+                "Content-Range".to_string(),
+                ContentRange::new_bytes(start, end, Some(self.length)).to_string(),
+            ));
+        } else {
+            let builder = ByteRangesBuilder::new(random_value, self.length);
+            headers.push(("Content-Type".to_string(), builder.content_type()));
+            headers.push((
+                "Content-Length".to_string(),
+                self.byteranges_length(builder.boundary(), &ranges)
+                    .to_string(),
+            ));
+            boundary = Some(builder.boundary().to_string());
+        }
+        self.push_validators(&mut headers);
+        ResponsePlan {
+            status: PlanStatus::PartialContent,
+            headers,
+            ranges,
+            boundary,
+            complete_length: self.length,
+            content_type: self.content_type.clone(),
+            has_body: true,
+        }
+    }
+
+    /// Accept-Ranges / ETag / Last-Modified を追加
+    fn push_validators(&self, headers: &mut Vec<(String, String)>) {
+        headers.push((
+            AcceptRanges::NAME.to_string(),
+            AcceptRanges::bytes().to_string(),
+        ));
+        if let Some(etag) = &self.etag {
+            headers.push(("ETag".to_string(), etag.to_string()));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push(("Last-Modified".to_string(), last_modified.to_string()));
+        }
+    }
+
+    /// `ByteRangesBuilder::build()` が生成する multipart/byteranges の長さを計算
+    fn byteranges_length(&self, boundary: &str, ranges: &[(u64, u64)]) -> u64 {
+        let boundary_length = boundary.len() as u64;
+        let content_type_length = self.content_type.as_ref().map_or(0, |content_type| {
+            // "Content-Type: " + 値 + CRLF
+            14 + content_type.to_string().len() as u64 + 2
+        });
+        let parts: u64 = ranges
+            .iter()
+            .map(|&(start, end)| {
+                let content_range = ContentRange::new_bytes(start, end, Some(self.length));
+                // "--" + 境界 + CRLF
+                (2 + boundary_length + 2)
+                    + content_type_length
+                    // "Content-Range: " + 値 + CRLF + CRLF
+                    + (15 + content_range.to_string().len() as u64 + 2 + 2)
+                    // データ + CRLF
+                    + (end - start + 1 + 2)
+            })
+            .sum();
+        // "--" + 境界 + "--" + CRLF
+        parts + 2 + boundary_length + 4
+    }
+}
+
+/// 決定したレスポンスのステータス
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanStatus {
+    /// 200 OK: 表現全体を返す
+    Ok,
+    /// 206 Partial Content: 範囲を返す
+    PartialContent,
+    /// 304 Not Modified
+    NotModified,
+    /// 412 Precondition Failed
+    PreconditionFailed,
+    /// 416 Range Not Satisfiable
+    RangeNotSatisfiable,
+}
+
+impl PlanStatus {
+    /// ステータスコード
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            PlanStatus::Ok => StatusCode::OK,
+            PlanStatus::PartialContent => StatusCode::PARTIAL_CONTENT,
+            PlanStatus::NotModified => StatusCode::NOT_MODIFIED,
+            PlanStatus::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            PlanStatus::RangeNotSatisfiable => StatusCode::RANGE_NOT_SATISFIABLE,
+        }
+    }
+}
+
+/// 決定したレスポンス ([`StaticResource::plan`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponsePlan {
+    status: PlanStatus,
+    headers: Vec<(String, String)>,
+    ranges: Vec<(u64, u64)>,
+    boundary: Option<String>,
+    complete_length: u64,
+    content_type: Option<ContentType>,
+    has_body: bool,
+}
+
+impl ResponsePlan {
+    /// ステータス
+    pub fn status(&self) -> PlanStatus {
+        self.status
+    }
+
+    /// ステータスコード
+    pub fn status_code(&self) -> u16 {
+        self.status.status_code().code()
+    }
+
+    /// レスポンスヘッダー
+    ///
+    /// Content-Type / Content-Length / Content-Range / Accept-Ranges / ETag /
+    /// Last-Modified のうち必要なものを含む。412 と 416 では Content-Length を含まない。
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// ヘッダーを取得
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 206 で返す範囲 (両端含む)
+    ///
+    /// 昇順に並べて重複・隣接を結合している。206 以外では空を返す。
+    pub fn ranges(&self) -> &[(u64, u64)] {
+        &self.ranges
+    }
+
+    /// 206 が multipart/byteranges か
+    pub fn is_multipart(&self) -> bool {
+        self.boundary.is_some()
+    }
+
+    /// 表現のデータを送信するか
+    ///
+    /// GET の 200 と 206 で true になる。HEAD の 200 では Content-Length などは
+    /// GET と同じになるが、データは送信しない。
+    pub fn has_body(&self) -> bool {
+        self.has_body
+    }
+
+    /// multipart/byteranges のボディを生成するビルダー
+    ///
+    /// Content-Type ヘッダーと同じ境界を使う。`ranges()` の各範囲のデータを
+    /// 順に `part()` で追加して `build()` すると、Content-Length と同じ長さになる。
+    /// multipart/byteranges ではない場合は `None` を返す。
+    pub fn byte_ranges_builder(&self) -> Option<ByteRangesBuilder> {
+        let boundary = self.boundary.as_deref()?;
+        let builder = ByteRangesBuilder::with_boundary(boundary, self.complete_length);
+        Some(match &self.content_type {
+            Some(content_type) => builder.with_content_type(content_type.clone()),
+            None => builder,
+        })
+    }
+
+    /// ボディのない Response を生成
+    ///
+    /// 200 / 206 では呼び出し側が Content-Length と同じ長さのボディを設定するか、
+    /// ヘッダーのみをエンコードしてボディを続けて送信する。
+    /// HEAD の 200 では `omit_body(true)` を設定する。412 / 416 は空のボディになる。
+    pub fn to_response(&self) -> Result<Response, EncodeError> {
+        let mut response = Response::with_status(self.status.status_code());
+        for (name, value) in &self.headers {
+            response.add_header(name.as_str(), value.as_str())?;
+        }
+        Ok(match self.status {
+            PlanStatus::PreconditionFailed | PlanStatus::RangeNotSatisfiable => {
+                response.body(Vec::new())
+            }
+            PlanStatus::Ok if !self.has_body => response.omit_body(true),
+            _ => response,
+        })
+    }
+}
+
+/// 同名のヘッダーを `", "` で結合して取得
+fn joined_header(request: &RequestHead, name: &str) -> Option<String> {
+    let values = request.get_headers(name);
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+/// 1 行だけのヘッダーを取得
+///
+/// 複数行ある場合はリストを取らないフィールドとして不正なため `None` を返す。
+fn single_header<'a>(request: &'a RequestHead, name: &str) -> Option<&'a str> {
+    match request.get_headers(name)[..] {
+        [value] => Some(value),
+        _ => None,
+    }
+}
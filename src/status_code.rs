@@ -7,7 +7,9 @@
 //! `Response::new(code, reason)` または `Response::with_version(version, code, reason)` を
 //! 使用すること。本型は IANA 登録済み code 専用の const 表現を提供する。
 
+use core::fmt;
 use core::num::NonZeroU16;
+use core::str::FromStr;
 
 /// HTTP ステータスコード
 ///
@@ -285,6 +287,31 @@ impl StatusCode {
         })
     }
 
+    /// 1xx Informational か (RFC 9110 Section 15.2)
+    pub const fn is_informational(&self) -> bool {
+        matches!(self.code.get(), 100..=199)
+    }
+
+    /// 2xx Successful か (RFC 9110 Section 15.3)
+    pub const fn is_success(&self) -> bool {
+        matches!(self.code.get(), 200..=299)
+    }
+
+    /// 3xx Redirection か (RFC 9110 Section 15.4)
+    pub const fn is_redirection(&self) -> bool {
+        matches!(self.code.get(), 300..=399)
+    }
+
+    /// 4xx Client Error か (RFC 9110 Section 15.5)
+    pub const fn is_client_error(&self) -> bool {
+        matches!(self.code.get(), 400..=499)
+    }
+
+    /// 5xx Server Error か (RFC 9110 Section 15.6)
+    pub const fn is_server_error(&self) -> bool {
+        matches!(self.code.get(), 500..=599)
+    }
+
     /// この `StatusCode` のクラス分類を返す。
     ///
     /// `StatusCode` は構築時に `100..=599` が保証されているため、
@@ -304,6 +331,69 @@ impl StatusCode {
     }
 }
 
+impl fmt::Display for StatusCode {
+    /// `404 Not Found` のように status-code と canonical reason phrase を出力する
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code(), self.canonical_reason())
+    }
+}
+
+impl FromStr for StatusCode {
+    type Err = StatusCodeError;
+
+    /// 3 桁の status-code 文字列から IANA 登録済みの `StatusCode` を得る
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 3 || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(StatusCodeError::InvalidFormat);
+        }
+        let code = s
+            .parse::<u16>()
+            .map_err(|_| StatusCodeError::InvalidFormat)?;
+        StatusCode::from_code(code).ok_or(StatusCodeError::Unregistered { code })
+    }
+}
+
+impl From<StatusCode> for u16 {
+    fn from(status: StatusCode) -> Self {
+        status.code()
+    }
+}
+
+impl PartialEq<u16> for StatusCode {
+    fn eq(&self, other: &u16) -> bool {
+        self.code() == *other
+    }
+}
+
+impl PartialEq<StatusCode> for u16 {
+    fn eq(&self, other: &StatusCode) -> bool {
+        *self == other.code()
+    }
+}
+
+/// `StatusCode` のパースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StatusCodeError {
+    /// 3 桁の数字でない
+    InvalidFormat,
+    /// IANA 未登録のステータスコード
+    Unregistered { code: u16 },
+}
+
+impl fmt::Display for StatusCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatusCodeError::InvalidFormat => write!(f, "invalid status code format"),
+            StatusCodeError::Unregistered { code } => {
+                write!(f, "unregistered status code: {code}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for StatusCodeError {}
+
 /// HTTP ステータスコードのクラス分類 — RFC 9110 Section 15 準拠。
 ///
 /// # 分類表
@@ -356,3 +446,22 @@ impl StatusClass {
         })
     }
 }
+
+/// ステータスコード値に対応する IANA 登録の canonical reason phrase を返す
+///
+/// 未登録コード (一時登録 / 私的拡張 / 範囲外を含む) は空文字列を返す。
+/// RFC 9112 Section 4 により reason-phrase は空でもよく、クライアントは
+/// reason-phrase の内容を無視すべき (SHOULD) とされている。
+///
+/// ```rust
+/// use shiguredo_http11::status_code::reason_phrase_for;
+///
+/// assert_eq!(reason_phrase_for(404), "Not Found");
+/// assert_eq!(reason_phrase_for(599), "");
+/// ```
+pub const fn reason_phrase_for(code: u16) -> &'static str {
+    match StatusCode::from_code(code) {
+        Some(status) => status.canonical_reason(),
+        None => "",
+    }
+}
@@ -0,0 +1,341 @@
+//! TE ヘッダーパース (RFC 9110 Section 10.1.4)
+//!
+//! ## 概要
+//!
+//! RFC 9110 に基づいた TE リクエストヘッダーのパースと生成を提供します。
+//!
+//! TE はクライアントがレスポンスで受け付ける転送コーディングと、
+//! chunked のトレーラーを受け付けるかどうか (`trailers`) を示す。
+//! サーバーはこれを見て、トレーラーを送るか、chunked 以外の転送コーディングを使うかを決める。
+//!
+//! - chunked は HTTP/1.1 の受信者が常に受け付けるため、TE に含まれていなくても受理される
+//! - `trailers` はパラメーターと重みを持たない
+//!
+//! ## ABNF
+//!
+//! ```text
+//! TE                 = #t-codings
+//! t-codings          = "trailers" / ( transfer-coding [ weight ] )
+//! transfer-coding    = token *( OWS ";" OWS transfer-parameter )
+//! transfer-parameter = token BWS "=" BWS ( token / quoted-string )
+//! weight             = OWS ";" OWS "q=" qvalue
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::te::Te;
+//!
+//! let te = Te::parse("trailers, gzip;q=0.5, deflate;q=0").unwrap();
+//! assert!(te.accepts_trailers());
+//! assert!(te.accepts("gzip"));
+//! assert!(!te.accepts("deflate"));
+//! // chunked は常に受理される
+//! assert!(te.accepts("chunked"));
+//! assert_eq!(te.negotiate(&["deflate", "gzip"]), Some("gzip"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::accept::QValue;
+use crate::decoder::HttpHead;
+use crate::typed_header::TypedHeader;
+use crate::validate::{
+    QuotedStringError, escape_quotes, is_token_char, is_valid_token, parse_quoted_string,
+    split_with_quotes, trim_ows,
+};
+
+/// TE パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TeError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正な転送コーディング名
+    InvalidCoding,
+    /// 不正な転送パラメーター
+    InvalidParameter,
+    /// 不正な q 値
+    InvalidQValue,
+    /// quoted-string の閉じ DQUOTE が見つからない (RFC 9110 Section 5.6.4)
+    UnterminatedQuote,
+}
+
+impl fmt::Display for TeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TeError::InvalidFormat => write!(f, "invalid TE header format"),
+            TeError::InvalidCoding => write!(f, "invalid transfer coding"),
+            TeError::InvalidParameter => write!(f, "invalid transfer parameter"),
+            TeError::InvalidQValue => write!(f, "invalid qvalue"),
+            TeError::UnterminatedQuote => write!(f, "unterminated quoted-string"),
+        }
+    }
+}
+
+impl core::error::Error for TeError {}
+
+impl From<QuotedStringError> for TeError {
+    fn from(e: QuotedStringError) -> Self {
+        match e {
+            QuotedStringError::InvalidQdtext | QuotedStringError::InvalidQuotedPair => {
+                TeError::InvalidParameter
+            }
+            QuotedStringError::Unterminated => TeError::UnterminatedQuote,
+        }
+    }
+}
+
+/// TE ヘッダー
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Te {
+    trailers: bool,
+    codings: Vec<TransferCoding>,
+}
+
+impl Te {
+    /// 空の TE を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// TE ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 受信者は空のリスト要素を無視しなければならない (MUST)。
+    /// 空の値は空リストとして受理する。転送コーディング名は小文字に正規化する。
+    pub fn parse(input: &str) -> Result<Self, TeError> {
+        let mut te = Te::new();
+        for part in split_with_quotes(trim_ows(input), ',') {
+            let part = trim_ows(&part);
+            if part.is_empty() {
+                continue;
+            }
+            let mut parts = split_with_quotes(part, ';').into_iter();
+            let coding_raw = parts.next().unwrap_or_default();
+            let coding = trim_ows(&coding_raw);
+            if coding.eq_ignore_ascii_case("trailers") {
+                // trailers はパラメーターも重みも持たない
+                if parts.next().is_some() {
+                    return Err(TeError::InvalidFormat);
+                }
+                te.trailers = true;
+                continue;
+            }
+            if coding.is_empty() {
+                return Err(TeError::InvalidFormat);
+            }
+            if !is_valid_token(coding) {
+                return Err(TeError::InvalidCoding);
+            }
+            let mut transfer_coding = TransferCoding::new(coding);
+            let mut q_seen = false;
+            for param in parts {
+                let param = trim_ows(&param);
+                if param.is_empty() {
+                    continue;
+                }
+                let (name, value) = param.split_once('=').ok_or(TeError::InvalidParameter)?;
+                let name = trim_ows(name);
+                // weight は最後に置く
+                if q_seen {
+                    return Err(TeError::InvalidParameter);
+                }
+                if name.eq_ignore_ascii_case("q") {
+                    transfer_coding.q =
+                        QValue::parse(trim_ows(value)).map_err(|_| TeError::InvalidQValue)?;
+                    q_seen = true;
+                } else {
+                    if !is_valid_token(name) {
+                        return Err(TeError::InvalidParameter);
+                    }
+                    transfer_coding
+                        .params
+                        .push((name.to_ascii_lowercase(), parse_param_value(value)?));
+                }
+            }
+            te.codings.push(transfer_coding);
+        }
+        Ok(te)
+    }
+
+    /// リクエストの TE ヘッダーを取得
+    ///
+    /// 複数行ある場合は `, ` で連結してパースする。
+    pub fn from_request<H: HttpHead>(request: &H) -> Result<Option<Self>, TeError> {
+        let values = request.get_headers("TE");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(&values.join(", ")).map(Some)
+    }
+
+    /// `trailers` を追加 (ビルダーパターン)
+    pub fn with_trailers(mut self) -> Self {
+        self.trailers = true;
+        self
+    }
+
+    /// 転送コーディングを追加 (ビルダーパターン)
+    ///
+    /// クライアントは chunked を TE に含めてはならない (MUST NOT) ため、
+    /// `chunked` と `trailers` はエラーにする。
+    pub fn with_coding(mut self, coding: &str, q: QValue) -> Result<Self, TeError> {
+        if !is_valid_token(coding)
+            || coding.eq_ignore_ascii_case("chunked")
+            || coding.eq_ignore_ascii_case("trailers")
+        {
+            return Err(TeError::InvalidCoding);
+        }
+        let mut transfer_coding = TransferCoding::new(coding);
+        transfer_coding.q = q;
+        self.codings.push(transfer_coding);
+        Ok(self)
+    }
+
+    /// chunked のトレーラーを受け付けるか
+    pub fn accepts_trailers(&self) -> bool {
+        self.trailers
+    }
+
+    /// 転送コーディングの一覧 (`trailers` は含まない)
+    pub fn codings(&self) -> &[TransferCoding] {
+        &self.codings
+    }
+
+    /// 転送コーディングに対する q 値を取得
+    ///
+    /// chunked は常に受理されるため、TE に含まれない場合も q=1 を返す。
+    /// それ以外で一致する転送コーディングがない場合は `None`。
+    pub fn quality(&self, coding: &str) -> Option<QValue> {
+        let found = self
+            .codings
+            .iter()
+            .find(|c| c.coding.eq_ignore_ascii_case(coding))
+            .map(|c| c.q);
+        if found.is_none() && coding.eq_ignore_ascii_case("chunked") {
+            return Some(QValue::default());
+        }
+        found
+    }
+
+    /// 転送コーディングを受け付けるか (q 値が 0 より大きいか)
+    pub fn accepts(&self, coding: &str) -> bool {
+        self.quality(coding).is_some_and(|q| q.value() > 0)
+    }
+
+    /// 提供可能な転送コーディングから TE に最も適合するものを選ぶ
+    ///
+    /// `available` はサーバーが対応する転送コーディングを優先順に並べたもの。
+    /// q 値が最大の候補を返し、q 値が同じ場合は `available` で先に現れた候補を選ぶ。
+    /// どの候補も受理されない場合は `None` を返す。この場合は chunked のみで送ればよい。
+    pub fn negotiate<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        let mut best: Option<(&'a str, QValue)> = None;
+        for &candidate in available {
+            let Some(q) = self.quality(candidate) else {
+                continue;
+            };
+            if q.value() == 0 {
+                continue;
+            }
+            if best.is_none_or(|(_, best_q)| q > best_q) {
+                best = Some((candidate, q));
+            }
+        }
+        best.map(|(candidate, _)| candidate)
+    }
+}
+
+impl fmt::Display for Te {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut values: Vec<String> = Vec::new();
+        if self.trailers {
+            values.push("trailers".to_string());
+        }
+        values.extend(self.codings.iter().map(|coding| coding.to_string()));
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+impl TypedHeader for Te {
+    const NAME: &'static str = "TE";
+    type Error = TeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// TE の転送コーディング
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferCoding {
+    coding: String,
+    params: Vec<(String, String)>,
+    q: QValue,
+}
+
+impl TransferCoding {
+    fn new(coding: &str) -> Self {
+        TransferCoding {
+            coding: coding.to_ascii_lowercase(),
+            params: Vec::new(),
+            q: QValue::default(),
+        }
+    }
+
+    /// 転送コーディング名 (小文字)
+    pub fn coding(&self) -> &str {
+        &self.coding
+    }
+
+    /// 転送パラメーター (名前は小文字)
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// q 値
+    pub fn qvalue(&self) -> QValue {
+        self.q
+    }
+}
+
+impl fmt::Display for TransferCoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.coding)?;
+        for (name, value) in &self.params {
+            if needs_quoting(value) {
+                write!(f, "; {}=\"{}\"", name, escape_quotes(value))?;
+            } else {
+                write!(f, "; {}={}", name, value)?;
+            }
+        }
+        if self.q.value() < 1000 {
+            write!(f, "; q={}", self.q)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_param_value(input: &str) -> Result<String, TeError> {
+    let input = trim_ows(input);
+    if let Some(rest) = input.strip_prefix('"') {
+        let (value, remaining) = parse_quoted_string(rest)?;
+        if !trim_ows(remaining).is_empty() {
+            return Err(TeError::InvalidParameter);
+        }
+        Ok(value)
+    } else if !is_valid_token(input) {
+        Err(TeError::InvalidParameter)
+    } else {
+        Ok(input.to_string())
+    }
+}
+
+fn needs_quoting(s: &str) -> bool {
+    s.is_empty() || s.bytes().any(|b| !is_token_char(b))
+}
@@ -0,0 +1,152 @@
+//! ワイヤーレベルのトレース
+//!
+//! ## 概要
+//!
+//! デコーダーとエンコーダーが扱ったバイト列を、パース単位ごとに観測するための
+//! フックを提供する。デバッグ用ミドルウェアやワイヤーキャプチャを、パーサーを
+//! 改変せずに実装するために使う。
+//!
+//! - デコーダー: start-line / ヘッダー行 / chunk-size 行 / トレーラー行をパースした時点で通知する
+//! - エンコーダー: `RequestEncoder` / `ResponseEncoder` でエンコードしたメッセージを通知する
+//!
+//! イベントは受信または送信したバイト列 (CRLF を除く) と、絶対バイトオフセット等の
+//! 時刻に依存しないメタデータのみを持つ。時刻が必要な場合は observer 側で取得する。
+//!
+//! observer を設定していない場合、イベントは構築しない。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//!
+//! use shiguredo_http11::RequestDecoder;
+//! use shiguredo_http11::trace::WireEvent;
+//!
+//! let lines = Arc::new(Mutex::new(Vec::new()));
+//! let captured = lines.clone();
+//!
+//! let mut decoder = RequestDecoder::new();
+//! decoder.set_wire_observer(Box::new(move |event: WireEvent<'_>| {
+//!     if let WireEvent::Header { name, .. } = event {
+//!         captured.lock().unwrap().push(name.to_string());
+//!     }
+//! }));
+//! decoder.feed(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n").unwrap();
+//! decoder.decode().unwrap().unwrap();
+//!
+//! assert_eq!(*lines.lock().unwrap(), ["Host"]);
+//! ```
+
+use alloc::boxed::Box;
+use core::fmt;
+
+/// ワイヤーレベルのイベント
+///
+/// デコーダーのイベントの `offset` は、デコーダーに投入したバイト列の先頭からの
+/// 絶対バイトオフセット (`DecoderStats::bytes_fed` 基準) で、`reset()` まで累計する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WireEvent<'a> {
+    /// start-line (request-line / status-line) をパースした
+    StartLine {
+        /// 行の先頭の絶対バイトオフセット
+        offset: u64,
+        /// 受信した行 (CRLF を除く)
+        raw: &'a [u8],
+    },
+    /// ヘッダー行をパースした
+    Header {
+        /// 行の先頭の絶対バイトオフセット
+        offset: u64,
+        /// field-name
+        name: &'a str,
+        /// field-value (前後の OWS を除去済み)
+        value: &'a str,
+        /// 受信した行 (CRLF を除く)
+        raw: &'a [u8],
+    },
+    /// chunk-size 行をパースした
+    ///
+    /// last-chunk (`size` が 0) も含む。
+    Chunk {
+        /// 行の先頭の絶対バイトオフセット
+        offset: u64,
+        /// chunk-size
+        size: usize,
+        /// chunk-ext (chunk-size 直後から CRLF の手前まで、BWS を含む)
+        ext: &'a [u8],
+        /// 受信した行 (CRLF を除く)
+        raw: &'a [u8],
+    },
+    /// トレーラー行をパースした
+    Trailer {
+        /// 行の先頭の絶対バイトオフセット
+        offset: u64,
+        /// field-name
+        name: &'a str,
+        /// field-value (前後の OWS を除去済み)
+        value: &'a str,
+        /// 受信した行 (CRLF を除く)
+        raw: &'a [u8],
+    },
+    /// メッセージをエンコードした
+    Encoded {
+        /// エンコードしたバイト列
+        raw: &'a [u8],
+    },
+}
+
+/// ワイヤーレベルのイベントを受け取る observer
+///
+/// `FnMut(WireEvent<'_>) + Send` を満たすクロージャはこのトレイトを実装する。
+pub trait WireObserver: Send {
+    /// イベントを受け取る
+    fn on_event(&mut self, event: WireEvent<'_>);
+}
+
+impl<F> WireObserver for F
+where
+    F: FnMut(WireEvent<'_>) + Send,
+{
+    fn on_event(&mut self, event: WireEvent<'_>) {
+        self(event)
+    }
+}
+
+/// デコーダー / エンコーダーが保持する observer
+#[derive(Default)]
+pub(crate) struct WireTracer {
+    observer: Option<Box<dyn WireObserver>>,
+}
+
+impl WireTracer {
+    /// observer を設定する
+    pub(crate) fn set(&mut self, observer: Box<dyn WireObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// observer を取り外して返す
+    pub(crate) fn take(&mut self) -> Option<Box<dyn WireObserver>> {
+        self.observer.take()
+    }
+
+    /// observer が設定されているか
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.observer.is_some()
+    }
+
+    /// observer が設定されていればイベントを通知する
+    pub(crate) fn emit(&mut self, event: WireEvent<'_>) {
+        if let Some(observer) = self.observer.as_mut() {
+            observer.on_event(event);
+        }
+    }
+}
+
+impl fmt::Debug for WireTracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WireTracer")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
@@ -15,11 +15,41 @@
 //! let trailer = Trailer::parse("X-Checksum, X-Test").unwrap();
 //! assert_eq!(trailer.fields().len(), 2);
 //! ```
+//!
+//! ## トレーラーの送信
+//!
+//! 送信するトレーラーは `Trailer` ヘッダーで事前に申告する (RFC 9110 Section 6.6.2)。
+//! クライアントがトレーラーを受け付けるかは `TE: trailers` で判定する
+//! (RFC 9110 Section 10.1.4)。
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//!
+//! use shiguredo_http11::trailer::{Trailer, accepts_trailers};
+//! use shiguredo_http11::{Request, Response, StatusCode};
+//!
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("TE", "trailers")
+//!     .unwrap();
+//! assert!(accepts_trailers(&request));
+//!
+//! let trailer = Trailer::new().with_field("X-Checksum").unwrap();
+//! let mut response = Response::with_status(StatusCode::OK);
+//! response.set_typed(&trailer).unwrap();
+//! assert_eq!(response.get_header("Trailer"), Some("x-checksum"));
+//!
+//! let trailers = [(Cow::Borrowed("X-Checksum"), "abc".to_string())];
+//! assert!(trailer.validate_fields(&trailers).is_ok());
+//! ```
 
-use alloc::string::String;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::decoder::HttpHead;
+use crate::typed_header::TypedHeader;
 use crate::validate::{is_valid_token, trim_ows};
 
 /// Trailer パースエラー
@@ -34,6 +64,8 @@ pub enum TrailerError {
     InvalidFieldName,
     /// 禁止フィールド (RFC 9112 Section 7.1.2)
     ProhibitedField(String),
+    /// Trailer ヘッダーで申告していないフィールド (RFC 9110 Section 6.6.2)
+    UndeclaredField(String),
 }
 
 impl fmt::Display for TrailerError {
@@ -45,6 +77,9 @@ impl fmt::Display for TrailerError {
             TrailerError::ProhibitedField(name) => {
                 write!(f, "prohibited trailer field: {}", name)
             }
+            TrailerError::UndeclaredField(name) => {
+                write!(f, "undeclared trailer field: {}", name)
+            }
         }
     }
 }
@@ -72,26 +107,95 @@ impl Trailer {
             if name.is_empty() {
                 continue;
             }
-            if !is_valid_token(name) {
-                return Err(TrailerError::InvalidFieldName);
-            }
-            let lower_name = name.to_ascii_lowercase();
-
-            // RFC 9112 Section 7.1.2: 禁止フィールドチェック
-            if is_prohibited_trailer_field(&lower_name) {
-                return Err(TrailerError::ProhibitedField(lower_name));
-            }
-
-            fields.push(lower_name);
+            fields.push(validate_field_name(name)?);
         }
 
         Ok(Trailer { fields })
     }
 
+    /// 空の Trailer ヘッダーを作成
+    pub fn new() -> Self {
+        Trailer { fields: Vec::new() }
+    }
+
+    /// 送信するトレーラーのフィールド名を追加 (ビルダーパターン)
+    ///
+    /// 名前は小文字にし、既に追加済みの名前は追加しない。
+    /// RFC 9110 Section 6.5.1 でトレーラーに置けないフィールドは `ProhibitedField` を返す。
+    pub fn with_field(mut self, name: &str) -> Result<Self, TrailerError> {
+        let name = validate_field_name(name)?;
+        if !self.fields.contains(&name) {
+            self.fields.push(name);
+        }
+        Ok(self)
+    }
+
     /// Trailer フィールド名 (小文字)
     pub fn fields(&self) -> &[String] {
         &self.fields
     }
+
+    /// フィールド名が申告されているか (大文字小文字を区別しない)
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.eq_ignore_ascii_case(name))
+    }
+
+    /// 送信するトレーラーが申告どおりか検証
+    ///
+    /// トレーラーに置けないフィールドは `ProhibitedField`、
+    /// 申告していないフィールドは `UndeclaredField` を返す。
+    pub fn validate_fields(
+        &self,
+        trailers: &[(Cow<'static, str>, String)],
+    ) -> Result<(), TrailerError> {
+        for (name, _) in trailers {
+            let name = validate_field_name(name)?;
+            if !self.contains(&name) {
+                return Err(TrailerError::UndeclaredField(name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Trailer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// フィールド名を検証して小文字にする
+fn validate_field_name(name: &str) -> Result<String, TrailerError> {
+    if !is_valid_token(name) {
+        return Err(TrailerError::InvalidFieldName);
+    }
+    let lower_name = name.to_ascii_lowercase();
+
+    // RFC 9112 Section 7.1.2: 禁止フィールドチェック
+    if is_prohibited_trailer_field(&lower_name) {
+        return Err(TrailerError::ProhibitedField(lower_name));
+    }
+    Ok(lower_name)
+}
+
+/// リクエストの TE ヘッダーがトレーラーを受け付けるか (RFC 9110 Section 10.1.4)
+///
+/// TE に `trailers` を含む場合に true を返す。複数行の TE も見る。
+/// TE がない場合や `trailers` を含まない場合、クライアントはトレーラーを
+/// 破棄する可能性がある。
+///
+/// 不正な TE でもエラーにせず `trailers` の有無だけを見る。
+/// 転送コーディングの q 値も扱う場合は `te::Te` を使う。
+pub fn accepts_trailers<H: HttpHead>(request: &H) -> bool {
+    request.get_headers("TE").iter().any(|value| {
+        value.split(',').any(|element| {
+            // trailers はパラメーターを持たないが、寛容に `;` 以降は無視する
+            let coding = element.split(';').next().unwrap_or_default();
+            trim_ows(coding).eq_ignore_ascii_case("trailers")
+        })
+    })
 }
 
 impl fmt::Display for Trailer {
@@ -100,6 +204,19 @@ impl fmt::Display for Trailer {
     }
 }
 
+impl TypedHeader for Trailer {
+    const NAME: &'static str = "Trailer";
+    type Error = TrailerError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// RFC 9110 Section 6.5.1: トレーラーに置けないカテゴリのフィールドかどうか
 ///
 /// RFC 9110 Section 6.5.1 は trailer section に含めてはならないフィールドとして
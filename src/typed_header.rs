@@ -0,0 +1,73 @@
+//! 型付きヘッダー
+//!
+//! ## 概要
+//!
+//! 各ヘッダーモジュールの構造化された値 (`ContentType`、`CacheControl`、`Range` 等) を
+//! ヘッダー名と結び付ける `TypedHeader` トレイトを提供する。
+//! `HttpHead::get_typed()` でヘッダーから取り出し、`Response::set_typed()` /
+//! `Request::set_typed()` でヘッダーに設定できる。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::cache::CacheControl;
+//! use shiguredo_http11::content_type::ContentType;
+//! use shiguredo_http11::{HttpHead, Response, ResponseDecoder, StatusCode};
+//!
+//! let mut response = Response::with_status(StatusCode::OK);
+//! let cache_control = CacheControl::new().with_no_store();
+//! response.set_typed(&cache_control).unwrap();
+//! response.set_typed(&ContentType::parse("text/plain").unwrap()).unwrap();
+//! assert_eq!(response.get_header("Cache-Control"), Some("no-store"));
+//!
+//! let mut decoder = ResponseDecoder::new();
+//! decoder.feed(&response.body(b"ok".to_vec()).encode().unwrap()).unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//! let content_type = head.get_typed::<ContentType>().unwrap().unwrap();
+//! assert_eq!(content_type.mime_type(), "text/plain");
+//! ```
+//!
+//! ## 複数のフィールド行
+//!
+//! 同名のヘッダーが複数行ある場合、`get_typed()` は RFC 9110 Section 5.3 に従い
+//! `", "` で結合した値をパースする。リスト形式のヘッダー (`Cache-Control`、`Vary` 等) は
+//! すべての行の要素を得られ、単一値のヘッダー (`Content-Type`、`Host` 等) は重複が
+//! パースエラーとして検出される。
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// ヘッダー名と構造化された値を結び付けるトレイト
+pub trait TypedHeader: Sized {
+    /// ヘッダー名
+    const NAME: &'static str;
+
+    /// パースエラー型
+    type Error;
+
+    /// フィールド値をパースする
+    fn parse_value(value: &str) -> Result<Self, Self::Error>;
+
+    /// フィールド値にエンコードする
+    fn encode_value(&self) -> String;
+}
+
+/// ヘッダーリストから型付きヘッダーを取り出す
+///
+/// ヘッダーが存在しない場合は `None`。複数行ある場合は `", "` で結合してパースする。
+pub(crate) fn decode_typed<T: TypedHeader>(
+    headers: &[(Cow<'static, str>, String)],
+) -> Option<Result<T, T::Error>> {
+    let mut values = headers
+        .iter()
+        .filter(|(n, _)| n.eq_ignore_ascii_case(T::NAME))
+        .map(|(_, v)| v.as_str());
+    let first = values.next()?;
+    let mut combined = Cow::Borrowed(first);
+    for value in values {
+        let combined = combined.to_mut();
+        combined.push_str(", ");
+        combined.push_str(value);
+    }
+    Some(T::parse_value(&combined))
+}
@@ -12,11 +12,55 @@
 //! let upgrade = Upgrade::parse("websocket, h2c/1.0").unwrap();
 //! assert!(upgrade.has_protocol("websocket"));
 //! ```
+//!
+//! ## h2c へのアップグレード
+//!
+//! `Upgrade: h2c` による平文 HTTP/2 への切り替え (RFC 7540 Section 3.2) を扱う。
+//! HTTP/2 のフレーム処理は行わないため、切り替え後は HTTP/2 の実装に引き渡す。
+//!
+//! - `Http2Settings`: `HTTP2-Settings` ヘッダー (SETTINGS フレームのペイロードの base64url)
+//! - `h2c_upgrade_request()`: クライアントがアップグレード要求のヘッダーを設定する
+//! - `h2c_upgrade_settings()`: サーバーがアップグレード要求を判定して SETTINGS を取り出す
+//! - `h2c_switching_protocols()`: サーバーが返す 101 レスポンスを作る
+//! - `is_h2c_upgrade_response()`: クライアントが 101 レスポンスを判定する
+//!
+//! 101 レスポンスの後に届いたバイト列は HTTP/2 のフレームであり、
+//! `ResponseDecoder::take_remaining()` / `RequestDecoder::take_remaining()` で取り出せる。
+//!
+//! 注: RFC 9113 Section 3.1 は h2c アップグレードを非推奨としている。
+//!
+//! ```rust
+//! use shiguredo_http11::upgrade::{
+//!     Http2Settings, h2c_switching_protocols, h2c_upgrade_request, h2c_upgrade_settings,
+//! };
+//! use shiguredo_http11::{Request, RequestDecoder};
+//!
+//! // SETTINGS_MAX_CONCURRENT_STREAMS = 100
+//! let settings = Http2Settings::new(vec![0x00, 0x03, 0x00, 0x00, 0x00, 0x64]).unwrap();
+//! let request = Request::new("GET", "/").unwrap().header("Host", "example.com").unwrap();
+//! let request = h2c_upgrade_request(request, &settings).unwrap();
+//! assert_eq!(request.get_header("HTTP2-Settings"), Some("AAMAAABk"));
+//!
+//! let mut decoder = RequestDecoder::new();
+//! decoder.feed(&request.encode().unwrap()).unwrap();
+//! let (head, _) = decoder.decode_headers().unwrap().unwrap();
+//! assert_eq!(h2c_upgrade_settings(&head).unwrap(), Some(settings));
+//!
+//! let response = h2c_switching_protocols();
+//! assert_eq!(response.status_code(), 101);
+//! ```
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::base64;
+use crate::decoder::{HttpHead, ResponseHead};
+use crate::error::EncodeError;
+use crate::request::Request;
+use crate::response::Response;
+use crate::status_code::StatusCode;
+use crate::typed_header::TypedHeader;
 use crate::validate::{is_valid_token, trim_ows};
 
 /// Upgrade パースエラー
@@ -31,6 +75,10 @@ pub enum UpgradeError {
     InvalidProtocol,
     /// 不正なバージョン
     InvalidVersion,
+    /// HTTP2-Settings ヘッダーがない、複数ある、または値が不正 (RFC 7540 Section 3.2.1)
+    InvalidHttp2Settings,
+    /// Connection ヘッダーに必要な接続オプションがない
+    MissingConnectionOption(String),
 }
 
 impl fmt::Display for UpgradeError {
@@ -40,6 +88,10 @@ impl fmt::Display for UpgradeError {
             UpgradeError::InvalidFormat => write!(f, "invalid Upgrade header format"),
             UpgradeError::InvalidProtocol => write!(f, "invalid Upgrade protocol"),
             UpgradeError::InvalidVersion => write!(f, "invalid Upgrade protocol version"),
+            UpgradeError::InvalidHttp2Settings => write!(f, "invalid HTTP2-Settings header"),
+            UpgradeError::MissingConnectionOption(option) => {
+                write!(f, "missing connection option: {}", option)
+            }
         }
     }
 }
@@ -122,6 +174,19 @@ impl fmt::Display for Upgrade {
     }
 }
 
+impl TypedHeader for Upgrade {
+    const NAME: &'static str = "Upgrade";
+    type Error = UpgradeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 /// Upgrade プロトコル
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Protocol {
@@ -149,3 +214,159 @@ impl fmt::Display for Protocol {
         }
     }
 }
+
+/// 平文 HTTP/2 のプロトコル名 (RFC 7540 Section 3.1)
+pub const H2C: &str = "h2c";
+
+/// SETTINGS フレームの 1 パラメーターの長さ (RFC 9113 Section 6.5.1)
+const SETTINGS_ENTRY_SIZE: usize = 6;
+
+/// HTTP2-Settings ヘッダー (RFC 7540 Section 3.2.1)
+///
+/// 値は SETTINGS フレームのペイロードをパディングなしの base64url で表したもの。
+/// ペイロードの中身 (識別子と値) は解釈しない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Http2Settings {
+    payload: Vec<u8>,
+}
+
+impl Http2Settings {
+    /// SETTINGS フレームのペイロードから作成
+    ///
+    /// ペイロードの長さは 6 の倍数でなければならない (RFC 9113 Section 6.5)。
+    pub fn new(payload: impl Into<Vec<u8>>) -> Result<Self, UpgradeError> {
+        let payload = payload.into();
+        if !payload.len().is_multiple_of(SETTINGS_ENTRY_SIZE) {
+            return Err(UpgradeError::InvalidHttp2Settings);
+        }
+        Ok(Http2Settings { payload })
+    }
+
+    /// HTTP2-Settings ヘッダーをパース
+    pub fn parse(input: &str) -> Result<Self, UpgradeError> {
+        let payload =
+            base64::decode_url(trim_ows(input)).map_err(|_| UpgradeError::InvalidHttp2Settings)?;
+        Self::new(payload)
+    }
+
+    /// SETTINGS フレームのペイロード
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+
+impl fmt::Display for Http2Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", base64::encode_url(&self.payload))
+    }
+}
+
+impl TypedHeader for Http2Settings {
+    const NAME: &'static str = "HTTP2-Settings";
+    type Error = UpgradeError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// h2c へのアップグレードを要求するヘッダーをリクエストに設定 (RFC 7540 Section 3.2)
+///
+/// `Upgrade: h2c` と `HTTP2-Settings` を設定し、`Connection` に `Upgrade` と
+/// `HTTP2-Settings` の接続オプションを追加する。既存の `Connection` の接続オプションは残す。
+pub fn h2c_upgrade_request(
+    mut request: Request,
+    settings: &Http2Settings,
+) -> Result<Request, EncodeError> {
+    let mut options: Vec<String> = Vec::new();
+    for value in request.get_headers("Connection") {
+        for option in value.split(',') {
+            let option = trim_ows(option);
+            if option.is_empty()
+                || option.eq_ignore_ascii_case("upgrade")
+                || option.eq_ignore_ascii_case("http2-settings")
+            {
+                continue;
+            }
+            options.push(option.to_string());
+        }
+    }
+    options.push("Upgrade".to_string());
+    options.push("HTTP2-Settings".to_string());
+
+    request.set_header("Connection", options.join(", "))?;
+    request.set_header("Upgrade", H2C)?;
+    request.set_typed(settings)?;
+    Ok(request)
+}
+
+/// リクエストが h2c へのアップグレード要求か判定し、SETTINGS を取り出す (RFC 7540 Section 3.2)
+///
+/// `Upgrade` に `h2c` を含まない場合は `Ok(None)`。
+/// `h2c` を含むのに `Connection` に `upgrade` / `http2-settings` の接続オプションがない場合や、
+/// `HTTP2-Settings` がちょうど 1 つでない場合はエラーを返す。
+/// エラーの場合はアップグレードしてはならない (MUST NOT) が、HTTP/1.1 で応答できる。
+pub fn h2c_upgrade_settings<H: HttpHead + ?Sized>(
+    request: &H,
+) -> Result<Option<Http2Settings>, UpgradeError> {
+    let upgrades = request.get_headers(Upgrade::NAME);
+    let mut requested = false;
+    for value in upgrades {
+        if Upgrade::parse(value)?.has_protocol(H2C) {
+            requested = true;
+        }
+    }
+    if !requested {
+        return Ok(None);
+    }
+
+    for option in ["upgrade", "http2-settings"] {
+        if !has_connection_option(request, option) {
+            return Err(UpgradeError::MissingConnectionOption(option.to_string()));
+        }
+    }
+
+    match request.get_headers(Http2Settings::NAME).as_slice() {
+        [value] => Http2Settings::parse(value).map(Some),
+        _ => Err(UpgradeError::InvalidHttp2Settings),
+    }
+}
+
+/// h2c へのアップグレードを受け入れる 101 Switching Protocols レスポンスを作成
+///
+/// `Connection: Upgrade` と `Upgrade: h2c` を設定する (RFC 9110 Section 7.8)。
+pub fn h2c_switching_protocols() -> Response {
+    let status = StatusCode::SWITCHING_PROTOCOLS;
+    Response::from_raw_parts(
+        "HTTP/1.1".to_string(),
+        status.code(),
+        status.canonical_reason().to_string(),
+        alloc::vec![
+            ("Connection".into(), "Upgrade".to_string()),
+            ("Upgrade".into(), H2C.to_string()),
+        ],
+        None,
+    )
+}
+
+/// レスポンスが h2c へのアップグレードを受け入れた 101 レスポンスか判定
+pub fn is_h2c_upgrade_response(head: &ResponseHead) -> bool {
+    head.status_code() == StatusCode::SWITCHING_PROTOCOLS.code()
+        && head
+            .get_headers(Upgrade::NAME)
+            .iter()
+            .any(|value| Upgrade::parse(value).is_ok_and(|upgrade| upgrade.has_protocol(H2C)))
+}
+
+/// Connection ヘッダーに接続オプションが含まれるか
+fn has_connection_option<H: HttpHead + ?Sized>(head: &H, option: &str) -> bool {
+    head.get_headers("Connection").iter().any(|value| {
+        value
+            .split(',')
+            .any(|token| trim_ows(token).eq_ignore_ascii_case(option))
+    })
+}
@@ -4,6 +4,10 @@
 //!
 //! RFC 3986 に基づいた URI のパースとパーセントエンコーディング/デコーディングを提供します。
 //!
+//! `Uri::parse()` は絶対 URI と相対参照 (RFC 3986 Section 4.2) の両方を受け付け、
+//! scheme / userinfo / host / port / path / query / fragment に分解して検証する。
+//! host は reg-name / IPv4 / IP-literal (IPv6 と IPvFuture) を扱う。
+//!
 //! ## 使い方
 //!
 //! ```rust
@@ -18,6 +22,13 @@
 //! assert_eq!(uri.query(), Some("query=value"));
 //! assert_eq!(uri.fragment(), Some("fragment"));
 //!
+//! // IPv6 リテラルと userinfo
+//! let uri = Uri::parse("http://user@[::1]/").unwrap();
+//! assert_eq!(uri.userinfo(), Some("user"));
+//! assert_eq!(uri.host(), Some("[::1]"));
+//! assert_eq!(uri.hostname(), Some("::1"));
+//! assert_eq!(uri.port_or_default(), Some(80));
+//!
 //! // パーセントエンコーディング
 //! let encoded = percent_encode("hello world");
 //! assert_eq!(encoded, "hello%20world");
@@ -26,6 +37,17 @@
 //! let decoded = percent_decode("hello%20world").unwrap();
 //! assert_eq!(decoded, "hello world");
 //! ```
+//!
+//! ## クエリ文字列
+//!
+//! ```rust
+//! use shiguredo_http11::uri::{Uri, parse_query, serialize_query};
+//!
+//! let uri = Uri::parse("/search?q=http%2F1.1&page=2").unwrap();
+//! let pairs = parse_query(uri.query().unwrap()).unwrap();
+//! assert_eq!(pairs[0], ("q".to_string(), "http/1.1".to_string()));
+//! assert_eq!(serialize_query(pairs), "q=http%2F1.1&page=2");
+//! ```
 
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
@@ -296,6 +318,84 @@ pub fn percent_decode_bytes(input: &str) -> Result<Vec<u8>, UriError> {
     Ok(result)
 }
 
+/// クエリ文字列をキーと値の組にパース
+///
+/// `&` で区切った各要素を最初の `=` でキーと値に分け、それぞれをパーセントデコードする。
+/// application/x-www-form-urlencoded に合わせて `+` は空白として扱う。
+///
+/// - 同じキーが複数回現れた場合は出現順にすべて返す
+/// - `=` のない要素 (`a`) と値が空の要素 (`a=`) は値を空文字列として返す
+/// - 空の要素 (`a=1&&b=2` の間など) は無視する
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::uri::parse_query;
+///
+/// let pairs = parse_query("a=1&b=%20&a=2&c").unwrap();
+/// assert_eq!(
+///     pairs,
+///     [
+///         ("a".to_string(), "1".to_string()),
+///         ("b".to_string(), " ".to_string()),
+///         ("a".to_string(), "2".to_string()),
+///         ("c".to_string(), "".to_string()),
+///     ]
+/// );
+/// ```
+pub fn parse_query(query: &str) -> Result<Vec<(String, String)>, UriError> {
+    let mut pairs = Vec::new();
+    for part in query.split('&') {
+        if part.is_empty() {
+            continue;
+        }
+        let (key, value) = part.split_once('=').unwrap_or((part, ""));
+        pairs.push((decode_query_component(key)?, decode_query_component(value)?));
+    }
+    Ok(pairs)
+}
+
+/// キーと値の組をクエリ文字列にシリアライズ
+///
+/// キーと値は unreserved 以外の文字をすべてパーセントエンコードする。
+/// 空白は `%20` になるため、`+` を空白として扱う受信者でも
+/// そうでない受信者でも同じ値として読める。
+/// 値が空の場合も `a=` の形で出力し、`parse_query()` で元の組に戻る。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::uri::serialize_query;
+///
+/// let query = serialize_query([("q", "hello world"), ("tag", "a&b"), ("empty", "")]);
+/// assert_eq!(query, "q=hello%20world&tag=a%26b&empty=");
+/// ```
+pub fn serialize_query<I, K, V>(pairs: I) -> String
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut result = String::new();
+    for (key, value) in pairs {
+        if !result.is_empty() {
+            result.push('&');
+        }
+        result.push_str(&percent_encode(key.as_ref()));
+        result.push('=');
+        result.push_str(&percent_encode(value.as_ref()));
+    }
+    result
+}
+
+fn decode_query_component(input: &str) -> Result<String, UriError> {
+    if input.contains('+') {
+        percent_decode(&input.replace('+', " "))
+    } else {
+        percent_decode(input)
+    }
+}
+
 fn from_hex_char(c: u8) -> Option<u8> {
     match c {
         b'0'..=b'9' => Some(c - b'0'),
@@ -324,8 +424,8 @@ pub struct Uri {
     authority_start: Option<usize>,
     /// authority の終了位置
     authority_end: Option<usize>,
-    /// ホストの終了位置
-    host_end: Option<usize>,
+    /// ホストの開始位置と終了位置
+    host_range: Option<(usize, usize)>,
     /// ポート番号
     port: Option<u16>,
     /// パスの開始位置
@@ -383,7 +483,7 @@ impl Uri {
         };
 
         // authority のパース (RFC 3986 Section 3.2)
-        let (authority_start, authority_end, host_end, port) =
+        let (authority_start, authority_end, host_range, port) =
             if pos + 1 < len && bytes[pos] == b'/' && bytes[pos + 1] == b'/' {
                 pos += 2;
                 let auth_start = pos;
@@ -396,13 +496,13 @@ impl Uri {
                     .unwrap_or(len);
 
                 let authority = &input[auth_start..auth_end];
-                let (h_end, p) = parse_authority(authority)?;
+                let (h_start, h_end, p) = parse_authority(authority)?;
 
                 pos = auth_end;
                 (
                     Some(auth_start),
                     Some(auth_end),
-                    Some(auth_start + h_end),
+                    Some((auth_start + h_start, auth_start + h_end)),
                     p,
                 )
             } else {
@@ -459,7 +559,7 @@ impl Uri {
             scheme_end,
             authority_start,
             authority_end,
-            host_end,
+            host_range,
             port,
             path_start,
             path_end,
@@ -482,20 +582,30 @@ impl Uri {
         }
     }
 
+    /// userinfo を取得 (RFC 3986 Section 3.2.1)
+    ///
+    /// authority に `@` がない場合は `None`。
+    pub fn userinfo(&self) -> Option<&str> {
+        let (auth_start, (host_start, _)) = (self.authority_start?, self.host_range?);
+        (host_start > auth_start).then(|| &self.source[auth_start..host_start - 1])
+    }
+
     /// ホストを取得
+    ///
+    /// IP-literal は角括弧を含めた形 (`[::1]` 等) で返す。
     pub fn host(&self) -> Option<&str> {
-        match (self.authority_start, self.host_end) {
-            (Some(start), Some(end)) => {
-                let auth = &self.source[start..end];
-                // userinfo を除去
-                if let Some(at_pos) = auth.rfind('@') {
-                    Some(&auth[at_pos + 1..])
-                } else {
-                    Some(auth)
-                }
-            }
-            _ => None,
-        }
+        self.host_range.map(|(start, end)| &self.source[start..end])
+    }
+
+    /// 角括弧を除いたホストを取得
+    ///
+    /// IP-literal は角括弧を除いた形 (`::1` 等) で返す。接続先の指定に使う。
+    pub fn hostname(&self) -> Option<&str> {
+        self.host().map(|host| {
+            host.strip_prefix('[')
+                .and_then(|h| h.strip_suffix(']'))
+                .unwrap_or(host)
+        })
     }
 
     /// ポート番号を取得
@@ -503,6 +613,18 @@ impl Uri {
         self.port
     }
 
+    /// スキームのデフォルトポートを取得
+    ///
+    /// `http` / `ws` は 80、`https` / `wss` は 443。それ以外のスキームは `None`。
+    pub fn default_port(&self) -> Option<u16> {
+        default_port(self.scheme()?)
+    }
+
+    /// ポート番号を取得し、ない場合はスキームのデフォルトポートを返す
+    pub fn port_or_default(&self) -> Option<u16> {
+        self.port.or_else(|| self.default_port())
+    }
+
     /// パスを取得
     pub fn path(&self) -> &str {
         &self.source[self.path_start..self.path_end]
@@ -549,6 +671,35 @@ impl Uri {
     pub fn is_relative(&self) -> bool {
         self.scheme_end.is_none()
     }
+
+    /// この URI を基底として参照文字列を解決
+    ///
+    /// `resolve(self, &Uri::parse(reference)?)` と同じだが、
+    /// 空の参照 (RFC 3986 Section 5.4.1 の `""`) は fragment を除いた基底 URI に解決する。
+    /// Location ヘッダーの相対参照をリクエスト URI に対して解決する場合に使う。
+    ///
+    /// # 例
+    ///
+    /// ```rust
+    /// use shiguredo_http11::uri::Uri;
+    ///
+    /// let base = Uri::parse("https://example.com/a/b?x=1").unwrap();
+    /// assert_eq!(base.join("../c").unwrap().as_str(), "https://example.com/c");
+    /// assert_eq!(base.join("?y=2").unwrap().as_str(), "https://example.com/a/b?y=2");
+    /// assert_eq!(base.join("//cdn.example.com/d").unwrap().as_str(), "https://cdn.example.com/d");
+    /// ```
+    pub fn join(&self, reference: &str) -> Result<Uri, UriError> {
+        if reference.is_empty() {
+            return Uri::parse(&build_uri(
+                self.scheme(),
+                self.authority(),
+                self.path(),
+                self.query(),
+                None,
+            ));
+        }
+        resolve(self, &Uri::parse(reference)?)
+    }
 }
 
 impl fmt::Display for Uri {
@@ -557,6 +708,249 @@ impl fmt::Display for Uri {
     }
 }
 
+/// スキームのデフォルトポート
+///
+/// http / ws は 80、https / wss は 443 を返す (大文字小文字を区別しない)。
+/// それ以外のスキームは `None`。
+pub fn default_port(scheme: &str) -> Option<u16> {
+    if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("ws") {
+        Some(80)
+    } else if scheme.eq_ignore_ascii_case("https") || scheme.eq_ignore_ascii_case("wss") {
+        Some(443)
+    } else {
+        None
+    }
+}
+
+/// コンポーネントごとにパーセントエンコードして URI を組み立てるビルダー
+///
+/// 各 `with_*` にはエンコード前の値を渡す。コンポーネントごとに RFC 3986 で
+/// 許可された文字だけをそのまま残し、それ以外 (`%` を含む) はパーセントエンコードする。
+///
+/// - path: pchar と `/` (RFC 3986 Section 3.3)
+/// - query / fragment: pchar と `/` と `?` (RFC 3986 Section 3.4, 3.5)
+/// - userinfo: unreserved と sub-delims (パスワードは `:` も) (RFC 3986 Section 3.2.1)
+///
+/// scheme と host はエンコードせずに検証し、不正な値はエラーにする。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::uri::UriBuilder;
+///
+/// let uri = UriBuilder::new()
+///     .with_scheme("https")
+///     .unwrap()
+///     .with_host("example.com")
+///     .unwrap()
+///     .with_port(8443)
+///     .with_path_segment("files")
+///     .with_path_segment("a/b c.txt")
+///     .with_query_pair("q", "x&y")
+///     .with_fragment("top")
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     uri.as_str(),
+///     "https://example.com:8443/files/a%2Fb%20c.txt?q=x%26y#top"
+/// );
+/// assert_eq!(uri.origin_form(), "/files/a%2Fb%20c.txt?q=x%26y");
+///
+/// // IPv6 アドレスは角括弧で囲む
+/// let uri = UriBuilder::new()
+///     .with_scheme("http")
+///     .unwrap()
+///     .with_host("::1")
+///     .unwrap()
+///     .with_path("/")
+///     .build()
+///     .unwrap();
+/// assert_eq!(uri.as_str(), "http://[::1]/");
+///
+/// assert!(UriBuilder::new().with_scheme("1http").is_err());
+/// assert!(UriBuilder::new().with_host("exa mple.com").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UriBuilder {
+    scheme: Option<String>,
+    userinfo: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl UriBuilder {
+    /// 空のビルダーを作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// スキームを設定
+    ///
+    /// RFC 3986 Section 3.1: scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+    /// 小文字に正規化する。
+    pub fn with_scheme(mut self, scheme: &str) -> Result<Self, UriError> {
+        let bytes = scheme.as_bytes();
+        if bytes.is_empty()
+            || !bytes[0].is_ascii_alphabetic()
+            || !bytes
+                .iter()
+                .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+        {
+            return Err(UriError::InvalidScheme);
+        }
+        self.scheme = Some(scheme.to_ascii_lowercase());
+        Ok(self)
+    }
+
+    /// userinfo を設定
+    ///
+    /// ユーザー名の `:` はエンコードし、パスワードとの区切りにだけ `:` を使う。
+    /// RFC 9110 Section 4.2.4 により http / https の URI では userinfo を使えないため、
+    /// その他のスキーム向け。
+    pub fn with_userinfo(mut self, user: &str, password: Option<&str>) -> Self {
+        let mut userinfo = encode_component(user, |b| is_unreserved(b) || is_sub_delim(b));
+        if let Some(password) = password {
+            userinfo.push(':');
+            userinfo.push_str(&encode_component(password, |b| {
+                is_unreserved(b) || is_sub_delim(b) || b == b':'
+            }));
+        }
+        self.userinfo = Some(userinfo);
+        self
+    }
+
+    /// ホストを設定
+    ///
+    /// reg-name / IPv4 アドレス / IPv6 アドレスを受け付ける。
+    /// IPv6 アドレスは角括弧の有無どちらでもよく、出力では角括弧で囲む。
+    /// 空のホストや reg-name に使えない文字を含むホストは `InvalidHost` を返す。
+    pub fn with_host(mut self, host: &str) -> Result<Self, UriError> {
+        if host.is_empty() {
+            return Err(UriError::InvalidHost);
+        }
+        let host = if host.parse::<core::net::Ipv6Addr>().is_ok() {
+            alloc::format!("[{}]", host)
+        } else {
+            validate_host(host)?;
+            host.to_string()
+        };
+        self.host = Some(host);
+        Ok(self)
+    }
+
+    /// ポートを設定
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// パスを設定
+    ///
+    /// `/` はセグメントの区切りとして残し、それ以外の pchar 以外の文字をエンコードする。
+    /// ホストがある場合、`/` で始まらないパスには `/` を補う。
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = encode_component(path, |b| is_pchar(b) || b == b'/');
+        self
+    }
+
+    /// パスの末尾にセグメントを追加
+    ///
+    /// 常に `/` を付けてから追加し、セグメント内の `/` もエンコードする。
+    /// 空のセグメントも 1 つのセグメントとして追加する。
+    /// `.` と `..` は unreserved のためエンコードされず、dot-segment として扱われる点に注意する。
+    pub fn with_path_segment(mut self, segment: &str) -> Self {
+        self.path.push('/');
+        self.path.push_str(&encode_component(segment, is_pchar));
+        self
+    }
+
+    /// クエリを設定
+    ///
+    /// `&` と `=` は区切りとしてそのまま残す。
+    /// キーと値をエンコードする場合は `with_query_pair()` を使う。
+    pub fn with_query(mut self, query: &str) -> Self {
+        self.query = Some(encode_component(query, is_query_or_fragment_char));
+        self
+    }
+
+    /// クエリの末尾にキーと値の組を追加
+    ///
+    /// キーと値は `serialize_query()` と同じく unreserved 以外をすべてエンコードする。
+    pub fn with_query_pair(mut self, key: &str, value: &str) -> Self {
+        let pair = serialize_query([(key, value)]);
+        match &mut self.query {
+            Some(query) if !query.is_empty() => {
+                query.push('&');
+                query.push_str(&pair);
+            }
+            _ => self.query = Some(pair),
+        }
+        self
+    }
+
+    /// フラグメントを設定
+    pub fn with_fragment(mut self, fragment: &str) -> Self {
+        self.fragment = Some(encode_component(fragment, is_query_or_fragment_char));
+        self
+    }
+
+    /// URI を組み立てる
+    ///
+    /// ホストなしで userinfo やポートを指定した場合は `InvalidHost` を返す。
+    /// 何も設定していない場合は `Empty` を返す。
+    pub fn build(self) -> Result<Uri, UriError> {
+        let authority = match self.host {
+            Some(host) => {
+                let mut authority = String::new();
+                if let Some(userinfo) = &self.userinfo {
+                    authority.push_str(userinfo);
+                    authority.push('@');
+                }
+                authority.push_str(&host);
+                if let Some(port) = self.port {
+                    authority.push_str(&alloc::format!(":{}", port));
+                }
+                Some(authority)
+            }
+            None if self.userinfo.is_some() || self.port.is_some() => {
+                return Err(UriError::InvalidHost);
+            }
+            None => None,
+        };
+
+        let mut path = self.path;
+        if authority.is_some() && !path.is_empty() && !path.starts_with('/') {
+            path.insert(0, '/');
+        }
+
+        Uri::parse(&build_uri(
+            self.scheme.as_deref(),
+            authority.as_deref(),
+            &path,
+            self.query.as_deref(),
+            self.fragment.as_deref(),
+        ))
+    }
+}
+
+/// `keep` が true を返すバイト以外をパーセントエンコード
+fn encode_component(input: &str, keep: fn(u8) -> bool) -> String {
+    let mut result = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if keep(byte) {
+            result.push(byte as char);
+        } else {
+            result.push('%');
+            result.push(to_hex_char(byte >> 4));
+            result.push(to_hex_char(byte & 0x0F));
+        }
+    }
+    result
+}
+
 /// スキームの終端位置を探す
 fn find_scheme_end(bytes: &[u8]) -> Option<usize> {
     for (i, &b) in bytes.iter().enumerate() {
@@ -699,60 +1093,53 @@ fn validate_userinfo(userinfo: &str) -> Result<(), UriError> {
 }
 
 /// authority をパース
-/// 戻り値: (host_end, port)
-fn parse_authority(authority: &str) -> Result<(usize, Option<u16>), UriError> {
-    if authority.is_empty() {
-        return Ok((0, None));
-    }
-
+///
+/// 戻り値: (host_start, host_end, port)。位置は authority 内のオフセット。
+///
+/// authority = [ userinfo "@" ] host [ ":" port ]
+fn parse_authority(authority: &str) -> Result<(usize, usize, Option<u16>), UriError> {
     // userinfo を検証して除去
-    let host_part = if let Some(at_pos) = authority.rfind('@') {
-        let userinfo = &authority[..at_pos];
-        validate_userinfo(userinfo)?;
-        &authority[at_pos + 1..]
+    let host_start = if let Some(at_pos) = authority.rfind('@') {
+        validate_userinfo(&authority[..at_pos])?;
+        at_pos + 1
     } else {
-        authority
+        0
     };
-
-    // IPv6 アドレス
-    if host_part.starts_with('[') {
-        if let Some(bracket_end) = host_part.find(']') {
-            validate_ip_literal(&host_part[1..bracket_end])?;
-            let after_bracket = &host_part[bracket_end + 1..];
-            if after_bracket.is_empty() {
-                return Ok((authority.len(), None));
-            } else if let Some(port_str) = after_bracket.strip_prefix(':') {
-                let port = port_str.parse::<u16>().map_err(|_| UriError::InvalidPort)?;
-                return Ok((authority.len() - after_bracket.len(), Some(port)));
-            } else {
-                return Err(UriError::InvalidHost);
-            }
-        } else {
+    let host_port = &authority[host_start..];
+
+    // host と port の境界
+    let host_len = if host_port.starts_with('[') {
+        // IP-literal の後ろは空か ":" port
+        let bracket_end = host_port.find(']').ok_or(UriError::InvalidHost)?;
+        let after_bracket = &host_port[bracket_end + 1..];
+        if !after_bracket.is_empty() && !after_bracket.starts_with(':') {
             return Err(UriError::InvalidHost);
         }
-    }
-
-    // 通常のホスト:ポート
-    if let Some(colon_pos) = host_part.rfind(':') {
-        let host_str = &host_part[..colon_pos];
-        let port_str = &host_part[colon_pos + 1..];
-        // RFC 3986 Section 3.2.2: host の文字種検証
-        validate_host(host_str)?;
-        if !port_str.is_empty() {
-            let port = port_str.parse::<u16>().map_err(|_| UriError::InvalidPort)?;
-            let host_end = if let Some(at_pos) = authority.rfind('@') {
-                at_pos + 1 + colon_pos
-            } else {
-                colon_pos
-            };
-            return Ok((host_end, Some(port)));
-        }
+        bracket_end + 1
     } else {
-        // RFC 3986 Section 3.2.2: host の文字種検証
-        validate_host(host_part)?;
-    }
+        host_port.rfind(':').unwrap_or(host_port.len())
+    };
+
+    // RFC 3986 Section 3.2.2: host の文字種検証
+    validate_host(&host_port[..host_len])?;
+
+    // RFC 3986 Section 3.2.3: port = *DIGIT (空の場合は省略と同じ)
+    let port = match host_port[host_len..].strip_prefix(':') {
+        Some("") | None => None,
+        Some(port_str) => Some(parse_port(port_str)?),
+    };
+
+    Ok((host_start, host_start + host_len, port))
+}
 
-    Ok((authority.len(), None))
+/// port をパース (RFC 3986 Section 3.2.3)
+///
+/// port = *DIGIT。`u16` に収まらない値はエラー。
+fn parse_port(port: &str) -> Result<u16, UriError> {
+    if !port.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(UriError::InvalidPort);
+    }
+    port.parse::<u16>().map_err(|_| UriError::InvalidPort)
 }
 
 /// 相対 URI を基底 URI に対して解決
@@ -840,8 +1227,25 @@ fn merge_paths(base: &Uri, reference_path: &str) -> String {
 
 /// `.` と `..` セグメントを除去
 ///
-/// RFC 3986 Section 5.2.4 のアルゴリズムに基づく
-fn remove_dot_segments(path: &str) -> String {
+/// RFC 3986 Section 5.2.4 のアルゴリズムに基づく。
+/// ルートを超える `..` は無視する。
+///
+/// パーセントエンコードされた `%2E` は dot-segment として扱わない。
+/// request-target を正規化する場合は、パーセントエンコーディングの正規化も行う
+/// [`normalize()`] を使う。
+///
+/// # 例
+///
+/// ```rust
+/// use shiguredo_http11::uri::{Uri, normalize, remove_dot_segments};
+///
+/// assert_eq!(remove_dot_segments("/a/b/c/./../../g"), "/a/g");
+/// assert_eq!(remove_dot_segments("/../etc/passwd"), "/etc/passwd");
+///
+/// let target = Uri::parse("/static/%2e%2e/a/./b?x").unwrap();
+/// assert_eq!(normalize(&target).unwrap().as_str(), "/a/b?x");
+/// ```
+pub fn remove_dot_segments(path: &str) -> String {
     let mut output: Vec<&str> = Vec::new();
     let mut i = 0;
     let bytes = path.as_bytes();
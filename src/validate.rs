@@ -59,7 +59,9 @@ pub(crate) fn is_valid_field_vchar(b: u8) -> bool {
 /// 現在の実装ではヘッダー値を UTF-8 として解釈しており、obs-text (0x80-0xFF) を
 /// バイト列として扱っていない。RFC 9110 Section 5.5 では obs-text は任意のバイト列
 /// として定義されているが、本実装では UTF-8 として解釈するため、不正な UTF-8
-/// シーケンスを含むヘッダー値は拒否される。現時点ではこの制限を維持する。
+/// シーケンスを含むヘッダー値は拒否される。
+/// デコーダーでは `NonUtf8Policy` を設定すると、UTF-8 として不正なバイト列を
+/// Latin-1 として解釈するか U+FFFD に置換した文字列が渡される。
 pub(crate) fn is_valid_field_value(value: &str) -> bool {
     value.bytes().all(is_valid_field_vchar)
 }
@@ -127,6 +129,19 @@ pub(crate) fn is_valid_protocol_version(version: &str) -> bool {
     after_dot.iter().all(|b| b.is_ascii_digit())
 }
 
+/// HTTP-version が有効か確認 (RFC 9112 Section 2.3)
+///
+/// `HTTP-version = HTTP-name "/" DIGIT "." DIGIT`、`HTTP-name = %s"HTTP"`。
+/// `is_valid_protocol_version` と異なり、プロトコル名は `HTTP` (大文字小文字を区別)、
+/// major / minor はそれぞれ 1 桁に限定する。
+pub(crate) fn is_valid_http_version(version: &str) -> bool {
+    matches!(
+        version.as_bytes(),
+        [b'H', b'T', b'T', b'P', b'/', major, b'.', minor]
+            if major.is_ascii_digit() && minor.is_ascii_digit()
+    )
+}
+
 /// ステータスコードが有効か確認 (RFC 9110 Section 15)
 ///
 /// ステータスコードは 3 桁の数字で、100-599 の範囲
@@ -149,7 +164,9 @@ pub(crate) fn is_valid_status_code(code: u16) -> bool {
 /// 現在の実装では reason-phrase を UTF-8 として解釈しており、obs-text (0x80-0xFF) を
 /// バイト列として扱っていない。RFC 9112 Section 4 では obs-text は任意のバイト列
 /// として定義されているが、本実装では UTF-8 として解釈するため、不正な UTF-8
-/// シーケンスを含む reason-phrase は拒否される。現時点ではこの制限を維持する。
+/// シーケンスを含む reason-phrase は拒否される。
+/// デコーダーでは `NonUtf8Policy` を設定すると、UTF-8 として不正なバイト列を
+/// Latin-1 として解釈するか U+FFFD に置換した文字列が渡される。
 pub(crate) fn is_valid_reason_phrase(phrase: &str) -> bool {
     !phrase.is_empty()
         && phrase
@@ -180,8 +197,16 @@ pub(crate) const RFC3986_EXCLUDED: &[u8] = b"\"#<>\\^`{|}";
 /// - 不正なパーセントエンコーディング (% の後に 2 桁の 16 進数がない)
 /// - パーセントエンコーディングされた NUL バイト (%00)
 pub(crate) fn is_valid_request_target(target: &str) -> bool {
+    request_target_violation(target).is_none()
+}
+
+/// request-target の検証に失敗した理由を返す（受信側用）
+///
+/// 検証内容は `is_valid_request_target` と同じ。有効な場合は `None` を返す。
+/// デコーダーのエラーメッセージで違反内容を示すために使う。
+pub(crate) fn request_target_violation(target: &str) -> Option<&'static str> {
     if target.is_empty() {
-        return false;
+        return Some("empty");
     }
 
     let bytes = target.as_bytes();
@@ -192,29 +217,34 @@ pub(crate) fn is_valid_request_target(target: &str) -> bool {
 
         // 制御文字の拒否 (0x00-0x20, 0x7F)
         if b <= 0x20 || b == 0x7F {
-            return false;
+            return Some("contains control character or whitespace");
+        }
+
+        // absolute-URI にフラグメントは含まれない (RFC 3986)
+        if b == b'#' {
+            return Some("fragment is not allowed");
         }
 
         // RFC 3986 除外文字の拒否
         if RFC3986_EXCLUDED.contains(&b) {
-            return false;
+            return Some("contains character not allowed in URI (RFC 3986 Section 2)");
         }
 
         // パーセントエンコーディングの検証
         if b == b'%' {
             if i + 2 >= bytes.len() {
-                return false; // 不完全
+                return Some("incomplete percent-encoding");
             }
             let high = bytes[i + 1];
             let low = bytes[i + 2];
 
             if !high.is_ascii_hexdigit() || !low.is_ascii_hexdigit() {
-                return false; // 不正な 16 進数
+                return Some("invalid percent-encoding");
             }
 
             // %00 (NUL) の拒否
             if high == b'0' && low == b'0' {
-                return false;
+                return Some("percent-encoded NUL");
             }
 
             i += 3;
@@ -224,7 +254,7 @@ pub(crate) fn is_valid_request_target(target: &str) -> bool {
         i += 1;
     }
 
-    true
+    None
 }
 
 /// pchar または "/" か確認 (RFC 3986)
@@ -417,6 +447,22 @@ pub(crate) fn trim_ows(s: &str) -> &str {
     &s[start..end]
 }
 
+/// OWS (SP / HTAB) をバイト列の前後から除去 (RFC 9110 Section 5.6.3)
+///
+/// `trim_ows` のバイト列版。UTF-8 として不正な field-value の受信バイト列に使う。
+pub(crate) fn trim_ows_bytes(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|&b| b != b' ' && b != b'\t')
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|&b| b != b' ' && b != b'\t')
+        .map(|p| p + 1)
+        .unwrap_or(start);
+    &bytes[start..end]
+}
+
 /// クォートを考慮したカンマ区切り分割
 ///
 /// delimiter (通常は `,`) で文字列を分割するが、引用符 (`"`) 内の
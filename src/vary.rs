@@ -12,11 +12,39 @@
 //! let vary = Vary::parse("Accept-Encoding, User-Agent").unwrap();
 //! assert_eq!(vary.fields().len(), 2);
 //! ```
+//!
+//! ## キャッシュの二次キー
+//!
+//! [`cache_key`] は Vary に列挙されたリクエストのフィールド値を正規化して、
+//! キャッシュの二次キー (RFC 9111 Section 4.1) を作る。
+//!
+//! - フィールド名は小文字にし、Vary での並び順や重複によらず同じキーになる
+//! - 同名のフィールドが複数行ある場合は結合する
+//! - 値の前後の空白を取り除き、連続する空白を 1 つにまとめる
+//! - `Accept` / `Accept-Charset` / `Accept-Encoding` / `Accept-Language` は
+//!   要素ごとに空白を取り除いて小文字にし、要素の並び順によらず同じキーになる
+//! - フィールドがないリクエストと、空の値を持つリクエストは区別する
+//! - `Vary: *` の場合はどのリクエストとも一致しないため `None` を返す
+//!
+//! ```rust
+//! use shiguredo_http11::Request;
+//! use shiguredo_http11::vary::{Vary, cache_key};
+//!
+//! let vary = Vary::parse("Accept-Encoding").unwrap();
+//! let a = Request::new("GET", "/").unwrap().header("Accept-Encoding", "gzip, br").unwrap();
+//! let b = Request::new("GET", "/").unwrap().header("accept-encoding", "BR,gzip").unwrap();
+//! assert_eq!(cache_key(&vary, &a), cache_key(&vary, &b));
+//!
+//! let any = Vary::parse("*").unwrap();
+//! assert_eq!(cache_key(&any, &a), None);
+//! ```
 
-use alloc::string::String;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 
+use crate::decoder::HttpHead;
+use crate::typed_header::TypedHeader;
 use crate::validate::{is_valid_token, trim_ows};
 
 /// Vary パースエラー
@@ -104,3 +132,121 @@ impl fmt::Display for Vary {
         write!(f, "{}", self.fields.join(", "))
     }
 }
+
+impl TypedHeader for Vary {
+    const NAME: &'static str = "Vary";
+    type Error = VaryError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// 要素の並び順と大文字小文字を区別しないリスト形式のフィールド
+const LIST_FIELDS: &[&str] = &[
+    "accept",
+    "accept-charset",
+    "accept-encoding",
+    "accept-language",
+];
+
+/// Vary に従ってリクエストからキャッシュの二次キーを作成 (RFC 9111 Section 4.1)
+///
+/// `Vary: *` の場合は `None` を返す。
+/// キーは正規化したフィールド名と値を改行でつないだ文字列で、同じキーになるリクエストは
+/// 同じ保存済みレスポンスを選ぶ。
+pub fn cache_key<H: HttpHead>(vary: &Vary, request: &H) -> Option<String> {
+    if vary.is_any() {
+        return None;
+    }
+
+    let mut names: Vec<&str> = vary.fields().iter().map(String::as_str).collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut key = String::new();
+    for name in names {
+        if !key.is_empty() {
+            key.push('\n');
+        }
+        key.push_str(name);
+        let values = request.get_headers(name);
+        // フィールドがない場合は値を付けない
+        if values.is_empty() {
+            continue;
+        }
+        key.push(':');
+        if LIST_FIELDS.contains(&name) {
+            key.push_str(&normalize_list_value(&values));
+        } else {
+            key.push_str(&normalize_value(&values));
+        }
+    }
+    Some(key)
+}
+
+/// 複数行を `", "` で結合し、空白を正規化する
+fn normalize_value(values: &[&str]) -> String {
+    let mut out = String::new();
+    for value in values {
+        let value = trim_ows(value);
+        if !out.is_empty() {
+            out.push_str(", ");
+        }
+        let mut in_space = false;
+        for c in value.chars() {
+            if c == ' ' || c == '\t' {
+                in_space = true;
+                continue;
+            }
+            if in_space {
+                out.push(' ');
+                in_space = false;
+            }
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// リスト形式の値を要素ごとに正規化して並べ替える
+///
+/// 引用符の外の空白を取り除いて小文字にする。空要素は無視する。
+fn normalize_list_value(values: &[&str]) -> String {
+    let mut elements = Vec::new();
+    for value in values {
+        let mut element = String::new();
+        let mut in_quotes = false;
+        let mut escaped = false;
+        for c in value.chars() {
+            if in_quotes {
+                element.push(c);
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_quotes = false;
+                }
+                continue;
+            }
+            match c {
+                ',' => elements.push(core::mem::take(&mut element)),
+                ' ' | '\t' => {}
+                '"' => {
+                    in_quotes = true;
+                    element.push(c);
+                }
+                _ => element.push(c.to_ascii_lowercase()),
+            }
+        }
+        elements.push(element);
+    }
+    elements.retain(|element| !element.is_empty());
+    elements.sort_unstable();
+    elements.join(",")
+}
@@ -0,0 +1,153 @@
+//! プロトコルバージョン型 (RFC 9112 Section 2.3)
+//!
+//! ## 概要
+//!
+//! start-line のプロトコルバージョン文字列を `HttpVersion` として扱う。
+//! `HTTP/1.0` と `HTTP/1.1` は専用のバリアントで表現し、それ以外
+//! (`HTTP/2.0`、`RTSP/1.0` 等) は `Other` に受信した文字列をそのまま保持する。
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::version::HttpVersion;
+//!
+//! assert_eq!(HttpVersion::parse("HTTP/1.1").unwrap(), HttpVersion::Http11);
+//! assert_eq!(
+//!     HttpVersion::parse("RTSP/1.0").unwrap(),
+//!     HttpVersion::Other("RTSP/1.0".to_string())
+//! );
+//!
+//! // HTTP-version の文法 (`"HTTP/" DIGIT "." DIGIT`) に限定する
+//! assert!(HttpVersion::parse_strict("RTSP/1.0").is_err());
+//! assert!(HttpVersion::parse_strict("HTTP/1.10").is_err());
+//! ```
+//!
+//! `String` に変換できるため、`Request::with_version` 等にそのまま渡せる。
+//!
+//! ```rust
+//! use shiguredo_http11::{HttpVersion, Request};
+//!
+//! let request = Request::with_version("GET", "/", HttpVersion::Http10).unwrap();
+//! assert_eq!(request.http_version(), HttpVersion::Http10);
+//! ```
+
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+use crate::validate::{is_valid_http_version, is_valid_protocol_version};
+
+/// プロトコルバージョンのパースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HttpVersionError {
+    /// 空の入力
+    Empty,
+    /// `protocol "/" DIGIT+ "." DIGIT+` の形式でない
+    InvalidFormat,
+    /// HTTP-version の文法 (`"HTTP/" DIGIT "." DIGIT`) でない
+    NotHttpVersion,
+}
+
+impl fmt::Display for HttpVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpVersionError::Empty => write!(f, "empty protocol version"),
+            HttpVersionError::InvalidFormat => write!(f, "invalid protocol version format"),
+            HttpVersionError::NotHttpVersion => {
+                write!(f, "not an HTTP-version (RFC 9112 Section 2.3)")
+            }
+        }
+    }
+}
+
+impl core::error::Error for HttpVersionError {}
+
+/// プロトコルバージョン
+///
+/// `Other` は `HTTP/1.0` / `HTTP/1.1` 以外のバージョン文字列を保持する。
+/// `Other("HTTP/1.1")` のように専用バリアントと重複する値は `parse()` では生成されない。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HttpVersion {
+    /// `HTTP/1.0`
+    Http10,
+    /// `HTTP/1.1`
+    Http11,
+    /// その他のバージョン (`HTTP/2.0`、`RTSP/1.0` 等)
+    Other(String),
+}
+
+impl HttpVersion {
+    /// バージョン文字列をパースする
+    ///
+    /// デコーダーと同じく `protocol "/" DIGIT+ "." DIGIT+` を受理する。
+    /// RTSP 等の HTTP 以外のプロトコルも `Other` として受理する。
+    pub fn parse(s: &str) -> Result<Self, HttpVersionError> {
+        if s.is_empty() {
+            return Err(HttpVersionError::Empty);
+        }
+        if !is_valid_protocol_version(s) {
+            return Err(HttpVersionError::InvalidFormat);
+        }
+        Ok(Self::from_valid(s))
+    }
+
+    /// HTTP-version の文法に限定してパースする (RFC 9112 Section 2.3)
+    ///
+    /// `HTTP-version = HTTP-name "/" DIGIT "." DIGIT` に一致しない場合はエラー。
+    /// `HTTP-name` は大文字小文字を区別する (`"http/1.1"` は不可)。
+    pub fn parse_strict(s: &str) -> Result<Self, HttpVersionError> {
+        let version = Self::parse(s)?;
+        if !is_valid_http_version(s) {
+            return Err(HttpVersionError::NotHttpVersion);
+        }
+        Ok(version)
+    }
+
+    /// 検証済みのバージョン文字列から構築する
+    pub(crate) fn from_valid(s: &str) -> Self {
+        match s {
+            "HTTP/1.0" => HttpVersion::Http10,
+            "HTTP/1.1" => HttpVersion::Http11,
+            _ => HttpVersion::Other(s.to_string()),
+        }
+    }
+
+    /// バージョン文字列を取得
+    pub fn as_str(&self) -> &str {
+        match self {
+            HttpVersion::Http10 => "HTTP/1.0",
+            HttpVersion::Http11 => "HTTP/1.1",
+            HttpVersion::Other(s) => s,
+        }
+    }
+
+    /// HTTP-version の文法 (`"HTTP/" DIGIT "." DIGIT`) に一致するか
+    pub fn is_http(&self) -> bool {
+        is_valid_http_version(self.as_str())
+    }
+}
+
+impl fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<HttpVersion> for String {
+    fn from(version: HttpVersion) -> Self {
+        match version {
+            HttpVersion::Other(s) => s,
+            v => v.as_str().to_string(),
+        }
+    }
+}
+
+impl FromStr for HttpVersion {
+    type Err = HttpVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
@@ -0,0 +1,431 @@
+//! Via ヘッダー (RFC 9110 Section 7.6.3)
+//!
+//! ## 概要
+//!
+//! RFC 9110 に基づいた Via ヘッダーのパースと生成を提供します。
+//! プロキシが自身のエントリを追加するヘルパーと、
+//! 自身の pseudonym がすでに含まれているかどうかによるループ検出も提供します。
+//!
+//! ## ABNF
+//!
+//! ```text
+//! Via = #( received-protocol RWS received-by [ RWS comment ] )
+//!
+//! received-protocol = [ protocol-name "/" ] protocol-version
+//! received-by       = pseudonym [ ":" port ]
+//! pseudonym         = token
+//! comment           = "(" *( ctext / quoted-pair / comment ) ")"
+//! ```
+//!
+//! ## 使い方
+//!
+//! ```rust
+//! use shiguredo_http11::via::{Via, ViaEntry};
+//!
+//! let via = Via::parse("1.0 fred, 1.1 p.example.net (Apache/2.4)").unwrap();
+//! assert_eq!(via.entries().len(), 2);
+//! assert_eq!(via.entries()[1].received_by(), "p.example.net");
+//! assert_eq!(via.entries()[1].comment(), Some("Apache/2.4"));
+//! assert!(via.has_pseudonym("FRED"));
+//!
+//! // protocol-name が HTTP の場合は省略する
+//! let entry = ViaEntry::new("HTTP/1.1", "proxy:8080").unwrap();
+//! assert_eq!(entry.to_string(), "1.1 proxy:8080");
+//! ```
+//!
+//! ## プロキシでの利用
+//!
+//! ```rust
+//! use shiguredo_http11::Request;
+//! use shiguredo_http11::via::{ViaEntry, append_via, detect_loop};
+//!
+//! let request = Request::new("GET", "/")
+//!     .unwrap()
+//!     .header("Via", "1.1 edge")
+//!     .unwrap();
+//!
+//! // 自身の pseudonym が含まれていればループしている
+//! assert_eq!(detect_loop(&request, "proxy"), Ok(false));
+//!
+//! let entry = ViaEntry::new(request.version(), "proxy").unwrap();
+//! let request = append_via(request, &entry).unwrap();
+//! assert_eq!(request.get_header("Via"), Some("1.1 edge, 1.1 proxy"));
+//! assert_eq!(detect_loop(&request, "proxy"), Ok(true));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::HttpHead;
+use crate::error::EncodeError;
+use crate::request::Request;
+use crate::response::Response;
+use crate::typed_header::TypedHeader;
+use crate::validate::is_valid_token;
+
+/// Via パースエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ViaError {
+    /// 不正な形式
+    InvalidFormat,
+    /// 不正な received-protocol
+    InvalidProtocol,
+    /// 不正な received-by
+    InvalidReceivedBy,
+    /// 不正な comment (ctext / quoted-pair の文字種違反)
+    InvalidComment,
+    /// comment の閉じ括弧が見つからない
+    UnterminatedComment,
+}
+
+impl fmt::Display for ViaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViaError::InvalidFormat => write!(f, "invalid Via header format"),
+            ViaError::InvalidProtocol => write!(f, "invalid Via received-protocol"),
+            ViaError::InvalidReceivedBy => write!(f, "invalid Via received-by"),
+            ViaError::InvalidComment => write!(f, "invalid Via comment"),
+            ViaError::UnterminatedComment => write!(f, "unterminated Via comment"),
+        }
+    }
+}
+
+impl core::error::Error for ViaError {}
+
+/// Via の 1 エントリ (1 ホップ分)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViaEntry {
+    protocol_name: Option<String>,
+    protocol_version: String,
+    received_by: String,
+    port: Option<u16>,
+    comment: Option<String>,
+}
+
+impl ViaEntry {
+    /// received-protocol と received-by からエントリを作成
+    ///
+    /// received-protocol は `HTTP/1.1` / `1.1` / `WebSocket/13` 等を受け付ける。
+    /// RFC 9110 Section 7.6.3: protocol-name は HTTP の場合のみ省略するため、
+    /// `HTTP` は取り除く。received-by は `pseudonym[:port]` 形式。
+    pub fn new(received_protocol: &str, received_by: &str) -> Result<Self, ViaError> {
+        let (protocol_name, protocol_version) = parse_received_protocol(received_protocol)?;
+        let (received_by, port) = parse_received_by(received_by)?;
+        Ok(ViaEntry {
+            protocol_name,
+            protocol_version,
+            received_by,
+            port,
+            comment: None,
+        })
+    }
+
+    /// comment を設定
+    ///
+    /// 括弧を除いた中身を指定する。入れ子の括弧は対応している必要があり、
+    /// 対応しない括弧は quoted-pair (`\(`) で表す。
+    pub fn with_comment(mut self, comment: &str) -> Result<Self, ViaError> {
+        let mut wrapped = String::with_capacity(comment.len() + 2);
+        wrapped.push('(');
+        wrapped.push_str(comment);
+        wrapped.push(')');
+        let (_, rest) = parse_comment(&wrapped)?;
+        if !rest.is_empty() {
+            return Err(ViaError::InvalidComment);
+        }
+        self.comment = Some(comment.to_string());
+        Ok(self)
+    }
+
+    /// protocol-name (HTTP の場合は `None`)
+    pub fn protocol_name(&self) -> Option<&str> {
+        self.protocol_name.as_deref()
+    }
+
+    /// protocol-version
+    pub fn protocol_version(&self) -> &str {
+        &self.protocol_version
+    }
+
+    /// received-by の pseudonym (またはホスト名)
+    pub fn received_by(&self) -> &str {
+        &self.received_by
+    }
+
+    /// received-by のポート
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// comment (括弧を除いた中身)
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+}
+
+impl fmt::Display for ViaEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.protocol_name {
+            write!(f, "{}/", name)?;
+        }
+        write!(f, "{} {}", self.protocol_version, self.received_by)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        if let Some(comment) = &self.comment {
+            write!(f, " ({})", comment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Via ヘッダー
+///
+/// エントリはクライアントに近いホップから順に並ぶ。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Via {
+    entries: Vec<ViaEntry>,
+}
+
+impl Via {
+    /// 空の Via を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Via ヘッダーをパース
+    ///
+    /// RFC 9110 Section 5.6.1.2: 空のリスト要素は無視する。
+    /// comment 内のカンマは要素を区切らない。
+    pub fn parse(input: &str) -> Result<Self, ViaError> {
+        let mut entries = Vec::new();
+        let mut rest = input;
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ',']);
+            if rest.is_empty() {
+                break;
+            }
+
+            let (received_protocol, after) = split_word(rest);
+            let after_ws = after.trim_start_matches([' ', '\t']);
+            // RWS
+            if after_ws.len() == after.len() {
+                return Err(ViaError::InvalidFormat);
+            }
+            let (received_by, after) = split_word(after_ws);
+            let mut entry = ViaEntry::new(received_protocol, received_by)?;
+
+            let after_ws = after.trim_start_matches([' ', '\t']);
+            rest = if after_ws.starts_with('(') {
+                if after_ws.len() == after.len() {
+                    return Err(ViaError::InvalidFormat);
+                }
+                let (comment, after) = parse_comment(after_ws)?;
+                entry.comment = Some(comment.to_string());
+                after.trim_start_matches([' ', '\t'])
+            } else {
+                after_ws
+            };
+            if !rest.is_empty() && !rest.starts_with(',') {
+                return Err(ViaError::InvalidFormat);
+            }
+            entries.push(entry);
+        }
+        Ok(Via { entries })
+    }
+
+    /// メッセージの Via ヘッダーを取得
+    ///
+    /// 複数行の Via は結合する。Via ヘッダーがない場合は `None` を返す。
+    pub fn from_message<H: HttpHead>(message: &H) -> Result<Option<Self>, ViaError> {
+        let values = message.get_headers("Via");
+        if values.is_empty() {
+            return Ok(None);
+        }
+        Via::parse(&values.join(", ")).map(Some)
+    }
+
+    /// エントリ一覧 (クライアントに近い順)
+    pub fn entries(&self) -> &[ViaEntry] {
+        &self.entries
+    }
+
+    /// 末尾にエントリを追加
+    pub fn push(&mut self, entry: ViaEntry) {
+        self.entries.push(entry);
+    }
+
+    /// 末尾にエントリを追加 (ビルダー)
+    pub fn with_entry(mut self, entry: ViaEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// 指定した pseudonym のエントリを含むかどうか
+    ///
+    /// 大文字小文字を区別せずに received-by を比較する (ポートは比較しない)。
+    pub fn has_pseudonym(&self, pseudonym: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.received_by.eq_ignore_ascii_case(pseudonym))
+    }
+}
+
+impl fmt::Display for Via {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = self.entries.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+impl TypedHeader for Via {
+    const NAME: &'static str = "Via";
+    type Error = ViaError;
+
+    fn parse_value(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+
+    fn encode_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// メッセージの Via に指定した pseudonym が含まれているかどうか
+///
+/// プロキシは自身の pseudonym が含まれるリクエストを受け取った場合、
+/// リクエストがループしていると判断できる (RFC 9110 Section 7.6.3)。
+/// Via ヘッダーがない場合は `false` を返す。
+pub fn detect_loop<H: HttpHead>(message: &H, pseudonym: &str) -> Result<bool, ViaError> {
+    Ok(Via::from_message(message)?.is_some_and(|via| via.has_pseudonym(pseudonym)))
+}
+
+/// リクエストの Via ヘッダーの末尾にエントリを追加
+///
+/// RFC 9110 Section 7.6.3: プロキシは転送するリクエストに自身の Via エントリを追加しなければならない (MUST)。
+/// 複数行の Via は 1 行にまとめる。既存の値は検証せずにそのまま残す。
+pub fn append_via(mut request: Request, entry: &ViaEntry) -> Result<Request, EncodeError> {
+    let value = append_list_value(request.get_headers("Via"), entry);
+    request.set_header("Via", value)?;
+    Ok(request)
+}
+
+/// レスポンスの Via ヘッダーの末尾にエントリを追加
+///
+/// RFC 9110 Section 7.6.3: ゲートウェイは転送するレスポンスに Via エントリを追加してもよい (MAY)。
+pub fn append_response_via(
+    mut response: Response,
+    entry: &ViaEntry,
+) -> Result<Response, EncodeError> {
+    let value = append_list_value(response.get_headers("Via"), entry);
+    response.set_header("Via", value)?;
+    Ok(response)
+}
+
+/// 既存の Via ヘッダー行を 1 行にまとめて末尾にエントリを追加した値を返す
+pub(crate) fn append_list_value(existing: Vec<&str>, entry: &ViaEntry) -> String {
+    let mut values: Vec<String> = existing
+        .into_iter()
+        .map(|value| value.trim_matches(|c| c == ',' || c == ' ' || c == '\t'))
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+        .collect();
+    values.push(entry.to_string());
+    values.join(", ")
+}
+
+/// 空白またはカンマまでを切り出す
+fn split_word(input: &str) -> (&str, &str) {
+    let end = input.find([' ', '\t', ',']).unwrap_or(input.len());
+    input.split_at(end)
+}
+
+fn parse_received_protocol(input: &str) -> Result<(Option<String>, String), ViaError> {
+    let (name, version) = match input.split_once('/') {
+        Some((name, version)) => (Some(name), version),
+        None => (None, input),
+    };
+    if !is_valid_token(version) || name.is_some_and(|name| !is_valid_token(name)) {
+        return Err(ViaError::InvalidProtocol);
+    }
+    let name = name.filter(|name| *name != "HTTP").map(ToString::to_string);
+    Ok((name, version.to_string()))
+}
+
+fn parse_received_by(input: &str) -> Result<(String, Option<u16>), ViaError> {
+    // 実装によっては IPv6 リテラルを送るため、角括弧で囲んだ形も受け付ける
+    let (name, port) = if input.starts_with('[') {
+        let end = input.find(']').ok_or(ViaError::InvalidReceivedBy)? + 1;
+        let (name, rest) = input.split_at(end);
+        if name[1..name.len() - 1]
+            .bytes()
+            .any(|b| !(b.is_ascii_hexdigit() || b == b':' || b == b'.'))
+        {
+            return Err(ViaError::InvalidReceivedBy);
+        }
+        let port = match rest {
+            "" => None,
+            _ => Some(rest.strip_prefix(':').ok_or(ViaError::InvalidReceivedBy)?),
+        };
+        (name, port)
+    } else {
+        let (name, port) = match input.split_once(':') {
+            Some((name, port)) => (name, Some(port)),
+            None => (input, None),
+        };
+        if !is_valid_token(name) {
+            return Err(ViaError::InvalidReceivedBy);
+        }
+        (name, port)
+    };
+
+    let port = match port {
+        Some(port) => {
+            if port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ViaError::InvalidReceivedBy);
+            }
+            Some(
+                port.parse::<u16>()
+                    .map_err(|_| ViaError::InvalidReceivedBy)?,
+            )
+        }
+        None => None,
+    };
+    Ok((name.to_string(), port))
+}
+
+/// comment をパースし、括弧を除いた中身と残りの入力を返す
+///
+/// RFC 9110 Section 5.6.5:
+/// ctext = HTAB / SP / %x21-27 / %x2A-5B / %x5D-7E / obs-text
+fn parse_comment(input: &str) -> Result<(&str, &str), ViaError> {
+    let bytes = input.as_bytes();
+    debug_assert_eq!(bytes.first(), Some(&b'('));
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((&input[1..i], &input[i + 1..]));
+                }
+            }
+            b'\\' => {
+                i += 1;
+                match bytes.get(i) {
+                    Some(&b) if b == b'\t' || (b' '..=b'~').contains(&b) || b >= 0x80 => {}
+                    Some(_) => return Err(ViaError::InvalidComment),
+                    None => return Err(ViaError::UnterminatedComment),
+                }
+            }
+            b if b == b'\t' || (b' '..=b'~').contains(&b) || b >= 0x80 => {}
+            _ => return Err(ViaError::InvalidComment),
+        }
+        i += 1;
+    }
+    Err(ViaError::UnterminatedComment)
+}
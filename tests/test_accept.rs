@@ -1,7 +1,8 @@
 //! Accept 系ヘッダーのユニットテスト
 
 use shiguredo_http11::accept::{
-    Accept, AcceptCharset, AcceptEncoding, AcceptError, AcceptLanguage, QValue,
+    Accept, AcceptCharset, AcceptEncoding, AcceptError, AcceptLanguage, QValue, filter_languages,
+    lookup_language, negotiate, negotiate_encoding,
 };
 
 // ========================================
@@ -254,3 +255,268 @@ fn test_accept_unterminated_quote() {
         Err(AcceptError::UnterminatedQuote),
     );
 }
+
+// ========================================
+// メディアタイプのネゴシエーションのテスト
+// ========================================
+
+#[test]
+fn test_accept_quality_specificity() {
+    let accept =
+        Accept::parse("text/*;q=0.3, text/html;q=0.7, text/html;level=1, */*;q=0.5").unwrap();
+    assert_eq!(
+        accept.quality("text/html;level=1"),
+        Some(QValue::parse("1").unwrap())
+    );
+    assert_eq!(
+        accept.quality("text/html"),
+        Some(QValue::parse("0.7").unwrap())
+    );
+    assert_eq!(
+        accept.quality("text/html;level=2"),
+        Some(QValue::parse("0.7").unwrap())
+    );
+    assert_eq!(
+        accept.quality("text/plain"),
+        Some(QValue::parse("0.3").unwrap())
+    );
+    assert_eq!(
+        accept.quality("image/jpeg"),
+        Some(QValue::parse("0.5").unwrap())
+    );
+}
+
+#[test]
+fn test_accept_quality_no_match() {
+    let accept = Accept::parse("text/html").unwrap();
+    assert_eq!(accept.quality("application/json"), None);
+    assert_eq!(accept.quality("invalid"), None);
+}
+
+#[test]
+fn test_accept_quality_case_insensitive() {
+    let accept = Accept::parse("Text/HTML;Charset=UTF-8;q=0.8").unwrap();
+    assert_eq!(
+        accept.quality("text/html; charset=utf-8"),
+        Some(QValue::parse("0.8").unwrap())
+    );
+}
+
+#[test]
+fn test_negotiate_highest_quality() {
+    let accept = Accept::parse("text/*;q=0.5, application/json").unwrap();
+    assert_eq!(
+        negotiate(&accept, &["text/html", "application/json"]),
+        Some("application/json")
+    );
+}
+
+#[test]
+fn test_negotiate_tie_prefers_server_order() {
+    let accept = Accept::parse("*/*").unwrap();
+    assert_eq!(
+        negotiate(&accept, &["application/json", "text/html"]),
+        Some("application/json")
+    );
+}
+
+#[test]
+fn test_negotiate_excludes_q_zero() {
+    let accept = Accept::parse("*/*, application/json;q=0").unwrap();
+    assert_eq!(
+        negotiate(&accept, &["application/json", "text/html"]),
+        Some("text/html")
+    );
+    let accept = Accept::parse("text/html;q=0").unwrap();
+    assert_eq!(negotiate(&accept, &["text/html"]), None);
+}
+
+#[test]
+fn test_negotiate_no_acceptable() {
+    let accept = Accept::parse("image/*").unwrap();
+    assert_eq!(negotiate(&accept, &["text/html", "application/json"]), None);
+    assert_eq!(negotiate(&accept, &[]), None);
+}
+
+#[test]
+fn test_negotiate_empty_accept() {
+    let accept = Accept::parse("").unwrap();
+    assert_eq!(
+        negotiate(&accept, &["text/html", "application/json"]),
+        Some("text/html")
+    );
+}
+
+// ========================================
+// 言語のネゴシエーションのテスト
+// ========================================
+
+#[test]
+fn test_accept_language_quality() {
+    let accept = AcceptLanguage::parse("de-CH, de;q=0.8, *;q=0.1").unwrap();
+    assert_eq!(
+        accept.quality("de-CH-1996"),
+        Some(QValue::parse("1").unwrap())
+    );
+    assert_eq!(accept.quality("de"), Some(QValue::parse("0.8").unwrap()));
+    assert_eq!(accept.quality("DE-AT"), Some(QValue::parse("0.8").unwrap()));
+    assert_eq!(accept.quality("en"), Some(QValue::parse("0.1").unwrap()));
+    // "de" は "deu" の接頭辞だがサブタグ境界ではない
+    let accept = AcceptLanguage::parse("de").unwrap();
+    assert_eq!(accept.quality("deu"), None);
+}
+
+#[test]
+fn test_filter_languages_order() {
+    let accept = AcceptLanguage::parse("en;q=0.5, ja, fr;q=0.5").unwrap();
+    assert_eq!(
+        filter_languages(&accept, &["fr-FR", "en-US", "de", "ja-JP"]),
+        vec!["ja-JP", "fr-FR", "en-US"]
+    );
+}
+
+#[test]
+fn test_filter_languages_wildcard_and_exclusion() {
+    let accept = AcceptLanguage::parse("*, fr;q=0").unwrap();
+    assert_eq!(
+        filter_languages(&accept, &["fr-CA", "en", "fr", "ja"]),
+        vec!["en", "ja"]
+    );
+}
+
+#[test]
+fn test_filter_languages_empty_accept() {
+    let accept = AcceptLanguage::parse("").unwrap();
+    assert_eq!(filter_languages(&accept, &["en", "ja"]), vec!["en", "ja"]);
+}
+
+#[test]
+fn test_lookup_language_truncation() {
+    // RFC 4647 Section 3.4 の例
+    let accept = AcceptLanguage::parse("zh-Hant-CN-x-private1-private2").unwrap();
+    assert_eq!(
+        lookup_language(&accept, &["zh", "zh-Hant-CN-x-private1"]),
+        Some("zh-Hant-CN-x-private1")
+    );
+    assert_eq!(
+        lookup_language(&accept, &["zh", "zh-Hant-CN"]),
+        Some("zh-Hant-CN")
+    );
+    assert_eq!(lookup_language(&accept, &["zh", "en"]), Some("zh"));
+}
+
+#[test]
+fn test_lookup_language_truncation_case_insensitive() {
+    // 切り詰めて一致した言語タグは基本フィルタリングでは一致しない
+    let accept = AcceptLanguage::parse("U-a;q=0.001").unwrap();
+    assert_eq!(lookup_language(&accept, &["u"]), Some("u"));
+    assert_eq!(accept.quality("u"), None);
+    assert!(filter_languages(&accept, &["u"]).is_empty());
+}
+
+#[test]
+fn test_lookup_language_priority() {
+    let accept = AcceptLanguage::parse("fr;q=0.5, ja-JP, en;q=0.8").unwrap();
+    assert_eq!(lookup_language(&accept, &["en", "fr", "ja"]), Some("ja"));
+    assert_eq!(lookup_language(&accept, &["fr", "en-US", "en"]), Some("en"));
+}
+
+#[test]
+fn test_lookup_language_not_found() {
+    let accept = AcceptLanguage::parse("*").unwrap();
+    assert_eq!(lookup_language(&accept, &["en"]), None);
+    let accept = AcceptLanguage::parse("en-US, en;q=0").unwrap();
+    assert_eq!(lookup_language(&accept, &["en"]), None);
+    let accept = AcceptLanguage::parse("de").unwrap();
+    assert_eq!(lookup_language(&accept, &["de-DE"]), None);
+}
+
+// ========================================
+// コンテンツコーディングのネゴシエーションのテスト
+// ========================================
+
+#[test]
+fn test_accept_encoding_quality() {
+    let accept = AcceptEncoding::parse("GZIP;q=0.5, *;q=0.1, br;q=0").unwrap();
+    assert_eq!(accept.quality("gzip"), Some(QValue::parse("0.5").unwrap()));
+    assert_eq!(
+        accept.quality("x-gzip"),
+        Some(QValue::parse("0.5").unwrap())
+    );
+    assert_eq!(accept.quality("zstd"), Some(QValue::parse("0.1").unwrap()));
+    assert_eq!(accept.quality("br"), Some(QValue::parse("0").unwrap()));
+
+    let accept = AcceptEncoding::parse("x-gzip").unwrap();
+    assert_eq!(accept.quality("gzip"), Some(QValue::parse("1").unwrap()));
+    assert_eq!(accept.quality("br"), None);
+}
+
+#[test]
+fn test_negotiate_encoding_quality_and_preference() {
+    let accept = AcceptEncoding::parse("gzip, br;q=0.9").unwrap();
+    assert_eq!(
+        negotiate_encoding(&accept, &["zstd", "br", "gzip"]),
+        Some("gzip")
+    );
+    let accept = AcceptEncoding::parse("gzip, br").unwrap();
+    assert_eq!(
+        negotiate_encoding(&accept, &["zstd", "br", "gzip"]),
+        Some("br")
+    );
+}
+
+#[test]
+fn test_negotiate_encoding_wildcard() {
+    let accept = AcceptEncoding::parse("*").unwrap();
+    assert_eq!(
+        negotiate_encoding(&accept, &["zstd", "br", "gzip"]),
+        Some("zstd")
+    );
+    let accept = AcceptEncoding::parse("*, zstd;q=0").unwrap();
+    assert_eq!(
+        negotiate_encoding(&accept, &["zstd", "br", "gzip"]),
+        Some("br")
+    );
+}
+
+#[test]
+fn test_negotiate_encoding_identity_fallback() {
+    // 空の Accept-Encoding は identity のみ受理する
+    let accept = AcceptEncoding::parse("").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("identity"));
+
+    let accept = AcceptEncoding::parse("deflate").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("identity"));
+
+    // identity の q 値が候補より大きければ identity を選ぶ
+    let accept = AcceptEncoding::parse("identity;q=1, gzip;q=0.5").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("identity"));
+    let accept = AcceptEncoding::parse("*, gzip;q=0.5").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("identity"));
+
+    // q 値が同じ場合は候補を優先する
+    let accept = AcceptEncoding::parse("identity, gzip").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("gzip"));
+    let accept = AcceptEncoding::parse("identity;q=0.5, gzip").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("gzip"));
+
+    // identity を候補に含めて優先度を指定できる
+    let accept = AcceptEncoding::parse("gzip;q=0.5, identity").unwrap();
+    assert_eq!(
+        negotiate_encoding(&accept, &["gzip", "identity"]),
+        Some("identity")
+    );
+}
+
+#[test]
+fn test_negotiate_encoding_not_acceptable() {
+    let accept = AcceptEncoding::parse("deflate, identity;q=0").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), None);
+
+    let accept = AcceptEncoding::parse("*;q=0").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), None);
+
+    // identity のレンジがあれば *;q=0 でも除外されない
+    let accept = AcceptEncoding::parse("*;q=0, identity;q=0.1").unwrap();
+    assert_eq!(negotiate_encoding(&accept, &["gzip"]), Some("identity"));
+}
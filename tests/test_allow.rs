@@ -0,0 +1,159 @@
+//! Allow ヘッダーと 405 レスポンスのユニットテスト
+
+use shiguredo_http11::allow::{Allow, AllowError, PROBLEM_CONTENT_TYPE};
+use shiguredo_http11::typed_header::TypedHeader;
+use shiguredo_http11::{EncodeError, Method, Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// AllowError のテスト
+// ========================================
+
+#[test]
+fn test_allow_error_display() {
+    let error = AllowError::InvalidMethod("G T".to_string());
+    assert_eq!(error.to_string(), "invalid Allow method: \"G T\"");
+}
+
+// ========================================
+// Allow のテスト
+// ========================================
+
+#[test]
+fn test_allow_parse() {
+    let allow = Allow::parse("GET, HEAD,POST").unwrap();
+    assert_eq!(allow.methods(), &[Method::GET, Method::HEAD, Method::POST]);
+    assert!(allow.contains("HEAD"));
+    // メソッドは大文字小文字を区別する
+    assert!(!allow.contains("head"));
+    assert_eq!(allow.to_string(), "GET, HEAD, POST");
+}
+
+#[test]
+fn test_allow_parse_empty() {
+    // 空フィールド値はどのメソッドも許可しない
+    let allow = Allow::parse("").unwrap();
+    assert!(allow.is_empty());
+    assert_eq!(allow.to_string(), "");
+
+    // 空要素は無視する
+    let allow = Allow::parse(" , GET ,, PURGE ,").unwrap();
+    assert_eq!(allow.to_string(), "GET, PURGE");
+}
+
+#[test]
+fn test_allow_parse_invalid() {
+    assert_eq!(
+        Allow::parse("GET, G(T"),
+        Err(AllowError::InvalidMethod("G(T".to_string()))
+    );
+    assert_eq!(
+        Allow::parse("GET HEAD"),
+        Err(AllowError::InvalidMethod("GET HEAD".to_string()))
+    );
+}
+
+#[test]
+fn test_allow_dedup() {
+    let allow = Allow::from_methods(&["GET", "HEAD", "GET"]).unwrap();
+    assert_eq!(allow.to_string(), "GET, HEAD");
+
+    let allow = Allow::new()
+        .with_method(Method::OPTIONS)
+        .with_method(Method::GET)
+        .with_method(Method::OPTIONS);
+    assert_eq!(allow.to_string(), "OPTIONS, GET");
+}
+
+#[test]
+fn test_allow_typed_header() {
+    let allow = Allow::from_methods(&["GET", "HEAD"]).unwrap();
+    assert_eq!(Allow::NAME, "Allow");
+    assert_eq!(allow.encode_value(), "GET, HEAD");
+    assert_eq!(Allow::parse_value("GET, HEAD"), Ok(allow));
+}
+
+#[test]
+fn test_allow_from_message() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Allow", "GET")
+        .unwrap()
+        .header("Allow", "HEAD, GET")
+        .unwrap();
+    let allow = Allow::from_message(&response).unwrap().unwrap();
+    assert_eq!(allow.to_string(), "GET, HEAD");
+
+    let response = Response::with_status(StatusCode::OK);
+    assert_eq!(Allow::from_message(&response), Ok(None));
+}
+
+// ========================================
+// 405 レスポンスのテスト
+// ========================================
+
+#[test]
+fn test_method_not_allowed() {
+    let response = Response::method_not_allowed(&["GET", "HEAD"]).unwrap();
+    assert_eq!(response.status_code(), 405);
+    assert_eq!(response.reason_phrase(), "Method Not Allowed");
+    assert_eq!(response.get_header("Allow"), Some("GET, HEAD"));
+
+    let encoded = response.encode().unwrap();
+    assert_eq!(
+        encoded,
+        b"HTTP/1.1 405 Method Not Allowed\r\nAllow: GET, HEAD\r\nContent-Length: 0\r\n\r\n"
+    );
+
+    // デコードして Allow を取得できる
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(&encoded).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(
+        Allow::from_message(&head)
+            .unwrap()
+            .unwrap()
+            .contains("HEAD")
+    );
+}
+
+#[test]
+fn test_method_not_allowed_empty() {
+    // 空の Allow はどのメソッドも許可しないことを表す
+    let response = Response::method_not_allowed(&[]).unwrap();
+    assert_eq!(response.get_header("Allow"), Some(""));
+}
+
+#[test]
+fn test_method_not_allowed_problem() {
+    let response = Response::method_not_allowed_problem(&["GET"], "DELETE").unwrap();
+    assert_eq!(response.status_code(), 405);
+    assert_eq!(response.get_header("Allow"), Some("GET"));
+    assert_eq!(
+        response.get_header("Content-Type"),
+        Some(PROBLEM_CONTENT_TYPE)
+    );
+    assert_eq!(
+        response.body_bytes(),
+        Some(
+            &br#"{"type":"about:blank","title":"Method Not Allowed","status":405,"detail":"method DELETE is not allowed"}"#[..]
+        )
+    );
+
+    // JSON の文字列としてエスケープする
+    let response = Response::method_not_allowed_problem(&["GET"], "\"\\\n").unwrap();
+    let body = String::from_utf8(response.body_bytes().unwrap().to_vec()).unwrap();
+    assert!(body.contains(r#""detail":"method \"\\\u000a is not allowed""#));
+}
+
+#[test]
+fn test_method_not_allowed_invalid_method() {
+    assert_eq!(
+        Response::method_not_allowed(&["GET", ""]).unwrap_err(),
+        EncodeError::InvalidMethod {
+            method: String::new()
+        }
+    );
+    assert!(matches!(
+        Response::method_not_allowed_problem(&["G T"], "POST"),
+        Err(EncodeError::InvalidMethod { .. })
+    ));
+}
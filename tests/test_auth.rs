@@ -2,7 +2,7 @@
 
 use shiguredo_http11::auth::{
     AuthChallenge, AuthError, Authorization, BasicAuth, BearerToken, DigestAuth, DigestChallenge,
-    WwwAuthenticate,
+    WwwAuthenticate, secure_eq,
 };
 
 // ========================================
@@ -338,3 +338,42 @@ fn test_basic_realm_quoted_pair_rejects_cr_lf_nul() {
         );
     }
 }
+
+// ========================================
+// 定数時間比較のテスト
+// ========================================
+
+#[test]
+fn test_secure_eq() {
+    assert!(secure_eq("", ""));
+    assert!(secure_eq("token", "token"));
+    assert!(secure_eq(b"\x00\xff", [0u8, 255]));
+    assert!(!secure_eq("token", "Token"));
+    assert!(!secure_eq("token", "toke"));
+    assert!(!secure_eq("toke", "token"));
+    assert!(!secure_eq("", "token"));
+    assert!(!secure_eq("token", ""));
+    // 短い側が 0 で埋められても一致とみなさない
+    assert!(!secure_eq(b"ab\x00", b"ab"));
+    assert!(!secure_eq(b"ab", b"ab\x00"));
+}
+
+#[test]
+fn test_basic_auth_verify() {
+    let auth = BasicAuth::parse("Basic dXNlcjpwYXNzd29yZA==").unwrap();
+    assert!(auth.verify("user", "password"));
+    assert!(!auth.verify("user", "password "));
+    assert!(!auth.verify("user", "Password"));
+    assert!(!auth.verify("User", "password"));
+    assert!(!auth.verify("", ""));
+
+    // 空のパスワード
+    let auth = BasicAuth::new("user", "").unwrap();
+    assert!(auth.verify("user", ""));
+    assert!(!auth.verify("user", "x"));
+
+    // パスワードに : を含む
+    let auth = BasicAuth::parse(&BasicAuth::new("user", "a:b").unwrap().to_header_value()).unwrap();
+    assert!(auth.verify("user", "a:b"));
+    assert!(!auth.verify("user:a", "b"));
+}
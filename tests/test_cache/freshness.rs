@@ -0,0 +1,242 @@
+//! `cache::freshness` の鮮度計算のユニットテスト
+
+use std::borrow::Cow;
+
+use shiguredo_http11::cache::freshness::{CacheKind, Freshness, LifetimeSource, ResponseTiming};
+use shiguredo_http11::date::HttpDate;
+
+// Sun, 06 Nov 1994 08:49:37 GMT
+const DATE: u64 = 784111777;
+const DATE_STR: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+fn headers(list: &[(&'static str, &str)]) -> Vec<(Cow<'static, str>, String)> {
+    list.iter()
+        .map(|(n, v)| (Cow::Borrowed(*n), v.to_string()))
+        .collect()
+}
+
+fn at(now: u64) -> ResponseTiming {
+    ResponseTiming::new(DATE, DATE, now)
+}
+
+fn http_date(seconds: u64) -> String {
+    HttpDate::from_unix_seconds(seconds).unwrap().to_string()
+}
+
+// ========================================
+// 有効期間のテスト
+// ========================================
+
+#[test]
+fn test_freshness_max_age() {
+    let h = headers(&[("Date", DATE_STR), ("Cache-Control", "max-age=60")]);
+    let f = Freshness::calculate(200, &h, at(DATE + 59), CacheKind::Private);
+    assert_eq!(f.lifetime(), 60);
+    assert_eq!(f.lifetime_source(), LifetimeSource::MaxAge);
+    assert!(f.is_fresh());
+    assert_eq!(f.time_to_live(), 1);
+    assert!(!f.requires_revalidation());
+
+    let f = Freshness::calculate(200, &h, at(DATE + 60), CacheKind::Private);
+    assert!(!f.is_fresh());
+    assert_eq!(f.staleness(), 0);
+    assert!(f.requires_revalidation());
+}
+
+#[test]
+fn test_freshness_s_maxage_only_for_shared() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Cache-Control", "max-age=60, s-maxage=600"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Shared);
+    assert_eq!(f.lifetime(), 600);
+    assert_eq!(f.lifetime_source(), LifetimeSource::SMaxAge);
+    // s-maxage は共有キャッシュで proxy-revalidate を含意する
+    assert!(!f.allows_stale());
+
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 60);
+    assert_eq!(f.lifetime_source(), LifetimeSource::MaxAge);
+    assert!(f.allows_stale());
+}
+
+#[test]
+fn test_freshness_expires() {
+    let h = headers(&[("Date", DATE_STR), ("Expires", &http_date(DATE + 3600))]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 3600);
+    assert_eq!(f.lifetime_source(), LifetimeSource::Expires);
+
+    // max-age は Expires より優先する
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Expires", &http_date(DATE + 3600)),
+        ("Cache-Control", "max-age=10"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 10);
+}
+
+#[test]
+fn test_freshness_invalid_expires_is_expired() {
+    let h = headers(&[("Date", DATE_STR), ("Expires", "0")]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 0);
+    assert_eq!(f.lifetime_source(), LifetimeSource::Expires);
+    assert!(!f.is_fresh());
+}
+
+#[test]
+fn test_freshness_rfc850_expires() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Expires", "Sunday, 06-Nov-94 09:49:37 GMT"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 3600);
+}
+
+#[test]
+fn test_freshness_heuristic() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Last-Modified", &http_date(DATE - 1000)),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 100);
+    assert!(f.is_heuristic());
+
+    // ヒューリスティックにキャッシュ可能でないステータスコード
+    let f = Freshness::calculate(302, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 0);
+    assert_eq!(f.lifetime_source(), LifetimeSource::None);
+
+    // public があれば適用する
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Last-Modified", &http_date(DATE - 1000)),
+        ("Cache-Control", "public"),
+    ]);
+    let f = Freshness::calculate(302, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 100);
+}
+
+#[test]
+fn test_freshness_no_explicit_lifetime() {
+    let h = headers(&[("Date", DATE_STR)]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert_eq!(f.lifetime(), 0);
+    assert_eq!(f.lifetime_source(), LifetimeSource::None);
+    assert!(!f.is_fresh());
+}
+
+// ========================================
+// Age のテスト
+// ========================================
+
+#[test]
+fn test_freshness_current_age() {
+    // Age: 10、応答遅延 5 秒、受信から 20 秒経過
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Age", "10"),
+        ("Cache-Control", "max-age=100"),
+    ]);
+    let timing = ResponseTiming::new(DATE - 5, DATE, DATE + 20);
+    let f = Freshness::calculate(200, &h, timing, CacheKind::Private);
+    assert_eq!(f.current_age(), 10 + 5 + 20);
+    assert_eq!(f.time_to_live(), 65);
+}
+
+#[test]
+fn test_freshness_apparent_age() {
+    // Date が受信時刻より 30 秒前で Age がない
+    let h = headers(&[("Date", DATE_STR), ("Cache-Control", "max-age=100")]);
+    let timing = ResponseTiming::new(DATE + 30, DATE + 30, DATE + 40);
+    let f = Freshness::calculate(200, &h, timing, CacheKind::Private);
+    assert_eq!(f.current_age(), 40);
+}
+
+#[test]
+fn test_freshness_missing_date_uses_response_time() {
+    let h = headers(&[("Cache-Control", "max-age=100")]);
+    let f = Freshness::calculate(200, &h, at(DATE + 10), CacheKind::Private);
+    assert_eq!(f.current_age(), 10);
+}
+
+// ========================================
+// 再検証のテスト
+// ========================================
+
+#[test]
+fn test_freshness_no_cache_requires_revalidation() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Cache-Control", "max-age=60, no-cache"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert!(f.is_fresh());
+    assert!(f.requires_revalidation());
+    assert!(!f.allows_stale());
+}
+
+#[test]
+fn test_freshness_invalid_cache_control_requires_revalidation() {
+    let h = headers(&[("Date", DATE_STR), ("Cache-Control", "max-age=abc")]);
+    let f = Freshness::calculate(200, &h, at(DATE), CacheKind::Private);
+    assert!(f.requires_revalidation());
+}
+
+#[test]
+fn test_freshness_must_revalidate() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Cache-Control", "max-age=60, must-revalidate"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE + 100), CacheKind::Private);
+    assert!(!f.allows_stale());
+
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Cache-Control", "max-age=60, proxy-revalidate"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE + 100), CacheKind::Private);
+    assert!(f.allows_stale());
+    let f = Freshness::calculate(200, &h, at(DATE + 100), CacheKind::Shared);
+    assert!(!f.allows_stale());
+}
+
+#[test]
+fn test_freshness_stale_windows() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        (
+            "Cache-Control",
+            "max-age=60, stale-while-revalidate=30, stale-if-error=300",
+        ),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE + 30), CacheKind::Private);
+    assert!(!f.is_within_stale_while_revalidate());
+
+    let f = Freshness::calculate(200, &h, at(DATE + 90), CacheKind::Private);
+    assert_eq!(f.staleness(), 30);
+    assert!(f.is_within_stale_while_revalidate());
+    assert!(f.is_within_stale_if_error());
+
+    let f = Freshness::calculate(200, &h, at(DATE + 91), CacheKind::Private);
+    assert!(!f.is_within_stale_while_revalidate());
+    assert!(f.is_within_stale_if_error());
+}
+
+#[test]
+fn test_freshness_multiple_cache_control_lines() {
+    let h = headers(&[
+        ("Date", DATE_STR),
+        ("Cache-Control", "max-age=60"),
+        ("Cache-Control", "must-revalidate"),
+    ]);
+    let f = Freshness::calculate(200, &h, at(DATE + 100), CacheKind::Private);
+    assert_eq!(f.lifetime(), 60);
+    assert!(!f.allows_stale());
+}
@@ -1,4 +1,12 @@
-//! キャッシュヘッダーのユニットテスト
+//! キャッシュのユニットテスト
+//!
+//! キャッシュヘッダー (Cache-Control / Expires / Age) のテストはここに置き、
+//! サブモジュールのテストは `src/cache/` のモジュールごとに分割する。
+//! - `freshness`: RFC 9111 の鮮度計算
+//! - `store`: インメモリ HTTP キャッシュ `HttpCache`
+
+mod freshness;
+mod store;
 
 use shiguredo_http11::cache::{Age, CacheControl, CacheError, Expires};
 
@@ -151,3 +159,89 @@ fn test_cache_control_accepts_unquoted() {
     let cc = CacheControl::parse("max-age=3600").unwrap();
     assert_eq!(cc.max_age(), Some(3600));
 }
+
+// ========================================
+// 修飾形式と拡張ディレクティブのテスト
+// ========================================
+
+#[test]
+fn test_cache_control_qualified_private_and_no_cache() {
+    let cc = CacheControl::parse("private=\"Set-Cookie, Authorization\", no-cache=\"Set-Cookie\"")
+        .unwrap();
+    assert!(cc.is_private());
+    assert_eq!(cc.private_fields(), ["Set-Cookie", "Authorization"]);
+    assert!(cc.is_no_cache());
+    assert_eq!(cc.no_cache_fields(), ["Set-Cookie"]);
+    assert_eq!(
+        cc.to_string(),
+        "no-cache=\"Set-Cookie\", private=\"Set-Cookie, Authorization\""
+    );
+
+    // token 形式の値も受理する
+    let cc = CacheControl::parse("private=Set-Cookie").unwrap();
+    assert_eq!(cc.private_fields(), ["Set-Cookie"]);
+
+    let cc = CacheControl::parse("private").unwrap();
+    assert!(cc.private_fields().is_empty());
+}
+
+#[test]
+fn test_cache_control_extensions_roundtrip() {
+    let cc = CacheControl::parse(
+        "max-age=60, Community=\"UCI\", x-flag, x-token=abc, stale-while-revalidate=30",
+    )
+    .unwrap();
+    assert_eq!(cc.max_age(), Some(60));
+    assert_eq!(cc.stale_while_revalidate(), Some(30));
+    assert_eq!(cc.extension("community"), Some(Some("UCI")));
+    assert_eq!(cc.extension("x-flag"), Some(None));
+    assert_eq!(cc.extension("x-token"), Some(Some("abc")));
+    assert_eq!(cc.extension("unknown"), None);
+    assert_eq!(cc.extensions().len(), 3);
+
+    let header = cc.to_string();
+    assert_eq!(
+        header,
+        "max-age=60, stale-while-revalidate=30, community=UCI, x-flag, x-token=abc"
+    );
+    assert_eq!(CacheControl::parse(&header).unwrap(), cc);
+}
+
+#[test]
+fn test_cache_control_extension_quoted_value() {
+    let cc = CacheControl::new().with_extension("x-note", Some("a \"b\", c"));
+    assert_eq!(cc.to_string(), "x-note=\"a \\\"b\\\", c\"");
+    let reparsed = CacheControl::parse(&cc.to_string()).unwrap();
+    assert_eq!(reparsed.extension("x-note"), Some(Some("a \"b\", c")));
+
+    // token でない名前は追加しない
+    let cc = CacheControl::new().with_extension("bad name", None);
+    assert!(cc.extensions().is_empty());
+}
+
+#[test]
+fn test_cache_control_builder_all_directives() {
+    let cc = CacheControl::new()
+        .with_max_stale(10)
+        .with_min_fresh(20)
+        .with_stale_while_revalidate(30)
+        .with_stale_if_error(40)
+        .with_only_if_cached()
+        .with_must_understand()
+        .with_no_cache_fields(["Set-Cookie"])
+        .with_private_fields(Vec::<String>::new());
+    assert_eq!(
+        cc.to_string(),
+        "max-stale=10, min-fresh=20, stale-while-revalidate=30, stale-if-error=40, \
+         no-cache=\"Set-Cookie\", only-if-cached, must-understand, private"
+    );
+    assert_eq!(CacheControl::parse(&cc.to_string()).unwrap(), cc);
+}
+
+#[test]
+fn test_cache_control_rejects_unterminated_quoted_field_list() {
+    assert_eq!(
+        CacheControl::parse("private=\"Set-Cookie, max-age=60"),
+        Err(CacheError::InvalidFormat)
+    );
+}
@@ -0,0 +1,442 @@
+//! `cache::store` のインメモリ HTTP キャッシュのユニットテスト
+
+use shiguredo_http11::cache::freshness::CacheKind;
+use shiguredo_http11::cache::{CacheDecision, HttpCache, update_stored_response};
+use shiguredo_http11::{Request, Response, ResponseDecoder, ResponseHead, StatusCode};
+
+fn get(uri: &str) -> Request {
+    Request::new("GET", uri)
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+}
+
+fn ok(cache_control: &str, body: &[u8]) -> Response {
+    Response::with_status(StatusCode::OK)
+        .header("Cache-Control", cache_control)
+        .unwrap()
+        .body(body.to_vec())
+}
+
+// ========================================
+// 保存と参照のテスト
+// ========================================
+
+#[test]
+fn test_http_cache_fresh_and_stale() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    let request = get("/a");
+    assert!(cache.store(&request, &ok("max-age=60", b"a"), 100, 100));
+    assert_eq!(cache.len(), 1);
+
+    let hit = cache.lookup(&request, 159).unwrap();
+    assert_eq!(hit.decision(), CacheDecision::Fresh);
+    assert_eq!(hit.freshness().current_age(), 59);
+    assert_eq!(hit.to_response().get_header("Age"), Some("59"));
+
+    let hit = cache.lookup(&request, 160).unwrap();
+    assert_eq!(hit.decision(), CacheDecision::MustRevalidate);
+}
+
+#[test]
+fn test_http_cache_miss() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    assert!(cache.store(&get("/a"), &ok("max-age=60", b"a"), 0, 0));
+    assert!(cache.lookup(&get("/b"), 0).is_none());
+
+    // Host が異なれば別のエントリ
+    let other_host = Request::new("GET", "/a")
+        .unwrap()
+        .header("Host", "example.org")
+        .unwrap();
+    assert!(cache.lookup(&other_host, 0).is_none());
+
+    // GET 以外は参照しない
+    let head = Request::new("HEAD", "/a")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    assert!(cache.lookup(&head, 0).is_none());
+}
+
+#[test]
+fn test_http_cache_not_storable() {
+    let mut cache = HttpCache::new(CacheKind::Shared, 1024);
+    assert!(!cache.store(&get("/"), &ok("no-store, max-age=60", b""), 0, 0));
+    assert!(!cache.store(&get("/"), &ok("private, max-age=60", b""), 0, 0));
+    assert!(!cache.store(&get("/"), &ok("max-age=abc", b""), 0, 0));
+
+    let post = Request::new("POST", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    assert!(!cache.store(&post, &ok("max-age=60", b""), 0, 0));
+
+    let no_store_request = get("/").header("Cache-Control", "no-store").unwrap();
+    assert!(!cache.store(&no_store_request, &ok("max-age=60", b""), 0, 0));
+
+    // 共有キャッシュは Authorization 付きのリクエストへのレスポンスを public 等がなければ保存しない
+    let authorized = get("/").header("Authorization", "Bearer x").unwrap();
+    assert!(!cache.store(&authorized, &ok("max-age=60", b""), 0, 0));
+    assert!(cache.store(&authorized, &ok("public, max-age=60", b""), 0, 0));
+
+    let partial = Response::with_status(StatusCode::PARTIAL_CONTENT)
+        .header("Cache-Control", "max-age=60")
+        .unwrap();
+    assert!(!cache.store(&get("/p"), &partial, 0, 0));
+
+    // 明示的な有効期間がなく、ヒューリスティックにキャッシュ可能でもない
+    let found = Response::with_status(StatusCode::FOUND);
+    assert!(!cache.store(&get("/f"), &found, 0, 0));
+}
+
+#[test]
+fn test_http_cache_private_cache_stores_private() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    assert!(cache.store(&get("/"), &ok("private", b""), 0, 0));
+}
+
+#[test]
+fn test_http_cache_replaces_entry() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    let request = get("/");
+    cache.store(&request, &ok("max-age=60", b"old"), 0, 0);
+    cache.store(&request, &ok("max-age=60", b"new"), 10, 10);
+    assert_eq!(cache.len(), 1);
+    let hit = cache.lookup(&request, 10).unwrap();
+    assert_eq!(hit.response().body_bytes(), Some(&b"new"[..]));
+}
+
+// ========================================
+// Vary のテスト
+// ========================================
+
+#[test]
+fn test_http_cache_vary_secondary_key() {
+    let mut cache = HttpCache::new(CacheKind::Private, 4096);
+    let gzip = get("/").header("Accept-Encoding", "gzip").unwrap();
+    let br = get("/").header("Accept-Encoding", "br").unwrap();
+    let none = get("/");
+
+    let vary_response = |body: &[u8]| {
+        ok("max-age=60", body)
+            .header("Vary", "Accept-Encoding")
+            .unwrap()
+    };
+    assert!(cache.store(&gzip, &vary_response(b"gzip"), 0, 0));
+    assert!(cache.store(&br, &vary_response(b"br"), 0, 0));
+    assert_eq!(cache.len(), 2);
+
+    let hit = cache.lookup(&gzip, 0).unwrap();
+    assert_eq!(hit.response().body_bytes(), Some(&b"gzip"[..]));
+    let hit = cache.lookup(&br, 0).unwrap();
+    assert_eq!(hit.response().body_bytes(), Some(&b"br"[..]));
+    assert!(cache.lookup(&none, 0).is_none());
+}
+
+/// RFC 9111 Section 4.1: 二次キーは正規化した値で比較する
+#[test]
+fn test_http_cache_vary_normalized() {
+    let mut cache = HttpCache::new(CacheKind::Private, 4096);
+    let stored = get("/").header("Accept-Encoding", "gzip, br").unwrap();
+    let response = ok("max-age=60", b"encoded")
+        .header("Vary", "Accept-Encoding")
+        .unwrap();
+    assert!(cache.store(&stored, &response, 0, 0));
+
+    let reordered = get("/").header("accept-encoding", "BR,gzip").unwrap();
+    assert!(cache.lookup(&reordered, 0).is_some());
+    let split = get("/")
+        .header("Accept-Encoding", "br")
+        .unwrap()
+        .header("Accept-Encoding", "gzip")
+        .unwrap();
+    assert!(cache.lookup(&split, 0).is_some());
+    let other = get("/").header("Accept-Encoding", "gzip").unwrap();
+    assert!(cache.lookup(&other, 0).is_none());
+}
+
+#[test]
+fn test_http_cache_vary_any_not_stored() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    let response = ok("max-age=60", b"").header("Vary", "*").unwrap();
+    assert!(!cache.store(&get("/"), &response, 0, 0));
+}
+
+// ========================================
+// リクエストの Cache-Control のテスト
+// ========================================
+
+#[test]
+fn test_http_cache_request_directives() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    cache.store(&get("/"), &ok("max-age=60", b""), 0, 0);
+
+    let no_cache = get("/").header("Cache-Control", "no-cache").unwrap();
+    assert_eq!(
+        cache.lookup(&no_cache, 10).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+
+    let pragma = get("/").header("Pragma", "no-cache").unwrap();
+    assert_eq!(
+        cache.lookup(&pragma, 10).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+
+    let max_age = get("/").header("Cache-Control", "max-age=5").unwrap();
+    assert_eq!(
+        cache.lookup(&max_age, 10).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+
+    let min_fresh = get("/").header("Cache-Control", "min-fresh=55").unwrap();
+    assert_eq!(
+        cache.lookup(&min_fresh, 10).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+
+    let max_stale = get("/").header("Cache-Control", "max-stale=30").unwrap();
+    assert_eq!(
+        cache.lookup(&max_stale, 90).unwrap().decision(),
+        CacheDecision::Stale
+    );
+    assert_eq!(
+        cache.lookup(&max_stale, 91).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+}
+
+#[test]
+fn test_http_cache_must_revalidate_ignores_max_stale() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    cache.store(&get("/"), &ok("max-age=60, must-revalidate", b""), 0, 0);
+    let max_stale = get("/").header("Cache-Control", "max-stale").unwrap();
+    assert_eq!(
+        cache.lookup(&max_stale, 100).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+}
+
+#[test]
+fn test_http_cache_stale_while_revalidate() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    cache.store(
+        &get("/"),
+        &ok("max-age=60, stale-while-revalidate=30", b""),
+        0,
+        0,
+    );
+    assert_eq!(
+        cache.lookup(&get("/"), 80).unwrap().decision(),
+        CacheDecision::Stale
+    );
+    assert_eq!(
+        cache.lookup(&get("/"), 100).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+}
+
+// ========================================
+// 再検証のテスト
+// ========================================
+
+#[test]
+fn test_http_cache_revalidation_request() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    let response = ok("no-cache", b"")
+        .header("ETag", "\"v1\"")
+        .unwrap()
+        .header("Last-Modified", "Sun, 06 Nov 1994 08:49:37 GMT")
+        .unwrap();
+    assert!(cache.store(&get("/"), &response, 0, 0));
+
+    let hit = cache.lookup(&get("/"), 0).unwrap();
+    assert_eq!(hit.decision(), CacheDecision::MustRevalidate);
+    let conditional = hit.revalidation_request(&get("/")).unwrap();
+    assert_eq!(conditional.method(), "GET");
+    assert_eq!(conditional.uri(), "/");
+    assert_eq!(conditional.get_header("If-None-Match"), Some("\"v1\""));
+    assert_eq!(
+        conditional.get_header("If-Modified-Since"),
+        Some("Sun, 06 Nov 1994 08:49:37 GMT")
+    );
+}
+
+// ========================================
+// 無効化のテスト
+// ========================================
+
+#[test]
+fn test_http_cache_invalidate() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    cache.store(&get("/a"), &ok("max-age=60", b""), 0, 0);
+    cache.store(&get("/b"), &ok("max-age=60", b""), 0, 0);
+
+    let post = Request::new("POST", "/a")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    let created = Response::with_status(StatusCode::CREATED)
+        .header("Location", "/b")
+        .unwrap();
+    assert!(cache.invalidate(&post, &created));
+    assert!(cache.is_empty());
+    assert_eq!(cache.size(), 0);
+}
+
+#[test]
+fn test_http_cache_invalidate_ignored() {
+    let mut cache = HttpCache::new(CacheKind::Private, 1024);
+    cache.store(&get("/a"), &ok("max-age=60", b""), 0, 0);
+
+    // 安全なメソッドとエラーレスポンスでは無効化しない
+    assert!(!cache.invalidate(&get("/a"), &Response::with_status(StatusCode::OK)));
+    let delete = Request::new("DELETE", "/a")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    assert!(!cache.invalidate(
+        &delete,
+        &Response::with_status(StatusCode::INTERNAL_SERVER_ERROR)
+    ));
+    assert_eq!(cache.len(), 1);
+}
+
+// ========================================
+// サイズ上限と LRU のテスト
+// ========================================
+
+#[test]
+fn test_http_cache_lru_eviction() {
+    let body = [0u8; 100];
+    let entry_size = {
+        let mut cache = HttpCache::new(CacheKind::Private, usize::MAX);
+        cache.store(&get("/a"), &ok("max-age=60", &body), 0, 0);
+        cache.size()
+    };
+    let mut cache = HttpCache::new(CacheKind::Private, entry_size * 2);
+    cache.store(&get("/a"), &ok("max-age=60", &body), 0, 0);
+    cache.store(&get("/b"), &ok("max-age=60", &body), 0, 0);
+    // /a を参照して /b を最も長く使われていないエントリにする
+    assert!(cache.lookup(&get("/a"), 0).is_some());
+    cache.store(&get("/c"), &ok("max-age=60", &body), 0, 0);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.size() <= cache.max_size());
+    assert!(cache.lookup(&get("/a"), 0).is_some());
+    assert!(cache.lookup(&get("/b"), 0).is_none());
+    assert!(cache.lookup(&get("/c"), 0).is_some());
+}
+
+#[test]
+fn test_http_cache_too_large() {
+    let mut cache = HttpCache::new(CacheKind::Private, 10);
+    assert!(!cache.store(&get("/"), &ok("max-age=60", &[0u8; 100]), 0, 0));
+    assert!(cache.is_empty());
+}
+
+// ========================================
+// 304 Not Modified による更新のテスト
+// ========================================
+
+fn not_modified(raw: &[u8]) -> ResponseHead {
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(raw).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+#[test]
+fn test_update_stored_response_merges_headers() {
+    let mut stored = Response::with_status(StatusCode::OK)
+        .header("Cache-Control", "max-age=60")
+        .unwrap()
+        .header("ETag", "\"v1\"")
+        .unwrap()
+        .header("Content-Type", "text/plain")
+        .unwrap()
+        .header("Link", "</old>; rel=preload")
+        .unwrap()
+        .body(b"hello".to_vec());
+    let head = not_modified(
+        b"HTTP/1.1 304 Not Modified\r\n\
+          Cache-Control: max-age=120\r\n\
+          ETag: \"v1\"\r\n\
+          Link: </a>; rel=preload\r\n\
+          Link: </b>; rel=preload\r\n\
+          Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+          Content-Length: 0\r\n\
+          Connection: keep-alive, X-Hop\r\n\
+          X-Hop: 1\r\n\r\n",
+    );
+    update_stored_response(&mut stored, &head).unwrap();
+
+    assert_eq!(stored.get_header("Cache-Control"), Some("max-age=120"));
+    assert_eq!(stored.get_header("Content-Type"), Some("text/plain"));
+    assert_eq!(
+        stored.get_headers("Link"),
+        vec!["</a>; rel=preload", "</b>; rel=preload"]
+    );
+    assert_eq!(
+        stored.get_header("Date"),
+        Some("Sun, 06 Nov 1994 08:49:37 GMT")
+    );
+    // 除外するフィールド
+    assert!(!stored.has_header("Content-Length"));
+    assert!(!stored.has_header("Connection"));
+    assert!(!stored.has_header("X-Hop"));
+    assert_eq!(stored.body_bytes(), Some(&b"hello"[..]));
+}
+
+#[test]
+fn test_update_stored_response_excludes_qualified_fields() {
+    let mut stored = Response::with_status(StatusCode::OK);
+    let head = not_modified(
+        b"HTTP/1.1 304 Not Modified\r\n\
+          Cache-Control: no-cache=\"Set-Cookie\"\r\n\
+          Set-Cookie: a=b\r\n\r\n",
+    );
+    update_stored_response(&mut stored, &head).unwrap();
+    assert!(!stored.has_header("Set-Cookie"));
+    assert_eq!(
+        stored.get_header("Cache-Control"),
+        Some("no-cache=\"Set-Cookie\"")
+    );
+}
+
+#[test]
+fn test_http_cache_update_from_not_modified() {
+    let mut cache = HttpCache::new(CacheKind::Private, 4096);
+    let response = ok("max-age=60", b"body").header("ETag", "\"v1\"").unwrap();
+    cache.store(&get("/"), &response, 0, 0);
+    assert_eq!(
+        cache.lookup(&get("/"), 100).unwrap().decision(),
+        CacheDecision::MustRevalidate
+    );
+
+    let head = not_modified(
+        b"HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nCache-Control: max-age=300\r\n\r\n",
+    );
+    assert!(cache.update_from_not_modified(&get("/"), &head, 100, 100));
+    let hit = cache.lookup(&get("/"), 150).unwrap();
+    assert_eq!(hit.decision(), CacheDecision::Fresh);
+    assert_eq!(hit.freshness().current_age(), 50);
+    assert_eq!(
+        hit.response().get_header("Cache-Control"),
+        Some("max-age=300")
+    );
+    assert_eq!(hit.response().body_bytes(), Some(&b"body"[..]));
+}
+
+#[test]
+fn test_http_cache_update_from_not_modified_validator_mismatch() {
+    let mut cache = HttpCache::new(CacheKind::Private, 4096);
+    let response = ok("max-age=60", b"").header("ETag", "\"v1\"").unwrap();
+    cache.store(&get("/"), &response, 0, 0);
+
+    let head = not_modified(b"HTTP/1.1 304 Not Modified\r\nETag: \"v2\"\r\n\r\n");
+    assert!(!cache.update_from_not_modified(&get("/"), &head, 100, 100));
+
+    let head = not_modified(b"HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 0\r\n\r\n");
+    assert!(!cache.update_from_not_modified(&get("/"), &head, 100, 100));
+}
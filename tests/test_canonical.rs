@@ -0,0 +1,159 @@
+//! メッセージの正規化と差分のユニットテスト
+
+use shiguredo_http11::canonical::{BodyDiff, Canonicalizer, DiffLine, HeaderOrder};
+use shiguredo_http11::{Request, Response, StatusCode};
+
+fn request(headers: &[(&str, &str)]) -> Request {
+    let mut request = Request::new("GET", "/").unwrap();
+    for (name, value) in headers {
+        request.add_header(*name, *value).unwrap();
+    }
+    request
+}
+
+// ========================================
+// 正規化
+// ========================================
+
+#[test]
+fn test_canonicalize_request() {
+    let message = Canonicalizer::new().request(&request(&[
+        ("Host", "example.com"),
+        ("X-B", "2"),
+        ("Accept", "*/*"),
+        ("x-b", "1"),
+    ]));
+    assert_eq!(message.start_line(), "GET / HTTP/1.1");
+    // 同名ヘッダー間の順序は維持する
+    assert_eq!(
+        message.headers(),
+        &[
+            ("accept".to_string(), "*/*".to_string()),
+            ("host".to_string(), "example.com".to_string()),
+            ("x-b".to_string(), "2".to_string()),
+            ("x-b".to_string(), "1".to_string()),
+        ]
+    );
+    assert!(message.body().is_empty());
+    assert_eq!(
+        message.to_string(),
+        "GET / HTTP/1.1\naccept: */*\nhost: example.com\nx-b: 2\nx-b: 1\n\n"
+    );
+}
+
+#[test]
+fn test_canonicalize_policy() {
+    let canonicalizer = Canonicalizer::new()
+        .with_header_order(HeaderOrder::Preserve)
+        .with_lowercase_names(false)
+        .with_ignored_header("date");
+    let response = Response::with_status(StatusCode::OK)
+        .header("Server", "a")
+        .unwrap()
+        .header("Date", "Sun, 06 Nov 1994 08:49:37 GMT")
+        .unwrap()
+        .header("Content-Type", "text/plain")
+        .unwrap()
+        .body(b"hello".to_vec());
+    let message = canonicalizer.response(&response);
+    assert_eq!(message.start_line(), "HTTP/1.1 200 OK");
+    assert_eq!(
+        message.headers(),
+        &[
+            ("Server".to_string(), "a".to_string()),
+            ("Content-Type".to_string(), "text/plain".to_string()),
+        ]
+    );
+    assert_eq!(message.body(), b"hello");
+}
+
+#[test]
+fn test_canonicalize_body_absent_equals_empty() {
+    let with_body = Request::new("POST", "/")
+        .unwrap()
+        .header("Host", "a")
+        .unwrap()
+        .body(Vec::new());
+    let without_body = Request::new("POST", "/")
+        .unwrap()
+        .header("Host", "a")
+        .unwrap();
+    assert!(
+        Canonicalizer::new()
+            .diff_requests(&with_body, &without_body)
+            .is_none()
+    );
+}
+
+// ========================================
+// 差分
+// ========================================
+
+#[test]
+fn test_diff_identical() {
+    let a = request(&[("Host", "a"), ("Accept", "*/*")]);
+    let b = request(&[("accept", "*/*"), ("host", "a")]);
+    assert!(Canonicalizer::new().diff_requests(&a, &b).is_none());
+    // ソートしない場合は順序の違いを差分とする
+    let diff = Canonicalizer::new()
+        .with_header_order(HeaderOrder::Preserve)
+        .diff_requests(&a, &b)
+        .unwrap();
+    assert_eq!(
+        diff.lines(),
+        &[
+            DiffLine::Same("GET / HTTP/1.1".to_string()),
+            DiffLine::Removed("host: a".to_string()),
+            DiffLine::Same("accept: */*".to_string()),
+            DiffLine::Added("host: a".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_diff_headers() {
+    let a = request(&[("Host", "a"), ("Via", "1.1 x"), ("X-Id", "1")]);
+    let b = request(&[("Host", "a"), ("X-Id", "2"), ("Forwarded", "for=1.2.3.4")]);
+    let diff = Canonicalizer::new().diff_requests(&a, &b).unwrap();
+    assert_eq!(diff.body(), None);
+    assert_eq!(
+        diff.to_string(),
+        "--- left\n\
+         +++ right\n \
+         GET / HTTP/1.1\n\
+         +forwarded: for=1.2.3.4\n \
+         host: a\n\
+         -via: 1.1 x\n\
+         -x-id: 1\n\
+         +x-id: 2\n"
+    );
+}
+
+#[test]
+fn test_diff_start_line_and_body() {
+    let a = Response::with_status(StatusCode::OK).body(b"hello".to_vec());
+    let b = Response::with_status(StatusCode::NOT_FOUND).body(b"help!!".to_vec());
+    let diff = Canonicalizer::new().diff_responses(&a, &b).unwrap();
+    assert_eq!(
+        diff.body(),
+        Some(&BodyDiff {
+            left_len: 5,
+            right_len: 6,
+            offset: 3,
+        })
+    );
+    assert_eq!(
+        diff.to_string(),
+        "--- left\n\
+         +++ right\n\
+         -HTTP/1.1 200 OK\n\
+         +HTTP/1.1 404 Not Found\n\
+         body differs: length 5 != 6, first difference at byte 3\n"
+    );
+
+    // 一方が他方の先頭部分の場合は短い方の長さ
+    let a = Response::with_status(StatusCode::OK).body(b"abc".to_vec());
+    let b = Response::with_status(StatusCode::OK).body(b"abcdef".to_vec());
+    let diff = Canonicalizer::new().diff_responses(&a, &b).unwrap();
+    assert_eq!(diff.body().unwrap().offset, 3);
+}
@@ -0,0 +1,176 @@
+//! charset を考慮したボディのテキストデコードのユニットテスト
+
+use shiguredo_http11::charset::{Charset, CharsetError, decode_text, decode_text_lossy};
+use shiguredo_http11::content_type::ContentType;
+use shiguredo_http11::{Request, Response, StatusCode};
+
+// ========================================
+// エラー表示のテスト
+// ========================================
+
+#[test]
+fn test_charset_error_display() {
+    let err = CharsetError::UnsupportedCharset {
+        charset: "Shift_JIS".to_string(),
+    };
+    assert_eq!(err.to_string(), "unsupported charset: \"Shift_JIS\"");
+    let err = CharsetError::InvalidEncoding {
+        charset: Charset::UsAscii,
+    };
+    assert_eq!(err.to_string(), "invalid US-ASCII byte sequence");
+}
+
+// ========================================
+// Charset のテスト
+// ========================================
+
+#[test]
+fn test_charset_from_label() {
+    let cases = [
+        ("utf-8", Some(Charset::Utf8)),
+        ("UTF8", Some(Charset::Utf8)),
+        (" Utf-8 ", Some(Charset::Utf8)),
+        ("us-ascii", Some(Charset::UsAscii)),
+        ("ANSI_X3.4-1968", Some(Charset::UsAscii)),
+        ("ISO-8859-1", Some(Charset::Iso8859_1)),
+        ("latin1", Some(Charset::Iso8859_1)),
+        ("windows-1252", None),
+        ("shift_jis", None),
+        ("", None),
+    ];
+    for (label, expected) in cases {
+        assert_eq!(Charset::from_label(label), expected, "{}", label);
+    }
+}
+
+#[test]
+fn test_charset_name_display() {
+    assert_eq!(Charset::Utf8.name(), "UTF-8");
+    assert_eq!(Charset::UsAscii.to_string(), "US-ASCII");
+    assert_eq!(Charset::Iso8859_1.to_string(), "ISO-8859-1");
+    for charset in [Charset::Utf8, Charset::UsAscii, Charset::Iso8859_1] {
+        assert_eq!(Charset::from_label(charset.name()), Some(charset));
+    }
+}
+
+#[test]
+fn test_charset_from_content_type() {
+    let ct = ContentType::parse("text/html").unwrap();
+    assert_eq!(Charset::from_content_type(&ct), Ok(Charset::Utf8));
+    let ct = ContentType::parse("text/html; Charset=\"us-ascii\"").unwrap();
+    assert_eq!(Charset::from_content_type(&ct), Ok(Charset::UsAscii));
+    let ct = ContentType::parse("text/html; charset=EUC-JP").unwrap();
+    assert_eq!(
+        Charset::from_content_type(&ct),
+        Err(CharsetError::UnsupportedCharset {
+            charset: "EUC-JP".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_charset_decode_utf8() {
+    assert_eq!(Charset::Utf8.decode("日本語".as_bytes()).unwrap(), "日本語");
+    // BOM は取り除く
+    assert_eq!(Charset::Utf8.decode(b"\xEF\xBB\xBFabc").unwrap(), "abc");
+    assert_eq!(
+        Charset::Utf8.decode(b"a\xffb"),
+        Err(CharsetError::InvalidEncoding {
+            charset: Charset::Utf8
+        })
+    );
+    assert_eq!(Charset::Utf8.decode_lossy(b"a\xffb"), "a\u{FFFD}b");
+}
+
+#[test]
+fn test_charset_decode_us_ascii() {
+    assert_eq!(Charset::UsAscii.decode(b"hello").unwrap(), "hello");
+    assert_eq!(
+        Charset::UsAscii.decode(b"caf\xe9"),
+        Err(CharsetError::InvalidEncoding {
+            charset: Charset::UsAscii
+        })
+    );
+    assert_eq!(Charset::UsAscii.decode_lossy(b"caf\xe9"), "caf\u{FFFD}");
+}
+
+#[test]
+fn test_charset_decode_iso_8859_1() {
+    assert_eq!(Charset::Iso8859_1.decode(b"caf\xe9").unwrap(), "café");
+    // BOM として扱わない
+    assert_eq!(
+        Charset::Iso8859_1.decode(b"\xEF\xBB\xBF").unwrap(),
+        "\u{EF}\u{BB}\u{BF}"
+    );
+    assert_eq!(Charset::Iso8859_1.decode_lossy(b"\xff"), "\u{FF}");
+}
+
+// ========================================
+// decode_text / decode_text_lossy のテスト
+// ========================================
+
+#[test]
+fn test_decode_text() {
+    assert_eq!(decode_text(None, b"abc").unwrap(), "abc");
+    let ct = ContentType::parse("text/plain; charset=latin1").unwrap();
+    assert_eq!(decode_text(Some(&ct), b"\xe9").unwrap(), "é");
+    let ct = ContentType::parse("text/plain; charset=koi8-r").unwrap();
+    assert!(matches!(
+        decode_text(Some(&ct), b"abc"),
+        Err(CharsetError::UnsupportedCharset { .. })
+    ));
+}
+
+#[test]
+fn test_decode_text_lossy() {
+    // サポートしていない charset は UTF-8 として扱う
+    let ct = ContentType::parse("text/plain; charset=koi8-r").unwrap();
+    assert_eq!(decode_text_lossy(Some(&ct), b"ab\xffc"), "ab\u{FFFD}c");
+    let ct = ContentType::parse("text/plain; charset=us-ascii").unwrap();
+    assert_eq!(
+        decode_text_lossy(Some(&ct), "é".as_bytes()),
+        "\u{FFFD}\u{FFFD}"
+    );
+    assert_eq!(decode_text_lossy(None, b""), "");
+}
+
+// ========================================
+// Request / Response のテスト
+// ========================================
+
+#[test]
+fn test_response_body_text() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Content-Type", "text/html; charset=ISO-8859-1")
+        .unwrap()
+        .body(b"caf\xe9".to_vec());
+    assert_eq!(response.body_text().unwrap(), "café");
+    assert_eq!(response.body_text_lossy(), "café");
+
+    // Content-Type がない場合は UTF-8
+    let response = Response::with_status(StatusCode::OK).body(b"caf\xe9".to_vec());
+    assert!(response.body_text().is_err());
+    assert_eq!(response.body_text_lossy(), "caf\u{FFFD}");
+
+    // ボディがない場合は空文字列
+    let response = Response::with_status(StatusCode::NO_CONTENT);
+    assert_eq!(response.body_text().unwrap(), "");
+}
+
+#[test]
+fn test_request_body_text() {
+    let request = Request::new("POST", "/")
+        .unwrap()
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .unwrap()
+        .body("こんにちは".as_bytes().to_vec());
+    assert_eq!(request.body_text().unwrap(), "こんにちは");
+
+    // パースできない Content-Type は UTF-8 として扱う
+    let request = Request::new("POST", "/")
+        .unwrap()
+        .header("Content-Type", "invalid")
+        .unwrap()
+        .body(b"abc".to_vec());
+    assert_eq!(request.body_text().unwrap(), "abc");
+}
@@ -0,0 +1,104 @@
+//! 組み込みの gzip / deflate 実装のテストで共通利用するヘルパー
+
+use shiguredo_http11::compression::{CompressionError, Compressor, Decompressor};
+
+pub(super) fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// 入力を `input_chunk` バイトずつ、出力を `output_chunk` バイトずつ処理して圧縮する
+pub(super) fn compress_with<C: Compressor>(
+    compressor: &mut C,
+    data: &[u8],
+    input_chunk: usize,
+    output_chunk: usize,
+) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    let mut output = vec![0u8; output_chunk];
+    for chunk in data.chunks(input_chunk) {
+        let mut input = chunk;
+        loop {
+            let status = compressor.compress(input, &mut output).unwrap();
+            compressed.extend_from_slice(&output[..status.produced()]);
+            input = &input[status.consumed()..];
+            if input.is_empty() && !status.is_output_full() {
+                break;
+            }
+        }
+    }
+    loop {
+        let status = compressor.finish(&mut output).unwrap();
+        compressed.extend_from_slice(&output[..status.produced()]);
+        if status.is_complete() {
+            break;
+        }
+    }
+    compressed
+}
+
+pub(super) fn compress<C: Compressor>(compressor: &mut C, data: &[u8]) -> Vec<u8> {
+    compress_with(compressor, data, data.len().max(1), 8192)
+}
+
+/// 入力を `input_chunk` バイトずつ、出力を `output_chunk` バイトずつ処理して展開する
+pub(super) fn decompress_with<D: Decompressor>(
+    decompressor: &mut D,
+    data: &[u8],
+    input_chunk: usize,
+    output_chunk: usize,
+) -> Result<Vec<u8>, CompressionError> {
+    let mut decompressed = Vec::new();
+    let mut output = vec![0u8; output_chunk];
+    let mut input = data;
+    loop {
+        let chunk = &input[..input.len().min(input_chunk)];
+        let status = decompressor.decompress(chunk, &mut output)?;
+        decompressed.extend_from_slice(&output[..status.produced()]);
+        input = &input[status.consumed()..];
+        if status.is_complete() {
+            return Ok(decompressed);
+        }
+        // 空の入力 (入力の終端) で完了しなければ途切れている
+        if chunk.is_empty() && status.produced() == 0 && !status.is_output_full() {
+            return Err(CompressionError::UnexpectedEof);
+        }
+    }
+}
+
+pub(super) fn decompress<D: Decompressor>(
+    decompressor: &mut D,
+    data: &[u8],
+) -> Result<Vec<u8>, CompressionError> {
+    decompress_with(decompressor, data, data.len().max(1), 8192)
+}
+
+/// 圧縮しやすい部分としにくい部分を含むテストデータ
+pub(super) fn sample_data(len: usize) -> Vec<u8> {
+    const TEXT: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n";
+    let mut state = 0x1234_5678u32;
+    (0..len)
+        .map(|i| {
+            if (i / 1000) % 2 == 0 {
+                TEXT[i % TEXT.len()]
+            } else {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            }
+        })
+        .collect()
+}
+
+pub(super) fn assert_invalid<D: Decompressor>(mut decompressor: D, data: &[u8]) {
+    assert!(
+        matches!(
+            decompress(&mut decompressor, data),
+            Err(CompressionError::InvalidData(_))
+        ),
+        "{data:02x?}"
+    );
+}
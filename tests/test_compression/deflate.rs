@@ -0,0 +1,130 @@
+//! `compression::deflate` の deflate (zlib / raw DEFLATE) 実装のユニットテスト
+
+use shiguredo_http11::compression::{Compressor, DeflateCompressor, DeflateDecompressor};
+
+use super::codec::{assert_invalid, compress, decompress, decompress_with, hex, sample_data};
+
+// ========================================
+// 既知のデータの展開
+// ========================================
+
+#[test]
+fn decompress_zlib_fixed_huffman() {
+    let data = b"The quick brown fox jumps over the lazy dog. ".repeat(20);
+    let compressed = hex(
+        "78da0bc94855282ccd4cce56482aca2fcf5348cbaf50c82acd2d2856c82f4b2d5228014ae72456552aa4e4a7eb29848c2a1e553caa98da8a0147a5431c",
+    );
+    let output = decompress(&mut DeflateDecompressor::new(), &compressed).unwrap();
+    assert_eq!(output, data);
+}
+
+#[test]
+fn decompress_zlib_dynamic_huffman() {
+    let data = b"abcccaaaacaabacaaaadcaabccabaabcabadaaaabbadabaababacaabaaabacaadaacdbdbaabbcaabadbbbdabcdbaaabdacba";
+    let compressed = hex(
+        "78da2d8ac111c0300cc26645b0ff0c05a77e604e48d856af81ae65b590bd6606d91f7916faf58e0ed9c2f100359d5322a30f89bc263a",
+    );
+    let output = decompress(&mut DeflateDecompressor::new(), &compressed).unwrap();
+    assert_eq!(output, data);
+}
+
+#[test]
+fn decompress_raw_deflate() {
+    // zlib ヘッダーのない raw DEFLATE も deflate として受け付ける
+    let data = b"The quick brown fox jumps over the lazy dog. ".repeat(20);
+    let compressed = hex(
+        "0bc94855282ccd4cce56482aca2fcf5348cbaf50c82acd2d2856c82f4b2d5228014ae72456552aa4e4a7eb29848c2a1e553caa98da8a01",
+    );
+    let output = decompress(&mut DeflateDecompressor::new(), &compressed).unwrap();
+    assert_eq!(output, data);
+}
+
+#[test]
+fn decompress_empty_stream() {
+    assert_eq!(
+        decompress(&mut DeflateDecompressor::new(), &hex("789c030000000001")).unwrap(),
+        b""
+    );
+}
+
+#[test]
+fn decompress_byte_by_byte() {
+    let data = b"abcccaaaacaabacaaaadcaabccabaabcabadaaaabbadabaababacaabaaabacaadaacdbdbaabbcaabadbbbdabcdbaaabdacba";
+    let compressed = hex(
+        "78da2d8ac111c0300cc26645b0ff0c05a77e604e48d856af81ae65b590bd6606d91f7916faf58e0ed9c2f100359d5322a30f89bc263a",
+    );
+    let output = decompress_with(&mut DeflateDecompressor::new(), &compressed, 1, 1).unwrap();
+    assert_eq!(output, data);
+}
+
+// ========================================
+// 往復
+// ========================================
+
+#[test]
+fn roundtrip_deflate() {
+    for len in [0, 1, 258, 259, 65_536, 100_000] {
+        let data = sample_data(len);
+        let compressed = compress(&mut DeflateCompressor::new(), &data);
+        assert_eq!(&compressed[..2], &[0x78, 0x01]);
+        let output = decompress(&mut DeflateDecompressor::new(), &compressed).unwrap();
+        assert_eq!(output, data, "len = {len}");
+    }
+}
+
+#[test]
+fn compress_incompressible_data_uses_stored_blocks() {
+    let data: Vec<u8> = sample_data(2000)[1000..2000].to_vec();
+    let compressed = compress(&mut DeflateCompressor::new(), &data);
+    // zlib ヘッダー 2 + ブロックヘッダー 5 + トレイラー 4
+    assert!(compressed.len() <= data.len() + 11, "{}", compressed.len());
+}
+
+// ========================================
+// 状態遷移
+// ========================================
+
+#[test]
+fn compressor_reset() {
+    let mut compressor = DeflateCompressor::new();
+    let first = compress(&mut compressor, b"hello");
+    compressor.reset();
+    let second = compress(&mut compressor, b"hello");
+    assert_eq!(first, second);
+}
+
+// ========================================
+// 不正なデータ
+// ========================================
+
+#[test]
+fn invalid_zlib_adler32() {
+    let mut compressed = compress(&mut DeflateCompressor::new(), b"hello world");
+    let len = compressed.len();
+    compressed[len - 1] ^= 1;
+    assert_invalid(DeflateDecompressor::new(), &compressed);
+}
+
+#[test]
+fn invalid_zlib_preset_dictionary() {
+    // FDICT フラグ付き
+    assert_invalid(DeflateDecompressor::new(), &hex("78bb00000000"));
+}
+
+#[test]
+fn invalid_block_type() {
+    // BFINAL=1, BTYPE=11
+    assert_invalid(DeflateDecompressor::new(), &hex("7801070000000001"));
+}
+
+#[test]
+fn invalid_stored_block_length() {
+    // LEN と NLEN が一致しない
+    assert_invalid(DeflateDecompressor::new(), &hex("7801010500fbff"));
+}
+
+#[test]
+fn invalid_distance_too_far_back() {
+    // 固定ハフマン: 最初のシンボルで距離 1 の一致
+    assert_invalid(DeflateDecompressor::new(), &hex("780103020000"));
+}
@@ -0,0 +1,242 @@
+//! `compression::gzip` の gzip 実装のユニットテスト
+
+use shiguredo_http11::compression::{
+    CompressionError, CompressionStatus, Compressor, Decompressor, GzipCompressor, GzipDecompressor,
+};
+
+use super::codec::{
+    assert_invalid, compress, compress_with, decompress, decompress_with, hex, sample_data,
+};
+
+// ========================================
+// 既知のデータの展開
+// ========================================
+
+#[test]
+fn decompress_gzip_with_filename() {
+    // FNAME フラグ付き ("fox.txt")
+    let compressed =
+        hex("1f8b08080000000002ff666f782e74787400cb48cdc9c95728cf2fca49e102002d3b08af0c000000");
+    let output = decompress(&mut GzipDecompressor::new(), &compressed).unwrap();
+    assert_eq!(output, b"hello world\n");
+}
+
+#[test]
+fn decompress_empty_stream() {
+    assert_eq!(
+        decompress(
+            &mut GzipDecompressor::new(),
+            &hex("1f8b080000000000020303000000000000000000")
+        )
+        .unwrap(),
+        b""
+    );
+}
+
+#[test]
+fn decompress_does_not_consume_trailing_data() {
+    let mut compressed = compress(&mut GzipCompressor::new(), b"hello");
+    let len = compressed.len();
+    compressed.extend_from_slice(b"extra");
+
+    let mut decompressor = GzipDecompressor::new();
+    let mut output = [0u8; 64];
+    let status = decompressor.decompress(&compressed, &mut output).unwrap();
+    assert!(status.is_complete());
+    assert_eq!(status.consumed(), len);
+    assert_eq!(&output[..status.produced()], b"hello");
+
+    // 完了後の呼び出しは何も消費しない
+    let status = decompressor.decompress(b"more", &mut output).unwrap();
+    assert!(status.is_complete());
+    assert_eq!(status.consumed(), 0);
+    assert_eq!(status.produced(), 0);
+}
+
+#[test]
+fn decompress_multiple_members() {
+    // RFC 1952 Section 2.2: 連結したメンバーは 1 つのストリームとして展開する
+    let mut compressed = compress(&mut GzipCompressor::new(), b"hello ");
+    compressed.extend_from_slice(&compress(&mut GzipCompressor::new(), b"world"));
+    assert_eq!(
+        decompress(&mut GzipDecompressor::new(), &compressed).unwrap(),
+        b"hello world"
+    );
+    // メンバーの境界をまたいで分割しても同じ結果になる
+    for input_chunk in [1, 2, 7] {
+        assert_eq!(
+            decompress_with(&mut GzipDecompressor::new(), &compressed, input_chunk, 3).unwrap(),
+            b"hello world"
+        );
+    }
+}
+
+#[test]
+fn decompress_member_boundary_waits_for_end_of_input() {
+    let first = compress(&mut GzipCompressor::new(), b"hello ");
+    let second = compress(&mut GzipCompressor::new(), b"world");
+
+    let mut decompressor = GzipDecompressor::new();
+    let mut output = [0u8; 64];
+    // メンバーの境界で入力が途切れた場合は次のメンバーを待つ
+    let status = decompressor.decompress(&first, &mut output).unwrap();
+    assert!(matches!(status, CompressionStatus::Continue { .. }));
+    assert_eq!(status.consumed(), first.len());
+    assert_eq!(&output[..status.produced()], b"hello ");
+
+    let status = decompressor.decompress(&second, &mut output).unwrap();
+    assert!(matches!(status, CompressionStatus::Continue { .. }));
+    assert_eq!(&output[..status.produced()], b"world");
+
+    // 空の入力で入力の終端を通知すると完了する
+    let status = decompressor.decompress(&[], &mut output).unwrap();
+    assert!(status.is_complete());
+    assert_eq!(status.produced(), 0);
+}
+
+#[test]
+fn decompress_invalid_second_member() {
+    let mut compressed = compress(&mut GzipCompressor::new(), b"hello");
+    let mut second = compress(&mut GzipCompressor::new(), b"world");
+    let len = second.len();
+    second[len - 8] ^= 1;
+    compressed.extend_from_slice(&second);
+    assert_invalid(GzipDecompressor::new(), &compressed);
+
+    // 2 つ目のメンバーの途中で途切れた場合
+    let mut truncated = compress(&mut GzipCompressor::new(), b"hello");
+    truncated.extend_from_slice(&[0x1F]);
+    assert_eq!(
+        decompress(&mut GzipDecompressor::new(), &truncated),
+        Err(CompressionError::UnexpectedEof)
+    );
+}
+
+#[test]
+fn decompress_empty_input_is_not_error() {
+    let mut decompressor = GzipDecompressor::new();
+    let mut output = [0u8; 16];
+    let status = decompressor.decompress(&[], &mut output).unwrap();
+    assert!(matches!(status, CompressionStatus::Continue { .. }));
+    assert_eq!(status.consumed(), 0);
+    assert_eq!(status.produced(), 0);
+}
+
+// ========================================
+// 往復
+// ========================================
+
+#[test]
+fn roundtrip_gzip() {
+    for len in [0, 1, 2, 3, 100, 32 * 1024, 32 * 1024 + 1, 100_000] {
+        let data = sample_data(len);
+        let compressed = compress(&mut GzipCompressor::new(), &data);
+        assert_eq!(&compressed[..3], &[0x1F, 0x8B, 0x08]);
+        let output = decompress(&mut GzipDecompressor::new(), &compressed).unwrap();
+        assert_eq!(output, data, "len = {len}");
+    }
+}
+
+#[test]
+fn roundtrip_small_buffers() {
+    let data = sample_data(5000);
+    let compressed = compress_with(&mut GzipCompressor::new(), &data, 7, 3);
+    assert_eq!(compressed, compress(&mut GzipCompressor::new(), &data));
+    let output = decompress_with(&mut GzipDecompressor::new(), &compressed, 5, 2).unwrap();
+    assert_eq!(output, data);
+}
+
+#[test]
+fn compress_reduces_repetitive_data() {
+    let data = b"a".repeat(10_000);
+    let compressed = compress(&mut GzipCompressor::new(), &data);
+    assert!(compressed.len() < 200, "{}", compressed.len());
+}
+
+// ========================================
+// 状態遷移
+// ========================================
+
+#[test]
+fn compressor_already_finished() {
+    let mut compressor = GzipCompressor::new();
+    let mut output = [0u8; 64];
+    assert!(compressor.finish(&mut output).unwrap().is_complete());
+    assert_eq!(
+        compressor.finish(&mut output),
+        Err(CompressionError::AlreadyFinished)
+    );
+    assert_eq!(
+        compressor.compress(b"a", &mut output),
+        Err(CompressionError::AlreadyFinished)
+    );
+}
+
+#[test]
+fn decompressor_reset() {
+    let compressed = compress(&mut GzipCompressor::new(), b"hello");
+    let mut decompressor = GzipDecompressor::new();
+    assert_eq!(
+        decompress(&mut decompressor, &compressed).unwrap(),
+        b"hello"
+    );
+    decompressor.reset();
+    assert_eq!(
+        decompress(&mut decompressor, &compressed).unwrap(),
+        b"hello"
+    );
+}
+
+// ========================================
+// 不正なデータ
+// ========================================
+
+#[test]
+fn invalid_gzip_header() {
+    assert_invalid(GzipDecompressor::new(), &hex("1f8c0800000000000003"));
+    // 圧縮方式が deflate 以外
+    assert_invalid(GzipDecompressor::new(), &hex("1f8b0700000000000003"));
+    // 予約フラグ
+    assert_invalid(GzipDecompressor::new(), &hex("1f8b0820000000000003"));
+}
+
+#[test]
+fn invalid_gzip_trailer() {
+    let compressed = compress(&mut GzipCompressor::new(), b"hello world");
+    let len = compressed.len();
+
+    let mut crc = compressed.clone();
+    crc[len - 8] ^= 1;
+    assert_invalid(GzipDecompressor::new(), &crc);
+
+    let mut isize = compressed;
+    isize[len - 4] ^= 1;
+    assert_invalid(GzipDecompressor::new(), &isize);
+}
+
+#[test]
+fn corrupted_deflate_data() {
+    // 圧縮データ部分の 1 ビットの反転は展開エラーか CRC-32 の不一致で検出する
+    let data = [
+        144, 144, 0, 144, 144, 1, 0, 144, 145, 0, 144, 0, 0, 0, 144, 146, 0, 144, 1, 1, 0, 145, 0,
+        145, 144, 144, 2, 0, 0, 145, 145, 0, 0, 146, 0, 0, 1, 144, 144, 3, 0, 0, 147, 0, 144, 147,
+        0, 0, 2, 0, 144,
+    ];
+    let mut compressed = compress(&mut GzipCompressor::new(), &data);
+    // トレイラー (8 バイト) の直前の圧縮データ
+    let index = compressed.len() - 11;
+    compressed[index] ^= 1 << 1;
+    assert_invalid(GzipDecompressor::new(), &compressed);
+}
+
+#[test]
+fn truncated_stream() {
+    let compressed = compress(&mut GzipCompressor::new(), &sample_data(3000));
+    for len in [0, 5, 10, compressed.len() / 2, compressed.len() - 1] {
+        assert_eq!(
+            decompress(&mut GzipDecompressor::new(), &compressed[..len]),
+            Err(CompressionError::UnexpectedEof),
+            "len = {len}"
+        );
+    }
+}
@@ -1,4 +1,17 @@
 //! 圧縮/展開トレイトのユニットテスト
+//!
+//! トレイトと `NoCompression` のテストはここに置き、`compression-impl` feature の
+//! 組み込み実装のテストは `src/compression/` のモジュールごとに分割する。
+//! - `deflate`: deflate (zlib / raw DEFLATE) の圧縮・展開
+//! - `gzip`: gzip の圧縮・展開
+//! - `codec`: `deflate` / `gzip` で共通利用するヘルパー
+
+#[cfg(feature = "compression-impl")]
+mod codec;
+#[cfg(feature = "compression-impl")]
+mod deflate;
+#[cfg(feature = "compression-impl")]
+mod gzip;
 
 use shiguredo_http11::compression::{
     CompressionError, CompressionStatus, Compressor, Decompressor, NoCompression,
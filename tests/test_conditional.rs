@@ -3,6 +3,7 @@
 use shiguredo_http11::conditional::{
     ConditionalError, IfMatch, IfModifiedSince, IfNoneMatch, IfRange, IfUnmodifiedSince,
 };
+use shiguredo_http11::date::HttpDate;
 use shiguredo_http11::etag::EntityTag;
 
 // ========================================
@@ -106,3 +107,45 @@ fn test_if_range_parse_errors() {
         Err(ConditionalError::DateError)
     ));
 }
+
+// ========================================
+// IfRange::matches のテスト
+// ========================================
+
+#[test]
+fn test_if_range_matches_etag_strong_comparison() {
+    let strong = EntityTag::strong("v1").unwrap();
+    let weak = EntityTag::weak("v1").unwrap();
+
+    let if_range = IfRange::parse("\"v1\"", 2026).unwrap();
+    assert!(if_range.matches(Some(&strong), None));
+    assert!(!if_range.matches(Some(&weak), None));
+    assert!(!if_range.matches(None, None));
+
+    let if_range = IfRange::parse("W/\"v1\"", 2026).unwrap();
+    assert!(!if_range.matches(Some(&strong), None));
+    assert!(!if_range.matches(Some(&weak), None));
+}
+
+#[test]
+fn test_if_range_matches_date_exact() {
+    let modified = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    let etag = EntityTag::strong("v1").unwrap();
+
+    let if_range = IfRange::parse("Sun, 06 Nov 1994 08:49:37 GMT", 2026).unwrap();
+    assert!(if_range.matches(Some(&etag), Some(&modified)));
+    assert!(!if_range.matches(Some(&etag), None));
+
+    // 前後の日時は一致しない
+    for value in [
+        "Sun, 06 Nov 1994 08:49:36 GMT",
+        "Sun, 06 Nov 1994 08:49:38 GMT",
+    ] {
+        let if_range = IfRange::parse(value, 2026).unwrap();
+        assert!(!if_range.matches(Some(&etag), Some(&modified)), "{}", value);
+    }
+
+    // asctime 形式でも同じ日時なら一致する
+    let if_range = IfRange::parse("Sun Nov  6 08:49:37 1994", 2026).unwrap();
+    assert!(if_range.matches(None, Some(&modified)));
+}
@@ -0,0 +1,239 @@
+//! 適合性テスト用ハーネスのユニットテスト
+
+use std::borrow::Cow;
+
+use shiguredo_http11::conformance::{Harness, Outcome, normalize};
+use shiguredo_http11::{BodyKind, DecoderLimits, DecoderProfile, Error, RequestHead, ResponseHead};
+
+fn request_head(method: &str, uri: &str, headers: &[(&str, &str)]) -> RequestHead {
+    let mut head = RequestHead::new(method, uri).unwrap();
+    for (name, value) in headers {
+        head.add_header(name, value).unwrap();
+    }
+    head
+}
+
+fn response_head(status: u16, reason: &str, headers: &[(&str, &str)]) -> ResponseHead {
+    let mut head = ResponseHead::new(status, reason).unwrap();
+    for (name, value) in headers {
+        head.add_header(name, value).unwrap();
+    }
+    head
+}
+
+fn complete() -> Outcome {
+    Outcome::Complete {
+        trailers: Vec::new(),
+    }
+}
+
+// ========================================
+// リクエスト
+// ========================================
+
+#[test]
+fn test_request_pipelined() {
+    let input = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n\
+                  POST /b HTTP/1.1\r\nHost: a\r\nContent-Length: 3\r\n\r\nabc";
+    let expected = [
+        Outcome::RequestHead {
+            head: request_head("GET", "/", &[("Host", "a")]),
+            body_kind: BodyKind::None,
+        },
+        complete(),
+        Outcome::RequestHead {
+            head: request_head("POST", "/b", &[("Host", "a"), ("Content-Length", "3")]),
+            body_kind: BodyKind::ContentLength(3),
+        },
+        Outcome::Body(b"abc".to_vec()),
+        complete(),
+    ];
+    Harness::request()
+        .check_all_splits(input, &expected)
+        .unwrap();
+}
+
+#[test]
+fn test_request_chunked_with_trailers() {
+    let input =
+        b"POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nTrailer: X-Sum\r\n\r\n\
+                  3\r\nabc\r\n2;x=1\r\nde\r\n0\r\nX-Sum: 5\r\n\r\n";
+    let outcomes = Harness::request().replay_all_splits(input).unwrap();
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[1], Outcome::Body(b"abcde".to_vec()));
+    assert_eq!(
+        outcomes[2],
+        Outcome::Complete {
+            trailers: vec![(Cow::Borrowed("X-Sum"), "5".to_string())],
+        }
+    );
+}
+
+#[test]
+fn test_request_incomplete() {
+    let input = b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 10\r\n\r\nabc";
+    let outcomes = Harness::request().replay_all_splits(input).unwrap();
+    // 完了していないメッセージは Complete を含まない
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[1], Outcome::Body(b"abc".to_vec()));
+}
+
+#[test]
+fn test_request_error_stops() {
+    let input = b"GET / HTTP/1.1\r\nHost: a\r\n\r\nGET / HTTP/1.1\r\nBad Header\r\n\r\nGET / HTTP/1.1\r\n\r\n";
+    let outcomes = Harness::request().replay_all_splits(input).unwrap();
+    assert_eq!(outcomes.len(), 3);
+    assert!(matches!(outcomes[2], Outcome::Error(_)));
+}
+
+#[test]
+fn test_request_limits() {
+    let limits = DecoderLimits {
+        max_body_size: 2,
+        ..Default::default()
+    };
+    let input = b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 3\r\n\r\nabc";
+    let outcomes = Harness::request()
+        .with_limits(limits)
+        .replay_all_splits(input)
+        .unwrap();
+    assert_eq!(
+        outcomes.last(),
+        Some(&Outcome::Error(Error::BodyTooLarge { size: 3, limit: 2 }))
+    );
+}
+
+#[test]
+fn test_request_profile() {
+    let input = b"GET / RTSP/1.0\r\nHost: a\r\n\r\n";
+    let outcomes = Harness::request()
+        .with_profile(DecoderProfile::internal())
+        .replay_all_splits(input)
+        .unwrap();
+    assert_eq!(outcomes.len(), 2);
+
+    let outcomes = Harness::request()
+        .with_profile(DecoderProfile::strict())
+        .replay_all_splits(input)
+        .unwrap();
+    assert!(matches!(outcomes[..], [Outcome::Error(_)]));
+}
+
+// ========================================
+// レスポンス
+// ========================================
+
+#[test]
+fn test_response_interim_and_final() {
+    let input = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+    let expected = [
+        Outcome::ResponseHead {
+            head: response_head(100, "Continue", &[]),
+            body_kind: BodyKind::None,
+        },
+        complete(),
+        Outcome::ResponseHead {
+            head: response_head(200, "OK", &[("Content-Length", "2")]),
+            body_kind: BodyKind::ContentLength(2),
+        },
+        Outcome::Body(b"ok".to_vec()),
+        complete(),
+    ];
+    Harness::response()
+        .check_all_splits(input, &expected)
+        .unwrap();
+}
+
+#[test]
+fn test_response_close_delimited() {
+    let input = b"HTTP/1.1 200 OK\r\n\r\nhello";
+    let outcomes = Harness::response().replay_all_splits(input).unwrap();
+    assert_eq!(outcomes.len(), 2);
+
+    // 接続終了を通知すると完了する
+    let outcomes = Harness::response()
+        .with_eof(true)
+        .replay_all_splits(input)
+        .unwrap();
+    assert_eq!(outcomes[1], Outcome::Body(b"hello".to_vec()));
+    assert_eq!(outcomes[2], complete());
+}
+
+#[test]
+fn test_response_request_method() {
+    let input = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n";
+    let outcomes = Harness::response()
+        .with_request_method("HEAD")
+        .replay_all_splits(input)
+        .unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert_eq!(outcomes[1], complete());
+
+    // トンネルモード以降は HTTP として解釈しない
+    let input = b"HTTP/1.1 200 OK\r\n\r\n\x16\x03\x01";
+    let outcomes = Harness::response()
+        .with_request_method("CONNECT")
+        .replay_all_splits(input)
+        .unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert!(matches!(
+        outcomes[0],
+        Outcome::ResponseHead {
+            body_kind: BodyKind::Tunnel,
+            ..
+        }
+    ));
+}
+
+// ========================================
+// 分割と比較
+// ========================================
+
+#[test]
+fn test_run_split_raw_outcomes() {
+    let input = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nabcd";
+    let outcomes = Harness::response().run_split(input, &[40, 42]);
+    // 正規化しない場合は分割位置ごとのボディを返す
+    assert_eq!(outcomes[1], Outcome::Body(b"ab".to_vec()));
+    assert_eq!(outcomes[2], Outcome::Body(b"cd".to_vec()));
+    assert_eq!(normalize(outcomes)[1], Outcome::Body(b"abcd".to_vec()));
+
+    let outcomes = Harness::response().run(&[&input[..10], &input[10..]]);
+    assert_eq!(outcomes, Harness::response().run(&[input]));
+}
+
+#[test]
+fn test_normalize() {
+    let outcomes = vec![
+        Outcome::Body(b"a".to_vec()),
+        Outcome::Body(Vec::new()),
+        Outcome::Body(b"b".to_vec()),
+        complete(),
+        Outcome::Body(Vec::new()),
+    ];
+    assert_eq!(
+        normalize(outcomes),
+        [Outcome::Body(b"ab".to_vec()), complete()]
+    );
+}
+
+#[test]
+fn test_mismatch() {
+    let input = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n";
+    let expected = [Outcome::RequestHead {
+        head: request_head("GET", "/", &[("Host", "b")]),
+        body_kind: BodyKind::None,
+    }];
+    let mismatch = Harness::request()
+        .check_all_splits(input, &expected)
+        .unwrap_err();
+    // 一括での投入から検証する
+    assert!(mismatch.split_points().is_empty());
+    assert_eq!(mismatch.expected(), &expected);
+    assert_eq!(mismatch.actual().len(), 2);
+    assert!(
+        mismatch
+            .to_string()
+            .starts_with("outcome mismatch at split points []: expected ")
+    );
+}
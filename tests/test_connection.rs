@@ -0,0 +1,218 @@
+//! 接続管理のユニットテスト
+
+use std::borrow::Cow;
+
+use shiguredo_http11::connection::{
+    ConnectionError, ConnectionOptions, HOP_BY_HOP_HEADERS, ResponsePersistence, is_hop_by_hop,
+    strip_hop_by_hop,
+};
+use shiguredo_http11::{BodyKind, Request, RequestDecoder, RequestHead, Response, StatusCode};
+
+fn decode_head(input: &[u8]) -> RequestHead {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(input).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+// ========================================
+// HTTP/1.0
+// ========================================
+
+#[test]
+fn test_http10_without_keep_alive_closes() {
+    let head = decode_head(b"GET / HTTP/1.0\r\n\r\n");
+    let plan = ResponsePersistence::for_request(&head, BodyKind::ContentLength(5));
+    assert!(!plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), Some("close"));
+    assert_eq!(plan.content_length_header(), Some(5));
+}
+
+#[test]
+fn test_http10_keep_alive_requires_content_length() {
+    let head = decode_head(b"GET / HTTP/1.0\r\nConnection: Keep-Alive\r\n\r\n");
+
+    let plan = ResponsePersistence::for_request(&head, BodyKind::ContentLength(0));
+    assert!(plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), Some("keep-alive"));
+
+    let plan = ResponsePersistence::for_request(&head, BodyKind::None);
+    assert!(plan.is_keep_alive());
+    assert_eq!(plan.content_length_header(), None);
+
+    let plan = ResponsePersistence::for_request(&head, BodyKind::CloseDelimited);
+    assert!(!plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), Some("close"));
+}
+
+#[test]
+fn test_http10_chunked_downgraded_to_close_delimited() {
+    // RFC 9112 Section 6.1: HTTP/1.0 への応答に Transfer-Encoding を含めない
+    let head = decode_head(b"GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\n");
+    let plan = ResponsePersistence::for_request(&head, BodyKind::Chunked);
+    assert_eq!(plan.framing(), BodyKind::CloseDelimited);
+    assert_eq!(plan.transfer_encoding_header(), None);
+    assert!(!plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), Some("close"));
+}
+
+// ========================================
+// HTTP/1.1
+// ========================================
+
+#[test]
+fn test_http11_default_persistent() {
+    let head = decode_head(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n");
+    let plan = ResponsePersistence::for_request(&head, BodyKind::Chunked);
+    assert!(plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), None);
+    assert_eq!(plan.transfer_encoding_header(), Some("chunked"));
+
+    let plan = ResponsePersistence::for_request(&head, BodyKind::CloseDelimited);
+    assert!(!plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), Some("close"));
+}
+
+#[test]
+fn test_http11_connection_close() {
+    let head = decode_head(b"GET / HTTP/1.1\r\nHost: a\r\nConnection: close\r\n\r\n");
+    let plan = ResponsePersistence::for_request(&head, BodyKind::ContentLength(1));
+    assert!(!plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), Some("close"));
+}
+
+#[test]
+fn test_tunnel_sets_no_connection_header() {
+    let head = decode_head(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n");
+    let plan = ResponsePersistence::for_request(&head, BodyKind::Tunnel);
+    assert!(!plan.is_keep_alive());
+    assert_eq!(plan.connection_header(), None);
+}
+
+// ========================================
+// apply
+// ========================================
+
+#[test]
+fn test_apply_sets_headers_and_encodes() {
+    let request = Request::with_version("GET", "/", "HTTP/1.0")
+        .unwrap()
+        .header("Connection", "keep-alive")
+        .unwrap();
+    let plan = ResponsePersistence::for_request(&request, BodyKind::ContentLength(2));
+
+    let mut response = Response::with_status(StatusCode::OK)
+        .header("Connection", "close")
+        .unwrap()
+        .body(b"ok".to_vec());
+    plan.apply(&mut response).unwrap();
+    assert_eq!(response.get_headers("Connection"), ["keep-alive"]);
+    assert_eq!(response.get_header("Content-Length"), Some("2"));
+    assert_eq!(
+        response.encode().unwrap(),
+        b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\nContent-Length: 2\r\n\r\nok"
+    );
+}
+
+// ========================================
+// Connection ヘッダー
+// ========================================
+
+#[test]
+fn test_connection_error_display() {
+    assert_eq!(
+        ConnectionError::InvalidOption.to_string(),
+        "invalid Connection option"
+    );
+}
+
+#[test]
+fn test_connection_options_parse() {
+    let options = ConnectionOptions::parse(" , Keep-Alive ,X-Trace,, keep-alive").unwrap();
+    assert_eq!(options.options(), ["keep-alive", "x-trace"]);
+    assert!(options.contains("x-TRACE"));
+    assert!(!options.is_close());
+    assert_eq!(options.to_string(), "keep-alive, x-trace");
+
+    assert!(ConnectionOptions::parse("").unwrap().is_empty());
+    assert!(ConnectionOptions::parse("close").unwrap().is_close());
+    assert_eq!(
+        ConnectionOptions::parse("close, a b"),
+        Err(ConnectionError::InvalidOption)
+    );
+    assert_eq!(
+        ConnectionOptions::new().with_option("a;b"),
+        Err(ConnectionError::InvalidOption)
+    );
+}
+
+#[test]
+fn test_connection_options_from_message() {
+    let head = decode_head(
+        b"GET / HTTP/1.1\r\nHost: a\r\nConnection: upgrade\r\nConnection: HTTP2-Settings\r\n\r\n",
+    );
+    let options = ConnectionOptions::from_message(&head).unwrap().unwrap();
+    assert_eq!(options.options(), ["upgrade", "http2-settings"]);
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(ConnectionOptions::from_message(&request), Ok(None));
+    let request = request.header("Connection", options.to_string()).unwrap();
+    assert_eq!(request.get_typed::<ConnectionOptions>(), Some(Ok(options)));
+}
+
+// ========================================
+// hop-by-hop ヘッダー
+// ========================================
+
+#[test]
+fn test_is_hop_by_hop() {
+    let none = ConnectionOptions::new();
+    for name in HOP_BY_HOP_HEADERS {
+        assert!(is_hop_by_hop(name, &none), "{name}");
+        assert!(is_hop_by_hop(&name.to_ascii_uppercase(), &none), "{name}");
+    }
+    assert!(!is_hop_by_hop("Host", &none));
+    assert!(!is_hop_by_hop("Trailer", &none));
+
+    let options = ConnectionOptions::parse("X-Trace").unwrap();
+    assert!(is_hop_by_hop("x-trace", &options));
+    assert!(!is_hop_by_hop("X-Other", &options));
+}
+
+#[test]
+fn test_strip_hop_by_hop() {
+    let mut headers: Vec<(Cow<'static, str>, String)> = [
+        ("Host", "example.com"),
+        ("Connection", "Keep-Alive, X-Trace"),
+        ("Keep-Alive", "timeout=5"),
+        ("X-Trace", "1"),
+        ("Accept", "*/*"),
+        ("connection", "x-debug"),
+        ("X-Debug", "on"),
+        ("Transfer-Encoding", "chunked"),
+        ("TE", "trailers"),
+        ("Upgrade", "websocket"),
+        ("Proxy-Authorization", "Basic abc"),
+        ("Trailer", "X-Checksum"),
+    ]
+    .iter()
+    .map(|(n, v)| (Cow::Borrowed(*n), v.to_string()))
+    .collect();
+    strip_hop_by_hop(&mut headers);
+    assert_eq!(
+        headers,
+        [
+            (Cow::Borrowed("Host"), "example.com".to_string()),
+            (Cow::Borrowed("Accept"), "*/*".to_string()),
+            (Cow::Borrowed("Trailer"), "X-Checksum".to_string()),
+        ]
+    );
+
+    // 不正な option もエラーにせず除去対象として扱う
+    let mut headers = vec![
+        (Cow::Borrowed("Connection"), "bad name, ".to_string()),
+        (Cow::Borrowed("bad name"), "x".to_string()),
+        (Cow::Borrowed("Host"), "a".to_string()),
+    ];
+    strip_hop_by_hop(&mut headers);
+    assert_eq!(headers, [(Cow::Borrowed("Host"), "a".to_string())]);
+}
@@ -0,0 +1,479 @@
+//! Content-Encoding を展開するストリーミングリーダーのテスト
+#![cfg(feature = "compression-impl")]
+
+use std::borrow::Cow;
+
+use shiguredo_http11::compression::{
+    CompressionError, Compressor, Decompressor, DecompressorChain, DeflateCompressor,
+    DeflateDecompressor, GzipCompressor, GzipDecompressor, NoCompression,
+};
+use shiguredo_http11::content_decoder::{
+    ContentDecoder, ContentDecoderError, DecompressorFactory, DefaultDecompressors,
+};
+use shiguredo_http11::content_encoding::{ContentCoding, ContentEncoding};
+use shiguredo_http11::{RequestDecoder, ResponseDecoder};
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    compress(&mut GzipCompressor::new(), data)
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    compress(&mut DeflateCompressor::new(), data)
+}
+
+fn compress(compressor: &mut dyn Compressor, data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0u8; data.len() + 1024];
+    let status = compressor.compress(data, &mut output).unwrap();
+    let mut len = status.produced();
+    let status = compressor.finish(&mut output[len..]).unwrap();
+    assert!(status.is_complete());
+    len += status.produced();
+    output.truncate(len);
+    output
+}
+
+fn chunked(data: &[u8], chunk_size: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    for chunk in data.chunks(chunk_size) {
+        out.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(b"0\r\n\r\n");
+    out
+}
+
+/// `reader` で `decoder` のボディをすべて読む
+fn read_all(
+    reader: &mut ContentDecoder,
+    decoder: &mut ResponseDecoder,
+) -> Result<Vec<u8>, ContentDecoderError> {
+    let mut body = Vec::new();
+    while let Some(data) = reader.peek_body(decoder)? {
+        body.extend_from_slice(data);
+        let len = data.len();
+        reader.consume_body(len);
+    }
+    Ok(body)
+}
+
+fn response(headers: &str, body: &[u8]) -> ResponseDecoder {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(format!("HTTP/1.1 200 OK\r\n{headers}\r\n").as_bytes())
+        .unwrap();
+    decoder.feed(body).unwrap();
+    decoder
+}
+
+// ========================================
+// 展開
+// ========================================
+
+#[test]
+fn content_length_gzip() {
+    let data = b"hello world ".repeat(1000);
+    let compressed = gzip(&data);
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: gzip\r\nContent-Length: {}\r\n",
+            compressed.len()
+        ),
+        &compressed,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), data);
+    assert!(reader.is_complete());
+    assert_eq!(reader.decoded_len(), data.len());
+}
+
+#[test]
+fn chunked_gzip_with_trailers() {
+    let data = b"0123456789".repeat(500);
+    let mut body = chunked(&gzip(&data), 7);
+    // 終端チャンクにトレーラーを付ける
+    body.truncate(body.len() - 2);
+    body.extend_from_slice(b"X-Checksum: abc\r\n\r\n");
+    let mut decoder = response(
+        "Content-Encoding: gzip\r\nTransfer-Encoding: chunked\r\nTrailer: X-Checksum\r\n",
+        &body,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), data);
+    assert!(reader.is_complete());
+    assert_eq!(
+        reader.trailers(),
+        &[(Cow::Borrowed("X-Checksum"), "abc".to_string())]
+    );
+}
+
+#[test]
+fn chunked_gzip_multiple_members() {
+    // メンバーごとにチャンクを送るサーバーの連結した gzip
+    let mut body = Vec::new();
+    for part in [&b"first "[..], b"second ", b"third"] {
+        let member = gzip(part);
+        body.extend_from_slice(format!("{:x}\r\n", member.len()).as_bytes());
+        body.extend_from_slice(&member);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(b"0\r\n\r\n");
+    let mut decoder = response(
+        "Content-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n",
+        &body,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+
+    assert_eq!(
+        read_all(&mut reader, &mut decoder).unwrap(),
+        b"first second third"
+    );
+    assert!(reader.is_complete());
+}
+
+#[test]
+fn streaming_feed() {
+    let data = b"streaming body ".repeat(300);
+    let compressed = gzip(&data);
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Encoding: x-gzip\r\nContent-Length: {}\r\n\r\n",
+                compressed.len()
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors)
+        .unwrap()
+        .with_buffer_size(16);
+
+    let mut body = Vec::new();
+    for piece in compressed.chunks(5) {
+        decoder.feed(piece).unwrap();
+        body.extend(read_all(&mut reader, &mut decoder).unwrap());
+    }
+    assert!(reader.is_complete());
+    assert_eq!(body, data);
+}
+
+#[test]
+fn identity_passthrough() {
+    let mut decoder = response("Content-Length: 5\r\n", b"hello");
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), b"hello");
+    assert!(reader.is_complete());
+
+    let mut decoder = response("Content-Encoding: identity\r\nContent-Length: 2\r\n", b"hi");
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), b"hi");
+}
+
+#[test]
+fn empty_body_with_content_encoding() {
+    // HEAD へのレスポンスは Content-Encoding があってもボディがない
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_request_method("HEAD");
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: 100\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), b"");
+    assert!(reader.is_complete());
+}
+
+#[test]
+fn request_decoder_source() {
+    let data = b"request body".to_vec();
+    let compressed = gzip(&data);
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(
+            format!(
+                "POST / HTTP/1.1\r\nHost: example.com\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
+                compressed.len()
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    decoder.feed(&compressed).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+
+    let mut body = Vec::new();
+    while let Some(data) = reader.peek_body(&mut decoder).unwrap() {
+        body.extend_from_slice(data);
+        let len = data.len();
+        reader.consume_body(len);
+    }
+    assert_eq!(body, data);
+}
+
+#[test]
+fn custom_factory() {
+    // 独自のコーディングを識別子として扱う展開器を追加する
+    let factory = |coding: &ContentCoding| -> Option<Box<dyn Decompressor>> {
+        match coding.as_str() {
+            "x-plain" => Some(Box::new(NoCompression::new())),
+            _ => DefaultDecompressors.create(coding),
+        }
+    };
+    let mut decoder = response("Content-Encoding: x-plain\r\nContent-Length: 3\r\n", b"abc");
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &factory).unwrap();
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), b"abc");
+}
+
+// ========================================
+// 複数のコーディング
+// ========================================
+
+#[test]
+fn multiple_codings() {
+    // deflate を適用した後に gzip を適用したボディ
+    let data = b"layered body ".repeat(2000);
+    let compressed = gzip(&deflate(&data));
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: deflate, gzip\r\nContent-Length: {}\r\n",
+            compressed.len()
+        ),
+        &compressed,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(read_all(&mut reader, &mut decoder).unwrap(), data);
+    assert!(reader.is_complete());
+}
+
+#[test]
+fn multiple_codings_streaming() {
+    let data: Vec<u8> = (0..50_000u32).map(|i| (i * 7 % 251) as u8).collect();
+    let compressed = chunked(&gzip(&gzip(&data)), 3);
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip, identity, gzip\r\nTransfer-Encoding: chunked\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors)
+        .unwrap()
+        .with_buffer_size(7);
+
+    let mut body = Vec::new();
+    for piece in compressed.chunks(11) {
+        decoder.feed(piece).unwrap();
+        body.extend(read_all(&mut reader, &mut decoder).unwrap());
+    }
+    assert!(reader.is_complete());
+    assert_eq!(body, data);
+}
+
+#[test]
+fn multiple_codings_unsupported() {
+    let encoding = ContentEncoding::parse("br, gzip").unwrap();
+    assert_eq!(
+        ContentDecoder::new(&encoding, &DefaultDecompressors).unwrap_err(),
+        ContentDecoderError::UnsupportedCoding {
+            coding: "br".to_string()
+        }
+    );
+}
+
+#[test]
+fn multiple_codings_truncated_outer_trailer() {
+    // 外側の gzip の CRC-32 / ISIZE が欠けている
+    let compressed = gzip(&gzip(b"hello world"));
+    let truncated = &compressed[..compressed.len() - 8];
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: gzip, gzip\r\nContent-Length: {}\r\n",
+            truncated.len()
+        ),
+        truncated,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(
+        read_all(&mut reader, &mut decoder),
+        Err(ContentDecoderError::Truncated)
+    );
+}
+
+#[test]
+fn multiple_codings_inner_trailing_data() {
+    // 内側の gzip の後ろに余分なデータがある
+    let mut inner = gzip(b"hello world");
+    inner.extend_from_slice(b"garbage");
+    let compressed = gzip(&inner);
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: gzip, gzip\r\nContent-Length: {}\r\n",
+            compressed.len()
+        ),
+        &compressed,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert!(matches!(
+        read_all(&mut reader, &mut decoder),
+        Err(ContentDecoderError::Decompression(
+            CompressionError::InvalidData(_)
+        ))
+    ));
+}
+
+#[test]
+fn decompressor_chain_small_output() {
+    let data = b"chain ".repeat(5000);
+    let compressed = gzip(&deflate(&data));
+    let mut chain = DecompressorChain::new(vec![
+        Box::new(GzipDecompressor::new()),
+        Box::new(DeflateDecompressor::new()),
+    ]);
+    assert_eq!(chain.len(), 2);
+
+    let mut input = compressed.as_slice();
+    let mut output = Vec::new();
+    let mut buf = [0u8; 13];
+    loop {
+        let status = chain.decompress(input, &mut buf).unwrap();
+        input = &input[status.consumed()..];
+        output.extend_from_slice(&buf[..status.produced()]);
+        if status.is_complete() {
+            break;
+        }
+    }
+    assert!(input.is_empty());
+    assert_eq!(output, data);
+
+    // reset 後は再利用できる
+    chain.reset();
+    let mut buf = vec![0u8; data.len() + 1];
+    let status = chain.decompress(&compressed, &mut buf).unwrap();
+    assert_eq!(status.consumed(), compressed.len());
+    assert_eq!(&buf[..status.produced()], data.as_slice());
+    // gzip は次のメンバーが続く可能性があるため、空の入力 (入力の終端) で完了する
+    let status = chain.decompress(&[], &mut buf).unwrap();
+    assert!(status.is_complete());
+    assert_eq!(status.produced(), 0);
+}
+
+#[test]
+fn decompressor_chain_empty() {
+    let mut chain = DecompressorChain::new(Vec::new());
+    assert!(chain.is_empty());
+    let mut buf = [0u8; 3];
+    let status = chain.decompress(b"hello", &mut buf).unwrap();
+    assert!(status.is_output_full());
+    assert_eq!(&buf, b"hel");
+}
+
+// ========================================
+// エラー
+// ========================================
+
+#[test]
+fn unsupported_coding() {
+    let encoding = ContentEncoding::parse("br").unwrap();
+    assert_eq!(
+        ContentDecoder::new(&encoding, &DefaultDecompressors).unwrap_err(),
+        ContentDecoderError::UnsupportedCoding {
+            coding: "br".to_string()
+        }
+    );
+}
+
+#[test]
+fn invalid_content_encoding() {
+    let mut decoder = response("Content-Encoding: g zip\r\nContent-Length: 0\r\n", b"");
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(matches!(
+        ContentDecoder::from_head(&head, &DefaultDecompressors),
+        Err(ContentDecoderError::InvalidContentEncoding(_))
+    ));
+}
+
+#[test]
+fn decoded_too_large() {
+    let data = vec![b'a'; 100_000];
+    let compressed = gzip(&data);
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: gzip\r\nContent-Length: {}\r\n",
+            compressed.len()
+        ),
+        &compressed,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors)
+        .unwrap()
+        .with_max_decoded_size(10_000);
+    assert!(matches!(
+        read_all(&mut reader, &mut decoder),
+        Err(ContentDecoderError::DecodedTooLarge { limit: 10_000, .. })
+    ));
+}
+
+#[test]
+fn truncated_body() {
+    let compressed = gzip(b"hello world");
+    let truncated = &compressed[..compressed.len() - 4];
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: gzip\r\nContent-Length: {}\r\n",
+            truncated.len()
+        ),
+        truncated,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(
+        read_all(&mut reader, &mut decoder),
+        Err(ContentDecoderError::Truncated)
+    );
+}
+
+#[test]
+fn trailing_data() {
+    let mut body = gzip(b"hello world");
+    body.extend_from_slice(b"garbage");
+    let mut decoder = response(
+        &format!(
+            "Content-Encoding: gzip\r\nContent-Length: {}\r\n",
+            body.len()
+        ),
+        &body,
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert_eq!(
+        read_all(&mut reader, &mut decoder),
+        Err(ContentDecoderError::TrailingData)
+    );
+}
+
+#[test]
+fn invalid_compressed_data() {
+    let mut decoder = response(
+        "Content-Encoding: gzip\r\nContent-Length: 10\r\n",
+        b"not gzip!!",
+    );
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut reader = ContentDecoder::from_head(&head, &DefaultDecompressors).unwrap();
+    assert!(matches!(
+        read_all(&mut reader, &mut decoder),
+        Err(ContentDecoderError::Decompression(
+            CompressionError::InvalidData(_)
+        ))
+    ));
+}
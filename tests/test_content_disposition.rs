@@ -326,3 +326,77 @@ fn test_content_disposition_filename_quoted_pair_rejects_cr_lf_nul() {
         );
     }
 }
+
+// ========================================
+// filename* と filename の優先順位 / with_filename_utf8 のテスト
+// ========================================
+
+#[test]
+fn test_content_disposition_filename_ext_precedence() {
+    // RFC 6266 Section 4.3: filename* を filename より優先する (出現順に依らない)
+    for input in [
+        "attachment; filename=\"EURO rates\"; filename*=utf-8''%e2%82%ac%20rates",
+        "attachment; filename*=UTF-8''%E2%82%AC%20rates; filename=\"EURO rates\"",
+    ] {
+        let cd = ContentDisposition::parse(input).unwrap();
+        assert_eq!(cd.filename(), Some("€ rates"), "{}", input);
+        assert_eq!(cd.filename_ascii(), Some("EURO rates"));
+        assert_eq!(cd.filename_ext(), Some("€ rates"));
+    }
+}
+
+#[test]
+fn test_content_disposition_filename_ext_language() {
+    let cd =
+        ContentDisposition::parse("attachment; filename*=UTF-8'ja'%E6%97%A5%E6%9C%AC.txt").unwrap();
+    assert_eq!(cd.filename(), Some("日本.txt"));
+    assert_eq!(cd.filename_ext_language(), Some("ja"));
+    assert_eq!(
+        cd.to_string(),
+        "attachment; filename*=UTF-8'ja'%E6%97%A5%E6%9C%AC.txt"
+    );
+
+    // 不正な言語タグ
+    let result = ContentDisposition::parse("attachment; filename*=UTF-8'j a'x.txt");
+    assert_eq!(
+        result.unwrap_err(),
+        ContentDispositionError::InvalidExtValue
+    );
+}
+
+#[test]
+fn test_content_disposition_with_filename_utf8_ascii() {
+    let cd =
+        ContentDisposition::new(DispositionType::Attachment).with_filename_utf8("report 1.pdf");
+    assert_eq!(cd.to_string(), "attachment; filename=\"report 1.pdf\"");
+    assert_eq!(cd.filename_ext(), None);
+}
+
+#[test]
+fn test_content_disposition_with_filename_utf8_non_ascii() {
+    let cd = ContentDisposition::new(DispositionType::Attachment).with_filename_utf8("€ 100%.txt");
+    assert_eq!(
+        cd.to_string(),
+        "attachment; filename=\"_ 100_.txt\"; filename*=UTF-8''%E2%82%AC%20100%25.txt"
+    );
+    let parsed = ContentDisposition::parse(&cd.to_string()).unwrap();
+    assert_eq!(parsed.filename(), Some("€ 100%.txt"));
+    assert_eq!(parsed.filename_ascii(), Some("_ 100_.txt"));
+    assert_eq!(parsed, cd);
+}
+
+#[test]
+fn test_content_disposition_with_filename_utf8_control_and_backslash() {
+    let cd = ContentDisposition::new(DispositionType::Attachment).with_filename_utf8("a\\b\tc");
+    assert_eq!(cd.filename_ascii(), Some("a_b_c"));
+    assert_eq!(cd.filename(), Some("a\\b\tc"));
+}
+
+#[test]
+fn test_content_disposition_with_filename_utf8_replaces_previous() {
+    let cd = ContentDisposition::new(DispositionType::Attachment)
+        .with_filename_utf8("日本.txt")
+        .with_filename_utf8("plain.txt");
+    assert_eq!(cd.filename_ext(), None);
+    assert_eq!(cd.to_string(), "attachment; filename=\"plain.txt\"");
+}
@@ -18,6 +18,10 @@ fn test_content_encoding_error_display() {
             ContentEncodingError::InvalidEncoding,
             "invalid Content-Encoding token",
         ),
+        (
+            ContentEncodingError::IdentityNotAlone,
+            "identity must not be combined with other content codings",
+        ),
     ];
 
     for (error, expected) in errors {
@@ -38,6 +42,86 @@ fn test_content_coding_as_str() {
     assert_eq!(ContentCoding::Other("br".to_string()).as_str(), "br");
 }
 
+#[test]
+fn test_content_coding_parse() {
+    assert_eq!(ContentCoding::parse("GZIP").unwrap(), ContentCoding::Gzip);
+    assert_eq!(
+        ContentCoding::parse(" br ").unwrap(),
+        ContentCoding::Other("br".to_string())
+    );
+    assert!(matches!(
+        ContentCoding::parse(""),
+        Err(ContentEncodingError::InvalidFormat)
+    ));
+    assert!(matches!(
+        ContentCoding::parse("g zip"),
+        Err(ContentEncodingError::InvalidEncoding)
+    ));
+}
+
+// ========================================
+// 複数のコーディングのテスト
+// ========================================
+
+// RFC 9110 Section 8.4: コーディングは適用した順に並ぶ
+#[test]
+fn test_content_encoding_decoding_order() {
+    let ce = ContentEncoding::parse("deflate, gzip").unwrap();
+    let order: Vec<&str> = ce.decoding_order().map(|c| c.as_str()).collect();
+    assert_eq!(order, ["gzip", "deflate"]);
+    assert_eq!(ce.last(), Some(&ContentCoding::Gzip));
+
+    // identity は展開しない
+    let ce = ContentEncoding::parse("identity").unwrap();
+    assert_eq!(ce.decoding_order().count(), 0);
+}
+
+#[test]
+fn test_content_encoding_push_pop() {
+    let mut ce = ContentEncoding::new();
+    assert!(ce.is_identity());
+    assert_eq!(ce.to_string(), "");
+
+    ce.push(ContentCoding::Gzip);
+    ce.push(ContentCoding::Other("br".to_string()));
+    assert_eq!(ce.to_string(), "gzip, br");
+    assert!(!ce.is_identity());
+
+    assert_eq!(ce.pop(), Some(ContentCoding::Other("br".to_string())));
+    assert_eq!(ce.to_string(), "gzip");
+    assert_eq!(ce.pop(), Some(ContentCoding::Gzip));
+    assert_eq!(ce.pop(), None);
+    assert!(ce.is_identity());
+}
+
+#[test]
+fn test_content_encoding_push_identity() {
+    // identity は追加しない
+    let mut ce = ContentEncoding::new();
+    ce.push(ContentCoding::Identity);
+    assert!(ce.encodings().is_empty());
+
+    // 既存の identity は取り除く
+    let mut ce = ContentEncoding::parse("identity").unwrap();
+    assert!(ce.is_identity());
+    ce.push(ContentCoding::Gzip);
+    assert_eq!(ce.encodings(), &[ContentCoding::Gzip]);
+    assert!(ce.validate().is_ok());
+}
+
+#[test]
+fn test_content_encoding_validate() {
+    for input in ["", "identity", "gzip", "gzip, br", "gzip, gzip"] {
+        assert!(ContentEncoding::parse(input).unwrap().validate().is_ok());
+    }
+    for input in ["identity, gzip", "gzip, identity", "identity, identity"] {
+        assert_eq!(
+            ContentEncoding::parse(input).unwrap().validate(),
+            Err(ContentEncodingError::IdentityNotAlone)
+        );
+    }
+}
+
 // ========================================
 // パースエラーのテスト
 // ========================================
@@ -1,6 +1,6 @@
 //! Content-Language ヘッダーのユニットテスト
 
-use shiguredo_http11::content_language::ContentLanguage;
+use shiguredo_http11::content_language::{ContentLanguage, ContentLanguageError, LanguageTag};
 
 #[test]
 fn parse_empty_elements() {
@@ -30,3 +30,168 @@ fn parse_primary_subtag_alpha_only() {
     let cl = ContentLanguage::parse("en-123").unwrap();
     assert_eq!(cl.tags()[0], "en-123");
 }
+
+#[test]
+fn content_language_error_display() {
+    let errors = [
+        (ContentLanguageError::Empty, "empty Content-Language"),
+        (
+            ContentLanguageError::InvalidFormat,
+            "invalid Content-Language format",
+        ),
+        (
+            ContentLanguageError::InvalidLanguageTag,
+            "invalid language tag",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+#[test]
+fn parse_rejects_ill_formed_tags() {
+    // 1*8ALPHA *("-" 1*8alphanum) の簡易検証では受理されていた形式
+    for input in ["en-US-a", "en-x", "a", "en-US-abcd", "en-US-12", "en-a-b-c"] {
+        assert_eq!(
+            ContentLanguage::parse(input),
+            Err(ContentLanguageError::InvalidLanguageTag),
+            "{input}"
+        );
+    }
+    let cl = ContentLanguage::parse("de-CH-1996, x-private, i-klingon").unwrap();
+    let tags = cl.language_tags();
+    assert_eq!(tags.len(), 3);
+    assert_eq!(tags[0].variants(), ["1996"]);
+    assert!(tags[1].is_private_use());
+    assert!(tags[2].is_grandfathered());
+}
+
+#[test]
+fn language_tag_subtags() {
+    let tag = LanguageTag::parse("zh-yue-Hant-HK-x-private").unwrap();
+    assert_eq!(tag.language(), Some("zh"));
+    assert_eq!(tag.extlangs(), ["yue"]);
+    assert_eq!(tag.script(), Some("Hant"));
+    assert_eq!(tag.region(), Some("HK"));
+    assert!(tag.variants().is_empty());
+    assert_eq!(tag.private_use(), Some("private"));
+    assert!(!tag.is_private_use());
+    assert_eq!(tag.to_string(), "zh-yue-Hant-HK-x-private");
+
+    let tag = LanguageTag::parse("es-419").unwrap();
+    assert_eq!(tag.region(), Some("419"));
+
+    let tag = LanguageTag::parse("sl-rozaj-biske-1994").unwrap();
+    assert_eq!(tag.variants(), ["rozaj", "biske", "1994"]);
+
+    let tag = LanguageTag::parse("en-US-u-ca-gregory-t-ja-x-foo-bar").unwrap();
+    assert_eq!(tag.extensions(), ["u-ca-gregory", "t-ja"]);
+    assert_eq!(tag.private_use(), Some("foo-bar"));
+
+    // 5*8ALPHA の language は extlang を持たない
+    let tag = LanguageTag::parse("abcdefgh").unwrap();
+    assert_eq!(tag.language(), Some("abcdefgh"));
+    assert!(LanguageTag::parse("abcde-abc").is_err());
+
+    // extlang は最大 3 つ
+    assert!(LanguageTag::parse("zh-abc-def-ghi").is_ok());
+    assert!(LanguageTag::parse("zh-abc-def-ghi-jkl").is_err());
+}
+
+#[test]
+fn language_tag_private_use_and_grandfathered() {
+    let tag = LanguageTag::parse("X-Whatever").unwrap();
+    assert!(tag.is_private_use());
+    assert_eq!(tag.language(), None);
+    assert_eq!(tag.private_use(), Some("Whatever"));
+    assert!(LanguageTag::parse("x").is_err());
+    assert!(LanguageTag::parse("x-123456789").is_err());
+
+    // irregular
+    let tag = LanguageTag::parse("en-GB-oed").unwrap();
+    assert!(tag.is_grandfathered());
+    assert_eq!(tag.language(), None);
+    assert!(LanguageTag::parse("I-AMI").unwrap().is_grandfathered());
+    assert!(LanguageTag::parse("i-unknown").is_err());
+
+    // regular は langtag としても分解する
+    let tag = LanguageTag::parse("zh-min-nan").unwrap();
+    assert!(tag.is_grandfathered());
+    assert_eq!(tag.language(), Some("zh"));
+    assert_eq!(tag.extlangs(), ["min", "nan"]);
+    assert!(!LanguageTag::parse("zh-min-yue").unwrap().is_grandfathered());
+}
+
+#[test]
+fn language_tag_invalid() {
+    for input in [
+        "",
+        "-",
+        "en-",
+        "-en",
+        "e",
+        "1ab",
+        "en--US",
+        "en_US",
+        "en-US-",
+        "abcdefghi",
+        "en-a",
+        "en-u-x-foo",
+        "en-u-abcdefghi",
+        "日本語",
+    ] {
+        assert_eq!(
+            LanguageTag::parse(input),
+            Err(ContentLanguageError::InvalidLanguageTag),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn language_tag_basic_filtering() {
+    let tag = LanguageTag::parse("de-DE-1996").unwrap();
+    assert!(tag.matches_basic_range("*"));
+    assert!(tag.matches_basic_range("de"));
+    assert!(tag.matches_basic_range("DE-de"));
+    assert!(tag.matches_basic_range("de-DE-1996"));
+    assert!(!tag.matches_basic_range("de-D"));
+    assert!(!tag.matches_basic_range("de-DE-1996-x"));
+    assert!(!tag.matches_basic_range("en"));
+    // 不正なレンジ
+    assert!(!tag.matches_basic_range(""));
+    assert!(!tag.matches_basic_range("de-*"));
+}
+
+#[test]
+fn language_tag_extended_filtering() {
+    // RFC 4647 Section 3.3.2 の例
+    for input in [
+        "de-DE",
+        "de-de",
+        "de-Latn-DE",
+        "de-Latf-DE",
+        "de-DE-x-goethe",
+        "de-Latn-DE-1996",
+        "de-Deva-DE",
+    ] {
+        let tag = LanguageTag::parse(input).unwrap();
+        assert!(tag.matches_extended_range("de-*-DE"), "{input}");
+        assert!(tag.matches_extended_range("de-DE"), "{input}");
+    }
+    for input in ["de", "de-x-DE", "de-Deva"] {
+        let tag = LanguageTag::parse(input).unwrap();
+        assert!(!tag.matches_extended_range("de-*-DE"), "{input}");
+        assert!(!tag.matches_extended_range("de-DE"), "{input}");
+    }
+
+    let tag = LanguageTag::parse("en-US").unwrap();
+    assert!(tag.matches_extended_range("*"));
+    assert!(tag.matches_extended_range("*-US"));
+    assert!(!tag.matches_extended_range("*-GB"));
+    assert!(!tag.matches_extended_range("en-US-*-x"));
+    // 不正なレンジ
+    assert!(!tag.matches_extended_range(""));
+    assert!(!tag.matches_extended_range("en--US"));
+}
@@ -73,6 +73,114 @@ fn test_content_type_is_form_urlencoded() {
     );
 }
 
+// ========================================
+// essence / サフィックスのテスト
+// ========================================
+
+#[test]
+fn test_content_type_essence_eq() {
+    let a = ContentType::parse("Text/HTML; charset=utf-8").unwrap();
+    let b = ContentType::parse("text/html").unwrap();
+    assert!(a.essence_eq(&b));
+    assert!(b.essence_eq(&a));
+    // パラメータが異なるため PartialEq では一致しない
+    assert_ne!(a, b);
+    assert!(!a.essence_eq(&ContentType::new("text", "plain")));
+}
+
+#[test]
+fn test_content_type_is_essence() {
+    let ct = ContentType::parse("application/json; charset=utf-8").unwrap();
+    assert!(ct.is_essence("application/json"));
+    assert!(ct.is_essence("APPLICATION/Json"));
+    assert!(!ct.is_essence("application/json; charset=utf-8"));
+    assert!(!ct.is_essence("application"));
+    assert!(!ct.is_essence("application/*"));
+    assert!(!ct.is_essence(""));
+}
+
+#[test]
+fn test_content_type_suffix() {
+    let cases = [
+        ("application/problem+json", Some("json")),
+        ("image/svg+xml", Some("xml")),
+        ("application/vnd.api+JSON", Some("json")),
+        ("application/a+b+cbor", Some("cbor")),
+        ("application/json", None),
+        ("application/+json", None),
+        ("application/json+", None),
+    ];
+    for (input, expected) in cases {
+        let ct = ContentType::parse(input).unwrap();
+        assert_eq!(ct.suffix(), expected, "{}", input);
+    }
+
+    let ct = ContentType::parse("application/ld+json").unwrap();
+    assert!(ct.has_suffix("json"));
+    assert!(ct.has_suffix("JSON"));
+    assert!(!ct.has_suffix("xml"));
+}
+
+#[test]
+fn test_content_type_is_json_compatible() {
+    for input in [
+        "application/json",
+        "application/problem+json; charset=utf-8",
+        "application/vnd.github.v3+json",
+    ] {
+        assert!(
+            ContentType::parse(input).unwrap().is_json_compatible(),
+            "{}",
+            input
+        );
+    }
+    for input in [
+        "text/json",
+        "application/jsonp",
+        "application/+json",
+        "application/xml",
+    ] {
+        assert!(
+            !ContentType::parse(input).unwrap().is_json_compatible(),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_content_type_is_xml_compatible() {
+    for input in [
+        "application/xml",
+        "text/xml",
+        "image/svg+xml",
+        "application/atom+xml",
+    ] {
+        assert!(
+            ContentType::parse(input).unwrap().is_xml_compatible(),
+            "{}",
+            input
+        );
+    }
+    for input in ["application/json", "image/xml", "application/xml-dtd"] {
+        assert!(
+            !ContentType::parse(input).unwrap().is_xml_compatible(),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_content_type_parameter_case_insensitive() {
+    let ct = ContentType::parse("multipart/form-data; BOUNDARY=abc; Charset=UTF-8").unwrap();
+    assert_eq!(ct.boundary(), Some("abc"));
+    assert_eq!(ct.parameter("Boundary"), Some("abc"));
+    assert_eq!(ct.charset(), Some("UTF-8"));
+    assert_eq!(ct.parameter("CHARSET"), Some("UTF-8"));
+    assert_eq!(ct.parameter("missing"), None);
+}
+
 // ========================================
 // エラーケースのテスト
 // ========================================
@@ -0,0 +1,380 @@
+//! `cookie::jar` の `CookieJar` のユニットテスト
+
+use shiguredo_http11::cookie::{CookieJar, SetCookie};
+use shiguredo_http11::{Response, StatusCode};
+
+const NOW: u64 = 1_700_000_000;
+
+fn set_cookie(input: &str) -> SetCookie {
+    SetCookie::parse(input, 2026).unwrap()
+}
+
+// ========================================
+// 保存と送信の基本
+// ========================================
+
+#[test]
+fn test_cookie_jar_empty() {
+    let jar = CookieJar::new();
+    assert!(jar.is_empty());
+    assert_eq!(jar.len(), 0);
+    assert_eq!(jar.cookie_header("https://example.com/", NOW), None);
+}
+
+#[test]
+fn test_cookie_jar_store_and_send() {
+    let mut jar = CookieJar::new();
+    assert!(jar.store("https://example.com/", &set_cookie("a=1"), NOW));
+    assert!(jar.store("https://example.com/", &set_cookie("b=2"), NOW));
+    assert_eq!(jar.len(), 2);
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("a=1; b=2".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_replace_keeps_creation_order() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("a=1"), NOW);
+    jar.store("https://example.com/", &set_cookie("b=2"), NOW);
+    // 同じ name / domain / path は置き換え、作成順は引き継ぐ
+    jar.store("https://example.com/", &set_cookie("a=3"), NOW + 1);
+    assert_eq!(jar.len(), 2);
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW + 1),
+        Some("a=3; b=2".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_invalid_url() {
+    let mut jar = CookieJar::new();
+    assert!(!jar.store("/relative", &set_cookie("a=1"), NOW));
+    assert!(!jar.store("ftp://example.com/", &set_cookie("a=1"), NOW));
+    assert!(jar.is_empty());
+    assert_eq!(jar.cookie_header("not a url", NOW), None);
+}
+
+// ========================================
+// Domain 属性 (RFC 6265 Section 5.3 / 5.1.3)
+// ========================================
+
+#[test]
+fn test_cookie_jar_host_only() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("a=1"), NOW);
+    assert_eq!(
+        jar.cookie_header("https://EXAMPLE.com/", NOW),
+        Some("a=1".to_string())
+    );
+    // host-only Cookie はサブドメインに送信しない
+    assert_eq!(jar.cookie_header("https://www.example.com/", NOW), None);
+}
+
+#[test]
+fn test_cookie_jar_domain_attribute() {
+    let mut jar = CookieJar::new();
+    assert!(jar.store(
+        "https://www.example.com/",
+        &set_cookie("a=1; Domain=example.com"),
+        NOW
+    ));
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("a=1".to_string())
+    );
+    assert_eq!(
+        jar.cookie_header("https://api.example.com/", NOW),
+        Some("a=1".to_string())
+    );
+    assert_eq!(jar.cookie_header("https://badexample.com/", NOW), None);
+    assert_eq!(jar.cookie_header("https://example.org/", NOW), None);
+}
+
+#[test]
+fn test_cookie_jar_domain_mismatch_rejected() {
+    let mut jar = CookieJar::new();
+    assert!(!jar.store(
+        "https://example.com/",
+        &set_cookie("a=1; Domain=other.com"),
+        NOW
+    ));
+    // サブドメインを Domain に指定することはできない
+    assert!(!jar.store(
+        "https://example.com/",
+        &set_cookie("a=1; Domain=www.example.com"),
+        NOW
+    ));
+    assert!(jar.is_empty());
+}
+
+#[test]
+fn test_cookie_jar_ip_address_domain() {
+    let mut jar = CookieJar::new();
+    assert!(jar.store(
+        "http://192.168.0.1/",
+        &set_cookie("a=1; Domain=192.168.0.1"),
+        NOW
+    ));
+    // IP アドレスは末尾一致で domain-match しない
+    assert!(!jar.store(
+        "http://192.168.0.1/",
+        &set_cookie("b=1; Domain=168.0.1"),
+        NOW
+    ));
+    assert_eq!(
+        jar.cookie_header("http://192.168.0.1/", NOW),
+        Some("a=1".to_string())
+    );
+}
+
+// ========================================
+// Path 属性 (RFC 6265 Section 5.1.4)
+// ========================================
+
+#[test]
+fn test_cookie_jar_default_path() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/app/login", &set_cookie("a=1"), NOW);
+    assert_eq!(
+        jar.cookie_header("https://example.com/app", NOW),
+        Some("a=1".to_string())
+    );
+    assert_eq!(
+        jar.cookie_header("https://example.com/app/page", NOW),
+        Some("a=1".to_string())
+    );
+    assert_eq!(jar.cookie_header("https://example.com/", NOW), None);
+    assert_eq!(
+        jar.cookie_header("https://example.com/application", NOW),
+        None
+    );
+
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/login", &set_cookie("a=1"), NOW);
+    assert_eq!(
+        jar.cookie_header("https://example.com/other", NOW),
+        Some("a=1".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_path_attribute_and_order() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("root=1; Path=/"), NOW);
+    jar.store(
+        "https://example.com/",
+        &set_cookie("deep=1; Path=/a/b/"),
+        NOW,
+    );
+    jar.store("https://example.com/", &set_cookie("mid=1; Path=/a"), NOW);
+    // Path が長い順に並べる
+    assert_eq!(
+        jar.cookie_header("https://example.com/a/b/c?x=1", NOW),
+        Some("deep=1; mid=1; root=1".to_string())
+    );
+    assert_eq!(
+        jar.cookie_header("https://example.com/a", NOW),
+        Some("mid=1; root=1".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_same_name_different_path() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("a=root; Path=/"), NOW);
+    jar.store("https://example.com/", &set_cookie("a=app; Path=/app"), NOW);
+    assert_eq!(jar.len(), 2);
+    assert_eq!(
+        jar.cookie_header("https://example.com/app/x", NOW),
+        Some("a=app; a=root".to_string())
+    );
+}
+
+// ========================================
+// Secure 属性とプレフィックス
+// ========================================
+
+#[test]
+fn test_cookie_jar_secure() {
+    let mut jar = CookieJar::new();
+    // 安全でない URL からは Secure Cookie を保存しない
+    assert!(!jar.store("http://example.com/", &set_cookie("a=1; Secure"), NOW));
+    assert!(jar.store("https://example.com/", &set_cookie("a=1; Secure"), NOW));
+    assert_eq!(jar.cookie_header("http://example.com/", NOW), None);
+    assert_eq!(
+        jar.cookie_header("wss://example.com/", NOW),
+        Some("a=1".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_insecure_cannot_overwrite_secure() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("a=secure; Secure"), NOW);
+    assert!(!jar.store("http://example.com/", &set_cookie("a=plain"), NOW));
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("a=secure".to_string())
+    );
+    // 安全な URL からは上書きできる
+    assert!(jar.store("https://example.com/", &set_cookie("a=new; Secure"), NOW));
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("a=new".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_prefixes() {
+    let mut jar = CookieJar::new();
+    assert!(!jar.store("https://example.com/", &set_cookie("__Secure-a=1"), NOW));
+    assert!(!jar.store(
+        "http://example.com/",
+        &set_cookie("__Secure-a=1; Secure"),
+        NOW
+    ));
+    assert!(jar.store(
+        "https://example.com/",
+        &set_cookie("__Secure-a=1; Secure"),
+        NOW
+    ));
+
+    assert!(!jar.store(
+        "https://example.com/",
+        &set_cookie("__Host-b=1; Secure; Path=/; Domain=example.com"),
+        NOW
+    ));
+    assert!(!jar.store(
+        "https://example.com/",
+        &set_cookie("__Host-b=1; Secure"),
+        NOW
+    ));
+    assert!(jar.store(
+        "https://example.com/",
+        &set_cookie("__Host-b=1; Secure; Path=/"),
+        NOW
+    ));
+    assert_eq!(jar.len(), 2);
+}
+
+#[test]
+fn test_cookie_jar_same_site_none_requires_secure() {
+    let mut jar = CookieJar::new();
+    assert!(!jar.store(
+        "https://example.com/",
+        &set_cookie("a=1; SameSite=None"),
+        NOW
+    ));
+    assert!(jar.store(
+        "https://example.com/",
+        &set_cookie("a=1; SameSite=None; Secure"),
+        NOW
+    ));
+}
+
+// ========================================
+// 有効期限 (Max-Age / Expires)
+// ========================================
+
+#[test]
+fn test_cookie_jar_max_age() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("a=1; Max-Age=60"), NOW);
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW + 59),
+        Some("a=1".to_string())
+    );
+    assert_eq!(jar.cookie_header("https://example.com/", NOW + 60), None);
+
+    jar.remove_expired(NOW + 60);
+    assert!(jar.is_empty());
+}
+
+#[test]
+fn test_cookie_jar_max_age_precedes_expires() {
+    let mut jar = CookieJar::new();
+    jar.store(
+        "https://example.com/",
+        &set_cookie("a=1; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Max-Age=60"),
+        NOW,
+    );
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("a=1".to_string())
+    );
+}
+
+#[test]
+fn test_cookie_jar_expires() {
+    // NOW = Tue, 14 Nov 2023 22:13:20 GMT
+    let mut jar = CookieJar::new();
+    jar.store(
+        "https://example.com/",
+        &set_cookie("a=1; Expires=Tue, 14 Nov 2023 22:14:20 GMT"),
+        NOW,
+    );
+    assert!(jar.cookie_header("https://example.com/", NOW).is_some());
+    assert_eq!(jar.cookie_header("https://example.com/", NOW + 60), None);
+}
+
+#[test]
+fn test_cookie_jar_delete_by_expired_cookie() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("a=1"), NOW);
+    assert!(!jar.store("https://example.com/", &set_cookie("a=; Max-Age=0"), NOW));
+    assert!(jar.is_empty());
+
+    jar.store("https://example.com/", &set_cookie("b=1"), NOW);
+    assert!(!jar.store(
+        "https://example.com/",
+        &set_cookie("b=; Expires=Thu, 01 Jan 1970 00:00:00 GMT"),
+        NOW
+    ));
+    assert!(jar.is_empty());
+}
+
+#[test]
+fn test_cookie_jar_clear_session_cookies() {
+    let mut jar = CookieJar::new();
+    jar.store("https://example.com/", &set_cookie("session=1"), NOW);
+    jar.store(
+        "https://example.com/",
+        &set_cookie("persistent=1; Max-Age=3600"),
+        NOW,
+    );
+    jar.clear_session_cookies();
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("persistent=1".to_string())
+    );
+    jar.clear();
+    assert!(jar.is_empty());
+}
+
+// ========================================
+// レスポンスからの保存
+// ========================================
+
+#[test]
+fn test_cookie_jar_store_response() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Set-Cookie", "a=1; Path=/")
+        .unwrap()
+        .header("Set-Cookie", "invalid")
+        .unwrap()
+        .header("set-cookie", "b=2; Secure")
+        .unwrap()
+        .header("Set-Cookie", "c=3; Domain=other.com")
+        .unwrap();
+    let mut jar = CookieJar::new();
+    assert_eq!(
+        jar.store_response("https://example.com/", &response, NOW),
+        2
+    );
+    assert_eq!(
+        jar.cookie_header("https://example.com/", NOW),
+        Some("a=1; b=2".to_string())
+    );
+}
@@ -1,6 +1,12 @@
 //! Cookie のユニットテスト
+//!
+//! Cookie / Set-Cookie ヘッダーのテストはここに置き、
+//! サブモジュールのテストは `src/cookie/` のモジュールごとに分割する。
+//! - `jar`: クライアント向け Cookie ストア `CookieJar`
 
-use shiguredo_http11::cookie::{Cookie, CookieError, SameSite, SetCookie};
+mod jar;
+
+use shiguredo_http11::cookie::{Cookie, CookieError, CookieHeader, SameSite, SetCookie};
 use shiguredo_http11::date::HttpDate;
 
 // ========================================
@@ -16,6 +22,14 @@ fn test_cookie_error_display() {
         (CookieError::InvalidValue, "invalid cookie value"),
         (CookieError::InvalidAttribute, "invalid cookie attribute"),
         (CookieError::InvalidSameSite, "invalid SameSite attribute"),
+        (
+            CookieError::InvalidPrefix,
+            "cookie name prefix requirements not met",
+        ),
+        (
+            CookieError::SecureRequired,
+            "cookie attribute requires Secure",
+        ),
     ];
 
     for (error, expected) in errors {
@@ -433,3 +447,247 @@ fn test_set_cookie_path_empty_is_none() {
     let cookie = SetCookie::parse("name=value; Path=", 2026).unwrap();
     assert!(cookie.path().is_none());
 }
+
+// ========================================
+// Expires の cookie-date パーステスト (RFC 6265 Section 5.1.1)
+// ========================================
+
+#[test]
+fn test_set_cookie_expires_cookie_date_formats() {
+    let expected = HttpDate::parse("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+    for input in [
+        // ブラウザが広く受理する 4 桁年のハイフン区切り
+        "id=a; Expires=Wed, 21-Oct-2015 07:28:00 GMT",
+        // 2 桁年 (70-99 は 1900 年代、0-69 は 2000 年代)
+        "id=a; Expires=Wednesday, 21-Oct-15 07:28:00 GMT",
+        // 曜日なし、月名の大文字小文字混在
+        "id=a; Expires=21 OCT 2015 07:28:00 GMT",
+        // 要素の順序が異なる
+        "id=a; Expires=Oct 21 07:28:00 2015",
+        // 月名は先頭 3 文字で判定する
+        "id=a; Expires=21 October 2015 7:28:0 UTC",
+    ] {
+        let cookie = SetCookie::parse(input, 2026).unwrap();
+        assert_eq!(cookie.expires(), Some(&expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_set_cookie_expires_cookie_date_two_digit_year() {
+    let cookie = SetCookie::parse("id=a; Expires=01-Jan-70 00:00:00 GMT", 2026).unwrap();
+    assert_eq!(cookie.expires().unwrap().year(), 1970);
+    let cookie = SetCookie::parse("id=a; Expires=01 Jan 69 00:00:00 GMT", 2026).unwrap();
+    assert_eq!(cookie.expires().unwrap().year(), 2069);
+}
+
+#[test]
+fn test_set_cookie_expires_cookie_date_day_of_week_computed() {
+    // 曜日のない入力でも日付から曜日を計算する
+    let cookie = SetCookie::parse("id=a; Expires=21 Oct 2015 07:28:00", 2026).unwrap();
+    assert_eq!(
+        cookie.expires().unwrap().to_string(),
+        "Wed, 21 Oct 2015 07:28:00 GMT"
+    );
+}
+
+#[test]
+fn test_set_cookie_expires_cookie_date_invalid_ignored() {
+    for input in [
+        // 年が 1601 より前
+        "id=a; Expires=21-Oct-1600 07:28:00 GMT",
+        // 存在しない日付
+        "id=a; Expires=30-Feb-2015 07:28:00 GMT",
+        // 時刻がない
+        "id=a; Expires=21-Oct-2015",
+        // 時刻の範囲外
+        "id=a; Expires=21-Oct-2015 24:00:00 GMT",
+        "id=a; Expires=21-Oct-2015 23:60:00 GMT",
+        // 月がない
+        "id=a; Expires=21 2015 07:28:00",
+    ] {
+        let cookie = SetCookie::parse(input, 2026).unwrap();
+        assert!(cookie.expires().is_none(), "{}", input);
+    }
+}
+
+// ========================================
+// Partitioned 属性のテスト
+// ========================================
+
+#[test]
+fn test_set_cookie_partitioned() {
+    let cookie = SetCookie::parse("id=a; Secure; Path=/; Partitioned", 2026).unwrap();
+    assert!(cookie.partitioned());
+
+    let cookie = SetCookie::parse("id=a; partitioned", 2026).unwrap();
+    assert!(cookie.partitioned());
+
+    let cookie = SetCookie::parse("id=a", 2026).unwrap();
+    assert!(!cookie.partitioned());
+}
+
+#[test]
+fn test_set_cookie_full_roundtrip() {
+    let cookie = SetCookie::new("__Host-id", "abc")
+        .unwrap()
+        .with_expires(HttpDate::parse("Wed, 21 Oct 2015 07:28:00 GMT").unwrap())
+        .with_max_age(3600)
+        .with_path("/")
+        .with_secure(true)
+        .with_http_only(true)
+        .with_same_site(SameSite::None)
+        .with_partitioned(true);
+    let displayed = cookie.to_string();
+    assert_eq!(
+        displayed,
+        "__Host-id=abc; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Max-Age=3600; Path=/; Secure; HttpOnly; SameSite=None; Partitioned"
+    );
+    assert_eq!(SetCookie::parse(&displayed, 2026).unwrap(), cookie);
+    assert!(cookie.validate().is_ok());
+}
+
+// ========================================
+// SetCookie::validate のテスト
+// ========================================
+
+#[test]
+fn test_set_cookie_validate_secure_prefix() {
+    let cookie = SetCookie::new("__Secure-id", "a").unwrap();
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidPrefix));
+    let cookie = cookie.with_secure(true).with_domain("example.com");
+    assert!(cookie.validate().is_ok());
+
+    // プレフィックスは大文字小文字を区別しない
+    let cookie = SetCookie::new("__SECURE-id", "a").unwrap();
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidPrefix));
+}
+
+#[test]
+fn test_set_cookie_validate_host_prefix() {
+    let base = SetCookie::new("__Host-id", "a").unwrap();
+    assert_eq!(
+        base.clone().with_path("/").validate(),
+        Err(CookieError::InvalidPrefix)
+    );
+    assert_eq!(
+        base.clone().with_secure(true).validate(),
+        Err(CookieError::InvalidPrefix)
+    );
+    assert_eq!(
+        base.clone().with_secure(true).with_path("/app").validate(),
+        Err(CookieError::InvalidPrefix)
+    );
+    assert_eq!(
+        base.clone()
+            .with_secure(true)
+            .with_path("/")
+            .with_domain("example.com")
+            .validate(),
+        Err(CookieError::InvalidPrefix)
+    );
+    assert!(base.with_secure(true).with_path("/").validate().is_ok());
+
+    // パースした Set-Cookie も検証できる
+    let cookie = SetCookie::parse("__host-id=a; Secure; Path=/; Domain=example.com", 2026).unwrap();
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidPrefix));
+}
+
+#[test]
+fn test_set_cookie_validate_secure_required() {
+    let cookie = SetCookie::new("id", "a")
+        .unwrap()
+        .with_same_site(SameSite::None);
+    assert_eq!(cookie.validate(), Err(CookieError::SecureRequired));
+    assert!(cookie.with_secure(true).validate().is_ok());
+
+    let cookie = SetCookie::new("id", "a").unwrap().with_partitioned(true);
+    assert_eq!(cookie.validate(), Err(CookieError::SecureRequired));
+    assert!(cookie.with_secure(true).validate().is_ok());
+
+    let cookie = SetCookie::new("id", "a")
+        .unwrap()
+        .with_same_site(SameSite::Lax);
+    assert!(cookie.validate().is_ok());
+}
+
+#[test]
+fn test_set_cookie_validate_attribute_values() {
+    // Path に ";" や制御文字を含めると別の属性を注入できてしまう
+    let cookie = SetCookie::new("id", "a")
+        .unwrap()
+        .with_path("/; Domain=evil");
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidAttribute));
+    let cookie = SetCookie::new("id", "a").unwrap().with_path("/\r\n");
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidAttribute));
+
+    let cookie = SetCookie::new("id", "a")
+        .unwrap()
+        .with_domain("exa mple.com");
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidAttribute));
+    let cookie = SetCookie::new("id", "a").unwrap().with_domain("");
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidAttribute));
+    let cookie = SetCookie::new("id", "a")
+        .unwrap()
+        .with_domain(".example.com");
+    assert_eq!(cookie.validate(), Err(CookieError::InvalidAttribute));
+}
+
+// ========================================
+// CookieHeader のテスト
+// ========================================
+
+#[test]
+fn test_cookie_header_parse_lenient() {
+    let header = CookieHeader::parse("a=1;; b=2 ;c; =x; d e=3; f=\"4\"; g=bad value;");
+    assert_eq!(header.len(), 3);
+    assert_eq!(header.get("a"), Some("1"));
+    assert_eq!(header.get("b"), Some("2"));
+    assert_eq!(header.get("f"), Some("4"));
+    assert_eq!(header.get("c"), None);
+    assert_eq!(header.get("g"), None);
+}
+
+#[test]
+fn test_cookie_header_parse_empty() {
+    assert!(CookieHeader::parse("").is_empty());
+    assert!(CookieHeader::parse(" ; ;").is_empty());
+}
+
+#[test]
+fn test_cookie_header_get_all_and_case() {
+    let header = CookieHeader::parse("id=deep; id=root; ID=other; empty=");
+    assert_eq!(header.get("id"), Some("deep"));
+    assert_eq!(header.get_all("id"), vec!["deep", "root"]);
+    assert_eq!(header.get("ID"), Some("other"));
+    assert_eq!(header.get("empty"), Some(""));
+    assert!(header.get_all("none").is_empty());
+}
+
+#[test]
+fn test_cookie_header_build() {
+    let mut header = CookieHeader::new()
+        .with_cookie("a", "1")
+        .unwrap()
+        .with_cookie("b", "")
+        .unwrap();
+    header.push(Cookie::new("c", "3").unwrap());
+    assert_eq!(header.to_string(), "a=1; b=; c=3");
+    assert_eq!(header.cookies()[2].name(), "c");
+
+    assert_eq!(CookieHeader::new().to_string(), "");
+    assert_eq!(
+        CookieHeader::new().with_cookie("bad name", "1"),
+        Err(CookieError::InvalidName)
+    );
+    assert_eq!(
+        CookieHeader::new().with_cookie("a", "x;y"),
+        Err(CookieError::InvalidValue)
+    );
+}
+
+#[test]
+fn test_cookie_header_roundtrip() {
+    let header = CookieHeader::parse("a=1;b=2;  c=\"3\"");
+    assert_eq!(header.to_string(), "a=1; b=2; c=3");
+    assert_eq!(CookieHeader::parse(&header.to_string()), header);
+}
@@ -0,0 +1,550 @@
+//! CORS のユニットテスト
+
+use shiguredo_http11::cors::{
+    CorsError, CorsOutcome, CorsPolicy, CorsRejection, Origin, apply_cors, requested_headers,
+    requested_method,
+};
+use shiguredo_http11::method::Method;
+use shiguredo_http11::{Request, RequestDecoder, Response, StatusCode};
+
+fn request(method: &str, headers: &[(&str, &str)]) -> Request {
+    let mut request = Request::new(method, "/resource").unwrap();
+    for (name, value) in headers {
+        request = request.header(*name, *value).unwrap();
+    }
+    request
+}
+
+fn header<'a>(outcome: &'a CorsOutcome, name: &str) -> Option<&'a str> {
+    outcome
+        .headers()
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+// ========================================
+// CorsError のテスト
+// ========================================
+
+#[test]
+fn test_cors_error_display() {
+    let errors = [
+        (CorsError::InvalidOrigin, "invalid Origin"),
+        (CorsError::InvalidMethod, "invalid CORS method"),
+        (CorsError::InvalidHeaderName, "invalid CORS header name"),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+
+    assert_eq!(
+        CorsRejection::HeaderNotAllowed("x-token".to_string()).to_string(),
+        "header not allowed: x-token"
+    );
+}
+
+// ========================================
+// Origin のテスト
+// ========================================
+
+#[test]
+fn test_origin_parse() {
+    let origin = Origin::parse("https://app.example").unwrap();
+    assert_eq!(origin.scheme(), Some("https"));
+    assert_eq!(origin.host(), Some("app.example"));
+    assert_eq!(origin.port(), None);
+    assert_eq!(origin.port_or_default(), Some(443));
+    assert!(!origin.is_null());
+
+    // スキームとホストは小文字に、デフォルトポートは省略する
+    let normalized = Origin::parse("HTTPS://App.Example:443").unwrap();
+    assert_eq!(normalized, origin);
+    assert_eq!(normalized.to_string(), "https://app.example");
+
+    let origin = Origin::parse("http://[::1]:8080").unwrap();
+    assert_eq!(origin.host(), Some("[::1]"));
+    assert_eq!(origin.port(), Some(8080));
+    assert_eq!(origin.to_string(), "http://[::1]:8080");
+
+    let null = Origin::parse("null").unwrap();
+    assert!(null.is_null());
+    assert_eq!(null, Origin::null());
+    assert_eq!(null.to_string(), "null");
+    assert_eq!(null.scheme(), None);
+}
+
+#[test]
+fn test_origin_parse_errors() {
+    let inputs = [
+        "",
+        "NULL",
+        "app.example",
+        "https://",
+        "https://app.example/",
+        "https://app.example?x",
+        "https://user@app.example",
+        "https://app.example:99999",
+        "1https://app.example",
+        "https://a.example https://b.example",
+    ];
+    for input in inputs {
+        assert_eq!(
+            Origin::parse(input),
+            Err(CorsError::InvalidOrigin),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_origin_from_request() {
+    let req = request("GET", &[("Origin", "https://app.example")]);
+    assert_eq!(
+        Origin::from_request(&req),
+        Ok(Some(Origin::parse("https://app.example").unwrap()))
+    );
+    assert_eq!(Origin::from_request(&request("GET", &[])), Ok(None));
+
+    let req = request(
+        "GET",
+        &[
+            ("Origin", "https://a.example"),
+            ("Origin", "https://b.example"),
+        ],
+    );
+    assert_eq!(Origin::from_request(&req), Err(CorsError::InvalidOrigin));
+}
+
+#[test]
+fn test_requested_method_and_headers() {
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Access-Control-Request-Method", "PATCH"),
+            ("Access-Control-Request-Headers", "Content-Type, X-Token"),
+            ("Access-Control-Request-Headers", ", x-trace"),
+        ],
+    );
+    assert_eq!(requested_method(&req), Ok(Some(Method::PATCH)));
+    assert_eq!(
+        requested_headers(&req),
+        Ok(vec![
+            "content-type".to_string(),
+            "x-token".to_string(),
+            "x-trace".to_string()
+        ])
+    );
+
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Access-Control-Request-Method", "PA TCH"),
+            ("Access-Control-Request-Headers", "x token"),
+        ],
+    );
+    assert_eq!(requested_method(&req), Err(CorsError::InvalidMethod));
+    assert_eq!(requested_headers(&req), Err(CorsError::InvalidHeaderName));
+
+    let req = request("OPTIONS", &[]);
+    assert_eq!(requested_method(&req), Ok(None));
+    assert_eq!(requested_headers(&req), Ok(vec![]));
+}
+
+// ========================================
+// ポリシー構築のテスト
+// ========================================
+
+#[test]
+fn test_cors_policy_builder_errors() {
+    assert_eq!(
+        CorsPolicy::new().with_allowed_origin("app.example"),
+        Err(CorsError::InvalidOrigin)
+    );
+    assert_eq!(
+        CorsPolicy::new().with_allowed_method("GET POST"),
+        Err(CorsError::InvalidMethod)
+    );
+    assert_eq!(
+        CorsPolicy::new().with_allowed_header("X Token"),
+        Err(CorsError::InvalidHeaderName)
+    );
+    assert_eq!(
+        CorsPolicy::new().with_exposed_header(""),
+        Err(CorsError::InvalidHeaderName)
+    );
+}
+
+#[test]
+fn test_cors_policy_predicates() {
+    let policy = CorsPolicy::new()
+        .with_allowed_origin("https://app.example")
+        .unwrap()
+        .with_allowed_method("DELETE")
+        .unwrap()
+        .with_allowed_header("X-Token")
+        .unwrap();
+    assert!(policy.is_origin_allowed(&Origin::parse("https://app.example:443").unwrap()));
+    assert!(!policy.is_origin_allowed(&Origin::parse("http://app.example").unwrap()));
+    assert!(!policy.is_origin_allowed(&Origin::null()));
+
+    // CORS-safelisted method は常に許可する
+    assert!(policy.is_method_allowed(&Method::GET));
+    assert!(policy.is_method_allowed(&Method::POST));
+    assert!(policy.is_method_allowed(&Method::DELETE));
+    assert!(!policy.is_method_allowed(&Method::PUT));
+    assert!(!policy.is_method_allowed(&Method::parse("delete").unwrap()));
+
+    assert!(policy.is_header_allowed("x-token"));
+    assert!(!policy.is_header_allowed("authorization"));
+}
+
+// ========================================
+// 実際のリクエストのテスト
+// ========================================
+
+#[test]
+fn test_cors_not_cors() {
+    let policy = CorsPolicy::new().with_any_origin();
+    let outcome = policy.evaluate("GET", &request("GET", &[]));
+    assert_eq!(outcome, CorsOutcome::NotCors);
+    assert!(outcome.headers().is_empty());
+    assert!(outcome.is_allowed());
+}
+
+#[test]
+fn test_cors_actual_any_origin() {
+    let policy = CorsPolicy::new()
+        .with_any_origin()
+        .with_exposed_header("X-Request-Id")
+        .unwrap()
+        .with_exposed_header("ETag")
+        .unwrap();
+    let outcome = policy.evaluate("GET", &request("GET", &[("Origin", "https://a.example")]));
+    assert_eq!(
+        outcome,
+        CorsOutcome::Actual {
+            headers: vec![
+                ("Access-Control-Allow-Origin".to_string(), "*".to_string()),
+                (
+                    "Access-Control-Expose-Headers".to_string(),
+                    "X-Request-Id, ETag".to_string()
+                ),
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_cors_actual_credentials_echoes_origin() {
+    let policy = CorsPolicy::new().with_any_origin().with_credentials(true);
+    let outcome = policy.evaluate(
+        "POST",
+        &request("POST", &[("Origin", "https://A.example:443")]),
+    );
+    assert_eq!(
+        outcome,
+        CorsOutcome::Actual {
+            headers: vec![
+                (
+                    "Access-Control-Allow-Origin".to_string(),
+                    "https://a.example".to_string()
+                ),
+                (
+                    "Access-Control-Allow-Credentials".to_string(),
+                    "true".to_string()
+                ),
+                ("Vary".to_string(), "Origin".to_string()),
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_cors_actual_rejected() {
+    let policy = CorsPolicy::new()
+        .with_allowed_origin("https://app.example")
+        .unwrap();
+
+    let outcome = policy.evaluate(
+        "GET",
+        &request("GET", &[("Origin", "https://evil.example")]),
+    );
+    assert_eq!(
+        outcome,
+        CorsOutcome::Rejected {
+            reason: CorsRejection::OriginNotAllowed,
+            headers: vec![("Vary".to_string(), "Origin".to_string())],
+        }
+    );
+    assert!(!outcome.is_allowed());
+
+    let outcome = policy.evaluate("GET", &request("GET", &[("Origin", "app.example")]));
+    assert!(matches!(
+        outcome,
+        CorsOutcome::Rejected {
+            reason: CorsRejection::InvalidRequest(CorsError::InvalidOrigin),
+            ..
+        }
+    ));
+
+    // null オリジンは明示的に許可した場合のみ許可する
+    let outcome = policy.evaluate("GET", &request("GET", &[("Origin", "null")]));
+    assert!(!outcome.is_allowed());
+    let policy = policy.with_allowed_origin("null").unwrap();
+    let outcome = policy.evaluate("GET", &request("GET", &[("Origin", "null")]));
+    assert_eq!(
+        header(&outcome, "Access-Control-Allow-Origin"),
+        Some("null")
+    );
+}
+
+#[test]
+fn test_cors_options_without_request_method_is_actual() {
+    let policy = CorsPolicy::new().with_any_origin();
+    let outcome = policy.evaluate(
+        "OPTIONS",
+        &request("OPTIONS", &[("Origin", "https://a.example")]),
+    );
+    assert!(matches!(outcome, CorsOutcome::Actual { .. }));
+}
+
+// ========================================
+// プリフライトのテスト
+// ========================================
+
+#[test]
+fn test_cors_preflight_allowed() {
+    let policy = CorsPolicy::new()
+        .with_allowed_origin("https://app.example")
+        .unwrap()
+        .with_allowed_method("PUT")
+        .unwrap()
+        .with_allowed_method("DELETE")
+        .unwrap()
+        .with_allowed_header("Content-Type")
+        .unwrap()
+        .with_allowed_header("X-Token")
+        .unwrap()
+        .with_credentials(true)
+        .with_max_age(7200);
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", "DELETE"),
+            ("Access-Control-Request-Headers", "x-token"),
+        ],
+    );
+    let outcome = policy.evaluate("OPTIONS", &req);
+    assert_eq!(
+        outcome,
+        CorsOutcome::Preflight {
+            headers: vec![
+                (
+                    "Access-Control-Allow-Origin".to_string(),
+                    "https://app.example".to_string()
+                ),
+                (
+                    "Access-Control-Allow-Credentials".to_string(),
+                    "true".to_string()
+                ),
+                (
+                    "Access-Control-Allow-Methods".to_string(),
+                    "PUT, DELETE".to_string()
+                ),
+                (
+                    "Access-Control-Allow-Headers".to_string(),
+                    "Content-Type, X-Token".to_string()
+                ),
+                ("Access-Control-Max-Age".to_string(), "7200".to_string()),
+                ("Vary".to_string(), "Origin".to_string()),
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_cors_preflight_any_method_and_header_echo() {
+    let policy = CorsPolicy::new()
+        .with_any_origin()
+        .with_any_method()
+        .with_any_header();
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", "PURGE"),
+            ("Access-Control-Request-Headers", "Authorization, X-Token"),
+        ],
+    );
+    let outcome = policy.evaluate("OPTIONS", &req);
+    assert_eq!(header(&outcome, "Access-Control-Allow-Origin"), Some("*"));
+    assert_eq!(
+        header(&outcome, "Access-Control-Allow-Methods"),
+        Some("PURGE")
+    );
+    assert_eq!(
+        header(&outcome, "Access-Control-Allow-Headers"),
+        Some("authorization, x-token")
+    );
+    assert_eq!(
+        header(&outcome, "Vary"),
+        Some("Access-Control-Request-Method, Access-Control-Request-Headers")
+    );
+    assert_eq!(header(&outcome, "Access-Control-Max-Age"), None);
+}
+
+#[test]
+fn test_cors_preflight_safelisted_method_without_list() {
+    let policy = CorsPolicy::new().with_any_origin();
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", "GET"),
+        ],
+    );
+    let outcome = policy.evaluate("OPTIONS", &req);
+    assert!(matches!(outcome, CorsOutcome::Preflight { .. }));
+    assert_eq!(header(&outcome, "Access-Control-Allow-Methods"), None);
+    assert_eq!(header(&outcome, "Access-Control-Allow-Headers"), None);
+}
+
+#[test]
+fn test_cors_preflight_rejected() {
+    let policy = CorsPolicy::new()
+        .with_allowed_origin("https://app.example")
+        .unwrap()
+        .with_allowed_header("Content-Type")
+        .unwrap();
+
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", "PUT"),
+        ],
+    );
+    assert_eq!(
+        policy.evaluate("OPTIONS", &req),
+        CorsOutcome::Rejected {
+            reason: CorsRejection::MethodNotAllowed("PUT".to_string()),
+            headers: vec![("Vary".to_string(), "Origin".to_string())],
+        }
+    );
+
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", "POST"),
+            ("Access-Control-Request-Headers", "content-type, x-token"),
+        ],
+    );
+    assert!(matches!(
+        policy.evaluate("OPTIONS", &req),
+        CorsOutcome::Rejected {
+            reason: CorsRejection::HeaderNotAllowed(name),
+            ..
+        } if name == "x-token"
+    ));
+
+    let req = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", ""),
+        ],
+    );
+    assert!(matches!(
+        policy.evaluate("OPTIONS", &req),
+        CorsOutcome::Rejected {
+            reason: CorsRejection::InvalidRequest(CorsError::InvalidMethod),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cors_evaluate_request_head() {
+    let policy = CorsPolicy::new().with_any_origin();
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(
+            b"OPTIONS /r HTTP/1.1\r\nHost: api.example\r\nOrigin: https://app.example\r\n\
+              Access-Control-Request-Method: GET\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let outcome = policy.evaluate(head.method(), &head);
+    assert!(matches!(outcome, CorsOutcome::Preflight { .. }));
+}
+
+// ========================================
+// レスポンス生成のテスト
+// ========================================
+
+#[test]
+fn test_response_cors_preflight() {
+    let policy = CorsPolicy::new()
+        .with_allowed_origin("https://app.example")
+        .unwrap();
+    let allowed = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://app.example"),
+            ("Access-Control-Request-Method", "GET"),
+        ],
+    );
+    let response = Response::cors_preflight(&policy.evaluate("OPTIONS", &allowed)).unwrap();
+    assert_eq!(response.status_code(), 204);
+    assert_eq!(
+        response.get_header("Access-Control-Allow-Origin"),
+        Some("https://app.example")
+    );
+
+    let rejected = request(
+        "OPTIONS",
+        &[
+            ("Origin", "https://evil.example"),
+            ("Access-Control-Request-Method", "GET"),
+        ],
+    );
+    let response = Response::cors_preflight(&policy.evaluate("OPTIONS", &rejected)).unwrap();
+    assert_eq!(response.status_code(), 403);
+    assert!(!response.has_header("Access-Control-Allow-Origin"));
+    assert_eq!(response.get_header("Vary"), Some("Origin"));
+}
+
+#[test]
+fn test_apply_cors_merges_vary() {
+    let policy = CorsPolicy::new()
+        .with_allowed_origin("https://app.example")
+        .unwrap();
+    let outcome = policy.evaluate("GET", &request("GET", &[("Origin", "https://app.example")]));
+
+    let response = Response::with_status(StatusCode::OK)
+        .header("Vary", "accept-encoding")
+        .unwrap()
+        .header("Vary", "origin")
+        .unwrap()
+        .header("Access-Control-Allow-Origin", "https://old.example")
+        .unwrap();
+    let response = apply_cors(response, &outcome).unwrap();
+    assert_eq!(response.get_headers("Vary"), ["accept-encoding, origin"]);
+    assert_eq!(
+        response.get_headers("Access-Control-Allow-Origin"),
+        ["https://app.example"]
+    );
+
+    let response = Response::with_status(StatusCode::OK)
+        .header("Vary", "*")
+        .unwrap();
+    let response = apply_cors(response, &outcome).unwrap();
+    assert_eq!(response.get_header("Vary"), Some("*"));
+
+    let response =
+        apply_cors(Response::with_status(StatusCode::OK), &CorsOutcome::NotCors).unwrap();
+    assert!(!response.has_header("Vary"));
+}
@@ -257,3 +257,126 @@ fn test_date_rfc850_4digit_year() {
     let date = HttpDate::parse_rfc850("Sunday, 06-Nov-1994 08:49:37 GMT", 2026).unwrap();
     assert_eq!(date.year(), 1994);
 }
+
+// ========================================
+// UNIX 時間との変換のテスト
+// ========================================
+
+#[test]
+fn test_unix_seconds_epoch() {
+    let date = HttpDate::from_unix_seconds(0).unwrap();
+    assert_eq!(date.to_string(), "Thu, 01 Jan 1970 00:00:00 GMT");
+    assert_eq!(date.unix_seconds(), 0);
+}
+
+#[test]
+fn test_unix_seconds_known_dates() {
+    let date = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    assert_eq!(date.unix_seconds(), 784111777);
+    assert_eq!(HttpDate::from_unix_seconds(784111777).unwrap(), date);
+
+    // うるう年の 2 月 29 日
+    let date = HttpDate::from_unix_seconds(951782400).unwrap();
+    assert_eq!(date.to_string(), "Tue, 29 Feb 2000 00:00:00 GMT");
+
+    let date = HttpDate::from_unix_seconds(253402300799).unwrap();
+    assert_eq!(date.to_string(), "Fri, 31 Dec 9999 23:59:59 GMT");
+}
+
+#[test]
+fn test_unix_seconds_out_of_range() {
+    assert_eq!(
+        HttpDate::from_unix_seconds(253402300800),
+        Err(DateError::InvalidYear)
+    );
+    // 1970 年より前は 0 になる
+    let date = HttpDate::parse("Wed, 31 Dec 1969 23:59:59 GMT").unwrap();
+    assert_eq!(date.unix_seconds(), 0);
+}
+
+#[test]
+fn test_unix_seconds_leap_second() {
+    let date = HttpDate::parse("Sat, 31 Dec 2016 23:59:60 GMT").unwrap();
+    assert_eq!(date.unix_seconds(), 1483228800);
+}
+
+// ========================================
+// 3 形式のパースのテスト
+// ========================================
+
+#[test]
+fn test_parse_any_all_formats() {
+    let expected = HttpDate::parse("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    for input in [
+        "Sun, 06 Nov 1994 08:49:37 GMT",
+        "Sunday, 06-Nov-94 08:49:37 GMT",
+        "Sun Nov  6 08:49:37 1994",
+    ] {
+        assert_eq!(
+            HttpDate::parse_any(input, 2026).unwrap(),
+            expected,
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_parse_any_rfc850_reference_year() {
+    // 基準年 + 50 年より未来に見える 2 桁年は過去として解釈する
+    let date = HttpDate::parse_any("Sunday, 06-Nov-94 08:49:37 GMT", 2026).unwrap();
+    assert_eq!(date.year(), 1994);
+    let date = HttpDate::parse_any("Thursday, 06-Nov-25 08:49:37 GMT", 2026).unwrap();
+    assert_eq!(date.year(), 2025);
+}
+
+#[test]
+fn test_parse_any_errors() {
+    assert_eq!(HttpDate::parse_any("", 2026), Err(DateError::Empty));
+    assert!(HttpDate::parse_any("Sun, 06 Nov 1994 08:49:37 JST", 2026).is_err());
+    assert!(HttpDate::parse_any("Sunday, 06-Foo-94 08:49:37 GMT", 2026).is_err());
+    assert!(HttpDate::parse_any("not a date", 2026).is_err());
+}
+
+// ========================================
+// SystemTime との相互変換のテスト
+// ========================================
+
+#[cfg(feature = "std")]
+#[test]
+fn test_system_time_roundtrip() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let time = UNIX_EPOCH + Duration::from_secs(784111777);
+    let date = HttpDate::from_system_time(time).unwrap();
+    assert_eq!(date.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+    assert_eq!(date.to_system_time(), time);
+
+    // 秒未満は切り捨てる
+    let date = HttpDate::from_system_time(time + Duration::from_millis(999)).unwrap();
+    assert_eq!(date.to_system_time(), time);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_system_time_out_of_range() {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    assert_eq!(
+        HttpDate::from_system_time(UNIX_EPOCH - Duration::from_secs(1)),
+        Err(DateError::InvalidYear)
+    );
+    // 1970 年より前は UNIX_EPOCH になる
+    let date = HttpDate::parse("Sun, 06 Nov 1960 08:49:37 GMT").unwrap();
+    assert_eq!(date.to_system_time(), UNIX_EPOCH);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_system_time_now_formats_imf_fixdate() {
+    let date = HttpDate::from_system_time(std::time::SystemTime::now()).unwrap();
+    let formatted = date.to_string();
+    assert_eq!(formatted.len(), 29);
+    assert!(formatted.ends_with(" GMT"));
+    assert_eq!(HttpDate::parse(&formatted).unwrap(), date);
+}
@@ -0,0 +1,131 @@
+//! Keep-Alive 接続でのメモリ確保回数のテスト
+//!
+//! - デコード済みのメッセージを `recycle_head` / `recycle_request` / `recycle_response` で
+//!   返却し続けると、定常状態ではメッセージごとのメモリ確保が 0 回になる
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use shiguredo_http11::{HttpHead, RequestDecoder, ResponseDecoder};
+
+/// メモリ確保回数をスレッドごとに数えるアロケーター
+///
+/// テストは並列に実行されるため、他のテストのメモリ確保を数えないようスレッドローカルで数える。
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<u64> = const { Cell::new(0) };
+}
+
+fn count_allocation() {
+    // スレッド終了処理中はスレッドローカルにアクセスできないため数えない
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// 記憶領域が行き渡るまでにデコードするメッセージ数
+const WARM_UP: usize = 3;
+
+/// メモリ確保回数を数えるメッセージ数
+const MESSAGES: usize = 100;
+
+const REQUEST: &[u8] = b"GET /index.html?lang=ja HTTP/1.1\r\n\
+Host: example.com\r\n\
+User-Agent: test/1.0\r\n\
+Accept: text/html,application/xhtml+xml\r\n\
+Accept-Language: ja,en;q=0.8\r\n\
+Accept-Encoding: gzip, deflate\r\n\
+Cache-Control: no-cache\r\n\
+Connection: keep-alive\r\n\
+Cookie: session=abcdef0123456789\r\n\
+X-Request-Id: 0123456789\r\n\
+\r\n";
+
+const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\n\
+Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+Server: test/1.0\r\n\
+Content-Type: text/html; charset=utf-8\r\n\
+Cache-Control: max-age=60\r\n\
+ETag: \"abcdef\"\r\n\
+X-Response-Id: 0123456789\r\n\
+Content-Length: 5\r\n\
+\r\n\
+hello";
+
+/// `f` を `WARM_UP` 回呼んだ後、`MESSAGES` 回呼ぶ間のメモリ確保回数を返す
+fn allocations_after_warm_up(mut f: impl FnMut()) -> u64 {
+    for _ in 0..WARM_UP {
+        f();
+    }
+    let before = ALLOCATIONS.with(Cell::get);
+    for _ in 0..MESSAGES {
+        f();
+    }
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn test_request_decode_headers_recycle_head_does_not_allocate() {
+    let mut decoder = RequestDecoder::new();
+    let allocations = allocations_after_warm_up(|| {
+        decoder.feed(REQUEST).unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        assert_eq!(head.get_header("X-Request-Id"), Some("0123456789"));
+        decoder.recycle_head(head);
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_request_decode_recycle_request_does_not_allocate() {
+    let mut decoder = RequestDecoder::new();
+    let allocations = allocations_after_warm_up(|| {
+        decoder.feed(REQUEST).unwrap();
+        let request = decoder.decode().unwrap().unwrap();
+        assert_eq!(request.uri(), "/index.html?lang=ja");
+        decoder.recycle_request(request);
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn test_response_decode_recycle_response_does_not_allocate() {
+    let mut decoder = ResponseDecoder::new();
+    let allocations = allocations_after_warm_up(|| {
+        decoder.feed(RESPONSE).unwrap();
+        let response = decoder.decode().unwrap().unwrap();
+        assert_eq!(response.body_bytes(), Some(&b"hello"[..]));
+        decoder.recycle_response(response);
+    });
+    assert_eq!(allocations, 0);
+}
+
+/// 返却しない場合は受信したメッセージごとに記憶領域を確保する
+#[test]
+fn test_response_decode_without_recycle_allocates() {
+    let mut decoder = ResponseDecoder::new();
+    let allocations = allocations_after_warm_up(|| {
+        decoder.feed(RESPONSE).unwrap();
+        let response = decoder.decode().unwrap().unwrap();
+        assert_eq!(response.status_code(), 200);
+    });
+    assert!(allocations >= MESSAGES as u64);
+}
@@ -8,8 +8,11 @@
 //! - chunked トレーラー (禁止フィールド、ホワイトリスト、サイズ / 行長制限)
 //! - chunked データ後の CRLF 分割到着
 //! - `peek_body_decompressed` の挙動
+//! - chunked の構文エラーの位置情報 (`Error::Syntax`)
 
-use shiguredo_http11::{BodyKind, BodyProgress, DecoderLimits, RequestDecoder, ResponseDecoder};
+use shiguredo_http11::{
+    BodyKind, BodyProgress, DecoderLimits, Error, ParsePhase, RequestDecoder, ResponseDecoder,
+};
 
 // ========================================
 // RFC 9112 Section 6.3 準拠テスト
@@ -726,3 +729,89 @@ mod peek_body_decompressed {
         assert!(result.is_none(), "進展なしのときは None になるべき");
     }
 }
+
+// ========================================
+// chunked 構文エラーの位置情報
+// ========================================
+
+/// 不正なチャンクサイズ行は ChunkSize 段階で、その行の絶対オフセットと共に報告される
+#[test]
+fn test_chunk_size_syntax_error_context() {
+    let head = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(head).unwrap();
+    decoder.feed(b"3\r\nabc\r\nzz\r\n").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let err = loop {
+        if let Some(data) = decoder.peek_body() {
+            let len = data.len();
+            if let Err(e) = decoder.consume_body(len) {
+                break e;
+            }
+            continue;
+        }
+        if let Err(e) = decoder.progress() {
+            break e;
+        }
+    };
+    match err {
+        Error::Syntax {
+            phase,
+            offset,
+            line,
+            ..
+        } => {
+            assert_eq!(phase, ParsePhase::ChunkSize);
+            assert_eq!(offset, head.len() as u64 + 8);
+            assert_eq!(line, "zz");
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+/// チャンクデータ直後に CRLF がない場合は ChunkData 段階で報告される
+#[test]
+fn test_chunk_data_crlf_syntax_error_context() {
+    let head = b"POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(head).unwrap();
+    decoder.feed(b"3\r\nabcXY").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    decoder.progress().unwrap();
+    let err = decoder.consume_body(3).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Syntax {
+            phase: ParsePhase::ChunkData,
+            ..
+        }
+    ));
+    if let Error::Syntax { offset, .. } = err {
+        assert_eq!(offset, head.len() as u64 + 6);
+    }
+}
+
+/// 申告外のトレーラーは Trailer 段階で、その行の絶対オフセットと共に報告される
+#[test]
+fn test_trailer_syntax_error_context() {
+    let head = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(head).unwrap();
+    decoder.feed(b"0\r\nX-Undeclared: 1\r\n\r\n").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    match decoder.progress() {
+        Err(Error::Syntax {
+            phase,
+            offset,
+            line,
+            reason,
+        }) => {
+            assert_eq!(phase, ParsePhase::Trailer);
+            assert_eq!(offset, head.len() as u64 + 3);
+            assert_eq!(line, "X-Undeclared: 1");
+            assert!(reason.contains("undeclared trailer field"));
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
@@ -0,0 +1,156 @@
+//! chunked ボディのフレーミング保持のテスト
+//!
+//! - `take_chunk_framing()` と `peek_body()` のデータの連結が元のボディと一致すること
+//! - `chunk_ext()` による chunk-ext の取得
+//! - 分割到着時と `reset()` 後の挙動
+
+use shiguredo_http11::{BodyProgress, RequestDecoder, ResponseDecoder, encode_chunk_with_ext};
+
+const HEAD: &[u8] =
+    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: X-Checksum, X-Other\r\n\r\n";
+
+/// `split` バイトずつ投入しながら、フレーミングとデータを受信順に連結する
+fn passthrough(body: &[u8], split: usize) -> (Vec<u8>, Vec<(usize, Vec<u8>)>) {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_preserve_chunk_framing(true);
+    decoder.feed(HEAD).unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let mut output = Vec::new();
+    let mut exts = Vec::new();
+    let mut parts = body.chunks(split);
+    loop {
+        output.extend_from_slice(&decoder.take_chunk_framing());
+        if let Some(data) = decoder.peek_body() {
+            let len = data.len();
+            output.extend_from_slice(data);
+            exts.push((len, decoder.chunk_ext().unwrap_or_default().to_vec()));
+            if let BodyProgress::Complete { .. } = decoder.consume_body(len).unwrap() {
+                break;
+            }
+            continue;
+        }
+        match decoder.progress().unwrap() {
+            BodyProgress::Complete { .. } => break,
+            BodyProgress::Advanced => {}
+            BodyProgress::NeedData => decoder.feed(parts.next().unwrap()).unwrap(),
+        }
+    }
+    output.extend_from_slice(&decoder.take_chunk_framing());
+    (output, exts)
+}
+
+#[test]
+fn test_chunk_framing_default_disabled() {
+    let mut decoder = ResponseDecoder::new();
+    assert!(!decoder.preserve_chunk_framing());
+    decoder.feed(HEAD).unwrap();
+    decoder.feed(b"5;a=b\r\nhello\r\n0\r\n\r\n").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    decoder.progress().unwrap();
+    decoder.consume_body(5).unwrap();
+    assert!(decoder.take_chunk_framing().is_empty());
+    assert_eq!(decoder.chunk_ext(), None);
+}
+
+#[test]
+fn test_chunk_framing_byte_identical() {
+    let body = b"5;name=\"v a\"\r\nhello\r\nA\r\n0123456789\r\n0 ;last\r\nX-Checksum: abc\r\nX-Other:  1 \r\n\r\n";
+    for split in [1, 2, 7, body.len()] {
+        let (output, exts) = passthrough(body, split);
+        assert_eq!(output, body, "split={split}");
+        // 分割されたデータも属するチャンクの chunk-ext を返す
+        let mut offset = 0;
+        for (len, ext) in exts {
+            let expected: &[u8] = if offset < 5 { b";name=\"v a\"" } else { b"" };
+            assert_eq!(ext, expected, "split={split} offset={offset}");
+            offset += len;
+        }
+        assert_eq!(offset, 15);
+    }
+}
+
+#[test]
+fn test_chunk_framing_chunk_ext() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_preserve_chunk_framing(true);
+    decoder.feed(HEAD).unwrap();
+    decoder
+        .feed(b"3 ; x\r\nabc\r\n2\r\nde\r\n0;end=1\r\n\r\n")
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    decoder.progress().unwrap();
+    assert_eq!(decoder.chunk_ext(), Some(&b" ; x"[..]));
+    assert_eq!(decoder.take_chunk_framing(), b"3 ; x\r\n");
+    decoder.consume_body(3).unwrap();
+    assert_eq!(decoder.take_chunk_framing(), b"\r\n");
+
+    decoder.progress().unwrap();
+    assert_eq!(decoder.chunk_ext(), None);
+    decoder.consume_body(2).unwrap();
+
+    assert!(matches!(
+        decoder.progress().unwrap(),
+        BodyProgress::Complete { .. }
+    ));
+    assert_eq!(decoder.chunk_ext(), Some(&b";end=1"[..]));
+    assert_eq!(decoder.take_chunk_framing(), b"2\r\n\r\n0;end=1\r\n\r\n");
+}
+
+#[test]
+fn test_chunk_framing_request_and_reset() {
+    let message =
+        b"POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n1;a\r\nx\r\n0\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.set_preserve_chunk_framing(true);
+    decoder.feed(message).unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    decoder.progress().unwrap();
+    assert_eq!(decoder.chunk_ext(), Some(&b";a"[..]));
+    decoder.consume_body(1).unwrap();
+    assert!(matches!(
+        decoder.progress().unwrap(),
+        BodyProgress::Complete { .. }
+    ));
+    assert_eq!(decoder.take_chunk_framing(), b"1;a\r\n\r\n0\r\n\r\n");
+
+    // reset() で記録は破棄するが、設定は維持する
+    decoder.feed(message).unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    decoder.progress().unwrap();
+    decoder.reset();
+    assert!(decoder.preserve_chunk_framing());
+    assert!(decoder.take_chunk_framing().is_empty());
+    assert_eq!(decoder.chunk_ext(), None);
+
+    // decode() はボディをすべて読むため記録を残さない
+    decoder.feed(message).unwrap();
+    decoder.decode().unwrap().unwrap();
+    assert!(decoder.take_chunk_framing().is_empty());
+
+    decoder.set_preserve_chunk_framing(false);
+    assert!(!decoder.preserve_chunk_framing());
+}
+
+#[test]
+fn test_chunk_framing_reencode_with_ext() {
+    // chunk-ext のみ保持して再エンコードしても同じチャンクになる
+    let body = b"4;sig=abc\r\ndata\r\n0\r\n\r\n";
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_preserve_chunk_framing(true);
+    decoder.feed(HEAD).unwrap();
+    decoder.feed(body).unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    decoder.progress().unwrap();
+
+    let data = decoder.peek_body().unwrap();
+    let ext = decoder.chunk_ext().unwrap_or_default();
+    let mut output = encode_chunk_with_ext(data, ext).unwrap();
+    decoder.consume_body(4).unwrap();
+    decoder.progress().unwrap();
+    output.extend_from_slice(
+        &encode_chunk_with_ext(b"", decoder.chunk_ext().unwrap_or_default()).unwrap(),
+    );
+    assert_eq!(output, body);
+}
@@ -7,8 +7,16 @@
 //! - ヘッダー値の制御文字拒否
 //! - Content-Length パース (Unicode 空白拒否、`HttpHead::content_length` 厳格パース)
 //! - `RequestDecoder::default()` / `ResponseDecoder::default()` の挙動
+//! - デコード済みの `RequestHead` / `ResponseHead` のヘッダー操作
+//! - デコード済みの `RequestHead` / `ResponseHead` の Connection / Transfer-Encoding 判定
+//! - よく使われるヘッダー名のインターン
 
-use shiguredo_http11::{RequestDecoder, ResponseDecoder};
+use std::borrow::Cow;
+
+use shiguredo_http11::{
+    DecoderLimits, EncodeError, Error, HttpHead, ParsePhase, Request, RequestDecoder, Response,
+    ResponseDecoder, StatusCode,
+};
 
 // ========================================
 // ヘッダー値の制御文字エラーのテスト
@@ -311,7 +319,7 @@ fn test_request_http_with_double_slash_ok() {
 // ========================================
 
 mod http_head_content_length {
-    use shiguredo_http11::{Error, Request, Response};
+    use shiguredo_http11::{Error, Request, RequestHead, Response, ResponseHead};
 
     fn make_request_with_cl(values: &[&str]) -> Request {
         let mut req = Request::new("POST", "/").unwrap();
@@ -417,4 +425,441 @@ mod http_head_content_length {
         let res = make_response_with_cl(&[" 100 "]);
         assert_eq!(res.content_length().unwrap(), Some(100));
     }
+
+    #[test]
+    fn test_head_content_length_same_as_message() {
+        // RequestHead / ResponseHead も Request / Response と同じ検証を行う
+        for (values, expected) in [
+            (&["100"][..], Some(Some(100))),
+            (&["100", "100"][..], Some(Some(100))),
+            (&["100", "101"][..], None),
+            (&["100, 101"][..], None),
+            (&["1e2"][..], None),
+            (&["-1"][..], None),
+            (&[][..], Some(None)),
+        ] {
+            let mut request_head = RequestHead::new("POST", "/").unwrap();
+            let mut response_head = ResponseHead::new(200, "OK").unwrap();
+            for v in values {
+                request_head.add_header("Content-Length", v).unwrap();
+                response_head.add_header("Content-Length", v).unwrap();
+            }
+            let request = make_request_with_cl(values);
+            let response = make_response_with_cl(values);
+            for actual in [
+                request_head.content_length(),
+                response_head.content_length(),
+                request.content_length(),
+                response.content_length(),
+            ] {
+                match expected {
+                    Some(length) => assert_eq!(actual.unwrap(), length, "{values:?}"),
+                    None => assert!(matches!(actual, Err(Error::InvalidData(_))), "{values:?}"),
+                }
+            }
+        }
+    }
+}
+
+// ========================================
+// Error::Syntax の位置情報 / suggested_status のテスト
+// ========================================
+
+/// 不正なリクエスト行は StartLine 段階、オフセット 0 で報告される
+#[test]
+fn test_syntax_error_start_line_context() {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(b"GET /\r\nHost: a\r\n\r\n").unwrap();
+    match decoder.decode_headers() {
+        Err(Error::Syntax {
+            phase,
+            offset,
+            line,
+            ..
+        }) => {
+            assert_eq!(phase, ParsePhase::StartLine);
+            assert_eq!(offset, 0);
+            assert_eq!(line, "GET /");
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+/// 不正なステータス行は StartLine 段階で報告される
+#[test]
+fn test_syntax_error_status_line_context() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(b"HTTP/1.1 999 Bad\r\n\r\n").unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Syntax {
+            phase: ParsePhase::StartLine,
+            offset: 0,
+            ..
+        }
+    ));
+    assert_eq!(err.suggested_status(), StatusCode::BAD_REQUEST);
+}
+
+/// 長い行は 64 バイトで切り詰められ "..." が付与される
+#[test]
+fn test_syntax_error_line_truncated() {
+    let long_target = "/".repeat(100);
+    let data = format!("GET {} HTTP/1.1 extra\r\n\r\n", long_target);
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(data.as_bytes()).unwrap();
+    match decoder.decode_headers() {
+        Err(Error::Syntax { line, .. }) => {
+            assert_eq!(line.len(), 64 + 3);
+            assert!(line.ends_with("..."));
+            assert!(data.starts_with(&line[..64]));
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+/// 非 UTF-8 の行は置換文字で保持される
+#[test]
+fn test_syntax_error_invalid_utf8_line() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nX-Bad: \xff\r\n\r\n")
+        .unwrap();
+    match decoder.decode_headers() {
+        Err(Error::Syntax {
+            phase,
+            offset,
+            line,
+            ..
+        }) => {
+            assert_eq!(phase, ParsePhase::Header);
+            assert_eq!(offset, 25);
+            assert_eq!(line, "X-Bad: \u{FFFD}");
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+/// ヘッダーセクション全体の検証エラー (Host 欠落) は終端の空行の位置で行なしで報告される
+#[test]
+fn test_syntax_error_missing_host_context() {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(b"GET / HTTP/1.1\r\nX-A: b\r\n\r\n").unwrap();
+    match decoder.decode_headers() {
+        Err(Error::Syntax {
+            phase,
+            offset,
+            line,
+            ..
+        }) => {
+            assert_eq!(phase, ParsePhase::Header);
+            assert_eq!(offset, 24);
+            assert!(line.is_empty());
+        }
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+/// Keep-Alive で 2 つ目のメッセージのオフセットは 1 つ目の続きから数え、reset() で 0 に戻る
+#[test]
+fn test_syntax_error_offset_across_messages_and_reset() {
+    let first = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(first).unwrap();
+    decoder.feed(b"BAD\r\n").unwrap();
+    assert!(decoder.decode().unwrap().is_some());
+    match decoder.decode_headers() {
+        Err(Error::Syntax { offset, .. }) => assert_eq!(offset, first.len() as u64),
+        other => panic!("unexpected: {other:?}"),
+    }
+
+    decoder.reset();
+    decoder.feed(b"BAD\r\n").unwrap();
+    match decoder.decode_headers() {
+        Err(Error::Syntax { offset, .. }) => assert_eq!(offset, 0),
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+/// 上限超過エラーの suggested_status
+#[test]
+fn test_suggested_status_for_limits() {
+    let limits = DecoderLimits {
+        max_headers_count: 1,
+        ..DecoderLimits::default()
+    };
+    let mut decoder = RequestDecoder::with_limits(limits);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nX-A: b\r\n\r\n")
+        .unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert!(matches!(err, Error::TooManyHeaders { .. }));
+    assert_eq!(
+        err.suggested_status(),
+        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+    );
+
+    let limits = DecoderLimits {
+        max_body_size: 4,
+        ..DecoderLimits::default()
+    };
+    let mut decoder = RequestDecoder::with_limits(limits);
+    decoder
+        .feed(b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\n\r\n")
+        .unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert_eq!(err.suggested_status(), StatusCode::CONTENT_TOO_LARGE);
+
+    let err = Error::BufferOverflow { size: 2, limit: 1 };
+    assert_eq!(
+        err.suggested_status(),
+        StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+    );
+}
+
+/// Display に段階・オフセット・行が含まれる
+#[test]
+fn test_syntax_error_display() {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(b"GET /\r\n").unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.starts_with("syntax error in start-line at offset 0: "));
+    assert!(msg.ends_with("(line: \"GET /\")"));
+}
+
+// ========================================
+// max_uri_length / max_method_length のテスト
+// ========================================
+
+/// request-target が上限を超えると UriTooLong (414)
+#[test]
+fn test_uri_too_long() {
+    let limits = DecoderLimits {
+        max_uri_length: 16,
+        ..DecoderLimits::default()
+    };
+    let mut decoder = RequestDecoder::with_limits(limits);
+    let data = format!("GET /{} HTTP/1.1\r\nHost: a\r\n\r\n", "a".repeat(16));
+    decoder.feed(data.as_bytes()).unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert_eq!(
+        err,
+        Error::UriTooLong {
+            size: 17,
+            limit: 16
+        }
+    );
+    assert_eq!(err.suggested_status(), StatusCode::URI_TOO_LONG);
+}
+
+/// 上限ちょうどの request-target は受け付ける
+#[test]
+fn test_uri_at_limit() {
+    let limits = DecoderLimits {
+        max_uri_length: 16,
+        ..DecoderLimits::default()
+    };
+    let mut decoder = RequestDecoder::with_limits(limits);
+    let data = format!("GET /{} HTTP/1.1\r\nHost: a\r\n\r\n", "a".repeat(15));
+    decoder.feed(data.as_bytes()).unwrap();
+    assert!(decoder.decode_headers().unwrap().is_some());
+}
+
+/// CRLF の受信前でも上限超過が確定した時点でエラーにする
+#[test]
+fn test_uri_too_long_before_crlf() {
+    let limits = DecoderLimits {
+        max_uri_length: 16,
+        ..DecoderLimits::default()
+    };
+    let mut decoder = RequestDecoder::with_limits(limits);
+    decoder.feed(b"GET /").unwrap();
+    assert_eq!(decoder.decode_headers().unwrap(), None);
+    decoder.feed(&[b'a'; 16]).unwrap();
+    assert!(matches!(
+        decoder.decode_headers(),
+        Err(Error::UriTooLong { limit: 16, .. })
+    ));
+}
+
+/// メソッドが上限を超えると MethodTooLong (501)
+#[test]
+fn test_method_too_long() {
+    let limits = DecoderLimits {
+        max_method_length: 8,
+        ..DecoderLimits::default()
+    };
+    let mut decoder = RequestDecoder::with_limits(limits.clone());
+    decoder.feed(b"PROPPATCH / HTTP/1.1\r\n").unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert_eq!(err, Error::MethodTooLong { size: 9, limit: 8 });
+    assert_eq!(err.suggested_status(), StatusCode::NOT_IMPLEMENTED);
+
+    // SP を受信する前でも上限超過が確定した時点でエラーにする
+    let mut decoder = RequestDecoder::with_limits(limits);
+    decoder.feed(b"AAAAAAAAA").unwrap();
+    assert!(matches!(
+        decoder.decode_headers(),
+        Err(Error::MethodTooLong { .. })
+    ));
+}
+
+// ========================================
+// デコード済みヘッドのヘッダー操作のテスト
+// ========================================
+
+#[test]
+fn test_request_head_set_and_remove_header() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nX-Forwarded-For: 1.1.1.1\r\nx-forwarded-for: 2.2.2.2\r\nAccept: */*\r\n\r\n")
+        .unwrap();
+    let (mut head, _) = decoder.decode_headers().unwrap().unwrap();
+
+    head.set_header("Host", "b").unwrap();
+    head.remove_header("X-FORWARDED-FOR");
+    assert_eq!(head.get_headers("Host"), vec!["b"]);
+    assert!(!head.has_header("X-Forwarded-For"));
+    let names: Vec<&str> = head.headers().iter().map(|(n, _)| n.as_ref()).collect();
+    assert_eq!(names, vec!["Accept", "Host"]);
+
+    // バリデーション失敗時は変更しない
+    assert!(matches!(
+        head.set_header("Host", "c\r\nX: y"),
+        Err(EncodeError::InvalidHeaderValue { .. })
+    ));
+    assert_eq!(head.get_header("Host"), Some("b"));
+}
+
+#[test]
+fn test_response_head_insert_header_if_absent() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    let (mut head, _) = decoder.decode_headers().unwrap().unwrap();
+
+    head.insert_header_if_absent("content-type", "text/plain")
+        .unwrap()
+        .insert_header_if_absent("Cache-Control", "no-cache")
+        .unwrap();
+    assert_eq!(head.get_headers("Content-Type"), vec!["text/html"]);
+    assert_eq!(head.get_header("Cache-Control"), Some("no-cache"));
+
+    head.set_header("Content-Type", "text/plain").unwrap();
+    head.remove_header("Content-Length");
+    assert_eq!(head.get_header("Content-Type"), Some("text/plain"));
+    assert_eq!(head.headers().len(), 2);
+}
+
+// ========================================
+// デコード済みヘッドの Connection / Transfer-Encoding 判定のテスト
+// ========================================
+
+/// Connection / Transfer-Encoding はトークンリストとして判定し、
+/// 同じ値を持つ Request / Response と結果が一致する
+#[test]
+fn test_decoded_head_connection_and_transfer_encoding_token_lists() {
+    let cases: &[(&str, &str, bool)] = &[
+        ("Connection", "keep-alive, upgrade", true),
+        ("Connection", "close, foo", false),
+        ("Connection", "Upgrade,\tKeep-Alive", true),
+        ("Connection", "foo, , CLOSE", false),
+        ("Transfer-Encoding", "chunked ", true),
+        ("Transfer-Encoding", "gzip,\tChunked", true),
+        ("Transfer-Encoding", "chunked, ", true),
+        ("Transfer-Encoding", "chunked, gzip", false),
+    ];
+    for (name, value, expected) in cases {
+        // keep-alive の判定がバージョンの既定値で決まらないよう HTTP/1.0 を使う。
+        // Transfer-Encoding は HTTP/1.1 でのみ受理される。
+        let version = if *name == "Connection" {
+            "HTTP/1.0"
+        } else {
+            "HTTP/1.1"
+        };
+        let check = |is_keep_alive: bool, is_chunked: bool| {
+            let actual = if *name == "Connection" {
+                is_keep_alive
+            } else {
+                is_chunked
+            };
+            assert_eq!(actual, *expected, "{name}: {value:?}");
+        };
+
+        // リクエストのデコーダーは chunked 以外の転送コーディングを拒否する
+        if !value.contains("gzip") {
+            let mut decoder = RequestDecoder::new();
+            decoder
+                .feed(format!("POST / {version}\r\nHost: a\r\n{name}: {value}\r\n\r\n").as_bytes())
+                .unwrap();
+            let (head, _) = decoder.decode_headers().unwrap().unwrap();
+            check(head.is_keep_alive(), head.is_chunked());
+        }
+        let request = Request::with_version("POST", "/", version)
+            .unwrap()
+            .header(*name, *value)
+            .unwrap();
+        check(request.is_keep_alive(), request.is_chunked());
+
+        let mut decoder = ResponseDecoder::new();
+        decoder
+            .feed(format!("{version} 200 OK\r\n{name}: {value}\r\n\r\n").as_bytes())
+            .unwrap();
+        let (head, _) = decoder.decode_headers().unwrap().unwrap();
+        check(head.is_keep_alive(), head.is_chunked());
+        let response = Response::with_version(version, 200, "OK")
+            .unwrap()
+            .header(*name, *value)
+            .unwrap();
+        check(response.is_keep_alive(), response.is_chunked());
+    }
+}
+
+// ========================================
+// ヘッダー名のインターンのテスト
+// ========================================
+
+/// よく使われるヘッダー名は受信した表記のまま `'static` な文字列を借用する
+#[test]
+fn test_well_known_header_names_are_interned() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(
+            b"GET / HTTP/1.1\r\nHost: a\r\ncontent-length: 0\r\nACCEPT: */*\r\nX-Custom: 1\r\n\r\n",
+        )
+        .unwrap();
+    let request = decoder.decode().unwrap().unwrap();
+    let headers = request.headers();
+    assert!(matches!(headers[0].0, Cow::Borrowed("Host")));
+    assert!(matches!(headers[1].0, Cow::Borrowed("content-length")));
+    // 表の表記とすべて小文字の表記以外は確保する
+    assert!(matches!(&headers[2].0, Cow::Owned(name) if name == "ACCEPT"));
+    assert!(matches!(&headers[3].0, Cow::Owned(name) if name == "X-Custom"));
+    assert_eq!(request.get_header("accept"), Some("*/*"));
+
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n0\r\nETag: \"a\"\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(matches!(head.headers()[0].0, Cow::Borrowed("Content-Type")));
+    assert!(matches!(
+        head.headers()[1].0,
+        Cow::Borrowed("Transfer-Encoding")
+    ));
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("User-Agent", "test")
+        .unwrap()
+        .header("X-Custom".to_string(), "1")
+        .unwrap();
+    assert!(matches!(
+        request.headers()[0].0,
+        Cow::Borrowed("User-Agent")
+    ));
+    assert!(matches!(request.headers()[1].0, Cow::Owned(_)));
 }
@@ -11,9 +11,21 @@
 //! - `decode_body`: 旧 `tests/test_decode_body.rs` に存在したボディデコード詳細テスト
 //!   (Transfer-Encoding token 検証、chunk-ext ABNF、HTTP バージョン別 TE 拒否、
 //!   IPv6 ブラケット検証など)
+//! - `non_utf8`: `NonUtf8Policy` による非 UTF-8 バイトの扱い
+//! - `read_body`: `read_body()` による呼び出し側バッファへの読み出し
+//! - `stats`: `DecoderStats` の計上
+//! - `chunk_framing`: chunked ボディのフレーミング保持 (プロキシ向けの透過転送)
+//! - `profile`: `DecoderProfile` のプリセットと適用
+//! - `allocation`: Keep-Alive 接続で返却したメッセージを再利用する場合のメモリ確保回数
 
+mod allocation;
 mod body;
+mod chunk_framing;
 mod decode_body;
 mod direct_buffer;
 mod head;
+mod non_utf8;
+mod profile;
+mod read_body;
+mod stats;
 mod streaming;
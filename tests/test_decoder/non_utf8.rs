@@ -0,0 +1,203 @@
+//! `NonUtf8Policy` のテスト
+//!
+//! - reason-phrase / ヘッダー値 / トレーラー値の Latin-1 解釈と U+FFFD 置換
+//! - request-line と field-name は設定に関わらず拒否される
+//! - `headers_raw()` / `get_header_bytes()` による受信バイト列の取得 (ヘッダー操作後を含む)
+
+use std::borrow::Cow;
+
+use shiguredo_http11::{
+    BodyRead, Error, HttpHead, NonUtf8Policy, ParsePhase, RequestDecoder, ResponseDecoder,
+};
+
+#[test]
+fn test_non_utf8_default_rejects() {
+    let mut decoder = ResponseDecoder::new();
+    assert_eq!(decoder.non_utf8_policy(), NonUtf8Policy::Reject);
+    decoder.feed(b"HTTP/1.1 200 Gr\xfc\xdfe\r\n\r\n").unwrap();
+    assert!(matches!(
+        decoder.decode_headers(),
+        Err(Error::Syntax {
+            phase: ParsePhase::StartLine,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_non_utf8_latin1_reason_phrase_and_header() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder
+        .feed(b"HTTP/1.1 200 Gr\xfc\xdfe\r\nX-Name: Jos\xe9\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.reason_phrase(), "Grüße");
+    assert_eq!(head.get_header("X-Name"), Some("José"));
+}
+
+#[test]
+fn test_non_utf8_replace_header() {
+    let mut decoder = RequestDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Replace);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nX-Name: Jos\xe9\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.get_header("X-Name"), Some("Jos\u{fffd}"));
+}
+
+#[test]
+fn test_non_utf8_valid_utf8_unchanged() {
+    // UTF-8 として正しい値は Latin-1 設定でも変換しない
+    let mut decoder = RequestDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder
+        .feed("GET / HTTP/1.1\r\nHost: a\r\nX-Name: José\r\n\r\n".as_bytes())
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.get_header("X-Name"), Some("José"));
+}
+
+#[test]
+fn test_non_utf8_request_line_always_rejected() {
+    let mut decoder = RequestDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder
+        .feed(b"GET /\xe9 HTTP/1.1\r\nHost: a\r\n\r\n")
+        .unwrap();
+    assert!(matches!(
+        decoder.decode_headers(),
+        Err(Error::Syntax {
+            phase: ParsePhase::StartLine,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_non_utf8_status_code_always_rejected() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder.feed(b"HTTP/1.1 2\xe90 OK\r\n\r\n").unwrap();
+    assert!(decoder.decode_headers().is_err());
+}
+
+#[test]
+fn test_non_utf8_header_name_always_rejected() {
+    let mut decoder = RequestDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Replace);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nX-N\xe9: v\r\n\r\n")
+        .unwrap();
+    assert!(matches!(
+        decoder.decode_headers(),
+        Err(Error::Syntax {
+            phase: ParsePhase::Header,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_non_utf8_latin1_trailer() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: X-Note\r\n\r\n\
+              0\r\nX-Note: caf\xe9\r\n\r\n",
+        )
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    let mut dst = [0u8; 8];
+    let BodyRead::Complete { trailers, .. } = decoder.read_body(&mut dst).unwrap() else {
+        panic!("body must be complete");
+    };
+    assert_eq!(
+        trailers,
+        vec![(Cow::Borrowed("X-Note"), "café".to_string())]
+    );
+}
+
+#[test]
+fn test_non_utf8_policy_kept_after_reset() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder.reset();
+    assert_eq!(decoder.non_utf8_policy(), NonUtf8Policy::Latin1);
+}
+
+#[test]
+fn test_headers_raw_returns_received_bytes() {
+    let mut decoder = RequestDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Replace);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nX-Name: \t Jos\xe9 \r\nX-Name: b\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+    // 置換後の値からは元のバイト列を復元できないが、受信バイト列は取得できる
+    assert_eq!(head.get_header("X-Name"), Some("Jos\u{fffd}"));
+    assert_eq!(head.get_header_bytes("x-name"), Some(&b"Jos\xe9"[..]));
+    let raw: Vec<(&str, &[u8])> = head.headers_raw().collect();
+    assert_eq!(
+        raw,
+        vec![
+            ("Host", &b"a"[..]),
+            ("X-Name", &b"Jos\xe9"[..]),
+            ("X-Name", &b"b"[..]),
+        ]
+    );
+}
+
+#[test]
+fn test_headers_raw_without_conversion() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed("HTTP/1.1 200 OK\r\nX-Name: José\r\nContent-Length: 0\r\n\r\n".as_bytes())
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.get_header_bytes("X-Name"), Some("José".as_bytes()));
+    assert_eq!(head.get_header_bytes("X-Missing"), None);
+    assert_eq!(head.headers_raw().count(), 2);
+}
+
+#[test]
+fn test_headers_raw_after_remove_header() {
+    // ヘッダーを削除しても残りのヘッダーの受信バイト列を取得できる
+    let mut decoder = RequestDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nX-A: \xe9\r\nHost: a\r\nX-B: \xfc\r\nX-A: \xe8\r\nX-C: \xdf\r\n\r\n")
+        .unwrap();
+    let (mut head, _) = decoder.decode_headers().unwrap().unwrap();
+    head.remove_header("x-a");
+    head.set_header("Host", "b").unwrap();
+    let raw: Vec<(&str, &[u8])> = head.headers_raw().collect();
+    assert_eq!(
+        raw,
+        vec![
+            ("X-B", &b"\xfc"[..]),
+            ("X-C", &b"\xdf"[..]),
+            ("Host", &b"b"[..]),
+        ]
+    );
+}
+
+#[test]
+fn test_headers_raw_reset_per_message() {
+    // Keep-Alive で次のメッセージに前のメッセージの受信バイト列が残らない
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Latin1);
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nX-A: \xe9\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nX-A: e\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    let first = decoder.decode().unwrap().unwrap();
+    assert!(first.headers().iter().any(|(_, v)| v == "é"));
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.get_header_bytes("X-A"), Some(&b"e"[..]));
+}
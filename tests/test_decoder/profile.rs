@@ -0,0 +1,119 @@
+//! `DecoderProfile` のテスト
+//!
+//! - プリセットの設定値と `with_profile()` / `set_profile()` / `profile()`
+//! - プリセットによる受理 / 拒否の違い
+//! - bare LF / obs-fold / Transfer-Encoding と Content-Length の併存はプリセットに関わらず受理しない
+
+use shiguredo_http11::{
+    DecoderLimits, DecoderProfile, Error, HttpHead, NonUtf8Policy, RequestDecoder, ResponseDecoder,
+};
+
+fn presets() -> [DecoderProfile; 3] {
+    [
+        DecoderProfile::strict(),
+        DecoderProfile::browser_compatible(),
+        DecoderProfile::internal(),
+    ]
+}
+
+#[test]
+fn test_profile_default_matches_decoder() {
+    assert_eq!(RequestDecoder::new().profile(), DecoderProfile::default());
+    assert_eq!(ResponseDecoder::new().profile(), DecoderProfile::default());
+}
+
+#[test]
+fn test_profile_presets() {
+    let strict = DecoderProfile::strict();
+    assert_eq!(strict.limits, DecoderLimits::default());
+    assert_eq!(strict.non_utf8_policy, NonUtf8Policy::Reject);
+    assert!(strict.strict_version);
+
+    let browser = DecoderProfile::browser_compatible();
+    assert_eq!(browser.non_utf8_policy, NonUtf8Policy::Latin1);
+    assert!(browser.strict_version);
+    assert!(browser.limits.max_header_line_size > strict.limits.max_header_line_size);
+
+    let internal = DecoderProfile::internal();
+    assert_eq!(internal.non_utf8_policy, NonUtf8Policy::Latin1);
+    assert!(!internal.strict_version);
+    assert!(internal.limits.max_body_size > strict.limits.max_body_size);
+    // 上限はなくさない
+    assert_ne!(internal.limits, DecoderLimits::unlimited());
+}
+
+#[test]
+fn test_profile_apply_and_tweak() {
+    let profile = DecoderProfile::strict()
+        .with_non_utf8_policy(NonUtf8Policy::Replace)
+        .with_strict_version(false);
+    let mut decoder = ResponseDecoder::with_profile(profile.clone());
+    assert_eq!(decoder.profile(), profile);
+    assert_eq!(decoder.non_utf8_policy(), NonUtf8Policy::Replace);
+
+    // 適用後の個別の設定変更も profile() に反映される
+    decoder.set_strict_version(true);
+    assert!(decoder.profile().strict_version);
+
+    let mut profile = DecoderProfile::internal();
+    profile.limits.max_headers_count = 1;
+    let mut decoder = RequestDecoder::new();
+    decoder.set_profile(&profile);
+    assert_eq!(decoder.limits().max_headers_count, 1);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nAccept: */*\r\n\r\n")
+        .unwrap();
+    assert!(matches!(
+        decoder.decode_headers(),
+        Err(Error::TooManyHeaders { .. })
+    ));
+
+    // reset() 後も設定は維持される
+    decoder.reset();
+    assert_eq!(decoder.profile(), profile);
+}
+
+#[test]
+fn test_profile_version() {
+    let input = b"RTSP/1.0 200 OK\r\nContent-Length: 0\r\n\r\n";
+    let mut decoder = ResponseDecoder::with_profile(DecoderProfile::strict());
+    decoder.feed(input).unwrap();
+    assert!(decoder.decode_headers().is_err());
+
+    let mut decoder = ResponseDecoder::with_profile(DecoderProfile::internal());
+    decoder.feed(input).unwrap();
+    assert!(decoder.decode_headers().unwrap().is_some());
+}
+
+#[test]
+fn test_profile_non_utf8() {
+    let input = b"HTTP/1.1 200 OK\r\nX-Name: Jos\xe9\r\nContent-Length: 0\r\n\r\n";
+    let mut decoder = ResponseDecoder::with_profile(DecoderProfile::strict());
+    decoder.feed(input).unwrap();
+    assert!(decoder.decode_headers().is_err());
+
+    let mut decoder = ResponseDecoder::with_profile(DecoderProfile::browser_compatible());
+    decoder.feed(input).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.get_header("X-Name"), Some("Jos\u{e9}"));
+}
+
+#[test]
+fn test_profile_always_rejected() {
+    let inputs: [&[u8]; 3] = [
+        b"GET / HTTP/1.1\nHost: a\n\n",
+        b"GET / HTTP/1.1\r\nHost: a\r\nX-Folded: a\r\n b\r\n\r\n",
+        b"POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nContent-Length: 3\r\n\r\n",
+    ];
+    for profile in presets() {
+        for input in inputs {
+            let mut decoder = RequestDecoder::with_profile(profile.clone());
+            decoder.feed(input).unwrap();
+            // bare LF は行の終端として扱わないため、エラーまたはデータ不足になる
+            assert!(
+                !matches!(decoder.decode_headers(), Ok(Some(_))),
+                "{profile:?} {input:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! `read_body()` のテスト
+//!
+//! - Content-Length / chunked / close-delimited ボディの呼び出し側バッファへの読み出し
+//! - `dst` が小さい場合の分割読み出しとトレーラーの受け取り
+//! - 空の `dst` のエラー
+
+use std::borrow::Cow;
+
+use shiguredo_http11::{BodyRead, Error, RequestDecoder, ResponseDecoder};
+
+/// dst に収まる Content-Length ボディは 1 回の呼び出しで完了する
+#[test]
+fn test_read_body_content_length() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\n\r\nhello")
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let mut dst = [0u8; 16];
+    let read = decoder.read_body(&mut dst).unwrap();
+    assert_eq!(
+        read,
+        BodyRead::Complete {
+            len: 5,
+            trailers: vec![]
+        }
+    );
+    assert_eq!(&dst[..5], b"hello");
+}
+
+/// 分割到着したボディは Data / NeedData を経て完了する
+#[test]
+fn test_read_body_need_data() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\n\r\nhel")
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let mut dst = [0u8; 16];
+    assert_eq!(decoder.read_body(&mut dst).unwrap(), BodyRead::Data(3));
+    assert_eq!(&dst[..3], b"hel");
+    assert_eq!(decoder.read_body(&mut dst).unwrap(), BodyRead::NeedData);
+
+    decoder.feed(b"lo").unwrap();
+    assert_eq!(
+        decoder.read_body(&mut dst).unwrap(),
+        BodyRead::Complete {
+            len: 2,
+            trailers: vec![]
+        }
+    );
+    assert_eq!(&dst[..2], b"lo");
+}
+
+/// chunked ボディはチャンク境界を跨いで dst を埋め、トレーラーを返す
+#[test]
+fn test_read_body_chunked_small_dst() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: X-Sum\r\n\r\n\
+              3\r\nabc\r\n4\r\ndefg\r\n0\r\nX-Sum: 7\r\n\r\n",
+        )
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let mut body = Vec::new();
+    let mut dst = [0u8; 4];
+    let trailers = loop {
+        match decoder.read_body(&mut dst).unwrap() {
+            BodyRead::Data(n) => {
+                assert_eq!(n, dst.len());
+                body.extend_from_slice(&dst[..n]);
+            }
+            BodyRead::Complete { len, trailers } => {
+                body.extend_from_slice(&dst[..len]);
+                break trailers;
+            }
+            other => panic!("unexpected: {other:?}"),
+        }
+    };
+    assert_eq!(body, b"abcdefg");
+    assert_eq!(trailers, vec![(Cow::Borrowed("X-Sum"), "7".to_string())]);
+    assert_eq!(decoder.stats().body_bytes, 7);
+}
+
+/// dst がちょうど埋まった後に終端チャンクだけが残る場合、次の呼び出しで len 0 の完了を返す
+#[test]
+fn test_read_body_chunked_exact_fill() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nabcd\r\n")
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let mut dst = [0u8; 4];
+    assert_eq!(decoder.read_body(&mut dst).unwrap(), BodyRead::Data(4));
+    assert_eq!(&dst, b"abcd");
+
+    decoder.feed(b"0\r\n\r\n").unwrap();
+    assert_eq!(
+        decoder.read_body(&mut dst).unwrap(),
+        BodyRead::Complete {
+            len: 0,
+            trailers: vec![]
+        }
+    );
+}
+
+/// close-delimited ボディは mark_eof() まで完了しない
+#[test]
+fn test_read_body_close_delimited() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(b"HTTP/1.1 200 OK\r\n\r\nhello").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    let mut dst = [0u8; 16];
+    assert_eq!(decoder.read_body(&mut dst).unwrap(), BodyRead::Data(5));
+    assert_eq!(decoder.read_body(&mut dst).unwrap(), BodyRead::NeedData);
+
+    decoder.mark_eof();
+    assert_eq!(
+        decoder.read_body(&mut dst).unwrap(),
+        BodyRead::Complete {
+            len: 0,
+            trailers: vec![]
+        }
+    );
+}
+
+/// 空の dst はエラー
+#[test]
+fn test_read_body_empty_dst() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 1\r\n\r\nx")
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    assert!(matches!(
+        decoder.read_body(&mut []),
+        Err(Error::InvalidData(_))
+    ));
+}
+
+/// decode_headers() 前の呼び出しはエラー
+#[test]
+fn test_read_body_before_headers() {
+    let mut decoder = RequestDecoder::new();
+    let mut dst = [0u8; 4];
+    assert!(matches!(
+        decoder.read_body(&mut dst),
+        Err(Error::InvalidData(_))
+    ));
+}
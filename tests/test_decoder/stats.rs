@@ -0,0 +1,77 @@
+//! `DecoderStats` のテスト
+//!
+//! - chunked のチャンク数 / トレーラー数 / フレーミングを除いたボディバイト数
+//! - Keep-Alive での累計と `reset()` によるクリア
+//! - `mut_buf` / `advance_buf` 経由の投入バイト数
+
+use shiguredo_http11::{DecoderStats, RequestDecoder, ResponseDecoder};
+
+/// chunked ボディではチャンクデータのみをボディバイト数として数える
+#[test]
+fn test_stats_chunked_with_trailer() {
+    let head = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: X-Sum\r\n\r\n";
+    let body = b"3\r\nabc\r\n2\r\nde\r\n0\r\nX-Sum: 5\r\n\r\n";
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(head).unwrap();
+    decoder.feed(body).unwrap();
+    let response = decoder.decode().unwrap().unwrap();
+    assert_eq!(response.body_bytes(), Some(&b"abcde"[..]));
+
+    let stats = decoder.stats();
+    assert_eq!(stats.bytes_fed, (head.len() + body.len()) as u64);
+    assert_eq!(stats.header_bytes, head.len() as u64);
+    assert_eq!(stats.body_bytes, 5);
+    assert_eq!(stats.messages, 1);
+    assert_eq!(stats.chunks, 2);
+    assert_eq!(stats.trailers, 1);
+}
+
+/// Keep-Alive では累計され、reset() でクリアされる
+#[test]
+fn test_stats_accumulate_and_reset() {
+    let request = b"POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 2\r\n\r\nok";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(request).unwrap();
+    decoder.feed(request).unwrap();
+    assert!(decoder.decode().unwrap().is_some());
+    assert!(decoder.decode().unwrap().is_some());
+
+    let stats = *decoder.stats();
+    assert_eq!(stats.bytes_fed, request.len() as u64 * 2);
+    assert_eq!(stats.body_bytes, 4);
+    assert_eq!(stats.messages, 2);
+    assert_eq!(stats.chunks, 0);
+
+    decoder.reset();
+    assert_eq!(*decoder.stats(), DecoderStats::default());
+}
+
+/// advance_buf で確定したバイト数のみを投入バイト数として数える
+#[test]
+fn test_stats_direct_buffer() {
+    let data = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    let buf = decoder.mut_buf(1024).unwrap();
+    buf[..data.len()].copy_from_slice(data);
+    decoder.advance_buf(data.len());
+    assert_eq!(decoder.stats().bytes_fed, data.len() as u64);
+
+    decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(decoder.stats().header_bytes, data.len() as u64);
+}
+
+/// CONNECT のトンネルデータはヘッダー / ボディのどちらにも数えない
+#[test]
+fn test_stats_tunnel_data_not_counted() {
+    let head = b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(head).unwrap();
+    decoder.feed(b"tunnel").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(decoder.take_remaining(), b"tunnel");
+
+    let stats = decoder.stats();
+    assert_eq!(stats.bytes_fed, head.len() as u64 + 6);
+    assert_eq!(stats.header_bytes, head.len() as u64);
+    assert_eq!(stats.body_bytes, 0);
+}
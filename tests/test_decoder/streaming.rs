@@ -4,7 +4,10 @@
 //!   `progress` / `take_remaining`) の混在エラー
 //! - `consume_body(0)` / トンネル中の `consume_body` / 残量超過の `consume_body` エラー
 //! - CONNECT メソッドへの 2xx レスポンスでのトンネル化と非トンネル化の判定
-//! - CONNECT リクエスト受信時のトンネルモード遷移と reset の挙動
+//! - CONNECT リクエスト受信時のトンネルモード遷移と reset / clear_for_next_message の挙動
+//! - recycle_head / recycle_request / recycle_response で返却した後のデコード結果
+
+use std::borrow::Cow;
 
 use shiguredo_http11::compression::{
     CompressionError, CompressionStatus, Decompressor, NoCompression,
@@ -597,3 +600,120 @@ fn test_connect_request_reset_clears_tunnel_mode() {
     assert_eq!(head.method(), "GET");
     assert_eq!(body_kind, BodyKind::None);
 }
+
+/// clear_for_next_message() はトンネルモードから脱出し、受信済みの次のリクエストを保持する
+#[test]
+fn test_connect_request_clear_for_next_message_keeps_pipelined_data() {
+    let mut decoder = RequestDecoder::new();
+    let input = "CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n\
+                 GET / HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    decoder.feed(input.as_bytes()).unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    assert!(decoder.is_tunnel());
+
+    // CONNECT を 4xx で拒否した場合、続くバイト列は次のリクエストとして扱う
+    decoder.clear_for_next_message();
+    assert!(!decoder.is_tunnel());
+    let (head, body_kind) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.method(), "GET");
+    assert_eq!(body_kind, BodyKind::None);
+    // 統計情報は保持する
+    assert_eq!(decoder.stats().bytes_fed, input.len() as u64);
+}
+
+/// clear_for_next_message() は設定済みのリクエストメソッドもクリアする
+#[test]
+fn test_response_clear_for_next_message() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_request_method("CONNECT");
+    decoder
+        .feed(b"HTTP/1.1 407 Proxy Authentication Required\r\nContent-Length: 3\r\n\r\nab")
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+
+    // ボディの途中で呼び出しても受信済みのデータは破棄しない
+    decoder.clear_for_next_message();
+    assert_eq!(decoder.remaining(), b"ab");
+
+    decoder.reset();
+    decoder.set_request_method("CONNECT");
+    decoder.feed(b"HTTP/1.1 200 OK\r\n\r\n").unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    assert!(decoder.is_tunnel());
+    decoder.clear_for_next_message();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+        .unwrap();
+    let (_, body_kind) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(body_kind, BodyKind::ContentLength(2));
+}
+
+/// 返却したリクエストの記憶領域を再利用しても、ヘッダー数やボディが異なる次のリクエストを正しくデコードする
+#[test]
+fn test_request_recycle_keeps_decode_result() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"POST /upload HTTP/1.1\r\nHost: example.com\r\nX-A: 1\r\nX-B: 2\r\nContent-Length: 5\r\n\r\nhello")
+        .unwrap();
+    let request = decoder.decode().unwrap().unwrap();
+    assert_eq!(request.body_bytes(), Some(&b"hello"[..]));
+    decoder.recycle_request(request);
+
+    decoder
+        .feed(b"GET /a HTTP/1.1\r\nHost: example.org\r\n\r\n")
+        .unwrap();
+    let request = decoder.decode().unwrap().unwrap();
+    assert_eq!(request.method(), "GET");
+    assert_eq!(request.uri(), "/a");
+    assert_eq!(request.version(), "HTTP/1.1");
+    assert_eq!(
+        request.headers(),
+        &[(Cow::Borrowed("Host"), "example.org".to_string())]
+    );
+    assert_eq!(request.body_bytes(), None);
+    decoder.recycle_request(request);
+
+    decoder
+        .feed(b"PUT /b HTTP/1.1\r\nHost: example.net\r\nX-C: 3\r\nContent-Length: 2\r\n\r\nok")
+        .unwrap();
+    let (head, body_kind) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.method(), "PUT");
+    assert_eq!(head.uri(), "/b");
+    assert_eq!(head.get_header("X-C"), Some("3"));
+    assert_eq!(head.get_header("X-A"), None);
+    assert_eq!(body_kind, BodyKind::ContentLength(2));
+    decoder.recycle_head(head);
+    assert_eq!(decoder.peek_body(), Some(&b"ok"[..]));
+}
+
+/// 返却したレスポンスの記憶領域を再利用しても、次のレスポンスを正しくデコードする
+#[test]
+fn test_response_recycle_keeps_decode_result() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 404 Not Found\r\nX-A: 1\r\nContent-Length: 3\r\n\r\nabc")
+        .unwrap();
+    let response = decoder.decode().unwrap().unwrap();
+    decoder.recycle_response(response);
+
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+        .unwrap();
+    let response = decoder.decode().unwrap().unwrap();
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.reason_phrase(), "OK");
+    assert_eq!(
+        response.headers(),
+        &[(Cow::Borrowed("Content-Length"), "2".to_string())]
+    );
+    assert_eq!(response.body_bytes(), Some(&b"ok"[..]));
+    decoder.recycle_response(response);
+
+    decoder.feed(b"HTTP/1.1 204 \r\n\r\n").unwrap();
+    let (head, body_kind) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.status_code(), 204);
+    assert_eq!(head.reason_phrase(), "");
+    assert!(head.headers().is_empty());
+    assert_eq!(body_kind, BodyKind::None);
+    decoder.recycle_head(head);
+}
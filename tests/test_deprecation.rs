@@ -0,0 +1,287 @@
+//! Deprecation / Sunset / Warning ヘッダーのユニットテスト
+
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::deprecation::{Deprecation, DeprecationError, Sunset, Warning, WarningValue};
+use shiguredo_http11::{Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// DeprecationError のテスト
+// ========================================
+
+#[test]
+fn test_deprecation_error_display() {
+    let errors = [
+        (DeprecationError::Empty, "empty header value"),
+        (DeprecationError::InvalidFormat, "invalid header format"),
+        (DeprecationError::InvalidDate, "invalid date"),
+        (DeprecationError::InvalidWarnCode, "invalid warn-code"),
+        (DeprecationError::InvalidWarnAgent, "invalid warn-agent"),
+        (DeprecationError::InvalidWarnText, "invalid warn-text"),
+        (
+            DeprecationError::UnterminatedQuote,
+            "unterminated quoted-string",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// Deprecation のテスト
+// ========================================
+
+#[test]
+fn test_deprecation_parse_sf_date() {
+    // RFC 9745 Section 2.1
+    let deprecation = Deprecation::parse("@1688169599", 2026).unwrap();
+    assert_eq!(deprecation.timestamp(), 1688169599);
+    assert_eq!(
+        deprecation.date().unwrap().to_string(),
+        "Fri, 30 Jun 2023 23:59:59 GMT"
+    );
+    assert!(!deprecation.is_deprecated_at(1688169598));
+    assert!(deprecation.is_deprecated_at(1688169599));
+    assert_eq!(deprecation.to_string(), "@1688169599");
+
+    // パラメーターは無視する
+    let deprecation = Deprecation::parse(" @0;foo=1 ", 2026).unwrap();
+    assert_eq!(deprecation.timestamp(), 0);
+}
+
+#[test]
+fn test_deprecation_parse_http_date() {
+    let deprecation = Deprecation::parse("Sun, 11 Nov 2018 23:59:59 GMT", 2026).unwrap();
+    assert_eq!(deprecation.timestamp(), 1541980799);
+    assert_eq!(deprecation.to_string(), "@1541980799");
+
+    let deprecation = Deprecation::parse("Sunday, 11-Nov-18 23:59:59 GMT", 2026).unwrap();
+    assert_eq!(deprecation.timestamp(), 1541980799);
+}
+
+#[test]
+fn test_deprecation_parse_errors() {
+    let cases = [
+        ("", DeprecationError::Empty),
+        ("   ", DeprecationError::Empty),
+        ("true", DeprecationError::InvalidFormat),
+        ("@", DeprecationError::InvalidDate),
+        ("@-1", DeprecationError::InvalidDate),
+        ("@1.5", DeprecationError::InvalidDate),
+        ("@1234567890123456", DeprecationError::InvalidDate),
+        ("@1 x", DeprecationError::InvalidFormat),
+        ("@1;", DeprecationError::InvalidFormat),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Deprecation::parse(input, 2026), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_deprecation_new() {
+    assert_eq!(
+        Deprecation::new(999_999_999_999_999).unwrap().to_string(),
+        "@999999999999999"
+    );
+    assert_eq!(
+        Deprecation::new(1_000_000_000_000_000),
+        Err(DeprecationError::InvalidDate)
+    );
+    // 9999 年を超える日時は HTTP-date にできない
+    assert_eq!(Deprecation::new(999_999_999_999).unwrap().date(), None);
+
+    let date = HttpDate::parse("Fri, 30 Jun 2023 23:59:59 GMT").unwrap();
+    assert_eq!(Deprecation::from_date(&date).timestamp(), 1688169599);
+}
+
+// ========================================
+// Sunset のテスト
+// ========================================
+
+#[test]
+fn test_sunset() {
+    // RFC 8594 Section 3 の例 (曜日は実際の日付に合わせている)
+    let sunset = Sunset::parse("Mon, 31 Dec 2018 23:59:59 GMT", 2026).unwrap();
+    assert_eq!(sunset.unix_seconds(), 1546300799);
+    assert_eq!(sunset.date().year(), 2018);
+    assert!(!sunset.is_past(1546300798));
+    assert!(sunset.is_past(1546300799));
+    assert_eq!(sunset.to_string(), "Mon, 31 Dec 2018 23:59:59 GMT");
+
+    assert_eq!(Sunset::parse("", 2026), Err(DeprecationError::Empty));
+    assert_eq!(
+        Sunset::parse("@1546300799", 2026),
+        Err(DeprecationError::InvalidDate)
+    );
+}
+
+// ========================================
+// Warning のテスト
+// ========================================
+
+#[test]
+fn test_warning_parse() {
+    let warning = Warning::parse(
+        "112 - \"network down\" \"Sat, 25 Aug 2012 23:34:45 GMT\", \
+         299 proxy.example.com:8080 \"Deprecated \\\"v1\\\" API\"",
+        2026,
+    )
+    .unwrap();
+    let values = warning.values();
+    assert_eq!(values.len(), 2);
+
+    assert_eq!(values[0].code(), WarningValue::DISCONNECTED_OPERATION);
+    assert_eq!(values[0].agent(), "-");
+    assert_eq!(values[0].text(), "network down");
+    assert_eq!(values[0].date().unwrap().unix_seconds(), 1345937685);
+    assert!(!values[0].is_persistent());
+
+    assert_eq!(values[1].code(), 299);
+    assert_eq!(values[1].agent(), "proxy.example.com:8080");
+    assert_eq!(values[1].text(), "Deprecated \"v1\" API");
+    assert_eq!(values[1].date(), None);
+    assert!(values[1].is_persistent());
+
+    assert_eq!(Warning::parse(&warning.to_string(), 2026), Ok(warning));
+}
+
+#[test]
+fn test_warning_parse_errors() {
+    let cases = [
+        ("", DeprecationError::Empty),
+        (" , ", DeprecationError::Empty),
+        ("99 - \"a\"", DeprecationError::InvalidWarnCode),
+        ("1a0 - \"a\"", DeprecationError::InvalidWarnCode),
+        ("0110 - \"a\"", DeprecationError::InvalidWarnCode),
+        ("110", DeprecationError::InvalidFormat),
+        ("110 -", DeprecationError::InvalidFormat),
+        ("110 - a", DeprecationError::InvalidWarnText),
+        ("110 - \"a", DeprecationError::UnterminatedQuote),
+        ("110 a\"b \"c\"", DeprecationError::InvalidWarnAgent),
+        ("110 - \"a\" \"yesterday\"", DeprecationError::InvalidDate),
+        (
+            "110 - \"a\" \"Sat, 25 Aug 2012 23:34:45 GMT",
+            DeprecationError::UnterminatedQuote,
+        ),
+        ("110 - \"a\" b", DeprecationError::InvalidFormat),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Warning::parse(input, 2026), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_warning_value_builder() {
+    let date = HttpDate::parse("Sat, 25 Aug 2012 23:34:45 GMT").unwrap();
+    let value = WarningValue::new(
+        WarningValue::RESPONSE_IS_STALE,
+        "cache",
+        "Response is \"Stale\"",
+    )
+    .unwrap()
+    .with_date(date.clone());
+    assert_eq!(
+        value.to_string(),
+        "110 cache \"Response is \\\"Stale\\\"\" \"Sat, 25 Aug 2012 23:34:45 GMT\""
+    );
+
+    assert_eq!(
+        WarningValue::new(99, "-", "a"),
+        Err(DeprecationError::InvalidWarnCode)
+    );
+    assert_eq!(
+        WarningValue::new(1000, "-", "a"),
+        Err(DeprecationError::InvalidWarnCode)
+    );
+    assert_eq!(
+        WarningValue::new(199, "a b", "a"),
+        Err(DeprecationError::InvalidWarnAgent)
+    );
+    assert_eq!(
+        WarningValue::new(199, "", "a"),
+        Err(DeprecationError::InvalidWarnAgent)
+    );
+    assert_eq!(
+        WarningValue::new(199, "-", "a\r\nb"),
+        Err(DeprecationError::InvalidWarnText)
+    );
+}
+
+#[test]
+fn test_warning_without_mismatched_dates() {
+    let date = HttpDate::parse("Sat, 25 Aug 2012 23:34:45 GMT").unwrap();
+    let other = HttpDate::parse("Sun, 26 Aug 2012 00:00:00 GMT").unwrap();
+    let warning = Warning::new()
+        .with_value(
+            WarningValue::new(110, "a", "match")
+                .unwrap()
+                .with_date(date.clone()),
+        )
+        .with_value(
+            WarningValue::new(110, "b", "mismatch")
+                .unwrap()
+                .with_date(other),
+        )
+        .with_value(WarningValue::new(299, "c", "no date").unwrap());
+
+    let warning = warning.without_mismatched_dates(&date);
+    let agents: Vec<_> = warning.values().iter().map(|v| v.agent()).collect();
+    assert_eq!(agents, ["a", "c"]);
+}
+
+// ========================================
+// Response 連携のテスト
+// ========================================
+
+#[test]
+fn test_response_lifecycle_headers() {
+    let deprecation = Deprecation::new(1688169599).unwrap();
+    let sunset = Sunset::new(HttpDate::from_unix_seconds(1700000000).unwrap());
+    let response = Response::with_status(StatusCode::OK)
+        .header("Deprecation", "@0")
+        .unwrap()
+        .deprecation(&deprecation)
+        .unwrap()
+        .sunset(&sunset)
+        .unwrap()
+        .warning(&WarningValue::new(299, "-", "first").unwrap())
+        .unwrap()
+        .warning(&WarningValue::new(199, "-", "second").unwrap())
+        .unwrap();
+
+    assert_eq!(response.get_headers("Deprecation"), ["@1688169599"]);
+    assert_eq!(
+        Deprecation::from_response(&response, 2026),
+        Ok(Some(deprecation))
+    );
+    assert_eq!(Sunset::from_response(&response, 2026), Ok(Some(sunset)));
+    let warning = Warning::from_response(&response, 2026).unwrap().unwrap();
+    assert_eq!(warning.to_string(), "299 - \"first\", 199 - \"second\"");
+
+    let response = Response::with_status(StatusCode::OK);
+    assert_eq!(Deprecation::from_response(&response, 2026), Ok(None));
+    assert_eq!(Sunset::from_response(&response, 2026), Ok(None));
+    assert_eq!(Warning::from_response(&response, 2026), Ok(None));
+}
+
+#[test]
+fn test_decoded_response_warning() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nWarning: 110 cache \"Response is Stale\"\r\n\
+              Warning: 214 proxy \"Transformation Applied\"\r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let warning = Warning::from_response(&head, 2026).unwrap().unwrap();
+    let codes: Vec<_> = warning.values().iter().map(|v| v.code()).collect();
+    assert_eq!(
+        codes,
+        [
+            WarningValue::RESPONSE_IS_STALE,
+            WarningValue::TRANSFORMATION_APPLIED
+        ]
+    );
+}
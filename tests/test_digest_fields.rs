@@ -1,6 +1,10 @@
 //! Digest Fields のユニットテスト
 
-use shiguredo_http11::digest_fields::{ContentDigest, DigestFieldsError, WantContentDigest};
+use shiguredo_http11::digest_fields::{
+    ContentDigest, DigestEntry, DigestFieldsError, DigestHasher, DigestPreference, ReprDigest,
+    SHA_256, SUPPORTED_ALGORITHMS, WantContentDigest, WantReprDigest,
+};
+use shiguredo_http11::{BodyProgress, HttpHead, RequestDecoder};
 
 // ========================================
 // DigestFieldsError のテスト
@@ -27,6 +31,11 @@ fn test_digest_fields_error_display() {
             DigestFieldsError::InvalidPreference,
             "invalid digest preference",
         ),
+        (
+            DigestFieldsError::UnsupportedAlgorithm,
+            "no supported digest algorithm",
+        ),
+        (DigestFieldsError::Mismatch, "digest mismatch"),
     ];
 
     for (error, expected) in errors {
@@ -185,3 +194,171 @@ fn test_digest_empty_only_error() {
     let result = ContentDigest::parse(",");
     assert!(result.is_err());
 }
+
+// ========================================
+// Digest の計算と検証のテスト
+// ========================================
+
+// RFC 9530 Appendix B.1 の例
+#[test]
+fn test_content_digest_sha256_rfc_example() {
+    let body = b"{\"hello\": \"world\"}\n";
+    let digest = ContentDigest::sha256(body);
+    assert_eq!(
+        digest.to_string(),
+        "sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:"
+    );
+    assert_eq!(digest.get("SHA-256").unwrap().bytes().len(), 32);
+    assert_eq!(digest.verify(body), Ok(()));
+    assert_eq!(digest.verify(b"{}"), Err(DigestFieldsError::Mismatch));
+
+    let repr = ReprDigest::sha256(body);
+    assert_eq!(repr.to_string(), digest.to_string());
+    assert_eq!(repr.verify(body), Ok(()));
+}
+
+#[test]
+fn test_digest_verify_parsed_header() {
+    let digest = ContentDigest::parse(
+        "sha-512=:YWJj:, sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:",
+    )
+    .unwrap();
+    assert_eq!(digest.verify(b"{\"hello\": \"world\"}\n"), Ok(()));
+}
+
+#[test]
+fn test_digest_verify_unsupported_algorithm() {
+    let digest = ContentDigest::parse("sha-512=:YWJj:, unixsum=:AAAA:").unwrap();
+    assert_eq!(
+        digest.verify(b"abc"),
+        Err(DigestFieldsError::UnsupportedAlgorithm)
+    );
+    assert!(digest.verifier().is_err());
+}
+
+#[test]
+fn test_digest_entry_new() {
+    let entry = DigestEntry::new("SHA-512", b"abc").unwrap();
+    assert_eq!(entry.algorithm(), "sha-512");
+    assert_eq!(entry.value().bytes(), b"abc");
+    assert_eq!(entry.to_string(), "sha-512=:YWJj:");
+    assert_eq!(
+        DigestEntry::new("sha 256", b"abc"),
+        Err(DigestFieldsError::InvalidAlgorithm)
+    );
+    assert_eq!(
+        DigestEntry::new("", b"abc"),
+        Err(DigestFieldsError::InvalidAlgorithm)
+    );
+
+    let digest = ContentDigest::sha256(b"abc").with_entry(entry);
+    assert_eq!(digest.items().len(), 2);
+    assert_eq!(ContentDigest::parse(&digest.to_string()).unwrap(), digest);
+}
+
+#[test]
+fn test_digest_hasher_matches_one_shot() {
+    let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    for split in [0, 1, 63, 64, 65, 999, 1000] {
+        let mut hasher = DigestHasher::new();
+        hasher.update(&data[..split]).update(&data[split..]);
+        assert_eq!(
+            hasher.finish(),
+            DigestEntry::sha256(&data),
+            "split={}",
+            split
+        );
+    }
+}
+
+#[test]
+fn test_digest_verifier_streaming_chunked_body() {
+    let body = b"hello, digest";
+    let digest = ContentDigest::sha256(body);
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: example.com\r\nTransfer-Encoding: chunked\r\nContent-Digest: {}\r\n\r\n5\r\nhello\r\n8\r\n, digest\r\n0\r\n\r\n",
+        digest
+    );
+
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(request.as_bytes()).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let mut verifier = head
+        .get_typed::<ContentDigest>()
+        .unwrap()
+        .unwrap()
+        .verifier()
+        .unwrap();
+
+    loop {
+        if let Some(data) = decoder.peek_body() {
+            verifier.update(data);
+            let len = data.len();
+            if let BodyProgress::Complete { .. } = decoder.consume_body(len).unwrap() {
+                break;
+            }
+        } else if let BodyProgress::Complete { .. } = decoder.progress().unwrap() {
+            break;
+        }
+    }
+    assert_eq!(verifier.finish(), Ok(()));
+}
+
+// ========================================
+// Want-*-Digest によるアルゴリズム選択のテスト
+// ========================================
+
+#[test]
+fn test_want_digest_choose_highest_weight() {
+    let want = WantContentDigest::parse("sha-256=3, sha-512=10").unwrap();
+    assert_eq!(want.choose(&["sha-256", "sha-512"]), Some("sha-512"));
+    assert_eq!(want.choose(&["sha-256"]), Some("sha-256"));
+    assert_eq!(want.choose(SUPPORTED_ALGORITHMS), Some(SHA_256));
+}
+
+#[test]
+fn test_want_digest_choose_tie_prefers_supported_order() {
+    let want = WantReprDigest::parse("sha-512=5, sha-256=5").unwrap();
+    assert_eq!(want.choose(&["sha-256", "sha-512"]), Some("sha-256"));
+    assert_eq!(want.choose(&["sha-512", "sha-256"]), Some("sha-512"));
+}
+
+#[test]
+fn test_want_digest_choose_excludes_zero_weight() {
+    let want = WantContentDigest::parse("sha-256=0, sha-512=1").unwrap();
+    assert_eq!(want.choose(&["sha-256"]), None);
+    assert_eq!(want.choose(&["sha-256", "sha-512"]), Some("sha-512"));
+    assert_eq!(want.choose(&[]), None);
+    assert_eq!(want.choose(&["md5"]), None);
+}
+
+#[test]
+fn test_want_digest_choose_case_insensitive() {
+    let want = WantContentDigest::parse("SHA-256=2").unwrap();
+    assert_eq!(want.choose(&["Sha-256"]), Some("Sha-256"));
+}
+
+#[test]
+fn test_want_digest_choose_duplicate_uses_first() {
+    let want = WantContentDigest::parse("sha-256=0, sha-256=10").unwrap();
+    assert_eq!(want.get("sha-256"), Some(0));
+    assert_eq!(want.choose(&["sha-256"]), None);
+}
+
+#[test]
+fn test_want_digest_builder() {
+    let want = WantContentDigest::new(DigestPreference::new("SHA-512", 3).unwrap())
+        .with_preference(DigestPreference::new("sha-256", 10).unwrap());
+    assert_eq!(want.to_string(), "sha-512=3, sha-256=10");
+    assert_eq!(WantContentDigest::parse(&want.to_string()).unwrap(), want);
+    assert_eq!(want.choose(&["sha-512", "sha-256"]), Some("sha-256"));
+
+    assert_eq!(
+        DigestPreference::new("sha-256", 11),
+        Err(DigestFieldsError::InvalidPreference)
+    );
+    assert_eq!(
+        DigestPreference::new("sha/256", 1),
+        Err(DigestFieldsError::InvalidAlgorithm)
+    );
+}
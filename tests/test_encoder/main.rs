@@ -3,8 +3,11 @@
 //! PBT でカバーできないエラーパス・境界値・エッジケースのみ記載する。
 
 use shiguredo_http11::{
-    EncodeError, Request, Response, StatusCode, encode_chunk, encode_chunks, encode_request,
-    encode_request_headers, encode_response, encode_response_headers,
+    EncodeError, HeaderCase, HttpHead, Request, Response, StatusCode, encode_chunk,
+    encode_chunk_with_ext, encode_chunks, encode_interim_response, encode_request,
+    encode_request_headers, encode_request_into, encode_request_vectored, encode_response,
+    encode_response_headers, encode_response_headers_into, encode_response_vectored,
+    encode_response_with_interim,
 };
 
 // ========================================
@@ -168,6 +171,52 @@ fn test_encode_chunk_empty() {
     assert_eq!(encoded, b"0\r\n\r\n");
 }
 
+// ========================================
+// encode_chunk_with_ext のテスト
+// ========================================
+
+#[test]
+fn test_encode_chunk_with_ext() {
+    assert_eq!(
+        encode_chunk_with_ext(b"hello", b";a=1;b=\"x y\"").unwrap(),
+        b"5;a=1;b=\"x y\"\r\nhello\r\n"
+    );
+    assert_eq!(
+        encode_chunk_with_ext(b"hello", b" ; a").unwrap(),
+        b"5 ; a\r\nhello\r\n"
+    );
+    // chunk-ext が空の場合は encode_chunk と同じ
+    assert_eq!(
+        encode_chunk_with_ext(b"hello", b"").unwrap(),
+        encode_chunk(b"hello")
+    );
+    // 終端チャンク
+    assert_eq!(
+        encode_chunk_with_ext(b"", b";end").unwrap(),
+        b"0;end\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_encode_chunk_with_ext_invalid() {
+    for ext in [&b"a=1"[..], b" ", b";", b";a=", b";a\r\n", b";a=\"x"] {
+        assert_eq!(
+            encode_chunk_with_ext(b"x", ext),
+            Err(EncodeError::InvalidChunkExtension {
+                ext: String::from_utf8_lossy(ext).into_owned()
+            }),
+            "{ext:?}"
+        );
+    }
+    assert_eq!(
+        EncodeError::InvalidChunkExtension {
+            ext: "a".to_string()
+        }
+        .to_string(),
+        "invalid chunk-ext \"a\" (RFC 9112 Section 7.1.1)"
+    );
+}
+
 // ========================================
 // encode_chunks のテスト
 // ========================================
@@ -561,6 +610,48 @@ fn test_encode_response_crlf_in_header_value() {
     }
 }
 
+#[test]
+fn test_header_value_control_bytes_rejected_on_all_mutators() {
+    // CR / LF / NUL 以外の制御文字 (HTAB を除く) も add_header / set_header の両経路で拒否する
+    for &value in &["v\r\nEvil: 1", "v\x01", "v\x1b[0m", "v\x7f", "v\x00"] {
+        let mut req = Request::new("GET", "/")
+            .unwrap()
+            .header("Host", "example.com")
+            .unwrap();
+        assert!(matches!(
+            req.add_header("X-A", value),
+            Err(EncodeError::InvalidHeaderValue { .. })
+        ));
+        assert!(matches!(
+            req.set_header("Host", value),
+            Err(EncodeError::InvalidHeaderValue { .. })
+        ));
+        // 失敗時は既存ヘッダーを変更しない
+        assert_eq!(req.get_headers("Host"), vec!["example.com"]);
+        assert_eq!(
+            encode_request(&req).unwrap(),
+            b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n"
+        );
+
+        let mut res = Response::with_status(StatusCode::OK);
+        assert!(matches!(
+            res.add_header("X-A", value),
+            Err(EncodeError::InvalidHeaderValue { .. })
+        ));
+        assert!(matches!(
+            res.set_header("X-A", value),
+            Err(EncodeError::InvalidHeaderValue { .. })
+        ));
+        assert!(!res.has_header("X-A"));
+    }
+
+    // HTAB はフィールド値内で許容される (RFC 9110 Section 5.5)
+    let res = Response::with_status(StatusCode::OK)
+        .header("X-A", "a\tb")
+        .unwrap();
+    assert!(encode_response(&res).is_ok());
+}
+
 // ========================================
 // userinfo テスト (RFC 9110 Section 4.2.4)
 // ========================================
@@ -940,6 +1031,17 @@ fn test_encode_request_connect_origin_form_error() {
     ));
 }
 
+#[test]
+fn test_encode_request_origin_form_with_scheme_like_path_ok() {
+    // path に "://" を含んでも "/" で始まれば origin-form (デコーダーと同じ判定順序)
+    let req = Request::new("GET", "/redirect/http://example.com")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    let encoded = encode_request(&req).unwrap();
+    assert!(encoded.starts_with(b"GET /redirect/http://example.com HTTP/1.1\r\n"));
+}
+
 #[test]
 fn test_encode_request_connect_absolute_form_error() {
     // CONNECT で absolute-form は不正
@@ -1369,3 +1471,360 @@ fn test_encode_response_headers_status_code_decimal_boundaries() {
         );
     }
 }
+
+// ========================================
+// encode_request_vectored / encode_response_vectored のテスト
+// ========================================
+
+#[test]
+fn test_encode_request_vectored_validation_error() {
+    // encode_request と同じく Host 欠落を拒否する
+    let req = Request::new("GET", "/").unwrap();
+    assert!(matches!(
+        encode_request_vectored(&req),
+        Err(EncodeError::MissingHostHeader)
+    ));
+}
+
+#[test]
+fn test_encode_request_vectored_without_body() {
+    let req = Request::new("GET", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    let parts = req.encode_vectored().unwrap();
+    assert_eq!(parts.head(), b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    assert!(parts.body().is_empty());
+    assert_eq!(parts.as_slices()[1], b"");
+    assert!(!parts.is_empty());
+}
+
+#[test]
+fn test_encode_response_vectored_bodyless_status() {
+    // 204 / 304 / HEAD (omit_body) ではボディを返さない
+    let res = Response::with_status(StatusCode::NOT_MODIFIED).body(b"ignored".to_vec());
+    let parts = encode_response_vectored(&res).unwrap();
+    assert!(parts.body().is_empty());
+    assert_eq!(parts.to_vec(), encode_response(&res).unwrap());
+
+    let res = Response::with_status(StatusCode::OK)
+        .body(b"hello".to_vec())
+        .omit_body(true);
+    let parts = res.encode_vectored().unwrap();
+    assert!(parts.body().is_empty());
+    assert_eq!(
+        parts.into_head(),
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n".to_vec()
+    );
+}
+
+#[test]
+fn test_encode_response_vectored_borrows_body() {
+    let body = vec![b'x'; 1024 * 1024];
+    let res = Response::with_status(StatusCode::OK).body(body);
+    let parts = res.encode_vectored().unwrap();
+    assert_eq!(parts.body().as_ptr(), res.body_bytes().unwrap().as_ptr());
+    assert_eq!(parts.body().len(), 1024 * 1024);
+}
+
+// ========================================
+// encode_into / encode_to_writer のテスト
+// ========================================
+
+#[test]
+fn test_encode_into_appends_and_reuses_buffer() {
+    let req = Request::new("GET", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    let res = Response::with_status(StatusCode::OK).body(b"hello".to_vec());
+
+    let mut buf = b"prefix".to_vec();
+    encode_request_into(&req, &mut buf).unwrap();
+    assert_eq!(&buf[..6], b"prefix");
+    assert_eq!(&buf[6..], encode_request(&req).unwrap());
+
+    // clear() してバッファを使い回す
+    buf.clear();
+    let capacity = buf.capacity();
+    res.encode_into(&mut buf).unwrap();
+    assert_eq!(buf, encode_response(&res).unwrap());
+    assert!(buf.capacity() >= capacity);
+}
+
+#[test]
+fn test_encode_into_error_leaves_buffer_untouched() {
+    let req = Request::new("GET", "/").unwrap();
+    let mut buf = b"keep".to_vec();
+    assert!(matches!(
+        req.encode_into(&mut buf),
+        Err(EncodeError::MissingHostHeader)
+    ));
+    assert!(matches!(
+        req.encode_headers_into(&mut buf),
+        Err(EncodeError::MissingHostHeader)
+    ));
+    assert_eq!(buf, b"keep");
+}
+
+#[test]
+fn test_encode_headers_into_matches_encode_headers() {
+    let res = Response::with_status(StatusCode::OK)
+        .header("Transfer-Encoding", "chunked")
+        .unwrap();
+    let mut buf = Vec::new();
+    encode_response_headers_into(&res, &mut buf).unwrap();
+    assert_eq!(buf, encode_response_headers(&res).unwrap());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_encode_to_writer() {
+    let req = Request::new("POST", "/upload")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .body(b"data".to_vec());
+    let mut out = Vec::new();
+    req.encode_to_writer(&mut out).unwrap();
+    assert_eq!(out, encode_request(&req).unwrap());
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    req.encode_headers_to_writer(&mut out).unwrap();
+    assert_eq!(out.into_inner(), encode_request_headers(&req).unwrap());
+
+    let res = Response::with_status(StatusCode::NO_CONTENT);
+    let mut out = Vec::new();
+    res.encode_to_writer(&mut out).unwrap();
+    assert_eq!(out, b"HTTP/1.1 204 No Content\r\n\r\n");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_encode_to_writer_validation_error() {
+    let req = Request::new("GET", "/").unwrap();
+    let mut out = Vec::new();
+    let err = req.encode_to_writer(&mut out).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let inner = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<EncodeError>()
+        .unwrap();
+    assert!(matches!(inner, EncodeError::MissingHostHeader));
+    assert!(out.is_empty());
+}
+
+// ========================================
+// ヘッダー名の大文字小文字
+// ========================================
+
+#[test]
+fn test_header_case_lowercase_applies_to_auto_headers() {
+    use shiguredo_http11::date::{DayOfWeek, HttpDate};
+
+    let now = HttpDate::new(DayOfWeek::Sunday, 6, 11, 1994, 8, 49, 37).unwrap();
+    let res = Response::with_status(StatusCode::OK)
+        .header("X-Custom-Header", "v")
+        .unwrap()
+        .auto_date(now)
+        .header_case(HeaderCase::Lowercase)
+        .body(b"ok".to_vec());
+    let expected: &[u8] = b"HTTP/1.1 200 OK\r\nx-custom-header: v\r\n\
+date: Sun, 06 Nov 1994 08:49:37 GMT\r\ncontent-length: 2\r\n\r\nok";
+    assert_eq!(encode_response(&res).unwrap(), expected);
+
+    let headers = encode_response_headers(&res).unwrap();
+    assert!(headers.starts_with(b"HTTP/1.1 200 OK\r\nx-custom-header: v\r\ndate: "));
+}
+
+#[test]
+fn test_header_case_title_case() {
+    let req = Request::new("GET", "/")
+        .unwrap()
+        .header("host", "example.com")
+        .unwrap()
+        .header("x-FORWARDED-for", "192.0.2.1")
+        .unwrap()
+        .header("www-authenticate", "Basic")
+        .unwrap()
+        .header_case(HeaderCase::TitleCase)
+        .body(Vec::new());
+    let expected: &[u8] = b"GET / HTTP/1.1\r\nHost: example.com\r\n\
+X-Forwarded-For: 192.0.2.1\r\nWww-Authenticate: Basic\r\nContent-Length: 0\r\n\r\n";
+    assert_eq!(encode_request(&req).unwrap(), expected);
+
+    // ヘッダーのみのエンコード・vectored・into も同じ表記になる
+    let headers = encode_request_headers(&req).unwrap();
+    assert!(headers.starts_with(b"GET / HTTP/1.1\r\nHost: example.com\r\nX-Forwarded-For:"));
+    assert_eq!(encode_request_vectored(&req).unwrap().to_vec(), expected);
+    let mut buf = Vec::new();
+    encode_request_into(&req, &mut buf).unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn test_header_case_preserve_is_default() {
+    let mut req = Request::new("GET", "/")
+        .unwrap()
+        .header("hOsT", "example.com")
+        .unwrap();
+    assert_eq!(req.header_case_policy(), HeaderCase::Preserve);
+    assert_eq!(
+        encode_request(&req).unwrap(),
+        b"GET / HTTP/1.1\r\nhOsT: example.com\r\n\r\n"
+    );
+
+    req.set_header_case(HeaderCase::Lowercase);
+    assert_eq!(
+        encode_request(&req).unwrap(),
+        b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n"
+    );
+    // 保持しているヘッダー名自体は変更しない
+    assert_eq!(req.headers()[0].0, "hOsT");
+}
+
+// ========================================
+// 中間レスポンス (1xx)
+// ========================================
+
+#[test]
+fn test_encode_interim_response_rejects_non_interim_status() {
+    for status in [
+        StatusCode::OK,
+        StatusCode::SWITCHING_PROTOCOLS,
+        StatusCode::NOT_FOUND,
+    ] {
+        let res = Response::with_status(status);
+        assert_eq!(
+            encode_interim_response(&res),
+            Err(EncodeError::InvalidInterimResponse {
+                status_code: status.code()
+            })
+        );
+    }
+}
+
+#[test]
+fn test_encode_interim_response_rejects_body() {
+    let res = Response::with_status(StatusCode::CONTINUE).body(b"x".to_vec());
+    assert_eq!(
+        encode_interim_response(&res),
+        Err(EncodeError::ForbiddenBodyForInformational { status_code: 100 })
+    );
+    // 空ボディは content を持たないため許容する
+    let res = Response::with_status(StatusCode::CONTINUE).body(Vec::new());
+    assert_eq!(
+        encode_interim_response(&res).unwrap(),
+        b"HTTP/1.1 100 Continue\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_encode_response_with_interim_sequence() {
+    let interim = [
+        Response::with_status(StatusCode::CONTINUE),
+        Response::early_hints(["</a.css>; rel=preload"]).unwrap(),
+    ];
+    let res = Response::with_status(StatusCode::OK).body(b"ok".to_vec());
+    let expected: &[u8] = b"HTTP/1.1 100 Continue\r\n\r\n\
+HTTP/1.1 103 Early Hints\r\nLink: </a.css>; rel=preload\r\n\r\n\
+HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+    assert_eq!(
+        encode_response_with_interim(&interim, &res).unwrap(),
+        expected
+    );
+
+    // 101 は最終レスポンスとして送信できる
+    let upgrade = Response::with_status(StatusCode::SWITCHING_PROTOCOLS);
+    assert!(encode_response_with_interim(&interim[..1], &upgrade).is_ok());
+}
+
+#[test]
+fn test_encode_response_with_interim_errors() {
+    let res = Response::with_status(StatusCode::OK);
+    let bad = [
+        Response::with_status(StatusCode::CONTINUE),
+        Response::with_status(StatusCode::OK),
+    ];
+    assert_eq!(
+        encode_response_with_interim(&bad, &res),
+        Err(EncodeError::InvalidInterimResponse { status_code: 200 })
+    );
+
+    let final_1xx = Response::with_status(StatusCode::EARLY_HINTS);
+    assert_eq!(
+        encode_response_with_interim(&[], &final_1xx),
+        Err(EncodeError::InvalidFinalResponse { status_code: 103 })
+    );
+
+    // 最終レスポンスの検証は encode_response と同じ
+    let mismatch = Response::with_status(StatusCode::OK)
+        .header("Content-Length", "5")
+        .unwrap()
+        .body(b"ok".to_vec());
+    assert!(matches!(
+        encode_response_with_interim(&[], &mismatch),
+        Err(EncodeError::ContentLengthMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_interim_error_display() {
+    assert_eq!(
+        EncodeError::ForbiddenBodyForInformational { status_code: 103 }.to_string(),
+        "103 response must not contain a body (RFC 9110 Section 15.2)"
+    );
+    assert_eq!(
+        EncodeError::InvalidInterimResponse { status_code: 101 }.to_string(),
+        "101 response cannot be sent as an interim response (RFC 9110 Section 15.2)"
+    );
+}
+
+// ========================================
+// encoded_len_hint
+// ========================================
+
+#[test]
+fn test_encoded_len_hint_exact_without_auto_content_length() {
+    let req = Request::new("GET", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    assert_eq!(req.encoded_len_hint(), encode_request(&req).unwrap().len());
+
+    let res = Response::with_status(StatusCode::NO_CONTENT);
+    assert_eq!(res.encoded_len_hint(), encode_response(&res).unwrap().len());
+}
+
+#[test]
+fn test_encoded_len_hint_with_auto_content_length() {
+    let res = Response::with_status(StatusCode::OK).body(Vec::new());
+    let len = encode_response(&res).unwrap().len();
+    // "Content-Length: 0" の値 1 桁に対して 20 桁で見積もる
+    assert_eq!(res.encoded_len_hint(), len + 19);
+
+    // HEAD 応答 (omit_body) ではボディを見積もりに含めない
+    let res = Response::with_status(StatusCode::OK)
+        .header("Content-Length", "1000")
+        .unwrap()
+        .body(Vec::new())
+        .omit_body(true);
+    assert_eq!(res.encoded_len_hint(), encode_response(&res).unwrap().len());
+}
+
+#[test]
+fn test_encoded_len_hint_used_for_single_allocation() {
+    let mut res = Response::with_status(StatusCode::OK).body(vec![b'x'; 4096]);
+    for i in 0..64 {
+        res.add_header(format!("X-Header-{i}"), "v".repeat(64))
+            .unwrap();
+    }
+    let encoded = res.encode().unwrap();
+    assert_eq!(encoded.capacity(), res.encoded_len_hint());
+
+    let mut buf = Vec::with_capacity(res.encoded_len_hint());
+    let capacity = buf.capacity();
+    res.encode_into(&mut buf).unwrap();
+    assert_eq!(buf.capacity(), capacity);
+}
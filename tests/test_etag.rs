@@ -1,6 +1,8 @@
 //! ETag ヘッダーのユニットテスト
 
-use shiguredo_http11::etag::{ETagList, EntityTag, parse_etag_list};
+use shiguredo_http11::etag::{
+    ETagHasher, ETagList, EntityTag, if_match_matches, if_none_match_matches, parse_etag_list,
+};
 
 #[test]
 fn test_parse_weak_lowercase_rejected() {
@@ -85,3 +87,133 @@ fn test_etag_list_display() {
     let any = parse_etag_list("*").unwrap();
     assert_eq!(any.to_string(), "*");
 }
+
+// ========================================
+// If-None-Match / If-Match の判定のテスト
+// ========================================
+
+#[test]
+fn test_if_none_match_matches_weak_comparison() {
+    let strong = EntityTag::strong("v1").unwrap();
+    let weak = EntityTag::weak("v1").unwrap();
+    assert!(if_none_match_matches("\"v1\"", Some(&strong)));
+    assert!(if_none_match_matches("W/\"v1\"", Some(&strong)));
+    assert!(if_none_match_matches("\"v1\"", Some(&weak)));
+    assert!(if_none_match_matches("\"v0\", W/\"v1\"", Some(&weak)));
+    assert!(!if_none_match_matches("\"v2\"", Some(&strong)));
+}
+
+#[test]
+fn test_if_none_match_matches_any_and_missing() {
+    let current = EntityTag::strong("v1").unwrap();
+    assert!(if_none_match_matches("*", Some(&current)));
+    assert!(!if_none_match_matches("*", None));
+    assert!(!if_none_match_matches("\"v1\"", None));
+}
+
+#[test]
+fn test_if_none_match_matches_invalid_is_ignored() {
+    let current = EntityTag::strong("v1").unwrap();
+    // 引用符のない値は不正なため条件を無視する
+    assert!(!if_none_match_matches("v1", Some(&current)));
+    assert!(!if_none_match_matches("", Some(&current)));
+}
+
+#[test]
+fn test_if_match_matches_strong_comparison() {
+    let strong = EntityTag::strong("v1").unwrap();
+    let weak = EntityTag::weak("v1").unwrap();
+    assert!(if_match_matches("\"v1\"", Some(&strong)));
+    assert!(if_match_matches("\"v0\", \"v1\"", Some(&strong)));
+    assert!(!if_match_matches("W/\"v1\"", Some(&strong)));
+    assert!(!if_match_matches("\"v1\"", Some(&weak)));
+    assert!(!if_match_matches("\"v2\"", Some(&strong)));
+}
+
+#[test]
+fn test_if_match_matches_any_and_invalid() {
+    let current = EntityTag::strong("v1").unwrap();
+    assert!(if_match_matches("*", Some(&current)));
+    assert!(!if_match_matches("*", None));
+    assert!(!if_match_matches("v1", Some(&current)));
+}
+
+// ========================================
+// ETag 生成のテスト
+// ========================================
+
+#[test]
+fn test_from_content_known_value() {
+    // SHA-256("hello") の先頭 16 バイト
+    let etag = EntityTag::from_content(b"hello");
+    assert!(etag.is_strong());
+    assert_eq!(etag.tag(), "2cf24dba5fb0a30e26e83b2ac5b9e29e");
+    assert_eq!(etag.to_string(), "\"2cf24dba5fb0a30e26e83b2ac5b9e29e\"");
+
+    let empty = EntityTag::from_content(b"");
+    assert_eq!(empty.tag(), "e3b0c44298fc1c149afbf4c8996fb924");
+}
+
+#[test]
+fn test_from_content_differs_by_content() {
+    assert_ne!(
+        EntityTag::from_content(b"hello"),
+        EntityTag::from_content(b"hello!")
+    );
+}
+
+#[test]
+fn test_weak_from_content() {
+    let strong = EntityTag::from_content(b"hello");
+    let weak = EntityTag::weak_from_content(b"hello");
+    assert!(weak.is_weak());
+    assert_eq!(weak.tag(), strong.tag());
+    assert!(weak.weak_compare(&strong));
+    assert!(!weak.strong_compare(&strong));
+}
+
+#[test]
+fn test_generated_etag_roundtrip() {
+    let etag = EntityTag::weak_from_content(b"body");
+    assert_eq!(EntityTag::parse(&etag.to_string()).unwrap(), etag);
+}
+
+#[test]
+fn test_from_metadata() {
+    let etag = EntityTag::from_metadata(1024, 1_700_000_000);
+    assert!(etag.is_weak());
+    assert_eq!(etag.tag(), "400-6553f100");
+    assert_eq!(etag.to_string(), "W/\"400-6553f100\"");
+
+    let etag = EntityTag::strong_from_metadata(0, 0);
+    assert!(etag.is_strong());
+    assert_eq!(etag.to_string(), "\"0-0\"");
+}
+
+#[test]
+fn test_from_metadata_differs_by_size_and_mtime() {
+    let base = EntityTag::from_metadata(10, 100);
+    assert_ne!(base, EntityTag::from_metadata(11, 100));
+    assert_ne!(base, EntityTag::from_metadata(10, 101));
+}
+
+#[test]
+fn test_etag_hasher_streaming() {
+    let mut hasher = ETagHasher::new();
+    hasher.update(b"hel").update(b"").update(b"lo");
+    assert_eq!(hasher.finish(), EntityTag::from_content(b"hello"));
+
+    let mut hasher = ETagHasher::default();
+    hasher.update(b"hello");
+    assert_eq!(hasher.finish_weak(), EntityTag::weak_from_content(b"hello"));
+}
+
+#[test]
+fn test_etag_hasher_large_input() {
+    let data: Vec<u8> = (0..=255u8).cycle().take(100_000).collect();
+    let mut hasher = ETagHasher::new();
+    for chunk in data.chunks(4096) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hasher.finish(), EntityTag::from_content(&data));
+}
@@ -1,6 +1,7 @@
 //! Expect ヘッダーのユニットテスト
 
-use shiguredo_http11::expect::{Expect, ExpectError};
+use shiguredo_http11::expect::{Expect, ExpectError, ExpectOutcome, evaluate};
+use shiguredo_http11::{RequestDecoder, Response};
 
 // ========================================
 // ExpectError のテスト
@@ -276,3 +277,99 @@ fn test_expect_unterminated_quote() {
         Err(ExpectError::UnterminatedQuote),
     );
 }
+
+// ========================================
+// 未知の expectation のテスト
+// ========================================
+
+#[test]
+fn test_expect_unknown() {
+    let expect = Expect::parse("100-continue").unwrap();
+    assert!(!expect.has_unknown());
+    assert!(expect.items()[0].is_known());
+
+    let expect = Expect::parse("100-Continue, x-foo, bar=\"a b\"").unwrap();
+    assert!(expect.has_unknown());
+    let unknown: Vec<String> = expect.unknown().map(|item| item.to_string()).collect();
+    assert_eq!(unknown, ["x-foo", "bar=\"a b\""]);
+
+    // 値付きの 100-continue は定義されていない
+    let expect = Expect::parse("100-continue=1").unwrap();
+    assert!(expect.has_100_continue());
+    assert!(expect.has_unknown());
+    assert!(!expect.items()[0].is_known());
+
+    // 空の Expect
+    let expect = Expect::parse("").unwrap();
+    assert!(!expect.has_unknown());
+}
+
+// ========================================
+// evaluate のテスト
+// ========================================
+
+fn evaluate_raw(raw: &str) -> ExpectOutcome {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw.as_bytes()).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    evaluate(&head)
+}
+
+#[test]
+fn test_expect_evaluate() {
+    assert_eq!(
+        evaluate_raw("PUT / HTTP/1.1\r\nHost: a\r\nContent-Length: 1\r\n\r\n"),
+        ExpectOutcome::None
+    );
+    assert_eq!(
+        evaluate_raw(
+            "PUT / HTTP/1.1\r\nHost: a\r\nExpect: 100-continue\r\nContent-Length: 1\r\n\r\n"
+        ),
+        ExpectOutcome::Continue
+    );
+    assert_eq!(
+        evaluate_raw(
+            "PUT / HTTP/1.1\r\nHost: a\r\nExpect: 100-CONTINUE\r\nContent-Length: 1\r\n\r\n"
+        ),
+        ExpectOutcome::Continue
+    );
+    assert_eq!(
+        evaluate_raw("PUT / HTTP/1.1\r\nHost: a\r\nExpect: x-foo\r\nContent-Length: 1\r\n\r\n"),
+        ExpectOutcome::Reject
+    );
+    // 複数行の Expect はまとめて判定する
+    assert_eq!(
+        evaluate_raw(
+            "PUT / HTTP/1.1\r\nHost: a\r\nExpect: 100-continue\r\nExpect: x-foo\r\nContent-Length: 1\r\n\r\n"
+        ),
+        ExpectOutcome::Reject
+    );
+    // パースできない Expect
+    assert_eq!(
+        evaluate_raw("PUT / HTTP/1.1\r\nHost: a\r\nExpect: =x\r\nContent-Length: 1\r\n\r\n"),
+        ExpectOutcome::Reject
+    );
+    // 空の Expect
+    assert_eq!(
+        evaluate_raw("PUT / HTTP/1.1\r\nHost: a\r\nExpect: \r\nContent-Length: 1\r\n\r\n"),
+        ExpectOutcome::None
+    );
+    // RFC 9110 Section 10.1.1: HTTP/1.0 の 100-continue は無視する
+    assert_eq!(
+        evaluate_raw("PUT / HTTP/1.0\r\nExpect: 100-continue\r\nContent-Length: 1\r\n\r\n"),
+        ExpectOutcome::None
+    );
+    assert_eq!(
+        evaluate_raw("PUT / HTTP/1.0\r\nExpect: x-foo\r\nContent-Length: 1\r\n\r\n"),
+        ExpectOutcome::Reject
+    );
+}
+
+#[test]
+fn test_expect_expectation_failed_response() {
+    let response = Response::expectation_failed();
+    assert_eq!(response.status_code(), 417);
+    assert_eq!(response.reason_phrase(), "Expectation Failed");
+    let encoded = String::from_utf8(response.encode().unwrap()).unwrap();
+    assert!(encoded.starts_with("HTTP/1.1 417 Expectation Failed\r\n"));
+}
@@ -0,0 +1,114 @@
+//! ExtValue (RFC 8187) のユニットテスト
+
+use shiguredo_http11::ext_value::{ExtValue, ExtValueError};
+
+// ========================================
+// ExtValueError のテスト
+// ========================================
+
+#[test]
+fn test_ext_value_error_display() {
+    let errors = [
+        (ExtValueError::InvalidFormat, "invalid ext-value format"),
+        (
+            ExtValueError::UnsupportedCharset,
+            "unsupported ext-value charset",
+        ),
+        (
+            ExtValueError::InvalidLanguage,
+            "invalid ext-value language tag",
+        ),
+        (
+            ExtValueError::InvalidEncoding,
+            "invalid ext-value percent-encoding",
+        ),
+        (ExtValueError::InvalidUtf8, "invalid UTF-8 in ext-value"),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_ext_value_parse() {
+    // RFC 8187 Section 3.2.2 の例
+    let ext = ExtValue::parse("UTF-8''%c2%a3%20and%20%e2%82%ac%20rates").unwrap();
+    assert_eq!(ext.value(), "£ and € rates");
+    assert_eq!(ext.language(), None);
+
+    let ext = ExtValue::parse("utf-8'en-US'plain.txt").unwrap();
+    assert_eq!(ext.value(), "plain.txt");
+    assert_eq!(ext.language(), Some("en-US"));
+
+    let ext = ExtValue::parse("UTF-8''").unwrap();
+    assert_eq!(ext.value(), "");
+    assert_eq!(ext.into_value(), "");
+}
+
+#[test]
+fn test_ext_value_parse_errors() {
+    let cases = [
+        ("", ExtValueError::InvalidFormat),
+        ("UTF-8", ExtValueError::InvalidFormat),
+        ("UTF-8'abc", ExtValueError::InvalidFormat),
+        ("ISO-8859-1''abc", ExtValueError::UnsupportedCharset),
+        ("''abc", ExtValueError::UnsupportedCharset),
+        ("UTF-8'en_US'abc", ExtValueError::InvalidLanguage),
+        ("UTF-8'123'abc", ExtValueError::InvalidLanguage),
+        ("UTF-8''a b", ExtValueError::InvalidEncoding),
+        ("UTF-8''a'b", ExtValueError::InvalidEncoding),
+        ("UTF-8''%", ExtValueError::InvalidEncoding),
+        ("UTF-8''%2", ExtValueError::InvalidEncoding),
+        ("UTF-8''%GG", ExtValueError::InvalidEncoding),
+        ("UTF-8''%+5", ExtValueError::InvalidEncoding),
+        ("UTF-8''日本", ExtValueError::InvalidEncoding),
+        ("UTF-8''%FF%FE", ExtValueError::InvalidUtf8),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(ExtValue::parse(input).unwrap_err(), expected, "{}", input);
+    }
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_ext_value_display() {
+    assert_eq!(ExtValue::new("abc.txt").to_string(), "UTF-8''abc.txt");
+    assert_eq!(
+        ExtValue::new("£ and € rates").to_string(),
+        "UTF-8''%C2%A3%20and%20%E2%82%AC%20rates"
+    );
+    assert_eq!(
+        ExtValue::new("a'b%c")
+            .with_language("en")
+            .unwrap()
+            .to_string(),
+        "UTF-8'en'a%27b%25c"
+    );
+}
+
+#[test]
+fn test_ext_value_with_language_invalid() {
+    assert_eq!(
+        ExtValue::new("a").with_language("").unwrap_err(),
+        ExtValueError::InvalidLanguage
+    );
+    assert_eq!(
+        ExtValue::new("a").with_language("en US").unwrap_err(),
+        ExtValueError::InvalidLanguage
+    );
+}
+
+#[test]
+fn test_ext_value_roundtrip() {
+    let ext = ExtValue::new("日本語 ファイル.txt")
+        .with_language("ja")
+        .unwrap();
+    assert_eq!(ExtValue::parse(&ext.to_string()).unwrap(), ext);
+}
@@ -0,0 +1,233 @@
+//! Fetch Metadata リクエストヘッダーのユニットテスト
+
+use shiguredo_http11::Request;
+use shiguredo_http11::fetch_metadata::{
+    FetchDest, FetchMetadata, FetchMetadataError, FetchMode, FetchSite, FetchUser, PolicyDecision,
+    ResourceIsolationPolicy,
+};
+use shiguredo_http11::typed_header::TypedHeader;
+
+// ========================================
+// FetchMetadataError のテスト
+// ========================================
+
+#[test]
+fn test_fetch_metadata_error_display() {
+    let errors = [
+        (
+            FetchMetadataError::InvalidFormat,
+            "invalid fetch metadata header format",
+        ),
+        (
+            FetchMetadataError::UnknownValue,
+            "unknown fetch metadata value",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// 各ヘッダーのパース
+// ========================================
+
+#[test]
+fn test_parse_fetch_site() {
+    assert_eq!(FetchSite::parse("cross-site"), Ok(FetchSite::CrossSite));
+    assert_eq!(FetchSite::parse("same-origin"), Ok(FetchSite::SameOrigin));
+    assert_eq!(FetchSite::parse("same-site"), Ok(FetchSite::SameSite));
+    assert_eq!(FetchSite::parse("none"), Ok(FetchSite::None));
+    // sf-item のパラメーターは無視する
+    assert_eq!(FetchSite::parse(" none;foo=1 "), Ok(FetchSite::None));
+
+    assert_eq!(
+        FetchSite::parse("other-site"),
+        Err(FetchMetadataError::UnknownValue)
+    );
+    // sf-token は大文字と小文字を区別する
+    assert_eq!(
+        FetchSite::parse("Cross-Site"),
+        Err(FetchMetadataError::UnknownValue)
+    );
+    assert_eq!(
+        FetchSite::parse("\"cross-site\""),
+        Err(FetchMetadataError::InvalidFormat)
+    );
+    assert_eq!(
+        FetchSite::parse("cross-site, none"),
+        Err(FetchMetadataError::InvalidFormat)
+    );
+    assert_eq!(FetchSite::parse(""), Err(FetchMetadataError::InvalidFormat));
+}
+
+#[test]
+fn test_parse_fetch_mode_and_dest() {
+    assert_eq!(FetchMode::parse("navigate"), Ok(FetchMode::Navigate));
+    assert_eq!(FetchMode::parse("no-cors"), Ok(FetchMode::NoCors));
+    assert_eq!(FetchMode::parse("websocket"), Ok(FetchMode::WebSocket));
+    assert_eq!(
+        FetchMode::parse("nested-navigate"),
+        Err(FetchMetadataError::UnknownValue)
+    );
+
+    assert_eq!(FetchDest::parse("document"), Ok(FetchDest::Document));
+    assert_eq!(FetchDest::parse("empty"), Ok(FetchDest::Empty));
+    assert_eq!(
+        FetchDest::parse("serviceworker"),
+        Ok(FetchDest::ServiceWorker)
+    );
+    assert_eq!(
+        FetchDest::parse("unknown"),
+        Err(FetchMetadataError::UnknownValue)
+    );
+}
+
+#[test]
+fn test_parse_fetch_user() {
+    assert_eq!(FetchUser::parse("?1"), Ok(FetchUser(true)));
+    assert_eq!(FetchUser::parse("?0"), Ok(FetchUser(false)));
+    assert_eq!(
+        FetchUser::parse("1"),
+        Err(FetchMetadataError::InvalidFormat)
+    );
+    assert_eq!(
+        FetchUser::parse("true"),
+        Err(FetchMetadataError::InvalidFormat)
+    );
+}
+
+#[test]
+fn test_typed_header() {
+    assert_eq!(FetchSite::NAME, "Sec-Fetch-Site");
+    assert_eq!(FetchMode::NAME, "Sec-Fetch-Mode");
+    assert_eq!(FetchDest::NAME, "Sec-Fetch-Dest");
+    assert_eq!(FetchUser::NAME, "Sec-Fetch-User");
+    assert_eq!(FetchSite::SameSite.encode_value(), "same-site");
+    assert_eq!(FetchMode::NoCors.encode_value(), "no-cors");
+    assert_eq!(FetchDest::AudioWorklet.encode_value(), "audioworklet");
+    assert_eq!(FetchUser(true).encode_value(), "?1");
+}
+
+// ========================================
+// FetchMetadata のテスト
+// ========================================
+
+#[test]
+fn test_from_request() {
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Sec-Fetch-Site", "same-origin")
+        .unwrap()
+        .header("Sec-Fetch-Mode", "navigate")
+        .unwrap()
+        .header("Sec-Fetch-Dest", "document")
+        .unwrap()
+        .header("Sec-Fetch-User", "?1")
+        .unwrap();
+    let metadata = FetchMetadata::from_request(&request);
+    assert!(metadata.is_present());
+    assert_eq!(metadata.site(), Some(FetchSite::SameOrigin));
+    assert_eq!(metadata.mode(), Some(FetchMode::Navigate));
+    assert_eq!(metadata.dest(), Some(FetchDest::Document));
+    assert!(metadata.is_user_activated());
+    assert_eq!(
+        metadata,
+        FetchMetadata::new(
+            FetchSite::SameOrigin,
+            FetchMode::Navigate,
+            FetchDest::Document
+        )
+        .with_user()
+    );
+}
+
+#[test]
+fn test_from_request_ignores_invalid_values() {
+    let metadata = FetchMetadata::from_request(&Request::new("GET", "/").unwrap());
+    assert_eq!(metadata, FetchMetadata::default());
+    assert!(!metadata.is_present());
+
+    // 不正な値・未知の値・複数行のヘッダーは無視する
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Sec-Fetch-Site", "cross-site")
+        .unwrap()
+        .header("Sec-Fetch-Site", "same-origin")
+        .unwrap()
+        .header("Sec-Fetch-Mode", "unknown")
+        .unwrap()
+        .header("Sec-Fetch-Dest", "\"image\"")
+        .unwrap()
+        .header("Sec-Fetch-User", "?0")
+        .unwrap();
+    let metadata = FetchMetadata::from_request(&request);
+    assert_eq!(metadata.site(), None);
+    assert_eq!(metadata.mode(), None);
+    assert_eq!(metadata.dest(), None);
+    assert!(!metadata.is_user_activated());
+}
+
+// ========================================
+// ResourceIsolationPolicy のテスト
+// ========================================
+
+#[test]
+fn test_policy_default() {
+    let policy = ResourceIsolationPolicy::new();
+
+    // Fetch Metadata を送らないクライアントは許可
+    assert_eq!(
+        policy.evaluate("POST", &FetchMetadata::default()),
+        PolicyDecision::Allow
+    );
+
+    for site in [FetchSite::SameOrigin, FetchSite::SameSite, FetchSite::None] {
+        let metadata = FetchMetadata::new(site, FetchMode::Cors, FetchDest::Empty);
+        assert!(policy.evaluate("DELETE", &metadata).is_allowed());
+    }
+
+    // クロスサイトのナビゲーションは GET のみ許可
+    let navigate = FetchMetadata::new(
+        FetchSite::CrossSite,
+        FetchMode::Navigate,
+        FetchDest::Document,
+    );
+    assert!(policy.evaluate("GET", &navigate).is_allowed());
+    assert!(!policy.evaluate("POST", &navigate).is_allowed());
+
+    // object / embed へのナビゲーションは拒否
+    let embed = FetchMetadata::new(FetchSite::CrossSite, FetchMode::Navigate, FetchDest::Embed);
+    assert_eq!(policy.evaluate("GET", &embed), PolicyDecision::Reject);
+
+    // クロスサイトの no-cors / cors は拒否
+    let image = FetchMetadata::new(FetchSite::CrossSite, FetchMode::NoCors, FetchDest::Image);
+    assert!(!policy.evaluate("GET", &image).is_allowed());
+    let cors = FetchMetadata::new(FetchSite::CrossSite, FetchMode::Cors, FetchDest::Empty);
+    assert!(!policy.evaluate("POST", &cors).is_allowed());
+}
+
+#[test]
+fn test_policy_options() {
+    let same_site = FetchMetadata::new(FetchSite::SameSite, FetchMode::Cors, FetchDest::Empty);
+    let policy = ResourceIsolationPolicy::new().with_same_origin_only();
+    assert!(!policy.evaluate("GET", &same_site).is_allowed());
+    let same_origin = FetchMetadata::new(FetchSite::SameOrigin, FetchMode::Cors, FetchDest::Empty);
+    assert!(policy.evaluate("GET", &same_origin).is_allowed());
+
+    let navigate = FetchMetadata::new(
+        FetchSite::CrossSite,
+        FetchMode::Navigate,
+        FetchDest::Document,
+    );
+    let policy = ResourceIsolationPolicy::new().without_cross_site_navigation();
+    assert!(!policy.evaluate("GET", &navigate).is_allowed());
+
+    // 状態を変更するエンドポイントへのクロスサイト no-cors リクエストのみ拒否する
+    let image = FetchMetadata::new(FetchSite::CrossSite, FetchMode::NoCors, FetchDest::Image);
+    let policy = ResourceIsolationPolicy::new().with_cross_site_safe_requests();
+    assert!(policy.evaluate("GET", &image).is_allowed());
+    assert!(policy.evaluate("HEAD", &image).is_allowed());
+    assert!(!policy.evaluate("POST", &image).is_allowed());
+    assert!(!policy.evaluate("PUT", &image).is_allowed());
+}
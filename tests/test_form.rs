@@ -0,0 +1,97 @@
+//! application/x-www-form-urlencoded のユニットテスト
+
+use shiguredo_http11::form::{self, CONTENT_TYPE};
+use shiguredo_http11::{Request, RequestDecoder};
+
+fn pair(name: &str, value: &str) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+// ========================================
+// serialize
+// ========================================
+
+#[test]
+fn test_serialize_reserved_characters() {
+    assert_eq!(
+        form::serialize(&[("a b", "c&d=e"), ("*-._", "~!'()")]),
+        "a+b=c%26d%3De&*-._=%7E%21%27%28%29"
+    );
+    assert_eq!(form::serialize(&[("plus", "1+1")]), "plus=1%2B1");
+    assert_eq!(form::serialize::<&str, &str>(&[]), "");
+    assert_eq!(form::serialize(&[("", "")]), "=");
+}
+
+#[test]
+fn test_serialize_owned_pairs() {
+    let pairs = vec![pair("q", "rust http"), pair("page", "2")];
+    assert_eq!(form::serialize(&pairs), "q=rust+http&page=2");
+}
+
+// ========================================
+// parse
+// ========================================
+
+#[test]
+fn test_parse_plus_and_percent() {
+    assert_eq!(
+        form::parse("q=rust+http%21&x=%E6%97%A5"),
+        [pair("q", "rust http!"), pair("x", "日")]
+    );
+    // %2B はデコード後の + でありスペースにしない
+    assert_eq!(form::parse("a=1%2B1"), [pair("a", "1+1")]);
+}
+
+#[test]
+fn test_parse_empty_and_missing_value() {
+    assert!(form::parse("").is_empty());
+    assert_eq!(
+        form::parse("&a&&b=&=c&"),
+        [pair("a", ""), pair("b", ""), pair("", "c")]
+    );
+    // 最初の = で分割する
+    assert_eq!(form::parse("a=b=c"), [pair("a", "b=c")]);
+}
+
+#[test]
+fn test_parse_invalid_percent_encoding_is_kept() {
+    assert_eq!(
+        form::parse("a=%&b=%4&c=%zz&d=100%"),
+        [
+            pair("a", "%"),
+            pair("b", "%4"),
+            pair("c", "%zz"),
+            pair("d", "100%")
+        ]
+    );
+}
+
+#[test]
+fn test_parse_invalid_utf8_is_replaced() {
+    assert_eq!(form::parse("a=%FF"), [pair("a", "\u{FFFD}")]);
+}
+
+// ========================================
+// Request::form_body
+// ========================================
+
+#[test]
+fn test_form_body_replaces_content_type_and_roundtrips() {
+    let request = Request::new("POST", "/submit")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .header("content-type", "text/plain")
+        .unwrap()
+        .form_body(&[("name", "山田 太郎"), ("agree", "yes")]);
+    assert_eq!(request.get_headers("Content-Type"), [CONTENT_TYPE]);
+
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(&request.encode().unwrap()).unwrap();
+    let decoded = decoder.decode().unwrap().unwrap();
+    let body = std::str::from_utf8(decoded.body_bytes().unwrap()).unwrap();
+    assert_eq!(
+        form::parse(body),
+        [pair("name", "山田 太郎"), pair("agree", "yes")]
+    );
+}
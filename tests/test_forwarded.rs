@@ -0,0 +1,420 @@
+//! Forwarded / X-Forwarded-* ヘッダーのユニットテスト
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use shiguredo_http11::forwarded::{
+    Forwarded, ForwardedElement, ForwardedError, Node, NodeName, NodePort, append_forwarded,
+    append_x_forwarded,
+};
+use shiguredo_http11::{Request, RequestDecoder};
+
+// ========================================
+// ForwardedError のテスト
+// ========================================
+
+#[test]
+fn test_forwarded_error_display() {
+    let errors = [
+        (
+            ForwardedError::InvalidFormat,
+            "invalid Forwarded header format",
+        ),
+        (
+            ForwardedError::InvalidParameterName,
+            "invalid Forwarded parameter name",
+        ),
+        (
+            ForwardedError::DuplicateParameter("for".to_string()),
+            "duplicate Forwarded parameter: for",
+        ),
+        (ForwardedError::InvalidValue, "invalid Forwarded value"),
+        (
+            ForwardedError::UnterminatedQuote,
+            "unterminated quoted-string",
+        ),
+        (ForwardedError::InvalidNode, "invalid Forwarded node"),
+        (ForwardedError::InvalidHost, "invalid Forwarded host"),
+        (ForwardedError::InvalidProto, "invalid Forwarded proto"),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// Node のテスト
+// ========================================
+
+#[test]
+fn test_node_parse() {
+    let node = Node::parse("192.0.2.43").unwrap();
+    assert_eq!(node.ip(), Some(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 43))));
+    assert_eq!(node.port(), None);
+
+    let node = Node::parse("[2001:db8:cafe::17]:4711").unwrap();
+    assert_eq!(
+        node.ip(),
+        Some(IpAddr::V6("2001:db8:cafe::17".parse::<Ipv6Addr>().unwrap()))
+    );
+    assert_eq!(node.port(), Some(&NodePort::Port(4711)));
+
+    assert_eq!(Node::parse("unknown").unwrap().name(), &NodeName::Unknown);
+    assert_eq!(Node::parse("UNKNOWN").unwrap().name(), &NodeName::Unknown);
+
+    let node = Node::parse("_hidden:_SEVKISEK").unwrap();
+    assert_eq!(node.name(), &NodeName::Obfuscated("_hidden".to_string()));
+    assert_eq!(
+        node.port(),
+        Some(&NodePort::Obfuscated("_SEVKISEK".to_string()))
+    );
+}
+
+#[test]
+fn test_node_parse_errors() {
+    let inputs = [
+        "",
+        "example.com",
+        "2001:db8::1",
+        "[2001:db8::1",
+        "[2001:db8::1]x",
+        "[192.0.2.1]",
+        "192.0.2.1:",
+        "192.0.2.1:65536",
+        "192.0.2.1:000080",
+        "192.0.2.1:http",
+        "_",
+        "_a b",
+    ];
+    for input in inputs {
+        assert_eq!(
+            Node::parse(input),
+            Err(ForwardedError::InvalidNode),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_node_display() {
+    let node = Node::from(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080));
+    assert_eq!(node.to_string(), "[::1]:8080");
+
+    let node = Node::from(IpAddr::V4(Ipv4Addr::new(198, 51, 100, 17)));
+    assert_eq!(node.to_string(), "198.51.100.17");
+
+    assert_eq!(Node::unknown().to_string(), "unknown");
+
+    let node = Node::obfuscated("_proxy1")
+        .unwrap()
+        .with_obfuscated_port("_p")
+        .unwrap();
+    assert_eq!(node.to_string(), "_proxy1:_p");
+
+    assert!(Node::obfuscated("proxy1").is_err());
+    assert!(Node::unknown().with_obfuscated_port("1").is_err());
+}
+
+// ========================================
+// Forwarded パースのテスト
+// ========================================
+
+#[test]
+fn test_forwarded_parse_rfc7239_examples() {
+    // RFC 7239 Section 4
+    let forwarded = Forwarded::parse(r#"for="_gazonk""#).unwrap();
+    assert_eq!(
+        forwarded.elements()[0].for_node().unwrap().name(),
+        &NodeName::Obfuscated("_gazonk".to_string())
+    );
+
+    let forwarded = Forwarded::parse(r#"For="[2001:db8:cafe::17]:4711""#).unwrap();
+    assert_eq!(
+        forwarded.elements()[0].for_node().unwrap().to_string(),
+        "[2001:db8:cafe::17]:4711"
+    );
+
+    let forwarded = Forwarded::parse("for=192.0.2.60;proto=http;by=203.0.113.43").unwrap();
+    let element = &forwarded.elements()[0];
+    assert_eq!(element.for_node().unwrap().to_string(), "192.0.2.60");
+    assert_eq!(element.proto(), Some("http"));
+    assert_eq!(element.by().unwrap().to_string(), "203.0.113.43");
+
+    let forwarded = Forwarded::parse("for=192.0.2.43, for=198.51.100.17").unwrap();
+    assert_eq!(forwarded.elements().len(), 2);
+    assert_eq!(
+        forwarded.elements()[1].for_node().unwrap().to_string(),
+        "198.51.100.17"
+    );
+}
+
+#[test]
+fn test_forwarded_parse_host_and_extensions() {
+    let forwarded =
+        Forwarded::parse(r#"host="example.com:8080";PROTO=HTTPS;secret="a b""#).unwrap();
+    let element = &forwarded.elements()[0];
+    assert_eq!(element.host().unwrap().host(), "example.com");
+    assert_eq!(element.host().unwrap().port(), Some(8080));
+    assert_eq!(element.proto(), Some("https"));
+    assert_eq!(
+        element.extensions(),
+        &[("secret".to_string(), "a b".to_string())]
+    );
+}
+
+#[test]
+fn test_forwarded_parse_empty_elements() {
+    assert!(Forwarded::parse("").unwrap().elements().is_empty());
+
+    let forwarded = Forwarded::parse(" , for=192.0.2.1;, ").unwrap();
+    assert_eq!(forwarded.elements().len(), 1);
+    assert_eq!(forwarded.to_string(), "for=192.0.2.1");
+
+    // 区切りの中のカンマは要素を分けない
+    let forwarded = Forwarded::parse(r#"for=_a;ext="x, y", for=_b"#).unwrap();
+    assert_eq!(forwarded.elements().len(), 2);
+}
+
+#[test]
+fn test_forwarded_display_empty_element() {
+    // 空の要素は空文字列になり、パースすると要素は残らない
+    let forwarded = Forwarded::new().with_element(ForwardedElement::new());
+    assert!(forwarded.elements()[0].is_empty());
+    assert_eq!(forwarded.to_string(), "");
+    assert!(
+        Forwarded::parse(&forwarded.to_string())
+            .unwrap()
+            .elements()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_forwarded_parse_errors() {
+    let cases = [
+        ("for", ForwardedError::InvalidFormat),
+        ("f or=_a", ForwardedError::InvalidParameterName),
+        ("=_a", ForwardedError::InvalidParameterName),
+        (
+            "for=_a;For=_b",
+            ForwardedError::DuplicateParameter("for".to_string()),
+        ),
+        ("for=", ForwardedError::InvalidValue),
+        ("for=[::1]", ForwardedError::InvalidValue),
+        (r#"for="_a"x"#, ForwardedError::InvalidValue),
+        (r#"for="_a"#, ForwardedError::UnterminatedQuote),
+        ("for=example.com", ForwardedError::InvalidNode),
+        (r#"host="a b""#, ForwardedError::InvalidHost),
+        ("proto=1http", ForwardedError::InvalidProto),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Forwarded::parse(input), Err(expected), "{input}");
+    }
+}
+
+// ========================================
+// Forwarded 生成のテスト
+// ========================================
+
+#[test]
+fn test_forwarded_display_quotes_when_needed() {
+    let element = ForwardedElement::new()
+        .with_for(Node::from(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(192, 0, 2, 60)),
+            443,
+        )))
+        .with_by(Node::obfuscated("_proxy").unwrap())
+        .with_host("example.com")
+        .unwrap()
+        .with_proto("HTTPS")
+        .unwrap();
+    assert_eq!(
+        element.to_string(),
+        r#"for="192.0.2.60:443";by=_proxy;host=example.com;proto=https"#
+    );
+
+    let forwarded = Forwarded::new()
+        .with_element(element)
+        .with_element(ForwardedElement::new().with_for(Node::unknown()));
+    assert_eq!(
+        forwarded.to_string(),
+        r#"for="192.0.2.60:443";by=_proxy;host=example.com;proto=https, for=unknown"#
+    );
+    assert_eq!(Forwarded::parse(&forwarded.to_string()).unwrap(), forwarded);
+}
+
+#[test]
+fn test_forwarded_element_builder_errors() {
+    assert_eq!(
+        ForwardedElement::new().with_host("a b"),
+        Err(ForwardedError::InvalidHost)
+    );
+    assert_eq!(
+        ForwardedElement::new().with_proto(""),
+        Err(ForwardedError::InvalidProto)
+    );
+    assert!(ForwardedElement::new().is_empty());
+}
+
+#[test]
+fn test_forwarded_typed_header() {
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Forwarded", "for=192.0.2.43")
+        .unwrap();
+    let forwarded = request.get_typed::<Forwarded>().unwrap().unwrap();
+    assert_eq!(forwarded.elements().len(), 1);
+}
+
+// ========================================
+// リクエストからの取得のテスト
+// ========================================
+
+#[test]
+fn test_forwarded_from_request_joins_lines() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(
+            b"GET / HTTP/1.1\r\nHost: example.com\r\nForwarded: for=192.0.2.43\r\n\
+              Forwarded: for=198.51.100.17;proto=https\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let forwarded = Forwarded::from_request(&head).unwrap().unwrap();
+    assert_eq!(forwarded.elements().len(), 2);
+    assert_eq!(forwarded.elements()[1].proto(), Some("https"));
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(Forwarded::from_request(&request), Ok(None));
+}
+
+#[test]
+fn test_forwarded_from_x_forwarded() {
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("X-Forwarded-For", "192.0.2.43, 2001:db8::1")
+        .unwrap()
+        .header("X-Forwarded-For", "unknown")
+        .unwrap()
+        .header("X-Forwarded-Proto", "HTTPS")
+        .unwrap()
+        .header("X-Forwarded-Host", "example.com")
+        .unwrap();
+    let forwarded = Forwarded::from_x_forwarded(&request).unwrap().unwrap();
+    assert_eq!(
+        forwarded.to_string(),
+        r#"for=192.0.2.43;host=example.com;proto=https, for="[2001:db8::1]", for=unknown"#
+    );
+}
+
+#[test]
+fn test_forwarded_from_x_forwarded_without_for() {
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("X-Forwarded-Proto", "https, http")
+        .unwrap();
+    let forwarded = Forwarded::from_x_forwarded(&request).unwrap().unwrap();
+    assert_eq!(forwarded.to_string(), "proto=https");
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(Forwarded::from_x_forwarded(&request), Ok(None));
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("X-Forwarded-For", "example.com")
+        .unwrap();
+    assert_eq!(
+        Forwarded::from_x_forwarded(&request),
+        Err(ForwardedError::InvalidNode)
+    );
+}
+
+#[test]
+fn test_forwarded_to_x_forwarded() {
+    let forwarded = Forwarded::parse(
+        r#"for="[2001:db8::1]:4711";host=example.com;proto=https, for=_hidden, by=_proxy"#,
+    )
+    .unwrap();
+    assert_eq!(
+        forwarded.to_x_forwarded(),
+        [
+            (
+                "X-Forwarded-For".to_string(),
+                "2001:db8::1, _hidden, unknown".to_string()
+            ),
+            ("X-Forwarded-Proto".to_string(), "https".to_string()),
+            ("X-Forwarded-Host".to_string(), "example.com".to_string()),
+        ]
+    );
+
+    assert!(Forwarded::new().to_x_forwarded().is_empty());
+}
+
+// ========================================
+// ホップ追加のテスト
+// ========================================
+
+#[test]
+fn test_append_forwarded() {
+    let element = ForwardedElement::new()
+        .with_for(Node::parse("198.51.100.17").unwrap())
+        .with_proto("http")
+        .unwrap();
+
+    let request = append_forwarded(Request::new("GET", "/").unwrap(), &element).unwrap();
+    assert_eq!(
+        request.get_header("Forwarded"),
+        Some("for=198.51.100.17;proto=http")
+    );
+
+    // 空の要素は追加しない
+    let request =
+        append_forwarded(Request::new("GET", "/").unwrap(), &ForwardedElement::new()).unwrap();
+    assert!(!request.has_header("Forwarded"));
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Forwarded", "for=192.0.2.43")
+        .unwrap()
+        .header("Forwarded", "for=_a, ")
+        .unwrap();
+    let request = append_forwarded(request, &element).unwrap();
+    assert_eq!(request.get_headers("Forwarded").len(), 1);
+    assert_eq!(
+        request.get_header("Forwarded"),
+        Some("for=192.0.2.43, for=_a, for=198.51.100.17;proto=http")
+    );
+}
+
+#[test]
+fn test_append_x_forwarded() {
+    let element = ForwardedElement::new()
+        .with_for(Node::parse("[2001:db8::1]:4711").unwrap())
+        .with_host("proxy.example")
+        .unwrap()
+        .with_proto("http")
+        .unwrap();
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("X-Forwarded-For", "192.0.2.43")
+        .unwrap()
+        .header("X-Forwarded-Proto", "https")
+        .unwrap();
+    let request = append_x_forwarded(request, &element).unwrap();
+    assert_eq!(
+        request.get_header("X-Forwarded-For"),
+        Some("192.0.2.43, 2001:db8::1")
+    );
+    // 既存の値は上書きしない
+    assert_eq!(request.get_header("X-Forwarded-Proto"), Some("https"));
+    assert_eq!(
+        request.get_header("X-Forwarded-Host"),
+        Some("proxy.example")
+    );
+
+    let request =
+        append_x_forwarded(Request::new("GET", "/").unwrap(), &ForwardedElement::new()).unwrap();
+    assert_eq!(request.get_header("X-Forwarded-For"), Some("unknown"));
+    assert!(!request.has_header("X-Forwarded-Proto"));
+}
@@ -1,6 +1,7 @@
 //! Host ヘッダーのユニットテスト
 
 use shiguredo_http11::host::{Host, HostError};
+use shiguredo_http11::uri::Uri;
 
 // ========================================
 // HostError のテスト
@@ -135,3 +136,130 @@ fn test_host_port_boundary() {
     assert!(Host::parse("example.com:65536").is_err());
     assert!(Host::parse("example.com:100000").is_err());
 }
+
+// ========================================
+// 構築のテスト
+// ========================================
+
+#[test]
+fn test_host_new() {
+    let host = Host::new("example.com", Some(8080)).unwrap();
+    assert_eq!(host.to_string(), "example.com:8080");
+
+    // IPv6 は角括弧の有無どちらでもよい
+    let host = Host::new("::1", None).unwrap();
+    assert_eq!(host.host(), "[::1]");
+    assert_eq!(host.hostname(), "::1");
+    assert!(host.is_ipv6());
+    assert_eq!(
+        Host::new("[::1]", Some(80)).unwrap().to_string(),
+        "[::1]:80"
+    );
+
+    // ポートを含む host や前後の空白は受け付けない
+    assert_eq!(
+        Host::new("example.com:80", None),
+        Err(HostError::InvalidHost)
+    );
+    assert_eq!(Host::new(" example.com", None), Err(HostError::InvalidHost));
+    assert_eq!(Host::new("", None), Err(HostError::Empty));
+    assert_eq!(Host::new("a@b", None), Err(HostError::InvalidHost));
+    assert_eq!(Host::new("[::1", None), Err(HostError::InvalidHost));
+}
+
+#[test]
+fn test_host_from_uri() {
+    let uri = Uri::parse("http://user@Example.com:8080/path").unwrap();
+    let host = Host::from_uri(&uri).unwrap();
+    assert_eq!(host.host(), "Example.com");
+    assert_eq!(host.port(), Some(8080));
+
+    let uri = Uri::parse("https://[2001:db8::1]/").unwrap();
+    let host = Host::from_uri(&uri).unwrap();
+    assert_eq!(host.host(), "[2001:db8::1]");
+    assert_eq!(host.port(), None);
+
+    assert_eq!(
+        Host::from_uri(&Uri::parse("/path").unwrap()),
+        Err(HostError::Empty)
+    );
+    assert_eq!(
+        Host::from_uri(&Uri::parse("file:///etc/hosts").unwrap()),
+        Err(HostError::Empty)
+    );
+}
+
+// ========================================
+// デフォルトポートのテスト
+// ========================================
+
+#[test]
+fn test_host_default_port() {
+    let host = Host::parse("example.com").unwrap();
+    assert_eq!(host.port_or_default("http"), Some(80));
+    assert_eq!(host.port_or_default("HTTPS"), Some(443));
+    assert_eq!(host.port_or_default("ws"), Some(80));
+    assert_eq!(host.port_or_default("wss"), Some(443));
+    assert_eq!(host.port_or_default("ftp"), None);
+
+    let host = Host::parse("example.com:8080").unwrap();
+    assert_eq!(host.port_or_default("http"), Some(8080));
+
+    let host = Host::parse("example.com:443").unwrap();
+    assert_eq!(
+        host.without_default_port("https").to_string(),
+        "example.com"
+    );
+    assert_eq!(
+        host.without_default_port("http").to_string(),
+        "example.com:443"
+    );
+    let host = Host::parse("[::1]:80").unwrap();
+    assert_eq!(host.without_default_port("http").to_string(), "[::1]");
+}
+
+// ========================================
+// 比較のテスト
+// ========================================
+
+#[test]
+fn test_host_matches() {
+    let cases = [
+        ("example.com", "example.com", "http", true),
+        ("Example.COM", "example.com", "http", true),
+        ("example.com", "example.com:80", "http", true),
+        ("example.com:443", "example.com", "https", true),
+        ("example.com", "example.com:443", "http", false),
+        ("example.com:8080", "example.com:8081", "http", false),
+        ("example.com", "example.org", "http", false),
+        ("example.com", "example.com:21", "ftp", false),
+        ("example.com:21", "example.com:21", "ftp", true),
+        ("[::1]", "[0:0::1]:80", "http", true),
+        ("[2001:DB8::1]", "[2001:db8::1]", "http", true),
+        ("[::1]", "[::2]", "http", false),
+        ("127.0.0.1", "[::ffff:127.0.0.1]", "http", false),
+    ];
+    for (a, b, scheme, expected) in cases {
+        let a = Host::parse(a).unwrap();
+        let b = Host::parse(b).unwrap();
+        assert_eq!(a.matches(&b, scheme), expected, "{a} {b} {scheme}");
+        assert_eq!(b.matches(&a, scheme), expected, "{b} {a} {scheme}");
+    }
+}
+
+#[test]
+fn test_host_matches_uri() {
+    let host = Host::parse("example.com").unwrap();
+    assert!(host.matches_uri(&Uri::parse("http://example.com/").unwrap()));
+    assert!(host.matches_uri(&Uri::parse("http://EXAMPLE.com:80/").unwrap()));
+    assert!(host.matches_uri(&Uri::parse("https://example.com:443/").unwrap()));
+    // userinfo は比較に含めない
+    assert!(host.matches_uri(&Uri::parse("http://user@example.com/").unwrap()));
+    assert!(!host.matches_uri(&Uri::parse("http://example.com:8080/").unwrap()));
+    assert!(!host.matches_uri(&Uri::parse("https://example.com:80/").unwrap()));
+    assert!(!host.matches_uri(&Uri::parse("http://example.org/").unwrap()));
+    assert!(!host.matches_uri(&Uri::parse("/index.html").unwrap()));
+
+    let host = Host::parse("[::1]:8443").unwrap();
+    assert!(host.matches_uri(&Uri::parse("https://[0::1]:8443/").unwrap()));
+}
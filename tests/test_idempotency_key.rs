@@ -0,0 +1,182 @@
+//! Idempotency-Key ヘッダーのユニットテスト
+
+use shiguredo_http11::Request;
+use shiguredo_http11::idempotency_key::{
+    DEFAULT_MAX_KEY_LENGTH, IdempotencyKey, IdempotencyKeyError, RequestIdempotency,
+};
+use shiguredo_http11::typed_header::TypedHeader;
+
+// ========================================
+// IdempotencyKeyError のテスト
+// ========================================
+
+#[test]
+fn test_idempotency_key_error_display() {
+    let errors = [
+        (
+            IdempotencyKeyError::InvalidFormat,
+            "invalid Idempotency-Key header format",
+        ),
+        (IdempotencyKeyError::Empty, "empty idempotency key"),
+        (IdempotencyKeyError::TooLong, "idempotency key too long"),
+        (
+            IdempotencyKeyError::InvalidCharacter,
+            "invalid character in idempotency key",
+        ),
+        (
+            IdempotencyKeyError::Multiple,
+            "multiple Idempotency-Key headers",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースと生成
+// ========================================
+
+#[test]
+fn test_parse() {
+    let key = IdempotencyKey::parse("\"8e03978e-40d5-43e8-bc93-6894a57f9324\"").unwrap();
+    assert_eq!(key.as_str(), "8e03978e-40d5-43e8-bc93-6894a57f9324");
+
+    // エスケープとパラメーター
+    let key = IdempotencyKey::parse(" \"a\\\"b\";foo=1 ").unwrap();
+    assert_eq!(key.as_str(), "a\"b");
+}
+
+#[test]
+fn test_parse_error() {
+    // sf-string 以外
+    assert_eq!(
+        IdempotencyKey::parse("abc"),
+        Err(IdempotencyKeyError::InvalidFormat)
+    );
+    assert_eq!(
+        IdempotencyKey::parse("\"abc"),
+        Err(IdempotencyKeyError::InvalidFormat)
+    );
+    assert_eq!(
+        IdempotencyKey::parse("\"a\", \"b\""),
+        Err(IdempotencyKeyError::InvalidFormat)
+    );
+    assert_eq!(
+        IdempotencyKey::parse("\"\""),
+        Err(IdempotencyKeyError::Empty)
+    );
+
+    let long = format!("\"{}\"", "a".repeat(DEFAULT_MAX_KEY_LENGTH + 1));
+    assert_eq!(
+        IdempotencyKey::parse(&long),
+        Err(IdempotencyKeyError::TooLong)
+    );
+    let max = format!("\"{}\"", "a".repeat(DEFAULT_MAX_KEY_LENGTH));
+    assert!(IdempotencyKey::parse(&max).is_ok());
+
+    assert_eq!(
+        IdempotencyKey::parse_with_max_length("\"abcdef\"", 4),
+        Err(IdempotencyKeyError::TooLong)
+    );
+    assert!(IdempotencyKey::parse_with_max_length("\"abcd\"", 4).is_ok());
+}
+
+#[test]
+fn test_new_and_display() {
+    let key = IdempotencyKey::new("key-1").unwrap();
+    assert_eq!(key.to_string(), "\"key-1\"");
+    assert_eq!(key.encode_value(), "\"key-1\"");
+    assert_eq!(IdempotencyKey::NAME, "Idempotency-Key");
+
+    let key = IdempotencyKey::new("a\\b\"c").unwrap();
+    assert_eq!(key.to_string(), "\"a\\\\b\\\"c\"");
+    assert_eq!(IdempotencyKey::parse(&key.to_string()), Ok(key));
+
+    assert_eq!(IdempotencyKey::new(""), Err(IdempotencyKeyError::Empty));
+    assert_eq!(
+        IdempotencyKey::new("a\tb"),
+        Err(IdempotencyKeyError::InvalidCharacter)
+    );
+    assert_eq!(
+        IdempotencyKey::new("キー"),
+        Err(IdempotencyKeyError::InvalidCharacter)
+    );
+    assert_eq!(
+        IdempotencyKey::with_max_length("abc", 2),
+        Err(IdempotencyKeyError::TooLong)
+    );
+}
+
+#[test]
+fn test_from_request() {
+    let request = Request::new("POST", "/").unwrap();
+    assert_eq!(IdempotencyKey::from_request(&request), Ok(None));
+
+    let request = Request::new("POST", "/")
+        .unwrap()
+        .header("Idempotency-Key", "\"abc\"")
+        .unwrap();
+    assert_eq!(
+        IdempotencyKey::from_request(&request),
+        Ok(Some(IdempotencyKey::new("abc").unwrap()))
+    );
+
+    let request = request.header("Idempotency-Key", "\"def\"").unwrap();
+    assert_eq!(
+        IdempotencyKey::from_request(&request),
+        Err(IdempotencyKeyError::Multiple)
+    );
+}
+
+// ========================================
+// RequestIdempotency のテスト
+// ========================================
+
+#[test]
+fn test_classify() {
+    let key = IdempotencyKey::new("abc").unwrap();
+
+    for method in ["GET", "HEAD", "OPTIONS", "TRACE"] {
+        assert_eq!(
+            RequestIdempotency::classify(method, None),
+            RequestIdempotency::Safe
+        );
+        assert_eq!(
+            RequestIdempotency::classify(method, Some(&key)),
+            RequestIdempotency::Safe
+        );
+    }
+    for method in ["PUT", "DELETE"] {
+        assert_eq!(
+            RequestIdempotency::classify(method, None),
+            RequestIdempotency::Idempotent
+        );
+    }
+    for method in ["POST", "PATCH", "CONNECT", "PROPFIND", "get"] {
+        assert_eq!(
+            RequestIdempotency::classify(method, None),
+            RequestIdempotency::NonIdempotent
+        );
+        assert_eq!(
+            RequestIdempotency::classify(method, Some(&key)),
+            RequestIdempotency::KeyProtected
+        );
+    }
+    assert_eq!(
+        RequestIdempotency::classify("", None),
+        RequestIdempotency::NonIdempotent
+    );
+}
+
+#[test]
+fn test_classify_properties() {
+    assert!(RequestIdempotency::Safe.is_safe());
+    assert!(!RequestIdempotency::Idempotent.is_safe());
+    assert!(!RequestIdempotency::KeyProtected.is_safe());
+
+    assert!(RequestIdempotency::Safe.is_retryable());
+    assert!(RequestIdempotency::Idempotent.is_retryable());
+    assert!(RequestIdempotency::KeyProtected.is_retryable());
+    assert!(!RequestIdempotency::NonIdempotent.is_retryable());
+}
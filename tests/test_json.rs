@@ -0,0 +1,89 @@
+//! JSON ボディ変換のユニットテスト (`serde_json` feature)
+#![cfg(feature = "serde_json")]
+
+use std::collections::BTreeMap;
+
+use shiguredo_http11::json::JsonError;
+use shiguredo_http11::{Request, RequestDecoder, Response, StatusCode};
+
+#[test]
+fn test_request_json_body_roundtrip() {
+    let mut value = BTreeMap::new();
+    value.insert("id".to_string(), 1u32);
+    value.insert("count".to_string(), 42);
+
+    let request = Request::new("POST", "/items")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .header("content-type", "text/plain")
+        .unwrap()
+        .json_body(&value)
+        .unwrap();
+    assert_eq!(request.get_headers("Content-Type"), ["application/json"]);
+    assert_eq!(request.body_bytes(), Some(&br#"{"count":42,"id":1}"#[..]));
+
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(&request.encode().unwrap()).unwrap();
+    let decoded = decoder.decode().unwrap().unwrap();
+    let parsed: BTreeMap<String, u32> = decoded.parse_json().unwrap();
+    assert_eq!(parsed, value);
+}
+
+#[test]
+fn test_parse_json_accepts_json_media_types() {
+    for content_type in [
+        "application/json",
+        "Application/JSON; charset=utf-8",
+        "application/problem+json",
+    ] {
+        let response = Response::with_status(StatusCode::OK)
+            .header("Content-Type", content_type)
+            .unwrap()
+            .body(b"[1,2]".to_vec());
+        let parsed: Vec<u8> = response.parse_json().unwrap();
+        assert_eq!(parsed, [1, 2], "{content_type}");
+    }
+}
+
+#[test]
+fn test_parse_json_rejects_other_content_type() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Content-Type", "text/plain")
+        .unwrap()
+        .body(b"[1]".to_vec());
+    let err = response.parse_json::<Vec<u8>>().unwrap_err();
+    assert!(matches!(
+        err,
+        JsonError::UnexpectedContentType { content_type: Some(ref ct) } if ct == "text/plain"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "unexpected Content-Type for JSON: \"text/plain\""
+    );
+
+    let response = Response::with_status(StatusCode::OK).body(b"[1]".to_vec());
+    assert!(matches!(
+        response.parse_json::<Vec<u8>>(),
+        Err(JsonError::UnexpectedContentType { content_type: None })
+    ));
+}
+
+#[test]
+fn test_parse_json_syntax_error() {
+    let response = Response::with_status(StatusCode::OK)
+        .json_body(&1u8)
+        .unwrap()
+        .body(b"{".to_vec());
+    assert!(matches!(
+        response.parse_json::<u8>(),
+        Err(JsonError::Json(_))
+    ));
+    // ボディがない場合は空入力として扱う
+    let mut response = response;
+    response.clear_body();
+    assert!(matches!(
+        response.parse_json::<u8>(),
+        Err(JsonError::Json(_))
+    ));
+}
@@ -16,6 +16,8 @@ fn test_decoder_limits_default_values() {
     assert_eq!(limits.max_header_line_size, 8 * 1024); // 8KB
     assert_eq!(limits.max_body_size, 10 * 1024 * 1024); // 10MB
     assert_eq!(limits.max_chunk_line_size, 64); // 64 bytes
+    assert_eq!(limits.max_uri_length, 8 * 1024); // 8KB
+    assert_eq!(limits.max_method_length, 32); // 32 bytes
 }
 
 // unlimited のプロパティ: 各フィールドが usize::MAX
@@ -28,4 +30,6 @@ fn test_decoder_limits_unlimited_values() {
     assert_eq!(limits.max_header_line_size, usize::MAX);
     assert_eq!(limits.max_body_size, usize::MAX);
     assert_eq!(limits.max_chunk_line_size, usize::MAX);
+    assert_eq!(limits.max_uri_length, usize::MAX);
+    assert_eq!(limits.max_method_length, usize::MAX);
 }
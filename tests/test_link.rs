@@ -0,0 +1,294 @@
+//! Link ヘッダーのユニットテスト
+
+use shiguredo_http11::ext_value::ExtValue;
+use shiguredo_http11::link::{Link, LinkError, LinkValue};
+use shiguredo_http11::uri::Uri;
+use shiguredo_http11::{Response, ResponseDecoder};
+
+// ========================================
+// LinkError のテスト
+// ========================================
+
+#[test]
+fn test_link_error_display() {
+    let errors = [
+        (LinkError::InvalidFormat, "invalid Link header format"),
+        (LinkError::InvalidTarget, "invalid Link target"),
+        (
+            LinkError::InvalidParameterName,
+            "invalid Link parameter name",
+        ),
+        (
+            LinkError::InvalidParameterValue,
+            "invalid Link parameter value",
+        ),
+        (LinkError::UnterminatedQuote, "unterminated quoted-string"),
+        (LinkError::InvalidExtValue, "invalid ext-value encoding"),
+        (LinkError::InvalidRelation, "invalid Link relation type"),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_link_parse_rfc8288_examples() {
+    // RFC 8288 Section 3.5
+    let link = Link::parse(
+        r#"<http://example.com/TheBook/chapter2>; rel="previous"; title="previous chapter""#,
+    )
+    .unwrap();
+    let value = &link.values()[0];
+    assert_eq!(value.target(), "http://example.com/TheBook/chapter2");
+    assert_eq!(value.rel(), ["previous"]);
+    assert_eq!(value.title(), Some("previous chapter"));
+
+    let link = Link::parse(r#"</>; rel="http://example.net/foo""#).unwrap();
+    assert!(link.values()[0].has_rel("http://example.net/foo"));
+
+    let link = Link::parse(r##"</terms>; rel="copyright"; anchor="#foo""##).unwrap();
+    assert_eq!(link.values()[0].anchor(), Some("#foo"));
+
+    let link = Link::parse(
+        "</TheBook/chapter2>; rel=\"previous\"; title*=UTF-8'de'letztes%20Kapitel, \
+         </TheBook/chapter4>; rel=\"next\"; title*=UTF-8'de'n%c3%a4chstes%20Kapitel",
+    )
+    .unwrap();
+    assert_eq!(link.values().len(), 2);
+    let next = link.find_rel("next").unwrap();
+    assert_eq!(next.title(), Some("nächstes Kapitel"));
+    assert_eq!(next.title_ext().unwrap().language(), Some("de"));
+
+    let link =
+        Link::parse(r#"<http://example.org/>; rel="start http://example.net/relation/other""#)
+            .unwrap();
+    assert!(link.values()[0].has_rel("START"));
+    assert!(link.values()[0].has_rel("http://example.net/relation/other"));
+}
+
+#[test]
+fn test_link_parse_params() {
+    let link = Link::parse(
+        "</font.woff2>;rel=preload;as=font;type=\"font/woff2\" ; crossorigin, </app.js>; rel=modulepreload",
+    )
+    .unwrap();
+    let font = &link.values()[0];
+    assert_eq!(font.param("as"), Some("font"));
+    assert_eq!(font.media_type(), Some("font/woff2"));
+    assert!(font.has_param("crossorigin"));
+    assert_eq!(font.param("crossorigin"), None);
+    assert_eq!(
+        font.parameters(),
+        [
+            ("as".to_string(), Some("font".to_string())),
+            ("type".to_string(), Some("font/woff2".to_string())),
+            ("crossorigin".to_string(), None),
+        ]
+    );
+    assert!(link.find_rel("modulepreload").is_some());
+    assert!(link.find_rel("prefetch").is_none());
+}
+
+#[test]
+fn test_link_parse_first_occurrence_wins() {
+    // RFC 8288 Section 3.3: 2 つ目以降の rel は無視する
+    let link = Link::parse(r#"</a>; rel=next; rel=prev; title=one; title=two"#).unwrap();
+    let value = &link.values()[0];
+    assert_eq!(value.rel(), ["next"]);
+    assert_eq!(value.title(), Some("one"));
+}
+
+#[test]
+fn test_link_parse_title_ext_preferred() {
+    let link = Link::parse(r#"</a>; title*=UTF-8''%E6%97%A5%E6%9C%AC; title="fallback""#).unwrap();
+    let value = &link.values()[0];
+    assert_eq!(value.title(), Some("日本"));
+}
+
+#[test]
+fn test_link_parse_commas_in_target_and_quotes() {
+    let link = Link::parse(r#"</a,b>; rel=next; title="x, y", </c>; rel=last"#).unwrap();
+    assert_eq!(link.values().len(), 2);
+    assert_eq!(link.values()[0].target(), "/a,b");
+    assert_eq!(link.values()[0].title(), Some("x, y"));
+}
+
+#[test]
+fn test_link_parse_empty_elements() {
+    assert!(Link::parse("").unwrap().values().is_empty());
+    let link = Link::parse(" , </a>;; rel=next; ,, </b> , ").unwrap();
+    assert_eq!(link.values().len(), 2);
+    assert_eq!(link.to_string(), "</a>; rel=next, </b>");
+}
+
+#[test]
+fn test_link_parse_errors() {
+    let cases = [
+        ("/a; rel=next", LinkError::InvalidFormat),
+        ("</a", LinkError::InvalidTarget),
+        ("</a b>", LinkError::InvalidTarget),
+        ("</a> rel=next", LinkError::InvalidFormat),
+        ("</a>; =next", LinkError::InvalidParameterName),
+        ("</a>; rel=", LinkError::InvalidParameterValue),
+        (r#"</a>; rel="next"x"#, LinkError::InvalidFormat),
+        (r#"</a>; title="x"#, LinkError::UnterminatedQuote),
+        ("</a>; title*=ISO-8859-1''x", LinkError::InvalidExtValue),
+        ("</a>; title*", LinkError::InvalidExtValue),
+        ("</a>; anchor", LinkError::InvalidParameterValue),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Link::parse(input), Err(expected), "{input}");
+    }
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_link_value_builder() {
+    let value = LinkValue::new("/TheBook/chapter4")
+        .unwrap()
+        .with_rel("next")
+        .unwrap()
+        .with_anchor("#c3")
+        .unwrap()
+        .with_title("next chapter")
+        .unwrap()
+        .with_title_ext(
+            ExtValue::new("nächstes Kapitel")
+                .with_language("de")
+                .unwrap(),
+        )
+        .with_param("type", Some("text/html"))
+        .unwrap()
+        .with_param("crossorigin", None)
+        .unwrap();
+    assert_eq!(
+        value.to_string(),
+        "</TheBook/chapter4>; rel=next; anchor=#c3; title=\"next chapter\"; \
+         title*=UTF-8'de'n%C3%A4chstes%20Kapitel; type=\"text/html\"; crossorigin"
+    );
+    assert_eq!(Link::parse(&value.to_string()).unwrap().values()[0], value);
+}
+
+#[test]
+fn test_link_value_builder_errors() {
+    assert_eq!(LinkValue::new("/a b").err(), Some(LinkError::InvalidTarget));
+    assert_eq!(LinkValue::new("/<a>").err(), Some(LinkError::InvalidTarget));
+
+    let value = LinkValue::new("/a").unwrap();
+    assert_eq!(
+        value.clone().with_rel(" ").err(),
+        Some(LinkError::InvalidRelation)
+    );
+    assert_eq!(
+        value.clone().with_rel("a\"b").err(),
+        Some(LinkError::InvalidRelation)
+    );
+    assert_eq!(
+        value.clone().with_title("a\r\nb").err(),
+        Some(LinkError::InvalidParameterValue)
+    );
+    assert_eq!(
+        value.clone().with_param("rel", Some("next")).err(),
+        Some(LinkError::InvalidParameterName)
+    );
+    assert_eq!(
+        value.with_param("a b", None).err(),
+        Some(LinkError::InvalidParameterName)
+    );
+}
+
+#[test]
+fn test_link_multiple_rel() {
+    let value = LinkValue::new("/a")
+        .unwrap()
+        .with_rel("preload prefetch")
+        .unwrap();
+    assert_eq!(value.to_string(), "</a>; rel=\"preload prefetch\"");
+
+    let link = Link::new()
+        .with_value(value)
+        .with_value(LinkValue::new("/b").unwrap().with_rel("preload").unwrap());
+    let targets: Vec<&str> = link.filter_rel("preload").map(LinkValue::target).collect();
+    assert_eq!(targets, ["/a", "/b"]);
+    assert_eq!(
+        link.to_string(),
+        "</a>; rel=\"preload prefetch\", </b>; rel=preload"
+    );
+}
+
+// ========================================
+// リンク先解決のテスト
+// ========================================
+
+#[test]
+fn test_link_resolve_target() {
+    let base = Uri::parse("https://api.example.com/v1/items?page=1").unwrap();
+    let link = Link::parse(
+        "<?page=2>; rel=next, <../v2/items>; rel=alternate, <https://other.example/>; rel=related",
+    )
+    .unwrap();
+    let resolved: Vec<String> = link
+        .values()
+        .iter()
+        .map(|value| value.resolve_target(&base).unwrap().as_str().to_string())
+        .collect();
+    assert_eq!(
+        resolved,
+        [
+            "https://api.example.com/v1/items?page=2",
+            "https://api.example.com/v2/items",
+            "https://other.example/",
+        ]
+    );
+}
+
+// ========================================
+// メッセージからの取得のテスト
+// ========================================
+
+#[test]
+fn test_link_from_message_joins_lines() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nLink: </a>; rel=prev\r\nLink: </c>; rel=next\r\n\
+              Content-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let link = Link::from_message(&head).unwrap().unwrap();
+    assert_eq!(link.find_rel("next").unwrap().target(), "/c");
+
+    let response = Response::with_status(shiguredo_http11::StatusCode::OK);
+    assert_eq!(Link::from_message(&response), Ok(None));
+}
+
+#[test]
+fn test_link_early_hints() {
+    let links = [
+        LinkValue::new("/style.css")
+            .unwrap()
+            .with_rel("preload")
+            .unwrap()
+            .with_param("as", Some("style"))
+            .unwrap(),
+        LinkValue::new("/script.js")
+            .unwrap()
+            .with_rel("preload")
+            .unwrap()
+            .with_param("as", Some("script"))
+            .unwrap(),
+    ];
+    let hints = Response::early_hints(links.iter().map(ToString::to_string)).unwrap();
+    assert_eq!(hints.get_headers("Link").len(), 2);
+    let parsed = Link::from_message(&hints).unwrap().unwrap();
+    assert_eq!(parsed.values(), links);
+}
@@ -0,0 +1,371 @@
+//! HTTP メッセージ検査のユニットテスト
+
+use shiguredo_http11::lint::{Severity, Violation, validate_request, validate_response};
+use shiguredo_http11::{Request, Response};
+
+fn get(uri: &str) -> Request {
+    Request::new("GET", uri)
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+}
+
+// ========================================
+// Violation のテスト
+// ========================================
+
+#[test]
+fn test_violation_display() {
+    let violations = [
+        (Violation::MissingHost, "missing Host header"),
+        (
+            Violation::ConflictingFraming,
+            "both Transfer-Encoding and Content-Length are present",
+        ),
+        (
+            Violation::ContentLengthMismatch {
+                header_value: 10,
+                body_length: 5,
+            },
+            "Content-Length mismatch: header=10, body=5",
+        ),
+        (
+            Violation::BodyNotAllowed { status_code: 204 },
+            "body not allowed for status 204",
+        ),
+        (
+            Violation::DuplicateSingletonField {
+                name: "Content-Type".to_string(),
+            },
+            "duplicate singleton header: Content-Type",
+        ),
+    ];
+    for (violation, expected) in violations {
+        assert_eq!(violation.to_string(), expected);
+    }
+}
+
+#[test]
+fn test_violation_severity() {
+    assert_eq!(Violation::MissingHost.severity(), Severity::Error);
+    assert!(Violation::ConflictingFraming.is_error());
+    assert_eq!(
+        Violation::TransferEncodingInHttp10.severity(),
+        Severity::Warning
+    );
+    assert!(
+        !Violation::SurroundingWhitespace {
+            name: "Accept".to_string()
+        }
+        .is_error()
+    );
+    assert!(Severity::Warning < Severity::Error);
+}
+
+// ========================================
+// リクエストの検査
+// ========================================
+
+#[test]
+fn test_validate_request_valid() {
+    assert!(validate_request(&get("/")).is_empty());
+
+    let request = Request::new("POST", "/upload")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .header("Content-Length", "5")
+        .unwrap()
+        .body(b"hello".to_vec());
+    assert!(validate_request(&request).is_empty());
+
+    // HTTP/1.0 は Host を必須としない
+    let request = Request::with_version("GET", "/", "HTTP/1.0").unwrap();
+    assert!(validate_request(&request).is_empty());
+}
+
+#[test]
+fn test_validate_request_host() {
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(validate_request(&request), vec![Violation::MissingHost]);
+
+    let request = get("/").header("Host", "example.org").unwrap();
+    assert_eq!(validate_request(&request), vec![Violation::DuplicateHost]);
+
+    let request = Request::new("GET", "http://example.com/")
+        .unwrap()
+        .header("Host", "example.org")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::HostAuthorityMismatch {
+            host: "example.org".to_string(),
+            authority: "example.com".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_validate_request_target_form() {
+    let request = Request::new("GET", "*")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::InvalidRequestTarget {
+            uri: "*".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_validate_request_framing() {
+    let request = get("/")
+        .header("Transfer-Encoding", "chunked")
+        .unwrap()
+        .header("Content-Length", "0")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::ConflictingFraming]
+    );
+
+    let request = get("/").header("Transfer-Encoding", "gzip").unwrap();
+    assert_eq!(validate_request(&request), vec![Violation::ChunkedNotFinal]);
+
+    let request = get("/")
+        .header("Transfer-Encoding", "chunked, chunked")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::InvalidTransferEncoding {
+            value: "chunked, chunked".to_string()
+        }]
+    );
+
+    let request = Request::with_version("POST", "/", "HTTP/1.0")
+        .unwrap()
+        .header("Transfer-Encoding", "chunked")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::TransferEncodingInHttp10]
+    );
+}
+
+#[test]
+fn test_validate_request_content_length() {
+    let request = get("/")
+        .header("Content-Length", "10")
+        .unwrap()
+        .body(b"hello".to_vec());
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::ContentLengthMismatch {
+            header_value: 10,
+            body_length: 5,
+        }]
+    );
+
+    let request = get("/").header("Content-Length", "+5").unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::InvalidContentLength {
+            value: "+5".to_string()
+        }]
+    );
+
+    let request = get("/")
+        .header("Content-Length", "5")
+        .unwrap()
+        .header("Content-Length", "6")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::DuplicateContentLength]
+    );
+
+    // 同じ値の重複は許容する
+    let request = get("/")
+        .header("Content-Length", "5")
+        .unwrap()
+        .header("Content-Length", "5")
+        .unwrap()
+        .body(b"hello".to_vec());
+    assert!(validate_request(&request).is_empty());
+}
+
+#[test]
+fn test_validate_request_trace_body() {
+    let request = Request::new("TRACE", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .body(b"hello".to_vec());
+    assert_eq!(
+        validate_request(&request),
+        vec![Violation::BodyNotAllowedForMethod {
+            method: "TRACE".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_validate_request_headers() {
+    let request = get("/")
+        .header("Accept", " text/html ")
+        .unwrap()
+        .header("Content-Type", "text/plain")
+        .unwrap()
+        .header("content-type", "text/html")
+        .unwrap();
+    let violations = validate_request(&request);
+    assert_eq!(
+        violations,
+        vec![
+            Violation::SurroundingWhitespace {
+                name: "Accept".to_string()
+            },
+            Violation::DuplicateSingletonField {
+                name: "Content-Type".to_string()
+            },
+        ]
+    );
+    assert!(violations.iter().all(|v| v.severity() == Severity::Warning));
+    // 警告のみの場合はエンコードできる
+    assert!(request.encode().is_ok());
+}
+
+#[test]
+fn test_validate_request_reports_all() {
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Transfer-Encoding", "chunked")
+        .unwrap()
+        .header("Content-Length", "abc")
+        .unwrap();
+    assert_eq!(
+        validate_request(&request),
+        vec![
+            Violation::MissingHost,
+            Violation::InvalidContentLength {
+                value: "abc".to_string()
+            },
+            Violation::ConflictingFraming,
+        ]
+    );
+}
+
+// ========================================
+// レスポンスの検査
+// ========================================
+
+#[test]
+fn test_validate_response_valid() {
+    let response = Response::new(200, "OK")
+        .unwrap()
+        .header("Content-Type", "text/plain")
+        .unwrap()
+        .body(b"hello".to_vec());
+    assert!(validate_response(&response).is_empty());
+
+    // HEAD への応答は表現の長さを Content-Length で示す
+    let response = Response::new(200, "OK")
+        .unwrap()
+        .header("Content-Length", "100")
+        .unwrap()
+        .omit_body(true);
+    assert!(validate_response(&response).is_empty());
+
+    // 304 の Content-Length は表現の長さ
+    let response = Response::new(304, "Not Modified")
+        .unwrap()
+        .header("Content-Length", "100")
+        .unwrap();
+    assert!(validate_response(&response).is_empty());
+
+    let response = Response::new(205, "Reset Content")
+        .unwrap()
+        .header("Content-Length", "0")
+        .unwrap();
+    assert!(validate_response(&response).is_empty());
+}
+
+#[test]
+fn test_validate_response_body_not_allowed() {
+    for status_code in [100, 204, 304] {
+        let response = Response::new(status_code, "X")
+            .unwrap()
+            .body(b"hello".to_vec());
+        assert_eq!(
+            validate_response(&response),
+            vec![Violation::BodyNotAllowed { status_code }]
+        );
+    }
+
+    let response = Response::new(205, "Reset Content")
+        .unwrap()
+        .header("Content-Length", "5")
+        .unwrap()
+        .body(b"hello".to_vec());
+    assert_eq!(
+        validate_response(&response),
+        vec![
+            Violation::ForbiddenFramingHeader {
+                status_code: 205,
+                name: "Content-Length".to_string(),
+            },
+            Violation::BodyNotAllowed { status_code: 205 },
+        ]
+    );
+}
+
+#[test]
+fn test_validate_response_framing() {
+    let response = Response::new(204, "No Content")
+        .unwrap()
+        .header("Transfer-Encoding", "chunked")
+        .unwrap();
+    assert_eq!(
+        validate_response(&response),
+        vec![Violation::ForbiddenFramingHeader {
+            status_code: 204,
+            name: "Transfer-Encoding".to_string(),
+        }]
+    );
+
+    // レスポンスは chunked で終わらない Transfer-Encoding を許容する (close-delimited)
+    let response = Response::new(200, "OK")
+        .unwrap()
+        .header("Transfer-Encoding", "gzip")
+        .unwrap();
+    assert!(validate_response(&response).is_empty());
+
+    let response = Response::with_version("HTTP/1.0", 200, "OK")
+        .unwrap()
+        .header("Transfer-Encoding", "chunked")
+        .unwrap();
+    assert_eq!(
+        validate_response(&response),
+        vec![Violation::TransferEncodingInHttp10]
+    );
+}
+
+#[test]
+fn test_validate_response_content_length_mismatch() {
+    let response = Response::new(200, "OK")
+        .unwrap()
+        .header("Content-Length", "3")
+        .unwrap()
+        .body(b"hello".to_vec());
+    let violations = validate_response(&response);
+    assert_eq!(
+        violations,
+        vec![Violation::ContentLengthMismatch {
+            header_value: 3,
+            body_length: 5,
+        }]
+    );
+    assert!(response.encode().is_err());
+}
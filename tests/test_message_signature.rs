@@ -0,0 +1,554 @@
+//! HTTP Message Signatures のユニットテスト
+
+use std::borrow::Cow;
+
+use shiguredo_http11::message_signature::{
+    self, ComponentId, Signature, SignatureError, SignatureInput, SignatureParams, SignedMessage,
+};
+use shiguredo_http11::{Request, RequestDecoder, Response, StatusCode};
+
+const CONTENT_DIGEST: &str = "sha-512=:WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==:";
+
+/// RFC 9421 Appendix B.2 のテストリクエスト
+fn test_request() -> Request {
+    Request::new("POST", "/foo?param=Value&Pet=dog")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .header("Date", "Tue, 20 Apr 2021 02:07:55 GMT")
+        .unwrap()
+        .header("Content-Type", "application/json")
+        .unwrap()
+        .header("Content-Digest", CONTENT_DIGEST)
+        .unwrap()
+        .header("Content-Length", "18")
+        .unwrap()
+}
+
+fn base_of(message: &SignedMessage<'_>, input: &str) -> Result<String, SignatureError> {
+    let input = SignatureInput::parse(input)?;
+    let (_, params) = input.iter().next().unwrap();
+    params.signature_base(message)
+}
+
+// ========================================
+// SignatureError のテスト
+// ========================================
+
+#[test]
+fn test_signature_error_display() {
+    let errors = [
+        (
+            SignatureError::InvalidSignatureInput,
+            "invalid Signature-Input header",
+        ),
+        (SignatureError::InvalidSignature, "invalid Signature header"),
+        (
+            SignatureError::InvalidComponent,
+            "invalid component identifier",
+        ),
+        (
+            SignatureError::InvalidParameter,
+            "invalid signature parameter",
+        ),
+        (SignatureError::InvalidLabel, "invalid signature label"),
+        (
+            SignatureError::MissingComponent("\"date\"".to_string()),
+            "missing component: \"date\"",
+        ),
+        (
+            SignatureError::DuplicateComponent("\"date\"".to_string()),
+            "duplicate component: \"date\"",
+        ),
+        (
+            SignatureError::UnknownLabel("sig1".to_string()),
+            "unknown signature label: sig1",
+        ),
+        (SignatureError::Expired, "signature expired"),
+        (SignatureError::SigningFailed, "signing failed"),
+        (
+            SignatureError::VerificationFailed,
+            "signature verification failed",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// 署名ベースのテスト
+// ========================================
+
+#[test]
+fn test_signature_base_rfc9421_minimal() {
+    // RFC 9421 Appendix B.2.1
+    let request = test_request();
+    let base = base_of(
+        &SignedMessage::from(&request),
+        "sig-b21=();created=1618884473;keyid=\"test-key-rsa-pss\";nonce=\"b3k2pp5k7z-50gnwp.yemd\"",
+    )
+    .unwrap();
+    assert_eq!(
+        base,
+        "\"@signature-params\": ();created=1618884473;keyid=\"test-key-rsa-pss\";\
+         nonce=\"b3k2pp5k7z-50gnwp.yemd\""
+    );
+}
+
+#[test]
+fn test_signature_base_rfc9421_selective() {
+    // RFC 9421 Appendix B.2.2
+    let request = test_request();
+    let base = base_of(
+        &SignedMessage::from(&request),
+        "sig-b22=(\"@authority\" \"content-digest\" \"@query-param\";name=\"Pet\")\
+         ;created=1618884473;keyid=\"test-key-rsa-pss\";tag=\"header-example\"",
+    )
+    .unwrap();
+    let expected = format!(
+        "\"@authority\": example.com\n\
+         \"content-digest\": {CONTENT_DIGEST}\n\
+         \"@query-param\";name=\"Pet\": dog\n\
+         \"@signature-params\": (\"@authority\" \"content-digest\" \"@query-param\";name=\"Pet\")\
+         ;created=1618884473;keyid=\"test-key-rsa-pss\";tag=\"header-example\""
+    );
+    assert_eq!(base, expected);
+}
+
+#[test]
+fn test_signature_base_rfc9421_full_coverage() {
+    // RFC 9421 Appendix B.2.3
+    let request = test_request();
+    let base = base_of(
+        &SignedMessage::from(&request),
+        "sig-b23=(\"date\" \"@method\" \"@path\" \"@query\" \"@authority\" \"content-type\" \
+         \"content-digest\" \"content-length\");created=1618884473;keyid=\"test-key-rsa-pss\"",
+    )
+    .unwrap();
+    let expected = format!(
+        "\"date\": Tue, 20 Apr 2021 02:07:55 GMT\n\
+         \"@method\": POST\n\
+         \"@path\": /foo\n\
+         \"@query\": ?param=Value&Pet=dog\n\
+         \"@authority\": example.com\n\
+         \"content-type\": application/json\n\
+         \"content-digest\": {CONTENT_DIGEST}\n\
+         \"content-length\": 18\n\
+         \"@signature-params\": (\"date\" \"@method\" \"@path\" \"@query\" \"@authority\" \
+         \"content-type\" \"content-digest\" \"content-length\")\
+         ;created=1618884473;keyid=\"test-key-rsa-pss\""
+    );
+    assert_eq!(base, expected);
+}
+
+#[test]
+fn test_derived_components() {
+    // RFC 9421 Section 2.2
+    let request = Request::new("POST", "/path?param=value")
+        .unwrap()
+        .header("Host", "WWW.Example.com:443")
+        .unwrap();
+    let message = SignedMessage::from(&request).with_scheme("https");
+    let base = base_of(
+        &message,
+        "sig1=(\"@target-uri\" \"@scheme\" \"@authority\" \"@request-target\")",
+    )
+    .unwrap();
+    assert_eq!(
+        base,
+        "\"@target-uri\": https://www.example.com/path?param=value\n\
+         \"@scheme\": https\n\
+         \"@authority\": www.example.com\n\
+         \"@request-target\": /path?param=value\n\
+         \"@signature-params\": (\"@target-uri\" \"@scheme\" \"@authority\" \"@request-target\")"
+    );
+
+    // absolute-form は request-target からスキームと authority を取る
+    let request = Request::new("GET", "HTTP://Example.com:8080").unwrap();
+    let base = base_of(
+        &SignedMessage::from(&request),
+        "sig1=(\"@scheme\" \"@authority\" \"@path\" \"@query\")",
+    )
+    .unwrap();
+    assert!(base.starts_with(
+        "\"@scheme\": http\n\"@authority\": example.com:8080\n\"@path\": /\n\"@query\": ?\n"
+    ));
+
+    // スキームが分からない場合は @target-uri を構築できない
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(
+        base_of(&SignedMessage::from(&request), "sig1=(\"@target-uri\")"),
+        Err(SignatureError::MissingComponent(
+            "\"@target-uri\"".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_query_param_component() {
+    // RFC 9421 Section 2.2.8
+    let request = Request::new(
+        "GET",
+        "/parameters?var=this%20is%20a%20big%0Avalue&bar=with+plus+whitespace\
+         &fa%C3%A7ade%22%3A%20=something&dup=1&dup=2",
+    )
+    .unwrap();
+    let message = SignedMessage::from(&request);
+    let params = SignatureParams::new()
+        .with_component(ComponentId::query_param("var"))
+        .unwrap()
+        .with_component(ComponentId::query_param("bar"))
+        .unwrap()
+        .with_component(ComponentId::query_param("façade\": "))
+        .unwrap();
+    assert_eq!(
+        params.signature_base(&message).unwrap(),
+        "\"@query-param\";name=\"var\": this%20is%20a%20big%0Avalue\n\
+         \"@query-param\";name=\"bar\": with%20plus%20whitespace\n\
+         \"@query-param\";name=\"fa%C3%A7ade%22%3A%20\": something\n\
+         \"@signature-params\": (\"@query-param\";name=\"var\" \"@query-param\";name=\"bar\" \
+         \"@query-param\";name=\"fa%C3%A7ade%22%3A%20\")"
+    );
+
+    // 同じ名前が複数ある場合と存在しない場合はエラー
+    let params = SignatureParams::new()
+        .with_component(ComponentId::query_param("dup"))
+        .unwrap();
+    assert_eq!(
+        params.signature_base(&message),
+        Err(SignatureError::InvalidComponent)
+    );
+    let params = SignatureParams::new()
+        .with_component(ComponentId::query_param("none"))
+        .unwrap();
+    assert!(matches!(
+        params.signature_base(&message),
+        Err(SignatureError::MissingComponent(_))
+    ));
+}
+
+#[test]
+fn test_field_components() {
+    // RFC 9421 Section 2.1
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("X-OWS-Header", "   Leading and trailing whitespace.   ")
+        .unwrap()
+        .header("Cache-Control", "max-age=60")
+        .unwrap()
+        .header("Cache-Control", "   must-revalidate")
+        .unwrap()
+        .header("Example-Header", "value, with, lots")
+        .unwrap()
+        .header("Example-Header", "of, commas")
+        .unwrap()
+        .header("Priority", "u=1,   i")
+        .unwrap()
+        .header("Content-Digest", CONTENT_DIGEST)
+        .unwrap();
+    let message = SignedMessage::from(&request);
+    let base = base_of(
+        &message,
+        "sig1=(\"x-ows-header\" \"cache-control\" \"example-header\";bs \"priority\";sf \
+         \"content-digest\";key=\"sha-512\")",
+    )
+    .unwrap();
+    let lines: Vec<&str> = base.lines().collect();
+    assert_eq!(
+        &lines[..5],
+        [
+            "\"x-ows-header\": Leading and trailing whitespace.",
+            "\"cache-control\": max-age=60, must-revalidate",
+            "\"example-header\";bs: :dmFsdWUsIHdpdGgsIGxvdHM=:, :b2YsIGNvbW1hcw==:",
+            "\"priority\";sf: u=1, i",
+            "\"content-digest\";key=\"sha-512\": :WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==:",
+        ]
+    );
+
+    // 既知の Structured Field 以外に sf は付けられない
+    assert_eq!(
+        base_of(&message, "sig1=(\"example-header\";sf)"),
+        Err(SignatureError::InvalidComponent)
+    );
+    // key のメンバーがない
+    assert_eq!(
+        base_of(&message, "sig1=(\"priority\";key=\"x\")"),
+        Err(SignatureError::MissingComponent(
+            "\"priority\";key=\"x\"".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_trailer_component() {
+    let headers = vec![(Cow::Borrowed("Trailer"), "Expires".to_string())];
+    let trailers = vec![(
+        Cow::Borrowed("Expires"),
+        "Wed, 9 Nov 2022 07:28:00 GMT".to_string(),
+    )];
+    let message = SignedMessage::response(200, &headers).with_trailers(&trailers);
+    let params = SignatureParams::new()
+        .with_component(ComponentId::new("@status").unwrap())
+        .unwrap()
+        .with_component(ComponentId::new("trailer").unwrap())
+        .unwrap()
+        .with_component(ComponentId::new("expires").unwrap().with_tr().unwrap())
+        .unwrap();
+    assert_eq!(
+        params.signature_base(&message).unwrap(),
+        "\"@status\": 200\n\
+         \"trailer\": Expires\n\
+         \"expires\";tr: Wed, 9 Nov 2022 07:28:00 GMT\n\
+         \"@signature-params\": (\"@status\" \"trailer\" \"expires\";tr)"
+    );
+}
+
+#[test]
+fn test_response_with_request_components() {
+    // RFC 9421 Section 2.4
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"POST /foo?param=Value&Pet=dog HTTP/1.1\r\nHost: example.com\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let request = SignedMessage::from(&head);
+
+    let response = Response::with_status(StatusCode::SERVICE_UNAVAILABLE)
+        .header("Content-Type", "application/json")
+        .unwrap();
+    let message = SignedMessage::from(&response).with_request(&request);
+    let base = base_of(
+        &message,
+        "reqres=(\"@status\" \"content-type\" \"@authority\";req \"@method\";req)",
+    )
+    .unwrap();
+    assert!(base.starts_with(
+        "\"@status\": 503\n\
+         \"content-type\": application/json\n\
+         \"@authority\";req: example.com\n\
+         \"@method\";req: POST\n"
+    ));
+
+    // リクエストがない場合と、リクエストに @status を使う場合はエラー
+    let message = SignedMessage::from(&response);
+    assert!(matches!(
+        base_of(&message, "sig1=(\"@method\";req)"),
+        Err(SignatureError::MissingComponent(_))
+    ));
+    assert!(matches!(
+        base_of(&message, "sig1=(\"@method\")"),
+        Err(SignatureError::MissingComponent(_))
+    ));
+    assert!(matches!(
+        base_of(&request, "sig1=(\"@status\")"),
+        Err(SignatureError::MissingComponent(_))
+    ));
+}
+
+// ========================================
+// Signature-Input / Signature のテスト
+// ========================================
+
+#[test]
+fn test_signature_input_parse() {
+    let input = SignatureInput::parse(
+        "sig1=(\"@method\" \"content-type\";req);created=1618884473;expires=1618884773\
+         ;keyid=\"k\";alg=\"ed25519\";nonce=\"n\";tag=\"t\";x=1, sig2=()",
+    )
+    .unwrap();
+    let params = input.get("sig1").unwrap();
+    assert_eq!(params.components().len(), 2);
+    assert!(params.components()[1].is_req());
+    assert_eq!(params.created(), Some(1618884473));
+    assert_eq!(params.expires(), Some(1618884773));
+    assert_eq!(params.keyid(), Some("k"));
+    assert_eq!(params.alg(), Some("ed25519"));
+    assert_eq!(params.nonce(), Some("n"));
+    assert_eq!(params.tag(), Some("t"));
+    assert!(!params.is_expired(1618884773));
+    assert!(params.is_expired(1618884774));
+    assert!(input.get("sig2").unwrap().components().is_empty());
+    assert_eq!(SignatureInput::parse(&input.to_string()), Ok(input));
+}
+
+#[test]
+fn test_signature_input_parse_errors() {
+    let cases = [
+        ("sig1=:AAAA:", SignatureError::InvalidSignatureInput),
+        ("sig1=(", SignatureError::InvalidSignatureInput),
+        ("sig1=(\"@Method\")", SignatureError::InvalidComponent),
+        ("sig1=(\"@unknown\")", SignatureError::InvalidComponent),
+        (
+            "sig1=(\"@signature-params\")",
+            SignatureError::InvalidComponent,
+        ),
+        ("sig1=(method)", SignatureError::InvalidComponent),
+        ("sig1=(\"@status\";sf)", SignatureError::InvalidComponent),
+        ("sig1=(\"@query-param\")", SignatureError::InvalidComponent),
+        ("sig1=(\"a\";bs;sf)", SignatureError::InvalidComponent),
+        ("sig1=(\"a\";name=\"b\")", SignatureError::InvalidComponent),
+        ("sig1=(\"a\";unknown)", SignatureError::InvalidComponent),
+        ("sig1=(\"a\";sf=?0)", SignatureError::InvalidComponent),
+        (
+            "sig1=(\"a\" \"a\")",
+            SignatureError::DuplicateComponent("\"a\"".to_string()),
+        ),
+        ("sig1=();created=\"x\"", SignatureError::InvalidParameter),
+        ("sig1=();expires=-1", SignatureError::InvalidParameter),
+        ("sig1=();keyid=k", SignatureError::InvalidParameter),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(SignatureInput::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_signature_parse() {
+    let signature = Signature::parse("sig1=:AQID:, sig2=::").unwrap();
+    assert_eq!(signature.get("sig1"), Some(&[1u8, 2, 3][..]));
+    assert_eq!(signature.get("sig2"), Some(&[][..]));
+    assert_eq!(signature.to_string(), "sig1=:AQID:, sig2=::");
+
+    assert_eq!(
+        Signature::parse("sig1=(\"a\")"),
+        Err(SignatureError::InvalidSignature)
+    );
+    assert_eq!(
+        Signature::parse("sig1=1"),
+        Err(SignatureError::InvalidSignature)
+    );
+    assert_eq!(
+        Signature::new().with_signature("Sig", vec![1]),
+        Err(SignatureError::InvalidLabel)
+    );
+}
+
+#[test]
+fn test_component_id_builder() {
+    let id = ComponentId::new("Content-Digest")
+        .unwrap()
+        .with_key("sha-256")
+        .unwrap()
+        .with_req();
+    assert_eq!(id.to_string(), "\"content-digest\";key=\"sha-256\";req");
+    assert!(!id.is_derived());
+
+    assert_eq!(
+        ComponentId::new("@query-param"),
+        Err(SignatureError::InvalidComponent)
+    );
+    assert_eq!(
+        ComponentId::new("a b"),
+        Err(SignatureError::InvalidComponent)
+    );
+    assert_eq!(
+        ComponentId::new("@method").unwrap().with_sf(),
+        Err(SignatureError::InvalidComponent)
+    );
+    assert_eq!(
+        ComponentId::new("a").unwrap().with_bs().unwrap().with_sf(),
+        Err(SignatureError::InvalidComponent)
+    );
+    assert_eq!(
+        ComponentId::new("a").unwrap().with_key("K"),
+        Err(SignatureError::InvalidComponent)
+    );
+    assert_eq!(
+        SignatureParams::new().with_keyid("\u{7f}"),
+        Err(SignatureError::InvalidParameter)
+    );
+}
+
+// ========================================
+// 署名と検証のテスト
+// ========================================
+
+#[test]
+fn test_sign_and_verify() {
+    let request = test_request();
+    let params = SignatureParams::new()
+        .with_component(ComponentId::new("@method").unwrap())
+        .unwrap()
+        .with_component(ComponentId::new("content-digest").unwrap())
+        .unwrap()
+        .with_created(1618884473)
+        .with_expires(1618884773)
+        .with_keyid("test-key")
+        .unwrap();
+    let (input, signature) =
+        message_signature::sign(&SignedMessage::from(&request), "sig1", &params, |base| {
+            Ok(base.iter().rev().copied().collect())
+        })
+        .unwrap();
+
+    let mut request = request;
+    request.set_typed(&input).unwrap();
+    request.set_typed(&signature).unwrap();
+    let verifier = |params: &SignatureParams, base: &[u8], signature: &[u8]| {
+        params.keyid() == Some("test-key") && base.iter().rev().eq(signature.iter())
+    };
+
+    let message = SignedMessage::from(&request);
+    assert_eq!(
+        message_signature::verify(&message, "sig1", 1618884500, verifier),
+        Ok(params)
+    );
+    assert_eq!(
+        message_signature::verify(&message, "sig1", 1618884774, verifier),
+        Err(SignatureError::Expired)
+    );
+    assert_eq!(
+        message_signature::verify(&message, "sig2", 1618884500, verifier),
+        Err(SignatureError::UnknownLabel("sig2".to_string()))
+    );
+
+    // 署名対象のヘッダーを書き換えると検証に失敗する
+    request
+        .set_header("Content-Digest", "sha-256=:AAAA:")
+        .unwrap();
+    assert_eq!(
+        message_signature::verify(&SignedMessage::from(&request), "sig1", 1618884500, verifier),
+        Err(SignatureError::VerificationFailed)
+    );
+    request
+        .set_header("Content-Digest", CONTENT_DIGEST)
+        .unwrap();
+    assert_eq!(
+        message_signature::verify(&SignedMessage::from(&request), "sig1", 1618884500, verifier),
+        Ok(SignatureInput::from_message(&request)
+            .unwrap()
+            .unwrap()
+            .get("sig1")
+            .unwrap()
+            .clone())
+    );
+}
+
+#[test]
+fn test_sign_errors() {
+    let request = Request::new("GET", "/").unwrap();
+    let message = SignedMessage::from(&request);
+    let params = SignatureParams::new()
+        .with_component(ComponentId::new("date").unwrap())
+        .unwrap();
+    assert_eq!(
+        message_signature::sign(&message, "sig1", &params, |_| Ok(vec![])),
+        Err(SignatureError::MissingComponent("\"date\"".to_string()))
+    );
+    assert_eq!(
+        message_signature::sign(&message, "sig1", &SignatureParams::new(), |_| {
+            Err(SignatureError::SigningFailed)
+        }),
+        Err(SignatureError::SigningFailed)
+    );
+    assert_eq!(
+        message_signature::sign(&message, "Sig1", &SignatureParams::new(), |_| Ok(vec![])),
+        Err(SignatureError::InvalidLabel)
+    );
+    assert_eq!(
+        message_signature::verify(&message, "sig1", 0, |_, _, _| true),
+        Err(SignatureError::UnknownLabel("sig1".to_string()))
+    );
+}
@@ -0,0 +1,89 @@
+//! HTTP メソッド型のユニットテスト
+
+use shiguredo_http11::method::{Method, MethodError};
+use shiguredo_http11::{Request, RequestDecoder};
+
+#[test]
+fn test_method_error_display() {
+    assert_eq!(MethodError::Empty.to_string(), "empty method");
+    assert_eq!(
+        MethodError::InvalidToken.to_string(),
+        "invalid method: not a token"
+    );
+}
+
+#[test]
+fn test_method_parse_registered() {
+    for (s, expected) in [
+        ("GET", Method::GET),
+        ("HEAD", Method::HEAD),
+        ("POST", Method::POST),
+        ("PUT", Method::PUT),
+        ("DELETE", Method::DELETE),
+        ("CONNECT", Method::CONNECT),
+        ("OPTIONS", Method::OPTIONS),
+        ("TRACE", Method::TRACE),
+        ("PATCH", Method::PATCH),
+    ] {
+        assert_eq!(Method::parse(s).unwrap(), expected);
+        assert_eq!(expected.as_str(), s);
+        assert_eq!(expected.to_string(), s);
+    }
+}
+
+#[test]
+fn test_method_parse_extension_and_case_sensitivity() {
+    let propfind = Method::parse("PROPFIND").unwrap();
+    assert_eq!(propfind.as_str(), "PROPFIND");
+    assert!(!propfind.is_safe());
+
+    // RFC 9110 Section 9.1: メソッド名は大文字小文字を区別する
+    let lower = Method::parse("get").unwrap();
+    assert_ne!(lower, Method::GET);
+    assert!(!lower.is_safe());
+}
+
+#[test]
+fn test_method_parse_invalid() {
+    assert_eq!(Method::parse(""), Err(MethodError::Empty));
+    for s in ["GE T", "GET\r\n", "(GET)", "GET/"] {
+        assert_eq!(Method::parse(s), Err(MethodError::InvalidToken), "{s}");
+    }
+    assert_eq!("".parse::<Method>(), Err(MethodError::Empty));
+}
+
+#[test]
+fn test_method_properties() {
+    // RFC 9110 Section 9.2.1 / 9.2.2 / 9.2.3
+    for m in [Method::GET, Method::HEAD, Method::OPTIONS, Method::TRACE] {
+        assert!(m.is_safe(), "{m}");
+        assert!(m.is_idempotent(), "{m}");
+    }
+    for m in [Method::PUT, Method::DELETE] {
+        assert!(!m.is_safe(), "{m}");
+        assert!(m.is_idempotent(), "{m}");
+    }
+    for m in [Method::POST, Method::PATCH, Method::CONNECT] {
+        assert!(!m.is_safe(), "{m}");
+        assert!(!m.is_idempotent(), "{m}");
+    }
+    assert!(Method::GET.is_cacheable());
+    assert!(Method::HEAD.is_cacheable());
+    assert!(!Method::POST.is_cacheable());
+}
+
+#[test]
+fn test_method_with_request_and_decoder() {
+    let request = Request::new(Method::DELETE, "/item/1").unwrap();
+    assert_eq!(request.method(), "DELETE");
+    assert!(request.method() == Method::DELETE);
+    assert!(Method::DELETE == request.method());
+
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"PATCH /x HTTP/1.1\r\nHost: example.com\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(head.method() == Method::PATCH);
+    assert_eq!(head.method().parse::<Method>().unwrap(), Method::PATCH);
+}
@@ -1,6 +1,8 @@
 //! multipart のユニットテスト
 
-use shiguredo_http11::multipart::{MultipartBuilder, MultipartError, MultipartParser};
+use shiguredo_http11::multipart::{
+    MultipartBuilder, MultipartEncoder, MultipartError, MultipartParser, Part,
+};
 
 // ========================================
 // MultipartError のテスト
@@ -625,3 +627,326 @@ fn test_multipart_parser_inner_delimiter_transport_padding_incomplete() {
     assert!(matches!(parser.next_part(), Ok(None)));
     assert!(parser.is_finished());
 }
+
+// ========================================
+// Part::with_header のテスト
+// ========================================
+
+#[test]
+fn test_part_with_header_roundtrip() {
+    let part = Part::file("file", "a.txt", "text/plain")
+        .with_header("Content-Transfer-Encoding", "binary")
+        .unwrap()
+        .with_header("X-Custom", "value")
+        .unwrap()
+        .with_body(b"hello");
+    let body = MultipartBuilder::with_boundary("b").part(part).build();
+    assert_eq!(
+        body,
+        b"--b\r\n\
+          Content-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\
+          Content-Type: text/plain\r\n\
+          Content-Transfer-Encoding: binary\r\n\
+          X-Custom: value\r\n\
+          \r\n\
+          hello\r\n\
+          --b--\r\n"
+    );
+
+    let mut parser = MultipartParser::new("b");
+    parser.feed(&body).unwrap();
+    let parsed = parser.next_part().unwrap().unwrap();
+    assert_eq!(
+        parsed.headers(),
+        &[
+            (
+                "Content-Transfer-Encoding".to_string(),
+                "binary".to_string()
+            ),
+            ("X-Custom".to_string(), "value".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_part_with_header_invalid() {
+    for (name, value) in [
+        ("X-Bad", "a\r\nInjected: 1"),
+        ("X-Bad", "a\0b"),
+        ("Bad Name", "value"),
+        ("", "value"),
+        ("Content-Type", "text/plain"),
+        ("content-disposition", "form-data; name=\"x\""),
+    ] {
+        assert_eq!(
+            Part::new("field").with_header(name, value).unwrap_err(),
+            MultipartError::InvalidHeader,
+            "{}: {}",
+            name,
+            value
+        );
+    }
+}
+
+// ========================================
+// MultipartBuilder::with_safe_boundary のテスト
+// ========================================
+
+#[test]
+fn test_with_safe_boundary_keeps_boundary_without_collision() {
+    let builder = MultipartBuilder::with_boundary("boundary")
+        .text_field("a", "no collision")
+        .with_safe_boundary();
+    assert_eq!(builder.boundary(), "boundary");
+}
+
+#[test]
+fn test_with_safe_boundary_avoids_collision() {
+    let builder = MultipartBuilder::with_boundary("boundary")
+        .text_field("a", "--boundary\r\n--boundary-1")
+        .file_field("b", "--boundary-2", "text/plain", b"x")
+        .with_safe_boundary();
+    assert_eq!(builder.boundary(), "boundary-3");
+    assert_eq!(
+        builder.content_type(),
+        "multipart/form-data; boundary=boundary-3"
+    );
+
+    let mut parser = MultipartParser::new(builder.boundary());
+    parser.feed(&builder.build()).unwrap();
+    assert_eq!(
+        parser.next_part().unwrap().unwrap().body(),
+        b"--boundary\r\n--boundary-1"
+    );
+    assert_eq!(
+        parser.next_part().unwrap().unwrap().filename(),
+        Some("--boundary-2")
+    );
+    assert!(parser.next_part().unwrap().is_none());
+}
+
+#[test]
+fn test_with_safe_boundary_stays_within_70_chars() {
+    let boundary = "a".repeat(70);
+    let content = format!("--{}", boundary);
+    let builder = MultipartBuilder::with_boundary(&boundary)
+        .text_field("a", &content)
+        .with_safe_boundary();
+    assert_eq!(builder.boundary().len(), 70);
+    assert!(builder.boundary().ends_with("-1"));
+    assert!(MultipartParser::try_new(builder.boundary()).is_ok());
+}
+
+// ========================================
+// MultipartEncoder のテスト
+// ========================================
+
+#[test]
+fn test_encoder_matches_builder() {
+    let builder = MultipartBuilder::with_boundary("b")
+        .text_field("field", "value")
+        .file_field("file", "a.bin", "application/octet-stream", b"0123456789");
+
+    let mut encoder = MultipartEncoder::with_boundary("b");
+    let mut body = Vec::new();
+    body.extend_from_slice(&encoder.begin_part(&Part::new("field")));
+    body.extend_from_slice(b"value");
+    body.extend_from_slice(&encoder.begin_part(&Part::file(
+        "file",
+        "a.bin",
+        "application/octet-stream",
+    )));
+    for chunk in b"0123456789".chunks(3) {
+        body.extend_from_slice(chunk);
+    }
+    body.extend_from_slice(&encoder.finish());
+
+    assert_eq!(body, builder.build());
+}
+
+#[test]
+fn test_encoder_ignores_part_body() {
+    let mut encoder = MultipartEncoder::with_boundary("b");
+    let head = encoder.begin_part(&Part::new("field").with_body(b"ignored"));
+    assert_eq!(
+        head,
+        b"--b\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\n"
+    );
+    assert_eq!(encoder.finish(), b"\r\n--b--\r\n");
+}
+
+#[test]
+fn test_encoder_no_parts() {
+    let encoder = MultipartEncoder::with_boundary("b");
+    assert_eq!(
+        encoder.finish(),
+        MultipartBuilder::with_boundary("b").build()
+    );
+}
+
+#[test]
+fn test_encoder_boundary_and_content_type() {
+    let encoder = MultipartEncoder::new(42);
+    assert_eq!(encoder.boundary(), "----FormBoundary42");
+    assert_eq!(
+        encoder.content_type(),
+        "multipart/form-data; boundary=----FormBoundary42"
+    );
+    assert_eq!(
+        MultipartEncoder::with_boundary("a b").content_type(),
+        "multipart/form-data; boundary=\"a b\""
+    );
+    assert!(MultipartEncoder::try_with_boundary("valid").is_ok());
+    assert_eq!(
+        MultipartEncoder::try_with_boundary("").unwrap_err(),
+        MultipartError::InvalidBoundary
+    );
+}
+
+// ========================================
+// 入れ子の multipart のテスト
+// ========================================
+
+/// RFC 2388 Section 5.1 / RFC 7578 Section 4.3 の multipart/mixed を含む form-data
+const NESTED_BODY: &[u8] = b"--AaB03x\r\n\
+    Content-Disposition: form-data; name=\"submit-name\"\r\n\
+    \r\n\
+    Larry\r\n\
+    --AaB03x\r\n\
+    Content-Disposition: form-data; name=\"files\"\r\n\
+    Content-Type: multipart/mixed; boundary=BbC04y\r\n\
+    \r\n\
+    --BbC04y\r\n\
+    Content-Disposition: attachment; filename=\"file1.txt\"\r\n\
+    Content-Type: text/plain\r\n\
+    \r\n\
+    ... contents of file1.txt ...\r\n\
+    --BbC04y\r\n\
+    Content-Disposition: attachment; filename=\"file2.gif\"\r\n\
+    Content-Type: image/gif\r\n\
+    Content-Transfer-Encoding: binary\r\n\
+    \r\n\
+    ...contents of file2.gif...\r\n\
+    --BbC04y--\r\n\
+    \r\n\
+    --AaB03x--\r\n";
+
+#[test]
+fn test_nested_multipart_not_expanded_by_default() {
+    let mut parser = MultipartParser::new("AaB03x");
+    parser.feed(NESTED_BODY).unwrap();
+    parser.next_part().unwrap().unwrap();
+    let files = parser.next_part().unwrap().unwrap();
+    assert!(files.is_multipart());
+    assert!(files.parts().is_empty());
+    assert!(files.body().starts_with(b"--BbC04y\r\n"));
+}
+
+#[test]
+fn test_nested_multipart_expanded() {
+    let mut parser = MultipartParser::new("AaB03x").with_max_depth(1);
+    parser.feed(NESTED_BODY).unwrap();
+
+    let name = parser.next_part().unwrap().unwrap();
+    assert!(!name.is_multipart());
+    assert!(name.parts().is_empty());
+    assert_eq!(name.body(), b"Larry");
+
+    let files = parser.next_part().unwrap().unwrap();
+    assert_eq!(files.name(), Some("files"));
+    assert_eq!(files.parts().len(), 2);
+    assert_eq!(files.parts()[0].filename(), Some("file1.txt"));
+    assert_eq!(files.parts()[0].name(), None);
+    assert_eq!(files.parts()[0].body(), b"... contents of file1.txt ...");
+    assert_eq!(
+        files.parts()[1].content_type().unwrap().mime_type(),
+        "image/gif"
+    );
+    assert_eq!(
+        files.parts()[1].headers(),
+        &[(
+            "Content-Transfer-Encoding".to_string(),
+            "binary".to_string()
+        )]
+    );
+
+    assert!(parser.next_part().unwrap().is_none());
+}
+
+#[test]
+fn test_nested_multipart_depth_limit() {
+    let inner = Part::attachment("a.txt", "text/plain").with_body(b"a");
+    let middle =
+        Part::attachment("m", "text/plain").with_nested_parts("mixed", "level2", vec![inner]);
+    let outer = Part::new("field").with_nested_parts("mixed", "level1", vec![middle]);
+    let body = MultipartBuilder::with_boundary("level0")
+        .part(outer)
+        .build();
+
+    let mut parser = MultipartParser::new("level0").with_max_depth(1);
+    parser.feed(&body).unwrap();
+    assert_eq!(
+        parser.next_part().unwrap_err(),
+        MultipartError::NestingTooDeep
+    );
+
+    let mut parser = MultipartParser::new("level0").with_max_depth(2);
+    parser.feed(&body).unwrap();
+    let part = parser.next_part().unwrap().unwrap();
+    assert_eq!(part.parts()[0].parts()[0].body(), b"a");
+}
+
+#[test]
+fn test_nested_multipart_incomplete_body() {
+    let body = b"--outer\r\n\
+        Content-Disposition: form-data; name=\"files\"\r\n\
+        Content-Type: multipart/mixed; boundary=inner\r\n\
+        \r\n\
+        --inner\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        no close delimiter\r\n\
+        --outer--\r\n";
+    let mut parser = MultipartParser::new("outer").with_max_depth(1);
+    parser.feed(body).unwrap();
+    assert_eq!(parser.next_part().unwrap_err(), MultipartError::InvalidPart);
+}
+
+#[test]
+fn test_nested_multipart_build() {
+    let files = Part::new("files").with_nested_parts(
+        "mixed",
+        "BbC04y",
+        vec![Part::attachment("file1.txt", "text/plain").with_body(b"one")],
+    );
+    assert!(files.is_multipart());
+    assert_eq!(
+        files.content_type().unwrap().to_string(),
+        "multipart/mixed; boundary=BbC04y"
+    );
+    assert_eq!(
+        files.body(),
+        b"--BbC04y\r\n\
+          Content-Disposition: attachment; filename=\"file1.txt\"\r\n\
+          Content-Type: text/plain\r\n\
+          \r\n\
+          one\r\n\
+          --BbC04y--\r\n"
+    );
+    assert_eq!(files.parts().len(), 1);
+
+    let body = MultipartBuilder::with_boundary("AaB03x")
+        .part(files.clone())
+        .build();
+    let mut parser = MultipartParser::new("AaB03x").with_max_depth(1);
+    parser.feed(&body).unwrap();
+    assert_eq!(parser.next_part().unwrap().unwrap(), files);
+}
+
+#[test]
+fn test_nesting_too_deep_display() {
+    assert_eq!(
+        MultipartError::NestingTooDeep.to_string(),
+        "multipart nesting too deep"
+    );
+}
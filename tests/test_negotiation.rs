@@ -0,0 +1,201 @@
+//! プロアクティブネゴシエーションのユニットテスト
+
+use shiguredo_http11::Request;
+use shiguredo_http11::negotiation::{NegotiationError, Negotiator};
+use shiguredo_http11::vary::{Vary, cache_key};
+
+fn request(headers: &[(&str, &str)]) -> Request {
+    let mut request = Request::new("GET", "/").unwrap();
+    for (name, value) in headers {
+        request.add_header(*name, *value).unwrap();
+    }
+    request
+}
+
+fn negotiator() -> Negotiator<'static> {
+    Negotiator::new()
+        .with_media_types(&["application/json", "text/html"])
+        .with_encodings(&["br", "gzip"])
+        .with_languages(&["en", "ja", "fr"])
+}
+
+// ========================================
+// NegotiationError のテスト
+// ========================================
+
+#[test]
+fn test_negotiation_error_display() {
+    let errors = [
+        (
+            NegotiationError::NoAcceptableMediaType,
+            "no acceptable media type",
+        ),
+        (
+            NegotiationError::NoAcceptableEncoding,
+            "no acceptable content coding",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// 選択
+// ========================================
+
+#[test]
+fn test_negotiate_without_headers() {
+    let negotiation = negotiator().negotiate(&request(&[])).unwrap();
+    assert_eq!(negotiation.media_type(), Some("application/json"));
+    assert_eq!(negotiation.content_encoding(), Some("br"));
+    assert_eq!(negotiation.language(), Some("en"));
+}
+
+#[test]
+fn test_negotiate_media_type() {
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept", "text/*")]))
+        .unwrap();
+    assert_eq!(negotiation.media_type(), Some("text/html"));
+
+    // q 値が同じ場合はサーバーの優先順
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept", "text/html, application/json")]))
+        .unwrap();
+    assert_eq!(negotiation.media_type(), Some("application/json"));
+
+    assert_eq!(
+        negotiator().negotiate(&request(&[("Accept", "image/png")])),
+        Err(NegotiationError::NoAcceptableMediaType)
+    );
+}
+
+#[test]
+fn test_negotiate_encoding() {
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept-Encoding", "gzip, br;q=0.5")]))
+        .unwrap();
+    assert_eq!(negotiation.content_encoding(), Some("gzip"));
+
+    // 候補が受理されない場合は identity
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept-Encoding", "deflate")]))
+        .unwrap();
+    assert_eq!(negotiation.content_encoding(), None);
+
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept-Encoding", "")]))
+        .unwrap();
+    assert_eq!(negotiation.content_encoding(), None);
+
+    assert_eq!(
+        negotiator().negotiate(&request(&[("Accept-Encoding", "deflate, identity;q=0")])),
+        Err(NegotiationError::NoAcceptableEncoding)
+    );
+}
+
+#[test]
+fn test_negotiate_language() {
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept-Language", "fr-CA, ja;q=0.8")]))
+        .unwrap();
+    assert_eq!(negotiation.language(), Some("fr"));
+
+    // 一致しない場合は先頭の言語
+    let negotiation = negotiator()
+        .negotiate(&request(&[("Accept-Language", "de")]))
+        .unwrap();
+    assert_eq!(negotiation.language(), Some("en"));
+}
+
+#[test]
+fn test_negotiate_multiple_header_lines() {
+    let negotiation = negotiator()
+        .negotiate(&request(&[
+            ("Accept-Encoding", "br;q=0.1"),
+            ("Accept-Encoding", "gzip"),
+        ]))
+        .unwrap();
+    assert_eq!(negotiation.content_encoding(), Some("gzip"));
+}
+
+#[test]
+fn test_negotiate_invalid_headers_ignored() {
+    let negotiation = negotiator()
+        .negotiate(&request(&[
+            ("Accept", "text/html;q=2"),
+            ("Accept-Encoding", "gzip;q=abc"),
+            ("Accept-Language", "en;;"),
+        ]))
+        .unwrap();
+    assert_eq!(negotiation.media_type(), Some("application/json"));
+    assert_eq!(negotiation.content_encoding(), Some("br"));
+    assert_eq!(negotiation.language(), Some("en"));
+}
+
+#[test]
+fn test_negotiate_unconfigured_dimensions() {
+    let negotiation = Negotiator::new()
+        .negotiate(&request(&[("Accept", "image/png")]))
+        .unwrap();
+    assert_eq!(negotiation.media_type(), None);
+    assert_eq!(negotiation.content_encoding(), None);
+    assert_eq!(negotiation.language(), None);
+    assert_eq!(negotiation.vary_header(), None);
+}
+
+// ========================================
+// Vary
+// ========================================
+
+#[test]
+fn test_vary_fields() {
+    assert_eq!(
+        negotiator().vary_fields(),
+        vec!["Accept", "Accept-Encoding", "Accept-Language"]
+    );
+
+    // 候補が 1 つのメディアタイプと言語は選択に影響しない
+    let negotiator = Negotiator::new()
+        .with_media_types(&["text/html"])
+        .with_languages(&["ja"]);
+    assert!(negotiator.vary_fields().is_empty());
+
+    // コーディングは identity との選択になる
+    let negotiator = negotiator.with_encodings(&["gzip"]);
+    assert_eq!(negotiator.vary_fields(), vec!["Accept-Encoding"]);
+    let negotiation = negotiator.negotiate(&request(&[])).unwrap();
+    assert_eq!(negotiation.vary_fields(), &["Accept-Encoding"]);
+    assert_eq!(
+        negotiation.vary_header().as_deref(),
+        Some("Accept-Encoding")
+    );
+}
+
+#[test]
+fn test_vary_independent_of_request() {
+    let negotiator = negotiator();
+    let with_headers = negotiator
+        .negotiate(&request(&[
+            ("Accept", "text/html"),
+            ("Accept-Language", "ja"),
+        ]))
+        .unwrap();
+    let without_headers = negotiator.negotiate(&request(&[])).unwrap();
+    assert_eq!(with_headers.vary_header(), without_headers.vary_header());
+}
+
+#[test]
+fn test_vary_cache_key() {
+    let negotiation = negotiator().negotiate(&request(&[])).unwrap();
+    let vary = Vary::parse(&negotiation.vary_header().unwrap()).unwrap();
+
+    let a = request(&[("Accept-Encoding", "gzip, br"), ("Accept", "text/html")]);
+    let b = request(&[("Accept", "text/html"), ("accept-encoding", "br,gzip")]);
+    assert_eq!(cache_key(&vary, &a), cache_key(&vary, &b));
+    assert_eq!(
+        negotiator().negotiate(&a).unwrap(),
+        negotiator().negotiate(&b).unwrap()
+    );
+}
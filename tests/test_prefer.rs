@@ -0,0 +1,256 @@
+//! Prefer / Preference-Applied ヘッダーのユニットテスト
+
+use shiguredo_http11::prefer::{
+    Handling, Prefer, PreferError, Preference, PreferenceApplied, ReturnPreference,
+};
+use shiguredo_http11::{Request, RequestDecoder, Response, StatusCode};
+
+// ========================================
+// PreferError のテスト
+// ========================================
+
+#[test]
+fn test_prefer_error_display() {
+    let errors = [
+        (PreferError::InvalidFormat, "invalid Prefer header format"),
+        (PreferError::InvalidName, "invalid preference name"),
+        (PreferError::InvalidValue, "invalid preference value"),
+        (PreferError::UnterminatedQuote, "unterminated quoted-string"),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// Prefer パースのテスト
+// ========================================
+
+#[test]
+fn test_prefer_parse_rfc7240_examples() {
+    // RFC 7240 Section 2
+    let prefer = Prefer::parse("foo; bar").unwrap();
+    let foo = prefer.get("foo").unwrap();
+    assert_eq!(foo.value(), None);
+    assert!(foo.has_param("bar"));
+
+    let prefer = Prefer::parse("foo=\"\"; bar").unwrap();
+    assert_eq!(prefer.get("foo").unwrap().value(), None);
+
+    let prefer = Prefer::parse("foo=abc, bar=\"x y\"; baz=1").unwrap();
+    assert_eq!(prefer.preferences().len(), 2);
+    assert_eq!(prefer.get("foo").unwrap().value(), Some("abc"));
+    let bar = prefer.get("bar").unwrap();
+    assert_eq!(bar.value(), Some("x y"));
+    assert_eq!(bar.param("baz"), Some("1"));
+
+    // RFC 7240 Section 4.1 - 4.4
+    let prefer = Prefer::parse("respond-async, wait=100, handling=lenient").unwrap();
+    assert!(prefer.respond_async());
+    assert_eq!(prefer.wait(), Some(100));
+    assert_eq!(prefer.handling(), Some(Handling::Lenient));
+    assert_eq!(prefer.return_preference(), None);
+
+    let prefer = Prefer::parse("return=representation").unwrap();
+    assert_eq!(
+        prefer.return_preference(),
+        Some(ReturnPreference::Representation)
+    );
+}
+
+#[test]
+fn test_prefer_parse_case_and_bws() {
+    let prefer = Prefer::parse("Return = Minimal ;  Foo = \"Bar\" , HANDLING=Strict").unwrap();
+    let ret = prefer.get("return").unwrap();
+    assert_eq!(ret.name(), "return");
+    assert_eq!(ret.value(), Some("Minimal"));
+    assert_eq!(ret.params(), [("foo".to_string(), Some("Bar".to_string()))]);
+    assert_eq!(prefer.return_preference(), Some(ReturnPreference::Minimal));
+    assert_eq!(prefer.handling(), Some(Handling::Strict));
+    assert!(prefer.has("Return"));
+}
+
+#[test]
+fn test_prefer_parse_first_wins() {
+    // RFC 7240 Section 2: 同じ preference は最初のものを採用する
+    let prefer = Prefer::parse("wait=5, return=minimal, wait=30").unwrap();
+    assert_eq!(prefer.wait(), Some(5));
+    assert_eq!(prefer.preferences().len(), 3);
+}
+
+#[test]
+fn test_prefer_parse_unknown_values() {
+    let prefer = Prefer::parse("return=full, wait=soon, handling=maybe").unwrap();
+    assert_eq!(prefer.return_preference(), None);
+    assert_eq!(prefer.wait(), None);
+    assert_eq!(prefer.handling(), None);
+
+    let prefer = Prefer::parse("wait=99999999999999999999999").unwrap();
+    assert_eq!(prefer.wait(), Some(u64::MAX));
+}
+
+#[test]
+fn test_prefer_parse_empty_elements() {
+    assert!(Prefer::parse("").unwrap().preferences().is_empty());
+    let prefer = Prefer::parse(" , respond-async;; ; x ,, wait=1 , ").unwrap();
+    assert_eq!(prefer.to_string(), "respond-async; x, wait=1");
+}
+
+#[test]
+fn test_prefer_parse_errors() {
+    let cases = [
+        ("=1", PreferError::InvalidName),
+        ("wait=", PreferError::InvalidValue),
+        ("wait 1", PreferError::InvalidFormat),
+        ("return=\"minimal\"x", PreferError::InvalidFormat),
+        ("return=\"minimal", PreferError::UnterminatedQuote),
+        ("a; =1", PreferError::InvalidName),
+        ("a; b=", PreferError::InvalidValue),
+        ("a=\"\u{7f}\"", PreferError::InvalidValue),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Prefer::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_prefer_from_request_joins_lines() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(
+            b"POST /items HTTP/1.1\r\nHost: example.com\r\nPrefer: respond-async\r\n\
+              Prefer: wait=10\r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let prefer = Prefer::from_request(&head).unwrap().unwrap();
+    assert!(prefer.respond_async());
+    assert_eq!(prefer.wait(), Some(10));
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(Prefer::from_request(&request), Ok(None));
+}
+
+// ========================================
+// Prefer 生成のテスト
+// ========================================
+
+#[test]
+fn test_preference_builder() {
+    let preference = Preference::new("Return")
+        .unwrap()
+        .with_value("minimal")
+        .unwrap()
+        .with_param("Profile", Some("a \"b\""))
+        .unwrap()
+        .with_param("flag", None)
+        .unwrap();
+    assert_eq!(
+        preference.to_string(),
+        "return=minimal; profile=\"a \\\"b\\\"\"; flag"
+    );
+
+    let prefer = Prefer::new()
+        .with_preference(preference)
+        .with_preference(Preference::new("respond-async").unwrap());
+    assert_eq!(Prefer::parse(&prefer.to_string()), Ok(prefer.clone()));
+
+    let request = Request::new("POST", "/")
+        .unwrap()
+        .header("Prefer", prefer.to_string())
+        .unwrap();
+    assert_eq!(request.get_typed::<Prefer>(), Some(Ok(prefer)));
+
+    // 空の値は値なし
+    let preference = Preference::new("foo").unwrap().with_value("").unwrap();
+    assert_eq!(preference.value(), None);
+    assert_eq!(preference.to_string(), "foo");
+}
+
+#[test]
+fn test_preference_builder_errors() {
+    assert_eq!(Preference::new("a b"), Err(PreferError::InvalidName));
+    assert_eq!(Preference::new(""), Err(PreferError::InvalidName));
+    let preference = Preference::new("a").unwrap();
+    assert_eq!(
+        preference.clone().with_value("a\r\nb"),
+        Err(PreferError::InvalidValue)
+    );
+    assert_eq!(
+        preference.clone().with_param("a;b", None),
+        Err(PreferError::InvalidName)
+    );
+    assert_eq!(
+        preference.with_param("a", Some("\0")),
+        Err(PreferError::InvalidValue)
+    );
+}
+
+// ========================================
+// Preference-Applied のテスト
+// ========================================
+
+#[test]
+fn test_preference_applied() {
+    let prefer = Prefer::parse("return=minimal; foo=bar, respond-async").unwrap();
+    let applied = PreferenceApplied::new()
+        .with_preference(prefer.get("return").unwrap())
+        .with("wait", Some("10"))
+        .unwrap();
+    // パラメーターは含めない
+    assert_eq!(applied.to_string(), "return=minimal, wait=10");
+    assert!(applied.contains("RETURN"));
+    assert_eq!(applied.value("wait"), Some("10"));
+    assert!(!applied.contains("respond-async"));
+    assert_eq!(PreferenceApplied::parse(&applied.to_string()), Ok(applied));
+
+    assert_eq!(
+        PreferenceApplied::new().with("a b", None),
+        Err(PreferError::InvalidName)
+    );
+}
+
+#[test]
+fn test_preference_applied_parse() {
+    let applied = PreferenceApplied::parse("Return=minimal , respond-async,").unwrap();
+    assert_eq!(
+        applied.applied(),
+        [
+            ("return".to_string(), Some("minimal".to_string())),
+            ("respond-async".to_string(), None),
+        ]
+    );
+    // applied-pref はパラメーターを持たない
+    assert_eq!(
+        PreferenceApplied::parse("return=minimal; foo"),
+        Err(PreferError::InvalidFormat)
+    );
+    assert!(PreferenceApplied::parse("").unwrap().is_empty());
+}
+
+#[test]
+fn test_response_preference_applied() {
+    let applied = PreferenceApplied::new()
+        .with("respond-async", None)
+        .unwrap();
+    let response = Response::with_status(StatusCode::ACCEPTED)
+        .header("Preference-Applied", "return=minimal")
+        .unwrap()
+        .preference_applied(&applied)
+        .unwrap();
+    assert_eq!(
+        response.get_headers("Preference-Applied"),
+        ["respond-async"]
+    );
+    assert_eq!(
+        PreferenceApplied::from_response(&response),
+        Ok(Some(applied))
+    );
+
+    let response = Response::with_status(StatusCode::OK)
+        .preference_applied(&PreferenceApplied::new())
+        .unwrap();
+    assert!(!response.has_header("Preference-Applied"));
+    assert_eq!(PreferenceApplied::from_response(&response), Ok(None));
+}
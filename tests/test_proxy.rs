@@ -0,0 +1,365 @@
+//! プロキシのメッセージ書き換えのユニットテスト
+
+use std::borrow::Cow;
+
+use shiguredo_http11::forwarded::{ForwardedElement, Node};
+use shiguredo_http11::host::Host;
+use shiguredo_http11::proxy::{ProxyError, ProxyRewriter, downstream_body_kind};
+use shiguredo_http11::via::ViaEntry;
+use shiguredo_http11::{
+    BodyKind, HttpHead, HttpVersion, RequestDecoder, RequestHead, ResponseDecoder, ResponseHead,
+};
+
+fn decode_request(input: &[u8]) -> (RequestHead, BodyKind) {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(input).unwrap();
+    decoder.decode_headers().unwrap().unwrap()
+}
+
+fn decode_response(input: &[u8]) -> (ResponseHead, BodyKind) {
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(input).unwrap();
+    decoder.decode_headers().unwrap().unwrap()
+}
+
+// ========================================
+// ProxyError のテスト
+// ========================================
+
+#[test]
+fn test_proxy_error_display() {
+    let errors = [
+        (ProxyError::LoopDetected, "request loop detected"),
+        (
+            ProxyError::MissingHost,
+            "missing host for forwarded request",
+        ),
+        (
+            ProxyError::InvalidFraming,
+            "invalid message framing for proxy",
+        ),
+        (
+            ProxyError::InvalidHeader,
+            "invalid header for forwarded message",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// リクエストの書き換え
+// ========================================
+
+#[test]
+fn test_rewrite_request_strips_hop_by_hop() {
+    let (head, body_kind) = decode_request(
+        b"GET /index.html HTTP/1.1\r\n\
+          Host: example.com\r\n\
+          Connection: keep-alive, X-Secret\r\n\
+          Keep-Alive: timeout=5\r\n\
+          X-Secret: 1\r\n\
+          Proxy-Authorization: Basic Zm9vOmJhcg==\r\n\
+          TE: trailers\r\n\
+          Upgrade: websocket\r\n\
+          Accept: text/html\r\n\r\n",
+    );
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, body_kind, None)
+        .unwrap();
+    assert_eq!(
+        request.head().headers(),
+        [
+            (Cow::Borrowed("Host"), "example.com".to_string()),
+            (Cow::Borrowed("Accept"), "text/html".to_string()),
+        ]
+    );
+    assert_eq!(request.body_kind(), BodyKind::None);
+    assert_eq!(
+        request.encode_head(),
+        b"GET /index.html HTTP/1.1\r\nHost: example.com\r\nAccept: text/html\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_rewrite_request_host() {
+    let (head, body_kind) =
+        decode_request(b"GET /a?b=c HTTP/1.0\r\nHost: example.com\r\nAccept: */*\r\n\r\n");
+
+    // 上流のホストに書き換え、Host を先頭に置く
+    let rewriter = ProxyRewriter::new().with_upstream_host(Host::parse("[::1]:8080").unwrap());
+    let request = rewriter.rewrite_request(&head, body_kind, None).unwrap();
+    assert_eq!(request.head().get_headers("Host"), ["[::1]:8080"]);
+    assert_eq!(request.head().headers()[0].0, "Host");
+    // 自身の HTTP-version で転送する
+    assert_eq!(request.head().version(), "HTTP/1.1");
+    assert_eq!(request.head().uri(), "/a?b=c");
+
+    // Host がない HTTP/1.0 のリクエスト
+    let (head, body_kind) = decode_request(b"GET / HTTP/1.0\r\n\r\n");
+    assert_eq!(
+        ProxyRewriter::new().rewrite_request(&head, body_kind, None),
+        Err(ProxyError::MissingHost)
+    );
+    assert!(rewriter.rewrite_request(&head, body_kind, None).is_ok());
+}
+
+#[test]
+fn test_rewrite_request_absolute_form() {
+    let (head, body_kind) = decode_request(
+        b"GET http://origin.example:8080?q=1 HTTP/1.1\r\nHost: ignored.example\r\n\r\n",
+    );
+
+    // RFC 9112 Section 3.2.2: absolute-form では受信した Host を使わない
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, body_kind, None)
+        .unwrap();
+    assert_eq!(request.head().uri(), "http://origin.example:8080?q=1");
+    assert_eq!(request.head().get_headers("Host"), ["origin.example:8080"]);
+
+    let request = ProxyRewriter::new()
+        .with_origin_form()
+        .rewrite_request(&head, body_kind, None)
+        .unwrap();
+    assert_eq!(request.head().uri(), "/?q=1");
+    assert_eq!(request.head().get_headers("Host"), ["origin.example:8080"]);
+
+    // origin-form はそのまま
+    let (head, body_kind) = decode_request(b"GET /x HTTP/1.1\r\nHost: a.example\r\n\r\n");
+    let request = ProxyRewriter::new()
+        .with_origin_form()
+        .rewrite_request(&head, body_kind, None)
+        .unwrap();
+    assert_eq!(request.head().uri(), "/x");
+}
+
+#[test]
+fn test_rewrite_request_framing() {
+    let head = RequestHead::new("POST", "/")
+        .unwrap()
+        .header("Host", "a.example")
+        .unwrap()
+        .header("Transfer-Encoding", "gzip, chunked")
+        .unwrap();
+    let body_kind = BodyKind::Chunked;
+
+    // chunked のまま転送する場合は転送コーディングを引き継ぐ
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, body_kind, None)
+        .unwrap();
+    assert_eq!(
+        request.head().get_headers("Transfer-Encoding"),
+        ["gzip, chunked"]
+    );
+    assert!(request.head().get_header("Content-Length").is_none());
+
+    // ボディを収集して Content-Length で転送する
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, BodyKind::ContentLength(42), None)
+        .unwrap();
+    assert_eq!(request.head().get_headers("Content-Length"), ["42"]);
+    assert!(request.head().get_header("Transfer-Encoding").is_none());
+
+    // Content-Length のリクエストを chunked で転送する
+    let (head, _) =
+        decode_request(b"PUT / HTTP/1.1\r\nHost: a.example\r\nContent-Length: 3\r\n\r\nabc");
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, BodyKind::Chunked, None)
+        .unwrap();
+    assert_eq!(request.head().get_headers("Transfer-Encoding"), ["chunked"]);
+    assert!(request.head().get_header("Content-Length").is_none());
+
+    // ボディなしで転送する場合は Content-Length も付けない
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, BodyKind::None, None)
+        .unwrap();
+    assert!(request.head().get_header("Content-Length").is_none());
+
+    // リクエストは close-delimited にできない
+    assert_eq!(
+        ProxyRewriter::new().rewrite_request(&head, BodyKind::CloseDelimited, None),
+        Err(ProxyError::InvalidFraming)
+    );
+}
+
+#[test]
+fn test_rewrite_request_framing_inherits_unchecked_transfer_encoding() {
+    // 受信した Transfer-Encoding は検証せずに引き継ぐ。デコーダーが返すヘッダーは
+    // chunked で終わることを検証済みだが、自前で組み立てたヘッダーはそうとは限らない
+    let head = RequestHead::new("POST", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .header("Transfer-Encoding", "a")
+        .unwrap();
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, BodyKind::Chunked, None)
+        .unwrap();
+    assert_eq!(request.head().get_headers("Transfer-Encoding"), ["a"]);
+
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(&request.encode_head()).unwrap();
+    assert!(decoder.decode_headers().is_err());
+}
+
+#[test]
+fn test_rewrite_request_via_and_forwarded() {
+    let (head, body_kind) = decode_request(
+        b"GET / HTTP/1.0\r\n\
+          Host: example.com\r\n\
+          Via: 1.0 fred, 1.1 p.example.net\r\n\
+          Forwarded: for=192.0.2.60\r\n\
+          X-Forwarded-For: 192.0.2.60\r\n\
+          X-Forwarded-Proto: https\r\n\r\n",
+    );
+    let rewriter = ProxyRewriter::new()
+        .with_via(ViaEntry::new("1.0", "proxy").unwrap())
+        .with_x_forwarded();
+    let forwarded = ForwardedElement::new()
+        .with_for(Node::parse("198.51.100.17").unwrap())
+        .with_proto("http")
+        .unwrap()
+        .with_host("example.com")
+        .unwrap();
+    let request = rewriter
+        .rewrite_request(&head, body_kind, Some(&forwarded))
+        .unwrap();
+    let head = request.head();
+    assert_eq!(
+        head.get_headers("Via"),
+        ["1.0 fred, 1.1 p.example.net, 1.0 proxy"]
+    );
+    assert_eq!(
+        head.get_headers("Forwarded"),
+        ["for=192.0.2.60, for=198.51.100.17;host=example.com;proto=http"]
+    );
+    assert_eq!(
+        head.get_headers("X-Forwarded-For"),
+        ["192.0.2.60, 198.51.100.17"]
+    );
+    // 既存の X-Forwarded-Proto は残す
+    assert_eq!(head.get_headers("X-Forwarded-Proto"), ["https"]);
+    assert_eq!(head.get_headers("X-Forwarded-Host"), ["example.com"]);
+
+    // Via を設定しない場合は既存の Via をそのまま転送する
+    let (head, body_kind) =
+        decode_request(b"GET / HTTP/1.1\r\nHost: example.com\r\nVia: 1.1 fred\r\n\r\n");
+    let request = ProxyRewriter::new()
+        .rewrite_request(&head, body_kind, None)
+        .unwrap();
+    assert_eq!(request.head().get_headers("Via"), ["1.1 fred"]);
+}
+
+#[test]
+fn test_rewrite_request_loop_detected() {
+    let (head, body_kind) =
+        decode_request(b"GET / HTTP/1.1\r\nHost: example.com\r\nVia: 1.1 a, 1.1 proxy\r\n\r\n");
+    let rewriter = ProxyRewriter::new().with_via(ViaEntry::new("1.1", "proxy").unwrap());
+    assert_eq!(
+        rewriter.rewrite_request(&head, body_kind, None),
+        Err(ProxyError::LoopDetected)
+    );
+    let rewriter = ProxyRewriter::new().with_via(ViaEntry::new("1.1", "other").unwrap());
+    assert!(rewriter.rewrite_request(&head, body_kind, None).is_ok());
+}
+
+// ========================================
+// レスポンスの書き換え
+// ========================================
+
+#[test]
+fn test_rewrite_response() {
+    let (head, body_kind) = decode_response(
+        b"HTTP/1.1 200 OK\r\n\
+          Connection: close, X-Internal\r\n\
+          X-Internal: 1\r\n\
+          Content-Type: text/plain\r\n\
+          Transfer-Encoding: chunked\r\n\r\n",
+    );
+    let rewriter = ProxyRewriter::new().with_via(ViaEntry::new("1.1", "proxy").unwrap());
+    let response = rewriter.rewrite_response(&head, body_kind).unwrap();
+    assert_eq!(response.body_kind(), BodyKind::Chunked);
+    assert_eq!(
+        response.encode_head(),
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\nVia: 1.1 proxy\r\n\r\n"
+    );
+
+    // HTTP/1.0 のクライアントには close-delimited で送る
+    let kind = downstream_body_kind(body_kind, &HttpVersion::Http10);
+    let response = rewriter.rewrite_response(&head, kind).unwrap();
+    assert_eq!(response.body_kind(), BodyKind::CloseDelimited);
+    assert_eq!(response.head().get_headers("Connection"), ["close"]);
+    assert!(response.head().get_header("Transfer-Encoding").is_none());
+}
+
+#[test]
+fn test_rewrite_response_close_delimited_and_empty_reason() {
+    let (head, body_kind) = decode_response(b"HTTP/1.0 200 \r\nContent-Type: text/plain\r\n\r\n");
+    assert_eq!(body_kind, BodyKind::CloseDelimited);
+
+    let kind = downstream_body_kind(body_kind, &HttpVersion::Http11);
+    assert_eq!(kind, BodyKind::Chunked);
+    let response = ProxyRewriter::new().rewrite_response(&head, kind).unwrap();
+    assert_eq!(
+        response.encode_head(),
+        b"HTTP/1.1 200 \r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_rewrite_response_without_body() {
+    // HEAD への応答は Content-Length を引き継ぐ
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_request_method("HEAD");
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nContent-Length: 1234\r\n\r\n")
+        .unwrap();
+    let (head, body_kind) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(body_kind, BodyKind::None);
+    let response = ProxyRewriter::new()
+        .rewrite_response(&head, body_kind)
+        .unwrap();
+    assert_eq!(response.head().get_headers("Content-Length"), ["1234"]);
+
+    let (head, body_kind) = decode_response(b"HTTP/1.1 204 No Content\r\nX-A: 1\r\n\r\n");
+    let response = ProxyRewriter::new()
+        .rewrite_response(&head, body_kind)
+        .unwrap();
+    assert_eq!(
+        response.encode_head(),
+        b"HTTP/1.1 204 No Content\r\nX-A: 1\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_downstream_body_kind() {
+    let cases = [
+        (
+            BodyKind::ContentLength(5),
+            HttpVersion::Http10,
+            BodyKind::ContentLength(5),
+        ),
+        (BodyKind::Chunked, HttpVersion::Http11, BodyKind::Chunked),
+        (
+            BodyKind::Chunked,
+            HttpVersion::Http10,
+            BodyKind::CloseDelimited,
+        ),
+        (
+            BodyKind::CloseDelimited,
+            HttpVersion::Http11,
+            BodyKind::Chunked,
+        ),
+        (
+            BodyKind::CloseDelimited,
+            HttpVersion::Http10,
+            BodyKind::CloseDelimited,
+        ),
+        (BodyKind::None, HttpVersion::Http10, BodyKind::None),
+        (BodyKind::Tunnel, HttpVersion::Http11, BodyKind::Tunnel),
+    ];
+    for (upstream, version, expected) in cases {
+        assert_eq!(downstream_body_kind(upstream, &version), expected);
+    }
+}
@@ -0,0 +1,237 @@
+//! Proxy-Status ヘッダーのユニットテスト
+
+use shiguredo_http11::proxy_status::{
+    ProxyErrorType, ProxyStatus, ProxyStatusEntry, ProxyStatusError,
+};
+use shiguredo_http11::sfv::BareItem;
+use shiguredo_http11::typed_header::TypedHeader;
+use shiguredo_http11::{Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// ProxyStatusError のテスト
+// ========================================
+
+#[test]
+fn test_proxy_status_error_display() {
+    let errors = [
+        (
+            ProxyStatusError::InvalidFormat,
+            "invalid Proxy-Status header format",
+        ),
+        (
+            ProxyStatusError::InvalidIdentifier,
+            "invalid Proxy-Status intermediary identifier",
+        ),
+        (
+            ProxyStatusError::InvalidParameter("error".to_string()),
+            "invalid Proxy-Status parameter: error",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// ProxyErrorType のテスト
+// ========================================
+
+#[test]
+fn test_proxy_error_type() {
+    let error = ProxyErrorType::from_name("dns_error").unwrap();
+    assert_eq!(error, ProxyErrorType::DnsError);
+    assert_eq!(error.as_str(), "dns_error");
+    assert_eq!(error.recommended_status(), Some(StatusCode::BAD_GATEWAY));
+
+    assert_eq!(
+        ProxyErrorType::HttpRequestDenied.recommended_status(),
+        Some(StatusCode::FORBIDDEN)
+    );
+    assert_eq!(
+        ProxyErrorType::ConnectionTimeout.recommended_status(),
+        Some(StatusCode::GATEWAY_TIMEOUT)
+    );
+    assert_eq!(ProxyErrorType::HttpUpgradeFailed.recommended_status(), None);
+
+    // レジストリにない種類
+    let error = ProxyErrorType::from_name("x_custom").unwrap();
+    assert_eq!(error, ProxyErrorType::Other("x_custom".to_string()));
+    assert_eq!(error.to_string(), "x_custom");
+    assert_eq!(error.recommended_status(), None);
+    assert!(ProxyErrorType::from_name("1bad").is_err());
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_proxy_status_parse() {
+    let status = ProxyStatus::parse(
+        "\"cdn.example\";received-status=503, \
+         gw;error=dns_error;rcode=\"NXDOMAIN\";next-hop=origin;details=\"lookup failed\"",
+    )
+    .unwrap();
+    assert_eq!(status.entries().len(), 2);
+
+    let cdn = status.find("cdn.example").unwrap();
+    assert_eq!(cdn.error(), None);
+    assert_eq!(cdn.received_status(), Some(503));
+
+    let gw = status.first_error().unwrap();
+    assert_eq!(gw.intermediary(), "gw");
+    assert_eq!(gw.error(), Some(&ProxyErrorType::DnsError));
+    assert_eq!(gw.next_hop(), Some("origin"));
+    assert_eq!(gw.details(), Some("lookup failed"));
+    assert_eq!(
+        gw.params().get("rcode"),
+        Some(&BareItem::String("NXDOMAIN".to_string()))
+    );
+}
+
+#[test]
+fn test_proxy_status_parse_next_protocol() {
+    let status = ProxyStatus::parse("proxy;next-protocol=:aDI=:").unwrap();
+    assert_eq!(status.entries()[0].next_protocol(), Some(&b"h2"[..]));
+}
+
+#[test]
+fn test_proxy_status_parse_invalid() {
+    let cases = [
+        ("a;error=", ProxyStatusError::InvalidFormat),
+        ("(a b)", ProxyStatusError::InvalidFormat),
+        ("1", ProxyStatusError::InvalidIdentifier),
+        ("\"\"", ProxyStatusError::InvalidIdentifier),
+        (
+            "a;error=\"dns_error\"",
+            ProxyStatusError::InvalidParameter("error".to_string()),
+        ),
+        (
+            "a;received-status=-1",
+            ProxyStatusError::InvalidParameter("received-status".to_string()),
+        ),
+        (
+            "a;details=token",
+            ProxyStatusError::InvalidParameter("details".to_string()),
+        ),
+        (
+            "a;next-protocol=\"h2\"",
+            ProxyStatusError::InvalidParameter("next-protocol".to_string()),
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(ProxyStatus::parse(input), Err(expected), "{input}");
+    }
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_proxy_status_entry_display() {
+    let entry = ProxyStatusEntry::new("proxy.example")
+        .unwrap()
+        .with_error(ProxyErrorType::TlsAlertReceived)
+        .with_param("alert-id", BareItem::Integer(42))
+        .unwrap()
+        .with_next_hop("backend")
+        .unwrap()
+        .with_next_protocol(b"http/1.1".to_vec())
+        .with_received_status(502)
+        .with_details("alert \"bad_certificate\"")
+        .unwrap();
+    assert_eq!(
+        entry.to_string(),
+        "proxy.example;error=tls_alert_received;next-hop=\"backend\";\
+         next-protocol=:aHR0cC8xLjE=:;received-status=502;\
+         details=\"alert \\\"bad_certificate\\\"\";alert-id=42"
+    );
+    assert_eq!(
+        ProxyStatus::parse(&entry.to_string()).unwrap().entries()[0],
+        entry
+    );
+
+    // sf-token として不正な識別子は sf-string として出力する
+    let entry = ProxyStatusEntry::new("my proxy").unwrap();
+    assert_eq!(entry.to_string(), "\"my proxy\"");
+}
+
+#[test]
+fn test_proxy_status_entry_invalid() {
+    assert_eq!(
+        ProxyStatusEntry::new(""),
+        Err(ProxyStatusError::InvalidIdentifier)
+    );
+    assert_eq!(
+        ProxyStatusEntry::new("caf\u{e9}"),
+        Err(ProxyStatusError::InvalidIdentifier)
+    );
+    let entry = ProxyStatusEntry::new("a").unwrap();
+    assert!(entry.clone().with_next_hop("\n").is_err());
+    assert!(entry.clone().with_details("\u{e9}").is_err());
+    // 型を定めたパラメーターは専用のメソッドで設定する
+    assert_eq!(
+        entry
+            .clone()
+            .with_param("error", BareItem::Token("x".to_string())),
+        Err(ProxyStatusError::InvalidParameter("error".to_string()))
+    );
+    assert!(entry.with_param("Bad", BareItem::Integer(1)).is_err());
+}
+
+#[test]
+fn test_proxy_status_typed_header() {
+    let status = ProxyStatus::new()
+        .with_entry(ProxyStatusEntry::new("a").unwrap())
+        .with_entry(
+            ProxyStatusEntry::new("b")
+                .unwrap()
+                .with_error(ProxyErrorType::ProxyLoopDetected),
+        );
+    assert_eq!(ProxyStatus::NAME, "Proxy-Status");
+    assert_eq!(status.encode_value(), "a, b;error=proxy_loop_detected");
+    assert_eq!(ProxyStatus::parse_value(&status.encode_value()), Ok(status));
+}
+
+// ========================================
+// レスポンスのテスト
+// ========================================
+
+#[test]
+fn test_response_proxy_error() {
+    let entry = ProxyStatusEntry::new("gw")
+        .unwrap()
+        .with_error(ProxyErrorType::DestinationUnavailable);
+    let response = Response::proxy_error(&entry).unwrap();
+    assert_eq!(response.status_code(), 503);
+    assert_eq!(
+        response.get_header("Proxy-Status"),
+        Some("gw;error=destination_unavailable")
+    );
+
+    // 推奨ステータスコードがない場合は 502
+    let entry = ProxyStatusEntry::new("gw").unwrap();
+    assert_eq!(Response::proxy_error(&entry).unwrap().status_code(), 502);
+}
+
+#[test]
+fn test_proxy_status_from_message() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 502 Bad Gateway\r\n\
+              Proxy-Status: origin-proxy;error=connection_refused\r\n\
+              Proxy-Status: edge;received-status=502\r\n\
+              Content-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let response = decoder.decode().unwrap().unwrap();
+    let status = ProxyStatus::from_message(&response).unwrap().unwrap();
+    assert_eq!(status.entries().len(), 2);
+    assert_eq!(status.first_error().unwrap().intermediary(), "origin-proxy");
+
+    let response = Response::with_status(StatusCode::OK);
+    assert_eq!(ProxyStatus::from_message(&response), Ok(None));
+}
@@ -0,0 +1,212 @@
+//! `range::byteranges` の multipart/byteranges のユニットテスト
+
+use shiguredo_http11::content_type::ContentType;
+use shiguredo_http11::multipart::{MultipartError, MultipartParser};
+use shiguredo_http11::range::{ByteRangesBuilder, ByteRangesParser, Range};
+
+#[test]
+fn test_byteranges_build() {
+    let body = ByteRangesBuilder::with_boundary("THIS_STRING_SEPARATES", 8000)
+        .with_content_type(ContentType::new("application", "pdf"))
+        .part(500, 503, b"abcd")
+        .part(7998, 7999, b"yz")
+        .build();
+    assert_eq!(
+        body,
+        b"--THIS_STRING_SEPARATES\r\n\
+          Content-Type: application/pdf\r\n\
+          Content-Range: bytes 500-503/8000\r\n\
+          \r\n\
+          abcd\r\n\
+          --THIS_STRING_SEPARATES\r\n\
+          Content-Type: application/pdf\r\n\
+          Content-Range: bytes 7998-7999/8000\r\n\
+          \r\n\
+          yz\r\n\
+          --THIS_STRING_SEPARATES--\r\n"
+    );
+}
+
+#[test]
+fn test_byteranges_content_type() {
+    let builder = ByteRangesBuilder::new(42, 100);
+    assert_eq!(builder.boundary(), "----RangeBoundary42");
+    assert_eq!(
+        builder.content_type(),
+        "multipart/byteranges; boundary=----RangeBoundary42"
+    );
+
+    let builder = ByteRangesBuilder::with_boundary("a b", 100);
+    assert_eq!(
+        builder.content_type(),
+        "multipart/byteranges; boundary=\"a b\""
+    );
+}
+
+#[test]
+fn test_byteranges_try_with_boundary() {
+    assert!(ByteRangesBuilder::try_with_boundary("valid", 10).is_ok());
+    assert_eq!(
+        ByteRangesBuilder::try_with_boundary("", 10).unwrap_err(),
+        MultipartError::InvalidBoundary
+    );
+    assert_eq!(
+        ByteRangesParser::try_new("bad\r\n").unwrap_err(),
+        MultipartError::InvalidBoundary
+    );
+}
+
+#[test]
+fn test_byteranges_parts_from_range() {
+    let content = b"0123456789";
+    let range = Range::parse("bytes=0-1, 8-, 20-30, -3").unwrap();
+    let builder = ByteRangesBuilder::with_boundary("b", content.len() as u64)
+        .parts_from_range(&range, content);
+    // 満たせない 20-30 は無視される
+    assert_eq!(builder.len(), 3);
+
+    let mut parser = ByteRangesParser::new("b");
+    parser.feed(&builder.build()).unwrap();
+    let mut parts = Vec::new();
+    while let Some(part) = parser.next_part().unwrap() {
+        parts.push(part);
+    }
+    assert!(parser.is_finished());
+    assert_eq!(parts.len(), 3);
+    assert_eq!((parts[0].start(), parts[0].end()), (0, 1));
+    assert_eq!(parts[0].body(), b"01");
+    assert_eq!((parts[1].start(), parts[1].end()), (8, 9));
+    assert_eq!(parts[1].body(), b"89");
+    assert_eq!((parts[2].start(), parts[2].end()), (7, 9));
+    assert_eq!(parts[2].body(), b"789");
+    assert_eq!(parts[2].content_range().complete_length(), Some(10));
+    assert!(parts[2].content_type().is_none());
+}
+
+#[test]
+fn test_byteranges_parts_from_range_non_bytes_unit() {
+    let range = Range::parse("items=0-1").unwrap();
+    let builder = ByteRangesBuilder::with_boundary("b", 10).parts_from_range(&range, b"0123456789");
+    assert!(builder.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "ByteRangesBuilder: data length must match the range length")]
+fn test_byteranges_part_length_mismatch() {
+    ByteRangesBuilder::with_boundary("b", 10).part(0, 3, b"abc");
+}
+
+#[test]
+fn test_byteranges_parse() {
+    let body = b"--SEP\r\n\
+        Content-Type: text/plain\r\n\
+        content-range: bytes 0-4/20\r\n\
+        \r\n\
+        hello\r\n\
+        --SEP\r\n\
+        Content-Range: bytes 15-19/*\r\n\
+        \r\n\
+        world\r\n\
+        --SEP--\r\n";
+    let mut parser = ByteRangesParser::new("SEP");
+    parser.feed(body).unwrap();
+
+    let part = parser.next_part().unwrap().unwrap();
+    assert_eq!(part.content_type().unwrap().mime_type(), "text/plain");
+    assert_eq!(part.content_range().to_string(), "bytes 0-4/20");
+    assert_eq!(part.body(), b"hello");
+
+    let part = parser.next_part().unwrap().unwrap();
+    assert!(part.content_type().is_none());
+    assert_eq!(part.content_range().complete_length(), None);
+    assert_eq!(part.body(), b"world");
+
+    assert!(parser.next_part().unwrap().is_none());
+    assert!(parser.is_finished());
+}
+
+#[test]
+fn test_byteranges_parse_incremental() {
+    let body = ByteRangesBuilder::with_boundary("SEP", 100)
+        .part(10, 14, b"abcde")
+        .build();
+    let mut parser = ByteRangesParser::new("SEP");
+    let mut parts = Vec::new();
+    for byte in &body {
+        parser.feed(core::slice::from_ref(byte)).unwrap();
+        match parser.next_part() {
+            Ok(Some(part)) => parts.push(part),
+            Ok(None) => {}
+            Err(MultipartError::Incomplete) => {}
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+    assert_eq!(parts.len(), 1);
+    assert_eq!(parts[0].body(), b"abcde");
+}
+
+#[test]
+fn test_byteranges_parse_missing_content_range() {
+    let body = b"--SEP\r\nContent-Type: text/plain\r\n\r\nhello\r\n--SEP--\r\n";
+    let mut parser = ByteRangesParser::new("SEP");
+    parser.feed(body).unwrap();
+    assert_eq!(
+        parser.next_part().unwrap_err(),
+        MultipartError::MissingContentRange
+    );
+}
+
+#[test]
+fn test_byteranges_parse_invalid_content_range() {
+    for content_range in ["bytes */20", "bytes 0-9/20", "bytes 5-1/20", "invalid"] {
+        let body = format!(
+            "--SEP\r\nContent-Range: {}\r\n\r\nhello\r\n--SEP--\r\n",
+            content_range
+        );
+        let mut parser = ByteRangesParser::new("SEP");
+        parser.feed(body.as_bytes()).unwrap();
+        assert_eq!(
+            parser.next_part().unwrap_err(),
+            MultipartError::InvalidContentRange,
+            "{}",
+            content_range
+        );
+    }
+}
+
+#[test]
+fn test_byteranges_parse_does_not_require_content_disposition() {
+    // multipart/form-data のパーサーでは Content-Disposition が必須
+    let body = b"--SEP\r\nContent-Range: bytes 0-4/5\r\n\r\nhello\r\n--SEP--\r\n";
+    let mut parser = MultipartParser::new("SEP");
+    parser.feed(body).unwrap();
+    assert_eq!(
+        parser.next_part().unwrap_err(),
+        MultipartError::MissingContentDisposition
+    );
+
+    let mut parser = ByteRangesParser::new("SEP");
+    parser.feed(body).unwrap();
+    assert_eq!(parser.next_part().unwrap().unwrap().body(), b"hello");
+}
+
+#[test]
+fn test_byteranges_parse_buffer_limit() {
+    let mut parser = ByteRangesParser::new("SEP").with_max_buffer_size(4);
+    assert_eq!(
+        parser.feed(b"--SEP\r\n").unwrap_err(),
+        MultipartError::BufferOverflow { size: 7, limit: 4 }
+    );
+}
+
+#[test]
+fn test_byteranges_error_display() {
+    assert_eq!(
+        MultipartError::MissingContentRange.to_string(),
+        "missing Content-Range header in multipart/byteranges part (RFC 9110 Section 14.6)"
+    );
+    assert_eq!(
+        MultipartError::InvalidContentRange.to_string(),
+        "invalid Content-Range in multipart/byteranges part"
+    );
+}
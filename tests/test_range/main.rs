@@ -0,0 +1,681 @@
+//! Range のユニットテスト
+//!
+//! Range / Content-Range / Accept-Ranges のテストはここに置き、
+//! サブモジュールのテストは `src/range/` のモジュールごとに分割する。
+//! - `byteranges`: multipart/byteranges の生成とパース
+
+mod byteranges;
+
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::range::{
+    AcceptRanges, ContentRange, Range, RangeError, RangeSatisfaction, RangeSpec, coalesce_ranges,
+    if_range_allows,
+};
+use shiguredo_http11::static_content::StaticResource;
+use shiguredo_http11::{RequestDecoder, RequestHead, Response, StatusCode};
+
+// ========================================
+// RangeError のテスト
+// ========================================
+
+#[test]
+fn test_range_error_display() {
+    let errors = [
+        (RangeError::Empty, "empty range header"),
+        (RangeError::InvalidFormat, "invalid range header format"),
+        (RangeError::InvalidUnit, "invalid range unit"),
+        (RangeError::InvalidRange, "invalid range specification"),
+        (RangeError::InvalidBounds, "invalid range bounds"),
+        (
+            RangeError::LengthMismatch {
+                expected: 100,
+                actual: 99,
+            },
+            "content range length mismatch: expected 100, actual 99",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// RangeSpec のテスト
+// ========================================
+
+// RangeSpec::Suffix length=0 のケース
+#[test]
+fn test_range_spec_suffix_zero_length() {
+    let spec = RangeSpec::Suffix { length: 0 };
+    assert!(spec.to_bounds(1000).is_none());
+}
+
+// ========================================
+// AcceptRanges のテスト
+// ========================================
+
+// AcceptRanges::bytes のテスト
+#[test]
+fn test_accept_ranges_bytes() {
+    let ar = AcceptRanges::bytes();
+    assert!(ar.accepts_bytes());
+    assert!(!ar.is_none());
+    assert_eq!(ar.units().len(), 1);
+    assert_eq!(ar.units()[0], "bytes");
+}
+
+// AcceptRanges::none のテスト
+#[test]
+fn test_accept_ranges_none() {
+    let ar = AcceptRanges::none();
+    assert!(!ar.accepts_bytes());
+    assert!(ar.is_none());
+    assert_eq!(ar.units().len(), 1);
+    assert_eq!(ar.units()[0], "none");
+}
+
+// AcceptRanges ラウンドトリップ
+#[test]
+fn test_accept_ranges_bytes_roundtrip() {
+    let ar = AcceptRanges::bytes();
+    let displayed = ar.to_string();
+    let reparsed = AcceptRanges::parse(&displayed).unwrap();
+    assert!(reparsed.accepts_bytes());
+}
+
+// AcceptRanges Display
+#[test]
+fn test_accept_ranges_display() {
+    let ar = AcceptRanges::bytes();
+    assert_eq!(ar.to_string(), "bytes");
+
+    let ar2 = AcceptRanges::none();
+    assert_eq!(ar2.to_string(), "none");
+}
+
+// AcceptRanges 複数単位
+#[test]
+fn test_accept_ranges_multiple_units() {
+    let ar = AcceptRanges::parse("bytes, custom").unwrap();
+    assert_eq!(ar.units().len(), 2);
+    assert!(ar.accepts_bytes());
+}
+
+// AcceptRanges の範囲単位の判定
+#[test]
+fn test_accept_ranges_accepts() {
+    let ar = AcceptRanges::parse("Bytes, items").unwrap();
+    assert!(ar.accepts("bytes"));
+    assert!(ar.accepts("ITEMS"));
+    assert!(!ar.accepts("pages"));
+
+    // none は範囲単位ではない
+    let ar = AcceptRanges::none();
+    assert!(!ar.accepts("none"));
+    assert!(!ar.accepts("bytes"));
+}
+
+// AcceptRanges をレスポンスから取得する
+#[test]
+fn test_accept_ranges_from_message() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Accept-Ranges", "bytes")
+        .unwrap()
+        .header("Accept-Ranges", "items")
+        .unwrap();
+    let ar = AcceptRanges::from_message(&response).unwrap().unwrap();
+    assert_eq!(ar.units(), &["bytes".to_string(), "items".to_string()]);
+
+    // ヘッダーがない場合は None
+    let response = Response::with_status(StatusCode::OK);
+    assert_eq!(AcceptRanges::from_message(&response), Ok(None));
+
+    // none と他の単位は混在できない
+    let response = Response::with_status(StatusCode::OK)
+        .header("Accept-Ranges", "none")
+        .unwrap()
+        .header("Accept-Ranges", "bytes")
+        .unwrap();
+    assert_eq!(
+        AcceptRanges::from_message(&response),
+        Err(RangeError::InvalidUnit)
+    );
+}
+
+// Accept-Ranges をレスポンスに設定する
+#[test]
+fn test_response_accept_ranges() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Accept-Ranges", "items")
+        .unwrap()
+        .accept_ranges(&AcceptRanges::bytes());
+    // 既存の値は置き換える
+    assert_eq!(response.get_headers("Accept-Ranges"), vec!["bytes"]);
+
+    // 静的コンテンツの計画と同じ値を付与する
+    let resource = StaticResource::new(10);
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let plan = resource.plan(&head, 0);
+    assert_eq!(
+        plan.get_header("Accept-Ranges"),
+        response.get_header("Accept-Ranges")
+    );
+}
+
+// ========================================
+// エラーケースのテスト
+// ========================================
+
+#[test]
+fn test_range_parse_errors() {
+    // 空
+    assert!(matches!(Range::parse(""), Err(RangeError::Empty)));
+
+    // = がない
+    assert!(matches!(
+        Range::parse("bytes0-499"),
+        Err(RangeError::InvalidFormat)
+    ));
+
+    // 単位が空
+    assert!(matches!(
+        Range::parse("=0-499"),
+        Err(RangeError::InvalidUnit)
+    ));
+
+    // 範囲指定が空
+    assert!(matches!(Range::parse("bytes="), Err(RangeError::Empty)));
+
+    // 不正な範囲
+    assert!(matches!(
+        Range::parse("bytes=-"),
+        Err(RangeError::InvalidRange)
+    ));
+
+    // start > end
+    assert!(matches!(
+        Range::parse("bytes=500-100"),
+        Err(RangeError::InvalidBounds)
+    ));
+
+    // 数値でない
+    assert!(matches!(
+        Range::parse("bytes=abc-def"),
+        Err(RangeError::InvalidRange)
+    ));
+}
+
+#[test]
+fn test_content_range_parse_errors() {
+    // 空
+    assert!(matches!(ContentRange::parse(""), Err(RangeError::Empty)));
+
+    // スペースがない
+    assert!(matches!(
+        ContentRange::parse("bytes0-499/1000"),
+        Err(RangeError::InvalidFormat)
+    ));
+
+    // / がない
+    assert!(matches!(
+        ContentRange::parse("bytes 0-499"),
+        Err(RangeError::InvalidFormat)
+    ));
+
+    // start > end
+    assert!(matches!(
+        ContentRange::parse("bytes 500-100/1000"),
+        Err(RangeError::InvalidBounds)
+    ));
+
+    // 不正な数値
+    assert!(matches!(
+        ContentRange::parse("bytes abc-def/1000"),
+        Err(RangeError::InvalidFormat)
+    ));
+
+    // 不正な長さ
+    assert!(matches!(
+        ContentRange::parse("bytes 0-499/abc"),
+        Err(RangeError::InvalidFormat)
+    ));
+
+    // complete_length <= end (RFC 9110 Section 14.4)
+    assert!(matches!(
+        ContentRange::parse("bytes 0-100/50"),
+        Err(RangeError::InvalidBounds)
+    ));
+    assert!(matches!(
+        ContentRange::parse("bytes 0-0/0"),
+        Err(RangeError::InvalidBounds)
+    ));
+}
+
+#[test]
+fn test_accept_ranges_parse_errors() {
+    // 空
+    assert!(matches!(AcceptRanges::parse(""), Err(RangeError::Empty)));
+
+    // 空白のみ
+    assert!(matches!(AcceptRanges::parse("   "), Err(RangeError::Empty)));
+
+    // カンマのみ
+    assert!(matches!(AcceptRanges::parse(",,,"), Err(RangeError::Empty)));
+}
+
+// ========================================
+// Accept-Ranges の none 混在拒否 (RFC 9110 Section 14.3)
+// ========================================
+
+#[test]
+fn test_accept_ranges_none_mixed_with_other_units_error() {
+    // none は他の単位と混在できない
+    assert!(matches!(
+        AcceptRanges::parse("bytes, none"),
+        Err(RangeError::InvalidUnit)
+    ));
+    assert!(matches!(
+        AcceptRanges::parse("none, bytes"),
+        Err(RangeError::InvalidUnit)
+    ));
+}
+
+#[test]
+fn test_accept_ranges_none_alone_ok() {
+    // none 単独は正常
+    let ar = AcceptRanges::parse("none").unwrap();
+    assert!(ar.is_none());
+}
+
+#[test]
+fn test_accept_ranges_multiple_units_without_none_ok() {
+    // none を含まない複数単位は正常
+    let ar = AcceptRanges::parse("bytes, items").unwrap();
+    assert!(!ar.is_none());
+    assert!(ar.accepts_bytes());
+}
+
+// ========================================
+// ContentRange::length() 境界値テスト
+// ========================================
+
+#[test]
+fn test_content_range_length_overflow_returns_none() {
+    // (s=0, e=u64::MAX) → None (唯一の overflow ケース)
+    let cr = ContentRange::new_bytes(0, u64::MAX, None);
+    assert_eq!(cr.length(), None);
+}
+
+#[test]
+fn test_content_range_length_max_some() {
+    // (s=1, e=u64::MAX) → Some(u64::MAX)
+    let cr = ContentRange::new_bytes(1, u64::MAX, None);
+    assert_eq!(cr.length(), Some(u64::MAX));
+}
+
+#[test]
+fn test_content_range_length_max_result() {
+    // (s=0, e=u64::MAX-1) → Some(u64::MAX)
+    let cr = ContentRange::new_bytes(0, u64::MAX - 1, None);
+    assert_eq!(cr.length(), Some(u64::MAX));
+}
+
+#[test]
+fn test_content_range_length_single_byte() {
+    // (s=0, e=0) → Some(1)
+    let cr = ContentRange::new_bytes(0, 0, Some(1));
+    assert_eq!(cr.length(), Some(1));
+}
+
+#[test]
+fn test_content_range_length_max_single_byte() {
+    // (s=u64::MAX, e=u64::MAX) → Some(1)
+    let cr = ContentRange::new_bytes(u64::MAX, u64::MAX, None);
+    assert_eq!(cr.length(), Some(1));
+}
+
+#[test]
+fn test_content_range_length_max_two_bytes() {
+    // (s=u64::MAX-1, e=u64::MAX) → Some(2)
+    let cr = ContentRange::new_bytes(u64::MAX - 1, u64::MAX, None);
+    assert_eq!(cr.length(), Some(2));
+}
+
+// ========================================
+// 部分アップロードの Content-Range のテスト
+// ========================================
+
+fn upload_request(content_range: &[&str]) -> RequestHead {
+    let mut request = b"PUT /upload HTTP/1.1\r\nHost: example.com\r\n".to_vec();
+    for value in content_range {
+        request.extend_from_slice(format!("Content-Range: {value}\r\n").as_bytes());
+    }
+    request.extend_from_slice(b"\r\n");
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(&request).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+#[test]
+fn test_content_range_from_request() {
+    let head = upload_request(&["bytes 100-199/1000"]);
+    let cr = ContentRange::from_message(&head).unwrap().unwrap();
+    assert_eq!(cr.start(), Some(100));
+    assert_eq!(cr.end(), Some(199));
+    assert_eq!(cr.complete_length(), Some(1000));
+
+    // 完全な長さが不明
+    let head = upload_request(&["bytes 0-99/*"]);
+    let cr = ContentRange::from_message(&head).unwrap().unwrap();
+    assert_eq!(cr.complete_length(), None);
+
+    // 完全な長さのみ
+    let head = upload_request(&["bytes */1000"]);
+    let cr = ContentRange::from_message(&head).unwrap().unwrap();
+    assert!(cr.is_unsatisfied());
+    assert_eq!(cr.complete_length(), Some(1000));
+
+    assert_eq!(ContentRange::from_message(&upload_request(&[])), Ok(None));
+}
+
+#[test]
+fn test_content_range_from_request_invalid() {
+    // 終了位置 < 開始位置
+    assert_eq!(
+        ContentRange::from_message(&upload_request(&["bytes 200-100/1000"])),
+        Err(RangeError::InvalidBounds)
+    );
+    // 終了位置が完全な長さの範囲外
+    assert_eq!(
+        ContentRange::from_message(&upload_request(&["bytes 0-1000/1000"])),
+        Err(RangeError::InvalidBounds)
+    );
+    // 複数行
+    assert_eq!(
+        ContentRange::from_message(&upload_request(&["bytes 0-9/10", "bytes 0-9/10"])),
+        Err(RangeError::InvalidFormat)
+    );
+}
+
+#[test]
+fn test_content_range_validate_upload() {
+    let cr = ContentRange::parse("bytes 100-199/1000").unwrap();
+    assert_eq!(cr.validate_upload(100), Ok(()));
+    assert_eq!(
+        cr.validate_upload(101),
+        Err(RangeError::LengthMismatch {
+            expected: 100,
+            actual: 101,
+        })
+    );
+
+    let cr = ContentRange::parse("bytes 0-0/*").unwrap();
+    assert_eq!(cr.validate_upload(1), Ok(()));
+
+    // 完全な長さのみを伝える場合はボディが空
+    let cr = ContentRange::parse("bytes */1000").unwrap();
+    assert_eq!(cr.validate_upload(0), Ok(()));
+    assert_eq!(
+        cr.validate_upload(1),
+        Err(RangeError::LengthMismatch {
+            expected: 0,
+            actual: 1,
+        })
+    );
+
+    // bytes 以外の範囲単位
+    let cr = ContentRange::parse("items 0-9/10").unwrap();
+    assert_eq!(cr.validate_upload(10), Err(RangeError::InvalidUnit));
+
+    // 範囲の長さが u64 に収まらない
+    let cr = ContentRange::new_bytes(0, u64::MAX, None);
+    assert_eq!(cr.validate_upload(u64::MAX), Err(RangeError::InvalidBounds));
+}
+
+// ========================================
+// ContentRange::new_bytes() バリデーションテスト
+// ========================================
+
+#[test]
+#[should_panic(expected = "ContentRange: start must be <= end")]
+fn test_new_bytes_start_greater_than_end() {
+    ContentRange::new_bytes(10, 5, None);
+}
+
+#[test]
+#[should_panic(expected = "ContentRange: complete_length must be > last-pos")]
+fn test_new_bytes_complete_length_less_than_end() {
+    ContentRange::new_bytes(0, 100, Some(50));
+}
+
+#[test]
+#[should_panic(expected = "ContentRange: complete_length must be > last-pos")]
+fn test_new_bytes_complete_length_equal_to_end() {
+    ContentRange::new_bytes(u64::MAX, u64::MAX, Some(u64::MAX));
+}
+
+#[test]
+#[should_panic(expected = "ContentRange: complete_length must be > last-pos")]
+fn test_new_bytes_complete_length_zero_with_max_end() {
+    ContentRange::new_bytes(0, u64::MAX, Some(0));
+}
+
+// ========================================
+// ContentRange::new_bytes() 正常系テスト
+// ========================================
+
+#[test]
+fn test_new_bytes_max_range_none_length() {
+    // new_bytes(0, u64::MAX, None) は正常終了する
+    let cr = ContentRange::new_bytes(0, u64::MAX, None);
+    assert_eq!(cr.length(), None);
+    assert_eq!(cr.to_string(), "bytes 0-18446744073709551615/*");
+}
+
+// ========================================
+// Range の評価のテスト
+// ========================================
+
+#[test]
+fn test_satisfiable_bounds_clamps_and_resolves_suffix() {
+    let range = Range::parse("bytes=900-2000, -100, 5000-, 0-0").unwrap();
+    assert_eq!(
+        range.satisfiable_bounds(1000),
+        vec![(900, 999), (900, 999), (0, 0)]
+    );
+
+    let range = Range::parse("bytes=-5000").unwrap();
+    assert_eq!(range.satisfiable_bounds(1000), vec![(0, 999)]);
+
+    let range = Range::parse("items=0-1").unwrap();
+    assert!(range.satisfiable_bounds(1000).is_empty());
+}
+
+#[test]
+fn test_evaluate_single_range() {
+    let satisfaction = Range::parse("bytes=0-499").unwrap().evaluate(1000);
+    assert!(satisfaction.is_satisfiable());
+    assert!(!satisfaction.is_unsatisfiable());
+    assert_eq!(satisfaction.ranges(), &[(0, 499)]);
+    assert_eq!(
+        satisfaction.content_range().unwrap().to_string(),
+        "bytes 0-499/1000"
+    );
+}
+
+#[test]
+fn test_evaluate_coalesces_ranges() {
+    let satisfaction = Range::parse("bytes=500-599, 0-99, 100-199, 550-650")
+        .unwrap()
+        .evaluate(1000);
+    assert_eq!(
+        satisfaction,
+        RangeSatisfaction::Satisfiable {
+            ranges: vec![(0, 199), (500, 650)],
+            complete_length: 1000,
+        }
+    );
+    // 複数範囲は multipart/byteranges の各パートに Content-Range を付与する
+    assert!(satisfaction.content_range().is_none());
+    let content_ranges: Vec<String> = satisfaction
+        .content_ranges()
+        .iter()
+        .map(|cr| cr.to_string())
+        .collect();
+    assert_eq!(content_ranges, ["bytes 0-199/1000", "bytes 500-650/1000"]);
+}
+
+#[test]
+fn test_evaluate_unsatisfiable() {
+    let satisfaction = Range::parse("bytes=1000-, 2000-3000")
+        .unwrap()
+        .evaluate(1000);
+    assert_eq!(
+        satisfaction,
+        RangeSatisfaction::Unsatisfiable {
+            complete_length: 1000
+        }
+    );
+    assert!(satisfaction.ranges().is_empty());
+    assert_eq!(
+        satisfaction.content_range().unwrap().to_string(),
+        "bytes */1000"
+    );
+    assert_eq!(satisfaction.content_ranges().len(), 1);
+
+    // suffix-length が 0 の suffix-range は満たせない
+    assert!(
+        Range::parse("bytes=-0")
+            .unwrap()
+            .evaluate(1000)
+            .is_unsatisfiable()
+    );
+    // 長さ 0 の表現に対する int-range は満たせない
+    assert!(
+        Range::parse("bytes=0-")
+            .unwrap()
+            .evaluate(0)
+            .is_unsatisfiable()
+    );
+}
+
+#[test]
+fn test_evaluate_ignored() {
+    // bytes 以外の範囲単位
+    let satisfaction = Range::parse("items=0-1").unwrap().evaluate(1000);
+    assert_eq!(satisfaction, RangeSatisfaction::Ignored);
+    assert!(satisfaction.content_range().is_none());
+    assert!(satisfaction.content_ranges().is_empty());
+
+    // 長さ 0 の表現に対する suffix-range
+    assert_eq!(
+        Range::parse("bytes=-100").unwrap().evaluate(0),
+        RangeSatisfaction::Ignored
+    );
+}
+
+#[test]
+fn test_range_not_satisfiable_response() {
+    let response = Response::range_not_satisfiable(1000);
+    assert_eq!(response.status_code(), 416);
+    assert_eq!(response.get_header("Content-Range"), Some("bytes */1000"));
+    assert_eq!(
+        response.encode().unwrap(),
+        b"HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */1000\r\nContent-Length: 0\r\n\r\n"
+    );
+    let content_range = ContentRange::parse(response.get_header("Content-Range").unwrap()).unwrap();
+    assert!(content_range.is_unsatisfied());
+    assert_eq!(content_range.complete_length(), Some(1000));
+
+    // 評価結果から作成する
+    let satisfaction = Range::parse("bytes=1000-").unwrap().evaluate(1000);
+    assert_eq!(satisfaction.to_unsatisfiable_response(), Some(response));
+    let satisfaction = Range::parse("bytes=0-").unwrap().evaluate(1000);
+    assert_eq!(satisfaction.to_unsatisfiable_response(), None);
+    assert_eq!(RangeSatisfaction::Ignored.to_unsatisfiable_response(), None);
+}
+
+#[test]
+fn test_coalesce_ranges() {
+    assert_eq!(coalesce_ranges(&[]), vec![]);
+    assert_eq!(coalesce_ranges(&[(5, 9), (0, 4)]), vec![(0, 9)]);
+    assert_eq!(coalesce_ranges(&[(0, 4), (6, 9)]), vec![(0, 4), (6, 9)]);
+    assert_eq!(coalesce_ranges(&[(0, 9), (2, 3)]), vec![(0, 9)]);
+    assert_eq!(coalesce_ranges(&[(3, 1), (0, 0)]), vec![(0, 0)]);
+    assert_eq!(
+        coalesce_ranges(&[(0, u64::MAX), (u64::MAX, u64::MAX)]),
+        vec![(0, u64::MAX)]
+    );
+}
+
+// ========================================
+// if_range_allows のテスト
+// ========================================
+
+#[test]
+fn test_if_range_allows_etag() {
+    let strong = EntityTag::strong("abc").unwrap();
+    let weak = EntityTag::weak("abc").unwrap();
+
+    assert!(if_range_allows("\"abc\"", Some(&strong), None));
+    assert!(!if_range_allows("\"xyz\"", Some(&strong), None));
+    // Weak ETag は Strong 比較で一致しない
+    assert!(!if_range_allows("W/\"abc\"", Some(&strong), None));
+    assert!(!if_range_allows("\"abc\"", Some(&weak), None));
+    // 現在の表現に ETag がない
+    assert!(!if_range_allows("\"abc\"", None, None));
+}
+
+#[test]
+fn test_if_range_allows_date() {
+    let modified = HttpDate::parse("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+    assert!(if_range_allows(
+        "Wed, 21 Oct 2015 07:28:00 GMT",
+        None,
+        Some(&modified)
+    ));
+    // RFC 850 形式の 2 桁年は Last-Modified の年を基準に解決する
+    assert!(if_range_allows(
+        "Wednesday, 21-Oct-15 07:28:00 GMT",
+        None,
+        Some(&modified)
+    ));
+    // 完全一致のみ
+    assert!(!if_range_allows(
+        "Wed, 21 Oct 2015 07:28:01 GMT",
+        None,
+        Some(&modified)
+    ));
+    assert!(!if_range_allows(
+        "Wed, 21 Oct 2015 07:27:59 GMT",
+        None,
+        Some(&modified)
+    ));
+    // Last-Modified がない
+    assert!(!if_range_allows(
+        "Wed, 21 Oct 2015 07:28:00 GMT",
+        None,
+        None
+    ));
+}
+
+#[test]
+fn test_if_range_allows_invalid_value() {
+    let etag = EntityTag::strong("abc").unwrap();
+    let modified = HttpDate::parse("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+    for value in ["", "abc", "w/\"abc\"", "\"abc", "2015-10-21"] {
+        assert!(
+            !if_range_allows(value, Some(&etag), Some(&modified)),
+            "{}",
+            value
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! RateLimit ヘッダーのユニットテスト
+
+use shiguredo_http11::rate_limit::{
+    LegacyRateLimit, QuotaPolicy, QuotaUnit, RateLimit, RateLimitBuilder, RateLimitError,
+    RateLimitPolicy, ServiceLimit, apply_rate_limit,
+};
+use shiguredo_http11::retry_after::RetryAfter;
+use shiguredo_http11::{Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// RateLimitError のテスト
+// ========================================
+
+#[test]
+fn test_rate_limit_error_display() {
+    let errors = [
+        (
+            RateLimitError::InvalidFormat,
+            "invalid RateLimit header format",
+        ),
+        (RateLimitError::InvalidName, "invalid RateLimit policy name"),
+        (
+            RateLimitError::MissingQuota,
+            "missing quota in RateLimit-Policy",
+        ),
+        (
+            RateLimitError::MissingRemaining,
+            "missing remaining in RateLimit",
+        ),
+        (
+            RateLimitError::InvalidParameter("q".to_string()),
+            "invalid RateLimit parameter: q",
+        ),
+        (
+            RateLimitError::InvalidLegacyHeader("X-RateLimit-Limit"),
+            "invalid X-RateLimit-Limit header",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// RateLimit-Policy のテスト
+// ========================================
+
+#[test]
+fn test_rate_limit_policy_parse() {
+    let policy = RateLimitPolicy::parse(
+        "\"burst\";q=100;w=60,\"daily\";q=1000;w=86400, \"bytes\";q=65536;qu=\"content-bytes\";pk=:cHJvamVjdC0x:",
+    )
+    .unwrap();
+    assert_eq!(policy.policies().len(), 3);
+
+    let burst = policy.find("burst").unwrap();
+    assert_eq!(burst.quota(), 100);
+    assert_eq!(burst.window(), Some(60));
+    assert_eq!(burst.unit(), &QuotaUnit::Requests);
+    assert_eq!(burst.partition_key(), None);
+
+    let bytes = policy.find("bytes").unwrap();
+    assert_eq!(bytes.unit(), &QuotaUnit::ContentBytes);
+    assert_eq!(bytes.window(), None);
+    assert_eq!(bytes.partition_key(), Some(&b"project-1"[..]));
+
+    assert!(policy.find("hourly").is_none());
+}
+
+#[test]
+fn test_rate_limit_policy_parse_ignores_unknown_parameters() {
+    let policy = RateLimitPolicy::parse("\"a\";q=10;x=?1;y=1.5;z=token;qu=\"tokens\"").unwrap();
+    let a = &policy.policies()[0];
+    assert_eq!(a.quota(), 10);
+    assert_eq!(a.unit(), &QuotaUnit::Other("tokens".to_string()));
+
+    // 同じパラメーターは最後の値を採用する
+    let policy = RateLimitPolicy::parse("\"a\";q=10;q=20").unwrap();
+    assert_eq!(policy.policies()[0].quota(), 20);
+
+    assert!(RateLimitPolicy::parse("").unwrap().policies().is_empty());
+}
+
+#[test]
+fn test_rate_limit_policy_parse_errors() {
+    let cases = [
+        ("\"a\"", RateLimitError::MissingQuota),
+        ("\"a\";w=10", RateLimitError::MissingQuota),
+        ("a;q=10", RateLimitError::InvalidName),
+        ("10;q=10", RateLimitError::InvalidName),
+        (
+            "\"a\";q=-1",
+            RateLimitError::InvalidParameter("q".to_string()),
+        ),
+        (
+            "\"a\";q=1.5",
+            RateLimitError::InvalidParameter("q".to_string()),
+        ),
+        ("\"a\";q", RateLimitError::InvalidParameter("q".to_string())),
+        (
+            "\"a\";q=1;qu=requests",
+            RateLimitError::InvalidParameter("qu".to_string()),
+        ),
+        (
+            "\"a\";q=1;pk=\"key\"",
+            RateLimitError::InvalidParameter("pk".to_string()),
+        ),
+        ("\"a\";q=1;pk=:!:", RateLimitError::InvalidFormat),
+        ("\"a\";q=1000000000000000", RateLimitError::InvalidFormat),
+        ("\"a\";q=1,", RateLimitError::InvalidFormat),
+        ("\"a\";q=1 x", RateLimitError::InvalidFormat),
+        ("\"a;q=1", RateLimitError::InvalidFormat),
+        ("\"a\";Q=1", RateLimitError::InvalidFormat),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(RateLimitPolicy::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_quota_policy_display() {
+    let policy = QuotaPolicy::new("per \"user\"", 5000)
+        .unwrap()
+        .with_unit(QuotaUnit::ConcurrentRequests)
+        .unwrap()
+        .with_window(3600)
+        .with_partition_key(*b"user-1");
+    assert_eq!(
+        policy.to_string(),
+        "\"per \\\"user\\\"\";q=5000;qu=\"concurrent-requests\";w=3600;pk=:dXNlci0x:"
+    );
+    let header = RateLimitPolicy::new()
+        .with_policy(policy.clone())
+        .with_policy(QuotaPolicy::new("default", 10).unwrap());
+    assert_eq!(header.to_string(), format!("{}, \"default\";q=10", policy));
+    assert_eq!(RateLimitPolicy::parse(&header.to_string()), Ok(header));
+
+    // sf-integer の上限を超える値は丸める
+    let policy = QuotaPolicy::new("huge", u64::MAX).unwrap();
+    assert_eq!(policy.to_string(), "\"huge\";q=999999999999999");
+}
+
+#[test]
+fn test_quota_policy_builder_errors() {
+    assert_eq!(
+        QuotaPolicy::new("日本", 1),
+        Err(RateLimitError::InvalidName)
+    );
+    assert_eq!(
+        QuotaPolicy::new("a\tb", 1),
+        Err(RateLimitError::InvalidName)
+    );
+    assert_eq!(
+        QuotaPolicy::new("a", 1)
+            .unwrap()
+            .with_unit(QuotaUnit::Other("a\nb".to_string())),
+        Err(RateLimitError::InvalidParameter("qu".to_string()))
+    );
+    assert_eq!(
+        ServiceLimit::new("\u{7f}", 1),
+        Err(RateLimitError::InvalidName)
+    );
+}
+
+// ========================================
+// RateLimit のテスト
+// ========================================
+
+#[test]
+fn test_rate_limit_parse() {
+    let limit = RateLimit::parse("\"burst\";r=0;t=30, \"daily\";r=750;t=50000;pk=:YQ==:").unwrap();
+    assert_eq!(limit.limits().len(), 2);
+    let burst = limit.find("burst").unwrap();
+    assert_eq!(burst.remaining(), 0);
+    assert_eq!(burst.reset(), Some(30));
+    let daily = limit.find("daily").unwrap();
+    assert_eq!(daily.partition_key(), Some(&b"a"[..]));
+    assert!(limit.is_exhausted());
+
+    let limit = RateLimit::parse("\"default\";r=5").unwrap();
+    assert_eq!(limit.limits()[0].reset(), None);
+    assert!(!limit.is_exhausted());
+
+    assert_eq!(
+        RateLimit::parse("\"default\";t=5"),
+        Err(RateLimitError::MissingRemaining)
+    );
+    assert_eq!(
+        RateLimit::parse("\"default\";r=5;t=-5"),
+        Err(RateLimitError::InvalidParameter("t".to_string()))
+    );
+}
+
+#[test]
+fn test_rate_limit_retry_after() {
+    let limit = RateLimit::parse("\"a\";r=0;t=30, \"b\";r=0;t=90, \"c\";r=5;t=600").unwrap();
+    assert_eq!(limit.retry_after(), Some(RetryAfter::Delay(90)));
+
+    // 残りクォータがある場合は None
+    let limit = RateLimit::parse("\"a\";r=1;t=30").unwrap();
+    assert_eq!(limit.retry_after(), None);
+
+    // リセットまでの秒数がわからない場合は None
+    let limit = RateLimit::parse("\"a\";r=0;t=30, \"b\";r=0").unwrap();
+    assert_eq!(limit.retry_after(), None);
+}
+
+#[test]
+fn test_rate_limit_from_message_joins_lines() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nRateLimit-Policy: \"a\";q=10;w=1\r\n\
+              RateLimit-Policy: \"b\";q=100;w=60\r\nRateLimit: \"a\";r=9;t=1\r\n\
+              RateLimit: \"b\";r=99;t=60\r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let policy = RateLimitPolicy::from_message(&head).unwrap().unwrap();
+    assert_eq!(policy.policies().len(), 2);
+    let limit = RateLimit::from_message(&head).unwrap().unwrap();
+    assert_eq!(limit.find("b").unwrap().remaining(), 99);
+
+    let response = Response::with_status(StatusCode::OK);
+    assert_eq!(RateLimit::from_message(&response), Ok(None));
+    assert_eq!(RateLimitPolicy::from_message(&response), Ok(None));
+}
+
+// ========================================
+// X-RateLimit-* のテスト
+// ========================================
+
+#[test]
+fn test_legacy_rate_limit() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("X-RateLimit-Limit", "60")
+        .unwrap()
+        .header("X-RateLimit-Remaining", " 56 ")
+        .unwrap()
+        .header("X-RateLimit-Reset", "1372700873")
+        .unwrap();
+    let legacy = LegacyRateLimit::from_message(&response).unwrap().unwrap();
+    assert_eq!(legacy.limit(), Some(60));
+    assert_eq!(legacy.remaining(), Some(56));
+    assert_eq!(legacy.reset(), Some(1372700873));
+
+    let response = Response::with_status(StatusCode::OK);
+    assert_eq!(LegacyRateLimit::from_message(&response), Ok(None));
+
+    let response = Response::with_status(StatusCode::OK)
+        .header("X-RateLimit-Remaining", "-1")
+        .unwrap();
+    assert_eq!(
+        LegacyRateLimit::from_message(&response),
+        Err(RateLimitError::InvalidLegacyHeader("X-RateLimit-Remaining"))
+    );
+
+    let response = Response::with_status(StatusCode::OK)
+        .header("X-RateLimit-Limit", "1")
+        .unwrap()
+        .header("X-RateLimit-Limit", "2")
+        .unwrap();
+    assert_eq!(
+        LegacyRateLimit::from_message(&response),
+        Err(RateLimitError::InvalidLegacyHeader("X-RateLimit-Limit"))
+    );
+
+    let legacy = LegacyRateLimit::new().with_limit(10).with_reset(5);
+    assert_eq!(
+        legacy.headers(),
+        [
+            ("X-RateLimit-Limit".to_string(), "10".to_string()),
+            ("X-RateLimit-Reset".to_string(), "5".to_string()),
+        ]
+    );
+}
+
+// ========================================
+// RateLimitBuilder のテスト
+// ========================================
+
+#[test]
+fn test_rate_limit_builder() {
+    let builder = RateLimitBuilder::new()
+        .with_quota(QuotaPolicy::new("burst", 10).unwrap().with_window(1), 3, 1)
+        .with_quota(
+            QuotaPolicy::new("daily", 1000)
+                .unwrap()
+                .with_window(86400)
+                .with_partition_key(*b"k"),
+            2000,
+            3600,
+        )
+        .with_legacy_headers();
+    assert!(!builder.is_exhausted());
+    assert_eq!(
+        builder.headers(),
+        [
+            (
+                "RateLimit-Policy".to_string(),
+                "\"burst\";q=10;w=1, \"daily\";q=1000;w=86400;pk=:aw==:".to_string()
+            ),
+            (
+                "RateLimit".to_string(),
+                // 残りクォータはクォータを超えない
+                "\"burst\";r=3;t=1, \"daily\";r=1000;t=3600;pk=:aw==:".to_string()
+            ),
+            ("X-RateLimit-Limit".to_string(), "10".to_string()),
+            ("X-RateLimit-Remaining".to_string(), "3".to_string()),
+            ("X-RateLimit-Reset".to_string(), "1".to_string()),
+        ]
+    );
+
+    assert!(RateLimitBuilder::new().headers().is_empty());
+    assert_eq!(RateLimitBuilder::new().legacy(), None);
+}
+
+#[test]
+fn test_apply_rate_limit() {
+    let builder =
+        RateLimitBuilder::new().with_quota(QuotaPolicy::new("default", 100).unwrap(), 99, 60);
+    let response = Response::with_status(StatusCode::OK)
+        .header("RateLimit", "\"old\";r=1")
+        .unwrap();
+    let response = apply_rate_limit(response, &builder).unwrap();
+    assert_eq!(response.get_headers("RateLimit"), ["\"default\";r=99;t=60"]);
+    assert!(!response.has_header("X-RateLimit-Limit"));
+    assert_eq!(
+        response.get_typed::<RateLimitPolicy>(),
+        Some(Ok(builder.policy().clone()))
+    );
+}
+
+#[test]
+fn test_response_too_many_requests() {
+    let builder = RateLimitBuilder::new()
+        .with_quota(QuotaPolicy::new("burst", 10).unwrap(), 0, 5)
+        .with_quota(QuotaPolicy::new("hourly", 100).unwrap(), 0, 1200)
+        .with_quota(QuotaPolicy::new("daily", 1000).unwrap(), 500, 40000);
+    assert!(builder.is_exhausted());
+    let response = Response::too_many_requests(&builder).unwrap();
+    assert_eq!(response.status_code(), 429);
+    assert_eq!(response.get_header("Retry-After"), Some("1200"));
+    assert!(response.has_header("RateLimit"));
+
+    // 枯渇していない場合は Retry-After を付けない
+    let builder = RateLimitBuilder::new().with_quota(QuotaPolicy::new("burst", 10).unwrap(), 1, 5);
+    let response = Response::too_many_requests(&builder).unwrap();
+    assert!(!response.has_header("Retry-After"));
+}
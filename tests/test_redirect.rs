@@ -0,0 +1,345 @@
+//! リダイレクトの追従のユニットテスト
+
+use shiguredo_http11::redirect::{DEFAULT_MAX_REDIRECTS, RedirectError, RedirectPolicy};
+use shiguredo_http11::{Request, ResponseDecoder, ResponseHead};
+
+fn response(status_line: &str, headers: &str) -> ResponseHead {
+    let input = format!("HTTP/1.1 {status_line}\r\n{headers}Content-Length: 0\r\n\r\n");
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(input.as_bytes()).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+fn redirect_to(status_code: u16, location: &str) -> ResponseHead {
+    response(
+        &format!("{status_code} Redirect"),
+        &format!("Location: {location}\r\n"),
+    )
+}
+
+fn post(url: &str) -> Request {
+    Request::from_url("POST", url)
+        .unwrap()
+        .0
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .unwrap()
+        .header("Authorization", "Bearer token")
+        .unwrap()
+        .header("Accept", "text/html")
+        .unwrap()
+        .body(b"a=1".to_vec())
+}
+
+// ========================================
+// RedirectError のテスト
+// ========================================
+
+#[test]
+fn test_redirect_error_display() {
+    let errors = [
+        (RedirectError::TooManyRedirects, "too many redirects"),
+        (RedirectError::MissingLocation, "missing Location header"),
+        (RedirectError::InvalidLocation, "invalid Location header"),
+        (RedirectError::InvalidUrl, "invalid request URL"),
+        (
+            RedirectError::UnsupportedScheme,
+            "unsupported redirect scheme",
+        ),
+        (
+            RedirectError::InsecureRedirect,
+            "redirect from https to http is not allowed",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// 追従の判定
+// ========================================
+
+#[test]
+fn test_follow_not_redirect() {
+    let policy = RedirectPolicy::new();
+    let request = post("https://example.com/");
+    for head in [
+        response("200 OK", ""),
+        response("304 Not Modified", ""),
+        response("300 Multiple Choices", ""),
+        response("304 Not Modified", "Location: /other\r\n"),
+    ] {
+        assert!(
+            policy
+                .follow(&request, "https://example.com/", &head, 0)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
+
+#[test]
+fn test_follow_location_errors() {
+    let policy = RedirectPolicy::new();
+    let request = post("https://example.com/");
+    let url = "https://example.com/";
+
+    let head = response("302 Found", "");
+    assert_eq!(
+        policy.follow(&request, url, &head, 0).unwrap_err(),
+        RedirectError::MissingLocation
+    );
+
+    let head = response("302 Found", "Location: /a\r\nLocation: /b\r\n");
+    assert_eq!(
+        policy.follow(&request, url, &head, 0).unwrap_err(),
+        RedirectError::InvalidLocation
+    );
+
+    let head = redirect_to(302, "ftp://example.com/file");
+    assert_eq!(
+        policy.follow(&request, url, &head, 0).unwrap_err(),
+        RedirectError::UnsupportedScheme
+    );
+
+    let head = redirect_to(302, "/a");
+    assert_eq!(
+        policy.follow(&request, "/relative", &head, 0).unwrap_err(),
+        RedirectError::InvalidUrl
+    );
+}
+
+#[test]
+fn test_follow_max_redirects() {
+    let request = post("https://example.com/");
+    let head = redirect_to(307, "/next");
+    let url = "https://example.com/";
+
+    let policy = RedirectPolicy::new();
+    assert_eq!(policy.max_redirects(), DEFAULT_MAX_REDIRECTS);
+    assert!(
+        policy
+            .follow(&request, url, &head, DEFAULT_MAX_REDIRECTS - 1)
+            .is_ok()
+    );
+    assert_eq!(
+        policy
+            .follow(&request, url, &head, DEFAULT_MAX_REDIRECTS)
+            .unwrap_err(),
+        RedirectError::TooManyRedirects
+    );
+
+    let policy = RedirectPolicy::new().with_max_redirects(0);
+    assert_eq!(
+        policy.follow(&request, url, &head, 0).unwrap_err(),
+        RedirectError::TooManyRedirects
+    );
+}
+
+#[test]
+fn test_follow_https_downgrade() {
+    let request = post("https://example.com/");
+    let head = redirect_to(302, "http://example.com/");
+    let url = "https://example.com/";
+
+    assert!(
+        RedirectPolicy::new()
+            .follow(&request, url, &head, 0)
+            .is_ok()
+    );
+    assert_eq!(
+        RedirectPolicy::new()
+            .without_https_downgrade()
+            .follow(&request, url, &head, 0)
+            .unwrap_err(),
+        RedirectError::InsecureRedirect
+    );
+}
+
+// ========================================
+// Location の解決
+// ========================================
+
+#[test]
+fn test_follow_resolves_location() {
+    let policy = RedirectPolicy::new();
+    let url = "https://example.com/a/b?x=1";
+    let request = Request::from_url("GET", url).unwrap().0;
+
+    let cases = [
+        ("../c", "https://example.com/c", "/c"),
+        ("?y=2", "https://example.com/a/b?y=2", "/a/b?y=2"),
+        ("//cdn.example.com/d", "https://cdn.example.com/d", "/d"),
+        (
+            "http://example.com:8080/e",
+            "http://example.com:8080/e",
+            "/e",
+        ),
+    ];
+    for (location, expected_url, expected_target) in cases {
+        let head = redirect_to(302, location);
+        let redirect = policy.follow(&request, url, &head, 0).unwrap().unwrap();
+        assert_eq!(redirect.url(), expected_url);
+        assert_eq!(redirect.request().uri(), expected_target);
+    }
+
+    let head = redirect_to(302, "http://example.com:8080/e");
+    let redirect = policy.follow(&request, url, &head, 0).unwrap().unwrap();
+    assert_eq!(redirect.endpoint().port(), 8080);
+    assert!(!redirect.endpoint().is_secure());
+    assert_eq!(
+        redirect.request().get_header("Host"),
+        Some("example.com:8080")
+    );
+}
+
+#[test]
+fn test_follow_inherits_fragment() {
+    let policy = RedirectPolicy::new();
+    let url = "https://example.com/a#section";
+    let request = Request::from_url("GET", url).unwrap().0;
+
+    let head = redirect_to(301, "/b");
+    let redirect = policy.follow(&request, url, &head, 0).unwrap().unwrap();
+    assert_eq!(redirect.url(), "https://example.com/b#section");
+    assert_eq!(redirect.request().uri(), "/b");
+
+    let head = redirect_to(301, "/b#other");
+    let redirect = policy.follow(&request, url, &head, 0).unwrap().unwrap();
+    assert_eq!(redirect.url(), "https://example.com/b#other");
+}
+
+// ========================================
+// メソッドの書き換え
+// ========================================
+
+#[test]
+fn test_follow_method_rewrite() {
+    let policy = RedirectPolicy::new();
+    let url = "https://example.com/form";
+
+    let cases = [
+        ("POST", 301, "GET"),
+        ("POST", 302, "GET"),
+        ("POST", 303, "GET"),
+        ("POST", 307, "POST"),
+        ("POST", 308, "POST"),
+        ("PUT", 301, "PUT"),
+        ("PUT", 302, "PUT"),
+        ("PUT", 303, "GET"),
+        ("DELETE", 303, "GET"),
+        ("HEAD", 303, "HEAD"),
+        ("GET", 303, "GET"),
+    ];
+    for (method, status_code, expected) in cases {
+        let request = Request::from_url(method, url).unwrap().0;
+        let head = redirect_to(status_code, "/next");
+        let redirect = policy.follow(&request, url, &head, 0).unwrap().unwrap();
+        assert_eq!(
+            redirect.request().method(),
+            expected,
+            "{method} {status_code}"
+        );
+        assert_eq!(redirect.status_code(), status_code);
+    }
+}
+
+#[test]
+fn test_follow_drops_body_on_method_change() {
+    let policy = RedirectPolicy::new();
+    let url = "https://example.com/form";
+    let request = post(url);
+
+    let redirect = policy
+        .follow(&request, url, &redirect_to(303, "/done"), 0)
+        .unwrap()
+        .unwrap();
+    let next = redirect.request();
+    assert_eq!(next.body_bytes(), None);
+    assert_eq!(next.get_header("Content-Type"), None);
+    assert_eq!(next.get_header("Accept"), Some("text/html"));
+    assert!(!redirect.is_permanent());
+
+    let redirect = policy
+        .follow(&request, url, &redirect_to(308, "/moved"), 0)
+        .unwrap()
+        .unwrap();
+    let next = redirect.request();
+    assert_eq!(next.method(), "POST");
+    assert_eq!(next.body_bytes(), Some(&b"a=1"[..]));
+    assert_eq!(
+        next.get_header("Content-Type"),
+        Some("application/x-www-form-urlencoded")
+    );
+    assert!(redirect.is_permanent());
+}
+
+// ========================================
+// 資格情報の除去
+// ========================================
+
+#[test]
+fn test_follow_strips_credentials_cross_origin() {
+    let policy = RedirectPolicy::new();
+    let url = "https://example.com/";
+    let request = post(url).header("Cookie", "session=1").unwrap();
+
+    // 同一オリジン (デフォルトポートの明示を含む)
+    for location in ["/next", "https://EXAMPLE.com:443/next"] {
+        let redirect = policy
+            .follow(&request, url, &redirect_to(307, location), 0)
+            .unwrap()
+            .unwrap();
+        assert!(!redirect.is_cross_origin());
+        assert_eq!(
+            redirect.request().get_header("Authorization"),
+            Some("Bearer token")
+        );
+        assert_eq!(redirect.request().get_header("Cookie"), Some("session=1"));
+    }
+
+    // スキーム、ホスト、ポートのいずれかが異なる
+    for location in [
+        "http://example.com/next",
+        "https://other.example.com/next",
+        "https://example.com:8443/next",
+    ] {
+        let redirect = policy
+            .follow(&request, url, &redirect_to(307, location), 0)
+            .unwrap()
+            .unwrap();
+        assert!(redirect.is_cross_origin(), "{location}");
+        let next = redirect.request();
+        assert_eq!(next.get_header("Authorization"), None);
+        assert_eq!(next.get_header("Cookie"), None);
+        assert_eq!(next.get_header("Accept"), Some("text/html"));
+        assert_eq!(next.get_headers("Host").len(), 1);
+    }
+}
+
+#[test]
+fn test_follow_chain() {
+    let policy = RedirectPolicy::new().with_max_redirects(2);
+    let mut url = "http://example.com/".to_string();
+    let mut request = Request::from_url("GET", &url).unwrap().0;
+    let mut redirects = 0;
+    let locations = ["https://example.com/", "/login", "/home"];
+
+    for location in locations {
+        match policy.follow(&request, &url, &redirect_to(301, location), redirects) {
+            Ok(Some(redirect)) => {
+                url = redirect.url().to_string();
+                request = redirect.into_request();
+                redirects += 1;
+            }
+            Ok(None) => unreachable!(),
+            Err(e) => {
+                assert_eq!(e, RedirectError::TooManyRedirects);
+                break;
+            }
+        }
+    }
+    assert_eq!(redirects, 2);
+    assert_eq!(url, "https://example.com/login");
+    assert_eq!(request.uri(), "/login");
+}
@@ -7,7 +7,13 @@
 //! asterisk-form ("*") は入力が一意であり、不正文字やパーセントエンコーディングの
 //! テストも特定のエッジケースを網羅的に確認する目的で書かれている。
 
-use shiguredo_http11::RequestDecoder;
+use shiguredo_http11::host::HostError;
+use shiguredo_http11::request_target::{
+    RequestTarget, RequestTargetError, RequestTargetForm, classify,
+};
+use shiguredo_http11::status_code::StatusCode;
+use shiguredo_http11::uri::UriError;
+use shiguredo_http11::{Error, ParsePhase, RequestDecoder, RequestHead};
 
 // ========================================
 // asterisk-form テスト
@@ -134,3 +140,324 @@ fn test_tel_absolute_form() {
     let (head, _) = decoder.decode_headers().unwrap().unwrap();
     assert_eq!(head.uri(), "tel:+1-201-555-0123");
 }
+
+// ========================================
+// 形式判定と RequestHead アクセサのテスト
+// ========================================
+
+#[test]
+fn test_classify() {
+    assert_eq!(classify("/"), Some(RequestTargetForm::Origin));
+    assert_eq!(classify("/a?b"), Some(RequestTargetForm::Origin));
+    assert_eq!(
+        classify("http://example.com"),
+        Some(RequestTargetForm::Absolute)
+    );
+    assert_eq!(
+        classify("urn:isbn:0451450523"),
+        Some(RequestTargetForm::Absolute)
+    );
+    assert_eq!(
+        classify("example.com:443"),
+        Some(RequestTargetForm::Authority)
+    );
+    assert_eq!(classify("*"), Some(RequestTargetForm::Asterisk));
+    assert_eq!(classify("example.com"), None);
+    assert_eq!(classify(""), None);
+}
+
+#[test]
+fn test_origin_form_accessors() {
+    // path に "://" を含んでも origin-form として扱う
+    let head = decode_head("GET /://?a HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    assert_eq!(head.target_form(), Some(RequestTargetForm::Origin));
+    assert_eq!(head.target_scheme(), None);
+    assert_eq!(head.target_authority(), None);
+    assert_eq!(head.target_path(), Some("/://"));
+    assert_eq!(head.query(), Some("a"));
+    assert_eq!(head.effective_authority(), Some("example.com"));
+}
+
+#[test]
+fn test_authority_form_accessors() {
+    let raw = "CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw.as_bytes()).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.target_form(), Some(RequestTargetForm::Authority));
+    assert_eq!(head.target_scheme(), None);
+    assert_eq!(head.target_authority(), Some("example.com:443"));
+    assert_eq!(head.target_path(), None);
+    assert_eq!(head.effective_authority(), Some("example.com:443"));
+}
+
+#[test]
+fn test_asterisk_form_accessors() {
+    let raw = "OPTIONS * HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw.as_bytes()).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.target_form(), Some(RequestTargetForm::Asterisk));
+    assert_eq!(head.target_authority(), None);
+    assert_eq!(head.target_path(), None);
+    assert_eq!(head.effective_authority(), Some("example.com"));
+}
+
+#[test]
+fn test_absolute_form_accessors() {
+    let raw = "GET HTTP://Example.com:8080?q=1 HTTP/1.1\r\nHost: other.example\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw.as_bytes()).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.target_form(), Some(RequestTargetForm::Absolute));
+    assert_eq!(head.target_scheme(), Some("HTTP"));
+    assert_eq!(head.target_authority(), Some("Example.com:8080"));
+    // 空の path はそのまま返す
+    assert_eq!(head.target_path(), Some(""));
+    assert_eq!(head.effective_authority(), Some("Example.com:8080"));
+}
+
+#[test]
+fn test_absolute_form_without_authority_accessors() {
+    let raw = "GET urn:isbn:0451450523 HTTP/1.1\r\nHost: example.com\r\n\r\n";
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw.as_bytes()).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.target_scheme(), Some("urn"));
+    assert_eq!(head.target_authority(), None);
+    assert_eq!(head.target_path(), Some("isbn:0451450523"));
+    assert_eq!(head.effective_authority(), Some("example.com"));
+}
+
+#[test]
+fn test_path_and_query_accessors() {
+    let head = decode_head("GET /a%2Fb/c?x=1&y=%E2%9C%93 HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    assert_eq!(head.path(), "/a%2Fb/c");
+    assert_eq!(head.path(), head.target_path().unwrap());
+    // %2F もデコードする
+    assert_eq!(head.decoded_path().unwrap(), "/a/b/c");
+    assert_eq!(head.query(), Some("x=1&y=%E2%9C%93"));
+    assert_eq!(
+        head.query_pairs().unwrap(),
+        [
+            ("x".to_string(), "1".to_string()),
+            ("y".to_string(), "✓".to_string()),
+        ]
+    );
+
+    let head =
+        decode_head("GET HTTP://Example.com:8080?q=1 HTTP/1.1\r\nHost: other.example\r\n\r\n");
+    assert_eq!(head.path(), "");
+    assert_eq!(head.query(), Some("q=1"));
+
+    let head = decode_head("CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n");
+    assert_eq!(head.path(), "");
+    assert_eq!(head.query(), None);
+    assert!(head.query_pairs().unwrap().is_empty());
+}
+
+#[test]
+fn test_target_form_unclassifiable() {
+    // RequestHead::new は形式を判定しないため、どの形式にも該当しない URI を受け付ける
+    let head = RequestHead::new("GET", "example.com").unwrap();
+    assert_eq!(head.target_form(), None);
+    assert_eq!(head.target_path(), None);
+    assert_eq!(head.effective_authority(), None);
+}
+
+// ========================================
+// RequestTarget のテスト
+// ========================================
+
+fn decode_head(raw: &str) -> RequestHead {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw.as_bytes()).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+#[test]
+fn test_request_target_error_display() {
+    let errors = [
+        (
+            RequestTargetError::UnknownForm,
+            "unknown request-target form",
+        ),
+        (
+            RequestTargetError::InvalidUri(UriError::InvalidHost),
+            "invalid request-target URI: invalid host",
+        ),
+        (
+            RequestTargetError::InvalidAuthority(HostError::InvalidPort),
+            "invalid request-target authority: invalid Host header port",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+#[test]
+fn test_request_target_origin_form() {
+    let head = decode_head("GET /search?q=a%20b&x HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    let target = head.target().unwrap();
+    assert_eq!(
+        target,
+        RequestTarget::OriginForm {
+            path: "/search".to_string(),
+            query: Some("q=a%20b&x".to_string()),
+        }
+    );
+    assert_eq!(target.form(), RequestTargetForm::Origin);
+    assert_eq!(target.to_string(), head.uri());
+
+    // 空の query と query なしを区別する
+    assert_eq!(
+        RequestTarget::parse("/?").unwrap(),
+        RequestTarget::OriginForm {
+            path: "/".to_string(),
+            query: Some(String::new()),
+        }
+    );
+    assert_eq!(
+        RequestTarget::parse("/").unwrap(),
+        RequestTarget::OriginForm {
+            path: "/".to_string(),
+            query: None,
+        }
+    );
+}
+
+#[test]
+fn test_request_target_absolute_form() {
+    let head =
+        decode_head("GET http://example.com:8080/a?b=1 HTTP/1.1\r\nHost: example.com:8080\r\n\r\n");
+    let RequestTarget::AbsoluteForm(uri) = head.target().unwrap() else {
+        panic!("absolute-form を期待");
+    };
+    assert_eq!(uri.scheme(), Some("http"));
+    assert_eq!(uri.host(), Some("example.com"));
+    assert_eq!(uri.port(), Some(8080));
+    assert_eq!(uri.path(), "/a");
+    assert_eq!(uri.query(), Some("b=1"));
+    assert_eq!(head.target().unwrap().to_string(), head.uri());
+}
+
+#[test]
+fn test_request_target_authority_form() {
+    let head = decode_head("CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n");
+    let target = head.target().unwrap();
+    assert_eq!(
+        target,
+        RequestTarget::AuthorityForm {
+            host: "example.com".to_string(),
+            port: 443,
+        }
+    );
+    assert_eq!(target.form(), RequestTargetForm::Authority);
+
+    // IPv6 は角括弧付きで保持する
+    let head = decode_head("CONNECT [::1]:8443 HTTP/1.1\r\nHost: [::1]:8443\r\n\r\n");
+    let target = head.target().unwrap();
+    assert_eq!(
+        target,
+        RequestTarget::AuthorityForm {
+            host: "[::1]".to_string(),
+            port: 8443,
+        }
+    );
+    assert_eq!(target.to_string(), "[::1]:8443");
+}
+
+#[test]
+fn test_request_target_asterisk_form() {
+    let head = decode_head("OPTIONS * HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    let target = head.target().unwrap();
+    assert_eq!(target, RequestTarget::Asterisk);
+    assert_eq!(target.form(), RequestTargetForm::Asterisk);
+    assert_eq!(target.to_string(), "*");
+
+    // OPTIONS でも origin-form はそのまま返す
+    let head = decode_head("OPTIONS /a HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    assert_eq!(head.target().unwrap().form(), RequestTargetForm::Origin);
+}
+
+#[test]
+fn test_request_target_invalid() {
+    // RequestHead::new は形式を判定しない
+    let head = RequestHead::new("GET", "example.com").unwrap();
+    assert_eq!(head.target(), Err(RequestTargetError::UnknownForm));
+    assert_eq!(
+        RequestTarget::parse(""),
+        Err(RequestTargetError::UnknownForm)
+    );
+    assert!(matches!(
+        RequestTarget::parse("a<b:443"),
+        Err(RequestTargetError::InvalidAuthority(_))
+    ));
+}
+
+// ========================================
+// request-line 検証エラーの理由のテスト
+// ========================================
+
+fn start_line_error_reason(raw: &[u8]) -> String {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw).unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert_eq!(err.suggested_status(), StatusCode::BAD_REQUEST);
+    match err {
+        Error::Syntax {
+            phase: ParsePhase::StartLine,
+            reason,
+            ..
+        } => reason,
+        other => panic!("unexpected: {other:?}"),
+    }
+}
+
+#[test]
+fn test_request_line_error_method_non_token() {
+    let reason = start_line_error_reason(b"GE(T / HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(
+        reason,
+        "invalid request line: invalid method: non-token character 0x28"
+    );
+}
+
+#[test]
+fn test_request_line_error_method_empty() {
+    let reason = start_line_error_reason(b" / HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(reason, "invalid request line: invalid method: empty");
+}
+
+#[test]
+fn test_request_line_error_target_control_character() {
+    let reason = start_line_error_reason(b"GET /a\x01b HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(
+        reason,
+        "invalid request line: invalid request-target: contains control character or whitespace"
+    );
+}
+
+#[test]
+fn test_request_line_error_target_fragment() {
+    let reason = start_line_error_reason(b"GET /a#b HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(
+        reason,
+        "invalid request line: invalid request-target: fragment is not allowed"
+    );
+}
+
+#[test]
+fn test_request_line_error_target_percent_encoding() {
+    let reason = start_line_error_reason(b"GET /a%2 HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(
+        reason,
+        "invalid request line: invalid request-target: incomplete percent-encoding"
+    );
+    let reason = start_line_error_reason(b"GET /a%00 HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(
+        reason,
+        "invalid request line: invalid request-target: percent-encoded NUL"
+    );
+}
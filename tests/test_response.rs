@@ -3,6 +3,7 @@
 //! 構築時に弾かれるエラー (CRLF 注入、token 違反、status_code 範囲外等) を網羅する。
 //! PBT で生成不可能な特定値を含むケースを担う。
 
+use shiguredo_http11::date::{DayOfWeek, HttpDate};
 use shiguredo_http11::{EncodeError, HttpHead, Response, StatusCode};
 
 #[test]
@@ -135,6 +136,32 @@ fn test_response_set_header_atomic_on_validation_failure() {
     assert_eq!(r.get_header("X-Custom"), Some("first"));
 }
 
+#[test]
+fn test_response_remove_header() {
+    let mut r = Response::with_status(StatusCode::OK);
+    r.add_header("Content-Type", "text/plain").unwrap();
+    r.add_header("X-Custom", "first").unwrap();
+    r.add_header("content-type", "text/html").unwrap();
+    r.remove_header("CONTENT-TYPE");
+    assert!(!r.has_header("Content-Type"));
+    assert_eq!(r.get_header("X-Custom"), Some("first"));
+}
+
+#[test]
+fn test_response_insert_header_if_absent() {
+    let mut r = Response::with_status(StatusCode::OK);
+    r.add_header("Content-Type", "text/html").unwrap();
+    r.insert_header_if_absent("content-type", "application/octet-stream")
+        .unwrap()
+        .insert_header_if_absent("Cache-Control", "no-store")
+        .unwrap();
+    assert_eq!(r.get_headers("Content-Type"), vec!["text/html"]);
+    assert_eq!(r.get_header("Cache-Control"), Some("no-store"));
+
+    let result = r.insert_header_if_absent("Bad Name", "value");
+    assert!(matches!(result, Err(EncodeError::InvalidHeaderName { .. })));
+}
+
 #[test]
 fn test_response_set_header_invalid_name() {
     let mut r = Response::with_status(StatusCode::OK);
@@ -312,3 +339,79 @@ fn test_response_add_header_chain_partial_failure() {
     assert!(result.is_err());
     assert_eq!(r.get_headers("X-A"), vec!["1"]);
 }
+
+// ========================================
+// Date ヘッダー自動付与のテスト
+// ========================================
+
+fn sample_date() -> HttpDate {
+    HttpDate::new(DayOfWeek::Sunday, 6, 11, 1994, 8, 49, 37).unwrap()
+}
+
+#[test]
+fn test_auto_date_inserted_when_absent() {
+    let res = Response::with_status(StatusCode::OK)
+        .auto_date(sample_date())
+        .body(b"hi".to_vec());
+    assert_eq!(res.pending_auto_date(), Some(&sample_date()));
+    // headers() には含まれず、エンコード時に付与される
+    assert!(!res.has_header("Date"));
+    assert_eq!(
+        res.encode().unwrap(),
+        b"HTTP/1.1 200 OK\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\nContent-Length: 2\r\n\r\nhi"
+    );
+    // ヘッダーのみのエンコードでも付与される
+    assert!(
+        res.encode_headers()
+            .unwrap()
+            .windows(6)
+            .any(|w| w == b"Date: ")
+    );
+}
+
+#[test]
+fn test_auto_date_keeps_existing_date_header() {
+    let res = Response::with_status(StatusCode::OK)
+        .header("date", "Mon, 07 Nov 1994 00:00:00 GMT")
+        .unwrap()
+        .auto_date(sample_date());
+    assert_eq!(res.pending_auto_date(), None);
+    let encoded = String::from_utf8(res.encode().unwrap()).unwrap();
+    assert_eq!(encoded.matches("ate: ").count(), 1);
+    assert!(encoded.contains("date: Mon, 07 Nov 1994 00:00:00 GMT\r\n"));
+}
+
+#[test]
+fn test_set_auto_date_none_disables() {
+    let mut res = Response::with_status(StatusCode::OK).auto_date(sample_date());
+    res.set_auto_date(None);
+    assert_eq!(res.pending_auto_date(), None);
+    assert_eq!(res.encode().unwrap(), b"HTTP/1.1 200 OK\r\n\r\n");
+}
+
+// ========================================
+// Response::early_hints
+// ========================================
+
+#[test]
+fn test_early_hints_builder() {
+    let hints = Response::early_hints(["</a.css>; rel=preload", "</b.js>; rel=preload"]).unwrap();
+    assert_eq!(hints.status_code(), 103);
+    assert_eq!(hints.reason_phrase(), "Early Hints");
+    assert_eq!(
+        hints.get_headers("Link"),
+        ["</a.css>; rel=preload", "</b.js>; rel=preload"]
+    );
+    assert!(hints.body_bytes().is_none());
+
+    let empty = Response::early_hints(Vec::<String>::new()).unwrap();
+    assert!(!empty.has_header("Link"));
+}
+
+#[test]
+fn test_early_hints_rejects_invalid_link() {
+    assert!(matches!(
+        Response::early_hints(["</a>\r\nSet-Cookie: x=y"]),
+        Err(EncodeError::InvalidHeaderValue { .. })
+    ));
+}
@@ -0,0 +1,262 @@
+//! リクエストの再送ポリシーのユニットテスト
+
+use core::time::Duration;
+
+use shiguredo_http11::retry::{
+    DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY, DEFAULT_MAX_RETRIES, Failure, Jitter, RetryDecision,
+    RetryPolicy, StopReason,
+};
+use shiguredo_http11::{Request, ResponseDecoder, ResponseHead};
+
+fn request(method: &str) -> Request {
+    Request::new(method, "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+}
+
+fn response(status_line: &str, headers: &str) -> ResponseHead {
+    let input = format!("HTTP/1.1 {status_line}\r\n{headers}Content-Length: 0\r\n\r\n");
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(input.as_bytes()).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+fn no_jitter() -> RetryPolicy {
+    RetryPolicy::new().with_jitter(Jitter::None)
+}
+
+// ========================================
+// RetryDecision のテスト
+// ========================================
+
+#[test]
+fn test_retry_decision() {
+    let retry = RetryDecision::Retry(Duration::from_secs(1));
+    assert!(retry.is_retry());
+    assert_eq!(retry.delay(), Some(Duration::from_secs(1)));
+
+    let stop = RetryDecision::Stop(StopReason::MaxRetries);
+    assert!(!stop.is_retry());
+    assert_eq!(stop.delay(), None);
+}
+
+// ========================================
+// バックオフ
+// ========================================
+
+#[test]
+fn test_backoff_exponential() {
+    let policy = no_jitter();
+    assert_eq!(policy.backoff(0, 0), DEFAULT_BASE_DELAY);
+    assert_eq!(policy.backoff(1, 0), DEFAULT_BASE_DELAY * 2);
+    assert_eq!(policy.backoff(3, 0), DEFAULT_BASE_DELAY * 8);
+    // 上限で抑える
+    assert_eq!(policy.backoff(10, 0), DEFAULT_MAX_DELAY);
+    assert_eq!(policy.backoff(u32::MAX, 0), DEFAULT_MAX_DELAY);
+}
+
+#[test]
+fn test_backoff_jitter() {
+    let policy = RetryPolicy::new()
+        .with_base_delay(Duration::from_nanos(100))
+        .with_max_delay(Duration::from_secs(1));
+
+    // Full: 0 から 100ns
+    assert_eq!(policy.backoff(0, 0), Duration::ZERO);
+    assert_eq!(policy.backoff(0, 100), Duration::from_nanos(100));
+    assert_eq!(policy.backoff(0, 101), Duration::ZERO);
+    assert_eq!(policy.backoff(0, 42), Duration::from_nanos(42));
+
+    // Equal: 50ns から 100ns
+    let policy = policy.with_jitter(Jitter::Equal);
+    assert_eq!(policy.backoff(0, 0), Duration::from_nanos(50));
+    assert_eq!(policy.backoff(0, 50), Duration::from_nanos(100));
+    assert_eq!(policy.backoff(0, 56), Duration::from_nanos(55));
+}
+
+// ========================================
+// 接続の失敗
+// ========================================
+
+#[test]
+fn test_decide_not_sent() {
+    let policy = no_jitter();
+    // 送信前の失敗はメソッドによらず再送する
+    for method in ["GET", "POST", "PATCH"] {
+        assert_eq!(
+            policy.decide(&request(method), &Failure::NotSent, 0, 0, 0),
+            RetryDecision::Retry(DEFAULT_BASE_DELAY)
+        );
+    }
+}
+
+#[test]
+fn test_decide_no_response() {
+    let policy = no_jitter();
+    for method in ["GET", "HEAD", "OPTIONS", "PUT", "DELETE"] {
+        assert!(
+            policy
+                .decide(&request(method), &Failure::NoResponse, 0, 0, 0)
+                .is_retry(),
+            "{method}"
+        );
+    }
+    for method in ["POST", "PATCH", "CONNECT"] {
+        assert_eq!(
+            policy.decide(&request(method), &Failure::NoResponse, 0, 0, 0),
+            RetryDecision::Stop(StopReason::NotRetryable),
+            "{method}"
+        );
+    }
+
+    // Idempotency-Key を持つ POST は再送する
+    let request = request("POST")
+        .header("Idempotency-Key", "\"8e03978e\"")
+        .unwrap();
+    assert!(
+        policy
+            .decide(&request, &Failure::NoResponse, 0, 0, 0)
+            .is_retry()
+    );
+}
+
+#[test]
+fn test_decide_max_retries() {
+    let policy = no_jitter();
+    let request = request("GET");
+    assert!(
+        policy
+            .decide(&request, &Failure::NotSent, DEFAULT_MAX_RETRIES - 1, 0, 0)
+            .is_retry()
+    );
+    assert_eq!(
+        policy.decide(&request, &Failure::NotSent, DEFAULT_MAX_RETRIES, 0, 0),
+        RetryDecision::Stop(StopReason::MaxRetries)
+    );
+
+    let policy = policy.with_max_retries(0);
+    assert_eq!(policy.max_retries(), 0);
+    assert_eq!(
+        policy.decide(&request, &Failure::NotSent, 0, 0, 0),
+        RetryDecision::Stop(StopReason::MaxRetries)
+    );
+}
+
+// ========================================
+// レスポンスのステータスコード
+// ========================================
+
+#[test]
+fn test_decide_status() {
+    let policy = no_jitter();
+    let post = request("POST");
+    let request = request("GET");
+
+    for status_line in ["429 Too Many Requests", "503 Service Unavailable"] {
+        let head = response(status_line, "");
+        assert_eq!(
+            policy.decide(&request, &Failure::Response(&head), 1, 0, 0),
+            RetryDecision::Retry(DEFAULT_BASE_DELAY * 2)
+        );
+    }
+    for (status_line, status_code) in [
+        ("200 OK", 200),
+        ("404 Not Found", 404),
+        ("500 Internal Server Error", 500),
+    ] {
+        let head = response(status_line, "Retry-After: 1\r\n");
+        assert_eq!(
+            policy.decide(&request, &Failure::Response(&head), 0, 0, 0),
+            RetryDecision::Stop(StopReason::Status(status_code))
+        );
+    }
+
+    // idempotent ではないリクエストはステータスによらず再送しない
+    let head = response("503 Service Unavailable", "");
+    assert_eq!(
+        policy.decide(&post, &Failure::Response(&head), 0, 0, 0),
+        RetryDecision::Stop(StopReason::NotRetryable)
+    );
+}
+
+#[test]
+fn test_decide_custom_statuses() {
+    let policy = no_jitter().with_retry_statuses(&[408, 502]);
+    assert!(policy.is_retry_status(502));
+    assert!(!policy.is_retry_status(503));
+
+    let request = request("GET");
+    let head = response("502 Bad Gateway", "");
+    assert!(
+        policy
+            .decide(&request, &Failure::Response(&head), 0, 0, 0)
+            .is_retry()
+    );
+    let head = response("503 Service Unavailable", "");
+    assert_eq!(
+        policy.decide(&request, &Failure::Response(&head), 0, 0, 0),
+        RetryDecision::Stop(StopReason::Status(503))
+    );
+}
+
+// ========================================
+// Retry-After
+// ========================================
+
+#[test]
+fn test_decide_retry_after_delay() {
+    let policy = RetryPolicy::new();
+    let request = request("GET");
+    let head = response("429 Too Many Requests", "Retry-After: 30\r\n");
+    assert_eq!(
+        policy.decide(&request, &Failure::Response(&head), 0, 1_000, 12345),
+        RetryDecision::Retry(Duration::from_secs(30))
+    );
+}
+
+#[test]
+fn test_decide_retry_after_date() {
+    let policy = RetryPolicy::new();
+    let request = request("GET");
+    let head = response(
+        "503 Service Unavailable",
+        "Retry-After: Fri, 31 Dec 1999 23:59:59 GMT\r\n",
+    );
+    assert_eq!(
+        policy.decide(&request, &Failure::Response(&head), 0, 946684789, 0),
+        RetryDecision::Retry(Duration::from_secs(10))
+    );
+    // 過去の日時はすぐに再送する
+    assert_eq!(
+        policy.decide(&request, &Failure::Response(&head), 0, 946684800, 0),
+        RetryDecision::Retry(Duration::ZERO)
+    );
+}
+
+#[test]
+fn test_decide_retry_after_too_long() {
+    let request = request("GET");
+    let head = response("503 Service Unavailable", "Retry-After: 3600\r\n");
+    assert_eq!(
+        RetryPolicy::new().decide(&request, &Failure::Response(&head), 0, 0, 0),
+        RetryDecision::Stop(StopReason::RetryAfterTooLong)
+    );
+    assert_eq!(
+        RetryPolicy::new()
+            .with_max_retry_after(Duration::from_secs(3600))
+            .decide(&request, &Failure::Response(&head), 0, 0, 0),
+        RetryDecision::Retry(Duration::from_secs(3600))
+    );
+}
+
+#[test]
+fn test_decide_invalid_retry_after() {
+    let policy = no_jitter();
+    let request = request("GET");
+    let head = response("503 Service Unavailable", "Retry-After: soon\r\n");
+    assert_eq!(
+        policy.decide(&request, &Failure::Response(&head), 0, 0, 0),
+        RetryDecision::Retry(DEFAULT_BASE_DELAY)
+    );
+}
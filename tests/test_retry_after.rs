@@ -0,0 +1,116 @@
+//! Retry-After ヘッダーのユニットテスト
+
+use core::time::Duration;
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::retry_after::{RetryAfter, RetryAfterError};
+
+// ========================================
+// RetryAfterError のテスト
+// ========================================
+
+#[test]
+fn test_retry_after_error_display() {
+    assert_eq!(
+        RetryAfterError::Empty.to_string(),
+        "empty Retry-After header"
+    );
+    assert_eq!(
+        RetryAfterError::InvalidFormat.to_string(),
+        "invalid Retry-After format"
+    );
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_retry_after_parse_delay() {
+    assert_eq!(RetryAfter::parse("120", 2026), Ok(RetryAfter::Delay(120)));
+    assert_eq!(RetryAfter::parse(" 0 ", 2026), Ok(RetryAfter::Delay(0)));
+    assert!(RetryAfter::parse("120", 2026).unwrap().is_delay());
+}
+
+#[test]
+fn test_retry_after_parse_delay_overflow_saturates() {
+    assert_eq!(
+        RetryAfter::parse("99999999999999999999999", 2026),
+        Ok(RetryAfter::Delay(u64::MAX))
+    );
+}
+
+#[test]
+fn test_retry_after_parse_date_formats() {
+    let expected = HttpDate::parse("Fri, 31 Dec 1999 23:59:59 GMT").unwrap();
+    for input in [
+        "Fri, 31 Dec 1999 23:59:59 GMT",
+        "Friday, 31-Dec-99 23:59:59 GMT",
+        "Fri Dec 31 23:59:59 1999",
+    ] {
+        let retry_after = RetryAfter::parse(input, 2026).unwrap();
+        assert!(retry_after.is_date());
+        assert_eq!(retry_after, RetryAfter::Date(expected.clone()), "{}", input);
+    }
+}
+
+#[test]
+fn test_retry_after_parse_errors() {
+    assert_eq!(RetryAfter::parse("", 2026), Err(RetryAfterError::Empty));
+    assert_eq!(RetryAfter::parse("  ", 2026), Err(RetryAfterError::Empty));
+    for input in ["-1", "1.5", "+10", "10s", "tomorrow", "1 2"] {
+        assert_eq!(
+            RetryAfter::parse(input, 2026),
+            Err(RetryAfterError::InvalidFormat),
+            "{}",
+            input
+        );
+    }
+}
+
+// ========================================
+// 待ち時間のテスト
+// ========================================
+
+#[test]
+fn test_retry_after_delay_from() {
+    let retry_after = RetryAfter::Delay(30);
+    assert_eq!(retry_after.retry_at(1000), 1030);
+    assert_eq!(retry_after.delay_from(1000), Duration::from_secs(30));
+    assert_eq!(RetryAfter::Delay(u64::MAX).retry_at(1), u64::MAX);
+}
+
+#[test]
+fn test_retry_after_date_delay_from() {
+    let date = HttpDate::from_unix_seconds(2_000).unwrap();
+    let retry_after = RetryAfter::Date(date);
+    assert_eq!(retry_after.retry_at(1_000), 2_000);
+    assert_eq!(retry_after.delay_from(1_000), Duration::from_secs(1_000));
+    // 過去の日時は待ち時間 0
+    assert_eq!(retry_after.delay_from(2_000), Duration::ZERO);
+    assert_eq!(retry_after.delay_from(3_000), Duration::ZERO);
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_retry_after_display() {
+    assert_eq!(RetryAfter::Delay(120).to_string(), "120");
+    let date = HttpDate::parse("Fri, 31 Dec 1999 23:59:59 GMT").unwrap();
+    assert_eq!(
+        RetryAfter::Date(date).to_header_value(),
+        "Fri, 31 Dec 1999 23:59:59 GMT"
+    );
+}
+
+#[test]
+fn test_retry_after_with_response() {
+    use shiguredo_http11::{Response, StatusCode};
+
+    let response = Response::with_status(StatusCode::SERVICE_UNAVAILABLE)
+        .header("Retry-After", RetryAfter::Delay(60).to_string())
+        .unwrap();
+    let retry_after = RetryAfter::parse(response.get_header("Retry-After").unwrap(), 2026).unwrap();
+    assert_eq!(retry_after.delay_from(0), Duration::from_secs(60));
+}
@@ -0,0 +1,504 @@
+//! セキュリティヘッダーのユニットテスト
+
+use shiguredo_http11::cors::Origin;
+use shiguredo_http11::security_headers::{
+    AllowlistEntry, ContentTypeOptions, CrossOriginEmbedderPolicy, CrossOriginOpenerPolicy,
+    EmbedderPolicy, FrameOptions, OpenerPolicy, PermissionsPolicy, ReferrerPolicy,
+    SecurityHeaderError, SecurityHeaders, StrictTransportSecurity,
+};
+use shiguredo_http11::{Response, StatusCode};
+
+// ========================================
+// SecurityHeaderError のテスト
+// ========================================
+
+#[test]
+fn test_security_header_error_display() {
+    let errors = [
+        (
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+            "invalid Strict-Transport-Security header",
+        ),
+        (
+            SecurityHeaderError::MissingMaxAge,
+            "missing max-age in Strict-Transport-Security",
+        ),
+        (
+            SecurityHeaderError::DuplicateDirective("max-age".to_string()),
+            "duplicate Strict-Transport-Security directive: max-age",
+        ),
+        (
+            SecurityHeaderError::InvalidContentTypeOptions,
+            "invalid X-Content-Type-Options header",
+        ),
+        (
+            SecurityHeaderError::InvalidFrameOptions,
+            "invalid X-Frame-Options header",
+        ),
+        (
+            SecurityHeaderError::InvalidReferrerPolicy,
+            "invalid Referrer-Policy header",
+        ),
+        (
+            SecurityHeaderError::InvalidOpenerPolicy,
+            "invalid Cross-Origin-Opener-Policy header",
+        ),
+        (
+            SecurityHeaderError::InvalidEmbedderPolicy,
+            "invalid Cross-Origin-Embedder-Policy header",
+        ),
+        (
+            SecurityHeaderError::InvalidReportTo,
+            "invalid report-to endpoint",
+        ),
+        (
+            SecurityHeaderError::InvalidPermissionsPolicy,
+            "invalid Permissions-Policy header",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// Strict-Transport-Security のテスト
+// ========================================
+
+#[test]
+fn test_hsts_parse_rfc6797_examples() {
+    // RFC 6797 Section 6.2
+    let hsts = StrictTransportSecurity::parse("max-age=31536000").unwrap();
+    assert_eq!(hsts.max_age(), 31536000);
+    assert!(!hsts.include_subdomains());
+
+    let hsts = StrictTransportSecurity::parse("max-age=15768000 ; includeSubDomains").unwrap();
+    assert_eq!(hsts.max_age(), 15768000);
+    assert!(hsts.include_subdomains());
+
+    let hsts = StrictTransportSecurity::parse("max-age=\"31536000\"").unwrap();
+    assert_eq!(hsts.max_age(), 31536000);
+
+    let hsts = StrictTransportSecurity::parse("max-age=0").unwrap();
+    assert_eq!(hsts.max_age(), 0);
+
+    let hsts = StrictTransportSecurity::parse("max-age=0; includeSubDomains").unwrap();
+    assert!(hsts.include_subdomains());
+}
+
+#[test]
+fn test_hsts_parse_case_and_unknown_directives() {
+    let hsts =
+        StrictTransportSecurity::parse("INCLUDESUBDOMAINS; Max-Age=60; Preload; foo=\"a;b\"; bar")
+            .unwrap();
+    assert_eq!(hsts.max_age(), 60);
+    assert!(hsts.include_subdomains());
+    assert!(hsts.preload());
+
+    let hsts = StrictTransportSecurity::parse("max-age=99999999999999999999999").unwrap();
+    assert_eq!(hsts.max_age(), u64::MAX);
+}
+
+#[test]
+fn test_hsts_parse_errors() {
+    let cases = [
+        ("", SecurityHeaderError::MissingMaxAge),
+        ("includeSubDomains", SecurityHeaderError::MissingMaxAge),
+        (
+            "max-age",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+        (
+            "max-age=",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+        (
+            "max-age=-1",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+        (
+            "max-age=1.5",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+        (
+            "max-age=\"60",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+        (
+            "max-age=60; includeSubDomains=1",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+        (
+            "max-age=60; max-age=60",
+            SecurityHeaderError::DuplicateDirective("max-age".to_string()),
+        ),
+        (
+            "max-age=60; includeSubDomains; includesubdomains",
+            SecurityHeaderError::DuplicateDirective("includesubdomains".to_string()),
+        ),
+        (
+            "max age=60",
+            SecurityHeaderError::InvalidStrictTransportSecurity,
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(
+            StrictTransportSecurity::parse(input),
+            Err(expected),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_hsts_display() {
+    assert_eq!(StrictTransportSecurity::new(0).to_string(), "max-age=0");
+    let hsts = StrictTransportSecurity::new(63072000)
+        .with_include_subdomains()
+        .with_preload();
+    assert_eq!(
+        hsts.to_string(),
+        "max-age=63072000; includeSubDomains; preload"
+    );
+    assert_eq!(StrictTransportSecurity::parse(&hsts.to_string()), Ok(hsts));
+}
+
+// ========================================
+// X-Content-Type-Options / X-Frame-Options のテスト
+// ========================================
+
+#[test]
+fn test_content_type_options() {
+    assert_eq!(
+        ContentTypeOptions::parse("nosniff"),
+        Ok(ContentTypeOptions::NoSniff)
+    );
+    assert_eq!(
+        ContentTypeOptions::parse(" NoSniff , other"),
+        Ok(ContentTypeOptions::NoSniff)
+    );
+    assert_eq!(ContentTypeOptions::NoSniff.to_string(), "nosniff");
+
+    for input in ["", "sniff", "other, nosniff", "no-sniff"] {
+        assert_eq!(
+            ContentTypeOptions::parse(input),
+            Err(SecurityHeaderError::InvalidContentTypeOptions),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_frame_options() {
+    assert_eq!(FrameOptions::parse("DENY"), Ok(FrameOptions::Deny));
+    assert_eq!(
+        FrameOptions::parse("sameorigin"),
+        Ok(FrameOptions::SameOrigin)
+    );
+    assert_eq!(
+        FrameOptions::parse("SAMEORIGIN, sameOrigin"),
+        Ok(FrameOptions::SameOrigin)
+    );
+    assert_eq!(FrameOptions::Deny.to_string(), "DENY");
+    assert_eq!(FrameOptions::SameOrigin.to_string(), "SAMEORIGIN");
+
+    for input in [
+        "",
+        "DENY, SAMEORIGIN",
+        "ALLOW-FROM https://example.com",
+        "ALLOWALL",
+        "DENY,",
+    ] {
+        assert_eq!(
+            FrameOptions::parse(input),
+            Err(SecurityHeaderError::InvalidFrameOptions),
+            "{input}"
+        );
+    }
+}
+
+// ========================================
+// Referrer-Policy のテスト
+// ========================================
+
+#[test]
+fn test_referrer_policy() {
+    let policies = [
+        ReferrerPolicy::NoReferrer,
+        ReferrerPolicy::NoReferrerWhenDowngrade,
+        ReferrerPolicy::SameOrigin,
+        ReferrerPolicy::Origin,
+        ReferrerPolicy::StrictOrigin,
+        ReferrerPolicy::OriginWhenCrossOrigin,
+        ReferrerPolicy::StrictOriginWhenCrossOrigin,
+        ReferrerPolicy::UnsafeUrl,
+    ];
+    for policy in policies {
+        assert_eq!(ReferrerPolicy::parse(policy.as_str()), Ok(policy));
+        assert_eq!(policy.to_string(), policy.as_str());
+    }
+}
+
+#[test]
+fn test_referrer_policy_fallback() {
+    // 認識できる最後の値を採用し、未知の値は無視する
+    assert_eq!(
+        ReferrerPolicy::parse("no-referrer, strict-origin-when-cross-origin, future-policy"),
+        Ok(ReferrerPolicy::StrictOriginWhenCrossOrigin)
+    );
+    assert_eq!(
+        ReferrerPolicy::parse("unknown"),
+        Err(SecurityHeaderError::InvalidReferrerPolicy)
+    );
+    assert_eq!(
+        ReferrerPolicy::parse("No-Referrer"),
+        Err(SecurityHeaderError::InvalidReferrerPolicy)
+    );
+    assert_eq!(
+        ReferrerPolicy::parse(""),
+        Err(SecurityHeaderError::InvalidReferrerPolicy)
+    );
+}
+
+// ========================================
+// Cross-Origin-Opener-Policy / Cross-Origin-Embedder-Policy のテスト
+// ========================================
+
+#[test]
+fn test_opener_policy() {
+    let coop = CrossOriginOpenerPolicy::parse("same-origin").unwrap();
+    assert_eq!(coop.policy(), OpenerPolicy::SameOrigin);
+    assert_eq!(coop.report_to(), None);
+
+    let coop = CrossOriginOpenerPolicy::parse("same-origin-allow-popups; report-to=\"coop\"; x=1")
+        .unwrap();
+    assert_eq!(coop.policy(), OpenerPolicy::SameOriginAllowPopups);
+    assert_eq!(coop.report_to(), Some("coop"));
+    assert_eq!(
+        coop.to_string(),
+        "same-origin-allow-popups;report-to=\"coop\""
+    );
+
+    let coop = CrossOriginOpenerPolicy::new(OpenerPolicy::NoopenerAllowPopups)
+        .with_report_to("a \"b\"")
+        .unwrap();
+    assert_eq!(
+        coop.to_string(),
+        "noopener-allow-popups;report-to=\"a \\\"b\\\"\""
+    );
+    assert_eq!(CrossOriginOpenerPolicy::parse(&coop.to_string()), Ok(coop));
+
+    for input in [
+        "",
+        "Same-Origin",
+        "\"same-origin\"",
+        "same-origin;",
+        "same-origin x",
+        "same-origin, unsafe-none",
+    ] {
+        assert_eq!(
+            CrossOriginOpenerPolicy::parse(input),
+            Err(SecurityHeaderError::InvalidOpenerPolicy),
+            "{input}"
+        );
+    }
+    assert_eq!(
+        CrossOriginOpenerPolicy::new(OpenerPolicy::SameOrigin).with_report_to("a\nb"),
+        Err(SecurityHeaderError::InvalidReportTo)
+    );
+}
+
+#[test]
+fn test_embedder_policy() {
+    let coep = CrossOriginEmbedderPolicy::parse("require-corp").unwrap();
+    assert_eq!(coep.policy(), EmbedderPolicy::RequireCorp);
+
+    let coep = CrossOriginEmbedderPolicy::parse("credentialless;report-to=\"e\"").unwrap();
+    assert_eq!(coep.policy(), EmbedderPolicy::Credentialless);
+    assert_eq!(coep.report_to(), Some("e"));
+
+    // report-to が string でない場合は無視する
+    let coep = CrossOriginEmbedderPolicy::parse("unsafe-none;report-to=e").unwrap();
+    assert_eq!(coep.report_to(), None);
+    assert_eq!(coep.to_string(), "unsafe-none");
+
+    assert_eq!(
+        CrossOriginEmbedderPolicy::parse("require-corp-x"),
+        Err(SecurityHeaderError::InvalidEmbedderPolicy)
+    );
+}
+
+// ========================================
+// Permissions-Policy のテスト
+// ========================================
+
+#[test]
+fn test_permissions_policy_parse() {
+    let policy = PermissionsPolicy::parse(
+        "camera=(), geolocation=(self \"https://maps.example\"), fullscreen=*, autoplay=self, \
+         picture-in-picture=(* src);x=1",
+    )
+    .unwrap();
+    assert_eq!(policy.features().len(), 5);
+    assert_eq!(policy.allowlist("camera"), Some(&[][..]));
+    assert_eq!(
+        policy.allowlist("geolocation").unwrap(),
+        [
+            AllowlistEntry::SelfOrigin,
+            AllowlistEntry::Origin(Origin::parse("https://maps.example").unwrap()),
+        ]
+    );
+    assert_eq!(
+        policy.allowlist("fullscreen").unwrap(),
+        [AllowlistEntry::Any]
+    );
+    assert_eq!(
+        policy.allowlist("autoplay").unwrap(),
+        [AllowlistEntry::SelfOrigin]
+    );
+    assert_eq!(
+        policy.allowlist("picture-in-picture").unwrap(),
+        [AllowlistEntry::Any, AllowlistEntry::Src]
+    );
+    assert_eq!(policy.allowlist("microphone"), None);
+}
+
+#[test]
+fn test_permissions_policy_parse_ignores_unknown_members() {
+    // 未知のトークンやオリジンでない文字列、パラメーターは無視する
+    let policy = PermissionsPolicy::parse(
+        "camera=(self;a=1 none \"not an origin\" \"null\" 1), camera=(self)",
+    )
+    .unwrap();
+    assert_eq!(policy.features().len(), 1);
+    assert_eq!(
+        policy.allowlist("camera").unwrap(),
+        [AllowlistEntry::SelfOrigin]
+    );
+    assert!(PermissionsPolicy::parse("").unwrap().is_empty());
+}
+
+#[test]
+fn test_permissions_policy_parse_errors() {
+    for input in [
+        "camera",
+        "Camera=()",
+        "camera=(self",
+        "camera=(self)x",
+        "camera=\"https://a.example",
+        "camera=(),",
+        "camera = ()",
+        "camera=(\"\\x\")",
+    ] {
+        assert_eq!(
+            PermissionsPolicy::parse(input),
+            Err(SecurityHeaderError::InvalidPermissionsPolicy),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_permissions_policy_builder() {
+    let origin = Origin::parse("https://A.example:443").unwrap();
+    let policy = PermissionsPolicy::new()
+        .with_feature("camera", [])
+        .unwrap()
+        .with_feature(
+            "geolocation",
+            [AllowlistEntry::SelfOrigin, AllowlistEntry::Origin(origin)],
+        )
+        .unwrap()
+        .with_feature("fullscreen", [AllowlistEntry::Any])
+        .unwrap()
+        .with_feature("camera", [AllowlistEntry::Src])
+        .unwrap();
+    assert_eq!(
+        policy.to_string(),
+        "camera=(src), geolocation=(self \"https://a.example\"), fullscreen=*"
+    );
+    assert_eq!(PermissionsPolicy::parse(&policy.to_string()), Ok(policy));
+
+    assert_eq!(
+        PermissionsPolicy::new().with_feature("Camera", []),
+        Err(SecurityHeaderError::InvalidPermissionsPolicy)
+    );
+}
+
+// ========================================
+// まとめて設定のテスト
+// ========================================
+
+#[test]
+fn test_security_headers_recommended() {
+    let headers = SecurityHeaders::recommended().headers();
+    assert_eq!(
+        headers,
+        [
+            (
+                "Strict-Transport-Security".to_string(),
+                "max-age=31536000; includeSubDomains".to_string()
+            ),
+            ("X-Content-Type-Options".to_string(), "nosniff".to_string()),
+            ("X-Frame-Options".to_string(), "DENY".to_string()),
+            (
+                "Referrer-Policy".to_string(),
+                "strict-origin-when-cross-origin".to_string()
+            ),
+            (
+                "Cross-Origin-Opener-Policy".to_string(),
+                "same-origin".to_string()
+            ),
+        ]
+    );
+    assert!(SecurityHeaders::new().headers().is_empty());
+}
+
+#[test]
+fn test_security_headers_customize() {
+    let headers = SecurityHeaders::recommended()
+        .without_strict_transport_security()
+        .without_frame_options()
+        .with_referrer_policy(ReferrerPolicy::NoReferrer)
+        .with_embedder_policy(CrossOriginEmbedderPolicy::new(EmbedderPolicy::RequireCorp))
+        .with_permissions_policy(PermissionsPolicy::new())
+        .headers();
+    let names: Vec<&str> = headers.iter().map(|(name, _)| name.as_str()).collect();
+    // 空の Permissions-Policy は付けない
+    assert_eq!(
+        names,
+        [
+            "X-Content-Type-Options",
+            "Referrer-Policy",
+            "Cross-Origin-Opener-Policy",
+            "Cross-Origin-Embedder-Policy",
+        ]
+    );
+    assert_eq!(headers[1].1, "no-referrer");
+}
+
+#[test]
+fn test_response_recommended_security_headers() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("x-frame-options", "SAMEORIGIN")
+        .unwrap()
+        .recommended_security_headers()
+        .unwrap();
+    // アプリケーションが設定したヘッダーは上書きしない
+    assert_eq!(response.get_headers("X-Frame-Options"), ["SAMEORIGIN"]);
+    assert_eq!(
+        response.get_header("Referrer-Policy"),
+        Some("strict-origin-when-cross-origin")
+    );
+
+    let hsts = response
+        .get_typed::<StrictTransportSecurity>()
+        .unwrap()
+        .unwrap();
+    assert_eq!(hsts.max_age(), 31536000);
+    assert_eq!(
+        response.get_typed::<CrossOriginOpenerPolicy>(),
+        Some(Ok(CrossOriginOpenerPolicy::new(OpenerPolicy::SameOrigin)))
+    );
+}
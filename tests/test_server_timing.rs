@@ -0,0 +1,199 @@
+//! Server-Timing ヘッダーのユニットテスト
+
+use core::time::Duration;
+
+use shiguredo_http11::server_timing::{Metric, ServerTiming, ServerTimingError};
+use shiguredo_http11::{Request, Response, ResponseDecoder, StatusCode};
+
+// ========================================
+// ServerTimingError のテスト
+// ========================================
+
+#[test]
+fn test_server_timing_error_display() {
+    let errors = [
+        (
+            ServerTimingError::InvalidFormat,
+            "invalid Server-Timing header format",
+        ),
+        (ServerTimingError::InvalidName, "invalid metric name"),
+        (
+            ServerTimingError::InvalidParameter,
+            "invalid metric parameter",
+        ),
+        (
+            ServerTimingError::InvalidDuration,
+            "invalid metric duration",
+        ),
+        (
+            ServerTimingError::UnterminatedQuote,
+            "unterminated quoted-string",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_server_timing_parse_spec_examples() {
+    // W3C Server Timing Section 3 の例
+    let timing = ServerTiming::parse("miss, db;dur=53, app;dur=47.2").unwrap();
+    assert_eq!(timing.metrics().len(), 3);
+    assert_eq!(timing.get("miss").unwrap().duration(), None);
+    assert_eq!(
+        timing.get("db").unwrap().duration(),
+        Some(Duration::from_millis(53))
+    );
+    assert_eq!(timing.get("app").unwrap().duration_ms(), Some(47.2));
+
+    let timing = ServerTiming::parse("customView, dc;desc=atl").unwrap();
+    assert_eq!(timing.get("dc").unwrap().description(), Some("atl"));
+
+    let timing = ServerTiming::parse("cache;desc=\"Cache Read\";dur=23.2").unwrap();
+    let cache = timing.get("cache").unwrap();
+    assert_eq!(cache.description(), Some("Cache Read"));
+    assert_eq!(cache.duration(), Some(Duration::from_micros(23_200)));
+    assert_eq!(timing.to_string(), "cache;dur=23.2;desc=\"Cache Read\"");
+}
+
+#[test]
+fn test_server_timing_parse_params() {
+    let timing = ServerTiming::parse(
+        " , total ; DUR = 1.23456 ; dur=9 ; Desc=\"a \\\"b\\\"\" ; desc=x ; Region=eu ,, ",
+    )
+    .unwrap();
+    let total = timing.get("total").unwrap();
+    // 小数点以下 4 桁目以降は切り捨て、同じパラメーターは最初のものを使う
+    assert_eq!(total.duration(), Some(Duration::from_micros(1234)));
+    assert_eq!(total.description(), Some("a \"b\""));
+    assert_eq!(total.params(), [("region".to_string(), "eu".to_string())]);
+    assert_eq!(
+        timing.to_string(),
+        "total;dur=1.234;desc=\"a \\\"b\\\"\";region=eu"
+    );
+    assert_eq!(ServerTiming::parse(&timing.to_string()), Ok(timing));
+
+    assert!(ServerTiming::parse("").unwrap().is_empty());
+    let timing = ServerTiming::parse("a;dur=99999999999999999999").unwrap();
+    assert_eq!(
+        timing.get("a").unwrap().duration(),
+        Some(Duration::from_micros(u64::MAX))
+    );
+}
+
+#[test]
+fn test_server_timing_parse_errors() {
+    let cases = [
+        (";dur=1", ServerTimingError::InvalidFormat),
+        ("a b", ServerTimingError::InvalidName),
+        ("a;dur", ServerTimingError::InvalidParameter),
+        ("a;=1", ServerTimingError::InvalidParameter),
+        ("a;desc=a b", ServerTimingError::InvalidParameter),
+        ("a;desc=\"x\"y", ServerTimingError::InvalidParameter),
+        ("a;desc=\"x", ServerTimingError::UnterminatedQuote),
+        ("a;dur=-1", ServerTimingError::InvalidDuration),
+        ("a;dur=1.", ServerTimingError::InvalidDuration),
+        ("a;dur=.5", ServerTimingError::InvalidDuration),
+        ("a;dur=abc", ServerTimingError::InvalidDuration),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(ServerTiming::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_server_timing_merges_header_lines() {
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nServer-Timing: db;dur=10\r\nContent-Length: 0\r\n\
+              Server-Timing: app;dur=20, cdn-cache;desc=HIT\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let timing = ServerTiming::from_message(&head).unwrap().unwrap();
+    let names: Vec<&str> = timing.metrics().iter().map(Metric::name).collect();
+    assert_eq!(names, ["db", "app", "cdn-cache"]);
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(ServerTiming::from_message(&request), Ok(None));
+
+    // トレーラーの Server-Timing をヘッダーのものに連結する
+    let trailer = ServerTiming::parse("total;dur=35").unwrap();
+    let merged = timing.merge(trailer);
+    assert_eq!(merged.metrics().len(), 4);
+    assert_eq!(
+        merged.to_string(),
+        "db;dur=10, app;dur=20, cdn-cache;desc=HIT, total;dur=35"
+    );
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_metric_builder() {
+    let metric = Metric::new("db")
+        .unwrap()
+        .with_duration(Duration::from_nanos(1_500_999))
+        .with_description("users, orders")
+        .unwrap()
+        .with_param("Shard", "3")
+        .unwrap();
+    assert_eq!(
+        metric.to_string(),
+        "db;dur=1.5;desc=\"users, orders\";shard=3"
+    );
+
+    let metric = Metric::new("zero").unwrap().with_duration(Duration::ZERO);
+    assert_eq!(metric.to_string(), "zero;dur=0");
+
+    assert_eq!(Metric::new("a b"), Err(ServerTimingError::InvalidName));
+    assert_eq!(Metric::new(""), Err(ServerTimingError::InvalidName));
+    let metric = Metric::new("a").unwrap();
+    assert_eq!(
+        metric.clone().with_description("a\r\nb"),
+        Err(ServerTimingError::InvalidParameter)
+    );
+    assert_eq!(
+        metric.clone().with_param("dur", "1"),
+        Err(ServerTimingError::InvalidParameter)
+    );
+    assert_eq!(
+        metric.with_param("x", "\0"),
+        Err(ServerTimingError::InvalidParameter)
+    );
+}
+
+#[test]
+fn test_response_server_timing() {
+    let timing = ServerTiming::new().with_metric(
+        Metric::new("app")
+            .unwrap()
+            .with_duration(Duration::from_millis(12)),
+    );
+    let response = Response::with_status(StatusCode::OK)
+        .header("Server-Timing", "edge;dur=1")
+        .unwrap()
+        .server_timing(&timing)
+        .unwrap()
+        .server_timing(&ServerTiming::new())
+        .unwrap();
+    assert_eq!(
+        response.get_headers("Server-Timing"),
+        ["edge;dur=1", "app;dur=12"]
+    );
+    let parsed = ServerTiming::from_message(&response).unwrap().unwrap();
+    assert_eq!(parsed.metrics().len(), 2);
+
+    let mut response = Response::with_status(StatusCode::OK);
+    response.set_typed(&timing).unwrap();
+    assert_eq!(response.get_typed::<ServerTiming>(), Some(Ok(timing)));
+}
@@ -0,0 +1,474 @@
+//! Structured Field Values のユニットテスト
+
+use shiguredo_http11::sfv::{
+    BareItem, Decimal, Dictionary, InnerList, Item, List, ListMember, MAX_INTEGER, MIN_INTEGER,
+    SfvError,
+};
+
+fn item(input: &str) -> Item {
+    Item::parse(input).unwrap()
+}
+
+// ========================================
+// SfvError のテスト
+// ========================================
+
+#[test]
+fn test_sfv_error_display() {
+    let errors = [
+        (
+            SfvError::NonAscii,
+            "non-ASCII character in structured field",
+        ),
+        (SfvError::Empty, "empty structured field item"),
+        (SfvError::InvalidBareItem, "invalid bare-item"),
+        (SfvError::InvalidKey, "invalid structured field key"),
+        (SfvError::InvalidInteger, "invalid sf-integer"),
+        (SfvError::InvalidDecimal, "invalid sf-decimal"),
+        (SfvError::InvalidString, "invalid sf-string"),
+        (SfvError::InvalidToken, "invalid sf-token"),
+        (SfvError::InvalidByteSequence, "invalid sf-binary"),
+        (SfvError::InvalidBoolean, "invalid sf-boolean"),
+        (SfvError::InvalidDate, "invalid sf-date"),
+        (SfvError::InvalidDisplayString, "invalid sf-displaystring"),
+        (SfvError::InvalidInnerList, "invalid inner-list"),
+        (
+            SfvError::InvalidSeparator,
+            "invalid structured field member separator",
+        ),
+        (
+            SfvError::TrailingCharacters,
+            "trailing characters after structured field",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// bare-item のテスト
+// ========================================
+
+#[test]
+fn test_parse_integer() {
+    assert_eq!(item("42").bare_item(), &BareItem::Integer(42));
+    assert_eq!(item("-42").bare_item(), &BareItem::Integer(-42));
+    assert_eq!(item("0042").bare_item(), &BareItem::Integer(42));
+    assert_eq!(item("-0").bare_item(), &BareItem::Integer(0));
+    assert_eq!(
+        item("999999999999999").bare_item(),
+        &BareItem::Integer(MAX_INTEGER)
+    );
+    assert_eq!(
+        item("-999999999999999").bare_item(),
+        &BareItem::Integer(MIN_INTEGER)
+    );
+
+    assert_eq!(
+        Item::parse("1000000000000000"),
+        Err(SfvError::InvalidInteger)
+    );
+    assert_eq!(Item::parse("-"), Err(SfvError::InvalidInteger));
+    assert_eq!(Item::parse("- 1"), Err(SfvError::InvalidInteger));
+    assert_eq!(Item::parse("1x"), Err(SfvError::TrailingCharacters));
+}
+
+#[test]
+fn test_parse_decimal() {
+    let decimal = |input: &str| item(input).bare_item().as_decimal().unwrap();
+    assert_eq!(decimal("1.5").thousandths(), 1500);
+    assert_eq!(decimal("-1.5").thousandths(), -1500);
+    assert_eq!(decimal("0.001").thousandths(), 1);
+    assert_eq!(decimal("123456789012.123").thousandths(), 123456789012123);
+    assert_eq!(decimal("1.50").to_string(), "1.5");
+    assert_eq!(decimal("1.000").to_string(), "1.0");
+    assert_eq!(decimal("-0.05").to_string(), "-0.05");
+    assert_eq!(decimal("12.345").integer_part(), 12);
+    assert_eq!(decimal("2.5").to_f64(), 2.5);
+
+    for input in ["1.", "1.1234", "1234567890123.0", "1..0", "-.5"] {
+        assert!(Item::parse(input).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn test_decimal_new() {
+    assert_eq!(
+        Decimal::from_thousandths(MAX_INTEGER).unwrap().to_string(),
+        "999999999999.999"
+    );
+    assert_eq!(
+        Decimal::from_thousandths(MAX_INTEGER + 1),
+        Err(SfvError::InvalidDecimal)
+    );
+    assert_eq!(Decimal::from_integer(-3).unwrap().to_string(), "-3.0");
+    assert_eq!(
+        Decimal::from_integer(1_000_000_000_000),
+        Err(SfvError::InvalidDecimal)
+    );
+    assert_eq!(
+        Decimal::from_integer(i64::MAX),
+        Err(SfvError::InvalidDecimal)
+    );
+}
+
+#[test]
+fn test_parse_string() {
+    assert_eq!(
+        item("\"hello world\"").bare_item().as_string(),
+        Some("hello world")
+    );
+    assert_eq!(
+        item(r#""a \"b\" \\ c""#).bare_item().as_string(),
+        Some(r#"a "b" \ c"#)
+    );
+    assert_eq!(item("\"\"").bare_item().as_string(), Some(""));
+
+    for input in ["\"abc", r#""\a""#, "\"a\tb\"", "\"a\u{7f}\""] {
+        assert_eq!(Item::parse(input), Err(SfvError::InvalidString), "{input}");
+    }
+    assert_eq!(Item::parse("\"é\""), Err(SfvError::NonAscii));
+}
+
+#[test]
+fn test_parse_token() {
+    assert_eq!(
+        item("foo123/456").bare_item().as_token(),
+        Some("foo123/456")
+    );
+    assert_eq!(item("*foo").bare_item().as_token(), Some("*foo"));
+    assert_eq!(
+        item("text/html:x!#$").bare_item().as_token(),
+        Some("text/html:x!#$")
+    );
+    assert_eq!(Item::parse("(foo)"), Err(SfvError::InvalidBareItem));
+    assert_eq!(Item::parse("_foo"), Err(SfvError::InvalidBareItem));
+}
+
+#[test]
+fn test_parse_byte_sequence() {
+    assert_eq!(
+        item(":aGVsbG8=:").bare_item().as_byte_sequence(),
+        Some(&b"hello"[..])
+    );
+    assert_eq!(item("::").bare_item().as_byte_sequence(), Some(&[][..]));
+    // "=" パディングがない場合も受理する
+    assert_eq!(
+        item(":aGVsbG8:").bare_item().as_byte_sequence(),
+        Some(&b"hello"[..])
+    );
+    assert_eq!(
+        item(":aGk:").bare_item().as_byte_sequence(),
+        Some(&b"hi"[..])
+    );
+    assert_eq!(item(":YQ:").bare_item().as_byte_sequence(), Some(&b"a"[..]));
+    assert_eq!(item(":aGVsbG8:").to_string(), ":aGVsbG8=:");
+
+    for input in [
+        ":aGVsbG8=",
+        ":aGVs bG8=:",
+        ":aGVsbG8_:",
+        ":aGVsb:",
+        ":aGV=sbG8:",
+    ] {
+        assert_eq!(
+            Item::parse(input),
+            Err(SfvError::InvalidByteSequence),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_boolean() {
+    assert_eq!(item("?1").bare_item().as_boolean(), Some(true));
+    assert_eq!(item("?0").bare_item().as_boolean(), Some(false));
+    assert_eq!(Item::parse("?2"), Err(SfvError::InvalidBoolean));
+    assert_eq!(Item::parse("?"), Err(SfvError::InvalidBoolean));
+}
+
+#[test]
+fn test_parse_date() {
+    // RFC 9651 Section 3.3.7
+    assert_eq!(item("@1659578233").bare_item().as_date(), Some(1659578233));
+    assert_eq!(item("@-1").bare_item().as_date(), Some(-1));
+    assert_eq!(Item::parse("@1.5"), Err(SfvError::InvalidDate));
+    assert_eq!(Item::parse("@"), Err(SfvError::InvalidDate));
+    assert_eq!(Item::parse("@x"), Err(SfvError::InvalidDate));
+}
+
+#[test]
+fn test_parse_display_string() {
+    // RFC 9651 Section 3.3.8
+    let value = item("%\"This is intended for display to %c3%bcsers.\"");
+    assert_eq!(
+        value.bare_item().as_display_string(),
+        Some("This is intended for display to üsers.")
+    );
+    assert_eq!(
+        value.to_string(),
+        "%\"This is intended for display to %c3%bcsers.\""
+    );
+    assert_eq!(
+        BareItem::DisplayString("100% \"ok\"".to_string()).to_string(),
+        "%\"100%25 %22ok%22\""
+    );
+
+    for input in ["%\"%C3%BC\"", "%\"%c3\"", "%\"%g0\"", "%\"abc", "%abc"] {
+        assert_eq!(
+            Item::parse(input),
+            Err(SfvError::InvalidDisplayString),
+            "{input}"
+        );
+    }
+}
+
+// ========================================
+// Parameters / Item のテスト
+// ========================================
+
+#[test]
+fn test_parse_parameters() {
+    let value = item("abc;a=1;b=2;  cde_456;a=3");
+    let params: Vec<_> = value.params().iter().collect();
+    // 同じ key は最初の位置のまま値を置き換える
+    assert_eq!(
+        params,
+        [
+            ("a", &BareItem::Integer(3)),
+            ("b", &BareItem::Integer(2)),
+            ("cde_456", &BareItem::Boolean(true)),
+        ]
+    );
+    assert_eq!(value.to_string(), "abc;a=3;b=2;cde_456");
+    assert!(value.params().contains_key("b"));
+    assert_eq!(value.params().len(), 3);
+
+    assert_eq!(Item::parse("abc;A=1"), Err(SfvError::InvalidKey));
+    assert_eq!(
+        Item::parse("abc;a=1 ;b=2"),
+        Err(SfvError::TrailingCharacters)
+    );
+    assert_eq!(Item::parse("abc;"), Err(SfvError::InvalidKey));
+    assert_eq!(Item::parse("abc;a="), Err(SfvError::InvalidBareItem));
+}
+
+#[test]
+fn test_item_parse_whitespace() {
+    assert_eq!(item("  1  ").bare_item(), &BareItem::Integer(1));
+    assert_eq!(Item::parse(""), Err(SfvError::Empty));
+    assert_eq!(Item::parse("   "), Err(SfvError::Empty));
+    assert_eq!(Item::parse("\t1"), Err(SfvError::InvalidBareItem));
+    assert_eq!(Item::parse("1, 2"), Err(SfvError::TrailingCharacters));
+}
+
+#[test]
+fn test_item_builder() {
+    let value = Item::new(BareItem::String("a \"b\"".to_string()))
+        .unwrap()
+        .with_param("n", BareItem::Integer(-5))
+        .unwrap()
+        .with_param("b", BareItem::ByteSequence(b"hi".to_vec()))
+        .unwrap()
+        .with_param("f", BareItem::Boolean(false))
+        .unwrap()
+        .with_param("t", BareItem::Boolean(true))
+        .unwrap()
+        .with_param("d", BareItem::Date(0))
+        .unwrap();
+    assert_eq!(value.to_string(), r#""a \"b\"";n=-5;b=:aGk=:;f=?0;t;d=@0"#);
+    assert_eq!(Item::parse(&value.to_string()), Ok(value));
+
+    assert_eq!(
+        Item::new(BareItem::Integer(MAX_INTEGER + 1)),
+        Err(SfvError::InvalidInteger)
+    );
+    assert_eq!(
+        Item::new(BareItem::Date(MIN_INTEGER - 1)),
+        Err(SfvError::InvalidDate)
+    );
+    assert_eq!(
+        Item::new(BareItem::String("a\nb".to_string())),
+        Err(SfvError::InvalidString)
+    );
+    assert_eq!(
+        Item::new(BareItem::Token("1abc".to_string())),
+        Err(SfvError::InvalidToken)
+    );
+    assert_eq!(
+        Item::new(BareItem::Token(String::new())),
+        Err(SfvError::InvalidToken)
+    );
+    assert_eq!(
+        Item::new(BareItem::from(1))
+            .unwrap()
+            .with_param("Key", true.into()),
+        Err(SfvError::InvalidKey)
+    );
+}
+
+// ========================================
+// List のテスト
+// ========================================
+
+#[test]
+fn test_parse_list() {
+    // RFC 9651 Section 3.1
+    let list = List::parse("sugar, tea, rum").unwrap();
+    let tokens: Vec<_> = list
+        .members()
+        .iter()
+        .map(|member| member.as_item().unwrap().bare_item().as_token().unwrap())
+        .collect();
+    assert_eq!(tokens, ["sugar", "tea", "rum"]);
+
+    let list = List::parse("(\"foo\" \"bar\"), (\"baz\"), (\"bat\" \"one\"), ()").unwrap();
+    assert_eq!(list.members().len(), 4);
+    assert!(
+        list.members()[3]
+            .as_inner_list()
+            .unwrap()
+            .items()
+            .is_empty()
+    );
+
+    let list = List::parse("(\"foo\"; a=1;b=2);lvl=5, (\"bar\" \"baz\");lvl=1").unwrap();
+    let first = list.members()[0].as_inner_list().unwrap();
+    assert_eq!(first.params().get("lvl"), Some(&BareItem::Integer(5)));
+    assert_eq!(first.items()[0].params().len(), 2);
+    assert_eq!(
+        list.to_string(),
+        "(\"foo\";a=1;b=2);lvl=5, (\"bar\" \"baz\");lvl=1"
+    );
+
+    // OWS はカンマの前後にのみ許される
+    let list = List::parse("a ,\tb,c").unwrap();
+    assert_eq!(list.to_string(), "a, b, c");
+
+    assert!(List::parse("").unwrap().is_empty());
+    assert!(List::parse("   ").unwrap().is_empty());
+}
+
+#[test]
+fn test_parse_list_errors() {
+    let cases = [
+        ("a,", SfvError::InvalidSeparator),
+        ("a, ", SfvError::InvalidSeparator),
+        (",a", SfvError::InvalidBareItem),
+        ("a b", SfvError::InvalidSeparator),
+        ("a,,b", SfvError::InvalidBareItem),
+        ("(a b", SfvError::InvalidInnerList),
+        ("(a,b)", SfvError::InvalidInnerList),
+        ("(a)(b)", SfvError::InvalidSeparator),
+        ("a;b=", SfvError::InvalidBareItem),
+        ("ü", SfvError::NonAscii),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(List::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_list_builder() {
+    let list = List::new()
+        .with_member(
+            InnerList::new()
+                .with_item(Item::new(BareItem::Integer(1)).unwrap())
+                .with_item(Item::new(BareItem::Decimal(Decimal::from_integer(2).unwrap())).unwrap())
+                .with_param("p", BareItem::Token("x".to_string()))
+                .unwrap(),
+        )
+        .with_member(InnerList::new())
+        .with_member(Item::new(BareItem::String("s".to_string())).unwrap());
+    assert_eq!(list.to_string(), "(1 2.0);p=x, (), \"s\"");
+    assert_eq!(List::parse(&list.to_string()), Ok(list));
+}
+
+// ========================================
+// Dictionary のテスト
+// ========================================
+
+#[test]
+fn test_parse_dictionary() {
+    // RFC 9651 Section 3.2
+    let dictionary = Dictionary::parse("en=\"Applepie\", da=:w4ZibGV0w6ZydGU=:").unwrap();
+    assert_eq!(dictionary.len(), 2);
+    assert_eq!(
+        dictionary
+            .get("en")
+            .and_then(ListMember::as_item)
+            .and_then(|item| item.bare_item().as_string()),
+        Some("Applepie")
+    );
+    assert_eq!(
+        dictionary
+            .get("da")
+            .and_then(ListMember::as_item)
+            .and_then(|item| item.bare_item().as_byte_sequence()),
+        Some("Æbletærte".as_bytes())
+    );
+
+    let dictionary = Dictionary::parse("a=?0, b, c; foo=bar").unwrap();
+    assert_eq!(dictionary.to_string(), "a=?0, b, c;foo=bar");
+    let c = dictionary.get("c").unwrap();
+    assert_eq!(c.as_item().unwrap().bare_item(), &BareItem::Boolean(true));
+    assert_eq!(
+        c.params().get("foo"),
+        Some(&BareItem::Token("bar".to_string()))
+    );
+
+    let dictionary = Dictionary::parse("rating=1.5, feelings=(joy sadness)").unwrap();
+    let feelings = dictionary.get("feelings").unwrap().as_inner_list().unwrap();
+    assert_eq!(feelings.items().len(), 2);
+
+    // 同じ key は最初の位置のまま値を置き換える
+    let dictionary = Dictionary::parse("a=1, b=2, a=3").unwrap();
+    let keys: Vec<_> = dictionary.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["a", "b"]);
+    assert_eq!(dictionary.to_string(), "a=3, b=2");
+
+    assert!(Dictionary::parse("").unwrap().is_empty());
+}
+
+#[test]
+fn test_parse_dictionary_errors() {
+    let cases = [
+        ("A=1", SfvError::InvalidKey),
+        ("a=1,", SfvError::InvalidSeparator),
+        ("a =1", SfvError::InvalidSeparator),
+        ("a= 1", SfvError::InvalidBareItem),
+        ("a=1 b=2", SfvError::InvalidSeparator),
+        ("1=a", SfvError::InvalidKey),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Dictionary::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_dictionary_builder() {
+    let dictionary = Dictionary::new()
+        .with_member("u", Item::new(BareItem::Integer(3)).unwrap())
+        .unwrap()
+        .with_member(
+            "i",
+            Item::new(BareItem::Boolean(true))
+                .unwrap()
+                .with_param("x", BareItem::Integer(1))
+                .unwrap(),
+        )
+        .unwrap()
+        .with_member("f", Item::new(BareItem::Boolean(false)).unwrap())
+        .unwrap()
+        .with_member("u", Item::new(BareItem::Integer(5)).unwrap())
+        .unwrap();
+    assert_eq!(dictionary.to_string(), "u=5, i;x=1, f=?0");
+    assert_eq!(Dictionary::parse(&dictionary.to_string()), Ok(dictionary));
+
+    assert_eq!(
+        Dictionary::new().with_member("U", InnerList::new()),
+        Err(SfvError::InvalidKey)
+    );
+}
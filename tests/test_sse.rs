@@ -0,0 +1,249 @@
+//! Server-Sent Events のユニットテスト
+
+use shiguredo_http11::sse::{
+    CONTENT_TYPE, DEFAULT_MAX_BUFFER_SIZE, Event, KEEP_ALIVE, SseDecoder, SseError, encode_comment,
+};
+
+fn decode_all(input: &[u8]) -> Vec<Event> {
+    let mut decoder = SseDecoder::new();
+    decoder.feed(input).unwrap();
+    let mut events = Vec::new();
+    while let Some(event) = decoder.next_event().unwrap() {
+        events.push(event);
+    }
+    events
+}
+
+// ========================================
+// SseError のテスト
+// ========================================
+
+#[test]
+fn test_sse_error_display() {
+    let errors = [
+        (SseError::InvalidEventType, "invalid event type"),
+        (SseError::InvalidId, "invalid event id"),
+        (
+            SseError::BufferOverflow {
+                size: 11,
+                limit: 10,
+            },
+            "buffer overflow: size=11, limit=10",
+        ),
+    ];
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// エンコード
+// ========================================
+
+#[test]
+fn test_encode_event() {
+    assert_eq!(CONTENT_TYPE, "text/event-stream");
+    assert_eq!(Event::new("hello").encode(), b"data: hello\n\n");
+    assert_eq!(Event::new("").encode(), b"data: \n\n");
+    assert_eq!(
+        Event::new("a\r\nb\rc\n")
+            .with_event("tick")
+            .unwrap()
+            .with_id("7")
+            .unwrap()
+            .with_retry(3000)
+            .encode(),
+        b"id: 7\nevent: tick\nretry: 3000\ndata: a\ndata: b\ndata: c\ndata: \n\n"
+    );
+}
+
+#[test]
+fn test_encode_invalid_fields() {
+    assert_eq!(
+        Event::new("x").with_event("a\nb"),
+        Err(SseError::InvalidEventType)
+    );
+    for id in ["a\rb", "a\nb", "a\0b"] {
+        assert_eq!(Event::new("x").with_id(id), Err(SseError::InvalidId));
+    }
+    // 空の ID は最後のイベント ID のリセット
+    assert_eq!(
+        Event::new("x").with_id("").unwrap().encode(),
+        b"id: \ndata: x\n\n"
+    );
+}
+
+#[test]
+fn test_encode_comment() {
+    assert_eq!(KEEP_ALIVE, b":\n");
+    assert_eq!(encode_comment("ping"), b": ping\n");
+    assert_eq!(encode_comment(""), b":\n");
+    assert_eq!(encode_comment("a\nb"), b": a\n: b\n");
+
+    let mut stream = encode_comment("ping");
+    stream.extend_from_slice(KEEP_ALIVE);
+    stream.extend_from_slice(&Event::new("x").encode());
+    assert_eq!(decode_all(&stream), vec![Event::new("x")]);
+}
+
+// ========================================
+// デコード
+// ========================================
+
+#[test]
+fn test_decode_fields() {
+    let events = decode_all(
+        b": comment\n\
+          event: add\n\
+          data: 73857293\n\
+          \n\
+          event: remove\n\
+          data: 2153\n\
+          \n\
+          data\n\
+          \n",
+    );
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].event(), Some("add"));
+    assert_eq!(events[0].data(), "73857293");
+    assert_eq!(events[1].event(), Some("remove"));
+    // イベントタイプは引き継がない
+    assert_eq!(events[2].event(), None);
+    assert_eq!(events[2].data(), "");
+}
+
+#[test]
+fn test_decode_multiline_data() {
+    let events = decode_all(b"data: YHOO\ndata: +2\ndata:10\ndata:  indented\n\n");
+    assert_eq!(events[0].data(), "YHOO\n+2\n10\n indented");
+}
+
+#[test]
+fn test_decode_line_endings() {
+    for input in [
+        &b"data: a\r\ndata: b\r\n\r\n"[..],
+        b"data: a\rdata: b\r\r",
+        b"data: a\ndata: b\n\n",
+        b"\xEF\xBB\xBFdata: a\rdata: b\n\r\n",
+    ] {
+        assert_eq!(decode_all(input), vec![Event::new("a\nb")], "{input:?}");
+    }
+}
+
+#[test]
+fn test_decode_id() {
+    let mut decoder = SseDecoder::new().with_last_event_id("0");
+    decoder
+        .feed(b"data: a\n\nid: 1\ndata: b\n\ndata: c\n\nid\ndata: d\n\nid: x\0y\ndata: e\n\n")
+        .unwrap();
+    let ids: Vec<Option<String>> = core::iter::from_fn(|| decoder.next_event().unwrap())
+        .map(|event| event.id().map(str::to_string))
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            Some("0".to_string()),
+            Some("1".to_string()),
+            // ID は後続のイベントに引き継ぐ
+            Some("1".to_string()),
+            // 空の ID でリセットする
+            None,
+            // NUL を含む ID は無視する
+            None,
+        ]
+    );
+    assert_eq!(decoder.last_event_id(), "");
+}
+
+#[test]
+fn test_decode_retry() {
+    let mut decoder = SseDecoder::new();
+    decoder
+        .feed(b"retry: 5000\n\nretry: 1x\ndata: a\n\nretry: 100\ndata: b\n\n")
+        .unwrap();
+    // data のないイベントは返さないが、再接続時間は反映する
+    let event = decoder.next_event().unwrap().unwrap();
+    assert_eq!(event.data(), "a");
+    assert_eq!(event.retry(), None);
+    assert_eq!(decoder.retry(), Some(5000));
+    let event = decoder.next_event().unwrap().unwrap();
+    assert_eq!(event.retry(), Some(100));
+    assert_eq!(decoder.retry(), Some(100));
+}
+
+#[test]
+fn test_decode_ignores_unknown_fields() {
+    let events = decode_all(b"Data: x\nfoo: bar\n\ndata: y\n\n");
+    assert_eq!(events, vec![Event::new("y")]);
+}
+
+#[test]
+fn test_decode_incremental() {
+    let input = Event::new("hello\nworld")
+        .with_event("greeting")
+        .unwrap()
+        .encode();
+    let mut decoder = SseDecoder::new();
+    let mut events = Vec::new();
+    // CR LF が分割される場合も含めて 1 バイトずつ渡す
+    for byte in b"\xEF\xBB\xBF".iter().chain(input.iter()) {
+        decoder.feed(&[*byte]).unwrap();
+        while let Some(event) = decoder.next_event().unwrap() {
+            events.push(event);
+        }
+    }
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].data(), "hello\nworld");
+    assert_eq!(decoder.buffered_len(), 0);
+
+    let mut decoder = SseDecoder::new();
+    for chunk in [&b"data: a\r"[..], b"\ndata: b\r", b"\r"] {
+        decoder.feed(chunk).unwrap();
+    }
+    assert_eq!(decoder.next_event().unwrap(), Some(Event::new("a\nb")));
+}
+
+#[test]
+fn test_decode_incomplete_event() {
+    let mut decoder = SseDecoder::new();
+    decoder.feed(b"data: a\n").unwrap();
+    assert_eq!(decoder.next_event().unwrap(), None);
+    decoder.feed(b"\n").unwrap();
+    assert_eq!(decoder.next_event().unwrap(), Some(Event::new("a")));
+}
+
+#[test]
+fn test_decode_invalid_utf8() {
+    let events = decode_all(b"data: \xFFa\n\n");
+    assert_eq!(events[0].data(), "\u{FFFD}a");
+}
+
+#[test]
+fn test_decode_buffer_overflow() {
+    let mut decoder = SseDecoder::new();
+    assert!(decoder.feed(&vec![b'a'; DEFAULT_MAX_BUFFER_SIZE]).is_ok());
+
+    let mut decoder = SseDecoder::new().with_max_buffer_size(10);
+    assert_eq!(
+        decoder.feed(b"data: 0123456789"),
+        Err(SseError::BufferOverflow {
+            size: 16,
+            limit: 10
+        })
+    );
+
+    // 行ごとに渡しても確定前のデータが上限を超える
+    let mut decoder = SseDecoder::new().with_max_buffer_size(10);
+    for line in [&b"data:0123\n"[..], b"data:4567\n"] {
+        decoder.feed(line).unwrap();
+        assert_eq!(decoder.next_event().unwrap(), None);
+    }
+    decoder.feed(b"data:89ab\n").unwrap();
+    assert_eq!(
+        decoder.next_event(),
+        Err(SseError::BufferOverflow {
+            size: 14,
+            limit: 10
+        })
+    );
+}
@@ -0,0 +1,343 @@
+//! 静的コンテンツのレスポンス決定のユニットテスト
+
+use shiguredo_http11::content_type::ContentType;
+use shiguredo_http11::date::HttpDate;
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::static_content::{PlanStatus, StaticResource};
+use shiguredo_http11::{RequestDecoder, RequestHead};
+
+const LAST_MODIFIED: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+fn request(method: &str, headers: &[(&str, &str)]) -> RequestHead {
+    let mut input = format!("{method} /file HTTP/1.1\r\nHost: example.com\r\n");
+    for (name, value) in headers {
+        input.push_str(&format!("{name}: {value}\r\n"));
+    }
+    input.push_str("\r\n");
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(input.as_bytes()).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+fn resource() -> StaticResource {
+    StaticResource::new(1000)
+        .with_etag(EntityTag::strong("v1").unwrap())
+        .with_last_modified(HttpDate::parse(LAST_MODIFIED).unwrap())
+}
+
+fn plan(method: &str, headers: &[(&str, &str)]) -> PlanStatus {
+    resource().plan(&request(method, headers), 0).status()
+}
+
+// ========================================
+// 200
+// ========================================
+
+#[test]
+fn test_plan_full() {
+    let resource = resource().with_content_type(ContentType::new("text", "plain"));
+    let plan = resource.plan(&request("GET", &[]), 0);
+    assert_eq!(plan.status(), PlanStatus::Ok);
+    assert_eq!(plan.status_code(), 200);
+    assert!(plan.has_body());
+    assert!(plan.ranges().is_empty());
+    assert_eq!(
+        plan.headers(),
+        &[
+            ("Content-Type".to_string(), "text/plain".to_string()),
+            ("Content-Length".to_string(), "1000".to_string()),
+            ("Accept-Ranges".to_string(), "bytes".to_string()),
+            ("ETag".to_string(), "\"v1\"".to_string()),
+            ("Last-Modified".to_string(), LAST_MODIFIED.to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_plan_head() {
+    let plan = resource().plan(&request("HEAD", &[("Range", "bytes=0-99")]), 0);
+    // HEAD では Range を無視する
+    assert_eq!(plan.status(), PlanStatus::Ok);
+    assert!(!plan.has_body());
+    assert_eq!(plan.get_header("Content-Length"), Some("1000"));
+
+    let encoded = plan.to_response().unwrap().encode().unwrap();
+    assert!(encoded.starts_with(b"HTTP/1.1 200 OK\r\n"));
+    assert!(encoded.ends_with(b"\r\n\r\n"));
+}
+
+// ========================================
+// If-Match / If-Unmodified-Since
+// ========================================
+
+#[test]
+fn test_plan_if_match() {
+    assert_eq!(
+        plan("PUT", &[("If-Match", "\"v0\", \"v1\"")]),
+        PlanStatus::Ok
+    );
+    assert_eq!(plan("PUT", &[("If-Match", "*")]), PlanStatus::Ok);
+    // Strong 比較
+    assert_eq!(
+        plan("PUT", &[("If-Match", "W/\"v1\"")]),
+        PlanStatus::PreconditionFailed
+    );
+    assert_eq!(
+        plan("GET", &[("If-Match", "\"v2\"")]),
+        PlanStatus::PreconditionFailed
+    );
+    // 不正な値は一致しない
+    assert_eq!(
+        plan("GET", &[("If-Match", "v1")]),
+        PlanStatus::PreconditionFailed
+    );
+
+    // ETag のない表現は `*` のみ一致する
+    let resource = StaticResource::new(10);
+    assert_eq!(
+        resource
+            .plan(&request("GET", &[("If-Match", "*")]), 0)
+            .status(),
+        PlanStatus::Ok
+    );
+    assert_eq!(
+        resource
+            .plan(&request("GET", &[("If-Match", "\"v1\"")]), 0)
+            .status(),
+        PlanStatus::PreconditionFailed
+    );
+}
+
+#[test]
+fn test_plan_if_unmodified_since() {
+    assert_eq!(
+        plan("PUT", &[("If-Unmodified-Since", LAST_MODIFIED)]),
+        PlanStatus::Ok
+    );
+    assert_eq!(
+        plan(
+            "PUT",
+            &[("If-Unmodified-Since", "Sun, 06 Nov 1994 08:49:36 GMT")]
+        ),
+        PlanStatus::PreconditionFailed
+    );
+    // If-Match がある場合は評価しない
+    assert_eq!(
+        plan(
+            "PUT",
+            &[
+                ("If-Match", "\"v1\""),
+                ("If-Unmodified-Since", "Sun, 06 Nov 1994 08:49:36 GMT"),
+            ]
+        ),
+        PlanStatus::Ok
+    );
+    // 不正な日時は無視する
+    assert_eq!(
+        plan("PUT", &[("If-Unmodified-Since", "yesterday")]),
+        PlanStatus::Ok
+    );
+
+    let plan = resource().plan(&request("PUT", &[("If-Match", "\"v2\"")]), 0);
+    assert!(plan.headers().is_empty());
+    assert!(!plan.has_body());
+    assert_eq!(
+        plan.to_response().unwrap().encode().unwrap(),
+        b"HTTP/1.1 412 Precondition Failed\r\nContent-Length: 0\r\n\r\n"
+    );
+}
+
+// ========================================
+// If-None-Match / If-Modified-Since
+// ========================================
+
+#[test]
+fn test_plan_if_none_match() {
+    // Weak 比較
+    for value in ["\"v1\"", "W/\"v1\"", "\"v0\", \"v1\"", "*"] {
+        assert_eq!(
+            plan("GET", &[("If-None-Match", value)]),
+            PlanStatus::NotModified,
+            "{value}"
+        );
+        assert_eq!(
+            plan("HEAD", &[("If-None-Match", value)]),
+            PlanStatus::NotModified,
+            "{value}"
+        );
+        assert_eq!(
+            plan("PUT", &[("If-None-Match", value)]),
+            PlanStatus::PreconditionFailed,
+            "{value}"
+        );
+    }
+    assert_eq!(plan("GET", &[("If-None-Match", "\"v2\"")]), PlanStatus::Ok);
+    // 不正な値は無視する
+    assert_eq!(plan("GET", &[("If-None-Match", "v1")]), PlanStatus::Ok);
+
+    let plan = resource().plan(&request("GET", &[("If-None-Match", "\"v1\"")]), 0);
+    assert_eq!(plan.status_code(), 304);
+    assert!(!plan.has_body());
+    assert_eq!(
+        plan.headers(),
+        &[("ETag".to_string(), "\"v1\"".to_string())]
+    );
+}
+
+#[test]
+fn test_plan_if_modified_since() {
+    assert_eq!(
+        plan("GET", &[("If-Modified-Since", LAST_MODIFIED)]),
+        PlanStatus::NotModified
+    );
+    assert_eq!(
+        plan(
+            "GET",
+            &[("If-Modified-Since", "Sun, 06 Nov 1994 08:49:36 GMT")]
+        ),
+        PlanStatus::Ok
+    );
+    // GET / HEAD 以外では評価しない
+    assert_eq!(
+        plan("PUT", &[("If-Modified-Since", LAST_MODIFIED)]),
+        PlanStatus::Ok
+    );
+    // If-None-Match がある場合は評価しない
+    assert_eq!(
+        plan(
+            "GET",
+            &[
+                ("If-None-Match", "\"v2\""),
+                ("If-Modified-Since", LAST_MODIFIED),
+            ]
+        ),
+        PlanStatus::Ok
+    );
+    // 複数行ある場合は無視する
+    assert_eq!(
+        plan(
+            "GET",
+            &[
+                ("If-Modified-Since", LAST_MODIFIED),
+                ("If-Modified-Since", LAST_MODIFIED),
+            ]
+        ),
+        PlanStatus::Ok
+    );
+
+    // ETag がない場合は Last-Modified を付与する
+    let resource =
+        StaticResource::new(10).with_last_modified(HttpDate::parse(LAST_MODIFIED).unwrap());
+    let plan = resource.plan(&request("GET", &[("If-Modified-Since", LAST_MODIFIED)]), 0);
+    assert_eq!(plan.status(), PlanStatus::NotModified);
+    assert_eq!(
+        plan.headers(),
+        &[("Last-Modified".to_string(), LAST_MODIFIED.to_string())]
+    );
+}
+
+// ========================================
+// Range
+// ========================================
+
+#[test]
+fn test_plan_single_range() {
+    let resource = resource().with_content_type(ContentType::new("text", "plain"));
+    let plan = resource.plan(&request("GET", &[("Range", "bytes=-100")]), 0);
+    assert_eq!(plan.status(), PlanStatus::PartialContent);
+    assert_eq!(plan.status_code(), 206);
+    assert!(plan.has_body());
+    assert!(!plan.is_multipart());
+    assert!(plan.byte_ranges_builder().is_none());
+    assert_eq!(plan.ranges(), &[(900, 999)]);
+    assert_eq!(plan.get_header("Content-Type"), Some("text/plain"));
+    assert_eq!(plan.get_header("Content-Length"), Some("100"));
+    assert_eq!(plan.get_header("Content-Range"), Some("bytes 900-999/1000"));
+    assert_eq!(plan.get_header("ETag"), Some("\"v1\""));
+}
+
+#[test]
+fn test_plan_multiple_ranges() {
+    let content: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    let resource = resource().with_content_type(ContentType::new("text", "plain"));
+    let plan = resource.plan(
+        &request("GET", &[("Range", "bytes=500-599, 0-9, 5-19")]),
+        42,
+    );
+    assert_eq!(plan.status(), PlanStatus::PartialContent);
+    assert!(plan.is_multipart());
+    // 昇順に並べて結合する
+    assert_eq!(plan.ranges(), &[(0, 19), (500, 599)]);
+    assert_eq!(plan.get_header("Content-Range"), None);
+
+    let mut builder = plan.byte_ranges_builder().unwrap();
+    assert_eq!(
+        plan.get_header("Content-Type"),
+        Some(builder.content_type().as_str())
+    );
+    for &(start, end) in plan.ranges() {
+        builder = builder.part(start, end, &content[start as usize..=end as usize]);
+    }
+    let body = builder.build();
+    assert_eq!(
+        plan.get_header("Content-Length"),
+        Some(body.len().to_string().as_str())
+    );
+    assert!(body.windows(12).any(|w| w == b"Content-Type"));
+
+    let response = plan.to_response().unwrap().body(body);
+    assert!(response.encode().is_ok());
+}
+
+#[test]
+fn test_plan_range_not_satisfiable() {
+    let plan = resource().plan(&request("GET", &[("Range", "bytes=1000-")]), 0);
+    assert_eq!(plan.status(), PlanStatus::RangeNotSatisfiable);
+    assert_eq!(plan.status_code(), 416);
+    assert!(!plan.has_body());
+    assert!(plan.ranges().is_empty());
+    assert_eq!(plan.get_header("Content-Range"), Some("bytes */1000"));
+    assert_eq!(plan.get_header("Content-Length"), None);
+    assert!(plan.to_response().unwrap().encode().is_ok());
+}
+
+#[test]
+fn test_plan_range_ignored() {
+    for headers in [
+        &[("Range", "bytes=abc")][..],
+        &[("Range", "items=0-9")],
+        &[("Range", "bytes=0-9"), ("Range", "bytes=10-19")],
+    ] {
+        assert_eq!(plan("GET", headers), PlanStatus::Ok, "{headers:?}");
+    }
+    // GET 以外では無視する
+    assert_eq!(plan("POST", &[("Range", "bytes=0-9")]), PlanStatus::Ok);
+    // 条件が優先される
+    assert_eq!(
+        plan(
+            "GET",
+            &[("Range", "bytes=0-9"), ("If-None-Match", "\"v1\"")]
+        ),
+        PlanStatus::NotModified
+    );
+}
+
+#[test]
+fn test_plan_if_range() {
+    for if_range in ["\"v1\"", LAST_MODIFIED] {
+        assert_eq!(
+            plan("GET", &[("Range", "bytes=0-9"), ("If-Range", if_range)]),
+            PlanStatus::PartialContent,
+            "{if_range}"
+        );
+    }
+    for if_range in ["\"v2\"", "W/\"v1\"", "Sun, 06 Nov 1994 08:49:38 GMT", "?"] {
+        assert_eq!(
+            plan("GET", &[("Range", "bytes=0-9"), ("If-Range", if_range)]),
+            PlanStatus::Ok,
+            "{if_range}"
+        );
+    }
+    // Range のないリクエストの If-Range は無視する
+    assert_eq!(plan("GET", &[("If-Range", "\"v2\"")]), PlanStatus::Ok);
+}
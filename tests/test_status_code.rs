@@ -5,7 +5,8 @@
 //! ラウンドトリップ系の検証は PBT (`prop_response.rs`) に任せ、
 //! ここでは const 値そのものの不変条件と境界値を担保する。
 
-use shiguredo_http11::{StatusClass, StatusCode};
+use shiguredo_http11::status_code::{StatusCodeError, reason_phrase_for};
+use shiguredo_http11::{Response, StatusClass, StatusCode};
 
 // 全 const 定数の網羅リスト
 // 追加・変更時は本リストにも反映する。
@@ -260,3 +261,69 @@ fn test_status_code_from_code_roundtrip_for_all_constants() {
         );
     }
 }
+
+#[test]
+fn test_reason_phrase_for() {
+    assert_eq!(reason_phrase_for(200), "OK");
+    assert_eq!(reason_phrase_for(404), "Not Found");
+    assert_eq!(reason_phrase_for(418), "I'm a teapot");
+    // 未登録コード / 範囲外は空文字列
+    assert_eq!(reason_phrase_for(299), "");
+    assert_eq!(reason_phrase_for(0), "");
+    assert_eq!(reason_phrase_for(1000), "");
+}
+
+#[test]
+fn test_response_from_status_unknown_code_has_empty_reason() {
+    let res = Response::from_status(299).unwrap();
+    // RFC 9112 Section 4: reason-phrase は空でもよいが、直前の SP は省略しない
+    assert_eq!(res.encode().unwrap(), b"HTTP/1.1 299 \r\n\r\n");
+}
+
+#[test]
+fn test_status_code_categorization_helpers() {
+    assert!(StatusCode::CONTINUE.is_informational());
+    assert!(StatusCode::NO_CONTENT.is_success());
+    assert!(StatusCode::FOUND.is_redirection());
+    assert!(StatusCode::NOT_FOUND.is_client_error());
+    assert!(StatusCode::BAD_GATEWAY.is_server_error());
+    assert!(!StatusCode::OK.is_client_error());
+}
+
+#[test]
+fn test_status_code_from_str_and_display() {
+    assert_eq!("404".parse::<StatusCode>().unwrap(), StatusCode::NOT_FOUND);
+    assert_eq!(StatusCode::NOT_FOUND.to_string(), "404 Not Found");
+    assert_eq!(
+        "299".parse::<StatusCode>(),
+        Err(StatusCodeError::Unregistered { code: 299 })
+    );
+    for s in ["", "40", "4040", "+40", "abc", " 404"] {
+        assert_eq!(
+            s.parse::<StatusCode>(),
+            Err(StatusCodeError::InvalidFormat),
+            "{s:?}"
+        );
+    }
+    assert_eq!(
+        StatusCodeError::Unregistered { code: 299 }.to_string(),
+        "unregistered status code: 299"
+    );
+}
+
+#[test]
+fn test_status_code_compare_with_u16() {
+    let res = Response::with_status(StatusCode::NOT_FOUND);
+    assert!(res.status_code() == StatusCode::NOT_FOUND);
+    assert!(StatusCode::NOT_FOUND == 404);
+    assert_eq!(u16::from(StatusCode::OK), 200);
+}
+
+#[test]
+fn test_response_from_status_unknown_informational_code() {
+    // 未登録の 1xx も空の reason-phrase でエンコードできる
+    let res = Response::from_status(104).unwrap();
+    assert_eq!(res.status_code(), 104);
+    assert_eq!(res.reason_phrase(), "");
+    assert_eq!(res.encode().unwrap(), b"HTTP/1.1 104 \r\n\r\n");
+}
@@ -0,0 +1,145 @@
+//! TE ヘッダーのユニットテスト
+
+use shiguredo_http11::accept::QValue;
+use shiguredo_http11::te::{Te, TeError};
+use shiguredo_http11::trailer::accepts_trailers;
+use shiguredo_http11::{Request, RequestDecoder};
+
+// ========================================
+// TeError のテスト
+// ========================================
+
+#[test]
+fn test_te_error_display() {
+    let errors = [
+        (TeError::InvalidFormat, "invalid TE header format"),
+        (TeError::InvalidCoding, "invalid transfer coding"),
+        (TeError::InvalidParameter, "invalid transfer parameter"),
+        (TeError::InvalidQValue, "invalid qvalue"),
+        (TeError::UnterminatedQuote, "unterminated quoted-string"),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_te_parse_rfc9110_examples() {
+    // RFC 9110 Section 10.1.4
+    let te = Te::parse("deflate").unwrap();
+    assert!(!te.accepts_trailers());
+    assert_eq!(te.codings().len(), 1);
+    assert_eq!(te.codings()[0].coding(), "deflate");
+    assert_eq!(te.codings()[0].qvalue().value(), 1000);
+
+    let te = Te::parse("").unwrap();
+    assert!(!te.accepts_trailers());
+    assert!(te.codings().is_empty());
+
+    let te = Te::parse("trailers, deflate;q=0.5").unwrap();
+    assert!(te.accepts_trailers());
+    assert_eq!(te.codings()[0].qvalue().value(), 500);
+    assert_eq!(te.to_string(), "trailers, deflate; q=0.5");
+}
+
+#[test]
+fn test_te_parse_params_and_case() {
+    let te = Te::parse(" , GZIP ; Level = \"9 x\" ; Q = 0.25 ,, TRAILERS ").unwrap();
+    assert!(te.accepts_trailers());
+    let gzip = &te.codings()[0];
+    assert_eq!(gzip.coding(), "gzip");
+    assert_eq!(gzip.params(), [("level".to_string(), "9 x".to_string())]);
+    assert_eq!(gzip.qvalue().value(), 250);
+    assert_eq!(te.to_string(), "trailers, gzip; level=\"9 x\"; q=0.25");
+    assert_eq!(Te::parse(&te.to_string()), Ok(te));
+}
+
+#[test]
+fn test_te_parse_errors() {
+    let cases = [
+        ("trailers;q=0.5", TeError::InvalidFormat),
+        (";q=1", TeError::InvalidFormat),
+        ("gz ip", TeError::InvalidCoding),
+        ("gzip;q=2", TeError::InvalidQValue),
+        ("gzip;q=0.5;q=0.5", TeError::InvalidParameter),
+        ("gzip;q=0.5;a=b", TeError::InvalidParameter),
+        ("gzip;a", TeError::InvalidParameter),
+        ("gzip;a=b c", TeError::InvalidParameter),
+        ("gzip;a=\"b", TeError::UnterminatedQuote),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Te::parse(input), Err(expected), "{input}");
+    }
+}
+
+// ========================================
+// 判定とネゴシエーションのテスト
+// ========================================
+
+#[test]
+fn test_te_quality_and_negotiate() {
+    let te = Te::parse("gzip;q=0.5, deflate;q=0, br").unwrap();
+    assert_eq!(te.quality("GZIP").map(|q| q.value()), Some(500));
+    assert_eq!(te.quality("compress"), None);
+    // chunked は常に受理される
+    assert_eq!(te.quality("chunked").map(|q| q.value()), Some(1000));
+    assert!(te.accepts("chunked"));
+    assert!(!te.accepts("deflate"));
+    assert!(!te.accepts("compress"));
+
+    assert_eq!(te.negotiate(&["gzip", "br"]), Some("br"));
+    assert_eq!(te.negotiate(&["deflate", "gzip"]), Some("gzip"));
+    assert_eq!(te.negotiate(&["deflate", "compress"]), None);
+    assert_eq!(Te::new().negotiate(&["gzip"]), None);
+}
+
+#[test]
+fn test_te_from_request() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: example.com\r\nTE: gzip;q=0.5\r\nTE: trailers\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    let te = Te::from_request(&head).unwrap().unwrap();
+    assert!(te.accepts_trailers());
+    assert!(te.accepts("gzip"));
+    assert_eq!(te.accepts_trailers(), accepts_trailers(&head));
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(Te::from_request(&request), Ok(None));
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_te_builder() {
+    let te = Te::new()
+        .with_coding("gzip", QValue::parse("0.8").unwrap())
+        .unwrap()
+        .with_trailers();
+    assert_eq!(te.to_string(), "trailers, gzip; q=0.8");
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("TE", te.to_string())
+        .unwrap();
+    assert_eq!(request.get_typed::<Te>(), Some(Ok(te)));
+
+    let q = QValue::default();
+    assert_eq!(
+        Te::new().with_coding("chunked", q),
+        Err(TeError::InvalidCoding)
+    );
+    assert_eq!(
+        Te::new().with_coding("Trailers", q),
+        Err(TeError::InvalidCoding)
+    );
+    assert_eq!(Te::new().with_coding("a b", q), Err(TeError::InvalidCoding));
+}
@@ -0,0 +1,229 @@
+//! ワイヤーレベルのトレースのユニットテスト
+
+use std::sync::{Arc, Mutex};
+
+use shiguredo_http11::trace::{WireEvent, WireObserver};
+use shiguredo_http11::{
+    BodyProgress, NonUtf8Policy, Request, RequestDecoder, RequestEncoder, Response,
+    ResponseDecoder, ResponseEncoder, StatusCode, encode_request, encode_response_headers,
+};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// イベントを文字列として記録する observer を作る
+fn recorder() -> (Log, Box<dyn WireObserver>) {
+    let log = Log::default();
+    let captured = log.clone();
+    let observer = Box::new(move |event: WireEvent<'_>| {
+        let entry = match event {
+            WireEvent::StartLine { offset, raw } => {
+                format!("start-line {offset} {}", String::from_utf8_lossy(raw))
+            }
+            WireEvent::Header {
+                offset,
+                name,
+                value,
+                raw,
+            } => format!(
+                "header {offset} {name}={value} {}",
+                String::from_utf8_lossy(raw)
+            ),
+            WireEvent::Chunk {
+                offset,
+                size,
+                ext,
+                raw,
+            } => format!(
+                "chunk {offset} {size} {} {}",
+                String::from_utf8_lossy(ext),
+                String::from_utf8_lossy(raw)
+            ),
+            WireEvent::Trailer {
+                offset,
+                name,
+                value,
+                raw,
+            } => format!(
+                "trailer {offset} {name}={value} {}",
+                String::from_utf8_lossy(raw)
+            ),
+            WireEvent::Encoded { raw } => format!("encoded {}", raw.len()),
+            _ => unreachable!(),
+        };
+        captured.lock().unwrap().push(entry);
+    });
+    (log, observer)
+}
+
+fn entries(log: &Log) -> Vec<String> {
+    log.lock().unwrap().clone()
+}
+
+// ========================================
+// デコーダー
+// ========================================
+
+#[test]
+fn test_trace_request_decoder() {
+    let (log, observer) = recorder();
+    let mut decoder = RequestDecoder::new();
+    decoder.set_wire_observer(observer);
+    decoder
+        .feed(b"GET /a HTTP/1.1\r\nHost: example.com\r\nX-Test:  v  \r\n\r\n")
+        .unwrap();
+    decoder.decode().unwrap().unwrap();
+    assert_eq!(
+        entries(&log),
+        [
+            "start-line 0 GET /a HTTP/1.1",
+            "header 17 Host=example.com Host: example.com",
+            "header 36 X-Test=v X-Test:  v  ",
+        ]
+    );
+}
+
+#[test]
+fn test_trace_response_chunked() {
+    let (log, observer) = recorder();
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_wire_observer(observer);
+    let message = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: X-Sum\r\n\r\n\
+                    3;a=1\r\nabc\r\n0\r\nX-Sum: 1\r\n\r\n";
+    // 1 バイトずつ投入しても行単位で 1 回ずつ通知する
+    let mut response = None;
+    for byte in message {
+        decoder.feed(&[*byte]).unwrap();
+        if let Some(decoded) = decoder.decode().unwrap() {
+            response = Some(decoded);
+        }
+    }
+    assert_eq!(response.unwrap().body_bytes(), Some(&b"abc"[..]));
+    assert_eq!(
+        entries(&log),
+        [
+            "start-line 0 HTTP/1.1 200 OK",
+            "header 17 Transfer-Encoding=chunked Transfer-Encoding: chunked",
+            "header 45 Trailer=X-Sum Trailer: X-Sum",
+            "chunk 63 3 ;a=1 3;a=1",
+            "chunk 75 0  0",
+            "trailer 78 X-Sum=1 X-Sum: 1",
+        ]
+    );
+}
+
+#[test]
+fn test_trace_streaming_api() {
+    let (log, observer) = recorder();
+    let mut decoder = RequestDecoder::new();
+    decoder.set_wire_observer(observer);
+    decoder
+        .feed(
+            b"POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhi\r\n0\r\n\r\n",
+        )
+        .unwrap();
+    decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(entries(&log).len(), 3);
+
+    let mut dst = [0u8; 16];
+    decoder.read_body(&mut dst).unwrap();
+    let entries = entries(&log);
+    assert_eq!(entries[3], "chunk 56 2  2");
+    assert_eq!(entries[4], "chunk 63 0  0");
+    assert!(matches!(
+        decoder.progress().unwrap(),
+        BodyProgress::Complete { .. }
+    ));
+}
+
+#[test]
+fn test_trace_non_utf8_raw() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let captured = log.clone();
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_non_utf8_policy(NonUtf8Policy::Replace);
+    decoder.set_wire_observer(Box::new(move |event: WireEvent<'_>| {
+        if let WireEvent::Header { value, raw, .. } = event {
+            captured
+                .lock()
+                .unwrap()
+                .push((value.to_string(), raw.to_vec()));
+        }
+    }));
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nX-Raw: \xFF\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    decoder.decode().unwrap().unwrap();
+    // value は変換後、raw は受信したバイト列
+    assert_eq!(
+        log.lock().unwrap()[0],
+        ("\u{FFFD}".to_string(), b"X-Raw: \xFF".to_vec())
+    );
+}
+
+#[test]
+fn test_trace_not_emitted_on_error() {
+    let (log, observer) = recorder();
+    let mut decoder = RequestDecoder::new();
+    decoder.set_wire_observer(observer);
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: a\r\nBad Header\r\n\r\n")
+        .unwrap();
+    assert!(decoder.decode_headers().is_err());
+    assert_eq!(entries(&log).len(), 2);
+}
+
+#[test]
+fn test_trace_observer_lifecycle() {
+    let (log, observer) = recorder();
+    let mut decoder = RequestDecoder::new();
+    decoder.set_wire_observer(observer);
+    decoder.reset();
+    decoder.feed(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+    decoder.decode().unwrap().unwrap();
+    // reset() 後も observer を維持する
+    assert_eq!(entries(&log).len(), 2);
+
+    assert!(decoder.take_wire_observer().is_some());
+    assert!(decoder.take_wire_observer().is_none());
+    decoder.feed(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n").unwrap();
+    decoder.decode().unwrap().unwrap();
+    assert_eq!(entries(&log).len(), 2);
+}
+
+// ========================================
+// エンコーダー
+// ========================================
+
+#[test]
+fn test_trace_encoder() {
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    let (log, observer) = recorder();
+    let mut encoder = RequestEncoder::new();
+    encoder.set_wire_observer(observer);
+    let encoded = encoder.encode(&request).unwrap();
+    assert_eq!(encoded, encode_request(&request).unwrap());
+    assert_eq!(entries(&log), [format!("encoded {}", encoded.len())]);
+
+    let response = Response::with_status(StatusCode::OK)
+        .header("Transfer-Encoding", "chunked")
+        .unwrap();
+    let (log, observer) = recorder();
+    let mut encoder = ResponseEncoder::new();
+    encoder.set_wire_observer(observer);
+    let encoded = encoder.encode_headers(&response).unwrap();
+    assert_eq!(encoded, encode_response_headers(&response).unwrap());
+    assert_eq!(entries(&log), [format!("encoded {}", encoded.len())]);
+
+    // エンコードに失敗した場合は通知しない
+    let invalid = Response::with_status(StatusCode::OK)
+        .header("Content-Length", "1")
+        .unwrap()
+        .header("Transfer-Encoding", "chunked")
+        .unwrap();
+    assert!(encoder.encode(&invalid).is_err());
+    assert_eq!(entries(&log).len(), 1);
+    assert!(encoder.take_wire_observer().is_some());
+}
@@ -1,6 +1,11 @@
 //! Trailer ヘッダーのユニットテスト
 
-use shiguredo_http11::trailer::{Trailer, TrailerError, is_prohibited_trailer_field};
+use std::borrow::Cow;
+
+use shiguredo_http11::trailer::{
+    Trailer, TrailerError, accepts_trailers, is_prohibited_trailer_field,
+};
+use shiguredo_http11::{Request, RequestDecoder, Response, StatusCode};
 
 #[test]
 fn parse_invalid() {
@@ -198,3 +203,115 @@ fn is_prohibited_trailer_field_function() {
     assert!(!is_prohibited_trailer_field("X-Custom"));
     assert!(!is_prohibited_trailer_field("X-Checksum"));
 }
+
+#[test]
+fn error_display() {
+    assert_eq!(
+        TrailerError::ProhibitedField("host".to_string()).to_string(),
+        "prohibited trailer field: host"
+    );
+    assert_eq!(
+        TrailerError::UndeclaredField("x-other".to_string()).to_string(),
+        "undeclared trailer field: x-other"
+    );
+}
+
+#[test]
+fn builder() {
+    let trailer = Trailer::new()
+        .with_field("X-Checksum")
+        .unwrap()
+        .with_field("Server-Timing")
+        .unwrap()
+        .with_field("x-checksum")
+        .unwrap();
+    assert_eq!(trailer.fields(), &["x-checksum", "server-timing"]);
+    assert_eq!(trailer.to_string(), "x-checksum, server-timing");
+    assert!(trailer.contains("X-CHECKSUM"));
+    assert!(!trailer.contains("X-Other"));
+    assert!(Trailer::default().fields().is_empty());
+}
+
+#[test]
+fn builder_rejects_invalid_fields() {
+    assert_eq!(
+        Trailer::new().with_field("Content-Length").unwrap_err(),
+        TrailerError::ProhibitedField("content-length".to_string())
+    );
+    assert_eq!(
+        Trailer::new().with_field("bad name").unwrap_err(),
+        TrailerError::InvalidFieldName
+    );
+    assert_eq!(
+        Trailer::new().with_field("").unwrap_err(),
+        TrailerError::InvalidFieldName
+    );
+}
+
+#[test]
+fn builder_sets_trailer_header() {
+    let trailer = Trailer::new().with_field("X-Checksum").unwrap();
+    let mut response = Response::with_status(StatusCode::OK);
+    response.set_typed(&trailer).unwrap();
+    assert_eq!(response.get_header("Trailer"), Some("x-checksum"));
+    assert_eq!(response.get_typed::<Trailer>(), Some(Ok(trailer)));
+}
+
+#[test]
+fn validate_fields() {
+    let trailer = Trailer::parse("X-Checksum, X-Trace-Id").unwrap();
+    let ok = [
+        (Cow::Borrowed("x-checksum"), "abc".to_string()),
+        (Cow::Borrowed("X-Trace-Id"), "1".to_string()),
+    ];
+    assert!(trailer.validate_fields(&ok).is_ok());
+    assert!(trailer.validate_fields(&[]).is_ok());
+
+    let undeclared = [(Cow::Borrowed("X-Other"), "1".to_string())];
+    assert_eq!(
+        trailer.validate_fields(&undeclared),
+        Err(TrailerError::UndeclaredField("x-other".to_string()))
+    );
+
+    // 申告していても禁止フィールドは送れない
+    let prohibited = [(Cow::Borrowed("Content-Type"), "text/plain".to_string())];
+    assert_eq!(
+        trailer.validate_fields(&prohibited),
+        Err(TrailerError::ProhibitedField("content-type".to_string()))
+    );
+}
+
+// ========================================
+// TE: trailers のテスト
+// ========================================
+
+fn request_with_te(values: &[&str]) -> Request {
+    let mut request = Request::new("GET", "/").unwrap();
+    for value in values {
+        request = request.header("TE", *value).unwrap();
+    }
+    request
+}
+
+/// RFC 9110 Section 10.1.4: TE の "trailers" はトレーラーを受け付けることを示す
+#[test]
+fn accepts_trailers_te() {
+    assert!(accepts_trailers(&request_with_te(&["trailers"])));
+    assert!(accepts_trailers(&request_with_te(&[
+        "gzip;q=0.5, Trailers"
+    ])));
+    assert!(accepts_trailers(&request_with_te(&["gzip", " trailers "])));
+    assert!(!accepts_trailers(&request_with_te(&[])));
+    assert!(!accepts_trailers(&request_with_te(&["gzip, deflate"])));
+    assert!(!accepts_trailers(&request_with_te(&["x-trailers"])));
+}
+
+#[test]
+fn accepts_trailers_request_head() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: example.com\r\nTE: trailers\r\nConnection: TE\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(accepts_trailers(&head));
+}
@@ -0,0 +1,134 @@
+//! 型付きヘッダーのユニットテスト
+
+use shiguredo_http11::accept::Accept;
+use shiguredo_http11::cache::{Age, CacheControl};
+use shiguredo_http11::content_type::{ContentType, ContentTypeError};
+use shiguredo_http11::etag::EntityTag;
+use shiguredo_http11::host::Host;
+use shiguredo_http11::range::Range;
+use shiguredo_http11::vary::Vary;
+use shiguredo_http11::{
+    HttpHead, Request, RequestDecoder, Response, ResponseDecoder, StatusCode, TypedHeader,
+};
+
+// ========================================
+// TypedHeader トレイト
+// ========================================
+
+#[test]
+fn test_typed_header_names() {
+    assert_eq!(ContentType::NAME, "Content-Type");
+    assert_eq!(CacheControl::NAME, "Cache-Control");
+    assert_eq!(EntityTag::NAME, "ETag");
+    assert_eq!(Range::NAME, "Range");
+    assert_eq!(Age::NAME, "Age");
+}
+
+#[test]
+fn test_typed_header_parse_and_encode_value() {
+    let ct = ContentType::parse_value("text/html; charset=utf-8").unwrap();
+    assert_eq!(ct.encode_value(), ct.to_string());
+    assert_eq!(ContentType::parse_value(""), Err(ContentTypeError::Empty));
+}
+
+// ========================================
+// get_typed
+// ========================================
+
+#[test]
+fn test_get_typed_missing_header() {
+    let response = Response::with_status(StatusCode::OK);
+    assert!(response.get_typed::<ContentType>().is_none());
+}
+
+#[test]
+fn test_get_typed_parse_error() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Content-Type", "not a media type")
+        .unwrap();
+    assert!(matches!(response.get_typed::<ContentType>(), Some(Err(_))));
+}
+
+#[test]
+fn test_get_typed_from_decoded_request_head() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: example.com:8080\r\nRange: bytes=0-99\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+    let host = head.get_typed::<Host>().unwrap().unwrap();
+    assert_eq!(host.host(), "example.com");
+    assert_eq!(host.port(), Some(8080));
+    assert!(head.get_typed::<Range>().unwrap().is_ok());
+}
+
+#[test]
+fn test_get_typed_combines_list_field_lines() {
+    // RFC 9110 Section 5.3: 複数行は ", " で結合して 1 つの値として扱う
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(
+            b"HTTP/1.1 200 OK\r\nCache-Control: no-cache\r\nCache-Control: max-age=60\r\n\
+Vary: Accept\r\nVary: Accept-Encoding\r\nContent-Length: 0\r\n\r\n",
+        )
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+
+    let cc = head.get_typed::<CacheControl>().unwrap().unwrap();
+    assert!(cc.is_no_cache());
+    assert_eq!(cc.max_age(), Some(60));
+
+    let vary = head.get_typed::<Vary>().unwrap().unwrap();
+    assert_eq!(vary.fields(), ["accept", "accept-encoding"]);
+}
+
+#[test]
+fn test_get_typed_rejects_duplicated_singleton() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Content-Type", "text/plain")
+        .unwrap()
+        .header("Content-Type", "text/html")
+        .unwrap();
+    assert!(matches!(response.get_typed::<ContentType>(), Some(Err(_))));
+}
+
+// ========================================
+// set_typed
+// ========================================
+
+#[test]
+fn test_set_typed_replaces_existing_header() {
+    let mut response = Response::with_status(StatusCode::OK)
+        .header("cache-control", "no-cache")
+        .unwrap();
+    let cc = CacheControl::new().with_max_age(3600).with_public();
+    response.set_typed(&cc).unwrap();
+
+    assert_eq!(response.get_headers("Cache-Control").len(), 1);
+    assert_eq!(response.get_typed::<CacheControl>().unwrap().unwrap(), cc);
+}
+
+#[test]
+fn test_set_typed_request_roundtrip() {
+    let mut request = Request::new("GET", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap();
+    let accept = Accept::parse("text/html, application/json;q=0.9").unwrap();
+    let etag = EntityTag::parse("W/\"v1\"").unwrap();
+    request
+        .set_typed(&accept)
+        .unwrap()
+        .set_typed(&etag)
+        .unwrap();
+
+    assert_eq!(request.get_typed::<Accept>().unwrap().unwrap(), accept);
+    assert_eq!(request.get_header("ETag"), Some("W/\"v1\""));
+
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(&request.encode().unwrap()).unwrap();
+    let decoded = decoder.decode().unwrap().unwrap();
+    assert_eq!(decoded.get_typed::<Accept>().unwrap().unwrap(), accept);
+    assert_eq!(decoded.get_typed::<EntityTag>().unwrap().unwrap(), etag);
+}
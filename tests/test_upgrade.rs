@@ -1,6 +1,10 @@
 //! Upgrade ヘッダーのユニットテスト
 
-use shiguredo_http11::upgrade::Upgrade;
+use shiguredo_http11::upgrade::{
+    H2C, Http2Settings, Upgrade, UpgradeError, h2c_switching_protocols, h2c_upgrade_request,
+    h2c_upgrade_settings, is_h2c_upgrade_response,
+};
+use shiguredo_http11::{Request, RequestDecoder, RequestHead, ResponseDecoder};
 
 /// RFC 9110 Section 5.6.1.2: 空フィールド値・空要素は受理する
 #[test]
@@ -20,3 +24,196 @@ fn display() {
     let upgrade = Upgrade::parse("websocket, h2c/1.0").unwrap();
     assert_eq!(upgrade.to_string(), "websocket, h2c/1.0");
 }
+
+// ========================================
+// HTTP2-Settings のテスト
+// ========================================
+
+/// SETTINGS_HEADER_TABLE_SIZE = 4096, SETTINGS_ENABLE_PUSH = 0
+const SETTINGS: [u8; 12] = [
+    0x00, 0x01, 0x00, 0x00, 0x10, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn http2_settings_encode() {
+    let settings = Http2Settings::new(SETTINGS).unwrap();
+    assert_eq!(settings.to_string(), "AAEAABAAAAIAAAAA");
+    assert_eq!(settings.payload(), &SETTINGS);
+
+    // 空のペイロードは空の値
+    assert_eq!(Http2Settings::new(Vec::new()).unwrap().to_string(), "");
+}
+
+/// RFC 7540 Section 3.2.1: パディングなしの base64url
+#[test]
+fn http2_settings_parse() {
+    let settings = Http2Settings::parse(" AAEAABAAAAIAAAAA ").unwrap();
+    assert_eq!(settings.payload(), &SETTINGS);
+
+    // SETTINGS_MAX_FRAME_SIZE = 0xfbfff8 は base64url で `-` / `_` を含む
+    let settings = Http2Settings::parse("AAUA-__4").unwrap();
+    assert_eq!(settings.payload(), &[0x00, 0x05, 0x00, 0xfb, 0xff, 0xf8]);
+}
+
+#[test]
+fn http2_settings_invalid() {
+    for input in [
+        "AAEAABAAAAIAAAAA==",
+        "AAEAABAA+AIAAAAA",
+        "AAEAABAA AIAAAAA",
+        "AAEA",
+    ] {
+        assert_eq!(
+            Http2Settings::parse(input),
+            Err(UpgradeError::InvalidHttp2Settings),
+            "{input}"
+        );
+    }
+    // ペイロードの長さは 6 の倍数
+    assert_eq!(
+        Http2Settings::new(vec![0; 5]),
+        Err(UpgradeError::InvalidHttp2Settings)
+    );
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(
+        UpgradeError::InvalidHttp2Settings.to_string(),
+        "invalid HTTP2-Settings header"
+    );
+    assert_eq!(
+        UpgradeError::MissingConnectionOption("upgrade".to_string()).to_string(),
+        "missing connection option: upgrade"
+    );
+}
+
+// ========================================
+// h2c アップグレードのテスト
+// ========================================
+
+fn decode_request(raw: &[u8]) -> RequestHead {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(raw).unwrap();
+    decoder.decode_headers().unwrap().unwrap().0
+}
+
+#[test]
+fn h2c_upgrade_request_headers() {
+    let settings = Http2Settings::new(SETTINGS).unwrap();
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Host", "example.com")
+        .unwrap()
+        .header("Connection", "keep-alive, upgrade")
+        .unwrap();
+    let request = h2c_upgrade_request(request, &settings).unwrap();
+    assert_eq!(
+        request.get_header("Connection"),
+        Some("keep-alive, Upgrade, HTTP2-Settings")
+    );
+    assert_eq!(request.get_header("Upgrade"), Some(H2C));
+    assert_eq!(
+        request.get_header("HTTP2-Settings"),
+        Some("AAEAABAAAAIAAAAA")
+    );
+
+    // サーバー側でそのまま受け付けられる
+    let head = decode_request(&request.encode().unwrap());
+    assert_eq!(h2c_upgrade_settings(&head).unwrap(), Some(settings));
+}
+
+#[test]
+fn h2c_upgrade_settings_not_requested() {
+    let head = decode_request(b"GET / HTTP/1.1\r\nHost: a\r\n\r\n");
+    assert_eq!(h2c_upgrade_settings(&head), Ok(None));
+
+    let head = decode_request(
+        b"GET / HTTP/1.1\r\nHost: a\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\r\n",
+    );
+    assert_eq!(h2c_upgrade_settings(&head), Ok(None));
+}
+
+/// RFC 7540 Section 3.2.1: HTTP2-Settings は接続オプションとして指定する
+#[test]
+fn h2c_upgrade_settings_missing_connection_option() {
+    let head = decode_request(
+        b"GET / HTTP/1.1\r\nHost: a\r\nConnection: Upgrade\r\nUpgrade: h2c\r\nHTTP2-Settings: \r\n\r\n",
+    );
+    assert_eq!(
+        h2c_upgrade_settings(&head),
+        Err(UpgradeError::MissingConnectionOption(
+            "http2-settings".to_string()
+        ))
+    );
+
+    let head =
+        decode_request(b"GET / HTTP/1.1\r\nHost: a\r\nUpgrade: h2c\r\nHTTP2-Settings: \r\n\r\n");
+    assert_eq!(
+        h2c_upgrade_settings(&head),
+        Err(UpgradeError::MissingConnectionOption("upgrade".to_string()))
+    );
+}
+
+/// RFC 7540 Section 3.2.1: HTTP2-Settings がないか複数ある場合はアップグレードしない
+#[test]
+fn h2c_upgrade_settings_header_count() {
+    let head = decode_request(
+        b"GET / HTTP/1.1\r\nHost: a\r\nConnection: Upgrade, HTTP2-Settings\r\nUpgrade: h2c\r\n\r\n",
+    );
+    assert_eq!(
+        h2c_upgrade_settings(&head),
+        Err(UpgradeError::InvalidHttp2Settings)
+    );
+
+    let head = decode_request(
+        b"GET / HTTP/1.1\r\nHost: a\r\nConnection: Upgrade\r\nConnection: HTTP2-Settings\r\nUpgrade: h2c\r\nHTTP2-Settings: \r\nHTTP2-Settings: \r\n\r\n",
+    );
+    assert_eq!(
+        h2c_upgrade_settings(&head),
+        Err(UpgradeError::InvalidHttp2Settings)
+    );
+}
+
+#[test]
+fn h2c_switching_protocols_response() {
+    let response = h2c_switching_protocols();
+    assert_eq!(response.status_code(), 101);
+    assert_eq!(response.get_header("Connection"), Some("Upgrade"));
+    assert_eq!(response.get_header("Upgrade"), Some("h2c"));
+
+    let encoded = response.encode().unwrap();
+    assert_eq!(
+        encoded,
+        b"HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\nUpgrade: h2c\r\n\r\n"
+    );
+}
+
+#[test]
+fn h2c_upgrade_response() {
+    // 101 の後ろに HTTP/2 のフレームが続く
+    let mut raw = h2c_switching_protocols().encode().unwrap();
+    raw.extend_from_slice(b"\x00\x00\x00\x04\x00\x00\x00\x00\x00");
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(&raw).unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(is_h2c_upgrade_response(&head));
+    assert_eq!(
+        decoder.take_remaining(),
+        b"\x00\x00\x00\x04\x00\x00\x00\x00\x00"
+    );
+
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(!is_h2c_upgrade_response(&head));
+
+    let mut decoder = ResponseDecoder::new();
+    decoder
+        .feed(b"HTTP/1.1 200 OK\r\nUpgrade: h2c\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert!(!is_h2c_upgrade_response(&head));
+}
@@ -1,6 +1,7 @@
 //! Vary ヘッダーのユニットテスト
 
-use shiguredo_http11::vary::Vary;
+use shiguredo_http11::Request;
+use shiguredo_http11::vary::{Vary, cache_key};
 
 #[test]
 fn parse_invalid() {
@@ -32,3 +33,112 @@ fn parse_wildcard_in_list() {
     assert!(vary.is_any());
     assert!(vary.fields().is_empty());
 }
+
+fn request(headers: &[(&str, &str)]) -> Request {
+    let mut request = Request::new("GET", "/").unwrap();
+    for (name, value) in headers {
+        request = request.header(*name, *value).unwrap();
+    }
+    request
+}
+
+fn key(vary: &str, headers: &[(&str, &str)]) -> Option<String> {
+    cache_key(&Vary::parse(vary).unwrap(), &request(headers))
+}
+
+/// RFC 9111 Section 4.1: Vary: * はどのリクエストとも一致しない
+#[test]
+fn cache_key_wildcard() {
+    assert_eq!(key("*", &[]), None);
+    assert_eq!(key("Accept, *", &[("Accept", "text/html")]), None);
+}
+
+#[test]
+fn cache_key_empty_vary() {
+    assert_eq!(key("", &[("Accept", "text/html")]), Some(String::new()));
+}
+
+#[test]
+fn cache_key_format() {
+    assert_eq!(
+        key(
+            "User-Agent, Accept-Encoding",
+            &[("Accept-Encoding", "gzip, br"), ("User-Agent", "curl/8.0")]
+        )
+        .unwrap(),
+        "accept-encoding:br,gzip\nuser-agent:curl/8.0"
+    );
+}
+
+#[test]
+fn cache_key_field_order_and_duplicates() {
+    let headers = [("Accept", "text/html"), ("User-Agent", "test")];
+    assert_eq!(
+        key("Accept, User-Agent", &headers),
+        key("user-agent, ACCEPT, Accept", &headers)
+    );
+}
+
+/// フィールドがない場合と空の値の場合は区別する
+#[test]
+fn cache_key_absent_and_empty() {
+    assert_eq!(key("X-Token", &[]).unwrap(), "x-token");
+    assert_eq!(key("X-Token", &[("X-Token", "")]).unwrap(), "x-token:");
+    assert_ne!(key("X-Token", &[]), key("X-Token", &[("X-Token", "")]));
+}
+
+#[test]
+fn cache_key_whitespace() {
+    assert_eq!(
+        key(
+            "User-Agent",
+            &[("User-Agent", "Mozilla/5.0  (X11;\tLinux)")]
+        ),
+        key("User-Agent", &[("User-Agent", "Mozilla/5.0 (X11; Linux)")])
+    );
+    // リスト形式でないフィールドは大文字小文字を区別する
+    assert_ne!(
+        key("X-Mode", &[("X-Mode", "Fast")]),
+        key("X-Mode", &[("X-Mode", "fast")])
+    );
+}
+
+/// RFC 9111 Section 4.1: 同名のフィールドが複数行ある場合は結合する
+#[test]
+fn cache_key_multiple_lines() {
+    assert_eq!(
+        key(
+            "Accept-Language",
+            &[("Accept-Language", "en"), ("Accept-Language", "ja")]
+        ),
+        key("Accept-Language", &[("Accept-Language", "ja, en")])
+    );
+    assert_eq!(
+        key("X-List", &[("X-List", "a"), ("X-List", "b")]).unwrap(),
+        "x-list:a, b"
+    );
+}
+
+#[test]
+fn cache_key_list_fields() {
+    assert_eq!(
+        key("Accept", &[("Accept", "text/html;q=0.9, application/json")]),
+        key(
+            "Accept",
+            &[("Accept", "Application/JSON,text/html ; q=0.9,")]
+        )
+    );
+    assert_ne!(
+        key("Accept-Encoding", &[("Accept-Encoding", "gzip")]),
+        key("Accept-Encoding", &[("Accept-Encoding", "br")])
+    );
+}
+
+#[test]
+fn cache_key_list_quoted_string() {
+    // 引用符内のカンマと空白はそのまま扱う
+    assert_eq!(
+        key("Accept", &[("Accept", "text/plain;x=\"a, B\"")]).unwrap(),
+        "accept:text/plain;x=\"a, B\""
+    );
+}
@@ -0,0 +1,214 @@
+//! プロトコルバージョン型のユニットテスト
+
+use shiguredo_http11::version::{HttpVersion, HttpVersionError};
+use shiguredo_http11::{HttpHead, Request, RequestDecoder, Response, ResponseDecoder};
+
+// ========================================
+// HttpVersionError のテスト
+// ========================================
+
+#[test]
+fn test_http_version_error_display() {
+    let errors = [
+        (HttpVersionError::Empty, "empty protocol version"),
+        (
+            HttpVersionError::InvalidFormat,
+            "invalid protocol version format",
+        ),
+        (
+            HttpVersionError::NotHttpVersion,
+            "not an HTTP-version (RFC 9112 Section 2.3)",
+        ),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_parse_known_versions() {
+    assert_eq!(HttpVersion::parse("HTTP/1.0").unwrap(), HttpVersion::Http10);
+    assert_eq!(HttpVersion::parse("HTTP/1.1").unwrap(), HttpVersion::Http11);
+    assert_eq!(
+        "HTTP/1.1".parse::<HttpVersion>().unwrap(),
+        HttpVersion::Http11
+    );
+}
+
+#[test]
+fn test_parse_other_versions() {
+    for s in [
+        "HTTP/2.0",
+        "HTTP/0.9",
+        "RTSP/1.0",
+        "RTSP/2.0",
+        "http/1.1",
+        "HTTP/1.10",
+    ] {
+        assert_eq!(
+            HttpVersion::parse(s).unwrap(),
+            HttpVersion::Other(s.to_string()),
+            "{s}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_invalid() {
+    assert_eq!(HttpVersion::parse(""), Err(HttpVersionError::Empty));
+    for s in [
+        "HTTP",
+        "HTTP/",
+        "HTTP/1",
+        "HTTP/1.",
+        "HTTP/.1",
+        "/1.1",
+        "HTTP/1.1 ",
+        "HTTP/a.b",
+    ] {
+        assert_eq!(
+            HttpVersion::parse(s),
+            Err(HttpVersionError::InvalidFormat),
+            "{s}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_strict() {
+    assert_eq!(
+        HttpVersion::parse_strict("HTTP/1.1").unwrap(),
+        HttpVersion::Http11
+    );
+    assert_eq!(
+        HttpVersion::parse_strict("HTTP/2.0").unwrap(),
+        HttpVersion::Other("HTTP/2.0".to_string())
+    );
+    for s in ["RTSP/1.0", "http/1.1", "HTTP/1.10", "HTTP/10.1"] {
+        assert_eq!(
+            HttpVersion::parse_strict(s),
+            Err(HttpVersionError::NotHttpVersion),
+            "{s}"
+        );
+    }
+    assert_eq!(
+        HttpVersion::parse_strict("HTTP/1"),
+        Err(HttpVersionError::InvalidFormat)
+    );
+}
+
+#[test]
+fn test_as_str_display_and_into_string() {
+    assert_eq!(HttpVersion::Http10.as_str(), "HTTP/1.0");
+    assert_eq!(HttpVersion::Http11.to_string(), "HTTP/1.1");
+    assert_eq!(String::from(HttpVersion::Http11), "HTTP/1.1");
+    assert_eq!(
+        String::from(HttpVersion::Other("RTSP/1.0".to_string())),
+        "RTSP/1.0"
+    );
+}
+
+#[test]
+fn test_is_http() {
+    assert!(HttpVersion::Http10.is_http());
+    assert!(HttpVersion::Http11.is_http());
+    assert!(HttpVersion::parse("HTTP/2.0").unwrap().is_http());
+    assert!(!HttpVersion::parse("RTSP/1.0").unwrap().is_http());
+}
+
+// ========================================
+// Request / Response / デコード結果のテスト
+// ========================================
+
+#[test]
+fn test_request_response_http_version() {
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(request.http_version(), HttpVersion::Http11);
+
+    let request = Request::with_version("GET", "/", HttpVersion::Http10).unwrap();
+    assert_eq!(request.version(), "HTTP/1.0");
+    assert_eq!(request.http_version(), HttpVersion::Http10);
+
+    let response = Response::with_version("RTSP/1.0", 200, "OK").unwrap();
+    assert_eq!(
+        response.http_version(),
+        HttpVersion::Other("RTSP/1.0".to_string())
+    );
+}
+
+#[test]
+fn test_decoded_head_http_version() {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.http_version(), HttpVersion::Http10);
+
+    let mut decoder = ResponseDecoder::new();
+    decoder.feed(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(HttpHead::http_version(&head), HttpVersion::Http11);
+}
+
+// ========================================
+// デコーダーの厳格なバージョン検証のテスト
+// ========================================
+
+#[test]
+fn test_request_decoder_strict_version() {
+    let mut decoder = RequestDecoder::new();
+    assert!(!decoder.strict_version());
+    decoder.set_strict_version(true);
+    assert!(decoder.strict_version());
+
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n")
+        .unwrap();
+    assert!(decoder.decode_headers().unwrap().is_some());
+
+    for line in [
+        &b"OPTIONS * RTSP/1.0\r\n\r\n"[..],
+        b"GET / HTTP/1.10\r\n\r\n",
+        b"GET / http/1.1\r\n\r\n",
+    ] {
+        let mut decoder = RequestDecoder::new();
+        decoder.set_strict_version(true);
+        decoder.feed(line).unwrap();
+        let err = decoder.decode_headers().unwrap_err();
+        assert!(
+            err.to_string().contains("invalid HTTP-version"),
+            "{err} for {line:?}"
+        );
+    }
+}
+
+#[test]
+fn test_request_decoder_lenient_version_by_default() {
+    let mut decoder = RequestDecoder::new();
+    decoder.feed(b"OPTIONS * RTSP/1.0\r\n\r\n").unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(
+        head.http_version(),
+        HttpVersion::Other("RTSP/1.0".to_string())
+    );
+}
+
+#[test]
+fn test_response_decoder_strict_version() {
+    let mut decoder = ResponseDecoder::new();
+    decoder.set_strict_version(true);
+    decoder.feed(b"RTSP/1.0 200 OK\r\n\r\n").unwrap();
+    let err = decoder.decode_headers().unwrap_err();
+    assert!(err.to_string().contains("invalid HTTP-version"), "{err}");
+
+    // reset() 後も設定は維持される
+    decoder.reset();
+    assert!(decoder.strict_version());
+    decoder.feed(b"HTTP/1.0 200 OK\r\n\r\n").unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(head.http_version(), HttpVersion::Http10);
+}
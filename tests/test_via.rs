@@ -0,0 +1,225 @@
+//! Via ヘッダーのユニットテスト
+
+use shiguredo_http11::via::{
+    Via, ViaEntry, ViaError, append_response_via, append_via, detect_loop,
+};
+use shiguredo_http11::{Request, RequestDecoder, Response, StatusCode};
+
+// ========================================
+// ViaError のテスト
+// ========================================
+
+#[test]
+fn test_via_error_display() {
+    let errors = [
+        (ViaError::InvalidFormat, "invalid Via header format"),
+        (ViaError::InvalidProtocol, "invalid Via received-protocol"),
+        (ViaError::InvalidReceivedBy, "invalid Via received-by"),
+        (ViaError::InvalidComment, "invalid Via comment"),
+        (ViaError::UnterminatedComment, "unterminated Via comment"),
+    ];
+
+    for (error, expected) in errors {
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+// ========================================
+// パースのテスト
+// ========================================
+
+#[test]
+fn test_via_parse_rfc9110_example() {
+    // RFC 9110 Section 7.6.3
+    let via = Via::parse("1.0 fred, 1.1 p.example.net").unwrap();
+    assert_eq!(via.entries().len(), 2);
+
+    let first = &via.entries()[0];
+    assert_eq!(first.protocol_name(), None);
+    assert_eq!(first.protocol_version(), "1.0");
+    assert_eq!(first.received_by(), "fred");
+    assert_eq!(first.port(), None);
+    assert_eq!(first.comment(), None);
+
+    assert_eq!(via.entries()[1].received_by(), "p.example.net");
+    assert_eq!(via.to_string(), "1.0 fred, 1.1 p.example.net");
+}
+
+#[test]
+fn test_via_parse_protocol_port_and_comment() {
+    let via = Via::parse(
+        "HTTP/1.1 proxy:8080 (squid/6.0), WebSocket/13 [2001:db8::1]:443\t(a (nested, comment) \\) )",
+    )
+    .unwrap();
+    assert_eq!(via.entries().len(), 2);
+
+    let first = &via.entries()[0];
+    assert_eq!(first.protocol_name(), None);
+    assert_eq!(first.protocol_version(), "1.1");
+    assert_eq!(first.received_by(), "proxy");
+    assert_eq!(first.port(), Some(8080));
+    assert_eq!(first.comment(), Some("squid/6.0"));
+
+    let second = &via.entries()[1];
+    assert_eq!(second.protocol_name(), Some("WebSocket"));
+    assert_eq!(second.protocol_version(), "13");
+    assert_eq!(second.received_by(), "[2001:db8::1]");
+    assert_eq!(second.port(), Some(443));
+    assert_eq!(second.comment(), Some("a (nested, comment) \\) "));
+
+    assert_eq!(Via::parse(&via.to_string()).unwrap(), via);
+}
+
+#[test]
+fn test_via_parse_empty_elements() {
+    assert!(Via::parse("").unwrap().entries().is_empty());
+    let via = Via::parse(" , 1.1 a ,, 1.1 b , ").unwrap();
+    assert_eq!(via.to_string(), "1.1 a, 1.1 b");
+}
+
+#[test]
+fn test_via_parse_errors() {
+    let cases = [
+        ("1.1", ViaError::InvalidFormat),
+        ("1.1 a b", ViaError::InvalidFormat),
+        ("1.1 a(comment)", ViaError::InvalidReceivedBy),
+        ("1.1 a (x) b", ViaError::InvalidFormat),
+        ("HTTP/ a", ViaError::InvalidProtocol),
+        ("/1.1 a", ViaError::InvalidProtocol),
+        ("1.1 a:", ViaError::InvalidReceivedBy),
+        ("1.1 a:65536", ViaError::InvalidReceivedBy),
+        ("1.1 a:http", ViaError::InvalidReceivedBy),
+        ("1.1 [::1", ViaError::InvalidReceivedBy),
+        ("1.1 [::1]x", ViaError::InvalidReceivedBy),
+        ("1.1 [a.example]", ViaError::InvalidReceivedBy),
+        ("1.1 a (x", ViaError::UnterminatedComment),
+        ("1.1 a (x\\", ViaError::UnterminatedComment),
+        ("1.1 a (x\u{7f})", ViaError::InvalidComment),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Via::parse(input), Err(expected), "{input}");
+    }
+}
+
+#[test]
+fn test_via_typed_header() {
+    let response = Response::with_status(StatusCode::OK)
+        .header("Via", "1.1 a")
+        .unwrap();
+    let via = response.get_typed::<Via>().unwrap().unwrap();
+    assert!(via.has_pseudonym("a"));
+}
+
+// ========================================
+// 生成のテスト
+// ========================================
+
+#[test]
+fn test_via_entry_new() {
+    let entry = ViaEntry::new("HTTP/1.0", "gateway").unwrap();
+    assert_eq!(entry.protocol_name(), None);
+    assert_eq!(entry.to_string(), "1.0 gateway");
+
+    let entry = ViaEntry::new("h2", "proxy:8443")
+        .unwrap()
+        .with_comment("shiguredo (test)")
+        .unwrap();
+    assert_eq!(entry.to_string(), "h2 proxy:8443 (shiguredo (test))");
+
+    let via = Via::new()
+        .with_entry(ViaEntry::new("1.1", "a").unwrap())
+        .with_entry(entry);
+    assert_eq!(via.to_string(), "1.1 a, h2 proxy:8443 (shiguredo (test))");
+}
+
+#[test]
+fn test_via_entry_new_errors() {
+    assert_eq!(ViaEntry::new("1 1", "a"), Err(ViaError::InvalidProtocol));
+    assert_eq!(
+        ViaEntry::new("1.1", "a b"),
+        Err(ViaError::InvalidReceivedBy)
+    );
+
+    let entry = ViaEntry::new("1.1", "a").unwrap();
+    assert_eq!(
+        entry.clone().with_comment("x) (y"),
+        Err(ViaError::InvalidComment)
+    );
+    assert_eq!(
+        entry.clone().with_comment("(x"),
+        Err(ViaError::UnterminatedComment)
+    );
+    assert_eq!(entry.with_comment("a\r\nb"), Err(ViaError::InvalidComment));
+}
+
+// ========================================
+// ループ検出のテスト
+// ========================================
+
+#[test]
+fn test_via_has_pseudonym() {
+    let via = Via::parse("1.1 Edge:8080, 1.1 origin-proxy").unwrap();
+    assert!(via.has_pseudonym("edge"));
+    assert!(via.has_pseudonym("ORIGIN-PROXY"));
+    assert!(!via.has_pseudonym("edge:8080"));
+    assert!(!via.has_pseudonym("origin"));
+}
+
+#[test]
+fn test_detect_loop_joins_lines() {
+    let mut decoder = RequestDecoder::new();
+    decoder
+        .feed(b"GET / HTTP/1.1\r\nHost: example.com\r\nVia: 1.1 a\r\nVia: 1.1 b (x)\r\n\r\n")
+        .unwrap();
+    let (head, _) = decoder.decode_headers().unwrap().unwrap();
+    assert_eq!(detect_loop(&head, "b"), Ok(true));
+    assert_eq!(detect_loop(&head, "c"), Ok(false));
+
+    let via = Via::from_message(&head).unwrap().unwrap();
+    assert_eq!(via.entries().len(), 2);
+
+    let request = Request::new("GET", "/").unwrap();
+    assert_eq!(detect_loop(&request, "a"), Ok(false));
+    assert_eq!(Via::from_message(&request), Ok(None));
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Via", "1.1")
+        .unwrap();
+    assert_eq!(detect_loop(&request, "a"), Err(ViaError::InvalidFormat));
+}
+
+// ========================================
+// エントリ追加のテスト
+// ========================================
+
+#[test]
+fn test_append_via() {
+    let entry = ViaEntry::new("HTTP/1.1", "proxy").unwrap();
+
+    let request = append_via(Request::new("GET", "/").unwrap(), &entry).unwrap();
+    assert_eq!(request.get_header("Via"), Some("1.1 proxy"));
+
+    let request = Request::new("GET", "/")
+        .unwrap()
+        .header("Via", "1.0 fred")
+        .unwrap()
+        .header("Via", "1.1 edge, ")
+        .unwrap();
+    let request = append_via(request, &entry).unwrap();
+    assert_eq!(request.get_headers("Via").len(), 1);
+    assert_eq!(
+        request.get_header("Via"),
+        Some("1.0 fred, 1.1 edge, 1.1 proxy")
+    );
+}
+
+#[test]
+fn test_append_response_via() {
+    let entry = ViaEntry::new("1.1", "proxy").unwrap();
+    let response = Response::with_status(StatusCode::OK)
+        .header("Via", "1.1 origin")
+        .unwrap();
+    let response = append_response_via(response, &entry).unwrap();
+    assert_eq!(response.get_header("Via"), Some("1.1 origin, 1.1 proxy"));
+}